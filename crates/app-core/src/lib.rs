@@ -23,6 +23,7 @@
 //! └──────────────────────────────────────────────────────────────┘
 //! ```
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
@@ -30,7 +31,7 @@ use anyhow::Result;
 // Re-export foundation types from dependent crates
 pub use termide_core::{Panel, PanelEvent};
 pub use termide_modal::ActiveModal;
-pub use termide_state::{PendingAction, UiState};
+pub use termide_state::{NotificationLevel, PendingAction, UiState};
 
 // ============================================================================
 // Synchronous Operation Traits
@@ -172,8 +173,8 @@ pub enum FileChange {
 /// Message type for asynchronous background operations.
 ///
 /// These messages are produced by background tasks (git watcher,
-/// filesystem watcher, etc.) and consumed by the main event loop.
-#[derive(Debug, Clone)]
+/// filesystem watcher, IPC server, etc.) and consumed by the main event loop.
+#[derive(Debug)]
 pub enum Message {
     // === Background task results ===
     /// Git status update for a repository
@@ -224,6 +225,10 @@ pub enum Message {
 
     /// Request to save current session
     SaveSession,
+
+    // === External control ===
+    /// Command sent by another program (IPC socket), run on the main loop
+    Command(AppCommand),
 }
 
 /// Trait for receiving messages from background tasks.
@@ -271,6 +276,13 @@ pub enum PanelType {
     LogViewer,
     /// Welcome screen panel
     Welcome,
+    /// Panel of a registered type
+    Custom {
+        /// Registered panel type name
+        name: String,
+        /// Parameters passed to the constructor
+        params: BTreeMap<String, String>,
+    },
 }
 
 /// Command type for explicit state mutations.
@@ -290,6 +302,15 @@ pub enum AppCommand {
     },
     /// Clear status bar
     ClearStatus,
+    /// Show a notification toast, kept in the notification history
+    Notify {
+        /// Message text
+        text: String,
+        /// Info and warnings go away after a timeout, errors stay
+        level: NotificationLevel,
+        /// Time on screen instead of the default of the level
+        timeout: Option<std::time::Duration>,
+    },
 
     // === Modal operations ===
     /// Open a modal dialog
@@ -310,6 +331,15 @@ pub enum AppCommand {
     },
     /// Close the active panel
     ClosePanel,
+    /// Open a file in an editor (or focus the editor that has it open)
+    OpenFile {
+        /// File to open
+        path: PathBuf,
+        /// 1-based line to put the cursor on
+        line: Option<usize>,
+        /// 1-based column to put the cursor on
+        column: Option<usize>,
+    },
 
     // === Navigation ===
     /// Navigate between panels/groups
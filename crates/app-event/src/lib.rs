@@ -3,6 +3,7 @@
 //! This crate provides:
 //! - `HotkeyProcessor` trait for checking global hotkeys
 //! - `KeyBinding` type for configurable hotkey mappings
+//! - `KeySequence` type for multi-key chords (`Ctrl+K Ctrl+W`)
 //! - Default hotkey processor implementation
 //!
 //! # Architecture
@@ -16,11 +17,19 @@
 //! ```
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use termide_app_core::{AppCommand, Direction, PanelType};
 
+mod sequence;
+
+pub use sequence::KeySequence;
+
+/// Time to press the next key of a chord before the prefix is dropped
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
 // ============================================================================
 // Key Binding Types
 // ============================================================================
@@ -88,10 +97,32 @@ pub enum HotkeyAction {
     NewEditor,
     /// Open debug/log panel
     NewDebug,
+    /// Open system monitor panel
+    NewSystemMonitor,
+    /// Open git panel
+    NewGitPanel,
+    /// Open workspace search panel
+    NewSearchPanel,
     /// Open help panel
     OpenHelp,
     /// Open preferences (config file)
     OpenPreferences,
+    /// Pick a theme with preview
+    SelectTheme,
+    /// Open the command palette
+    CommandPalette,
+    /// Open the fuzzy file finder
+    OpenFileFinder,
+    /// List the project's bookmarks
+    Bookmarks,
+    /// Toggle sending keys typed into a terminal to all terminals
+    ToggleTerminalBroadcast,
+    /// Run a task from the config
+    RunTask,
+    /// Show the active editor's buffer in another panel
+    SplitEditor,
+    /// Open a panel of a registered type (by its name)
+    OpenPanel(String),
 
     // === Navigation ===
     /// Navigate to previous group
@@ -110,6 +141,8 @@ pub enum HotkeyAction {
     ClosePanel,
     /// Toggle panel stacking
     ToggleStacking,
+    /// Toggle showing the active group as tabs
+    ToggleTabs,
     /// Swap panel left
     SwapPanelLeft,
     /// Swap panel right
@@ -122,11 +155,168 @@ pub enum HotkeyAction {
     ResizePanel(i16),
 
     // === Application ===
+    /// Remove the notification toasts from the screen
+    DismissNotifications,
+    /// List past notifications
+    NotificationHistory,
     /// Request quit (with confirmation if needed)
     RequestQuit,
 }
 
+/// Group an action is listed under in the help panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ActionCategory {
+    /// Opening panels and dialogs
+    Panels,
+    /// Moving between groups and panels
+    Navigation,
+    /// Closing, moving and resizing panels
+    Layout,
+    /// Menu and quitting
+    Application,
+}
+
+impl ActionCategory {
+    /// All categories in display order.
+    pub const ALL: [Self; 4] = [
+        Self::Panels,
+        Self::Navigation,
+        Self::Layout,
+        Self::Application,
+    ];
+
+    /// Identifier used for the translated title (`help_category_<name>`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Panels => "panels",
+            Self::Navigation => "navigation",
+            Self::Layout => "layout",
+            Self::Application => "application",
+        }
+    }
+}
+
+/// Config names of actions, in the order the command palette lists them.
+///
+/// `GoToPanel(n)` is named `go_to_panel_<n>` and `OpenPanel(name)` is
+/// named `open_panel_<name>`; they are not in the table.
+const ACTION_NAMES: &[(&str, HotkeyAction)] = &[
+    ("toggle_menu", HotkeyAction::ToggleMenu),
+    ("new_file_manager", HotkeyAction::NewFileManager),
+    ("new_terminal", HotkeyAction::NewTerminal),
+    ("new_editor", HotkeyAction::NewEditor),
+    ("new_debug", HotkeyAction::NewDebug),
+    ("new_system_monitor", HotkeyAction::NewSystemMonitor),
+    ("new_git_panel", HotkeyAction::NewGitPanel),
+    ("new_search_panel", HotkeyAction::NewSearchPanel),
+    ("open_help", HotkeyAction::OpenHelp),
+    ("open_preferences", HotkeyAction::OpenPreferences),
+    ("select_theme", HotkeyAction::SelectTheme),
+    ("command_palette", HotkeyAction::CommandPalette),
+    ("open_file_finder", HotkeyAction::OpenFileFinder),
+    ("bookmarks", HotkeyAction::Bookmarks),
+    (
+        "toggle_terminal_broadcast",
+        HotkeyAction::ToggleTerminalBroadcast,
+    ),
+    ("run_task", HotkeyAction::RunTask),
+    ("split_editor", HotkeyAction::SplitEditor),
+    ("prev_group", HotkeyAction::PrevGroup),
+    ("next_group", HotkeyAction::NextGroup),
+    ("prev_in_group", HotkeyAction::PrevInGroup),
+    ("next_in_group", HotkeyAction::NextInGroup),
+    ("close_panel", HotkeyAction::ClosePanel),
+    ("toggle_stacking", HotkeyAction::ToggleStacking),
+    ("toggle_tabs", HotkeyAction::ToggleTabs),
+    ("swap_panel_left", HotkeyAction::SwapPanelLeft),
+    ("swap_panel_right", HotkeyAction::SwapPanelRight),
+    ("move_to_first", HotkeyAction::MoveToFirst),
+    ("move_to_last", HotkeyAction::MoveToLast),
+    ("shrink_panel", HotkeyAction::ResizePanel(-1)),
+    ("grow_panel", HotkeyAction::ResizePanel(1)),
+    ("dismiss_notifications", HotkeyAction::DismissNotifications),
+    ("notification_history", HotkeyAction::NotificationHistory),
+    ("quit", HotkeyAction::RequestQuit),
+];
+
 impl HotkeyAction {
+    /// Action for a config name (`close_panel`, `go_to_panel_3`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some((_, action)) = ACTION_NAMES.iter().find(|(n, _)| *n == name) {
+            return Some(action.clone());
+        }
+        if let Some(panel) = name.strip_prefix("open_panel_") {
+            return (!panel.is_empty()).then(|| Self::OpenPanel(panel.to_string()));
+        }
+        let number = name.strip_prefix("go_to_panel_")?.parse().ok()?;
+        (1..=9).contains(&number).then_some(Self::GoToPanel(number))
+    }
+
+    /// Config name of the action (inverse of `from_name`).
+    pub fn name(&self) -> String {
+        match self {
+            Self::GoToPanel(number) => return format!("go_to_panel_{}", number),
+            Self::OpenPanel(panel) => return format!("open_panel_{}", panel),
+            _ => {}
+        }
+        ACTION_NAMES
+            .iter()
+            .find(|(_, action)| action == self)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| format!("{:?}", self))
+    }
+
+    /// All built-in actions that can be bound or run from the command
+    /// palette (registered panels add their own).
+    pub fn all() -> Vec<Self> {
+        let mut actions: Vec<Self> = ACTION_NAMES
+            .iter()
+            .map(|(_, action)| action.clone())
+            .collect();
+        actions.extend((1..=9).map(Self::GoToPanel));
+        actions
+    }
+
+    /// Help panel group of the action.
+    pub fn category(&self) -> ActionCategory {
+        match self {
+            Self::ToggleMenu
+            | Self::DismissNotifications
+            | Self::NotificationHistory
+            | Self::RequestQuit => ActionCategory::Application,
+            Self::NewFileManager
+            | Self::NewTerminal
+            | Self::NewEditor
+            | Self::NewDebug
+            | Self::NewSystemMonitor
+            | Self::NewGitPanel
+            | Self::NewSearchPanel
+            | Self::OpenHelp
+            | Self::OpenPreferences
+            | Self::SelectTheme
+            | Self::CommandPalette
+            | Self::OpenFileFinder
+            | Self::Bookmarks
+            | Self::ToggleTerminalBroadcast
+            | Self::RunTask
+            | Self::SplitEditor
+            | Self::OpenPanel(_) => ActionCategory::Panels,
+            Self::PrevGroup
+            | Self::NextGroup
+            | Self::PrevInGroup
+            | Self::NextInGroup
+            | Self::GoToPanel(_) => ActionCategory::Navigation,
+            Self::ClosePanel
+            | Self::ToggleStacking
+            | Self::ToggleTabs
+            | Self::SwapPanelLeft
+            | Self::SwapPanelRight
+            | Self::MoveToFirst
+            | Self::MoveToLast
+            | Self::ResizePanel(_) => ActionCategory::Layout,
+        }
+    }
+
     /// Convert action to AppCommand.
     ///
     /// Some actions require additional context and return None,
@@ -160,6 +350,12 @@ impl HotkeyAction {
             HotkeyAction::OpenHelp => Some(AppCommand::CreatePanel {
                 panel_type: PanelType::Welcome,
             }),
+            HotkeyAction::OpenPanel(name) => Some(AppCommand::CreatePanel {
+                panel_type: PanelType::Custom {
+                    name: name.clone(),
+                    params: Default::default(),
+                },
+            }),
 
             // Panel management
             HotkeyAction::ClosePanel => Some(AppCommand::ClosePanel),
@@ -167,10 +363,23 @@ impl HotkeyAction {
 
             // Actions that need special handling (return None)
             HotkeyAction::ToggleMenu
+            | HotkeyAction::NewSystemMonitor
+            | HotkeyAction::NewGitPanel
+            | HotkeyAction::NewSearchPanel
             | HotkeyAction::OpenPreferences
+            | HotkeyAction::SelectTheme
+            | HotkeyAction::CommandPalette
+            | HotkeyAction::OpenFileFinder
+            | HotkeyAction::Bookmarks
+            | HotkeyAction::ToggleTerminalBroadcast
+            | HotkeyAction::RunTask
+            | HotkeyAction::SplitEditor
+            | HotkeyAction::DismissNotifications
+            | HotkeyAction::NotificationHistory
             | HotkeyAction::PrevInGroup
             | HotkeyAction::NextInGroup
             | HotkeyAction::ToggleStacking
+            | HotkeyAction::ToggleTabs
             | HotkeyAction::SwapPanelLeft
             | HotkeyAction::SwapPanelRight
             | HotkeyAction::MoveToFirst
@@ -184,16 +393,36 @@ impl HotkeyAction {
 // Hotkey Processor Trait
 // ============================================================================
 
+/// Result of processing a key event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyMatch {
+    /// Key completed a hotkey
+    Action(HotkeyAction),
+    /// Key started or continued a chord, waiting for the next key
+    Pending,
+    /// Escape dropped the pending chord prefix
+    Cancelled,
+    /// Not a hotkey: pass the key to the active panel
+    NoMatch,
+}
+
 /// Trait for processing global hotkeys.
 ///
 /// Implementations check if a key event is a global hotkey and
-/// return the corresponding action if so.
+/// return the corresponding action if so. Chords keep the pressed
+/// prefix until the next key.
 pub trait HotkeyProcessor {
-    /// Check if key is a global hotkey.
+    /// Process a key event.
     ///
-    /// Returns the action if the key matches a hotkey binding,
-    /// or None if it should be passed to the active panel.
-    fn process_hotkey(&self, key: &KeyEvent) -> Option<HotkeyAction>;
+    /// Returns the action if the key completes a hotkey binding,
+    /// `Pending` if it is a prefix of a chord, or `NoMatch` if it
+    /// should be passed to the active panel.
+    fn process_hotkey(&mut self, key: &KeyEvent) -> HotkeyMatch;
+
+    /// Keys of the chord waiting for its next key.
+    fn pending_prefix(&self) -> Option<&KeySequence> {
+        None
+    }
 
     /// Check if Escape should close the panel.
     ///
@@ -208,12 +437,22 @@ pub trait HotkeyProcessor {
 // Default Hotkey Processor
 // ============================================================================
 
+/// Chord prefix waiting for its next key
+#[derive(Debug, Clone)]
+struct PendingChord {
+    keys: KeySequence,
+    /// Time of the last key
+    pressed: Instant,
+}
+
 /// Default hotkey processor with standard key bindings.
 ///
-/// Uses Alt+key combinations for all hotkeys as per termide conventions.
+/// Uses Alt+key combinations for all default hotkeys as per termide
+/// conventions; chords can be added with `bind` or from config.
 #[derive(Debug, Clone)]
 pub struct DefaultHotkeyProcessor {
-    bindings: HashMap<KeyBinding, HotkeyAction>,
+    bindings: HashMap<KeySequence, HotkeyAction>,
+    pending: Option<PendingChord>,
 }
 
 impl Default for DefaultHotkeyProcessor {
@@ -226,170 +465,346 @@ impl DefaultHotkeyProcessor {
     /// Create a new processor with default bindings.
     pub fn new() -> Self {
         let mut bindings = HashMap::new();
+        let mut bind = |key: KeyBinding, action: HotkeyAction| {
+            bindings.insert(KeySequence::from(key), action);
+        };
 
         // Menu
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('m')),
             HotkeyAction::ToggleMenu,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('M')),
             HotkeyAction::ToggleMenu,
         );
 
         // Panel creation
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('f')),
             HotkeyAction::NewFileManager,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('F')),
             HotkeyAction::NewFileManager,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('t')),
             HotkeyAction::NewTerminal,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('T')),
             HotkeyAction::NewTerminal,
         );
-        bindings.insert(KeyBinding::alt(KeyCode::Char('e')), HotkeyAction::NewEditor);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('E')), HotkeyAction::NewEditor);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('l')), HotkeyAction::NewDebug);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('L')), HotkeyAction::NewDebug);
-        bindings.insert(
+        bind(KeyBinding::alt(KeyCode::Char('e')), HotkeyAction::NewEditor);
+        bind(KeyBinding::alt(KeyCode::Char('E')), HotkeyAction::NewEditor);
+        bind(KeyBinding::alt(KeyCode::Char('l')), HotkeyAction::NewDebug);
+        bind(KeyBinding::alt(KeyCode::Char('L')), HotkeyAction::NewDebug);
+        bind(
+            KeyBinding::alt(KeyCode::Char('r')),
+            HotkeyAction::NewSystemMonitor,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('R')),
+            HotkeyAction::NewSystemMonitor,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('g')),
+            HotkeyAction::NewGitPanel,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('G')),
+            HotkeyAction::NewGitPanel,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('/')),
+            HotkeyAction::NewSearchPanel,
+        );
+        bind(
             KeyBinding::alt(KeyCode::Char('p')),
             HotkeyAction::OpenPreferences,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('P')),
             HotkeyAction::OpenPreferences,
         );
-        bindings.insert(KeyBinding::alt(KeyCode::Char('h')), HotkeyAction::OpenHelp);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('H')), HotkeyAction::OpenHelp);
+        bind(KeyBinding::alt(KeyCode::Char('h')), HotkeyAction::OpenHelp);
+        bind(KeyBinding::alt(KeyCode::Char('H')), HotkeyAction::OpenHelp);
+        bind(
+            KeyBinding::new(
+                KeyCode::Char('P'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            ),
+            HotkeyAction::CommandPalette,
+        );
+        bind(
+            KeyBinding::new(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            ),
+            HotkeyAction::CommandPalette,
+        );
+        bind(
+            KeyBinding::new(KeyCode::Char('p'), KeyModifiers::CONTROL),
+            HotkeyAction::OpenFileFinder,
+        );
+        bind(KeyBinding::alt(KeyCode::Char('j')), HotkeyAction::Bookmarks);
+        bind(KeyBinding::alt(KeyCode::Char('J')), HotkeyAction::Bookmarks);
+        bind(
+            KeyBinding::alt(KeyCode::Char('b')),
+            HotkeyAction::ToggleTerminalBroadcast,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('B')),
+            HotkeyAction::ToggleTerminalBroadcast,
+        );
+        bind(
+            KeyBinding::new(
+                KeyCode::Char('B'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            ),
+            HotkeyAction::RunTask,
+        );
+        bind(
+            KeyBinding::new(
+                KeyCode::Char('b'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            ),
+            HotkeyAction::RunTask,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('\\')),
+            HotkeyAction::SplitEditor,
+        );
+
+        // Notifications
+        bind(
+            KeyBinding::alt(KeyCode::Char('k')),
+            HotkeyAction::DismissNotifications,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('K')),
+            HotkeyAction::DismissNotifications,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('n')),
+            HotkeyAction::NotificationHistory,
+        );
+        bind(
+            KeyBinding::alt(KeyCode::Char('N')),
+            HotkeyAction::NotificationHistory,
+        );
 
         // Quit
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('q')),
             HotkeyAction::RequestQuit,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('Q')),
             HotkeyAction::RequestQuit,
         );
 
         // Close panel
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('x')),
             HotkeyAction::ClosePanel,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('X')),
             HotkeyAction::ClosePanel,
         );
-        bindings.insert(KeyBinding::alt(KeyCode::Delete), HotkeyAction::ClosePanel);
+        bind(KeyBinding::alt(KeyCode::Delete), HotkeyAction::ClosePanel);
 
         // Navigation - arrows
-        bindings.insert(KeyBinding::alt(KeyCode::Left), HotkeyAction::PrevGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Right), HotkeyAction::NextGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Up), HotkeyAction::PrevInGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Down), HotkeyAction::NextInGroup);
+        bind(KeyBinding::alt(KeyCode::Left), HotkeyAction::PrevGroup);
+        bind(KeyBinding::alt(KeyCode::Right), HotkeyAction::NextGroup);
+        bind(KeyBinding::alt(KeyCode::Up), HotkeyAction::PrevInGroup);
+        bind(KeyBinding::alt(KeyCode::Down), HotkeyAction::NextInGroup);
 
         // Navigation - alternative keys (vim-style WASD)
-        bindings.insert(KeyBinding::alt(KeyCode::Char('a')), HotkeyAction::PrevGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('A')), HotkeyAction::PrevGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('d')), HotkeyAction::NextGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('D')), HotkeyAction::NextGroup);
-        bindings.insert(
+        bind(KeyBinding::alt(KeyCode::Char('a')), HotkeyAction::PrevGroup);
+        bind(KeyBinding::alt(KeyCode::Char('A')), HotkeyAction::PrevGroup);
+        bind(KeyBinding::alt(KeyCode::Char('d')), HotkeyAction::NextGroup);
+        bind(KeyBinding::alt(KeyCode::Char('D')), HotkeyAction::NextGroup);
+        bind(
             KeyBinding::alt(KeyCode::Char('w')),
             HotkeyAction::PrevInGroup,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('W')),
             HotkeyAction::PrevInGroup,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('s')),
             HotkeyAction::NextInGroup,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('S')),
             HotkeyAction::NextInGroup,
         );
 
         // Navigation - comma/period
-        bindings.insert(KeyBinding::alt(KeyCode::Char(',')), HotkeyAction::PrevGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('<')), HotkeyAction::PrevGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('.')), HotkeyAction::NextGroup);
-        bindings.insert(KeyBinding::alt(KeyCode::Char('>')), HotkeyAction::NextGroup);
+        bind(KeyBinding::alt(KeyCode::Char(',')), HotkeyAction::PrevGroup);
+        bind(KeyBinding::alt(KeyCode::Char('<')), HotkeyAction::PrevGroup);
+        bind(KeyBinding::alt(KeyCode::Char('.')), HotkeyAction::NextGroup);
+        bind(KeyBinding::alt(KeyCode::Char('>')), HotkeyAction::NextGroup);
 
         // Panel management
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Backspace),
             HotkeyAction::ToggleStacking,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::PageUp),
             HotkeyAction::SwapPanelLeft,
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::PageDown),
             HotkeyAction::SwapPanelRight,
         );
-        bindings.insert(KeyBinding::alt(KeyCode::Home), HotkeyAction::MoveToFirst);
-        bindings.insert(KeyBinding::alt(KeyCode::End), HotkeyAction::MoveToLast);
+        bind(KeyBinding::alt(KeyCode::Home), HotkeyAction::MoveToFirst);
+        bind(KeyBinding::alt(KeyCode::End), HotkeyAction::MoveToLast);
 
         // Resize
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('-')),
             HotkeyAction::ResizePanel(-1),
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('+')),
             HotkeyAction::ResizePanel(1),
         );
-        bindings.insert(
+        bind(
             KeyBinding::alt(KeyCode::Char('=')),
             HotkeyAction::ResizePanel(1),
         );
 
         // Number keys for panel selection
         for i in 1..=9u8 {
-            bindings.insert(
+            bind(
                 KeyBinding::alt(KeyCode::Char((b'0' + i) as char)),
                 HotkeyAction::GoToPanel(i as usize),
             );
         }
 
-        Self { bindings }
+        Self {
+            bindings,
+            pending: None,
+        }
     }
 
-    /// Add or replace a hotkey binding.
-    pub fn bind(&mut self, key: KeyBinding, action: HotkeyAction) {
-        self.bindings.insert(key, action);
+    /// Add or replace a hotkey binding (a single key or a chord).
+    ///
+    /// A binding that is also the prefix of a longer chord wins over it.
+    pub fn bind(&mut self, keys: impl Into<KeySequence>, action: HotkeyAction) {
+        self.bindings.insert(keys.into(), action);
     }
 
     /// Remove a hotkey binding.
-    pub fn unbind(&mut self, key: &KeyBinding) {
-        self.bindings.remove(key);
+    pub fn unbind(&mut self, keys: impl Into<KeySequence>) {
+        self.bindings.remove(&keys.into());
     }
 
     /// Get all current bindings.
-    pub fn bindings(&self) -> &HashMap<KeyBinding, HotkeyAction> {
+    pub fn bindings(&self) -> &HashMap<KeySequence, HotkeyAction> {
         &self.bindings
     }
+
+    /// Key sequences bound to an action as display text, sorted and
+    /// without duplicates (`Alt+m` and `Alt+M` both show as `Alt+M`).
+    pub fn keys_for(&self, action: &HotkeyAction) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(keys, _)| keys.to_string())
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Add bindings from config `(action name, keys)` pairs
+    /// (`close_panel = "ctrl+k ctrl+w"`). Invalid entries are skipped and
+    /// returned as error messages.
+    pub fn apply_keybindings<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, spec) in entries {
+            let Some(action) = HotkeyAction::from_name(name) else {
+                errors.push(format!("unknown action '{}'", name));
+                continue;
+            };
+            match spec.parse::<KeySequence>() {
+                Ok(keys) => self.bind(keys, action),
+                Err(error) => errors.push(format!("{}: {}", name, error)),
+            }
+        }
+        errors
+    }
+
+    /// Process a key event pressed at `now`.
+    pub fn process_hotkey_at(&mut self, key: &KeyEvent, now: Instant) -> HotkeyMatch {
+        let pending = self.pending.take();
+        let mut keys = match pending {
+            Some(pending) if now.duration_since(pending.pressed) <= CHORD_TIMEOUT => {
+                if key.code == KeyCode::Esc && key.modifiers.is_empty() {
+                    return HotkeyMatch::Cancelled;
+                }
+                pending.keys.keys().to_vec()
+            }
+            _ => Vec::new(),
+        };
+        keys.push(KeyBinding::from(*key));
+        let keys = KeySequence::new(keys);
+
+        if let Some(action) = self.bindings.get(&keys) {
+            return HotkeyMatch::Action(action.clone());
+        }
+        if self.bindings.keys().any(|binding| binding.extends(&keys)) {
+            self.pending = Some(PendingChord { keys, pressed: now });
+            return HotkeyMatch::Pending;
+        }
+        HotkeyMatch::NoMatch
+    }
+
+    /// Drop the pending chord prefix if it timed out at `now`.
+    ///
+    /// Returns true if a prefix was dropped.
+    pub fn expire_pending_at(&mut self, now: Instant) -> bool {
+        let expired = self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| now.duration_since(pending.pressed) > CHORD_TIMEOUT);
+        if expired {
+            self.pending = None;
+        }
+        expired
+    }
+
+    /// Drop the pending chord prefix if it timed out.
+    pub fn expire_pending(&mut self) -> bool {
+        self.expire_pending_at(Instant::now())
+    }
+
+    /// When the pending chord prefix times out
+    pub fn pending_deadline(&self) -> Option<Instant> {
+        self.pending
+            .as_ref()
+            .map(|pending| pending.pressed + CHORD_TIMEOUT)
+    }
 }
 
 impl HotkeyProcessor for DefaultHotkeyProcessor {
-    fn process_hotkey(&self, key: &KeyEvent) -> Option<HotkeyAction> {
-        // Only process Alt+key combinations
-        if !key.modifiers.contains(KeyModifiers::ALT) {
-            return None;
-        }
+    fn process_hotkey(&mut self, key: &KeyEvent) -> HotkeyMatch {
+        self.process_hotkey_at(key, Instant::now())
+    }
 
-        let binding = KeyBinding::from(*key);
-        self.bindings.get(&binding).cloned()
+    fn pending_prefix(&self) -> Option<&KeySequence> {
+        self.pending.as_ref().map(|pending| &pending.keys)
     }
 }
 
@@ -419,113 +834,116 @@ mod tests {
 
     #[test]
     fn test_default_processor_toggle_menu() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
         let action = processor.process_hotkey(&alt_key('m'));
-        assert_eq!(action, Some(HotkeyAction::ToggleMenu));
+        assert_eq!(action, HotkeyMatch::Action(HotkeyAction::ToggleMenu));
     }
 
     #[test]
     fn test_default_processor_new_file_manager() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
         let action = processor.process_hotkey(&alt_key('f'));
-        assert_eq!(action, Some(HotkeyAction::NewFileManager));
+        assert_eq!(action, HotkeyMatch::Action(HotkeyAction::NewFileManager));
     }
 
     #[test]
     fn test_default_processor_new_terminal() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
         let action = processor.process_hotkey(&alt_key('t'));
-        assert_eq!(action, Some(HotkeyAction::NewTerminal));
+        assert_eq!(action, HotkeyMatch::Action(HotkeyAction::NewTerminal));
     }
 
     #[test]
     fn test_default_processor_quit() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
         let action = processor.process_hotkey(&alt_key('q'));
-        assert_eq!(action, Some(HotkeyAction::RequestQuit));
+        assert_eq!(action, HotkeyMatch::Action(HotkeyAction::RequestQuit));
     }
 
     #[test]
     fn test_default_processor_navigation() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
 
         // Arrow keys
         assert_eq!(
             processor.process_hotkey(&key_event(KeyCode::Left, KeyModifiers::ALT)),
-            Some(HotkeyAction::PrevGroup)
+            HotkeyMatch::Action(HotkeyAction::PrevGroup)
         );
         assert_eq!(
             processor.process_hotkey(&key_event(KeyCode::Right, KeyModifiers::ALT)),
-            Some(HotkeyAction::NextGroup)
+            HotkeyMatch::Action(HotkeyAction::NextGroup)
         );
         assert_eq!(
             processor.process_hotkey(&key_event(KeyCode::Up, KeyModifiers::ALT)),
-            Some(HotkeyAction::PrevInGroup)
+            HotkeyMatch::Action(HotkeyAction::PrevInGroup)
         );
         assert_eq!(
             processor.process_hotkey(&key_event(KeyCode::Down, KeyModifiers::ALT)),
-            Some(HotkeyAction::NextInGroup)
+            HotkeyMatch::Action(HotkeyAction::NextInGroup)
         );
 
         // WASD alternatives
         assert_eq!(
             processor.process_hotkey(&alt_key('a')),
-            Some(HotkeyAction::PrevGroup)
+            HotkeyMatch::Action(HotkeyAction::PrevGroup)
         );
         assert_eq!(
             processor.process_hotkey(&alt_key('d')),
-            Some(HotkeyAction::NextGroup)
+            HotkeyMatch::Action(HotkeyAction::NextGroup)
         );
         assert_eq!(
             processor.process_hotkey(&alt_key('w')),
-            Some(HotkeyAction::PrevInGroup)
+            HotkeyMatch::Action(HotkeyAction::PrevInGroup)
         );
         assert_eq!(
             processor.process_hotkey(&alt_key('s')),
-            Some(HotkeyAction::NextInGroup)
+            HotkeyMatch::Action(HotkeyAction::NextInGroup)
         );
     }
 
     #[test]
     fn test_default_processor_panel_numbers() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
 
         for i in 1..=9 {
             let action = processor.process_hotkey(&alt_key((b'0' + i) as char));
-            assert_eq!(action, Some(HotkeyAction::GoToPanel(i as usize)));
+            assert_eq!(
+                action,
+                HotkeyMatch::Action(HotkeyAction::GoToPanel(i as usize))
+            );
         }
     }
 
     #[test]
     fn test_default_processor_resize() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
 
         assert_eq!(
             processor.process_hotkey(&alt_key('-')),
-            Some(HotkeyAction::ResizePanel(-1))
+            HotkeyMatch::Action(HotkeyAction::ResizePanel(-1))
         );
         assert_eq!(
             processor.process_hotkey(&alt_key('+')),
-            Some(HotkeyAction::ResizePanel(1))
+            HotkeyMatch::Action(HotkeyAction::ResizePanel(1))
         );
         assert_eq!(
             processor.process_hotkey(&alt_key('=')),
-            Some(HotkeyAction::ResizePanel(1))
+            HotkeyMatch::Action(HotkeyAction::ResizePanel(1))
         );
     }
 
     #[test]
     fn test_default_processor_non_alt_keys() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
 
-        // Non-Alt keys should return None
+        // Non-Alt keys are not default hotkeys
         assert_eq!(
             processor.process_hotkey(&key_event(KeyCode::Char('m'), KeyModifiers::NONE)),
-            None
+            HotkeyMatch::NoMatch
         );
         assert_eq!(
             processor.process_hotkey(&key_event(KeyCode::Char('m'), KeyModifiers::CONTROL)),
-            None
+            HotkeyMatch::NoMatch
         );
     }
 
@@ -600,11 +1018,190 @@ mod tests {
 
         assert_eq!(
             processor.process_hotkey(&alt_key('z')),
-            Some(HotkeyAction::ToggleStacking)
+            HotkeyMatch::Action(HotkeyAction::ToggleStacking)
         );
 
         // Remove binding
-        processor.unbind(&KeyBinding::alt(KeyCode::Char('z')));
-        assert_eq!(processor.process_hotkey(&alt_key('z')), None);
+        processor.unbind(KeyBinding::alt(KeyCode::Char('z')));
+        assert_eq!(
+            processor.process_hotkey(&alt_key('z')),
+            HotkeyMatch::NoMatch
+        );
+    }
+
+    fn ctrl_key(c: char) -> KeyEvent {
+        key_event(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn chord_processor() -> DefaultHotkeyProcessor {
+        let mut processor = DefaultHotkeyProcessor::new();
+        let errors = processor.apply_keybindings([("close_panel", "ctrl+k ctrl+w")]);
+        assert!(errors.is_empty());
+        processor
+    }
+
+    #[test]
+    fn test_chord_completes_after_prefix() {
+        let mut processor = chord_processor();
+        let start = Instant::now();
+
+        assert_eq!(
+            processor.process_hotkey_at(&ctrl_key('k'), start),
+            HotkeyMatch::Pending
+        );
+        assert_eq!(
+            processor.pending_prefix().map(|keys| keys.to_string()),
+            Some("Ctrl+K".to_string())
+        );
+        assert_eq!(
+            processor.process_hotkey_at(&ctrl_key('w'), start + Duration::from_millis(500)),
+            HotkeyMatch::Action(HotkeyAction::ClosePanel)
+        );
+        assert!(processor.pending_prefix().is_none());
+
+        // A second key completing no chord falls through to the panel
+        processor.process_hotkey_at(&ctrl_key('k'), start);
+        assert_eq!(
+            processor.process_hotkey_at(&ctrl_key('x'), start),
+            HotkeyMatch::NoMatch
+        );
+        assert!(processor.pending_prefix().is_none());
+    }
+
+    #[test]
+    fn test_chord_prefix_times_out() {
+        let mut processor = chord_processor();
+        let start = Instant::now();
+        let late = start + CHORD_TIMEOUT + Duration::from_millis(1);
+
+        processor.process_hotkey_at(&ctrl_key('k'), start);
+        assert!(!processor.expire_pending_at(start + CHORD_TIMEOUT));
+        assert!(processor.expire_pending_at(late));
+        assert!(processor.pending_prefix().is_none());
+
+        // A late second key starts over instead of completing the chord
+        processor.process_hotkey_at(&ctrl_key('k'), start);
+        assert_eq!(
+            processor.process_hotkey_at(&ctrl_key('w'), late),
+            HotkeyMatch::NoMatch
+        );
+    }
+
+    #[test]
+    fn test_escape_cancels_chord_prefix() {
+        let mut processor = chord_processor();
+        let start = Instant::now();
+        let esc = key_event(KeyCode::Esc, KeyModifiers::NONE);
+
+        processor.process_hotkey_at(&ctrl_key('k'), start);
+        assert_eq!(
+            processor.process_hotkey_at(&esc, start),
+            HotkeyMatch::Cancelled
+        );
+        assert!(processor.pending_prefix().is_none());
+        assert_eq!(
+            processor.process_hotkey_at(&ctrl_key('w'), start),
+            HotkeyMatch::NoMatch
+        );
+
+        // Without a pending prefix Escape is left to the panel
+        assert_eq!(
+            processor.process_hotkey_at(&esc, start),
+            HotkeyMatch::NoMatch
+        );
+    }
+
+    #[test]
+    fn test_keybindings_from_config() {
+        let mut processor = DefaultHotkeyProcessor::new();
+        let errors = processor.apply_keybindings([
+            ("go_to_panel_2", "space 2"),
+            ("new_terminal", "ctrl+shift+t"),
+            ("go_to_panel_10", "f10"),
+            ("toggle_menu", "ctrl+bogus"),
+        ]);
+        assert_eq!(errors.len(), 2);
+
+        assert_eq!(
+            processor.process_hotkey(&key_event(
+                KeyCode::Char('T'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )),
+            HotkeyMatch::Action(HotkeyAction::NewTerminal)
+        );
+        assert_eq!(
+            processor.process_hotkey(&key_event(KeyCode::Char(' '), KeyModifiers::NONE)),
+            HotkeyMatch::Pending
+        );
+        assert_eq!(
+            processor.process_hotkey(&key_event(KeyCode::Char('2'), KeyModifiers::NONE)),
+            HotkeyMatch::Action(HotkeyAction::GoToPanel(2))
+        );
+        // Defaults are kept
+        assert_eq!(
+            processor.process_hotkey(&alt_key('x')),
+            HotkeyMatch::Action(HotkeyAction::ClosePanel)
+        );
+    }
+
+    #[test]
+    fn test_action_names_round_trip() {
+        for action in HotkeyAction::all() {
+            assert_eq!(HotkeyAction::from_name(&action.name()), Some(action));
+        }
+        assert_eq!(HotkeyAction::GoToPanel(4).name(), "go_to_panel_4");
+
+        let open_panel = HotkeyAction::OpenPanel("scratchpad".to_string());
+        assert_eq!(open_panel.name(), "open_panel_scratchpad");
+        assert_eq!(
+            HotkeyAction::from_name("open_panel_scratchpad"),
+            Some(open_panel)
+        );
+        assert_eq!(HotkeyAction::from_name("open_panel_"), None);
+    }
+
+    #[test]
+    fn test_action_categories() {
+        assert_eq!(HotkeyAction::NewTerminal.category(), ActionCategory::Panels);
+        assert_eq!(
+            HotkeyAction::GoToPanel(2).category(),
+            ActionCategory::Navigation
+        );
+        assert_eq!(
+            HotkeyAction::ResizePanel(-1).category(),
+            ActionCategory::Layout
+        );
+        assert_eq!(
+            HotkeyAction::RequestQuit.category(),
+            ActionCategory::Application
+        );
+        // Every category has something to show
+        for category in ActionCategory::ALL {
+            assert!(HotkeyAction::all()
+                .iter()
+                .any(|action| action.category() == category));
+        }
+    }
+
+    #[test]
+    fn test_keys_for_action() {
+        let mut processor = DefaultHotkeyProcessor::new();
+        assert_eq!(
+            processor.keys_for(&HotkeyAction::ClosePanel),
+            vec!["Alt+Delete", "Alt+X"]
+        );
+        assert_eq!(
+            processor.keys_for(&HotkeyAction::CommandPalette),
+            vec!["Ctrl+Shift+P"]
+        );
+
+        processor.bind(
+            "ctrl+k ctrl+w".parse::<KeySequence>().unwrap(),
+            HotkeyAction::ClosePanel,
+        );
+        assert_eq!(
+            processor.keys_for(&HotkeyAction::ClosePanel),
+            vec!["Alt+Delete", "Alt+X", "Ctrl+K Ctrl+W"]
+        );
     }
 }
@@ -0,0 +1,218 @@
+//! Key sequences (chords) and their text form.
+//!
+//! Keys are written as `+`-joined modifiers and a key name (`alt+x`,
+//! `ctrl+shift+f9`, `space`), sequences as keys separated by spaces
+//! (`ctrl+k ctrl+w`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::KeyBinding;
+
+/// Named keys: (name, code). The first name of a code is used for display.
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("Space", KeyCode::Char(' ')),
+    ("Enter", KeyCode::Enter),
+    ("Return", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Escape", KeyCode::Esc),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Del", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Ins", KeyCode::Insert),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PgUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("PgDn", KeyCode::PageDown),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+];
+
+/// A sequence of keys pressed one after another (`Ctrl+K Ctrl+W`).
+///
+/// Single-key hotkeys are sequences of one key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence {
+    keys: Vec<KeyBinding>,
+}
+
+impl KeySequence {
+    /// Create a sequence from keys.
+    pub fn new(keys: Vec<KeyBinding>) -> Self {
+        Self { keys }
+    }
+
+    /// Keys of the sequence.
+    pub fn keys(&self) -> &[KeyBinding] {
+        &self.keys
+    }
+
+    /// Check if this sequence starts with all keys of `prefix` and is longer.
+    pub fn extends(&self, prefix: &KeySequence) -> bool {
+        self.keys.len() > prefix.keys.len() && self.keys.starts_with(&prefix.keys)
+    }
+}
+
+impl From<KeyBinding> for KeySequence {
+    fn from(key: KeyBinding) -> Self {
+        Self::new(vec![key])
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    /// Parse a key like `ctrl+k`, `alt+shift+left` or `f5` (case-insensitive).
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        // "ctrl++" binds the plus key
+        let (modifiers, key) = match spec.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => spec.rsplit_once('+').unwrap_or(("", spec)),
+        };
+        if key.is_empty() {
+            return Err(format!("missing key in '{}'", spec));
+        }
+
+        let mut mods = KeyModifiers::NONE;
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            mods |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", modifier, spec)),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => parse_key_name(key).ok_or_else(|| format!("unknown key '{}'", key))?,
+        };
+
+        // Terminals report Shift+letter as the uppercase letter
+        let code = match code {
+            KeyCode::Char(ch) if mods.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(ch.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        Ok(KeyBinding::new(code, mods))
+    }
+}
+
+/// Key code for a key name (`Esc`, `PageUp`, `F5`), case-insensitive
+fn parse_key_name(name: &str) -> Option<KeyCode> {
+    if let Some((_, code)) = KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+    {
+        return Some(*code);
+    }
+    let number = name.strip_prefix(['f', 'F'])?.parse::<u8>().ok()?;
+    (1..=12).contains(&number).then_some(KeyCode::F(number))
+}
+
+impl FromStr for KeySequence {
+    type Err = String;
+
+    /// Parse space-separated keys (`ctrl+k ctrl+w`).
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let keys = spec
+            .split_whitespace()
+            .map(KeyBinding::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err("empty key sequence".to_string());
+        }
+        Ok(Self::new(keys))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    /// Format as `Ctrl+Alt+Shift+Key`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl"),
+            (KeyModifiers::ALT, "Alt"),
+            (KeyModifiers::SHIFT, "Shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        if let Some((name, _)) = KEY_NAMES.iter().find(|(_, code)| *code == self.code) {
+            return f.write_str(name);
+        }
+        match self.code {
+            KeyCode::Char(ch) => write!(f, "{}", ch.to_ascii_uppercase()),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+impl fmt::Display for KeySequence {
+    /// Format as space-separated keys
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, key) in self.keys.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            "ctrl+k".parse(),
+            Ok(KeyBinding::new(KeyCode::Char('k'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            "Ctrl+Shift+k".parse(),
+            Ok(KeyBinding::new(
+                KeyCode::Char('K'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ))
+        );
+        assert_eq!("alt+PgUp".parse(), Ok(KeyBinding::alt(KeyCode::PageUp)));
+        assert_eq!("space".parse(), Ok(KeyBinding::plain(KeyCode::Char(' '))));
+        assert_eq!("f12".parse(), Ok(KeyBinding::plain(KeyCode::F(12))));
+        assert_eq!("alt++".parse(), Ok(KeyBinding::alt(KeyCode::Char('+'))));
+
+        assert!("hyper+k".parse::<KeyBinding>().is_err());
+        assert!("ctrl+".parse::<KeyBinding>().is_err());
+        assert!("f13".parse::<KeyBinding>().is_err());
+        assert!("ctrl+foo".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        let sequence: KeySequence = "ctrl+k  ctrl+w".parse().unwrap();
+        assert_eq!(sequence.keys().len(), 2);
+        assert_eq!(sequence.to_string(), "Ctrl+K Ctrl+W");
+        assert!(sequence.extends(&"ctrl+k".parse().unwrap()));
+        assert!(!sequence.extends(&sequence));
+
+        assert_eq!(
+            "space f".parse::<KeySequence>().unwrap().to_string(),
+            "Space F"
+        );
+        assert!("".parse::<KeySequence>().is_err());
+        assert!("ctrl+k bogus+w".parse::<KeySequence>().is_err());
+    }
+}
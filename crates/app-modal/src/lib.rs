@@ -22,7 +22,9 @@ use std::path::PathBuf;
 use anyhow::Result;
 
 use termide_app_core::AppCommand;
-use termide_state::{BatchOperation, BatchOperationType, ConflictMode, RenamePattern};
+use termide_state::{
+    BatchOperation, BatchOperationType, CancellationToken, ConflictMode, RenamePattern,
+};
 
 // ============================================================================
 // Modal Result Types
@@ -113,6 +115,12 @@ pub enum BatchOperationState {
         /// Final operation state with statistics
         operation: BatchOperation,
     },
+
+    /// Operation stopped by user
+    Cancelled {
+        /// Operation state with partial statistics
+        operation: BatchOperation,
+    },
 }
 
 impl BatchOperationState {
@@ -141,13 +149,19 @@ impl BatchOperationState {
         matches!(self, Self::Completed { .. })
     }
 
+    /// Check if operation was cancelled by user.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled { .. })
+    }
+
     /// Get operation if available.
     pub fn operation(&self) -> Option<&BatchOperation> {
         match self {
             Self::AwaitingConflict { operation, .. }
             | Self::AwaitingRename { operation, .. }
             | Self::InProgress { operation }
-            | Self::Completed { operation } => Some(operation),
+            | Self::Completed { operation }
+            | Self::Cancelled { operation } => Some(operation),
             _ => None,
         }
     }
@@ -383,6 +397,51 @@ impl BatchOperationProcessor {
         }
     }
 
+    /// Record bytes copied by the transfer worker.
+    pub fn record_bytes(&mut self, bytes: u64) {
+        if let BatchOperationState::InProgress { operation } = &mut self.state {
+            operation.add_bytes(bytes);
+        }
+    }
+
+    /// Set total bytes of all sources (once scanned).
+    pub fn set_bytes_total(&mut self, bytes: u64) {
+        if let BatchOperationState::InProgress { operation } = &mut self.state {
+            operation.set_bytes_total(bytes);
+        }
+    }
+
+    /// Get cancellation token to hand to transfer workers.
+    pub fn cancel_token(&self) -> Option<CancellationToken> {
+        self.state.operation().map(|op| op.cancel_token.clone())
+    }
+
+    /// Ask running transfer to stop after the current chunk.
+    pub fn request_cancel(&self) {
+        if let Some(operation) = self.state.operation() {
+            operation.request_cancel();
+        }
+    }
+
+    /// Mark in-progress operation as cancelled, keeping partial statistics.
+    ///
+    /// Transitions from InProgress to Cancelled.
+    pub fn mark_cancelled(&mut self) -> ProcessResult {
+        match std::mem::take(&mut self.state) {
+            BatchOperationState::InProgress { mut operation } => {
+                operation.mark_cancelled();
+                self.state = BatchOperationState::Cancelled { operation };
+                ProcessResult::Cancelled
+            }
+            state => {
+                self.state = state;
+                ProcessResult::Error {
+                    message: "No operation in progress".to_string(),
+                }
+            }
+        }
+    }
+
     /// Cancel the operation.
     pub fn cancel(&mut self) {
         self.state = BatchOperationState::Idle;
@@ -393,6 +452,13 @@ impl BatchOperationProcessor {
         self.state = BatchOperationState::Idle;
     }
 
+    /// Get bytes progress (done, total if scanned).
+    pub fn bytes_progress(&self) -> Option<(u64, Option<u64>)> {
+        self.state
+            .operation()
+            .map(|op| (op.bytes_done, op.bytes_total))
+    }
+
     /// Get operation statistics.
     pub fn statistics(&self) -> Option<(usize, usize, usize, usize)> {
         self.state.operation().map(|op| {
@@ -599,6 +665,44 @@ mod tests {
         assert_eq!(total, 3);
     }
 
+    #[test]
+    fn test_processor_bytes_progress() {
+        let mut processor = BatchOperationProcessor::new();
+        processor
+            .start(test_sources(), BatchOperationType::Copy)
+            .unwrap();
+        processor.set_destination(PathBuf::from("/dest")).unwrap();
+
+        assert_eq!(processor.bytes_progress(), Some((0, None)));
+        processor.set_bytes_total(100);
+        processor.record_bytes(40);
+        processor.record_bytes(20);
+        assert_eq!(processor.bytes_progress(), Some((60, Some(100))));
+    }
+
+    #[test]
+    fn test_processor_mark_cancelled_keeps_statistics() {
+        let mut processor = BatchOperationProcessor::new();
+        processor
+            .start(test_sources(), BatchOperationType::Copy)
+            .unwrap();
+        processor.set_destination(PathBuf::from("/dest")).unwrap();
+
+        let _ = processor.process_current(|_, _| false);
+        processor.mark_success();
+        processor.record_bytes(10);
+
+        let token = processor.cancel_token().unwrap();
+        processor.request_cancel();
+        assert!(token.is_cancelled());
+
+        let result = processor.mark_cancelled();
+        assert!(matches!(result, ProcessResult::Cancelled));
+        assert!(processor.state().is_cancelled());
+        assert_eq!(processor.statistics(), Some((1, 0, 0, 3)));
+        assert_eq!(processor.bytes_progress(), Some((10, None)));
+    }
+
     #[test]
     fn test_conflict_result_variants() {
         assert_eq!(ConflictResult::Overwrite, ConflictResult::Overwrite);
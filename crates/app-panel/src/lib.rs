@@ -4,6 +4,7 @@
 //! - `PanelFactory` trait for creating panel instances
 //! - `PanelLifecycle` trait for managing panel close/cleanup
 //! - `CloseDecision` enum for close confirmation results
+//! - `PanelRegistry` for panel types registered outside the app crate
 //!
 //! # Architecture
 //!
@@ -22,6 +23,10 @@ use anyhow::Result;
 
 use termide_app_core::{Panel, PanelType};
 
+mod registry;
+
+pub use registry::{PanelConstructor, PanelParams, PanelRegistration, PanelRegistry};
+
 // ============================================================================
 // Panel Creation Configuration
 // ============================================================================
@@ -70,6 +75,13 @@ pub enum PanelCreationConfig {
     LogViewer,
     /// Create welcome panel (no config needed)
     Welcome,
+    /// Create panel of a registered type
+    Custom {
+        /// Registered panel type name
+        name: String,
+        /// Parameters passed to the constructor
+        params: PanelParams,
+    },
 }
 
 impl From<PanelType> for PanelCreationConfig {
@@ -91,6 +103,7 @@ impl From<PanelType> for PanelCreationConfig {
             }
             PanelType::LogViewer => PanelCreationConfig::LogViewer,
             PanelType::Welcome => PanelCreationConfig::Welcome,
+            PanelType::Custom { name, params } => PanelCreationConfig::Custom { name, params },
         }
     }
 }
@@ -106,7 +119,16 @@ impl From<PanelType> for PanelCreationConfig {
 /// specific panel implementations.
 pub trait PanelFactory {
     /// Create a panel from configuration.
-    fn create(&self, config: PanelCreationConfig) -> Result<Box<dyn Panel>>;
+    fn create(&self, config: PanelCreationConfig) -> Result<Box<dyn Panel>> {
+        match config {
+            PanelCreationConfig::Editor(config) => self.create_editor(config),
+            PanelCreationConfig::Terminal(config) => self.create_terminal(config),
+            PanelCreationConfig::FileManager(config) => self.create_file_manager(config),
+            PanelCreationConfig::LogViewer => self.create_log_viewer(),
+            PanelCreationConfig::Welcome => self.create_welcome(),
+            PanelCreationConfig::Custom { name, params } => self.create_custom(&name, &params),
+        }
+    }
 
     /// Create an editor panel.
     fn create_editor(&self, config: EditorCreationConfig) -> Result<Box<dyn Panel>>;
@@ -122,6 +144,14 @@ pub trait PanelFactory {
 
     /// Create a welcome panel.
     fn create_welcome(&self) -> Result<Box<dyn Panel>>;
+
+    /// Registered panel types.
+    fn registry(&self) -> &PanelRegistry;
+
+    /// Create a panel of a registered type.
+    fn create_custom(&self, name: &str, params: &PanelParams) -> Result<Box<dyn Panel>> {
+        self.registry().create(name, params)
+    }
 }
 
 // ============================================================================
@@ -149,7 +179,7 @@ pub enum CloseDecision {
 pub enum ConfirmationType {
     /// Simple yes/no confirmation
     Simple,
-    /// Editor with unsaved changes (save/discard/cancel)
+    /// Editor with unsaved changes (save/discard/review/cancel)
     UnsavedChanges,
     /// Editor with external changes (overwrite/reload/cancel)
     ExternalChanges,
@@ -361,6 +391,134 @@ mod tests {
         let welcome_type = PanelType::Welcome;
         let config: PanelCreationConfig = welcome_type.into();
         assert!(matches!(config, PanelCreationConfig::Welcome));
+
+        let custom_type = PanelType::Custom {
+            name: "notes".to_string(),
+            params: PanelParams::new(),
+        };
+        let config: PanelCreationConfig = custom_type.into();
+        assert!(matches!(config, PanelCreationConfig::Custom { name, .. } if name == "notes"));
+    }
+
+    /// Panel of a registered type showing its parameter
+    struct MockPanel {
+        text: String,
+    }
+
+    impl Panel for MockPanel {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn title(&self) -> String {
+            self.text.clone()
+        }
+
+        fn render(
+            &mut self,
+            _area: ratatui::layout::Rect,
+            _buf: &mut ratatui::buffer::Buffer,
+            _ctx: &termide_core::RenderContext,
+        ) {
+        }
+
+        fn handle_key(
+            &mut self,
+            _key: crossterm::event::KeyEvent,
+        ) -> Vec<termide_app_core::PanelEvent> {
+            vec![]
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+    }
+
+    /// Factory creating only registered panels
+    struct MockFactory {
+        registry: PanelRegistry,
+    }
+
+    impl PanelFactory for MockFactory {
+        fn create_editor(&self, _config: EditorCreationConfig) -> Result<Box<dyn Panel>> {
+            anyhow::bail!("not supported")
+        }
+
+        fn create_terminal(&self, _config: TerminalCreationConfig) -> Result<Box<dyn Panel>> {
+            anyhow::bail!("not supported")
+        }
+
+        fn create_file_manager(
+            &self,
+            _config: FileManagerCreationConfig,
+        ) -> Result<Box<dyn Panel>> {
+            anyhow::bail!("not supported")
+        }
+
+        fn create_log_viewer(&self) -> Result<Box<dyn Panel>> {
+            anyhow::bail!("not supported")
+        }
+
+        fn create_welcome(&self) -> Result<Box<dyn Panel>> {
+            anyhow::bail!("not supported")
+        }
+
+        fn registry(&self) -> &PanelRegistry {
+            &self.registry
+        }
+    }
+
+    fn mock_registration(name: &str) -> PanelRegistration {
+        PanelRegistration::new(name, "Mock", |params: &PanelParams| {
+            let text = params.get("text").cloned().unwrap_or_default();
+            Ok(Box::new(MockPanel { text }) as Box<dyn Panel>)
+        })
+    }
+
+    #[test]
+    fn test_create_registered_panel_through_factory() {
+        let mut registry = PanelRegistry::new();
+        registry
+            .register(mock_registration("mock").with_hotkey("alt+j"))
+            .unwrap();
+        let factory = MockFactory { registry };
+
+        let params = PanelParams::from([("text".to_string(), "hello".to_string())]);
+        let panel = factory
+            .create(
+                PanelType::Custom {
+                    name: "mock".to_string(),
+                    params,
+                }
+                .into(),
+            )
+            .unwrap();
+        assert_eq!(panel.name(), "mock");
+        assert_eq!(panel.title(), "hello");
+
+        let registration = factory.registry().get("mock").unwrap();
+        assert_eq!(registration.action_name(), "open_panel_mock");
+        assert_eq!(registration.hotkey.as_deref(), Some("alt+j"));
+
+        let unknown = PanelType::Custom {
+            name: "missing".to_string(),
+            params: PanelParams::new(),
+        };
+        assert!(factory.create(unknown.into()).is_err());
+    }
+
+    #[test]
+    fn test_register_rejects_duplicate_and_invalid_names() {
+        let mut registry = PanelRegistry::new();
+        registry.register(mock_registration("mock")).unwrap();
+        assert!(registry.register(mock_registration("mock")).is_err());
+        assert!(registry.register(mock_registration("")).is_err());
+        assert!(registry.register(mock_registration("two words")).is_err());
+        assert_eq!(registry.iter().count(), 1);
     }
 
     #[test]
@@ -0,0 +1,123 @@
+//! Registry of panel types defined outside the app crate.
+//!
+//! A crate (or the app at startup) registers a panel type under a name with
+//! a constructor taking string parameters, a label for the command palette
+//! and an optional default hotkey. `PanelType::Custom` creates registered
+//! panels through the registry, and panels returning
+//! `SessionPanel::Custom` from `to_session` are created again the same way
+//! when the session is restored.
+//!
+//! ```text
+//! PanelType::Custom { name, params } → PanelRegistry → constructor(params)
+//! ```
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use termide_app_core::Panel;
+
+/// Parameters of a registered panel (also saved in the session)
+pub type PanelParams = BTreeMap<String, String>;
+
+/// Constructor of a registered panel type
+pub type PanelConstructor = Box<dyn Fn(&PanelParams) -> Result<Box<dyn Panel>>>;
+
+/// A registered panel type.
+pub struct PanelRegistration {
+    /// Panel type name (unique, used in the session and in
+    /// `open_panel_<name>` keybindings)
+    pub name: String,
+    /// Command palette entry opening the panel
+    pub label: String,
+    /// Default keys opening the panel (`"alt+j"`, `"ctrl+k s"`)
+    pub hotkey: Option<String>,
+    constructor: PanelConstructor,
+}
+
+impl PanelRegistration {
+    /// Register a panel type created by `constructor`.
+    pub fn new(
+        name: impl Into<String>,
+        label: impl Into<String>,
+        constructor: impl Fn(&PanelParams) -> Result<Box<dyn Panel>> + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            label: label.into(),
+            hotkey: None,
+            constructor: Box::new(constructor),
+        }
+    }
+
+    /// Open the panel with `keys` unless the config binds them otherwise.
+    pub fn with_hotkey(mut self, keys: impl Into<String>) -> Self {
+        self.hotkey = Some(keys.into());
+        self
+    }
+
+    /// Config name of the action opening the panel.
+    pub fn action_name(&self) -> String {
+        format!("open_panel_{}", self.name)
+    }
+}
+
+impl std::fmt::Debug for PanelRegistration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PanelRegistration")
+            .field("name", &self.name)
+            .field("label", &self.label)
+            .field("hotkey", &self.hotkey)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Registered panel types, in registration order.
+#[derive(Debug, Default)]
+pub struct PanelRegistry {
+    panels: Vec<PanelRegistration>,
+}
+
+impl PanelRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a panel type. Fails if the name is empty, contains characters
+    /// other than letters, digits and `_`, or is already registered.
+    pub fn register(&mut self, registration: PanelRegistration) -> Result<()> {
+        let name = &registration.name;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("Invalid panel type name '{}'", name);
+        }
+        if self.get(name).is_some() {
+            bail!("Panel type '{}' is already registered", name);
+        }
+        self.panels.push(registration);
+        Ok(())
+    }
+
+    /// Get a registered panel type.
+    pub fn get(&self, name: &str) -> Option<&PanelRegistration> {
+        self.panels.iter().find(|panel| panel.name == name)
+    }
+
+    /// Iterate over registered panel types in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &PanelRegistration> {
+        self.panels.iter()
+    }
+
+    /// Check if no panel types are registered.
+    pub fn is_empty(&self) -> bool {
+        self.panels.is_empty()
+    }
+
+    /// Create a panel of a registered type.
+    pub fn create(&self, name: &str, params: &PanelParams) -> Result<Box<dyn Panel>> {
+        match self.get(name) {
+            Some(registration) => (registration.constructor)(params),
+            None => bail!("Unknown panel type '{}'", name),
+        }
+    }
+}
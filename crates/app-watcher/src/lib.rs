@@ -2,7 +2,8 @@
 //!
 //! This crate provides:
 //! - `WatcherRegistry` trait for registering/unregistering watches
-//! - `MessageCollector` for aggregating messages from multiple sources
+//! - `MessageCollector` for aggregating messages from multiple sources,
+//!   coalescing filesystem changes per directory
 //! - `UpdateThrottler` for rate-limiting updates
 //!
 //! # Architecture
@@ -21,6 +22,7 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use termide_app_core::{FileChange, GitStatus, Message};
+use termide_watcher::FileSystemWatcher;
 
 // ============================================================================
 // Watcher Registry Trait
@@ -45,6 +47,55 @@ pub trait WatcherRegistry {
 
     /// Check if a directory is being watched.
     fn is_watching_directory(&self, path: &Path) -> bool;
+
+    /// Register a directory and its subdirectories for watching, skipping
+    /// gitignored ones.
+    fn register_recursive(&mut self, path: &Path) -> anyhow::Result<()>;
+
+    /// Unregister a recursive watch, with the watches of all its
+    /// subdirectories.
+    fn unregister_recursive(&mut self, path: &Path);
+
+    /// Check if a directory is being watched recursively.
+    fn is_watching_recursive(&self, path: &Path) -> bool;
+}
+
+impl WatcherRegistry for FileSystemWatcher {
+    fn register_git_repo(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.watch_repository(path.to_path_buf())
+    }
+
+    fn unregister_git_repo(&mut self, path: &Path) {
+        self.unwatch_repository(path);
+    }
+
+    fn is_watching_git_repo(&self, path: &Path) -> bool {
+        self.is_watching_repo(path)
+    }
+
+    fn register_directory(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.watch_directory(path.to_path_buf())
+    }
+
+    fn unregister_directory(&mut self, path: &Path) {
+        self.unwatch_directory(path);
+    }
+
+    fn is_watching_directory(&self, path: &Path) -> bool {
+        self.is_watching_dir(path)
+    }
+
+    fn register_recursive(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.watch_recursive(path.to_path_buf())
+    }
+
+    fn unregister_recursive(&mut self, path: &Path) {
+        self.unwatch_recursive(path);
+    }
+
+    fn is_watching_recursive(&self, path: &Path) -> bool {
+        FileSystemWatcher::is_watching_recursive(self, path)
+    }
 }
 
 // ============================================================================
@@ -101,14 +152,22 @@ pub trait UpdateReceiver {
 }
 
 /// Collects and deduplicates updates from multiple sources.
+///
+/// Filesystem changes are coalesced per directory: the first change in a
+/// directory stands for all the others until the message is taken.
 #[derive(Debug, Default)]
 pub struct MessageCollector {
     /// Pending messages
     messages: Vec<Message>,
     /// Paths that have pending git updates (for deduplication)
     pending_git_paths: HashSet<PathBuf>,
-    /// Paths that have pending fs updates (for deduplication)
-    pending_fs_paths: HashSet<PathBuf>,
+    /// Directories that have pending fs updates (for coalescing)
+    pending_fs_dirs: HashSet<PathBuf>,
+}
+
+/// Directory whose listing a filesystem change affects
+fn changed_directory(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(path).to_path_buf()
 }
 
 impl MessageCollector {
@@ -127,8 +186,8 @@ impl MessageCollector {
                 }
             }
             WatcherUpdate::FileSystem { path, .. } => {
-                // Deduplicate fs updates by path
-                if self.pending_fs_paths.insert(path.clone()) {
+                // Coalesce fs updates by directory
+                if self.pending_fs_dirs.insert(changed_directory(path)) {
                     self.messages.push(update.to_message());
                 }
             }
@@ -142,10 +201,36 @@ impl MessageCollector {
     /// Take all collected messages.
     pub fn take_messages(&mut self) -> Vec<Message> {
         self.pending_git_paths.clear();
-        self.pending_fs_paths.clear();
+        self.pending_fs_dirs.clear();
         std::mem::take(&mut self.messages)
     }
 
+    /// Take collected messages, holding back fs updates of directories
+    /// updated less than the throttler's interval ago.
+    ///
+    /// Held updates keep coalescing further changes in their directory and
+    /// are returned by a later call once the interval has passed, so a burst
+    /// of changes yields at most one update per directory and interval.
+    pub fn take_throttled(&mut self, throttler: &mut UpdateThrottler) -> Vec<Message> {
+        let (ready, held): (Vec<_>, Vec<_>) = std::mem::take(&mut self.messages)
+            .into_iter()
+            .partition(|message| match message {
+                Message::FsUpdate { path, .. } => throttler.should_update(&changed_directory(path)),
+                _ => true,
+            });
+
+        self.pending_git_paths.clear();
+        self.pending_fs_dirs = held
+            .iter()
+            .filter_map(|message| match message {
+                Message::FsUpdate { path, .. } => Some(changed_directory(path)),
+                _ => None,
+            })
+            .collect();
+        self.messages = held;
+        ready
+    }
+
     /// Check if there are any pending messages.
     pub fn has_messages(&self) -> bool {
         !self.messages.is_empty()
@@ -160,7 +245,7 @@ impl MessageCollector {
     pub fn clear(&mut self) {
         self.messages.clear();
         self.pending_git_paths.clear();
-        self.pending_fs_paths.clear();
+        self.pending_fs_dirs.clear();
     }
 }
 
@@ -396,15 +481,116 @@ mod tests {
         // Should only have one message
         assert_eq!(collector.message_count(), 1);
 
-        // Add different path
+        // Another file in the same directory is coalesced
         collector.add_update(WatcherUpdate::FileSystem {
             path: PathBuf::from("/other.txt"),
             change: FileChange::Created,
         });
+        assert_eq!(collector.message_count(), 1);
+
+        // Add path in a different directory
+        collector.add_update(WatcherUpdate::FileSystem {
+            path: PathBuf::from("/dir/other.txt"),
+            change: FileChange::Created,
+        });
 
         assert_eq!(collector.message_count(), 2);
     }
 
+    #[test]
+    fn test_message_collector_take_throttled() {
+        let mut collector = MessageCollector::new();
+        let mut throttler = UpdateThrottler::new(Duration::from_millis(50));
+        let change = |path: &str| WatcherUpdate::FileSystem {
+            path: PathBuf::from(path),
+            change: FileChange::Modified,
+        };
+
+        collector.add_update(change("/src/a.rs"));
+        collector.add_update(change("/src/b.rs"));
+        assert_eq!(collector.take_throttled(&mut throttler).len(), 1);
+
+        // Within the interval the directory's update is held, still coalescing
+        collector.add_update(change("/src/c.rs"));
+        collector.add_update(change("/src/d.rs"));
+        collector.add_update(change("/doc/e.md"));
+        let messages = collector.take_throttled(&mut throttler);
+        assert_eq!(messages.len(), 1);
+        assert!(
+            matches!(&messages[0], Message::FsUpdate { path, .. } if path == Path::new("/doc/e.md"))
+        );
+        assert_eq!(collector.message_count(), 1);
+
+        std::thread::sleep(Duration::from_millis(60));
+        let messages = collector.take_throttled(&mut throttler);
+        assert!(
+            matches!(&messages[..], [Message::FsUpdate { path, .. }] if path == Path::new("/src/c.rs"))
+        );
+        assert!(!collector.has_messages());
+    }
+
+    #[test]
+    fn test_nested_changes_refresh_once() {
+        use std::process::Command;
+
+        let root = std::env::temp_dir().join(format!("termide_fs_watch_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src/module")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        let initialized = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&root)
+            .status()
+            .is_ok_and(|status| status.success());
+        if !initialized {
+            let _ = std::fs::remove_dir_all(&root);
+            return;
+        }
+
+        let (tx, rx) = termide_wake::channel();
+        let mut watcher = FileSystemWatcher::new(tx).unwrap();
+        watcher.register_recursive(&root).unwrap();
+        // root, src and src/module
+        assert_eq!(watcher.watched_tree_dirs(), 3);
+
+        // A new ignored directory is not watched and its churn is dropped
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(root.join("src/module").join(name), name).unwrap();
+        }
+        std::fs::write(root.join("target/debug/out.o"), "out").unwrap();
+
+        let mut updates = Vec::new();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while updates.len() < 4 && std::time::Instant::now() < deadline {
+            if let Ok(update) = rx.recv_timeout(Duration::from_millis(100)) {
+                updates.push(update);
+            }
+        }
+        let updates = watcher.filter_updates(updates);
+        assert_eq!(watcher.watched_tree_dirs(), 3);
+
+        let mut collector = MessageCollector::new();
+        for update in updates {
+            collector.add_update(WatcherUpdate::FileSystem {
+                path: update.changed_path,
+                change: FileChange::Modified,
+            });
+        }
+        let messages = collector.take_throttled(&mut UpdateThrottler::default());
+        assert!(
+            matches!(&messages[..], [Message::FsUpdate { path, .. }] if path.parent() == Some(&root.join("src/module"))),
+            "{:?}",
+            messages
+        );
+
+        // Unregistering drops the watches of every subdirectory
+        watcher.unregister_recursive(&root);
+        assert!(!watcher.is_watching_recursive(&root));
+        assert_eq!(watcher.watched_tree_dirs(), 0);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn test_message_collector_take_messages() {
         let mut collector = MessageCollector::new();
@@ -0,0 +1,146 @@
+//! Bookmarks: named lines of project files.
+//!
+//! Editors keep marks for the bookmarks of their file, which move with the
+//! text; on each tick the application reads the marks back. Bookmarks of
+//! files not open in an editor are checked against the file when the list
+//! is opened. Bookmarks of deleted files stay listed until removed.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use termide_i18n as i18n;
+use termide_modal::{BookmarkChoice, BookmarkItem, BookmarksModal, InputModal};
+use termide_session::Bookmark;
+
+use super::App;
+use crate::state::{ActiveModal, PendingAction};
+use crate::PanelExt;
+
+impl App {
+    /// Read back where editors show bookmarks now, then let editors place
+    /// marks for bookmarks set or removed since
+    pub(super) fn sync_bookmarks(&mut self) {
+        let bookmarks = &mut self.state.bookmarks;
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            let Some(editor) = panel.as_editor_mut() else {
+                continue;
+            };
+            if let Some(path) = editor.file_path().map(Path::to_path_buf) {
+                for (name, line, text) in editor.bookmark_positions() {
+                    bookmarks.update(&path, &name, line, &text);
+                }
+            }
+        }
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if let Some(editor) = panel.as_editor_mut() {
+                editor.set_bookmarks(bookmarks);
+            }
+        }
+    }
+
+    /// Ask for the name of a bookmark at `line` of `path`, offering the
+    /// name of the bookmark already there or the next free number
+    pub(super) fn event_set_bookmark(&mut self, path: PathBuf, line: usize, text: String) {
+        let bookmarks = &self.state.bookmarks;
+        let name = bookmarks
+            .name_at(&path, line)
+            .map(str::to_string)
+            .unwrap_or_else(|| bookmarks.next_number());
+        let t = i18n::t();
+        let modal =
+            InputModal::with_default(t.modal_bookmark_title(), t.modal_bookmark_prompt(), &name);
+        self.state.set_pending_action(
+            PendingAction::SetBookmark { path, line, text },
+            ActiveModal::Input(Box::new(modal)),
+        );
+    }
+
+    /// Set the bookmark `name` (a number if empty)
+    pub(super) fn set_bookmark(&mut self, name: &str, path: PathBuf, line: usize, text: &str) {
+        let name = match name.trim() {
+            "" => self.state.bookmarks.next_number(),
+            name => name.to_string(),
+        };
+        self.state.set_info(i18n::t().status_bookmark_set(&name));
+        self.state
+            .bookmarks
+            .set(Bookmark::new(name, path, line, text));
+        self.auto_save_session();
+    }
+
+    /// List the bookmarks with the cursor on item `cursor`
+    pub(super) fn open_bookmarks(&mut self, cursor: usize) {
+        self.sync_bookmarks();
+        // Files edited outside of the editors
+        let closed: Vec<PathBuf> = self
+            .state
+            .bookmarks
+            .entries()
+            .iter()
+            .map(|bookmark| bookmark.path.clone())
+            .filter(|path| !self.open_file_paths.iter().any(|(open, _)| open == path))
+            .collect();
+        for path in closed {
+            if let Ok(text) = std::fs::read_to_string(&path) {
+                self.state.bookmarks.reanchor_file(&path, &text);
+            }
+        }
+
+        let items = self
+            .state
+            .bookmarks
+            .entries()
+            .iter()
+            .map(|bookmark| {
+                let path = bookmark
+                    .path
+                    .strip_prefix(&self.project_root)
+                    .unwrap_or(&bookmark.path);
+                BookmarkItem {
+                    name: bookmark.name.clone(),
+                    location: format!("{}:{}", path.display(), bookmark.line + 1),
+                    text: bookmark.text.clone(),
+                    missing: !bookmark.path.exists(),
+                }
+            })
+            .collect();
+        self.state.set_pending_action(
+            PendingAction::PickBookmark,
+            ActiveModal::Bookmarks(Box::new(BookmarksModal::new(items, cursor))),
+        );
+    }
+
+    /// Jump to the picked bookmark, or remove it and list the rest
+    pub(super) fn handle_bookmark_choice(&mut self, choice: BookmarkChoice) -> Result<()> {
+        let t = i18n::t();
+        match choice {
+            BookmarkChoice::Jump(index) => {
+                let Some(bookmark) = self.state.bookmarks.entries().get(index).cloned() else {
+                    return Ok(());
+                };
+                if !bookmark.path.exists() {
+                    self.state
+                        .set_error(t.status_bookmark_missing(&bookmark.name));
+                    return Ok(());
+                }
+                self.event_open_file(bookmark.path, Some(bookmark.line + 1), None)?;
+            }
+            BookmarkChoice::Remove(index) => {
+                let Some(name) = self
+                    .state
+                    .bookmarks
+                    .entries()
+                    .get(index)
+                    .map(|bookmark| bookmark.name.clone())
+                else {
+                    return Ok(());
+                };
+                self.state.bookmarks.remove(&name);
+                self.state.set_info(t.status_bookmark_removed(&name));
+                self.auto_save_session();
+                self.open_bookmarks(index);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+//! Config changes applied while termide runs.
+//!
+//! The config file is watched like the user themes: when it is saved, by
+//! termide's editor or any other program, it is read again and the changed
+//! settings are applied to the running app and its open panels. A config
+//! that cannot be parsed is reported and the previous one stays in use.
+
+use std::str::FromStr;
+
+use termide_config::Config;
+use termide_i18n as i18n;
+use termide_logger as logger;
+
+use super::App;
+use crate::PanelExt;
+
+/// Settings only read at startup
+const RESTART_SETTINGS: &[&str] = &["general.language", "logging.file_path"];
+
+impl App {
+    /// Read the config file again after it changed on disk
+    pub(super) fn reload_config(&mut self) {
+        match Config::reload() {
+            // Also seen after termide saved the config itself
+            Ok(config) if self.state.config.changed_settings(&config).is_empty() => {}
+            Ok(config) => {
+                logger::info("Config file changed, applying it");
+                self.apply_config(config);
+            }
+            Err(e) => {
+                logger::error(format!("Config file not applied: {}", e));
+                self.state
+                    .set_error(i18n::t().status_config_invalid(&e.to_string()));
+            }
+        }
+        self.state.needs_redraw = true;
+    }
+
+    /// Use a new config, telling which changed settings need a restart
+    /// (returns true if some do)
+    pub(super) fn apply_config(&mut self, config: Config) -> bool {
+        let changed = self.state.config.changed_settings(&config);
+        self.state.config = config;
+
+        self.state.apply_theme();
+        // Switching to "auto" mode needs the terminal background
+        if self.state.terminal_light.is_none() {
+            self.detect_terminal_background();
+        }
+        self.state.apply_status_bar();
+        self.state.apply_spell_dictionaries();
+        self.state
+            .notifications
+            .set_timeout(std::time::Duration::from_secs(
+                self.state.config.general.notification_timeout,
+            ));
+        termide_clipboard::set_copy_target(self.state.config.general.clipboard_target);
+        termide_git::set_backend(self.state.config.general.git_backend);
+        if let Ok(level) = logger::LogLevel::from_str(&self.state.config.logging.min_level) {
+            logger::set_min_level(level);
+        }
+        if changed
+            .iter()
+            .any(|setting| setting == "general.mouse_capture")
+        {
+            set_mouse_capture(self.state.config.general.mouse_capture);
+        }
+        if changed
+            .iter()
+            .any(|setting| setting == "general.ipc_server")
+        {
+            self.apply_ipc_server();
+        }
+        self.apply_keybindings();
+        self.apply_lsp_servers();
+
+        // File managers pick up their settings when rendered
+        self.apply_project_settings();
+
+        let restart: Vec<&str> = changed
+            .iter()
+            .map(String::as_str)
+            .filter(|setting| RESTART_SETTINGS.contains(setting))
+            .collect();
+        let t = i18n::t();
+        if restart.is_empty() {
+            self.state.set_info(t.status_config_applied().to_string());
+        } else {
+            self.state
+                .set_info(t.status_config_restart(&restart.join(", ")));
+        }
+        !restart.is_empty()
+    }
+
+    /// Give open editors and the file index the settings of their project
+    /// (`.termide.toml`) layered over the global config
+    pub(super) fn apply_project_settings(&mut self) {
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if let Some(editor) = panel.as_editor_mut() {
+                let config = match editor.file_path() {
+                    Some(path) => self.state.editor_config_for(path),
+                    None => self.state.editor_config(),
+                };
+                editor.apply_settings(config);
+            }
+        }
+        if let Some(index) = &mut self.file_index {
+            let exclude_dirs = self.state.config_for(index.root()).general.exclude_dirs;
+            index.set_exclude_dirs(exclude_dirs);
+        }
+    }
+}
+
+/// Turn mouse reporting of the terminal on or off
+fn set_mouse_capture(enabled: bool) {
+    use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+
+    let result = if enabled {
+        crossterm::execute!(std::io::stdout(), EnableMouseCapture)
+    } else {
+        crossterm::execute!(std::io::stdout(), DisableMouseCapture)
+    };
+    if let Err(e) = result {
+        logger::error(format!("Failed to switch mouse capture: {}", e));
+    }
+}
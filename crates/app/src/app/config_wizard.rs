@@ -0,0 +1,369 @@
+//! Config wizard shown on first run and by `termide --init-config`.
+//!
+//! A few questions (theme, shell, tab size, mouse capture) are asked one
+//! modal at a time and their answers are filled into the current config,
+//! which is then written with the documentation of every setting and
+//! applied. Settings the wizard does not ask about (keybindings, tasks,
+//! language servers, ...) are kept. Cancelling a question keeps the config
+//! file as it is.
+
+use termide_config::Config;
+use termide_i18n as i18n;
+use termide_logger as logger;
+use termide_modal::{ConfirmModal, SelectModal};
+
+use super::App;
+use crate::state::{ActiveModal, ConfigWizardStep, PendingAction};
+
+/// Questions in the order they are asked
+const STEPS: &[ConfigWizardStep] = &[
+    ConfigWizardStep::Theme,
+    ConfigWizardStep::Shell,
+    ConfigWizardStep::TabSize,
+    ConfigWizardStep::MouseCapture,
+];
+
+/// Tab sizes offered
+const TAB_SIZES: &[usize] = &[2, 4, 8];
+
+/// Shells looked for when `/etc/shells` lists none
+const COMMON_SHELLS: &[&str] = &["/bin/bash", "/bin/zsh", "/usr/bin/fish", "/bin/sh"];
+
+impl App {
+    /// Ask the config wizard questions, first asking whether to replace the
+    /// config file when `confirm_overwrite` is set
+    pub fn start_config_wizard(&mut self, confirm_overwrite: bool) {
+        let config = Box::new(self.state.config.clone());
+        let original_theme = self.state.config.general.theme.clone();
+        if !confirm_overwrite {
+            self.show_config_wizard_step(STEPS[0], config, original_theme);
+            return;
+        }
+        let t = i18n::t();
+        let path = Config::config_file_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let modal = ConfirmModal::new(
+            t.modal_wizard_setup_title(),
+            t.modal_wizard_overwrite(&path),
+        );
+        self.state.set_pending_action(
+            PendingAction::ConfigWizard {
+                step: ConfigWizardStep::Overwrite,
+                config,
+                values: Vec::new(),
+                original_theme,
+            },
+            ActiveModal::Confirm(Box::new(modal)),
+        );
+    }
+
+    /// Fill in the answer to a wizard question and ask the next one, or
+    /// write the config after the last one
+    pub(super) fn handle_config_wizard(
+        &mut self,
+        step: ConfigWizardStep,
+        mut config: Box<Config>,
+        values: Vec<String>,
+        original_theme: String,
+        value: Box<dyn std::any::Any>,
+    ) {
+        if step == ConfigWizardStep::Overwrite {
+            if value.downcast_ref::<bool>() == Some(&true) {
+                self.show_config_wizard_step(STEPS[0], config, original_theme);
+            }
+            return;
+        }
+        let Some(answer) = value
+            .downcast_ref::<Vec<usize>>()
+            .and_then(|selected| selected.first())
+            .and_then(|&index| values.get(index))
+        else {
+            return;
+        };
+        apply_answer(&mut config, step, answer);
+        match next_step(step) {
+            Some(next) => self.show_config_wizard_step(next, config, original_theme),
+            None => self.finish_config_wizard(*config),
+        }
+    }
+
+    /// Show the modal of a wizard question, its options starting at the
+    /// value `config` has
+    fn show_config_wizard_step(
+        &mut self,
+        step: ConfigWizardStep,
+        config: Box<Config>,
+        original_theme: String,
+    ) {
+        let t = i18n::t();
+        let (title, labels, values, cursor) = match step {
+            ConfigWizardStep::Theme => {
+                let names = termide_theme::available_theme_names();
+                let cursor = names.iter().position(|name| *name == original_theme);
+                (t.modal_theme_title(), names.clone(), names, cursor)
+            }
+            ConfigWizardStep::Shell => {
+                let shells = available_shells();
+                let labels = std::iter::once(t.modal_wizard_shell_auto().to_string())
+                    .chain(shells.iter().cloned())
+                    .collect();
+                let values = std::iter::once(String::new()).chain(shells).collect();
+                (t.modal_wizard_shell_title(), labels, values, None)
+            }
+            ConfigWizardStep::TabSize => {
+                let values: Vec<String> = TAB_SIZES.iter().map(usize::to_string).collect();
+                let cursor = TAB_SIZES
+                    .iter()
+                    .position(|&size| size == config.editor.tab_size);
+                (
+                    t.modal_wizard_tab_size_title(),
+                    values.clone(),
+                    values,
+                    cursor,
+                )
+            }
+            ConfigWizardStep::MouseCapture => {
+                let labels = vec![
+                    t.modal_wizard_mouse_on().to_string(),
+                    t.modal_wizard_mouse_off().to_string(),
+                ];
+                let values = vec!["true".to_string(), "false".to_string()];
+                let cursor = Some(usize::from(!config.general.mouse_capture));
+                (t.modal_wizard_mouse_title(), labels, values, cursor)
+            }
+            ConfigWizardStep::Overwrite => return,
+        };
+        let number = STEPS.iter().position(|&s| s == step).unwrap_or(0) + 1;
+        let modal = SelectModal::single(
+            t.modal_wizard_step_title(number, STEPS.len(), title),
+            "",
+            labels,
+        )
+        .with_cursor(cursor.unwrap_or(0));
+        self.state.set_pending_action(
+            PendingAction::ConfigWizard {
+                step,
+                config,
+                values,
+                original_theme,
+            },
+            ActiveModal::Select(Box::new(modal)),
+        );
+    }
+
+    /// Write the config made from the answers and start using it
+    fn finish_config_wizard(&mut self, config: Config) {
+        let t = i18n::t();
+        if let Err(e) = config.save() {
+            logger::error(format!("Failed to write config: {}", e));
+            self.state.set_error(t.status_error_save(&e.to_string()));
+            return;
+        }
+        let path = Config::config_file_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        logger::info(format!("Config wizard wrote {}", path));
+        // Keep the message about settings that need a restart
+        if !self.apply_config(config) {
+            self.state.set_info(t.status_config_written(&path));
+        }
+    }
+}
+
+/// Fill the answer to a wizard question into `config`
+fn apply_answer(config: &mut Config, step: ConfigWizardStep, answer: &str) {
+    match step {
+        ConfigWizardStep::Theme => config.general.theme = answer.to_string(),
+        ConfigWizardStep::Shell => {
+            config.terminal.shell = (!answer.is_empty()).then(|| answer.to_string())
+        }
+        ConfigWizardStep::TabSize => {
+            if let Ok(size) = answer.parse() {
+                config.editor.tab_size = size;
+            }
+        }
+        ConfigWizardStep::MouseCapture => config.general.mouse_capture = answer == "true",
+        ConfigWizardStep::Overwrite => {}
+    }
+}
+
+/// Question asked after `step`, None after the last one
+fn next_step(step: ConfigWizardStep) -> Option<ConfigWizardStep> {
+    STEPS
+        .iter()
+        .position(|&s| s == step)
+        .and_then(|index| STEPS.get(index + 1))
+        .copied()
+}
+
+/// Installed shells listed in `/etc/shells` (one per program name)
+fn available_shells() -> Vec<String> {
+    let listed = std::fs::read_to_string("/etc/shells").unwrap_or_default();
+    let candidates: Vec<&str> = listed
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('/'))
+        .collect();
+    let candidates = if candidates.is_empty() {
+        COMMON_SHELLS.to_vec()
+    } else {
+        candidates
+    };
+
+    let mut shells: Vec<String> = Vec::new();
+    for shell in candidates {
+        let path = std::path::Path::new(shell);
+        let name = path.file_name();
+        let known = shells
+            .iter()
+            .any(|other| std::path::Path::new(other).file_name() == name);
+        if !known && path.exists() {
+            shells.push(shell.to_string());
+        }
+    }
+    shells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AppState;
+    use termide_theme::Theme;
+
+    fn app() -> App {
+        i18n::init_with_language("en");
+        let config = Config::default();
+        let theme = Theme::get_by_name(&config.general.theme);
+        App::with_state(
+            AppState::with_config_and_theme(config, theme),
+            std::env::temp_dir(),
+        )
+    }
+
+    /// Step of the question the wizard is asking
+    fn current_step(app: &App) -> Option<ConfigWizardStep> {
+        match &app.state.pending_action {
+            Some(PendingAction::ConfigWizard { step, .. }) => Some(*step),
+            _ => None,
+        }
+    }
+
+    /// Answer the current question with its first option
+    fn answer_first(app: &mut App) {
+        let Some(PendingAction::ConfigWizard {
+            step,
+            config,
+            values,
+            original_theme,
+        }) = app.state.take_pending_action()
+        else {
+            panic!("wizard is not asking a question");
+        };
+        app.state.active_modal = None;
+        app.handle_config_wizard(step, config, values, original_theme, Box::new(vec![0usize]));
+    }
+
+    #[test]
+    fn test_overwrite_declined() {
+        let mut app = app();
+        app.start_config_wizard(true);
+        assert_eq!(current_step(&app), Some(ConfigWizardStep::Overwrite));
+
+        let Some(PendingAction::ConfigWizard {
+            step,
+            config,
+            values,
+            original_theme,
+        }) = app.state.take_pending_action()
+        else {
+            panic!("wizard is not asking a question");
+        };
+        app.state.active_modal = None;
+        app.handle_config_wizard(step, config, values, original_theme, Box::new(false));
+        assert!(app.state.pending_action.is_none());
+        assert!(app.state.active_modal.is_none());
+    }
+
+    #[test]
+    fn test_overwrite_accepted_asks_theme() {
+        let mut app = app();
+        app.start_config_wizard(true);
+        let Some(PendingAction::ConfigWizard {
+            step,
+            config,
+            values,
+            original_theme,
+        }) = app.state.take_pending_action()
+        else {
+            panic!("wizard is not asking a question");
+        };
+        app.handle_config_wizard(step, config, values, original_theme, Box::new(true));
+        assert_eq!(current_step(&app), Some(ConfigWizardStep::Theme));
+    }
+
+    #[test]
+    fn test_step_order() {
+        let mut app = app();
+        app.start_config_wizard(false);
+        let mut asked = vec![current_step(&app).unwrap()];
+        // The last answer would write the config file
+        while asked.len() < STEPS.len() {
+            answer_first(&mut app);
+            asked.push(current_step(&app).unwrap());
+        }
+        assert_eq!(asked, STEPS);
+        assert_eq!(next_step(ConfigWizardStep::MouseCapture), None);
+    }
+
+    #[test]
+    fn test_answers_fill_config() {
+        let mut config = Config::default();
+        apply_answer(&mut config, ConfigWizardStep::Theme, "nord");
+        apply_answer(&mut config, ConfigWizardStep::Shell, "/bin/zsh");
+        apply_answer(&mut config, ConfigWizardStep::TabSize, "8");
+        apply_answer(&mut config, ConfigWizardStep::MouseCapture, "false");
+        assert_eq!(config.general.theme, "nord");
+        assert_eq!(config.terminal.shell.as_deref(), Some("/bin/zsh"));
+        assert_eq!(config.editor.tab_size, 8);
+        assert!(!config.general.mouse_capture);
+
+        apply_answer(&mut config, ConfigWizardStep::Shell, "");
+        apply_answer(&mut config, ConfigWizardStep::MouseCapture, "true");
+        assert_eq!(config.terminal.shell, None);
+        assert!(config.general.mouse_capture);
+    }
+
+    #[test]
+    fn test_starts_from_current_config() {
+        let mut app = app();
+        app.state
+            .config
+            .tasks
+            .insert("build".to_string(), Default::default());
+        app.start_config_wizard(false);
+        match &app.state.pending_action {
+            Some(PendingAction::ConfigWizard { config, .. }) => {
+                assert!(config.tasks.contains_key("build"))
+            }
+            _ => panic!("wizard is not asking a question"),
+        }
+    }
+
+    #[test]
+    fn test_cancel_restores_theme() {
+        let mut app = app();
+        let original = app.state.config.general.theme.clone();
+        app.start_config_wizard(false);
+        let preview = termide_theme::available_theme_names()
+            .into_iter()
+            .find(|name| *name != original)
+            .unwrap();
+        app.state.set_theme(&preview);
+
+        app.state.active_modal = None;
+        app.update_theme_preview();
+        assert_eq!(app.state.config.general.theme, original);
+        assert!(app.state.pending_action.is_none());
+    }
+}
@@ -0,0 +1,80 @@
+//! Panels of types registered outside the app crate.
+//!
+//! Registered panel types are opened from the command palette or with
+//! their hotkey (`open_panel_<name>` in `[keybindings]`) and are restored
+//! with the session through the registry.
+
+use anyhow::Result;
+
+use termide_app_panel::{PanelParams, PanelRegistration};
+use termide_i18n as i18n;
+use termide_logger as logger;
+
+use super::App;
+
+impl App {
+    /// Register a panel type (before the session is loaded, so that its
+    /// panels are restored)
+    pub fn register_panel(&mut self, registration: PanelRegistration) -> Result<()> {
+        logger::debug(format!("Registered panel type '{}'", registration.name));
+        self.panel_registry.register(registration)?;
+        // Default hotkeys of the new type
+        self.apply_keybindings();
+        Ok(())
+    }
+
+    /// Register the panel types built into this build
+    pub(super) fn register_builtin_panels(&mut self) {
+        #[cfg(feature = "scratchpad")]
+        {
+            use termide_panel_misc::{ScratchpadPanel, SCRATCHPAD_NAME};
+
+            let title = i18n::t().panel_scratchpad().to_string();
+            let config = self.state.editor_config();
+            let registration =
+                PanelRegistration::new(SCRATCHPAD_NAME, title.clone(), move |params| {
+                    Ok(Box::new(ScratchpadPanel::new(
+                        params,
+                        &title,
+                        config.clone(),
+                    )))
+                });
+            if let Err(e) = self.register_panel(registration) {
+                logger::error(format!("Failed to register scratchpad panel: {}", e));
+            }
+        }
+    }
+
+    /// Keybindings of registered panel types as `(action name, keys)`
+    pub(super) fn registered_panel_keybindings(&self) -> Vec<(String, String)> {
+        self.panel_registry
+            .iter()
+            .filter_map(|panel| Some((panel.action_name(), panel.hotkey.clone()?)))
+            .collect()
+    }
+
+    /// Command palette label of a registered panel type
+    pub(super) fn registered_panel_label(&self, name: &str) -> String {
+        self.panel_registry
+            .get(name)
+            .map(|panel| panel.label.clone())
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Open a new panel of a registered type
+    pub(super) fn open_registered_panel(&mut self, name: &str) {
+        match self.panel_registry.create(name, &PanelParams::new()) {
+            Ok(panel) => {
+                logger::debug(format!("Opening {} panel", name));
+                self.close_welcome_panels();
+                self.add_panel(panel);
+                self.auto_save_session();
+            }
+            Err(e) => {
+                logger::error(format!("Failed to open {} panel: {}", name, e));
+                self.state
+                    .set_error(i18n::t().status_error_open_panel(name, &e.to_string()));
+            }
+        }
+    }
+}
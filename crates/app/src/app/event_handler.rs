@@ -8,7 +8,7 @@
 #![allow(deprecated)]
 
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::App;
 use crate::PanelExt;
@@ -16,6 +16,10 @@ use termide_core::PanelEvent;
 use termide_i18n as i18n;
 use termide_logger as logger;
 use termide_panel_editor::Editor;
+use termide_panel_file_manager::FileManager;
+use termide_panel_misc::dir_compare::Budget;
+use termide_panel_misc::duplicates;
+use termide_panel_misc::{DiffPanel, DirComparePanel, DuplicatesPanel, LogViewerPanel};
 
 impl App {
     /// Process events emitted by a panel.
@@ -33,8 +37,36 @@ impl App {
     fn process_single_event(&mut self, event: PanelEvent) -> Result<()> {
         match event {
             // === File operations ===
-            PanelEvent::OpenFile(path) => {
-                self.event_open_file(path)?;
+            PanelEvent::OpenFile { path, line, column } => {
+                self.event_open_file(path, line, column)?;
+            }
+
+            PanelEvent::OpenDirectory(path) => {
+                self.event_open_directory(path);
+            }
+
+            PanelEvent::CompareFiles { left, right } => {
+                self.event_compare_files(left, right);
+            }
+
+            PanelEvent::CompareDirectories { left, right } => {
+                self.event_compare_directories(left, right);
+            }
+
+            PanelEvent::FindDuplicates(directory) => {
+                self.event_find_duplicates(directory);
+            }
+
+            PanelEvent::DiffAgainstHead { path, content } => {
+                self.event_diff_against_head(path, &content);
+            }
+
+            PanelEvent::OpenMarkdownPreview => {
+                self.event_open_markdown_preview();
+            }
+
+            PanelEvent::SetBookmark { path, line, text } => {
+                self.event_set_bookmark(path, line, text);
             }
 
             PanelEvent::ClosePanel => {
@@ -63,6 +95,10 @@ impl App {
                 self.state.clear_status();
             }
 
+            PanelEvent::Notify { text, level } => {
+                self.state.notify(text, level);
+            }
+
             // === Panel navigation ===
             PanelEvent::NextPanel => {
                 self.layout_manager.next_group();
@@ -81,7 +117,8 @@ impl App {
 
             // === Events not yet implemented ===
             PanelEvent::NeedsRedraw => {
-                // UI will redraw on next frame anyway
+                // Already set after keys and mouse events, not after ticks
+                self.state.needs_redraw = true;
             }
 
             PanelEvent::Quit => {
@@ -106,6 +143,14 @@ impl App {
                 self.event_goto_line(line);
             }
 
+            PanelEvent::GotoDefinition {
+                path,
+                line,
+                character,
+            } => {
+                self.event_goto_definition(path, line, character);
+            }
+
             PanelEvent::ShowConfirm {
                 message,
                 on_confirm,
@@ -189,7 +234,21 @@ impl App {
     }
 
     /// Handle OpenFile event - open file in editor
-    fn event_open_file(&mut self, file_path: PathBuf) -> Result<()> {
+    ///
+    /// A file that is already open (also through a symlink) is shown in its
+    /// panel, at the line and column (both 1-based) if given, unless
+    /// `reuse_open_editors` is off.
+    pub(super) fn event_open_file(
+        &mut self,
+        file_path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Result<()> {
+        if self.state.config.editor.reuse_open_editors
+            && self.focus_open_file(&file_path, line, column)
+        {
+            return Ok(());
+        }
         self.close_welcome_panels();
         let filename = file_path
             .file_name()
@@ -198,12 +257,40 @@ impl App {
         let t = i18n::t();
         logger::info(format!("Opening file via event: {}", filename));
 
-        match Editor::open_file_with_config(file_path.clone(), self.state.editor_config()) {
-            Ok(editor_panel) => {
+        if self.state.config.file_manager.open_logs_in_viewer && is_log_file(&file_path) {
+            match LogViewerPanel::open_file(file_path.clone()) {
+                Ok(viewer) => {
+                    self.add_panel(Box::new(viewer));
+                    self.auto_save_session();
+                    self.remember_recent(|recent| recent.add_file(&file_path));
+                    logger::info(format!("File '{}' opened in log viewer", filename));
+                    self.state.set_info(t.editor_file_opened(filename));
+                }
+                Err(e) => {
+                    logger::error(format!("Error opening '{}': {}", filename, e));
+                    self.state
+                        .set_error(t.status_error_open_file(filename, &e.to_string()));
+                }
+            }
+            return Ok(());
+        }
+
+        let editor_config = self.state.editor_config_for(&file_path);
+        match Editor::open_file_with_config(file_path.clone(), editor_config) {
+            Ok(mut editor_panel) => {
+                if let Some(line) = line {
+                    goto_position(&mut editor_panel, line, column);
+                }
+                // Warning about bytes replaced while decoding the file
+                let warning = editor_panel.take_status_message();
                 self.add_panel(Box::new(editor_panel));
                 self.auto_save_session();
+                self.remember_recent(|recent| recent.add_file(&file_path));
                 logger::info(format!("File '{}' opened in editor", filename));
-                self.state.set_info(t.editor_file_opened(filename));
+                match warning {
+                    Some(warning) => self.state.set_error(warning),
+                    None => self.state.set_info(t.editor_file_opened(filename)),
+                }
             }
             Err(e) => {
                 let error_msg = t.status_error_open_file(filename, &e.to_string());
@@ -214,13 +301,113 @@ impl App {
         Ok(())
     }
 
+    /// Focus the panel showing `file_path` and move an editor's cursor to
+    /// `line` and `column` (1-based). Returns false if no panel shows the file.
+    pub(super) fn focus_open_file(
+        &mut self,
+        file_path: &Path,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> bool {
+        let target = canonical_path(file_path);
+        for (group_idx, group) in self.layout_manager.panel_groups.iter_mut().enumerate() {
+            let existing = group.panels().iter().position(|panel| {
+                panel
+                    .file_path()
+                    .is_some_and(|path| path == file_path || canonical_path(path) == target)
+            });
+            if let Some(panel_idx) = existing {
+                if let (Some(line), Some(editor)) =
+                    (line, group.panels_mut()[panel_idx].as_editor_mut())
+                {
+                    goto_position(editor, line, column);
+                }
+                group.set_expanded(panel_idx);
+                self.layout_manager.focus = group_idx;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Handle CompareFiles event - open a diff panel for two files
+    fn event_compare_files(&mut self, left: PathBuf, right: PathBuf) {
+        let layout = self.state.config.editor.diff_layout;
+        match DiffPanel::compare_files(left, right, layout) {
+            Ok(panel) => {
+                self.close_welcome_panels();
+                self.add_panel(Box::new(panel));
+            }
+            Err(e) => self.state.set_error(i18n::t().diff_failed(&e.to_string())),
+        }
+    }
+
+    /// Handle CompareDirectories event - open a comparison panel for two
+    /// directories
+    pub(super) fn event_compare_directories(&mut self, left: PathBuf, right: PathBuf) {
+        let settings = &self.state.config.file_manager;
+        let budget = Budget {
+            max_depth: settings.compare_max_depth,
+            max_entries: settings.compare_max_entries,
+        };
+        self.close_welcome_panels();
+        self.add_panel(Box::new(DirComparePanel::new(left, right, budget)));
+    }
+
+    /// Handle FindDuplicates event - open a panel listing the duplicate
+    /// files below a directory
+    fn event_find_duplicates(&mut self, directory: PathBuf) {
+        let settings = &self.state.config.file_manager;
+        let options = duplicates::Options {
+            recursive: true,
+            skip_ignored: settings.duplicates_skip_ignored,
+            max_files: settings.duplicates_max_files,
+        };
+        self.close_welcome_panels();
+        self.add_panel(Box::new(DuplicatesPanel::new(directory, options)));
+    }
+
+    /// Handle DiffAgainstHead event - open a diff panel for editor content
+    /// against the file's version in HEAD
+    fn event_diff_against_head(&mut self, path: PathBuf, content: &str) {
+        let t = i18n::t();
+        let Some(head) = termide_git::head_content(&path) else {
+            self.state.set_error(t.git_not_repository().to_string());
+            return;
+        };
+        let layout = self.state.config.editor.diff_layout;
+        self.close_welcome_panels();
+        self.add_panel(Box::new(DiffPanel::against_head(
+            path, &head, content, layout,
+        )));
+    }
+
+    /// Handle OpenMarkdownPreview event - open a preview of the active editor
+    fn event_open_markdown_preview(&mut self) {
+        let Some(editor) = self.active_editor_mut() else {
+            return;
+        };
+        let preview = editor.markdown_preview();
+        self.close_welcome_panels();
+        self.add_panel(Box::new(preview));
+    }
+
+    /// Handle OpenDirectory event - open directory in a new file manager
+    pub(super) fn event_open_directory(&mut self, path: PathBuf) {
+        self.close_welcome_panels();
+        logger::info(format!("Opening directory via event: {}", path.display()));
+        self.add_panel(Box::new(FileManager::new_with_path(path.clone())));
+        self.auto_save_session();
+        self.remember_recent(|recent| recent.add_project(&path));
+    }
+
     /// Handle GotoLine event - move cursor to specific line in editor
     fn event_goto_line(&mut self, line: usize) {
         if let Some(panel) = self.layout_manager.active_panel_mut() {
             if let Some(editor) = panel.as_editor_mut() {
                 // Convert from 1-based (user-facing) to 0-based (internal)
                 let line_0based = line.saturating_sub(1);
-                editor.set_cursor_line(line_0based);
+                editor.goto_line(line_0based);
                 logger::debug(format!("Moved to line {}", line));
             }
         }
@@ -531,3 +718,46 @@ impl App {
         }
     }
 }
+
+/// Check if a file is a log file by its `.log` extension
+fn is_log_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("log"))
+}
+
+/// Path with symlinks resolved (unchanged if it does not exist)
+fn canonical_path(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Move the editor cursor to a 1-based line and optional column
+fn goto_position(editor: &mut Editor, line: usize, column: Option<usize>) {
+    let line = line.saturating_sub(1);
+    match column {
+        Some(column) => editor.goto_position(line, column.saturating_sub(1)),
+        None => editor.goto_line(line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_path_resolves_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "text").unwrap();
+        let link = dir.path().join("link.txt");
+        std::os::unix::fs::symlink(&file, &link).unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        assert_eq!(canonical_path(&link), canonical_path(&file));
+        assert_eq!(
+            canonical_path(&dir.path().join("sub/../file.txt")),
+            canonical_path(&file)
+        );
+        let missing = dir.path().join("missing.txt");
+        assert_eq!(canonical_path(&missing), missing);
+    }
+}
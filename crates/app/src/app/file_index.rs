@@ -0,0 +1,361 @@
+//! Index of project files for the file finder.
+//!
+//! Files are listed on a background thread, with git when the project is a
+//! repository (so ignored files are skipped) or by a bounded walk otherwise.
+//! Directories named in `general.exclude_dirs` (with the project's own
+//! additions) are skipped either way. Files arrive in batches, so the
+//! finder can search a partial index. Files
+//! created or deleted afterwards mark the index as stale, and it is rebuilt
+//! once the filesystem has been quiet for a while.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use termide_config::constants::{FILE_INDEX_REFRESH_DELAY_MS, MAX_INDEXED_FILES};
+
+/// Files sent per message while indexing
+const BATCH_SIZE: usize = 1000;
+
+/// Message sent by the indexing thread
+enum BuildUpdate {
+    /// More files (relative paths)
+    Files(Vec<String>),
+    /// All files were listed
+    Done,
+}
+
+/// Indexing running on a background thread
+struct Build {
+    receiver: Receiver<BuildUpdate>,
+    /// Set to stop the thread
+    cancel: Arc<AtomicBool>,
+    /// Files of a rebuild, swapped in when complete
+    pending: Option<Vec<String>>,
+}
+
+impl Drop for Build {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Change of the indexed files reported by [`FileIndex::poll`]
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum IndexChange {
+    /// Files were added from this position on
+    Added(usize),
+    /// The index was rebuilt
+    Replaced,
+    /// Indexing finished, no files changed
+    Finished,
+}
+
+/// Files of the project, relative to its root with `/` separators
+pub(super) struct FileIndex {
+    root: PathBuf,
+    /// Names of directories not indexed (build output and dependencies)
+    exclude_dirs: Arc<Vec<String>>,
+    files: Vec<String>,
+    build: Option<Build>,
+    /// Time of the last change not yet indexed
+    changed_at: Option<Instant>,
+}
+
+impl FileIndex {
+    /// Start indexing the files under `root`, skipping directories named
+    /// in `exclude_dirs`
+    pub(super) fn new(root: PathBuf, exclude_dirs: Vec<String>) -> Self {
+        let mut index = Self {
+            root,
+            exclude_dirs: Arc::new(exclude_dirs),
+            files: Vec::new(),
+            build: None,
+            changed_at: None,
+        };
+        index.start_build(false);
+        index
+    }
+
+    /// Directory the paths are relative to
+    pub(super) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Skip other directories, rebuilding the index when they changed
+    pub(super) fn set_exclude_dirs(&mut self, exclude_dirs: Vec<String>) {
+        if *self.exclude_dirs != exclude_dirs {
+            self.exclude_dirs = Arc::new(exclude_dirs);
+            self.start_build(true);
+        }
+    }
+
+    /// Indexed files
+    pub(super) fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// The first build is still running
+    pub(super) fn is_indexing(&self) -> bool {
+        self.build
+            .as_ref()
+            .is_some_and(|build| build.pending.is_none())
+    }
+
+    /// Start listing the files. A rebuild keeps the current files until it
+    /// completes.
+    fn start_build(&mut self, rebuild: bool) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = termide_wake::channel();
+        let root = self.root.clone();
+        let exclude_dirs = Arc::clone(&self.exclude_dirs);
+        let thread_cancel = Arc::clone(&cancel);
+        thread::spawn(move || list_files(&root, &exclude_dirs, &thread_cancel, &sender));
+        self.build = Some(Build {
+            receiver,
+            cancel,
+            pending: rebuild.then(Vec::new),
+        });
+    }
+
+    /// Note a filesystem change. Only files created or deleted under the
+    /// root make the index stale.
+    pub(super) fn on_fs_change(&mut self, path: &Path) {
+        let Some(relative) = path
+            .strip_prefix(&self.root)
+            .ok()
+            .map(|relative| relative.to_string_lossy().into_owned())
+        else {
+            return;
+        };
+        if relative.is_empty()
+            || relative.split('/').any(|part| part == ".git")
+            || is_excluded(&relative, &self.exclude_dirs)
+        {
+            return;
+        }
+
+        let indexed = self.files.binary_search(&relative).is_ok();
+        let stale = if path.is_file() {
+            !indexed
+        } else if path.exists() {
+            false
+        } else {
+            // A deleted directory takes the files under it along
+            let prefix = format!("{}/", relative);
+            indexed || self.files.iter().any(|file| file.starts_with(&prefix))
+        };
+        if stale {
+            self.changed_at = Some(Instant::now());
+        }
+    }
+
+    /// When the changes settle and a stale index is rebuilt
+    pub(super) fn refresh_deadline(&self) -> Option<Instant> {
+        self.changed_at
+            .map(|changed_at| changed_at + Duration::from_millis(FILE_INDEX_REFRESH_DELAY_MS))
+    }
+
+    /// Pick up indexed files and rebuild a stale index once changes settled
+    pub(super) fn poll(&mut self) -> Option<IndexChange> {
+        let mut change = None;
+        if let Some(build) = &mut self.build {
+            let start = self.files.len();
+            let mut done = false;
+            for update in build.receiver.try_iter() {
+                match update {
+                    BuildUpdate::Files(files) => match &mut build.pending {
+                        Some(pending) => pending.extend(files),
+                        None => self.files.extend(files),
+                    },
+                    BuildUpdate::Done => done = true,
+                }
+            }
+
+            let rebuilt = build.pending.take();
+            if done {
+                self.build = None;
+                match rebuilt {
+                    Some(mut files) => {
+                        files.sort();
+                        if files != self.files {
+                            self.files = files;
+                            change = Some(IndexChange::Replaced);
+                        }
+                    }
+                    // Order of the files does not change for the finder
+                    None => self.files.sort(),
+                }
+                change = change.or(Some(IndexChange::Finished));
+            } else {
+                if let Some(build) = &mut self.build {
+                    build.pending = rebuilt;
+                }
+                if self.files.len() > start {
+                    change = Some(IndexChange::Added(start));
+                }
+            }
+        }
+
+        let settled = self.changed_at.is_some_and(|changed_at| {
+            changed_at.elapsed() >= Duration::from_millis(FILE_INDEX_REFRESH_DELAY_MS)
+        });
+        if settled && self.build.is_none() {
+            self.changed_at = None;
+            self.start_build(true);
+        }
+        change
+    }
+}
+
+/// Whether the relative path `path` is inside a directory named in
+/// `exclude_dirs`
+fn is_excluded(path: &str, exclude_dirs: &[String]) -> bool {
+    path.split('/')
+        .rev()
+        .skip(1)
+        .any(|part| exclude_dirs.iter().any(|dir| dir == part))
+}
+
+/// List the files under `root` and send them in batches
+fn list_files(
+    root: &Path,
+    exclude_dirs: &[String],
+    cancel: &AtomicBool,
+    sender: &termide_wake::Sender<BuildUpdate>,
+) {
+    let files = match termide_git::list_files(root) {
+        // Deleted files stay listed by git until the deletion is staged
+        Some(files) => files
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .filter(|path| !is_excluded(path, exclude_dirs) && root.join(path).is_file())
+            .take(MAX_INDEXED_FILES)
+            .collect(),
+        None => walk(root, exclude_dirs, cancel),
+    };
+    for batch in files.chunks(BATCH_SIZE) {
+        if cancel.load(Ordering::Relaxed)
+            || sender.send(BuildUpdate::Files(batch.to_vec())).is_err()
+        {
+            return;
+        }
+    }
+    let _ = sender.send(BuildUpdate::Done);
+}
+
+/// List the files under `root`, skipping hidden entries and excluded
+/// directories, without following symbolic links
+fn walk(root: &Path, exclude_dirs: &[String], cancel: &AtomicBool) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut directories = vec![PathBuf::new()];
+    while let Some(relative) = directories.pop() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let Ok(entries) = std::fs::read_dir(root.join(&relative)) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = relative.join(&name);
+            if file_type.is_dir() {
+                if !exclude_dirs.contains(&name) {
+                    directories.push(path);
+                }
+            } else if file_type.is_file() {
+                files.push(path.to_string_lossy().into_owned());
+                if files.len() == MAX_INDEXED_FILES {
+                    return files;
+                }
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Poll until the running build is done
+    fn wait(index: &mut FileIndex) -> Vec<IndexChange> {
+        let mut changes = Vec::new();
+        while index.build.is_some() {
+            changes.extend(index.poll());
+            thread::sleep(Duration::from_millis(5));
+        }
+        changes
+    }
+
+    fn exclude_dirs() -> Vec<String> {
+        termide_config::Config::default().general.exclude_dirs
+    }
+
+    #[test]
+    fn test_index_skips_hidden_and_build_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join(".cache")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        std::fs::write(root.join("target/debug/app"), "").unwrap();
+        std::fs::write(root.join(".cache/data"), "").unwrap();
+
+        let mut index = FileIndex::new(root.clone(), exclude_dirs());
+        let changes = wait(&mut index);
+        assert_eq!(changes.last(), Some(&IndexChange::Finished));
+        assert_eq!(index.files(), ["README.md", "src/main.rs"]);
+
+        // A project excluding another directory
+        let mut dirs = exclude_dirs();
+        dirs.push("src".to_string());
+        index.set_exclude_dirs(dirs);
+        assert!(wait(&mut index).contains(&IndexChange::Replaced));
+        assert_eq!(index.files(), ["README.md"]);
+        index.on_fs_change(&root.join("src/new.rs"));
+        assert!(index.changed_at.is_none());
+    }
+
+    #[test]
+    fn test_only_created_or_deleted_files_make_index_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        let mut index = FileIndex::new(root.clone(), exclude_dirs());
+        wait(&mut index);
+
+        // Modified file
+        std::fs::write(root.join("src/lib.rs"), "changed").unwrap();
+        index.on_fs_change(&root.join("src/lib.rs"));
+        assert!(index.changed_at.is_none());
+
+        // Change outside of the project
+        index.on_fs_change(Path::new("/elsewhere/new.rs"));
+        assert!(index.changed_at.is_none());
+
+        // Deleted directory
+        std::fs::remove_dir_all(root.join("src")).unwrap();
+        index.on_fs_change(&root.join("src"));
+        assert!(index.changed_at.is_some());
+
+        // The rebuild replaces the files once changes settled
+        index.changed_at = Some(Instant::now() - Duration::from_secs(5));
+        assert_eq!(index.poll(), None);
+        assert!(index.build.is_some() && !index.is_indexing());
+        assert!(wait(&mut index).contains(&IndexChange::Replaced));
+        assert!(index.files().is_empty());
+    }
+}
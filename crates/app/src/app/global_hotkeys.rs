@@ -4,23 +4,55 @@
 //! for navigation, panel management, and quick actions.
 
 use anyhow::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, KeyModifiers};
 
-use termide_app_event::{HotkeyAction, HotkeyProcessor};
+use std::collections::HashSet;
 
+use termide_app_event::{
+    ActionCategory, DefaultHotkeyProcessor, HotkeyAction, HotkeyMatch, HotkeyProcessor, KeySequence,
+};
+
+use super::file_index::FileIndex;
 use super::App;
 use crate::state::{ActiveModal, PendingAction};
+use crate::PanelExt;
+use termide_config::constants::MAX_RECENT_COMMANDS;
 use termide_i18n as i18n;
+use termide_modal::{CommandPaletteModal, FileFinderModal, NotificationsModal, PaletteItem};
+use termide_panel_misc::{HelpEntry, HelpPanel, HelpSection};
 
 impl App {
     /// Handle global hotkeys (Alt+key combinations)
     ///
     /// Returns `Some(())` if the hotkey was handled, `None` to pass to panel.
     pub(super) fn handle_global_hotkeys(&mut self, key: KeyEvent) -> Result<Option<()>> {
-        // Check if this is a global hotkey
-        if let Some(action) = self.hotkey_processor.process_hotkey(&key) {
-            self.execute_hotkey_action(action)?;
-            return Ok(Some(()));
+        // Check if this is a global hotkey or part of a chord
+        match self.hotkey_processor.process_hotkey(&key) {
+            HotkeyMatch::Action(action) => {
+                // Shells use Ctrl+P for history, so it stays with terminals
+                if action == HotkeyAction::OpenFileFinder
+                    && key.modifiers == KeyModifiers::CONTROL
+                    && self
+                        .layout_manager
+                        .active_panel_mut()
+                        .is_some_and(|panel| panel.as_terminal_mut().is_some())
+                {
+                    return Ok(None);
+                }
+                self.execute_hotkey_action(action)?;
+                return Ok(Some(()));
+            }
+            HotkeyMatch::Pending => {
+                if let Some(prefix) = self.hotkey_processor.pending_prefix() {
+                    let t = i18n::t();
+                    self.state
+                        .set_info(t.status_keys_pending(&prefix.to_string()));
+                }
+                return Ok(Some(()));
+            }
+            // Status message was already cleared by the key press
+            HotkeyMatch::Cancelled => return Ok(Some(())),
+            HotkeyMatch::NoMatch => {}
         }
 
         // Escape - close panel (without modifiers)
@@ -38,6 +70,214 @@ impl App {
         Ok(None) // Not handled, pass to panel
     }
 
+    /// Rebuild the hotkey processor with extra bindings from config,
+    /// reporting invalid entries
+    pub(super) fn apply_keybindings(&mut self) {
+        let mut processor = DefaultHotkeyProcessor::new();
+        // Registered panels bring default keys; the config overrides them
+        let registered = self.registered_panel_keybindings();
+        let errors = processor.apply_keybindings(
+            registered
+                .iter()
+                .map(|(action, keys)| (action, keys))
+                .chain(self.state.config.keybindings.iter())
+                .map(|(action, keys)| (action.as_str(), keys.as_str())),
+        );
+        self.hotkey_processor = processor;
+        if !errors.is_empty() {
+            self.state
+                .set_error(i18n::t().error_keybindings(&errors.join("; ")));
+        }
+        self.refresh_help_panels();
+    }
+
+    /// Keyboard shortcuts panel content: the bound actions by category,
+    /// then the keys of each open panel type. Panel keys taken by a global
+    /// binding are left out, they never reach the panel.
+    pub(super) fn help_sections(&mut self) -> Vec<HelpSection> {
+        let t = i18n::t();
+        let registered = self
+            .panel_registry
+            .iter()
+            .map(|panel| HotkeyAction::OpenPanel(panel.name.clone()));
+        let actions: Vec<HotkeyAction> =
+            HotkeyAction::all().into_iter().chain(registered).collect();
+
+        let mut sections = Vec::new();
+        for category in ActionCategory::ALL {
+            let entries: Vec<HelpEntry> = actions
+                .iter()
+                .filter(|action| action.category() == category)
+                .filter_map(|action| {
+                    let mut keys = self.hotkey_processor.keys_for(action);
+                    // Escape closes panels that do not use it themselves
+                    if *action == HotkeyAction::ClosePanel {
+                        keys.push("Esc".to_string());
+                    }
+                    if keys.is_empty() {
+                        return None;
+                    }
+                    let description = match action {
+                        HotkeyAction::OpenPanel(name) => self.registered_panel_label(name),
+                        _ => t.command_title(&action.name()),
+                    };
+                    Some(HelpEntry {
+                        keys: keys.join(", "),
+                        description,
+                        name: action.name(),
+                    })
+                })
+                .collect();
+            if !entries.is_empty() {
+                sections.push(HelpSection {
+                    title: t.help_category(category.name()).to_string(),
+                    entries,
+                });
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let panel_keys: Vec<(&'static str, Vec<(String, String)>)> = self
+            .layout_manager
+            .iter_all_panels_mut()
+            .filter(|panel| seen.insert(panel.name()))
+            .map(|panel| (panel.name(), panel.key_help()))
+            .collect();
+        for (name, key_help) in panel_keys {
+            let entries: Vec<HelpEntry> = key_help
+                .into_iter()
+                .filter_map(|(keys, description)| {
+                    let keys: Vec<&str> = keys
+                        .split(", ")
+                        .filter(|keys| !self.is_global_key(keys))
+                        .collect();
+                    (!keys.is_empty()).then(|| HelpEntry {
+                        keys: keys.join(", "),
+                        description,
+                        name: String::new(),
+                    })
+                })
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            let title = if self.panel_registry.get(name).is_some() {
+                self.registered_panel_label(name)
+            } else {
+                t.help_section(name).to_string()
+            };
+            sections.push(HelpSection { title, entries });
+        }
+        sections
+    }
+
+    /// Whether `keys` (as shown in the help) is bound to an action
+    fn is_global_key(&self, keys: &str) -> bool {
+        keys.parse::<KeySequence>()
+            .is_ok_and(|keys| self.hotkey_processor.bindings().contains_key(&keys))
+    }
+
+    /// Rebuild the open keyboard shortcuts panels after the bindings changed
+    fn refresh_help_panels(&mut self) {
+        let sections = self.help_sections();
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if let Some(help) = panel.as_any_mut().downcast_mut::<HelpPanel>() {
+                help.set_sections(sections.clone());
+            }
+        }
+    }
+
+    /// Drop a chord prefix that was not completed in time
+    pub(super) fn expire_pending_chord(&mut self) {
+        if self.hotkey_processor.expire_pending() {
+            self.state.clear_status();
+            self.state.needs_redraw = true;
+        }
+    }
+
+    /// Open the command palette with all actions and registered panels,
+    /// recently run ones first
+    fn open_command_palette(&mut self) {
+        let t = i18n::t();
+        let registered = self
+            .panel_registry
+            .iter()
+            .map(|panel| HotkeyAction::OpenPanel(panel.name.clone()));
+        let mut actions = self.recent_commands.clone();
+        actions.extend(
+            HotkeyAction::all()
+                .into_iter()
+                .chain(registered)
+                .filter(|action| {
+                    *action != HotkeyAction::CommandPalette
+                        && !self.recent_commands.contains(action)
+                }),
+        );
+
+        let items = actions
+            .iter()
+            .map(|action| PaletteItem {
+                label: match action {
+                    HotkeyAction::OpenPanel(name) => self.registered_panel_label(name),
+                    _ => t.command_title(&action.name()),
+                },
+                keys: self.hotkey_processor.keys_for(action).join("  "),
+            })
+            .collect();
+        let modal = CommandPaletteModal::new(t.modal_command_palette_title(), items);
+        self.state.set_pending_action(
+            PendingAction::RunCommand {
+                commands: actions.iter().map(HotkeyAction::name).collect(),
+            },
+            ActiveModal::CommandPalette(Box::new(modal)),
+        );
+    }
+
+    /// Open the file finder over the project files, recently opened ones
+    /// first. The index is built when the finder is first opened.
+    fn open_file_finder(&mut self) {
+        let index = self.file_index.get_or_insert_with(|| {
+            let root = termide_git::find_repo_root(&self.project_root)
+                .unwrap_or_else(|| self.project_root.clone());
+            // Created and deleted files refresh the index
+            if let Some(watcher) = &mut self.state.fs_watcher {
+                let is_git_repo = root.join(".git").exists();
+                if is_git_repo && !watcher.is_watching_repo(&root) {
+                    let _ = watcher.watch_repository(root.clone());
+                } else if !is_git_repo && !watcher.is_watching_dir(&root) {
+                    let _ = watcher.watch_directory(root.clone());
+                }
+            }
+            let exclude_dirs = self.state.config_for(&root).general.exclude_dirs;
+            FileIndex::new(root, exclude_dirs)
+        });
+
+        let recent = termide_session::RecentPaths::load()
+            .files
+            .iter()
+            .filter_map(|path| path.strip_prefix(index.root()).ok())
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        let t = i18n::t();
+        let mut modal =
+            FileFinderModal::new(t.modal_file_finder_title(), index.files().to_vec(), recent);
+        modal.set_indexing(index.is_indexing(), t.modal_file_finder_indexing());
+        self.state.set_pending_action(
+            PendingAction::OpenFoundFile {
+                root: index.root().to_path_buf(),
+            },
+            ActiveModal::FileFinder(Box::new(modal)),
+        );
+    }
+
+    /// Run a command picked in the command palette and remember it as recent
+    pub(super) fn run_palette_command(&mut self, action: HotkeyAction) -> Result<()> {
+        self.recent_commands.retain(|recent| *recent != action);
+        self.recent_commands.insert(0, action.clone());
+        self.recent_commands.truncate(MAX_RECENT_COMMANDS);
+        self.execute_hotkey_action(action)
+    }
+
     /// Execute a hotkey action
     fn execute_hotkey_action(&mut self, action: HotkeyAction) -> Result<()> {
         match action {
@@ -59,12 +299,45 @@ impl App {
             HotkeyAction::NewDebug => {
                 self.handle_new_debug()?;
             }
+            HotkeyAction::NewSystemMonitor => {
+                self.handle_new_system_monitor();
+            }
+            HotkeyAction::NewGitPanel => {
+                self.handle_new_git_panel();
+            }
+            HotkeyAction::NewSearchPanel => {
+                self.handle_new_search_panel();
+            }
             HotkeyAction::OpenHelp => {
                 self.handle_new_help()?;
             }
             HotkeyAction::OpenPreferences => {
                 self.open_config_in_editor()?;
             }
+            HotkeyAction::SelectTheme => {
+                self.open_theme_picker();
+            }
+            HotkeyAction::CommandPalette => {
+                self.open_command_palette();
+            }
+            HotkeyAction::OpenFileFinder => {
+                self.open_file_finder();
+            }
+            HotkeyAction::Bookmarks => {
+                self.open_bookmarks(0);
+            }
+            HotkeyAction::ToggleTerminalBroadcast => {
+                self.toggle_terminal_broadcast();
+            }
+            HotkeyAction::RunTask => {
+                self.handle_run_task();
+            }
+            HotkeyAction::SplitEditor => {
+                self.handle_split_editor();
+            }
+            HotkeyAction::OpenPanel(name) => {
+                self.open_registered_panel(&name);
+            }
 
             // Navigation
             HotkeyAction::PrevGroup => {
@@ -90,6 +363,10 @@ impl App {
             HotkeyAction::ToggleStacking => {
                 self.toggle_panel_stacking();
             }
+            HotkeyAction::ToggleTabs => {
+                self.layout_manager.toggle_tabs();
+                self.auto_save_session();
+            }
             HotkeyAction::SwapPanelLeft => {
                 self.handle_swap_panel_left()?;
             }
@@ -107,6 +384,15 @@ impl App {
             }
 
             // Application
+            HotkeyAction::DismissNotifications => {
+                self.state.notifications.dismiss();
+            }
+            HotkeyAction::NotificationHistory => {
+                let history = self.state.notifications.history().cloned().collect();
+                self.state.active_modal = Some(ActiveModal::Notifications(Box::new(
+                    NotificationsModal::new(history),
+                )));
+            }
             HotkeyAction::RequestQuit => {
                 self.handle_quit_request()?;
             }
@@ -114,24 +400,6 @@ impl App {
         Ok(())
     }
 
-    /// Handle quit request with confirmation if needed
-    pub(super) fn handle_quit_request(&mut self) -> Result<()> {
-        // Always save session before quit
-        self.auto_save_session();
-
-        if self.has_panels_requiring_confirmation() {
-            let t = i18n::t();
-            let modal = termide_modal::ConfirmModal::new(t.modal_yes(), t.app_quit_confirm());
-            self.state.set_pending_action(
-                PendingAction::QuitApplication,
-                ActiveModal::Confirm(Box::new(modal)),
-            );
-        } else {
-            self.state.quit();
-        }
-        Ok(())
-    }
-
     /// Check if session should be saved and save if needed
     fn check_and_save_session(&mut self) {
         if self.state.should_save_session() {
@@ -164,11 +432,16 @@ impl App {
         self.check_and_save_session();
     }
 
-    /// Navigate to specific group by number (1-indexed)
-    fn navigate_to_group(&mut self, group_num: usize) {
+    /// Navigate to specific group by number (1-indexed), or to the tab
+    /// with that number when the active group is shown as tabs
+    pub(super) fn navigate_to_group(&mut self, group_num: usize) {
         // Convert from 1-indexed (user-facing) to 0-indexed (internal)
         let index = group_num.saturating_sub(1);
-        self.layout_manager.set_focus(index);
+        let focus = self.layout_manager.focus;
+        match self.layout_manager.get_group_mut(focus) {
+            Some(group) if group.tabs => group.set_expanded(index),
+            _ => self.layout_manager.set_focus(index),
+        }
         self.check_and_save_session();
     }
 
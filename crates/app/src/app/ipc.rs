@@ -0,0 +1,93 @@
+//! Commands sent by other programs.
+//!
+//! With `ipc_server` enabled, commands arrive as messages of the IPC server
+//! (see the `termide-ipc` crate) and are run here, on the main loop, like
+//! the app's own commands. Terminals started afterwards get the socket in
+//! `TERMIDE_SOCKET`, so `termide --send` in them talks to this termide.
+
+use termide_app_core::{AppCommand, Direction, Message, MessageReceiver};
+use termide_config::TerminalSettings;
+use termide_ipc::IpcServer;
+use termide_logger as logger;
+
+use super::App;
+
+impl App {
+    /// Start or stop the IPC server as set in the config
+    pub fn apply_ipc_server(&mut self) {
+        if !self.state.config.general.ipc_server {
+            if self.ipc.take().is_some() {
+                logger::info("IPC server stopped");
+            }
+            return;
+        }
+        if self.ipc.is_some() {
+            return;
+        }
+        match IpcServer::start(termide_ipc::socket_path(std::process::id())) {
+            Ok(server) => {
+                logger::info(format!(
+                    "IPC server listening on {}",
+                    server.path().display()
+                ));
+                self.ipc = Some(server);
+            }
+            Err(e) => logger::error(format!("Failed to start IPC server: {}", e)),
+        }
+    }
+
+    /// Settings for new terminals, with the socket of the running IPC
+    /// server in `TERMIDE_SOCKET`
+    pub(super) fn terminal_settings(&self) -> TerminalSettings {
+        let mut settings = self.state.config.terminal.clone();
+        if let Some(server) = &self.ipc {
+            settings.env.insert(
+                termide_ipc::SOCKET_ENV.to_string(),
+                server.path().to_string_lossy().into_owned(),
+            );
+        }
+        settings
+    }
+
+    /// Run the commands received by the IPC server
+    pub(super) fn check_ipc(&mut self) {
+        let Some(server) = self.ipc.as_mut() else {
+            return;
+        };
+        for message in server.poll_messages() {
+            if let Message::Command(command) = message {
+                self.run_remote_command(command);
+                self.state.needs_redraw = true;
+            }
+        }
+    }
+
+    fn run_remote_command(&mut self, command: AppCommand) {
+        match command {
+            AppCommand::OpenFile { path, line, column } => {
+                if let Err(e) = self.event_open_file(path, line, column) {
+                    self.state.set_error(e.to_string());
+                }
+            }
+            AppCommand::Navigate {
+                direction: Direction::Index(index),
+            } => self.navigate_to_group(index + 1),
+            AppCommand::SetStatus { message, is_error } => {
+                if is_error {
+                    self.state.set_error(message);
+                } else {
+                    self.state.set_info(message);
+                }
+            }
+            AppCommand::Notify {
+                text,
+                level,
+                timeout,
+            } => self
+                .state
+                .notifications
+                .push(text, level, timeout, std::time::Instant::now()),
+            command => logger::warn(format!("Unsupported IPC command: {:?}", command)),
+        }
+    }
+}
@@ -47,6 +47,10 @@ impl App {
             return Ok(());
         }
 
+        if self.terminal_broadcast {
+            self.broadcast_terminal_key(key);
+        }
+
         // Pass event to active panel and collect results
         let (events, modal_request, config_update) =
             if let Some(panel) = self.layout_manager.active_panel_mut() {
@@ -71,9 +75,7 @@ impl App {
 
         // Apply config update if present (legacy, still used by Editor)
         if let Some(new_config) = config_update {
-            self.state.config = new_config.clone();
-            self.state.set_theme(&new_config.general.theme);
-            self.state.set_info("Config saved and applied".to_string());
+            self.apply_config(new_config);
         }
 
         // Handle modal window request from panel (legacy, still used)
@@ -84,8 +86,59 @@ impl App {
         Ok(())
     }
 
+    /// Send a key typed into the active terminal to the other terminals
+    fn broadcast_terminal_key(&mut self, key: crossterm::event::KeyEvent) {
+        let focus = self.layout_manager.focus;
+        let Some(active) = self
+            .layout_manager
+            .panel_groups
+            .get(focus)
+            .map(|group| group.expanded_index())
+        else {
+            return;
+        };
+        let typed_in_terminal = self
+            .layout_manager
+            .active_panel_mut()
+            .is_some_and(|panel| panel.as_terminal_mut().is_some());
+        if !typed_in_terminal {
+            return;
+        }
+
+        for (group_idx, group) in self.layout_manager.panel_groups.iter_mut().enumerate() {
+            for (panel_idx, panel) in group.panels_mut().iter_mut().enumerate() {
+                if (group_idx, panel_idx) == (focus, active) {
+                    continue;
+                }
+                if let Some(terminal) = panel.as_terminal_mut() {
+                    terminal.handle_broadcast_key(key);
+                }
+            }
+        }
+    }
+
+    /// Turn broadcasting keys to all terminals on or off
+    pub(super) fn toggle_terminal_broadcast(&mut self) {
+        self.terminal_broadcast = !self.terminal_broadcast;
+        let broadcast = self.terminal_broadcast;
+        for terminal in self
+            .layout_manager
+            .iter_all_panels_mut()
+            .filter_map(|panel| panel.as_terminal_mut())
+        {
+            terminal.set_broadcast(broadcast);
+        }
+
+        let t = i18n::t();
+        self.state.set_info(if broadcast {
+            t.status_broadcast_on().to_string()
+        } else {
+            t.status_broadcast_off().to_string()
+        });
+    }
+
     /// Handle modal request from panel
-    fn handle_modal_request(
+    pub(super) fn handle_modal_request(
         &mut self,
         mut action: PendingAction,
         mut modal: ActiveModal,
@@ -94,12 +147,18 @@ impl App {
         match &mut action {
             PendingAction::CreateFile { panel_index, .. }
             | PendingAction::CreateDirectory { panel_index, .. }
+            | PendingAction::CreateSymlink { panel_index, .. }
             | PendingAction::DeletePath { panel_index, .. }
+            | PendingAction::TrashPath { panel_index, .. }
+            | PendingAction::RestoreFromTrash { panel_index, .. }
             | PendingAction::CopyPath { panel_index, .. }
             | PendingAction::MovePath { panel_index, .. }
             | PendingAction::SaveFileAs { panel_index, .. }
+            | PendingAction::ChangePermissions { panel_index, .. }
+            | PendingAction::ChangePermissionsRecursively { panel_index, .. }
             | PendingAction::ClosePanel { panel_index }
             | PendingAction::CloseEditorWithSave { panel_index }
+            | PendingAction::ReviewUnsavedChanges { panel_index }
             | PendingAction::CloseEditorExternal { panel_index }
             | PendingAction::CloseEditorConflict { panel_index }
             | PendingAction::OverwriteDecision { panel_index, .. } => {
@@ -110,9 +169,28 @@ impl App {
             | PendingAction::RenameWithPattern { .. }
             | PendingAction::Search
             | PendingAction::Replace
+            | PendingAction::SelectSyntax { .. }
+            | PendingAction::SelectEncoding { .. }
+            | PendingAction::SwitchUndoBranch { .. }
+            | PendingAction::CompareDirectory { .. }
+            | PendingAction::CorrectSpelling { .. }
+            | PendingAction::OpenDroppedFiles { .. }
+            | PendingAction::CreateMissingPaths { .. }
+            | PendingAction::RecoverBuffers { .. }
+            | PendingAction::RecoverBuffer { .. }
+            | PendingAction::ResolveExternalChange { .. }
+            | PendingAction::GitCommit { .. }
+            | PendingAction::RunCommand { .. }
+            | PendingAction::OpenFoundFile { .. }
+            | PendingAction::SetBookmark { .. }
+            | PendingAction::PickBookmark
+            | PendingAction::RunTask { .. }
+            | PendingAction::SelectTheme { .. }
+            | PendingAction::ConfigWizard { .. }
             | PendingAction::NextPanel
             | PendingAction::PrevPanel
-            | PendingAction::QuitApplication => {
+            | PendingAction::QuitApplication
+            | PendingAction::QuitWithChanges { .. } => {
                 // These actions don't require panel_index update
             }
         }
@@ -130,18 +208,41 @@ impl App {
                 target_directory,
                 sources,
                 ..
-            } => {
-                if target_directory.is_none() && !sources.is_empty() {
-                    modal = self.prepare_copy_move_modal(sources, target_directory, is_copy);
+            } if target_directory.is_none() && !sources.is_empty() => {
+                modal = self.prepare_copy_move_modal(sources, target_directory, is_copy);
+            }
+            PendingAction::CompareDirectory { left } => {
+                if let Some(suggested) = self.compare_directory_suggestion(left) {
+                    let t = i18n::t();
+                    let name = left.file_name().and_then(|n| n.to_str()).unwrap_or("?");
+                    modal = ActiveModal::Input(Box::new(termide_modal::InputModal::with_default(
+                        t.modal_compare_title(),
+                        t.modal_compare_prompt(name),
+                        suggested.display().to_string(),
+                    )));
                 }
             }
             _ => {}
         }
 
+        // Up/Down in the search modal recall previous queries
+        if let ActiveModal::Search(search_modal) = &mut modal {
+            search_modal.set_history(
+                self.state
+                    .search_history
+                    .entries()
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+
         // Handle navigation actions without modal window
         match action {
             PendingAction::NextPanel => {
-                self.layout_manager.next_group();
+                // Tab in a two-panel layout switches between the file managers
+                if !self.focus_other_file_manager() {
+                    self.layout_manager.next_group();
+                }
                 return Ok(());
             }
             PendingAction::PrevPanel => {
@@ -153,18 +254,33 @@ impl App {
 
         self.state.set_pending_action(action, modal);
 
-        // Check if there's a channel receiver for directory size in panel
+        // Check if there are channel receivers for directory size and checksum in panel
         if let Some(panel) = self.layout_manager.active_panel_mut() {
             if let Some(fm) = panel.as_file_manager_mut() {
                 if let Some(rx) = fm.dir_size_receiver.take() {
                     self.state.dir_size_receiver = Some(rx);
                 }
+                // A new modal replaces the Info modal of an earlier checksum
+                self.state.checksum_receiver = fm.checksum_receiver.take();
             }
         }
 
         Ok(())
     }
 
+    /// Directory to compare `left` with: the directory of the same name in
+    /// the other file manager of a two-panel layout, or else that file
+    /// manager's directory
+    fn compare_directory_suggestion(&self, left: &std::path::Path) -> Option<std::path::PathBuf> {
+        let other = self.other_file_manager_dir()?;
+        let same_name = left.file_name().map(|name| other.join(name));
+        match same_name {
+            Some(dir) if dir.is_dir() && dir != left => Some(dir),
+            _ if other != left => Some(other),
+            _ => None,
+        }
+    }
+
     /// Prepare modal for copy/move operations
     fn prepare_copy_move_modal(
         &mut self,
@@ -174,19 +290,15 @@ impl App {
     ) -> ActiveModal {
         // Get source directory to exclude from default selection
         let source_dir = sources[0].parent().map(|p| p.to_path_buf());
-        let source_dir_str = source_dir.as_ref().map(|p| p.display().to_string());
 
         // Find all unique paths from other panels
         let options = self.find_all_other_panel_paths();
         let unique_paths_count = options.len();
 
-        // Filter out source directory for default selection
-        let default_dir = options
-            .iter()
-            .find(|opt| source_dir_str.as_ref() != Some(&opt.value))
-            .map(|opt| std::path::PathBuf::from(&opt.value))
-            .or_else(|| source_dir.clone())
-            .unwrap_or_else(|| std::path::PathBuf::from("/"));
+        // In a two-panel layout the target is the other file manager
+        let other_fm_dir = self.other_file_manager_dir();
+
+        let default_dir = default_target_dir(other_fm_dir.clone(), &options, source_dir);
 
         *target_directory = Some(default_dir.clone());
 
@@ -223,6 +335,16 @@ impl App {
             )
         };
 
+        // Show where the target comes from (it stays editable)
+        let prompt = match other_fm_dir {
+            Some(dir) => format!(
+                "{}\n{}",
+                t.modal_copy_move_other_panel(&dir.display().to_string()),
+                prompt
+            ),
+            None => prompt,
+        };
+
         // Choose modal based on number of unique paths
         if unique_paths_count >= 2 {
             let new_modal =
@@ -234,3 +356,54 @@ impl App {
         }
     }
 }
+
+/// Default copy/move target: the other file manager of a two-panel layout,
+/// else the first panel directory other than the source directory
+fn default_target_dir(
+    other_fm_dir: Option<std::path::PathBuf>,
+    options: &[termide_modal::SelectOption],
+    source_dir: Option<std::path::PathBuf>,
+) -> std::path::PathBuf {
+    let source_dir_str = source_dir.as_ref().map(|p| p.display().to_string());
+    other_fm_dir
+        .or_else(|| {
+            options
+                .iter()
+                .find(|opt| source_dir_str.as_ref() != Some(&opt.value))
+                .map(|opt| std::path::PathBuf::from(&opt.value))
+        })
+        .or(source_dir)
+        .unwrap_or_else(|| std::path::PathBuf::from("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use termide_modal::SelectOption;
+
+    #[test]
+    fn test_default_target_prefers_other_file_manager() {
+        let option = |value: &str| SelectOption {
+            panel_index: 0,
+            value: value.to_string(),
+            display: value.to_string(),
+        };
+        let options = [option("/src"), option("/a"), option("/b")];
+        let source = Some(PathBuf::from("/src"));
+
+        assert_eq!(
+            default_target_dir(Some(PathBuf::from("/b")), &options, source.clone()),
+            PathBuf::from("/b")
+        );
+        // Without a two-panel layout: the first directory that is not the source
+        assert_eq!(
+            default_target_dir(None, &options, source.clone()),
+            PathBuf::from("/a")
+        );
+        assert_eq!(
+            default_target_dir(None, &options[..1], source),
+            PathBuf::from("/src")
+        );
+    }
+}
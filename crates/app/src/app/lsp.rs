@@ -0,0 +1,176 @@
+//! Language servers of the files open in editors.
+//!
+//! On each tick the text of edited files is sent to their language servers
+//! (after a pause in typing), and the diagnostics and definitions the
+//! servers answer with are handed to the editors. Servers run on background
+//! threads, so a slow or crashed server never blocks the UI; its files are
+//! edited without it and a status message says so.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use termide_config::constants::LSP_CHANGE_DEBOUNCE_MS;
+use termide_config::Config;
+use termide_i18n as i18n;
+use termide_logger as logger;
+use termide_lsp::{grapheme_column, Location, LspEvent, Position};
+
+use super::App;
+use crate::PanelExt;
+
+/// Server commands of the config (none when language servers are disabled)
+pub(super) fn configured_servers(config: &Config) -> BTreeMap<String, String> {
+    if config.lsp.enabled {
+        config.lsp.servers.clone()
+    } else {
+        BTreeMap::new()
+    }
+}
+
+impl App {
+    /// Send edited files to their language servers and apply the events of
+    /// the servers
+    pub(super) fn check_lsp(&mut self) -> Result<()> {
+        self.send_lsp_updates(Duration::from_millis(LSP_CHANGE_DEBOUNCE_MS));
+
+        for event in self.lsp.poll() {
+            match event {
+                LspEvent::Diagnostics { path, diagnostics } => {
+                    for panel in self.layout_manager.iter_all_panels_mut() {
+                        if let Some(editor) = panel.as_editor_mut() {
+                            if editor.file_path() == Some(path.as_path()) {
+                                editor.set_lsp_diagnostics(&diagnostics);
+                                self.state.needs_redraw = true;
+                            }
+                        }
+                    }
+                }
+                LspEvent::Definition(Some(location)) => {
+                    self.open_definition(location)?;
+                    self.state.needs_redraw = true;
+                }
+                LspEvent::Definition(None) => {
+                    self.state
+                        .set_info(i18n::t().status_lsp_no_definition().to_string());
+                    self.state.needs_redraw = true;
+                }
+                LspEvent::Started { server, root } => {
+                    logger::info(format!(
+                        "Started language server '{}' in {}",
+                        server,
+                        root.display()
+                    ));
+                }
+                LspEvent::NotInstalled { server } => {
+                    logger::info(format!(
+                        "Language server '{}' is not installed, skipping it",
+                        server
+                    ));
+                }
+                LspEvent::FailedToStart { server, error } => {
+                    logger::warn(format!(
+                        "Failed to start language server '{}': {}",
+                        server, error
+                    ));
+                    self.state
+                        .set_error(i18n::t().status_lsp_not_started(&server, &error));
+                    self.state.needs_redraw = true;
+                }
+                LspEvent::Stopped { server } => {
+                    logger::warn(format!("Language server '{}' stopped", server));
+                    self.state.set_error(i18n::t().status_lsp_stopped(&server));
+                    self.state.needs_redraw = true;
+                }
+                LspEvent::Message {
+                    server,
+                    text,
+                    is_error,
+                } => {
+                    let message = format!("{}: {}", server, text);
+                    if is_error {
+                        logger::warn(message);
+                    } else {
+                        logger::debug(message);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask the language server of a file for the definition at a position
+    pub(super) fn event_goto_definition(&mut self, path: PathBuf, line: usize, character: usize) {
+        // The server needs the text the position belongs to
+        self.send_lsp_updates(Duration::ZERO);
+        if !self
+            .lsp
+            .goto_definition(&path, Position::new(line, character))
+        {
+            self.state
+                .set_info(i18n::t().status_lsp_no_server().to_string());
+        }
+    }
+
+    /// Use the server commands of the config (after it was changed)
+    pub(super) fn apply_lsp_servers(&mut self) {
+        self.lsp.set_servers(configured_servers(&self.state.config));
+    }
+
+    /// Send the text of files edited for `debounce` to their servers and
+    /// close the files no longer open
+    fn send_lsp_updates(&mut self, debounce: Duration) {
+        let mut open: Vec<PathBuf> = Vec::new();
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            let Some(editor) = panel.as_editor_mut() else {
+                continue;
+            };
+            let Some(path) = editor.file_path().map(Path::to_path_buf) else {
+                continue;
+            };
+            // Split views of a file share its text
+            if open.contains(&path) {
+                continue;
+            }
+            let Some(language) = editor
+                .lsp_language()
+                .filter(|language| self.lsp.has_server(language))
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            if let Some(text) = editor.take_lsp_update(debounce) {
+                self.lsp.update_document(&path, &language, &text);
+            }
+            open.push(path);
+        }
+        self.lsp.retain_documents(&open);
+    }
+
+    /// Open the file of a definition at its position
+    fn open_definition(&mut self, location: Location) -> Result<()> {
+        let Position { line, character } = location.position;
+        // Servers count columns in UTF-16 units, editors in graphemes
+        let column = self
+            .definition_line(&location.path, line)
+            .map_or(character, |text| grapheme_column(&text, character));
+        self.event_open_file(location.path, Some(line + 1), Some(column + 1))
+    }
+
+    /// Text of a line of a file, from its editor if it is open
+    fn definition_line(&mut self, path: &Path, line: usize) -> Option<String> {
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if let Some(editor) = panel.as_editor_mut() {
+                if editor.file_path() == Some(path) {
+                    return editor.buffer().line(line);
+                }
+            }
+        }
+        std::fs::read_to_string(path)
+            .ok()?
+            .lines()
+            .nth(line)
+            .map(str::to_string)
+    }
+}
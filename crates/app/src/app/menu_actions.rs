@@ -8,17 +8,22 @@
 use anyhow::Result;
 use crossterm::event::KeyCode;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use super::App;
-use crate::state::{ActiveModal, PendingAction};
+use crate::state::{ActiveModal, ConfigWizardStep, PendingAction};
 use crate::PanelExt;
+use termide_core::{CommandResult, PanelCommand};
 use termide_i18n as i18n;
 use termide_logger as logger;
+use termide_modal::SelectModal;
 use termide_panel_editor::Editor;
 use termide_panel_file_manager::FileManager;
-use termide_panel_misc::{LogViewerPanel as LogViewer, WelcomePanel as Welcome};
+use termide_panel_misc::LogViewerPanel as LogViewer;
+use termide_panel_misc::SystemMonitorPanel as SystemMonitor;
+use termide_panel_misc::{GitPanel, HelpPanel, SearchPanel, TaskPanel};
 use termide_panel_terminal::Terminal;
-use termide_ui_render::menu::MENU_ITEM_COUNT;
+use termide_ui_render::menu::{MENU_ITEM_COUNT, PANEL_MENU_INDEX};
 
 impl App {
     /// Handle keyboard event in menu
@@ -29,9 +34,16 @@ impl App {
             }
             KeyCode::Left => {
                 self.state.prev_menu_item(MENU_ITEM_COUNT);
+                self.select_first_panel_menu_item();
             }
             KeyCode::Right => {
                 self.state.next_menu_item(MENU_ITEM_COUNT);
+                self.select_first_panel_menu_item();
+            }
+            KeyCode::Up | KeyCode::Down
+                if self.state.ui.selected_menu_item == Some(PANEL_MENU_INDEX) =>
+            {
+                self.select_panel_menu_item(key.code == KeyCode::Down);
             }
             KeyCode::Enter => {
                 self.execute_menu_action()?;
@@ -65,30 +77,24 @@ impl App {
                     self.handle_new_debug()?;
                     self.state.close_menu();
                 }
-                4 => {
+                PANEL_MENU_INDEX => {
+                    // Panel - run the chosen action of the focused panel
+                    self.run_panel_menu_item(self.state.ui.selected_dropdown_item)?;
+                }
+                5 => {
                     // Preferences - open config file in editor
                     self.state.close_menu();
                     self.open_config_in_editor()?;
                 }
-                5 => {
+                6 => {
                     // Help - show help
                     self.state.close_menu();
                     self.handle_new_help()?;
                 }
-                6 => {
+                7 => {
                     // Quit - exit
                     self.state.close_menu();
-                    if self.has_panels_requiring_confirmation() {
-                        let t = i18n::t();
-                        let modal =
-                            termide_modal::ConfirmModal::new(t.modal_yes(), t.app_quit_confirm());
-                        self.state.set_pending_action(
-                            PendingAction::QuitApplication,
-                            ActiveModal::Confirm(Box::new(modal)),
-                        );
-                    } else {
-                        self.state.quit();
-                    }
+                    self.handle_quit_request()?;
                 }
                 _ => {}
             }
@@ -96,6 +102,70 @@ impl App {
         Ok(())
     }
 
+    /// Select the first enabled item when the Panel menu is shown
+    pub(super) fn select_first_panel_menu_item(&mut self) {
+        if self.state.ui.selected_menu_item != Some(PANEL_MENU_INDEX) {
+            return;
+        }
+        let count = self
+            .layout_manager
+            .active_panel_mut()
+            .map_or(0, |panel| panel.menu_items().len());
+        // Stepping down from the last item wraps to the first
+        self.state.ui.selected_dropdown_item = count.saturating_sub(1);
+        self.select_panel_menu_item(true);
+    }
+
+    /// Move the selection in the Panel menu to the next (or previous)
+    /// enabled item
+    fn select_panel_menu_item(&mut self, down: bool) {
+        let Some(panel) = self.layout_manager.active_panel_mut() else {
+            return;
+        };
+        let items = panel.menu_items();
+        let count = items.len();
+        let mut index = self.state.ui.selected_dropdown_item;
+        for _ in 0..count {
+            index = if down {
+                (index + 1) % count
+            } else {
+                (index + count - 1) % count
+            };
+            if items[index].enabled {
+                self.state.ui.selected_dropdown_item = index;
+                return;
+            }
+        }
+    }
+
+    /// Run item `index` of the focused panel's menu; disabled items keep
+    /// the menu open
+    pub(super) fn run_panel_menu_item(&mut self, index: usize) -> Result<()> {
+        let Some(panel) = self.layout_manager.active_panel_mut() else {
+            self.state.close_menu();
+            return Ok(());
+        };
+        let Some(item) = panel
+            .menu_items()
+            .into_iter()
+            .nth(index)
+            .filter(|item| item.enabled)
+        else {
+            return Ok(());
+        };
+        self.state.close_menu();
+
+        let result = panel.handle_command(PanelCommand::MenuAction { id: &item.id });
+        let modal_request = panel.take_modal_request();
+        if let CommandResult::Events(events) = result {
+            self.process_panel_events(events)?;
+        }
+        if let Some((action, modal)) = modal_request {
+            self.handle_modal_request(action, modal)?;
+        }
+        Ok(())
+    }
+
     /// Create new terminal
     pub(super) fn handle_new_terminal(&mut self) -> Result<()> {
         logger::debug("Opening new Terminal panel");
@@ -112,7 +182,12 @@ impl App {
         let term_height = height.saturating_sub(3);
         let term_width = width.saturating_sub(2);
 
-        if let Ok(terminal_panel) = Terminal::new_with_cwd(term_height, term_width, working_dir) {
+        if let Ok(terminal_panel) = Terminal::new_with_cwd(
+            term_height,
+            term_width,
+            working_dir,
+            &self.terminal_settings(),
+        ) {
             self.add_panel(Box::new(terminal_panel));
             self.auto_save_session();
         }
@@ -182,11 +257,167 @@ impl App {
         false
     }
 
+    /// Open the system monitor panel, or focus it if it is already open
+    pub(super) fn handle_new_system_monitor(&mut self) {
+        for (group_idx, group) in self.layout_manager.panel_groups.iter_mut().enumerate() {
+            let existing = group
+                .panels()
+                .iter()
+                .position(|panel| panel.as_any().is::<SystemMonitor>());
+            if let Some(panel_idx) = existing {
+                group.set_expanded(panel_idx);
+                self.layout_manager.focus = group_idx;
+                return;
+            }
+        }
+
+        logger::debug("Opening new System Monitor panel");
+        self.close_welcome_panels();
+        let settings = &self.state.config.logging;
+        let monitor = SystemMonitor::new(
+            Duration::from_millis(settings.system_monitor_interval),
+            settings.system_monitor_delta,
+        );
+        self.add_panel(Box::new(monitor));
+    }
+
+    /// Open the git panel for the active panel's repository, or switch to
+    /// the one already open for it
+    pub(super) fn handle_new_git_panel(&mut self) {
+        let directory = self
+            .layout_manager
+            .active_panel_mut()
+            .and_then(|p| p.get_working_directory())
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("/"));
+        let repo_root = termide_git::find_repo_root(&directory);
+
+        for (group_idx, group) in self.layout_manager.panel_groups.iter_mut().enumerate() {
+            let existing = group.panels().iter().position(|panel| {
+                panel
+                    .as_any()
+                    .downcast_ref::<GitPanel>()
+                    .is_some_and(|git| {
+                        repo_root.is_some() && git.repo_root() == repo_root.as_deref()
+                    })
+            });
+            if let Some(panel_idx) = existing {
+                group.set_expanded(panel_idx);
+                self.layout_manager.focus = group_idx;
+                return;
+            }
+        }
+
+        logger::debug("Opening new Git panel");
+        self.close_welcome_panels();
+        self.add_panel(Box::new(GitPanel::new(directory)));
+        self.auto_save_session();
+    }
+
+    /// Open the search panel for the active panel's repository (or
+    /// directory), or switch to the one already open for it
+    pub(super) fn handle_new_search_panel(&mut self) {
+        let directory = self
+            .layout_manager
+            .active_panel_mut()
+            .and_then(|p| p.get_working_directory())
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("/"));
+        let panel = SearchPanel::new(directory);
+
+        for (group_idx, group) in self.layout_manager.panel_groups.iter_mut().enumerate() {
+            let existing = group.panels().iter().position(|other| {
+                other
+                    .as_any()
+                    .downcast_ref::<SearchPanel>()
+                    .is_some_and(|search| search.root() == panel.root())
+            });
+            if let Some(panel_idx) = existing {
+                group.set_expanded(panel_idx);
+                self.layout_manager.focus = group_idx;
+                return;
+            }
+        }
+
+        logger::debug("Opening new Search panel");
+        self.close_welcome_panels();
+        self.add_panel(Box::new(panel));
+    }
+
+    /// Run the only configured task, or pick one of the configured tasks
+    pub(super) fn handle_run_task(&mut self) {
+        let t = i18n::t();
+        let names: Vec<String> = self.state.config.tasks.keys().cloned().collect();
+        match names.as_slice() {
+            [] => self.state.set_info(t.status_no_tasks().to_string()),
+            [name] => self.run_task(name.clone()),
+            _ => {
+                let cursor = self
+                    .last_task
+                    .as_ref()
+                    .and_then(|last| names.iter().position(|name| name == last))
+                    .unwrap_or(0);
+                let modal = SelectModal::single(t.modal_task_title(), "", names.clone())
+                    .with_cursor(cursor);
+                self.state.set_pending_action(
+                    PendingAction::RunTask { names },
+                    ActiveModal::Select(Box::new(modal)),
+                );
+            }
+        }
+    }
+
+    /// Run a configured task in the project root, reusing its panel
+    pub(super) fn run_task(&mut self, name: String) {
+        let Some(command) = self.state.config.tasks.get(&name).cloned() else {
+            return;
+        };
+        self.last_task = Some(name.clone());
+
+        for (group_idx, group) in self.layout_manager.panel_groups.iter_mut().enumerate() {
+            let existing = group.panels().iter().position(|panel| {
+                panel
+                    .as_any()
+                    .downcast_ref::<TaskPanel>()
+                    .is_some_and(|task| task.task_name() == name)
+            });
+            if let Some(panel_idx) = existing {
+                if let Some(task) = group.panels_mut()[panel_idx]
+                    .as_any_mut()
+                    .downcast_mut::<TaskPanel>()
+                {
+                    task.restart(command);
+                }
+                group.set_expanded(panel_idx);
+                self.layout_manager.focus = group_idx;
+                return;
+            }
+        }
+
+        logger::debug(format!("Running task {}", name));
+        self.close_welcome_panels();
+        let panel = TaskPanel::new(name, command, self.project_root.clone());
+        self.add_panel(Box::new(panel));
+    }
+
+    /// Show the active editor's buffer in a new panel (split view)
+    pub(super) fn handle_split_editor(&mut self) {
+        let Some(editor) = self.active_editor_mut() else {
+            self.state
+                .set_info(i18n::t().status_split_no_editor().to_string());
+            return;
+        };
+        let view = editor.split_view();
+        self.add_panel(Box::new(view));
+        self.auto_save_session();
+    }
+
     /// Open or switch to help panel (Welcome)
     pub(super) fn handle_new_help(&mut self) -> Result<()> {
-        logger::debug("Opening new Help/Welcome panel");
-        let welcome = Welcome::new();
-        self.add_panel(Box::new(welcome));
+        logger::debug("Opening keyboard shortcuts panel");
+        self.close_welcome_panels();
+        let help = HelpPanel::new(self.help_sections());
+        self.add_panel(Box::new(help));
         self.auto_save_session();
         Ok(())
     }
@@ -205,6 +436,11 @@ impl App {
             }
         };
 
+        if self.state.config.editor.reuse_open_editors
+            && self.focus_open_file(&config_path, None, None)
+        {
+            return Ok(());
+        }
         self.close_welcome_panels();
 
         match Editor::open_file_with_config(config_path, self.state.editor_config()) {
@@ -221,6 +457,88 @@ impl App {
         Ok(())
     }
 
+    /// Open the theme picker over built-in and user themes, starting at
+    /// the current theme. Only light or dark themes are listed when the
+    /// theme mode wants one of them
+    pub(super) fn open_theme_picker(&mut self) {
+        let t = i18n::t();
+        let mut names = termide_theme::available_theme_names();
+        if let Some(light) = self.state.wanted_light_theme() {
+            names.retain(|name| termide_theme::Theme::get_by_name(name).is_light() == light);
+        }
+        let original = self.state.config.general.theme.clone();
+        let active = self.state.active_theme_name();
+        let cursor = names.iter().position(|name| name == active).unwrap_or(0);
+        let modal =
+            SelectModal::single(t.modal_theme_title(), "", names.clone()).with_cursor(cursor);
+        self.state.set_pending_action(
+            PendingAction::SelectTheme { names, original },
+            ActiveModal::Select(Box::new(modal)),
+        );
+    }
+
+    /// Preview the theme under the cursor of the theme picker (or the
+    /// config wizard), or restore the original theme when it was closed
+    /// without a choice
+    pub(super) fn update_theme_preview(&mut self) {
+        let (names, original) = match &self.state.pending_action {
+            Some(PendingAction::SelectTheme { names, original }) => (names.as_slice(), original),
+            Some(PendingAction::ConfigWizard {
+                step,
+                values,
+                original_theme,
+                ..
+            }) => {
+                let names = if *step == ConfigWizardStep::Theme {
+                    values.as_slice()
+                } else {
+                    &[]
+                };
+                (names, original_theme)
+            }
+            _ => return,
+        };
+        let name = match &self.state.active_modal {
+            Some(ActiveModal::Select(modal)) => names.get(modal.cursor()).cloned(),
+            Some(_) => None,
+            None => {
+                let original = original.clone();
+                self.state.pending_action = None;
+                Some(original)
+            }
+        };
+        if let Some(name) = name {
+            if name != self.state.config.general.theme {
+                self.state.set_theme(&name);
+            }
+        }
+    }
+
+    /// Switch to a theme picked in the theme picker and save it in the config
+    pub(super) fn select_theme(&mut self, name: &str) {
+        self.state.set_theme(name);
+        if let Err(e) = self.state.config.save() {
+            logger::error(format!("Failed to save config: {}", e));
+            self.state
+                .set_error(i18n::t().status_error_save(&e.to_string()));
+        }
+    }
+
+    /// Reload the current theme after its file changed on disk
+    pub(super) fn reload_theme(&mut self) {
+        let name = self.state.active_theme_name().to_string();
+        let reloaded = termide_theme::reload_user_theme(&name);
+        // Restyles editors; terminals redraw their cached lines when the
+        // theme they rendered with differs
+        self.state.apply_theme();
+        if reloaded {
+            logger::info(format!("Theme '{}' reloaded", name));
+        } else {
+            self.state
+                .set_error(i18n::t().status_theme_not_loaded(&name));
+        }
+    }
+
     /// Check if any panel requires close confirmation
     pub(super) fn has_panels_requiring_confirmation(&self) -> bool {
         // Check if any panel has unsaved changes or running processes
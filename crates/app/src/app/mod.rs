@@ -9,12 +9,16 @@
 use anyhow::Result;
 use ratatui::{backend::Backend, Terminal};
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use termide_app_core::{LayoutController, PanelProvider};
-use termide_app_event::DefaultHotkeyProcessor;
+use termide_app_event::{DefaultHotkeyProcessor, HotkeyAction};
+use termide_app_panel::PanelRegistry;
 use termide_core::event::{Event, EventHandler};
+use termide_core::{FrameScheduler, FrameTiming, Wait};
 use termide_layout::LayoutManager;
+use termide_panel_editor::SearchHistory;
+use termide_session::Bookmarks;
 
 use crate::LayoutManagerSession;
 
@@ -22,33 +26,71 @@ use crate::state::AppState;
 use crate::PanelExt;
 
 // Panel trait re-export
+pub use startup::StartupTarget;
 pub use termide_core::Panel;
 
+mod bookmarks;
+mod config_reload;
+mod config_wizard;
+mod custom_panels;
 mod event_handler;
+mod file_index;
 mod global_hotkeys;
+mod ipc;
 mod key_handler;
+mod lsp;
 mod menu_actions;
 mod modal;
 mod modal_handler;
 mod mouse_handler;
 mod panel_manager;
 mod panel_operations;
+mod paste;
+mod quit;
+mod recovery;
+mod startup;
 
 /// Main application
 pub struct App {
     state: AppState,
     layout_manager: LayoutManager,
     event_handler: EventHandler,
+    /// When to draw and how long to wait for input
+    frames: FrameScheduler,
     /// Project root directory (used for per-project session storage)
     project_root: std::path::PathBuf,
     /// Global hotkey processor
     hotkey_processor: DefaultHotkeyProcessor,
+    /// Commands run from the command palette, most recent first
+    recent_commands: Vec<HotkeyAction>,
+    /// Project files for the file finder (built when first opened)
+    file_index: Option<file_index::FileIndex>,
+    /// Keys typed into a terminal are sent to all terminals
+    terminal_broadcast: bool,
+    /// Name of the task run last (preselected in the task picker)
+    last_task: Option<String>,
+    /// Files open in editors with unsaved changes flag, as reported by the editors
+    open_file_paths: Vec<(std::path::PathBuf, bool)>,
+    /// Canonical paths of `open_file_paths` passed to file managers
+    open_files: Vec<(std::path::PathBuf, bool)>,
+    /// Language servers of the files open in editors
+    lsp: termide_lsp::LspManager,
+    /// Layout changes are saved to the session (off for headless rendering)
+    session_saving: bool,
+    /// Recovery copies of unsaved changes written by this run
+    recovery_files: std::collections::BTreeSet<String>,
+    /// Panel types registered outside the app crate
+    panel_registry: PanelRegistry,
+    /// Server of commands sent by other programs (`ipc_server`)
+    ipc: Option<termide_ipc::IpcServer>,
 }
 
 impl App {
     /// Create a new application
     pub fn new() -> Self {
         let mut state = AppState::new();
+        termide_clipboard::set_copy_target(state.config.general.clipboard_target);
+        termide_git::set_backend(state.config.general.git_backend);
 
         // Get project root from current working directory
         let project_root = std::env::current_dir().unwrap_or_else(|_| {
@@ -80,6 +122,8 @@ impl App {
             min_log_level,
         );
         termide_logger::info("Application started");
+        state.apply_theme();
+        state.apply_status_bar();
 
         // Initialize git watcher for automatic status updates
         match termide_git::create_git_watcher() {
@@ -95,7 +139,20 @@ impl App {
 
         // Initialize filesystem watcher for automatic directory updates
         match termide_watcher::create_fs_watcher() {
-            Ok((watcher, receiver)) => {
+            Ok((mut watcher, receiver)) => {
+                // Edited user themes are reloaded while in use
+                if let Ok(themes_dir) = termide_config::Config::get_themes_dir() {
+                    if themes_dir.is_dir() {
+                        let _ = watcher.watch_directory(themes_dir);
+                    }
+                }
+                // So is the edited config
+                if let Some(config_dir) = termide_config::Config::config_file_path()
+                    .ok()
+                    .and_then(|path| path.parent().map(|dir| dir.to_path_buf()))
+                {
+                    let _ = watcher.watch_directory(config_dir);
+                }
                 state.fs_watcher = Some(watcher);
                 state.fs_watcher_receiver = Some(receiver);
                 termide_logger::info("FS watcher initialized");
@@ -111,15 +168,40 @@ impl App {
             termide_logger::warn(format!("Failed to cleanup old sessions: {}", e));
         }
 
-        Self {
+        let app = Self::with_state(state, project_root);
+        let project_root = app.project_root.clone();
+        app.remember_recent(|recent| recent.add_project(&project_root));
+        app
+    }
+
+    /// Application for `state` in `project_root`, without the loggers,
+    /// watchers and session cleanup `new` sets up
+    fn with_state(state: AppState, project_root: std::path::PathBuf) -> Self {
+        let lsp = termide_lsp::LspManager::new(lsp::configured_servers(&state.config));
+        let mut app = Self {
             state,
             layout_manager: LayoutManager::new(),
             event_handler: EventHandler::new(Duration::from_millis(
                 termide_config::constants::EVENT_HANDLER_INTERVAL_MS,
             )),
+            frames: FrameScheduler::new(frame_timing()),
             project_root,
             hotkey_processor: DefaultHotkeyProcessor::new(),
-        }
+            recent_commands: Vec::new(),
+            file_index: None,
+            terminal_broadcast: false,
+            last_task: None,
+            open_file_paths: Vec::new(),
+            open_files: Vec::new(),
+            lsp,
+            session_saving: true,
+            recovery_files: std::collections::BTreeSet::new(),
+            panel_registry: PanelRegistry::new(),
+            ipc: None,
+        };
+        app.apply_keybindings();
+        app.register_builtin_panels();
+        app
     }
 
     /// Create a new application with specified terminal size
@@ -138,6 +220,31 @@ impl App {
         self.layout_manager.add_panel(panel, config, terminal_width);
     }
 
+    /// Ask the terminal for its background in "auto" theme mode and switch
+    /// to a light or dark theme to match (call in raw mode, outside of
+    /// event reading)
+    pub fn detect_terminal_background(&mut self) {
+        use termide_config::ThemeMode;
+
+        if self.state.config.general.theme_mode != ThemeMode::Auto {
+            return;
+        }
+        let timeout = Duration::from_millis(termide_config::constants::BACKGROUND_QUERY_TIMEOUT_MS);
+        let Some(background) = termide_theme::query_background(timeout) else {
+            termide_logger::debug("Terminal did not report its background color");
+            return;
+        };
+        let light = termide_theme::is_light_color(background);
+        if self.state.terminal_light != Some(light) {
+            termide_logger::info(format!(
+                "Terminal background is {}",
+                if light { "light" } else { "dark" }
+            ));
+            self.state.terminal_light = Some(light);
+            self.state.apply_theme();
+        }
+    }
+
     /// Run the main application loop
     pub fn run<B: Backend>(
         &mut self,
@@ -148,9 +255,20 @@ impl App {
         let size = terminal.size()?;
         self.state.update_terminal_size(size.width, size.height);
 
+        // Terminal output and background work wake the loop when idle
+        if let Err(e) = self.event_handler.listen_for_wakeups() {
+            termide_logger::warn(format!("Cannot wait for background output: {}", e));
+        }
+
         while !self.state.should_quit {
-            // Process events
-            match self.event_handler.next()? {
+            // Wait for input until the next frame, or while idle until another
+            // thread wakes the loop or a timer is due
+            let event = match self.frames.wait(Instant::now()) {
+                Wait::Frame(timeout) => self.event_handler.next_within(timeout)?,
+                Wait::Idle(timeout) => self.event_handler.next_or_wake(timeout)?,
+            };
+            let input = !matches!(event, Event::Tick | Event::Wake | Event::FocusLost);
+            match event {
                 Event::Key(key) => {
                     self.handle_key_event(key)?;
                     self.state.needs_redraw = true;
@@ -159,6 +277,10 @@ impl App {
                     self.handle_mouse_event(mouse)?;
                     self.state.needs_redraw = true;
                 }
+                Event::Paste(text) => {
+                    self.handle_paste_event(text)?;
+                    self.state.needs_redraw = true;
+                }
                 Event::Resize(width, height) => {
                     // Update terminal dimensions in state
                     self.state.update_terminal_size(width, height);
@@ -178,53 +300,182 @@ impl App {
                 Event::FocusGained => {
                     // Redraw on focus gain to refresh display
                     self.state.needs_redraw = true;
-                }
-                Event::Tick => {
-                    // Check terminal panels for pending output (efficient redraw trigger)
-                    for panel in self.layout_manager.iter_all_panels_mut() {
-                        if let Some(terminal) = panel.as_terminal_mut() {
-                            if terminal.has_pending_output() {
-                                self.state.needs_redraw = true;
-                                break; // One terminal with output is enough to trigger redraw
-                            }
-                        }
+                    // The terminal may have switched between light and dark
+                    if self.state.config.general.follow_terminal_background {
+                        self.detect_terminal_background();
                     }
+                }
+                // Pick up what the other thread sent
+                Event::Wake => self.frames.request_tick(),
+                Event::Tick => {}
+            }
+            if input {
+                self.frames.note_activity(Instant::now());
+            }
 
-                    // Check channel for directory size calculation results
-                    self.check_dir_size_update();
-
-                    // Check channel for git status update events
-                    self.check_git_status_update();
-
-                    // Check channel for filesystem update events
-                    self.check_fs_update();
-
-                    // Check pending git diff updates (debounced)
-                    self.check_pending_git_diff_updates();
-
-                    // Update system resource monitoring (CPU, RAM)
-                    self.update_system_resources();
+            // Panels that changed by themselves (e.g. terminal output); all
+            // are asked so that each clears its flag
+            let changed = self
+                .layout_manager
+                .iter_all_panels_mut()
+                .filter(|panel| panel.needs_render())
+                .count();
+            if changed > 0 {
+                self.frames.note_activity(Instant::now());
+            }
 
-                    // Update spinner in Info modal if it's open
-                    self.update_info_modal_spinner();
+            if self.frames.tick_due(Instant::now()) {
+                self.run_background_work()?;
+                // Progress windows and spinners update on their own
+                if self.background_job_running() {
+                    self.frames.keep_awake(Instant::now());
                 }
             }
 
             // Check and close panels that should auto-close
             self.check_auto_close_panels()?;
 
-            // Render UI only when needed (reduces idle CPU from 24fps to near-zero)
+            // Draw at most once per frame, and only when something changed
             if self.state.needs_redraw {
+                self.frames.request_frame();
+                self.state.needs_redraw = false;
+            }
+            if self.frames.should_render(Instant::now()) {
                 terminal.draw(|frame| {
                     render_fn(frame, &mut self.state, &mut self.layout_manager);
                 })?;
-                self.state.needs_redraw = false;
+                self.frames.rendered(Instant::now());
             }
         }
 
+        // Nothing is lost on a clean exit
+        self.remove_recovery_files();
+        Ok(())
+    }
+
+    /// Pick up results of background work and let panels and timers
+    /// advance (runs on the tick of the frame scheduler)
+    fn run_background_work(&mut self) -> Result<()> {
+        // Check channel for directory size calculation results
+        self.check_dir_size_update();
+
+        // Check channel for file checksum progress
+        self.check_checksum_update();
+
+        // Check channel for batch copy/move progress
+        self.check_batch_transfer();
+
+        // Check channel for background deletion progress
+        self.check_batch_delete();
+
+        // Check channel for background permissions change progress
+        self.check_batch_permissions();
+
+        // Check channel for git status update events
+        self.check_git_status_update();
+
+        // Check channel for filesystem update events
+        self.check_fs_update();
+
+        // Pick up indexed files for the file finder
+        self.check_file_index();
+
+        // Run commands sent by other programs
+        self.check_ipc();
+
+        // Highlighting and git diffs deferred until typing pauses
+        self.run_deferred_work();
+
+        // Update system resource monitoring (CPU, RAM)
+        self.update_system_resources();
+
+        // Let panels pick up results of background work
+        self.tick_panels()?;
+
+        // Mark files open in editors in file managers
+        self.sync_open_files();
+
+        // Follow bookmarks moved by edits, show new ones in editors
+        self.sync_bookmarks();
+
+        // Talk to the language servers of open files
+        self.check_lsp()?;
+
+        // Copy unsaved changes for crash recovery
+        self.check_recovery();
+
+        // Update spinner in Info modal if it's open
+        self.update_info_modal_spinner();
+
+        // Cancel a chord prefix after its timeout
+        self.expire_pending_chord();
+
+        // Fade old toasts
+        self.check_notifications();
+
+        self.set_timers();
+
+        Ok(())
+    }
+
+    /// Wake an idle loop when timed background work is due
+    fn set_timers(&mut self) {
+        let interval = Duration::from_millis(self.state.config.logging.resource_monitor_interval);
+        let timers = [
+            Some(self.state.last_resource_update + interval),
+            self.state.notifications.next_expiry(),
+            self.hotkey_processor.pending_deadline(),
+            self.recovery_deadline(),
+            self.file_index
+                .as_ref()
+                .and_then(|index| index.refresh_deadline()),
+        ];
+        for at in timers.into_iter().flatten() {
+            self.frames.wake_at(at);
+        }
+    }
+
+    /// Remove toasts whose time is up
+    fn check_notifications(&mut self) {
+        if self.state.notifications.expire(Instant::now()) {
+            self.state.needs_redraw = true;
+        }
+    }
+
+    /// Whether a background job shown in a modal is running
+    fn background_job_running(&self) -> bool {
+        self.state.dir_size_receiver.is_some()
+            || self.state.checksum_receiver.is_some()
+            || self.state.batch_transfer.is_some()
+            || self.state.batch_delete.is_some()
+            || self.state.batch_permissions.is_some()
+    }
+
+    /// Draw a single frame of the current layout, without reading events
+    /// (for `--render-once` and snapshot tests)
+    pub fn render_once<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        render_fn: impl Fn(&mut ratatui::Frame<'_>, &mut AppState, &mut LayoutManager),
+    ) -> Result<()> {
+        let size = terminal.size()?;
+        self.state.update_terminal_size(size.width, size.height);
+        self.tick_panels()?;
+        self.sync_open_files();
+        self.sync_bookmarks();
+        terminal.draw(|frame| {
+            render_fn(frame, &mut self.state, &mut self.layout_manager);
+        })?;
+        self.state.needs_redraw = false;
         Ok(())
     }
 
+    /// Stop saving layout changes to the session file, so that a headless
+    /// run leaves the session of the project as it was
+    pub fn disable_session_saving(&mut self) {
+        self.session_saving = false;
+    }
+
     /// Check and close panels that should auto-close
     fn check_auto_close_panels(&mut self) -> Result<()> {
         // Check if active panel should auto-close
@@ -269,6 +520,38 @@ impl App {
         }
     }
 
+    /// Show checksum progress and result in the Info modal; closing the
+    /// modal drops the receiver, which stops the worker
+    fn check_checksum_update(&mut self) {
+        use crate::state::ActiveModal;
+        use termide_panel_file_manager::checksum::INFO_KEY;
+        use termide_state::ChecksumUpdate;
+
+        let Some(rx) = &self.state.checksum_receiver else {
+            return;
+        };
+        let Some(ActiveModal::Info(ref mut modal)) = self.state.active_modal else {
+            self.state.checksum_receiver = None;
+            return;
+        };
+        let Some(update) = rx.try_iter().last() else {
+            return;
+        };
+        let t = termide_i18n::t();
+        let value = match update {
+            ChecksumUpdate::Progress { done, total } => {
+                let percent = (done * 100).checked_div(total).unwrap_or(0).min(100);
+                format!("{}... {}%", t.file_info_calculating(), percent)
+            }
+            ChecksumUpdate::Finished(result) => {
+                self.state.checksum_receiver = None;
+                result.unwrap_or_else(|e| e)
+            }
+        };
+        modal.update_value(INFO_KEY, value);
+        self.state.needs_redraw = true;
+    }
+
     /// Check channel for git status update events
     fn check_git_status_update(&mut self) {
         use termide_core::PanelCommand;
@@ -307,6 +590,13 @@ impl App {
                 .into_iter()
                 .collect();
 
+            // Branches are read again on the next status bar render
+            for repo_path in &repo_paths {
+                if self.state.git_branches.remove(*repo_path).is_some() {
+                    self.state.needs_redraw = true;
+                }
+            }
+
             // Update each panel at most once using handle_command
             for panel in self.layout_manager.iter_all_panels_mut() {
                 if panel
@@ -323,6 +613,8 @@ impl App {
 
     /// Check channel for filesystem update events
     fn check_fs_update(&mut self) {
+        use termide_app_core::{FileChange, Message};
+        use termide_app_watcher::WatcherUpdate;
         use termide_core::{CommandResult, PanelCommand};
         use termide_git::find_repo_root;
 
@@ -380,31 +672,126 @@ impl App {
                 updates.push(update);
             }
         }
+        // Gitignored churn (build output) goes no further
+        if let Some(watcher) = &mut self.state.fs_watcher {
+            updates = watcher.filter_updates(updates);
+        }
 
-        // Process collected updates using handle_command
+        // The current theme's file was edited
+        let theme_path = termide_theme::user_theme_path(self.state.active_theme_name());
+        if updates
+            .iter()
+            .any(|update| Some(&update.changed_path) == theme_path.as_ref())
+        {
+            self.reload_theme();
+        }
+
+        let config_path = termide_config::Config::config_file_path().ok();
+        if updates
+            .iter()
+            .any(|update| Some(&update.changed_path) == config_path.as_ref())
+        {
+            self.reload_config();
+        }
+
+        // A project settings file changed, appeared or went away
+        let mut project_changed = false;
+        for update in &updates {
+            project_changed |= self.state.project_configs.invalidate(&update.changed_path);
+        }
+        if project_changed {
+            self.apply_project_settings();
+        }
+
+        // Panels get one update per changed directory and throttle window
         for update in updates {
+            if let Some(index) = &mut self.file_index {
+                index.on_fs_change(&update.changed_path);
+            }
+            self.state.fs_updates.add_update(WatcherUpdate::FileSystem {
+                path: update.changed_path,
+                change: FileChange::Modified,
+            });
+        }
+        let messages = self
+            .state
+            .fs_updates
+            .take_throttled(&mut self.state.fs_throttler);
+
+        // Process collected updates using handle_command
+        let mut modal_request = None;
+        for message in messages {
+            let Message::FsUpdate { path, .. } = message else {
+                continue;
+            };
             for panel in self.layout_manager.iter_all_panels_mut() {
                 // Use OnFsUpdate command - panel decides if it needs to update
                 if panel
                     .handle_command(PanelCommand::OnFsUpdate {
-                        changed_path: &update.changed_path,
+                        changed_path: &path,
                     })
                     .needs_redraw()
                 {
                     self.state.needs_redraw = true;
                 }
+
+                // Editors reload changed files or ask about conflicts
+                if let Some(editor) = panel.as_editor_mut() {
+                    if let Some(message) = editor.take_status_message() {
+                        self.state.set_info(message);
+                    }
+                    // Another modal is open: the request waits for the next key
+                    if modal_request.is_none() && !self.state.has_modal() {
+                        modal_request = editor.take_modal_request();
+                    }
+                }
+            }
+        }
+        if let Some((action, modal)) = modal_request {
+            if let Err(e) = self.handle_modal_request(action, modal) {
+                termide_logger::error(format!("Failed to open modal: {}", e));
             }
         }
     }
 
-    /// Check and apply pending git diff updates (debounced) and async git diff results
-    fn check_pending_git_diff_updates(&mut self) {
+    /// Poll the file finder index and pass new files to an open file finder
+    fn check_file_index(&mut self) {
+        use crate::state::ActiveModal;
+        use file_index::IndexChange;
+
+        let Some(index) = &mut self.file_index else {
+            return;
+        };
+        let Some(change) = index.poll() else {
+            return;
+        };
+        if let Some(ActiveModal::FileFinder(modal)) = &mut self.state.active_modal {
+            match change {
+                IndexChange::Added(start) => modal.add_files(&index.files()[start..]),
+                IndexChange::Replaced => modal.set_files(index.files().to_vec()),
+                IndexChange::Finished => {}
+            }
+            modal.set_indexing(
+                index.is_indexing(),
+                termide_i18n::t().modal_file_finder_indexing(),
+            );
+            self.state.needs_redraw = true;
+        }
+    }
+
+    /// Run work panels deferred until edits pause (highlighting of whole
+    /// documents, git diffs of buffers) and apply async git diff results
+    fn run_deferred_work(&mut self) {
         use termide_core::PanelCommand;
 
-        // Check all panels for pending git diff updates and async results using handle_command
         for panel in self.layout_manager.iter_all_panels_mut() {
-            // Check debounced buffer updates
-            panel.handle_command(PanelCommand::CheckPendingGitDiff);
+            // Work debounced after buffer edits
+            if panel
+                .handle_command(PanelCommand::RunDeferredWork)
+                .needs_redraw()
+            {
+                self.state.needs_redraw = true;
+            }
             // Check async git diff results (from background thread)
             if panel
                 .handle_command(PanelCommand::CheckGitDiffReceiver)
@@ -415,6 +802,49 @@ impl App {
         }
     }
 
+    /// Pass the files open in editors to file managers
+    ///
+    /// Paths are canonicalized only when an editor opens, closes or saves a
+    /// file (or its unsaved changes flag changes).
+    fn sync_open_files(&mut self) {
+        use termide_core::PanelCommand;
+
+        let open_file_paths: Vec<_> = self
+            .layout_manager
+            .iter_all_panels_mut()
+            .filter_map(|panel| {
+                let (is_modified, _) = panel
+                    .handle_command(PanelCommand::GetModificationStatus)
+                    .modification_status()?;
+                Some((panel.file_path()?.to_path_buf(), is_modified))
+            })
+            .collect();
+        if open_file_paths != self.open_file_paths {
+            // A file open in several editors is listed once, modified if
+            // any of them has unsaved changes
+            self.open_files.clear();
+            for (path, is_modified) in &open_file_paths {
+                let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+                match self.open_files.iter_mut().find(|(open, _)| *open == path) {
+                    Some((_, modified)) => *modified |= is_modified,
+                    None => self.open_files.push((path, *is_modified)),
+                }
+            }
+            self.open_file_paths = open_file_paths;
+        }
+
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if panel
+                .handle_command(PanelCommand::SetOpenFiles {
+                    files: &self.open_files,
+                })
+                .needs_redraw()
+            {
+                self.state.needs_redraw = true;
+            }
+        }
+    }
+
     /// Update system resource monitoring (CPU, RAM)
     /// Respects the configured update interval
     fn update_system_resources(&mut self) {
@@ -429,6 +859,73 @@ impl App {
         }
     }
 
+    /// Call `Panel::tick` on all panels and process the events they emit.
+    ///
+    /// System monitor panels are given the shells of terminal panels first.
+    fn tick_panels(&mut self) -> Result<()> {
+        use termide_panel_misc::SystemMonitorPanel;
+        use termide_system_monitor::ProcessTree;
+
+        let has_monitor = self
+            .layout_manager
+            .iter_all_panels_mut()
+            .any(|panel| panel.as_any().is::<SystemMonitorPanel>());
+        if has_monitor {
+            let mut trees = Vec::new();
+            for panel in self.layout_manager.iter_all_panels_mut() {
+                let label = panel.title();
+                if let Some(root_pid) = panel.as_terminal_mut().and_then(|t| t.shell_pid()) {
+                    trees.push(ProcessTree { label, root_pid });
+                }
+            }
+            for panel in self.layout_manager.iter_all_panels_mut() {
+                if let Some(monitor) = panel.as_any_mut().downcast_mut::<SystemMonitorPanel>() {
+                    monitor.set_trees(trees.clone());
+                }
+            }
+        }
+
+        // Terminals opened while broadcasting show it too
+        if self.terminal_broadcast {
+            for terminal in self
+                .layout_manager
+                .iter_all_panels_mut()
+                .filter_map(|panel| panel.as_terminal_mut())
+            {
+                terminal.set_broadcast(true);
+            }
+        }
+
+        let events: Vec<_> = self
+            .layout_manager
+            .iter_all_panels_mut()
+            .flat_map(|panel| panel.tick())
+            .collect();
+        // Panels reporting background results stay responsive
+        if !events.is_empty() {
+            self.frames.keep_awake(Instant::now());
+        }
+        self.refresh_search_match_info();
+        self.process_panel_events(events)
+    }
+
+    /// Show the matches of a search the editor ran after a pause in typing
+    fn refresh_search_match_info(&mut self) {
+        use crate::state::ActiveModal;
+
+        if !matches!(self.state.active_modal, Some(ActiveModal::Search(_))) {
+            return;
+        }
+        let match_info = self
+            .active_editor_mut()
+            .and_then(|editor| editor.get_search_match_info());
+        if let (Some(ActiveModal::Search(modal)), Some((current, total))) =
+            (&mut self.state.active_modal, match_info)
+        {
+            modal.set_match_info(current, total);
+        }
+    }
+
     /// Update spinner in Info modal if it's open
     /// Throttled to 125ms (8 FPS) to reduce unnecessary redraws
     fn update_info_modal_spinner(&mut self) {
@@ -438,7 +935,7 @@ impl App {
 
         if let Some(ActiveModal::Info(ref mut modal)) = self.state.active_modal {
             // Update spinner only if calculation is still ongoing
-            if self.state.dir_size_receiver.is_some() {
+            if self.state.dir_size_receiver.is_some() || self.state.checksum_receiver.is_some() {
                 // Throttle spinner updates
                 let should_update = self
                     .state
@@ -460,7 +957,14 @@ impl App {
         let session_dir = termide_session::Session::get_session_dir(&self.project_root)?;
 
         // Serialize layout to session (may save temporary buffers)
-        let session = self.layout_manager.to_session(&session_dir);
+        let mut session = self.layout_manager.to_session(&session_dir);
+        session.search_history = self
+            .state
+            .search_history
+            .entries()
+            .map(str::to_string)
+            .collect();
+        session.bookmarks = self.state.bookmarks.entries().to_vec();
 
         // Save session to file
         session.save(&self.project_root)?;
@@ -471,7 +975,10 @@ impl App {
     /// Load session from file and restore layout
     pub fn load_session(&mut self) -> Result<()> {
         // Load session for this project
-        let session = termide_session::Session::load(&self.project_root)?;
+        let mut session = termide_session::Session::load(&self.project_root)?;
+        self.state.search_history =
+            SearchHistory::from_entries(std::mem::take(&mut session.search_history));
+        self.state.bookmarks = Bookmarks::from_entries(std::mem::take(&mut session.bookmarks));
 
         // Get session directory for restoring temporary buffers
         let session_dir = termide_session::Session::get_session_dir(&self.project_root)?;
@@ -487,8 +994,15 @@ impl App {
             term_height,
             term_width,
             self.state.editor_config(),
+            &self.terminal_settings(),
+            &self.panel_registry,
         )?;
+        // Saved widths are for the terminal size at save time
+        self.layout_manager
+            .redistribute_widths_proportionally(self.state.terminal.width);
         termide_logger::info("Session loaded");
+        // The session restores editors with the global settings
+        self.apply_project_settings();
 
         // Clean up orphaned buffer files (not referenced in session anymore)
         if let Err(e) = termide_session::cleanup_orphaned_buffers(&session_dir) {
@@ -500,6 +1014,9 @@ impl App {
 
     /// Auto-save session (ignores errors to not disrupt user experience)
     pub fn auto_save_session(&mut self) {
+        if !self.session_saving {
+            return;
+        }
         if let Err(e) = self.save_session() {
             // Log error but don't interrupt user workflow
             termide_logger::error(format!("Failed to auto-save session: {}", e));
@@ -613,3 +1130,14 @@ impl LayoutController for App {
         self.layout_manager.set_focus(index);
     }
 }
+
+/// Timing of the main loop
+fn frame_timing() -> FrameTiming {
+    use termide_config::constants;
+
+    FrameTiming {
+        frame: Duration::from_millis(constants::FRAME_INTERVAL_MS),
+        tick: Duration::from_millis(constants::EVENT_HANDLER_INTERVAL_MS),
+        active: Duration::from_millis(constants::ACTIVE_INTERVAL_MS),
+    }
+}
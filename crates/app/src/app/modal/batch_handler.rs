@@ -5,12 +5,20 @@
 
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::mpsc::TryRecvError;
 
 use super::super::App;
-use crate::state::{ActiveModal, BatchOperation, BatchOperationType, ConflictMode, PendingAction};
+use crate::state::{
+    ActiveModal, BatchDelete, BatchOperation, BatchOperationType, BatchPermissions,
+    BatchProgressUpdate, BatchTransfer, ConflictMode, DeleteProgressUpdate, DeleteSummary,
+    PendingAction, PermissionsProgressUpdate, PermissionsReport,
+};
 use crate::PanelExt;
 use termide_i18n as i18n;
-use termide_modal::ConflictModal;
+use termide_modal::{ConflictModal, ProgressModal};
+use termide_panel_file_manager::{CopyOptions, FileManager};
+use termide_panel_misc::DuplicatesPanel;
+use termide_state::{CancellationToken, NotificationLevel, PermissionsChange};
 use termide_ui::path_utils;
 
 impl App {
@@ -123,54 +131,17 @@ impl App {
         if let Some(resolution) = value.downcast_ref::<ConflictResolution>() {
             match resolution {
                 ConflictResolution::Overwrite => {
-                    // Overwrite this file - execute operation directly
+                    // Overwrite this file - transfer it without conflict check
                     if let Some(source) = operation.current_source().cloned() {
-                        let item_name = path_utils::get_file_name_string(&source);
-
                         let final_dest = path_utils::resolve_batch_destination_path(
                             &source,
                             &operation.destination,
                             operation.sources.len() == 1,
                         );
-
-                        // Execute operation
-                        if let Some(panel) = self.layout_manager.active_panel_mut() {
-                            if let Some(fm) = panel.as_file_manager_mut() {
-                                let result = match operation.operation_type {
-                                    BatchOperationType::Copy => fm.copy_path(source, final_dest),
-                                    BatchOperationType::Move => fm.move_path(source, final_dest),
-                                };
-
-                                match result {
-                                    Ok(_) => {
-                                        let action_name = match operation.operation_type {
-                                            BatchOperationType::Copy => "copied",
-                                            BatchOperationType::Move => "moved",
-                                        };
-                                        termide_logger::info(format!(
-                                            "File {}: {}",
-                                            action_name, item_name
-                                        ));
-                                        operation.increment_success();
-                                    }
-                                    Err(e) => {
-                                        let action_name = match operation.operation_type {
-                                            BatchOperationType::Copy => "copy",
-                                            BatchOperationType::Move => "move",
-                                        };
-                                        termide_logger::error(format!(
-                                            "Failed to {} '{}': {}",
-                                            action_name, item_name, e
-                                        ));
-                                        operation.increment_error();
-                                    }
-                                }
-                            }
-                        }
+                        self.start_batch_transfer(operation, source, final_dest);
+                    } else {
+                        self.process_batch_operation(operation);
                     }
-                    // Move to next file
-                    operation.advance();
-                    self.process_batch_operation(operation.clone());
                 }
                 ConflictResolution::Skip => {
                     // Skip this file
@@ -254,8 +225,17 @@ impl App {
 
     /// Handle batch file operation (copy/move)
     pub(in crate::app) fn process_batch_operation(&mut self, mut operation: BatchOperation) {
+        // Stop before next file if user requested cancellation
+        if operation.is_cancel_requested() {
+            operation.mark_cancelled();
+        }
+
         // Check if operation is complete
         if operation.is_complete() {
+            if matches!(self.state.active_modal, Some(ActiveModal::Progress(_))) {
+                self.state.close_modal();
+            }
+
             // Show final results
             self.show_batch_results(&operation);
 
@@ -340,42 +320,136 @@ impl App {
             }
         }
 
-        // Execute operation
-        if let Some(panel) = self.layout_manager.active_panel_mut() {
-            if let Some(fm) = panel.as_file_manager_mut() {
-                let result = match operation.operation_type {
-                    BatchOperationType::Copy => fm.copy_path(source, final_dest),
-                    BatchOperationType::Move => fm.move_path(source, final_dest),
-                };
+        // Execute operation on worker thread (continues in check_batch_transfer)
+        self.start_batch_transfer(operation, source, final_dest);
+    }
 
-                match result {
-                    Ok(_) => {
-                        let t = i18n::t();
-                        let action_name = match operation.operation_type {
-                            BatchOperationType::Copy => t.action_copied(),
-                            BatchOperationType::Move => t.action_moved(),
-                        };
-                        termide_logger::info(format!("'{}' {}", item_name, action_name));
-                        operation.increment_success();
-                    }
-                    Err(e) => {
-                        let t = i18n::t();
-                        let action_name = match operation.operation_type {
-                            BatchOperationType::Copy => t.action_copying(),
-                            BatchOperationType::Move => t.action_moving(),
-                        };
-                        termide_logger::error(format!(
-                            "Ошибка {} '{}': {}",
-                            action_name, item_name, e
-                        ));
-                        operation.increment_error();
-                    }
+    /// Start copying/moving current item in background and show progress modal
+    fn start_batch_transfer(
+        &mut self,
+        operation: BatchOperation,
+        source: PathBuf,
+        destination: PathBuf,
+    ) {
+        let item_name = path_utils::get_file_name_string(&source);
+
+        // First transfer also scans total size of all sources
+        let scan = operation
+            .bytes_total
+            .is_none()
+            .then(|| operation.sources.clone());
+        let options = CopyOptions {
+            dereference_symlinks: self.state.config.file_manager.copy_dereference_symlinks,
+        };
+        let receiver = termide_panel_file_manager::spawn_transfer(
+            operation.operation_type,
+            source,
+            destination,
+            options,
+            operation.cancel_token.clone(),
+            scan,
+        );
+
+        // Progress modal is kept open between items
+        if !matches!(self.state.active_modal, Some(ActiveModal::Progress(_))) {
+            let t = i18n::t();
+            let title = match operation.operation_type {
+                BatchOperationType::Copy => t.progress_copy_title(),
+                BatchOperationType::Move => t.progress_move_title(),
+            };
+            let modal = ProgressModal::new(
+                title,
+                operation.cancel_token.clone(),
+                FileManager::format_size_static,
+            );
+            self.state.active_modal = Some(ActiveModal::Progress(Box::new(modal)));
+        }
+
+        let transfer = BatchTransfer {
+            operation,
+            receiver,
+            item_name,
+        };
+        update_progress_modal(&mut self.state.active_modal, &transfer);
+        self.state.batch_transfer = Some(transfer);
+        self.state.needs_redraw = true;
+    }
+
+    /// Check channel for batch transfer progress and continue with next item when done
+    pub(in crate::app) fn check_batch_transfer(&mut self) {
+        let Some(transfer) = self.state.batch_transfer.as_mut() else {
+            return;
+        };
+
+        let mut received = false;
+        let mut finished = None;
+        loop {
+            match transfer.receiver.try_recv() {
+                Ok(BatchProgressUpdate::TotalBytes(total)) => {
+                    transfer.operation.set_bytes_total(total)
+                }
+                Ok(BatchProgressUpdate::Bytes(bytes)) => transfer.operation.add_bytes(bytes),
+                Ok(update) => {
+                    finished = Some(update);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished = Some(BatchProgressUpdate::Finished(Err(
+                        "transfer worker stopped unexpectedly".to_string(),
+                    )));
+                    break;
                 }
             }
+            received = true;
+        }
+
+        if received || finished.is_some() {
+            self.state.needs_redraw = true;
+        }
+
+        let Some(update) = finished else {
+            if let Some(transfer) = self.state.batch_transfer.as_ref().filter(|_| received) {
+                update_progress_modal(&mut self.state.active_modal, transfer);
+            }
+            return;
+        };
+
+        let Some(BatchTransfer {
+            mut operation,
+            item_name,
+            ..
+        }) = self.state.batch_transfer.take()
+        else {
+            return;
+        };
+
+        let t = i18n::t();
+        match update {
+            BatchProgressUpdate::Finished(Ok(())) => {
+                let action_name = match operation.operation_type {
+                    BatchOperationType::Copy => t.action_copied(),
+                    BatchOperationType::Move => t.action_moved(),
+                };
+                termide_logger::info(format!("'{}' {}", item_name, action_name));
+                operation.increment_success();
+                operation.advance();
+            }
+            BatchProgressUpdate::Finished(Err(e)) => {
+                let action_name = match operation.operation_type {
+                    BatchOperationType::Copy => t.action_copying(),
+                    BatchOperationType::Move => t.action_moving(),
+                };
+                termide_logger::error(format!("Ошибка {} '{}': {}", action_name, item_name, e));
+                operation.increment_error();
+                operation.advance();
+            }
+            _ => {
+                termide_logger::info(format!("'{}' cancelled", item_name));
+                operation.mark_cancelled();
+            }
         }
 
-        // Move to next file
-        operation.advance();
         self.process_batch_operation(operation);
     }
 
@@ -392,18 +466,25 @@ impl App {
             BatchOperationType::Move => (t.batch_result_file_moved(), t.batch_result_moved()),
         };
 
-        if total == 1 {
+        if total == 1 && !operation.cancelled {
             if success == 1 {
-                self.state.set_info(format!("Файл {}", action_name.0));
+                let message = format!("Файл {}", action_name.0);
+                self.state.set_info(message.clone());
+                self.state.notify(message, NotificationLevel::Info);
             } else {
                 let error_msg = match operation.operation_type {
                     BatchOperationType::Copy => t.batch_result_error_copy(),
                     BatchOperationType::Move => t.batch_result_error_move(),
                 };
                 self.state.set_error(error_msg.to_string());
+                self.state
+                    .notify(error_msg.to_string(), NotificationLevel::Error);
             }
         } else {
             let mut parts = vec![];
+            if operation.cancelled {
+                parts.push(t.batch_result_cancelled().to_string());
+            }
             if success > 0 {
                 parts.push(format!("{}: {}", action_name.1, success));
             }
@@ -414,7 +495,281 @@ impl App {
                 parts.push(t.batch_result_errors_fmt(errors));
             }
 
-            self.state.set_info(parts.join(", "));
+            let message = parts.join(", ");
+            self.state.set_info(message.clone());
+            let level = if errors > 0 {
+                NotificationLevel::Error
+            } else if operation.cancelled || skipped > 0 {
+                NotificationLevel::Warning
+            } else {
+                NotificationLevel::Info
+            };
+            self.state.notify(message, level);
+        }
+    }
+
+    /// Start deleting paths permanently in background and show progress modal
+    pub(in crate::app) fn start_batch_delete(&mut self, paths: Vec<PathBuf>) {
+        if self.state.batch_delete.is_some() {
+            return;
+        }
+        termide_logger::info(format!("Deleting {} items", paths.len()));
+
+        let cancel_token = CancellationToken::new();
+        let receiver =
+            termide_panel_file_manager::spawn_delete(paths.clone(), cancel_token.clone());
+        let modal = ProgressModal::new(
+            i18n::t().progress_delete_title(),
+            cancel_token,
+            FileManager::format_size_static,
+        )
+        .without_sizes();
+        self.state.active_modal = Some(ActiveModal::Progress(Box::new(modal)));
+
+        let delete = BatchDelete {
+            current: paths.first().cloned().unwrap_or_default(),
+            paths,
+            receiver,
+            total: 0,
+            removed: 0,
+        };
+        update_delete_modal(&mut self.state.active_modal, &delete);
+        self.state.batch_delete = Some(delete);
+        self.state.needs_redraw = true;
+    }
+
+    /// Check channel for deletion progress and report the result when done
+    ///
+    /// Panels showing the deleted paths pick up changes through the
+    /// filesystem watcher while the worker runs and are reloaded once at
+    /// the end.
+    pub(in crate::app) fn check_batch_delete(&mut self) {
+        let Some(delete) = self.state.batch_delete.as_mut() else {
+            return;
+        };
+
+        let mut received = false;
+        let mut finished = None;
+        loop {
+            match delete.receiver.try_recv() {
+                Ok(DeleteProgressUpdate::Total(total)) => delete.total = total,
+                Ok(DeleteProgressUpdate::Removed { count, current }) => {
+                    delete.removed = count;
+                    delete.current = current;
+                }
+                Ok(DeleteProgressUpdate::Finished(summary)) => {
+                    finished = Some(summary);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished = Some(DeleteSummary {
+                        errors: vec![(
+                            PathBuf::new(),
+                            "deletion worker stopped unexpectedly".to_string(),
+                        )],
+                        ..DeleteSummary::default()
+                    });
+                    break;
+                }
+            }
+            received = true;
+        }
+
+        if received || finished.is_some() {
+            self.state.needs_redraw = true;
+        }
+
+        let Some(summary) = finished else {
+            if let Some(delete) = self.state.batch_delete.as_ref().filter(|_| received) {
+                update_delete_modal(&mut self.state.active_modal, delete);
+            }
+            return;
+        };
+        let Some(delete) = self.state.batch_delete.take() else {
+            return;
+        };
+
+        if matches!(self.state.active_modal, Some(ActiveModal::Progress(_))) {
+            self.state.close_modal();
+        }
+        self.show_delete_results(&delete.paths, &summary);
+
+        // Clear selection and refresh panels showing the deleted paths
+        if summary.deleted > 0 {
+            if let Some(fm) = self
+                .layout_manager
+                .active_panel_mut()
+                .and_then(|panel| panel.as_file_manager_mut())
+            {
+                fm.clear_selection();
+            }
+        }
+        let mut parents: Vec<PathBuf> = delete
+            .paths
+            .iter()
+            .filter_map(|path| path.parent().map(PathBuf::from))
+            .collect();
+        parents.dedup();
+        for parent in parents {
+            self.refresh_fm_panels(&parent);
+        }
+        // Drop the deleted files from duplicate lists
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if let Some(duplicates) = panel.as_any_mut().downcast_mut::<DuplicatesPanel>() {
+                duplicates.prune_missing();
+            }
+        }
+    }
+
+    /// Show deletion final results, logging entries that could not be removed
+    fn show_delete_results(&mut self, paths: &[PathBuf], summary: &DeleteSummary) {
+        for (path, error) in &summary.errors {
+            termide_logger::error(format!("Deletion error '{}': {}", path.display(), error));
+        }
+        termide_logger::info(format!(
+            "Deleted {} of {} items ({} entries removed{})",
+            summary.deleted,
+            paths.len(),
+            summary.removed,
+            if summary.cancelled { ", cancelled" } else { "" }
+        ));
+
+        let t = i18n::t();
+        let (message, level) = if summary.cancelled {
+            (
+                t.status_delete_cancelled(summary.removed),
+                NotificationLevel::Warning,
+            )
+        } else if let Some((path, error)) = summary.errors.first() {
+            let first = format!("{}: {}", path.display(), error);
+            (
+                t.status_delete_entry_errors(summary.deleted, summary.errors.len(), &first),
+                NotificationLevel::Error,
+            )
+        } else if paths.len() == 1 {
+            (t.status_item_deleted().to_string(), NotificationLevel::Info)
+        } else {
+            (
+                t.status_items_deleted(summary.deleted),
+                NotificationLevel::Info,
+            )
+        };
+        if level == NotificationLevel::Error {
+            self.state.set_error(message.clone());
+        } else {
+            self.state.set_info(message.clone());
+        }
+        self.state.notify(message, level);
+    }
+
+    /// Start a recursive permissions change in background and show
+    /// progress modal
+    pub(in crate::app) fn start_batch_permissions(
+        &mut self,
+        paths: Vec<PathBuf>,
+        change: PermissionsChange,
+    ) {
+        if self.state.batch_permissions.is_some() {
+            return;
+        }
+        termide_logger::info(format!("Changing permissions of {} items", paths.len()));
+
+        let cancel_token = CancellationToken::new();
+        let receiver = termide_panel_file_manager::spawn_change_permissions(
+            paths.clone(),
+            change,
+            cancel_token.clone(),
+        );
+        let modal = ProgressModal::new(
+            i18n::t().progress_permissions_title(),
+            cancel_token,
+            FileManager::format_size_static,
+        )
+        .without_sizes();
+        self.state.active_modal = Some(ActiveModal::Progress(Box::new(modal)));
+
+        let permissions = BatchPermissions {
+            current: paths.first().cloned().unwrap_or_default(),
+            paths,
+            receiver,
+            total: 0,
+            changed: 0,
+        };
+        update_permissions_modal(&mut self.state.active_modal, &permissions);
+        self.state.batch_permissions = Some(permissions);
+        self.state.needs_redraw = true;
+    }
+
+    /// Check channel for permissions change progress and report the result
+    /// when done
+    pub(in crate::app) fn check_batch_permissions(&mut self) {
+        let Some(permissions) = self.state.batch_permissions.as_mut() else {
+            return;
+        };
+
+        let mut received = false;
+        let mut finished = None;
+        loop {
+            match permissions.receiver.try_recv() {
+                Ok(PermissionsProgressUpdate::Total(total)) => permissions.total = total,
+                Ok(PermissionsProgressUpdate::Changed { count, current }) => {
+                    permissions.changed = count;
+                    permissions.current = current;
+                }
+                Ok(PermissionsProgressUpdate::Finished(report)) => {
+                    finished = Some(report);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished = Some(PermissionsReport {
+                        failures: vec![(
+                            PathBuf::new(),
+                            "permissions worker stopped unexpectedly".to_string(),
+                        )],
+                        ..PermissionsReport::default()
+                    });
+                    break;
+                }
+            }
+            received = true;
+        }
+
+        if received || finished.is_some() {
+            self.state.needs_redraw = true;
+        }
+
+        let Some(report) = finished else {
+            if let Some(permissions) = self.state.batch_permissions.as_ref().filter(|_| received) {
+                update_permissions_modal(&mut self.state.active_modal, permissions);
+            }
+            return;
+        };
+        let Some(permissions) = self.state.batch_permissions.take() else {
+            return;
+        };
+
+        if matches!(self.state.active_modal, Some(ActiveModal::Progress(_))) {
+            self.state.close_modal();
+        }
+        termide_logger::info(format!(
+            "Permissions changed for {} of {} items{}",
+            report.changed,
+            report.total(),
+            if report.cancelled { ", cancelled" } else { "" }
+        ));
+        self.show_permissions_results(&report);
+
+        // Reload panels so the read-only marks show the new permissions
+        let mut parents: Vec<PathBuf> = permissions
+            .paths
+            .iter()
+            .filter_map(|path| path.parent().map(PathBuf::from))
+            .collect();
+        parents.dedup();
+        for parent in parents {
+            self.refresh_fm_panels(&parent);
         }
     }
 
@@ -476,3 +831,36 @@ impl App {
         Ok(())
     }
 }
+
+/// Refresh progress modal (if open) from transfer state
+fn update_progress_modal(active_modal: &mut Option<ActiveModal>, transfer: &BatchTransfer) {
+    if let Some(ActiveModal::Progress(modal)) = active_modal {
+        let operation = &transfer.operation;
+        modal.set_current_file(transfer.item_name.as_str());
+        modal.set_progress(
+            operation.current_index,
+            operation.total_count(),
+            operation.bytes_done,
+            operation.bytes_total,
+        );
+    }
+}
+
+/// Refresh progress modal (if open) from deletion state
+fn update_delete_modal(active_modal: &mut Option<ActiveModal>, delete: &BatchDelete) {
+    if let Some(ActiveModal::Progress(modal)) = active_modal {
+        modal.set_current_file(delete.current.display().to_string());
+        modal.set_progress(delete.removed, delete.total, 0, None);
+    }
+}
+
+/// Refresh progress modal (if open) from permissions change state
+fn update_permissions_modal(
+    active_modal: &mut Option<ActiveModal>,
+    permissions: &BatchPermissions,
+) {
+    if let Some(ActiveModal::Progress(modal)) = active_modal {
+        modal.set_current_file(permissions.current.display().to_string());
+        modal.set_progress(permissions.changed, permissions.total, 0, None);
+    }
+}
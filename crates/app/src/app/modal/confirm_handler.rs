@@ -7,94 +7,102 @@ use anyhow::Result;
 use std::path::PathBuf;
 
 use super::super::App;
+use crate::state::{ActiveModal, PendingAction};
 use crate::PanelExt;
 use termide_i18n as i18n;
+use termide_modal::ConfirmModal;
+use termide_state::{PermissionsChange, PermissionsReport};
 use termide_ui::path_utils;
 
 impl App {
-    /// Handle deletion of files/directories
+    /// Handle deletion of files/directories (permanently or to trash)
+    ///
+    /// Permanent deletion runs in background (see `start_batch_delete`),
+    /// moving to trash is done here.
     pub(in crate::app) fn handle_delete_path(
         &mut self,
         _panel_index: usize, // obsolete with LayoutManager
         paths: Vec<PathBuf>,
+        to_trash: bool,
         value: Box<dyn std::any::Any>,
     ) -> Result<()> {
-        if let Some(confirmed) = value.downcast_ref::<bool>() {
-            if *confirmed {
-                // Get active FileManager and delete files/directories
-                let (success_count, error_count, total_count) = {
-                    if let Some(panel) = self.layout_manager.active_panel_mut() {
-                        if let Some(fm) = panel.as_file_manager_mut() {
-                            let mut success_count = 0;
-                            let mut error_count = 0;
-                            let total_count = paths.len();
-
-                            // Delete each file/directory
-                            for path in &paths {
-                                let item_name = path_utils::get_file_name_str(path);
-                                let is_dir = path.is_dir();
+        if value.downcast_ref::<bool>() != Some(&true) {
+            return Ok(());
+        }
+        if !to_trash {
+            self.start_batch_delete(paths);
+            return Ok(());
+        }
+
+        // Get active FileManager and move files/directories to trash
+        let (success_count, error_count, total_count) = {
+            if let Some(panel) = self.layout_manager.active_panel_mut() {
+                if let Some(fm) = panel.as_file_manager_mut() {
+                    let mut success_count = 0;
+                    let mut error_count = 0;
+                    let total_count = paths.len();
 
+                    for path in &paths {
+                        let item_name = path_utils::get_file_name_str(path);
+                        let is_dir = path.is_dir();
+
+                        termide_logger::info(format!(
+                            "Attempting to trash {}: {}",
+                            if is_dir { "directory" } else { "file" },
+                            item_name
+                        ));
+
+                        match fm.trash_path(path.clone()) {
+                            Ok(_) => {
                                 termide_logger::info(format!(
-                                    "Attempting to delete {}: {}",
-                                    if is_dir { "directory" } else { "file" },
+                                    "{} moved to trash: {}",
+                                    if is_dir { "Directory" } else { "File" },
                                     item_name
                                 ));
-
-                                match fm.delete_path(path.clone()) {
-                                    Ok(_) => {
-                                        termide_logger::info(format!(
-                                            "{} deleted: {}",
-                                            if is_dir { "Directory" } else { "File" },
-                                            item_name
-                                        ));
-                                        success_count += 1;
-                                    }
-                                    Err(e) => {
-                                        termide_logger::error(format!(
-                                            "Deletion error '{}': {}",
-                                            item_name, e
-                                        ));
-                                        error_count += 1;
-                                    }
-                                }
+                                success_count += 1;
                             }
-
-                            // Clear selection after successful deletion
-                            if success_count > 0 {
-                                fm.clear_selection();
+                            Err(e) => {
+                                termide_logger::error(format!(
+                                    "Deletion error '{}': {}",
+                                    item_name, e
+                                ));
+                                error_count += 1;
                             }
-
-                            // Refresh directory contents
-                            let _ = fm.load_directory();
-
-                            (success_count, error_count, total_count)
-                        } else {
-                            termide_logger::error(
-                                "FileManager panel could not be accessed".to_string(),
-                            );
-                            (0, 0, 0)
                         }
-                    } else {
-                        termide_logger::error("FileManager not found".to_string());
-                        (0, 0, 0)
                     }
-                };
-
-                // Show final message (now fm_panel is dropped, can access self.state)
-                let t = i18n::t();
-                if total_count == 1 {
-                    if success_count == 1 {
-                        self.state.set_info(t.status_item_deleted().to_string());
-                    } else {
-                        self.state.set_error(t.status_error_delete().to_string());
+
+                    // Clear selection after successful deletion
+                    if success_count > 0 {
+                        fm.clear_selection();
                     }
-                } else if error_count == 0 {
-                    self.state.set_info(t.status_items_deleted(success_count));
+
+                    // Refresh directory contents
+                    let _ = fm.load_directory();
+
+                    (success_count, error_count, total_count)
                 } else {
-                    self.state
-                        .set_info(t.status_items_deleted_with_errors(success_count, error_count));
+                    termide_logger::error("FileManager panel could not be accessed".to_string());
+                    (0, 0, 0)
                 }
+            } else {
+                termide_logger::error("FileManager not found".to_string());
+                (0, 0, 0)
             }
+        };
+
+        // Show final message (now fm_panel is dropped, can access self.state)
+        let t = i18n::t();
+        if total_count == 1 {
+            if success_count == 1 {
+                self.state.set_info(t.status_item_trashed().to_string());
+            } else {
+                self.state.set_error(t.status_error_delete().to_string());
+            }
+        } else if error_count == 0 {
+            self.state.set_info(t.status_items_trashed(success_count));
+        } else {
+            self.state
+                .set_info(t.status_items_deleted_with_errors(success_count, error_count));
         }
         Ok(())
     }
@@ -117,4 +125,92 @@ impl App {
         }
         Ok(())
     }
+
+    /// Handle the permissions dialog: recursive changes are confirmed first
+    pub(in crate::app) fn handle_change_permissions(
+        &mut self,
+        paths: Vec<PathBuf>,
+        value: Box<dyn std::any::Any>,
+    ) {
+        let Ok(change) = value.downcast::<PermissionsChange>() else {
+            return;
+        };
+        if change.is_empty() {
+            return;
+        }
+        if change.recursive {
+            let directories = paths.iter().filter(|path| path.is_dir()).count();
+            let t = i18n::t();
+            let modal = ConfirmModal::new(
+                t.permissions_recursive_title(),
+                t.permissions_recursive_confirm(directories),
+            );
+            let action = PendingAction::ChangePermissionsRecursively {
+                panel_index: 0,
+                paths,
+                change: *change,
+            };
+            self.state
+                .set_pending_action(action, ActiveModal::Confirm(Box::new(modal)));
+            return;
+        }
+        self.apply_permissions(&paths, &change);
+    }
+
+    /// Handle confirmation of a recursive permissions change, which runs
+    /// in background (see `start_batch_permissions`)
+    pub(in crate::app) fn handle_change_permissions_recursively(
+        &mut self,
+        paths: Vec<PathBuf>,
+        change: PermissionsChange,
+        value: Box<dyn std::any::Any>,
+    ) {
+        if value.downcast_ref::<bool>() == Some(&true) {
+            self.start_batch_permissions(paths, change);
+        }
+    }
+
+    /// Apply a permissions change to the selected items in the active file
+    /// manager and report the outcome
+    fn apply_permissions(&mut self, paths: &[PathBuf], change: &PermissionsChange) {
+        let Some(fm) = self
+            .layout_manager
+            .active_panel_mut()
+            .and_then(|panel| panel.as_file_manager_mut())
+        else {
+            termide_logger::error("FileManager not found".to_string());
+            return;
+        };
+        let report = fm.change_permissions(paths, change);
+        self.show_permissions_results(&report);
+    }
+
+    /// Show the outcome of a permissions change, logging the failures
+    pub(in crate::app) fn show_permissions_results(&mut self, report: &PermissionsReport) {
+        let t = i18n::t();
+        if report.cancelled {
+            self.state
+                .set_info(t.status_permissions_cancelled(report.changed));
+            return;
+        }
+        match report.failures.first() {
+            None => self
+                .state
+                .set_info(t.status_permissions_changed(report.changed)),
+            Some((path, error)) => {
+                termide_logger::error(format!(
+                    "Permissions not changed for {} items, first {}: {}",
+                    report.failures.len(),
+                    path.display(),
+                    error
+                ));
+                self.state.set_error(t.status_permissions_failed(
+                    report.failures.len(),
+                    report.total(),
+                    path_utils::get_file_name_str(path),
+                    error,
+                ));
+            }
+        }
+    }
 }
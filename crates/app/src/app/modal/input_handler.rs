@@ -9,6 +9,8 @@ use std::path::PathBuf;
 use super::super::App;
 use crate::PanelExt;
 use termide_i18n as i18n;
+use termide_modal::SymlinkModalResult;
+use termide_ui::path_utils;
 
 impl App {
     /// Handle file creation
@@ -104,6 +106,87 @@ impl App {
         Ok(())
     }
 
+    /// Handle symlink creation
+    pub(in crate::app) fn handle_create_symlink(
+        &mut self,
+        _panel_index: usize, // obsolete with LayoutManager
+        target: PathBuf,
+        value: Box<dyn std::any::Any>,
+    ) -> Result<()> {
+        if let Some(result) = value.downcast_ref::<SymlinkModalResult>() {
+            let t = i18n::t();
+            // Get active FileManager and create symlink
+            let result = if let Some(panel) = self.layout_manager.active_panel_mut() {
+                if let Some(fm) = panel.as_file_manager_mut() {
+                    let result = fm.create_symlink(
+                        target.clone(),
+                        PathBuf::from(&result.link_path),
+                        result.relative,
+                    );
+                    if let Ok(link) = &result {
+                        termide_logger::info(format!(
+                            "Symlink created: {} -> {}",
+                            link.display(),
+                            target.display()
+                        ));
+                    }
+                    Some(result)
+                } else {
+                    termide_logger::error("FileManager panel could not be accessed".to_string());
+                    None
+                }
+            } else {
+                termide_logger::error("FileManager not found".to_string());
+                None
+            };
+
+            // Update status after FM borrow is dropped
+            if let Some(result) = result {
+                match result {
+                    Ok(link) => {
+                        self.state.set_info(
+                            t.status_symlink_created(path_utils::get_file_name_str(&link)),
+                        );
+                    }
+                    Err(e) => {
+                        termide_logger::error(format!(
+                            "Symlink creation error '{}': {}",
+                            target.display(),
+                            e
+                        ));
+                        self.state
+                            .set_error(t.status_error_create_symlink(&e.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle the directory to compare a directory with (relative to the
+    /// directory containing it)
+    pub(in crate::app) fn handle_compare_directory(
+        &mut self,
+        left: PathBuf,
+        value: Box<dyn std::any::Any>,
+    ) {
+        let Some(input) = value.downcast_ref::<String>() else {
+            return;
+        };
+        let right = PathBuf::from(input.trim());
+        let right = match left.parent() {
+            Some(parent) if right.is_relative() => parent.join(right),
+            _ => right,
+        };
+        if !right.is_dir() {
+            let t = i18n::t();
+            self.state
+                .set_error(t.compare_not_directory(&right.display().to_string()));
+            return;
+        }
+        self.event_compare_directories(left, right);
+    }
+
     /// Handle saving file with new name
     pub(in crate::app) fn handle_save_file_as(
         &mut self,
@@ -118,9 +201,10 @@ impl App {
                 if let Some(editor) = panel.as_editor_mut() {
                     // User enters path - parse it directly
                     let file_path = PathBuf::from(filename);
-                    match editor.save_file_as(file_path) {
+                    match editor.save_file_as(file_path.clone()) {
                         Ok(_) => {
                             termide_logger::info(format!("File saved as: {}", filename));
+                            self.remember_recent(|recent| recent.add_file(&file_path));
                             self.state.set_info(t.status_file_saved(filename));
                         }
                         Err(e) => {
@@ -70,6 +70,10 @@ impl App {
                     termide_logger::info("Selected: Close without saving");
                     self.close_panel_at_index(0); // panel_index is obsolete
                 }
+                2 => {
+                    termide_logger::info("Selected: Review unsaved changes");
+                    self.review_unsaved_changes();
+                }
                 _ => {
                     // Cancel - do nothing
                     termide_logger::info("Selected: Cancel closing");
@@ -79,6 +83,30 @@ impl App {
         Ok(())
     }
 
+    /// Show the unsaved changes of the active editor; the close prompt
+    /// comes back when the diff is closed
+    fn review_unsaved_changes(&mut self) {
+        let Some(panel) = self.layout_manager.active_panel_mut() else {
+            return;
+        };
+        let title = i18n::t().editor_diff_title(&panel.title());
+        let Some(editor) = panel.as_editor_mut() else {
+            return;
+        };
+        match editor.unsaved_diff() {
+            Ok(diff) => {
+                let modal = termide_modal::DiffModal::new(title, &diff);
+                let action = PendingAction::ReviewUnsavedChanges { panel_index: 0 };
+                self.state
+                    .set_pending_action(action, ActiveModal::Diff(Box::new(modal)));
+            }
+            Err(e) => {
+                termide_logger::error(format!("Diff error: {}", e));
+                self.state.set_error(e.to_string());
+            }
+        }
+    }
+
     /// Handle editor closure with external changes (file changed on disk)
     pub(in crate::app) fn handle_close_editor_external(
         &mut self,
@@ -189,6 +217,61 @@ impl App {
         Ok(())
     }
 
+    /// Handle the choice for a file changed on disk under unsaved edits
+    pub(in crate::app) fn handle_resolve_external_change(
+        &mut self,
+        path: PathBuf,
+        value: Box<dyn std::any::Any>,
+    ) {
+        let Some(&choice) = value
+            .downcast_ref::<Vec<usize>>()
+            .and_then(|selected| selected.first())
+        else {
+            // Esc keeps local edits, the title still shows the change
+            return;
+        };
+
+        let t = i18n::t();
+        let Some(editor) = self
+            .layout_manager
+            .iter_all_panels_mut()
+            .filter_map(|panel| panel.as_editor_mut())
+            .find(|editor| editor.file_path() == Some(path.as_path()))
+        else {
+            return;
+        };
+
+        match choice {
+            0 => {
+                termide_logger::info("Selected: Keep local changes over disk version");
+                editor.keep_local_changes();
+            }
+            1 => {
+                termide_logger::info("Selected: Reload changed file from disk");
+                if let Err(e) = editor.reload_from_disk() {
+                    termide_logger::error(format!("Reload error: {}", e));
+                    self.state.set_error(t.status_error_reload(&e.to_string()));
+                } else {
+                    self.state.set_info(t.status_file_reloaded().to_string());
+                }
+            }
+            _ => {
+                termide_logger::info("Selected: Show diff with disk version");
+                match editor.external_diff() {
+                    Ok(diff) => {
+                        let title = t.editor_diff_title(path_utils::get_file_name_str(&path));
+                        let viewer = termide_panel_editor::Editor::from_text(&diff, title);
+                        self.add_panel(Box::new(viewer));
+                    }
+                    Err(e) => {
+                        termide_logger::error(format!("Diff error: {}", e));
+                        self.state.set_error(e.to_string());
+                    }
+                }
+            }
+        }
+    }
+
     /// Handle file overwrite decision
     pub(in crate::app) fn handle_overwrite_decision(
         &mut self,
@@ -289,4 +372,53 @@ impl App {
         }
         Ok(())
     }
+
+    /// Handle restoring items picked from trash
+    pub(in crate::app) fn handle_restore_from_trash(
+        &mut self,
+        _panel_index: usize, // obsolete with LayoutManager
+        entries: Vec<PathBuf>,
+        value: Box<dyn std::any::Any>,
+    ) -> Result<()> {
+        if let Some(selected) = value.downcast_ref::<Vec<usize>>() {
+            let t = i18n::t();
+            let mut restored_count = 0;
+            let mut last_error = None;
+
+            if let Some(fm) = self
+                .layout_manager
+                .active_panel_mut()
+                .and_then(|panel| panel.as_file_manager_mut())
+            {
+                for info_path in selected.iter().filter_map(|&idx| entries.get(idx)) {
+                    match fm.restore_from_trash(info_path.clone()) {
+                        Ok(restored) => {
+                            termide_logger::info(format!(
+                                "Restored from trash: {}",
+                                restored.display()
+                            ));
+                            restored_count += 1;
+                        }
+                        Err(e) => {
+                            termide_logger::error(format!(
+                                "Restore error '{}': {}",
+                                info_path.display(),
+                                e
+                            ));
+                            last_error = Some(e.to_string());
+                        }
+                    }
+                }
+            } else {
+                termide_logger::error("FileManager not found".to_string());
+            }
+
+            // Update status after FM borrow is dropped
+            match last_error {
+                Some(error) => self.state.set_error(t.status_error_restore(&error)),
+                None => self.state.set_info(t.status_items_restored(restored_count)),
+            }
+        }
+        Ok(())
+    }
 }
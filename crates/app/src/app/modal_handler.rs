@@ -4,8 +4,11 @@ use anyhow::Result;
 
 use super::App;
 use crate::state::ActiveModal;
+use termide_app_event::HotkeyAction;
+use termide_i18n as i18n;
 use termide_modal::{
-    Modal, ModalResult, ReplaceAction, ReplaceModalResult, SearchAction, SearchModalResult,
+    BookmarkChoice, Modal, ModalResult, ReplaceAction, ReplaceModalResult, SearchAction,
+    SearchModalResult,
 };
 use termide_ui::path_utils;
 
@@ -40,6 +43,7 @@ impl App {
             let modal_result = match modal {
                 ActiveModal::Confirm(m) => m.handle_key(key)?.map(box_modal_result),
                 ActiveModal::Input(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::MultilineInput(m) => m.handle_key(key)?.map(box_modal_result),
                 ActiveModal::Select(m) => m.handle_key(key)?.map(box_modal_result),
                 ActiveModal::Overwrite(m) => m.handle_key(key)?.map(box_modal_result),
                 ActiveModal::Conflict(m) => m.handle_key(key)?.map(box_modal_result),
@@ -48,6 +52,15 @@ impl App {
                 ActiveModal::EditableSelect(m) => m.handle_key(key)?.map(box_modal_result),
                 ActiveModal::Search(m) => m.handle_key(key)?.map(box_modal_result),
                 ActiveModal::Replace(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Symlink(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Progress(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::CommandPalette(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::FileFinder(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Quit(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Diff(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Permissions(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Notifications(m) => m.handle_key(key)?.map(box_modal_result),
+                ActiveModal::Bookmarks(m) => m.handle_key(key)?.map(box_modal_result),
             };
 
             // If modal window returned result, handle it
@@ -107,6 +120,7 @@ impl App {
                 }
             }
         }
+        self.update_theme_preview();
         Ok(())
     }
 
@@ -132,6 +146,14 @@ impl App {
                     }
                     ModalResult::Cancelled => ModalResult::Cancelled,
                 }),
+                ActiveModal::MultilineInput(m) => {
+                    m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                        ModalResult::Confirmed(value) => {
+                            ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                        }
+                        ModalResult::Cancelled => ModalResult::Cancelled,
+                    })
+                }
                 ActiveModal::Select(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
                     ModalResult::Confirmed(value) => {
                         ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
@@ -184,6 +206,66 @@ impl App {
                     }
                     ModalResult::Cancelled => ModalResult::Cancelled,
                 }),
+                ActiveModal::Symlink(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                    ModalResult::Confirmed(value) => {
+                        ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                    }
+                    ModalResult::Cancelled => ModalResult::Cancelled,
+                }),
+                ActiveModal::Progress(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                    ModalResult::Confirmed(value) => {
+                        ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                    }
+                    ModalResult::Cancelled => ModalResult::Cancelled,
+                }),
+                ActiveModal::CommandPalette(m) => {
+                    m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                        ModalResult::Confirmed(value) => {
+                            ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                        }
+                        ModalResult::Cancelled => ModalResult::Cancelled,
+                    })
+                }
+                ActiveModal::FileFinder(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                    ModalResult::Confirmed(value) => {
+                        ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                    }
+                    ModalResult::Cancelled => ModalResult::Cancelled,
+                }),
+                ActiveModal::Quit(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                    ModalResult::Confirmed(value) => {
+                        ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                    }
+                    ModalResult::Cancelled => ModalResult::Cancelled,
+                }),
+                ActiveModal::Diff(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                    ModalResult::Confirmed(value) => {
+                        ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                    }
+                    ModalResult::Cancelled => ModalResult::Cancelled,
+                }),
+                ActiveModal::Permissions(m) => {
+                    m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                        ModalResult::Confirmed(value) => {
+                            ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                        }
+                        ModalResult::Cancelled => ModalResult::Cancelled,
+                    })
+                }
+                ActiveModal::Notifications(m) => {
+                    m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                        ModalResult::Confirmed(value) => {
+                            ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                        }
+                        ModalResult::Cancelled => ModalResult::Cancelled,
+                    })
+                }
+                ActiveModal::Bookmarks(m) => m.handle_mouse(mouse, modal_area)?.map(|r| match r {
+                    ModalResult::Confirmed(value) => {
+                        ModalResult::Confirmed(Box::new(value) as Box<dyn std::any::Any>)
+                    }
+                    ModalResult::Cancelled => ModalResult::Cancelled,
+                }),
             };
 
             // If modal window returned result, handle it
@@ -206,6 +288,7 @@ impl App {
                 }
             }
         }
+        self.update_theme_preview();
         Ok(())
     }
 
@@ -227,8 +310,23 @@ impl App {
                 } => {
                     self.handle_create_directory(panel_index, directory, value)?;
                 }
+                PendingAction::CreateSymlink {
+                    panel_index,
+                    target,
+                } => {
+                    self.handle_create_symlink(panel_index, target, value)?;
+                }
                 PendingAction::DeletePath { panel_index, paths } => {
-                    self.handle_delete_path(panel_index, paths, value)?;
+                    self.handle_delete_path(panel_index, paths, false, value)?;
+                }
+                PendingAction::TrashPath { panel_index, paths } => {
+                    self.handle_delete_path(panel_index, paths, true, value)?;
+                }
+                PendingAction::RestoreFromTrash {
+                    panel_index,
+                    entries,
+                } => {
+                    self.handle_restore_from_trash(panel_index, entries, value)?;
                 }
                 PendingAction::SaveFileAs {
                     panel_index,
@@ -236,18 +334,33 @@ impl App {
                 } => {
                     self.handle_save_file_as(panel_index, directory, value)?;
                 }
+                PendingAction::CompareDirectory { left } => {
+                    self.handle_compare_directory(left, value);
+                }
                 PendingAction::ClosePanel { panel_index } => {
                     self.handle_close_panel(panel_index, value)?;
                 }
                 PendingAction::CloseEditorWithSave { panel_index } => {
                     self.handle_close_editor_with_save(panel_index, value)?;
                 }
+                PendingAction::ChangePermissions { paths, .. } => {
+                    self.handle_change_permissions(paths, value);
+                }
+                PendingAction::ChangePermissionsRecursively { paths, change, .. } => {
+                    self.handle_change_permissions_recursively(paths, change, value);
+                }
+                PendingAction::ReviewUnsavedChanges { .. } => {
+                    self.show_close_unsaved_prompt();
+                }
                 PendingAction::CloseEditorExternal { panel_index } => {
                     self.handle_close_editor_external(panel_index, value)?;
                 }
                 PendingAction::CloseEditorConflict { panel_index } => {
                     self.handle_close_editor_conflict(panel_index, value)?;
                 }
+                PendingAction::ResolveExternalChange { path } => {
+                    self.handle_resolve_external_change(path, value);
+                }
                 PendingAction::OverwriteDecision {
                     panel_index,
                     source,
@@ -296,10 +409,95 @@ impl App {
                     // called from handle_modal_key/handle_modal_mouse (lines 183-233, 383-434).
                     // No additional processing needed here, similar to how SearchModal works.
                 }
+                PendingAction::SelectSyntax { languages } => {
+                    self.handle_select_syntax(languages, value);
+                }
+                PendingAction::SelectEncoding { encodings } => {
+                    self.handle_select_encoding(encodings, value);
+                }
+                PendingAction::SwitchUndoBranch { branches } => {
+                    self.handle_switch_undo_branch(branches, value);
+                }
+                PendingAction::CorrectSpelling {
+                    line,
+                    column,
+                    word,
+                    suggestions,
+                } => {
+                    self.handle_correct_spelling(line, column, &word, suggestions, value);
+                }
+                PendingAction::CreateMissingPaths { paths } => {
+                    self.handle_create_missing_paths(paths, value)?;
+                }
+                PendingAction::RecoverBuffers { ids } => {
+                    self.handle_recovered_buffers(ids, value);
+                }
+                PendingAction::RecoverBuffer { ids, index } => {
+                    self.handle_recovered_buffer(ids, index, value)?;
+                }
+                PendingAction::OpenDroppedFiles { paths, text } => {
+                    self.handle_open_dropped_files(paths, text, value)?;
+                }
+                PendingAction::GitCommit { repo_root } => {
+                    self.handle_git_commit(&repo_root, value);
+                }
+                PendingAction::RunCommand { commands } => {
+                    let action = value
+                        .downcast_ref::<usize>()
+                        .and_then(|&index| commands.get(index))
+                        .and_then(|name| HotkeyAction::from_name(name));
+                    if let Some(action) = action {
+                        self.run_palette_command(action)?;
+                    }
+                }
+                PendingAction::OpenFoundFile { root } => {
+                    if let Some(path) = value.downcast_ref::<String>() {
+                        self.event_open_file(root.join(path), None, None)?;
+                    }
+                }
+                PendingAction::SetBookmark { path, line, text } => {
+                    if let Some(name) = value.downcast_ref::<String>() {
+                        self.set_bookmark(name, path, line, &text);
+                    }
+                }
+                PendingAction::PickBookmark => {
+                    if let Some(&choice) = value.downcast_ref::<BookmarkChoice>() {
+                        self.handle_bookmark_choice(choice)?;
+                    }
+                }
+                PendingAction::RunTask { names } => {
+                    let name = value
+                        .downcast_ref::<Vec<usize>>()
+                        .and_then(|selected| selected.first())
+                        .and_then(|&index| names.get(index));
+                    if let Some(name) = name {
+                        self.run_task(name.clone());
+                    }
+                }
+                PendingAction::SelectTheme { names, .. } => {
+                    let name = value
+                        .downcast_ref::<Vec<usize>>()
+                        .and_then(|selected| selected.first())
+                        .and_then(|&index| names.get(index));
+                    if let Some(name) = name {
+                        self.select_theme(name);
+                    }
+                }
+                PendingAction::ConfigWizard {
+                    step,
+                    config,
+                    values,
+                    original_theme,
+                } => {
+                    self.handle_config_wizard(step, config, values, original_theme, value);
+                }
                 PendingAction::QuitApplication => {
                     // User confirmed quit - exit application
                     self.state.quit();
                 }
+                PendingAction::QuitWithChanges { panels } => {
+                    self.handle_quit_with_changes(panels, value);
+                }
                 // Navigation actions are handled in key_handler, should not get here
                 PendingAction::NextPanel | PendingAction::PrevPanel => {}
             }
@@ -318,10 +516,119 @@ impl App {
         Ok(())
     }
 
+    /// Handle syntax language picked for the active editor
+    fn handle_select_syntax(&mut self, languages: Vec<String>, value: Box<dyn std::any::Any>) {
+        let Some(selected) = value.downcast_ref::<Vec<usize>>() else {
+            return;
+        };
+        let language = selected.first().and_then(|&index| languages.get(index));
+        if let (Some(language), Some(editor)) = (language, self.active_editor_mut()) {
+            editor.set_syntax_override(language);
+        }
+    }
+
+    /// Replace a misspelled word with the chosen suggestion, or add it to
+    /// the user dictionary
+    fn handle_correct_spelling(
+        &mut self,
+        line: usize,
+        column: usize,
+        word: &str,
+        suggestions: Vec<String>,
+        value: Box<dyn std::any::Any>,
+    ) {
+        let Some(&index) = value
+            .downcast_ref::<Vec<usize>>()
+            .and_then(|selected| selected.first())
+        else {
+            return;
+        };
+        let Some(editor) = self.active_editor_mut() else {
+            return;
+        };
+        let replacement = suggestions.get(index).map(String::as_str);
+        match editor.correct_spelling(line, column, word, replacement) {
+            Ok(()) => {
+                if let Some(message) = editor.take_status_message() {
+                    self.state.set_info(message);
+                }
+            }
+            Err(e) if replacement.is_none() => {
+                let message = i18n::t().editor_spell_add_failed(&e.to_string());
+                self.state.set_error(message);
+            }
+            Err(e) => self.state.set_error(e.to_string()),
+        }
+    }
+
+    /// Reopen or convert the active editor's file in the chosen encoding
+    fn handle_select_encoding(&mut self, encodings: Vec<String>, value: Box<dyn std::any::Any>) {
+        let Some(selected) = value.downcast_ref::<Vec<usize>>() else {
+            return;
+        };
+        let Some(encoding) = selected.first().and_then(|&index| encodings.get(index)) else {
+            return;
+        };
+        let Some(editor) = self.active_editor_mut() else {
+            return;
+        };
+        match editor.set_encoding(encoding) {
+            Ok(()) => {
+                if let Some(message) = editor.take_status_message() {
+                    self.state.set_info(message);
+                }
+            }
+            Err(e) => {
+                let message = i18n::t().status_error_reload(&e.to_string());
+                self.state.set_error(message);
+            }
+        }
+    }
+
+    /// Switch the active editor to the picked undo branch
+    fn handle_switch_undo_branch(&mut self, branches: Vec<usize>, value: Box<dyn std::any::Any>) {
+        let Some(selected) = value.downcast_ref::<Vec<usize>>() else {
+            return;
+        };
+        let Some(&id) = selected.first().and_then(|&index| branches.get(index)) else {
+            return;
+        };
+        let Some(editor) = self.active_editor_mut() else {
+            return;
+        };
+        if let Err(e) = editor.switch_undo_branch(id) {
+            self.state.set_error(e.to_string());
+        }
+    }
+
+    /// Commit the staged changes with the entered message
+    fn handle_git_commit(&mut self, repo_root: &std::path::Path, value: Box<dyn std::any::Any>) {
+        use termide_core::PanelCommand;
+
+        let Some(message) = value.downcast_ref::<String>() else {
+            return;
+        };
+        let t = i18n::t();
+        match termide_git::commit(repo_root, message) {
+            Ok(summary) => self.state.set_info(t.git_committed(&summary)),
+            Err(e) => self.state.set_error(t.git_commit_failed(&e.to_string())),
+        }
+
+        // Show the result right away instead of after the watcher's debounce
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            panel.handle_command(PanelCommand::OnGitUpdate {
+                repo_paths: &[repo_root],
+            });
+        }
+        self.state.needs_redraw = true;
+    }
+
     /// Handle replace action from ReplaceModal
     fn handle_replace_action(&mut self, replace_result: &ReplaceModalResult) -> Result<()> {
         // Get active editor
         if let Some(editor) = self.active_editor_mut() {
+            let options = replace_result.options;
+            editor.set_search_options(options.whole_word, options.in_selection);
             match replace_result.action {
                 ReplaceAction::Search => {
                     // Perform new search/replace (or update existing)
@@ -360,6 +667,9 @@ impl App {
                     );
                     // Replace all matches (now uses updated replace_with)
                     editor.replace_all()?;
+                    if let Some(message) = editor.take_status_message() {
+                        self.state.set_info(message);
+                    }
                 }
             }
         }
@@ -370,10 +680,12 @@ impl App {
     fn handle_search_action(&mut self, search_result: &SearchModalResult) -> Result<()> {
         // Get active editor
         if let Some(editor) = self.active_editor_mut() {
+            let options = search_result.options;
+            editor.set_search_options(options.whole_word, options.in_selection);
             match search_result.action {
                 SearchAction::Search => {
-                    // Perform new search (or update existing)
-                    editor.start_search(search_result.query.clone(), false);
+                    // Search as the query is typed, from where the search started
+                    editor.search_as_you_type(search_result.query.clone());
                 }
                 SearchAction::Next => {
                     // Navigate to next match
@@ -384,11 +696,15 @@ impl App {
                     editor.search_prev();
                 }
                 SearchAction::CloseWithSelection => {
-                    // Just ensure search is active (will be handled by modal close logic)
-                    // Selection is already set by editor methods
+                    // Keep the match selected by the editor
+                    editor.confirm_search();
                 }
             }
         }
+        // Queries navigated with or confirmed go to the history
+        if search_result.action != SearchAction::Search {
+            self.state.search_history.push(&search_result.query);
+        }
         Ok(())
     }
 
@@ -404,21 +720,25 @@ impl App {
                     return SearchReplaceResult::Close;
                 }
 
-                // Get match info from active editor
-                let match_info = self
+                // Get match info and options in effect from active editor
+                let (match_info, options) = self
                     .active_editor_mut()
-                    .and_then(|editor| editor.get_search_match_info());
+                    .map(|editor| (editor.get_search_match_info(), editor.search_options()))
+                    .unzip();
 
                 // Check if we should close modal
                 if matches!(search_result.action, SearchAction::CloseWithSelection) {
                     return SearchReplaceResult::Close;
                 }
 
-                // Update match info in modal for other actions
-                if let Some((current, total)) = match_info {
-                    if let Some(ActiveModal::Search(search_modal)) = &mut self.state.active_modal {
+                // Update match info and options in modal for other actions
+                if let Some(ActiveModal::Search(search_modal)) = &mut self.state.active_modal {
+                    if let Some((current, total)) = match_info.flatten() {
                         search_modal.set_match_info(current, total);
                     }
+                    if let Some((whole_word, in_selection)) = options {
+                        search_modal.set_options(whole_word, in_selection);
+                    }
                 }
 
                 return SearchReplaceResult::KeepOpen;
@@ -441,22 +761,25 @@ impl App {
                     return SearchReplaceResult::Close;
                 }
 
-                // Get match info from active editor
-                let match_info = self
+                // Get match info and options in effect from active editor
+                let (match_info, options) = self
                     .active_editor_mut()
-                    .and_then(|editor| editor.get_search_match_info());
+                    .map(|editor| (editor.get_search_match_info(), editor.search_options()))
+                    .unzip();
 
                 // Check if we should close modal
                 if matches!(replace_result.action, ReplaceAction::ReplaceAll) {
                     return SearchReplaceResult::Close;
                 }
 
-                // Update match info in modal for other actions
-                if let Some((current, total)) = match_info {
-                    if let Some(ActiveModal::Replace(replace_modal)) = &mut self.state.active_modal
-                    {
+                // Update match info and options in modal for other actions
+                if let Some(ActiveModal::Replace(replace_modal)) = &mut self.state.active_modal {
+                    if let Some((current, total)) = match_info.flatten() {
                         replace_modal.set_match_info(current, total);
                     }
+                    if let Some((whole_word, in_selection)) = options {
+                        replace_modal.set_options(whole_word, in_selection);
+                    }
                 }
 
                 return SearchReplaceResult::KeepOpen;
@@ -483,8 +806,9 @@ impl App {
                 }
                 SearchReplaceResult::Cancelled => {
                     self.state.close_modal();
+                    // The cursor goes back to where the search started
                     if let Some(editor) = self.active_editor_mut() {
-                        editor.close_search();
+                        editor.cancel_search();
                     }
                     return Some(());
                 }
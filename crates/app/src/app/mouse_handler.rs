@@ -2,10 +2,12 @@
 
 use anyhow::Result;
 use crossterm::event::{MouseButton, MouseEventKind};
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Position, Rect};
 
 use super::App;
-use termide_ui_render::dropdown::{get_help_items, get_tools_items};
+use termide_layout::TabHit;
+use termide_ui_render::menu::{menu_item_x, MENU_ITEM_COUNT, PANEL_MENU_INDEX};
+use termide_ui_render::Dropdown;
 
 impl App {
     /// Handle mouse event
@@ -28,28 +30,95 @@ impl App {
             return Ok(());
         }
 
-        // Click on dropdown when menu is open
+        // Click on dropdown when menu is open, elsewhere closes the menu
         if self.state.ui.menu_open && matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left))
         {
-            self.handle_dropdown_click(mouse.column, mouse.row)?;
+            if !self.handle_dropdown_click(mouse.column, mouse.row)? {
+                self.state.close_menu();
+            }
             return Ok(());
         }
 
-        // If menu is open, close it on click outside menu
-        if self.state.ui.menu_open && matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left))
-        {
-            self.state.close_menu();
+        // A border between groups follows the mouse until released
+        if let Some(border) = self.layout_manager.resizing_border {
+            match mouse.kind {
+                MouseEventKind::Drag(MouseButton::Left) => {
+                    let terminal_width = self.state.terminal.width;
+                    self.layout_manager
+                        .resize_border(border, mouse.column, terminal_width);
+                    return Ok(());
+                }
+                MouseEventKind::Up(MouseButton::Left) => {
+                    self.layout_manager.resizing_border = None;
+                    self.auto_save_session();
+                    return Ok(());
+                }
+                _ => self.layout_manager.resizing_border = None,
+            }
+        }
+
+        // Highlight the border under the mouse as a resize handle
+        let border = self
+            .layout_manager
+            .border_at(self.main_area(), mouse.column, mouse.row);
+        self.layout_manager.hovered_border = border;
+        if let (Some(border), MouseEventKind::Down(MouseButton::Left)) = (border, mouse.kind) {
+            self.layout_manager.resizing_border = Some(border);
             return Ok(());
         }
 
+        // A panel dragged by its title bar follows the mouse until released
+        if self.layout_manager.drag.is_some() {
+            match mouse.kind {
+                MouseEventKind::Drag(MouseButton::Left) => {
+                    let area = self.main_area();
+                    self.layout_manager
+                        .update_drag(area, mouse.column, mouse.row);
+                    return Ok(());
+                }
+                MouseEventKind::Up(MouseButton::Left) => {
+                    let terminal_width = self.state.terminal.width;
+                    if self.layout_manager.finish_drag(terminal_width)? {
+                        self.auto_save_session();
+                    }
+                    return Ok(());
+                }
+                _ => self.layout_manager.drag = None,
+            }
+        }
+
+        // Middle click on a title bar or tab closes that panel
+        if matches!(mouse.kind, MouseEventKind::Down(MouseButton::Middle)) {
+            if let Some((group_idx, panel_idx)) =
+                self.layout_manager
+                    .header_at(self.main_area(), mouse.column, mouse.row)
+            {
+                termide_logger::debug("Panel title middle-clicked");
+                self.close_panel_at(group_idx, panel_idx)?;
+                return Ok(());
+            }
+        }
+
         // Check click on panel [X] button
         if matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left)) {
+            if self.handle_tab_click(mouse.column, mouse.row)? {
+                return Ok(());
+            }
             if self.handle_panel_close_click(mouse.column, mouse.row)? {
                 return Ok(());
             }
 
             // Check click on panel to switch focus
             self.handle_panel_focus_click(mouse.column, mouse.row)?;
+
+            // Pressing on the rest of a title bar starts dragging the panel
+            if let Some((group_idx, panel_idx)) =
+                self.layout_manager
+                    .header_at(self.main_area(), mouse.column, mouse.row)
+            {
+                self.layout_manager.start_drag(group_idx, panel_idx);
+                return Ok(());
+            }
         }
 
         // For scroll - forward to panel under cursor (doesn't require focus)
@@ -144,14 +213,38 @@ impl App {
         }
     }
 
+    /// Handle click on a tab of a group shown as tabs: its [X] closes the
+    /// panel, the rest switches to it and starts dragging it.
+    /// Returns true if a tab was clicked
+    fn handle_tab_click(&mut self, click_x: u16, click_y: u16) -> Result<bool> {
+        let Some((group_idx, hit)) = self
+            .layout_manager
+            .tab_at(self.main_area(), click_x, click_y)
+        else {
+            return Ok(false);
+        };
+        match hit {
+            TabHit::Close(panel_idx) => {
+                termide_logger::debug("Tab close button [X] clicked");
+                self.close_panel_at(group_idx, panel_idx)?;
+            }
+            TabHit::Select(panel_idx) => {
+                self.focus_panel_at(group_idx, panel_idx);
+                self.layout_manager.start_drag(group_idx, panel_idx);
+            }
+        }
+        Ok(true)
+    }
+
     /// Handle click on panel [X] button or [▶]/[▼] expand/collapse button
     /// Returns true if a button was clicked
     fn handle_panel_close_click(&mut self, click_x: u16, click_y: u16) -> Result<bool> {
         let panel_rects = self.calculate_panel_rects();
 
         for (group_idx, panel_idx, rect, is_expanded) in panel_rects {
-            // Check if click is on this panel's top line
-            if click_y != rect.y {
+            // Check if click is on this panel's top line (tabs have their
+            // own buttons)
+            if click_y != rect.y || self.layout_manager.panel_groups[group_idx].tabs {
                 continue;
             }
 
@@ -170,14 +263,7 @@ impl App {
             if (1..=3).contains(&relative_x) {
                 // Click on [X] button - close panel with confirmation if needed
                 termide_logger::debug("Panel close button [X] clicked");
-                // First, activate the clicked panel
-                if let Some(group) = self.layout_manager.panel_groups.get_mut(group_idx) {
-                    group.set_expanded(panel_idx);
-                }
-                self.layout_manager.focus = group_idx;
-
-                // Now use the same close logic as keyboard shortcut (with confirmation)
-                self.handle_close_panel_request(0)?;
+                self.close_panel_at(group_idx, panel_idx)?;
                 return Ok(true);
             } else if (4..=6).contains(&relative_x) {
                 // Click on [▶]/[▼] button - expand/collapse panel
@@ -212,10 +298,7 @@ impl App {
                 && click_y < rect.y + rect.height
             {
                 // Click on a panel group - make it active
-                self.layout_manager.focus = group_idx;
-                if let Some(group) = self.layout_manager.panel_groups.get_mut(group_idx) {
-                    group.set_expanded(panel_idx);
-                }
+                self.focus_panel_at(group_idx, panel_idx);
                 return Ok(());
             }
         }
@@ -223,126 +306,78 @@ impl App {
         Ok(())
     }
 
-    /// Handle click on menu
-    fn handle_menu_click(&mut self, x: u16) -> Result<()> {
-        let mut current_x = 1_u16;
+    /// Expand a panel and focus its group
+    fn focus_panel_at(&mut self, group_idx: usize, panel_idx: usize) {
+        if let Some(group) = self.layout_manager.panel_groups.get_mut(group_idx) {
+            group.set_expanded(panel_idx);
+        }
+        self.layout_manager.focus = group_idx;
+    }
 
-        // Get menu items with translations
-        let menu_items = termide_ui_render::menu::get_menu_items();
+    /// Close a panel from its title bar or tab, focused or not, with the
+    /// same confirmation as the keyboard shortcut
+    fn close_panel_at(&mut self, group_idx: usize, panel_idx: usize) -> Result<()> {
+        self.focus_panel_at(group_idx, panel_idx);
+        self.handle_close_panel_request(0)
+    }
 
-        for (i, item) in menu_items.iter().enumerate() {
-            let item_width = item.len() as u16;
-            if x >= current_x && x < current_x + item_width {
-                // Set selected item and immediately execute action
-                self.state.ui.selected_menu_item = Some(i);
+    /// Handle click on menu
+    fn handle_menu_click(&mut self, x: u16) -> Result<()> {
+        for index in 0..MENU_ITEM_COUNT {
+            // Items are two columns apart
+            if x < menu_item_x(index) || x + 2 >= menu_item_x(index + 1) {
+                continue;
+            }
+            // The Panel menu opens its dropdown, other items run right away
+            if index == PANEL_MENU_INDEX {
+                self.state.open_menu(Some(index));
+                self.select_first_panel_menu_item();
+            } else {
+                self.state.ui.selected_menu_item = Some(index);
                 self.execute_menu_action()?;
-                return Ok(());
             }
-            current_x += item_width + 2; // +2 for spaces
+            break;
         }
-
         Ok(())
     }
 
-    /// Handle click on dropdown
-    fn handle_dropdown_click(&mut self, _x: u16, y: u16) -> Result<()> {
-        if let Some(menu_index) = self.state.ui.selected_menu_item {
-            // Dropdown starts from row 1
-            if y >= 2 {
-                // -2 for menu row and top border of dropdown
-                let item_index = (y - 2) as usize;
-
-                let item_count = match menu_index {
-                    0 => get_tools_items().len(),
-                    1 => get_help_items().len(),
-                    _ => 0,
-                };
-
-                if item_index < item_count {
-                    self.state.ui.selected_dropdown_item = item_index;
-                    self.execute_menu_action()?;
-                }
-            }
+    /// Handle click on the Panel menu dropdown; false when the click is
+    /// outside of it
+    fn handle_dropdown_click(&mut self, x: u16, y: u16) -> Result<bool> {
+        if self.state.ui.selected_menu_item != Some(PANEL_MENU_INDEX) {
+            return Ok(false);
         }
-
-        Ok(())
+        let Some(items) = self
+            .layout_manager
+            .active_panel_mut()
+            .map(|panel| panel.menu_items())
+        else {
+            return Ok(false);
+        };
+        let screen = Rect::new(0, 0, self.state.terminal.width, self.state.terminal.height);
+        let dropdown = Dropdown::new(&items, 0, menu_item_x(PANEL_MENU_INDEX), 1);
+        if !dropdown.area(screen).contains(Position { x, y }) {
+            return Ok(false);
+        }
+        if let Some(index) = dropdown.item_at(screen, y) {
+            self.run_panel_menu_item(index)?;
+        }
+        Ok(true)
     }
 
-    /// Calculate panel rectangles for mouse hit testing
-    /// Returns Vec<(group_idx, panel_idx, rect, is_expanded)>
-    fn calculate_panel_rects(&self) -> Vec<(usize, usize, Rect, bool)> {
-        let mut result = Vec::new();
-
-        let width = self.state.terminal.width;
-        let height = self.state.terminal.height;
-
-        // Main area: from row 1 to height-2 (excluding menu and status bar)
-        let main_area = Rect {
+    /// Main area below the menu and above the status bar
+    fn main_area(&self) -> Rect {
+        Rect {
             x: 0,
             y: 1,
-            width,
-            height: height.saturating_sub(2),
-        };
-
-        // Calculate group areas
-        if !self.layout_manager.panel_groups.is_empty() {
-            let groups_area = main_area;
-
-            // Calculate horizontal constraints for groups (using widths)
-            // Группы могут иметь фиксированную ширину или auto-width
-            let group_constraints: Vec<Constraint> = self
-                .layout_manager
-                .panel_groups
-                .iter()
-                .map(|g| {
-                    let width = g.width.unwrap_or(groups_area.width);
-                    Constraint::Length(width.max(20))
-                })
-                .collect();
-
-            let group_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(group_constraints)
-                .split(groups_area);
-
-            // Process each group
-            for (group_idx, group) in self.layout_manager.panel_groups.iter().enumerate() {
-                if group.is_empty() || group_chunks[group_idx].height == 0 {
-                    continue;
-                }
-
-                let group_area = group_chunks[group_idx];
-                let expanded_idx = group.expanded_index();
-
-                // Build vertical constraints for panels in group
-                let vertical_constraints: Vec<Constraint> = (0..group.len())
-                    .map(|i| {
-                        if i == expanded_idx {
-                            Constraint::Min(0) // Expanded panel
-                        } else {
-                            Constraint::Length(1) // Collapsed panel (1 line)
-                        }
-                    })
-                    .collect();
-
-                let vertical_chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints(vertical_constraints)
-                    .split(group_area);
-
-                // Add each panel's rect to results
-                for panel_idx in 0..group.len() {
-                    let is_expanded = panel_idx == expanded_idx;
-                    result.push((
-                        group_idx,
-                        panel_idx,
-                        vertical_chunks[panel_idx],
-                        is_expanded,
-                    ));
-                }
-            }
+            width: self.state.terminal.width,
+            height: self.state.terminal.height.saturating_sub(2),
         }
+    }
 
-        result
+    /// Calculate panel rectangles for mouse hit testing
+    /// Returns Vec<(group_idx, panel_idx, rect, is_expanded)>
+    fn calculate_panel_rects(&self) -> Vec<(usize, usize, Rect, bool)> {
+        self.layout_manager.panel_rects(self.main_area())
     }
 }
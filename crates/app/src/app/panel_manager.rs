@@ -8,7 +8,9 @@ use std::path::PathBuf;
 use super::App;
 use crate::PanelExt;
 use termide_core::Panel;
-use termide_panel_misc::WelcomePanel as Welcome;
+use termide_layout::LayoutManager;
+use termide_panel_file_manager::FileManager;
+use termide_panel_misc::{LogViewerPanel, WelcomePanel as Welcome};
 
 impl App {
     /// Close panel by index and switch focus to next visible panel
@@ -37,16 +39,21 @@ impl App {
         }
 
         // Before closing, unwatch filesystem if this is a FileManager panel
+        // or a log viewer following a file
         if let Some(panel) = self.layout_manager.active_panel_mut() {
-            if let Some(fm) = panel.as_file_manager_mut() {
-                // Unwatch the filesystem root for this FileManager
-                if let Some(watched_root) = fm.take_watched_root() {
-                    if let Some(watcher) = &mut self.state.fs_watcher {
-                        if termide_git::find_repo_root(&watched_root).is_some() {
-                            watcher.unwatch_repository(&watched_root);
-                        } else {
-                            watcher.unwatch_directory(&watched_root);
-                        }
+            let watched_root = match panel.as_file_manager_mut() {
+                Some(fm) => fm.take_watched_root(),
+                None => panel
+                    .as_any_mut()
+                    .downcast_mut::<LogViewerPanel>()
+                    .and_then(LogViewerPanel::take_watched_root),
+            };
+            if let Some(watched_root) = watched_root {
+                if let Some(watcher) = &mut self.state.fs_watcher {
+                    if termide_git::find_repo_root(&watched_root).is_some() {
+                        watcher.unwatch_repository(&watched_root);
+                    } else {
+                        watcher.unwatch_directory(&watched_root);
                     }
                 }
             }
@@ -74,7 +81,7 @@ impl App {
         let should_add_welcome = self.layout_manager.panel_groups.is_empty();
 
         if should_add_welcome {
-            let welcome = Welcome::new();
+            let welcome = self.new_welcome_panel();
             self.add_panel(Box::new(welcome));
         }
 
@@ -82,6 +89,42 @@ impl App {
         // No need to manually update active_panel index
     }
 
+    /// Create a Welcome panel listing recent files and projects.
+    ///
+    /// Deleted entries are shown greyed out this time and dropped from the
+    /// stored lists.
+    pub(super) fn new_welcome_panel(&self) -> Welcome {
+        let mut recent = termide_session::RecentPaths::load();
+        let welcome = Welcome::with_recent(recent.files.clone(), recent.projects.clone());
+        if recent.prune_missing() {
+            if let Err(e) = recent.save() {
+                termide_logger::warn(format!("Failed to save recent files: {}", e));
+            }
+        }
+        welcome
+    }
+
+    /// Move a path to the top of the stored recent files or projects
+    pub(super) fn remember_recent(&self, update: impl FnOnce(&mut termide_session::RecentPaths)) {
+        // Reload first: other instances update the same lists
+        let mut recent = termide_session::RecentPaths::load();
+        update(&mut recent);
+        if let Err(e) = recent.save() {
+            termide_logger::warn(format!("Failed to save recent files: {}", e));
+        }
+    }
+
+    /// Current directory of the other file manager of a two-panel layout
+    pub(super) fn other_file_manager_dir(&self) -> Option<PathBuf> {
+        other_file_manager_dir(&self.layout_manager)
+    }
+
+    /// Focus the other file manager of a two-panel layout.
+    /// Returns false if there is none.
+    pub(super) fn focus_other_file_manager(&mut self) -> bool {
+        focus_other_file_manager(&mut self.layout_manager)
+    }
+
     /// Find all panels that have working directories
     /// Returns deduplicated and sorted list of paths from all panel types (FM, Terminal, Editor)
     pub(super) fn find_all_other_panel_paths(&self) -> Vec<termide_modal::SelectOption> {
@@ -135,3 +178,126 @@ impl App {
         }
     }
 }
+
+/// Position (group, panel) of the other file manager of a two-panel
+/// layout: exactly two file managers are open and one of them is active
+fn other_file_manager(layout: &LayoutManager) -> Option<(usize, usize)> {
+    let file_managers: Vec<(usize, usize)> = layout
+        .panel_groups
+        .iter()
+        .enumerate()
+        .flat_map(|(group_idx, group)| {
+            group
+                .panels()
+                .iter()
+                .enumerate()
+                .filter(|(_, panel)| panel.as_any().is::<FileManager>())
+                .map(move |(panel_idx, _)| (group_idx, panel_idx))
+        })
+        .collect();
+    let [first, second] = file_managers[..] else {
+        return None;
+    };
+
+    let focus = layout.focus;
+    let active = layout
+        .panel_groups
+        .get(focus)
+        .map(|group| (focus, group.expanded_index()))?;
+    if active == first {
+        Some(second)
+    } else if active == second {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+fn other_file_manager_dir(layout: &LayoutManager) -> Option<PathBuf> {
+    let (group_idx, panel_idx) = other_file_manager(layout)?;
+    layout.panel_groups[group_idx].panels()[panel_idx].get_working_directory()
+}
+
+fn focus_other_file_manager(layout: &mut LayoutManager) -> bool {
+    let Some((group_idx, panel_idx)) = other_file_manager(layout) else {
+        return false;
+    };
+    layout.panel_groups[group_idx].set_expanded(panel_idx);
+    layout.focus = group_idx;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_config::Config;
+    use termide_panel_editor::Editor;
+
+    /// Layout with one panel per group, the first group focused
+    fn layout(panels: Vec<Box<dyn Panel>>) -> LayoutManager {
+        let mut layout = LayoutManager::new();
+        for panel in panels {
+            layout.add_panel(panel, &Config::default(), 400);
+        }
+        layout.focus = 0;
+        layout
+    }
+
+    fn file_manager(dir: &std::path::Path) -> Box<dyn Panel> {
+        Box::new(FileManager::new_with_path(dir.to_path_buf()))
+    }
+
+    #[test]
+    fn test_two_file_managers_switch_and_target_each_other() {
+        let (left, right) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let mut layout = layout(vec![
+            file_manager(left.path()),
+            Box::new(Editor::new()),
+            file_manager(right.path()),
+        ]);
+        assert_eq!(layout.panel_groups.len(), 3);
+
+        assert_eq!(
+            other_file_manager_dir(&layout),
+            Some(right.path().to_path_buf())
+        );
+        assert!(focus_other_file_manager(&mut layout));
+        assert_eq!(layout.focus, 2);
+        assert_eq!(
+            other_file_manager_dir(&layout),
+            Some(left.path().to_path_buf())
+        );
+        assert!(focus_other_file_manager(&mut layout));
+        assert_eq!(layout.focus, 0);
+    }
+
+    #[test]
+    fn test_no_other_file_manager_with_one_or_three() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut single = layout(vec![file_manager(dir.path()), Box::new(Editor::new())]);
+        assert_eq!(other_file_manager_dir(&single), None);
+        assert!(!focus_other_file_manager(&mut single));
+        assert_eq!(single.focus, 0);
+
+        let mut three = layout(vec![
+            file_manager(dir.path()),
+            file_manager(dir.path()),
+            file_manager(dir.path()),
+        ]);
+        assert_eq!(other_file_manager(&three), None);
+        assert!(!focus_other_file_manager(&mut three));
+    }
+
+    #[test]
+    fn test_no_other_file_manager_when_active_panel_is_not_one() {
+        let (left, right) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let mut layout = layout(vec![
+            Box::new(Editor::new()),
+            file_manager(left.path()),
+            file_manager(right.path()),
+        ]);
+        assert_eq!(other_file_manager_dir(&layout), None);
+        assert!(!focus_other_file_manager(&mut layout));
+        assert_eq!(layout.focus, 0);
+    }
+}
@@ -45,18 +45,7 @@ impl App {
                         return Ok(());
                     } else if is_modified {
                         // Only local changes
-                        let modal = SelectModal::single(
-                            t.editor_close_unsaved(),
-                            t.editor_close_unsaved_question(),
-                            vec![
-                                t.editor_save_and_close().to_string(),
-                                t.editor_close_without_saving().to_string(),
-                                t.editor_cancel().to_string(),
-                            ],
-                        );
-                        let action = PendingAction::CloseEditorWithSave { panel_index: 0 };
-                        self.state
-                            .set_pending_action(action, ActiveModal::Select(Box::new(modal)));
+                        self.show_close_unsaved_prompt();
                         return Ok(());
                     } else if has_external {
                         // Only external changes
@@ -92,6 +81,24 @@ impl App {
         Ok(())
     }
 
+    /// Ask what to do with the unsaved changes of the editor being closed
+    pub(super) fn show_close_unsaved_prompt(&mut self) {
+        let t = i18n::t();
+        let modal = termide_modal::SelectModal::single(
+            t.editor_close_unsaved(),
+            t.editor_close_unsaved_question(),
+            vec![
+                t.editor_save_and_close().to_string(),
+                t.editor_close_without_saving().to_string(),
+                t.editor_review_changes().to_string(),
+                t.editor_cancel().to_string(),
+            ],
+        );
+        let action = PendingAction::CloseEditorWithSave { panel_index: 0 };
+        self.state
+            .set_pending_action(action, ActiveModal::Select(Box::new(modal)));
+    }
+
     /// Close all Welcome panels (called before opening new panel)
     pub(super) fn close_welcome_panels(&mut self) {
         logger::debug("Closing Welcome panel(s)");
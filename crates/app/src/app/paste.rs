@@ -0,0 +1,274 @@
+//! Text pasted into the terminal (bracketed paste).
+//!
+//! Terminals paste the paths of files dragged onto them from a graphical
+//! file manager, usually quoted. Pasted text made only of paths of existing
+//! files opens them from an editor (or inserts the text, as configured) and
+//! copies them into the directory of a file manager. Other text goes to the
+//! active panel or modal.
+
+use anyhow::Result;
+use std::path::PathBuf;
+
+use termide_config::DroppedFiles;
+use termide_core::paste_key_events;
+use termide_i18n as i18n;
+use termide_logger as logger;
+use termide_modal::{ConfirmModal, SelectModal};
+
+use super::App;
+use crate::state::{ActiveModal, BatchOperation, BatchOperationType, PendingAction};
+use crate::PanelExt;
+
+impl App {
+    /// Handle text pasted into the terminal
+    pub(super) fn handle_paste_event(&mut self, text: String) -> Result<()> {
+        if self.state.ui.status_message.is_some() {
+            self.state.clear_status();
+        }
+
+        // Modal inputs take a single line, typed key by key
+        if self.state.has_modal() {
+            let line = text.lines().next().unwrap_or_default();
+            for key in paste_key_events(line) {
+                self.handle_modal_key(key)?;
+                if !self.state.has_modal() {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+        if self.state.ui.menu_open {
+            return Ok(());
+        }
+
+        let paths = dropped_paths(&text);
+        let Some(panel) = self.layout_manager.active_panel_mut() else {
+            return Ok(());
+        };
+        if let Some(fm) = panel.as_file_manager_mut() {
+            // Other text means nothing to a file manager
+            if let Some(paths) = paths {
+                let directory = fm.get_current_directory();
+                self.copy_dropped_files(paths, directory);
+            }
+            return Ok(());
+        }
+        let is_editor = panel.as_editor_mut().is_some();
+        if let (Some(paths), true) = (&paths, is_editor) {
+            match self.state.config.editor.dropped_files {
+                DroppedFiles::Ask => {
+                    let t = i18n::t();
+                    let options = vec![
+                        t.modal_dropped_open().to_string(),
+                        t.modal_dropped_insert().to_string(),
+                    ];
+                    let modal =
+                        SelectModal::single(t.modal_dropped_files_title(paths.len()), "", options);
+                    self.state.set_pending_action(
+                        PendingAction::OpenDroppedFiles {
+                            paths: paths.clone(),
+                            text,
+                        },
+                        ActiveModal::Select(Box::new(modal)),
+                    );
+                    return Ok(());
+                }
+                DroppedFiles::Open => return self.open_dropped_files(paths.clone()),
+                DroppedFiles::Insert => {}
+            }
+        }
+        self.paste_to_active_panel(&text)
+    }
+
+    /// Open the dropped files, or insert the pasted text into the editor
+    pub(super) fn handle_open_dropped_files(
+        &mut self,
+        paths: Vec<PathBuf>,
+        text: String,
+        value: Box<dyn std::any::Any>,
+    ) -> Result<()> {
+        match value
+            .downcast_ref::<Vec<usize>>()
+            .and_then(|selected| selected.first())
+        {
+            Some(0) => self.open_dropped_files(paths),
+            Some(_) => self.paste_to_active_panel(&text),
+            None => Ok(()),
+        }
+    }
+
+    /// Give pasted text to the active panel
+    fn paste_to_active_panel(&mut self, text: &str) -> Result<()> {
+        let (events, modal_request) = match self.layout_manager.active_panel_mut() {
+            Some(panel) => (panel.handle_paste(text), panel.take_modal_request()),
+            None => return Ok(()),
+        };
+        self.process_panel_events(events)?;
+        if let Some((action, modal)) = modal_request {
+            self.handle_modal_request(action, modal)?;
+        }
+        Ok(())
+    }
+
+    /// Open dropped files in editors and directories in file managers
+    fn open_dropped_files(&mut self, paths: Vec<PathBuf>) -> Result<()> {
+        for path in paths {
+            logger::info(format!("Opening dropped path: {}", path.display()));
+            if path.is_dir() {
+                self.event_open_directory(path);
+            } else {
+                self.event_open_file(path, None, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy dropped files into a directory, after confirmation unless
+    /// `confirm_dropped_files` is off
+    fn copy_dropped_files(&mut self, paths: Vec<PathBuf>, directory: PathBuf) {
+        if !self.state.config.file_manager.confirm_dropped_files {
+            let operation = BatchOperation::new(BatchOperationType::Copy, paths, directory);
+            self.process_batch_operation(operation);
+            return;
+        }
+        let message =
+            i18n::t().fm_paste_confirm(paths.len(), "Copy", &directory.display().to_string());
+        let modal = ConfirmModal::new("Confirm", &message);
+        self.state.set_pending_action(
+            PendingAction::CopyPath {
+                panel_index: 0,
+                sources: paths,
+                target_directory: Some(directory),
+            },
+            ActiveModal::Confirm(Box::new(modal)),
+        );
+    }
+}
+
+/// Paths of existing files that make up all of the pasted text, or None.
+///
+/// Paths are separated by whitespace and may be quoted (`'…'` or `"…"`),
+/// have escaped spaces (`a\ b`) or be `file://` URLs, as terminals paste
+/// dropped files. Each one must be absolute and exist.
+fn dropped_paths(text: &str) -> Option<Vec<PathBuf>> {
+    let paths: Vec<PathBuf> = split_words(text)?
+        .iter()
+        .map(|word| match word.strip_prefix("file://") {
+            Some(url) => percent_decode(url.strip_prefix("localhost").unwrap_or(url)),
+            None => Some(word.clone()),
+        })
+        .map(|path| path.map(PathBuf::from))
+        .collect::<Option<_>>()?;
+    let all_exist = paths.iter().all(|path| path.is_absolute() && path.exists());
+    (!paths.is_empty() && all_exist).then_some(paths)
+}
+
+/// Split text into words like a shell does (None for unbalanced quotes)
+fn split_words(text: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => word.push(chars.next()?),
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).push(chars.next()?),
+            ch => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    words.extend(word);
+    Some(words)
+}
+
+/// Decode `%XX` escapes of a URL path (None if they are not valid UTF-8)
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("notes.txt");
+        let spaced = dir.path().join("my file.txt");
+        std::fs::write(&plain, "").unwrap();
+        std::fs::write(&spaced, "").unwrap();
+        let plain_str = plain.display().to_string();
+        let spaced_str = spaced.display().to_string();
+
+        assert_eq!(dropped_paths(&plain_str), Some(vec![plain.clone()]));
+        assert_eq!(
+            dropped_paths(&format!("'{}' '{}' ", plain_str, spaced_str)),
+            Some(vec![plain.clone(), spaced.clone()])
+        );
+        assert_eq!(
+            dropped_paths(&spaced_str.replace(' ', "\\ ")),
+            Some(vec![spaced.clone()])
+        );
+        assert_eq!(
+            dropped_paths(&format!(
+                "\"{}\"\nfile://{}",
+                spaced_str,
+                spaced_str.replace(' ', "%20")
+            )),
+            Some(vec![spaced.clone(), spaced.clone()])
+        );
+    }
+
+    #[test]
+    fn test_text_is_not_dropped_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "").unwrap();
+        let file_str = file.display().to_string();
+
+        assert_eq!(dropped_paths(""), None);
+        assert_eq!(dropped_paths("notes.txt"), None);
+        assert_eq!(dropped_paths(&format!("see {}", file_str)), None);
+        assert_eq!(dropped_paths(&format!("'{}", file_str)), None);
+        assert_eq!(
+            dropped_paths(&dir.path().join("missing.txt").display().to_string()),
+            None
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Quitting with unsaved changes or running processes.
+//!
+//! Editors with unsaved or externally changed content and terminals with
+//! running processes are listed in one modal, each with its own choice
+//! (save or discard a file; terminals are killed). The chosen files are
+//! saved first: a failed save aborts the quit with its error, leaving the
+//! other panels as they were. Cancelling the modal changes nothing.
+
+use anyhow::Result;
+
+use super::App;
+use crate::state::{ActiveModal, PendingAction};
+use crate::PanelExt;
+use termide_core::Panel;
+use termide_i18n as i18n;
+use termide_logger as logger;
+use termide_modal::{QuitChoice, QuitItem, QuitModal};
+use termide_panel_editor::Editor;
+
+impl App {
+    /// Quit, first asking what to do with panels that would lose work
+    pub(super) fn handle_quit_request(&mut self) -> Result<()> {
+        // Always save session before quit
+        self.auto_save_session();
+
+        let t = i18n::t();
+        let (panels, items): (Vec<_>, Vec<_>) = self.quit_candidates().into_iter().unzip();
+        if !panels.is_empty() {
+            let modal = QuitModal::new(t.modal_quit_title(), t.modal_quit_prompt(), items);
+            self.state.set_pending_action(
+                PendingAction::QuitWithChanges { panels },
+                ActiveModal::Quit(Box::new(modal)),
+            );
+        } else if self.has_panels_requiring_confirmation() {
+            // Running batch file operation
+            let modal = termide_modal::ConfirmModal::new(t.modal_yes(), t.app_quit_confirm());
+            self.state.set_pending_action(
+                PendingAction::QuitApplication,
+                ActiveModal::Confirm(Box::new(modal)),
+            );
+        } else {
+            self.state.quit();
+        }
+        Ok(())
+    }
+
+    /// Panels that would lose work on exit, by (group, panel) position.
+    ///
+    /// A buffer shown in several views is listed once.
+    fn quit_candidates(&self) -> Vec<((usize, usize), QuitItem)> {
+        let t = i18n::t();
+        let mut candidates = Vec::new();
+        let mut editors: Vec<&Editor> = Vec::new();
+        for (group_index, group) in self.layout_manager.panel_groups.iter().enumerate() {
+            for (panel_index, panel) in group.panels().iter().enumerate() {
+                let position = (group_index, panel_index);
+                if let Some(editor) = panel.as_editor() {
+                    if editors.iter().any(|other| other.shares_buffer_with(editor)) {
+                        continue;
+                    }
+                    let detail = match (editor.buffer_is_modified(), editor.has_external_change()) {
+                        (true, true) => t.modal_quit_conflict(),
+                        (true, false) => t.modal_quit_modified(),
+                        (false, true) => t.modal_quit_external(),
+                        (false, false) => continue,
+                    };
+                    editors.push(editor);
+                    candidates.push((position, QuitItem::file(editor_name(editor), detail)));
+                } else if panel.needs_close_confirmation().is_some() {
+                    candidates.push((
+                        position,
+                        QuitItem::terminal(panel.title(), t.modal_quit_running()),
+                    ));
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Save the files chosen in the quit modal, then quit
+    pub(super) fn handle_quit_with_changes(
+        &mut self,
+        panels: Vec<(usize, usize)>,
+        value: Box<dyn std::any::Any>,
+    ) {
+        let Some(choices) = value.downcast_ref::<Vec<QuitChoice>>() else {
+            return;
+        };
+        let t = i18n::t();
+
+        // A terminal finished or a panel closed while the modal was open:
+        // the choices may no longer match the panels
+        let current: Vec<(usize, usize)> = self
+            .quit_candidates()
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect();
+        if current != panels {
+            self.state.set_error(t.modal_quit_changed().to_string());
+            return;
+        }
+
+        for (&(group, panel), &choice) in panels.iter().zip(choices) {
+            if choice != QuitChoice::Save {
+                continue;
+            }
+            let Some(editor) = self
+                .layout_manager
+                .get_group_mut(group)
+                .and_then(|group| group.panels_mut().get_mut(panel))
+                .and_then(|panel| panel.as_editor_mut())
+            else {
+                continue;
+            };
+            // Saving was chosen knowing the file changed on disk
+            let saved = if editor.has_external_change() {
+                editor.force_save()
+            } else {
+                editor.save()
+            };
+            if let Err(e) = saved {
+                let name = editor_name(editor);
+                logger::error(format!("Quit aborted, {} was not saved: {}", name, e));
+                self.state
+                    .set_error(t.modal_quit_save_failed(&name, &e.to_string()));
+                return;
+            }
+        }
+
+        // Discarded buffers and terminals (killed on drop) go with the app
+        self.state.quit();
+    }
+}
+
+/// Path of the file of an editor, or its title for unnamed buffers
+fn editor_name(editor: &Editor) -> String {
+    editor
+        .file_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| editor.title())
+}
@@ -0,0 +1,327 @@
+//! Crash recovery of unsaved changes.
+//!
+//! On each tick the editors with unsaved changes are copied to the recovery
+//! directory (after a pause in typing); copies are deleted once the changes
+//! are saved or undone, when their editor is closed and on exit. At start
+//! the copies left by a termide that did not exit are listed, and each can
+//! be restored, compared with its file or discarded.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use termide_config::constants::{MEGABYTE, RECOVERY_MAX_EDITS};
+use termide_i18n as i18n;
+use termide_logger as logger;
+use termide_modal::SelectModal;
+use termide_panel_editor::{Editor, RecoveryUpdate};
+use termide_panel_misc::DiffPanel;
+use termide_session::{orphaned_recovery_files, RecoveryFile};
+
+use super::App;
+use crate::state::{ActiveModal, PendingAction};
+use crate::PanelExt;
+
+/// What can be done with a recovered buffer, in the order offered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecoveryChoice {
+    Restore,
+    /// Only offered for files that exist
+    ShowDiff,
+    Discard,
+}
+
+impl RecoveryChoice {
+    /// Choices offered for a recovered buffer
+    fn offered(file: &RecoveryFile) -> Vec<Self> {
+        let has_file = file.path.as_ref().is_some_and(|path| path.is_file());
+        let mut choices = vec![Self::Restore];
+        if has_file {
+            choices.push(Self::ShowDiff);
+        }
+        choices.push(Self::Discard);
+        choices
+    }
+
+    fn label(self) -> String {
+        let t = i18n::t();
+        match self {
+            Self::Restore => t.modal_recovery_restore(),
+            Self::ShowDiff => t.modal_recovery_diff(),
+            Self::Discard => t.modal_recovery_discard(),
+        }
+        .to_string()
+    }
+}
+
+impl App {
+    /// When an editor has changes due for a recovery copy
+    pub(super) fn recovery_deadline(&mut self) -> Option<Instant> {
+        let settings = &self.state.config.editor;
+        if !settings.recovery {
+            return None;
+        }
+        let idle = Duration::from_secs(settings.recovery_idle_secs);
+        self.layout_manager
+            .iter_all_panels_mut()
+            .filter_map(|panel| panel.as_editor_mut())
+            .filter_map(|editor| editor.recovery_deadline(idle))
+            .min()
+    }
+
+    /// Write recovery copies of buffers with unsaved changes and delete the
+    /// copies that are no longer needed
+    pub(super) fn check_recovery(&mut self) {
+        let Ok(dir) = RecoveryFile::dir() else {
+            return;
+        };
+        let settings = &self.state.config.editor;
+        if !settings.recovery {
+            self.remove_recovery_files();
+            return;
+        }
+        let idle = Duration::from_secs(settings.recovery_idle_secs);
+        let max_bytes =
+            usize::try_from(settings.recovery_max_size_mb * MEGABYTE).unwrap_or(usize::MAX);
+
+        let mut updates = Vec::new();
+        let mut current = Vec::new();
+        for panel in self.layout_manager.iter_all_panels_mut() {
+            if let Some(editor) = panel.as_editor_mut() {
+                updates.extend(editor.take_recovery_update(idle, RECOVERY_MAX_EDITS, max_bytes));
+                current.extend(editor.recovery_id());
+            }
+        }
+
+        for update in updates {
+            match update {
+                RecoveryUpdate::Write {
+                    id,
+                    path,
+                    title,
+                    text,
+                } => match RecoveryFile::new(&id, path, &title).write(&dir, &text) {
+                    Ok(()) => {
+                        self.recovery_files.insert(id);
+                    }
+                    Err(e) => logger::error(format!("Failed to write recovery copy: {}", e)),
+                },
+                RecoveryUpdate::Remove { id } => self.remove_recovery_file(&id),
+            }
+        }
+
+        // Copies of closed editors
+        let closed: Vec<String> = self
+            .recovery_files
+            .iter()
+            .filter(|id| !current.contains(id))
+            .cloned()
+            .collect();
+        for id in closed {
+            self.remove_recovery_file(&id);
+        }
+    }
+
+    /// Delete the recovery copies written by this run (on exit, when
+    /// nothing is lost)
+    pub fn remove_recovery_files(&mut self) {
+        let ids: Vec<String> = self.recovery_files.iter().cloned().collect();
+        for id in ids {
+            self.remove_recovery_file(&id);
+        }
+    }
+
+    fn remove_recovery_file(&mut self, id: &str) {
+        self.recovery_files.remove(id);
+        let result = RecoveryFile::dir().and_then(|dir| RecoveryFile::remove(&dir, id));
+        if let Err(e) = result {
+            logger::error(format!("Failed to delete recovery copy: {}", e));
+        }
+    }
+
+    /// Offer to restore the buffers of a termide that did not exit (call
+    /// at start, before any copy is written)
+    pub fn offer_recovered_buffers(&mut self) {
+        // Another question (paths to create) comes first; the copies stay
+        // for the next start
+        if !self.state.config.editor.recovery || self.state.has_modal() {
+            return;
+        }
+        let Ok(dir) = RecoveryFile::dir() else {
+            return;
+        };
+        let ids: Vec<String> = orphaned_recovery_files(&dir)
+            .into_iter()
+            .map(|file| file.id)
+            .collect();
+        if !ids.is_empty() {
+            logger::info(format!("Found {} recovered buffers", ids.len()));
+        }
+        self.show_recovered_buffers(ids);
+    }
+
+    /// List the recovered buffers (none shown if all were handled)
+    fn show_recovered_buffers(&mut self, ids: Vec<String>) {
+        let Ok(dir) = RecoveryFile::dir() else {
+            return;
+        };
+        let files: Vec<RecoveryFile> = ids
+            .iter()
+            .filter_map(|id| RecoveryFile::load(&dir, id).ok())
+            .collect();
+        if files.is_empty() {
+            return;
+        }
+        let t = i18n::t();
+        let labels = files
+            .iter()
+            .map(|file| {
+                let path = file
+                    .path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| t.modal_recovery_unnamed().to_string());
+                let time = file.saved_at.format("%Y-%m-%d %H:%M").to_string();
+                t.modal_recovery_item(&file.title, &path, &time)
+            })
+            .collect();
+        let modal =
+            SelectModal::single(t.modal_recovery_title(), t.modal_recovery_prompt(), labels);
+        let ids = files.into_iter().map(|file| file.id).collect();
+        self.state.set_pending_action(
+            PendingAction::RecoverBuffers { ids },
+            ActiveModal::Select(Box::new(modal)),
+        );
+    }
+
+    /// Ask what to do with the picked recovered buffer
+    pub(super) fn handle_recovered_buffers(
+        &mut self,
+        ids: Vec<String>,
+        value: Box<dyn std::any::Any>,
+    ) {
+        let Some(&index) = value
+            .downcast_ref::<Vec<usize>>()
+            .and_then(|selected| selected.first())
+        else {
+            return;
+        };
+        self.show_recovered_buffer_choices(ids, index);
+    }
+
+    fn show_recovered_buffer_choices(&mut self, ids: Vec<String>, index: usize) {
+        let Some(file) = ids.get(index).and_then(|id| {
+            RecoveryFile::dir()
+                .and_then(|dir| RecoveryFile::load(&dir, id))
+                .ok()
+        }) else {
+            return;
+        };
+        let labels = RecoveryChoice::offered(&file)
+            .into_iter()
+            .map(RecoveryChoice::label)
+            .collect();
+        let modal = SelectModal::single(file.title, "", labels);
+        self.state.set_pending_action(
+            PendingAction::RecoverBuffer { ids, index },
+            ActiveModal::Select(Box::new(modal)),
+        );
+    }
+
+    /// Restore, compare or discard a recovered buffer, then list the others
+    pub(super) fn handle_recovered_buffer(
+        &mut self,
+        mut ids: Vec<String>,
+        index: usize,
+        value: Box<dyn std::any::Any>,
+    ) -> anyhow::Result<()> {
+        let dir = RecoveryFile::dir()?;
+        let Some(file) = ids
+            .get(index)
+            .and_then(|id| RecoveryFile::load(&dir, id).ok())
+        else {
+            return Ok(());
+        };
+        let Some(choice) = value
+            .downcast_ref::<Vec<usize>>()
+            .and_then(|selected| selected.first())
+            .and_then(|&choice| RecoveryChoice::offered(&file).get(choice).copied())
+        else {
+            return Ok(());
+        };
+
+        let t = i18n::t();
+        let text = match file.read_text(&dir) {
+            Ok(text) => text,
+            Err(e) => {
+                self.state
+                    .set_error(t.status_error_recovery(&e.to_string()));
+                return Ok(());
+            }
+        };
+        match choice {
+            RecoveryChoice::Restore => {
+                if let Err(e) = self.restore_recovered_buffer(&file, &text) {
+                    logger::error(format!("Failed to restore recovered buffer: {}", e));
+                    self.state
+                        .set_error(t.status_error_recovery(&e.to_string()));
+                    return Ok(());
+                }
+                self.state.set_info(t.status_recovery_restored(&file.title));
+            }
+            RecoveryChoice::ShowDiff => {
+                if let Some(path) = file.path.clone() {
+                    self.show_recovery_diff(path, &text);
+                }
+                // Decide about the buffer after looking at the diff
+                self.show_recovered_buffer_choices(ids, index);
+                return Ok(());
+            }
+            RecoveryChoice::Discard => {
+                logger::info(format!("Discarded recovered buffer '{}'", file.title));
+            }
+        }
+        RecoveryFile::remove(&dir, &file.id)?;
+        ids.remove(index);
+        self.show_recovered_buffers(ids);
+        Ok(())
+    }
+
+    /// Open the file of a recovered buffer (or a new buffer without a file)
+    /// with the recovered text
+    fn restore_recovered_buffer(&mut self, file: &RecoveryFile, text: &str) -> anyhow::Result<()> {
+        match file.path.as_ref().filter(|path| path.is_file()) {
+            Some(path) => {
+                self.event_open_file(path.clone(), None, None)?;
+                let editor = self
+                    .active_editor_mut()
+                    .filter(|editor| editor.file_path() == Some(path.as_path()))
+                    .ok_or_else(|| anyhow::anyhow!("{} is not open", path.display()))?;
+                editor.restore_text(text)?;
+            }
+            None => {
+                let editor = Editor::with_text(text, &file.title, self.state.editor_config());
+                self.close_welcome_panels();
+                self.add_panel(Box::new(editor));
+            }
+        }
+        logger::info(format!("Restored recovered buffer '{}'", file.title));
+        Ok(())
+    }
+
+    /// Compare a file with its recovered text
+    fn show_recovery_diff(&mut self, path: PathBuf, text: &str) {
+        let disk = match std::fs::read(&path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => {
+                self.state
+                    .set_error(i18n::t().status_error_recovery(&e.to_string()));
+                return;
+            }
+        };
+        let layout = self.state.config.editor.diff_layout;
+        self.close_welcome_panels();
+        self.add_panel(Box::new(DiffPanel::against_recovery(
+            path, &disk, text, layout,
+        )));
+    }
+}
@@ -0,0 +1,315 @@
+//! Panels opened for command line arguments.
+//!
+//! `termide src/main.rs:120 ./docs -` opens an editor at line 120, a file
+//! manager on `./docs` and an editor with the text read from standard
+//! input, in that order, focusing the first. Paths that do not exist are
+//! offered to be created.
+
+use std::path::{Path, PathBuf};
+
+use termide_i18n as i18n;
+use termide_logger as logger;
+use termide_modal::ConfirmModal;
+use termide_panel_editor::Editor;
+
+use super::App;
+use crate::state::{ActiveModal, PendingAction};
+
+/// Title of the editor holding standard input
+const STDIN_TITLE: &str = "stdin";
+
+/// What a command line argument opens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupTarget {
+    /// File in an editor, at a 1-based line and column
+    File {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    /// Directory in a file manager
+    Directory(PathBuf),
+    /// Path that does not exist (a directory if it ends with `/`; a
+    /// `:line` suffix is dropped)
+    Missing { path: PathBuf, directory: bool },
+    /// Text read from standard input (`-`), in an editor without a file
+    Stdin,
+}
+
+impl StartupTarget {
+    /// Classify an argument: `-`, an existing file or directory, a file
+    /// with a `:line` or `:line:column` suffix, or a missing path
+    pub fn parse(arg: &str) -> Self {
+        if arg == "-" {
+            return Self::Stdin;
+        }
+        let path = Path::new(arg);
+        if path.is_dir() {
+            return Self::Directory(path.to_path_buf());
+        }
+        if path.exists() {
+            return Self::file(path, None, None);
+        }
+        match split_position(arg) {
+            Some((file, line, column)) if Path::new(file).is_file() => {
+                Self::file(Path::new(file), Some(line), column)
+            }
+            // A new file gets no position
+            Some((file, ..)) => Self::Missing {
+                path: PathBuf::from(file),
+                directory: false,
+            },
+            None => Self::Missing {
+                path: path.to_path_buf(),
+                directory: arg.ends_with('/'),
+            },
+        }
+    }
+
+    fn file(path: &Path, line: Option<usize>, column: Option<usize>) -> Self {
+        Self::File {
+            path: path.to_path_buf(),
+            line,
+            column,
+        }
+    }
+}
+
+/// Split `path:line` or `path:line:column` (None without a line number)
+fn split_position(arg: &str) -> Option<(&str, usize, Option<usize>)> {
+    let (rest, last) = arg.rsplit_once(':')?;
+    let last = last.parse().ok()?;
+    match rest.rsplit_once(':') {
+        Some((file, line)) if !file.is_empty() => match line.parse() {
+            Ok(line) => Some((file, line, Some(last))),
+            Err(_) => Some((rest, last, None)),
+        },
+        _ => (!rest.is_empty()).then_some((rest, last, None)),
+    }
+}
+
+impl App {
+    /// Open panels for command line arguments in order and focus the
+    /// first. `stdin` is the text read for `-`
+    pub fn open_startup_targets(&mut self, targets: Vec<StartupTarget>, stdin: Option<String>) {
+        let mut first_panel = None;
+        let mut missing = Vec::new();
+        for target in targets {
+            match target {
+                StartupTarget::File { path, line, column } => {
+                    if let Err(e) = self.event_open_file(path, line, column) {
+                        logger::error(format!("Failed to open file: {}", e));
+                    }
+                }
+                StartupTarget::Directory(path) => self.event_open_directory(path),
+                StartupTarget::Missing { path, directory } => {
+                    missing.push((path, directory));
+                    continue;
+                }
+                StartupTarget::Stdin => {
+                    let text = stdin.as_deref().unwrap_or_default();
+                    let editor = Editor::with_text(text, STDIN_TITLE, self.state.editor_config());
+                    self.close_welcome_panels();
+                    self.add_panel(Box::new(editor));
+                }
+            }
+            if first_panel.is_none() {
+                first_panel = self.focused_panel_position();
+            }
+        }
+
+        if self.layout_manager.panel_groups.is_empty() {
+            let welcome = self.new_welcome_panel();
+            self.add_panel(Box::new(welcome));
+        }
+        if let Some((group_idx, panel_idx)) = first_panel {
+            if let Some(group) = self.layout_manager.panel_groups.get_mut(group_idx) {
+                group.set_expanded(panel_idx);
+                self.layout_manager.focus = group_idx;
+            }
+        }
+        self.ask_create_missing_paths(missing);
+    }
+
+    /// Group and panel index of the focused panel
+    fn focused_panel_position(&self) -> Option<(usize, usize)> {
+        let group_idx = self.layout_manager.focus;
+        let group = self.layout_manager.panel_groups.get(group_idx)?;
+        Some((group_idx, group.expanded_index()))
+    }
+
+    /// Ask whether to create the first of the missing paths (the others are
+    /// asked about after it)
+    fn ask_create_missing_paths(&mut self, paths: Vec<(PathBuf, bool)>) {
+        if paths.is_empty() {
+            return;
+        }
+        let (path, directory) = &paths[0];
+        let t = i18n::t();
+        let title = if *directory {
+            t.modal_create_dir_title()
+        } else {
+            t.modal_create_file_title()
+        };
+        let modal = ConfirmModal::new(title, t.modal_create_missing(&path.display().to_string()));
+        self.state.set_pending_action(
+            PendingAction::CreateMissingPaths { paths },
+            ActiveModal::Confirm(Box::new(modal)),
+        );
+    }
+
+    /// Create the first missing path if confirmed and open it, then ask
+    /// about the next one
+    pub(super) fn handle_create_missing_paths(
+        &mut self,
+        mut paths: Vec<(PathBuf, bool)>,
+        value: Box<dyn std::any::Any>,
+    ) -> anyhow::Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let (path, directory) = paths.remove(0);
+        if value.downcast_ref::<bool>() == Some(&true) {
+            let t = i18n::t();
+            let name = path.display().to_string();
+            if directory {
+                match std::fs::create_dir_all(&path) {
+                    Ok(()) => {
+                        self.event_open_directory(path);
+                        self.state.set_info(t.status_dir_created(&name));
+                    }
+                    Err(e) => self
+                        .state
+                        .set_error(t.status_error_create_dir(&e.to_string())),
+                }
+            } else {
+                match create_file(&path) {
+                    Ok(()) => {
+                        self.event_open_file(path, None, None)?;
+                        self.state.set_info(t.status_file_created(&name));
+                    }
+                    Err(e) => self
+                        .state
+                        .set_error(t.status_error_create_file(&e.to_string())),
+                }
+            }
+        }
+        self.ask_create_missing_paths(paths);
+        Ok(())
+    }
+}
+
+/// Create an empty file and its missing parent directories
+fn create_file(path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_position() {
+        assert_eq!(split_position("main.rs:120"), Some(("main.rs", 120, None)));
+        assert_eq!(
+            split_position("main.rs:12:5"),
+            Some(("main.rs", 12, Some(5)))
+        );
+        assert_eq!(split_position("a:b:7"), Some(("a:b", 7, None)));
+        assert_eq!(split_position("main.rs"), None);
+        assert_eq!(split_position("main.rs:"), None);
+        assert_eq!(split_position(":12"), None);
+    }
+
+    #[test]
+    fn test_parse_startup_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+        let file_arg = file.display().to_string();
+
+        assert_eq!(StartupTarget::parse("-"), StartupTarget::Stdin);
+        assert_eq!(
+            StartupTarget::parse(&dir.path().display().to_string()),
+            StartupTarget::Directory(dir.path().to_path_buf())
+        );
+        assert_eq!(
+            StartupTarget::parse(&file_arg),
+            StartupTarget::File {
+                path: file.clone(),
+                line: None,
+                column: None
+            }
+        );
+        assert_eq!(
+            StartupTarget::parse(&format!("{}:120:4", file_arg)),
+            StartupTarget::File {
+                path: file.clone(),
+                line: Some(120),
+                column: Some(4)
+            }
+        );
+
+        let missing = dir.path().join("new.rs");
+        assert_eq!(
+            StartupTarget::parse(&format!("{}:3", missing.display())),
+            StartupTarget::Missing {
+                path: missing.clone(),
+                directory: false
+            }
+        );
+        assert_eq!(
+            StartupTarget::parse(&format!("{}/", missing.display())),
+            StartupTarget::Missing {
+                path: PathBuf::from(format!("{}/", missing.display())),
+                directory: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_created_path_is_named_in_status() {
+        i18n::init_with_language("en");
+        termide_logger::init(
+            std::env::temp_dir().join("termide-test.log"),
+            100,
+            termide_logger::LogLevel::Error,
+        );
+        let config = termide_config::Config::default();
+        let theme = termide_theme::Theme::get_by_name(&config.general.theme);
+        let mut app = App::with_state(
+            crate::state::AppState::with_config_and_theme(config, theme),
+            std::env::temp_dir(),
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("new");
+
+        app.handle_create_missing_paths(vec![(missing.clone(), true)], Box::new(true))
+            .unwrap();
+        assert!(missing.is_dir());
+        assert_eq!(
+            app.state.ui.status_message,
+            Some((format!("Directory '{}' created", missing.display()), false))
+        );
+
+        // The parent is a file, so the directory cannot be created
+        let blocked = dir.path().join("file");
+        std::fs::write(&blocked, "").unwrap();
+        app.handle_create_missing_paths(vec![(blocked.join("sub"), true)], Box::new(true))
+            .unwrap();
+        let (message, is_error) = app.state.ui.status_message.clone().unwrap();
+        assert!(is_error);
+        assert!(message.starts_with("Error creating directory: "));
+        assert!(!message.contains('{'));
+    }
+}
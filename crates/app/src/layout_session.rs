@@ -6,11 +6,13 @@ use std::path::Path;
 
 use anyhow::Result;
 
+use termide_app_panel::PanelRegistry;
+use termide_config::TerminalSettings;
 use termide_core::Panel;
 use termide_layout::{LayoutManager, PanelGroup};
 use termide_panel_editor::{Editor, EditorConfig};
 use termide_panel_file_manager::FileManager;
-use termide_panel_misc::LogViewerPanel;
+use termide_panel_misc::{GitPanel, LogViewerPanel};
 use termide_panel_terminal::Terminal;
 use termide_session::{
     cleanup_unsaved_buffer, load_unsaved_buffer, Session, SessionPanel, SessionPanelGroup,
@@ -28,32 +30,51 @@ pub trait LayoutManagerSession {
         term_height: u16,
         term_width: u16,
         editor_config: EditorConfig,
+        terminal_settings: &TerminalSettings,
+        registry: &PanelRegistry,
     ) -> Result<LayoutManager>;
 }
 
 impl LayoutManagerSession for LayoutManager {
     fn to_session(&mut self, session_dir: &Path) -> Session {
-        let panel_groups: Vec<SessionPanelGroup> = self
-            .panel_groups
-            .iter_mut()
-            .map(|group| {
-                let panels: Vec<_> = group
-                    .panels_mut()
-                    .iter_mut()
-                    .filter_map(|panel| panel.to_session(session_dir))
-                    .collect();
-
-                SessionPanelGroup {
-                    panels,
-                    expanded_index: group.expanded_index(),
-                    width: group.width,
+        let mut panel_groups = Vec::with_capacity(self.panel_groups.len());
+        let mut focused_group = 0;
+
+        for (group_idx, group) in self.panel_groups.iter_mut().enumerate() {
+            let expanded = group.expanded_index();
+            let mut panels = Vec::with_capacity(group.len());
+            let mut expanded_index = 0;
+
+            // Panels that are not saved (Welcome) shift the indices of the
+            // following ones; keep pointing at the same expanded panel
+            for (idx, panel) in group.panels_mut().iter_mut().enumerate() {
+                if let Some(session_panel) = panel.to_session(session_dir) {
+                    if idx <= expanded {
+                        expanded_index = panels.len();
+                    }
+                    panels.push(session_panel);
                 }
-            })
-            .collect();
+            }
+
+            if panels.is_empty() {
+                continue;
+            }
+            if group_idx <= self.focus {
+                focused_group = panel_groups.len();
+            }
+            panel_groups.push(SessionPanelGroup {
+                panels,
+                expanded_index,
+                width: group.width,
+                tabs: group.tabs,
+            });
+        }
 
         Session {
             panel_groups,
-            focused_group: self.focus,
+            focused_group,
+            search_history: Vec::new(),
+            bookmarks: Vec::new(),
         }
     }
 
@@ -63,71 +84,33 @@ impl LayoutManagerSession for LayoutManager {
         term_height: u16,
         term_width: u16,
         editor_config: EditorConfig,
+        terminal_settings: &TerminalSettings,
+        registry: &PanelRegistry,
     ) -> Result<LayoutManager> {
         let mut layout = LayoutManager::new();
 
-        for session_group in session.panel_groups {
-            if session_group.panels.is_empty() {
-                continue;
-            }
-
+        for (group_idx, session_group) in session.panel_groups.into_iter().enumerate() {
             let mut panels: Vec<Box<dyn Panel>> = Vec::with_capacity(session_group.panels.len());
+            let mut expanded_index = 0;
 
-            for session_panel in session_group.panels {
-                let panel: Option<Box<dyn Panel>> = match session_panel {
-                    SessionPanel::FileManager { path } => {
-                        Some(Box::new(FileManager::new_with_path(path)))
-                    }
-                    SessionPanel::Editor {
-                        path,
-                        unsaved_buffer_file,
-                    } => {
-                        if let Some(file_path) = path {
-                            Editor::open_file_with_config(file_path, editor_config.clone())
-                                .ok()
-                                .map(|e| Box::new(e) as Box<dyn Panel>)
-                        } else if let Some(ref buffer_file) = unsaved_buffer_file {
-                            match load_unsaved_buffer(session_dir, buffer_file) {
-                                Ok(content) => {
-                                    if content.trim().is_empty() {
-                                        let _ = cleanup_unsaved_buffer(session_dir, buffer_file);
-                                        None
-                                    } else {
-                                        let mut editor = Editor::with_config(editor_config.clone());
-                                        if let Err(e) = editor.insert_text(&content) {
-                                            eprintln!(
-                                                "Warning: Failed to restore unsaved buffer content: {}",
-                                                e
-                                            );
-                                            None
-                                        } else {
-                                            editor
-                                                .set_unsaved_buffer_file(Some(buffer_file.clone()));
-                                            Some(Box::new(editor) as Box<dyn Panel>)
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!(
-                                        "Warning: Failed to load unsaved buffer {}: {}",
-                                        buffer_file, e
-                                    );
-                                    None
-                                }
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    SessionPanel::Terminal { working_dir } => {
-                        Terminal::new_with_cwd(term_height, term_width, Some(working_dir))
-                            .ok()
-                            .map(|t| Box::new(t) as Box<dyn Panel>)
-                    }
-                    SessionPanel::Debug => Some(Box::new(LogViewerPanel::default())),
-                };
-
+            // Panels that fail to restore (deleted files) are skipped like in to_session
+            for (idx, session_panel) in session_group.panels.into_iter().enumerate() {
+                // Editors of a file restored before become split views of it
+                let panel = split_restored_editor(&session_panel, &layout, &panels).or_else(|| {
+                    restore_panel(
+                        session_panel,
+                        session_dir,
+                        term_height,
+                        term_width,
+                        &editor_config,
+                        terminal_settings,
+                        registry,
+                    )
+                });
                 if let Some(p) = panel {
+                    if idx <= session_group.expanded_index {
+                        expanded_index = panels.len();
+                    }
                     panels.push(p);
                 }
             }
@@ -140,20 +123,336 @@ impl LayoutManagerSession for LayoutManager {
             for panel in panels {
                 group.add_panel(panel);
             }
-
-            let expanded_idx = session_group
-                .expanded_index
-                .min(group.len().saturating_sub(1));
-            group.set_expanded(expanded_idx);
+            group.set_expanded(expanded_index);
             group.width = session_group.width;
+            group.tabs = session_group.tabs;
 
+            if group_idx <= session.focused_group {
+                layout.focus = layout.panel_groups.len();
+            }
             layout.panel_groups.push(group);
         }
 
-        layout.focus = session
-            .focused_group
-            .min(layout.panel_groups.len().saturating_sub(1));
-
         Ok(layout)
     }
 }
+
+/// Split view of an editor already restored for the file of `session_panel`
+fn split_restored_editor(
+    session_panel: &SessionPanel,
+    layout: &LayoutManager,
+    panels: &[Box<dyn Panel>],
+) -> Option<Box<dyn Panel>> {
+    let SessionPanel::Editor {
+        path: Some(path),
+        syntax,
+        ..
+    } = session_panel
+    else {
+        return None;
+    };
+    let restored = layout
+        .panel_groups
+        .iter()
+        .flat_map(|group| group.panels())
+        .chain(panels)
+        .filter_map(|panel| panel.as_any().downcast_ref::<Editor>())
+        .find(|editor| editor.file_path() == Some(path.as_path()))?;
+    let mut editor = restored.split_view();
+    if let Some(language) = syntax {
+        editor.set_syntax_override(language);
+    }
+    Some(Box::new(editor))
+}
+
+/// Create a panel from its session data (None if it cannot be restored)
+fn restore_panel(
+    session_panel: SessionPanel,
+    session_dir: &Path,
+    term_height: u16,
+    term_width: u16,
+    editor_config: &EditorConfig,
+    terminal_settings: &TerminalSettings,
+    registry: &PanelRegistry,
+) -> Option<Box<dyn Panel>> {
+    match session_panel {
+        SessionPanel::FileManager { path, views } => {
+            Some(Box::new(FileManager::new_with_views(path, views)))
+        }
+        SessionPanel::Editor {
+            path,
+            unsaved_buffer_file,
+            syntax,
+        } => {
+            if let Some(file_path) = path {
+                Editor::open_file_with_config(file_path, editor_config.clone())
+                    .ok()
+                    .map(|mut editor| {
+                        if let Some(language) = &syntax {
+                            editor.set_syntax_override(language);
+                        }
+                        Box::new(editor) as Box<dyn Panel>
+                    })
+            } else if let Some(ref buffer_file) = unsaved_buffer_file {
+                match load_unsaved_buffer(session_dir, buffer_file) {
+                    Ok(content) => {
+                        if content.trim().is_empty() {
+                            let _ = cleanup_unsaved_buffer(session_dir, buffer_file);
+                            None
+                        } else {
+                            let mut editor = Editor::with_config(editor_config.clone());
+                            if let Err(e) = editor.insert_text(&content) {
+                                eprintln!(
+                                    "Warning: Failed to restore unsaved buffer content: {}",
+                                    e
+                                );
+                                None
+                            } else {
+                                editor.set_unsaved_buffer_file(Some(buffer_file.clone()));
+                                if let Some(language) = &syntax {
+                                    editor.set_syntax_override(language);
+                                }
+                                Some(Box::new(editor) as Box<dyn Panel>)
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: Failed to load unsaved buffer {}: {}",
+                            buffer_file, e
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            }
+        }
+        SessionPanel::Terminal {
+            working_dir,
+            command,
+        } => match command {
+            Some(command) => Terminal::new_with_command(
+                term_height,
+                term_width,
+                Some(working_dir),
+                terminal_settings,
+                command,
+            ),
+            None => Terminal::new_with_cwd(
+                term_height,
+                term_width,
+                Some(working_dir),
+                terminal_settings,
+            ),
+        }
+        .ok()
+        .map(|t| Box::new(t) as Box<dyn Panel>),
+        SessionPanel::Debug => Some(Box::new(LogViewerPanel::default())),
+        SessionPanel::LogFile { path } => LogViewerPanel::open_file(path)
+            .ok()
+            .map(|viewer| Box::new(viewer) as Box<dyn Panel>),
+        SessionPanel::Git { path } => Some(Box::new(GitPanel::new(path))),
+        // Types no longer registered are dropped
+        SessionPanel::Custom { name, params } => match registry.create(&name, &params) {
+            Ok(panel) => Some(panel),
+            Err(e) => {
+                eprintln!("Warning: Failed to restore {} panel: {}", name, e);
+                None
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use termide_panel_misc::WelcomePanel;
+
+    fn group(panels: Vec<Box<dyn Panel>>, expanded: usize, width: Option<u16>) -> PanelGroup {
+        let mut panels = panels.into_iter();
+        let mut group = PanelGroup::new(panels.next().unwrap());
+        for panel in panels {
+            group.add_panel(panel);
+        }
+        group.set_expanded(expanded);
+        group.width = width;
+        group
+    }
+
+    fn file_manager(path: &Path) -> Box<dyn Panel> {
+        Box::new(FileManager::new_with_path(path.to_path_buf()))
+    }
+
+    fn log() -> Box<dyn Panel> {
+        Box::new(LogViewerPanel::default())
+    }
+
+    fn restore(session: &Session, session_dir: &Path) -> LayoutManager {
+        restore_with(session, session_dir, &PanelRegistry::new())
+    }
+
+    fn restore_with(
+        session: &Session,
+        session_dir: &Path,
+        registry: &PanelRegistry,
+    ) -> LayoutManager {
+        // Go through the file format like a real save/load
+        let text = toml::to_string_pretty(session).unwrap();
+        let parsed: Session = toml::from_str(&text).unwrap();
+        assert_eq!(&parsed, session);
+        LayoutManager::from_session(
+            parsed,
+            session_dir,
+            24,
+            80,
+            EditorConfig::default(),
+            &TerminalSettings::default(),
+            registry,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_three_group_layout_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let (left, right) = (dir.path().join("left"), dir.path().join("right"));
+        std::fs::create_dir(&left).unwrap();
+        std::fs::create_dir(&right).unwrap();
+
+        let mut layout = LayoutManager::new();
+        layout
+            .panel_groups
+            .push(group(vec![file_manager(&left)], 0, Some(50)));
+        // Stacked group with the second panel expanded, shown as tabs
+        let mut tabbed = group(vec![log(), file_manager(&right), log()], 1, Some(70));
+        tabbed.tabs = true;
+        layout.panel_groups.push(tabbed);
+        layout
+            .panel_groups
+            .push(group(vec![file_manager(&right)], 0, None));
+        layout.focus = 1;
+
+        let session = layout.to_session(dir.path());
+        assert_eq!(session.panel_groups.len(), 3);
+        assert_eq!(session.focused_group, 1);
+
+        let mut restored = restore(&session, dir.path());
+        assert_eq!(restored.focus, 1);
+        let shape: Vec<(usize, usize, Option<u16>, bool)> = restored
+            .panel_groups
+            .iter()
+            .map(|g| (g.len(), g.expanded_index(), g.width, g.tabs))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                (1, 0, Some(50), false),
+                (3, 1, Some(70), true),
+                (1, 0, None, false)
+            ]
+        );
+        assert_eq!(restored.to_session(dir.path()), session);
+    }
+
+    #[test]
+    fn test_unsaved_panels_keep_focus_and_expanded_panel() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut layout = LayoutManager::new();
+        // A group with only a Welcome panel is not saved at all
+        layout
+            .panel_groups
+            .push(group(vec![Box::new(WelcomePanel::new())], 0, None));
+        layout.panel_groups.push(group(
+            vec![
+                Box::new(WelcomePanel::new()),
+                log(),
+                file_manager(dir.path()),
+            ],
+            2,
+            None,
+        ));
+        layout.focus = 1;
+
+        let session = layout.to_session(dir.path());
+        assert_eq!(session.panel_groups.len(), 1);
+        assert_eq!(session.focused_group, 0);
+        assert_eq!(session.panel_groups[0].expanded_index, 1);
+
+        // An editor whose file is gone is skipped on restore
+        let mut session = session;
+        session.panel_groups[0].panels.insert(
+            0,
+            SessionPanel::Editor {
+                path: Some(dir.path().join("deleted.txt")),
+                unsaved_buffer_file: None,
+                syntax: None,
+            },
+        );
+        session.panel_groups[0].expanded_index = 2;
+        let layout = restore(&session, dir.path());
+        assert_eq!(layout.panel_groups[0].len(), 2);
+        assert_eq!(layout.panel_groups[0].expanded_index(), 1);
+    }
+
+    #[test]
+    fn test_registered_panels_restored_through_registry() {
+        use termide_app_panel::{PanelParams, PanelRegistration};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = PanelRegistry::new();
+        registry
+            .register(PanelRegistration::new(
+                "browser",
+                "Browser",
+                |params: &PanelParams| {
+                    let path = params.get("path").map(PathBuf::from).unwrap_or_default();
+                    Ok(Box::new(FileManager::new_with_path(path)) as Box<dyn Panel>)
+                },
+            ))
+            .unwrap();
+
+        let custom = |name: &str| SessionPanel::Custom {
+            name: name.to_string(),
+            params: PanelParams::from([("path".to_string(), dir.path().display().to_string())]),
+        };
+        let session = Session {
+            panel_groups: vec![SessionPanelGroup {
+                // Types that are not registered are dropped
+                panels: vec![custom("browser"), custom("unknown")],
+                expanded_index: 0,
+                width: None,
+                tabs: false,
+            }],
+            focused_group: 0,
+            search_history: Vec::new(),
+            bookmarks: Vec::new(),
+        };
+        let mut layout = restore_with(&session, dir.path(), &registry);
+        let panels = layout.panel_groups[0].panels_mut();
+        assert_eq!(panels.len(), 1);
+        assert_eq!(
+            panels[0].get_working_directory().as_deref(),
+            Some(dir.path())
+        );
+    }
+
+    #[test]
+    fn test_session_without_layout_fields_loads() {
+        let session: Session = toml::from_str(
+            r#"
+            [[panel_groups]]
+            [[panel_groups.panels]]
+            type = "debug"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(session.focused_group, 0);
+        assert_eq!(session.panel_groups[0].expanded_index, 0);
+        assert_eq!(session.panel_groups[0].width, None);
+        assert!(!session.panel_groups[0].tabs);
+    }
+}
@@ -34,7 +34,7 @@ pub mod panel_ext;
 pub mod state;
 
 // Re-export main types for convenience
-pub use app::App;
+pub use app::{App, StartupTarget};
 pub use layout_session::LayoutManagerSession;
 #[allow(deprecated)]
 pub use panel_ext::PanelExt;
@@ -55,7 +55,9 @@ pub use termide_app_core::{
     AppCommand, Direction, LayoutController, Message, ModalManager, PanelProvider, PanelType,
     StateManager,
 };
-pub use termide_app_event::{DefaultHotkeyProcessor, HotkeyAction, HotkeyProcessor};
+pub use termide_app_event::{
+    DefaultHotkeyProcessor, HotkeyAction, HotkeyMatch, HotkeyProcessor, KeySequence,
+};
 pub use termide_app_modal::{BatchOperationProcessor, BatchOperationState, ConflictResult};
 pub use termide_app_panel::{CloseDecision, ConfirmationType, PanelFactory, PanelLifecycle};
 pub use termide_app_session::{AutoSaveConfig, SessionManager, SessionState};
@@ -181,8 +183,10 @@ impl DefaultCommandExecutor {
 
             // Commands that need external handling
             AppCommand::OpenModal { .. }
+            | AppCommand::Notify { .. }
             | AppCommand::CloseModal
             | AppCommand::CreatePanel { .. }
+            | AppCommand::OpenFile { .. }
             | AppCommand::SaveSession
             | AppCommand::PanelEvent { .. } => {
                 // These require access to components not in AppContext
@@ -252,20 +256,24 @@ impl LoopState {
 
 /// Process hotkey and convert to command.
 pub fn process_hotkey(
-    processor: &impl HotkeyProcessor,
+    processor: &mut impl HotkeyProcessor,
     key: &crossterm::event::KeyEvent,
 ) -> Option<AppCommand> {
-    processor
-        .process_hotkey(key)
-        .and_then(|action| action.to_command())
+    match processor.process_hotkey(key) {
+        HotkeyMatch::Action(action) => action.to_command(),
+        _ => None,
+    }
 }
 
-/// Check if key event is a global hotkey.
+/// Check if key event is a global hotkey (or a chord prefix).
 pub fn is_global_hotkey(
-    processor: &impl HotkeyProcessor,
+    processor: &mut impl HotkeyProcessor,
     key: &crossterm::event::KeyEvent,
 ) -> bool {
-    processor.process_hotkey(key).is_some()
+    matches!(
+        processor.process_hotkey(key),
+        HotkeyMatch::Action(_) | HotkeyMatch::Pending
+    )
 }
 
 // ============================================================================
@@ -363,14 +371,14 @@ mod tests {
 
     #[test]
     fn test_process_hotkey() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
 
         // Alt+Q should produce Quit command
         let key = crossterm::event::KeyEvent::new(
             crossterm::event::KeyCode::Char('q'),
             crossterm::event::KeyModifiers::ALT,
         );
-        let cmd = process_hotkey(&processor, &key);
+        let cmd = process_hotkey(&mut processor, &key);
         assert!(matches!(cmd, Some(AppCommand::Quit)));
 
         // Regular key should not produce command
@@ -378,26 +386,26 @@ mod tests {
             crossterm::event::KeyCode::Char('a'),
             crossterm::event::KeyModifiers::NONE,
         );
-        let cmd = process_hotkey(&processor, &key);
+        let cmd = process_hotkey(&mut processor, &key);
         assert!(cmd.is_none());
     }
 
     #[test]
     fn test_is_global_hotkey() {
-        let processor = DefaultHotkeyProcessor::new();
+        let mut processor = DefaultHotkeyProcessor::new();
 
         // Alt+M is a hotkey
         let key = crossterm::event::KeyEvent::new(
             crossterm::event::KeyCode::Char('m'),
             crossterm::event::KeyModifiers::ALT,
         );
-        assert!(is_global_hotkey(&processor, &key));
+        assert!(is_global_hotkey(&mut processor, &key));
 
         // Regular 'm' is not
         let key = crossterm::event::KeyEvent::new(
             crossterm::event::KeyCode::Char('m'),
             crossterm::event::KeyModifiers::NONE,
         );
-        assert!(!is_global_hotkey(&processor, &key));
+        assert!(!is_global_hotkey(&mut processor, &key));
     }
 }
@@ -34,7 +34,7 @@ use termide_core::Panel;
 use termide_modal::ActiveModal;
 use termide_panel_editor::Editor;
 use termide_panel_file_manager::FileManager;
-use termide_panel_misc::LogViewerPanel;
+use termide_panel_misc::{DuplicatesPanel, GitPanel, LogViewerPanel};
 use termide_panel_terminal::Terminal;
 use termide_state::PendingAction;
 
@@ -59,9 +59,9 @@ pub trait PanelExt {
     fn as_file_manager_mut(&mut self) -> Option<&mut FileManager>;
     /// Downcast to Terminal (mutable)
     fn as_terminal_mut(&mut self) -> Option<&mut Terminal>;
-    /// Check if panel is a LogViewer
+    /// Check if panel is a LogViewer showing the application log
     fn is_log_viewer(&self) -> bool;
-    /// Take modal request from FileManager or Editor panels.
+    /// Take modal request from FileManager, Editor, Git or Duplicates panels.
     fn take_modal_request(&mut self) -> Option<(PendingAction, ActiveModal)>;
 }
 
@@ -84,7 +84,10 @@ impl PanelExt for dyn Panel {
     }
 
     fn is_log_viewer(&self) -> bool {
-        (self as &dyn Any).is::<LogViewerPanel>()
+        // Viewers following log files on disk are not the Log panel
+        (self as &dyn Any)
+            .downcast_ref::<LogViewerPanel>()
+            .is_some_and(|viewer| viewer.file_path().is_none())
     }
 
     fn take_modal_request(&mut self) -> Option<(PendingAction, ActiveModal)> {
@@ -94,6 +97,12 @@ impl PanelExt for dyn Panel {
         if let Some(editor) = self.as_editor_mut() {
             return editor.take_modal_request();
         }
+        if let Some(git) = (self as &mut dyn Any).downcast_mut::<GitPanel>() {
+            return git.take_modal_request();
+        }
+        if let Some(duplicates) = (self as &mut dyn Any).downcast_mut::<DuplicatesPanel>() {
+            return duplicates.take_modal_request();
+        }
         None
     }
 }
@@ -6,28 +6,81 @@
 //! Implements core traits from termide-app-core for standardized
 //! state management and modal handling.
 
-use std::sync::mpsc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
 
-use termide_config::constants::DEFAULT_MAIN_PANEL_WIDTH;
-use termide_config::Config;
+use termide_config::constants::{DEFAULT_MAIN_PANEL_WIDTH, MEGABYTE};
+use termide_config::{Config, ProjectConfigs, ThemeMode, TrimTrailingWhitespace};
+use termide_core::StatusKind;
 use termide_git::{GitStatusUpdate, GitWatcher};
-use termide_panel_editor::EditorConfig;
+use termide_panel_editor::{EditorConfig, SaveTransforms, SearchHistory, WriteOptions};
+use termide_session::Bookmarks;
+use termide_spell::Dictionary;
 use termide_system_monitor::SystemMonitor;
 use termide_theme::Theme;
 use termide_watcher::{DirectoryUpdate, FileSystemWatcher};
 
+use termide_app_watcher::{MessageCollector, UpdateThrottler};
+
+/// Minimum time between panel refreshes for changes in one directory
+const FS_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
 // Import core traits
 use termide_app_core::{ModalManager, StateManager};
 
 // Re-export pure types from state crate
 pub use termide_state::{
-    BatchOperation, BatchOperationType, ConflictMode, DirSizeResult, LayoutInfo, LayoutMode,
-    PendingAction, RenamePattern, TerminalState, UiState,
+    BatchOperation, BatchOperationType, BatchProgressUpdate, ChecksumUpdate, ConfigWizardStep,
+    ConflictMode, DeleteProgressUpdate, DeleteSummary, DirSizeResult, LayoutInfo, LayoutMode,
+    NotificationLevel, Notifications, PendingAction, PermissionsProgressUpdate, PermissionsReport,
+    RenamePattern, TerminalState, UiState,
 };
 
 // Re-export ActiveModal from modal crate
 pub use termide_modal::ActiveModal;
 
+/// Batch operation item being copied/moved on a worker thread
+#[derive(Debug)]
+pub struct BatchTransfer {
+    /// Operation the item belongs to (progress counters are updated here)
+    pub operation: BatchOperation,
+    /// Progress updates from the worker
+    pub receiver: mpsc::Receiver<BatchProgressUpdate>,
+    /// Name of the item being transferred
+    pub item_name: String,
+}
+
+/// Permanent deletion running on a worker thread
+#[derive(Debug)]
+pub struct BatchDelete {
+    /// Paths being deleted
+    pub paths: Vec<PathBuf>,
+    /// Progress updates from the worker
+    pub receiver: mpsc::Receiver<DeleteProgressUpdate>,
+    /// Estimated number of entries to remove
+    pub total: usize,
+    /// Entries removed so far
+    pub removed: usize,
+    /// Path being removed
+    pub current: PathBuf,
+}
+
+/// Recursive permissions change running on a worker thread
+#[derive(Debug)]
+pub struct BatchPermissions {
+    /// Paths being changed
+    pub paths: Vec<PathBuf>,
+    /// Progress updates from the worker
+    pub receiver: mpsc::Receiver<PermissionsProgressUpdate>,
+    /// Estimated number of items to change
+    pub total: usize,
+    /// Items changed so far
+    pub changed: usize,
+    /// Path being changed
+    pub current: PathBuf,
+}
+
 /// Global application state
 #[derive(Debug)]
 pub struct AppState {
@@ -47,6 +100,14 @@ pub struct AppState {
     pub pending_action: Option<PendingAction>,
     /// Receiver channel for background directory size calculation results
     pub dir_size_receiver: Option<mpsc::Receiver<DirSizeResult>>,
+    /// Receiver channel for the checksum of the file shown in the Info modal
+    pub checksum_receiver: Option<mpsc::Receiver<ChecksumUpdate>>,
+    /// Batch copy/move item currently running in background
+    pub batch_transfer: Option<BatchTransfer>,
+    /// Deletion currently running in background
+    pub batch_delete: Option<BatchDelete>,
+    /// Active background permissions change (if any)
+    pub batch_permissions: Option<BatchPermissions>,
     /// Receiver channel for git status update events
     pub git_watcher_receiver: Option<mpsc::Receiver<GitStatusUpdate>>,
     /// Git watcher instance (kept alive for cleanup)
@@ -55,10 +116,18 @@ pub struct AppState {
     pub fs_watcher_receiver: Option<mpsc::Receiver<DirectoryUpdate>>,
     /// Filesystem watcher instance (kept alive for cleanup)
     pub fs_watcher: Option<FileSystemWatcher>,
+    /// Filesystem changes waiting to reach panels, one per directory
+    pub fs_updates: MessageCollector,
+    /// Limits panel refreshes to one per directory and interval
+    pub fs_throttler: UpdateThrottler,
     /// Current theme
     pub theme: &'static Theme,
+    /// Whether the terminal background is light (None until detected)
+    pub terminal_light: Option<bool>,
     /// Application configuration
     pub config: Config,
+    /// Project settings files (`.termide.toml`) read so far
+    pub project_configs: ProjectConfigs,
     /// System resource monitor (CPU, RAM)
     pub system_monitor: SystemMonitor,
     /// Last time system resources were updated
@@ -69,6 +138,20 @@ pub struct AppState {
     pub needs_redraw: bool,
     /// Last time spinner was updated (for throttling spinner animation)
     pub last_spinner_update: Option<std::time::Instant>,
+    /// Status bar segments shown on the left
+    pub status_bar_left: Vec<StatusKind>,
+    /// Status bar segments shown on the right
+    pub status_bar_right: Vec<StatusKind>,
+    /// Checked out branch of each repository (for the status bar)
+    pub git_branches: HashMap<PathBuf, Option<String>>,
+    /// Spelling dictionary shared by the editors (read on first use)
+    pub spell_dictionary: Arc<Dictionary>,
+    /// Queries of the editors' search modal, saved with the session
+    pub search_history: SearchHistory,
+    /// Bookmarks of the project, saved with the session
+    pub bookmarks: Bookmarks,
+    /// Notification toasts and history
+    pub notifications: Notifications,
 }
 
 impl Default for AppState {
@@ -96,6 +179,13 @@ impl AppState {
             main_panel_width: DEFAULT_MAIN_PANEL_WIDTH,
         };
 
+        let (status_bar_left, _) = parse_status_segments(&config.general.status_bar_left);
+        let (status_bar_right, _) = parse_status_segments(&config.general.status_bar_right);
+        let spell_dictionary = Arc::new(spell_dictionary(&config));
+        let notifications = Notifications::new(std::time::Duration::from_secs(
+            config.general.notification_timeout,
+        ));
+
         Self {
             should_quit: false,
             ui: UiState::default(),
@@ -105,24 +195,79 @@ impl AppState {
             active_modal: None,
             pending_action: None,
             dir_size_receiver: None,
+            checksum_receiver: None,
+            batch_transfer: None,
+            batch_delete: None,
+            batch_permissions: None,
             git_watcher_receiver: None,
             git_watcher: None,
             fs_watcher_receiver: None,
             fs_watcher: None,
+            fs_updates: MessageCollector::new(),
+            fs_throttler: UpdateThrottler::new(FS_UPDATE_INTERVAL),
             theme,
+            terminal_light: None,
             config,
             system_monitor: SystemMonitor::new(),
             last_resource_update: std::time::Instant::now(),
             last_session_save: None,
             needs_redraw: true, // Initial draw needed
             last_spinner_update: None,
+            status_bar_left,
+            status_bar_right,
+            git_branches: HashMap::new(),
+            spell_dictionary,
+            project_configs: ProjectConfigs::new(),
+            search_history: SearchHistory::new(),
+            bookmarks: Bookmarks::default(),
+            notifications,
         }
     }
 
     /// Set new theme and update config
     pub fn set_theme(&mut self, theme_name: &str) {
-        self.theme = Theme::get_by_name(theme_name);
         self.config.general.theme = theme_name.to_string();
+        self.apply_theme();
+    }
+
+    /// Whether a light (true) or dark (false) theme is wanted by the theme
+    /// mode, None if any theme goes
+    pub fn wanted_light_theme(&self) -> Option<bool> {
+        match self.config.general.theme_mode {
+            ThemeMode::Fixed => None,
+            ThemeMode::Auto => self.terminal_light,
+            ThemeMode::Light => Some(true),
+            ThemeMode::Dark => Some(false),
+        }
+    }
+
+    /// Name of the theme in use: the configured theme, or the configured
+    /// light or dark theme when its background does not match the mode
+    pub fn active_theme_name(&self) -> &str {
+        let general = &self.config.general;
+        match self.wanted_light_theme() {
+            Some(light) if Theme::get_by_name(&general.theme).is_light() != light => {
+                if light {
+                    &general.light_theme
+                } else {
+                    &general.dark_theme
+                }
+            }
+            _ => &general.theme,
+        }
+    }
+
+    /// Apply the theme from config with the highlight styles of its file,
+    /// logging problems found in theme files
+    pub fn apply_theme(&mut self) {
+        self.theme = Theme::get_by_name(self.active_theme_name());
+        for warning in termide_theme::take_warnings() {
+            termide_logger::warn(warning);
+        }
+        // Bumps the highlight styles generation, so editors restyle
+        // their cached lines for the new palette
+        self.apply_highlight_styles();
+        self.needs_redraw = true;
     }
 
     /// Request application quit
@@ -227,12 +372,102 @@ impl AppState {
         self.ui.status_message = None;
     }
 
+    /// Show a notification toast with the default timeout of its level
+    pub fn notify(&mut self, text: String, level: NotificationLevel) {
+        self.notifications
+            .push(text, level, None, std::time::Instant::now());
+        self.needs_redraw = true;
+    }
+
+    /// Apply syntax highlight styles from the theme file and config (config
+    /// entries win), reporting invalid entries
+    pub fn apply_highlight_styles(&mut self) {
+        let theme_styles = termide_theme::highlight_styles(self.active_theme_name());
+        let errors = termide_highlight::global_highlighter().set_style_overrides(
+            theme_styles
+                .iter()
+                .map(|(name, spec)| (name.as_str(), spec.as_str()))
+                .chain(
+                    self.config
+                        .highlight
+                        .iter()
+                        .map(|(name, spec)| (name.as_str(), spec.as_str())),
+                ),
+        );
+        if !errors.is_empty() {
+            self.set_error(termide_i18n::t().error_highlight_styles(&errors.join("; ")));
+        }
+    }
+
+    /// Apply status bar segments from config, logging unknown names
+    pub fn apply_status_bar(&mut self) {
+        let (left, unknown_left) = parse_status_segments(&self.config.general.status_bar_left);
+        let (right, unknown_right) = parse_status_segments(&self.config.general.status_bar_right);
+        for name in unknown_left.iter().chain(&unknown_right) {
+            termide_logger::warn(format!("Unknown status bar segment '{}' skipped", name));
+        }
+        self.status_bar_left = left;
+        self.status_bar_right = right;
+    }
+
+    /// Use the dictionaries of the config (after it was changed)
+    pub fn apply_spell_dictionaries(&mut self) {
+        let dictionary = spell_dictionary(&self.config);
+        if dictionary.paths() != self.spell_dictionary.paths() {
+            self.spell_dictionary = Arc::new(dictionary);
+        }
+    }
+
     /// Create EditorConfig with settings from global config
     pub fn editor_config(&self) -> EditorConfig {
-        let mut config = EditorConfig::default();
-        config.tab_size = self.config.editor.tab_size;
-        config.word_wrap = self.config.editor.word_wrap;
-        config
+        self.editor_config_from(&self.config)
+    }
+
+    /// Create EditorConfig for a file, with the settings of the project it
+    /// belongs to (`.termide.toml`) over the global config
+    pub fn editor_config_for(&mut self, path: &Path) -> EditorConfig {
+        let config = self.project_configs.config_for(&self.config, path);
+        self.editor_config_from(&config)
+    }
+
+    /// Global config with the project settings of `path` over it
+    pub fn config_for(&mut self, path: &Path) -> Config {
+        self.project_configs.config_for(&self.config, path)
+    }
+
+    fn editor_config_from(&self, settings: &Config) -> EditorConfig {
+        let editor = &settings.editor;
+        EditorConfig {
+            tab_size: editor.tab_size,
+            indent_style: editor.indent_style,
+            word_wrap: editor.word_wrap,
+            visual_line_navigation: editor.visual_line_navigation,
+            save_transforms: SaveTransforms {
+                trim_trailing_whitespace: editor.trim_trailing_whitespace
+                    != TrimTrailingWhitespace::Off,
+                trim_modified_lines_only: editor.trim_trailing_whitespace
+                    == TrimTrailingWhitespace::Modified,
+                ensure_final_newline: editor.ensure_final_newline,
+            },
+            large_file_threshold_bytes: editor.large_file_threshold_mb * MEGABYTE,
+            large_file_threshold_lines: editor.large_file_threshold_lines,
+            max_highlight_columns: editor.max_highlight_columns,
+            auto_close_brackets: editor.auto_close_brackets,
+            jump_list_size: editor.jump_list_size,
+            undo_tree_size: editor.undo_tree_size,
+            scroll_off: editor.scroll_off,
+            autocomplete: editor.autocomplete,
+            autocomplete_min_chars: editor.autocomplete_min_chars,
+            autocomplete_open_buffers: editor.autocomplete_open_buffers,
+            write_options: WriteOptions {
+                backup: editor.backup_on_save,
+            },
+            formatters: settings.format.commands.clone(),
+            format_on_save: settings.format.on_save.clone(),
+            spell_dictionary: editor.spell_check.then(|| self.spell_dictionary.clone()),
+            render_whitespace: editor.render_whitespace,
+            ..EditorConfig::default()
+        }
     }
 
     /// Check if enough time has passed since last session save (debounce check)
@@ -250,6 +485,39 @@ impl AppState {
     pub fn update_last_session_save(&mut self) {
         self.last_session_save = Some(std::time::Instant::now());
     }
+
+    /// Checked out branch of a repository, asking git on first use
+    /// (dropped from the cache when the repository changes)
+    pub fn git_branch(&mut self, repo_root: &std::path::Path) -> Option<&str> {
+        self.git_branches
+            .entry(repo_root.to_path_buf())
+            .or_insert_with(|| termide_git::current_branch(repo_root))
+            .as_deref()
+    }
+}
+
+/// Dictionary of the configured word lists and the user dictionary
+fn spell_dictionary(config: &Config) -> Dictionary {
+    let paths = config
+        .editor
+        .spell_dictionaries
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    Dictionary::new(paths, Config::dictionary_file_path().ok())
+}
+
+/// Status bar segment kinds for config names, with the unknown names
+fn parse_status_segments(names: &[String]) -> (Vec<StatusKind>, Vec<&str>) {
+    let mut kinds = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names {
+        match StatusKind::from_name(name) {
+            Some(kind) => kinds.push(kind),
+            None => unknown.push(name.as_str()),
+        }
+    }
+    (kinds, unknown)
 }
 
 // ============================================================================
@@ -308,3 +576,33 @@ impl ModalManager for AppState {
         self.pending_action.take()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_theme_follows_mode() {
+        let mut config = Config::default();
+        config.general.theme = "dracula".to_string();
+        config.general.light_theme = "solarized-light".to_string();
+        config.general.dark_theme = "nord".to_string();
+        let mut state = AppState::with_config_and_theme(config, Theme::get_by_name("dracula"));
+
+        assert_eq!(state.active_theme_name(), "dracula");
+
+        // A dark theme is kept while the background is unknown or dark
+        state.config.general.theme_mode = ThemeMode::Auto;
+        assert_eq!(state.active_theme_name(), "dracula");
+        state.terminal_light = Some(false);
+        assert_eq!(state.active_theme_name(), "dracula");
+        state.terminal_light = Some(true);
+        assert_eq!(state.active_theme_name(), "solarized-light");
+
+        state.config.general.theme_mode = ThemeMode::Dark;
+        state.config.general.theme = "github-light".to_string();
+        assert_eq!(state.active_theme_name(), "nord");
+        state.config.general.theme_mode = ThemeMode::Light;
+        assert_eq!(state.active_theme_name(), "github-light");
+    }
+}
@@ -0,0 +1,118 @@
+//! Positions that follow edits.
+//!
+//! An anchor is a character offset registered with a buffer, which moves it
+//! on every insertion and deletion (undo and redo included), so marks and
+//! diagnostics stay on the text they were placed on. The buffer only keeps
+//! weak references: dropping the last handle of an anchor unregisters it.
+
+use std::cell::Cell;
+use std::ops::Range;
+use std::rc::{Rc, Weak};
+
+/// Side an anchor keeps to when text is inserted at its position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Affinity {
+    /// Stay before the inserted text
+    Left,
+    /// Move past the inserted text, keeping to the character on the right
+    Right,
+}
+
+#[derive(Debug)]
+struct AnchorState {
+    offset: Cell<usize>,
+    affinity: Affinity,
+}
+
+/// Position in a buffer adjusted on every edit.
+///
+/// Created with `TextBuffer::anchor`; clones share the position.
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    state: Rc<AnchorState>,
+}
+
+impl Anchor {
+    /// Character offset in the buffer
+    pub fn char_offset(&self) -> usize {
+        self.state.offset.get()
+    }
+
+    /// Side kept when text is inserted at the anchor
+    pub fn affinity(&self) -> Affinity {
+        self.state.affinity
+    }
+}
+
+/// Anchors registered with a buffer.
+#[derive(Debug, Default)]
+pub(crate) struct Anchors {
+    registered: Vec<Weak<AnchorState>>,
+}
+
+impl Anchors {
+    /// Register an anchor at `offset`
+    pub(crate) fn create(&mut self, offset: usize, affinity: Affinity) -> Anchor {
+        let state = Rc::new(AnchorState {
+            offset: Cell::new(offset),
+            affinity,
+        });
+        self.registered.push(Rc::downgrade(&state));
+        Anchor { state }
+    }
+
+    /// Number of registered anchors (dropped ones go on the next edit)
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.registered.len()
+    }
+
+    /// Update live anchors, dropping the ones no longer used
+    fn adjust(&mut self, mut update: impl FnMut(&AnchorState) -> usize) {
+        self.registered.retain(|anchor| match anchor.upgrade() {
+            Some(state) => {
+                state.offset.set(update(&state));
+                true
+            }
+            None => false,
+        });
+    }
+
+    /// `len` characters were inserted at `at`
+    pub(crate) fn inserted(&mut self, at: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        self.adjust(|state| {
+            let offset = state.offset.get();
+            if offset > at || (offset == at && state.affinity == Affinity::Right) {
+                offset + len
+            } else {
+                offset
+            }
+        });
+    }
+
+    /// The characters in `range` were deleted; anchors inside it move to
+    /// its start
+    pub(crate) fn removed(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.adjust(|state| {
+            let offset = state.offset.get();
+            if offset >= range.end {
+                offset - range.len()
+            } else {
+                offset.min(range.start)
+            }
+        });
+    }
+}
+
+/// A copy of a buffer starts without anchors: they follow the original.
+impl Clone for Anchors {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
@@ -1,9 +1,21 @@
 use anyhow::{Context, Result};
 use ropey::Rope;
+use std::cell::RefCell;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Action, Cursor, History};
+use super::anchor::{Affinity, Anchor, Anchors};
+use super::comment::{is_commented, leading_whitespace, uncomment_ranges};
+use super::dirty::DirtyLines;
+use super::fold::LineEdit;
+use super::line_index::{ColumnUnit, LineIndex, LineIndexes, LineSlice, LineUnit, LONG_LINE_BYTES};
+use super::save::trailing_whitespace_len;
+use super::write::write_file;
+use super::{
+    indent_column, Action, CommentToggle, Cursor, History, IndentStyle, LineEnding, SaveCleanup,
+    SaveTransforms, TextEncoding, UndoBranch, WriteMethod, WriteOptions,
+};
 
 /// Text buffer based on Rope for efficient work with large files
 #[derive(Debug, Clone)]
@@ -18,13 +30,24 @@ pub struct TextBuffer {
     line_ending: LineEnding,
     /// Edit history for undo/redo
     history: History,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(clippy::upper_case_acronyms)]
-pub enum LineEnding {
-    LF,   // Unix \n
-    CRLF, // Windows \r\n
+    /// Indentation style detected on load (None if undetermined)
+    indent_style: Option<IndentStyle>,
+    /// Lines edited since load or last save
+    dirty_lines: DirtyLines,
+    /// CRLF and LF line break counts on disk (rope always stores \n)
+    disk_line_breaks: (usize, usize),
+    /// Incremented on every text edit
+    revision: u64,
+    /// Line edits not yet taken by the view (None if tracking is disabled)
+    line_edits: Option<Vec<LineEdit>>,
+    /// Encoding of the file on disk (used on save)
+    encoding: TextEncoding,
+    /// Invalid byte sequences were replaced with U+FFFD on load
+    decode_errors: bool,
+    /// Column indexes of long lines (built when first needed)
+    line_indexes: RefCell<LineIndexes>,
+    /// Positions moved along with edits
+    anchors: Anchors,
 }
 
 impl TextBuffer {
@@ -36,6 +59,15 @@ impl TextBuffer {
             modified: false,
             line_ending: LineEnding::LF,
             history: History::new(),
+            indent_style: None,
+            dirty_lines: DirtyLines::default(),
+            disk_line_breaks: (0, 0),
+            revision: 0,
+            line_edits: None,
+            encoding: TextEncoding::default(),
+            decode_errors: false,
+            line_indexes: RefCell::default(),
+            anchors: Anchors::default(),
         }
     }
 
@@ -47,87 +79,131 @@ impl TextBuffer {
             modified: false,
             line_ending: LineEnding::LF,
             history: History::new(),
+            indent_style: None,
+            dirty_lines: DirtyLines::default(),
+            disk_line_breaks: (0, 0),
+            revision: 0,
+            line_edits: None,
+            encoding: TextEncoding::default(),
+            decode_errors: false,
+            line_indexes: RefCell::default(),
+            anchors: Anchors::default(),
         }
     }
 
     /// Create buffer from text string.
+    ///
+    /// Line ending is detected the same way as for files.
     pub fn from_text(text: &str) -> Self {
+        let crlf_count = text.matches("\r\n").count();
+        let lf_count = text.matches('\n').count() - crlf_count;
+
+        // Keep \n in the rope; line ending is applied when saving
+        let rope = if crlf_count > 0 {
+            Rope::from_str(&text.replace("\r\n", "\n"))
+        } else {
+            Rope::from_str(text)
+        };
+
         Self {
-            rope: Rope::from_str(text),
+            rope,
             file_path: None,
             modified: false,
-            line_ending: LineEnding::LF,
+            // Majority wins, LF on ties
+            line_ending: if crlf_count > lf_count {
+                LineEnding::CRLF
+            } else {
+                LineEnding::LF
+            },
             history: History::new(),
+            indent_style: IndentStyle::detect(text.lines()),
+            dirty_lines: DirtyLines::default(),
+            disk_line_breaks: (crlf_count, lf_count),
+            revision: 0,
+            line_edits: None,
+            encoding: TextEncoding::default(),
+            decode_errors: false,
+            line_indexes: RefCell::default(),
+            anchors: Anchors::default(),
         }
     }
 
-    /// Load file
+    /// Load file, detecting its encoding
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
-        let contents = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        Self::load(path.as_ref(), None)
+    }
 
-        // Determine line ending type
-        let line_ending = if contents.contains("\r\n") {
-            LineEnding::CRLF
-        } else {
-            LineEnding::LF
-        };
+    /// Load file decoded with the given encoding
+    pub fn from_file_with_encoding<P: AsRef<Path>>(
+        path: P,
+        encoding: TextEncoding,
+    ) -> Result<Self> {
+        Self::load(path.as_ref(), Some(encoding))
+    }
 
-        // Rope automatically normalizes line endings to \n
-        let rope = Rope::from_str(&contents);
+    fn load(path: &Path, encoding: Option<TextEncoding>) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
-        Ok(Self {
-            rope,
-            file_path: Some(path.to_path_buf()),
-            modified: false,
-            line_ending,
-            history: History::new(),
-        })
+        let encoding = encoding.unwrap_or_else(|| TextEncoding::detect(&bytes));
+        let (contents, encoding, decode_errors) = encoding.decode(&bytes);
+        drop(bytes);
+
+        let mut buffer = Self::from_text(&contents);
+        buffer.file_path = Some(path.to_path_buf());
+        buffer.encoding = encoding;
+        buffer.decode_errors = decode_errors;
+        Ok(buffer)
     }
 
     /// Save file
-    pub fn save(&mut self) -> Result<()> {
+    pub fn save(&mut self, options: WriteOptions) -> Result<WriteMethod> {
         if let Some(path) = self.file_path.clone() {
-            self.save_to(&path)?;
+            let method = self.save_to(&path, options)?;
             self.modified = false;
-            Ok(())
+            Ok(method)
         } else {
             anyhow::bail!("No file path set")
         }
     }
 
     /// Save to specified file
-    pub fn save_to<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+    pub fn save_to<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: WriteOptions,
+    ) -> Result<WriteMethod> {
         let path = path.as_ref();
-        let mut contents = String::new();
+        let contents = self.encoding.encode(&self.contents_for_save())?;
+
+        let method = write_file(path, &contents, options)?;
+
+        self.file_path = Some(path.to_path_buf());
+        self.modified = false;
+        self.dirty_lines.clear();
+        self.disk_line_breaks = (0, 0);
+        self.decode_errors = false;
+        Ok(method)
+    }
+
+    /// Buffer text with line breaks converted to the buffer's line ending
+    fn contents_for_save(&self) -> String {
+        let line_break = self.line_ending.as_str();
 
-        // Collect text with appropriate line endings
         // rope.lines() returns lines with '\n' at the end (except possibly the last line)
-        // We need to replace '\n' with the appropriate line ending
+        let mut contents = String::with_capacity(self.rope.len_bytes());
         for line in self.rope.lines() {
             let line_str = line.to_string();
-
-            // If line ends with '\n', replace it with the appropriate line ending
-            if line_str.ends_with('\n') {
-                let line_without_newline = &line_str[..line_str.len() - 1];
-                contents.push_str(line_without_newline);
-                match self.line_ending {
-                    LineEnding::LF => contents.push('\n'),
-                    LineEnding::CRLF => contents.push_str("\r\n"),
+            match line_str.strip_suffix('\n') {
+                Some(text) => {
+                    contents.push_str(text.strip_suffix('\r').unwrap_or(text));
+                    contents.push_str(line_break);
                 }
-            } else {
                 // Last line without '\n' - add as is
-                contents.push_str(&line_str);
+                None => contents.push_str(&line_str),
             }
         }
-
-        std::fs::write(path, contents)
-            .with_context(|| format!("Failed to write file: {}", path.display()))?;
-
-        self.file_path = Some(path.to_path_buf());
-        self.modified = false;
-        Ok(())
+        contents
     }
 
     /// Check if buffer content differs from file on disk
@@ -138,11 +214,11 @@ impl TextBuffer {
         };
 
         // Try to read file content
-        match std::fs::read_to_string(path) {
+        match std::fs::read(path) {
             Ok(file_content) => {
-                // Compare buffer content with file content
-                let buffer_content = self.rope.to_string();
-                Ok(buffer_content != file_content)
+                // Compare content as it would be saved with file content
+                let saved = self.encoding.encode(&self.contents_for_save());
+                Ok(saved.map_or(true, |saved| saved != file_content))
             }
             Err(_) => {
                 // If can't read file (deleted, permissions, etc.), keep current flag
@@ -151,11 +227,34 @@ impl TextBuffer {
         }
     }
 
+    /// Encoding the file is saved in
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    /// Save the file in another encoding (marks the buffer modified)
+    pub fn set_encoding(&mut self, encoding: TextEncoding) {
+        if encoding != self.encoding {
+            self.encoding = encoding;
+            self.modified = true;
+        }
+    }
+
+    /// Check if invalid bytes were replaced when the file was loaded
+    pub fn had_decode_errors(&self) -> bool {
+        self.decode_errors
+    }
+
     /// Get line count
     pub fn line_count(&self) -> usize {
         self.rope.len_lines()
     }
 
+    /// Get text length in bytes
+    pub fn len_bytes(&self) -> usize {
+        self.rope.len_bytes()
+    }
+
     /// Get line by index
     pub fn line(&self, index: usize) -> Option<String> {
         if index < self.line_count() {
@@ -167,6 +266,9 @@ impl TextBuffer {
 
     /// Get line length in graphemes (without newline character)
     pub fn line_len_graphemes(&self, line_idx: usize) -> usize {
+        if let Some(index) = self.line_index(line_idx) {
+            return index.len();
+        }
         if let Some(line) = self.line(line_idx) {
             // Remove newline character before counting
             line.trim_end_matches('\n').graphemes(true).count()
@@ -175,6 +277,94 @@ impl TextBuffer {
         }
     }
 
+    /// Check if a line is longer than `LONG_LINE_BYTES`, so that its
+    /// columns are found through an index instead of walking the line
+    pub fn is_long_line(&self, line_idx: usize) -> bool {
+        self.line_text_len(line_idx) > LONG_LINE_BYTES
+    }
+
+    /// Check if any line is longer than `LONG_LINE_BYTES` (such documents
+    /// are too slow to parse as a whole)
+    pub fn has_long_lines(&self) -> bool {
+        if self.len_bytes() <= LONG_LINE_BYTES {
+            return false;
+        }
+        self.line_indexes.borrow_mut().has_long_lines(|| {
+            self.rope
+                .lines()
+                .any(|line| text_len(line) > LONG_LINE_BYTES)
+        })
+    }
+
+    /// Graphemes of a line in `graphemes` (clamped to the line)
+    pub fn line_slice(&self, line_idx: usize, graphemes: Range<usize>) -> Option<LineSlice> {
+        self.slice_line(line_idx, LineUnit::Graphemes, graphemes)
+    }
+
+    /// Graphemes of a line starting at display columns in `columns`
+    pub fn line_slice_columns(&self, line_idx: usize, columns: Range<usize>) -> Option<LineSlice> {
+        self.slice_line(line_idx, LineUnit::Columns, columns)
+    }
+
+    fn slice_line(
+        &self,
+        line_idx: usize,
+        unit: LineUnit,
+        range: Range<usize>,
+    ) -> Option<LineSlice> {
+        if line_idx >= self.line_count() {
+            return None;
+        }
+        let text_len = self.line_text_len(line_idx);
+        let Some(index) = self.line_index(line_idx) else {
+            let line = self.rope.line(line_idx).byte_slice(..text_len).to_string();
+            let len = line.graphemes(true).count();
+            return Some(LineSlice::cut(
+                &line,
+                Default::default(),
+                len,
+                true,
+                unit,
+                range,
+            ));
+        };
+        // Walk from the checkpoints around the range only
+        let (from, to) = index.bounds(unit, range.start, range.end);
+        let to = to.min(text_len);
+        let text = self
+            .rope
+            .line(line_idx)
+            .byte_slice(from.byte..to)
+            .to_string();
+        Some(LineSlice::cut(
+            &text,
+            from,
+            index.len(),
+            to == text_len,
+            unit,
+            range,
+        ))
+    }
+
+    /// Length of a line in bytes without its line break
+    fn line_text_len(&self, line_idx: usize) -> usize {
+        if line_idx >= self.line_count() {
+            return 0;
+        }
+        text_len(self.rope.line(line_idx))
+    }
+
+    /// Index of a long line (None for other lines)
+    fn line_index(&self, line_idx: usize) -> Option<std::rc::Rc<LineIndex>> {
+        if !self.is_long_line(line_idx) {
+            return None;
+        }
+        let text_len = self.line_text_len(line_idx);
+        Some(self.line_indexes.borrow_mut().get(line_idx, || {
+            self.rope.line(line_idx).byte_slice(..text_len).to_string()
+        }))
+    }
+
     /// Get all text
     pub fn text(&self) -> String {
         self.rope.to_string()
@@ -182,9 +372,19 @@ impl TextBuffer {
 
     /// Insert text at cursor position
     pub fn insert(&mut self, cursor: &Cursor, text: &str) -> Result<Cursor> {
+        // Line breaks are stored as \n (pasted \r\n is converted)
+        let normalized;
+        let text = if text.contains("\r\n") {
+            normalized = text.replace("\r\n", "\n");
+            normalized.as_str()
+        } else {
+            text
+        };
+
         let char_idx = self.cursor_to_char_idx(cursor)?;
-        self.rope.insert(char_idx, text);
+        self.rope_insert(char_idx, text);
         self.modified = true;
+        self.mark_dirty(cursor.line, "", text);
 
         // Record to history
         self.history.push(Action::Insert {
@@ -210,8 +410,9 @@ impl TextBuffer {
         let deleted_char = self.rope.char(char_idx).to_string();
 
         // Delete one character
-        self.rope.remove(char_idx..char_idx + 1);
+        self.rope_remove(char_idx..char_idx + 1);
         self.modified = true;
+        self.mark_dirty(cursor.line, &deleted_char, "");
 
         // Record to history
         self.history.push(Action::Delete {
@@ -247,8 +448,9 @@ impl TextBuffer {
         };
 
         // Delete character before cursor
-        self.rope.remove(char_idx - 1..char_idx);
+        self.rope_remove(char_idx - 1..char_idx);
         self.modified = true;
+        self.mark_dirty(new_cursor.line, &deleted_char, "");
 
         // Record to history (position is the new cursor position after deletion)
         self.history.push(Action::Delete {
@@ -269,8 +471,9 @@ impl TextBuffer {
             let deleted_text: String = self.rope.slice(start_idx..end_idx).to_string();
 
             // Delete text
-            self.rope.remove(start_idx..end_idx);
+            self.rope_remove(start_idx..end_idx);
             self.modified = true;
+            self.mark_dirty(start.line, &deleted_text, "");
 
             // Record to history
             self.history.push(Action::Delete {
@@ -282,6 +485,209 @@ impl TextBuffer {
         Ok(())
     }
 
+    /// Replace text between `start` and `end` as a single undo step.
+    ///
+    /// Returns the cursor position after the inserted text.
+    pub fn replace_range(&mut self, start: &Cursor, end: &Cursor, text: &str) -> Result<Cursor> {
+        self.history.begin_group();
+        let result = self.delete_range(start, end).and_then(|_| {
+            if text.is_empty() {
+                Ok(*start)
+            } else {
+                self.insert(start, text)
+            }
+        });
+        self.history.end_group();
+        result
+    }
+
+    /// Wrap text between `start` and `end` in `open` and `close` as a single undo step
+    pub fn surround(
+        &mut self,
+        start: &Cursor,
+        end: &Cursor,
+        open: &str,
+        close: &str,
+    ) -> Result<()> {
+        self.history.begin_group();
+        // Close first so the start position stays valid
+        let result = self
+            .insert(end, close)
+            .and_then(|_| self.insert(start, open));
+        self.history.end_group();
+
+        result.map(|_| ())
+    }
+
+    /// Insert text into the rope, moving anchors after it
+    fn rope_insert(&mut self, char_idx: usize, text: &str) {
+        self.rope.insert(char_idx, text);
+        self.anchors.inserted(char_idx, text.chars().count());
+    }
+
+    /// Remove characters from the rope, moving anchors after them
+    fn rope_remove(&mut self, range: Range<usize>) {
+        self.rope.remove(range.clone());
+        self.anchors.removed(range);
+    }
+
+    /// Register a position at character offset `char_idx` that follows
+    /// edits (None if out of range).
+    ///
+    /// Undo and redo move it like any other edit: it returns to its place
+    /// when an edit around it is undone, while an anchor inside deleted
+    /// text stays at the start of the deletion.
+    pub fn anchor(&mut self, char_idx: usize, affinity: Affinity) -> Option<Anchor> {
+        (char_idx <= self.rope.len_chars()).then(|| self.anchors.create(char_idx, affinity))
+    }
+
+    /// Register a position at a cursor position that follows edits
+    pub fn anchor_at(&mut self, cursor: &Cursor, affinity: Affinity) -> Result<Anchor> {
+        let char_idx = self.cursor_to_char_idx(cursor)?;
+        Ok(self.anchors.create(char_idx, affinity))
+    }
+
+    /// Cursor position of an anchor
+    pub fn anchor_cursor(&self, anchor: &Anchor) -> Cursor {
+        self.char_idx_to_cursor(anchor.char_offset().min(self.rope.len_chars()))
+    }
+
+    /// Get text length in characters
+    pub fn len_chars(&self) -> usize {
+        self.rope.len_chars()
+    }
+
+    /// Character offset of a byte offset (None if out of range); a byte
+    /// inside a character gives that character
+    pub fn byte_to_char(&self, byte_idx: usize) -> Option<usize> {
+        (byte_idx <= self.rope.len_bytes()).then(|| self.rope.byte_to_char(byte_idx))
+    }
+
+    /// Byte offset of a character offset (None if out of range)
+    pub fn char_to_byte(&self, char_idx: usize) -> Option<usize> {
+        (char_idx <= self.rope.len_chars()).then(|| self.rope.char_to_byte(char_idx))
+    }
+
+    /// Line and column of a character offset (None if out of range)
+    pub fn char_to_line_column(&self, char_idx: usize, unit: ColumnUnit) -> Option<(usize, usize)> {
+        if char_idx > self.rope.len_chars() {
+            return None;
+        }
+        let line = self.rope.char_to_line(char_idx);
+        let line_start = self.rope.line_to_char(line);
+        let column = match unit {
+            ColumnUnit::Grapheme => self.char_idx_to_cursor(char_idx).column,
+            ColumnUnit::Char => char_idx - line_start,
+            ColumnUnit::Utf8 => {
+                self.rope.char_to_byte(char_idx) - self.rope.char_to_byte(line_start)
+            }
+            ColumnUnit::Utf16 => {
+                self.rope.char_to_utf16_cu(char_idx) - self.rope.char_to_utf16_cu(line_start)
+            }
+        };
+        Some((line, column))
+    }
+
+    /// Character offset of a line and column (None if the line is out of
+    /// range).
+    ///
+    /// Columns past the end of the line give its end (before the line
+    /// break), and a column inside a character or grapheme gives its start.
+    pub fn line_column_to_char(
+        &self,
+        line: usize,
+        column: usize,
+        unit: ColumnUnit,
+    ) -> Option<usize> {
+        if line >= self.rope.len_lines() {
+            return None;
+        }
+        let line_start = self.rope.line_to_char(line);
+        let text = self.rope.line(line).to_string();
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+
+        let mut units = 0;
+        let mut chars = 0;
+        if unit == ColumnUnit::Grapheme {
+            for grapheme in text.graphemes(true).take(column) {
+                chars += grapheme.chars().count();
+            }
+        } else {
+            for ch in text.chars() {
+                units += match unit {
+                    ColumnUnit::Utf8 => ch.len_utf8(),
+                    ColumnUnit::Utf16 => ch.len_utf16(),
+                    _ => 1,
+                };
+                if units > column {
+                    break;
+                }
+                chars += 1;
+            }
+        }
+        Some(line_start + chars)
+    }
+
+    /// Line and column of a byte offset (None if out of range)
+    pub fn byte_to_line_column(&self, byte_idx: usize, unit: ColumnUnit) -> Option<(usize, usize)> {
+        self.char_to_line_column(self.byte_to_char(byte_idx)?, unit)
+    }
+
+    /// Byte offset of a line and column (None if the line is out of range)
+    pub fn line_column_to_byte(
+        &self,
+        line: usize,
+        column: usize,
+        unit: ColumnUnit,
+    ) -> Option<usize> {
+        self.char_to_byte(self.line_column_to_char(line, column, unit)?)
+    }
+
+    /// Record edited lines for trimming modified lines on save
+    /// and for views tracking line edits
+    fn mark_dirty(&mut self, line: usize, removed: &str, inserted: &str) {
+        let edit = LineEdit {
+            line,
+            removed: removed.matches('\n').count(),
+            inserted: inserted.matches('\n').count(),
+        };
+        self.dirty_lines
+            .record_edit(edit.line, edit.removed, edit.inserted);
+        self.line_indexes
+            .get_mut()
+            .record_edit(edit.line, edit.removed, edit.inserted);
+        self.revision += 1;
+        if let Some(edits) = &mut self.line_edits {
+            edits.push(edit);
+        }
+    }
+
+    /// Text revision, incremented on every edit (including undo and redo)
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Enable or disable recording of line edits for `take_line_edits`
+    pub fn set_line_edit_tracking(&mut self, enabled: bool) {
+        self.line_edits = enabled.then(Vec::new);
+    }
+
+    /// Take line edits recorded since the last call
+    pub fn take_line_edits(&mut self) -> Vec<LineEdit> {
+        self.line_edits
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Convert character index in Rope to cursor position
+    fn char_idx_to_cursor(&self, char_idx: usize) -> Cursor {
+        let line = self.rope.char_to_line(char_idx);
+        let line_start = self.rope.line_to_char(line);
+        let prefix = self.rope.slice(line_start..char_idx).to_string();
+        Cursor::at(line, prefix.graphemes(true).count())
+    }
+
     /// Convert cursor position to character index in Rope
     fn cursor_to_char_idx(&self, cursor: &Cursor) -> Result<usize> {
         if cursor.line >= self.line_count() {
@@ -341,6 +747,378 @@ impl TextBuffer {
         self.file_path.as_deref()
     }
 
+    /// Get line ending used when saving
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Change line ending used when saving (undoable).
+    ///
+    /// `cursor` is restored when the change is undone or redone.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cursor: &Cursor) {
+        if line_ending == self.line_ending {
+            return;
+        }
+
+        self.history.push(Action::SetLineEnding {
+            position: *cursor,
+            from: self.line_ending,
+            to: line_ending,
+        });
+        self.line_ending = line_ending;
+
+        // Converting back to the on-disk line ending clears the modified flag
+        self.modified = true;
+        self.modified = self.is_content_modified().unwrap_or(true);
+    }
+
+    /// Check if file had both LF and CRLF line breaks when loaded (until saved)
+    pub fn has_mixed_line_endings(&self) -> bool {
+        self.disk_line_breaks.0 > 0 && self.disk_line_breaks.1 > 0
+    }
+
+    /// Number of line breaks that saving will convert in a mixed file
+    pub fn mixed_line_endings(&self) -> usize {
+        if !self.has_mixed_line_endings() {
+            return 0;
+        }
+        match self.line_ending {
+            LineEnding::LF => self.disk_line_breaks.0,
+            LineEnding::CRLF => self.disk_line_breaks.1,
+        }
+    }
+
+    /// Get indentation style (detected on load or set explicitly)
+    pub fn indent_style(&self) -> Option<IndentStyle> {
+        self.indent_style
+    }
+
+    /// Set indentation style
+    pub fn set_indent_style(&mut self, style: IndentStyle) {
+        self.indent_style = Some(style);
+    }
+
+    /// Re-indent all lines with given style as a single undo step.
+    ///
+    /// `tab_width` is the visual width of a tab stop. Returns number of changed lines.
+    pub fn convert_indentation(&mut self, style: IndentStyle, tab_width: usize) -> Result<usize> {
+        self.history.begin_group();
+        let result = self.convert_indentation_lines(style, tab_width);
+        self.history.end_group();
+
+        self.indent_style = Some(style);
+        result
+    }
+
+    /// Replace lines `start..start + count` with text as a single undo step.
+    ///
+    /// `text` should end with a line break unless it replaces the last line.
+    /// With `count == 0` the text is inserted before line `start`.
+    pub fn replace_lines(&mut self, start: usize, count: usize, text: &str) -> Result<()> {
+        self.history.begin_group();
+        let result = self.replace_lines_in_group(start, count, text);
+        self.history.end_group();
+        result
+    }
+
+    /// Replace several line ranges `(start, count, text)` as a single undo
+    /// step.
+    ///
+    /// Ranges refer to the lines before the edit and must be sorted and not
+    /// overlap; they are applied from the last one so earlier line numbers
+    /// stay valid.
+    pub fn replace_line_ranges(&mut self, edits: &[(usize, usize, String)]) -> Result<()> {
+        self.history.begin_group();
+        let result = edits
+            .iter()
+            .rev()
+            .try_for_each(|(start, count, text)| self.replace_lines_in_group(*start, *count, text));
+        self.history.end_group();
+        result
+    }
+
+    /// Insert `rows` as a rectangle at the cursor column, row `i` into line
+    /// `cursor.line + i`, as a single undo step.
+    ///
+    /// Lines shorter than the column are padded with spaces and lines past
+    /// the end of the buffer are created. Returns the position after the
+    /// last row.
+    pub fn insert_block(&mut self, cursor: &Cursor, rows: &[&str]) -> Result<Cursor> {
+        self.history.begin_group();
+        let result = self.insert_block_in_group(cursor, rows);
+        self.history.end_group();
+        result
+    }
+
+    /// Insert rows of a rectangle (history group must be open)
+    fn insert_block_in_group(&mut self, cursor: &Cursor, rows: &[&str]) -> Result<Cursor> {
+        let mut end = *cursor;
+        for (offset, row) in rows.iter().enumerate() {
+            let line_idx = cursor.line + offset;
+            if line_idx >= self.line_count() {
+                let last = self.line_count() - 1;
+                self.insert(&Cursor::at(last, self.line_len_graphemes(last)), "\n")?;
+            }
+            if row.is_empty() {
+                continue;
+            }
+            let len = self.line_len_graphemes(line_idx);
+            let padding = " ".repeat(cursor.column.saturating_sub(len));
+            let start = Cursor::at(line_idx, cursor.column.min(len));
+            end = self.insert(&start, &format!("{}{}", padding, row))?;
+        }
+        Ok(end)
+    }
+
+    /// Replace lines (history group must be open)
+    fn replace_lines_in_group(&mut self, start: usize, count: usize, text: &str) -> Result<()> {
+        if start >= self.line_count() {
+            anyhow::bail!("Line {} out of range", start);
+        }
+
+        let start_cursor = Cursor::at(start, 0);
+        let end_line = (start + count).min(self.rope.len_lines());
+        let end_cursor = self.char_idx_to_cursor(self.rope.line_to_char(end_line));
+        self.delete_range(&start_cursor, &end_cursor)?;
+        if !text.is_empty() {
+            self.insert(&start_cursor, text)?;
+        }
+        Ok(())
+    }
+
+    /// Replace leading whitespace of every line (history group must be open)
+    fn convert_indentation_lines(&mut self, style: IndentStyle, tab_width: usize) -> Result<usize> {
+        let mut changed = 0;
+        for line_idx in 0..self.line_count() {
+            let line = self.rope.line(line_idx).to_string();
+            let content = line.trim_start_matches([' ', '\t']);
+            let leading = &line[..line.len() - content.len()];
+            if leading.is_empty() || content.trim_end_matches(['\r', '\n']).is_empty() {
+                continue;
+            }
+
+            let new_leading = style.indent_for_column(indent_column(leading, tab_width), tab_width);
+            if new_leading != leading {
+                let start = Cursor::at(line_idx, 0);
+                self.delete_range(&start, &Cursor::at(line_idx, leading.chars().count()))?;
+                self.insert(&start, &new_leading)?;
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Apply on-save cleanup as a single undo step.
+    ///
+    /// The returned summary also counts line breaks of a mixed file that
+    /// saving converts to the buffer's line ending.
+    pub fn apply_save_transforms(&mut self, transforms: &SaveTransforms) -> Result<SaveCleanup> {
+        let mut cleanup = SaveCleanup {
+            line_endings: self.mixed_line_endings(),
+            ..SaveCleanup::default()
+        };
+
+        self.history.begin_group();
+        let result = self.apply_save_transforms_lines(transforms, &mut cleanup);
+        self.history.end_group();
+
+        result.map(|()| cleanup)
+    }
+
+    /// Edit lines for save transforms (history group must be open)
+    fn apply_save_transforms_lines(
+        &mut self,
+        transforms: &SaveTransforms,
+        cleanup: &mut SaveCleanup,
+    ) -> Result<()> {
+        for line_idx in 0..self.line_count() {
+            let line = self.rope.line(line_idx).to_string();
+            let text = line.strip_suffix('\n').unwrap_or(&line);
+            let text_len = text.graphemes(true).count();
+
+            let trim_line = transforms.trim_trailing_whitespace
+                && (!transforms.trim_modified_lines_only || self.dirty_lines.contains(line_idx));
+            let whitespace_len = trailing_whitespace_len(text);
+            if trim_line && whitespace_len > 0 {
+                self.delete_range(
+                    &Cursor::at(line_idx, text_len - whitespace_len),
+                    &Cursor::at(line_idx, text_len),
+                )?;
+                cleanup.trailing_whitespace += whitespace_len;
+            }
+        }
+
+        if transforms.ensure_final_newline {
+            let text = self.rope.to_string();
+            let content = text.trim_end_matches('\n');
+            // Leave empty files empty
+            if !content.is_empty() && text.len() - content.len() != 1 {
+                let start = self.char_idx_to_cursor(content.chars().count());
+                let last_line = self.line_count() - 1;
+                let end = Cursor::at(last_line, self.line_len_graphemes(last_line));
+                self.delete_range(&start, &end)?;
+                self.insert(&start, "\n")?;
+                cleanup.final_newline = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Comment or uncomment lines `first..=last` as a single undo step.
+    ///
+    /// Lines are uncommented only if every non-blank line is already
+    /// commented. The prefix is inserted at the smallest indentation of the
+    /// range so comments line up; blank lines are left alone. `suffix` is
+    /// empty for line comments. Returns None if all lines are blank.
+    pub fn toggle_comments(
+        &mut self,
+        first: usize,
+        last: usize,
+        prefix: &str,
+        suffix: &str,
+    ) -> Result<Option<CommentToggle>> {
+        let last = last.min(self.line_count().saturating_sub(1));
+        let lines: Vec<(usize, String)> = (first..=last)
+            .filter_map(|line_idx| {
+                let text = self.line(line_idx)?;
+                let text = text.trim_end_matches('\n');
+                (!text.trim().is_empty()).then(|| (line_idx, text.to_string()))
+            })
+            .collect();
+        if lines.is_empty() {
+            return Ok(None);
+        }
+
+        let toggle = CommentToggle {
+            commented: !lines
+                .iter()
+                .all(|(_, text)| is_commented(text, prefix, suffix)),
+            column: lines
+                .iter()
+                .map(|(_, text)| leading_whitespace(text).graphemes(true).count())
+                .min()
+                .unwrap_or(0),
+        };
+
+        self.history.begin_group();
+        let result = self.toggle_comment_lines(&lines, toggle, prefix, suffix);
+        self.history.end_group();
+
+        result.map(|()| Some(toggle))
+    }
+
+    /// Edit lines for comment toggling (history group must be open)
+    fn toggle_comment_lines(
+        &mut self,
+        lines: &[(usize, String)],
+        toggle: CommentToggle,
+        prefix: &str,
+        suffix: &str,
+    ) -> Result<()> {
+        let column = |text: &str, byte: usize| text[..byte].graphemes(true).count();
+
+        for (line_idx, text) in lines {
+            let line_idx = *line_idx;
+            if toggle.commented {
+                // Suffix first so the prefix column stays valid
+                if !suffix.is_empty() {
+                    let end = Cursor::at(line_idx, column(text, text.len()));
+                    self.insert(&end, &format!(" {}", suffix))?;
+                }
+                self.insert(
+                    &Cursor::at(line_idx, toggle.column),
+                    &format!("{} ", prefix),
+                )?;
+            } else {
+                let (suffix_range, (start, end)) = uncomment_ranges(text, prefix, suffix);
+                if let Some((suffix_start, suffix_end)) = suffix_range {
+                    self.delete_range(
+                        &Cursor::at(line_idx, column(text, suffix_start)),
+                        &Cursor::at(line_idx, column(text, suffix_end)),
+                    )?;
+                }
+                self.delete_range(
+                    &Cursor::at(line_idx, column(text, start)),
+                    &Cursor::at(line_idx, column(text, end)),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert `unit` at the start of lines `first..=last` as a single undo
+    /// step, leaving empty lines alone.
+    ///
+    /// Returns the number of graphemes inserted into each line of the range.
+    pub fn indent_lines(&mut self, first: usize, last: usize, unit: &str) -> Result<Vec<usize>> {
+        let last = last.min(self.line_count().saturating_sub(1));
+        let unit_len = unit.graphemes(true).count();
+        let inserted: Vec<usize> = (first..=last)
+            .map(|line_idx| {
+                let text = self.line(line_idx).unwrap_or_default();
+                if text.trim_end_matches(['\r', '\n']).is_empty() {
+                    0
+                } else {
+                    unit_len
+                }
+            })
+            .collect();
+
+        self.history.begin_group();
+        let result = (first..=last)
+            .zip(&inserted)
+            .filter(|(_, &count)| count > 0)
+            .try_for_each(|(line_idx, _)| self.insert(&Cursor::at(line_idx, 0), unit).map(drop));
+        self.history.end_group();
+
+        result.map(|()| inserted)
+    }
+
+    /// Remove up to one indent level (`indent_width` columns, tabs
+    /// advancing to the next `tab_width` stop) of leading whitespace from
+    /// lines `first..=last` as a single undo step.
+    ///
+    /// Returns the number of graphemes removed from each line of the range.
+    pub fn outdent_lines(
+        &mut self,
+        first: usize,
+        last: usize,
+        indent_width: usize,
+        tab_width: usize,
+    ) -> Result<Vec<usize>> {
+        let last = last.min(self.line_count().saturating_sub(1));
+        let tab_width = tab_width.max(1);
+        let removed: Vec<usize> = (first..=last)
+            .map(|line_idx| {
+                let text = self.line(line_idx).unwrap_or_default();
+                let (mut width, mut count) = (0, 0);
+                for ch in leading_whitespace(&text).chars() {
+                    if width >= indent_width {
+                        break;
+                    }
+                    width = match ch {
+                        '\t' => (width / tab_width + 1) * tab_width,
+                        _ => width + 1,
+                    };
+                    count += 1;
+                }
+                count
+            })
+            .collect();
+
+        self.history.begin_group();
+        let result = (first..=last)
+            .zip(&removed)
+            .filter(|(_, &count)| count > 0)
+            .try_for_each(|(line_idx, &count)| {
+                self.delete_range(&Cursor::at(line_idx, 0), &Cursor::at(line_idx, count))
+            });
+        self.history.end_group();
+
+        result.map(|()| removed)
+    }
+
     /// Get buffer contents as string
     #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
@@ -351,7 +1129,8 @@ impl TextBuffer {
     #[allow(dead_code)]
     pub fn append(&mut self, text: &str) {
         let len = self.rope.len_chars();
-        self.rope.insert(len, text);
+        self.rope_insert(len, text);
+        self.line_indexes.get_mut().clear();
         // Don't mark as modified - this is for internal use (log viewer)
     }
 
@@ -388,19 +1167,45 @@ impl TextBuffer {
         }
     }
 
+    /// Branches of the undo tree, most recently edited first
+    pub fn undo_branches(&mut self) -> Vec<UndoBranch> {
+        self.history.branches()
+    }
+
+    /// Switch to the end of another undo tree branch
+    pub fn switch_undo_branch(&mut self, id: usize) -> Result<Option<Cursor>> {
+        let Some(actions) = self.history.switch_to(id) else {
+            return Ok(None);
+        };
+        let mut cursor = None;
+        for action in &actions {
+            cursor = Some(self.apply_action(action)?);
+        }
+        // Check if buffer content actually differs from file
+        self.modified = self.is_content_modified()?;
+        Ok(cursor)
+    }
+
+    /// Set the maximum number of edits kept in the undo tree
+    pub fn set_undo_limit(&mut self, max_nodes: usize) {
+        self.history.set_max_nodes(max_nodes);
+    }
+
     /// Apply action to buffer (for undo/redo)
     fn apply_action(&mut self, action: &Action) -> Result<Cursor> {
         match action {
             Action::Insert { position, text } => {
                 let char_idx = self.cursor_to_char_idx(position)?;
-                self.rope.insert(char_idx, text);
+                self.rope_insert(char_idx, text);
+                self.mark_dirty(position.line, "", text);
                 let new_cursor = self.advance_cursor(position, text);
                 Ok(new_cursor)
             }
             Action::Delete { position, text } => {
                 let char_idx = self.cursor_to_char_idx(position)?;
                 let end_idx = char_idx + text.chars().count();
-                self.rope.remove(char_idx..end_idx);
+                self.rope_remove(char_idx..end_idx);
+                self.mark_dirty(position.line, text, "");
                 Ok(*position)
             }
             Action::Group { actions } => {
@@ -410,6 +1215,10 @@ impl TextBuffer {
                 }
                 Ok(cursor)
             }
+            Action::SetLineEnding { position, to, .. } => {
+                self.line_ending = *to;
+                Ok(*position)
+            }
         }
     }
 
@@ -432,6 +1241,16 @@ impl Default for TextBuffer {
     }
 }
 
+/// Length of a line in bytes without its line break
+fn text_len(line: ropey::RopeSlice) -> usize {
+    let len = line.len_bytes();
+    if len > 0 && line.byte(len - 1) == b'\n' {
+        len - 1
+    } else {
+        len
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -517,7 +1336,7 @@ mod tests {
             .unwrap();
 
         // Save the buffer
-        buf.save_to(temp_path).unwrap();
+        buf.save_to(temp_path, WriteOptions::default()).unwrap();
 
         // Read the saved content
         let saved_content = fs::read_to_string(temp_path).unwrap();
@@ -529,7 +1348,7 @@ mod tests {
         // Save it again to a different temp file
         let temp_file2 = NamedTempFile::new().unwrap();
         let temp_path2 = temp_file2.path();
-        buf2.save_to(temp_path2).unwrap();
+        buf2.save_to(temp_path2, WriteOptions::default()).unwrap();
 
         // Read the re-saved content
         let resaved_content = fs::read_to_string(temp_path2).unwrap();
@@ -554,14 +1373,14 @@ mod tests {
         buf.insert(&Cursor::at(0, 0), "1\n2\n3\n4\n5").unwrap();
 
         // Save
-        buf.save_to(temp_path).unwrap();
+        buf.save_to(temp_path, WriteOptions::default()).unwrap();
         let content1 = fs::read_to_string(temp_path).unwrap();
         let lines1: Vec<&str> = content1.lines().collect();
         assert_eq!(lines1.len(), 5, "First save should have 5 lines");
 
         // Load and save again
         let mut buf2 = TextBuffer::from_file(temp_path).unwrap();
-        buf2.save_to(temp_path).unwrap();
+        buf2.save_to(temp_path, WriteOptions::default()).unwrap();
         let content2 = fs::read_to_string(temp_path).unwrap();
         let lines2: Vec<&str> = content2.lines().collect();
         assert_eq!(lines2.len(), 5, "Second save should still have 5 lines");
@@ -569,4 +1388,507 @@ mod tests {
         // Verify content is identical
         assert_eq!(content1, content2, "Content should not change across saves");
     }
+
+    #[test]
+    fn test_convert_indentation_single_undo() {
+        let text = "fn a() {\n    if x {\n        y();\n    }\n}\n";
+        let mut buf = TextBuffer::from_text(text);
+        assert_eq!(buf.indent_style(), Some(IndentStyle::Spaces(4)));
+
+        let changed = buf.convert_indentation(IndentStyle::Tabs, 4).unwrap();
+        assert_eq!(changed, 3);
+        assert_eq!(buf.text(), "fn a() {\n\tif x {\n\t\ty();\n\t}\n}\n");
+        assert_eq!(buf.indent_style(), Some(IndentStyle::Tabs));
+
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), text);
+        assert!(!buf.can_undo());
+    }
+
+    #[test]
+    fn test_replace_lines_single_undo() {
+        let mut buf = TextBuffer::from_text("a\nB\nC\nd\n");
+        buf.replace_lines(1, 2, "b\n").unwrap();
+        assert_eq!(buf.text(), "a\nb\nd\n");
+
+        // Insert before a line
+        buf.replace_lines(2, 0, "c\n").unwrap();
+        assert_eq!(buf.text(), "a\nb\nc\nd\n");
+
+        buf.undo().unwrap();
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "a\nB\nC\nd\n");
+    }
+
+    #[test]
+    fn test_insert_block_pads_and_creates_lines() {
+        let mut buf = TextBuffer::from_text("abcd\na\nabcd");
+        let end = buf
+            .insert_block(&Cursor::at(0, 2), &["XY", "XY", "XY", "", "XY"])
+            .unwrap();
+        assert_eq!(buf.text(), "abXYcd\na XY\nabXYcd\n\n  XY");
+        assert_eq!(end, Cursor::at(4, 4));
+
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "abcd\na\nabcd");
+    }
+
+    #[test]
+    fn test_switch_undo_branch() {
+        let mut buf = TextBuffer::from_text("one\n");
+        buf.insert(&Cursor::at(0, 3), " two").unwrap();
+        buf.undo().unwrap();
+        buf.insert(&Cursor::at(0, 0), "zero ").unwrap();
+        assert_eq!(buf.text(), "zero one\n");
+        // Typing after the undo did not drop " two"
+        assert!(!buf.can_redo());
+
+        let old = buf
+            .undo_branches()
+            .into_iter()
+            .find(|b| !b.current)
+            .unwrap();
+        let cursor = buf.switch_undo_branch(old.id).unwrap();
+        assert_eq!(buf.text(), "one two\n");
+        assert_eq!(cursor, Some(Cursor::at(0, 7)));
+        assert!(buf.is_modified());
+
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "one\n");
+    }
+
+    #[test]
+    fn test_replace_line_ranges_single_undo() {
+        let mut buf = TextBuffer::from_text("a\nB\nc\nD\nE\n");
+        buf.replace_line_ranges(&[
+            (1, 1, "b\n".to_string()),
+            (3, 2, "d\ne\nf\n".to_string()),
+            (5, 0, "g".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(buf.text(), "a\nb\nc\nd\ne\nf\ng");
+
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "a\nB\nc\nD\nE\n");
+    }
+
+    #[test]
+    fn test_save_transforms_trim_modified_lines() {
+        let mut buf = TextBuffer::from_text("keep  \nedit  \nend");
+        buf.insert(&Cursor::at(1, 0), "x").unwrap();
+
+        let transforms = SaveTransforms {
+            trim_trailing_whitespace: true,
+            trim_modified_lines_only: true,
+            ensure_final_newline: true,
+        };
+        let cleanup = buf.apply_save_transforms(&transforms).unwrap();
+
+        assert_eq!(buf.text(), "keep  \nxedit\nend\n");
+        assert_eq!(cleanup.trailing_whitespace, 2);
+        assert!(cleanup.final_newline);
+
+        // Whole cleanup is undone at once
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "keep  \nxedit  \nend");
+    }
+
+    #[test]
+    fn test_toggle_comments() {
+        let mut buf = TextBuffer::from_text("fn a() {\n    x();\n\n  // y();\n}\n");
+
+        let toggle = buf.toggle_comments(1, 3, "//", "").unwrap().unwrap();
+        assert!(toggle.commented);
+        assert_eq!(toggle.column, 2);
+        assert_eq!(buf.text(), "fn a() {\n  //   x();\n\n  // // y();\n}\n");
+
+        // Whole toggle is undone at once
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "fn a() {\n    x();\n\n  // y();\n}\n");
+
+        let toggle = buf.toggle_comments(3, 3, "//", "").unwrap().unwrap();
+        assert!(!toggle.commented);
+        assert_eq!(buf.text(), "fn a() {\n    x();\n\n  y();\n}\n");
+
+        assert_eq!(buf.toggle_comments(2, 2, "//", "").unwrap(), None);
+    }
+
+    #[test]
+    fn test_indent_and_outdent_lines() {
+        let mut buf = TextBuffer::from_text("a\n\n  b\n\tc\n");
+
+        // Empty lines are not indented
+        assert_eq!(buf.indent_lines(0, 3, "    ").unwrap(), [4, 0, 4, 4]);
+        assert_eq!(buf.text(), "    a\n\n      b\n    \tc\n");
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "a\n\n  b\n\tc\n");
+
+        // One level at most, never past the leading whitespace
+        let mut buf = TextBuffer::from_text("      a\n  b\nc\n\t\td\n \te\n");
+        assert_eq!(buf.outdent_lines(0, 4, 4, 4).unwrap(), [4, 2, 0, 1, 2]);
+        assert_eq!(buf.text(), "  a\nb\nc\n\td\ne\n");
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "      a\n  b\nc\n\t\td\n \te\n");
+    }
+
+    #[test]
+    fn test_position_conversions() {
+        // é is 2 bytes, 😀 is 4 bytes and 2 UTF-16 units, x̂ is 2 chars
+        let buf = TextBuffer::from_text("aé😀\nx\u{302}y\n");
+        assert_eq!(buf.len_chars(), 8);
+
+        assert_eq!(
+            buf.char_to_line_column(3, ColumnUnit::Grapheme),
+            Some((0, 3))
+        );
+        assert_eq!(buf.char_to_line_column(3, ColumnUnit::Char), Some((0, 3)));
+        assert_eq!(buf.char_to_line_column(3, ColumnUnit::Utf8), Some((0, 7)));
+        assert_eq!(buf.char_to_line_column(3, ColumnUnit::Utf16), Some((0, 4)));
+        assert_eq!(
+            buf.char_to_line_column(7, ColumnUnit::Grapheme),
+            Some((1, 2))
+        );
+        assert_eq!(buf.char_to_line_column(7, ColumnUnit::Char), Some((1, 3)));
+        assert_eq!(buf.char_to_line_column(8, ColumnUnit::Utf16), Some((2, 0)));
+        assert_eq!(buf.char_to_line_column(9, ColumnUnit::Char), None);
+
+        assert_eq!(buf.line_column_to_char(0, 7, ColumnUnit::Utf8), Some(3));
+        assert_eq!(buf.line_column_to_char(0, 4, ColumnUnit::Utf16), Some(3));
+        assert_eq!(buf.line_column_to_char(1, 1, ColumnUnit::Grapheme), Some(6));
+        assert_eq!(buf.line_column_to_char(1, 1, ColumnUnit::Char), Some(5));
+        // Inside a character: its start
+        assert_eq!(buf.line_column_to_char(0, 5, ColumnUnit::Utf8), Some(2));
+        assert_eq!(buf.line_column_to_char(0, 3, ColumnUnit::Utf16), Some(2));
+        // Past the end: before the line break
+        assert_eq!(buf.line_column_to_char(0, 100, ColumnUnit::Char), Some(3));
+        assert_eq!(buf.line_column_to_char(2, 0, ColumnUnit::Char), Some(8));
+        assert_eq!(buf.line_column_to_char(3, 0, ColumnUnit::Char), None);
+
+        assert_eq!(buf.byte_to_char(3), Some(2));
+        assert_eq!(buf.byte_to_char(2), Some(1));
+        assert_eq!(buf.byte_to_char(buf.len_bytes()), Some(8));
+        assert_eq!(buf.byte_to_char(buf.len_bytes() + 1), None);
+        assert_eq!(buf.char_to_byte(3), Some(7));
+        assert_eq!(buf.char_to_byte(9), None);
+        assert_eq!(buf.byte_to_line_column(9, ColumnUnit::Char), Some((1, 1)));
+        assert_eq!(
+            buf.line_column_to_byte(1, 1, ColumnUnit::Grapheme),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_anchors_follow_edits() {
+        let mut buf = TextBuffer::from_text("hello world");
+        let start = buf.anchor(0, Affinity::Left).unwrap();
+        let left = buf.anchor(5, Affinity::Left).unwrap();
+        let right = buf.anchor(5, Affinity::Right).unwrap();
+        let end = buf.anchor(11, Affinity::Right).unwrap();
+        assert!(buf.anchor(12, Affinity::Left).is_none());
+        let offsets = || [&start, &left, &right, &end].map(|anchor| anchor.char_offset());
+
+        // Insert at the anchors: affinity decides
+        buf.insert(&Cursor::at(0, 5), ", big").unwrap();
+        assert_eq!(buf.text(), "hello, big world");
+        assert_eq!(offsets(), [0, 5, 10, 16]);
+
+        // Delete around an anchor: it moves to the start
+        buf.delete_range(&Cursor::at(0, 3), &Cursor::at(0, 7))
+            .unwrap();
+        assert_eq!(buf.text(), "helbig world");
+        assert_eq!(offsets(), [0, 3, 6, 12]);
+
+        // Undo and redo apply the inverse edits
+        buf.undo().unwrap();
+        assert_eq!(offsets(), [0, 3, 10, 16]);
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "hello world");
+        assert_eq!(offsets(), [0, 3, 5, 11]);
+        buf.redo().unwrap();
+        assert_eq!(offsets(), [0, 3, 10, 16]);
+
+        // Edits after an anchor leave it alone
+        buf.insert(&Cursor::at(0, 16), "!").unwrap();
+        assert_eq!(offsets(), [0, 3, 10, 17]);
+
+        // A copy of the buffer does not move the anchors
+        let mut copy = buf.clone();
+        copy.insert(&Cursor::at(0, 0), ">").unwrap();
+        assert_eq!(offsets(), [0, 3, 10, 17]);
+    }
+
+    #[test]
+    fn test_anchors_with_multibyte_text() {
+        let mut buf = TextBuffer::from_text("é😀\nx\u{302}y");
+        let anchor = buf.anchor_at(&Cursor::at(1, 1), Affinity::Right).unwrap();
+        assert_eq!(anchor.char_offset(), 5);
+        assert_eq!(buf.anchor_cursor(&anchor), Cursor::at(1, 1));
+
+        buf.insert(&Cursor::at(0, 1), "ß\n").unwrap();
+        assert_eq!(anchor.char_offset(), 7);
+        assert_eq!(buf.anchor_cursor(&anchor), Cursor::at(2, 1));
+        assert_eq!(
+            buf.char_to_line_column(anchor.char_offset(), ColumnUnit::Utf8),
+            Some((2, 3))
+        );
+
+        // Backspace and delete before the anchor
+        assert!(buf.delete_char(&Cursor::at(0, 0)).unwrap());
+        buf.backspace(&Cursor::at(1, 0)).unwrap();
+        assert_eq!(buf.text(), "ß😀\nx\u{302}y");
+        assert_eq!(buf.anchor_cursor(&anchor), Cursor::at(1, 1));
+    }
+
+    #[test]
+    fn test_dropped_anchors_are_unregistered() {
+        let mut buf = TextBuffer::from_text("abc");
+        let kept = buf.anchor(1, Affinity::Left).unwrap();
+        let shared = kept.clone();
+        let dropped = buf.anchor(2, Affinity::Left).unwrap();
+        assert_eq!(buf.anchors.len(), 2);
+
+        drop(dropped);
+        drop(kept);
+        buf.insert(&Cursor::at(0, 0), "x").unwrap();
+        assert_eq!(buf.anchors.len(), 1);
+        assert_eq!(shared.char_offset(), 2);
+
+        drop(shared);
+        buf.append("d");
+        assert_eq!(buf.anchors.len(), 0);
+    }
+
+    #[test]
+    fn test_surround_is_single_undo_step() {
+        let mut buf = TextBuffer::from_text("a b c");
+        buf.surround(&Cursor::at(0, 2), &Cursor::at(0, 3), "(", ")")
+            .unwrap();
+        assert_eq!(buf.text(), "a (b) c");
+
+        buf.undo().unwrap();
+        assert_eq!(buf.text(), "a b c");
+    }
+
+    #[test]
+    fn test_long_line_slices() {
+        let long = "ab字".repeat(5_000);
+        let mut buf = TextBuffer::from_text(&format!("short\n{}\n", long));
+        assert!(!buf.is_long_line(0) && buf.is_long_line(1));
+        assert!(buf.has_long_lines());
+        assert_eq!(buf.line_len_graphemes(1), 15_000);
+
+        let slice = buf.line_slice(1, 9_000..9_004).unwrap();
+        assert_eq!(slice.text, "ab字a");
+        assert_eq!(slice.start.column, 12_000);
+        assert!(!slice.to_end);
+        let slice = buf.line_slice_columns(1, 19_994..20_010).unwrap();
+        assert_eq!(slice.text, "字ab字");
+        assert!(slice.to_end);
+        assert_eq!(buf.line_slice(0, 2..10).unwrap().text, "ort");
+
+        // Edits drop the index of the edited line
+        buf.insert(&Cursor::at(1, 9_000), "x").unwrap();
+        assert_eq!(buf.line_len_graphemes(1), 15_001);
+        assert_eq!(buf.line_slice(1, 9_000..9_002).unwrap().text, "xa");
+        buf.insert(&Cursor::at(0, 0), "\n").unwrap();
+        assert_eq!(buf.line_len_graphemes(2), 15_001);
+
+        buf.delete_range(&Cursor::at(2, 0), &Cursor::at(2, 15_001))
+            .unwrap();
+        assert!(!buf.has_long_lines());
+    }
+
+    #[test]
+    fn test_line_edits_are_tracked_when_enabled() {
+        let mut buf = TextBuffer::from_text("a\nb\nc");
+        buf.insert(&Cursor::at(0, 1), "x").unwrap();
+        assert_eq!(buf.revision(), 1);
+        assert!(buf.take_line_edits().is_empty());
+
+        buf.set_line_edit_tracking(true);
+        buf.insert(&Cursor::at(1, 0), "y\nz\n").unwrap();
+        buf.undo().unwrap();
+        assert_eq!(buf.revision(), 3);
+        assert_eq!(
+            buf.take_line_edits(),
+            vec![
+                LineEdit {
+                    line: 1,
+                    removed: 0,
+                    inserted: 2
+                },
+                LineEdit {
+                    line: 1,
+                    removed: 2,
+                    inserted: 0
+                },
+            ]
+        );
+        assert!(buf.take_line_edits().is_empty());
+    }
+
+    #[test]
+    fn test_toggle_block_comments() {
+        let mut buf = TextBuffer::from_text("<p>\n  <b>\n");
+
+        buf.toggle_comments(0, 1, "<!--", "-->").unwrap();
+        assert_eq!(buf.text(), "<!-- <p> -->\n<!--   <b> -->\n");
+
+        buf.toggle_comments(0, 1, "<!--", "-->").unwrap();
+        assert_eq!(buf.text(), "<p>\n  <b>\n");
+    }
+
+    #[test]
+    fn test_save_transforms_final_newline() {
+        let transforms = SaveTransforms {
+            ensure_final_newline: true,
+            ..SaveTransforms::default()
+        };
+
+        let mut buf = TextBuffer::from_text("text\n\n\n");
+        assert!(
+            buf.apply_save_transforms(&transforms)
+                .unwrap()
+                .final_newline
+        );
+        assert_eq!(buf.text(), "text\n");
+
+        assert!(buf.apply_save_transforms(&transforms).unwrap().is_empty());
+        assert_eq!(buf.text(), "text\n");
+
+        let mut empty = TextBuffer::new();
+        assert!(empty.apply_save_transforms(&transforms).unwrap().is_empty());
+        assert_eq!(empty.text(), "");
+    }
+
+    #[test]
+    fn test_mixed_line_endings_normalized() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "a\r\nb\r\nc\n").unwrap();
+
+        let mut buf = TextBuffer::from_file(temp_file.path()).unwrap();
+        assert_eq!(buf.text(), "a\nb\nc\n");
+        assert!(!buf.is_modified());
+
+        // Pasted CRLF text is stored with \n
+        buf.insert(&Cursor::at(3, 0), "d\r\n").unwrap();
+        assert_eq!(buf.text(), "a\nb\nc\nd\n");
+
+        let cleanup = buf
+            .apply_save_transforms(&SaveTransforms::default())
+            .unwrap();
+        assert_eq!(cleanup.line_endings, 1);
+
+        buf.save(WriteOptions::default()).unwrap();
+        let saved = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert_eq!(saved, "a\r\nb\r\nc\r\nd\r\n");
+    }
+
+    /// Load bytes from a temporary file, edit the first line and save
+    fn round_trip(bytes: &[u8], edit: &str) -> (TextBuffer, Vec<u8>, tempfile::NamedTempFile) {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytes).unwrap();
+        let mut buf = TextBuffer::from_file(file.path()).unwrap();
+        buf.insert(&Cursor::new(), edit).unwrap();
+        buf.save(WriteOptions::default()).unwrap();
+        let saved = std::fs::read(file.path()).unwrap();
+        (buf, saved, file)
+    }
+
+    #[test]
+    fn test_utf16le_bom_round_trip() {
+        // "Hi Ж\r\n" in UTF-16LE with BOM
+        let bytes = b"\xFF\xFEH\0i\0 \0\x16\x04\r\0\n\0";
+        let (buf, saved, _file) = round_trip(bytes, "!");
+
+        assert_eq!(buf.encoding().name(), "UTF-16LE");
+        assert_eq!(buf.line_ending(), LineEnding::CRLF);
+        assert_eq!(buf.line(0).unwrap(), "!Hi Ж\n");
+        assert_eq!(saved[..4], *b"\xFF\xFE!\0");
+        assert_eq!(saved[4..], bytes[2..]);
+    }
+
+    #[test]
+    fn test_windows_1251_round_trip() {
+        // "Привет, мир! Это тест.\n" in windows-1251
+        let bytes = b"\xcf\xf0\xe8\xe2\xe5\xf2, \xec\xe8\xf0! \xdd\xf2\xee \xf2\xe5\xf1\xf2.\n";
+        let (mut buf, saved, _file) = round_trip(bytes, "Ж");
+
+        assert_eq!(buf.encoding().name(), "windows-1251");
+        assert!(!buf.had_decode_errors());
+        assert_eq!(saved[0], 0xC6);
+        assert_eq!(saved[1..], bytes[..]);
+
+        // Characters missing from the encoding are not saved as garbage
+        buf.insert(&Cursor::new(), "你").unwrap();
+        assert!(buf.save(WriteOptions::default()).is_err());
+        assert_eq!(std::fs::read(buf.file_path().unwrap()).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_invalid_utf8_loads_with_replacement() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let bytes = ["Привет, ".as_bytes(), b"\xff", " мир\n".as_bytes()].concat();
+        std::fs::write(file.path(), bytes).unwrap();
+        let buf = TextBuffer::from_file(file.path()).unwrap();
+
+        assert!(buf.encoding().is_plain_utf8());
+        assert!(buf.had_decode_errors());
+        assert_eq!(buf.line(0).unwrap(), "Привет, \u{FFFD} мир\n");
+    }
+
+    #[test]
+    fn test_line_ending_detection() {
+        let buf = TextBuffer::from_text("a\nb\nc");
+        assert_eq!(buf.line_ending(), LineEnding::LF);
+        assert!(!buf.has_mixed_line_endings());
+
+        let buf = TextBuffer::from_text("a\r\nb\r\n");
+        assert_eq!(buf.line_ending(), LineEnding::CRLF);
+        assert!(!buf.has_mixed_line_endings());
+        assert_eq!(buf.text(), "a\nb\n");
+
+        // No line breaks and ties default to LF
+        assert_eq!(TextBuffer::from_text("a").line_ending(), LineEnding::LF);
+        let buf = TextBuffer::from_text("a\r\nb\n");
+        assert_eq!(buf.line_ending(), LineEnding::LF);
+        assert!(buf.has_mixed_line_endings());
+        assert_eq!(buf.mixed_line_endings(), 1);
+    }
+
+    #[test]
+    fn test_mixed_line_endings_majority() {
+        let buf = TextBuffer::from_text("a\r\nb\r\nc\nd\r\n");
+        assert_eq!(buf.line_ending(), LineEnding::CRLF);
+        assert!(buf.has_mixed_line_endings());
+        assert_eq!(buf.mixed_line_endings(), 1);
+
+        let buf = TextBuffer::from_text("a\nb\r\nc\nd\n");
+        assert_eq!(buf.line_ending(), LineEnding::LF);
+        assert_eq!(buf.mixed_line_endings(), 1);
+    }
+
+    #[test]
+    fn test_set_line_ending_undo() {
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "a\nb\n").unwrap();
+        let mut buf = TextBuffer::from_file(temp_file.path()).unwrap();
+
+        buf.set_line_ending(LineEnding::CRLF, &Cursor::at(1, 0));
+        assert_eq!(buf.line_ending(), LineEnding::CRLF);
+        assert!(buf.is_modified());
+
+        assert_eq!(buf.undo().unwrap(), Some(Cursor::at(1, 0)));
+        assert_eq!(buf.line_ending(), LineEnding::LF);
+        assert!(!buf.is_modified());
+
+        buf.redo().unwrap();
+        buf.save(WriteOptions::default()).unwrap();
+        let saved = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert_eq!(saved, "a\r\nb\r\n");
+    }
 }
@@ -0,0 +1,125 @@
+//! Line comment toggling helpers.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Result of toggling comments on a range of lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentToggle {
+    /// Lines were commented (false if uncommented)
+    pub commented: bool,
+    /// Column where the comment prefix was inserted (when commenting)
+    pub column: usize,
+}
+
+impl CommentToggle {
+    /// Column of a position after the toggle, given the text of its line
+    /// before the toggle (positions inside a removed prefix move to its start)
+    pub fn shift_column(&self, text: &str, column: usize, prefix: &str) -> usize {
+        if text.trim().is_empty() {
+            return column;
+        }
+
+        let prefix_len = prefix.graphemes(true).count();
+        if self.commented {
+            return if column >= self.column {
+                column + prefix_len + 1
+            } else {
+                column
+            };
+        }
+
+        let prefix_start = leading_whitespace(text).len();
+        let after_prefix = &text[(prefix_start + prefix.len()).min(text.len())..];
+        let removed = prefix_len + usize::from(after_prefix.starts_with(' '));
+        let start_column = text[..prefix_start].graphemes(true).count();
+        if column > start_column {
+            column.saturating_sub(removed).max(start_column)
+        } else {
+            column
+        }
+    }
+}
+
+/// Leading spaces and tabs of a line
+pub(crate) fn leading_whitespace(text: &str) -> &str {
+    &text[..text.len() - text.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Check if line content is wrapped in comment delimiters
+/// (`suffix` is empty for line comments)
+pub(crate) fn is_commented(text: &str, prefix: &str, suffix: &str) -> bool {
+    let content = text.trim_start_matches([' ', '\t']).trim_end();
+    content.starts_with(prefix)
+        && content.len() >= prefix.len() + suffix.len()
+        && content.ends_with(suffix)
+}
+
+/// Byte ranges removed to uncomment a commented line: the suffix with the
+/// space before it (if any) and the prefix with the space after it
+pub(crate) fn uncomment_ranges(
+    text: &str,
+    prefix: &str,
+    suffix: &str,
+) -> (Option<(usize, usize)>, (usize, usize)) {
+    let prefix_start = leading_whitespace(text).len();
+    let mut prefix_end = prefix_start + prefix.len();
+    if text[prefix_end..].starts_with(' ') {
+        prefix_end += 1;
+    }
+
+    let suffix_range = (!suffix.is_empty()).then(|| {
+        let end = text.trim_end().len();
+        let mut start = (end - suffix.len()).max(prefix_end);
+        if start > prefix_end && text[..start].ends_with(' ') {
+            start -= 1;
+        }
+        (start, end)
+    });
+
+    (suffix_range, (prefix_start, prefix_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_commented() {
+        assert!(is_commented("    // x", "//", ""));
+        assert!(is_commented("\t#", "#", ""));
+        assert!(!is_commented("x // y", "//", ""));
+        assert!(is_commented("<!-- x -->  ", "<!--", "-->"));
+        assert!(!is_commented("<!-- x", "<!--", "-->"));
+    }
+
+    #[test]
+    fn test_uncomment_ranges() {
+        assert_eq!(uncomment_ranges("  // x", "//", ""), (None, (2, 5)));
+        assert_eq!(uncomment_ranges("//x", "//", ""), (None, (0, 2)));
+        assert_eq!(
+            uncomment_ranges("/* a */", "/*", "*/"),
+            (Some((4, 7)), (0, 3))
+        );
+        assert_eq!(uncomment_ranges("/**/", "/*", "*/"), (Some((2, 4)), (0, 2)));
+    }
+
+    #[test]
+    fn test_shift_column() {
+        let comment = CommentToggle {
+            commented: true,
+            column: 2,
+        };
+        assert_eq!(comment.shift_column("    x", 4, "//"), 7);
+        assert_eq!(comment.shift_column("    x", 1, "//"), 1);
+        assert_eq!(comment.shift_column("", 0, "//"), 0);
+
+        let uncomment = CommentToggle {
+            commented: false,
+            column: 0,
+        };
+        assert_eq!(uncomment.shift_column("  // x", 6, "//"), 3);
+        assert_eq!(uncomment.shift_column("  // x", 3, "//"), 2);
+        assert_eq!(uncomment.shift_column("  //x", 4, "//"), 2);
+        assert_eq!(uncomment.shift_column("  // x", 1, "//"), 1);
+    }
+}
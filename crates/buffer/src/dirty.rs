@@ -0,0 +1,67 @@
+//! Tracking of lines edited since the last save.
+
+use std::collections::BTreeSet;
+
+/// Set of line indices edited since the buffer was loaded or saved
+#[derive(Debug, Clone, Default)]
+pub struct DirtyLines {
+    lines: BTreeSet<usize>,
+}
+
+impl DirtyLines {
+    /// Record an edit starting at `line` that removed `removed` and
+    /// inserted `inserted` line breaks (lines below are shifted accordingly)
+    pub fn record_edit(&mut self, line: usize, removed: usize, inserted: usize) {
+        if removed != inserted {
+            let old_end = line + removed;
+            let shifted: Vec<usize> = self
+                .lines
+                .iter()
+                .filter(|l| **l > old_end)
+                .map(|l| l - removed + inserted)
+                .collect();
+            self.lines.retain(|l| *l < line);
+            self.lines.extend(shifted);
+        }
+        self.lines.extend(line..=line + inserted);
+    }
+
+    /// Check if line was edited
+    pub fn contains(&self, line: usize) -> bool {
+        self.lines.contains(&line)
+    }
+
+    /// Forget all edits (after save)
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inserted_lines_shift_dirty_lines() {
+        let mut dirty = DirtyLines::default();
+        dirty.record_edit(5, 0, 0);
+        dirty.record_edit(1, 0, 2);
+
+        assert!(dirty.contains(1) && dirty.contains(2) && dirty.contains(3));
+        assert!(!dirty.contains(5));
+        assert!(dirty.contains(7));
+    }
+
+    #[test]
+    fn test_removed_lines_shift_dirty_lines() {
+        let mut dirty = DirtyLines::default();
+        dirty.record_edit(2, 0, 0);
+        dirty.record_edit(8, 0, 0);
+        dirty.record_edit(1, 3, 0);
+
+        assert!(dirty.contains(1));
+        assert!(!dirty.contains(2));
+        assert!(dirty.contains(5));
+        assert!(!dirty.contains(8));
+    }
+}
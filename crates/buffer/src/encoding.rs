@@ -0,0 +1,250 @@
+//! Text encodings of files on disk.
+//!
+//! The rope always holds UTF-8: files are decoded with the detected (or
+//! chosen) encoding on load and encoded back to it on save.
+
+use anyhow::Result;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// Encodings offered when reopening a file, by label
+pub const COMMON_ENCODINGS: &[&str] = &[
+    "UTF-8",
+    "UTF-16LE",
+    "UTF-16BE",
+    "windows-1252",
+    "ISO-8859-15",
+    "windows-1250",
+    "ISO-8859-2",
+    "windows-1251",
+    "KOI8-R",
+    "IBM866",
+    "windows-1253",
+    "windows-1254",
+    "windows-1255",
+    "windows-1256",
+    "windows-874",
+    "Shift_JIS",
+    "EUC-JP",
+    "EUC-KR",
+    "GBK",
+    "gb18030",
+    "Big5",
+];
+
+/// Bytes checked by the heuristic detector for non-UTF-8 files
+const DETECT_SAMPLE_BYTES: usize = 64 * 1024;
+
+/// Encoding of a file with its byte order mark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEncoding {
+    encoding: &'static Encoding,
+    /// File starts with a byte order mark (written back on save)
+    bom: bool,
+}
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        Self {
+            encoding: UTF_8,
+            bom: false,
+        }
+    }
+}
+
+impl TextEncoding {
+    /// Encoding for a label like `utf-8`, `latin1` or `cp1251`
+    pub fn for_label(label: &str) -> Option<Self> {
+        Encoding::for_label(label.as_bytes()).map(|encoding| Self {
+            encoding,
+            bom: false,
+        })
+    }
+
+    /// Detect the encoding of file contents.
+    ///
+    /// A byte order mark wins; then text with NUL bytes in every other
+    /// position is UTF-16, mostly valid UTF-8 is UTF-8 (invalid bytes
+    /// become replacement characters), and anything else is guessed from
+    /// byte frequencies.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return Self {
+                encoding,
+                bom: true,
+            };
+        }
+        if let Some(encoding) = detect_utf16(bytes) {
+            return Self {
+                encoding,
+                bom: false,
+            };
+        }
+        if is_mostly_utf8(bytes) {
+            return Self::default();
+        }
+
+        let sample = &bytes[..bytes.len().min(DETECT_SAMPLE_BYTES)];
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(sample, sample.len() == bytes.len());
+        Self {
+            encoding: detector.guess(None, false),
+            bom: false,
+        }
+    }
+
+    /// Name shown to the user (`UTF-8`, `UTF-8 BOM`, `windows-1251`)
+    pub fn name(&self) -> String {
+        let name = self.encoding.name();
+        if self.bom && self.encoding == UTF_8 {
+            format!("{} BOM", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Same encoding without a byte order mark
+    pub fn without_bom(self) -> Self {
+        Self { bom: false, ..self }
+    }
+
+    /// Check if this is UTF-8 without a byte order mark
+    pub fn is_plain_utf8(&self) -> bool {
+        self.encoding == UTF_8 && !self.bom
+    }
+
+    /// Decode file contents, dropping the byte order mark.
+    ///
+    /// Returns the encoding with the BOM flag set from the contents, and
+    /// whether invalid byte sequences were replaced with U+FFFD.
+    pub fn decode(self, bytes: &[u8]) -> (String, Self, bool) {
+        let bom = Encoding::for_bom(bytes).is_some_and(|(found, _)| found == self.encoding);
+        let (text, had_errors) = self.encoding.decode_with_bom_removal(bytes);
+        (
+            text.into_owned(),
+            Self {
+                encoding: self.encoding,
+                bom,
+            },
+            had_errors,
+        )
+    }
+
+    /// Encode text for saving.
+    ///
+    /// Fails if the text has characters this encoding cannot represent.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            // encoding_rs only decodes UTF-16
+            let little_endian = self.encoding == UTF_16LE;
+            if self.bom {
+                bytes.extend_from_slice(if little_endian {
+                    &[0xFF, 0xFE]
+                } else {
+                    &[0xFE, 0xFF]
+                });
+            }
+            for unit in text.encode_utf16() {
+                bytes.extend_from_slice(&if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+            return Ok(bytes);
+        }
+
+        if self.bom && self.encoding == UTF_8 {
+            bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+        }
+        let (encoded, _, unmappable) = self.encoding.encode(text);
+        if unmappable {
+            let ch = text
+                .chars()
+                .find(|ch| self.encoding.encode(ch.encode_utf8(&mut [0; 4])).2)
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            anyhow::bail!(
+                "Character '{}' (U+{:04X}) cannot be saved in {}",
+                ch,
+                ch as u32,
+                self.name()
+            );
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+}
+
+/// Check if bytes are UTF-8, allowing a few invalid sequences among
+/// valid non-ASCII characters (a damaged UTF-8 file rather than another
+/// encoding)
+fn is_mostly_utf8(bytes: &[u8]) -> bool {
+    let mut invalid = 0;
+    let mut non_ascii = 0;
+    for chunk in bytes.utf8_chunks() {
+        non_ascii += chunk.valid().chars().filter(|ch| !ch.is_ascii()).count();
+        if !chunk.invalid().is_empty() {
+            invalid += 1;
+        }
+    }
+    invalid == 0 || non_ascii >= invalid * 4
+}
+
+/// Detect UTF-16 without BOM by NUL high bytes of ASCII characters
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let sample = &bytes[..bytes.len().min(DETECT_SAMPLE_BYTES)];
+    let pairs = sample.len() / 2;
+    let even_nuls = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_nuls = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|b| **b == 0)
+        .count();
+    // Mostly ASCII text: one byte of each pair is NUL, the other is not
+    if odd_nuls * 2 > pairs && even_nuls * 10 < pairs {
+        Some(UTF_16LE)
+    } else if even_nuls * 2 > pairs && odd_nuls * 10 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert!(TextEncoding::detect(b"plain ascii").is_plain_utf8());
+        assert!(TextEncoding::detect("Привет".as_bytes()).is_plain_utf8());
+        assert_eq!(TextEncoding::detect(b"\xEF\xBB\xBFbom").name(), "UTF-8 BOM");
+        assert_eq!(TextEncoding::detect(b"\xFF\xFEh\0i\0").name(), "UTF-16LE");
+        assert_eq!(TextEncoding::detect(b"\0h\0i\0!").name(), "UTF-16BE");
+        // "Привет, мир! Это тест." in windows-1251
+        let cp1251 = b"\xcf\xf0\xe8\xe2\xe5\xf2, \xec\xe8\xf0! \xdd\xf2\xee \xf2\xe5\xf1\xf2.";
+        assert_eq!(TextEncoding::detect(cp1251).name(), "windows-1251");
+        // One damaged byte in UTF-8 text stays UTF-8
+        let damaged = ["Привет, ".as_bytes(), b"\xff", " мир".as_bytes()].concat();
+        assert!(TextEncoding::detect(&damaged).is_plain_utf8());
+    }
+
+    #[test]
+    fn test_invalid_bytes_replaced() {
+        let (text, _, had_errors) = TextEncoding::default().decode(b"a\xffb");
+        assert_eq!(text, "a\u{FFFD}b");
+        assert!(had_errors);
+    }
+
+    #[test]
+    fn test_unmappable_characters_rejected() {
+        let cp1251 = TextEncoding::for_label("cp1251").unwrap();
+        assert!(cp1251.encode("Привет").is_ok());
+        let error = cp1251.encode("Привет 你").unwrap_err().to_string();
+        assert!(error.contains("U+4F60"));
+    }
+}
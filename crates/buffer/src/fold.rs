@@ -0,0 +1,396 @@
+//! Code folding: foldable regions and folded line ranges.
+
+use std::collections::BTreeMap;
+
+use crate::indent::indent_column;
+
+/// Range of lines that can be folded.
+///
+/// The header line `start` stays visible, lines `start + 1..=end` are hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FoldRange {
+    /// Create a new fold range
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Number of lines hidden when folded
+    pub fn hidden_lines(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Check if line is the header or hidden part of the range
+    pub fn contains(&self, line: usize) -> bool {
+        line >= self.start && line <= self.end
+    }
+}
+
+/// Lines inserted and removed by a single buffer edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineEdit {
+    /// First edited line
+    pub line: usize,
+    /// Line breaks removed
+    pub removed: usize,
+    /// Line breaks inserted
+    pub inserted: usize,
+}
+
+impl LineEdit {
+    /// Shift a line below the edit, None if the line was touched by the edit
+    pub(crate) fn shift(&self, line: usize) -> Option<usize> {
+        (line > self.line + self.removed).then(|| line - self.removed + self.inserted)
+    }
+
+    /// Line a position moves to: shifted when below the edit, kept within
+    /// the edited lines otherwise
+    pub fn move_line(&self, line: usize) -> usize {
+        self.shift(line)
+            .unwrap_or_else(|| line.min(self.line + self.inserted))
+    }
+}
+
+/// Foldable regions and folded ranges of a document view.
+///
+/// Folded ranges never overlap: folding a range drops folds nested in it.
+#[derive(Debug, Clone, Default)]
+pub struct FoldMap {
+    /// Folded ranges: header line -> last hidden line
+    folded: BTreeMap<usize, usize>,
+    /// Foldable regions sorted by header line
+    regions: Vec<FoldRange>,
+}
+
+impl FoldMap {
+    /// Replace foldable regions
+    pub fn set_regions(&mut self, mut regions: Vec<FoldRange>) {
+        regions.retain(|region| region.hidden_lines() > 0);
+        regions.sort_by_key(|region| (region.start, region.end));
+        self.regions = regions;
+    }
+
+    /// Foldable regions sorted by header line
+    pub fn regions(&self) -> &[FoldRange] {
+        &self.regions
+    }
+
+    /// Check if a foldable region starts at line
+    pub fn is_foldable(&self, line: usize) -> bool {
+        self.regions
+            .binary_search_by_key(&line, |region| region.start)
+            .is_ok()
+    }
+
+    /// Smallest foldable region containing line (as header or hidden line)
+    pub fn innermost_region(&self, line: usize) -> Option<FoldRange> {
+        self.regions
+            .iter()
+            .take_while(|region| region.start <= line)
+            .filter(|region| region.contains(line))
+            .min_by_key(|region| region.hidden_lines())
+            .copied()
+    }
+
+    /// Check if any range is folded
+    pub fn has_folds(&self) -> bool {
+        !self.folded.is_empty()
+    }
+
+    /// Folded ranges in line order
+    pub fn folded(&self) -> impl Iterator<Item = FoldRange> + '_ {
+        self.folded
+            .iter()
+            .map(|(&start, &end)| FoldRange::new(start, end))
+    }
+
+    /// Check if line is the header of a folded range
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.folded.contains_key(&line)
+    }
+
+    /// Fold a range (ignored if empty or its header is hidden)
+    pub fn fold(&mut self, range: FoldRange) -> bool {
+        if range.hidden_lines() == 0 || self.is_hidden(range.start) {
+            return false;
+        }
+        self.folded
+            .retain(|&start, _| start < range.start || start > range.end);
+        self.folded.insert(range.start, range.end);
+        true
+    }
+
+    /// Unfold the range with given header line
+    pub fn unfold(&mut self, start: usize) -> bool {
+        self.folded.remove(&start).is_some()
+    }
+
+    /// Fold all outermost foldable regions
+    pub fn fold_all(&mut self) {
+        self.folded.clear();
+        let mut last_end = None;
+        for region in &self.regions {
+            if last_end.is_some_and(|end| region.start <= end) {
+                continue;
+            }
+            self.folded.insert(region.start, region.end);
+            last_end = Some(region.end);
+        }
+    }
+
+    /// Unfold all ranges
+    pub fn unfold_all(&mut self) {
+        self.folded.clear();
+    }
+
+    /// Folded range hiding line
+    fn hiding(&self, line: usize) -> Option<FoldRange> {
+        self.folded
+            .range(..line)
+            .next_back()
+            .map(|(&start, &end)| FoldRange::new(start, end))
+            .filter(|range| line <= range.end)
+    }
+
+    /// Check if line is hidden by a folded range
+    pub fn is_hidden(&self, line: usize) -> bool {
+        self.hiding(line).is_some()
+    }
+
+    /// Unfold the range hiding line. Returns true if anything was unfolded
+    pub fn reveal(&mut self, line: usize) -> bool {
+        match self.hiding(line) {
+            Some(range) => self.unfold(range.start),
+            None => false,
+        }
+    }
+
+    /// Line shown in place of line (header of the range hiding it)
+    pub fn visible_line(&self, line: usize) -> usize {
+        self.hiding(line).map_or(line, |range| range.start)
+    }
+
+    /// First visible line after line
+    pub fn next_visible(&self, line: usize) -> usize {
+        self.folded.get(&line).map_or(line, |&end| end) + 1
+    }
+
+    /// Last visible line before line
+    pub fn prev_visible(&self, line: usize) -> Option<usize> {
+        line.checked_sub(1).map(|prev| self.visible_line(prev))
+    }
+
+    /// Total number of hidden lines
+    pub fn hidden_lines(&self) -> usize {
+        self.folded().map(|range| range.hidden_lines()).sum()
+    }
+
+    /// Visible row of line (hidden lines map to their header's row)
+    pub fn row_of(&self, line: usize) -> usize {
+        let line = self.visible_line(line);
+        let hidden: usize = self
+            .folded()
+            .take_while(|range| range.end < line)
+            .map(|range| range.hidden_lines())
+            .sum();
+        line - hidden
+    }
+
+    /// Line shown at visible row
+    pub fn line_of(&self, row: usize) -> usize {
+        let mut line = row;
+        for range in self.folded() {
+            if range.start >= line {
+                break;
+            }
+            line += range.hidden_lines();
+        }
+        line
+    }
+
+    /// Update ranges after a buffer edit: ranges touched by the edit or
+    /// right next to it are unfolded, ranges below are shifted
+    pub fn record_edit(&mut self, edit: &LineEdit) {
+        let touched = |range: &FoldRange| {
+            range.start <= edit.line + edit.removed && range.end + 1 >= edit.line
+        };
+
+        self.folded = self
+            .folded()
+            .filter(|range| !touched(range))
+            .map(|range| match edit.shift(range.start) {
+                Some(start) => (start, edit.shift(range.end).unwrap_or(range.end)),
+                None => (range.start, range.end),
+            })
+            .collect();
+
+        self.regions = self
+            .regions
+            .iter()
+            .filter(|range| !touched(range))
+            .map(|range| match edit.shift(range.start) {
+                Some(start) => FoldRange::new(start, edit.shift(range.end).unwrap_or(range.end)),
+                None => *range,
+            })
+            .collect();
+    }
+}
+
+/// Foldable regions computed from indentation: a line followed by more
+/// indented lines folds up to the last of them (blank lines are skipped)
+pub fn indent_regions<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    tab_width: usize,
+) -> Vec<FoldRange> {
+    let mut regions = Vec::new();
+    // Open headers: (indent, line)
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut last_content = 0;
+
+    for (idx, line) in lines.into_iter().enumerate() {
+        let content = line.trim_start_matches([' ', '\t']);
+        if content.trim().is_empty() {
+            continue;
+        }
+        let indent = indent_column(&line[..line.len() - content.len()], tab_width);
+        while let Some(&(open_indent, start)) = open.last() {
+            if open_indent < indent {
+                break;
+            }
+            open.pop();
+            if last_content > start {
+                regions.push(FoldRange::new(start, last_content));
+            }
+        }
+        open.push((indent, idx));
+        last_content = idx;
+    }
+
+    for (_, start) in open {
+        if last_content > start {
+            regions.push(FoldRange::new(start, last_content));
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_with_regions(regions: &[(usize, usize)]) -> FoldMap {
+        let mut folds = FoldMap::default();
+        folds.set_regions(
+            regions
+                .iter()
+                .map(|&(start, end)| FoldRange::new(start, end))
+                .collect(),
+        );
+        folds
+    }
+
+    #[test]
+    fn test_rows_skip_folded_lines() {
+        let mut folds = FoldMap::default();
+        folds.fold(FoldRange::new(2, 5));
+        folds.fold(FoldRange::new(8, 9));
+
+        assert!(folds.is_hidden(3) && folds.is_hidden(5));
+        assert!(!folds.is_hidden(2) && !folds.is_hidden(6));
+        assert_eq!(folds.next_visible(2), 6);
+        assert_eq!(folds.prev_visible(6), Some(2));
+        assert_eq!(folds.hidden_lines(), 4);
+
+        assert_eq!(folds.row_of(2), 2);
+        assert_eq!(folds.row_of(4), 2);
+        assert_eq!(folds.row_of(6), 3);
+        assert_eq!(folds.row_of(10), 6);
+        for row in 0..8 {
+            assert_eq!(folds.row_of(folds.line_of(row)), row);
+        }
+        assert_eq!(folds.line_of(3), 6);
+        assert_eq!(folds.line_of(6), 10);
+    }
+
+    #[test]
+    fn test_fold_all_folds_outermost_regions() {
+        let mut folds = map_with_regions(&[(4, 5), (0, 6), (1, 2), (8, 10)]);
+        assert!(folds.is_foldable(1));
+        assert_eq!(folds.innermost_region(2), Some(FoldRange::new(1, 2)));
+        assert_eq!(folds.innermost_region(3), Some(FoldRange::new(0, 6)));
+        assert_eq!(folds.innermost_region(7), None);
+
+        folds.fold_all();
+        let folded: Vec<_> = folds.folded().collect();
+        assert_eq!(folded, vec![FoldRange::new(0, 6), FoldRange::new(8, 10)]);
+
+        // Folding an outer range drops nested folds
+        folds.unfold_all();
+        folds.fold(FoldRange::new(1, 2));
+        folds.fold(FoldRange::new(0, 6));
+        assert_eq!(folds.folded().count(), 1);
+        assert!(!folds.fold(FoldRange::new(4, 5)));
+    }
+
+    #[test]
+    fn test_edits_shift_or_unfold_ranges() {
+        let mut folds = map_with_regions(&[(2, 4), (10, 12)]);
+        folds.fold_all();
+
+        // Two lines inserted above both folds
+        folds.record_edit(&LineEdit {
+            line: 0,
+            removed: 0,
+            inserted: 2,
+        });
+        assert!(folds.is_folded(4) && folds.is_folded(12));
+        assert!(folds.is_foldable(4));
+
+        // Edit right after the first fold unfolds it
+        folds.record_edit(&LineEdit {
+            line: 7,
+            removed: 0,
+            inserted: 0,
+        });
+        assert!(!folds.is_folded(4));
+        assert!(folds.is_folded(12));
+
+        // Deleting lines inside the fold unfolds it
+        folds.record_edit(&LineEdit {
+            line: 11,
+            removed: 2,
+            inserted: 0,
+        });
+        assert!(!folds.has_folds());
+    }
+
+    #[test]
+    fn test_indent_regions() {
+        let text = "fn a() {\n    if x {\n        y();\n\n    }\n}\nfn b() {}\nclass C:\n\tdef f():\n\t\tpass\n";
+        let mut regions = indent_regions(text.lines(), 4);
+        regions.sort_by_key(|region| region.start);
+        assert_eq!(
+            regions,
+            vec![
+                FoldRange::new(0, 4),
+                FoldRange::new(1, 2),
+                FoldRange::new(7, 9),
+                FoldRange::new(8, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reveal_unfolds_hiding_range() {
+        let mut folds = FoldMap::default();
+        folds.fold(FoldRange::new(2, 5));
+        assert_eq!(folds.visible_line(4), 2);
+        assert!(!folds.reveal(2));
+        assert!(folds.reveal(4));
+        assert!(!folds.is_hidden(4));
+    }
+}
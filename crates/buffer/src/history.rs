@@ -1,4 +1,7 @@
-use super::Cursor;
+use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
+
+use super::{Cursor, LineEnding};
 
 /// Action for undo/redo
 #[derive(Debug, Clone)]
@@ -7,9 +10,14 @@ pub enum Action {
     Insert { position: Cursor, text: String },
     /// Text deletion
     Delete { position: Cursor, text: String },
-    /// Action group (for merging consecutive insertions)
-    #[allow(dead_code)]
+    /// Action group (undone and redone as a single step)
     Group { actions: Vec<Action> },
+    /// Line ending change (position is the cursor to restore)
+    SetLineEnding {
+        position: Cursor,
+        from: LineEnding,
+        to: LineEnding,
+    },
 }
 
 impl Action {
@@ -27,6 +35,11 @@ impl Action {
             Action::Group { actions } => Action::Group {
                 actions: actions.iter().rev().map(|a| a.inverse()).collect(),
             },
+            Action::SetLineEnding { position, from, to } => Action::SetLineEnding {
+                position: *position,
+                from: *to,
+                to: *from,
+            },
         }
     }
 
@@ -107,17 +120,61 @@ impl Action {
     }
 }
 
-/// Edit history for undo/redo
+/// Node of the undo tree
+#[derive(Debug, Clone)]
+struct Node {
+    /// Action leading from the parent's text to this node's text
+    action: Action,
+    /// Parent node (None for the root, the oldest text kept)
+    parent: Option<usize>,
+    /// Child nodes, oldest first
+    children: Vec<usize>,
+    /// Child that redo goes to (the one created or left last)
+    redo: Option<usize>,
+    /// When the action was recorded
+    time: SystemTime,
+}
+
+/// Branch of the undo tree: the edits from the root to a leaf.
+#[derive(Debug, Clone)]
+pub struct UndoBranch {
+    /// Leaf node of the branch (for `History::switch_to`)
+    pub id: usize,
+    /// When the last edit of the branch was recorded
+    pub time: SystemTime,
+    /// Number of edits on the branch
+    pub edits: usize,
+    /// First edit of the branch that is not applied to the current text
+    /// (None if the current text is the end of the branch)
+    pub first_difference: Option<Action>,
+    /// Redo follows this branch from the current text
+    pub current: bool,
+}
+
+/// Edit history for undo/redo.
+///
+/// Edits form a tree: an edit after an undo starts a new branch instead of
+/// dropping the undone edits. Undo goes to the parent and redo to the
+/// child created or left last, so they walk the current branch like a
+/// linear history; `switch_to` moves to another branch. The oldest
+/// abandoned branches (then the oldest edits) are pruned beyond
+/// `max_nodes` edits.
 #[derive(Debug, Clone)]
 pub struct History {
-    /// Action stack for undo
-    undo_stack: Vec<Action>,
-    /// Action stack for redo
-    redo_stack: Vec<Action>,
-    /// Maximum history size
-    max_size: usize,
+    /// Nodes of the tree by id
+    nodes: HashMap<usize, Node>,
+    /// Root node (the text before the oldest edit kept)
+    root: usize,
+    /// Node of the current text
+    current: usize,
+    /// Id of the next node
+    next_id: usize,
+    /// Maximum number of edits kept
+    max_nodes: usize,
     /// Current accumulated action
     pending_action: Option<Action>,
+    /// Actions collected while a group is open
+    group: Option<Vec<Action>>,
 }
 
 impl History {
@@ -126,20 +183,41 @@ impl History {
         Self::with_capacity(1000)
     }
 
-    /// Create history with specified size
-    pub fn with_capacity(max_size: usize) -> Self {
+    /// Create history keeping at most `max_nodes` edits
+    pub fn with_capacity(max_nodes: usize) -> Self {
+        let root = Node {
+            action: Action::Group {
+                actions: Vec::new(),
+            },
+            parent: None,
+            children: Vec::new(),
+            redo: None,
+            time: SystemTime::now(),
+        };
         Self {
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-            max_size,
+            nodes: HashMap::from([(0, root)]),
+            root: 0,
+            current: 0,
+            next_id: 1,
+            max_nodes,
             pending_action: None,
+            group: None,
         }
     }
 
+    /// Change the maximum number of edits kept
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+        self.prune();
+    }
+
     /// Record action to history
     pub fn push(&mut self, action: Action) {
-        // Clear redo stack on new action
-        self.redo_stack.clear();
+        // Collect into open group without merging
+        if let Some(group) = &mut self.group {
+            group.push(action);
+            return;
+        }
 
         // Try to merge with previous action
         if let Some(pending) = &mut self.pending_action {
@@ -152,23 +230,103 @@ impl History {
                     .pending_action
                     .take()
                     .expect("pending_action is Some inside if let Some branch");
-                self.undo_stack.push(completed);
+                self.add_node(completed);
             }
         }
 
         // Start new accumulation
         self.pending_action = Some(action);
-
-        // Limit history size
-        if self.undo_stack.len() > self.max_size {
-            self.undo_stack.remove(0);
-        }
     }
 
     /// Complete current action group (e.g., on focus loss)
     pub fn commit_pending(&mut self) {
         if let Some(action) = self.pending_action.take() {
-            self.undo_stack.push(action);
+            self.add_node(action);
+        }
+    }
+
+    /// Start collecting actions into a single undo step
+    pub fn begin_group(&mut self) {
+        self.commit_pending();
+        self.group = Some(Vec::new());
+    }
+
+    /// Finish the open group and record it as one action
+    pub fn end_group(&mut self) {
+        if let Some(actions) = self.group.take() {
+            if !actions.is_empty() {
+                self.add_node(Action::Group { actions });
+            }
+        }
+    }
+
+    /// Record an action as a child of the current node (a new branch if
+    /// the current node already has children) and make it current
+    fn add_node(&mut self, action: Action) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.insert(
+            id,
+            Node {
+                action,
+                parent: Some(self.current),
+                children: Vec::new(),
+                redo: None,
+                time: SystemTime::now(),
+            },
+        );
+        let parent = self.node_mut(self.current);
+        parent.children.push(id);
+        parent.redo = Some(id);
+        self.current = id;
+        self.prune();
+    }
+
+    fn node(&self, id: usize) -> &Node {
+        self.nodes.get(&id).expect("undo tree node ids are valid")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node {
+        self.nodes
+            .get_mut(&id)
+            .expect("undo tree node ids are valid")
+    }
+
+    /// Nodes from `id` up to the root
+    fn path_to_root(&self, id: usize) -> Vec<usize> {
+        std::iter::successors(Some(id), |&id| self.node(id).parent).collect()
+    }
+
+    /// Drop the oldest branches not leading to the current node, then the
+    /// oldest edits, until at most `max_nodes` edits are left
+    fn prune(&mut self) {
+        while self.nodes.len() > self.max_nodes + 1 {
+            let path: HashSet<usize> = self.path_to_root(self.current).into_iter().collect();
+            let oldest_leaf = self
+                .nodes
+                .iter()
+                .filter(|(id, node)| node.children.is_empty() && !path.contains(id))
+                .map(|(&id, _)| id)
+                .min();
+            if let Some(leaf) = oldest_leaf {
+                let node = self.nodes.remove(&leaf).expect("leaf is in the tree");
+                if let Some(parent) = node.parent {
+                    let parent = self.node_mut(parent);
+                    parent.children.retain(|&child| child != leaf);
+                    if parent.redo == Some(leaf) {
+                        parent.redo = parent.children.last().copied();
+                    }
+                }
+                continue;
+            }
+
+            // Only the current branch is left: forget its oldest edit
+            let Some(&next_root) = self.node(self.root).children.first() else {
+                break;
+            };
+            self.nodes.remove(&self.root);
+            self.node_mut(next_root).parent = None;
+            self.root = next_root;
         }
     }
 
@@ -177,13 +335,14 @@ impl History {
         // First complete current action
         self.commit_pending();
 
-        if let Some(action) = self.undo_stack.pop() {
-            let inverse = action.inverse();
-            self.redo_stack.push(action);
-            Some(inverse)
-        } else {
-            None
-        }
+        let current = self.current;
+        let node = self.node(current);
+        let parent = node.parent?;
+        let inverse = node.action.inverse();
+        // Redo comes back to this branch
+        self.node_mut(parent).redo = Some(current);
+        self.current = parent;
+        Some(inverse)
     }
 
     /// Redo undone action
@@ -191,34 +350,96 @@ impl History {
         // Complete current action before redo
         self.commit_pending();
 
-        if let Some(action) = self.redo_stack.pop() {
-            // Return original action (not inverse!)
-            // Store action in undo_stack for possible subsequent undo
-            self.undo_stack.push(action.clone());
-            Some(action)
-        } else {
-            None
+        let child = self.node(self.current).redo?;
+        self.current = child;
+        // Return original action (not inverse!)
+        Some(self.node(child).action.clone())
+    }
+
+    /// Branches of the tree, most recently edited first
+    pub fn branches(&mut self) -> Vec<UndoBranch> {
+        self.commit_pending();
+        if self.nodes.len() == 1 {
+            return Vec::new();
+        }
+        let current_path: HashSet<usize> = self.path_to_root(self.current).into_iter().collect();
+        let redo_leaf = std::iter::successors(Some(self.current), |&id| self.node(id).redo)
+            .last()
+            .unwrap_or(self.current);
+
+        let mut branches: Vec<UndoBranch> = self
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.children.is_empty())
+            .map(|(&id, node)| {
+                let path = self.path_to_root(id);
+                // Path nodes below the last node shared with the current text
+                let shared = path
+                    .iter()
+                    .position(|id| current_path.contains(id))
+                    .unwrap_or(path.len());
+                UndoBranch {
+                    id,
+                    time: node.time,
+                    edits: path.len() - 1,
+                    first_difference: shared
+                        .checked_sub(1)
+                        .map(|i| self.node(path[i]).action.clone()),
+                    current: id == redo_leaf,
+                }
+            })
+            .collect();
+        branches.sort_by_key(|branch| std::cmp::Reverse((branch.time, branch.id)));
+        branches
+    }
+
+    /// Move to the end of the branch with leaf `id`: returns the actions
+    /// to apply in order (undoing up to the edit shared with the branch,
+    /// then redoing its edits), or None if there is no such branch
+    pub fn switch_to(&mut self, id: usize) -> Option<Vec<Action>> {
+        self.commit_pending();
+        if !self.nodes.contains_key(&id) {
+            return None;
+        }
+
+        let target_path = self.path_to_root(id);
+        let mut actions = Vec::new();
+        while !target_path.contains(&self.current) {
+            actions.extend(self.undo());
+        }
+        let shared = target_path
+            .iter()
+            .position(|&node| node == self.current)
+            .expect("the root is on every path");
+        for &node in target_path[..shared].iter().rev() {
+            self.node_mut(self.current).redo = Some(node);
+            actions.extend(self.redo());
         }
+        Some(actions)
     }
 
     /// Check if undo is possible
     #[allow(dead_code)]
     pub fn can_undo(&self) -> bool {
-        !self.undo_stack.is_empty() || self.pending_action.is_some()
+        self.current != self.root || self.pending_action.is_some()
     }
 
     /// Check if redo is possible
     #[allow(dead_code)]
     pub fn can_redo(&self) -> bool {
-        !self.redo_stack.is_empty()
+        self.pending_action.is_none() && self.node(self.current).redo.is_some()
+    }
+
+    /// Number of edits undo can go back through (test helper)
+    #[cfg(test)]
+    fn undo_depth(&self) -> usize {
+        self.path_to_root(self.current).len() - 1
     }
 
     /// Clear history
     #[allow(dead_code)]
     pub fn clear(&mut self) {
-        self.undo_stack.clear();
-        self.redo_stack.clear();
-        self.pending_action = None;
+        *self = Self::with_capacity(self.max_nodes);
     }
 }
 
@@ -290,7 +511,7 @@ mod tests {
         history.commit_pending();
 
         // Should be one action in stack
-        assert_eq!(history.undo_stack.len(), 1);
+        assert_eq!(history.undo_depth(), 1);
 
         let undo_action = history.undo().unwrap();
         match undo_action {
@@ -348,6 +569,123 @@ mod tests {
         history.commit_pending();
 
         // Newline should break merging
-        assert_eq!(history.undo_stack.len(), 2);
+        assert_eq!(history.undo_depth(), 2);
+    }
+
+    #[test]
+    fn test_group_is_single_undo_step() {
+        let mut history = History::new();
+
+        history.begin_group();
+        history.push(Action::Insert {
+            position: Cursor::at(0, 0),
+            text: "a".to_string(),
+        });
+        history.push(Action::Insert {
+            position: Cursor::at(1, 0),
+            text: "b".to_string(),
+        });
+        history.end_group();
+
+        assert_eq!(history.undo_depth(), 1);
+
+        match history.undo().unwrap() {
+            Action::Group { actions } => {
+                assert_eq!(actions.len(), 2);
+                assert!(matches!(
+                    &actions[0],
+                    Action::Delete { position, .. } if *position == Cursor::at(1, 0)
+                ));
+            }
+            _ => panic!("Expected Group action"),
+        }
+        assert!(!history.can_undo());
+    }
+
+    fn insert(text: &str) -> Action {
+        Action::Insert {
+            position: Cursor::at(0, 0),
+            text: text.to_string(),
+        }
+    }
+
+    /// Record an action as its own undo step
+    fn edit(history: &mut History, text: &str) {
+        history.push(insert(text));
+        history.commit_pending();
+    }
+
+    fn inserted(action: &Action) -> &str {
+        match action {
+            Action::Insert { text, .. } | Action::Delete { text, .. } => text,
+            _ => panic!("Expected Insert or Delete action"),
+        }
+    }
+
+    #[test]
+    fn test_edit_after_undo_keeps_redo_branch() {
+        let mut history = History::new();
+        for text in ["a", "b", "c"] {
+            edit(&mut history, text);
+        }
+        history.undo();
+        history.undo();
+        edit(&mut history, "x");
+
+        // Undo/redo walk the new branch
+        assert!(!history.can_redo());
+        assert_eq!(inserted(&history.undo().unwrap()), "x");
+        assert_eq!(inserted(&history.redo().unwrap()), "x");
+
+        let branches = history.branches();
+        assert_eq!(branches.len(), 2);
+        let current = branches.iter().find(|b| b.current).unwrap();
+        assert_eq!(current.edits, 2);
+        assert!(current.first_difference.is_none());
+        let old = branches.iter().find(|b| !b.current).unwrap();
+        assert_eq!(old.edits, 3);
+        assert_eq!(inserted(old.first_difference.as_ref().unwrap()), "b");
+
+        // Switching undoes "x" and redoes "b" and "c"
+        let actions = history.switch_to(old.id).unwrap();
+        let texts: Vec<_> = actions.iter().map(inserted).collect();
+        assert_eq!(texts, ["x", "b", "c"]);
+        assert!(matches!(actions[0], Action::Delete { .. }));
+        assert!(matches!(actions[2], Action::Insert { .. }));
+        assert_eq!(history.undo_depth(), 3);
+
+        // Redo after undo stays on the branch switched to
+        history.undo();
+        history.undo();
+        assert_eq!(inserted(&history.redo().unwrap()), "b");
+        assert!(history.switch_to(1000).is_none());
+    }
+
+    #[test]
+    fn test_prune_oldest_branches_first() {
+        let mut history = History::with_capacity(4);
+        edit(&mut history, "a");
+        edit(&mut history, "b");
+        history.undo();
+        edit(&mut history, "c");
+        history.undo();
+        edit(&mut history, "d");
+        assert_eq!(history.branches().len(), 3);
+
+        // The branch of "b" is the oldest abandoned one
+        edit(&mut history, "e");
+        let branches = history.branches();
+        assert_eq!(branches.len(), 2);
+        let old = branches.iter().find(|b| !b.current).unwrap();
+        assert_eq!(inserted(old.first_difference.as_ref().unwrap()), "c");
+
+        // Then "c", then the oldest edits of the current branch
+        edit(&mut history, "f");
+        edit(&mut history, "g");
+        assert_eq!(history.branches().len(), 1);
+        assert_eq!(history.undo_depth(), 4);
+        let undone: Vec<_> = std::iter::from_fn(|| history.undo()).collect();
+        let texts: Vec<_> = undone.iter().map(inserted).collect();
+        assert_eq!(texts, ["g", "f", "e", "d"]);
     }
 }
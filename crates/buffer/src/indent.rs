@@ -0,0 +1,144 @@
+//! Indentation style detection.
+
+use std::cmp::Reverse;
+
+/// Number of lines scanned when detecting indentation
+const DETECT_LINES: usize = 500;
+
+/// Largest indent width considered during detection
+const MAX_INDENT_WIDTH: usize = 8;
+
+/// Indentation style of a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// One tab character per indent level
+    Tabs,
+    /// Given number of spaces per indent level
+    Spaces(usize),
+}
+
+impl IndentStyle {
+    /// Detect indentation style from the first lines of a text.
+    ///
+    /// Tabs win if more lines start with a tab than with spaces. For spaces,
+    /// the width is the most frequent indent increase between consecutive
+    /// non-blank lines. Returns None if the text has no indented lines.
+    pub fn detect<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut tab_lines = 0usize;
+        let mut space_lines = 0usize;
+        let mut deltas = [0usize; MAX_INDENT_WIDTH + 1];
+        let mut prev_indent = 0usize;
+
+        for line in lines.into_iter().take(DETECT_LINES) {
+            let content = line.trim_start_matches([' ', '\t']);
+            if content.trim().is_empty() {
+                continue;
+            }
+
+            let leading = &line[..line.len() - content.len()];
+            if leading.starts_with('\t') {
+                tab_lines += 1;
+                continue;
+            }
+            // Skip mixed indentation and block comment continuation lines
+            // (" * text"), which are aligned rather than indented
+            if leading.contains('\t') || content.starts_with('*') {
+                continue;
+            }
+
+            let indent = leading.len();
+            if indent > 0 {
+                space_lines += 1;
+            }
+            if indent > prev_indent && indent - prev_indent <= MAX_INDENT_WIDTH {
+                deltas[indent - prev_indent] += 1;
+            }
+            prev_indent = indent;
+        }
+
+        if tab_lines > space_lines {
+            return Some(Self::Tabs);
+        }
+
+        // Most frequent width, preferring the smaller one on ties
+        (2..=MAX_INDENT_WIDTH)
+            .filter(|width| deltas[*width] > 0)
+            .max_by_key(|width| (deltas[*width], Reverse(*width)))
+            .map(Self::Spaces)
+    }
+
+    /// Text inserted for one indent level
+    pub fn unit(&self) -> String {
+        match self {
+            Self::Tabs => "\t".to_string(),
+            Self::Spaces(width) => " ".repeat(*width),
+        }
+    }
+
+    /// Check if style uses tabs
+    pub fn is_tabs(&self) -> bool {
+        matches!(self, Self::Tabs)
+    }
+
+    /// Build leading whitespace for given visual indent column
+    pub fn indent_for_column(&self, column: usize, tab_width: usize) -> String {
+        match self {
+            Self::Tabs if tab_width > 0 => format!(
+                "{}{}",
+                "\t".repeat(column / tab_width),
+                " ".repeat(column % tab_width)
+            ),
+            _ => " ".repeat(column),
+        }
+    }
+}
+
+/// Visual column of leading whitespace (tabs advance to next tab stop)
+pub fn indent_column(leading: &str, tab_width: usize) -> usize {
+    leading.chars().fold(0, |column, ch| match ch {
+        '\t' if tab_width > 0 => (column / tab_width + 1) * tab_width,
+        _ => column + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_spaces_width() {
+        let text = "fn main() {\n  if x {\n    y();\n  }\n}\n";
+        assert_eq!(
+            IndentStyle::detect(text.lines()),
+            Some(IndentStyle::Spaces(2))
+        );
+
+        let text = "class A:\n    def f(self):\n        pass\n\n    def g(self):\n        pass\n";
+        assert_eq!(
+            IndentStyle::detect(text.lines()),
+            Some(IndentStyle::Spaces(4))
+        );
+    }
+
+    #[test]
+    fn test_detect_tabs() {
+        let text = "func main() {\n\tif x {\n\t\ty()\n\t}\n}\n/*\n * comment\n */\n";
+        assert_eq!(IndentStyle::detect(text.lines()), Some(IndentStyle::Tabs));
+    }
+
+    #[test]
+    fn test_detect_unindented_text() {
+        assert_eq!(IndentStyle::detect("one\ntwo\n\nthree".lines()), None);
+        assert_eq!(IndentStyle::detect(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_indent_conversion_helpers() {
+        assert_eq!(indent_column("\t  ", 4), 6);
+        assert_eq!(indent_column("  \t", 4), 4);
+        assert_eq!(IndentStyle::Tabs.indent_for_column(6, 4), "\t  ");
+        assert_eq!(IndentStyle::Spaces(2).indent_for_column(3, 4), "   ");
+        assert_eq!(IndentStyle::Tabs.unit(), "\t");
+        assert_eq!(IndentStyle::Spaces(3).unit(), "   ");
+    }
+}
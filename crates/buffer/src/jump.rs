@@ -0,0 +1,154 @@
+//! Jump list: cursor positions recorded before large movements.
+
+use crate::cursor::Cursor;
+use crate::fold::LineEdit;
+
+/// Positions to go back and forward to, like browser history.
+///
+/// Each line is recorded at most once: pushing a position drops older
+/// entries on the same line. Positions follow line edits but are not
+/// clamped to the buffer, callers clamp them when jumping.
+#[derive(Debug, Clone)]
+pub struct JumpList {
+    entries: Vec<Cursor>,
+    /// Current entry while navigating, `entries.len()` otherwise
+    index: usize,
+    /// Maximum number of entries (0 disables the list)
+    capacity: usize,
+}
+
+impl JumpList {
+    /// Create an empty jump list holding at most `capacity` positions
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            index: 0,
+            capacity,
+        }
+    }
+
+    /// Number of recorded positions
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no positions are recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Record the position a jump starts from.
+    ///
+    /// Positions ahead of the current entry are discarded, and the oldest
+    /// positions are dropped when the list is full.
+    pub fn push(&mut self, position: Cursor) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.truncate(self.index);
+        self.entries.retain(|entry| entry.line != position.line);
+        self.entries.push(position);
+        let excess = self.entries.len().saturating_sub(self.capacity);
+        self.entries.drain(..excess);
+        self.index = self.entries.len();
+    }
+
+    /// Go to the previous position. `current` is recorded first when not
+    /// navigating yet, so that `forward` can return to it
+    pub fn back(&mut self, current: Cursor) -> Option<Cursor> {
+        if self.index == self.entries.len() {
+            self.push(current);
+            self.index = self.entries.len().saturating_sub(1);
+        }
+        while self.index > 0 {
+            self.index -= 1;
+            let entry = self.entries[self.index];
+            if entry.line != current.line {
+                return Some(entry);
+            }
+        }
+        None
+    }
+
+    /// Go to the next position after going back
+    pub fn forward(&mut self, current: Cursor) -> Option<Cursor> {
+        while self.index + 1 < self.entries.len() {
+            self.index += 1;
+            let entry = self.entries[self.index];
+            if entry.line != current.line {
+                return Some(entry);
+            }
+        }
+        None
+    }
+
+    /// Update positions after a buffer edit: positions below are shifted,
+    /// positions in removed lines move to the end of the edit
+    pub fn record_edit(&mut self, edit: &LineEdit) {
+        for entry in &mut self.entries {
+            entry.line = edit.move_line(entry.line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(jumps: &JumpList) -> Vec<usize> {
+        jumps.entries.iter().map(|entry| entry.line).collect()
+    }
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut jumps = JumpList::new(10);
+        jumps.push(Cursor::at(1, 0));
+        jumps.push(Cursor::at(20, 3));
+
+        assert_eq!(jumps.back(Cursor::at(40, 0)), Some(Cursor::at(20, 3)));
+        assert_eq!(jumps.back(Cursor::at(20, 3)), Some(Cursor::at(1, 0)));
+        assert_eq!(jumps.back(Cursor::at(1, 0)), None);
+        assert_eq!(jumps.forward(Cursor::at(1, 0)), Some(Cursor::at(20, 3)));
+        assert_eq!(jumps.forward(Cursor::at(20, 3)), Some(Cursor::at(40, 0)));
+        assert_eq!(jumps.forward(Cursor::at(40, 0)), None);
+
+        // A new jump after going back discards the forward positions
+        jumps.back(Cursor::at(40, 0));
+        jumps.push(Cursor::at(20, 0));
+        assert_eq!(lines(&jumps), vec![1, 20]);
+        assert_eq!(jumps.forward(Cursor::at(5, 0)), None);
+    }
+
+    #[test]
+    fn test_push_coalesces_lines_and_caps_size() {
+        let mut jumps = JumpList::new(3);
+        for line in [1, 2, 1, 3, 4] {
+            jumps.push(Cursor::at(line, 0));
+        }
+        assert_eq!(lines(&jumps), vec![1, 3, 4]);
+
+        // Going back from a recorded line skips it
+        assert_eq!(jumps.back(Cursor::at(4, 2)), Some(Cursor::at(3, 0)));
+
+        let mut disabled = JumpList::new(0);
+        disabled.push(Cursor::at(1, 0));
+        assert!(disabled.is_empty());
+        assert_eq!(disabled.back(Cursor::at(2, 0)), None);
+    }
+
+    #[test]
+    fn test_edits_shift_positions() {
+        let mut jumps = JumpList::new(10);
+        for line in [2, 10, 20] {
+            jumps.push(Cursor::at(line, 0));
+        }
+
+        // Lines 5..=12 replaced by a single line
+        jumps.record_edit(&LineEdit {
+            line: 5,
+            removed: 7,
+            inserted: 0,
+        });
+        assert_eq!(lines(&jumps), vec![2, 5, 13]);
+    }
+}
@@ -3,24 +3,72 @@
 //! Provides efficient text storage and manipulation using ropey,
 //! along with cursor management, history (undo/redo), viewport, and search.
 
+mod anchor;
 mod buffer;
+mod comment;
 mod cursor;
+mod dirty;
+mod encoding;
+mod fold;
 mod history;
+mod indent;
+mod jump;
+mod line_index;
+mod save;
 mod search;
 mod viewport;
 mod wrap;
+mod write;
 
+pub use anchor::{Affinity, Anchor};
 pub use buffer::TextBuffer;
+pub use comment::CommentToggle;
 pub use cursor::{Cursor, Selection};
-pub use history::{Action, History};
-pub use search::{SearchDirection, SearchState};
+pub use encoding::{TextEncoding, COMMON_ENCODINGS};
+pub use fold::{indent_regions, FoldMap, FoldRange, LineEdit};
+pub use history::{Action, History, UndoBranch};
+pub use indent::{indent_column, IndentStyle};
+pub use jump::JumpList;
+pub use line_index::{ColumnUnit, LinePosition, LineSlice, LONG_LINE_BYTES};
+pub use save::{SaveCleanup, SaveTransforms};
+pub use search::{SearchDirection, SearchHistory, SearchState};
 pub use viewport::Viewport;
-pub use wrap::{calculate_wrap_point, calculate_wrap_points_for_line, is_word_boundary};
+pub use wrap::{
+    calculate_wrap_point, calculate_wrap_points_for_line, is_word_boundary, is_word_char, WrapMode,
+};
+pub use write::{WriteMethod, WriteOptions};
 
 /// Line ending type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum LineEnding {
     #[default]
     LF, // Unix \n
     CRLF, // Windows \r\n
 }
+
+impl LineEnding {
+    /// Line break characters
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LF => "\n",
+            Self::CRLF => "\r\n",
+        }
+    }
+
+    /// Display name ("LF" or "CRLF")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LF => "LF",
+            Self::CRLF => "CRLF",
+        }
+    }
+
+    /// The other line ending
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::LF => Self::CRLF,
+            Self::CRLF => Self::LF,
+        }
+    }
+}
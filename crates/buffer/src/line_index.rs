@@ -0,0 +1,292 @@
+//! Column index of long lines.
+//!
+//! Cursor columns count graphemes, so finding a column of a line means
+//! walking its graphemes from the start. That is fine for ordinary lines but
+//! stalls on minified files with megabytes on one line. Lines longer than
+//! `LONG_LINE_BYTES` get an index of checkpoints every `CHECKPOINT_GRAPHEMES`
+//! graphemes (byte offset and display column), so that a part of the line
+//! is found by walking from the nearest checkpoint only.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Lines longer than this (in bytes, without the line break) are indexed
+pub const LONG_LINE_BYTES: usize = 10_000;
+
+/// Graphemes between checkpoints of the index
+const CHECKPOINT_GRAPHEMES: usize = 256;
+
+/// Position in a line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinePosition {
+    /// Grapheme index (cursor column)
+    pub grapheme: usize,
+    /// Byte offset
+    pub byte: usize,
+    /// Display column
+    pub column: usize,
+}
+
+impl LinePosition {
+    fn get(&self, unit: LineUnit) -> usize {
+        match unit {
+            LineUnit::Graphemes => self.grapheme,
+            LineUnit::Columns => self.column,
+        }
+    }
+
+    /// Move past a grapheme
+    fn advance(&mut self, grapheme: &str) {
+        self.grapheme += 1;
+        self.byte += grapheme.len();
+        self.column += grapheme.width();
+    }
+}
+
+/// Unit of a column in a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Graphemes, as cursor columns count
+    Grapheme,
+    /// Unicode scalar values
+    Char,
+    /// UTF-8 bytes
+    Utf8,
+    /// UTF-16 code units, as LSP counts by default
+    Utf16,
+}
+
+/// Unit of a range in a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineUnit {
+    Graphemes,
+    Columns,
+}
+
+/// Part of a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineSlice {
+    /// Text of the part (without the line break)
+    pub text: String,
+    /// Position of the start of the text in the line
+    pub start: LinePosition,
+    /// Length of the whole line in graphemes
+    pub len: usize,
+    /// The text reaches the end of the line
+    pub to_end: bool,
+}
+
+impl LineSlice {
+    /// Cut the graphemes in `range` (counted in `unit`) out of `text`,
+    /// which starts at `from` in a line of `len` graphemes and reaches its
+    /// end if `to_end`.
+    pub(crate) fn cut(
+        text: &str,
+        from: LinePosition,
+        len: usize,
+        to_end: bool,
+        unit: LineUnit,
+        range: Range<usize>,
+    ) -> Self {
+        let mut pos = from;
+        let mut start = None;
+        let mut end = None;
+        for grapheme in text.graphemes(true) {
+            if start.is_none() && pos.get(unit) >= range.start {
+                start = Some(pos);
+            }
+            if pos.get(unit) >= range.end.max(range.start) {
+                end = Some(pos);
+                break;
+            }
+            pos.advance(grapheme);
+        }
+        let start = start.unwrap_or(pos);
+        let end = end.unwrap_or(pos);
+        Self {
+            text: text[start.byte - from.byte..end.byte - from.byte].to_string(),
+            start,
+            len,
+            to_end: to_end && end.byte - from.byte == text.len(),
+        }
+    }
+}
+
+/// Checkpoints of a long line.
+#[derive(Debug)]
+pub struct LineIndex {
+    /// Positions of every `CHECKPOINT_GRAPHEMES`th grapheme
+    checkpoints: Vec<LinePosition>,
+    /// Position of the end of the line
+    end: LinePosition,
+}
+
+impl LineIndex {
+    /// Index a line (without its line break).
+    pub fn new(line: &str) -> Self {
+        let mut checkpoints = Vec::new();
+        let mut pos = LinePosition::default();
+        for grapheme in line.graphemes(true) {
+            if pos.grapheme % CHECKPOINT_GRAPHEMES == 0 {
+                checkpoints.push(pos);
+            }
+            pos.advance(grapheme);
+        }
+        Self {
+            checkpoints,
+            end: pos,
+        }
+    }
+
+    /// Length of the line in graphemes
+    pub fn len(&self) -> usize {
+        self.end.grapheme
+    }
+
+    /// Last checkpoint at or before `target`, and the byte offset of the
+    /// first checkpoint at or after `end` (the end of the line if none),
+    /// which bound the text to walk for `target..end`
+    pub(crate) fn bounds(
+        &self,
+        unit: LineUnit,
+        target: usize,
+        end: usize,
+    ) -> (LinePosition, usize) {
+        let before = self
+            .checkpoints
+            .partition_point(|pos| pos.get(unit) <= target);
+        let from = before
+            .checked_sub(1)
+            .map_or_else(LinePosition::default, |i| self.checkpoints[i]);
+        let after = self
+            .checkpoints
+            .partition_point(|pos| pos.get(unit) < end.max(target));
+        let to = self.checkpoints.get(after).unwrap_or(&self.end).byte;
+        (from, to)
+    }
+}
+
+/// Indexes of the long lines asked for since they were last edited.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LineIndexes {
+    lines: HashMap<usize, Rc<LineIndex>>,
+    /// The text has long lines (None = not checked since the last edit)
+    has_long_lines: Option<bool>,
+}
+
+impl LineIndexes {
+    /// Index of a line, built with `line` if it isn't cached
+    pub fn get(&mut self, line_idx: usize, line: impl FnOnce() -> String) -> Rc<LineIndex> {
+        self.lines
+            .entry(line_idx)
+            .or_insert_with(|| Rc::new(LineIndex::new(&line())))
+            .clone()
+    }
+
+    /// Whether the text has long lines, checked with `check` once per edit
+    pub fn has_long_lines(&mut self, check: impl FnOnce() -> bool) -> bool {
+        *self.has_long_lines.get_or_insert_with(check)
+    }
+
+    /// Drop the indexes of the lines changed by an edit starting at `line`
+    /// that removed `removed` and inserted `inserted` line breaks (lines
+    /// below are shifted accordingly)
+    pub fn record_edit(&mut self, line: usize, removed: usize, inserted: usize) {
+        let old_end = line + removed;
+        self.has_long_lines = None;
+        self.lines = std::mem::take(&mut self.lines)
+            .into_iter()
+            .filter(|(l, _)| *l < line || *l > old_end)
+            .map(|(l, index)| {
+                if l > old_end {
+                    (l - removed + inserted, index)
+                } else {
+                    (l, index)
+                }
+            })
+            .collect();
+    }
+
+    /// Drop all indexes
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.has_long_lines = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_bounds_walk_from_nearest_checkpoint() {
+        // Wide characters after the first checkpoint
+        let line = format!("{}{}", "a".repeat(300), "字".repeat(300));
+        let index = LineIndex::new(&line);
+        assert_eq!(index.len(), 600);
+
+        let (from, to) = index.bounds(LineUnit::Graphemes, 400, 410);
+        assert_eq!(from.grapheme, 256);
+        assert_eq!(to, 300 + 212 * 3);
+        assert_eq!(index.bounds(LineUnit::Graphemes, 590, 700).1, line.len());
+
+        let (from, to) = index.bounds(LineUnit::Columns, 100, 120);
+        assert_eq!(from, LinePosition::default());
+        assert_eq!(to, 256);
+        let (from, _) = index.bounds(LineUnit::Columns, 800, 820);
+        assert_eq!(
+            from,
+            LinePosition {
+                grapheme: 512,
+                byte: 300 + 212 * 3,
+                column: 300 + 212 * 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_cut_slice() {
+        let line = "ab字cd";
+        let slice = LineSlice::cut(
+            line,
+            LinePosition::default(),
+            5,
+            true,
+            LineUnit::Columns,
+            3..9,
+        );
+        // The wide character starting before column 3 is left out
+        assert_eq!(slice.text, "cd");
+        assert_eq!(slice.start.grapheme, 3);
+        assert_eq!(slice.start.column, 4);
+        assert!(slice.to_end);
+
+        let slice = LineSlice::cut(
+            line,
+            LinePosition::default(),
+            5,
+            true,
+            LineUnit::Graphemes,
+            1..3,
+        );
+        assert_eq!(slice.text, "b字");
+        assert!(!slice.to_end);
+    }
+
+    #[test]
+    fn test_edits_drop_and_shift_indexes() {
+        let mut indexes = LineIndexes::default();
+        for line in [1, 3, 6] {
+            indexes.get(line, || "x".repeat(line));
+        }
+        indexes.record_edit(3, 1, 0);
+
+        assert_eq!(indexes.lines.len(), 2);
+        assert_eq!(indexes.get(1, String::new).len(), 1);
+        assert_eq!(indexes.get(5, String::new).len(), 6);
+    }
+}
@@ -0,0 +1,38 @@
+//! On-save cleanup of whitespace and line endings.
+
+/// Cleanup applied to buffer contents before saving
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveTransforms {
+    /// Strip trailing spaces and tabs
+    pub trim_trailing_whitespace: bool,
+    /// Strip trailing whitespace only on lines edited since last save
+    pub trim_modified_lines_only: bool,
+    /// End non-empty files with exactly one newline
+    pub ensure_final_newline: bool,
+}
+
+/// Summary of changes made by save transforms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveCleanup {
+    /// Trailing whitespace characters removed
+    pub trailing_whitespace: usize,
+    /// Final newline added or extra trailing newlines removed
+    pub final_newline: bool,
+    /// Line endings converted to the buffer's line ending
+    pub line_endings: usize,
+}
+
+impl SaveCleanup {
+    /// Check if nothing was changed
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Number of trailing spaces and tabs in a line (without line break)
+pub(crate) fn trailing_whitespace_len(text: &str) -> usize {
+    text.chars()
+        .rev()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .count()
+}
@@ -1,4 +1,10 @@
-use crate::Cursor;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::wrap::is_word_char;
+use crate::{Cursor, Selection};
 
 /// Search direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +27,10 @@ pub struct SearchState {
     pub matches: Vec<Cursor>,
     /// Case sensitivity
     pub case_sensitive: bool,
+    /// Match whole words only
+    pub whole_word: bool,
+    /// Range the matches are restricted to (search in selection)
+    pub in_selection: Option<Selection>,
     /// Search direction
     #[allow(dead_code)]
     pub direction: SearchDirection,
@@ -35,6 +45,8 @@ impl SearchState {
             current_match: None,
             matches: Vec::new(),
             case_sensitive,
+            whole_word: false,
+            in_selection: None,
             direction: SearchDirection::Forward,
         }
     }
@@ -47,10 +59,59 @@ impl SearchState {
             current_match: None,
             matches: Vec::new(),
             case_sensitive,
+            whole_word: false,
+            in_selection: None,
             direction: SearchDirection::Forward,
         }
     }
 
+    /// Set the whole-word and in-selection options
+    pub fn with_options(mut self, whole_word: bool, in_selection: Option<Selection>) -> Self {
+        self.whole_word = whole_word;
+        self.in_selection = in_selection.filter(|selection| !selection.is_empty());
+        self
+    }
+
+    /// Length of the query in graphemes (cursor columns)
+    pub fn query_len(&self) -> usize {
+        self.query.graphemes(true).count()
+    }
+
+    /// Check if a match from `start` to `end` lies in the searched range:
+    /// the whole document, or the selection when searching in selection.
+    /// Matches touching the selection edges are inside it.
+    pub fn in_scope(&self, start: &Cursor, end: &Cursor) -> bool {
+        self.in_selection
+            .as_ref()
+            .is_none_or(|selection| selection.start() <= *start && *end <= selection.end())
+    }
+
+    /// Check if the match at bytes `range` of `line` is a whole word:
+    /// not preceded or followed by a word character (the word rules of
+    /// word wrapping and word-wise editing)
+    pub fn is_whole_word(line: &str, range: Range<usize>) -> bool {
+        let before = line[..range.start].chars().next_back();
+        let after = line[range.end..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    }
+
+    /// Shift the end of the selection searched in after replacing a match
+    /// at `at` of `query_len` graphemes with `replace_len` graphemes
+    pub fn shift_selection_after_replace(
+        &mut self,
+        at: &Cursor,
+        query_len: usize,
+        replace_len: usize,
+    ) {
+        if let Some(ref mut selection) = self.in_selection {
+            for pos in [&mut selection.anchor, &mut selection.active] {
+                if pos.line == at.line && pos.column >= at.column + query_len {
+                    pos.column = pos.column + replace_len - query_len;
+                }
+            }
+        }
+    }
+
     /// Check if replace mode is enabled
     #[allow(dead_code)]
     pub fn is_replace_mode(&self) -> bool {
@@ -127,10 +188,79 @@ impl SearchState {
     }
 }
 
+/// Queries kept in the search history
+pub const SEARCH_HISTORY_SIZE: usize = 50;
+
+/// Previous search queries, most recent first and without duplicates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchHistory {
+    entries: VecDeque<String>,
+}
+
+impl SearchHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// History of saved queries, most recent first
+    pub fn from_entries(entries: impl IntoIterator<Item = String>) -> Self {
+        let entries: Vec<String> = entries.into_iter().collect();
+        let mut history = Self::new();
+        for query in entries.iter().rev() {
+            history.push(query);
+        }
+        history
+    }
+
+    /// Record a query as the most recent one; an earlier use of it moves
+    /// to the front, and the oldest query is dropped when full
+    pub fn push(&mut self, query: &str) {
+        if query.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.push_front(query.to_string());
+        self.entries.truncate(SEARCH_HISTORY_SIZE);
+    }
+
+    /// Queries, most recent first
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    /// Check if no query was recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_search_history_dedup_and_order() {
+        let mut history = SearchHistory::new();
+        history.push("foo");
+        history.push("bar");
+        history.push("");
+        history.push("foo");
+        assert_eq!(history.entries().collect::<Vec<_>>(), ["foo", "bar"]);
+
+        for index in 0..SEARCH_HISTORY_SIZE + 5 {
+            history.push(&index.to_string());
+        }
+        assert_eq!(history.entries().count(), SEARCH_HISTORY_SIZE);
+        assert_eq!(history.entries().next(), Some("54"));
+        assert!(!history.entries().any(|query| query == "foo"));
+
+        // Restored in the saved order, dropping duplicates
+        let saved = ["b", "a", "b"].map(String::from);
+        let restored = SearchHistory::from_entries(saved);
+        assert_eq!(restored.entries().collect::<Vec<_>>(), ["b", "a"]);
+    }
+
     #[test]
     fn test_search_state_navigation() {
         let mut state = SearchState::new("test".to_string(), false);
@@ -195,4 +325,52 @@ mod tests {
         });
         assert_eq!(state.current_match, Some(0));
     }
+
+    #[test]
+    fn test_whole_word() {
+        let line = "let word = words_word + (word);";
+        // "word" at 4, "words" at 11, "word" in "words_word" at 17, "(word)" at 25
+        assert!(SearchState::is_whole_word(line, 4..8));
+        assert!(!SearchState::is_whole_word(line, 11..15));
+        assert!(!SearchState::is_whole_word(line, 17..21));
+        assert!(SearchState::is_whole_word(line, 25..29));
+        // Line edges
+        assert!(SearchState::is_whole_word("word", 0..4));
+        assert!(SearchState::is_whole_word("é word", 3..7));
+        assert!(!SearchState::is_whole_word("éword", 2..6));
+    }
+
+    #[test]
+    fn test_in_selection_scope() {
+        let selection = Selection::new(Cursor::at(3, 4), Cursor::at(1, 2));
+        let state = SearchState::new("ab".to_string(), false).with_options(false, Some(selection));
+        let scope =
+            |line, column| state.in_scope(&Cursor::at(line, column), &Cursor::at(line, column + 2));
+
+        // Matches touching the edges are inside
+        assert!(scope(1, 2));
+        assert!(scope(3, 2));
+        assert!(scope(2, 100));
+        // Matches crossing the edges are outside
+        assert!(!scope(1, 1));
+        assert!(!scope(3, 3));
+        assert!(!scope(0, 5));
+
+        // Without a selection (or with an empty one) everything is in scope
+        let empty = Selection::new(Cursor::at(1, 2), Cursor::at(1, 2));
+        let state = SearchState::new("ab".to_string(), false).with_options(false, Some(empty));
+        assert!(state.in_selection.is_none());
+        assert!(state.in_scope(&Cursor::at(0, 0), &Cursor::at(0, 2)));
+    }
+
+    #[test]
+    fn test_selection_shifts_after_replace() {
+        let selection = Selection::new(Cursor::at(0, 2), Cursor::at(0, 10));
+        let mut state =
+            SearchState::new("ab".to_string(), false).with_options(false, Some(selection));
+        state.shift_selection_after_replace(&Cursor::at(0, 4), 2, 5);
+        let selection = state.in_selection.unwrap();
+        assert_eq!(selection.start(), Cursor::at(0, 2));
+        assert_eq!(selection.end(), Cursor::at(0, 13));
+    }
 }
@@ -1,10 +1,15 @@
 // Allow dead code for future API methods
 #![allow(dead_code)]
 
-use super::Cursor;
+use std::ops::Range;
+
+use super::{Cursor, FoldMap};
 
 /// Viewport for virtual scrolling
-/// Tracks which part of document is visible on screen
+/// Tracks which part of document is visible on screen.
+///
+/// Vertical positions are counted in visible rows: lines hidden by folds
+/// take no rows, so scrolling and row conversions skip them.
 #[derive(Debug, Clone)]
 pub struct Viewport {
     /// First visible line (0-based)
@@ -15,6 +20,10 @@ pub struct Viewport {
     pub left_column: usize,
     /// Width of visible area
     pub width: usize,
+    /// Folded line ranges
+    pub folds: FoldMap,
+    /// Rows kept visible above and below the cursor when scrolling
+    pub scroll_off: usize,
 }
 
 impl Viewport {
@@ -25,6 +34,8 @@ impl Viewport {
             height,
             left_column: 0,
             width,
+            folds: FoldMap::default(),
+            scroll_off: 0,
         }
     }
 
@@ -34,9 +45,29 @@ impl Viewport {
         self.height = height;
     }
 
+    /// Visible row of the first visible line
+    pub fn top_row(&self) -> usize {
+        self.folds.row_of(self.top_line)
+    }
+
+    /// Set first visible line from a visible row
+    fn set_top_row(&mut self, row: usize) {
+        self.top_line = self.folds.line_of(row);
+    }
+
+    /// Scroll-off margin that fits the viewport height
+    pub fn margin(&self) -> usize {
+        self.scroll_off.min(self.height.saturating_sub(1) / 2)
+    }
+
+    /// Number of visible rows for a document with total_lines lines
+    pub fn total_rows(&self, total_lines: usize) -> usize {
+        total_lines.saturating_sub(self.folds.hidden_lines())
+    }
+
     /// Get index of last visible line (exclusive)
     pub fn bottom_line(&self) -> usize {
-        self.top_line + self.height
+        self.folds.line_of(self.top_row() + self.height)
     }
 
     /// Get index of last visible column (exclusive)
@@ -44,9 +75,14 @@ impl Viewport {
         self.left_column + self.width
     }
 
+    /// Visible columns: the part of long lines that is rendered
+    pub fn columns(&self) -> Range<usize> {
+        self.left_column..self.right_column()
+    }
+
     /// Check if line is visible
     pub fn is_line_visible(&self, line: usize) -> bool {
-        line >= self.top_line && line < self.bottom_line()
+        line >= self.top_line && line < self.bottom_line() && !self.folds.is_hidden(line)
     }
 
     /// Check if column is visible
@@ -62,23 +98,26 @@ impl Viewport {
     /// Scroll viewport to make cursor visible
     /// Returns true if viewport was changed
     pub fn ensure_cursor_visible(&mut self, cursor: &Cursor, total_lines: usize) -> bool {
+        let old_top = self.top_line;
         let mut changed = false;
 
         // Vertical scroll
-        if cursor.line < self.top_line {
-            // Cursor above viewport - scroll up
-            self.top_line = cursor.line;
-            changed = true;
-        } else if cursor.line >= self.bottom_line() {
-            // Cursor below viewport - scroll down
-            self.top_line = cursor.line.saturating_sub(self.height - 1);
-            changed = true;
+        let top_row = self.top_row();
+        let cursor_row = self.folds.row_of(cursor.line);
+        let margin = self.margin();
+        let mut new_top = top_row;
+        if cursor_row < top_row + margin {
+            // Cursor above viewport or in the top margin - scroll up
+            new_top = cursor_row.saturating_sub(margin);
+        } else if cursor_row + margin >= top_row + self.height {
+            // Cursor below viewport or in the bottom margin - scroll down
+            new_top = (cursor_row + margin + 1).saturating_sub(self.height);
         }
 
-        // Limit top_line to avoid empty space at bottom
-        let max_top = total_lines.saturating_sub(self.height);
-        if self.top_line > max_top {
-            self.top_line = max_top;
+        // Limit top row to avoid empty space at bottom
+        let max_top = self.total_rows(total_lines).saturating_sub(self.height);
+        self.set_top_row(new_top.min(max_top));
+        if self.top_line != old_top {
             changed = true;
         }
 
@@ -99,7 +138,7 @@ impl Viewport {
     /// Scroll up by N lines
     pub fn scroll_up(&mut self, lines: usize) -> bool {
         if self.top_line > 0 {
-            self.top_line = self.top_line.saturating_sub(lines);
+            self.set_top_row(self.top_row().saturating_sub(lines));
             true
         } else {
             false
@@ -108,9 +147,10 @@ impl Viewport {
 
     /// Scroll down by N lines
     pub fn scroll_down(&mut self, lines: usize, total_lines: usize) -> bool {
-        let max_top = total_lines.saturating_sub(self.height);
-        if self.top_line < max_top {
-            self.top_line = (self.top_line + lines).min(max_top);
+        let max_top = self.total_rows(total_lines).saturating_sub(self.height);
+        let top_row = self.top_row();
+        if top_row < max_top {
+            self.set_top_row((top_row + lines).min(max_top));
             true
         } else {
             false
@@ -145,7 +185,9 @@ impl Viewport {
 
     /// Scroll to document end
     pub fn scroll_to_bottom(&mut self, total_lines: usize) -> bool {
-        let max_top = total_lines.saturating_sub(self.height);
+        let max_top = self
+            .folds
+            .line_of(self.total_rows(total_lines).saturating_sub(self.height));
         if self.top_line != max_top {
             self.top_line = max_top;
             true
@@ -154,11 +196,31 @@ impl Viewport {
         }
     }
 
+    /// Scroll so the viewport starts at visible row `row`, as far as the
+    /// document end allows
+    pub fn scroll_to_row(&mut self, row: usize, total_lines: usize) -> bool {
+        let max_top = self.total_rows(total_lines).saturating_sub(self.height);
+        let old_top = self.top_line;
+        self.set_top_row(row.min(max_top));
+        self.top_line != old_top
+    }
+
     /// Center viewport on cursor
     pub fn center_on_cursor(&mut self, cursor: &Cursor, total_lines: usize) -> bool {
-        let target_top = cursor.line.saturating_sub(self.height / 2);
-        let max_top = total_lines.saturating_sub(self.height);
-        let new_top = target_top.min(max_top);
+        self.scroll_cursor_to_row(cursor, self.height / 2, total_lines)
+    }
+
+    /// Scroll so the cursor line is shown on viewport row `row`, as far as
+    /// the document start and end allow
+    pub fn scroll_cursor_to_row(
+        &mut self,
+        cursor: &Cursor,
+        row: usize,
+        total_lines: usize,
+    ) -> bool {
+        let target_top = self.folds.row_of(cursor.line).saturating_sub(row);
+        let max_top = self.total_rows(total_lines).saturating_sub(self.height);
+        let new_top = self.folds.line_of(target_top.min(max_top));
 
         if self.top_line != new_top {
             self.top_line = new_top;
@@ -168,6 +230,25 @@ impl Viewport {
         }
     }
 
+    /// Nearest line to `line` that the viewport shows outside its scroll-off
+    /// margins (the margins end at the document start and end)
+    pub fn line_within_margin(&self, line: usize, total_lines: usize) -> usize {
+        let margin = self.margin();
+        let top_row = self.top_row();
+        let total_rows = self.total_rows(total_lines);
+        let first_row = if top_row == 0 { 0 } else { top_row + margin };
+        let last_row = if top_row + self.height >= total_rows {
+            total_rows.saturating_sub(1)
+        } else {
+            (top_row + self.height).saturating_sub(margin + 1)
+        };
+        let row = self
+            .folds
+            .row_of(line)
+            .clamp(first_row, last_row.max(first_row));
+        self.folds.line_of(row)
+    }
+
     /// Get relative cursor position in viewport
     /// Returns (row, col) relative to viewport start
     pub fn cursor_to_viewport_pos(&self, cursor: &Cursor) -> Option<(usize, usize)> {
@@ -175,14 +256,17 @@ impl Viewport {
             return None;
         }
 
-        let row = cursor.line - self.top_line;
+        let row = self.folds.row_of(cursor.line) - self.top_row();
         let col = cursor.column - self.left_column;
         Some((row, col))
     }
 
     /// Convert viewport position to absolute cursor position
     pub fn viewport_pos_to_cursor(&self, row: usize, col: usize) -> Cursor {
-        Cursor::at(self.top_line + row, self.left_column + col)
+        Cursor::at(
+            self.folds.line_of(self.top_row() + row),
+            self.left_column + col,
+        )
     }
 }
 
@@ -257,6 +341,52 @@ mod tests {
         assert!(vp.is_cursor_visible(&cursor));
     }
 
+    #[test]
+    fn test_scroll_off_margin() {
+        let mut vp = Viewport::new(80, 10);
+        vp.scroll_off = 3;
+
+        // Near the document start the cursor reaches the first row
+        assert!(!vp.ensure_cursor_visible(&Cursor::at(1, 0), 100));
+        assert_eq!(vp.top_line, 0);
+
+        // Moving down keeps 3 rows below the cursor
+        vp.ensure_cursor_visible(&Cursor::at(7, 0), 100);
+        assert_eq!(vp.top_line, 1);
+        vp.ensure_cursor_visible(&Cursor::at(20, 0), 100);
+        assert_eq!(vp.top_line, 14);
+
+        // Moving up keeps 3 rows above it
+        vp.ensure_cursor_visible(&Cursor::at(16, 0), 100);
+        assert_eq!(vp.top_line, 13);
+
+        // Near the document end the cursor reaches the last row
+        vp.ensure_cursor_visible(&Cursor::at(99, 0), 100);
+        assert_eq!(vp.top_line, 90);
+        vp.ensure_cursor_visible(&Cursor::at(95, 0), 100);
+        assert_eq!(vp.top_line, 90);
+        assert_eq!(vp.line_within_margin(99, 100), 99);
+        assert_eq!(vp.line_within_margin(90, 100), 93);
+
+        // The margin shrinks to fit small viewports
+        vp.height = 4;
+        assert_eq!(vp.margin(), 1);
+    }
+
+    #[test]
+    fn test_scroll_cursor_to_row() {
+        let mut vp = Viewport::new(80, 10);
+        let cursor = Cursor::at(50, 0);
+
+        vp.scroll_cursor_to_row(&cursor, 0, 100);
+        assert_eq!(vp.top_line, 50);
+        vp.scroll_cursor_to_row(&cursor, 9, 100);
+        assert_eq!(vp.top_line, 41);
+        // Limited by the document end
+        vp.scroll_cursor_to_row(&Cursor::at(95, 0), 0, 100);
+        assert_eq!(vp.top_line, 90);
+    }
+
     #[test]
     fn test_cursor_to_viewport_pos() {
         let mut vp = Viewport::new(80, 24);
@@ -279,6 +409,27 @@ mod tests {
         assert_eq!(cursor, Cursor::at(15, 10));
     }
 
+    #[test]
+    fn test_folded_lines_take_no_rows() {
+        use crate::FoldRange;
+
+        let mut vp = Viewport::new(80, 10);
+        vp.folds.fold(FoldRange::new(2, 21));
+
+        assert!(!vp.is_line_visible(5));
+        assert_eq!(vp.bottom_line(), 29);
+        assert_eq!(vp.cursor_to_viewport_pos(&Cursor::at(22, 0)), Some((3, 0)));
+        assert_eq!(vp.viewport_pos_to_cursor(3, 0), Cursor::at(22, 0));
+
+        // 40 lines with 19 hidden leave 21 rows
+        vp.ensure_cursor_visible(&Cursor::at(39, 0), 40);
+        assert_eq!(vp.top_line, 30);
+        vp.scroll_up(9);
+        assert_eq!(vp.top_line, 2);
+        vp.scroll_down(100, 40);
+        assert_eq!(vp.top_line, 30);
+    }
+
     #[test]
     fn test_horizontal_scroll() {
         let mut vp = Viewport::new(80, 24);
@@ -3,16 +3,52 @@
 //! This module provides functions for intelligent line wrapping that respects
 //! word boundaries when possible, falling back to hard breaks for words wider
 //! than the viewport.
+//!
+//! Lines are measured in display width and cut between grapheme clusters,
+//! so wide (CJK) characters, combining marks and emoji sequences are never
+//! split. Wrap points are grapheme indices.
 
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+/// Where lines are broken when wrapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// At the width, wherever it falls
+    Hard,
+    /// After whitespace; words wider than the viewport are broken at the
+    /// width
+    Words,
+    /// After whitespace, or else after punctuation (`/`, `-`, `·`, ...), so
+    /// paths and URLs are broken between their parts
+    #[default]
+    WordsAndPunctuation,
+}
+
+/// Whitespace grapheme (breaking after it leaves no visible gap)
+fn is_space(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// Punctuation or symbol grapheme
+fn is_punctuation(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| !c.is_alphanumeric() && !c.is_whitespace())
+}
+
 /// Calculate the optimal wrap point for a line segment using graphemes
 ///
-/// This function tries to find a word boundary (non-alphanumeric character)
-/// to break the line at, but will force a break at max_width if:
-/// - No word boundary is found (single long word)
+/// In the word modes this function looks for whitespace to break the line
+/// after (and, with `WrapMode::WordsAndPunctuation`, punctuation when there
+/// is no whitespace), but will force a break at max_width if:
+/// - No break opportunity is found (single long word)
 /// - The word would be wider than the viewport
 ///
+/// Whitespace right at the width stays on the row (past the width), so the
+/// next row does not start with it.
+///
 /// Uses display width and grapheme clusters for proper Unicode handling
 /// (CJK characters, combining characters like Hindi vowel signs, etc.)
 ///
@@ -21,14 +57,17 @@ use unicode_width::UnicodeWidthStr;
 /// * `start` - Starting position in the grapheme array
 /// * `max_width` - Maximum display width before wrapping (content width)
 /// * `line_len` - Total length of the line (grapheme count)
+/// * `mode` - Where the line may be broken
 ///
 /// # Returns
-/// The grapheme index where the line should be wrapped
+/// The grapheme index where the line should be wrapped, after `start`
+/// unless the line ends there
 pub fn calculate_wrap_point(
     graphemes: &[&str],
     start: usize,
     max_width: usize,
     line_len: usize,
+    mode: WrapMode,
 ) -> usize {
     if start >= line_len {
         return line_len;
@@ -36,23 +75,14 @@ pub fn calculate_wrap_point(
 
     // Find the grapheme index where display width exceeds max_width
     let mut display_width = 0;
-    let mut ideal_end = start;
-
-    for (i, grapheme) in graphemes
-        .iter()
-        .enumerate()
-        .skip(start)
-        .take(line_len - start)
-    {
+    let mut ideal_end = line_len;
+    for (i, grapheme) in graphemes.iter().enumerate().take(line_len).skip(start) {
         let grapheme_width = grapheme.width();
-
         if display_width + grapheme_width > max_width {
             ideal_end = i;
             break;
         }
-
         display_width += grapheme_width;
-        ideal_end = i + 1;
     }
 
     // If we reached end of line, no wrapping needed
@@ -60,28 +90,36 @@ pub fn calculate_wrap_point(
         return line_len;
     }
 
-    // Check if grapheme is a word boundary (first char is non-alphanumeric)
-    let is_boundary = |g: &str| g.chars().next().is_none_or(|c| !c.is_alphanumeric());
+    // A grapheme wider than the viewport still gets a row of its own
+    let hard_end = ideal_end.max(start + 1);
+    if mode == WrapMode::Hard {
+        return hard_end;
+    }
 
-    // If the grapheme at ideal_end is a word boundary, we can break there
-    if ideal_end < line_len && is_boundary(graphemes[ideal_end]) {
+    if is_space(graphemes[ideal_end]) {
         return ideal_end + 1;
     }
 
-    // Search backwards from ideal_end for a word boundary
-    for i in (start..ideal_end).rev() {
-        if is_boundary(graphemes[i]) {
-            // Found a boundary - wrap after this grapheme
-            // But avoid wrapping right after start (would create empty visual line)
-            if i > start {
-                return i + 1;
-            }
+    // Search backwards for a break opportunity, wrapping after it
+    // (but not right after start, which would leave a row of one grapheme)
+    if let Some(i) = (start + 1..ideal_end)
+        .rev()
+        .find(|&i| is_space(graphemes[i]))
+    {
+        return i + 1;
+    }
+    if mode == WrapMode::WordsAndPunctuation {
+        if let Some(i) = (start + 1..ideal_end)
+            .rev()
+            .find(|&i| is_punctuation(graphemes[i]))
+        {
+            return i + 1;
         }
     }
 
-    // No word boundary found - this means we have a single long word
+    // No break opportunity - this means we have a single long word
     // Force break at ideal_end to prevent horizontal overflow
-    ideal_end.max(start + 1) // Ensure at least one grapheme is included
+    hard_end
 }
 
 /// Calculate all wrap points for a line
@@ -94,38 +132,29 @@ pub fn calculate_wrap_point(
 /// # Arguments
 /// * `line_text` - The text of the line
 /// * `max_width` - Maximum display width before wrapping (content width)
+/// * `mode` - Where the line may be broken
 ///
 /// # Returns
 /// Vector of wrap points (grapheme indices). Empty if line doesn't need wrapping.
-#[allow(dead_code)]
-pub fn calculate_wrap_points_for_line(line_text: &str, max_width: usize) -> Vec<usize> {
-    use unicode_segmentation::UnicodeSegmentation;
-
-    let graphemes: Vec<&str> = line_text.graphemes(true).collect();
-    let line_len = graphemes.len();
-
+pub fn calculate_wrap_points_for_line(
+    line_text: &str,
+    max_width: usize,
+    mode: WrapMode,
+) -> Vec<usize> {
     // Check display width, not grapheme count
     if line_text.width() <= max_width {
         return Vec::new(); // No wrapping needed
     }
 
-    let mut wrap_points = Vec::new();
-    let mut grapheme_offset = 0;
+    let graphemes: Vec<&str> = line_text.graphemes(true).collect();
+    let line_len = graphemes.len();
 
+    let mut wrap_points = Vec::new();
+    let mut grapheme_offset = calculate_wrap_point(&graphemes, 0, max_width, line_len, mode);
     while grapheme_offset < line_len {
-        let chunk_end = calculate_wrap_point(&graphemes, grapheme_offset, max_width, line_len);
-
-        // Only add wrap point if we're not at the start
-        if grapheme_offset > 0 {
-            wrap_points.push(grapheme_offset);
-        }
-
-        grapheme_offset = chunk_end;
-
-        // Safety: prevent infinite loop
-        if chunk_end == grapheme_offset && grapheme_offset < line_len {
-            grapheme_offset += 1;
-        }
+        wrap_points.push(grapheme_offset);
+        grapheme_offset =
+            calculate_wrap_point(&graphemes, grapheme_offset, max_width, line_len, mode);
     }
 
     wrap_points
@@ -139,6 +168,14 @@ pub fn is_word_boundary(c: char) -> bool {
     !c.is_alphanumeric()
 }
 
+/// Check if a character is part of a word for word-wise editing
+///
+/// Unlike wrapping, underscores join words (identifiers like `snake_case`).
+/// Used by word selection, word deletion and transposition.
+pub fn is_word_char(c: char) -> bool {
+    c == '_' || !is_word_boundary(c)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,14 +183,14 @@ mod tests {
     #[test]
     fn test_no_wrap_needed() {
         let text = "Short line";
-        let wrap_points = calculate_wrap_points_for_line(text, 80);
+        let wrap_points = calculate_wrap_points_for_line(text, 80, WrapMode::default());
         assert_eq!(wrap_points.len(), 0);
     }
 
     #[test]
     fn test_wrap_at_space() {
         let text = "This is a long line that needs to be wrapped at spaces";
-        let wrap_points = calculate_wrap_points_for_line(text, 20);
+        let wrap_points = calculate_wrap_points_for_line(text, 20, WrapMode::default());
         assert!(!wrap_points.is_empty());
 
         // Check that wrap points are reasonable
@@ -166,7 +203,7 @@ mod tests {
     fn test_long_word_force_break() {
         // Single word wider than viewport
         let text = "verylongwordthatcannotbebrokenatanyboundary";
-        let wrap_points = calculate_wrap_points_for_line(text, 10);
+        let wrap_points = calculate_wrap_points_for_line(text, 10, WrapMode::default());
         assert!(!wrap_points.is_empty());
 
         // Should force breaks every 10 characters
@@ -176,7 +213,7 @@ mod tests {
     #[test]
     fn test_unicode() {
         let text = "Привет мир как дела это тест юникода";
-        let wrap_points = calculate_wrap_points_for_line(text, 15);
+        let wrap_points = calculate_wrap_points_for_line(text, 15, WrapMode::default());
         // Should wrap on spaces between Cyrillic words
         assert!(!wrap_points.is_empty());
     }
@@ -184,35 +221,143 @@ mod tests {
     #[test]
     fn test_mixed_alphanumeric() {
         let text = "function_name123 another_function456 test789";
-        let wrap_points = calculate_wrap_points_for_line(text, 20);
+        let wrap_points = calculate_wrap_points_for_line(text, 20, WrapMode::default());
         // Underscores are not alphanumeric, so they're word boundaries
         assert!(!wrap_points.is_empty());
     }
 
     #[test]
     fn test_calculate_wrap_point_basic() {
-        use unicode_segmentation::UnicodeSegmentation;
-
         let text = "hello world test";
         let graphemes: Vec<&str> = text.graphemes(true).collect();
 
         // Should wrap after "hello "
-        let wrap_point = calculate_wrap_point(&graphemes, 0, 10, graphemes.len());
+        let wrap_point = calculate_wrap_point(&graphemes, 0, 10, graphemes.len(), WrapMode::Words);
         assert_eq!(wrap_point, 6); // After space
     }
 
     #[test]
     fn test_calculate_wrap_point_long_word() {
-        use unicode_segmentation::UnicodeSegmentation;
-
         let text = "verylongword";
         let graphemes: Vec<&str> = text.graphemes(true).collect();
 
         // Should force break at max_width
-        let wrap_point = calculate_wrap_point(&graphemes, 0, 5, graphemes.len());
+        let wrap_point = calculate_wrap_point(&graphemes, 0, 5, graphemes.len(), WrapMode::Words);
         assert_eq!(wrap_point, 5);
     }
 
+    /// Rows of a wrapped line
+    fn rows(text: &str, max_width: usize, mode: WrapMode) -> Vec<String> {
+        let graphemes: Vec<&str> = text.graphemes(true).collect();
+        let mut points = vec![0];
+        points.extend(calculate_wrap_points_for_line(text, max_width, mode));
+        points.push(graphemes.len());
+        points
+            .windows(2)
+            .map(|range| graphemes[range[0]..range[1]].concat())
+            .collect()
+    }
+
+    #[test]
+    fn test_graphemes_are_not_split() {
+        // Family emoji (ZWJ sequence, width 2) and "e" with a combining accent
+        let text = "👨\u{200d}👩\u{200d}👧e\u{301}e\u{301}👨\u{200d}👩\u{200d}👧";
+        assert_eq!(
+            rows(text, 3, WrapMode::Hard),
+            [
+                "👨\u{200d}👩\u{200d}👧e\u{301}",
+                "e\u{301}👨\u{200d}👩\u{200d}👧"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_wrap_by_width() {
+        // Each ideograph takes two columns
+        assert_eq!(
+            rows("中文字符测试", 5, WrapMode::Words),
+            ["中文", "字符", "测试"]
+        );
+        // A character wider than the viewport still gets a row
+        assert_eq!(rows("中文", 1, WrapMode::Hard), ["中", "文"]);
+    }
+
+    #[test]
+    fn test_punctuation_breaks() {
+        let url = "https://example.com/some/long/path";
+        assert_eq!(
+            rows(url, 16, WrapMode::WordsAndPunctuation),
+            ["https://example.", "com/some/long/", "path"]
+        );
+        assert_eq!(
+            rows(url, 16, WrapMode::Words),
+            ["https://example.", "com/some/long/pa", "th"]
+        );
+        // Whitespace is preferred over closer punctuation
+        assert_eq!(
+            rows("see a-b-c-d", 9, WrapMode::WordsAndPunctuation),
+            ["see ", "a-b-c-d"]
+        );
+        assert_eq!(
+            rows("x·y·z·w", 4, WrapMode::WordsAndPunctuation),
+            ["x·y·", "z·w"]
+        );
+    }
+
+    #[test]
+    fn test_wrapped_rows_fit_width() {
+        let pieces = [
+            "a",
+            "word",
+            " ",
+            "  ",
+            "-",
+            "/",
+            "·",
+            "中",
+            "文",
+            "e\u{301}",
+            "👍",
+            "👩\u{200d}💻",
+            "\u{915}\u{93f}",
+            "x",
+        ];
+        // Deterministic pseudo-random lines (xorshift)
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+        for _ in 0..500 {
+            let text: String = (0..=next(40)).map(|_| pieces[next(pieces.len())]).collect();
+            let max_width = 1 + next(12);
+            for mode in [
+                WrapMode::Hard,
+                WrapMode::Words,
+                WrapMode::WordsAndPunctuation,
+            ] {
+                let rows = rows(&text, max_width, mode);
+                assert_eq!(rows.concat(), text);
+                for row in &rows {
+                    // Whitespace may hang past the width; a single grapheme
+                    // wider than the viewport is kept whole
+                    let visible = row.strip_suffix(' ').unwrap_or(row);
+                    assert!(
+                        visible.width() <= max_width || row.graphemes(true).count() == 1,
+                        "{:?} wider than {} in {:?} ({:?})",
+                        row,
+                        max_width,
+                        text,
+                        mode
+                    );
+                    assert!(!row.is_empty());
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_is_word_boundary() {
         assert!(is_word_boundary(' '));
@@ -226,5 +371,9 @@ mod tests {
         assert!(!is_word_boundary('5'));
         assert!(!is_word_boundary('ж')); // Cyrillic
         assert!(!is_word_boundary('中')); // Chinese
+
+        assert!(is_word_char('_'));
+        assert!(is_word_char('ж'));
+        assert!(!is_word_char('.'));
     }
 }
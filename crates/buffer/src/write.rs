@@ -0,0 +1,282 @@
+//! Writing files to disk without losing them on a failed save.
+//!
+//! Contents go to a temporary file next to the target, which is synced and
+//! renamed over the target, so the file is either the old or the new
+//! version. Symlinks are followed: the file they point to is written and
+//! the link itself is kept.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Symlink hops followed before giving up (as the kernel's SYMLOOP_MAX)
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+/// Attempts to find a free temporary file name
+const TEMP_NAME_ATTEMPTS: u32 = 100;
+
+/// How files are written on save
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Copy the previous version to `file~` before writing
+    pub backup: bool,
+}
+
+/// How a file was written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteMethod {
+    /// Temporary file renamed over the target
+    Atomic,
+    /// Target overwritten in place (atomic rename was not possible)
+    Direct {
+        /// Why the atomic rename was not used
+        reason: String,
+    },
+}
+
+/// Write `contents` to `path` atomically where possible.
+///
+/// Permissions and (on Unix) ownership of an existing file are kept. Falls
+/// back to overwriting the file in place when a temporary file cannot be
+/// created or renamed over it, when the owner cannot be preserved, or when
+/// the file has other hard links that a rename would detach.
+pub(crate) fn write_file(
+    path: &Path,
+    contents: &[u8],
+    options: WriteOptions,
+) -> Result<WriteMethod> {
+    let target = resolve_symlinks(path);
+    let metadata = fs::metadata(&target).ok();
+
+    if options.backup && metadata.is_some() {
+        let backup = backup_path(&target);
+        fs::copy(&target, &backup)
+            .with_context(|| format!("Failed to write backup: {}", backup.display()))?;
+    }
+
+    let method = match prepare_temp_file(&target, contents, metadata.as_ref())? {
+        Ok(temp) => match fs::rename(&temp, &target) {
+            Ok(()) => {
+                sync_parent_dir(&target);
+                return Ok(WriteMethod::Atomic);
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&temp);
+                WriteMethod::Direct {
+                    reason: format!("rename failed: {}", e),
+                }
+            }
+        },
+        Err(reason) => WriteMethod::Direct { reason },
+    };
+
+    fs::write(&target, contents)
+        .with_context(|| format!("Failed to write file: {}", target.display()))?;
+    Ok(method)
+}
+
+/// Path of the backup copy (`file~`)
+pub(crate) fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push("~");
+    path.with_file_name(name)
+}
+
+/// Follow symlinks to the file that is actually written.
+///
+/// Unlike `canonicalize`, works for links to files that do not exist yet.
+fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut current = path.to_path_buf();
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        let Ok(link) = fs::read_link(&current) else {
+            break;
+        };
+        current = match current.parent() {
+            Some(parent) => parent.join(link),
+            None => link,
+        };
+    }
+    current
+}
+
+/// Write contents to a synced temporary file next to `target`.
+///
+/// Returns `Ok(Err(reason))` if the file should be written in place
+/// instead. Write errors are returned as errors: the disk is likely full,
+/// and writing in place would truncate the original.
+fn prepare_temp_file(
+    target: &Path,
+    contents: &[u8],
+    metadata: Option<&fs::Metadata>,
+) -> Result<std::result::Result<PathBuf, String>> {
+    #[cfg(unix)]
+    if let Some(metadata) = metadata {
+        use std::os::unix::fs::MetadataExt;
+        if metadata.nlink() > 1 {
+            return Ok(Err("file has multiple hard links".to_string()));
+        }
+    }
+
+    let (temp, mut file) = match create_temp_file(target) {
+        Ok(created) => created,
+        Err(e) => return Ok(Err(format!("cannot create temporary file: {}", e))),
+    };
+
+    let written = file.write_all(contents).and_then(|_| file.sync_all());
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_context(|| format!("Failed to write file: {}", target.display()));
+    }
+
+    if let Some(metadata) = metadata {
+        if let Err(reason) = copy_file_attributes(&temp, metadata) {
+            let _ = fs::remove_file(&temp);
+            return Ok(Err(reason));
+        }
+    }
+    Ok(Ok(temp))
+}
+
+/// Create a new hidden file in the target's directory
+fn create_temp_file(target: &Path) -> std::io::Result<(PathBuf, File)> {
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    let mut attempt = 0;
+    loop {
+        let temp =
+            target.with_file_name(format!(".{}.{}-{}.tmp", name, std::process::id(), attempt));
+        match OpenOptions::new().write(true).create_new(true).open(&temp) {
+            Ok(file) => return Ok((temp, file)),
+            Err(e)
+                if e.kind() == std::io::ErrorKind::AlreadyExists
+                    && attempt < TEMP_NAME_ATTEMPTS =>
+            {
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Give the temporary file the original's permissions and owner
+fn copy_file_attributes(temp: &Path, metadata: &fs::Metadata) -> std::result::Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let owner = fs::metadata(temp).map(|m| (m.uid(), m.gid())).ok();
+        if owner != Some((metadata.uid(), metadata.gid())) {
+            std::os::unix::fs::chown(temp, Some(metadata.uid()), Some(metadata.gid()))
+                .map_err(|e| format!("cannot preserve file owner: {}", e))?;
+        }
+    }
+    fs::set_permissions(temp, metadata.permissions())
+        .map_err(|e| format!("cannot preserve permissions: {}", e))
+}
+
+/// Persist the rename (best effort, not supported everywhere)
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_write_and_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+
+        // New files are written atomically too
+        assert_eq!(
+            write_file(&path, b"one", WriteOptions::default()).unwrap(),
+            WriteMethod::Atomic
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one");
+        assert!(!backup_path(&path).exists());
+
+        let options = WriteOptions { backup: true };
+        assert_eq!(
+            write_file(&path, b"two", options).unwrap(),
+            WriteMethod::Atomic
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("file.txt~")).unwrap(),
+            "one"
+        );
+
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permissions_preserved() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script.sh");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+        write_file(&path, b"new", WriteOptions { backup: true }).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+        let backup_mode = fs::metadata(backup_path(&path))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(backup_mode & 0o777, 0o750);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_target_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target.txt");
+        let link = dir.path().join("link.txt");
+        fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink("target.txt", &link).unwrap();
+
+        write_file(&link, b"new", WriteOptions { backup: true }).unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        // The backup belongs to the file that was changed
+        assert_eq!(
+            fs::read_to_string(dir.path().join("target.txt~")).unwrap(),
+            "old"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hard_links_written_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        let other = dir.path().join("other.txt");
+        fs::write(&path, "old").unwrap();
+        fs::hard_link(&path, &other).unwrap();
+
+        let method = write_file(&path, b"new", WriteOptions::default()).unwrap();
+
+        assert!(matches!(method, WriteMethod::Direct { .. }));
+        assert_eq!(fs::read_to_string(&other).unwrap(), "new");
+    }
+}
@@ -2,71 +2,188 @@
 //!
 //! Provides cross-platform clipboard access using arboard.
 //! On Linux, supports both CLIPBOARD and PRIMARY selections.
+//! Without a local clipboard provider (no display, e.g. over SSH) and in
+//! SSH sessions, the terminal's clipboard is used through OSC 52.
+
+mod osc52;
 
 use arboard::Clipboard;
 use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
 #[cfg(target_os = "linux")]
 use arboard::{GetExtLinux, LinuxClipboardKind, SetExtLinux};
 
-/// Global clipboard instance that persists for the application lifetime.
-static CLIPBOARD: OnceLock<Mutex<Clipboard>> = OnceLock::new();
+use termide_config::constants::OSC52_PASTE_TIMEOUT_MS;
+pub use termide_config::ClipboardTarget;
+
+/// Global clipboard instance that persists for the application lifetime
+/// (None if no clipboard provider is available).
+static CLIPBOARD: OnceLock<Option<Mutex<Clipboard>>> = OnceLock::new();
+
+/// Selections receiving copied text.
+static COPY_TARGET: Mutex<ClipboardTarget> = Mutex::new(ClipboardTarget::Both);
+
+/// Text last copied as a block (column selection), pasted as a rectangle
+/// while the clipboard still holds it.
+static BLOCK_TEXT: Mutex<Option<String>> = Mutex::new(None);
 
 /// Get or initialize the global clipboard instance.
-fn get_clipboard() -> &'static Mutex<Clipboard> {
-    CLIPBOARD.get_or_init(|| Mutex::new(Clipboard::new().expect("Failed to initialize clipboard")))
+fn get_clipboard() -> Option<&'static Mutex<Clipboard>> {
+    CLIPBOARD
+        .get_or_init(|| match Clipboard::new() {
+            Ok(clipboard) => Some(Mutex::new(clipboard)),
+            Err(e) => {
+                log::warn!("No clipboard provider, using OSC 52: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Check if termide runs in an SSH session (the local clipboard is remote).
+fn is_remote_session() -> bool {
+    std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some()
+}
+
+/// Set the selections copied text goes to.
+pub fn set_copy_target(target: ClipboardTarget) {
+    if let Ok(mut copy_target) = COPY_TARGET.lock() {
+        *copy_target = target;
+    }
+}
+
+/// Selections copied text goes to.
+fn copy_target() -> ClipboardTarget {
+    COPY_TARGET
+        .lock()
+        .map(|target| *target)
+        .unwrap_or(ClipboardTarget::Both)
 }
 
 /// Copy text to system clipboard.
 ///
-/// Uses arboard for cross-platform clipboard access.
-/// On Linux, copies to BOTH CLIPBOARD and PRIMARY selections.
+/// Copies to the selections chosen with `set_copy_target` (CLIPBOARD and
+/// PRIMARY by default). In SSH sessions or without a clipboard provider
+/// the text is also sent to the terminal with OSC 52.
 ///
 /// Returns Ok(()) on success, or Err with detailed error message.
 pub fn copy(text: &str) -> Result<(), String> {
+    set_block_text(None);
+    copy_text(text)
+}
+
+/// Copy text of a block (column) selection, one line per row.
+///
+/// Besides copying like `copy`, tags the text so that `is_block` reports
+/// it until something else is copied.
+pub fn copy_block(text: &str) -> Result<(), String> {
+    copy_text(text)?;
+    set_block_text(Some(text.to_string()));
+    Ok(())
+}
+
+/// Check if pasted text was copied as a block.
+///
+/// Text copied by another application in the meantime is not a block,
+/// even when it was copied as one before.
+pub fn is_block(text: &str) -> bool {
+    BLOCK_TEXT.lock().is_ok_and(|block| {
+        block
+            .as_deref()
+            .is_some_and(|block| block == text || block == text.replace("\r\n", "\n"))
+    })
+}
+
+/// Remember (or forget) the text copied as a block.
+fn set_block_text(text: Option<String>) {
+    if let Ok(mut block) = BLOCK_TEXT.lock() {
+        *block = text;
+    }
+}
+
+/// Copy text to the selections chosen with `set_copy_target`.
+fn copy_text(text: &str) -> Result<(), String> {
     if text.is_empty() {
         return Err("Cannot copy empty text".to_string());
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        let mut clipboard = get_clipboard()
-            .lock()
-            .map_err(|e| format!("Failed to lock clipboard: {}", e))?;
+    let target = copy_target();
+    let clipboard = get_clipboard();
+    let mut copied = false;
 
-        // Copy to CLIPBOARD selection (Ctrl+C/V)
-        clipboard
-            .set()
-            .clipboard(LinuxClipboardKind::Clipboard)
-            .text(text.to_string())
-            .map_err(|e| format!("Failed to set clipboard text: {}", e))?;
+    if clipboard.is_none() || is_remote_session() {
+        let selections = match target {
+            ClipboardTarget::Clipboard => "c",
+            ClipboardTarget::Primary => "p",
+            ClipboardTarget::Both => "cp",
+        };
+        copied = osc52::set(selections, text).is_ok();
+    }
 
-        // Copy to PRIMARY selection (middle-click/Shift+Insert)
-        let _ = clipboard
-            .set()
-            .clipboard(LinuxClipboardKind::Primary)
-            .text(text.to_string());
+    match clipboard {
+        Some(clipboard) => match copy_local(clipboard, target, text) {
+            Ok(()) => Ok(()),
+            Err(_) if copied => Ok(()),
+            Err(e) => Err(e),
+        },
+        None if copied => Ok(()),
+        None => Err("No clipboard available".to_string()),
+    }
+}
+
+/// Copy text with the local clipboard provider.
+fn copy_local(
+    clipboard: &Mutex<Clipboard>,
+    target: ClipboardTarget,
+    text: &str,
+) -> Result<(), String> {
+    let mut clipboard = clipboard
+        .lock()
+        .map_err(|e| format!("Failed to lock clipboard: {}", e))?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let set_kind = |clipboard: &mut Clipboard, kind| {
+            clipboard
+                .set()
+                .clipboard(kind)
+                .text(text.to_string())
+                .map_err(|e| format!("Failed to set clipboard text: {}", e))
+        };
+        match target {
+            ClipboardTarget::Clipboard => set_kind(&mut clipboard, LinuxClipboardKind::Clipboard),
+            ClipboardTarget::Primary => set_kind(&mut clipboard, LinuxClipboardKind::Primary),
+            ClipboardTarget::Both => {
+                // CLIPBOARD selection (Ctrl+C/V)
+                set_kind(&mut clipboard, LinuxClipboardKind::Clipboard)?;
+                // PRIMARY selection (middle-click/Shift+Insert)
+                let _ = set_kind(&mut clipboard, LinuxClipboardKind::Primary);
+                Ok(())
+            }
+        }
     }
 
+    // Other platforms have no PRIMARY selection
     #[cfg(not(target_os = "linux"))]
     {
-        let mut clipboard = get_clipboard()
-            .lock()
-            .map_err(|e| format!("Failed to lock clipboard: {}", e))?;
+        let _ = target;
         clipboard
             .set_text(text)
-            .map_err(|e| format!("Failed to set clipboard text: {}", e))?;
+            .map_err(|e| format!("Failed to set clipboard text: {}", e))
     }
-
-    Ok(())
 }
 
 /// Paste text from system clipboard.
 ///
 /// On Linux, tries CLIPBOARD selection first, then falls back to PRIMARY.
+/// Without a clipboard provider, asks the terminal with OSC 52.
 /// Returns None if clipboard is empty or inaccessible.
 pub fn paste() -> Option<String> {
-    let mut clipboard = get_clipboard().lock().ok()?;
+    let Some(clipboard) = get_clipboard() else {
+        return osc52::query('c', Duration::from_millis(OSC52_PASTE_TIMEOUT_MS));
+    };
+    let mut clipboard = clipboard.lock().ok()?;
 
     #[cfg(target_os = "linux")]
     {
@@ -93,6 +210,34 @@ pub fn paste() -> Option<String> {
     clipboard.get_text().ok()
 }
 
+/// Paste text from the PRIMARY selection (middle click).
+///
+/// Platforms without PRIMARY use the clipboard. Without a clipboard
+/// provider, asks the terminal with OSC 52.
+pub fn paste_primary() -> Option<String> {
+    let Some(clipboard) = get_clipboard() else {
+        return osc52::query('p', Duration::from_millis(OSC52_PASTE_TIMEOUT_MS));
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        clipboard
+            .lock()
+            .ok()?
+            .get()
+            .clipboard(LinuxClipboardKind::Primary)
+            .text()
+            .ok()
+            .filter(|text| !text.is_empty())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = clipboard;
+        paste()
+    }
+}
+
 /// Cut text to clipboard.
 ///
 /// Same as copy - actual deletion is handled by the caller.
@@ -104,3 +249,19 @@ pub fn cut(text: &str) -> Result<(), String> {
 pub fn has_text() -> bool {
     paste().map(|t| !t.is_empty()).unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_tag_matches_copied_text() {
+        set_block_text(Some("ab\ncd".to_string()));
+        assert!(is_block("ab\ncd"));
+        assert!(is_block("ab\r\ncd"));
+        assert!(!is_block("ab\ncd\n"));
+
+        set_block_text(None);
+        assert!(!is_block("ab\ncd"));
+    }
+}
@@ -0,0 +1,128 @@
+//! OSC 52 clipboard escape sequences.
+//!
+//! Terminals supporting OSC 52 set their clipboard from an escape sequence
+//! and report it when asked, which reaches the clipboard of the local
+//! machine from a remote session (SSH) without a clipboard provider.
+
+use std::io::Write;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Start of an OSC 52 sequence
+const PREFIX: &[u8] = b"\x1b]52;";
+
+/// Sequence setting `selections` (`c` clipboard, `p` primary) to `text`
+pub(crate) fn set_sequence(selections: &str, text: &str) -> String {
+    format!("\x1b]52;{};{}\x07", selections, STANDARD.encode(text))
+}
+
+/// Ask the terminal to set its `selections` to `text`
+pub(crate) fn set(selections: &str, text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(set_sequence(selections, text).as_bytes())?;
+    stdout.flush()
+}
+
+/// Ask the terminal for the contents of `selection` (`c` or `p`)
+///
+/// Returns None if the terminal does not answer within `timeout` (OSC 52
+/// reads are not supported or disabled). Keys typed while waiting are lost.
+#[cfg(unix)]
+pub(crate) fn query(selection: char, timeout: Duration) -> Option<String> {
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = std::fs::File::open("/dev/tty").ok()?;
+    {
+        let mut stdout = std::io::stdout().lock();
+        write!(stdout, "\x1b]52;{};?\x07", selection).ok()?;
+        stdout.flush().ok()?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return None;
+        }
+        let mut pollfd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pollfd is a valid pollfd for the duration of the call
+        let ready = unsafe { libc::poll(&mut pollfd, 1, left.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return None;
+        }
+        let read = tty.read(&mut chunk).ok()?;
+        if read == 0 {
+            return None;
+        }
+        reply.extend_from_slice(&chunk[..read]);
+        if let Some(text) = parse_reply(&reply) {
+            return text;
+        }
+    }
+}
+
+/// OSC 52 reads need a Unix terminal device
+#[cfg(not(unix))]
+pub(crate) fn query(_selection: char, _timeout: Duration) -> Option<String> {
+    None
+}
+
+/// Text of a complete OSC 52 reply (`ESC ] 52 ; c ; <base64> BEL`, or
+/// terminated by `ESC \`), or None while the reply is incomplete
+fn parse_reply(reply: &[u8]) -> Option<Option<String>> {
+    let start = reply
+        .windows(PREFIX.len())
+        .position(|window| window == PREFIX)?;
+    let body = &reply[start + PREFIX.len()..];
+    let end = (0..body.len())
+        .find(|&i| body[i] == 0x07 || (body[i] == 0x1b && body.get(i + 1) == Some(&b'\\')))?;
+
+    let data = body[..end]
+        .iter()
+        .position(|&b| b == b';')
+        .map(|semicolon| &body[semicolon + 1..end]);
+    let text = data
+        .and_then(|data| STANDARD.decode(data).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|text| !text.is_empty());
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_sequence_encodes_text() {
+        assert_eq!(set_sequence("c", "hi"), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        // Incomplete replies wait for more input
+        assert_eq!(parse_reply(b""), None);
+        assert_eq!(parse_reply(b"\x1b]52;c;aG"), None);
+
+        assert_eq!(
+            parse_reply(b"\x1b]52;c;aGk=\x07"),
+            Some(Some("hi".to_string()))
+        );
+        assert_eq!(
+            parse_reply(b"x\x1b]52;p;aGk=\x1b\\"),
+            Some(Some("hi".to_string()))
+        );
+        // Empty or invalid clipboard
+        assert_eq!(parse_reply(b"\x1b]52;c;\x07"), Some(None));
+        assert_eq!(parse_reply(b"\x1b]52;c;!!\x07"), Some(None));
+    }
+}
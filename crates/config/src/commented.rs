@@ -0,0 +1,592 @@
+//! Config file with a comment for every setting.
+//!
+//! `toml` cannot write comments, so the documentation of each setting is
+//! kept here, in the order of the config sections, and the file is written
+//! section by section. Optional settings that are not set are written
+//! commented out with an example value.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use crate::Config;
+
+/// Text at the top of the config file
+const HEADER: &str = "\
+# termide configuration
+#
+# Every setting is listed with its documentation. Changes are applied when
+# the file is saved; `language` and the log file path need a restart.
+";
+
+/// Documented setting of a section
+struct Setting {
+    key: &'static str,
+    comment: &'static str,
+    /// Written commented out when the setting is not set (or is an empty table)
+    example: Option<&'static str>,
+}
+
+/// Documented config section
+struct Section {
+    name: &'static str,
+    comment: &'static str,
+    settings: &'static [Setting],
+    /// Written commented out when the section is a table without entries
+    example: Option<&'static str>,
+}
+
+const fn setting(key: &'static str, comment: &'static str) -> Setting {
+    Setting {
+        key,
+        comment,
+        example: None,
+    }
+}
+
+const fn optional(key: &'static str, comment: &'static str, example: &'static str) -> Setting {
+    Setting {
+        key,
+        comment,
+        example: Some(example),
+    }
+}
+
+const SECTIONS: &[Section] = &[
+    Section {
+        name: "general",
+        comment: "General application settings",
+        settings: &[
+            setting("theme", "Selected theme name"),
+            setting(
+                "theme_mode",
+                "Light or dark theme choice (\"fixed\" uses `theme` as is, \"auto\"\n\
+                 follows the terminal background, \"light\" or \"dark\" force one)",
+            ),
+            setting(
+                "light_theme",
+                "Theme used when a light theme is wanted and `theme` is dark",
+            ),
+            setting(
+                "dark_theme",
+                "Theme used when a dark theme is wanted and `theme` is light",
+            ),
+            setting(
+                "follow_terminal_background",
+                "Check the terminal background again when the terminal gets focus\n\
+                 (\"auto\" mode)",
+            ),
+            setting(
+                "language",
+                "Interface language (en, de, es, fr, hi, pt, ru, th, zh, or auto)",
+            ),
+            setting("min_panel_width", "Minimum panel width in characters"),
+            setting("session_retention_days", "Session retention period in days"),
+            setting(
+                "restore_session_with_args",
+                "Restore the saved session also when files or directories are given\n\
+                 on the command line (their panels are added to it)",
+            ),
+            setting(
+                "clipboard_target",
+                "Selections copied text goes to (\"clipboard\", \"primary\" or \"both\")",
+            ),
+            setting(
+                "git_backend",
+                "How git status is read (\"auto\", \"cli\" or \"libgit2\")",
+            ),
+            setting(
+                "status_bar_left",
+                "Status bar segments shown on the left (\"file\", \"cursor\", \"disk\", ...)",
+            ),
+            setting("status_bar_right", "Status bar segments shown on the right"),
+            setting(
+                "mouse_capture",
+                "Capture the mouse (clicks, scrolling, dragging panel borders); when\n\
+                 off the terminal's own text selection works",
+            ),
+            setting(
+                "ipc_server",
+                "Accept commands from other programs over a Unix socket in the\n\
+                 runtime directory (`termide --send`)",
+            ),
+            setting(
+                "notification_timeout",
+                "Seconds before notifications other than errors go away (errors stay\n\
+                 until dismissed)",
+            ),
+            setting(
+                "max_notifications",
+                "Notifications shown at once in the top right corner",
+            ),
+            setting(
+                "exclude_dirs",
+                "Directories skipped when indexing project files (the file finder)",
+            ),
+        ],
+        example: None,
+    },
+    Section {
+        name: "editor",
+        comment: "Editor settings",
+        settings: &[
+            setting("tab_size", "Tab size (number of spaces)"),
+            setting(
+                "indent_style",
+                "Indentation inserted: \"auto\" (follow the file), \"spaces\" or \"tabs\"",
+            ),
+            setting("show_git_diff", "Show git diff status colors on line numbers"),
+            setting("word_wrap", "Enable word wrap in editor"),
+            setting(
+                "wrap_at_punctuation",
+                "Break wrapped lines after punctuation (/, -, ...) when there is no space",
+            ),
+            setting(
+                "visual_line_navigation",
+                "Move by wrapped rows with Up/Down, Home/End and PageUp/PageDown (false: whole lines)",
+            ),
+            setting(
+                "large_file_threshold_mb",
+                "File size threshold in MB for large file mode",
+            ),
+            setting(
+                "large_file_threshold_lines",
+                "Line count threshold for large file mode",
+            ),
+            setting(
+                "max_highlight_columns",
+                "Columns of a line highlighted before the rest is shown plain (0 = no limit)",
+            ),
+            setting(
+                "trim_trailing_whitespace",
+                "Strip trailing whitespace on save (\"off\", \"modified\" lines only, or \"all\" lines)",
+            ),
+            setting(
+                "ensure_final_newline",
+                "End files with exactly one newline on save",
+            ),
+            setting(
+                "auto_close_brackets",
+                "Insert closing brackets and quotes automatically",
+            ),
+            setting(
+                "jump_list_size",
+                "Maximum number of positions in the jump list (0 disables it)",
+            ),
+            setting(
+                "undo_tree_size",
+                "Maximum number of edits kept for undo, oldest abandoned branches dropped first",
+            ),
+            setting(
+                "scroll_off",
+                "Lines kept visible above and below the cursor when scrolling",
+            ),
+            setting("autocomplete", "Offer words of open buffers while typing"),
+            setting(
+                "autocomplete_min_chars",
+                "Characters typed before completion is offered",
+            ),
+            setting(
+                "autocomplete_open_buffers",
+                "Also complete words of other open files",
+            ),
+            setting(
+                "backup_on_save",
+                "Keep the previous version as `file~` when saving",
+            ),
+            setting(
+                "diff_layout",
+                "Layout of diff panels (\"side_by_side\" or \"unified\")",
+            ),
+            setting(
+                "reuse_open_editors",
+                "Focus the editor already showing a file instead of opening another one",
+            ),
+            setting(
+                "spell_check",
+                "Underline misspelled words in comments, strings and prose",
+            ),
+            setting(
+                "spell_dictionaries",
+                "Word lists or hunspell `.dic` files to check spelling with\n\
+                 (missing files are skipped)",
+            ),
+            setting(
+                "dropped_files",
+                "What pasting paths of existing files does (\"ask\", \"open\" or \"insert\")",
+            ),
+            setting(
+                "recovery",
+                "Copy unsaved changes to the recovery directory, offered for restore\n\
+                 after a crash",
+            ),
+            setting(
+                "recovery_idle_secs",
+                "Seconds without edits after which unsaved changes are copied",
+            ),
+            setting(
+                "recovery_max_size_mb",
+                "Buffers larger than this (in megabytes) are not copied",
+            ),
+            setting(
+                "render_whitespace",
+                "Whitespace drawn visibly: \"off\", \"trailing\" (trailing whitespace and\n\
+                 non-breaking or zero-width spaces) or \"all\"",
+            ),
+            setting("whitespace_space_glyph", "Glyph drawn for spaces"),
+            setting("whitespace_tab_glyph", "Glyph drawn for tabs"),
+            setting(
+                "whitespace_unicode_glyph",
+                "Glyph drawn for non-breaking and zero-width spaces",
+            ),
+        ],
+        example: None,
+    },
+    Section {
+        name: "file_manager",
+        comment: "File manager settings",
+        settings: &[
+            setting(
+                "extended_view_width",
+                "Minimum width to display extended columns (size, time)",
+            ),
+            setting(
+                "show_symlink_targets",
+                "Show symlink targets inline after the name (\"link → target\")",
+            ),
+            setting(
+                "use_trash",
+                "Move deleted files to trash instead of removing them permanently",
+            ),
+            setting(
+                "copy_dereference_symlinks",
+                "Copy files that symlinks point to instead of the symlinks themselves",
+            ),
+            setting(
+                "open_logs_in_viewer",
+                "Open `.log` files in a tailing log viewer instead of the editor",
+            ),
+            setting(
+                "sort",
+                "Order of entries in directories without their own sort order\n\
+                 (\"name\", \"size\" or \"modified\")",
+            ),
+            setting(
+                "show_hidden",
+                "Show hidden (dot) files in directories without their own setting",
+            ),
+            setting(
+                "confirm_dropped_files",
+                "Ask before copying files whose paths are pasted (or dropped) here",
+            ),
+            setting(
+                "compare_max_depth",
+                "Directory levels walked when comparing directories",
+            ),
+            setting(
+                "compare_max_entries",
+                "Entries examined when comparing directories before the comparison\n\
+                 stops",
+            ),
+            setting(
+                "duplicates_max_files",
+                "Files listed when looking for duplicates before the search stops",
+            ),
+            setting(
+                "duplicates_skip_ignored",
+                "Leave out files ignored by git when looking for duplicates",
+            ),
+        ],
+        example: None,
+    },
+    Section {
+        name: "terminal",
+        comment: "Terminal settings",
+        settings: &[
+            optional(
+                "shell",
+                "Shell to run (detected when not set)",
+                "\"/bin/bash\"",
+            ),
+            optional(
+                "shell_args",
+                "Shell arguments (login/interactive flags for the shell when not set)",
+                "[\"-l\"]",
+            ),
+            setting(
+                "on_exit",
+                "What happens to a terminal running the shell when it exits: \"close\",\n\
+                 \"keep\" (the output stays for scrolling and copying until closed)\n\
+                 or \"keep-on-error\" (kept only for a nonzero exit code)",
+            ),
+            setting(
+                "close_on_exit",
+                "Close terminals running a command (not the shell) when it exits;\n\
+                 otherwise they stay open and show the exit code",
+            ),
+            setting(
+                "program_title",
+                "Show the title set by the running program (vim, ssh) in the panel\n\
+                 header instead of user@host:dir",
+            ),
+            optional(
+                "env",
+                "Environment variables added for terminal processes",
+                "EDITOR = \"termide\"",
+            ),
+        ],
+        example: None,
+    },
+    Section {
+        name: "logging",
+        comment: "Logging settings",
+        settings: &[
+            optional(
+                "file_path",
+                "Log file path (optional)",
+                "\"/tmp/termide.log\"",
+            ),
+            setting("min_level", "Minimum log level (debug, info, warn, error)"),
+            setting(
+                "resource_monitor_interval",
+                "System resource monitor update interval in ms",
+            ),
+            setting(
+                "system_monitor_interval",
+                "System monitor panel sampling interval in ms",
+            ),
+            setting(
+                "system_monitor_delta",
+                "Smallest change of CPU or memory usage (in percentage points) that\n\
+                 redraws the system monitor panel",
+            ),
+        ],
+        example: None,
+    },
+    Section {
+        name: "lsp",
+        comment: "Language server settings",
+        settings: &[
+            setting("enabled", "Start language servers for the open files"),
+            optional(
+                "servers",
+                "Server command lines (speaking LSP over stdio): language name\n\
+                 (`rust`, `python`) -> command (`\"rust-analyzer\"`)",
+                "rust = \"rust-analyzer\"",
+            ),
+        ],
+        example: None,
+    },
+    Section {
+        name: "format",
+        comment: "External formatter settings",
+        settings: &[
+            optional(
+                "commands",
+                "Formatter command lines (reading the text on stdin and writing the\n\
+                 formatted text to stdout): language name (`rust`, `python`)\n\
+                 -> command (`\"rustfmt --edition 2021\"`); `{file}` is replaced with\n\
+                 the path of the file",
+                "rust = \"rustfmt --edition 2021\"",
+            ),
+            setting("on_save", "Languages formatted before each save (Ctrl+S)"),
+        ],
+        example: None,
+    },
+    Section {
+        name: "highlight",
+        comment: "Syntax highlight styles overriding the built-in colors:\n\
+                  highlight name (`keyword`, `string`, ...) -> `\"#rrggbb bold italic\"`",
+        settings: &[],
+        example: Some("keyword = \"#c678dd bold\""),
+    },
+    Section {
+        name: "keybindings",
+        comment: "Extra global hotkeys: action name (`close_panel`, `go_to_panel_2`)\n\
+                  -> key or chord (`\"ctrl+k ctrl+w\"`)",
+        settings: &[],
+        example: Some("close_panel = \"ctrl+k ctrl+w\""),
+    },
+    Section {
+        name: "tasks",
+        comment: "Tasks run from the project root: name (`build`, `test`)\n\
+                  -> shell command (`\"cargo build\"`)",
+        settings: &[],
+        example: Some("build = \"cargo build\""),
+    },
+];
+
+/// Longest array written on one line
+const MAX_INLINE_ARRAY: usize = 60;
+
+impl Config {
+    /// The config as TOML with the documentation of every setting
+    pub fn to_commented_toml(&self) -> Result<String> {
+        let toml::Value::Table(mut sections) = toml::Value::try_from(self)? else {
+            anyhow::bail!("config is not a table");
+        };
+        let mut out = String::from(HEADER);
+        for section in SECTIONS {
+            let mut values = match sections.remove(section.name) {
+                Some(toml::Value::Table(values)) => values,
+                _ => toml::Table::new(),
+            };
+            out.push('\n');
+            write_comment(&mut out, section.comment);
+            writeln!(out, "[{}]", section.name)?;
+            // Tables of a section follow its plain settings
+            let mut tables = Vec::new();
+            for setting in section.settings {
+                match values.remove(setting.key) {
+                    Some(toml::Value::Table(table)) => tables.push((setting, table)),
+                    Some(value) => {
+                        write_comment(&mut out, setting.comment);
+                        writeln!(out, "{} = {}", key(setting.key), format_value(&value))?;
+                    }
+                    None => {
+                        write_comment(&mut out, setting.comment);
+                        if let Some(example) = setting.example {
+                            writeln!(out, "# {} = {}", key(setting.key), example)?;
+                        }
+                    }
+                }
+            }
+            if section.settings.is_empty() {
+                write_entries(&mut out, &values, section.example)?;
+            } else {
+                // Settings without documentation are kept all the same
+                let undocumented: toml::Table = values
+                    .into_iter()
+                    .filter(|(_, value)| !value.is_table())
+                    .collect();
+                write_entries(&mut out, &undocumented, None)?;
+            }
+            for (setting, table) in tables {
+                out.push('\n');
+                write_comment(&mut out, setting.comment);
+                writeln!(out, "[{}.{}]", section.name, key(setting.key))?;
+                write_entries(&mut out, &table, setting.example)?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Write the default config, with the documentation of every setting,
+    /// to the config file
+    pub fn write_default_with_comments() -> Result<()> {
+        Self::default().save()
+    }
+}
+
+/// Write `key = value` lines, or the example commented out if there are none
+fn write_entries(out: &mut String, table: &toml::Table, example: Option<&str>) -> Result<()> {
+    if table.is_empty() {
+        if let Some(example) = example {
+            writeln!(out, "# {}", example)?;
+        }
+    }
+    for (name, value) in table {
+        writeln!(out, "{} = {}", key(name), format_value(value))?;
+    }
+    Ok(())
+}
+
+fn write_comment(out: &mut String, comment: &str) {
+    for line in comment.lines() {
+        out.push_str("# ");
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Key as written in TOML (quoted unless it is a bare key)
+fn key(name: &str) -> String {
+    let bare = !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if bare {
+        name.to_string()
+    } else {
+        toml::Value::String(name.to_string()).to_string()
+    }
+}
+
+/// Value as written in TOML, long arrays one item per line
+fn format_value(value: &toml::Value) -> String {
+    let inline = value.to_string();
+    match value {
+        toml::Value::Array(items) if inline.len() > MAX_INLINE_ARRAY => {
+            let mut out = String::from("[\n");
+            for item in items {
+                out.push_str("    ");
+                out.push_str(&item.to_string());
+                out.push_str(",\n");
+            }
+            out.push(']');
+            out
+        }
+        _ => inline,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_setting_is_documented() {
+        let mut config = Config::default();
+        config.terminal.shell = Some("/bin/zsh".to_string());
+        config.terminal.shell_args = Some(vec!["-l".to_string()]);
+        config.logging.file_path = Some("/tmp/termide.log".to_string());
+        let toml::Value::Table(sections) = toml::Value::try_from(&config).unwrap() else {
+            panic!("config is not a table");
+        };
+        for (name, values) in sections {
+            let section = SECTIONS
+                .iter()
+                .find(|section| section.name == name)
+                .unwrap_or_else(|| panic!("section {} is not documented", name));
+            if section.settings.is_empty() {
+                continue;
+            }
+            for key in values.as_table().unwrap().keys() {
+                assert!(
+                    section.settings.iter().any(|setting| setting.key == key),
+                    "{}.{} is not documented",
+                    name,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_commented_toml_reads_back() {
+        let default = Config::default();
+        let content = default.to_commented_toml().unwrap();
+        assert!(content.contains("# Tab size (number of spaces)\ntab_size = 4\n"));
+        assert!(content.contains("# shell = \"/bin/bash\"\n"));
+        assert!(content.contains("[lsp.servers]\n"));
+        let read = Config::validate_content(&content).unwrap();
+        assert!(default.changed_settings(&read).is_empty());
+
+        let mut config = Config::default();
+        config.general.theme = "nord".to_string();
+        config.editor.tab_size = 2;
+        config.terminal.shell = Some("/usr/bin/fish".to_string());
+        config
+            .terminal
+            .env
+            .insert("PAGER".to_string(), "less -R".to_string());
+        config
+            .keybindings
+            .insert("go_to_panel_2".to_string(), "alt+2".to_string());
+        config
+            .tasks
+            .insert("unit tests".to_string(), "cargo test".to_string());
+        let read = Config::validate_content(&config.to_commented_toml().unwrap()).unwrap();
+        assert!(config.changed_settings(&read).is_empty());
+    }
+}
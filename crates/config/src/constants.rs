@@ -14,8 +14,8 @@ pub const GIGABYTE: u64 = MEGABYTE * 1024;
 /// Maximum file size to open in editor (100 MB).
 pub const MAX_EDITOR_FILE_SIZE: u64 = 100 * MEGABYTE;
 
-/// Default file size threshold in MB for enabling smart features (5 MB).
-pub const DEFAULT_LARGE_FILE_THRESHOLD_MB: u64 = 5;
+/// Default file size threshold in MB for large file mode (10 MB).
+pub const DEFAULT_LARGE_FILE_THRESHOLD_MB: u64 = 10;
 
 // ===== UI and layout constants =====
 
@@ -31,6 +31,9 @@ pub const MIN_MAIN_PANEL_WIDTH: u16 = 80;
 /// Default main panel width in multi-panel mode.
 pub const DEFAULT_MAIN_PANEL_WIDTH: u16 = 80;
 
+/// Minimum width of a panel group when resizing.
+pub const MIN_PANEL_GROUP_WIDTH: u16 = 20;
+
 // ===== Modal constants =====
 
 /// Maximum modal width as percentage of screen width (default).
@@ -65,8 +68,62 @@ pub const MAX_DIRECTORY_COPY_DEPTH: usize = 100;
 /// Maximum number of log entries.
 pub const MAX_LOG_ENTRIES: usize = 1000;
 
-/// Event update interval in milliseconds (42ms = ~24 FPS).
+/// Maximum number of lines kept by a log viewer tailing a file.
+pub const MAX_LOG_FILE_LINES: usize = 10_000;
+
+/// Bytes read from the end of a log file when it is opened.
+pub const LOG_FILE_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Maximum number of matching lines listed per file by the search panel.
+pub const MAX_SEARCH_MATCHES_PER_FILE: usize = 100;
+
+/// Maximum number of matching lines listed by the search panel.
+pub const MAX_SEARCH_MATCHES: usize = 2000;
+
+/// Files larger than this are skipped by the search panel.
+pub const MAX_SEARCH_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Output lines kept by a task panel (older lines are dropped).
+pub const MAX_TASK_OUTPUT_LINES: usize = 10_000;
+
+/// Time to wait for the terminal to answer an OSC 52 clipboard query.
+pub const OSC52_PASTE_TIMEOUT_MS: u64 = 500;
+
+/// Time to wait for the terminal to report its background color.
+pub const BACKGROUND_QUERY_TIMEOUT_MS: u64 = 200;
+
+/// Quiet time after an edit before the text is sent to the language server.
+pub const LSP_CHANGE_DEBOUNCE_MS: u64 = 300;
+
+/// Edits after which a modified buffer is copied for recovery without
+/// waiting for a pause in typing.
+pub const RECOVERY_MAX_EDITS: u64 = 300;
+
+/// Time an external formatter may run before it is killed.
+pub const FORMAT_TIMEOUT_MS: u64 = 5000;
+
+/// Maximum number of files indexed for the file finder.
+pub const MAX_INDEXED_FILES: usize = 50_000;
+
+/// Quiet time after filesystem changes before the file finder index is rebuilt.
+pub const FILE_INDEX_REFRESH_DELAY_MS: u64 = 1000;
+
+/// Number of recently run commands listed first in the command palette.
+pub const MAX_RECENT_COMMANDS: usize = 5;
+
+/// Number of recently visited directories whose view a file manager remembers.
+pub const MAX_DIR_VIEWS: usize = 32;
+
+/// Interval of background work (channels, watchers, panel ticks) while
+/// active, in milliseconds.
 pub const EVENT_HANDLER_INTERVAL_MS: u64 = 42;
 
+/// Shortest time between two frames in milliseconds (~60 FPS).
+pub const FRAME_INTERVAL_MS: u64 = 16;
+
+/// Time after input or panel output during which the main loop polls at
+/// the frame rate, in milliseconds.
+pub const ACTIVE_INTERVAL_MS: u64 = 1000;
+
 /// Double-click detection interval in milliseconds.
 pub const DOUBLE_CLICK_INTERVAL_MS: u128 = 500;
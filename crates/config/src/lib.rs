@@ -3,12 +3,21 @@
 //! This crate provides configuration loading, saving, and validation
 //! with support for TOML format and XDG directory conventions.
 
+mod commented;
 pub mod constants;
+mod project;
 mod settings;
 mod xdg;
 
+pub use project::{
+    is_project_file, ProjectConfig, ProjectConfigs, ProjectEditorSettings, ProjectFormatSettings,
+    ProjectGeneralSettings, PROJECT_FILE_NAME,
+};
 pub use settings::{
-    Config, EditorSettings, FileManagerSettings, GeneralSettings, LegacyConfig, LoggingSettings,
+    ClipboardTarget, Config, DiffLayout, DroppedFiles, EditorSettings, FileManagerSettings,
+    FileSort, FormatSettings, GeneralSettings, GitBackend, IndentMode, LegacyConfig,
+    LoggingSettings, LspSettings, RenderWhitespace, TerminalExit, TerminalSettings, ThemeMode,
+    TrimTrailingWhitespace,
 };
 pub use xdg::{get_cache_dir, get_config_dir, get_data_dir};
 
@@ -18,16 +27,111 @@ use std::path::PathBuf;
 /// Default values as constants
 pub mod defaults {
     pub const THEME_NAME: &str = "default";
+    pub const THEME_MODE: crate::ThemeMode = crate::ThemeMode::Fixed;
+    pub const LIGHT_THEME: &str = "github-light";
+    pub const DARK_THEME: &str = "default";
+    pub const FOLLOW_TERMINAL_BACKGROUND: bool = false;
     pub const LANGUAGE: &str = "auto";
     pub const MIN_PANEL_WIDTH: u16 = 80;
     pub const SESSION_RETENTION_DAYS: u32 = 30;
+    pub const RESTORE_SESSION_WITH_ARGS: bool = false;
+    pub const CLIPBOARD_TARGET: crate::ClipboardTarget = crate::ClipboardTarget::Both;
+    pub const GIT_BACKEND: crate::GitBackend = crate::GitBackend::Auto;
+    pub const STATUS_BAR_LEFT: &[&str] = &[
+        "user_host",
+        "cwd",
+        "file",
+        "size",
+        "permissions",
+        "owner",
+        "selection",
+    ];
+    pub const STATUS_BAR_RIGHT: &[&str] = &[
+        "cursor",
+        "indent",
+        "encoding",
+        "line_ending",
+        "language",
+        "mode",
+        "diagnostics",
+        "disk",
+    ];
+    pub const MOUSE_CAPTURE: bool = true;
+    pub const IPC_SERVER: bool = false;
+    pub const NOTIFICATION_TIMEOUT: u64 = 5;
+    pub const MAX_NOTIFICATIONS: usize = 3;
+    pub const EXCLUDE_DIRS: &[&str] = &["node_modules", "target", "build", "dist", "__pycache__"];
     pub const TAB_SIZE: usize = 4;
+    pub const INDENT_STYLE: crate::IndentMode = crate::IndentMode::Auto;
     pub const SHOW_GIT_DIFF: bool = true;
     pub const WORD_WRAP: bool = true;
-    pub const LARGE_FILE_THRESHOLD_MB: u64 = 5;
+    pub const VISUAL_LINE_NAVIGATION: bool = true;
+    pub const WRAP_AT_PUNCTUATION: bool = true;
+    pub const LARGE_FILE_THRESHOLD_MB: u64 = 10;
+    pub const LARGE_FILE_THRESHOLD_LINES: usize = 100_000;
+    pub const MAX_HIGHLIGHT_COLUMNS: usize = 10_000;
+    pub const TRIM_TRAILING_WHITESPACE: crate::TrimTrailingWhitespace =
+        crate::TrimTrailingWhitespace::Off;
+    pub const ENSURE_FINAL_NEWLINE: bool = false;
+    pub const AUTO_CLOSE_BRACKETS: bool = true;
+    pub const JUMP_LIST_SIZE: usize = 100;
+    pub const UNDO_TREE_SIZE: usize = 1000;
+    pub const SCROLL_OFF: usize = 3;
+    pub const AUTOCOMPLETE: bool = true;
+    pub const AUTOCOMPLETE_MIN_CHARS: usize = 3;
+    pub const AUTOCOMPLETE_OPEN_BUFFERS: bool = true;
+    pub const BACKUP_ON_SAVE: bool = false;
+    pub const DIFF_LAYOUT: crate::DiffLayout = crate::DiffLayout::SideBySide;
+    pub const REUSE_OPEN_EDITORS: bool = true;
+    pub const SPELL_CHECK: bool = false;
+    pub const SPELL_DICTIONARIES: &[&str] = &[
+        "/usr/share/dict/words",
+        "/usr/share/hunspell/en_US.dic",
+        "/usr/share/myspell/en_US.dic",
+    ];
+    pub const DROPPED_FILES: crate::DroppedFiles = crate::DroppedFiles::Ask;
+    pub const RECOVERY: bool = true;
+    pub const RECOVERY_IDLE_SECS: u64 = 10;
+    pub const RECOVERY_MAX_SIZE_MB: u64 = 20;
+    pub const RENDER_WHITESPACE: crate::RenderWhitespace = crate::RenderWhitespace::Off;
+    pub const WHITESPACE_SPACE_GLYPH: &str = "·";
+    pub const WHITESPACE_TAB_GLYPH: &str = "→";
+    pub const WHITESPACE_UNICODE_GLYPH: &str = "⍽";
     pub const EXTENDED_VIEW_WIDTH: usize = 50;
+    pub const SHOW_SYMLINK_TARGETS: bool = false;
+    pub const USE_TRASH: bool = true;
+    pub const COPY_DEREFERENCE_SYMLINKS: bool = false;
+    pub const OPEN_LOGS_IN_VIEWER: bool = false;
+    pub const FILE_SORT: crate::FileSort = crate::FileSort::Name;
+    pub const SHOW_HIDDEN: bool = true;
+    pub const CONFIRM_DROPPED_FILES: bool = true;
+    pub const COMPARE_MAX_DEPTH: usize = 32;
+    pub const COMPARE_MAX_ENTRIES: usize = 200_000;
+    pub const DUPLICATES_MAX_FILES: usize = 50_000;
+    pub const DUPLICATES_SKIP_IGNORED: bool = true;
+    pub const ON_EXIT: crate::TerminalExit = crate::TerminalExit::Close;
+    pub const CLOSE_ON_EXIT: bool = false;
+    pub const PROGRAM_TITLE: bool = true;
     pub const MIN_LOG_LEVEL: &str = "info";
     pub const RESOURCE_MONITOR_INTERVAL: u64 = 1000;
+    pub const SYSTEM_MONITOR_INTERVAL: u64 = 2000;
+    pub const SYSTEM_MONITOR_DELTA: f32 = 1.0;
+    pub const LSP_ENABLED: bool = true;
+    pub const LSP_SERVERS: &[(&str, &str)] = &[
+        ("python", "pyright-langserver --stdio"),
+        ("rust", "rust-analyzer"),
+    ];
+    pub const FORMAT_COMMANDS: &[(&str, &str)] = &[
+        ("css", "prettier --stdin-filepath {file}"),
+        ("go", "gofmt"),
+        ("javascript", "prettier --stdin-filepath {file}"),
+        ("json", "prettier --stdin-filepath {file}"),
+        ("jsx", "prettier --stdin-filepath {file}"),
+        ("python", "black --quiet -"),
+        ("rust", "rustfmt --edition 2021"),
+        ("tsx", "prettier --stdin-filepath {file}"),
+        ("typescript", "prettier --stdin-filepath {file}"),
+    ];
 }
 
 impl Config {
@@ -55,19 +159,18 @@ impl Config {
                 }
             };
 
-            // Serialize back to get normalized content
-            let normalized_content = toml::to_string_pretty(&config)?;
-
-            // If content changed, save the updated config
-            if original_content != normalized_content {
+            // Save the config again if settings are missing (comments and
+            // formatting of a complete config are kept)
+            let original: toml::Value = toml::from_str(&original_content)?;
+            if original != toml::Value::try_from(&config)? {
                 config.save()?;
             }
 
             Ok(config)
         } else {
             // First run - create config file with default values
+            Self::write_default_with_comments()?;
             let config = Self::default();
-            config.save()?;
 
             // Create themes directory
             Self::ensure_themes_dir()?;
@@ -76,7 +179,7 @@ impl Config {
         }
     }
 
-    /// Save configuration to file.
+    /// Save configuration to file, with the documentation of every setting.
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_file_path()?;
 
@@ -84,7 +187,7 @@ impl Config {
             std::fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
+        let content = self.to_commented_toml()?;
         std::fs::write(config_path, content)?;
         Ok(())
     }
@@ -99,14 +202,75 @@ impl Config {
         Ok(get_config_dir()?.join("themes"))
     }
 
+    /// Get path to the user spelling dictionary.
+    pub fn dictionary_file_path() -> Result<PathBuf> {
+        Ok(get_config_dir()?.join("dictionary.txt"))
+    }
+
     /// Check if path is the config file.
     pub fn is_config_file(path: &std::path::Path) -> bool {
         Self::config_file_path().map(|p| p == path).unwrap_or(false)
     }
 
+    /// Read the config file again without rewriting it (after it was
+    /// edited while termide runs).
+    pub fn reload() -> Result<Self> {
+        let content = std::fs::read_to_string(Self::config_file_path()?)?;
+        Self::validate_content(&content)
+    }
+
     /// Validate config content.
+    ///
+    /// Errors are a single line starting with the line and column of the
+    /// problem.
     pub fn validate_content(content: &str) -> Result<Config> {
-        toml::from_str(content).map_err(|e| anyhow::anyhow!("{}", e))
+        toml::from_str(content).map_err(|e| {
+            let message = e.message().trim_end();
+            match e.span() {
+                Some(span) => {
+                    let before = &content[..span.start.min(content.len())];
+                    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+                    anyhow::anyhow!(
+                        "line {}, column {}: {}",
+                        before.matches('\n').count() + 1,
+                        before[line_start..].chars().count() + 1,
+                        message
+                    )
+                }
+                None => anyhow::anyhow!("{}", message),
+            }
+        })
+    }
+
+    /// Settings that differ between two configs, as `section.key`
+    /// (`editor.tab_size`, `keybindings.close_panel`).
+    pub fn changed_settings(&self, other: &Config) -> Vec<String> {
+        let (Ok(toml::Value::Table(old)), Ok(toml::Value::Table(new))) =
+            (toml::Value::try_from(self), toml::Value::try_from(other))
+        else {
+            return Vec::new();
+        };
+        let empty = toml::Table::new();
+        let mut changed = Vec::new();
+        for section in old
+            .keys()
+            .chain(new.keys().filter(|key| !old.contains_key(*key)))
+        {
+            let old_section = old.get(section).and_then(toml::Value::as_table);
+            let new_section = new.get(section).and_then(toml::Value::as_table);
+            let (old_section, new_section) =
+                (old_section.unwrap_or(&empty), new_section.unwrap_or(&empty));
+            for key in old_section.keys().chain(
+                new_section
+                    .keys()
+                    .filter(|key| !old_section.contains_key(*key)),
+            ) {
+                if old_section.get(key) != new_section.get(key) {
+                    changed.push(format!("{}.{}", section, key));
+                }
+            }
+        }
+        changed
     }
 
     /// Ensure themes directory exists.
@@ -118,3 +282,38 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_content_reports_position() {
+        let error = Config::validate_content("[editor]\ntab_size = \"four\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("line 2, column 12: "), "{}", error);
+        assert!(!error.contains('\n'), "{}", error);
+    }
+
+    #[test]
+    fn test_changed_settings() {
+        let config = Config::default();
+        assert!(config.changed_settings(&config.clone()).is_empty());
+
+        let mut changed = config.clone();
+        changed.editor.tab_size = 2;
+        changed.general.language = "de".to_string();
+        changed
+            .keybindings
+            .insert("close_panel".to_string(), "ctrl+w".to_string());
+        assert_eq!(
+            config.changed_settings(&changed),
+            [
+                "editor.tab_size",
+                "general.language",
+                "keybindings.close_panel"
+            ]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Per-project settings.
+//!
+//! A `.termide.toml` overrides a few settings for the files below its
+//! directory. Looking up a file walks up from its directory to the root of
+//! its git work tree (the directory holding `.git`) and layers every project
+//! file found on the way over the global config, the innermost last, so the
+//! precedence is inner project > outer project > global > default. Only the
+//! settings of `ProjectConfig` may be set; other keys are an error.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::{Config, IndentMode};
+
+/// Name of the per-project settings file.
+pub const PROJECT_FILE_NAME: &str = ".termide.toml";
+
+/// Settings a project file may override; unset ones keep the global value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub general: ProjectGeneralSettings,
+    #[serde(default)]
+    pub editor: ProjectEditorSettings,
+    #[serde(default)]
+    pub format: ProjectFormatSettings,
+}
+
+/// `[general]` of a project file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectGeneralSettings {
+    /// Directories skipped when indexing, added to the global ones
+    #[serde(default)]
+    pub exclude_dirs: Vec<String>,
+}
+
+/// `[editor]` of a project file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectEditorSettings {
+    pub tab_size: Option<usize>,
+    pub indent_style: Option<IndentMode>,
+    pub word_wrap: Option<bool>,
+}
+
+/// `[format]` of a project file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectFormatSettings {
+    /// Formatter commands by language, replacing the global one of each
+    #[serde(default)]
+    pub commands: BTreeMap<String, String>,
+    pub on_save: Option<Vec<String>>,
+}
+
+impl ProjectConfig {
+    /// Read a project file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Override the settings this file sets in `config`.
+    pub fn apply_to(&self, config: &mut Config) {
+        for dir in &self.general.exclude_dirs {
+            if !config.general.exclude_dirs.contains(dir) {
+                config.general.exclude_dirs.push(dir.clone());
+            }
+        }
+        let editor = &self.editor;
+        if let Some(tab_size) = editor.tab_size {
+            config.editor.tab_size = tab_size;
+        }
+        if let Some(indent_style) = editor.indent_style {
+            config.editor.indent_style = indent_style;
+        }
+        if let Some(word_wrap) = editor.word_wrap {
+            config.editor.word_wrap = word_wrap;
+        }
+        for (language, command) in &self.format.commands {
+            config
+                .format
+                .commands
+                .insert(language.clone(), command.clone());
+        }
+        if let Some(on_save) = &self.format.on_save {
+            config.format.on_save = on_save.clone();
+        }
+    }
+}
+
+/// Whether `path` is a project settings file.
+pub fn is_project_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == PROJECT_FILE_NAME)
+}
+
+/// Directories holding a project file from `dir` up to the root of its work
+/// tree (or the file system root outside one), outermost first.
+fn project_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for ancestor in dir.ancestors() {
+        if ancestor.join(PROJECT_FILE_NAME).is_file() {
+            dirs.push(ancestor.to_path_buf());
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+    dirs.reverse();
+    dirs
+}
+
+/// Project files read once, shared by every panel of a project.
+#[derive(Debug, Default)]
+pub struct ProjectConfigs {
+    /// Parsed project files by the directory holding them (`None` when
+    /// the file could not be read)
+    files: HashMap<PathBuf, Option<ProjectConfig>>,
+    /// Project directories above each directory looked up, outermost first
+    dirs: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl ProjectConfigs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `global` with the project files above `path` (a file or directory)
+    /// layered over it.
+    pub fn config_for(&mut self, global: &Config, path: &Path) -> Config {
+        let mut config = global.clone();
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        let project_dirs = self
+            .dirs
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| project_dirs(dir))
+            .clone();
+        for project_dir in project_dirs {
+            let project = self.files.entry(project_dir).or_insert_with_key(|dir| {
+                ProjectConfig::load(&dir.join(PROJECT_FILE_NAME))
+                    .inspect_err(|e| log::warn!("{:#}", e))
+                    .ok()
+            });
+            if let Some(project) = project {
+                project.apply_to(&mut config);
+            }
+        }
+        config
+    }
+
+    /// Forget `path` if it is a project file that changed, appeared or
+    /// went away; true when it was one.
+    pub fn invalidate(&mut self, path: &Path) -> bool {
+        if !is_project_file(path) {
+            return false;
+        }
+        if let Some(dir) = path.parent() {
+            self.files.remove(dir);
+        }
+        // Directories below it may have gained or lost a project
+        self.dirs.clear();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Work tree with a project file at its root and one in `inner`
+    fn nested_projects() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("inner/src")).unwrap();
+        fs::write(
+            root.join(PROJECT_FILE_NAME),
+            "[editor]\ntab_size = 2\nword_wrap = false\n\n\
+             [format]\ncommands = { rust = \"rustfmt --edition 2024\" }\n\n\
+             [general]\nexclude_dirs = [\"vendor\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("inner").join(PROJECT_FILE_NAME),
+            "[editor]\ntab_size = 8\nindent_style = \"tabs\"\n\n\
+             [format]\non_save = [\"rust\"]\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_inner_project_wins() {
+        let dir = nested_projects();
+        let global = Config::default();
+        let mut projects = ProjectConfigs::new();
+
+        let outer = projects.config_for(&global, &dir.path().join("main.rs"));
+        assert_eq!(outer.editor.tab_size, 2);
+        assert!(!outer.editor.word_wrap);
+        assert_eq!(outer.editor.indent_style, IndentMode::Auto);
+        assert!(outer.format.on_save.is_empty());
+        assert!(outer.general.exclude_dirs.contains(&"vendor".to_string()));
+        assert!(outer.general.exclude_dirs.contains(&"target".to_string()));
+
+        let inner = projects.config_for(&global, &dir.path().join("inner/src/lib.rs"));
+        assert_eq!(inner.editor.tab_size, 8);
+        assert_eq!(inner.editor.indent_style, IndentMode::Tabs);
+        // Set only by the outer project
+        assert!(!inner.editor.word_wrap);
+        assert_eq!(inner.format.commands["rust"], "rustfmt --edition 2024");
+        assert_eq!(inner.format.on_save, ["rust"]);
+        // Global values stay where no project sets them
+        assert_eq!(inner.format.commands["go"], "gofmt");
+        assert_eq!(inner.editor.scroll_off, global.editor.scroll_off);
+    }
+
+    #[test]
+    fn test_lookup_stops_at_work_tree_root() {
+        let dir = nested_projects();
+        let repo = dir.path().join("inner/sub");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+
+        let config = ProjectConfigs::new().config_for(&Config::default(), &repo.join("a.rs"));
+        assert_eq!(config.editor.tab_size, crate::defaults::TAB_SIZE);
+    }
+
+    #[test]
+    fn test_invalidate_rereads_changed_file() {
+        let dir = nested_projects();
+        let file = dir.path().join("inner/src/lib.rs");
+        let global = Config::default();
+        let mut projects = ProjectConfigs::new();
+        assert_eq!(projects.config_for(&global, &file).editor.tab_size, 8);
+
+        let inner = dir.path().join("inner").join(PROJECT_FILE_NAME);
+        fs::remove_file(&inner).unwrap();
+        // Cached until the change is reported
+        assert_eq!(projects.config_for(&global, &file).editor.tab_size, 8);
+        assert!(!projects.invalidate(&file));
+        assert!(projects.invalidate(&inner));
+        assert_eq!(projects.config_for(&global, &file).editor.tab_size, 2);
+
+        fs::write(&inner, "[editor]\ntab_size = 3\n").unwrap();
+        projects.invalidate(&inner);
+        assert_eq!(projects.config_for(&global, &file).editor.tab_size, 3);
+    }
+
+    #[test]
+    fn test_unknown_settings_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROJECT_FILE_NAME);
+        fs::write(&path, "[editor]\nscroll_off = 10\n").unwrap();
+        let error = format!("{:#}", ProjectConfig::load(&path).unwrap_err());
+        assert!(error.contains("scroll_off"), "{}", error);
+
+        // An invalid file leaves the global settings
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let config = ProjectConfigs::new().config_for(&Config::default(), &dir.path().join("a"));
+        assert_eq!(config.editor.scroll_off, crate::defaults::SCROLL_OFF);
+    }
+}
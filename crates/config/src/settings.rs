@@ -1,5 +1,7 @@
 //! Configuration structures for termide settings.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::defaults;
@@ -19,9 +21,36 @@ pub struct Config {
     #[serde(default)]
     pub file_manager: FileManagerSettings,
 
+    /// Terminal settings
+    #[serde(default)]
+    pub terminal: TerminalSettings,
+
     /// Logging settings
     #[serde(default)]
     pub logging: LoggingSettings,
+
+    /// Language server settings
+    #[serde(default)]
+    pub lsp: LspSettings,
+
+    /// External formatter settings
+    #[serde(default)]
+    pub format: FormatSettings,
+
+    /// Syntax highlight styles overriding the built-in colors:
+    /// highlight name (`keyword`, `string`, ...) -> `"#rrggbb bold italic"`
+    #[serde(default)]
+    pub highlight: BTreeMap<String, String>,
+
+    /// Extra global hotkeys: action name (`close_panel`, `go_to_panel_2`)
+    /// -> key or chord (`"ctrl+k ctrl+w"`)
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, String>,
+
+    /// Tasks run from the project root: name (`build`, `test`)
+    /// -> shell command (`"cargo build"`)
+    #[serde(default)]
+    pub tasks: BTreeMap<String, String>,
 }
 
 /// General application settings.
@@ -31,6 +60,24 @@ pub struct GeneralSettings {
     #[serde(default = "default_theme_name")]
     pub theme: String,
 
+    /// Light or dark theme choice ("fixed" uses `theme` as is, "auto"
+    /// follows the terminal background, "light" or "dark" force one)
+    #[serde(default = "default_theme_mode")]
+    pub theme_mode: ThemeMode,
+
+    /// Theme used when a light theme is wanted and `theme` is dark
+    #[serde(default = "default_light_theme")]
+    pub light_theme: String,
+
+    /// Theme used when a dark theme is wanted and `theme` is light
+    #[serde(default = "default_dark_theme")]
+    pub dark_theme: String,
+
+    /// Check the terminal background again when the terminal gets focus
+    /// ("auto" mode)
+    #[serde(default = "default_follow_terminal_background")]
+    pub follow_terminal_background: bool,
+
     /// Interface language (en, de, es, fr, hi, pt, ru, th, zh, or auto)
     #[serde(default = "default_language")]
     pub language: String,
@@ -42,6 +89,50 @@ pub struct GeneralSettings {
     /// Session retention period in days
     #[serde(default = "default_session_retention_days")]
     pub session_retention_days: u32,
+
+    /// Restore the saved session also when files or directories are given
+    /// on the command line (their panels are added to it)
+    #[serde(default = "default_restore_session_with_args")]
+    pub restore_session_with_args: bool,
+
+    /// Selections copied text goes to ("clipboard", "primary" or "both")
+    #[serde(default = "default_clipboard_target")]
+    pub clipboard_target: ClipboardTarget,
+
+    /// How git status is read ("auto", "cli" or "libgit2")
+    #[serde(default = "default_git_backend")]
+    pub git_backend: GitBackend,
+
+    /// Status bar segments shown on the left ("file", "cursor", "disk", ...)
+    #[serde(default = "default_status_bar_left")]
+    pub status_bar_left: Vec<String>,
+
+    /// Status bar segments shown on the right
+    #[serde(default = "default_status_bar_right")]
+    pub status_bar_right: Vec<String>,
+
+    /// Capture the mouse (clicks, scrolling, dragging panel borders); when
+    /// off the terminal's own text selection works
+    #[serde(default = "default_mouse_capture")]
+    pub mouse_capture: bool,
+
+    /// Accept commands from other programs over a Unix socket in the
+    /// runtime directory (`termide --send`)
+    #[serde(default = "default_ipc_server")]
+    pub ipc_server: bool,
+
+    /// Seconds before notifications other than errors go away (errors stay
+    /// until dismissed)
+    #[serde(default = "default_notification_timeout")]
+    pub notification_timeout: u64,
+
+    /// Notifications shown at once in the top right corner
+    #[serde(default = "default_max_notifications")]
+    pub max_notifications: usize,
+
+    /// Directories skipped when indexing project files (the file finder)
+    #[serde(default = "default_exclude_dirs")]
+    pub exclude_dirs: Vec<String>,
 }
 
 /// Editor settings.
@@ -51,6 +142,10 @@ pub struct EditorSettings {
     #[serde(default = "default_tab_size")]
     pub tab_size: usize,
 
+    /// Indentation inserted by the editor ("auto" follows the file)
+    #[serde(default = "default_indent_style")]
+    pub indent_style: IndentMode,
+
     /// Show git diff status colors on line numbers
     #[serde(default = "default_show_git_diff")]
     pub show_git_diff: bool,
@@ -59,9 +154,227 @@ pub struct EditorSettings {
     #[serde(default = "default_word_wrap")]
     pub word_wrap: bool,
 
-    /// File size threshold in MB for disabling smart features
+    /// Break wrapped lines after punctuation (`/`, `-`, ...) when a row has
+    /// no whitespace to break at
+    #[serde(default = "default_wrap_at_punctuation")]
+    pub wrap_at_punctuation: bool,
+
+    /// Up/Down, Home/End and PageUp/PageDown move by wrapped rows when word
+    /// wrap is on (false: by whole lines)
+    #[serde(default = "default_visual_line_navigation")]
+    pub visual_line_navigation: bool,
+
+    /// File size threshold in MB for large file mode
     #[serde(default = "default_large_file_threshold_mb")]
     pub large_file_threshold_mb: u64,
+
+    /// Line count threshold for large file mode
+    #[serde(default = "default_large_file_threshold_lines")]
+    pub large_file_threshold_lines: usize,
+
+    /// Columns of a line highlighted before the rest is shown plain
+    /// (0 = no limit)
+    #[serde(default = "default_max_highlight_columns")]
+    pub max_highlight_columns: usize,
+
+    /// Strip trailing whitespace on save ("off", "modified" lines only, or "all" lines)
+    #[serde(default = "default_trim_trailing_whitespace")]
+    pub trim_trailing_whitespace: TrimTrailingWhitespace,
+
+    /// End files with exactly one newline on save
+    #[serde(default = "default_ensure_final_newline")]
+    pub ensure_final_newline: bool,
+
+    /// Insert closing brackets and quotes automatically
+    #[serde(default = "default_auto_close_brackets")]
+    pub auto_close_brackets: bool,
+
+    /// Maximum number of positions in the jump list (0 disables it)
+    #[serde(default = "default_jump_list_size")]
+    pub jump_list_size: usize,
+
+    /// Maximum number of edits kept in the undo tree; the oldest abandoned
+    /// branches are dropped first
+    #[serde(default = "default_undo_tree_size")]
+    pub undo_tree_size: usize,
+
+    /// Lines kept visible above and below the cursor when scrolling
+    #[serde(default = "default_scroll_off")]
+    pub scroll_off: usize,
+
+    /// Offer words of open buffers while typing
+    #[serde(default = "default_autocomplete")]
+    pub autocomplete: bool,
+
+    /// Characters typed before completion is offered
+    #[serde(default = "default_autocomplete_min_chars")]
+    pub autocomplete_min_chars: usize,
+
+    /// Also complete words of other open files
+    #[serde(default = "default_autocomplete_open_buffers")]
+    pub autocomplete_open_buffers: bool,
+
+    /// Keep the previous version as `file~` when saving
+    #[serde(default = "default_backup_on_save")]
+    pub backup_on_save: bool,
+
+    /// Layout of diff panels ("side_by_side" or "unified")
+    #[serde(default = "default_diff_layout")]
+    pub diff_layout: DiffLayout,
+
+    /// Focus the editor already showing a file instead of opening another one
+    #[serde(default = "default_reuse_open_editors")]
+    pub reuse_open_editors: bool,
+
+    /// Underline misspelled words in comments, strings and prose
+    #[serde(default = "default_spell_check")]
+    pub spell_check: bool,
+
+    /// Word lists or hunspell `.dic` files to check spelling with
+    /// (missing files are skipped)
+    #[serde(default = "default_spell_dictionaries")]
+    pub spell_dictionaries: Vec<String>,
+
+    /// What pasting paths of existing files does ("ask", "open" or "insert")
+    #[serde(default = "default_dropped_files")]
+    pub dropped_files: DroppedFiles,
+
+    /// Copy unsaved changes to the recovery directory, offered for restore
+    /// after a crash
+    #[serde(default = "default_recovery")]
+    pub recovery: bool,
+
+    /// Seconds without edits after which unsaved changes are copied
+    #[serde(default = "default_recovery_idle_secs")]
+    pub recovery_idle_secs: u64,
+
+    /// Buffers larger than this (in megabytes) are not copied
+    #[serde(default = "default_recovery_max_size_mb")]
+    pub recovery_max_size_mb: u64,
+
+    /// Whitespace drawn visibly ("off", "trailing" or "all")
+    #[serde(default = "default_render_whitespace")]
+    pub render_whitespace: RenderWhitespace,
+
+    /// Glyph drawn for spaces
+    #[serde(default = "default_whitespace_space_glyph")]
+    pub whitespace_space_glyph: String,
+
+    /// Glyph drawn for tabs
+    #[serde(default = "default_whitespace_tab_glyph")]
+    pub whitespace_tab_glyph: String,
+
+    /// Glyph drawn for non-breaking and zero-width spaces
+    #[serde(default = "default_whitespace_unicode_glyph")]
+    pub whitespace_unicode_glyph: String,
+}
+
+/// Selections receiving copied text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardTarget {
+    /// CLIPBOARD selection (Ctrl+V)
+    Clipboard,
+    /// PRIMARY selection (middle click, X11 and Wayland only)
+    Primary,
+    /// Both selections
+    Both,
+}
+
+/// Implementation reading git status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitBackend {
+    /// libgit2, falling back to the git command when it fails
+    Auto,
+    /// The git command
+    Cli,
+    /// libgit2 only, without the git command
+    Libgit2,
+}
+
+/// Lines stripped of trailing whitespace on save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrimTrailingWhitespace {
+    /// Keep trailing whitespace
+    Off,
+    /// Lines edited since the file was opened or last saved
+    Modified,
+    /// All lines
+    All,
+}
+
+/// Whitespace the editor draws with visible glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderWhitespace {
+    /// Whitespace is not shown
+    Off,
+    /// Trailing whitespace and non-breaking or zero-width spaces
+    Trailing,
+    /// All whitespace
+    All,
+}
+
+/// Indentation the editor inserts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentMode {
+    /// Detected from the file, spaces when nothing is indented yet
+    Auto,
+    /// `tab_size` spaces
+    Spaces,
+    /// Tab characters
+    Tabs,
+}
+
+/// How diff panels show the two versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLayout {
+    /// Old and new version in two columns
+    SideBySide,
+    /// Removed and added lines in one column
+    Unified,
+}
+
+/// What pasting (or dropping) paths of existing files into an editor does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DroppedFiles {
+    /// Ask whether to open the files or insert the paths
+    Ask,
+    /// Open the files
+    Open,
+    /// Insert the paths as text
+    Insert,
+}
+
+/// Whether a light or dark theme is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    /// The configured theme, whatever its background
+    Fixed,
+    /// Light or dark by the terminal background
+    Auto,
+    /// Always a light theme
+    Light,
+    /// Always a dark theme
+    Dark,
+}
+
+/// Order of file manager entries (directories always come first).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSort {
+    /// Alphabetical, ignoring case
+    Name,
+    /// Largest files first
+    Size,
+    /// Most recently modified first
+    Modified,
 }
 
 /// File manager settings.
@@ -70,6 +383,93 @@ pub struct FileManagerSettings {
     /// Minimum width to display extended columns (size, time)
     #[serde(default = "default_extended_view_width")]
     pub extended_view_width: usize,
+
+    /// Show symlink targets inline after the name ("link → target")
+    #[serde(default = "default_show_symlink_targets")]
+    pub show_symlink_targets: bool,
+
+    /// Move deleted files to trash instead of removing them permanently
+    #[serde(default = "default_use_trash")]
+    pub use_trash: bool,
+
+    /// Copy files that symlinks point to instead of the symlinks themselves
+    #[serde(default = "default_copy_dereference_symlinks")]
+    pub copy_dereference_symlinks: bool,
+
+    /// Open `.log` files in a tailing log viewer instead of the editor
+    #[serde(default = "default_open_logs_in_viewer")]
+    pub open_logs_in_viewer: bool,
+
+    /// Order of entries in directories without their own sort order
+    #[serde(default = "default_file_sort")]
+    pub sort: FileSort,
+
+    /// Show hidden (dot) files in directories without their own setting
+    #[serde(default = "default_show_hidden")]
+    pub show_hidden: bool,
+
+    /// Ask before copying files whose paths are pasted (or dropped) here
+    #[serde(default = "default_confirm_dropped_files")]
+    pub confirm_dropped_files: bool,
+
+    /// Directory levels walked when comparing directories
+    #[serde(default = "default_compare_max_depth")]
+    pub compare_max_depth: usize,
+
+    /// Entries examined when comparing directories before the comparison
+    /// stops
+    #[serde(default = "default_compare_max_entries")]
+    pub compare_max_entries: usize,
+
+    /// Files listed when looking for duplicates before the search stops
+    #[serde(default = "default_duplicates_max_files")]
+    pub duplicates_max_files: usize,
+
+    /// Leave out files ignored by git when looking for duplicates
+    #[serde(default = "default_duplicates_skip_ignored")]
+    pub duplicates_skip_ignored: bool,
+}
+
+/// What happens to a terminal panel when its shell exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TerminalExit {
+    /// Close the panel
+    Close,
+    /// Keep the output for scrolling and copying until closed
+    Keep,
+    /// Keep the output only when the exit code is not zero
+    KeepOnError,
+}
+
+/// Terminal settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalSettings {
+    /// Shell to run (detected when not set)
+    #[serde(default)]
+    pub shell: Option<String>,
+
+    /// Shell arguments (login/interactive flags for the shell when not set)
+    #[serde(default)]
+    pub shell_args: Option<Vec<String>>,
+
+    /// What happens to a terminal running the shell when it exits
+    #[serde(default = "default_on_exit")]
+    pub on_exit: TerminalExit,
+
+    /// Close terminals running a command (not the shell) when it exits;
+    /// otherwise they stay open and show the exit code
+    #[serde(default = "default_close_on_exit")]
+    pub close_on_exit: bool,
+
+    /// Show the title set by the running program (OSC 0/2) instead of
+    /// user@host:dir
+    #[serde(default = "default_program_title")]
+    pub program_title: bool,
+
+    /// Environment variables added for terminal processes
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
 }
 
 /// Logging settings.
@@ -86,6 +486,43 @@ pub struct LoggingSettings {
     /// System resource monitor update interval in ms
     #[serde(default = "default_resource_monitor_interval")]
     pub resource_monitor_interval: u64,
+
+    /// System monitor panel sampling interval in ms
+    #[serde(default = "default_system_monitor_interval")]
+    pub system_monitor_interval: u64,
+
+    /// Smallest change of CPU or memory usage (in percentage points) that
+    /// redraws the system monitor panel
+    #[serde(default = "default_system_monitor_delta")]
+    pub system_monitor_delta: f32,
+}
+
+/// Language server settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LspSettings {
+    /// Start language servers for the open files
+    #[serde(default = "default_lsp_enabled")]
+    pub enabled: bool,
+
+    /// Server command lines (speaking LSP over stdio): language name
+    /// (`rust`, `python`) -> command (`"rust-analyzer"`)
+    #[serde(default = "default_lsp_servers")]
+    pub servers: BTreeMap<String, String>,
+}
+
+/// External formatter settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatSettings {
+    /// Formatter command lines (reading the text on stdin and writing the
+    /// formatted text to stdout): language name (`rust`, `python`)
+    /// -> command (`"rustfmt --edition 2021"`); `{file}` is replaced with
+    /// the path of the file
+    #[serde(default = "default_format_commands")]
+    pub commands: BTreeMap<String, String>,
+
+    /// Languages formatted before each save (Ctrl+S)
+    #[serde(default)]
+    pub on_save: Vec<String>,
 }
 
 // Default value functions for serde
@@ -93,6 +530,22 @@ fn default_theme_name() -> String {
     defaults::THEME_NAME.to_string()
 }
 
+fn default_theme_mode() -> ThemeMode {
+    defaults::THEME_MODE
+}
+
+fn default_light_theme() -> String {
+    defaults::LIGHT_THEME.to_string()
+}
+
+fn default_dark_theme() -> String {
+    defaults::DARK_THEME.to_string()
+}
+
+fn default_follow_terminal_background() -> bool {
+    defaults::FOLLOW_TERMINAL_BACKGROUND
+}
+
 fn default_language() -> String {
     defaults::LANGUAGE.to_string()
 }
@@ -105,10 +558,63 @@ fn default_session_retention_days() -> u32 {
     defaults::SESSION_RETENTION_DAYS
 }
 
+fn default_restore_session_with_args() -> bool {
+    defaults::RESTORE_SESSION_WITH_ARGS
+}
+
+fn default_clipboard_target() -> ClipboardTarget {
+    defaults::CLIPBOARD_TARGET
+}
+
+fn default_git_backend() -> GitBackend {
+    defaults::GIT_BACKEND
+}
+
+fn default_status_bar_left() -> Vec<String> {
+    defaults::STATUS_BAR_LEFT
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn default_status_bar_right() -> Vec<String> {
+    defaults::STATUS_BAR_RIGHT
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn default_mouse_capture() -> bool {
+    defaults::MOUSE_CAPTURE
+}
+
+fn default_ipc_server() -> bool {
+    defaults::IPC_SERVER
+}
+
+fn default_notification_timeout() -> u64 {
+    defaults::NOTIFICATION_TIMEOUT
+}
+
+fn default_max_notifications() -> usize {
+    defaults::MAX_NOTIFICATIONS
+}
+
+fn default_exclude_dirs() -> Vec<String> {
+    defaults::EXCLUDE_DIRS
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
 fn default_tab_size() -> usize {
     defaults::TAB_SIZE
 }
 
+fn default_indent_style() -> IndentMode {
+    defaults::INDENT_STYLE
+}
+
 fn default_show_git_diff() -> bool {
     defaults::SHOW_GIT_DIFF
 }
@@ -117,14 +623,177 @@ fn default_word_wrap() -> bool {
     defaults::WORD_WRAP
 }
 
+fn default_visual_line_navigation() -> bool {
+    defaults::VISUAL_LINE_NAVIGATION
+}
+
+fn default_wrap_at_punctuation() -> bool {
+    defaults::WRAP_AT_PUNCTUATION
+}
+
 fn default_large_file_threshold_mb() -> u64 {
     defaults::LARGE_FILE_THRESHOLD_MB
 }
 
+fn default_large_file_threshold_lines() -> usize {
+    defaults::LARGE_FILE_THRESHOLD_LINES
+}
+
+fn default_max_highlight_columns() -> usize {
+    defaults::MAX_HIGHLIGHT_COLUMNS
+}
+
+fn default_trim_trailing_whitespace() -> TrimTrailingWhitespace {
+    defaults::TRIM_TRAILING_WHITESPACE
+}
+
+fn default_ensure_final_newline() -> bool {
+    defaults::ENSURE_FINAL_NEWLINE
+}
+
+fn default_auto_close_brackets() -> bool {
+    defaults::AUTO_CLOSE_BRACKETS
+}
+
+fn default_autocomplete() -> bool {
+    defaults::AUTOCOMPLETE
+}
+
+fn default_autocomplete_min_chars() -> usize {
+    defaults::AUTOCOMPLETE_MIN_CHARS
+}
+
+fn default_autocomplete_open_buffers() -> bool {
+    defaults::AUTOCOMPLETE_OPEN_BUFFERS
+}
+
+fn default_backup_on_save() -> bool {
+    defaults::BACKUP_ON_SAVE
+}
+
+fn default_diff_layout() -> DiffLayout {
+    defaults::DIFF_LAYOUT
+}
+
+fn default_reuse_open_editors() -> bool {
+    defaults::REUSE_OPEN_EDITORS
+}
+
+fn default_spell_check() -> bool {
+    defaults::SPELL_CHECK
+}
+
+fn default_spell_dictionaries() -> Vec<String> {
+    defaults::SPELL_DICTIONARIES
+        .iter()
+        .map(|path| path.to_string())
+        .collect()
+}
+
+fn default_dropped_files() -> DroppedFiles {
+    defaults::DROPPED_FILES
+}
+
+fn default_recovery() -> bool {
+    defaults::RECOVERY
+}
+
+fn default_recovery_idle_secs() -> u64 {
+    defaults::RECOVERY_IDLE_SECS
+}
+
+fn default_recovery_max_size_mb() -> u64 {
+    defaults::RECOVERY_MAX_SIZE_MB
+}
+
+fn default_render_whitespace() -> RenderWhitespace {
+    defaults::RENDER_WHITESPACE
+}
+
+fn default_whitespace_space_glyph() -> String {
+    defaults::WHITESPACE_SPACE_GLYPH.to_string()
+}
+
+fn default_whitespace_tab_glyph() -> String {
+    defaults::WHITESPACE_TAB_GLYPH.to_string()
+}
+
+fn default_whitespace_unicode_glyph() -> String {
+    defaults::WHITESPACE_UNICODE_GLYPH.to_string()
+}
+
+fn default_jump_list_size() -> usize {
+    defaults::JUMP_LIST_SIZE
+}
+
+fn default_undo_tree_size() -> usize {
+    defaults::UNDO_TREE_SIZE
+}
+
+fn default_scroll_off() -> usize {
+    defaults::SCROLL_OFF
+}
+
 fn default_extended_view_width() -> usize {
     defaults::EXTENDED_VIEW_WIDTH
 }
 
+fn default_show_symlink_targets() -> bool {
+    defaults::SHOW_SYMLINK_TARGETS
+}
+
+fn default_use_trash() -> bool {
+    defaults::USE_TRASH
+}
+
+fn default_copy_dereference_symlinks() -> bool {
+    defaults::COPY_DEREFERENCE_SYMLINKS
+}
+
+fn default_open_logs_in_viewer() -> bool {
+    defaults::OPEN_LOGS_IN_VIEWER
+}
+
+fn default_file_sort() -> FileSort {
+    defaults::FILE_SORT
+}
+
+fn default_show_hidden() -> bool {
+    defaults::SHOW_HIDDEN
+}
+
+fn default_confirm_dropped_files() -> bool {
+    defaults::CONFIRM_DROPPED_FILES
+}
+
+fn default_compare_max_depth() -> usize {
+    defaults::COMPARE_MAX_DEPTH
+}
+
+fn default_compare_max_entries() -> usize {
+    defaults::COMPARE_MAX_ENTRIES
+}
+
+fn default_duplicates_max_files() -> usize {
+    defaults::DUPLICATES_MAX_FILES
+}
+
+fn default_duplicates_skip_ignored() -> bool {
+    defaults::DUPLICATES_SKIP_IGNORED
+}
+
+fn default_on_exit() -> TerminalExit {
+    defaults::ON_EXIT
+}
+
+fn default_close_on_exit() -> bool {
+    defaults::CLOSE_ON_EXIT
+}
+
+fn default_program_title() -> bool {
+    defaults::PROGRAM_TITLE
+}
+
 fn default_min_level() -> String {
     defaults::MIN_LOG_LEVEL.to_string()
 }
@@ -133,6 +802,32 @@ fn default_resource_monitor_interval() -> u64 {
     defaults::RESOURCE_MONITOR_INTERVAL
 }
 
+fn default_system_monitor_interval() -> u64 {
+    defaults::SYSTEM_MONITOR_INTERVAL
+}
+
+fn default_system_monitor_delta() -> f32 {
+    defaults::SYSTEM_MONITOR_DELTA
+}
+
+fn default_lsp_enabled() -> bool {
+    defaults::LSP_ENABLED
+}
+
+fn default_lsp_servers() -> BTreeMap<String, String> {
+    defaults::LSP_SERVERS
+        .iter()
+        .map(|(language, command)| (language.to_string(), command.to_string()))
+        .collect()
+}
+
+fn default_format_commands() -> BTreeMap<String, String> {
+    defaults::FORMAT_COMMANDS
+        .iter()
+        .map(|(language, command)| (language.to_string(), command.to_string()))
+        .collect()
+}
+
 /// Legacy flat config format for migration.
 #[derive(Debug, Clone, Deserialize)]
 pub struct LegacyConfig {
@@ -167,24 +862,84 @@ impl From<LegacyConfig> for Config {
         Self {
             general: GeneralSettings {
                 theme: legacy.theme,
+                theme_mode: default_theme_mode(),
+                light_theme: default_light_theme(),
+                dark_theme: default_dark_theme(),
+                follow_terminal_background: default_follow_terminal_background(),
                 language: legacy.language,
                 min_panel_width: legacy.min_panel_width,
                 session_retention_days: legacy.session_retention_days,
+                restore_session_with_args: default_restore_session_with_args(),
+                clipboard_target: default_clipboard_target(),
+                git_backend: default_git_backend(),
+                status_bar_left: default_status_bar_left(),
+                status_bar_right: default_status_bar_right(),
+                mouse_capture: default_mouse_capture(),
+                ipc_server: default_ipc_server(),
+                notification_timeout: default_notification_timeout(),
+                max_notifications: default_max_notifications(),
+                exclude_dirs: default_exclude_dirs(),
             },
             editor: EditorSettings {
                 tab_size: legacy.tab_size,
+                indent_style: default_indent_style(),
                 show_git_diff: legacy.show_git_diff,
                 word_wrap: legacy.word_wrap,
+                wrap_at_punctuation: default_wrap_at_punctuation(),
+                visual_line_navigation: default_visual_line_navigation(),
                 large_file_threshold_mb: legacy.large_file_threshold_mb,
+                large_file_threshold_lines: default_large_file_threshold_lines(),
+                max_highlight_columns: default_max_highlight_columns(),
+                trim_trailing_whitespace: default_trim_trailing_whitespace(),
+                ensure_final_newline: default_ensure_final_newline(),
+                auto_close_brackets: default_auto_close_brackets(),
+                jump_list_size: default_jump_list_size(),
+                undo_tree_size: default_undo_tree_size(),
+                scroll_off: default_scroll_off(),
+                autocomplete: default_autocomplete(),
+                autocomplete_min_chars: default_autocomplete_min_chars(),
+                autocomplete_open_buffers: default_autocomplete_open_buffers(),
+                backup_on_save: default_backup_on_save(),
+                diff_layout: default_diff_layout(),
+                reuse_open_editors: default_reuse_open_editors(),
+                spell_check: default_spell_check(),
+                spell_dictionaries: default_spell_dictionaries(),
+                dropped_files: default_dropped_files(),
+                recovery: default_recovery(),
+                recovery_idle_secs: default_recovery_idle_secs(),
+                recovery_max_size_mb: default_recovery_max_size_mb(),
+                render_whitespace: default_render_whitespace(),
+                whitespace_space_glyph: default_whitespace_space_glyph(),
+                whitespace_tab_glyph: default_whitespace_tab_glyph(),
+                whitespace_unicode_glyph: default_whitespace_unicode_glyph(),
             },
             file_manager: FileManagerSettings {
                 extended_view_width: legacy.fm_extended_view_width,
+                show_symlink_targets: default_show_symlink_targets(),
+                use_trash: default_use_trash(),
+                copy_dereference_symlinks: default_copy_dereference_symlinks(),
+                open_logs_in_viewer: default_open_logs_in_viewer(),
+                sort: default_file_sort(),
+                show_hidden: default_show_hidden(),
+                confirm_dropped_files: default_confirm_dropped_files(),
+                compare_max_depth: default_compare_max_depth(),
+                compare_max_entries: default_compare_max_entries(),
+                duplicates_max_files: default_duplicates_max_files(),
+                duplicates_skip_ignored: default_duplicates_skip_ignored(),
             },
+            terminal: TerminalSettings::default(),
             logging: LoggingSettings {
                 file_path: legacy.log_file_path,
                 min_level: legacy.min_log_level,
                 resource_monitor_interval: legacy.resource_monitor_interval,
+                system_monitor_interval: default_system_monitor_interval(),
+                system_monitor_delta: default_system_monitor_delta(),
             },
+            lsp: LspSettings::default(),
+            format: FormatSettings::default(),
+            highlight: BTreeMap::new(),
+            keybindings: BTreeMap::new(),
+            tasks: BTreeMap::new(),
         }
     }
 }
@@ -194,9 +949,23 @@ impl Default for GeneralSettings {
     fn default() -> Self {
         Self {
             theme: default_theme_name(),
+            theme_mode: default_theme_mode(),
+            light_theme: default_light_theme(),
+            dark_theme: default_dark_theme(),
+            follow_terminal_background: default_follow_terminal_background(),
             language: default_language(),
             min_panel_width: default_min_panel_width(),
             session_retention_days: default_session_retention_days(),
+            restore_session_with_args: default_restore_session_with_args(),
+            clipboard_target: default_clipboard_target(),
+            git_backend: default_git_backend(),
+            status_bar_left: default_status_bar_left(),
+            status_bar_right: default_status_bar_right(),
+            mouse_capture: default_mouse_capture(),
+            ipc_server: default_ipc_server(),
+            notification_timeout: default_notification_timeout(),
+            max_notifications: default_max_notifications(),
+            exclude_dirs: default_exclude_dirs(),
         }
     }
 }
@@ -205,9 +974,36 @@ impl Default for EditorSettings {
     fn default() -> Self {
         Self {
             tab_size: default_tab_size(),
+            indent_style: default_indent_style(),
             show_git_diff: default_show_git_diff(),
             word_wrap: default_word_wrap(),
+            wrap_at_punctuation: default_wrap_at_punctuation(),
+            visual_line_navigation: default_visual_line_navigation(),
             large_file_threshold_mb: default_large_file_threshold_mb(),
+            large_file_threshold_lines: default_large_file_threshold_lines(),
+            max_highlight_columns: default_max_highlight_columns(),
+            trim_trailing_whitespace: default_trim_trailing_whitespace(),
+            ensure_final_newline: default_ensure_final_newline(),
+            auto_close_brackets: default_auto_close_brackets(),
+            jump_list_size: default_jump_list_size(),
+            undo_tree_size: default_undo_tree_size(),
+            scroll_off: default_scroll_off(),
+            autocomplete: default_autocomplete(),
+            autocomplete_min_chars: default_autocomplete_min_chars(),
+            autocomplete_open_buffers: default_autocomplete_open_buffers(),
+            backup_on_save: default_backup_on_save(),
+            diff_layout: default_diff_layout(),
+            reuse_open_editors: default_reuse_open_editors(),
+            spell_check: default_spell_check(),
+            spell_dictionaries: default_spell_dictionaries(),
+            dropped_files: default_dropped_files(),
+            recovery: default_recovery(),
+            recovery_idle_secs: default_recovery_idle_secs(),
+            recovery_max_size_mb: default_recovery_max_size_mb(),
+            render_whitespace: default_render_whitespace(),
+            whitespace_space_glyph: default_whitespace_space_glyph(),
+            whitespace_tab_glyph: default_whitespace_tab_glyph(),
+            whitespace_unicode_glyph: default_whitespace_unicode_glyph(),
         }
     }
 }
@@ -216,6 +1012,30 @@ impl Default for FileManagerSettings {
     fn default() -> Self {
         Self {
             extended_view_width: default_extended_view_width(),
+            show_symlink_targets: default_show_symlink_targets(),
+            use_trash: default_use_trash(),
+            copy_dereference_symlinks: default_copy_dereference_symlinks(),
+            open_logs_in_viewer: default_open_logs_in_viewer(),
+            sort: default_file_sort(),
+            show_hidden: default_show_hidden(),
+            confirm_dropped_files: default_confirm_dropped_files(),
+            compare_max_depth: default_compare_max_depth(),
+            compare_max_entries: default_compare_max_entries(),
+            duplicates_max_files: default_duplicates_max_files(),
+            duplicates_skip_ignored: default_duplicates_skip_ignored(),
+        }
+    }
+}
+
+impl Default for TerminalSettings {
+    fn default() -> Self {
+        Self {
+            shell: None,
+            shell_args: None,
+            on_exit: default_on_exit(),
+            close_on_exit: default_close_on_exit(),
+            program_title: default_program_title(),
+            env: BTreeMap::new(),
         }
     }
 }
@@ -226,6 +1046,26 @@ impl Default for LoggingSettings {
             file_path: None,
             min_level: default_min_level(),
             resource_monitor_interval: default_resource_monitor_interval(),
+            system_monitor_interval: default_system_monitor_interval(),
+            system_monitor_delta: default_system_monitor_delta(),
+        }
+    }
+}
+
+impl Default for LspSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_lsp_enabled(),
+            servers: default_lsp_servers(),
+        }
+    }
+}
+
+impl Default for FormatSettings {
+    fn default() -> Self {
+        Self {
+            commands: default_format_commands(),
+            on_save: Vec::new(),
         }
     }
 }
@@ -5,6 +5,8 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::PanelEvent;
+
 /// Commands that can be sent to panels during tick/watcher processing.
 #[derive(Debug, Clone)]
 pub enum PanelCommand<'a> {
@@ -21,9 +23,10 @@ pub enum PanelCommand<'a> {
         repo_paths: &'a [&'a Path],
     },
 
-    /// Check and apply pending git diff updates (debounced buffer changes).
-    /// Response: `CommandResult::None`
-    CheckPendingGitDiff,
+    /// Run work deferred until edits pause (highlighting of the whole
+    /// document, git diff of the buffer).
+    /// Response: `CommandResult::NeedsRedraw(bool)`
+    RunDeferredWork,
 
     /// Check for async git diff results from background thread.
     /// Response: `CommandResult::NeedsRedraw(bool)`
@@ -86,6 +89,21 @@ pub enum PanelCommand<'a> {
     /// Refresh file manager directory listing.
     /// Response: `CommandResult::NeedsRedraw(bool)`
     RefreshDirectory,
+
+    /// Set the files open in editor panels (to mark them in the listing).
+    /// Response: `CommandResult::NeedsRedraw(bool)`
+    SetOpenFiles {
+        /// Canonical paths of open files with their unsaved changes flag
+        files: &'a [(PathBuf, bool)],
+    },
+
+    // === Menu commands ===
+    /// Run an item of the panel's `menu_items()`.
+    /// Response: `CommandResult::Events(Vec<PanelEvent>)`
+    MenuAction {
+        /// Id of the chosen item
+        id: &'a str,
+    },
 }
 
 /// Result of handling a panel command.
@@ -129,6 +147,9 @@ pub enum CommandResult {
         /// Error message if save failed
         error: Option<String>,
     },
+
+    /// Events raised by the command, handled like those of a key press.
+    Events(Vec<PanelEvent>),
 }
 
 impl CommandResult {
@@ -1,15 +1,17 @@
 //! Event types for termide application.
 //!
 //! This module provides:
-//! - `Event` - Application-level events (keyboard, mouse, resize)
-//! - `EventHandler` - Polling for terminal events
+//! - `Event` - Application-level events (keyboard, mouse, paste, resize)
+//! - `EventHandler` - Waiting for terminal events and wakeups
 //! - `PanelEvent` - Events emitted by panels to communicate with the application
 
 use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent};
+use crossterm::event::{
+    self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent,
+};
 
 /// Application event
 #[derive(Debug, Clone)]
@@ -18,10 +20,15 @@ pub enum Event {
     Key(KeyEvent),
     /// Mouse event
     Mouse(MouseEvent),
+    /// Text pasted into the terminal (bracketed paste), with `\n` line breaks
+    Paste(String),
     /// Terminal resize event
     Resize(u16, u16),
     /// Tick event (for animations and periodic updates)
     Tick,
+    /// Another thread has something for the main loop (terminal output,
+    /// results of background work)
+    Wake,
     /// Terminal focus lost event
     FocusLost,
     /// Terminal focus gained event
@@ -31,32 +38,124 @@ pub enum Event {
 /// Event handler for polling terminal events
 pub struct EventHandler {
     tick_rate: Duration,
+    /// Wakeups from other threads and resize signals, once listened for
+    #[cfg(unix)]
+    wakeup: Option<termide_wake::Wakeup>,
+    /// Terminal opened for input when stdin is not one (as crossterm does)
+    #[cfg(unix)]
+    tty: Option<std::fs::File>,
 }
 
 impl EventHandler {
     /// Create new event handler with specified tick rate
     pub fn new(tick_rate: Duration) -> Self {
-        Self { tick_rate }
+        Self {
+            tick_rate,
+            #[cfg(unix)]
+            wakeup: None,
+            #[cfg(unix)]
+            tty: None,
+        }
+    }
+
+    /// Let [`termide_wake::wake`] and terminal resizes end the waits of
+    /// [`Self::next_or_wake`], so that it can wait without a timeout.
+    /// Without this (or where it fails) its waits are cut to the tick rate.
+    pub fn listen_for_wakeups(&mut self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            // SAFETY: isatty only inspects the descriptor
+            if unsafe { libc::isatty(libc::STDIN_FILENO) } != 1 {
+                self.tty = Some(std::fs::File::open("/dev/tty")?);
+            }
+            self.wakeup = Some(termide_wake::Wakeup::install(&[libc::SIGWINCH])?);
+        }
+        Ok(())
     }
 
     /// Wait for next event
     pub fn next(&self) -> Result<Event> {
-        if event::poll(self.tick_rate)? {
-            match event::read()? {
-                // With kitty keyboard protocol, we receive Press, Release, and Repeat events.
-                // Only handle Press events to avoid duplicate actions.
-                CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => Ok(Event::Key(key)),
-                CrosstermEvent::Key(_) => Ok(Event::Tick), // Ignore Release and Repeat
-                CrosstermEvent::Mouse(mouse) => Ok(Event::Mouse(mouse)),
-                CrosstermEvent::Resize(width, height) => Ok(Event::Resize(width, height)),
-                CrosstermEvent::FocusLost => Ok(Event::FocusLost),
-                CrosstermEvent::FocusGained => Ok(Event::FocusGained),
-                _ => Ok(Event::Tick),
-            }
+        self.next_within(self.tick_rate)
+    }
+
+    /// Wait for next event at most `timeout` (`Event::Tick` when none came)
+    pub fn next_within(&self, timeout: Duration) -> Result<Event> {
+        if event::poll(timeout)? {
+            Self::read()
         } else {
             Ok(Event::Tick)
         }
     }
+
+    /// Wait for next event or a wakeup from another thread (`Event::Wake`),
+    /// at most `timeout` when set (`Event::Tick` when nothing came)
+    pub fn next_or_wake(&self, timeout: Option<Duration>) -> Result<Event> {
+        // Events crossterm has read already do not make the input readable
+        if event::poll(Duration::ZERO)? {
+            return Self::read();
+        }
+        if !self.wait(timeout)? {
+            return Ok(Event::Tick);
+        }
+        // Input, or a resize crossterm picked up from its signal pipe
+        if event::poll(Duration::ZERO)? {
+            Self::read()
+        } else {
+            Ok(Event::Wake)
+        }
+    }
+
+    /// Wait until input or a wakeup arrives; false on timeout
+    #[cfg(unix)]
+    fn wait(&self, timeout: Option<Duration>) -> Result<bool> {
+        use std::os::fd::AsRawFd;
+
+        let Some(wakeup) = &self.wakeup else {
+            return Ok(event::poll(timeout.unwrap_or(self.tick_rate))?);
+        };
+        let input = self
+            .tty
+            .as_ref()
+            .map_or(libc::STDIN_FILENO, |tty| tty.as_raw_fd());
+        Ok(wakeup.wait(Some(input), timeout)?)
+    }
+
+    /// Wait until input arrives; false on timeout
+    #[cfg(not(unix))]
+    fn wait(&self, timeout: Option<Duration>) -> Result<bool> {
+        Ok(event::poll(timeout.unwrap_or(self.tick_rate))?)
+    }
+
+    /// Read the next terminal event
+    fn read() -> Result<Event> {
+        match event::read()? {
+            // With kitty keyboard protocol, we receive Press, Release, and Repeat events.
+            // Only handle Press events to avoid duplicate actions.
+            CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => Ok(Event::Key(key)),
+            CrosstermEvent::Key(_) => Ok(Event::Tick), // Ignore Release and Repeat
+            CrosstermEvent::Mouse(mouse) => Ok(Event::Mouse(mouse)),
+            CrosstermEvent::Resize(width, height) => Ok(Event::Resize(width, height)),
+            CrosstermEvent::Paste(text) => {
+                Ok(Event::Paste(text.replace("\r\n", "\n").replace('\r', "\n")))
+            }
+            CrosstermEvent::FocusLost => Ok(Event::FocusLost),
+            CrosstermEvent::FocusGained => Ok(Event::FocusGained),
+        }
+    }
+}
+
+/// Key presses typing pasted text, as terminals without bracketed paste
+/// send it (line breaks are Enter, other control characters are dropped).
+pub fn paste_key_events(text: &str) -> Vec<KeyEvent> {
+    text.chars()
+        .filter_map(|ch| match ch {
+            '\n' => Some(KeyCode::Enter),
+            '\t' => Some(KeyCode::Tab),
+            ch if ch.is_control() => None,
+            ch => Some(KeyCode::Char(ch)),
+        })
+        .map(|code| KeyEvent::new(code, KeyModifiers::NONE))
+        .collect()
 }
 
 /// Events emitted by panels to communicate with the application.
@@ -70,8 +169,15 @@ pub enum PanelEvent {
     Quit,
 
     // === File operations ===
-    /// Open a file in the editor
-    OpenFile(PathBuf),
+    /// Open a file in the editor, optionally at a line and column (1-based)
+    OpenFile {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+
+    /// Open a directory in a new file manager
+    OpenDirectory(PathBuf),
 
     /// Save file to disk
     SaveFile(PathBuf),
@@ -79,6 +185,30 @@ pub enum PanelEvent {
     /// Close current file/panel
     CloseFile,
 
+    /// Compare two files in a diff panel
+    CompareFiles { left: PathBuf, right: PathBuf },
+
+    /// Compare two directory trees
+    CompareDirectories { left: PathBuf, right: PathBuf },
+
+    /// Look for duplicate files below a directory
+    FindDuplicates(PathBuf),
+
+    /// Compare editor content with the version of its file in HEAD
+    DiffAgainstHead { path: PathBuf, content: String },
+
+    /// Open a markdown preview of the active editor
+    OpenMarkdownPreview,
+
+    /// Bookmark a line of a file, asking for the bookmark's name
+    SetBookmark {
+        path: PathBuf,
+        /// Line (0-based)
+        line: usize,
+        /// Text of the line
+        text: String,
+    },
+
     /// Request close panel (with confirmation if needed)
     ClosePanel,
 
@@ -89,6 +219,14 @@ pub enum PanelEvent {
     /// Go to specific line in editor
     GotoLine(usize),
 
+    /// Jump to the definition of the symbol at a position of a file
+    /// (0-based line and UTF-16 column, as language servers count them)
+    GotoDefinition {
+        path: PathBuf,
+        line: usize,
+        character: usize,
+    },
+
     // === Modal dialogs ===
     /// Show informational message
     ShowMessage(String),
@@ -139,6 +277,13 @@ pub enum PanelEvent {
     /// Clear status bar message
     ClearStatus,
 
+    /// Show a notification toast, kept in the notification history (for
+    /// results worth more than a status message)
+    Notify {
+        text: String,
+        level: termide_state::NotificationLevel,
+    },
+
     // === File watcher registration ===
     /// Register path for watching
     WatchPath(PathBuf),
@@ -174,6 +319,26 @@ pub enum PanelEvent {
     PrevPanel,
 }
 
+impl PanelEvent {
+    /// Open a file in the editor
+    pub fn open_file(path: impl Into<PathBuf>) -> Self {
+        Self::OpenFile {
+            path: path.into(),
+            line: None,
+            column: None,
+        }
+    }
+
+    /// Open a file in the editor at a line and optional column (1-based)
+    pub fn open_file_at(path: impl Into<PathBuf>, line: usize, column: Option<usize>) -> Self {
+        Self::OpenFile {
+            path: path.into(),
+            line: Some(line),
+            column,
+        }
+    }
+}
+
 /// Confirmation dialog actions.
 #[derive(Debug, Clone)]
 pub enum ConfirmAction {
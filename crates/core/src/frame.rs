@@ -0,0 +1,347 @@
+//! Frame scheduling for the main loop.
+//!
+//! Changes only mark the screen dirty; a dirty screen is drawn at most once
+//! per frame interval, so a burst of input or a terminal streaming output
+//! costs one frame per interval instead of one per wakeup. How the loop
+//! waits for input is chosen here: until the next frame when the screen is
+//! dirty, a frame interval shortly after input or output (to pick up
+//! streaming output smoothly), and otherwise until other threads wake it
+//! (see `termide_wake`) or a timer of background work is due. Background
+//! work runs on its own, slower tick.
+
+use std::time::{Duration, Instant};
+
+/// Timing of the main loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTiming {
+    /// Shortest time between two frames
+    pub frame: Duration,
+    /// Time between two runs of background work while active
+    pub tick: Duration,
+    /// Time after input or output during which the loop stays active
+    pub active: Duration,
+}
+
+/// How the main loop waits for input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wait {
+    /// At most until the next frame; output of other threads is picked up
+    /// then
+    Frame(Duration),
+    /// Until another thread wakes the loop, or at most until the next timer
+    /// of background work (None without a timer)
+    Idle(Option<Duration>),
+}
+
+/// Decides when the main loop draws and how long it waits for input
+#[derive(Debug)]
+pub struct FrameScheduler {
+    timing: FrameTiming,
+    /// The screen changed since the last frame
+    dirty: bool,
+    last_frame: Option<Instant>,
+    last_tick: Option<Instant>,
+    /// Last input, or output of a panel
+    last_activity: Option<Instant>,
+    /// Earliest timer of background work set since its last run
+    wake_at: Option<Instant>,
+}
+
+impl FrameScheduler {
+    pub fn new(timing: FrameTiming) -> Self {
+        Self {
+            timing,
+            dirty: true,
+            last_frame: None,
+            last_tick: None,
+            last_activity: None,
+            wake_at: None,
+        }
+    }
+
+    /// The screen changed and has to be drawn
+    pub fn request_frame(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Input arrived or a panel changed by itself: stay active for a while
+    pub fn note_activity(&mut self, now: Instant) {
+        self.dirty = true;
+        self.last_activity = Some(now);
+    }
+
+    /// Something animates or progresses in the background: keep waking at
+    /// the frame rate without drawing
+    pub fn keep_awake(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+    }
+
+    /// Background work has a timer due at `at` (a toast to fade, a chord
+    /// to expire): wake up for it. Timers are set anew by each run of the
+    /// background work.
+    pub fn wake_at(&mut self, at: Instant) {
+        self.wake_at = Some(self.wake_at.map_or(at, |wake_at| wake_at.min(at)));
+    }
+
+    /// Whether to draw now: the screen is dirty and the frame interval
+    /// since the last frame has passed
+    pub fn should_render(&self, now: Instant) -> bool {
+        self.dirty && self.next_frame(now) <= now
+    }
+
+    /// A frame was drawn
+    pub fn rendered(&mut self, now: Instant) {
+        self.dirty = false;
+        self.last_frame = Some(now);
+    }
+
+    /// Whether background work is due; true marks it done
+    pub fn tick_due(&mut self, now: Instant) -> bool {
+        let due = self
+            .last_tick
+            .is_none_or(|tick| now.duration_since(tick) >= self.timing.tick);
+        if due {
+            self.last_tick = Some(now);
+            self.wake_at = None;
+        }
+        due
+    }
+
+    /// Another thread woke the loop: run the background work now to pick
+    /// up what it sent
+    pub fn request_tick(&mut self) {
+        self.last_tick = None;
+    }
+
+    /// How to wait for input
+    pub fn wait(&self, now: Instant) -> Wait {
+        if self.dirty {
+            Wait::Frame(self.next_frame(now).saturating_duration_since(now))
+        } else if self.is_active(now) {
+            Wait::Frame(self.timing.frame)
+        } else {
+            // A timer runs with the background work, not before its tick
+            let tick = self.last_tick.map(|tick| tick + self.timing.tick);
+            Wait::Idle(
+                self.wake_at
+                    .map(|at| tick.map_or(at, |tick| at.max(tick)))
+                    .map(|at| at.saturating_duration_since(now)),
+            )
+        }
+    }
+
+    fn is_active(&self, now: Instant) -> bool {
+        self.last_activity
+            .is_some_and(|activity| now.duration_since(activity) < self.timing.active)
+    }
+
+    /// Earliest time of the next frame
+    fn next_frame(&self, now: Instant) -> Instant {
+        self.last_frame
+            .map_or(now, |frame| frame + self.timing.frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Panel, RenderContext};
+    use ratatui::{buffer::Buffer, layout::Rect};
+    use std::any::Any;
+
+    const TIMING: FrameTiming = FrameTiming {
+        frame: Duration::from_millis(16),
+        tick: Duration::from_millis(42),
+        active: Duration::from_millis(1000),
+    };
+
+    /// Panel printing output like a terminal running a busy command
+    struct StreamingPanel {
+        /// Output arrived since the panel was last asked
+        pending: std::cell::Cell<bool>,
+    }
+
+    impl Panel for StreamingPanel {
+        fn name(&self) -> &'static str {
+            "streaming"
+        }
+
+        fn title(&self) -> String {
+            String::new()
+        }
+
+        fn render(&mut self, _area: Rect, _buf: &mut Buffer, _ctx: &RenderContext) {}
+
+        fn handle_key(&mut self, _key: crossterm::event::KeyEvent) -> Vec<crate::PanelEvent> {
+            vec![]
+        }
+
+        fn needs_render(&self) -> bool {
+            self.pending.replace(false)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    /// Main loop on a simulated clock: `input` are times of key presses,
+    /// `output` times the panel prints (its reader wakes an idle loop).
+    /// Returns the times of the frames and of the wakeups.
+    fn simulate(
+        input: &[Duration],
+        output: &[Duration],
+        until: Duration,
+    ) -> (Vec<Duration>, Vec<Duration>) {
+        let start = Instant::now();
+        let panel = StreamingPanel {
+            pending: std::cell::Cell::new(false),
+        };
+        let mut frames = FrameScheduler::new(TIMING);
+        let (mut rendered, mut wakeups) = (Vec::new(), Vec::new());
+        let (mut input, mut output) = (input.iter().peekable(), output.iter().peekable());
+        let mut now = Duration::ZERO;
+        while now < until {
+            // Wait for the timeout or the next key press, whichever is first;
+            // when idle, output ends the wait too
+            let (timeout, woken) = match frames.wait(start + now) {
+                Wait::Frame(timeout) => (Some(now + timeout), None),
+                Wait::Idle(timeout) => (
+                    timeout.map(|timeout| now + timeout),
+                    output.peek().map(|&&at| at.max(now)),
+                ),
+            };
+            let timeout = [timeout, woken].into_iter().flatten().min();
+            let key = input
+                .peek()
+                .filter(|&&&at| timeout.is_none_or(|timeout| at <= timeout))
+                .copied();
+            let Some(next) = key.map(|&at| at.max(now)).or(timeout) else {
+                break;
+            };
+            if next >= until {
+                break;
+            }
+            now = next;
+            wakeups.push(now);
+            if key.is_some() {
+                input.next();
+                frames.note_activity(start + now);
+            }
+            while output.next_if(|&&at| at <= now).is_some() {
+                panel.pending.set(true);
+            }
+            if panel.needs_render() {
+                frames.note_activity(start + now);
+            }
+            if frames.should_render(start + now) {
+                frames.rendered(start + now);
+                rendered.push(now);
+            }
+        }
+        (rendered, wakeups)
+    }
+
+    fn millis(range: impl Iterator<Item = u64>) -> Vec<Duration> {
+        range.map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_streaming_output_is_capped_at_frame_rate() {
+        // A chunk of output every millisecond for a second
+        let (rendered, _) = simulate(&[], &millis(0..1000), Duration::from_millis(1000));
+        assert!(
+            (55..=64).contains(&rendered.len()),
+            "{} frames",
+            rendered.len()
+        );
+        assert!(rendered
+            .windows(2)
+            .all(|pair| pair[1] - pair[0] >= TIMING.frame));
+    }
+
+    #[test]
+    fn test_key_burst_coalesces_and_first_key_draws_at_once() {
+        // 200 keys in 10 ms (a paste typed key by key), then nothing
+        let keys: Vec<Duration> = (0..200).map(|i| Duration::from_micros(i * 50)).collect();
+        let (rendered, _) = simulate(&keys, &[], Duration::from_millis(100));
+        // The first key at once, the rest of the burst in the next frame
+        assert_eq!(rendered, [Duration::ZERO, Duration::from_millis(16)]);
+
+        let (rendered, _) = simulate(&millis([500].into_iter()), &[], Duration::from_millis(600));
+        assert_eq!(rendered[1], Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_idle_loop_sleeps() {
+        let (rendered, wakeups) = simulate(
+            &millis([0].into_iter()),
+            &millis(0..50),
+            Duration::from_millis(10_000),
+        );
+        // Nothing drawn once the output stopped
+        assert!(rendered.iter().all(|&at| at < Duration::from_millis(100)));
+        // After the active second the loop waits without waking up
+        assert!(wakeups.iter().all(|&at| at <= Duration::from_millis(1100)));
+    }
+
+    #[test]
+    fn test_idle_output_is_rendered_within_one_frame() {
+        // A command prints after the loop has been idle for seconds
+        let output = millis([5000, 5001, 5002, 9000].into_iter());
+        let (rendered, _) = simulate(
+            &millis([0].into_iter()),
+            &output,
+            Duration::from_millis(10_000),
+        );
+        for at in [5000, 9000].map(Duration::from_millis) {
+            assert!(
+                rendered
+                    .iter()
+                    .any(|&frame| frame >= at && frame - at <= TIMING.frame),
+                "output at {:?} drawn at {:?}",
+                at,
+                rendered
+            );
+        }
+    }
+
+    #[test]
+    fn test_idle_wait_ends_at_timer() {
+        let start = Instant::now();
+        let mut frames = FrameScheduler::new(TIMING);
+        frames.rendered(start);
+        assert_eq!(frames.wait(start), Wait::Idle(None));
+
+        assert!(frames.tick_due(start));
+        frames.wake_at(start + Duration::from_secs(3));
+        frames.wake_at(start + Duration::from_secs(2));
+        assert_eq!(
+            frames.wait(start + Duration::from_secs(1)),
+            Wait::Idle(Some(Duration::from_secs(1)))
+        );
+        // A timer due before the next tick waits for the tick
+        frames.wake_at(start);
+        assert_eq!(frames.wait(start), Wait::Idle(Some(TIMING.tick)));
+
+        // Each run of the background work sets its timers anew
+        assert!(frames.tick_due(start + Duration::from_secs(2)));
+        assert_eq!(
+            frames.wait(start + Duration::from_secs(2)),
+            Wait::Idle(None)
+        );
+    }
+
+    #[test]
+    fn test_tick_runs_at_its_own_interval() {
+        let start = Instant::now();
+        let mut frames = FrameScheduler::new(TIMING);
+        assert!(frames.tick_due(start));
+        assert!(!frames.tick_due(start + Duration::from_millis(16)));
+        assert!(frames.tick_due(start + Duration::from_millis(42)));
+    }
+}
@@ -5,15 +5,24 @@
 
 pub mod command;
 pub mod event;
+pub mod frame;
+pub mod menu;
 pub mod panel;
+pub mod snapshot;
+pub mod status;
 
 pub use command::{CommandResult, PanelCommand};
 pub use event::{
-    ConfirmAction, ConflictResolution, Event, EventHandler, InputAction, PanelEvent, SelectAction,
-    SplitDirection,
+    paste_key_events, ConfirmAction, ConflictResolution, Event, EventHandler, InputAction,
+    PanelEvent, SelectAction, SplitDirection,
 };
+pub use frame::{FrameScheduler, FrameTiming, Wait};
+pub use menu::MenuItem;
 pub use panel::{Panel, PanelConfig, RenderContext, SessionPanel, ThemeColors};
+pub use snapshot::{buffer_ansi, buffer_text};
+pub use status::{StatusKind, StatusSegment};
 
 // Re-export theme and config for convenience
 pub use termide_config::Config;
+pub use termide_state::{Notification, NotificationLevel};
 pub use termide_theme::Theme;
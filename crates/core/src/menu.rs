@@ -0,0 +1,53 @@
+//! Panel menu types.
+//!
+//! The focused panel adds its own actions to the menu bar under "Panel".
+//! Choosing one sends `PanelCommand::MenuAction` with the item's id back
+//! to the panel.
+
+/// Action offered by a panel in the "Panel" menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    /// Passed back in `PanelCommand::MenuAction`
+    pub id: String,
+    /// Translated label
+    pub label: String,
+    /// Disabled items are dimmed and cannot be chosen
+    pub enabled: bool,
+    /// State of an on/off option (`None` for plain actions)
+    pub checked: Option<bool>,
+    /// Keys doing the same, shown after the label
+    pub key_hint: Option<String>,
+}
+
+impl MenuItem {
+    /// Enabled action without a key hint
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            enabled: true,
+            checked: None,
+            key_hint: None,
+        }
+    }
+
+    /// On/off option showing a checkmark while on
+    pub fn toggle(id: impl Into<String>, label: impl Into<String>, checked: bool) -> Self {
+        Self {
+            checked: Some(checked),
+            ..Self::new(id, label)
+        }
+    }
+
+    /// Set whether the item can be chosen
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Set the keys shown after the label
+    pub fn with_key_hint(mut self, keys: impl Into<String>) -> Self {
+        self.key_hint = Some(keys.into());
+        self
+    }
+}
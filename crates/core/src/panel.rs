@@ -11,7 +11,7 @@ use ratatui::{buffer::Buffer, layout::Rect, style::Color};
 use termide_config::Config;
 use termide_theme::Theme;
 
-use crate::{CommandResult, PanelCommand, PanelEvent};
+use crate::{paste_key_events, CommandResult, MenuItem, PanelCommand, PanelEvent, StatusSegment};
 
 // Re-export SessionPanel from termide-session for unified type
 pub use termide_session::SessionPanel;
@@ -154,6 +154,16 @@ pub trait Panel: Any {
         vec![]
     }
 
+    /// Handle text pasted into the terminal (bracketed paste).
+    ///
+    /// By default the text is typed key by key.
+    fn handle_paste(&mut self, text: &str) -> Vec<PanelEvent> {
+        paste_key_events(text)
+            .into_iter()
+            .flat_map(|key| self.handle_key(key))
+            .collect()
+    }
+
     /// Periodic tick for background tasks.
     ///
     /// Called periodically to allow panels to perform background work
@@ -162,6 +172,14 @@ pub trait Panel: Any {
         vec![]
     }
 
+    /// Check if the panel changed by itself since it was last asked.
+    ///
+    /// Polled by the main loop between frames (e.g. a terminal with new
+    /// output); true schedules a redraw and keeps the loop active.
+    fn needs_render(&self) -> bool {
+        false
+    }
+
     /// Handle a command from the application.
     ///
     /// Commands allow the App to interact with panels without downcasting.
@@ -229,6 +247,18 @@ pub trait Panel: Any {
         None
     }
 
+    /// Get the file shown by the panel (for editors and file viewers).
+    fn file_path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Information about the panel for the status bar.
+    ///
+    /// Segments not chosen in the config are not shown.
+    fn status_segments(&self) -> Vec<StatusSegment> {
+        Vec::new()
+    }
+
     /// Check if there are running child processes (for terminal).
     fn has_running_processes(&self) -> bool {
         false
@@ -241,4 +271,20 @@ pub trait Panel: Any {
     fn is_welcome_panel(&self) -> bool {
         false
     }
+
+    /// Keys handled by the panel itself as `(keys, description)` pairs,
+    /// listed in the keyboard shortcuts panel.
+    ///
+    /// Panels without keys of their own list none.
+    fn key_help(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Actions of the panel for the "Panel" menu, reflecting its current
+    /// state (checked options, disabled actions).
+    ///
+    /// A chosen item comes back as `PanelCommand::MenuAction`.
+    fn menu_items(&self) -> Vec<MenuItem> {
+        Vec::new()
+    }
 }
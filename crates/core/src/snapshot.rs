@@ -0,0 +1,145 @@
+//! Text dumps of rendered buffers.
+//!
+//! Used by `termide --render-once` and by tests comparing what panels
+//! render. Each buffer row becomes a line; cells covered by the previous
+//! wide character are skipped, as a terminal shows them.
+
+use std::fmt::Write;
+
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+/// Rendered text of a buffer, without trailing spaces on each line
+pub fn buffer_text(buffer: &Buffer) -> String {
+    rows(buffer)
+        .map(|cells| {
+            let line: String = cells.iter().map(|cell| cell.symbol()).collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+/// Rendered text of a buffer with ANSI escape sequences for its colors and
+/// text styles (each line ends with a reset)
+pub fn buffer_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for cells in rows(buffer) {
+        let mut style = None;
+        for cell in cells {
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                out.push_str(&sgr(cell));
+                style = Some(cell_style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Cells shown on each row of the buffer
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<&Cell>> {
+    let width = usize::from(buffer.area.width).max(1);
+    buffer.content.chunks(width).map(|row| {
+        let mut cells = Vec::with_capacity(row.len());
+        let mut covered = 0;
+        for cell in row {
+            if covered > 0 {
+                covered -= 1;
+                continue;
+            }
+            covered = cell.symbol().width().saturating_sub(1);
+            cells.push(cell);
+        }
+        cells
+    })
+}
+
+/// Escape sequence selecting the colors and text style of a cell
+fn sgr(cell: &Cell) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if cell.modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color_code(cell.fg, false));
+    codes.extend(color_code(cell.bg, true));
+
+    let mut out = String::from("\x1b[");
+    let _ = write!(out, "{}m", codes.join(";"));
+    out
+}
+
+/// SGR parameter of a foreground or background color (None for the default)
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base.to_string(),
+        Color::Red => (base + 1).to_string(),
+        Color::Green => (base + 2).to_string(),
+        Color::Yellow => (base + 3).to_string(),
+        Color::Blue => (base + 4).to_string(),
+        Color::Magenta => (base + 5).to_string(),
+        Color::Cyan => (base + 6).to_string(),
+        Color::Gray => (base + 7).to_string(),
+        Color::DarkGray => (base + 60).to_string(),
+        Color::LightRed => (base + 61).to_string(),
+        Color::LightGreen => (base + 62).to_string(),
+        Color::LightYellow => (base + 63).to_string(),
+        Color::LightBlue => (base + 64).to_string(),
+        Color::LightMagenta => (base + 65).to_string(),
+        Color::LightCyan => (base + 66).to_string(),
+        Color::White => (base + 67).to_string(),
+        Color::Indexed(index) => format!("{};5;{}", base + 8, index),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_buffer_text() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 2));
+        buffer.set_string(0, 0, "日本 ok", Style::default());
+        buffer.set_string(2, 1, "x", Style::default());
+        assert_eq!(buffer_text(&buffer), "日本 ok\n  x\n");
+    }
+
+    #[test]
+    fn test_buffer_ansi() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 1));
+        buffer.set_string(0, 0, "ab", Style::default().fg(Color::Red));
+        buffer.set_string(
+            2,
+            0,
+            "c",
+            Style::default()
+                .bg(Color::Rgb(1, 2, 3))
+                .add_modifier(Modifier::BOLD),
+        );
+        assert_eq!(
+            buffer_ansi(&buffer),
+            "\x1b[0;31mab\x1b[0;1;48;2;1;2;3mc\x1b[0m \x1b[0m\n"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Status bar segment types.
+//!
+//! Panels describe what they show in the status bar as segments; the
+//! configuration chooses which segments are shown and on which side.
+
+/// Kind of information shown by a status bar segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    /// Terminal user and host (`user@host`)
+    UserHost,
+    /// Terminal working directory
+    Cwd,
+    /// Name of the file or directory under the cursor
+    File,
+    /// Size of the file under the cursor
+    Size,
+    /// Access permissions of the entry under the cursor
+    Permissions,
+    /// Owner and group of the entry under the cursor
+    Owner,
+    /// Number and size of selected entries
+    Selection,
+    /// Number of directories and files in the listing
+    Entries,
+    /// Cursor position in the editor
+    Cursor,
+    /// Indentation style and width
+    Indent,
+    /// Text encoding
+    Encoding,
+    /// Line ending used on save
+    LineEnding,
+    /// Syntax language
+    Language,
+    /// Editor modes (read-only, large file)
+    Mode,
+    /// Errors and warnings reported by the language server
+    Diagnostics,
+    /// Disk space of the current directory
+    Disk,
+    /// Checked out git branch (provided by the application)
+    GitBranch,
+    /// Current time (provided by the application)
+    Clock,
+}
+
+impl StatusKind {
+    /// All segment kinds
+    pub const ALL: [StatusKind; 18] = [
+        StatusKind::UserHost,
+        StatusKind::Cwd,
+        StatusKind::File,
+        StatusKind::Size,
+        StatusKind::Permissions,
+        StatusKind::Owner,
+        StatusKind::Selection,
+        StatusKind::Entries,
+        StatusKind::Cursor,
+        StatusKind::Indent,
+        StatusKind::Encoding,
+        StatusKind::LineEnding,
+        StatusKind::Language,
+        StatusKind::Mode,
+        StatusKind::Diagnostics,
+        StatusKind::Disk,
+        StatusKind::GitBranch,
+        StatusKind::Clock,
+    ];
+
+    /// Name of the segment in the config
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusKind::UserHost => "user_host",
+            StatusKind::Cwd => "cwd",
+            StatusKind::File => "file",
+            StatusKind::Size => "size",
+            StatusKind::Permissions => "permissions",
+            StatusKind::Owner => "owner",
+            StatusKind::Selection => "selection",
+            StatusKind::Entries => "entries",
+            StatusKind::Cursor => "cursor",
+            StatusKind::Indent => "indent",
+            StatusKind::Encoding => "encoding",
+            StatusKind::LineEnding => "line_ending",
+            StatusKind::Language => "language",
+            StatusKind::Mode => "mode",
+            StatusKind::Diagnostics => "diagnostics",
+            StatusKind::Disk => "disk",
+            StatusKind::GitBranch => "git_branch",
+            StatusKind::Clock => "clock",
+        }
+    }
+
+    /// Segment kind with the given config name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// One piece of information shown in the status bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusSegment {
+    /// What the segment shows
+    pub kind: StatusKind,
+    /// Dimmed label before the text ("File:", "Pos:")
+    pub label: Option<String>,
+    /// Highlighted text
+    pub text: String,
+    /// Usage in percent coloring the text like resource meters (disk)
+    pub usage: Option<u8>,
+}
+
+impl StatusSegment {
+    /// Segment showing `text` without a label
+    pub fn new(kind: StatusKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            label: None,
+            text: text.into(),
+            usage: None,
+        }
+    }
+
+    /// Segment showing `text` after a dimmed `label`
+    pub fn labeled(kind: StatusKind, label: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            label: Some(label.into()),
+            ..Self::new(kind, text)
+        }
+    }
+
+    /// Set the usage coloring the text
+    pub fn with_usage(mut self, usage: u8) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_kind_names_round_trip() {
+        for kind in StatusKind::ALL {
+            assert_eq!(StatusKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(StatusKind::from_name("battery"), None);
+    }
+}
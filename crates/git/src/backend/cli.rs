@@ -0,0 +1,94 @@
+//! Status read by running the git command.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use anyhow::{Context, Result};
+
+use super::Backend;
+
+/// The git command.
+pub(super) struct Cli;
+
+/// Run git in `dir`; an error only when it could not be started
+fn git(dir: &Path, args: &[&str], path: Option<&Path>) -> Result<Output> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(dir);
+    if let Some(path) = path {
+        command.arg("--").arg(path);
+    }
+    command.output().context("Failed to run git")
+}
+
+/// Standard output of a successful run
+fn stdout(output: Output) -> Option<String> {
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+impl Backend for Cli {
+    fn name(&self) -> &'static str {
+        "cli"
+    }
+
+    fn repo_root(&self, dir: &Path) -> Result<Option<PathBuf>> {
+        if !git(dir, &["rev-parse", "--is-inside-work-tree"], None)?
+            .status
+            .success()
+        {
+            return Ok(None);
+        }
+        let root = stdout(git(dir, &["rev-parse", "--show-toplevel"], None)?)
+            .map(|root| PathBuf::from(root.trim()))
+            .unwrap_or_else(|| dir.to_path_buf());
+        Ok(Some(root))
+    }
+
+    fn status(
+        &self,
+        repo_root: &Path,
+        pathspec: Option<&Path>,
+        ignored: bool,
+    ) -> Result<Vec<String>> {
+        let args: &[&str] = if ignored {
+            &["status", "--porcelain=v1", "--ignored"]
+        } else {
+            &["status", "--porcelain=v1"]
+        };
+        Ok(stdout(git(repo_root, args, pathspec)?)
+            .map(|stdout| stdout.lines().map(String::from).collect())
+            .unwrap_or_default())
+    }
+
+    fn is_ignored(&self, repo_root: &Path, relative: &Path) -> Result<bool> {
+        let output = Command::new("git")
+            .args(["check-ignore", "-q"])
+            .arg(relative)
+            .current_dir(repo_root)
+            .output()
+            .context("Failed to run git")?;
+        Ok(output.status.success())
+    }
+
+    fn ahead_behind(
+        &self,
+        repo_root: &Path,
+        pathspec: Option<&Path>,
+    ) -> Result<Option<(usize, usize)>> {
+        // The whole tree, so that commits changing nothing are not counted
+        let path = pathspec.unwrap_or(Path::new("."));
+        let count = |range| -> Result<Option<usize>> {
+            Ok(
+                stdout(git(repo_root, &["rev-list", "--count", range], Some(path))?)
+                    .and_then(|count| count.trim().parse().ok()),
+            )
+        };
+        match (count("@{upstream}..HEAD")?, count("HEAD..@{upstream}")?) {
+            (Some(ahead), Some(behind)) => Ok(Some((ahead, behind))),
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Status read in-process with libgit2.
+//!
+//! Output follows `git status --porcelain=v1`: changed entries, then
+//! untracked, then ignored ones, each sorted by path; untracked and ignored
+//! directories are listed once with a trailing slash, and paths are quoted
+//! the way git quotes them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use git2::{Branch, Commit, ErrorCode, Oid, Repository, Status, StatusOptions, Tree};
+
+use super::Backend;
+
+/// libgit2.
+pub(super) struct Libgit2;
+
+impl Backend for Libgit2 {
+    fn name(&self) -> &'static str {
+        "libgit2"
+    }
+
+    fn repo_root(&self, dir: &Path) -> Result<Option<PathBuf>> {
+        let repo = match Repository::discover(dir) {
+            Ok(repo) => repo,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        // libgit2 keeps a trailing slash
+        Ok(repo.workdir().map(|root| root.components().collect()))
+    }
+
+    fn status(
+        &self,
+        repo_root: &Path,
+        pathspec: Option<&Path>,
+        ignored: bool,
+    ) -> Result<Vec<String>> {
+        let repo = Repository::open(repo_root)?;
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .include_ignored(ignored)
+            .recurse_ignored_dirs(false)
+            .renames_head_to_index(true);
+        if let Some(pathspec) = pathspec {
+            options.pathspec(pathspec);
+        }
+        let statuses = repo.statuses(Some(&mut options))?;
+        let conflicts = conflict_codes(&repo)?;
+        let quote_path = repo
+            .config()
+            .and_then(|config| config.get_bool("core.quotePath"))
+            .unwrap_or(true);
+        let quote = |path: &[u8]| quote(path, quote_path);
+
+        let mut changed = Vec::new();
+        let mut untracked = Vec::new();
+        let mut ignored = Vec::new();
+        for entry in statuses.iter() {
+            let status = entry.status();
+            let path = entry.path_bytes().to_vec();
+            if status.is_ignored() {
+                // libgit2 reports directories without files as ignored,
+                // git leaves them out
+                if path.ends_with(b"/")
+                    && !has_files(&repo_root.join(&*String::from_utf8_lossy(&path)))
+                {
+                    continue;
+                }
+                let line = format!("!! {}", quote(&path));
+                ignored.push((path, line));
+            } else if status == Status::WT_NEW {
+                let line = format!("?? {}", quote(&path));
+                untracked.push((path, line));
+            } else if status.is_conflicted() {
+                let code = conflicts.get(&path).copied().unwrap_or("UU");
+                let line = format!("{} {}", code, quote(&path));
+                changed.push((path, line));
+            } else if let Some(delta) = entry.head_to_index().filter(|_| status.is_index_renamed())
+            {
+                let old = delta.old_file().path_bytes().unwrap_or_default();
+                let new = delta.new_file().path_bytes().unwrap_or_default().to_vec();
+                let line = format!(
+                    "R{} {} -> {}",
+                    worktree_code(status),
+                    quote(old),
+                    quote(&new)
+                );
+                changed.push((new, line));
+            } else {
+                let line = format!(
+                    "{}{} {}",
+                    index_code(status),
+                    worktree_code(status),
+                    quote(&path)
+                );
+                changed.push((path, line));
+            }
+        }
+
+        Ok([changed, untracked, ignored]
+            .into_iter()
+            .flat_map(|mut group| {
+                group.sort();
+                group.into_iter().map(|(_, line)| line)
+            })
+            .collect())
+    }
+
+    fn is_ignored(&self, repo_root: &Path, relative: &Path) -> Result<bool> {
+        let repo = Repository::open(repo_root)?;
+        // Tracked files are not ignored, whatever the patterns say
+        if repo.index()?.get_path(relative, 0).is_some() {
+            return Ok(false);
+        }
+        Ok(repo.status_should_ignore(relative)?)
+    }
+
+    fn ahead_behind(
+        &self,
+        repo_root: &Path,
+        pathspec: Option<&Path>,
+    ) -> Result<Option<(usize, usize)>> {
+        let repo = Repository::open(repo_root)?;
+        let head = match repo.head() {
+            Ok(head) if head.is_branch() => head,
+            _ => return Ok(None),
+        };
+        let Some(local) = head.target() else {
+            return Ok(None);
+        };
+        let Some(upstream) = Branch::wrap(head)
+            .upstream()
+            .ok()
+            .and_then(|upstream| upstream.get().target())
+        else {
+            return Ok(None);
+        };
+
+        let ahead = count_changing(&repo, local, upstream, pathspec)?;
+        let behind = count_changing(&repo, upstream, local, pathspec)?;
+        Ok(Some((ahead, behind)))
+    }
+}
+
+/// Status code of the index side
+fn index_code(status: Status) -> char {
+    if status.is_index_new() {
+        'A'
+    } else if status.is_index_renamed() {
+        'R'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_typechange() {
+        'T'
+    } else {
+        ' '
+    }
+}
+
+/// Status code of the work tree side
+fn worktree_code(status: Status) -> char {
+    if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_typechange() {
+        'T'
+    } else {
+        ' '
+    }
+}
+
+/// Whether `dir` or a directory below it holds anything but directories
+fn has_files(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            !entry.file_type().is_ok_and(|kind| kind.is_dir()) || has_files(&entry.path())
+        })
+    })
+}
+
+/// Status codes of unmerged paths, by which sides have the path
+fn conflict_codes(repo: &Repository) -> Result<HashMap<Vec<u8>, &'static str>> {
+    let mut codes = HashMap::new();
+    let index = repo.index()?;
+    if !index.has_conflicts() {
+        return Ok(codes);
+    }
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let code = match (
+            conflict.ancestor.is_some(),
+            conflict.our.is_some(),
+            conflict.their.is_some(),
+        ) {
+            (true, false, false) => "DD",
+            (false, true, false) => "AU",
+            (false, false, true) => "UA",
+            (true, true, false) => "UD",
+            (true, false, true) => "DU",
+            (false, true, true) => "AA",
+            _ => "UU",
+        };
+        let path = [conflict.our, conflict.their, conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| entry.path);
+        if let Some(path) = path {
+            codes.insert(path, code);
+        }
+    }
+    Ok(codes)
+}
+
+/// Commits reachable from `from` but not from `hide` that change `pathspec`
+/// (the whole tree when `None`), as `git rev-list --count` with a path
+/// counts them: a commit is skipped when the path is the same in one of its
+/// parents.
+fn count_changing(
+    repo: &Repository,
+    from: Oid,
+    hide: Oid,
+    pathspec: Option<&Path>,
+) -> Result<usize> {
+    let mut walk = repo.revwalk()?;
+    walk.push(from)?;
+    walk.hide(hide)?;
+    let mut count = 0;
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if changes_path(&commit, pathspec)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn changes_path(commit: &Commit, pathspec: Option<&Path>) -> Result<bool> {
+    let id = path_id(&commit.tree()?, pathspec)?;
+    if commit.parent_count() == 0 {
+        return Ok(id.is_some());
+    }
+    for parent in commit.parents() {
+        if path_id(&parent.tree()?, pathspec)? == id {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Object at `pathspec` in `tree`
+fn path_id(tree: &Tree, pathspec: Option<&Path>) -> Result<Option<Oid>> {
+    let Some(path) = pathspec else {
+        return Ok(Some(tree.id()));
+    };
+    match tree.get_path(path) {
+        Ok(entry) => Ok(Some(entry.id())),
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Path as git prints it in status: in double quotes with C escapes when it
+/// has special characters, spaces or (with `core.quotePath`) non-ASCII bytes
+fn quote(path: &[u8], quote_non_ascii: bool) -> String {
+    let needs_escape = |byte: u8| {
+        byte < 0x20
+            || byte == b'"'
+            || byte == b'\\'
+            || byte == 0x7f
+            || (quote_non_ascii && byte >= 0x80)
+    };
+    if !path.iter().any(|&byte| byte == b' ' || needs_escape(byte)) {
+        return String::from_utf8_lossy(path).into_owned();
+    }
+
+    let mut quoted = Vec::with_capacity(path.len() + 2);
+    quoted.push(b'"');
+    for &byte in path {
+        if !needs_escape(byte) {
+            quoted.push(byte);
+            continue;
+        }
+        quoted.push(b'\\');
+        match byte {
+            0x07 => quoted.push(b'a'),
+            0x08 => quoted.push(b'b'),
+            b'\t' => quoted.push(b't'),
+            b'\n' => quoted.push(b'n'),
+            0x0b => quoted.push(b'v'),
+            0x0c => quoted.push(b'f'),
+            b'\r' => quoted.push(b'r'),
+            b'"' | b'\\' => quoted.push(byte),
+            _ => quoted.extend(format!("{:03o}", byte).bytes()),
+        }
+    }
+    quoted.push(b'"');
+    String::from_utf8_lossy(&quoted).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote(b"src/lib.rs", true), "src/lib.rs");
+        assert_eq!(quote(b"a b.txt", true), "\"a b.txt\"");
+        assert_eq!(quote(b"a\tb\"c\\", true), "\"a\\tb\\\"c\\\\\"");
+        assert_eq!(quote("ü.txt".as_bytes(), true), "\"\\303\\274.txt\"");
+        assert_eq!(quote("ü.txt".as_bytes(), false), "ü.txt");
+    }
+}
@@ -0,0 +1,325 @@
+//! Implementations reading repository status.
+//!
+//! Status is read either by running the git command or in-process with
+//! libgit2. Both report status in `git status --porcelain=v1` form, so the
+//! callers parse one format whichever backend answered. The backend is
+//! chosen with `set_backend`; with `GitBackend::Auto` libgit2 is tried
+//! first and the git command answers when it fails (e.g. a repository
+//! format or extension libgit2 does not support).
+
+mod cli;
+mod libgit2;
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::Result;
+use termide_config::GitBackend;
+
+/// Backend chosen with `set_backend`.
+static BACKEND: Mutex<GitBackend> = Mutex::new(GitBackend::Auto);
+
+/// Source of repository status.
+pub(crate) trait Backend: Sync {
+    /// Name shown in logs
+    fn name(&self) -> &'static str;
+
+    /// Root of the work tree containing `dir`, `None` outside a work tree
+    fn repo_root(&self, dir: &Path) -> Result<Option<PathBuf>>;
+
+    /// `git status --porcelain=v1` lines, limited to `pathspec` (relative
+    /// to `repo_root`) when given, with ignored entries when `ignored` is set
+    fn status(
+        &self,
+        repo_root: &Path,
+        pathspec: Option<&Path>,
+        ignored: bool,
+    ) -> Result<Vec<String>>;
+
+    /// Whether the untracked path `relative` is ignored
+    fn is_ignored(&self, repo_root: &Path, relative: &Path) -> Result<bool>;
+
+    /// Commits of HEAD missing from its upstream and the reverse, counting
+    /// only commits changing `pathspec` when given; `None` without upstream
+    fn ahead_behind(
+        &self,
+        repo_root: &Path,
+        pathspec: Option<&Path>,
+    ) -> Result<Option<(usize, usize)>>;
+}
+
+/// Choose how repository status is read.
+pub fn set_backend(backend: GitBackend) {
+    if let Ok(mut current) = BACKEND.lock() {
+        *current = backend;
+    }
+}
+
+/// Backends to try, in order.
+fn backends() -> Vec<&'static dyn Backend> {
+    let choice = BACKEND
+        .lock()
+        .map(|backend| *backend)
+        .unwrap_or(GitBackend::Auto);
+    let mut backends: Vec<&'static dyn Backend> = Vec::new();
+    if choice != GitBackend::Cli {
+        backends.push(&libgit2::Libgit2);
+    }
+    if choice != GitBackend::Libgit2 && crate::is_available() {
+        backends.push(&cli::Cli);
+    }
+    backends
+}
+
+/// Run `operation` with the first backend that succeeds.
+///
+/// The time each backend took is logged, which is how the two are compared
+/// on a given repository.
+pub(crate) fn run<T>(what: &str, operation: impl Fn(&dyn Backend) -> Result<T>) -> Option<T> {
+    for backend in backends() {
+        let started = Instant::now();
+        let result = operation(backend);
+        log::debug!(
+            "git {} with {} took {:?}",
+            what,
+            backend.name(),
+            started.elapsed()
+        );
+        match result {
+            Ok(value) => return Some(value),
+            Err(e) => log::warn!("git {} with {} failed: {:#}", what, backend.name(), e),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args([
+                "-c",
+                "commit.gpgsign=false",
+                "-c",
+                "init.defaultBranch=main",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn write(path: &Path, content: &str) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, content).unwrap();
+    }
+
+    /// Repository with every kind of status and a branch ahead of and
+    /// behind its upstream
+    fn fixture(name: &str) -> PathBuf {
+        let base = std::env::temp_dir().join(format!(
+            "termide_git_backend_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&base);
+        let upstream = base.join("upstream");
+        let repo = base.join("repo");
+        fs::create_dir_all(&upstream).unwrap();
+
+        git(&upstream, &["init", "-q"]);
+        write(&upstream.join("src/lib.rs"), "lib\n");
+        write(&upstream.join("src/main.rs"), "main\n");
+        write(&upstream.join("README.md"), "readme\n");
+        write(&upstream.join("old name.txt"), "renamed\n");
+        write(&upstream.join("gone.txt"), "gone\n");
+        write(&upstream.join("staged gone.txt"), "staged gone\n");
+        write(&upstream.join("conflict.txt"), "base\n");
+        write(&upstream.join(".gitignore"), "*.log\nbuild/\n");
+        git(&upstream, &["add", "-A"]);
+        git(&upstream, &["commit", "-q", "-m", "initial"]);
+        git(&base, &["clone", "-q", "upstream", "repo"]);
+
+        // Behind: a commit in src/ on the upstream
+        write(&upstream.join("src/lib.rs"), "lib\nupstream\n");
+        write(&upstream.join("conflict.txt"), "theirs\n");
+        git(&upstream, &["commit", "-q", "-am", "upstream"]);
+        // Ahead: a commit outside src/ and an empty one
+        write(&repo.join("README.md"), "readme\nlocal\n");
+        git(&repo, &["commit", "-q", "-am", "local"]);
+        git(&repo, &["commit", "-q", "--allow-empty", "-m", "empty"]);
+        git(&repo, &["fetch", "-q"]);
+
+        // Conflict: commit a change to conflict.txt and merge the upstream
+        write(&repo.join("conflict.txt"), "ours\n");
+        git(&repo, &["commit", "-q", "-am", "ours"]);
+        let _ = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(["merge", "-q", "--no-edit", "@{upstream}"])
+            .current_dir(&repo)
+            .output();
+
+        write(&repo.join("src/main.rs"), "main\nchanged\n");
+        write(&repo.join("src/new.rs"), "new\n");
+        git(&repo, &["add", "src/new.rs"]);
+        write(&repo.join("src/new.rs"), "new\nchanged\n");
+        git(&repo, &["mv", "old name.txt", "new name.txt"]);
+        fs::remove_file(repo.join("gone.txt")).unwrap();
+        git(&repo, &["rm", "-q", "staged gone.txt"]);
+        write(&repo.join("untracked.txt"), "untracked\n");
+        write(&repo.join("notes/a.txt"), "a\n");
+        write(&repo.join("notes/b.txt"), "b\n");
+        write(&repo.join("tab\tand \"quote\".txt"), "quoted\n");
+        write(&repo.join("ünïcode.txt"), "unicode\n");
+        write(&repo.join("debug.log"), "log\n");
+        write(&repo.join("src/trace.log"), "log\n");
+        write(&repo.join("build/out.o"), "out\n");
+        fs::create_dir_all(repo.join("empty/nested")).unwrap();
+        fs::create_dir_all(repo.join("build/empty")).unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        if !crate::is_available() {
+            return;
+        }
+        let repo = fixture("agree");
+        let repo_root = repo.canonicalize().unwrap();
+        let backends: [&dyn Backend; 2] = [&cli::Cli, &libgit2::Libgit2];
+
+        let outputs: Vec<_> = backends
+            .iter()
+            .map(|backend| {
+                let root = backend.repo_root(&repo.join("src")).unwrap().unwrap();
+                let outside = backend.repo_root(&std::env::temp_dir()).unwrap();
+                let status = |pathspec: Option<&str>, ignored| {
+                    backend
+                        .status(&root, pathspec.map(Path::new), ignored)
+                        .unwrap()
+                };
+                let ignored: Vec<bool> = ["debug.log", "src/trace.log", "build", "untracked.txt"]
+                    .iter()
+                    .map(|path| backend.is_ignored(&root, Path::new(path)).unwrap())
+                    .collect();
+                (
+                    root.canonicalize().unwrap(),
+                    outside.is_some(),
+                    status(None, true),
+                    status(None, false),
+                    status(Some("src"), true),
+                    status(Some("notes"), false),
+                    status(Some("README.md"), false),
+                    ignored,
+                    backend.ahead_behind(&root, None).unwrap(),
+                    backend.ahead_behind(&root, Some(Path::new("src"))).unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(outputs[0], outputs[1]);
+        let (root, outside, all, ..) = &outputs[1];
+        assert_eq!(root, &repo_root);
+        assert!(!outside);
+        for line in [
+            "UU conflict.txt",
+            " D gone.txt",
+            "R  \"old name.txt\" -> \"new name.txt\"",
+            "D  \"staged gone.txt\"",
+            " M src/main.rs",
+            "AM src/new.rs",
+            "?? notes/",
+            "?? \"tab\\tand \\\"quote\\\".txt\"",
+            "?? \"\\303\\274n\\303\\257code.txt\"",
+            "!! build/",
+            "!! debug.log",
+        ] {
+            assert!(all.iter().any(|l| l == line), "{} missing: {:?}", line, all);
+        }
+        // The empty commit changes no path
+        assert_eq!(outputs[1].8, Some((2, 1)));
+        assert_eq!(outputs[1].9, Some((0, 1)));
+
+        let _ = fs::remove_dir_all(repo.parent().unwrap());
+    }
+
+    #[test]
+    fn test_public_functions_agree() {
+        if !crate::is_available() {
+            return;
+        }
+        let repo = fixture("public");
+        let run_with = |backend| {
+            set_backend(backend);
+            let cache = crate::get_git_status(&repo.join("src")).unwrap();
+            let root = cache.repo_root().to_path_buf();
+            let statuses: Vec<_> = ["main.rs", "new.rs", "lib.rs", "trace.log"]
+                .iter()
+                .map(|name| cache.get_status(name))
+                .collect();
+            (
+                statuses,
+                cache.changes().to_vec(),
+                crate::file_status(&root, &root.join("debug.log")),
+                crate::file_status(&root, &root.join("src/main.rs")),
+                crate::get_repo_status(&root, &root.join("src")).map(|status| {
+                    (
+                        status.uncommitted_changes,
+                        status.ahead,
+                        status.behind,
+                        status.is_ignored,
+                    )
+                }),
+            )
+        };
+
+        let cli = run_with(GitBackend::Cli);
+        let libgit2 = run_with(GitBackend::Libgit2);
+        set_backend(GitBackend::Auto);
+        assert_eq!(cli, libgit2);
+        assert_eq!(cli.2, crate::GitStatus::Ignored);
+        assert_eq!(cli.3, crate::GitStatus::Modified);
+
+        let _ = fs::remove_dir_all(repo.parent().unwrap());
+    }
+
+    /// Compare the backends on the repository termide is built from:
+    /// `cargo test -p termide-git -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn compare_backend_timing() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let time = |backend: &dyn Backend| {
+            let started = Instant::now();
+            let root = backend.repo_root(dir).unwrap().unwrap();
+            let status = backend.status(&root, None, true).unwrap();
+            let ahead_behind = backend.ahead_behind(&root, None).unwrap();
+            (started.elapsed(), status, ahead_behind)
+        };
+
+        let (cli_time, cli_status, cli_ahead_behind) = time(&cli::Cli);
+        let (libgit2_time, libgit2_status, libgit2_ahead_behind) = time(&libgit2::Libgit2);
+        println!(
+            "{} entries: cli {:?}, libgit2 {:?}",
+            cli_status.len(),
+            cli_time,
+            libgit2_time
+        );
+        assert_eq!(cli_status, libgit2_status);
+        assert_eq!(cli_ahead_behind, libgit2_ahead_behind);
+    }
+}
@@ -0,0 +1,358 @@
+//! Staged and unstaged changes of a repository.
+//!
+//! Files are staged and unstaged as a whole with `git add` / `git reset`;
+//! single hunks are staged by applying a patch made of the file header and
+//! that hunk to the index (`git apply --cached`, reversed to unstage).
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::GitStatus;
+
+/// Changes of one file in the index and in the working tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Path relative to the repository root
+    pub path: PathBuf,
+    /// Change staged in the index (None if nothing is staged)
+    pub staged: Option<GitStatus>,
+    /// Change in the working tree not staged yet (untracked files are Added)
+    pub unstaged: Option<GitStatus>,
+}
+
+/// Parse a `git status --porcelain=v1` line (ignored files are skipped)
+pub(crate) fn parse_status_line(line: &str) -> Option<FileChange> {
+    let mut codes = line.chars();
+    let (index, worktree) = (codes.next()?, codes.next()?);
+    let path = line.get(3..)?;
+    if index == '!' {
+        return None;
+    }
+    // Renames and copies are listed as "old -> new"
+    let path = match path.split_once(" -> ") {
+        Some((_, new)) if matches!(index, 'R' | 'C') => new,
+        _ => path,
+    };
+    Some(FileChange {
+        path: PathBuf::from(unquote(path)),
+        // Untracked files ("??") have nothing staged
+        staged: code_status(index).filter(|_| index != '?'),
+        unstaged: code_status(worktree),
+    })
+}
+
+/// Status of one column of a porcelain status code
+fn code_status(code: char) -> Option<GitStatus> {
+    match code {
+        'M' | 'T' | 'U' => Some(GitStatus::Modified),
+        'A' | 'R' | 'C' | '?' => Some(GitStatus::Added),
+        'D' => Some(GitStatus::Deleted),
+        _ => None,
+    }
+}
+
+/// Undo the C-style quoting git applies to paths with unusual characters
+fn unquote(path: &str) -> String {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.bytes().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(digit @ b'0'..=b'7') => {
+                // Octal escape of a (UTF-8) byte
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    if let Some(&next @ b'0'..=b'7') = chars.peek() {
+                        value = value * 8 + u32::from(next - b'0');
+                        chars.next();
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => {}
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Hunk of a file diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchHunk {
+    /// Hunk lines starting with the `@@` header, with line breaks
+    pub text: String,
+}
+
+impl PatchHunk {
+    /// The `@@ -a,b +c,d @@` header line
+    pub fn header(&self) -> &str {
+        self.text.lines().next().unwrap_or_default()
+    }
+
+    /// Number of added and removed lines
+    pub fn line_counts(&self) -> (usize, usize) {
+        self.text
+            .lines()
+            .skip(1)
+            .fold((0, 0), |(added, removed), line| {
+                match line.as_bytes().first() {
+                    Some(b'+') => (added + 1, removed),
+                    Some(b'-') => (added, removed + 1),
+                    _ => (added, removed),
+                }
+            })
+    }
+}
+
+/// Diff of one file split into hunks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    /// Lines before the first hunk (`diff --git`, `---`, `+++`, ...)
+    header: String,
+    /// Hunks in file order
+    pub hunks: Vec<PatchHunk>,
+}
+
+impl FilePatch {
+    /// Split `git diff` output of a single file into header and hunks
+    pub(crate) fn parse(diff: &str) -> Self {
+        let mut header = String::new();
+        let mut hunks: Vec<PatchHunk> = Vec::new();
+        for line in diff.split_inclusive('\n') {
+            if line.starts_with("@@") {
+                hunks.push(PatchHunk {
+                    text: String::new(),
+                });
+            }
+            match hunks.last_mut() {
+                Some(hunk) => hunk.text.push_str(line),
+                None => header.push_str(line),
+            }
+        }
+        Self { header, hunks }
+    }
+
+    /// Patch with the file header and a single hunk
+    fn hunk_patch(&self, hunk: &PatchHunk) -> String {
+        let mut patch = format!("{}{}", self.header, hunk.text);
+        if !patch.ends_with('\n') {
+            patch.push('\n');
+        }
+        patch
+    }
+}
+
+/// Run git in the repository, returning its output or failing with its
+/// error message
+fn run_git(repo_root: &Path, args: &[&str], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git")?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin
+            .write_all(input.as_bytes())
+            .context("Failed to write to git")?;
+    }
+
+    let output = child.wait_with_output().context("Failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let message = if stderr.trim().is_empty() {
+            stdout.trim()
+        } else {
+            stderr.trim()
+        };
+        bail!("{}", message);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Stage all changes of a file (including its deletion)
+pub fn stage_file(repo_root: &Path, path: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_git(repo_root, &["add", "-A", "--", &path], None).map(|_| ())
+}
+
+/// Remove a file's changes from the index, keeping the working tree
+pub fn unstage_file(repo_root: &Path, path: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_git(repo_root, &["reset", "-q", "--", &path], None).map(|_| ())
+}
+
+/// Diff of a file between the index and the working tree (`staged` false)
+/// or between HEAD and the index (`staged` true)
+pub fn file_patch(repo_root: &Path, path: &Path, staged: bool) -> Result<FilePatch> {
+    let path = path.to_string_lossy();
+    let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+    if staged {
+        args.push("--cached");
+    }
+    args.extend(["--", &path]);
+    run_git(repo_root, &args, None).map(|diff| FilePatch::parse(&diff))
+}
+
+/// Stage one hunk of an unstaged patch
+pub fn stage_hunk(repo_root: &Path, patch: &FilePatch, hunk: &PatchHunk) -> Result<()> {
+    let input = patch.hunk_patch(hunk);
+    run_git(repo_root, &["apply", "--cached", "-"], Some(&input)).map(|_| ())
+}
+
+/// Unstage one hunk of a staged patch
+pub fn unstage_hunk(repo_root: &Path, patch: &FilePatch, hunk: &PatchHunk) -> Result<()> {
+    let input = patch.hunk_patch(hunk);
+    run_git(
+        repo_root,
+        &["apply", "--cached", "--reverse", "-"],
+        Some(&input),
+    )
+    .map(|_| ())
+}
+
+/// Commit the staged changes, returning git's summary line
+/// (e.g. `[main 1a2b3c4] Fix parser`)
+pub fn commit(repo_root: &Path, message: &str) -> Result<String> {
+    run_git(repo_root, &["commit", "-F", "-"], Some(message))
+        .map(|output| output.lines().next().unwrap_or_default().to_string())
+}
+
+/// Name of the checked out branch (short commit hash when detached)
+pub fn current_branch(repo_root: &Path) -> Option<String> {
+    run_git(repo_root, &["symbolic-ref", "--short", "-q", "HEAD"], None)
+        .or_else(|_| run_git(repo_root, &["rev-parse", "--short", "HEAD"], None))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_status_line() {
+        let change = |line| parse_status_line(line).unwrap();
+
+        assert_eq!(
+            change("MM src/lib.rs"),
+            FileChange {
+                path: PathBuf::from("src/lib.rs"),
+                staged: Some(GitStatus::Modified),
+                unstaged: Some(GitStatus::Modified),
+            }
+        );
+        let untracked = change("?? notes.txt");
+        assert_eq!(untracked.staged, None);
+        assert_eq!(untracked.unstaged, Some(GitStatus::Added));
+        let deleted = change(" D old.rs");
+        assert_eq!(deleted.staged, None);
+        assert_eq!(deleted.unstaged, Some(GitStatus::Deleted));
+
+        assert_eq!(change("R  a.rs -> b.rs").path, PathBuf::from("b.rs"));
+        assert_eq!(
+            change(r#"A  "with space \321\217.txt""#).path,
+            PathBuf::from("with space я.txt")
+        );
+        assert!(parse_status_line("!! target/").is_none());
+    }
+
+    #[test]
+    fn test_parse_patch() {
+        let diff = "diff --git a/f b/f\n--- a/f\n+++ b/f\n\
+                    @@ -1,2 +1,2 @@\n-one\n+ONE\n two\n\
+                    @@ -9 +9,2 @@\n nine\n+ten\n";
+        let patch = FilePatch::parse(diff);
+
+        assert_eq!(patch.hunks.len(), 2);
+        assert_eq!(patch.hunks[0].header(), "@@ -1,2 +1,2 @@");
+        assert_eq!(patch.hunks[0].line_counts(), (1, 1));
+        assert_eq!(patch.hunks[1].line_counts(), (1, 0));
+        assert_eq!(
+            patch.hunk_patch(&patch.hunks[1]),
+            "diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -9 +9,2 @@\n nine\n+ten\n"
+        );
+        assert!(FilePatch::parse("").hunks.is_empty());
+    }
+
+    #[test]
+    fn test_stage_hunk_and_commit() {
+        if !crate::is_available() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("termide-git-changes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| run_git(&dir, args, None).unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "commit.gpgsign", "false"]);
+
+        let lines: Vec<String> = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        fs::write(dir.join("file.txt"), lines.concat()).unwrap();
+        stage_file(&dir, Path::new("file.txt")).unwrap();
+        commit(&dir, "Initial").unwrap();
+
+        // Two changes far enough apart to be separate hunks
+        let mut changed = lines.clone();
+        changed[1] = "second\n".to_string();
+        changed[18] = "nineteenth\n".to_string();
+        fs::write(dir.join("file.txt"), changed.concat()).unwrap();
+
+        let unstaged = file_patch(&dir, Path::new("file.txt"), false).unwrap();
+        assert_eq!(unstaged.hunks.len(), 2);
+        stage_hunk(&dir, &unstaged, &unstaged.hunks[0]).unwrap();
+
+        let staged = file_patch(&dir, Path::new("file.txt"), true).unwrap();
+        assert_eq!(staged.hunks.len(), 1);
+        assert!(staged.hunks[0].text.contains("+second"));
+        assert_eq!(
+            file_patch(&dir, Path::new("file.txt"), false)
+                .unwrap()
+                .hunks
+                .len(),
+            1
+        );
+
+        let summary = commit(&dir, "Change second line\n\nWith a body").unwrap();
+        assert!(summary.contains("Change second line"));
+        assert_eq!(git(&["log", "-1", "--format=%b"]).trim(), "With a body");
+
+        let status = crate::get_git_status(&dir).unwrap();
+        assert_eq!(
+            status.changes(),
+            &[FileChange {
+                path: PathBuf::from("file.txt"),
+                staged: None,
+                unstaged: Some(GitStatus::Modified),
+            }]
+        );
+        assert!(commit(&dir, "Nothing staged").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -18,7 +18,7 @@ pub struct GitDiffAsyncResult {
 /// Load original content from HEAD in a background thread
 /// Returns a receiver that will receive the result
 pub fn load_original_async(file_path: PathBuf) -> mpsc::Receiver<GitDiffAsyncResult> {
-    let (tx, rx) = mpsc::channel();
+    let (tx, rx) = termide_wake::channel();
 
     std::thread::spawn(move || {
         let original_content = load_original_from_head_sync(&file_path);
@@ -31,6 +31,13 @@ pub fn load_original_async(file_path: PathBuf) -> mpsc::Receiver<GitDiffAsyncRes
     rx
 }
 
+/// Content of a file in HEAD, with LF line breaks.
+///
+/// Empty for files not in HEAD yet, None outside a repository.
+pub fn head_content(file_path: &std::path::Path) -> Option<String> {
+    load_original_from_head_sync(file_path).map(normalize_line_breaks)
+}
+
 /// Synchronous function to load original content from HEAD
 /// Extracted for use in background thread
 fn load_original_from_head_sync(file_path: &std::path::Path) -> Option<String> {
@@ -84,6 +91,37 @@ pub enum LineStatus {
     DeletedAfter,
 }
 
+/// Contiguous change between HEAD and the current content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// First line of the change in the current content (0-based)
+    pub new_start: usize,
+    /// Number of current lines in the change (0 for pure deletions)
+    pub new_count: usize,
+    /// Lines from HEAD replaced by the change (with line breaks)
+    pub original: String,
+}
+
+impl Hunk {
+    /// Line the hunk is shown at (deletions are marked after the previous line)
+    pub fn anchor_line(&self) -> usize {
+        if self.new_count == 0 {
+            self.new_start.saturating_sub(1)
+        } else {
+            self.new_start
+        }
+    }
+
+    /// Check if hunk covers given line of the current content
+    pub fn contains_line(&self, line: usize) -> bool {
+        if self.new_count == 0 {
+            line == self.anchor_line()
+        } else {
+            (self.new_start..self.new_start + self.new_count).contains(&line)
+        }
+    }
+}
+
 /// Represents a single hunk from git diff output
 #[derive(Debug, Clone)]
 struct DiffHunk {
@@ -102,6 +140,8 @@ pub struct GitDiffCache {
     line_statuses: HashMap<usize, LineStatus>,
     /// Map of line numbers to count of deleted lines after them (line_idx -> deletion_count)
     deleted_after_lines: HashMap<usize, usize>,
+    /// Changes ordered by position in the current content
+    hunks: Vec<Hunk>,
     /// Timestamp when diff was last fetched
     last_updated: std::time::Instant,
     /// Original content from HEAD (for in-memory diff)
@@ -115,6 +155,7 @@ impl GitDiffCache {
             file_path,
             line_statuses: HashMap::new(),
             deleted_after_lines: HashMap::new(),
+            hunks: Vec::new(),
             last_updated: std::time::Instant::now(),
             original_content: None,
         }
@@ -162,7 +203,7 @@ impl GitDiffCache {
         let content =
             String::from_utf8(output.stdout).context("Failed to parse git show output as UTF-8")?;
 
-        self.original_content = Some(content);
+        self.original_content = Some(normalize_line_breaks(content));
         Ok(())
     }
 
@@ -178,22 +219,42 @@ impl GitDiffCache {
             .as_ref()
             .expect("original_content set by load_original_from_head above");
 
-        // Compute diff using similar crate
-        let diff = TextDiff::from_lines(original.as_str(), current_content);
-        let (statuses, deleted_after) = compute_line_statuses_from_textdiff(&diff);
-
-        // Use the computed results directly - they are the source of truth
         // TextDiff compares HEAD with current buffer, which correctly handles:
         // - Pure deletions (creates markers)
         // - Modified line deletions (creates markers)
         // - Restored lines via undo (removes markers)
-        self.line_statuses = statuses;
-        self.deleted_after_lines = deleted_after;
-        self.last_updated = std::time::Instant::now();
+        let diff = BufferDiff::compute(original, current_content);
+        self.apply_buffer_diff(diff);
 
         Ok(())
     }
 
+    /// Compare buffer content with the original from HEAD in a background
+    /// thread, so that large files do not hold up typing. None until the
+    /// original was loaded (see [`Self::apply_async_result`]).
+    ///
+    /// `current_content` is only called when the original was loaded.
+    pub fn diff_buffer_async(
+        &self,
+        current_content: impl FnOnce() -> String,
+    ) -> Option<mpsc::Receiver<BufferDiff>> {
+        let original = self.original_content.clone()?;
+        let current_content = current_content();
+        let (tx, rx) = termide_wake::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(BufferDiff::compute(&original, &current_content));
+        });
+        Some(rx)
+    }
+
+    /// Show line statuses computed by [`Self::diff_buffer_async`]
+    pub fn apply_buffer_diff(&mut self, diff: BufferDiff) {
+        self.line_statuses = diff.line_statuses;
+        self.deleted_after_lines = diff.deleted_after_lines;
+        self.hunks = diff.hunks;
+        self.last_updated = std::time::Instant::now();
+    }
+
     /// Update git diff by comparing file on disk with HEAD
     pub fn update(&mut self) -> Result<()> {
         // Load original content from HEAD
@@ -205,17 +266,19 @@ impl GitDiffCache {
             _ => {
                 self.line_statuses.clear();
                 self.deleted_after_lines.clear();
+                self.hunks.clear();
                 return Ok(());
             }
         };
 
         // Read current file content from disk
         let current_content = match std::fs::read_to_string(&self.file_path) {
-            Ok(content) => content,
+            Ok(content) => normalize_line_breaks(content),
             Err(_) => {
                 // File might not exist or can't be read
                 self.line_statuses.clear();
                 self.deleted_after_lines.clear();
+                self.hunks.clear();
                 return Ok(());
             }
         };
@@ -226,6 +289,7 @@ impl GitDiffCache {
 
         self.line_statuses = statuses;
         self.deleted_after_lines = deleted_after;
+        self.hunks = compute_hunks(&diff);
         self.last_updated = std::time::Instant::now();
 
         Ok(())
@@ -249,17 +313,49 @@ impl GitDiffCache {
         self.deleted_after_lines.get(&line).copied().unwrap_or(0)
     }
 
+    /// Get all changes ordered by position
+    pub fn hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+
+    /// Get change covering given line
+    pub fn hunk_at(&self, line: usize) -> Option<&Hunk> {
+        self.hunks.iter().find(|hunk| hunk.contains_line(line))
+    }
+
+    /// Get first change below given line, wrapping to the first change in the file
+    pub fn next_hunk(&self, line: usize) -> Option<&Hunk> {
+        self.hunks
+            .iter()
+            .find(|hunk| hunk.anchor_line() > line)
+            .or_else(|| self.hunks.first())
+    }
+
+    /// Get last change above given line, wrapping to the last change in the file
+    pub fn prev_hunk(&self, line: usize) -> Option<&Hunk> {
+        self.hunks
+            .iter()
+            .rev()
+            .find(|hunk| hunk.anchor_line() < line)
+            .or_else(|| self.hunks.last())
+    }
+
     /// Check if cache is stale (older than threshold)
     #[allow(dead_code)]
     pub fn is_stale(&self, threshold: std::time::Duration) -> bool {
         self.last_updated.elapsed() > threshold
     }
 
+    /// Time the diff was last computed
+    pub fn last_updated(&self) -> std::time::Instant {
+        self.last_updated
+    }
+
     /// Apply async result and recompute diff
     /// Called when background thread completes loading original content
     pub fn apply_async_result(&mut self, result: GitDiffAsyncResult) {
         // Store original content
-        self.original_content = result.original_content;
+        self.original_content = result.original_content.map(normalize_line_breaks);
 
         // Recompute diff if we have original content
         let original = match self.original_content.as_ref() {
@@ -267,16 +363,18 @@ impl GitDiffCache {
             _ => {
                 self.line_statuses.clear();
                 self.deleted_after_lines.clear();
+                self.hunks.clear();
                 return;
             }
         };
 
         // Read current file content from disk
         let current_content = match std::fs::read_to_string(&self.file_path) {
-            Ok(content) => content,
+            Ok(content) => normalize_line_breaks(content),
             Err(_) => {
                 self.line_statuses.clear();
                 self.deleted_after_lines.clear();
+                self.hunks.clear();
                 return;
             }
         };
@@ -287,10 +385,58 @@ impl GitDiffCache {
 
         self.line_statuses = statuses;
         self.deleted_after_lines = deleted_after;
+        self.hunks = compute_hunks(&diff);
         self.last_updated = std::time::Instant::now();
     }
 }
 
+/// Buffer content compared with the original from HEAD
+#[derive(Debug)]
+pub struct BufferDiff {
+    line_statuses: HashMap<usize, LineStatus>,
+    deleted_after_lines: HashMap<usize, usize>,
+    hunks: Vec<Hunk>,
+}
+
+impl BufferDiff {
+    fn compute(original: &str, current: &str) -> Self {
+        let diff = TextDiff::from_lines(original, current);
+        let (line_statuses, deleted_after_lines) = compute_line_statuses_from_textdiff(&diff);
+        Self {
+            line_statuses,
+            deleted_after_lines,
+            hunks: compute_hunks(&diff),
+        }
+    }
+}
+
+/// Unified diff between two versions of a text, compared line by line
+/// like the gutter compares a buffer with HEAD. CRLF line breaks count as
+/// LF; equal texts give an empty string.
+pub fn unified_diff(
+    original: &str,
+    current: &str,
+    original_label: &str,
+    current_label: &str,
+) -> String {
+    let original = normalize_line_breaks(original.to_string());
+    let current = normalize_line_breaks(current.to_string());
+    TextDiff::from_lines(original.as_str(), current.as_str())
+        .unified_diff()
+        .context_radius(3)
+        .header(original_label, current_label)
+        .to_string()
+}
+
+/// Convert CRLF line breaks to LF (editor buffers always use LF)
+fn normalize_line_breaks(content: String) -> String {
+    if content.contains("\r\n") {
+        content.replace("\r\n", "\n")
+    } else {
+        content
+    }
+}
+
 /// Parse git diff hunks from unified diff format
 /// Format: @@ -<old_start>,<old_count> +<new_start>,<new_count> @@
 fn parse_diff_hunks(diff_text: &str) -> Result<Vec<DiffHunk>> {
@@ -434,6 +580,43 @@ fn compute_line_statuses_from_textdiff<'a>(
     (statuses, deleted_after)
 }
 
+/// Collect changed regions from TextDiff (adjacent deletes and inserts form one hunk)
+fn compute_hunks<'a>(diff: &TextDiff<'a, 'a, 'a, str>) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut new_line_idx = 0;
+    let mut in_hunk = false;
+
+    for change in diff.iter_all_changes() {
+        use similar::ChangeTag;
+
+        match change.tag() {
+            ChangeTag::Equal => {
+                in_hunk = false;
+                new_line_idx += 1;
+            }
+            tag => {
+                if !in_hunk {
+                    hunks.push(Hunk {
+                        new_start: new_line_idx,
+                        new_count: 0,
+                        original: String::new(),
+                    });
+                    in_hunk = true;
+                }
+                let hunk = hunks.last_mut().expect("hunk pushed above");
+                if tag == ChangeTag::Delete {
+                    hunk.original.push_str(change.value());
+                } else {
+                    hunk.new_count += 1;
+                    new_line_idx += 1;
+                }
+            }
+        }
+    }
+
+    hunks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -482,6 +665,88 @@ mod tests {
         assert_eq!(hunks[0].new_count, 0);
     }
 
+    #[test]
+    fn test_compute_hunks() {
+        let original = "a\nb\nc\nd\ne\n";
+        let current = "a\nB\nc\nnew\nd\n";
+        let diff = TextDiff::from_lines(original, current);
+        let hunks = compute_hunks(&diff);
+
+        assert_eq!(
+            hunks,
+            vec![
+                Hunk {
+                    new_start: 1,
+                    new_count: 1,
+                    original: "b\n".to_string(),
+                },
+                Hunk {
+                    new_start: 3,
+                    new_count: 1,
+                    original: String::new(),
+                },
+                Hunk {
+                    new_start: 5,
+                    new_count: 0,
+                    original: "e\n".to_string(),
+                },
+            ]
+        );
+        assert_eq!(hunks[2].anchor_line(), 4);
+        assert!(hunks[2].contains_line(4));
+        assert!(!hunks[0].contains_line(2));
+    }
+
+    #[test]
+    fn test_crlf_original_matches_lf_buffer() {
+        let mut cache = GitDiffCache::new(PathBuf::from("test.txt"));
+        cache.apply_async_result(GitDiffAsyncResult {
+            file_path: PathBuf::from("test.txt"),
+            original_content: Some("a\r\nb\r\n".to_string()),
+        });
+        cache.update_from_buffer("a\nb\n").unwrap();
+
+        assert!(cache.hunks().is_empty());
+        assert_eq!(cache.get_line_status(0), LineStatus::Unchanged);
+    }
+
+    #[test]
+    fn test_hunk_navigation_wraps() {
+        let mut cache = GitDiffCache::new(PathBuf::from("test.txt"));
+        cache.original_content = Some("a\nb\nc\nd\n".to_string());
+        cache.update_from_buffer("A\nb\nc\nD\n").unwrap();
+
+        assert_eq!(cache.next_hunk(0).map(|h| h.new_start), Some(3));
+        assert_eq!(cache.next_hunk(3).map(|h| h.new_start), Some(0));
+        assert_eq!(cache.prev_hunk(3).map(|h| h.new_start), Some(0));
+        assert_eq!(cache.prev_hunk(0).map(|h| h.new_start), Some(3));
+        assert_eq!(cache.hunk_at(3).map(|h| h.original.as_str()), Some("d\n"));
+        assert!(cache.hunk_at(1).is_none());
+    }
+
+    #[test]
+    fn test_diff_buffer_async_matches_update_from_buffer() {
+        let mut cache = GitDiffCache::new(PathBuf::from("test.txt"));
+        assert!(cache.diff_buffer_async(|| "a\n".to_string()).is_none());
+
+        cache.original_content = Some("a\nb\nc\nd\n".to_string());
+        let rx = cache.diff_buffer_async(|| "a\nB\nc\n".to_string()).unwrap();
+        cache.apply_buffer_diff(rx.recv().unwrap());
+        let hunks = cache.hunks().to_vec();
+        assert_eq!(cache.get_line_status(1), LineStatus::Modified);
+
+        cache.update_from_buffer("a\nB\nc\n").unwrap();
+        assert_eq!(cache.hunks(), hunks.as_slice());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("a\r\nb\r\n", "a\nB\n", "old", "new");
+        assert!(diff.starts_with("--- old\n+++ new\n@@ -1,2 +1,2 @@\n"));
+        assert!(diff.contains(" a\n-b\n+B\n"));
+        assert!(unified_diff("same\r\n", "same\n", "old", "new").is_empty());
+    }
+
     // Tests for old compute_line_statuses API - disabled as we now use TextDiff
     // These tests can be re-enabled when compute_line_statuses is updated
     // to return the new (HashMap<LineStatus>, HashMap<deletion_count>) format
@@ -9,10 +9,20 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::OnceLock;
 
+mod backend;
+pub mod changes;
 pub mod diff;
 pub mod watcher;
 
-pub use diff::{load_original_async, GitDiffAsyncResult, GitDiffCache, LineStatus};
+pub use backend::set_backend;
+pub use changes::{
+    commit, current_branch, file_patch, stage_file, stage_hunk, unstage_file, unstage_hunk,
+    FileChange, FilePatch, PatchHunk,
+};
+pub use diff::{
+    head_content, load_original_async, unified_diff, BufferDiff, GitDiffAsyncResult, GitDiffCache,
+    Hunk, LineStatus,
+};
 pub use watcher::{create_git_watcher, GitStatusUpdate, GitWatcher};
 
 /// Get git status for a specific file relative to repo root.
@@ -22,45 +32,30 @@ pub fn file_status(repo_root: &Path, file_path: &Path) -> GitStatus {
         Err(_) => return GitStatus::default(),
     };
 
-    // Check if file is ignored
-    if let Ok(output) = Command::new("git")
-        .args(["check-ignore", "-q"])
-        .arg(relative)
-        .current_dir(repo_root)
-        .output()
-    {
-        if output.status.success() {
-            return GitStatus::Ignored;
+    backend::run("file status", |backend| {
+        if backend.is_ignored(repo_root, relative)? {
+            return Ok(GitStatus::Ignored);
         }
-    }
+        let lines = backend.status(repo_root, Some(relative), false)?;
+        Ok(lines
+            .first()
+            .and_then(|line| line.get(0..2))
+            .and_then(status_from_code)
+            .unwrap_or_default())
+    })
+    .unwrap_or_default()
+}
 
-    // Get status
-    if let Ok(output) = Command::new("git")
-        .args(["status", "--porcelain=v1", "--"])
-        .arg(relative)
-        .current_dir(repo_root)
-        .output()
-    {
-        if output.status.success() {
-            if let Ok(stdout) = String::from_utf8(output.stdout) {
-                if let Some(line) = stdout.lines().next() {
-                    if line.len() >= 2 {
-                        let status_code = &line[0..2];
-                        return match status_code {
-                            "!!" => GitStatus::Ignored,
-                            " M" | "M " | "MM" => GitStatus::Modified,
-                            "A " | " A" | "AM" | "AA" => GitStatus::Added,
-                            " D" | "D " | "DD" => GitStatus::Deleted,
-                            "??" => GitStatus::Added,
-                            _ => GitStatus::Unmodified,
-                        };
-                    }
-                }
-            }
-        }
+/// File status of a `git status --porcelain=v1` code
+fn status_from_code(code: &str) -> Option<GitStatus> {
+    match code {
+        "!!" => Some(GitStatus::Ignored),
+        " M" | "M " | "MM" => Some(GitStatus::Modified),
+        "A " | " A" | "AM" | "AA" => Some(GitStatus::Added),
+        " D" | "D " | "DD" => Some(GitStatus::Deleted),
+        "??" => Some(GitStatus::Added),
+        _ => None,
     }
-
-    GitStatus::Unmodified
 }
 
 /// Global flag for git availability on system.
@@ -105,79 +100,66 @@ pub fn find_repo_root(path: &Path) -> Option<PathBuf> {
     }
 }
 
-/// Get git status for directory.
-pub fn get_git_status(dir: &Path) -> Option<GitStatusCache> {
-    if !is_available() {
-        return None;
-    }
-
-    let is_repo = Command::new("git")
-        .arg("rev-parse")
-        .arg("--is-inside-work-tree")
-        .current_dir(dir)
+/// List the tracked and untracked files of a repository that are not
+/// ignored, relative to `repo_root`.
+pub fn list_files(repo_root: &Path) -> Option<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .current_dir(repo_root)
         .output()
-        .ok()
-        .map(|output| output.status.success())
-        .unwrap_or(false);
-
-    if !is_repo {
+        .ok()?;
+    if !output.status.success() {
         return None;
     }
 
-    let repo_root = Command::new("git")
-        .arg("rev-parse")
-        .arg("--show-toplevel")
-        .current_dir(dir)
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .map(|s| PathBuf::from(s.trim()))
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| dir.to_path_buf());
+    // Unmerged files are listed once per stage
+    let mut seen = HashSet::new();
+    let files = output
+        .stdout
+        .split(|&byte| byte == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| PathBuf::from(String::from_utf8_lossy(name).into_owned()))
+        .filter(|path| seen.insert(path.clone()))
+        .collect();
+    Some(files)
+}
+
+/// Get git status for directory.
+pub fn get_git_status(dir: &Path) -> Option<GitStatusCache> {
+    let (repo_root, lines) = backend::run("status", |backend| {
+        let Some(repo_root) = backend.repo_root(dir)? else {
+            return Ok(None);
+        };
+        let lines = backend.status(&repo_root, None, true)?;
+        Ok(Some((repo_root, lines)))
+    })??;
 
     let relative_path = dir
         .strip_prefix(&repo_root)
         .unwrap_or(Path::new(""))
         .to_path_buf();
 
-    let ignored = get_ignored_files(&repo_root);
+    let mut ignored = HashSet::new();
     let mut status_map = HashMap::new();
+    let mut changes = Vec::new();
+    for line in &lines {
+        if line.len() < 4 {
+            continue;
+        }
+        changes.extend(changes::parse_status_line(line));
 
-    if let Ok(output) = Command::new("git")
-        .arg("status")
-        .arg("--porcelain=v1")
-        .arg("--ignored")
-        .current_dir(&repo_root)
-        .output()
-    {
-        if output.status.success() {
-            if let Ok(stdout) = String::from_utf8(output.stdout) {
-                for line in stdout.lines() {
-                    if line.len() < 4 {
-                        continue;
-                    }
-
-                    let status_code = &line[0..2];
-                    let file_path = &line[3..];
-
-                    let status = match status_code {
-                        "!!" => GitStatus::Ignored,
-                        " M" | "M " | "MM" => GitStatus::Modified,
-                        "A " | " A" | "AM" | "AA" => GitStatus::Added,
-                        " D" | "D " | "DD" => GitStatus::Deleted,
-                        "??" => GitStatus::Added,
-                        _ => continue,
-                    };
-
-                    status_map.insert(PathBuf::from(file_path), status);
-                }
-            }
+        let file_path = &line[3..];
+        if line.starts_with("!! ") {
+            ignored.insert(PathBuf::from(file_path));
+        }
+        if let Some(status) = status_from_code(&line[0..2]) {
+            status_map.insert(PathBuf::from(file_path), status);
         }
     }
 
@@ -185,42 +167,33 @@ pub fn get_git_status(dir: &Path) -> Option<GitStatusCache> {
         status_map,
         ignored_files: ignored,
         relative_path,
+        repo_root,
+        changes,
     })
 }
 
-fn get_ignored_files(repo_root: &Path) -> HashSet<PathBuf> {
-    let mut ignored = HashSet::new();
-
-    if let Ok(output) = Command::new("git")
-        .arg("status")
-        .arg("--porcelain=v1")
-        .arg("--ignored")
-        .current_dir(repo_root)
-        .output()
-    {
-        if output.status.success() {
-            if let Ok(stdout) = String::from_utf8(output.stdout) {
-                for line in stdout.lines() {
-                    if let Some(file_path) = line.strip_prefix("!! ") {
-                        ignored.insert(PathBuf::from(file_path));
-                    }
-                }
-            }
-        }
-    }
-
-    ignored
-}
-
 /// Git status cache for directory.
 #[derive(Debug)]
 pub struct GitStatusCache {
     status_map: HashMap<PathBuf, GitStatus>,
     ignored_files: HashSet<PathBuf>,
     relative_path: PathBuf,
+    repo_root: PathBuf,
+    /// Changed and untracked files, in `git status` order
+    changes: Vec<FileChange>,
 }
 
 impl GitStatusCache {
+    /// Root directory of the repository
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Staged and unstaged changes of the repository
+    pub fn changes(&self) -> &[FileChange] {
+        &self.changes
+    }
+
     fn is_parent_ignored(&self, path: &Path) -> bool {
         let mut current = path;
         while let Some(parent) = current.parent() {
@@ -359,126 +332,41 @@ pub struct GitRepoStatus {
 
 /// Get git repository status for a specific file or directory.
 pub fn get_repo_status(repo_path: &Path, item_path: &Path) -> Option<GitRepoStatus> {
-    if !is_available() {
-        return None;
-    }
-
     let git_work_dir = if item_path.is_file() {
         item_path.parent().unwrap_or(repo_path)
     } else {
         item_path
     };
 
-    let is_repo = Command::new("git")
-        .arg("rev-parse")
-        .arg("--is-inside-work-tree")
-        .current_dir(git_work_dir)
-        .output()
-        .ok()
-        .map(|output| output.status.success())
-        .unwrap_or(false);
-
-    if !is_repo {
-        return None;
-    }
-
-    let repo_root = Command::new("git")
-        .arg("rev-parse")
-        .arg("--show-toplevel")
-        .current_dir(git_work_dir)
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .map(|s| PathBuf::from(s.trim()))
-            } else {
-                None
-            }
-        })?;
-
-    let relative_path = item_path.strip_prefix(&repo_root).ok()?;
-    let git_path = if relative_path.as_os_str().is_empty() {
-        Path::new(".")
-    } else {
-        relative_path
-    };
-
-    let is_ignored = Command::new("git")
-        .args(["status", "--porcelain", "--ignored", "--"])
-        .arg(git_path)
-        .current_dir(&repo_root)
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .map(|stdout| stdout.lines().any(|line| line.starts_with("!! ")))
-            } else {
-                None
-            }
-        })
-        .unwrap_or(false);
-
-    let uncommitted_changes = Command::new("git")
-        .args(["status", "--porcelain", "--"])
-        .arg(git_path)
-        .current_dir(&repo_root)
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .map(|stdout| stdout.lines().filter(|l| !l.starts_with("!!")).count())
-            } else {
-                None
-            }
-        })
-        .unwrap_or(0);
-
-    let ahead = Command::new("git")
-        .args(["rev-list", "--count", "@{upstream}..HEAD", "--"])
-        .arg(git_path)
-        .current_dir(&repo_root)
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .and_then(|s| s.trim().parse().ok())
-            } else {
-                None
-            }
-        })
-        .unwrap_or(0);
-
-    let behind = Command::new("git")
-        .args(["rev-list", "--count", "HEAD..@{upstream}", "--"])
-        .arg(git_path)
-        .current_dir(&repo_root)
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .and_then(|s| s.trim().parse().ok())
-            } else {
-                None
-            }
-        })
-        .unwrap_or(0);
+    backend::run("repository status", |backend| {
+        let Some(repo_root) = backend.repo_root(git_work_dir)? else {
+            return Ok(None);
+        };
+        let Ok(relative_path) = item_path.strip_prefix(&repo_root) else {
+            return Ok(None);
+        };
+        let pathspec = Some(relative_path).filter(|path| !path.as_os_str().is_empty());
 
-    Some(GitRepoStatus {
-        uncommitted_changes,
-        ahead,
-        behind,
-        is_ignored,
-    })
+        let is_ignored = backend
+            .status(&repo_root, pathspec, true)?
+            .iter()
+            .any(|line| line.starts_with("!! "));
+        let uncommitted_changes = backend
+            .status(&repo_root, pathspec, false)?
+            .iter()
+            .filter(|line| !line.starts_with("!!"))
+            .count();
+        let (ahead, behind) = backend
+            .ahead_behind(&repo_root, pathspec)?
+            .unwrap_or_default();
+
+        Ok(Some(GitRepoStatus {
+            uncommitted_changes,
+            ahead,
+            behind,
+            is_ignored,
+        }))
+    })?
 }
 
 #[cfg(test)]
@@ -3,7 +3,6 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{channel, Sender};
 use std::time::Duration;
 
 use notify::{RecommendedWatcher, RecursiveMode};
@@ -26,7 +25,7 @@ pub struct GitWatcher {
 impl GitWatcher {
     /// Create a new GitWatcher that sends events through the provided channel
     /// Debounces events to minimum 1000ms intervals
-    pub fn new(tx: Sender<GitStatusUpdate>) -> anyhow::Result<Self> {
+    pub fn new(tx: termide_wake::Sender<GitStatusUpdate>) -> anyhow::Result<Self> {
         let debouncer = new_debouncer(
             Duration::from_millis(1000),
             move |result: notify_debouncer_mini::DebounceEventResult| {
@@ -128,7 +127,7 @@ impl GitWatcher {
 /// This is created once at application startup and runs in a background thread
 pub fn create_git_watcher(
 ) -> anyhow::Result<(GitWatcher, std::sync::mpsc::Receiver<GitStatusUpdate>)> {
-    let (tx, rx) = channel();
+    let (tx, rx) = termide_wake::channel();
     let watcher = GitWatcher::new(tx)?;
     Ok((watcher, rx))
 }
@@ -0,0 +1,78 @@
+; Kotlin highlights (tree-sitter-kotlin-ng does not ship a query)
+
+[
+  (line_comment)
+  (block_comment)
+  (shebang)
+] @comment
+
+[
+  (string_literal)
+  (multiline_string_literal)
+  (character_literal)
+] @string
+
+(escape_sequence) @escape
+
+(interpolation
+  "${" @punctuation.special
+  "}" @punctuation.special)
+
+[
+  (number_literal)
+  (float_literal)
+] @number
+
+(annotation) @attribute
+
+(label) @label
+
+(class_declaration
+  name: (identifier) @type)
+
+(object_declaration
+  name: (identifier) @type)
+
+(user_type
+  (identifier) @type)
+
+(function_declaration
+  name: (identifier) @function)
+
+(call_expression
+  (identifier) @function)
+
+(call_expression
+  (navigation_expression
+    (identifier) @function.method .))
+
+(parameter
+  (identifier) @variable.parameter)
+
+(class_parameter
+  (identifier) @property)
+
+(this_expression) @variable.builtin
+
+(super_expression) @variable.builtin
+
+[
+  "abstract" "actual" "annotation" "as" "as?" "by" "catch" "class"
+  "companion" "const" "constructor" "crossinline" "data" "do" "else"
+  "enum" "expect" "external" "final" "finally" "for" "fun" "get" "if"
+  "import" "in" "infix" "init" "inline" "inner" "interface" "internal"
+  "is" "lateinit" "noinline" "object" "open" "operator" "out" "override"
+  "package" "private" "protected" "public" "return" "sealed" "set"
+  "suspend" "tailrec" "throw" "try" "typealias" "val" "value" "var"
+  "vararg" "when" "where" "while"
+] @keyword
+
+[
+  "!" "!!" "!=" "!==" "%" "%=" "&&" "*" "*=" "+" "++" "+=" "-" "--"
+  "-=" "->" ".." "..<" "/" "/=" "<" "<=" "=" "==" "===" ">" ">=" "?:"
+  "||" "::"
+] @operator
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
+
+["." "," ";" ":" "?."] @punctuation.delimiter
@@ -0,0 +1,471 @@
+//! Whole-document parsing for multi-line aware highlighting.
+//!
+//! The document is parsed once and re-parsed incrementally after edits, so
+//! constructs spanning several lines (block comments, raw strings, code
+//! fences) are highlighted on every line they cover. Embedded languages
+//! (markdown code fences, HTML `<script>`, PHP heredocs) are parsed as
+//! injection layers and highlighted over the host language.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{InputEdit, Language, Parser, Point, Query, QueryCursor, Tree};
+
+use crate::{injection_language, TreeSitterHighlighter, HIGHLIGHT_NAMES};
+
+/// Maximum nesting of injected languages (PHP -> HTML -> JavaScript)
+const MAX_INJECTION_DEPTH: usize = 3;
+
+/// Markdown blocks whose lines are not prose
+const MARKDOWN_CODE_BLOCKS: &[&str] = &["fenced_code_block", "indented_code_block", "html_block"];
+
+/// Highlights and injections queries of a language
+pub(crate) struct HighlightQuery {
+    query: Query,
+    /// Index in `HIGHLIGHT_NAMES` for each capture (None if not highlighted)
+    highlights: Vec<Option<usize>>,
+    injections: Option<InjectionQuery>,
+}
+
+impl HighlightQuery {
+    /// Compile highlights and injections queries. An invalid injections
+    /// query only disables injections
+    pub fn new(language: &Language, highlights: &str, injections: &str) -> Option<Self> {
+        let query = Query::new(language, highlights).ok()?;
+        let highlights = query
+            .capture_names()
+            .iter()
+            .map(|name| highlight_for_capture(name))
+            .collect();
+        Some(Self {
+            query,
+            highlights,
+            injections: InjectionQuery::new(language, injections),
+        })
+    }
+}
+
+/// Highlight matching a capture name: the recognized name with most parts,
+/// all of which are parts of the capture name (as tree-sitter-highlight does)
+fn highlight_for_capture(capture: &str) -> Option<usize> {
+    let parts: Vec<&str> = capture.split('.').collect();
+    let mut best = None;
+    let mut best_len = 0;
+    for (idx, name) in HIGHLIGHT_NAMES.iter().enumerate() {
+        let len = name.split('.').count();
+        if len > best_len && name.split('.').all(|part| parts.contains(&part)) {
+            best = Some(idx);
+            best_len = len;
+        }
+    }
+    best
+}
+
+/// Injections query with indexes of its `@injection.*` captures
+struct InjectionQuery {
+    query: Query,
+    content: u32,
+    language: Option<u32>,
+}
+
+impl InjectionQuery {
+    fn new(language: &Language, source: &str) -> Option<Self> {
+        if source.is_empty() {
+            return None;
+        }
+        let query = Query::new(language, source).ok()?;
+        let content = query.capture_index_for_name("injection.content")?;
+        let language = query.capture_index_for_name("injection.language");
+        Some(Self {
+            query,
+            content,
+            language,
+        })
+    }
+
+    /// `#set!` property of a pattern (empty value for flags)
+    fn property(&self, pattern: usize, key: &str) -> Option<&str> {
+        self.query
+            .property_settings(pattern)
+            .iter()
+            .find(|property| &*property.key == key)
+            .map(|property| property.value.as_deref().unwrap_or_default())
+    }
+
+    /// Injected languages and the ranges they cover.
+    ///
+    /// Matches of patterns with `injection.combined` are parsed as a single
+    /// layer. Content nodes are included with their children.
+    fn layers(&self, tree: &Tree, text: &str) -> Vec<(&'static str, Vec<tree_sitter::Range>)> {
+        let mut layers: Vec<(&'static str, Vec<tree_sitter::Range>)> = Vec::new();
+        // Layer index of combined (pattern, language)
+        let mut combined: HashMap<(usize, &'static str), usize> = HashMap::new();
+
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), text.as_bytes());
+        while let Some(query_match) = matches.next() {
+            let pattern = query_match.pattern_index;
+            let name = query_match
+                .captures
+                .iter()
+                .find(|capture| Some(capture.index) == self.language)
+                .and_then(|capture| text.get(capture.node.byte_range()))
+                .or_else(|| self.property(pattern, "injection.language"));
+            let Some(language) = name.and_then(injection_language) else {
+                continue;
+            };
+            let ranges = query_match
+                .captures
+                .iter()
+                .filter(|capture| capture.index == self.content)
+                .map(|capture| capture.node.range());
+
+            if self.property(pattern, "injection.combined").is_some() {
+                let idx = *combined.entry((pattern, language)).or_insert_with(|| {
+                    layers.push((language, Vec::new()));
+                    layers.len() - 1
+                });
+                layers[idx].1.extend(ranges);
+            } else {
+                layers.push((language, ranges.collect()));
+            }
+        }
+
+        for (_, ranges) in &mut layers {
+            ranges.retain(|range| range.start_byte < range.end_byte);
+            ranges.sort_by_key(|range| range.start_byte);
+            ranges.dedup_by(|next, prev| next.start_byte < prev.end_byte);
+        }
+        layers.retain(|(_, ranges)| !ranges.is_empty());
+        layers
+    }
+}
+
+/// Embedded language parsed over ranges of the document
+struct Injection {
+    language: &'static str,
+    tree: Tree,
+    /// Bytes from the start of the first injected range to the end of the last
+    span: Range<usize>,
+}
+
+/// Parsed document text
+pub(crate) struct Document {
+    language: &'static str,
+    parser: Parser,
+    tree: Tree,
+    text: String,
+    /// Byte offset of each line start
+    line_starts: Vec<usize>,
+    /// Injection layers, outer languages first
+    injections: Vec<Injection>,
+}
+
+impl Document {
+    /// Parse a document. Returns None if the language cannot be loaded
+    pub fn parse(
+        highlighter: &TreeSitterHighlighter,
+        language: &str,
+        text: String,
+    ) -> Option<Self> {
+        let (&language, config) = highlighter.configs.get_key_value(language)?;
+        let mut parser = Parser::new();
+        parser.set_language(&config.language).ok()?;
+        let tree = parser.parse(&text, None)?;
+        let line_starts = line_starts(&text);
+        let mut document = Self {
+            language,
+            parser,
+            tree,
+            text,
+            line_starts,
+            injections: Vec::new(),
+        };
+        document.parse_injections(highlighter);
+        Some(document)
+    }
+
+    /// Replace the text, re-parsing incrementally.
+    ///
+    /// Returns the first line whose highlighting may have changed, or None
+    /// if the text is unchanged.
+    pub fn update(&mut self, highlighter: &TreeSitterHighlighter, text: String) -> Option<usize> {
+        let (old, new) = (self.text.as_bytes(), text.as_bytes());
+        let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        if prefix == old.len() && old.len() == new.len() {
+            return None;
+        }
+        let max_suffix = old.len().min(new.len()) - prefix;
+        let suffix = old
+            .iter()
+            .rev()
+            .zip(new.iter().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let new_line_starts = line_starts(&text);
+        let edit = InputEdit {
+            start_byte: prefix,
+            old_end_byte: old.len() - suffix,
+            new_end_byte: new.len() - suffix,
+            start_position: point(&self.line_starts, prefix),
+            old_end_position: point(&self.line_starts, old.len() - suffix),
+            new_end_position: point(&new_line_starts, new.len() - suffix),
+        };
+        self.tree.edit(&edit);
+        self.text = text;
+        self.line_starts = new_line_starts;
+
+        // Parsing only fails when cancelled, keep the edited tree then
+        let Some(tree) = self.parser.parse(&self.text, Some(&self.tree)) else {
+            return Some(0);
+        };
+        let mut first_changed = self
+            .tree
+            .changed_ranges(&tree)
+            .map(|range| range.start_point.row)
+            .min()
+            .map_or(edit.start_position.row, |row| {
+                row.min(edit.start_position.row)
+            });
+        self.tree = tree;
+
+        // Injections are parsed again from scratch. One that appeared above
+        // the edit re-highlights lines from its start
+        let old_spans: Vec<Range<usize>> = self
+            .injections
+            .iter()
+            .map(|injection| injection.span.clone())
+            .collect();
+        self.parse_injections(highlighter);
+        for injection in &self.injections {
+            if injection.span.start < edit.start_byte && !old_spans.contains(&injection.span) {
+                let row = point(&self.line_starts, injection.span.start).row;
+                first_changed = first_changed.min(row);
+            }
+        }
+        Some(first_changed)
+    }
+
+    /// Parse embedded languages (languages without a config are skipped)
+    fn parse_injections(&mut self, highlighter: &TreeSitterHighlighter) {
+        self.injections.clear();
+
+        // Layers to search for nested injections: (language, injection, depth)
+        let mut pending = vec![(self.language, None, 0)];
+        while let Some((language, layer, depth)) = pending.pop() {
+            let Some(query) = highlighter
+                .highlight_query(language)
+                .and_then(|query| query.injections.as_ref())
+            else {
+                continue;
+            };
+            let tree = layer.map_or(&self.tree, |idx: usize| &self.injections[idx].tree);
+            for (language, ranges) in query.layers(tree, &self.text) {
+                let Some(config) = highlighter.get_config(language) else {
+                    continue;
+                };
+                let span = ranges[0].start_byte..ranges[ranges.len() - 1].end_byte;
+                let ready = self.parser.set_language(&config.language).is_ok()
+                    && self.parser.set_included_ranges(&ranges).is_ok();
+                let Some(tree) = ready.then(|| self.parser.parse(&self.text, None)).flatten()
+                else {
+                    continue;
+                };
+                if depth + 1 < MAX_INJECTION_DEPTH {
+                    pending.push((language, Some(self.injections.len()), depth + 1));
+                }
+                self.injections.push(Injection {
+                    language,
+                    tree,
+                    span,
+                });
+            }
+        }
+
+        // Restore the parser for incremental parsing of the whole document
+        if let Some(config) = highlighter.get_config(self.language) {
+            let _ = self.parser.set_language(&config.language);
+        }
+        let _ = self.parser.set_included_ranges(&[]);
+    }
+
+    /// Byte range of a line without its line break
+    fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.text.len(), |&next| next - 1);
+        Some(start..end)
+    }
+
+    /// Text of a line without its line break
+    pub fn line(&self, line: usize) -> Option<&str> {
+        self.line_range(line).map(|range| &self.text[range])
+    }
+
+    /// Highlight of each byte of a line (index in `HIGHLIGHT_NAMES`).
+    ///
+    /// Injected languages paint over the host language. Returns None if the
+    /// highlights query of the document language is not available.
+    pub fn highlight_line(
+        &self,
+        highlighter: &TreeSitterHighlighter,
+        line: usize,
+    ) -> Option<Vec<Option<usize>>> {
+        let query = highlighter.highlight_query(self.language)?;
+        let Some(range) = self.line_range(line) else {
+            return Some(Vec::new());
+        };
+        let mut highlights = vec![None; range.len()];
+        self.paint_captures(query, &self.tree, &range, &mut highlights);
+        for injection in &self.injections {
+            if injection.span.start > range.end || injection.span.end < range.start {
+                continue;
+            }
+            if let Some(query) = highlighter.highlight_query(injection.language) {
+                self.paint_captures(query, &injection.tree, &range, &mut highlights);
+            }
+        }
+        Some(highlights)
+    }
+
+    /// Byte ranges of a line worth spell checking: markdown outside code
+    /// and HTML blocks, comments and strings in other languages
+    pub fn prose_ranges(
+        &self,
+        highlighter: &TreeSitterHighlighter,
+        line: usize,
+    ) -> Vec<Range<usize>> {
+        let Some(range) = self.line_range(line).filter(|range| !range.is_empty()) else {
+            return Vec::new();
+        };
+        if self.language == "markdown" {
+            let node = self
+                .tree
+                .root_node()
+                .descendant_for_byte_range(range.start, range.end);
+            let in_code = std::iter::successors(node, |node| node.parent())
+                .any(|node| MARKDOWN_CODE_BLOCKS.contains(&node.kind()));
+            return if in_code {
+                Vec::new()
+            } else {
+                std::iter::once(0..range.len()).collect()
+            };
+        }
+
+        let is_prose = |highlight: &Option<usize>| {
+            highlight.is_some_and(|idx| matches!(HIGHLIGHT_NAMES[idx], "comment" | "string"))
+        };
+        let Some(highlights) = self.highlight_line(highlighter, line) else {
+            return Vec::new();
+        };
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (idx, highlight) in highlights.iter().enumerate() {
+            if !is_prose(highlight) {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == idx => last.end = idx + 1,
+                _ => ranges.push(idx..idx + 1),
+            }
+        }
+        ranges
+    }
+
+    /// Paint highlights of a tree's captures within a line
+    fn paint_captures(
+        &self,
+        query: &HighlightQuery,
+        tree: &Tree,
+        range: &Range<usize>,
+        highlights: &mut [Option<usize>],
+    ) {
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(range.clone());
+
+        // Captures in line, outer nodes first so inner ones paint over them.
+        // For several captures of the same node the first pattern wins.
+        let mut captures = Vec::new();
+        let mut matches = cursor.captures(&query.query, tree.root_node(), self.text.as_bytes());
+        while let Some((query_match, capture_idx)) = matches.next() {
+            let capture = query_match.captures[*capture_idx];
+            let Some(highlight) = query.highlights[capture.index as usize] else {
+                continue;
+            };
+            let node = capture.node;
+            captures.push((
+                node.start_byte(),
+                node.end_byte(),
+                query_match.pattern_index,
+                highlight,
+            ));
+        }
+        captures.sort_by_key(|&(start, end, pattern, _)| (start, std::cmp::Reverse(end), pattern));
+        captures.dedup_by_key(|&mut (start, end, _, _)| (start, end));
+
+        for (start, end, _, highlight) in captures {
+            let start = start.clamp(range.start, range.end) - range.start;
+            let end = end.clamp(range.start, range.end) - range.start;
+            highlights[start..end].fill(Some(highlight));
+        }
+    }
+}
+
+/// Byte offsets of line starts
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
+
+/// Row and byte column of a byte offset
+fn point(line_starts: &[usize], byte: usize) -> Point {
+    let row = line_starts.partition_point(|&start| start <= byte) - 1;
+    Point::new(row, byte - line_starts[row])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::global_highlighter;
+
+    #[test]
+    fn test_capture_names_map_to_highlights() {
+        let index = |name| HIGHLIGHT_NAMES.iter().position(|n| *n == name);
+        assert_eq!(highlight_for_capture("comment"), index("comment"));
+        assert_eq!(
+            highlight_for_capture("comment.documentation"),
+            index("comment")
+        );
+        assert_eq!(
+            highlight_for_capture("function.method.call"),
+            index("function.method")
+        );
+        assert_eq!(highlight_for_capture("local.scope"), None);
+    }
+
+    #[test]
+    fn test_update_reports_first_changed_line() {
+        let highlighter = global_highlighter();
+        let text = "fn a() {}\nfn b() {}\nfn c() {}\n";
+        let mut document = Document::parse(highlighter, "rust", text.to_string()).unwrap();
+        assert_eq!(document.line(1), Some("fn b() {}"));
+        assert_eq!(document.line(3), Some(""));
+        assert_eq!(document.line(4), None);
+
+        assert_eq!(document.update(highlighter, text.to_string()), None);
+        let edited = "fn a() {}\nfn b() { 1 }\nfn c() {}\n";
+        assert_eq!(document.update(highlighter, edited.to_string()), Some(1));
+        assert_eq!(document.line(1), Some("fn b() { 1 }"));
+
+        // Edits on the first line
+        assert_eq!(
+            document.update(highlighter, format!("/*{}", edited)),
+            Some(0)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Foldable line ranges from tree-sitter syntax trees.
+
+use std::collections::BTreeMap;
+
+use tree_sitter::{Node, Parser};
+
+use crate::global_highlighter;
+
+/// Closing tokens kept visible below a folded range
+const CLOSING_TOKENS: &[&str] = &["}", ")", "]", "end", "</"];
+
+/// Indentation-delimited bodies (Python blocks, YAML mappings). They start
+/// at their first statement, which is not a header, so the enclosing
+/// node folds them instead.
+const BODY_KINDS: &[&str] = &["block", "block_node", "block_mapping", "block_sequence"];
+
+/// Compute foldable line ranges `(start, end)` of a text from its syntax tree.
+///
+/// Every named node spanning several lines folds from its first line. A
+/// closing bracket, `end` keyword or closing tag on the last line stays
+/// visible. Returns None if the language is not supported or parsing fails.
+pub fn fold_ranges(lang: &str, text: &str) -> Option<Vec<(usize, usize)>> {
+    let config = global_highlighter().get_config(lang)?;
+    let mut parser = Parser::new();
+    parser.set_language(&config.language).ok()?;
+    let tree = parser.parse(text, None)?;
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut ranges = BTreeMap::new();
+    let mut cursor = tree.walk();
+    let mut stack: Vec<Node> = Vec::new();
+    stack.extend(tree.root_node().named_children(&mut cursor));
+
+    while let Some(node) = stack.pop() {
+        let start = node.start_position().row;
+        let end_position = node.end_position();
+        if end_position.row <= start {
+            continue;
+        }
+        stack.extend(node.named_children(&mut node.walk()));
+        if BODY_KINDS.contains(&node.kind())
+            && node
+                .named_child(0)
+                .is_some_and(|child| child.start_byte() == node.start_byte())
+        {
+            continue;
+        }
+
+        // Node ending at column 0 ends on the previous line
+        let mut end = if end_position.column == 0 {
+            end_position.row - 1
+        } else {
+            end_position.row
+        };
+        if end > start && lines.get(end).is_some_and(|line| is_closing_line(line)) {
+            end -= 1;
+        }
+
+        if end > start {
+            let entry = ranges.entry(start).or_insert(end);
+            *entry = (*entry).max(end);
+        }
+    }
+
+    Some(ranges.into_iter().collect())
+}
+
+/// Check if line starts with a closing token
+fn is_closing_line(line: &str) -> bool {
+    let line = line.trim_start();
+    CLOSING_TOKENS.iter().any(|token| {
+        line.strip_prefix(token).is_some_and(|rest| {
+            !token.ends_with(|c: char| c.is_alphanumeric())
+                || !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_fold_ranges_keep_closing_brace() {
+        let text = "fn main() {\n    if x {\n        y();\n    }\n}\n\nfn other() {}\n";
+        assert_eq!(fold_ranges("rust", text), Some(vec![(0, 3), (1, 2)]));
+    }
+
+    #[test]
+    fn test_closing_lines() {
+        assert!(is_closing_line("    }"));
+        assert!(is_closing_line("end"));
+        assert!(is_closing_line("  </div>"));
+        assert!(!is_closing_line("  end_time = 1"));
+    }
+
+    #[test]
+    fn test_python_fold_ranges() {
+        let text = "def f():\n    x = 1\n    if x:\n        pass\n    return x\n\nprint(f())\n";
+        assert_eq!(fold_ranges("python", text), Some(vec![(0, 4), (2, 3)]));
+        assert_eq!(fold_ranges("unknown", text), None);
+    }
+}
@@ -4,10 +4,24 @@
 
 use ratatui::style::{Color, Modifier, Style};
 use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use termide_spell::Dictionary;
 use tree_sitter_highlight::HighlightConfiguration;
 
+use document::{Document, HighlightQuery};
+use styles::StyleOverrides;
+
+mod document;
+mod fold;
+mod log;
+mod styles;
+
+pub use fold::fold_ranges;
+pub use log::{line_severity, LogHighlighter, LOG_LANGUAGE};
+
 /// Global static highlighter (lazily initialized)
 static GLOBAL_HIGHLIGHTER: OnceLock<TreeSitterHighlighter> = OnceLock::new();
 
@@ -77,8 +91,11 @@ pub fn style_for_highlight(index: usize, base_fg: Color) -> Style {
     style
 }
 
-/// Detect language from file extension.
+/// Detect language from file name or extension.
 pub fn detect_language(path: &Path) -> Option<&'static str> {
+    if let Some(language) = detect_language_by_filename(path) {
+        return Some(language);
+    }
     let ext = path.extension()?.to_str()?;
 
     match ext.to_lowercase().as_str() {
@@ -96,6 +113,13 @@ pub fn detect_language(path: &Path) -> Option<&'static str> {
         "php" => Some("php"),
         "hs" => Some("haskell"),
         "nix" => Some("nix"),
+        "lua" => Some("lua"),
+        "zig" | "zon" => Some("zig"),
+        "kt" | "kts" => Some("kotlin"),
+        "sql" => Some("sql"),
+        "dockerfile" => Some("dockerfile"),
+        "mk" => Some("make"),
+        "cmake" => Some("cmake"),
         "html" | "htm" => Some("html"),
         "css" => Some("css"),
         "json" => Some("json"),
@@ -103,6 +127,51 @@ pub fn detect_language(path: &Path) -> Option<&'static str> {
         "yaml" | "yml" => Some("yaml"),
         "sh" | "bash" | "zsh" => Some("bash"),
         "md" | "markdown" => Some("markdown"),
+        "log" => Some(LOG_LANGUAGE),
+        _ => None,
+    }
+}
+
+/// Detect language of files known by name rather than extension
+/// (`Dockerfile`, `Dockerfile.dev`, `Makefile`, `CMakeLists.txt`).
+pub fn detect_language_by_filename(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.split('.').next().unwrap_or(name);
+
+    match name {
+        "Makefile" | "makefile" | "GNUmakefile" => Some("make"),
+        "CMakeLists.txt" => Some("cmake"),
+        _ if matches!(stem, "Dockerfile" | "Containerfile") => Some("dockerfile"),
+        _ => None,
+    }
+}
+
+/// Detect language of a script from its first line: the interpreter of a
+/// shebang (`#!/bin/sh`, `#!/usr/bin/env -S python3 -u`) or a leading `<?php`.
+///
+/// Used for files whose name does not tell the language.
+pub fn detect_language_from_content(first_line: &str) -> Option<&'static str> {
+    let first_line = first_line.trim_start_matches('\u{feff}');
+    if first_line.starts_with("<?php") {
+        return Some("php");
+    }
+
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        // Skip env options (`-S`, `-i`) and variable assignments
+        interpreter = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    // Versioned interpreters: python3, python3.12
+    match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+        "sh" | "bash" | "zsh" | "dash" | "ksh" => Some("bash"),
+        "python" => Some("python"),
+        "node" | "nodejs" => Some("javascript"),
+        "ruby" => Some("ruby"),
+        "perl" => Some("perl"),
+        "php" => Some("php"),
+        "lua" => Some("lua"),
         _ => None,
     }
 }
@@ -123,6 +192,11 @@ pub const SUPPORTED_LANGUAGES: &[&str] = &[
     "php",
     "haskell",
     "nix",
+    "lua",
+    "zig",
+    "kotlin",
+    "sql",
+    "dockerfile",
     "html",
     "css",
     "json",
@@ -132,23 +206,92 @@ pub const SUPPORTED_LANGUAGES: &[&str] = &[
     "markdown",
 ];
 
+/// Comment syntax of a language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// Line comment prefix (`//`, `#`)
+    Line(&'static str),
+    /// Block comment delimiters wrapped around each line (`<!--`, `-->`)
+    Block(&'static str, &'static str),
+}
+
+/// Comment syntax for language returned by `detect_language`.
+///
+/// Returns None for languages without comments (JSON).
+pub fn comment_style(lang: &str) -> Option<CommentStyle> {
+    match lang {
+        "rust" | "go" | "javascript" | "typescript" | "tsx" | "jsx" | "c" | "cpp" | "java"
+        | "php" | "zig" | "kotlin" => Some(CommentStyle::Line("//")),
+        "python" | "ruby" | "perl" | "nix" | "toml" | "yaml" | "bash" | "dockerfile" | "make"
+        | "cmake" => Some(CommentStyle::Line("#")),
+        "haskell" | "lua" | "sql" => Some(CommentStyle::Line("--")),
+        "html" | "markdown" => Some(CommentStyle::Block("<!--", "-->")),
+        "css" => Some(CommentStyle::Block("/*", "*/")),
+        _ => None,
+    }
+}
+
+/// Language for an injected language name: a language or file extension,
+/// matched case-insensitively (`rust`, `rs`, `Python`, `sh`)
+fn injection_language(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    if let Some(language) = SUPPORTED_LANGUAGES.iter().find(|lang| **lang == name) {
+        return Some(language);
+    }
+    match name.as_str() {
+        "shell" | "console" => Some("bash"),
+        "c++" => Some("cpp"),
+        "golang" => Some("go"),
+        "python3" => Some("python"),
+        _ => detect_language(Path::new(&format!("file.{}", name))),
+    }
+}
+
 /// Check if language is supported.
 pub fn is_language_supported(lang: &str) -> bool {
     SUPPORTED_LANGUAGES.contains(&lang)
 }
 
+/// Supported languages whose grammar loaded, so they can be highlighted
+pub fn highlighted_languages() -> impl Iterator<Item = &'static str> {
+    SUPPORTED_LANGUAGES
+        .iter()
+        .copied()
+        .filter(|lang| global_highlighter().get_config(lang).is_some())
+}
+
+/// HTML outside `<?php ?>` tags (`queries/injections-text.scm` of
+/// tree-sitter-php, which the crate does not export)
+const PHP_TEXT_INJECTIONS_QUERY: &str = r#"((text) @injection.content
+ (#set! injection.language "html")
+ (#set! injection.combined))"#;
+
+/// Query sources of a language, compiled on first use
+struct LanguageQueries {
+    highlights: &'static str,
+    injections: String,
+    compiled: OnceLock<Option<HighlightQuery>>,
+}
+
 /// Syntax highlighter manager based on tree-sitter
 pub struct TreeSitterHighlighter {
     /// Configurations for each supported language
     configs: HashMap<&'static str, HighlightConfiguration>,
+    /// Queries for whole-document highlighting
+    queries: HashMap<&'static str, LanguageQueries>,
     /// Highlight category names for mapping to colors
     highlight_names: Vec<String>,
+    /// User styles taking precedence over the built-in palettes
+    style_overrides: RwLock<StyleOverrides>,
+    /// Incremented when style overrides change
+    styles_generation: AtomicU64,
 }
 
 impl TreeSitterHighlighter {
     /// Create new highlighter with support for all languages
     pub fn new() -> Self {
         let mut configs = HashMap::new();
+        let mut queries = HashMap::new();
 
         // Define highlight category names (standard for tree-sitter)
         let highlight_names = HIGHLIGHT_NAMES
@@ -160,6 +303,7 @@ impl TreeSitterHighlighter {
         // Main programming languages
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "rust",
             tree_sitter_rust::LANGUAGE.into(),
             tree_sitter_rust::HIGHLIGHTS_QUERY,
@@ -169,6 +313,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "python",
             tree_sitter_python::LANGUAGE.into(),
             tree_sitter_python::HIGHLIGHTS_QUERY,
@@ -178,6 +323,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "go",
             tree_sitter_go::LANGUAGE.into(),
             tree_sitter_go::HIGHLIGHTS_QUERY,
@@ -187,6 +333,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "javascript",
             tree_sitter_javascript::LANGUAGE.into(),
             tree_sitter_javascript::HIGHLIGHT_QUERY,
@@ -196,6 +343,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "typescript",
             tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
             tree_sitter_typescript::HIGHLIGHTS_QUERY,
@@ -205,6 +353,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "tsx",
             tree_sitter_typescript::LANGUAGE_TSX.into(),
             tree_sitter_typescript::HIGHLIGHTS_QUERY,
@@ -214,6 +363,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "c",
             tree_sitter_c::LANGUAGE.into(),
             tree_sitter_c::HIGHLIGHT_QUERY,
@@ -223,6 +373,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "cpp",
             tree_sitter_cpp::LANGUAGE.into(),
             tree_sitter_cpp::HIGHLIGHT_QUERY,
@@ -232,6 +383,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "java",
             tree_sitter_java::LANGUAGE.into(),
             tree_sitter_java::HIGHLIGHTS_QUERY,
@@ -241,6 +393,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "ruby",
             tree_sitter_ruby::LANGUAGE.into(),
             tree_sitter_ruby::HIGHLIGHTS_QUERY,
@@ -250,15 +403,21 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "php",
             tree_sitter_php::LANGUAGE_PHP.into(),
             tree_sitter_php::HIGHLIGHTS_QUERY,
-            tree_sitter_php::INJECTIONS_QUERY,
+            &format!(
+                "{}\n{}",
+                tree_sitter_php::INJECTIONS_QUERY,
+                PHP_TEXT_INJECTIONS_QUERY
+            ),
             &highlight_names,
         );
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "haskell",
             tree_sitter_haskell::LANGUAGE.into(),
             tree_sitter_haskell::HIGHLIGHTS_QUERY,
@@ -268,6 +427,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "nix",
             tree_sitter_nix::LANGUAGE.into(),
             tree_sitter_nix::HIGHLIGHTS_QUERY,
@@ -275,9 +435,50 @@ impl TreeSitterHighlighter {
             &highlight_names,
         );
 
+        Self::load_language_config(
+            &mut configs,
+            &mut queries,
+            "lua",
+            tree_sitter_lua::LANGUAGE.into(),
+            tree_sitter_lua::HIGHLIGHTS_QUERY,
+            tree_sitter_lua::INJECTIONS_QUERY,
+            &highlight_names,
+        );
+
+        Self::load_language_config(
+            &mut configs,
+            &mut queries,
+            "zig",
+            tree_sitter_zig::LANGUAGE.into(),
+            tree_sitter_zig::HIGHLIGHTS_QUERY,
+            tree_sitter_zig::INJECTIONS_QUERY,
+            &highlight_names,
+        );
+
+        Self::load_language_config(
+            &mut configs,
+            &mut queries,
+            "kotlin",
+            tree_sitter_kotlin_ng::LANGUAGE.into(),
+            include_str!("../queries/kotlin.scm"),
+            "",
+            &highlight_names,
+        );
+
+        Self::load_language_config(
+            &mut configs,
+            &mut queries,
+            "sql",
+            tree_sitter_sequel::LANGUAGE.into(),
+            tree_sitter_sequel::HIGHLIGHTS_QUERY,
+            "",
+            &highlight_names,
+        );
+
         // Web technologies
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "html",
             tree_sitter_html::LANGUAGE.into(),
             tree_sitter_html::HIGHLIGHTS_QUERY,
@@ -287,6 +488,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "css",
             tree_sitter_css::LANGUAGE.into(),
             tree_sitter_css::HIGHLIGHTS_QUERY,
@@ -296,6 +498,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "json",
             tree_sitter_json::LANGUAGE.into(),
             tree_sitter_json::HIGHLIGHTS_QUERY,
@@ -306,6 +509,7 @@ impl TreeSitterHighlighter {
         // Configuration formats
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "toml",
             tree_sitter_toml_ng::LANGUAGE.into(),
             tree_sitter_toml_ng::HIGHLIGHTS_QUERY,
@@ -315,6 +519,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "yaml",
             tree_sitter_yaml::LANGUAGE.into(),
             tree_sitter_yaml::HIGHLIGHTS_QUERY,
@@ -324,6 +529,7 @@ impl TreeSitterHighlighter {
 
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "bash",
             tree_sitter_bash::LANGUAGE.into(),
             tree_sitter_bash::HIGHLIGHT_QUERY,
@@ -331,9 +537,23 @@ impl TreeSitterHighlighter {
             &highlight_names,
         );
 
+        // No Dockerfile grammar for this tree-sitter version: instructions
+        // are mostly shell commands, so the bash grammar colors comments,
+        // strings and variables
+        Self::load_language_config(
+            &mut configs,
+            &mut queries,
+            "dockerfile",
+            tree_sitter_bash::LANGUAGE.into(),
+            tree_sitter_bash::HIGHLIGHT_QUERY,
+            "",
+            &highlight_names,
+        );
+
         // Markdown (has separate block and inline grammars)
         Self::load_language_config(
             &mut configs,
+            &mut queries,
             "markdown",
             tree_sitter_md::LANGUAGE.into(),
             tree_sitter_md::HIGHLIGHT_QUERY_BLOCK,
@@ -343,16 +563,20 @@ impl TreeSitterHighlighter {
 
         Self {
             configs,
+            queries,
             highlight_names,
+            style_overrides: RwLock::new(StyleOverrides::default()),
+            styles_generation: AtomicU64::new(0),
         }
     }
 
     /// Helper function to load language configuration
     fn load_language_config(
         configs: &mut HashMap<&'static str, HighlightConfiguration>,
+        queries: &mut HashMap<&'static str, LanguageQueries>,
         name: &'static str,
         language: tree_sitter::Language,
-        highlights_query: &str,
+        highlights_query: &'static str,
         injections_query: &str,
         highlight_names: &[String],
     ) {
@@ -361,10 +585,18 @@ impl TreeSitterHighlighter {
         {
             config.configure(highlight_names);
             configs.insert(name, config);
+            queries.insert(
+                name,
+                LanguageQueries {
+                    highlights: highlights_query,
+                    injections: injections_query.to_string(),
+                    compiled: OnceLock::new(),
+                },
+            );
         }
     }
 
-    /// Determine language by file extension
+    /// Determine language by file name or extension
     pub fn language_for_file(&self, path: &Path) -> Option<&'static str> {
         detect_language(path)
     }
@@ -374,6 +606,46 @@ impl TreeSitterHighlighter {
         self.configs.get(language)
     }
 
+    /// Get configuration for an injected language name (markdown code
+    /// fence info, HTML `<script>`), None for unknown languages
+    pub fn injection_config(&self, name: &str) -> Option<&HighlightConfiguration> {
+        injection_language(name).and_then(|language| self.get_config(language))
+    }
+
+    /// Get compiled queries for whole-document highlighting
+    fn highlight_query(&self, language: &str) -> Option<&HighlightQuery> {
+        let config = self.configs.get(language)?;
+        let queries = self.queries.get(language)?;
+        queries
+            .compiled
+            .get_or_init(|| {
+                HighlightQuery::new(&config.language, queries.highlights, &queries.injections)
+            })
+            .as_ref()
+    }
+
+    /// Replace user highlight styles with `(name, spec)` pairs, e.g.
+    /// `("keyword", "#c678dd bold")`. Spec is an optional `#rrggbb` color
+    /// and modifiers (`bold`, `italic`, `underlined`, `dim`).
+    ///
+    /// Returns errors for invalid entries, which are skipped.
+    pub fn set_style_overrides<'a>(
+        &self,
+        overrides: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Vec<String> {
+        let (overrides, errors) = StyleOverrides::parse(overrides);
+        if let Ok(mut current) = self.style_overrides.write() {
+            *current = overrides;
+        }
+        self.styles_generation.fetch_add(1, Ordering::Relaxed);
+        errors
+    }
+
+    /// Counter incremented whenever user highlight styles change
+    pub fn styles_generation(&self) -> u64 {
+        self.styles_generation.load(Ordering::Relaxed)
+    }
+
     /// Convert highlight index to ratatui Style
     pub fn style_for_highlight(&self, highlight_id: usize, is_light_theme: bool) -> Style {
         let highlight_name = self
@@ -389,6 +661,15 @@ impl TreeSitterHighlighter {
             self.color_for_highlight_dark(highlight_name)
         };
 
+        let user_style = self
+            .style_overrides
+            .read()
+            .ok()
+            .and_then(|overrides| overrides.style(highlight_name, fg));
+        if let Some(style) = user_style {
+            return style;
+        }
+
         let mut style = Style::default().fg(fg);
         for modifier in modifiers {
             style = style.add_modifier(modifier);
@@ -475,6 +756,10 @@ use tree_sitter_highlight::{HighlightEvent, Highlighter};
 /// Maximum highlight cache size (lines)
 const MAX_CACHE_SIZE: usize = 1000;
 
+/// Maximum document size (bytes) parsed as a whole, larger documents are
+/// highlighted line by line
+const MAX_DOCUMENT_SIZE: usize = 2 * 1024 * 1024;
+
 /// Trait for line-based syntax highlighting.
 /// Allows custom highlighters (e.g., for log files) to integrate with Editor.
 pub trait LineHighlighter: Send + Sync {
@@ -491,10 +776,19 @@ pub trait LineHighlighter: Send + Sync {
     fn has_syntax(&self) -> bool;
 }
 
+/// Cached highlighting of a line.
+struct CachedLine {
+    segments: Vec<(String, Style)>,
+    /// Byte ranges of misspelled words (computed when first requested)
+    misspellings: Option<Vec<Range<usize>>>,
+    /// Last access time for LRU
+    access_time: u64,
+}
+
 /// Highlighted lines cache for incremental highlighting.
 pub struct HighlightCache {
-    /// Highlighted lines: line number -> (vector of segments, last access time)
-    lines: HashMap<usize, (Vec<(String, Style)>, u64)>,
+    /// Highlighted lines by line number
+    lines: HashMap<usize, CachedLine>,
     /// Current language
     language: Option<String>,
     /// Global SyntaxHighlighter (static)
@@ -503,6 +797,16 @@ pub struct HighlightCache {
     is_light_theme: bool,
     /// Access counter for LRU
     access_counter: u64,
+    /// Whole parsed document (None when highlighting line by line)
+    document: Option<Document>,
+    /// Text revision of the document
+    document_revision: Option<u64>,
+    /// User highlight styles generation the cached lines were built with
+    styles_generation: u64,
+    /// Dictionary to check comments, strings and prose with
+    dictionary: Option<Arc<Dictionary>>,
+    /// Dictionary generation the cached misspellings were found with
+    dictionary_generation: u64,
 }
 
 impl HighlightCache {
@@ -514,6 +818,11 @@ impl HighlightCache {
             syntax_highlighter,
             is_light_theme,
             access_counter: 0,
+            document: None,
+            document_revision: None,
+            styles_generation: syntax_highlighter.styles_generation(),
+            dictionary: None,
+            dictionary_generation: 0,
         }
     }
 
@@ -523,12 +832,57 @@ impl HighlightCache {
             return;
         }
 
-        if self.syntax_highlighter.get_config(language_name).is_some() {
+        if language_name == LOG_LANGUAGE
+            || self.syntax_highlighter.get_config(language_name).is_some()
+        {
             self.language = Some(language_name.to_string());
+            self.document = None;
             self.invalidate_all();
         }
     }
 
+    /// Turn highlighting off (plain text), dropping cached lines.
+    pub fn clear_syntax(&mut self) {
+        self.language = None;
+        self.document = None;
+        self.invalidate_all();
+    }
+
+    /// Sync the whole document text, so that constructs spanning several
+    /// lines (block comments, multi-line strings) are highlighted correctly.
+    ///
+    /// `text` is only called when `revision` differs from the last sync.
+    /// Documents larger than `MAX_DOCUMENT_SIZE` are highlighted line by line.
+    pub fn sync_document(&mut self, revision: u64, size: usize, text: impl FnOnce() -> String) {
+        if self.document_revision == Some(revision) {
+            return;
+        }
+        self.document_revision = Some(revision);
+
+        let language = self
+            .language
+            .as_deref()
+            .filter(|language| self.syntax_highlighter.get_config(language).is_some());
+        let Some(language) = language.filter(|_| size <= MAX_DOCUMENT_SIZE) else {
+            if self.document.take().is_some() {
+                self.lines.clear();
+            }
+            return;
+        };
+
+        match &mut self.document {
+            Some(document) => {
+                if let Some(line) = document.update(self.syntax_highlighter, text()) {
+                    LineHighlighter::invalidate_from(self, line);
+                }
+            }
+            None => {
+                self.document = Document::parse(self.syntax_highlighter, language, text());
+                self.lines.clear();
+            }
+        }
+    }
+
     /// Set syntax by file extension.
     pub fn set_syntax_from_path(&mut self, path: &Path) {
         if let Some(language) = self.syntax_highlighter.language_for_file(path) {
@@ -540,31 +894,126 @@ impl HighlightCache {
     pub fn get_line_segments(&mut self, line_idx: usize, line_text: &str) -> &[(String, Style)] {
         self.access_counter += 1;
 
-        if let Some((_, access_time)) = self.lines.get_mut(&line_idx) {
-            *access_time = self.access_counter;
+        if let Some(line) = self.lines.get_mut(&line_idx) {
+            line.access_time = self.access_counter;
         } else {
-            let segments = self.compute_line_segments(line_text);
+            let segments = self.compute_line_segments(line_idx, line_text);
 
             if self.lines.len() >= MAX_CACHE_SIZE {
                 self.evict_lru();
             }
 
-            self.lines.insert(line_idx, (segments, self.access_counter));
+            self.lines.insert(
+                line_idx,
+                CachedLine {
+                    segments,
+                    misspellings: None,
+                    access_time: self.access_counter,
+                },
+            );
         }
 
         &self
             .lines
             .get(&line_idx)
             .expect("line was just inserted or updated above")
-            .0
+            .segments
+    }
+
+    /// Check comments, strings and prose with a dictionary (None turns
+    /// spell checking off).
+    pub fn set_dictionary(&mut self, dictionary: Option<Arc<Dictionary>>) {
+        let same = match (&self.dictionary, &dictionary) {
+            (Some(current), Some(new)) => Arc::ptr_eq(current, new),
+            (current, new) => current.is_none() && new.is_none(),
+        };
+        if !same {
+            self.dictionary = dictionary;
+            self.clear_misspellings();
+        }
+    }
+
+    /// Byte ranges of misspelled words of a line (with caching).
+    ///
+    /// Code is checked in comments and strings only, plain text and
+    /// markdown outside code blocks entirely. Lines are checked again when
+    /// their highlighting is invalidated or words are added to the
+    /// dictionary.
+    pub fn misspellings(&mut self, line_idx: usize, line_text: &str) -> &[Range<usize>] {
+        let Some(dictionary) = self.dictionary.clone() else {
+            return &[];
+        };
+        if self.dictionary_generation != dictionary.generation() {
+            self.dictionary_generation = dictionary.generation();
+            self.clear_misspellings();
+        }
+
+        self.get_line_segments(line_idx, line_text);
+        let checked = self
+            .lines
+            .get(&line_idx)
+            .is_some_and(|line| line.misspellings.is_some());
+        if !checked {
+            let misspellings = self
+                .prose_ranges(line_idx, line_text)
+                .into_iter()
+                .flat_map(|range| {
+                    dictionary
+                        .misspelled(&line_text[range.clone()])
+                        .into_iter()
+                        .map(move |word| range.start + word.start..range.start + word.end)
+                })
+                .collect();
+            if let Some(line) = self.lines.get_mut(&line_idx) {
+                line.misspellings = Some(misspellings);
+            }
+        }
+
+        self.lines
+            .get(&line_idx)
+            .and_then(|line| line.misspellings.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Byte ranges of a line to check spelling in
+    fn prose_ranges(&self, line_idx: usize, line_text: &str) -> Vec<Range<usize>> {
+        match self.language.as_deref() {
+            None => std::iter::once(0..line_text.len()).collect(),
+            Some(LOG_LANGUAGE) => Vec::new(),
+            Some(_) => self
+                .document
+                .as_ref()
+                .filter(|document| document.line(line_idx) == Some(line_text))
+                .map(|document| document.prose_ranges(self.syntax_highlighter, line_idx))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn clear_misspellings(&mut self) {
+        for line in self.lines.values_mut() {
+            line.misspellings = None;
+        }
     }
 
     /// Compute highlighting for line.
-    fn compute_line_segments(&self, line_text: &str) -> Vec<(String, Style)> {
+    fn compute_line_segments(&self, line_idx: usize, line_text: &str) -> Vec<(String, Style)> {
         let Some(ref language) = self.language else {
             return vec![(line_text.to_string(), Style::default())];
         };
 
+        if language == LOG_LANGUAGE {
+            return log::log_line_segments(line_text);
+        }
+
+        let highlights = self
+            .document
+            .as_ref()
+            .filter(|document| document.line(line_idx) == Some(line_text))
+            .and_then(|document| document.highlight_line(self.syntax_highlighter, line_idx));
+        if let Some(highlights) = highlights {
+            return self.document_line_segments(&highlights, line_text);
+        }
+
         let Some(config) = self.syntax_highlighter.get_config(language) else {
             return vec![(line_text.to_string(), Style::default())];
         };
@@ -572,7 +1021,9 @@ impl HighlightCache {
         let mut highlighter = Highlighter::new();
         let source = line_text.as_bytes();
 
-        let highlights = match highlighter.highlight(config, source, None, |_| None) {
+        let highlights = match highlighter.highlight(config, source, None, |name| {
+            self.syntax_highlighter.injection_config(name)
+        }) {
             Ok(h) => h,
             Err(_) => return vec![(line_text.to_string(), Style::default())],
         };
@@ -621,6 +1072,35 @@ impl HighlightCache {
         }
     }
 
+    /// Highlighting for line sliced from the whole document parse.
+    fn document_line_segments(
+        &self,
+        highlights: &[Option<usize>],
+        line_text: &str,
+    ) -> Vec<(String, Style)> {
+        let mut segments: Vec<(String, Style)> = Vec::new();
+        let mut start = 0;
+        for end in 1..=highlights.len() {
+            if end < highlights.len()
+                && (highlights[end] == highlights[start] || !line_text.is_char_boundary(end))
+            {
+                continue;
+            }
+            let style = highlights[start].map_or(Style::default(), |highlight| {
+                self.syntax_highlighter
+                    .style_for_highlight(highlight, self.is_light_theme)
+            });
+            segments.push((line_text[start..end].to_string(), style));
+            start = end;
+        }
+
+        if segments.is_empty() {
+            vec![(line_text.to_string(), Style::default())]
+        } else {
+            segments
+        }
+    }
+
     /// Remove oldest entries from cache (LRU).
     fn evict_lru(&mut self) {
         let evict_count = MAX_CACHE_SIZE / 5;
@@ -628,7 +1108,7 @@ impl HighlightCache {
         let mut entries: Vec<(usize, u64)> = self
             .lines
             .iter()
-            .map(|(line_idx, (_, access_time))| (*line_idx, *access_time))
+            .map(|(line_idx, line)| (*line_idx, line.access_time))
             .collect();
 
         entries.sort_by_key(|(_, access_time)| *access_time);
@@ -645,20 +1125,50 @@ impl HighlightCache {
 
     /// Invalidate line range.
     pub fn invalidate_range(&mut self, start_line: usize, end_line: usize) {
-        for idx in start_line..=end_line {
-            self.lines.remove(&idx);
+        // Only cached lines are visited, ranges may span whole files
+        self.lines
+            .retain(|&line_idx, _| line_idx < start_line || line_idx > end_line);
+    }
+
+    /// Follow an edit of lines `line..=line + removed`, which are now
+    /// `line..=line + inserted`: drops the highlighting of the edited lines
+    /// and moves the lines below.
+    ///
+    /// Lines whose highlighting depends on the edit (a block comment opened
+    /// above) keep their old highlighting until the document is synced
+    /// again (see [`Self::sync_document`]).
+    pub fn record_edit(&mut self, line: usize, removed: usize, inserted: usize) {
+        if removed == 0 && inserted == 0 {
+            self.lines.remove(&line);
+            return;
         }
+        let lines = std::mem::take(&mut self.lines);
+        self.lines = lines
+            .into_iter()
+            .filter(|&(line_idx, _)| line_idx < line || line_idx > line + removed)
+            .map(|(line_idx, cached)| {
+                let line_idx = if line_idx > line {
+                    line_idx - removed + inserted
+                } else {
+                    line_idx
+                };
+                (line_idx, cached)
+            })
+            .collect();
     }
 
     /// Invalidate entire cache.
     pub fn invalidate_all(&mut self) {
         self.lines.clear();
+        self.document_revision = None;
     }
 
-    /// Change theme (light/dark).
+    /// Change theme (light/dark). Also picks up changed user highlight styles.
     pub fn set_light_theme(&mut self, is_light: bool) {
-        if self.is_light_theme != is_light {
+        let generation = self.syntax_highlighter.styles_generation();
+        if self.is_light_theme != is_light || self.styles_generation != generation {
             self.is_light_theme = is_light;
+            self.styles_generation = generation;
             self.invalidate_all();
         }
     }
@@ -695,3 +1205,272 @@ impl LineHighlighter for HighlightCache {
         HighlightCache::has_syntax(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight_style(name: &str) -> Style {
+        let index = HIGHLIGHT_NAMES.iter().position(|n| *n == name).unwrap();
+        global_highlighter().style_for_highlight(index, false)
+    }
+
+    #[test]
+    fn test_comment_style_for_supported_languages() {
+        for lang in SUPPORTED_LANGUAGES {
+            assert_eq!(comment_style(lang).is_none(), *lang == "json", "{}", lang);
+        }
+        assert_eq!(
+            detect_language(Path::new("main.rs")).and_then(comment_style),
+            Some(CommentStyle::Line("//"))
+        );
+    }
+
+    #[test]
+    fn test_detect_language_by_filename() {
+        let cases = [
+            ("Dockerfile", Some("dockerfile")),
+            ("/src/app/Dockerfile.dev", Some("dockerfile")),
+            ("Containerfile", Some("dockerfile")),
+            ("build.dockerfile", Some("dockerfile")),
+            ("Makefile", Some("make")),
+            ("GNUmakefile", Some("make")),
+            ("CMakeLists.txt", Some("cmake")),
+            ("notes.txt", None),
+            ("README", None),
+            ("init.lua", Some("lua")),
+            ("build.zig", Some("zig")),
+            ("build.gradle.kts", Some("kotlin")),
+            ("schema.sql", Some("sql")),
+        ];
+        for (path, language) in cases {
+            assert_eq!(detect_language(Path::new(path)), language, "{}", path);
+        }
+        assert_eq!(
+            detect_language(Path::new("Dockerfile")).and_then(comment_style),
+            Some(CommentStyle::Line("#"))
+        );
+    }
+
+    #[test]
+    fn test_detect_language_from_shebang() {
+        let cases = [
+            ("#!/bin/sh", Some("bash")),
+            ("#!/usr/bin/env bash", Some("bash")),
+            ("#! /bin/zsh -e", Some("bash")),
+            ("#!/usr/bin/python3", Some("python")),
+            ("#!/usr/bin/env python3.12", Some("python")),
+            ("#!/usr/bin/env -S python3 -u", Some("python")),
+            (
+                "#!/usr/bin/env -S NODE_ENV=production node",
+                Some("javascript"),
+            ),
+            ("#!/usr/local/bin/ruby -w", Some("ruby")),
+            ("#!/usr/bin/perl", Some("perl")),
+            ("<?php", Some("php")),
+            ("<?php echo 'hi';", Some("php")),
+            ("#!/usr/bin/env awk -f", None),
+            ("#!", None),
+            ("# comment", None),
+            ("", None),
+        ];
+        for (line, language) in cases {
+            assert_eq!(detect_language_from_content(line), language, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_log_files_use_log_highlighting() {
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax_from_path(Path::new("/var/log/app.log"));
+        assert_eq!(cache.current_syntax(), Some(LOG_LANGUAGE));
+
+        let segments = cache.get_line_segments(0, "WARN low disk");
+        assert_eq!(segments[0].0, "WARN");
+        assert_eq!(segments[0].1.fg, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn test_block_comment_spanning_lines() {
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax("rust");
+        let comment = highlight_style("comment");
+
+        let text = "fn main() {}\n/* start\nlet x = 1;\nend */\nlet y = 2;\n";
+        cache.sync_document(1, text.len(), || text.to_string());
+        assert_eq!(
+            cache.get_line_segments(2, "let x = 1;"),
+            &[("let x = 1;".to_string(), comment)]
+        );
+        assert_ne!(cache.get_line_segments(4, "let y = 2;")[0].1, comment);
+
+        // Removing the comment start re-highlights the lines below it
+        let text = text.replace("/* start", "// start");
+        cache.sync_document(2, text.len(), || text);
+        assert_ne!(cache.get_line_segments(2, "let x = 1;")[0].1, comment);
+    }
+
+    #[test]
+    fn test_record_edit_moves_lines_below() {
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax("rust");
+        let comment = highlight_style("comment");
+
+        let text = "/* a\nb */\nlet x = 1;\n";
+        cache.sync_document(1, text.len(), || text.to_string());
+        cache.get_line_segments(1, "b */");
+        cache.get_line_segments(2, "let x = 1;");
+
+        // A line break typed on the first line moves the comment end down;
+        // its highlighting moves along without parsing the document again
+        cache.record_edit(0, 0, 1);
+        assert_eq!(
+            cache.get_line_segments(2, "b */"),
+            &[("b */".to_string(), comment)]
+        );
+        assert_ne!(cache.get_line_segments(3, "let x = 1;")[0].1, comment);
+
+        // Joining the lines again drops the joined line
+        cache.record_edit(1, 1, 0);
+        assert!(!cache.lines.contains_key(&1));
+        assert!(cache.lines.contains_key(&2));
+
+        cache.invalidate_range(0, usize::MAX);
+        assert!(cache.lines.is_empty());
+    }
+
+    #[test]
+    fn test_markdown_code_fences_use_injected_languages() {
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax("markdown");
+        let keyword = highlight_style("keyword");
+
+        let text = "# Example\n\n```rust\nfn main() {}\n```\n\n```python\ndef main(): pass\n```\n\n```unknown\nfn main() {}\n```\n";
+        cache.sync_document(1, text.len(), || text.to_string());
+        assert_eq!(
+            cache.get_line_segments(3, "fn main() {}")[0],
+            ("fn".to_string(), keyword)
+        );
+        assert_eq!(
+            cache.get_line_segments(7, "def main(): pass")[0],
+            ("def".to_string(), keyword)
+        );
+
+        // Unknown languages stay plain text
+        assert_eq!(
+            cache.get_line_segments(11, "fn main() {}"),
+            &[("fn main() {}".to_string(), Style::default())]
+        );
+    }
+
+    #[test]
+    fn test_php_highlights_html_outside_tags() {
+        assert!(global_highlighter().get_config("php").is_some());
+
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax("php");
+        let tag = highlight_style("tag");
+
+        let text = "<div>\n<?php echo 'hi'; ?>\n</div>\n";
+        cache.sync_document(1, text.len(), || text.to_string());
+        assert!(cache
+            .get_line_segments(0, "<div>")
+            .contains(&("div".to_string(), tag)));
+        assert!(cache
+            .get_line_segments(2, "</div>")
+            .contains(&("div".to_string(), tag)));
+        assert!(cache
+            .get_line_segments(1, "<?php echo 'hi'; ?>")
+            .contains(&("echo".to_string(), highlight_style("keyword"))));
+    }
+
+    #[test]
+    fn test_kotlin_highlights_keywords() {
+        assert!(global_highlighter().get_config("kotlin").is_some());
+
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax("kotlin");
+        let text = "fun main() {}\n";
+        cache.sync_document(1, text.len(), || text.to_string());
+        assert_eq!(
+            cache.get_line_segments(0, "fun main() {}")[0],
+            ("fun".to_string(), highlight_style("keyword"))
+        );
+    }
+
+    #[test]
+    fn test_injection_language_names() {
+        assert_eq!(injection_language("rust"), Some("rust"));
+        assert_eq!(injection_language("Python"), Some("python"));
+        assert_eq!(injection_language("rs"), Some("rust"));
+        assert_eq!(injection_language("shell"), Some("bash"));
+        assert_eq!(injection_language("yml"), Some("yaml"));
+        assert_eq!(injection_language("brainfuck"), None);
+    }
+
+    #[test]
+    fn test_user_styles_override_palette() {
+        let highlighter: &'static TreeSitterHighlighter =
+            Box::leak(Box::new(TreeSitterHighlighter::new()));
+        let keyword = HIGHLIGHT_NAMES
+            .iter()
+            .position(|n| *n == "keyword")
+            .unwrap();
+        let mut cache = HighlightCache::new(highlighter, false);
+        cache.set_syntax("rust");
+        let text = "fn main() {}";
+        cache.sync_document(1, text.len(), || text.to_string());
+        assert_eq!(
+            cache.get_line_segments(0, text)[0].1,
+            highlighter.style_for_highlight(keyword, false)
+        );
+
+        let errors = highlighter.set_style_overrides([("keyword", "#ff0000"), ("bogus", "bold")]);
+        assert_eq!(errors.len(), 1);
+        let red = Style::default().fg(Color::Rgb(255, 0, 0));
+        assert_eq!(highlighter.style_for_highlight(keyword, true), red);
+
+        // Cached lines are rebuilt by the theme hook
+        cache.set_light_theme(false);
+        assert_eq!(cache.get_line_segments(0, text)[0].1, red);
+    }
+
+    #[test]
+    fn test_misspellings_in_comments_strings_and_prose() {
+        let dir = tempfile::tempdir().unwrap();
+        let words = dir.path().join("words");
+        std::fs::write(&words, "the\nvalue\nname\nexample\n").unwrap();
+        let dictionary = Arc::new(Dictionary::new(vec![words], None));
+        let misspelled = |cache: &mut HighlightCache, line: usize, text: &str| -> Vec<String> {
+            cache
+                .misspellings(line, text)
+                .iter()
+                .map(|range| text[range.clone()].to_string())
+                .collect()
+        };
+
+        let mut cache = HighlightCache::new(global_highlighter(), false);
+        cache.set_syntax("rust");
+        cache.set_dictionary(Some(dictionary.clone()));
+        let text = "/* the valeu\nname */\nlet nmae = \"teh name\";\n";
+        cache.sync_document(1, text.len(), || text.to_string());
+        assert_eq!(misspelled(&mut cache, 0, "/* the valeu"), ["valeu"]);
+        assert!(misspelled(&mut cache, 1, "name */").is_empty());
+        assert_eq!(
+            misspelled(&mut cache, 2, "let nmae = \"teh name\";"),
+            ["teh"]
+        );
+
+        dictionary.add_word("valeu").unwrap();
+        assert!(misspelled(&mut cache, 0, "/* the valeu").is_empty());
+
+        cache.set_syntax("markdown");
+        let text = "The exampel\n\n```\nsome codee\n```\n";
+        cache.sync_document(2, text.len(), || text.to_string());
+        assert_eq!(misspelled(&mut cache, 0, "The exampel"), ["exampel"]);
+        assert!(misspelled(&mut cache, 3, "some codee").is_empty());
+
+        cache.set_dictionary(None);
+        assert!(misspelled(&mut cache, 0, "The exampel").is_empty());
+    }
+}
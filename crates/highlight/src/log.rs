@@ -0,0 +1,457 @@
+//! Log file highlighting by line severity.
+//!
+//! Lines are colored by the first severity word they contain (ERROR, WARN,
+//! INFO, DEBUG, TRACE and common aliases), with timestamps dimmed and the
+//! keys of JSON payloads colored. Lines are scanned by hand, without regexes.
+
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+
+use crate::{LineHighlighter, MAX_CACHE_SIZE};
+
+/// Language name used for log files by `detect_language`
+pub const LOG_LANGUAGE: &str = "log";
+
+/// Severity of a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Severity {
+    /// Severity named by a word (case-insensitive)
+    fn from_word(word: &str) -> Option<Self> {
+        const WORDS: &[(&str, Severity)] = &[
+            ("error", Severity::Error),
+            ("err", Severity::Error),
+            ("fatal", Severity::Error),
+            ("critical", Severity::Error),
+            ("crit", Severity::Error),
+            ("panic", Severity::Error),
+            ("warn", Severity::Warn),
+            ("warning", Severity::Warn),
+            ("info", Severity::Info),
+            ("notice", Severity::Info),
+            ("debug", Severity::Debug),
+            ("trace", Severity::Trace),
+        ];
+        WORDS
+            .iter()
+            .find(|(name, _)| word.eq_ignore_ascii_case(name))
+            .map(|&(_, severity)| severity)
+    }
+
+    /// Lowercase name of the severity
+    fn name(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+
+    /// Style of a line with this severity
+    fn line_style(self) -> Style {
+        match self {
+            Self::Error => Style::default().fg(Color::Red),
+            Self::Warn => Style::default().fg(Color::Yellow),
+            Self::Info => Style::default(),
+            Self::Debug => Style::default().fg(Color::Cyan),
+            Self::Trace => Style::default().fg(Color::DarkGray),
+        }
+    }
+
+    /// Style of the severity word itself
+    fn word_style(self) -> Style {
+        let style = match self {
+            Self::Info => Style::default().fg(Color::Green),
+            _ => self.line_style(),
+        };
+        style.add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Style of timestamps
+const TIMESTAMP_STYLE: Style = Style::new().fg(Color::DarkGray);
+
+/// Style of JSON keys
+const JSON_KEY_STYLE: Style = Style::new().fg(Color::Blue);
+
+/// Find the severity word of a log line: the first one wins.
+///
+/// Words are runs of letters, digits and `_`, so `[ERROR]`, `level=warn`
+/// and `"level":"info"` are detected while `error_count` is not.
+/// Returns the byte range of the word and its severity.
+fn find_severity(line: &str) -> Option<(usize, usize, Severity)> {
+    words(line).find_map(|(start, word)| {
+        Severity::from_word(word).map(|severity| (start, start + word.len(), severity))
+    })
+}
+
+/// Severity of a log line by its first severity word, as a lowercase
+/// name (`error`, `warn`, `info`, `debug`, `trace`)
+pub fn line_severity(line: &str) -> Option<&'static str> {
+    find_severity(line).map(|(_, _, severity)| severity.name())
+}
+
+/// Highlighted segments of a log line (concatenated, they equal the line)
+pub(crate) fn log_line_segments(line: &str) -> Vec<(String, Style)> {
+    let severity = find_severity(line);
+    let base = severity.map_or(Style::default(), |(_, _, severity)| severity.line_style());
+
+    let mut spans = timestamp_spans(line);
+    if let Some((start, end, severity)) = severity {
+        spans.push((start, end, severity.word_style()));
+    }
+    spans.extend(
+        json_key_spans(line)
+            .into_iter()
+            .map(|(start, end)| (start, end, JSON_KEY_STYLE)),
+    );
+    spans.sort_by_key(|&(start, _, _)| start);
+
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for (start, end, style) in spans {
+        // Overlapping spans: the earlier one wins
+        if start < pos {
+            continue;
+        }
+        if start > pos {
+            segments.push((line[pos..start].to_string(), base));
+        }
+        segments.push((line[start..end].to_string(), style));
+        pos = end;
+    }
+    if pos < line.len() || segments.is_empty() {
+        segments.push((line[pos..].to_string(), base));
+    }
+    segments
+}
+
+/// Words with their byte offsets
+fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut rest = 0;
+    std::iter::from_fn(move || {
+        let start = rest + line[rest..].find(is_word)?;
+        let end = line[start..]
+            .find(|c| !is_word(c))
+            .map_or(line.len(), |len| start + len);
+        rest = end;
+        Some((start, &line[start..end]))
+    })
+}
+
+/// Byte ranges of ISO 8601 dates and times (`2024-05-01T12:00:00.123Z`,
+/// `2024-05-01 12:00:00,123`, `12:00:00`)
+fn timestamp_spans(line: &str) -> Vec<(usize, usize, Style)> {
+    let bytes = line.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let at_boundary = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if at_boundary && bytes[i].is_ascii_digit() {
+            if let Some(len) = timestamp_len(&bytes[i..]) {
+                spans.push((i, i + len, TIMESTAMP_STYLE));
+                i += len;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    spans
+}
+
+/// Length of a date, time or date-time at the start of bytes
+fn timestamp_len(bytes: &[u8]) -> Option<usize> {
+    let date = date_len(bytes);
+    let mut len = date.unwrap_or(0);
+    if date.is_some() && matches!(bytes.get(len), Some(b'T' | b' ')) {
+        if let Some(time) = time_len(&bytes[len + 1..]) {
+            len += 1 + time;
+        }
+    } else if date.is_none() {
+        len = time_len(bytes)?;
+    }
+    (!bytes.get(len).is_some_and(u8::is_ascii_alphanumeric)).then_some(len)
+}
+
+/// Length of a `YYYY-MM-DD` date at the start of bytes
+fn date_len(bytes: &[u8]) -> Option<usize> {
+    digits_with_separators(bytes, &[4, 2, 2], b'-')
+}
+
+/// Length of a `HH:MM:SS[.fff][Z|+HH:MM]` time at the start of bytes
+fn time_len(bytes: &[u8]) -> Option<usize> {
+    let mut len = digits_with_separators(bytes, &[2, 2, 2], b':')?;
+    if matches!(bytes.get(len), Some(b'.' | b',')) {
+        let fraction = count_digits(&bytes[len + 1..]);
+        if fraction > 0 {
+            len += 1 + fraction;
+        }
+    }
+    match bytes.get(len) {
+        Some(b'Z') => len += 1,
+        Some(b'+' | b'-') => {
+            if let Some(offset) = digits_with_separators(&bytes[len + 1..], &[2, 2], b':')
+                .or_else(|| (count_digits(&bytes[len + 1..]) == 4).then_some(4))
+            {
+                len += 1 + offset;
+            }
+        }
+        _ => {}
+    }
+    Some(len)
+}
+
+/// Length of digit groups of given sizes joined by a separator
+fn digits_with_separators(bytes: &[u8], groups: &[usize], separator: u8) -> Option<usize> {
+    let mut len = 0;
+    for (idx, &group) in groups.iter().enumerate() {
+        if idx > 0 {
+            if bytes.get(len) != Some(&separator) {
+                return None;
+            }
+            len += 1;
+        }
+        if count_digits(&bytes[len..]) != group {
+            return None;
+        }
+        len += group;
+    }
+    Some(len)
+}
+
+/// Number of leading ASCII digits
+fn count_digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+/// Byte ranges of JSON object keys (quoted strings followed by `:`)
+/// after the first `{` of the line
+fn json_key_spans(line: &str) -> Vec<(usize, usize)> {
+    let Some(object_start) = line.find('{') else {
+        return Vec::new();
+    };
+    let bytes = line.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = object_start;
+    while i < bytes.len() {
+        if bytes[i] != b'"' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i] != b'"' {
+            i += if bytes[i] == b'\\' { 2 } else { 1 };
+        }
+        let end = (i + 1).min(bytes.len());
+        let after = line[end..].trim_start();
+        if after.starts_with(':') {
+            spans.push((start, end));
+        }
+        i = end;
+    }
+    spans
+}
+
+/// Line highlighter for log files and the log viewer.
+///
+/// Caches segments per line like `HighlightCache`.
+pub struct LogHighlighter {
+    /// Highlighted lines: line number -> (vector of segments, last access time)
+    lines: HashMap<usize, (Vec<(String, Style)>, u64)>,
+    /// Access counter for LRU
+    access_counter: u64,
+}
+
+impl LogHighlighter {
+    /// Create a new log highlighter.
+    pub fn new() -> Self {
+        Self {
+            lines: HashMap::new(),
+            access_counter: 0,
+        }
+    }
+
+    /// Remove oldest entries from cache (LRU).
+    fn evict_lru(&mut self) {
+        let evict_count = MAX_CACHE_SIZE / 5;
+
+        let mut entries: Vec<(usize, u64)> = self
+            .lines
+            .iter()
+            .map(|(line_idx, (_, access_time))| (*line_idx, *access_time))
+            .collect();
+
+        entries.sort_by_key(|(_, access_time)| *access_time);
+
+        for (line_idx, _) in entries.iter().take(evict_count) {
+            self.lines.remove(line_idx);
+        }
+    }
+}
+
+impl Default for LogHighlighter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineHighlighter for LogHighlighter {
+    fn get_line_segments(&mut self, line_idx: usize, line_text: &str) -> &[(String, Style)] {
+        self.access_counter += 1;
+
+        if let Some((_, access_time)) = self.lines.get_mut(&line_idx) {
+            *access_time = self.access_counter;
+        } else {
+            let segments = log_line_segments(line_text);
+
+            if self.lines.len() >= MAX_CACHE_SIZE {
+                self.evict_lru();
+            }
+
+            self.lines.insert(line_idx, (segments, self.access_counter));
+        }
+
+        &self
+            .lines
+            .get(&line_idx)
+            .expect("line was just inserted or updated above")
+            .0
+    }
+
+    fn invalidate_from(&mut self, line: usize) {
+        self.lines.retain(|&line_idx, _| line_idx < line);
+    }
+
+    fn invalidate_all(&mut self) {
+        self.lines.clear();
+    }
+
+    fn has_syntax(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text of segments with given style
+    fn styled(segments: &[(String, Style)], style: Style) -> Vec<&str> {
+        segments
+            .iter()
+            .filter(|(_, segment_style)| *segment_style == style)
+            .map(|(text, _)| text.as_str())
+            .collect()
+    }
+
+    fn joined(segments: &[(String, Style)]) -> String {
+        segments.iter().map(|(text, _)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_find_severity() {
+        let lines = [
+            (
+                "[12:00:01] ERROR Failed to open file",
+                Some(Severity::Error),
+            ),
+            (
+                "2024-05-01 12:00:00,123 - app - WARNING - disk low",
+                Some(Severity::Warn),
+            ),
+            (
+                "level=info msg=\"started\" error_count=0",
+                Some(Severity::Info),
+            ),
+            (
+                "<7>Jun 1 12:00:00 kernel: [debug] probing",
+                Some(Severity::Debug),
+            ),
+            (
+                "{\"level\":\"trace\",\"msg\":\"tick\"}",
+                Some(Severity::Trace),
+            ),
+            ("E: unable to locate package", None),
+            ("error_count=3 warnings_total=1", None),
+        ];
+        for (line, severity) in lines {
+            let detected = find_severity(line).map(|(_, _, severity)| severity);
+            assert_eq!(detected, severity, "{}", line);
+        }
+        assert_eq!(line_severity("app - WARNING - disk low"), Some("warn"));
+    }
+
+    #[test]
+    fn test_timestamps_are_dimmed() {
+        // Output of a tracing subscriber with ANSI colors stripped
+        let line = "2024-05-01T12:00:00.123456Z  INFO server::http: listening on 0.0.0.0:8080";
+        let segments = log_line_segments(line);
+        assert_eq!(joined(&segments), line);
+        assert_eq!(
+            styled(&segments, TIMESTAMP_STYLE),
+            vec!["2024-05-01T12:00:00.123456Z"]
+        );
+        assert_eq!(styled(&segments, Severity::Info.word_style()), vec!["INFO"]);
+
+        let line = "[12:34:56] WARN  retrying at 2024-05-01 13:00:00+02:00";
+        let segments = log_line_segments(line);
+        assert_eq!(
+            styled(&segments, TIMESTAMP_STYLE),
+            vec!["12:34:56", "2024-05-01 13:00:00+02:00"]
+        );
+        assert_eq!(
+            styled(&segments, Severity::Warn.line_style()),
+            vec!["[", "] ", "  retrying at "]
+        );
+
+        // Versions and addresses are not timestamps
+        assert!(timestamp_spans("v1.2.3 at 10.0.0.1:8080 id 12:34:567").is_empty());
+    }
+
+    #[test]
+    fn test_json_keys_are_colored() {
+        let line = r#"ERROR request failed {"status": 500, "path": "/a:b", "err": "say \"hi\""}"#;
+        let segments = log_line_segments(line);
+        assert_eq!(joined(&segments), line);
+        assert_eq!(
+            styled(&segments, JSON_KEY_STYLE),
+            vec![r#""status""#, r#""path""#, r#""err""#]
+        );
+        assert_eq!(
+            styled(&segments, Severity::Error.word_style()),
+            vec!["ERROR"]
+        );
+    }
+
+    #[test]
+    fn test_plain_lines_keep_default_style() {
+        assert_eq!(
+            log_line_segments("  at main.rs:10"),
+            vec![("  at main.rs:10".to_string(), Style::default())]
+        );
+        assert_eq!(
+            log_line_segments(""),
+            vec![(String::new(), Style::default())]
+        );
+
+        let mut highlighter = LogHighlighter::new();
+        let segments = highlighter.get_line_segments(0, "DEBUG cache hit").to_vec();
+        assert_eq!(
+            segments[0],
+            ("DEBUG".to_string(), Severity::Debug.word_style())
+        );
+        highlighter.invalidate_from(0);
+        assert!(highlighter.lines.is_empty());
+    }
+}
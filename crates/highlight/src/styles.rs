@@ -0,0 +1,134 @@
+//! User overrides of syntax highlight styles.
+
+use std::collections::HashMap;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::HIGHLIGHT_NAMES;
+
+/// Highlight styles set by the user, by highlight name
+#[derive(Debug, Default)]
+pub(crate) struct StyleOverrides {
+    styles: HashMap<String, Style>,
+}
+
+impl StyleOverrides {
+    /// Parse `(name, spec)` pairs. Invalid entries are skipped and returned
+    /// as error messages
+    pub fn parse<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> (Self, Vec<String>) {
+        let mut styles = HashMap::new();
+        let mut errors = Vec::new();
+        for (name, spec) in entries {
+            if !HIGHLIGHT_NAMES.contains(&name) {
+                errors.push(format!("unknown highlight name '{}'", name));
+                continue;
+            }
+            match parse_style(spec) {
+                Ok(style) => {
+                    styles.insert(name.to_string(), style);
+                }
+                Err(error) => errors.push(format!("{}: {}", name, error)),
+            }
+        }
+        (Self { styles }, errors)
+    }
+
+    /// Style for highlight name, falling back to its parent names
+    /// (`function.method` uses the `function` style if not set itself).
+    ///
+    /// Styles without a color keep `palette_fg`.
+    pub fn style(&self, name: &str, palette_fg: Color) -> Option<Style> {
+        let mut name = name;
+        loop {
+            if let Some(style) = self.styles.get(name) {
+                return Some(if style.fg.is_none() {
+                    style.fg(palette_fg)
+                } else {
+                    *style
+                });
+            }
+            name = &name[..name.rfind('.')?];
+        }
+    }
+}
+
+/// Parse a style spec: an optional `#rrggbb` color followed by modifiers
+/// (`bold`, `italic`, `underlined`, `dim`), e.g. `"#c678dd bold"`
+fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut empty = true;
+    for word in spec.split_whitespace() {
+        empty = false;
+        if let Some(hex) = word.strip_prefix('#') {
+            let color = parse_hex_color(hex).ok_or_else(|| format!("invalid color '{}'", word))?;
+            style = style.fg(color);
+            continue;
+        }
+        let modifier = match word.to_lowercase().as_str() {
+            "bold" => Modifier::BOLD,
+            "italic" => Modifier::ITALIC,
+            "underlined" => Modifier::UNDERLINED,
+            "dim" => Modifier::DIM,
+            _ => return Err(format!("unknown modifier '{}'", word)),
+        };
+        style = style.add_modifier(modifier);
+    }
+    if empty {
+        return Err("empty style".to_string());
+    }
+    Ok(style)
+}
+
+/// Parse `rrggbb` hex digits
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style_specs() {
+        assert_eq!(
+            parse_style("#c678dd bold"),
+            Ok(Style::default()
+                .fg(Color::Rgb(0xc6, 0x78, 0xdd))
+                .add_modifier(Modifier::BOLD))
+        );
+        assert_eq!(
+            parse_style("italic"),
+            Ok(Style::default().add_modifier(Modifier::ITALIC))
+        );
+        assert!(parse_style("#12345").is_err());
+        assert!(parse_style("#c678dd blink").is_err());
+        assert!(parse_style("").is_err());
+    }
+
+    #[test]
+    fn test_overrides_fall_back_to_parent_names() {
+        let (overrides, errors) = StyleOverrides::parse([
+            ("function", "#ff0000"),
+            ("comment", "italic"),
+            ("keyword", "#zzzzzz"),
+            ("bogus", "#ffffff"),
+        ]);
+        assert_eq!(errors.len(), 2);
+
+        let red = Style::default().fg(Color::Rgb(255, 0, 0));
+        assert_eq!(overrides.style("function.method", Color::Blue), Some(red));
+        assert_eq!(
+            overrides.style("comment", Color::Gray),
+            Some(
+                Style::default()
+                    .fg(Color::Gray)
+                    .add_modifier(Modifier::ITALIC)
+            )
+        );
+        assert_eq!(overrides.style("keyword", Color::Magenta), None);
+    }
+}
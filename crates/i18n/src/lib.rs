@@ -38,6 +38,14 @@ pub trait Translation: Send + Sync {
     fn fm_search_prompt(&self) -> &str;
     fn fm_no_results(&self) -> &str;
     fn fm_operation_cancelled(&self) -> &str;
+    fn fm_symlink_prompt(&self, name: &str) -> String;
+    fn fm_broken_symlink(&self, name: &str, target: &str) -> String;
+    fn fm_trash_empty(&self) -> &str;
+    fn fm_sort_by_name(&self) -> &str;
+    fn fm_sort_by_size(&self) -> &str;
+    fn fm_sort_by_modified(&self) -> &str;
+    fn fm_hidden_shown(&self) -> &str;
+    fn fm_hidden_hidden(&self) -> &str;
 
     // Modal buttons
     fn modal_yes(&self) -> &str;
@@ -50,6 +58,140 @@ pub trait Translation: Send + Sync {
     fn panel_editor(&self, filename: &str) -> String;
     fn panel_terminal(&self) -> &str;
     fn panel_welcome(&self) -> &str;
+    fn panel_scratchpad(&self) -> &str;
+    fn welcome_recent_projects(&self) -> &str;
+    fn welcome_recent_files(&self) -> &str;
+    fn welcome_recent_hint(&self) -> &str;
+    fn panel_help(&self) -> &str;
+    fn help_filter_hint(&self) -> &str;
+    fn help_no_matches(&self) -> &str;
+    /// Title of a group of application keys (`panels`, `navigation`)
+    fn help_category(&self, category: &str) -> &str;
+    /// Title of the keys of a panel type by its name (`file_manager`)
+    fn help_section(&self, panel: &str) -> &str;
+    /// Description of a key handled by a panel (`fm_copy`)
+    fn key_help(&self, key: &str) -> &str;
+    fn log_level(&self) -> &str;
+    fn log_filter(&self) -> &str;
+    fn log_showing(&self, shown: &str, total: &str) -> String;
+    fn log_following(&self) -> &str;
+    fn log_paused(&self) -> &str;
+    fn log_filter_hint(&self) -> &str;
+    fn sysmon_title(&self) -> &str;
+    fn sysmon_cpu(&self) -> &str;
+    fn sysmon_memory(&self) -> &str;
+    fn sysmon_load(&self) -> &str;
+    fn sysmon_name(&self) -> &str;
+    fn sysmon_sort(&self) -> &str;
+    fn sysmon_sort_tree(&self) -> &str;
+    fn sysmon_hint(&self) -> &str;
+    fn sysmon_no_terminals(&self) -> &str;
+    fn sysmon_signal_sent(&self, signal: &str, pid: &str, name: &str) -> String;
+    fn sysmon_signal_failed(&self, signal: &str, pid: &str) -> String;
+    fn git_title(&self) -> &str;
+    fn git_staged(&self) -> &str;
+    fn git_unstaged(&self) -> &str;
+    fn git_not_repository(&self) -> &str;
+    fn git_no_changes(&self) -> &str;
+    fn git_hint(&self) -> &str;
+    fn git_commit_title(&self) -> &str;
+    fn git_nothing_staged(&self) -> &str;
+    fn git_commit_prompt(&self, count: usize, branch: &str) -> String;
+    fn git_committed(&self, summary: &str) -> String;
+    fn git_commit_failed(&self, error: &str) -> String;
+    fn git_error(&self, error: &str) -> String;
+    fn modal_multiline_hint(&self) -> &str;
+    fn diff_title(&self) -> &str;
+    fn diff_identical(&self) -> &str;
+    fn notifications_title(&self) -> &str;
+    fn notifications_empty(&self) -> &str;
+    fn bookmarks_title(&self) -> &str;
+    fn bookmarks_empty(&self) -> &str;
+    fn bookmarks_hint(&self) -> &str;
+    fn bookmarks_missing(&self) -> &str;
+    fn diff_hint(&self) -> &str;
+    fn diff_select_two(&self) -> &str;
+    fn diff_hunk_position(&self, current: usize, total: usize) -> String;
+    fn diff_hunk_count(&self, count: usize) -> String;
+    fn diff_binary_file(&self, path: &str) -> String;
+    fn diff_recovered(&self) -> &str;
+    fn diff_failed(&self, error: &str) -> String;
+    fn markdown_preview_title(&self, name: &str) -> String;
+    fn search_title(&self) -> &str;
+    fn search_prompt(&self) -> &str;
+    fn search_hint(&self) -> &str;
+    fn search_running(&self) -> &str;
+    fn search_no_matches(&self) -> &str;
+    fn search_truncated(&self) -> &str;
+    fn search_more_in_file(&self) -> &str;
+    fn search_summary(&self, matches: usize, files: usize) -> String;
+    fn search_invalid_regex(&self, error: &str) -> String;
+    fn compare_title(&self) -> &str;
+    fn compare_running(&self) -> &str;
+    fn compare_cancelled(&self) -> &str;
+    fn compare_truncated(&self) -> &str;
+    fn compare_only_left(&self) -> &str;
+    fn compare_only_right(&self) -> &str;
+    fn compare_newer_left(&self) -> &str;
+    fn compare_newer_right(&self) -> &str;
+    fn compare_contents_differ(&self) -> &str;
+    fn compare_kind_differs(&self) -> &str;
+    fn compare_contents(&self) -> &str;
+    fn compare_hint(&self) -> &str;
+    fn compare_found(&self, count: usize) -> String;
+    fn compare_summary(&self, differences: usize, same: usize) -> String;
+    fn compare_size(&self, left: &str, right: &str) -> String;
+    fn compare_copied_right(&self, path: &str) -> String;
+    fn compare_copied_left(&self, path: &str) -> String;
+    fn compare_copy_failed(&self, path: &str, error: &str) -> String;
+    fn compare_not_directory(&self, path: &str) -> String;
+    fn modal_compare_title(&self) -> &str;
+    fn duplicates_title(&self) -> &str;
+    fn duplicates_recursive(&self) -> &str;
+    fn duplicates_skip_ignored(&self) -> &str;
+    fn duplicates_none(&self) -> &str;
+    fn duplicates_keep_one(&self) -> &str;
+    fn duplicates_hint(&self) -> &str;
+    fn duplicates_listing(&self, count: usize) -> String;
+    fn duplicates_hashing(&self, percent: usize) -> String;
+    fn duplicates_found(&self, count: usize) -> String;
+    fn duplicates_summary(&self, count: usize, size: &str) -> String;
+    fn duplicates_set(&self, count: usize, size: &str) -> String;
+    fn duplicates_truncated(&self, count: usize) -> String;
+    fn duplicates_marked(&self, count: usize) -> String;
+    fn permissions_others(&self) -> &str;
+    fn permissions_special(&self) -> &str;
+    fn permissions_read(&self) -> &str;
+    fn permissions_write(&self) -> &str;
+    fn permissions_execute(&self) -> &str;
+    fn permissions_setuid(&self) -> &str;
+    fn permissions_setgid(&self) -> &str;
+    fn permissions_sticky(&self) -> &str;
+    fn permissions_octal(&self) -> &str;
+    fn permissions_recursive(&self) -> &str;
+    fn permissions_apply(&self) -> &str;
+    fn permissions_recursive_title(&self) -> &str;
+    fn permissions_title(&self, name: &str) -> String;
+    fn permissions_title_multiple(&self, count: usize) -> String;
+    fn permissions_recursive_confirm(&self, count: usize) -> String;
+    fn status_permissions_cancelled(&self, count: usize) -> String;
+    fn status_permissions_changed(&self, count: usize) -> String;
+    fn status_permissions_failed(
+        &self,
+        failed: usize,
+        total: usize,
+        name: &str,
+        error: &str,
+    ) -> String;
+    fn modal_compare_prompt(&self, name: &str) -> String;
+    fn task_title(&self) -> &str;
+    fn task_running(&self) -> &str;
+    fn task_stopped(&self) -> &str;
+    fn task_no_problems(&self) -> &str;
+    fn task_hint(&self) -> &str;
+    fn task_exited(&self, code: i32) -> String;
+    fn task_problems(&self, errors: usize, warnings: usize) -> String;
+    fn task_failed_to_start(&self, error: &str) -> String;
 
     // Editor
     fn editor_close_unsaved(&self) -> &str;
@@ -65,8 +207,18 @@ pub trait Translation: Send + Sync {
     fn editor_close_conflict(&self) -> &str;
     fn editor_close_conflict_question(&self) -> &str;
     fn editor_reload_from_disk(&self) -> &str;
+    fn editor_external_conflict_question(&self) -> &str;
+    fn editor_keep_mine(&self) -> &str;
+    fn editor_show_diff(&self) -> &str;
+    fn editor_review_changes(&self) -> &str;
+    fn editor_reloaded_external(&self) -> &str;
+    fn editor_diff_title(&self, name: &str) -> String;
     fn editor_save_error(&self, error: &str) -> String;
     fn editor_saved(&self, path: &str) -> String;
+    fn editor_saved_in_place(&self, reason: &str) -> String;
+    fn editor_invalid_bytes(&self, encoding: &str) -> String;
+    fn editor_encoding_reopened(&self, encoding: &str) -> String;
+    fn editor_encoding_convert(&self, encoding: &str) -> String;
     fn editor_file_opened(&self, filename: &str) -> String;
     fn editor_search_title(&self) -> &str;
     fn editor_search_prompt(&self) -> &str;
@@ -76,9 +228,63 @@ pub trait Translation: Send + Sync {
     fn editor_search_match_info(&self, current: usize, total: usize) -> String;
     fn editor_search_no_matches(&self) -> &str;
     fn editor_deletion_marker(&self, count: usize) -> String;
+    fn editor_indent_tabs(&self) -> &str;
+    fn editor_indent_spaces(&self, width: usize) -> String;
+    fn editor_indent_converted(&self, style: &str, count: usize) -> String;
+    fn editor_stripped_whitespace(&self, count: usize) -> String;
+    fn editor_fixed_final_newline(&self) -> &str;
+    fn editor_normalized_line_endings(&self, count: usize) -> String;
+    fn editor_saved_with_cleanup(&self, changes: &str) -> String;
+    fn editor_line_ending_set(&self, ending: &str) -> String;
+    fn editor_whitespace_shown(&self) -> &str;
+    fn editor_whitespace_hidden(&self) -> &str;
+    fn editor_word_wrap_on(&self) -> &str;
+    fn editor_word_wrap_off(&self) -> &str;
+    fn editor_large_file_features_enabled(&self) -> &str;
+    fn editor_read_only_rejected(&self) -> &str;
+    fn editor_edit_anyway(&self) -> &str;
+    fn editor_no_git_changes(&self) -> &str;
+    fn editor_diff_no_file(&self) -> &str;
+    fn editor_preview_not_markdown(&self) -> &str;
+    fn editor_bookmark_no_file(&self) -> &str;
+    fn editor_definition_no_file(&self) -> &str;
+    fn editor_formatting(&self) -> &str;
+    fn editor_formatted(&self) -> &str;
+    fn editor_format_unchanged(&self) -> &str;
+    fn editor_format_unavailable(&self) -> &str;
+    fn editor_format_busy(&self) -> &str;
+    fn editor_format_discarded(&self) -> &str;
+    fn editor_format_failed(&self, error: &str) -> String;
+    fn editor_format_timed_out(&self, seconds: u64) -> String;
+    fn editor_spell_check_off(&self) -> &str;
+    fn editor_spell_no_dictionary(&self) -> &str;
+    fn editor_spell_no_word(&self) -> &str;
+    fn editor_spell_add_word(&self) -> &str;
+    fn editor_spell_word_added(&self, word: &str) -> String;
+    fn editor_spell_add_failed(&self, error: &str) -> String;
+    fn editor_no_hunk_at_cursor(&self) -> &str;
+    fn editor_hunk_reverted(&self, count: usize) -> String;
+    fn editor_undo_tree_empty(&self) -> &str;
+    fn editor_undo_branch_here(&self) -> &str;
+    fn editor_undo_branch(&self, time: &str, change: &str, edits: usize) -> String;
+    fn editor_replaced(&self, count: usize) -> String;
+    fn editor_replaced_in_selection(&self, count: usize) -> String;
+    fn editor_comment_unsupported(&self) -> &str;
+    fn editor_nothing_to_fold(&self) -> &str;
+    fn editor_plain_text(&self) -> &str;
 
     // Terminal
     fn terminal_exit_confirm(&self) -> &str;
+    fn terminal_broadcast(&self) -> &str;
+    fn terminal_exited_title(&self) -> &str;
+    fn status_broadcast_on(&self) -> &str;
+    fn status_broadcast_off(&self) -> &str;
+    fn status_no_tasks(&self) -> &str;
+    fn status_split_no_editor(&self) -> &str;
+    fn status_lsp_no_definition(&self) -> &str;
+    fn status_lsp_no_server(&self) -> &str;
+    fn status_lsp_not_started(&self, server: &str, error: &str) -> String;
+    fn status_lsp_stopped(&self, server: &str) -> String;
     fn terminal_exited(&self, code: i32) -> String;
 
     // Git status
@@ -94,6 +300,9 @@ pub trait Translation: Send + Sync {
     fn error_invalid_path(&self) -> &str;
     fn error_source_eq_dest(&self) -> &str;
     fn error_dest_is_subdir(&self) -> &str;
+    fn error_highlight_styles(&self, errors: &str) -> String;
+    fn error_keybindings(&self, errors: &str) -> String;
+    fn status_keys_pending(&self, keys: &str) -> String;
 
     // Help modal
     fn help_title(&self) -> &str;
@@ -149,17 +358,36 @@ pub trait Translation: Send + Sync {
     // File operation status
     fn status_file_created(&self, name: &str) -> String;
     fn status_error_create_file(&self, error: &str) -> String;
+    fn status_recovery_restored(&self, title: &str) -> String;
+    fn status_error_recovery(&self, error: &str) -> String;
+    fn status_error_open_panel(&self, name: &str, error: &str) -> String;
     fn status_dir_created(&self, name: &str) -> String;
     fn status_error_create_dir(&self, error: &str) -> String;
+    fn status_symlink_created(&self, name: &str) -> String;
+    fn status_error_create_symlink(&self, error: &str) -> String;
     fn status_item_deleted(&self) -> &str;
     fn status_error_delete(&self) -> &str;
     fn status_items_deleted(&self, count: usize) -> String;
     fn status_items_deleted_with_errors(&self, success: usize, errors: usize) -> String;
+    fn status_delete_cancelled(&self, removed: usize) -> String;
+    fn status_delete_entry_errors(&self, deleted: usize, failed: usize, first: &str) -> String;
+    fn status_item_trashed(&self) -> &str;
+    fn status_items_trashed(&self, count: usize) -> String;
+    fn status_items_restored(&self, count: usize) -> String;
+    fn status_error_restore(&self, error: &str) -> String;
     fn status_file_saved(&self, name: &str) -> String;
     fn status_error_save(&self, error: &str) -> String;
+    fn status_theme_not_loaded(&self, name: &str) -> String;
+    fn status_config_applied(&self) -> &str;
+    fn status_config_restart(&self, settings: &str) -> String;
+    fn status_config_invalid(&self, error: &str) -> String;
+    fn status_config_written(&self, path: &str) -> String;
     fn status_file_reloaded(&self) -> &str;
     fn status_error_reload(&self, error: &str) -> String;
     fn status_error_open_file(&self, name: &str, error: &str) -> String;
+    fn status_bookmark_set(&self, name: &str) -> String;
+    fn status_bookmark_removed(&self, name: &str) -> String;
+    fn status_bookmark_missing(&self, name: &str) -> String;
     fn status_item_actioned(&self, name: &str, action: &str) -> String;
     fn status_error_action(&self, action: &str, error: &str) -> String;
     fn status_operation_skipped(&self, name: &str) -> String;
@@ -177,14 +405,70 @@ pub trait Translation: Send + Sync {
     fn modal_move_multiple_title(&self, count: usize) -> String;
     fn modal_create_file_title(&self) -> &str;
     fn modal_create_dir_title(&self) -> &str;
+    fn modal_create_symlink_title(&self) -> &str;
+    fn modal_symlink_relative(&self) -> &str;
     fn modal_delete_single_title(&self, name: &str) -> String;
     fn modal_delete_multiple_title(&self, count: usize) -> String;
+    fn modal_trash_single_title(&self, name: &str) -> String;
+    fn modal_trash_multiple_title(&self, count: usize) -> String;
+    fn modal_restore_title(&self) -> &str;
+    fn modal_restore_prompt(&self) -> &str;
     fn modal_save_as_title(&self) -> &str;
     fn modal_enter_filename(&self) -> &str;
+    fn modal_bookmark_title(&self) -> &str;
+    fn modal_bookmark_prompt(&self) -> &str;
+    fn modal_syntax_title(&self) -> &str;
+    fn modal_encoding_title(&self) -> &str;
+    fn modal_undo_tree_title(&self) -> &str;
+    fn modal_spelling_title(&self, word: &str) -> String;
+    fn modal_create_missing(&self, path: &str) -> String;
+    fn modal_recovery_title(&self) -> &str;
+    fn modal_recovery_prompt(&self) -> &str;
+    fn modal_recovery_unnamed(&self) -> &str;
+    fn modal_recovery_item(&self, title: &str, path: &str, time: &str) -> String;
+    fn modal_recovery_restore(&self) -> &str;
+    fn modal_recovery_diff(&self) -> &str;
+    fn modal_recovery_discard(&self) -> &str;
+    fn modal_quit_title(&self) -> &str;
+    fn modal_quit_prompt(&self) -> &str;
+    fn modal_quit_modified(&self) -> &str;
+    fn modal_quit_external(&self) -> &str;
+    fn modal_quit_conflict(&self) -> &str;
+    fn modal_quit_running(&self) -> &str;
+    fn modal_quit_save(&self) -> &str;
+    fn modal_quit_discard(&self) -> &str;
+    fn modal_quit_kill(&self) -> &str;
+    fn modal_quit_save_all(&self) -> &str;
+    fn modal_quit_discard_all(&self) -> &str;
+    fn modal_quit_button(&self) -> &str;
+    fn modal_quit_changed(&self) -> &str;
+    fn modal_quit_save_failed(&self, path: &str, error: &str) -> String;
+    fn modal_dropped_files_title(&self, count: usize) -> String;
+    fn modal_dropped_open(&self) -> &str;
+    fn modal_dropped_insert(&self) -> &str;
+    fn modal_command_palette_title(&self) -> &str;
+    fn modal_file_finder_title(&self) -> &str;
+    fn modal_task_title(&self) -> &str;
+    fn modal_theme_title(&self) -> &str;
+    fn modal_wizard_step_title(&self, step: usize, total: usize, title: &str) -> String;
+    fn modal_wizard_setup_title(&self) -> &str;
+    fn modal_wizard_overwrite(&self, path: &str) -> String;
+    fn modal_wizard_shell_title(&self) -> &str;
+    fn modal_wizard_shell_auto(&self) -> &str;
+    fn modal_wizard_tab_size_title(&self) -> &str;
+    fn modal_wizard_mouse_title(&self) -> &str;
+    fn modal_wizard_mouse_on(&self) -> &str;
+    fn modal_wizard_mouse_off(&self) -> &str;
+    fn modal_file_finder_indexing(&self) -> &str;
+    /// Human-readable name of a hotkey action by its config name
+    /// (`close_panel`, `go_to_panel_3`)
+    fn command_title(&self, action: &str) -> String;
     fn modal_copy_single_prompt(&self, name: &str) -> String;
     fn modal_copy_multiple_prompt(&self, count: usize) -> String;
     fn modal_move_single_prompt(&self, name: &str) -> String;
     fn modal_move_multiple_prompt(&self, count: usize) -> String;
+    /// Target directory taken from the other file manager panel
+    fn modal_copy_move_other_panel(&self, path: &str) -> String;
 
     // Batch results
     fn batch_result_file_copied(&self) -> &str;
@@ -195,6 +479,18 @@ pub trait Translation: Send + Sync {
     fn batch_result_moved(&self) -> &str;
     fn batch_result_skipped_fmt(&self, count: usize) -> String;
     fn batch_result_errors_fmt(&self, count: usize) -> String;
+    fn batch_result_cancelled(&self) -> &str;
+
+    // Batch progress
+    fn progress_copy_title(&self) -> &str;
+    fn progress_move_title(&self) -> &str;
+    fn progress_delete_title(&self) -> &str;
+    fn progress_permissions_title(&self) -> &str;
+    fn progress_files(&self) -> &str;
+    fn progress_size(&self) -> &str;
+    fn progress_speed(&self) -> &str;
+    fn progress_cancel_hint(&self) -> &str;
+    fn progress_cancelling(&self) -> &str;
 
     // Menu
     fn menu_files(&self) -> &str;
@@ -202,19 +498,26 @@ pub trait Translation: Send + Sync {
     fn menu_editor(&self) -> &str;
     fn menu_debug(&self) -> &str;
     fn menu_preferences(&self) -> &str;
+    fn menu_panel(&self) -> &str;
     fn menu_help(&self) -> &str;
     fn menu_quit(&self) -> &str;
+    fn menu_panel_empty(&self) -> &str;
+    fn panel_menu_item(&self, id: &str) -> &str;
     fn menu_navigate_hint(&self) -> &str;
     fn menu_open_hint(&self) -> &str;
 
     // Status bar
     fn status_dir(&self) -> &str;
+    fn status_entries(&self, dirs: usize, files: usize) -> String;
     fn status_file(&self) -> &str;
     fn status_mod(&self) -> &str;
     fn status_owner(&self) -> &str;
     fn status_selected(&self) -> &str;
     fn status_pos(&self) -> &str;
-    fn status_tab(&self) -> &str;
+    fn status_indent_spaces(&self) -> &str;
+    fn status_indent_tabs(&self) -> &str;
+    fn status_mixed_line_endings(&self) -> &str;
+    fn status_large_file(&self) -> &str;
     fn status_plain_text(&self) -> &str;
     fn status_readonly(&self) -> &str;
     fn status_cwd(&self) -> &str;
@@ -261,6 +564,8 @@ pub trait Translation: Send + Sync {
     fn file_info_git_ahead(&self, count: usize) -> String;
     fn file_info_git_behind(&self, count: usize) -> String;
     fn file_info_git_ignored(&self) -> &str;
+    fn file_info_symlink_target(&self) -> &str;
+    fn file_info_symlink_broken(&self, target: &str) -> String;
 
     // File types
     fn file_type_directory(&self) -> &str;
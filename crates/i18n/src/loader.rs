@@ -133,6 +133,35 @@ mod tests {
         assert_eq!(rules.other, "ов");
     }
 
+    #[test]
+    fn test_file_reloaded_is_a_plain_string() {
+        // Read with `get_string`, so a key in [formats] shows up empty
+        for lang in ["en", "ru", "de", "es", "fr", "pt", "zh", "hi", "th"] {
+            let data = load_language(lang).unwrap();
+            assert!(
+                data.strings.contains_key("status_file_reloaded"),
+                "{}",
+                lang
+            );
+            assert!(
+                !data.formats.contains_key("status_file_reloaded"),
+                "{}",
+                lang
+            );
+        }
+    }
+
+    #[test]
+    fn test_formats_use_named_placeholders() {
+        // `format` fills only `{name}`, a bare `{}` would be shown as is
+        for lang in ["en", "ru", "de", "es", "fr", "pt", "zh", "hi", "th"] {
+            let data = load_language(lang).unwrap();
+            for (key, template) in &data.formats {
+                assert!(!template.contains("{}"), "{}: {}", lang, key);
+            }
+        }
+    }
+
     // Note: Actual language loading tests will be added once TOML files are generated
     // For now, these will fail since TOML files don't exist yet
     #[test]
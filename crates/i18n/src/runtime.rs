@@ -122,6 +122,38 @@ impl Translation for RuntimeTranslation {
         self.get_string("fm_operation_cancelled")
     }
 
+    fn fm_symlink_prompt(&self, name: &str) -> String {
+        self.format("fm_symlink_prompt", &[("name", name)])
+    }
+
+    fn fm_broken_symlink(&self, name: &str, target: &str) -> String {
+        self.format("fm_broken_symlink", &[("name", name), ("target", target)])
+    }
+
+    fn fm_trash_empty(&self) -> &str {
+        self.get_string("fm_trash_empty")
+    }
+
+    fn fm_sort_by_name(&self) -> &str {
+        self.get_string("fm_sort_by_name")
+    }
+
+    fn fm_sort_by_size(&self) -> &str {
+        self.get_string("fm_sort_by_size")
+    }
+
+    fn fm_sort_by_modified(&self) -> &str {
+        self.get_string("fm_sort_by_modified")
+    }
+
+    fn fm_hidden_shown(&self) -> &str {
+        self.get_string("fm_hidden_shown")
+    }
+
+    fn fm_hidden_hidden(&self) -> &str {
+        self.get_string("fm_hidden_hidden")
+    }
+
     fn modal_yes(&self) -> &str {
         self.get_string("modal_yes")
     }
@@ -154,6 +186,568 @@ impl Translation for RuntimeTranslation {
         self.get_string("panel_welcome")
     }
 
+    fn panel_scratchpad(&self) -> &str {
+        self.get_string("panel_scratchpad")
+    }
+
+    fn welcome_recent_projects(&self) -> &str {
+        self.get_string("welcome_recent_projects")
+    }
+
+    fn welcome_recent_files(&self) -> &str {
+        self.get_string("welcome_recent_files")
+    }
+
+    fn welcome_recent_hint(&self) -> &str {
+        self.get_string("welcome_recent_hint")
+    }
+
+    fn panel_help(&self) -> &str {
+        self.get_string("panel_help")
+    }
+
+    fn help_filter_hint(&self) -> &str {
+        self.get_string("help_filter_hint")
+    }
+
+    fn help_no_matches(&self) -> &str {
+        self.get_string("help_no_matches")
+    }
+
+    fn help_category(&self, category: &str) -> &str {
+        self.get_string(&format!("help_category_{}", category))
+    }
+
+    fn help_section(&self, panel: &str) -> &str {
+        self.get_string(&format!("help_section_{}", panel))
+    }
+
+    fn key_help(&self, key: &str) -> &str {
+        self.get_string(&format!("key_help_{}", key))
+    }
+
+    fn log_level(&self) -> &str {
+        self.get_string("log_level")
+    }
+
+    fn log_filter(&self) -> &str {
+        self.get_string("log_filter")
+    }
+
+    fn log_showing(&self, shown: &str, total: &str) -> String {
+        self.format("log_showing", &[("shown", shown), ("total", total)])
+    }
+
+    fn log_following(&self) -> &str {
+        self.get_string("log_following")
+    }
+
+    fn log_paused(&self) -> &str {
+        self.get_string("log_paused")
+    }
+
+    fn log_filter_hint(&self) -> &str {
+        self.get_string("log_filter_hint")
+    }
+
+    fn sysmon_title(&self) -> &str {
+        self.get_string("sysmon_title")
+    }
+
+    fn sysmon_cpu(&self) -> &str {
+        self.get_string("sysmon_cpu")
+    }
+
+    fn sysmon_memory(&self) -> &str {
+        self.get_string("sysmon_memory")
+    }
+
+    fn sysmon_load(&self) -> &str {
+        self.get_string("sysmon_load")
+    }
+
+    fn sysmon_name(&self) -> &str {
+        self.get_string("sysmon_name")
+    }
+
+    fn sysmon_sort(&self) -> &str {
+        self.get_string("sysmon_sort")
+    }
+
+    fn sysmon_sort_tree(&self) -> &str {
+        self.get_string("sysmon_sort_tree")
+    }
+
+    fn sysmon_hint(&self) -> &str {
+        self.get_string("sysmon_hint")
+    }
+
+    fn sysmon_no_terminals(&self) -> &str {
+        self.get_string("sysmon_no_terminals")
+    }
+
+    fn sysmon_signal_sent(&self, signal: &str, pid: &str, name: &str) -> String {
+        self.format(
+            "sysmon_signal_sent",
+            &[("signal", signal), ("pid", pid), ("name", name)],
+        )
+    }
+
+    fn sysmon_signal_failed(&self, signal: &str, pid: &str) -> String {
+        self.format("sysmon_signal_failed", &[("signal", signal), ("pid", pid)])
+    }
+
+    fn git_title(&self) -> &str {
+        self.get_string("git_title")
+    }
+
+    fn git_staged(&self) -> &str {
+        self.get_string("git_staged")
+    }
+
+    fn git_unstaged(&self) -> &str {
+        self.get_string("git_unstaged")
+    }
+
+    fn git_not_repository(&self) -> &str {
+        self.get_string("git_not_repository")
+    }
+
+    fn git_no_changes(&self) -> &str {
+        self.get_string("git_no_changes")
+    }
+
+    fn git_hint(&self) -> &str {
+        self.get_string("git_hint")
+    }
+
+    fn git_commit_title(&self) -> &str {
+        self.get_string("git_commit_title")
+    }
+
+    fn git_nothing_staged(&self) -> &str {
+        self.get_string("git_nothing_staged")
+    }
+
+    fn git_commit_prompt(&self, count: usize, branch: &str) -> String {
+        self.format(
+            "git_commit_prompt",
+            &[("count", &count.to_string()), ("branch", branch)],
+        )
+    }
+
+    fn git_committed(&self, summary: &str) -> String {
+        self.format("git_committed", &[("summary", summary)])
+    }
+
+    fn git_commit_failed(&self, error: &str) -> String {
+        self.format("git_commit_failed", &[("error", error)])
+    }
+
+    fn git_error(&self, error: &str) -> String {
+        self.format("git_error", &[("error", error)])
+    }
+
+    fn modal_multiline_hint(&self) -> &str {
+        self.get_string("modal_multiline_hint")
+    }
+
+    fn diff_title(&self) -> &str {
+        self.get_string("diff_title")
+    }
+
+    fn notifications_title(&self) -> &str {
+        self.get_string("notifications_title")
+    }
+
+    fn notifications_empty(&self) -> &str {
+        self.get_string("notifications_empty")
+    }
+
+    fn bookmarks_title(&self) -> &str {
+        self.get_string("bookmarks_title")
+    }
+
+    fn bookmarks_empty(&self) -> &str {
+        self.get_string("bookmarks_empty")
+    }
+
+    fn bookmarks_hint(&self) -> &str {
+        self.get_string("bookmarks_hint")
+    }
+
+    fn bookmarks_missing(&self) -> &str {
+        self.get_string("bookmarks_missing")
+    }
+
+    fn diff_identical(&self) -> &str {
+        self.get_string("diff_identical")
+    }
+
+    fn diff_hint(&self) -> &str {
+        self.get_string("diff_hint")
+    }
+
+    fn diff_select_two(&self) -> &str {
+        self.get_string("diff_select_two")
+    }
+
+    fn diff_hunk_position(&self, current: usize, total: usize) -> String {
+        self.format(
+            "diff_hunk_position",
+            &[
+                ("current", &current.to_string()),
+                ("total", &total.to_string()),
+            ],
+        )
+    }
+
+    fn diff_hunk_count(&self, count: usize) -> String {
+        self.format("diff_hunk_count", &[("count", &count.to_string())])
+    }
+
+    fn diff_binary_file(&self, path: &str) -> String {
+        self.format("diff_binary_file", &[("path", path)])
+    }
+
+    fn diff_recovered(&self) -> &str {
+        self.get_string("diff_recovered")
+    }
+
+    fn diff_failed(&self, error: &str) -> String {
+        self.format("diff_failed", &[("error", error)])
+    }
+
+    fn markdown_preview_title(&self, name: &str) -> String {
+        self.format("markdown_preview_title", &[("name", name)])
+    }
+
+    fn search_title(&self) -> &str {
+        self.get_string("search_title")
+    }
+
+    fn search_prompt(&self) -> &str {
+        self.get_string("search_prompt")
+    }
+
+    fn search_hint(&self) -> &str {
+        self.get_string("search_hint")
+    }
+
+    fn search_running(&self) -> &str {
+        self.get_string("search_running")
+    }
+
+    fn search_no_matches(&self) -> &str {
+        self.get_string("search_no_matches")
+    }
+
+    fn search_truncated(&self) -> &str {
+        self.get_string("search_truncated")
+    }
+
+    fn search_more_in_file(&self) -> &str {
+        self.get_string("search_more_in_file")
+    }
+
+    fn search_summary(&self, matches: usize, files: usize) -> String {
+        self.format(
+            "search_summary",
+            &[
+                ("matches", &matches.to_string()),
+                ("files", &files.to_string()),
+            ],
+        )
+    }
+
+    fn search_invalid_regex(&self, error: &str) -> String {
+        self.format("search_invalid_regex", &[("error", error)])
+    }
+
+    fn compare_title(&self) -> &str {
+        self.get_string("compare_title")
+    }
+
+    fn compare_running(&self) -> &str {
+        self.get_string("compare_running")
+    }
+
+    fn compare_cancelled(&self) -> &str {
+        self.get_string("compare_cancelled")
+    }
+
+    fn compare_truncated(&self) -> &str {
+        self.get_string("compare_truncated")
+    }
+
+    fn compare_only_left(&self) -> &str {
+        self.get_string("compare_only_left")
+    }
+
+    fn compare_only_right(&self) -> &str {
+        self.get_string("compare_only_right")
+    }
+
+    fn compare_newer_left(&self) -> &str {
+        self.get_string("compare_newer_left")
+    }
+
+    fn compare_newer_right(&self) -> &str {
+        self.get_string("compare_newer_right")
+    }
+
+    fn compare_contents_differ(&self) -> &str {
+        self.get_string("compare_contents_differ")
+    }
+
+    fn compare_kind_differs(&self) -> &str {
+        self.get_string("compare_kind_differs")
+    }
+
+    fn compare_contents(&self) -> &str {
+        self.get_string("compare_contents")
+    }
+
+    fn compare_hint(&self) -> &str {
+        self.get_string("compare_hint")
+    }
+
+    fn compare_found(&self, count: usize) -> String {
+        self.format("compare_found", &[("count", &count.to_string())])
+    }
+
+    fn compare_summary(&self, differences: usize, same: usize) -> String {
+        self.format(
+            "compare_summary",
+            &[
+                ("differences", &differences.to_string()),
+                ("same", &same.to_string()),
+            ],
+        )
+    }
+
+    fn compare_size(&self, left: &str, right: &str) -> String {
+        self.format("compare_size", &[("left", left), ("right", right)])
+    }
+
+    fn compare_copied_right(&self, path: &str) -> String {
+        self.format("compare_copied_right", &[("path", path)])
+    }
+
+    fn compare_copied_left(&self, path: &str) -> String {
+        self.format("compare_copied_left", &[("path", path)])
+    }
+
+    fn compare_copy_failed(&self, path: &str, error: &str) -> String {
+        self.format("compare_copy_failed", &[("path", path), ("error", error)])
+    }
+
+    fn compare_not_directory(&self, path: &str) -> String {
+        self.format("compare_not_directory", &[("path", path)])
+    }
+
+    fn duplicates_title(&self) -> &str {
+        self.get_string("duplicates_title")
+    }
+
+    fn duplicates_recursive(&self) -> &str {
+        self.get_string("duplicates_recursive")
+    }
+
+    fn duplicates_skip_ignored(&self) -> &str {
+        self.get_string("duplicates_skip_ignored")
+    }
+
+    fn duplicates_none(&self) -> &str {
+        self.get_string("duplicates_none")
+    }
+
+    fn duplicates_keep_one(&self) -> &str {
+        self.get_string("duplicates_keep_one")
+    }
+
+    fn duplicates_hint(&self) -> &str {
+        self.get_string("duplicates_hint")
+    }
+
+    fn duplicates_listing(&self, count: usize) -> String {
+        self.format("duplicates_listing", &[("count", &count.to_string())])
+    }
+
+    fn duplicates_hashing(&self, percent: usize) -> String {
+        self.format("duplicates_hashing", &[("percent", &percent.to_string())])
+    }
+
+    fn duplicates_found(&self, count: usize) -> String {
+        self.format("duplicates_found", &[("count", &count.to_string())])
+    }
+
+    fn duplicates_summary(&self, count: usize, size: &str) -> String {
+        self.format(
+            "duplicates_summary",
+            &[("count", &count.to_string()), ("size", size)],
+        )
+    }
+
+    fn duplicates_set(&self, count: usize, size: &str) -> String {
+        self.format(
+            "duplicates_set",
+            &[("count", &count.to_string()), ("size", size)],
+        )
+    }
+
+    fn duplicates_truncated(&self, count: usize) -> String {
+        self.format("duplicates_truncated", &[("count", &count.to_string())])
+    }
+
+    fn duplicates_marked(&self, count: usize) -> String {
+        self.format("duplicates_marked", &[("count", &count.to_string())])
+    }
+
+    fn permissions_others(&self) -> &str {
+        self.get_string("permissions_others")
+    }
+
+    fn permissions_special(&self) -> &str {
+        self.get_string("permissions_special")
+    }
+
+    fn permissions_read(&self) -> &str {
+        self.get_string("permissions_read")
+    }
+
+    fn permissions_write(&self) -> &str {
+        self.get_string("permissions_write")
+    }
+
+    fn permissions_execute(&self) -> &str {
+        self.get_string("permissions_execute")
+    }
+
+    fn permissions_setuid(&self) -> &str {
+        self.get_string("permissions_setuid")
+    }
+
+    fn permissions_setgid(&self) -> &str {
+        self.get_string("permissions_setgid")
+    }
+
+    fn permissions_sticky(&self) -> &str {
+        self.get_string("permissions_sticky")
+    }
+
+    fn permissions_octal(&self) -> &str {
+        self.get_string("permissions_octal")
+    }
+
+    fn permissions_recursive(&self) -> &str {
+        self.get_string("permissions_recursive")
+    }
+
+    fn permissions_apply(&self) -> &str {
+        self.get_string("permissions_apply")
+    }
+
+    fn permissions_recursive_title(&self) -> &str {
+        self.get_string("permissions_recursive_title")
+    }
+
+    fn permissions_title(&self, name: &str) -> String {
+        self.format("permissions_title", &[("name", name)])
+    }
+
+    fn permissions_title_multiple(&self, count: usize) -> String {
+        self.format(
+            "permissions_title_multiple",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn permissions_recursive_confirm(&self, count: usize) -> String {
+        self.format(
+            "permissions_recursive_confirm",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn status_permissions_cancelled(&self, count: usize) -> String {
+        self.format(
+            "status_permissions_cancelled",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn status_permissions_changed(&self, count: usize) -> String {
+        self.format(
+            "status_permissions_changed",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn status_permissions_failed(
+        &self,
+        failed: usize,
+        total: usize,
+        name: &str,
+        error: &str,
+    ) -> String {
+        self.format(
+            "status_permissions_failed",
+            &[
+                ("failed", &failed.to_string()),
+                ("total", &total.to_string()),
+                ("name", name),
+                ("error", error),
+            ],
+        )
+    }
+
+    fn modal_compare_title(&self) -> &str {
+        self.get_string("modal_compare_title")
+    }
+
+    fn modal_compare_prompt(&self, name: &str) -> String {
+        self.format("modal_compare_prompt", &[("name", name)])
+    }
+
+    fn task_title(&self) -> &str {
+        self.get_string("task_title")
+    }
+
+    fn task_running(&self) -> &str {
+        self.get_string("task_running")
+    }
+
+    fn task_stopped(&self) -> &str {
+        self.get_string("task_stopped")
+    }
+
+    fn task_no_problems(&self) -> &str {
+        self.get_string("task_no_problems")
+    }
+
+    fn task_hint(&self) -> &str {
+        self.get_string("task_hint")
+    }
+
+    fn task_exited(&self, code: i32) -> String {
+        self.format("task_exited", &[("code", &code.to_string())])
+    }
+
+    fn task_problems(&self, errors: usize, warnings: usize) -> String {
+        self.format(
+            "task_problems",
+            &[
+                ("errors", &errors.to_string()),
+                ("warnings", &warnings.to_string()),
+            ],
+        )
+    }
+
+    fn task_failed_to_start(&self, error: &str) -> String {
+        self.format("task_failed_to_start", &[("error", error)])
+    }
+
     fn editor_close_unsaved(&self) -> &str {
         self.get_string("editor_close_unsaved")
     }
@@ -202,66 +796,347 @@ impl Translation for RuntimeTranslation {
         self.get_string("editor_close_conflict_question")
     }
 
-    fn editor_reload_from_disk(&self) -> &str {
-        self.get_string("editor_reload_from_disk")
+    fn editor_reload_from_disk(&self) -> &str {
+        self.get_string("editor_reload_from_disk")
+    }
+
+    fn editor_external_conflict_question(&self) -> &str {
+        self.get_string("editor_external_conflict_question")
+    }
+
+    fn editor_keep_mine(&self) -> &str {
+        self.get_string("editor_keep_mine")
+    }
+
+    fn editor_show_diff(&self) -> &str {
+        self.get_string("editor_show_diff")
+    }
+
+    fn editor_review_changes(&self) -> &str {
+        self.get_string("editor_review_changes")
+    }
+
+    fn editor_reloaded_external(&self) -> &str {
+        self.get_string("editor_reloaded_external")
+    }
+
+    fn editor_diff_title(&self, name: &str) -> String {
+        self.format("editor_diff_title", &[("name", name)])
+    }
+
+    fn editor_save_error(&self, error: &str) -> String {
+        self.format("editor_save_error", &[("error", error)])
+    }
+
+    fn editor_saved(&self, path: &str) -> String {
+        self.format("editor_saved", &[("path", path)])
+    }
+
+    fn editor_saved_in_place(&self, reason: &str) -> String {
+        self.format("editor_saved_in_place", &[("reason", reason)])
+    }
+
+    fn editor_invalid_bytes(&self, encoding: &str) -> String {
+        self.format("editor_invalid_bytes", &[("encoding", encoding)])
+    }
+
+    fn editor_encoding_reopened(&self, encoding: &str) -> String {
+        self.format("editor_encoding_reopened", &[("encoding", encoding)])
+    }
+
+    fn editor_encoding_convert(&self, encoding: &str) -> String {
+        self.format("editor_encoding_convert", &[("encoding", encoding)])
+    }
+
+    fn editor_file_opened(&self, filename: &str) -> String {
+        self.format("editor_file_opened", &[("filename", filename)])
+    }
+
+    fn editor_search_title(&self) -> &str {
+        self.get_string("editor_search_title")
+    }
+
+    fn editor_search_prompt(&self) -> &str {
+        self.get_string("editor_search_prompt")
+    }
+
+    fn editor_replace_title(&self) -> &str {
+        self.get_string("editor_replace_title")
+    }
+
+    fn editor_replace_prompt(&self) -> &str {
+        self.get_string("editor_replace_prompt")
+    }
+
+    fn editor_replace_with_prompt(&self) -> &str {
+        self.get_string("editor_replace_with_prompt")
+    }
+
+    fn editor_search_match_info(&self, current: usize, total: usize) -> String {
+        self.format(
+            "editor_search_match_info",
+            &[
+                ("current", &current.to_string()),
+                ("total", &total.to_string()),
+            ],
+        )
+    }
+
+    fn editor_search_no_matches(&self) -> &str {
+        self.get_string("editor_search_no_matches")
+    }
+
+    fn editor_deletion_marker(&self, count: usize) -> String {
+        let plural = self.pluralize(count, "file");
+        self.format(
+            "editor_deletion_marker",
+            &[("count", &count.to_string()), ("plural", plural)],
+        )
+    }
+
+    fn editor_indent_tabs(&self) -> &str {
+        self.get_string("editor_indent_tabs")
+    }
+
+    fn editor_indent_spaces(&self, width: usize) -> String {
+        self.format("editor_indent_spaces", &[("width", &width.to_string())])
+    }
+
+    fn editor_indent_converted(&self, style: &str, count: usize) -> String {
+        self.format(
+            "editor_indent_converted",
+            &[("style", style), ("count", &count.to_string())],
+        )
+    }
+
+    fn editor_stripped_whitespace(&self, count: usize) -> String {
+        self.format(
+            "editor_stripped_whitespace",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn editor_fixed_final_newline(&self) -> &str {
+        self.get_string("editor_fixed_final_newline")
+    }
+
+    fn editor_normalized_line_endings(&self, count: usize) -> String {
+        self.format(
+            "editor_normalized_line_endings",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn editor_saved_with_cleanup(&self, changes: &str) -> String {
+        self.format("editor_saved_with_cleanup", &[("changes", changes)])
+    }
+
+    fn editor_line_ending_set(&self, ending: &str) -> String {
+        self.format("editor_line_ending_set", &[("ending", ending)])
+    }
+
+    fn editor_whitespace_shown(&self) -> &str {
+        self.get_string("editor_whitespace_shown")
+    }
+
+    fn editor_whitespace_hidden(&self) -> &str {
+        self.get_string("editor_whitespace_hidden")
+    }
+
+    fn editor_word_wrap_on(&self) -> &str {
+        self.get_string("editor_word_wrap_on")
+    }
+
+    fn editor_word_wrap_off(&self) -> &str {
+        self.get_string("editor_word_wrap_off")
+    }
+
+    fn editor_large_file_features_enabled(&self) -> &str {
+        self.get_string("editor_large_file_features_enabled")
+    }
+
+    fn editor_read_only_rejected(&self) -> &str {
+        self.get_string("editor_read_only_rejected")
+    }
+
+    fn editor_edit_anyway(&self) -> &str {
+        self.get_string("editor_edit_anyway")
+    }
+
+    fn editor_no_git_changes(&self) -> &str {
+        self.get_string("editor_no_git_changes")
+    }
+
+    fn editor_diff_no_file(&self) -> &str {
+        self.get_string("editor_diff_no_file")
+    }
+
+    fn editor_preview_not_markdown(&self) -> &str {
+        self.get_string("editor_preview_not_markdown")
+    }
+
+    fn editor_bookmark_no_file(&self) -> &str {
+        self.get_string("editor_bookmark_no_file")
+    }
+
+    fn editor_definition_no_file(&self) -> &str {
+        self.get_string("editor_definition_no_file")
+    }
+
+    fn editor_formatting(&self) -> &str {
+        self.get_string("editor_formatting")
+    }
+
+    fn editor_formatted(&self) -> &str {
+        self.get_string("editor_formatted")
+    }
+
+    fn editor_format_unchanged(&self) -> &str {
+        self.get_string("editor_format_unchanged")
+    }
+
+    fn editor_format_unavailable(&self) -> &str {
+        self.get_string("editor_format_unavailable")
+    }
+
+    fn editor_format_busy(&self) -> &str {
+        self.get_string("editor_format_busy")
+    }
+
+    fn editor_format_discarded(&self) -> &str {
+        self.get_string("editor_format_discarded")
+    }
+
+    fn editor_format_failed(&self, error: &str) -> String {
+        self.format("editor_format_failed", &[("error", error)])
+    }
+
+    fn editor_format_timed_out(&self, seconds: u64) -> String {
+        self.format(
+            "editor_format_timed_out",
+            &[("seconds", &seconds.to_string())],
+        )
+    }
+
+    fn editor_spell_check_off(&self) -> &str {
+        self.get_string("editor_spell_check_off")
+    }
+
+    fn editor_spell_no_dictionary(&self) -> &str {
+        self.get_string("editor_spell_no_dictionary")
+    }
+
+    fn editor_spell_no_word(&self) -> &str {
+        self.get_string("editor_spell_no_word")
+    }
+
+    fn editor_spell_add_word(&self) -> &str {
+        self.get_string("editor_spell_add_word")
+    }
+
+    fn editor_spell_word_added(&self, word: &str) -> String {
+        self.format("editor_spell_word_added", &[("word", word)])
+    }
+
+    fn editor_spell_add_failed(&self, error: &str) -> String {
+        self.format("editor_spell_add_failed", &[("error", error)])
+    }
+
+    fn editor_no_hunk_at_cursor(&self) -> &str {
+        self.get_string("editor_no_hunk_at_cursor")
+    }
+
+    fn editor_hunk_reverted(&self, count: usize) -> String {
+        self.format("editor_hunk_reverted", &[("count", &count.to_string())])
+    }
+
+    fn editor_undo_tree_empty(&self) -> &str {
+        self.get_string("editor_undo_tree_empty")
+    }
+
+    fn editor_undo_branch_here(&self) -> &str {
+        self.get_string("editor_undo_branch_here")
+    }
+
+    fn editor_undo_branch(&self, time: &str, change: &str, edits: usize) -> String {
+        self.format(
+            "editor_undo_branch",
+            &[
+                ("time", time),
+                ("change", change),
+                ("edits", &edits.to_string()),
+            ],
+        )
+    }
+
+    fn editor_replaced(&self, count: usize) -> String {
+        self.format("editor_replaced", &[("count", &count.to_string())])
+    }
+
+    fn editor_replaced_in_selection(&self, count: usize) -> String {
+        self.format(
+            "editor_replaced_in_selection",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn editor_comment_unsupported(&self) -> &str {
+        self.get_string("editor_comment_unsupported")
+    }
+
+    fn editor_nothing_to_fold(&self) -> &str {
+        self.get_string("editor_nothing_to_fold")
     }
 
-    fn editor_save_error(&self, error: &str) -> String {
-        self.format("editor_save_error", &[("error", error)])
+    fn editor_plain_text(&self) -> &str {
+        self.get_string("editor_plain_text")
     }
 
-    fn editor_saved(&self, path: &str) -> String {
-        self.format("editor_saved", &[("path", path)])
+    fn terminal_exit_confirm(&self) -> &str {
+        self.get_string("terminal_exit_confirm")
     }
 
-    fn editor_file_opened(&self, filename: &str) -> String {
-        self.format("editor_file_opened", &[("filename", filename)])
+    fn terminal_broadcast(&self) -> &str {
+        self.get_string("terminal_broadcast")
     }
 
-    fn editor_search_title(&self) -> &str {
-        self.get_string("editor_search_title")
+    fn terminal_exited_title(&self) -> &str {
+        self.get_string("terminal_exited_title")
     }
 
-    fn editor_search_prompt(&self) -> &str {
-        self.get_string("editor_search_prompt")
+    fn status_broadcast_on(&self) -> &str {
+        self.get_string("status_broadcast_on")
     }
 
-    fn editor_replace_title(&self) -> &str {
-        self.get_string("editor_replace_title")
+    fn status_broadcast_off(&self) -> &str {
+        self.get_string("status_broadcast_off")
     }
 
-    fn editor_replace_prompt(&self) -> &str {
-        self.get_string("editor_replace_prompt")
+    fn status_no_tasks(&self) -> &str {
+        self.get_string("status_no_tasks")
     }
 
-    fn editor_replace_with_prompt(&self) -> &str {
-        self.get_string("editor_replace_with_prompt")
+    fn status_split_no_editor(&self) -> &str {
+        self.get_string("status_split_no_editor")
     }
 
-    fn editor_search_match_info(&self, current: usize, total: usize) -> String {
-        self.format(
-            "editor_search_match_info",
-            &[
-                ("current", &current.to_string()),
-                ("total", &total.to_string()),
-            ],
-        )
+    fn status_lsp_no_definition(&self) -> &str {
+        self.get_string("status_lsp_no_definition")
     }
 
-    fn editor_search_no_matches(&self) -> &str {
-        self.get_string("editor_search_no_matches")
+    fn status_lsp_no_server(&self) -> &str {
+        self.get_string("status_lsp_no_server")
     }
 
-    fn editor_deletion_marker(&self, count: usize) -> String {
-        let plural = self.pluralize(count, "file");
+    fn status_lsp_not_started(&self, server: &str, error: &str) -> String {
         self.format(
-            "editor_deletion_marker",
-            &[("count", &count.to_string()), ("plural", plural)],
+            "status_lsp_not_started",
+            &[("server", server), ("error", error)],
         )
     }
 
-    fn terminal_exit_confirm(&self) -> &str {
-        self.get_string("terminal_exit_confirm")
+    fn status_lsp_stopped(&self, server: &str) -> String {
+        self.format("status_lsp_stopped", &[("server", server)])
     }
 
     fn terminal_exited(&self, code: i32) -> String {
@@ -300,6 +1175,18 @@ impl Translation for RuntimeTranslation {
         self.get_string("error_dest_is_subdir")
     }
 
+    fn error_highlight_styles(&self, errors: &str) -> String {
+        self.format("error_highlight_styles", &[("errors", errors)])
+    }
+
+    fn error_keybindings(&self, errors: &str) -> String {
+        self.format("error_keybindings", &[("errors", errors)])
+    }
+
+    fn status_keys_pending(&self, keys: &str) -> String {
+        self.format("status_keys_pending", &[("keys", keys)])
+    }
+
     fn help_title(&self) -> &str {
         self.get_string("help_title")
     }
@@ -496,6 +1383,21 @@ impl Translation for RuntimeTranslation {
         self.format("status_error_create_file", &[("error", error)])
     }
 
+    fn status_recovery_restored(&self, title: &str) -> String {
+        self.format("status_recovery_restored", &[("title", title)])
+    }
+
+    fn status_error_recovery(&self, error: &str) -> String {
+        self.format("status_error_recovery", &[("error", error)])
+    }
+
+    fn status_error_open_panel(&self, name: &str, error: &str) -> String {
+        self.format(
+            "status_error_open_panel",
+            &[("name", name), ("error", error)],
+        )
+    }
+
     fn status_dir_created(&self, name: &str) -> String {
         self.format("status_dir_created", &[("name", name)])
     }
@@ -504,6 +1406,14 @@ impl Translation for RuntimeTranslation {
         self.format("status_error_create_dir", &[("error", error)])
     }
 
+    fn status_symlink_created(&self, name: &str) -> String {
+        self.format("status_symlink_created", &[("name", name)])
+    }
+
+    fn status_error_create_symlink(&self, error: &str) -> String {
+        self.format("status_error_create_symlink", &[("error", error)])
+    }
+
     fn status_item_deleted(&self) -> &str {
         self.get_string("status_item_deleted")
     }
@@ -530,6 +1440,40 @@ impl Translation for RuntimeTranslation {
         )
     }
 
+    fn status_delete_cancelled(&self, removed: usize) -> String {
+        self.format(
+            "status_delete_cancelled",
+            &[("removed", &removed.to_string())],
+        )
+    }
+
+    fn status_delete_entry_errors(&self, deleted: usize, failed: usize, first: &str) -> String {
+        self.format(
+            "status_delete_entry_errors",
+            &[
+                ("deleted", &deleted.to_string()),
+                ("failed", &failed.to_string()),
+                ("first", first),
+            ],
+        )
+    }
+
+    fn status_item_trashed(&self) -> &str {
+        self.get_string("status_item_trashed")
+    }
+
+    fn status_items_trashed(&self, count: usize) -> String {
+        self.format("status_items_trashed", &[("count", &count.to_string())])
+    }
+
+    fn status_items_restored(&self, count: usize) -> String {
+        self.format("status_items_restored", &[("count", &count.to_string())])
+    }
+
+    fn status_error_restore(&self, error: &str) -> String {
+        self.format("status_error_restore", &[("error", error)])
+    }
+
     fn status_file_saved(&self, name: &str) -> String {
         self.format("status_file_saved", &[("name", name)])
     }
@@ -538,6 +1482,26 @@ impl Translation for RuntimeTranslation {
         self.format("status_error_save", &[("error", error)])
     }
 
+    fn status_theme_not_loaded(&self, name: &str) -> String {
+        self.format("status_theme_not_loaded", &[("name", name)])
+    }
+
+    fn status_config_applied(&self) -> &str {
+        self.get_string("status_config_applied")
+    }
+
+    fn status_config_restart(&self, settings: &str) -> String {
+        self.format("status_config_restart", &[("settings", settings)])
+    }
+
+    fn status_config_invalid(&self, error: &str) -> String {
+        self.format("status_config_invalid", &[("error", error)])
+    }
+
+    fn status_config_written(&self, path: &str) -> String {
+        self.format("status_config_written", &[("path", path)])
+    }
+
     fn status_file_reloaded(&self) -> &str {
         self.get_string("status_file_reloaded")
     }
@@ -553,6 +1517,18 @@ impl Translation for RuntimeTranslation {
         )
     }
 
+    fn status_bookmark_set(&self, name: &str) -> String {
+        self.format("status_bookmark_set", &[("name", name)])
+    }
+
+    fn status_bookmark_removed(&self, name: &str) -> String {
+        self.format("status_bookmark_removed", &[("name", name)])
+    }
+
+    fn status_bookmark_missing(&self, name: &str) -> String {
+        self.format("status_bookmark_missing", &[("name", name)])
+    }
+
     fn status_item_actioned(&self, name: &str, action: &str) -> String {
         self.format(
             "status_item_actioned",
@@ -617,6 +1593,14 @@ impl Translation for RuntimeTranslation {
         self.get_string("modal_create_dir_title")
     }
 
+    fn modal_create_symlink_title(&self) -> &str {
+        self.get_string("modal_create_symlink_title")
+    }
+
+    fn modal_symlink_relative(&self) -> &str {
+        self.get_string("modal_symlink_relative")
+    }
+
     fn modal_delete_single_title(&self, name: &str) -> String {
         self.format("modal_delete_single_title", &[("name", name)])
     }
@@ -628,6 +1612,25 @@ impl Translation for RuntimeTranslation {
         )
     }
 
+    fn modal_trash_single_title(&self, name: &str) -> String {
+        self.format("modal_trash_single_title", &[("name", name)])
+    }
+
+    fn modal_trash_multiple_title(&self, count: usize) -> String {
+        self.format(
+            "modal_trash_multiple_title",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn modal_restore_title(&self) -> &str {
+        self.get_string("modal_restore_title")
+    }
+
+    fn modal_restore_prompt(&self) -> &str {
+        self.get_string("modal_restore_prompt")
+    }
+
     fn modal_save_as_title(&self) -> &str {
         self.get_string("modal_save_as_title")
     }
@@ -636,6 +1639,209 @@ impl Translation for RuntimeTranslation {
         self.get_string("modal_enter_filename")
     }
 
+    fn modal_bookmark_title(&self) -> &str {
+        self.get_string("modal_bookmark_title")
+    }
+
+    fn modal_bookmark_prompt(&self) -> &str {
+        self.get_string("modal_bookmark_prompt")
+    }
+
+    fn modal_syntax_title(&self) -> &str {
+        self.get_string("modal_syntax_title")
+    }
+
+    fn modal_encoding_title(&self) -> &str {
+        self.get_string("modal_encoding_title")
+    }
+
+    fn modal_undo_tree_title(&self) -> &str {
+        self.get_string("modal_undo_tree_title")
+    }
+
+    fn modal_spelling_title(&self, word: &str) -> String {
+        self.format("modal_spelling_title", &[("word", word)])
+    }
+
+    fn modal_create_missing(&self, path: &str) -> String {
+        self.format("modal_create_missing", &[("path", path)])
+    }
+
+    fn modal_recovery_title(&self) -> &str {
+        self.get_string("modal_recovery_title")
+    }
+
+    fn modal_recovery_prompt(&self) -> &str {
+        self.get_string("modal_recovery_prompt")
+    }
+
+    fn modal_recovery_unnamed(&self) -> &str {
+        self.get_string("modal_recovery_unnamed")
+    }
+
+    fn modal_recovery_item(&self, title: &str, path: &str, time: &str) -> String {
+        self.format(
+            "modal_recovery_item",
+            &[("title", title), ("path", path), ("time", time)],
+        )
+    }
+
+    fn modal_recovery_restore(&self) -> &str {
+        self.get_string("modal_recovery_restore")
+    }
+
+    fn modal_recovery_diff(&self) -> &str {
+        self.get_string("modal_recovery_diff")
+    }
+
+    fn modal_recovery_discard(&self) -> &str {
+        self.get_string("modal_recovery_discard")
+    }
+
+    fn modal_quit_title(&self) -> &str {
+        self.get_string("modal_quit_title")
+    }
+
+    fn modal_quit_prompt(&self) -> &str {
+        self.get_string("modal_quit_prompt")
+    }
+
+    fn modal_quit_modified(&self) -> &str {
+        self.get_string("modal_quit_modified")
+    }
+
+    fn modal_quit_external(&self) -> &str {
+        self.get_string("modal_quit_external")
+    }
+
+    fn modal_quit_conflict(&self) -> &str {
+        self.get_string("modal_quit_conflict")
+    }
+
+    fn modal_quit_running(&self) -> &str {
+        self.get_string("modal_quit_running")
+    }
+
+    fn modal_quit_save(&self) -> &str {
+        self.get_string("modal_quit_save")
+    }
+
+    fn modal_quit_discard(&self) -> &str {
+        self.get_string("modal_quit_discard")
+    }
+
+    fn modal_quit_kill(&self) -> &str {
+        self.get_string("modal_quit_kill")
+    }
+
+    fn modal_quit_save_all(&self) -> &str {
+        self.get_string("modal_quit_save_all")
+    }
+
+    fn modal_quit_discard_all(&self) -> &str {
+        self.get_string("modal_quit_discard_all")
+    }
+
+    fn modal_quit_button(&self) -> &str {
+        self.get_string("modal_quit_button")
+    }
+
+    fn modal_quit_changed(&self) -> &str {
+        self.get_string("modal_quit_changed")
+    }
+
+    fn modal_quit_save_failed(&self, path: &str, error: &str) -> String {
+        self.format(
+            "modal_quit_save_failed",
+            &[("path", path), ("error", error)],
+        )
+    }
+
+    fn modal_dropped_files_title(&self, count: usize) -> String {
+        self.format(
+            "modal_dropped_files_title",
+            &[("count", &count.to_string())],
+        )
+    }
+
+    fn modal_dropped_open(&self) -> &str {
+        self.get_string("modal_dropped_open")
+    }
+
+    fn modal_dropped_insert(&self) -> &str {
+        self.get_string("modal_dropped_insert")
+    }
+
+    fn modal_command_palette_title(&self) -> &str {
+        self.get_string("modal_command_palette_title")
+    }
+
+    fn modal_file_finder_title(&self) -> &str {
+        self.get_string("modal_file_finder_title")
+    }
+
+    fn modal_task_title(&self) -> &str {
+        self.get_string("modal_task_title")
+    }
+
+    fn modal_theme_title(&self) -> &str {
+        self.get_string("modal_theme_title")
+    }
+
+    fn modal_wizard_step_title(&self, step: usize, total: usize, title: &str) -> String {
+        self.format(
+            "modal_wizard_step_title",
+            &[
+                ("step", &step.to_string()),
+                ("total", &total.to_string()),
+                ("title", title),
+            ],
+        )
+    }
+
+    fn modal_wizard_setup_title(&self) -> &str {
+        self.get_string("modal_wizard_setup_title")
+    }
+
+    fn modal_wizard_overwrite(&self, path: &str) -> String {
+        self.format("modal_wizard_overwrite", &[("path", path)])
+    }
+
+    fn modal_wizard_shell_title(&self) -> &str {
+        self.get_string("modal_wizard_shell_title")
+    }
+
+    fn modal_wizard_shell_auto(&self) -> &str {
+        self.get_string("modal_wizard_shell_auto")
+    }
+
+    fn modal_wizard_tab_size_title(&self) -> &str {
+        self.get_string("modal_wizard_tab_size_title")
+    }
+
+    fn modal_wizard_mouse_title(&self) -> &str {
+        self.get_string("modal_wizard_mouse_title")
+    }
+
+    fn modal_wizard_mouse_on(&self) -> &str {
+        self.get_string("modal_wizard_mouse_on")
+    }
+
+    fn modal_wizard_mouse_off(&self) -> &str {
+        self.get_string("modal_wizard_mouse_off")
+    }
+
+    fn modal_file_finder_indexing(&self) -> &str {
+        self.get_string("modal_file_finder_indexing")
+    }
+
+    fn command_title(&self, action: &str) -> String {
+        match action.strip_prefix("go_to_panel_") {
+            Some(number) => self.format("command_go_to_panel", &[("number", number)]),
+            None => self.get_string(&format!("command_{}", action)).to_string(),
+        }
+    }
+
     fn modal_copy_single_prompt(&self, name: &str) -> String {
         self.format("modal_copy_single_prompt", &[("name", name)])
     }
@@ -658,6 +1864,10 @@ impl Translation for RuntimeTranslation {
         )
     }
 
+    fn modal_copy_move_other_panel(&self, path: &str) -> String {
+        self.format("modal_copy_move_other_panel", &[("path", path)])
+    }
+
     fn batch_result_file_copied(&self) -> &str {
         self.get_string("batch_result_file_copied")
     }
@@ -690,6 +1900,46 @@ impl Translation for RuntimeTranslation {
         self.format("batch_result_errors_fmt", &[("count", &count.to_string())])
     }
 
+    fn batch_result_cancelled(&self) -> &str {
+        self.get_string("batch_result_cancelled")
+    }
+
+    fn progress_copy_title(&self) -> &str {
+        self.get_string("progress_copy_title")
+    }
+
+    fn progress_move_title(&self) -> &str {
+        self.get_string("progress_move_title")
+    }
+
+    fn progress_delete_title(&self) -> &str {
+        self.get_string("progress_delete_title")
+    }
+
+    fn progress_permissions_title(&self) -> &str {
+        self.get_string("progress_permissions_title")
+    }
+
+    fn progress_files(&self) -> &str {
+        self.get_string("progress_files")
+    }
+
+    fn progress_size(&self) -> &str {
+        self.get_string("progress_size")
+    }
+
+    fn progress_speed(&self) -> &str {
+        self.get_string("progress_speed")
+    }
+
+    fn progress_cancel_hint(&self) -> &str {
+        self.get_string("progress_cancel_hint")
+    }
+
+    fn progress_cancelling(&self) -> &str {
+        self.get_string("progress_cancelling")
+    }
+
     fn menu_files(&self) -> &str {
         self.get_string("menu_files")
     }
@@ -710,6 +1960,10 @@ impl Translation for RuntimeTranslation {
         self.get_string("menu_preferences")
     }
 
+    fn menu_panel(&self) -> &str {
+        self.get_string("menu_panel")
+    }
+
     fn menu_help(&self) -> &str {
         self.get_string("menu_help")
     }
@@ -718,6 +1972,14 @@ impl Translation for RuntimeTranslation {
         self.get_string("menu_quit")
     }
 
+    fn menu_panel_empty(&self) -> &str {
+        self.get_string("menu_panel_empty")
+    }
+
+    fn panel_menu_item(&self, id: &str) -> &str {
+        self.get_string(&format!("panel_menu_{}", id))
+    }
+
     fn menu_navigate_hint(&self) -> &str {
         self.get_string("menu_navigate_hint")
     }
@@ -730,6 +1992,13 @@ impl Translation for RuntimeTranslation {
         self.get_string("status_dir")
     }
 
+    fn status_entries(&self, dirs: usize, files: usize) -> String {
+        self.format(
+            "status_entries",
+            &[("dirs", &dirs.to_string()), ("files", &files.to_string())],
+        )
+    }
+
     fn status_file(&self) -> &str {
         self.get_string("status_file")
     }
@@ -750,8 +2019,20 @@ impl Translation for RuntimeTranslation {
         self.get_string("status_pos")
     }
 
-    fn status_tab(&self) -> &str {
-        self.get_string("status_tab")
+    fn status_indent_spaces(&self) -> &str {
+        self.get_string("status_indent_spaces")
+    }
+
+    fn status_indent_tabs(&self) -> &str {
+        self.get_string("status_indent_tabs")
+    }
+
+    fn status_mixed_line_endings(&self) -> &str {
+        self.get_string("status_mixed_line_endings")
+    }
+
+    fn status_large_file(&self) -> &str {
+        self.get_string("status_large_file")
     }
 
     fn status_plain_text(&self) -> &str {
@@ -917,6 +2198,14 @@ impl Translation for RuntimeTranslation {
         self.get_string("file_info_git_ignored")
     }
 
+    fn file_info_symlink_target(&self) -> &str {
+        self.get_string("file_info_symlink_target")
+    }
+
+    fn file_info_symlink_broken(&self, target: &str) -> String {
+        self.format("file_info_symlink_broken", &[("target", target)])
+    }
+
     fn file_type_directory(&self) -> &str {
         self.get_string("file_type_directory")
     }
@@ -929,3 +2218,24 @@ impl Translation for RuntimeTranslation {
         self.get_string("file_type_symlink")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_summaries_fill_placeholders() {
+        for lang in ["en", "ru", "de", "es", "fr", "pt", "zh", "hi", "th"] {
+            let t = RuntimeTranslation::new(lang).unwrap();
+            let cancelled = t.status_delete_cancelled(3);
+            assert!(!cancelled.contains('{'), "{}: {}", lang, cancelled);
+            assert!(cancelled.contains('3'), "{}: {}", lang, cancelled);
+
+            let errors = t.status_delete_entry_errors(5, 2, "a.txt");
+            assert!(!errors.contains('{'), "{}: {}", lang, errors);
+            for part in ["5", "2", "a.txt"] {
+                assert!(errors.contains(part), "{}: {}", lang, errors);
+            }
+        }
+    }
+}
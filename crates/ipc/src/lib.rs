@@ -0,0 +1,502 @@
+//! Control of a running termide from other programs.
+//!
+//! With `ipc_server` enabled in the config, termide listens on a Unix socket
+//! in the runtime directory (`$XDG_RUNTIME_DIR/termide-<pid>.sock`) for
+//! commands, one JSON object per line:
+//!
+//! ```text
+//! {"cmd":"open_file","path":"src/main.rs","line":10}
+//! {"cmd":"focus_panel","index":2}
+//! {"cmd":"notify","text":"build done"}
+//! ```
+//!
+//! Each line gets a one-line reply, `{"ok":true}` or
+//! `{"ok":false,"error":"..."}`. Commands are translated into
+//! [`AppCommand`]s and handed to the main loop as [`Message::Command`]
+//! through [`MessageReceiver`], so the server threads never touch the app.
+//!
+//! There is no authentication: the socket is only accessible to the user
+//! running termide (mode 0600, in a directory only the user can access).
+//! Without a runtime directory, sockets go to `termide-<uid>` in the
+//! temporary directory. `termide --send` makes a relative `open_file` path
+//! absolute, as the running termide may be in another directory.
+
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use termide_app_core::{AppCommand, Direction, Message, MessageReceiver, NotificationLevel};
+
+/// Environment variable with the socket of the termide a program runs in
+/// (set for the terminals of termide)
+pub const SOCKET_ENV: &str = "TERMIDE_SOCKET";
+
+/// Longest command line accepted; a longer one ends the connection
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// Command sent by another program.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// Open a file in an editor, at a 1-based line and column
+    OpenFile {
+        path: PathBuf,
+        #[serde(default)]
+        line: Option<usize>,
+        #[serde(default)]
+        column: Option<usize>,
+    },
+    /// Focus the panel group with a 1-based index (as `Alt+number`)
+    FocusPanel { index: usize },
+    /// Show a notification (errors stay until dismissed)
+    Notify {
+        text: String,
+        #[serde(default)]
+        error: bool,
+        /// Seconds on screen instead of the default
+        #[serde(default)]
+        timeout: Option<u64>,
+    },
+}
+
+impl Request {
+    /// Parse a command line
+    pub fn parse(line: &str) -> Result<Self> {
+        serde_json::from_str(line).map_err(|e| anyhow!("invalid command: {}", e))
+    }
+
+    /// App command doing what was asked
+    pub fn into_command(self) -> Result<AppCommand> {
+        Ok(match self {
+            Self::OpenFile { path, line, column } => AppCommand::OpenFile { path, line, column },
+            Self::FocusPanel { index: 0 } => bail!("panel index starts at 1"),
+            Self::FocusPanel { index } => AppCommand::Navigate {
+                direction: Direction::Index(index - 1),
+            },
+            Self::Notify {
+                text,
+                error,
+                timeout,
+            } => AppCommand::Notify {
+                text,
+                level: if error {
+                    NotificationLevel::Error
+                } else {
+                    NotificationLevel::Info
+                },
+                timeout: timeout.map(Duration::from_secs),
+            },
+        })
+    }
+}
+
+/// Socket of the termide process `pid`
+pub fn socket_path(pid: u32) -> PathBuf {
+    runtime_dir().join(format!("termide-{}.sock", pid))
+}
+
+/// Per-user directory for sockets (a directory of the user in the
+/// temporary directory where there is no runtime directory)
+fn runtime_dir() -> PathBuf {
+    dirs::runtime_dir().unwrap_or_else(|| {
+        #[cfg(unix)]
+        let name = format!("termide-{}", user_id());
+        #[cfg(not(unix))]
+        let name = "termide".to_string();
+        std::env::temp_dir().join(name)
+    })
+}
+
+/// Id of the user running termide
+#[cfg(unix)]
+fn user_id() -> libc::uid_t {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+/// Fail unless `dir` is a directory of the user that other users cannot
+/// access: they could replace or connect to sockets in it
+#[cfg(unix)]
+fn check_private(dir: &Path) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != user_id() || metadata.mode() & 0o077 != 0 {
+        bail!(
+            "{} is not a directory accessible only to the user",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Sockets of running termide processes to try, in order: the one of
+/// `TERMIDE_SOCKET`, then the most recently started
+#[cfg(unix)]
+fn sockets() -> Vec<PathBuf> {
+    if let Some(path) = std::env::var_os(SOCKET_ENV).filter(|path| !path.is_empty()) {
+        return vec![PathBuf::from(path)];
+    }
+    let dir = runtime_dir();
+    if check_private(&dir).is_err() {
+        return Vec::new();
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sockets: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("termide-") && name.ends_with(".sock")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    sockets.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    sockets.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Reply line to a command
+fn reply(result: &Result<()>) -> String {
+    match result {
+        Ok(()) => serde_json::json!({ "ok": true }),
+        Err(e) => serde_json::json!({ "ok": false, "error": e.to_string() }),
+    }
+    .to_string()
+}
+
+/// Parse a command line and pass its command to the main loop
+fn handle_line(line: &str, sender: &termide_wake::Sender<Message>) -> Result<()> {
+    let command = Request::parse(line)?.into_command()?;
+    sender
+        .send(Message::Command(command))
+        .map_err(|_| anyhow!("termide is shutting down"))
+}
+
+/// Answer the command lines of a connection until it is closed or sends a
+/// line longer than [`MAX_LINE_LEN`]
+fn serve(mut reader: impl BufRead, mut writer: impl Write, sender: &termide_wake::Sender<Message>) {
+    loop {
+        let mut line = String::new();
+        // Read one byte more than allowed to see whether the line fits
+        match (&mut reader)
+            .take(MAX_LINE_LEN as u64 + 1)
+            .read_line(&mut line)
+        {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if line.len() > MAX_LINE_LEN && !line.ends_with('\n') {
+            let error = Err(anyhow!("command longer than {} bytes", MAX_LINE_LEN));
+            let _ = writeln!(writer, "{}", reply(&error));
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = handle_line(&line, sender);
+        if writeln!(writer, "{}", reply(&result)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Server listening for commands of other programs.
+///
+/// The socket is removed when the server is dropped.
+pub struct IpcServer {
+    path: PathBuf,
+    receiver: Receiver<Message>,
+}
+
+impl IpcServer {
+    /// Listen on a socket at `path`, replacing a socket left there. The
+    /// directory of `path` is created accessible to the user only; an
+    /// existing one other users can access is refused
+    #[cfg(unix)]
+    pub fn start(path: PathBuf) -> Result<Self> {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        use std::os::unix::net::UnixListener;
+
+        if let Some(dir) = path.parent() {
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)?;
+            // The socket is only reachable through the directory, so it
+            // cannot be connected to before its mode is set
+            check_private(dir)?;
+        }
+        // Left by a crashed process with the same pid
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+        let (sender, receiver) = termide_wake::channel();
+        std::thread::Builder::new()
+            .name("ipc-server".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+                    let sender = sender.clone();
+                    let _ = std::thread::Builder::new()
+                        .name("ipc-client".to_string())
+                        .spawn(move || {
+                            if let Ok(reader) = stream.try_clone() {
+                                serve(std::io::BufReader::new(reader), stream, &sender);
+                            }
+                        });
+                }
+            })?;
+        Ok(Self { path, receiver })
+    }
+
+    /// Unix domain sockets are not available
+    #[cfg(not(unix))]
+    pub fn start(_path: PathBuf) -> Result<Self> {
+        bail!("the IPC server needs Unix domain sockets")
+    }
+
+    /// Path of the socket
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl MessageReceiver for IpcServer {
+    fn poll_messages(&mut self) -> Vec<Message> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Send a command line to a running termide (the one of `TERMIDE_SOCKET`,
+/// or else the most recently started) and return the error it replied
+/// with as an error
+#[cfg(unix)]
+pub fn send(command: &str) -> Result<()> {
+    use std::os::unix::net::UnixStream;
+
+    let command = match std::env::current_dir() {
+        Ok(cwd) => absolute_path(command, &cwd),
+        Err(_) => command.to_string(),
+    };
+    for path in sockets() {
+        if let Ok(stream) = UnixStream::connect(&path) {
+            return exchange(stream, &command);
+        }
+    }
+    bail!("no running termide with ipc_server enabled found")
+}
+
+/// Unix domain sockets are not available
+#[cfg(not(unix))]
+pub fn send(_command: &str) -> Result<()> {
+    bail!("sending commands needs Unix domain sockets")
+}
+
+/// Command line with a relative `open_file` path made absolute against
+/// `cwd`; other commands and lines that are not JSON are left as they are
+fn absolute_path(command: &str, cwd: &Path) -> String {
+    let Ok(mut request) = serde_json::from_str::<serde_json::Value>(command) else {
+        return command.to_string();
+    };
+    if request["cmd"] != "open_file" {
+        return command.to_string();
+    }
+    let path = request["path"]
+        .as_str()
+        .map(Path::new)
+        .filter(|path| path.is_relative())
+        .and_then(|path| cwd.join(path).to_str().map(str::to_string));
+    match path {
+        Some(path) => {
+            request["path"] = path.into();
+            request.to_string()
+        }
+        None => command.to_string(),
+    }
+}
+
+/// Write a command to a connected socket and read the reply
+#[cfg(unix)]
+fn exchange(mut stream: std::os::unix::net::UnixStream, command: &str) -> Result<()> {
+    // Line breaks can only be whitespace in JSON
+    writeln!(stream, "{}", command.trim().replace(['\r', '\n'], " "))?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    let mut line = String::new();
+    std::io::BufReader::new(stream).read_line(&mut line)?;
+
+    let reply: serde_json::Value =
+        serde_json::from_str(&line).map_err(|_| anyhow!("no reply from termide"))?;
+    if reply["ok"].as_bool() == Some(true) {
+        Ok(())
+    } else {
+        bail!("{}", reply["error"].as_str().unwrap_or("command failed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_become_commands() {
+        let request = Request::parse(r#"{"cmd":"open_file","path":"src/main.rs","line":10}"#);
+        assert!(matches!(
+            request.unwrap().into_command().unwrap(),
+            AppCommand::OpenFile { path, line: Some(10), column: None }
+                if path == Path::new("src/main.rs")
+        ));
+        let focus = Request::parse(r#"{"cmd":"focus_panel","index":2}"#).unwrap();
+        assert!(matches!(
+            focus.into_command().unwrap(),
+            AppCommand::Navigate {
+                direction: Direction::Index(1)
+            }
+        ));
+        let notify = Request::parse(r#"{"cmd":"notify","text":"build done"}"#).unwrap();
+        assert_eq!(
+            notify,
+            Request::Notify {
+                text: "build done".to_string(),
+                error: false,
+                timeout: None,
+            }
+        );
+        let failed =
+            Request::parse(r#"{"cmd":"notify","text":"tests failed","error":true,"timeout":30}"#)
+                .unwrap();
+        assert!(matches!(
+            failed.into_command().unwrap(),
+            AppCommand::Notify {
+                level: NotificationLevel::Error,
+                timeout: Some(timeout),
+                ..
+            } if timeout == Duration::from_secs(30)
+        ));
+
+        assert!(Request::parse(r#"{"cmd":"format_disk"}"#).is_err());
+        assert!(Request::parse(r#"{"cmd":"focus_panel"}"#).is_err());
+        let zero = Request::parse(r#"{"cmd":"focus_panel","index":0}"#).unwrap();
+        assert!(zero.into_command().is_err());
+    }
+
+    #[test]
+    fn test_malformed_lines_get_error_replies() {
+        let (sender, receiver) = termide_wake::channel();
+        let input = "not json\n\n{\"cmd\":\"notify\",\"text\":\"hi\"}\n\u{0}\n";
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output, &sender);
+
+        let replies: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["ok"], false);
+        assert!(replies[0]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("invalid command"));
+        assert_eq!(replies[1]["ok"], true);
+        assert_eq!(replies[2]["ok"], false);
+        assert_eq!(receiver.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_long_lines_end_the_connection() {
+        let (sender, receiver) = termide_wake::channel();
+        let input = format!(
+            "{}\n{{\"cmd\":\"notify\",\"text\":\"hi\"}}\n",
+            "x".repeat(MAX_LINE_LEN + 1)
+        );
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output, &sender);
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        let reply: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(reply["ok"], false);
+        assert!(reply["error"].as_str().unwrap().contains("longer than"));
+        assert_eq!(receiver.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_relative_open_paths_become_absolute() {
+        let cwd = Path::new("/home/user/project");
+        let open = absolute_path(r#"{"cmd":"open_file","path":"src/main.rs","line":3}"#, cwd);
+        assert_eq!(
+            Request::parse(&open).unwrap(),
+            Request::OpenFile {
+                path: PathBuf::from("/home/user/project/src/main.rs"),
+                line: Some(3),
+                column: None,
+            }
+        );
+
+        let absolute = r#"{"cmd":"open_file","path":"/etc/hosts"}"#;
+        assert_eq!(absolute_path(absolute, cwd), absolute);
+        let notify = r#"{"cmd":"notify","text":"src/main.rs"}"#;
+        assert_eq!(absolute_path(notify, cwd), notify);
+        assert_eq!(absolute_path("not json", cwd), "not json");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shared_socket_directory_is_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(IpcServer::start(dir.path().join("termide-test.sock")).is_err());
+
+        let nested = dir.path().join("private/termide-test.sock");
+        let server = IpcServer::start(nested.clone()).unwrap();
+        let mode = std::fs::metadata(nested.parent().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+        drop(server);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commands_over_socket() {
+        use std::os::unix::net::UnixStream;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Created private by the server
+        let path = dir.path().join("run/termide-test.sock");
+        let mut server = IpcServer::start(path.clone()).unwrap();
+        let connect = || UnixStream::connect(&path).unwrap();
+
+        exchange(connect(), r#"{"cmd":"notify","text":"build done"}"#).unwrap();
+        let error = exchange(connect(), r#"{"cmd":"open_file"}"#).unwrap_err();
+        assert!(error.to_string().contains("path"));
+
+        let messages = server.poll_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(matches!(
+            &messages[0],
+            Message::Command(AppCommand::Notify { text, level: NotificationLevel::Info, .. })
+                if text == "build done"
+        ));
+
+        drop(server);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,22 @@
+//! Dragging panels with the mouse.
+
+/// Where a dragged panel is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropTarget {
+    /// Into a group, before the panel at `index` (`index == len` appends).
+    Group { group: usize, index: usize },
+    /// Into a new group inserted before the group at this index
+    /// (the group count appends).
+    NewGroup(usize),
+}
+
+/// Panel being dragged by its title bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelDrag {
+    /// Group of the dragged panel.
+    pub group: usize,
+    /// Index of the dragged panel in its group.
+    pub panel: usize,
+    /// Drop target under the mouse (None = dropping cancels).
+    pub target: Option<DropTarget>,
+}
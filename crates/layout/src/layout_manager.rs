@@ -1,11 +1,13 @@
 //! Layout manager for panel arrangement.
 
 use anyhow::{anyhow, Result};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
+use termide_config::constants::MIN_PANEL_GROUP_WIDTH;
 use termide_config::Config;
 use termide_core::Panel;
 
-use crate::PanelGroup;
+use crate::{DropTarget, PanelDrag, PanelGroup, TabHit};
 
 /// Panel layout manager with accordion support.
 pub struct LayoutManager {
@@ -13,6 +15,12 @@ pub struct LayoutManager {
     pub panel_groups: Vec<PanelGroup>,
     /// Current focus (active group index).
     pub focus: usize,
+    /// Panel being dragged with the mouse.
+    pub drag: Option<PanelDrag>,
+    /// Border under the mouse (index of the group left of it).
+    pub hovered_border: Option<usize>,
+    /// Border being dragged to resize the groups next to it.
+    pub resizing_border: Option<usize>,
 }
 
 impl LayoutManager {
@@ -21,6 +29,9 @@ impl LayoutManager {
         Self {
             panel_groups: Vec::new(),
             focus: 0,
+            drag: None,
+            hovered_border: None,
+            resizing_border: None,
         }
     }
 
@@ -516,6 +527,368 @@ impl LayoutManager {
         }
     }
 
+    /// Areas of the groups in `area`.
+    fn group_areas(&self, area: Rect) -> Vec<Rect> {
+        // Auto-width groups take all the space, the layout shrinks them
+        let constraints: Vec<Constraint> = self
+            .panel_groups
+            .iter()
+            .map(|g| Constraint::Length(g.width.unwrap_or(area.width).max(20)))
+            .collect();
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(area)
+            .to_vec()
+    }
+
+    /// Areas of all panels in `area` for mouse hit testing.
+    ///
+    /// Returns `(group_idx, panel_idx, rect, is_expanded)`; collapsed panels
+    /// are one line high. A group shown as tabs has only its expanded panel
+    /// (over the whole group, the tabs are in its top border).
+    pub fn panel_rects(&self, area: Rect) -> Vec<(usize, usize, Rect, bool)> {
+        let mut result = Vec::new();
+        for (group_idx, (group, group_area)) in self
+            .panel_groups
+            .iter()
+            .zip(self.group_areas(area))
+            .enumerate()
+        {
+            if group.is_empty() || group_area.height == 0 {
+                continue;
+            }
+
+            let expanded_idx = group.expanded_index();
+            if group.tabs {
+                result.push((group_idx, expanded_idx, group_area, true));
+                continue;
+            }
+            let constraints: Vec<Constraint> = (0..group.len())
+                .map(|i| {
+                    if i == expanded_idx {
+                        Constraint::Min(0)
+                    } else {
+                        Constraint::Length(1)
+                    }
+                })
+                .collect();
+            let panel_areas = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(group_area);
+
+            for (panel_idx, rect) in panel_areas.iter().enumerate() {
+                result.push((group_idx, panel_idx, *rect, panel_idx == expanded_idx));
+            }
+        }
+        result
+    }
+
+    /// Toggle showing the panels of the active group as tabs.
+    pub fn toggle_tabs(&mut self) {
+        if let Some(group) = self.panel_groups.get_mut(self.focus) {
+            group.tabs = !group.tabs;
+        }
+    }
+
+    /// Area of the tab strip of a group shown as tabs: the top border of
+    /// the group without its corners.
+    pub fn tab_strip_area(group_area: Rect) -> Rect {
+        Rect::new(
+            group_area.x + 1,
+            group_area.y,
+            group_area.width.saturating_sub(2),
+            group_area.height.min(1),
+        )
+    }
+
+    /// Tab under `(x, y)` in a group shown as tabs, with the group index.
+    pub fn tab_at(&self, area: Rect, x: u16, y: u16) -> Option<(usize, TabHit)> {
+        self.panel_groups
+            .iter()
+            .zip(self.group_areas(area))
+            .enumerate()
+            .filter(|(_, (group, _))| group.tabs && !group.is_empty())
+            .find_map(|(group_idx, (group, group_area))| {
+                let strip = Self::tab_strip_area(group_area);
+                if !strip.contains((x, y).into()) {
+                    return None;
+                }
+                let hit = group.tab_strip(strip.width).hit(x - strip.x)?;
+                Some((group_idx, hit))
+            })
+    }
+
+    /// Panel whose title bar or tab is under `(x, y)`, as
+    /// `(group_idx, panel_idx)`.
+    pub fn header_at(&self, area: Rect, x: u16, y: u16) -> Option<(usize, usize)> {
+        if let Some((group_idx, TabHit::Select(panel_idx) | TabHit::Close(panel_idx))) =
+            self.tab_at(area, x, y)
+        {
+            return Some((group_idx, panel_idx));
+        }
+        self.panel_rects(area)
+            .into_iter()
+            .find(|(group_idx, _, rect, _)| {
+                !self.panel_groups[*group_idx].tabs && rect.y == y && rect.contains((x, y).into())
+            })
+            .map(|(group_idx, panel_idx, ..)| (group_idx, panel_idx))
+    }
+
+    /// Start dragging a panel by its title bar.
+    pub fn start_drag(&mut self, group: usize, panel: usize) {
+        let exists = self
+            .panel_groups
+            .get(group)
+            .is_some_and(|g| panel < g.len());
+        self.drag = exists.then_some(PanelDrag {
+            group,
+            panel,
+            target: None,
+        });
+    }
+
+    /// Update the drop target of the dragged panel for the mouse at `(x, y)`.
+    ///
+    /// The outer quarters of a group drop into a new group on that side,
+    /// the rest drops before or after the panel under the mouse. Returns
+    /// true if the target changed.
+    pub fn update_drag(&mut self, area: Rect, x: u16, y: u16) -> bool {
+        let Some(drag) = self.drag else {
+            return false;
+        };
+        let target = self
+            .drop_target_at(area, x, y)
+            .filter(|target| !self.is_noop_drop(&drag, target));
+        if drag.target == target {
+            return false;
+        }
+        self.drag = Some(PanelDrag { target, ..drag });
+        true
+    }
+
+    /// Drop the dragged panel on its target.
+    ///
+    /// Returns true if the panel moved; dropping outside a target cancels.
+    pub fn finish_drag(&mut self, available_width: u16) -> Result<bool> {
+        let Some(PanelDrag {
+            group,
+            panel,
+            target: Some(target),
+        }) = self.drag.take()
+        else {
+            return Ok(false);
+        };
+        self.move_panel(group, panel, target, available_width)?;
+        Ok(true)
+    }
+
+    /// Drop target under `(x, y)`.
+    fn drop_target_at(&self, area: Rect, x: u16, y: u16) -> Option<DropTarget> {
+        let (group_idx, group_area) = self
+            .group_areas(area)
+            .into_iter()
+            .enumerate()
+            .find(|(_, rect)| rect.contains((x, y).into()))?;
+
+        let edge = (group_area.width / 4).max(1);
+        if x < group_area.x + edge {
+            return Some(DropTarget::NewGroup(group_idx));
+        }
+        if x >= group_area.right().saturating_sub(edge) {
+            return Some(DropTarget::NewGroup(group_idx + 1));
+        }
+
+        self.panel_rects(area)
+            .into_iter()
+            .find(|(g, _, rect, _)| *g == group_idx && rect.contains((x, y).into()))
+            .map(|(_, panel_idx, rect, _)| {
+                let upper_half = (y - rect.y) * 2 < rect.height;
+                DropTarget::Group {
+                    group: group_idx,
+                    index: if upper_half { panel_idx } else { panel_idx + 1 },
+                }
+            })
+    }
+
+    /// Dropping on `target` leaves the panel where it is.
+    fn is_noop_drop(&self, drag: &PanelDrag, target: &DropTarget) -> bool {
+        let alone = self.panel_groups.get(drag.group).map(|g| g.len()) == Some(1);
+        match *target {
+            DropTarget::Group { group, index } => {
+                group == drag.group && (index == drag.panel || index == drag.panel + 1)
+            }
+            DropTarget::NewGroup(index) => {
+                alone && (index == drag.group || index == drag.group + 1)
+            }
+        }
+    }
+
+    /// Area of the drop indicator of the dragged panel in `area`:
+    /// a column between groups or a row between panels of a group.
+    pub fn drop_indicator(&self, area: Rect) -> Option<Rect> {
+        let target = self.drag?.target?;
+        let group_areas = self.group_areas(area);
+        match target {
+            DropTarget::NewGroup(index) => {
+                let x = match group_areas.get(index) {
+                    Some(rect) => rect.x,
+                    None => group_areas.last()?.right().saturating_sub(1),
+                };
+                Some(Rect::new(x, area.y, 1, area.height))
+            }
+            DropTarget::Group { group, index } => {
+                let group_area = *group_areas.get(group)?;
+                let panels: Vec<Rect> = self
+                    .panel_rects(area)
+                    .into_iter()
+                    .filter(|(g, ..)| *g == group)
+                    .map(|(_, _, rect, _)| rect)
+                    .collect();
+                let y = match panels.get(index) {
+                    Some(rect) => rect.y,
+                    None => panels.last()?.bottom().saturating_sub(1),
+                };
+                Some(Rect::new(group_area.x, y, group_area.width, 1))
+            }
+        }
+    }
+
+    /// Move a panel to `target`, within or across groups.
+    ///
+    /// The moved panel becomes the expanded panel of its new group and
+    /// the group gets focus; a group left empty is removed.
+    pub fn move_panel(
+        &mut self,
+        group_idx: usize,
+        panel_idx: usize,
+        target: DropTarget,
+        available_width: u16,
+    ) -> Result<()> {
+        let drag = PanelDrag {
+            group: group_idx,
+            panel: panel_idx,
+            target: None,
+        };
+        if self.is_noop_drop(&drag, &target) {
+            return Ok(());
+        }
+        let group = self
+            .panel_groups
+            .get_mut(group_idx)
+            .ok_or_else(|| anyhow!("No such group"))?;
+        let was_alone = group.len() == 1;
+        let panel = group
+            .remove_panel(panel_idx)
+            .ok_or_else(|| anyhow!("No such panel"))?;
+
+        // Indices of the target shift when the source group is removed
+        // or the panel left a position before it
+        if was_alone {
+            self.panel_groups.remove(group_idx);
+        }
+        let shift_group = |index: usize| {
+            if was_alone && index > group_idx {
+                index - 1
+            } else {
+                index
+            }
+        };
+
+        match target {
+            DropTarget::Group { group, index } => {
+                let index = if group == group_idx && index > panel_idx {
+                    index - 1
+                } else {
+                    index
+                };
+                let group = shift_group(group);
+                let target_group = self
+                    .panel_groups
+                    .get_mut(group)
+                    .ok_or_else(|| anyhow!("No such group"))?;
+                let index = index.min(target_group.len());
+                target_group.insert_panel(index, panel);
+                target_group.set_expanded(index);
+                self.focus = group;
+            }
+            DropTarget::NewGroup(index) => {
+                let index = shift_group(index).min(self.panel_groups.len());
+                self.panel_groups.insert(index, PanelGroup::new(panel));
+                self.focus = index;
+            }
+        }
+
+        let groups_changed = was_alone || matches!(target, DropTarget::NewGroup(_));
+        if groups_changed {
+            self.redistribute_widths_proportionally(available_width);
+        }
+        Ok(())
+    }
+
+    /// Border between two groups under `(x, y)`: the right edge of a group
+    /// or the left edge of the next one. Returns the index of the left group.
+    pub fn border_at(&self, area: Rect, x: u16, y: u16) -> Option<usize> {
+        if y < area.y || y >= area.bottom() {
+            return None;
+        }
+        self.group_areas(area)
+            .windows(2)
+            .position(|pair| x + 1 == pair[0].right() || x == pair[1].x)
+    }
+
+    /// Area of the border right of group `index` (both edge columns).
+    pub fn border_rect(&self, area: Rect, index: usize) -> Option<Rect> {
+        let group_areas = self.group_areas(area);
+        let left = group_areas.get(index)?;
+        let right = group_areas.get(index + 1)?;
+        let x = left.right().saturating_sub(1);
+        Some(Rect::new(x, area.y, right.x + 1 - x, area.height))
+    }
+
+    /// Move the border right of group `index` to column `x`, resizing the
+    /// two groups next to it (each keeps at least `MIN_PANEL_GROUP_WIDTH`).
+    ///
+    /// Returns true if the widths changed.
+    pub fn resize_border(&mut self, index: usize, x: u16, available_width: u16) -> bool {
+        if index + 1 >= self.panel_groups.len() {
+            return false;
+        }
+
+        // Freeze auto-width groups so only the two groups change
+        let widths = self.calculate_actual_widths(available_width);
+        for (group, width) in self.panel_groups.iter_mut().zip(&widths) {
+            group.width = Some(group.width.unwrap_or(*width).max(MIN_PANEL_GROUP_WIDTH));
+        }
+
+        let start: u16 = self.panel_groups[..index]
+            .iter()
+            .filter_map(|g| g.width)
+            .sum();
+        let left = self.panel_groups[index]
+            .width
+            .unwrap_or(MIN_PANEL_GROUP_WIDTH);
+        let right = self.panel_groups[index + 1]
+            .width
+            .unwrap_or(MIN_PANEL_GROUP_WIDTH);
+        let pair = left + right;
+        if pair < 2 * MIN_PANEL_GROUP_WIDTH {
+            return false;
+        }
+
+        // The left group's right edge follows the mouse
+        let new_left = (x + 1)
+            .saturating_sub(start)
+            .clamp(MIN_PANEL_GROUP_WIDTH, pair - MIN_PANEL_GROUP_WIDTH);
+        if new_left == left {
+            return false;
+        }
+        self.panel_groups[index].width = Some(new_left);
+        self.panel_groups[index + 1].width = Some(pair - new_left);
+        true
+    }
+
     /// Set focus to specific group index.
     pub fn set_focus(&mut self, index: usize) {
         if index < self.panel_groups.len() {
@@ -544,3 +917,182 @@ impl Default for LayoutManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::buffer::Buffer;
+    use std::any::Any;
+    use termide_core::{PanelEvent, RenderContext};
+
+    struct TestPanel(&'static str);
+
+    impl Panel for TestPanel {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        fn title(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn render(&mut self, _area: Rect, _buf: &mut Buffer, _ctx: &RenderContext) {}
+
+        fn handle_key(&mut self, _key: crossterm::event::KeyEvent) -> Vec<PanelEvent> {
+            Vec::new()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    /// Layout with a group per entry of `groups`
+    fn layout(groups: &[&[&'static str]]) -> LayoutManager {
+        let mut manager = LayoutManager::new();
+        for titles in groups {
+            let mut group = PanelGroup::new(Box::new(TestPanel(titles[0])));
+            for title in &titles[1..] {
+                group.add_panel(Box::new(TestPanel(title)));
+            }
+            manager.panel_groups.push(group);
+        }
+        manager
+    }
+
+    fn titles(manager: &LayoutManager) -> Vec<Vec<String>> {
+        manager
+            .panel_groups
+            .iter()
+            .map(|g| g.panels().iter().map(|p| p.title()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_move_panel_within_and_across_groups() {
+        let mut manager = layout(&[&["a", "b", "c"], &["d"]]);
+        manager
+            .move_panel(0, 0, DropTarget::Group { group: 0, index: 3 }, 100)
+            .unwrap();
+        assert_eq!(titles(&manager), [vec!["b", "c", "a"], vec!["d"]]);
+        assert_eq!(manager.panel_groups[0].expanded_index(), 2);
+
+        // The last panel of a group leaves it
+        manager
+            .move_panel(1, 0, DropTarget::Group { group: 0, index: 1 }, 100)
+            .unwrap();
+        assert_eq!(titles(&manager), [vec!["b", "d", "c", "a"]]);
+        assert_eq!(manager.focus, 0);
+
+        manager
+            .move_panel(0, 3, DropTarget::NewGroup(1), 100)
+            .unwrap();
+        assert_eq!(titles(&manager), [vec!["b", "d", "c"], vec!["a"]]);
+        assert_eq!(manager.focus, 1);
+    }
+
+    #[test]
+    fn test_resize_border_with_mouse() {
+        let mut manager = layout(&[&["a"], &["b"], &["c"]]);
+        let area = Rect::new(0, 1, 90, 20);
+
+        // Auto-width groups are 30 columns wide
+        assert_eq!(manager.border_at(area, 29, 5), Some(0));
+        assert_eq!(manager.border_at(area, 30, 5), Some(0));
+        assert_eq!(manager.border_at(area, 31, 5), None);
+        assert_eq!(manager.border_at(area, 60, 5), Some(1));
+        assert_eq!(manager.border_at(area, 30, 0), None);
+        assert_eq!(manager.border_rect(area, 1), Some(Rect::new(59, 1, 2, 20)));
+
+        assert!(manager.resize_border(0, 39, 90));
+        let widths: Vec<_> = manager.panel_groups.iter().map(|g| g.width).collect();
+        assert_eq!(widths, [Some(40), Some(20), Some(30)]);
+
+        // Clamped to the minimum width of the groups
+        assert!(!manager.resize_border(0, 60, 90));
+        assert!(!manager.resize_border(1, 5, 90));
+        assert!(manager.resize_border(1, 64, 90));
+        let widths: Vec<_> = manager.panel_groups.iter().map(|g| g.width).collect();
+        assert_eq!(widths, [Some(40), Some(25), Some(25)]);
+    }
+
+    #[test]
+    fn test_drag_drops_on_target_under_mouse() {
+        let mut manager = layout(&[&["a"], &["b", "c"]]);
+        manager.panel_groups[0].width = Some(40);
+        manager.panel_groups[1].width = Some(40);
+        let area = Rect::new(0, 1, 80, 20);
+
+        // Own position is not a target
+        manager.start_drag(0, 0);
+        assert!(!manager.update_drag(area, 20, 10));
+        assert_eq!(manager.drop_indicator(area), None);
+
+        // Top half of the collapsed panel `b` drops before it
+        assert!(manager.update_drag(area, 60, 1));
+        assert_eq!(
+            manager.drag.unwrap().target,
+            Some(DropTarget::Group { group: 1, index: 0 })
+        );
+        assert_eq!(manager.drop_indicator(area), Some(Rect::new(40, 1, 40, 1)));
+        assert!(manager.finish_drag(80).unwrap());
+        assert_eq!(titles(&manager), [vec!["a", "b", "c"]]);
+        assert!(manager.drag.is_none());
+
+        // Dropping outside any target cancels
+        manager.start_drag(0, 1);
+        manager.update_drag(area, 40, 30);
+        assert!(!manager.finish_drag(80).unwrap());
+        assert_eq!(titles(&manager), [vec!["a", "b", "c"]]);
+    }
+
+    #[test]
+    fn test_tabbed_group_takes_whole_area() {
+        let mut manager = layout(&[&["a"], &["b", "c"]]);
+        manager.panel_groups[0].width = Some(40);
+        manager.panel_groups[1].width = Some(40);
+        manager.focus = 1;
+        manager.toggle_tabs();
+        assert!(manager.panel_groups[1].tabs);
+        let area = Rect::new(0, 1, 80, 20);
+
+        let rects: Vec<_> = manager
+            .panel_rects(area)
+            .into_iter()
+            .filter(|(g, ..)| *g == 1)
+            .collect();
+        assert_eq!(rects, [(1, 0, Rect::new(40, 1, 40, 20), true)]);
+
+        // `┌[X] b │[X] c │`
+        assert_eq!(manager.tab_at(area, 41, 1), Some((1, TabHit::Close(0))));
+        assert_eq!(manager.tab_at(area, 45, 1), Some((1, TabHit::Select(0))));
+        assert_eq!(manager.tab_at(area, 49, 1), Some((1, TabHit::Close(1))));
+        assert_eq!(manager.tab_at(area, 53, 1), Some((1, TabHit::Select(1))));
+        assert_eq!(manager.tab_at(area, 53, 2), None);
+        // Groups without tabs have none
+        assert_eq!(manager.tab_at(area, 1, 1), None);
+    }
+
+    #[test]
+    fn test_header_at_finds_title_bars_and_tabs() {
+        let mut manager = layout(&[&["a", "b"], &["c", "d"]]);
+        manager.panel_groups[0].width = Some(40);
+        manager.panel_groups[1].width = Some(40);
+        manager.focus = 1;
+        manager.toggle_tabs();
+        let area = Rect::new(0, 1, 80, 20);
+
+        // `a` is expanded above the collapsed title bar of `b`
+        assert_eq!(manager.header_at(area, 10, 1), Some((0, 0)));
+        assert_eq!(manager.header_at(area, 10, 20), Some((0, 1)));
+        assert_eq!(manager.header_at(area, 10, 2), None);
+        // Tabs of the other group, whichever is shown
+        assert_eq!(manager.header_at(area, 45, 1), Some((1, 0)));
+        assert_eq!(manager.header_at(area, 53, 1), Some((1, 1)));
+        assert_eq!(manager.header_at(area, 53, 2), None);
+    }
+}
@@ -3,9 +3,15 @@
 //! This crate provides panel layout management with accordion support:
 //! - `PanelGroup` - vertical stack of panels with expandable accordion
 //! - `LayoutManager` - horizontal arrangement of panel groups
+//! - `PanelDrag` - panel dragged with the mouse and its drop target
+//! - `TabStrip` - header row of a group showing its panels as tabs
 
+pub mod drag;
 pub mod layout_manager;
 pub mod panel_group;
+pub mod tab_strip;
 
+pub use drag::{DropTarget, PanelDrag};
 pub use layout_manager::LayoutManager;
 pub use panel_group::PanelGroup;
+pub use tab_strip::{Tab, TabHit, TabOverflow, TabStrip, TAB_CLOSE_BUTTON, TAB_SEPARATOR};
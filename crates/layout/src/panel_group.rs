@@ -2,12 +2,17 @@
 
 use termide_core::Panel;
 
+use crate::TabStrip;
+
 /// Group of panels in accordion (vertical stack).
 pub struct PanelGroup {
     panels: Vec<Box<dyn Panel>>,
     expanded_index: usize,
     /// Width in characters (None = auto-distribution).
     pub width: Option<u16>,
+    /// Show the panels as tabs in one header row instead of stacked
+    /// title bars.
+    pub tabs: bool,
 }
 
 impl PanelGroup {
@@ -17,6 +22,7 @@ impl PanelGroup {
             panels: vec![panel],
             expanded_index: 0,
             width: None,
+            tabs: false,
         }
     }
 
@@ -25,6 +31,15 @@ impl PanelGroup {
         self.panels.push(panel);
     }
 
+    /// Insert panel into group before `index` (appends past the end).
+    pub fn insert_panel(&mut self, index: usize, panel: Box<dyn Panel>) {
+        let index = index.min(self.panels.len());
+        self.panels.insert(index, panel);
+        if index <= self.expanded_index && self.panels.len() > 1 {
+            self.expanded_index += 1;
+        }
+    }
+
     /// Remove panel from group by index.
     pub fn remove_panel(&mut self, index: usize) -> Option<Box<dyn Panel>> {
         if index >= self.panels.len() {
@@ -144,6 +159,12 @@ impl PanelGroup {
         Ok(())
     }
 
+    /// Tabs of the panels fitted into `width` columns.
+    pub fn tab_strip(&self, width: u16) -> TabStrip {
+        let titles: Vec<String> = self.panels.iter().map(|panel| panel.title()).collect();
+        TabStrip::new(&titles, self.expanded_index, width)
+    }
+
     /// Take all panels from group (empties the group).
     pub fn take_panels(self) -> Vec<Box<dyn Panel>> {
         self.panels
@@ -0,0 +1,258 @@
+//! Tab strip of a group shown as tabs.
+//!
+//! A group in tab mode shows its panels as tabs in the top border of the
+//! expanded panel instead of stacked title bars:
+//!
+//! ```text
+//! ┌[X] main.rs │[X] Terminal │[X] Fil… │ +2┐
+//! ```
+//!
+//! Titles are shortened with an ellipsis to fit the group. When even short
+//! tabs don't fit, the strip scrolls to keep the active tab visible and the
+//! hidden tabs are counted in a `+N` marker.
+
+/// Close button at the start of each tab.
+pub const TAB_CLOSE_BUTTON: &str = "[X]";
+
+/// Separator drawn after each tab.
+pub const TAB_SEPARATOR: &str = "│";
+
+/// Columns of a tab besides its title: `[X] `, a space and the separator.
+const TAB_OVERHEAD: usize = 6;
+
+/// Titles are not shortened below this width (with the ellipsis) while
+/// other tabs can be hidden instead.
+const MIN_TITLE_WIDTH: usize = 6;
+
+/// A tab shown in the strip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tab {
+    /// Index of the panel in its group.
+    pub panel: usize,
+    /// Column of the tab from the start of the strip.
+    pub x: u16,
+    /// Text of the tab: `[X] title ` (the separator follows it).
+    pub label: String,
+}
+
+impl Tab {
+    /// Width of the label in columns.
+    pub fn width(&self) -> u16 {
+        self.label.chars().count() as u16
+    }
+}
+
+/// `+N` marker counting the tabs that don't fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabOverflow {
+    /// Column of the marker from the start of the strip.
+    pub x: u16,
+    /// Text of the marker: ` +N`.
+    pub label: String,
+    /// Hidden panel selected by clicking the marker.
+    pub panel: usize,
+}
+
+/// What a click on the tab strip hits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabHit {
+    /// Tab of the panel (or the `+N` marker showing a hidden panel).
+    Select(usize),
+    /// Close button of the panel's tab.
+    Close(usize),
+}
+
+/// Tabs of a group fitted into the width of its strip.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TabStrip {
+    /// Visible tabs, left to right.
+    pub tabs: Vec<Tab>,
+    /// Marker of hidden tabs (None = all tabs are shown).
+    pub overflow: Option<TabOverflow>,
+}
+
+impl TabStrip {
+    /// Fit tabs with `titles` into `width` columns, keeping the tab of the
+    /// `active` panel visible.
+    pub fn new(titles: &[String], active: usize, width: u16) -> Self {
+        let width = usize::from(width);
+        let lens: Vec<usize> = titles.iter().map(|t| t.chars().count()).collect();
+        if lens.is_empty() {
+            return Self::default();
+        }
+        let active = active.min(lens.len() - 1);
+        let fits = |limit: usize| {
+            lens.iter()
+                .map(|&len| len.min(limit) + TAB_OVERHEAD)
+                .sum::<usize>()
+                <= width
+        };
+
+        // Shorten the longest titles first while all tabs fit
+        let longest = lens.iter().copied().max().unwrap_or(0);
+        if let Some(limit) = (MIN_TITLE_WIDTH.min(longest)..=longest)
+            .rev()
+            .find(|&limit| fits(limit))
+        {
+            return Self::build(titles, 0..lens.len(), limit, None);
+        }
+
+        // Scroll: as many short tabs around the active one as fit next to
+        // the widest marker
+        let marker_width = overflow_label(lens.len() - 1).chars().count();
+        let budget = width.saturating_sub(marker_width);
+        let cost = |i: usize| lens[i].min(MIN_TITLE_WIDTH) + TAB_OVERHEAD;
+        let (mut first, mut last) = (active, active + 1);
+        let mut used = cost(active);
+        while last < lens.len() && used + cost(last) <= budget {
+            used += cost(last);
+            last += 1;
+        }
+        while first > 0 && used + cost(first - 1) <= budget {
+            used += cost(first - 1);
+            first -= 1;
+        }
+        // Even the active tab alone is too wide
+        let limit = if used > budget {
+            budget.saturating_sub(TAB_OVERHEAD)
+        } else {
+            MIN_TITLE_WIDTH
+        };
+        if limit == 0 {
+            return Self::default();
+        }
+
+        let hidden = lens.len() - (last - first);
+        let next_hidden = if last < lens.len() { last } else { 0 };
+        Self::build(titles, first..last, limit, Some((hidden, next_hidden)))
+    }
+
+    /// Strip of the tabs in `range` with titles of at most `limit` columns,
+    /// followed by a marker of `(hidden count, panel it selects)`.
+    fn build(
+        titles: &[String],
+        range: std::ops::Range<usize>,
+        limit: usize,
+        hidden: Option<(usize, usize)>,
+    ) -> Self {
+        let mut x = 0u16;
+        let tabs = range
+            .map(|panel| {
+                let tab = Tab {
+                    panel,
+                    x,
+                    label: format!("{} {} ", TAB_CLOSE_BUTTON, truncate(&titles[panel], limit)),
+                };
+                x += tab.width() + 1;
+                tab
+            })
+            .collect();
+        let overflow = hidden.map(|(count, panel)| TabOverflow {
+            x,
+            label: overflow_label(count),
+            panel,
+        });
+        Self { tabs, overflow }
+    }
+
+    /// What the strip has at column `x` from its start.
+    pub fn hit(&self, x: u16) -> Option<TabHit> {
+        if let Some(tab) = self
+            .tabs
+            .iter()
+            .find(|tab| x >= tab.x && x < tab.x + tab.width())
+        {
+            let close_width = TAB_CLOSE_BUTTON.chars().count() as u16;
+            return Some(if x < tab.x + close_width {
+                TabHit::Close(tab.panel)
+            } else {
+                TabHit::Select(tab.panel)
+            });
+        }
+        self.overflow
+            .as_ref()
+            .filter(|more| x >= more.x && x < more.x + more.label.chars().count() as u16)
+            .map(|more| TabHit::Select(more.panel))
+    }
+}
+
+/// Marker of `count` hidden tabs.
+fn overflow_label(count: usize) -> String {
+    format!(" +{}", count)
+}
+
+/// `title` shortened to `limit` columns, ending with an ellipsis if cut.
+fn truncate(title: &str, limit: usize) -> String {
+    if title.chars().count() <= limit {
+        return title.to_string();
+    }
+    let mut short: String = title.chars().take(limit.saturating_sub(1)).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(titles: &[&str], active: usize, width: u16) -> TabStrip {
+        let titles: Vec<String> = titles.iter().map(|t| t.to_string()).collect();
+        TabStrip::new(&titles, active, width)
+    }
+
+    fn labels(strip: &TabStrip) -> Vec<&str> {
+        strip.tabs.iter().map(|tab| tab.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tabs_shortened_to_fit() {
+        let titles = ["main.rs", "Terminal", "Files"];
+        let wide = strip(&titles, 0, 80);
+        assert_eq!(
+            labels(&wide),
+            ["[X] main.rs ", "[X] Terminal ", "[X] Files "]
+        );
+        assert_eq!(wide.tabs[1].x, 13);
+        assert_eq!(wide.overflow, None);
+
+        // The longest titles are shortened first
+        let narrow = strip(&titles, 0, 37);
+        assert_eq!(
+            labels(&narrow),
+            ["[X] main.rs ", "[X] Termin… ", "[X] Files "]
+        );
+    }
+
+    #[test]
+    fn test_tabs_scroll_to_active_when_too_many() {
+        let titles = ["one", "two", "three", "four", "five", "six"];
+        // Four short tabs and the marker fit
+        let scrolled = strip(&titles, 5, 43);
+        assert_eq!(
+            labels(&scrolled),
+            ["[X] three ", "[X] four ", "[X] five ", "[X] six "]
+        );
+        let more = scrolled.overflow.as_ref().unwrap();
+        assert_eq!(more.label, " +2");
+        // Hidden tabs are before the strip: the marker shows the first one
+        assert_eq!(more.panel, 0);
+    }
+
+    #[test]
+    fn test_tab_hits() {
+        let tabs = strip(&["a", "b"], 0, 30);
+        assert_eq!(tabs.hit(0), Some(TabHit::Close(0)));
+        assert_eq!(tabs.hit(4), Some(TabHit::Select(0)));
+        // Separator
+        assert_eq!(tabs.hit(6), None);
+        assert_eq!(tabs.hit(8), Some(TabHit::Close(1)));
+        assert_eq!(tabs.hit(12), Some(TabHit::Select(1)));
+        assert_eq!(tabs.hit(20), None);
+
+        // Too narrow for the active tab: its title is cut shorter
+        let narrow = strip(&["main.rs", "lib.rs"], 1, 12);
+        assert_eq!(labels(&narrow), ["[X] li… "]);
+        assert_eq!(narrow.hit(10), Some(TabHit::Select(0)));
+        assert!(strip(&["main.rs", "lib.rs"], 1, 8).tabs.is_empty());
+    }
+}
@@ -24,6 +24,7 @@ pub struct LogEntry {
 /// Log level
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogLevel {
+    Trace,
     Debug,
     Info,
     Warn,
@@ -34,6 +35,7 @@ impl LogLevel {
     /// Convert log level to string
     pub fn to_str(self) -> &'static str {
         match self {
+            LogLevel::Trace => "TRACE",
             LogLevel::Debug => "DEBUG",
             LogLevel::Info => "INFO",
             LogLevel::Warn => "WARN",
@@ -47,6 +49,7 @@ impl std::str::FromStr for LogLevel {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
             "debug" => Ok(LogLevel::Debug),
             "info" => Ok(LogLevel::Info),
             "warn" | "warning" => Ok(LogLevel::Warn),
@@ -67,6 +70,8 @@ struct Logger {
     min_level: LogLevel,
     /// Log file path
     file_path: PathBuf,
+    /// Number of entries recorded so far (sequence number of the next one)
+    next_seq: u64,
 }
 
 impl Logger {
@@ -92,6 +97,7 @@ impl Logger {
             max_entries,
             min_level,
             file_path,
+            next_seq: 0,
         }
     }
 
@@ -111,6 +117,7 @@ impl Logger {
 
         // Add to queue
         self.entries.push_back(entry);
+        self.next_seq += 1;
 
         // Limit queue size
         while self.entries.len() > self.max_entries {
@@ -132,6 +139,16 @@ impl Logger {
         self.entries.iter().cloned().collect()
     }
 
+    /// Get entries recorded since sequence number `seq` (those still kept)
+    fn get_entries_since(&self, seq: u64) -> (Vec<LogEntry>, u64) {
+        let first_seq = self.next_seq - self.entries.len() as u64;
+        let skip = seq.saturating_sub(first_seq).min(self.entries.len() as u64) as usize;
+        (
+            self.entries.iter().skip(skip).cloned().collect(),
+            self.next_seq,
+        )
+    }
+
     /// Set minimum log level
     #[allow(dead_code)]
     fn set_min_level(&mut self, level: LogLevel) {
@@ -159,7 +176,7 @@ fn get_logger() -> &'static Mutex<Logger> {
 ///
 /// * `file_path` - Path to the log file
 /// * `max_entries` - Maximum number of log entries to keep in memory
-/// * `min_level` - Minimum log level to record (Debug, Info, Warn, Error)
+/// * `min_level` - Minimum log level to record (Trace, Debug, Info, Warn, Error)
 pub fn init(file_path: PathBuf, max_entries: usize, min_level: LogLevel) {
     LOGGER.get_or_init(|| Mutex::new(Logger::new(file_path, max_entries, min_level)));
 }
@@ -175,6 +192,13 @@ pub fn set_min_level(level: LogLevel) {
     }
 }
 
+/// Log a trace message
+pub fn trace(message: impl Into<String>) {
+    if let Ok(mut logger) = get_logger().lock() {
+        logger.add_entry(LogLevel::Trace, message.into());
+    }
+}
+
 /// Log a debug message
 pub fn debug(message: impl Into<String>) {
     if let Ok(mut logger) = get_logger().lock() {
@@ -214,3 +238,49 @@ pub fn get_entries() -> Vec<LogEntry> {
         Vec::new()
     }
 }
+
+/// Get entries recorded since sequence number `seq`
+///
+/// Returns the new entries and the sequence number to pass next time, so
+/// viewers can pick up only what was added. Entries already dropped from
+/// memory are skipped.
+pub fn get_entries_since(seq: u64) -> (Vec<LogEntry>, u64) {
+    if let Ok(logger) = get_logger().lock() {
+        logger.get_entries_since(seq)
+    } else {
+        (Vec::new(), seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_since() {
+        let path = std::env::temp_dir().join(format!("termide-log-{}.log", std::process::id()));
+        let mut logger = Logger::new(path.clone(), 3, LogLevel::Debug);
+        logger.add_entry(LogLevel::Info, "one".to_string());
+        logger.add_entry(LogLevel::Trace, "filtered".to_string());
+        logger.add_entry(LogLevel::Warn, "two".to_string());
+
+        let (entries, seq) = logger.get_entries_since(0);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(seq, 2);
+        let (entries, seq) = logger.get_entries_since(seq);
+        assert!(entries.is_empty());
+
+        // Entries dropped past the limit are skipped
+        for message in ["three", "four", "five"] {
+            logger.add_entry(LogLevel::Error, message.to_string());
+        }
+        let (entries, seq) = logger.get_entries_since(seq);
+        let messages: Vec<&str> = entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["three", "four", "five"]);
+        assert_eq!(seq, 5);
+        let (entries, _) = logger.get_entries_since(1);
+        assert_eq!(entries.len(), 3);
+
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,582 @@
+//! Connection to one language server.
+//!
+//! Messages are written and read on background threads, so a server that
+//! stops reading or answering never blocks the caller: messages are queued
+//! and answers are picked up by `poll`. Notifications are held back until
+//! the server answered `initialize`, as the protocol requires.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::protocol::{self, Diagnostic, Location, Position};
+use crate::transport;
+
+/// Id of the `initialize` request
+const INITIALIZE_ID: u64 = 0;
+
+/// Time a server is given to exit after `exit` before it is killed
+const EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Something a language server reported
+#[derive(Debug, Clone, PartialEq)]
+pub enum LspEvent {
+    /// Diagnostics of a file, replacing the ones reported before
+    Diagnostics {
+        path: PathBuf,
+        diagnostics: Vec<Diagnostic>,
+    },
+    /// Answer to a go-to-definition request (None if nothing was found)
+    Definition(Option<Location>),
+    /// A server was started for a project
+    Started { server: String, root: PathBuf },
+    /// The server could not be started
+    FailedToStart { server: String, error: String },
+    /// The server program is not installed (not found on `PATH`)
+    NotInstalled { server: String },
+    /// The server exited or closed its output
+    Stopped { server: String },
+    /// Message of the server for the log (`is_error` for errors and warnings)
+    Message {
+        server: String,
+        text: String,
+        is_error: bool,
+    },
+}
+
+/// Document opened on the server
+struct Document {
+    uri: String,
+    version: i64,
+}
+
+/// Client of a running language server
+pub struct LspClient {
+    /// Command line of the server (for messages)
+    server: String,
+    /// Messages for the writer thread
+    outgoing: Sender<Value>,
+    /// Messages read by the reader thread
+    incoming: Receiver<Value>,
+    /// Messages waiting for the answer to `initialize` (None once answered)
+    queued: Option<Vec<Value>>,
+    next_id: u64,
+    /// Latest go-to-definition request (answers to older ones are dropped)
+    definition_request: Option<u64>,
+    /// Open documents by the path given by the editor
+    documents: HashMap<PathBuf, Document>,
+    child: Option<Child>,
+    stopped: bool,
+}
+
+impl LspClient {
+    /// Start the server `command` (program and arguments separated by
+    /// spaces) for the project in `root`
+    pub fn spawn(command: &str, root: &Path) -> io::Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+        let mut child = Command::new(program)
+            .args(words)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(io::Error::other("no pipes to the server"));
+        };
+
+        let mut client = Self::connect(stdout, stdin, command, root);
+        client.child = Some(child);
+        Ok(client)
+    }
+
+    /// Talk to a server reading from `reader` and writing to `writer`, and
+    /// send it `initialize`
+    pub fn connect(
+        reader: impl Read + Send + 'static,
+        mut writer: impl Write + Send + 'static,
+        server: &str,
+        root: &Path,
+    ) -> Self {
+        let (outgoing, outgoing_rx) = mpsc::channel::<Value>();
+        thread::spawn(move || {
+            for message in outgoing_rx {
+                if transport::write_message(&mut writer, &message).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let (incoming_tx, incoming) = termide_wake::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            loop {
+                match transport::read_message(&mut reader) {
+                    Ok(Some(message)) => {
+                        if incoming_tx.send(message).is_err() {
+                            return;
+                        }
+                    }
+                    // Skip messages that are not JSON
+                    Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+                    Ok(None) | Err(_) => return,
+                }
+            }
+        });
+
+        let _ = outgoing.send(protocol::request(
+            INITIALIZE_ID,
+            "initialize",
+            protocol::initialize_params(root),
+        ));
+        Self {
+            server: server.to_string(),
+            outgoing,
+            incoming,
+            queued: Some(Vec::new()),
+            next_id: INITIALIZE_ID + 1,
+            definition_request: None,
+            documents: HashMap::new(),
+            child: None,
+            stopped: false,
+        }
+    }
+
+    /// Command line of the server
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Check if the server stopped
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Paths of the documents opened on the server
+    pub fn documents(&self) -> impl Iterator<Item = &Path> {
+        self.documents.keys().map(PathBuf::as_path)
+    }
+
+    /// Open a document on the server, or send its new text if it is open
+    pub fn update_document(&mut self, path: &Path, language: &str, text: &str) {
+        if let Some(document) = self.documents.get_mut(path) {
+            document.version += 1;
+            let params = json!({
+                "textDocument": { "uri": document.uri, "version": document.version },
+                "contentChanges": [{ "text": text }],
+            });
+            self.send(protocol::notification("textDocument/didChange", params));
+            return;
+        }
+
+        // Servers know files by their real path
+        let real_path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let uri = protocol::path_to_uri(&real_path);
+        let params = json!({
+            "textDocument": {
+                "uri": uri,
+                "languageId": protocol::language_id(language),
+                "version": 1,
+                "text": text,
+            },
+        });
+        self.send(protocol::notification("textDocument/didOpen", params));
+        self.documents
+            .insert(path.to_path_buf(), Document { uri, version: 1 });
+    }
+
+    /// Close a document opened on the server
+    pub fn close_document(&mut self, path: &Path) {
+        if let Some(document) = self.documents.remove(path) {
+            let params = json!({ "textDocument": { "uri": document.uri } });
+            self.send(protocol::notification("textDocument/didClose", params));
+        }
+    }
+
+    /// Ask for the definition of the symbol at `position` of an open
+    /// document (answered by a `Definition` event)
+    pub fn goto_definition(&mut self, path: &Path, position: Position) -> bool {
+        let Some(document) = self.documents.get(path) else {
+            return false;
+        };
+        let id = self.next_id;
+        self.next_id += 1;
+        let params = protocol::position_params(&document.uri, position);
+        self.send(protocol::request(id, "textDocument/definition", params));
+        self.definition_request = Some(id);
+        true
+    }
+
+    /// Events received since the previous call
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let mut events = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => self.handle_message(message, &mut events),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if !self.stopped {
+                        self.stopped = true;
+                        events.push(LspEvent::Stopped {
+                            server: self.server.clone(),
+                        });
+                    }
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    /// Send a message, or queue it until the server is initialized
+    fn send(&mut self, message: Value) {
+        match &mut self.queued {
+            Some(queued) => queued.push(message),
+            None => {
+                let _ = self.outgoing.send(message);
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: Value, events: &mut Vec<LspEvent>) {
+        let method = message.get("method").and_then(Value::as_str);
+        match (method, message.get("id")) {
+            // Request of the server
+            (Some(method), Some(id)) => {
+                let result = match method {
+                    // One (default) setting for each item asked for
+                    "workspace/configuration" => {
+                        let items = message
+                            .pointer("/params/items")
+                            .and_then(Value::as_array)
+                            .map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    _ => Value::Null,
+                };
+                let _ = self.outgoing.send(protocol::response(id, result));
+            }
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let Some((uri, diagnostics)) =
+                    message.get("params").and_then(protocol::parse_diagnostics)
+                else {
+                    return;
+                };
+                let path = self
+                    .documents
+                    .iter()
+                    .find(|(_, document)| document.uri == uri)
+                    .map(|(path, _)| path.clone())
+                    .or_else(|| protocol::uri_to_path(&uri));
+                if let Some(path) = path {
+                    events.push(LspEvent::Diagnostics { path, diagnostics });
+                }
+            }
+            (Some("window/showMessage" | "window/logMessage"), None) => {
+                let text = message
+                    .pointer("/params/message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                // Message types: 1 error, 2 warning, 3 info, 4 log
+                let kind = message.pointer("/params/type").and_then(Value::as_u64);
+                events.push(LspEvent::Message {
+                    server: self.server.clone(),
+                    text: text.to_string(),
+                    is_error: matches!(kind, Some(1 | 2)),
+                });
+            }
+            (Some(_), None) => {}
+            // Answer to a request
+            (None, Some(id)) => {
+                if let Some(error) = message.get("error") {
+                    events.push(LspEvent::Message {
+                        server: self.server.clone(),
+                        text: error.to_string(),
+                        is_error: true,
+                    });
+                }
+                let id = id.as_u64();
+                if id == Some(INITIALIZE_ID) {
+                    self.on_initialized();
+                } else if id.is_some() && id == self.definition_request {
+                    self.definition_request = None;
+                    let location = message.get("result").and_then(protocol::parse_definition);
+                    events.push(LspEvent::Definition(location));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Confirm the initialization and send the queued messages
+    fn on_initialized(&mut self) {
+        let _ = self
+            .outgoing
+            .send(protocol::notification("initialized", json!({})));
+        for message in self.queued.take().unwrap_or_default() {
+            let _ = self.outgoing.send(message);
+        }
+    }
+}
+
+impl Drop for LspClient {
+    /// Ask the server to exit, and kill it if it is still running after a
+    /// while (without waiting for it here)
+    fn drop(&mut self) {
+        if !self.stopped {
+            let id = self.next_id;
+            let _ = self
+                .outgoing
+                .send(protocol::request(id, "shutdown", Value::Null));
+            let _ = self
+                .outgoing
+                .send(protocol::notification("exit", Value::Null));
+        }
+        if let Some(mut child) = self.child.take() {
+            thread::spawn(move || {
+                let deadline = Instant::now() + EXIT_TIMEOUT;
+                while Instant::now() < deadline {
+                    if !matches!(child.try_wait(), Ok(None)) {
+                        return;
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                let _ = child.kill();
+                let _ = child.wait();
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One end of an in-memory byte stream
+    struct Pipe {
+        sender: Option<Sender<Vec<u8>>>,
+        receiver: Option<Receiver<Vec<u8>>>,
+        pending: io::Cursor<Vec<u8>>,
+    }
+
+    /// Connected reading and writing ends
+    fn pipe() -> (Pipe, Pipe) {
+        let (sender, receiver) = mpsc::channel();
+        let reader = Pipe {
+            sender: None,
+            receiver: Some(receiver),
+            pending: io::Cursor::default(),
+        };
+        let writer = Pipe {
+            sender: Some(sender),
+            receiver: None,
+            pending: io::Cursor::default(),
+        };
+        (reader, writer)
+    }
+
+    impl Read for Pipe {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.position() as usize == self.pending.get_ref().len() {
+                match self.receiver.as_ref().map(Receiver::recv) {
+                    Some(Ok(bytes)) => self.pending = io::Cursor::new(bytes),
+                    _ => return Ok(0),
+                }
+            }
+            self.pending.read(buf)
+        }
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let sender = self.sender.as_ref().ok_or(io::ErrorKind::BrokenPipe)?;
+            sender
+                .send(buf.to_vec())
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Fake server end: reads what the client sends, writes what it receives
+    struct FakeServer {
+        input: BufReader<Pipe>,
+        output: Option<Pipe>,
+    }
+
+    impl FakeServer {
+        fn start() -> (LspClient, FakeServer) {
+            let (client_reader, server_writer) = pipe();
+            let (server_reader, client_writer) = pipe();
+            let client = LspClient::connect(
+                client_reader,
+                client_writer,
+                "fake-server",
+                Path::new("/project"),
+            );
+            let server = FakeServer {
+                input: BufReader::new(server_reader),
+                output: Some(server_writer),
+            };
+            (client, server)
+        }
+
+        fn receive(&mut self) -> Value {
+            transport::read_message(&mut self.input).unwrap().unwrap()
+        }
+
+        fn send(&mut self, message: Value) {
+            transport::write_message(self.output.as_mut().unwrap(), &message).unwrap();
+        }
+
+        /// Close the output, as a crashed server does
+        fn crash(&mut self) {
+            self.output = None;
+        }
+    }
+
+    /// Poll the client until it reports at least one event
+    fn wait_events(client: &mut LspClient) -> Vec<LspEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            let events = client.poll();
+            if !events.is_empty() {
+                return events;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        Vec::new()
+    }
+
+    #[test]
+    fn test_documents_wait_for_initialization() {
+        let (mut client, mut server) = FakeServer::start();
+        let path = Path::new("/project/main.rs");
+        client.update_document(path, "rust", "fn main() {}");
+        client.update_document(path, "rust", "fn main() { }");
+
+        let initialize = server.receive();
+        assert_eq!(initialize["method"], "initialize");
+        assert_eq!(initialize["params"]["rootUri"], "file:///project");
+
+        // Nothing else is sent before the answer
+        server.send(json!({ "jsonrpc": "2.0", "id": initialize["id"], "result": {} }));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while client.queued.is_some() && Instant::now() < deadline {
+            client.poll();
+        }
+        assert_eq!(server.receive()["method"], "initialized");
+        let open = server.receive();
+        assert_eq!(open["method"], "textDocument/didOpen");
+        assert_eq!(
+            open["params"]["textDocument"]["uri"],
+            "file:///project/main.rs"
+        );
+        assert_eq!(open["params"]["textDocument"]["languageId"], "rust");
+        let change = server.receive();
+        assert_eq!(change["method"], "textDocument/didChange");
+        assert_eq!(change["params"]["textDocument"]["version"], 2);
+        assert_eq!(
+            change["params"]["contentChanges"][0]["text"],
+            "fn main() { }"
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_definition_and_server_requests() {
+        let (mut client, mut server) = FakeServer::start();
+        let path = Path::new("/project/main.rs");
+        let initialize = server.receive();
+        server.send(json!({ "id": initialize["id"], "result": {} }));
+        client.update_document(path, "rust", "fn main() {}");
+
+        // Server requests are answered
+        server.send(json!({
+            "id": "cfg",
+            "method": "workspace/configuration",
+            "params": { "items": [{}, {}] },
+        }));
+        server.send(json!({
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": "file:///project/main.rs",
+                "diagnostics": [{
+                    "range": {
+                        "start": { "line": 0, "character": 3 },
+                        "end": { "line": 0, "character": 7 },
+                    },
+                    "message": "bad",
+                }],
+            },
+        }));
+        let events = wait_events(&mut client);
+        assert!(matches!(
+            &events[..],
+            [LspEvent::Diagnostics { path: p, diagnostics }]
+                if p == path && diagnostics[0].message == "bad"
+        ));
+        let mut answer = server.receive();
+        while answer.get("id") != Some(&json!("cfg")) {
+            answer = server.receive();
+        }
+        assert_eq!(answer["result"], json!([null, null]));
+
+        // Only the answer to the latest definition request is reported
+        assert!(client.goto_definition(path, Position::new(0, 4)));
+        assert!(client.goto_definition(path, Position::new(0, 5)));
+        assert!(!client.goto_definition(Path::new("/other.rs"), Position::default()));
+        let first = server.receive();
+        let second = server.receive();
+        assert_eq!(second["method"], "textDocument/definition");
+        assert_eq!(second["params"]["position"]["character"], 5);
+        server.send(json!({ "id": first["id"], "result": null }));
+        server.send(json!({
+            "id": second["id"],
+            "result": [{
+                "uri": "file:///project/lib.rs",
+                "range": {
+                    "start": { "line": 9, "character": 1 },
+                    "end": { "line": 9, "character": 4 },
+                },
+            }],
+        }));
+        assert_eq!(
+            wait_events(&mut client),
+            [LspEvent::Definition(Some(Location {
+                path: PathBuf::from("/project/lib.rs"),
+                position: Position::new(9, 1),
+            }))]
+        );
+    }
+
+    #[test]
+    fn test_crashed_server_is_reported_once() {
+        let (mut client, mut server) = FakeServer::start();
+        server.receive();
+        server.crash();
+        assert_eq!(
+            wait_events(&mut client),
+            [LspEvent::Stopped {
+                server: "fake-server".to_string()
+            }]
+        );
+        assert!(client.is_stopped());
+        assert!(client.poll().is_empty());
+
+        // Edits after the crash do not block or fail
+        client.update_document(Path::new("/project/a.rs"), "rust", "");
+    }
+}
@@ -0,0 +1,17 @@
+//! Language server client for termide.
+//!
+//! Starts the language servers configured for the languages of open files,
+//! keeps them informed of the text of the files, and reports diagnostics
+//! and go-to-definition answers. All talking to the servers happens on
+//! background threads; the application polls for events.
+
+pub mod client;
+pub mod manager;
+pub mod protocol;
+pub mod transport;
+
+pub use client::{LspClient, LspEvent};
+pub use manager::LspManager;
+pub use protocol::{
+    grapheme_column, language_id, utf16_column, Diagnostic, Location, Position, Severity,
+};
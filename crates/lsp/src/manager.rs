@@ -0,0 +1,243 @@
+//! Language servers of the open files.
+//!
+//! A server is started for a language and project root the first time a
+//! file of that language is sent to it. The project root is the nearest
+//! directory above the file with a `.git` entry, or the file's directory.
+//! A server that is not installed, fails to start or stops is not started
+//! again, and its files are edited without it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::client::{LspClient, LspEvent};
+use crate::protocol::Position;
+
+/// Language and project root served by one server
+type ServerKey = (String, PathBuf);
+
+/// Language servers of the open files
+pub struct LspManager {
+    /// Server command line of each language
+    servers: BTreeMap<String, String>,
+    clients: HashMap<ServerKey, LspClient>,
+    /// Servers not installed, failed to start or stopped
+    failed: HashSet<ServerKey>,
+    /// Server of each document sent to a server
+    documents: HashMap<PathBuf, ServerKey>,
+    /// Events produced outside of `poll` (server starts)
+    pending: Vec<LspEvent>,
+}
+
+impl LspManager {
+    /// Manager starting `servers` (language -> command line)
+    pub fn new(servers: BTreeMap<String, String>) -> Self {
+        Self {
+            servers,
+            clients: HashMap::new(),
+            failed: HashSet::new(),
+            documents: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Use other server commands; servers whose command changed are
+    /// stopped and started again when their files change
+    pub fn set_servers(&mut self, servers: BTreeMap<String, String>) {
+        if servers == self.servers {
+            return;
+        }
+        self.clients.retain(|(language, _), client| {
+            servers.get(language).map(String::as_str) == Some(client.server())
+        });
+        self.documents
+            .retain(|_, key| self.clients.contains_key(key));
+        self.failed.clear();
+        self.servers = servers;
+    }
+
+    /// Check if a server is configured for `language`
+    pub fn has_server(&self, language: &str) -> bool {
+        self.servers.contains_key(language)
+    }
+
+    /// Send the text of a file to the server of its language, starting the
+    /// server if needed
+    pub fn update_document(&mut self, path: &Path, language: &str, text: &str) {
+        let key = match self.documents.get(path) {
+            Some(key) if key.0 == language => key.clone(),
+            // The language of the file was changed
+            Some(_) => {
+                self.close_document(path);
+                return self.update_document(path, language, text);
+            }
+            None => (language.to_string(), project_root(path)),
+        };
+        if let Some(client) = self.client(&key) {
+            client.update_document(path, language, text);
+            self.documents.insert(path.to_path_buf(), key);
+        }
+    }
+
+    /// Close the documents that are not in `open`
+    pub fn retain_documents(&mut self, open: &[PathBuf]) {
+        let closed: Vec<PathBuf> = self
+            .documents
+            .keys()
+            .filter(|path| !open.contains(path))
+            .cloned()
+            .collect();
+        for path in closed {
+            self.close_document(&path);
+        }
+    }
+
+    /// Ask the server of a document for the definition at `position`;
+    /// false if the document has no server
+    pub fn goto_definition(&mut self, path: &Path, position: Position) -> bool {
+        let Some(key) = self.documents.get(path) else {
+            return false;
+        };
+        self.clients
+            .get_mut(key)
+            .is_some_and(|client| client.goto_definition(path, position))
+    }
+
+    /// Events of all servers since the previous call
+    ///
+    /// Stopped servers are forgotten, and their documents are reported
+    /// without diagnostics.
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let mut events = std::mem::take(&mut self.pending);
+        let mut stopped = Vec::new();
+        for (key, client) in &mut self.clients {
+            events.extend(client.poll());
+            if client.is_stopped() {
+                stopped.push(key.clone());
+            }
+        }
+        for key in stopped {
+            if let Some(client) = self.clients.remove(&key) {
+                events.extend(client.documents().map(|path| LspEvent::Diagnostics {
+                    path: path.to_path_buf(),
+                    diagnostics: Vec::new(),
+                }));
+            }
+            self.documents
+                .retain(|_, document_key| *document_key != key);
+            self.failed.insert(key);
+        }
+        events
+    }
+
+    fn close_document(&mut self, path: &Path) {
+        if let Some(key) = self.documents.remove(path) {
+            if let Some(client) = self.clients.get_mut(&key) {
+                client.close_document(path);
+            }
+        }
+    }
+
+    /// Client of a server, started if needed (None if no server is
+    /// configured or it failed)
+    fn client(&mut self, key: &ServerKey) -> Option<&mut LspClient> {
+        if self.failed.contains(key) {
+            return None;
+        }
+        if !self.clients.contains_key(key) {
+            let command = self.servers.get(&key.0)?;
+            if !is_installed(command) {
+                self.pending.push(LspEvent::NotInstalled {
+                    server: command.clone(),
+                });
+                self.failed.insert(key.clone());
+                return None;
+            }
+            match LspClient::spawn(command, &key.1) {
+                Ok(client) => {
+                    self.pending.push(LspEvent::Started {
+                        server: command.clone(),
+                        root: key.1.clone(),
+                    });
+                    self.clients.insert(key.clone(), client);
+                }
+                Err(e) => {
+                    self.pending.push(LspEvent::FailedToStart {
+                        server: command.clone(),
+                        error: e.to_string(),
+                    });
+                    self.failed.insert(key.clone());
+                    return None;
+                }
+            }
+        }
+        self.clients.get_mut(key)
+    }
+}
+
+/// Check the program of a server command is a file, looked up on `PATH`
+/// unless it is a path
+fn is_installed(command: &str) -> bool {
+    let Some(program) = command.split_whitespace().next() else {
+        return false;
+    };
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+/// Nearest directory above `path` with a `.git` entry, or its directory
+fn project_root(path: &Path) -> PathBuf {
+    let dir = path.parent().unwrap_or(path);
+    dir.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        assert_eq!(project_root(&src.join("main.rs")), src);
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        assert_eq!(project_root(&src.join("main.rs")), dir.path());
+    }
+
+    #[test]
+    fn test_is_installed() {
+        assert!(is_installed("sh -c true"));
+        assert!(is_installed("/bin/sh"));
+        assert!(!is_installed("termide-no-such-language-server --stdio"));
+        assert!(!is_installed("/nonexistent/rust-analyzer"));
+        assert!(!is_installed(""));
+    }
+
+    #[test]
+    fn test_missing_server_fails_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        let servers = BTreeMap::from([(
+            "rust".to_string(),
+            "termide-no-such-language-server".to_string(),
+        )]);
+        let mut manager = LspManager::new(servers);
+
+        manager.update_document(&path, "rust", "fn main() {}");
+        manager.update_document(&path, "rust", "fn main() { }");
+        manager.update_document(&path, "markdown", "# notes");
+        let events = manager.poll();
+        assert!(matches!(
+            &events[..],
+            [LspEvent::NotInstalled { server }] if server == "termide-no-such-language-server"
+        ));
+        assert!(!manager.goto_definition(&path, Position::default()));
+        assert!(manager.poll().is_empty());
+    }
+}
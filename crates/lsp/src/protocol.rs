@@ -0,0 +1,305 @@
+//! Language server protocol types used by the editor and their JSON form.
+//!
+//! Positions are kept as the protocol sends them: 0-based lines and columns
+//! in UTF-16 code units. `utf16_column` and `grapheme_column` convert them
+//! to and from the grapheme columns of the editor.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Position in a document (0-based line, UTF-16 column)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, character: usize) -> Self {
+        Self { line, character }
+    }
+
+    fn to_json(self) -> Value {
+        json!({ "line": self.line, "character": self.character })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            line: value.get("line")?.as_u64()? as usize,
+            character: value.get("character")?.as_u64()? as usize,
+        })
+    }
+}
+
+/// Severity of a diagnostic (most severe first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl Severity {
+    fn from_json(value: Option<&Value>) -> Self {
+        match value.and_then(Value::as_u64) {
+            Some(2) => Self::Warning,
+            Some(3) => Self::Information,
+            Some(4) => Self::Hint,
+            // Servers omitting the severity mean errors
+            _ => Self::Error,
+        }
+    }
+}
+
+/// Problem reported by a language server for a range of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub start: Position,
+    /// End of the range (exclusive)
+    pub end: Position,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn from_json(value: &Value) -> Option<Self> {
+        let range = value.get("range")?;
+        Some(Self {
+            start: Position::from_json(range.get("start")?)?,
+            end: Position::from_json(range.get("end")?)?,
+            severity: Severity::from_json(value.get("severity")),
+            message: value.get("message")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// Position in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub position: Position,
+}
+
+impl Location {
+    /// Location from a `Location` or `LocationLink` object
+    fn from_json(value: &Value) -> Option<Self> {
+        let uri = value.get("uri").or_else(|| value.get("targetUri"))?;
+        let range = value
+            .get("range")
+            .or_else(|| value.get("targetSelectionRange"))?;
+        Some(Self {
+            path: uri_to_path(uri.as_str()?)?,
+            position: Position::from_json(range.get("start")?)?,
+        })
+    }
+}
+
+/// Language identifier of the protocol for a language name of the editor
+pub fn language_id(language: &str) -> &str {
+    match language {
+        "tsx" => "typescriptreact",
+        "jsx" => "javascriptreact",
+        "bash" => "shellscript",
+        language => language,
+    }
+}
+
+/// UTF-16 column of the grapheme `column` of `line`
+pub fn utf16_column(line: &str, column: usize) -> usize {
+    line.graphemes(true)
+        .take(column)
+        .map(|grapheme| grapheme.encode_utf16().count())
+        .sum()
+}
+
+/// Grapheme column of the UTF-16 `character` of `line` (a column inside
+/// a grapheme rounds down, a column past the end gives the line length)
+pub fn grapheme_column(line: &str, character: usize) -> usize {
+    let mut units = 0;
+    let mut column = 0;
+    for grapheme in line.trim_end_matches(['\n', '\r']).graphemes(true) {
+        units += grapheme.encode_utf16().count();
+        if units > character {
+            break;
+        }
+        column += 1;
+    }
+    column
+}
+
+/// `file://` URI of an absolute path
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// Path of a `file://` URI
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let hex = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (encoded[i], hex) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(bytes).ok()?))
+}
+
+/// Request message
+pub(crate) fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+/// Notification message
+pub(crate) fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// Response to a request of the server
+pub(crate) fn response(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Parameters of `initialize` for a project in `root`
+pub(crate) fn initialize_params(root: &Path) -> Value {
+    let uri = path_to_uri(root);
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    json!({
+        "processId": std::process::id(),
+        "clientInfo": { "name": "termide", "version": env!("CARGO_PKG_VERSION") },
+        "rootUri": uri,
+        "workspaceFolders": [{ "uri": uri, "name": name }],
+        "capabilities": {
+            "general": { "positionEncodings": ["utf-16"] },
+            "textDocument": {
+                "synchronization": { "dynamicRegistration": false },
+                "publishDiagnostics": { "relatedInformation": false },
+                "definition": { "linkSupport": true },
+            },
+        },
+    })
+}
+
+/// Parameters naming a document and a position in it
+pub(crate) fn position_params(uri: &str, position: Position) -> Value {
+    json!({ "textDocument": { "uri": uri }, "position": position.to_json() })
+}
+
+/// URI and diagnostics of `textDocument/publishDiagnostics` parameters
+pub(crate) fn parse_diagnostics(params: &Value) -> Option<(String, Vec<Diagnostic>)> {
+    let uri = params.get("uri")?.as_str()?.to_string();
+    let diagnostics = params
+        .get("diagnostics")?
+        .as_array()?
+        .iter()
+        .filter_map(Diagnostic::from_json)
+        .collect();
+    Some((uri, diagnostics))
+}
+
+/// First location of a `textDocument/definition` result (a location, an
+/// array of locations or location links, or null)
+pub(crate) fn parse_definition(result: &Value) -> Option<Location> {
+    match result {
+        Value::Array(locations) => locations.iter().find_map(Location::from_json),
+        result => Location::from_json(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_count_utf16_units() {
+        // "é" is one unit, "😀" two, "e\u{301}" is one grapheme of two units
+        let line = "é😀e\u{301}x\n";
+        assert_eq!(utf16_column(line, 0), 0);
+        assert_eq!(utf16_column(line, 2), 3);
+        assert_eq!(utf16_column(line, 4), 6);
+        assert_eq!(grapheme_column(line, 3), 2);
+        assert_eq!(grapheme_column(line, 2), 1);
+        assert_eq!(grapheme_column(line, 6), 4);
+        assert_eq!(grapheme_column(line, 100), 4);
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let path = Path::new("/home/me/my project/ä#1.rs");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///home/me/my%20project/%C3%A4%231.rs");
+        assert_eq!(uri_to_path(&uri).as_deref(), Some(path));
+        assert_eq!(uri_to_path("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_parse_diagnostics_and_definition() {
+        let params = json!({
+            "uri": "file:///src/main.rs",
+            "diagnostics": [
+                {
+                    "range": {
+                        "start": { "line": 1, "character": 4 },
+                        "end": { "line": 1, "character": 9 },
+                    },
+                    "severity": 2,
+                    "message": "unused variable",
+                },
+                { "message": "no range" },
+            ],
+        });
+        let (uri, diagnostics) = parse_diagnostics(&params).unwrap();
+        assert_eq!(uri, "file:///src/main.rs");
+        assert_eq!(
+            diagnostics,
+            [Diagnostic {
+                start: Position::new(1, 4),
+                end: Position::new(1, 9),
+                severity: Severity::Warning,
+                message: "unused variable".to_string(),
+            }]
+        );
+
+        let location = Location {
+            path: PathBuf::from("/src/lib.rs"),
+            position: Position::new(7, 2),
+        };
+        let range =
+            json!({ "start": { "line": 7, "character": 2 }, "end": { "line": 7, "character": 5 } });
+        let plain = json!({ "uri": "file:///src/lib.rs", "range": range });
+        let link = json!({
+            "targetUri": "file:///src/lib.rs",
+            "targetRange": range,
+            "targetSelectionRange": range,
+        });
+        assert_eq!(parse_definition(&plain), Some(location.clone()));
+        assert_eq!(parse_definition(&json!([link])), Some(location));
+        assert_eq!(parse_definition(&json!([])), None);
+        assert_eq!(parse_definition(&Value::Null), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! JSON-RPC message framing of the language server protocol.
+//!
+//! Each message is a JSON object preceded by a `Content-Length` header and
+//! an empty line. Other headers (`Content-Type`) are read and ignored.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Write `message` with its header
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Read the next message, or None when the stream is closed
+///
+/// A body that is not valid JSON is reported as `InvalidData`; the stream
+/// stays usable and the next message can be read.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            // Headers end with an empty line
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_messages_round_trip() {
+        let mut stream = Vec::new();
+        write_message(&mut stream, &json!({"id": 1, "method": "ä"})).unwrap();
+        write_message(&mut stream, &json!({"id": 2})).unwrap();
+        assert!(stream.starts_with(b"Content-Length: 22\r\n\r\n{"));
+
+        let mut reader = io::Cursor::new(stream);
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Some(json!({"id": 1, "method": "ä"}))
+        );
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!({"id": 2})));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_body_is_skipped() {
+        let stream = "Content-Type: application/vscode-jsonrpc\r\nContent-Length: 3\r\n\r\n{x}\
+                      content-length: 2\r\n\r\n{}";
+        let mut reader = io::Cursor::new(stream.as_bytes());
+        let error = read_message(&mut reader).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!({})));
+    }
+
+    #[test]
+    fn test_truncated_body_is_an_error() {
+        let mut reader = io::Cursor::new(&b"Content-Length: 10\r\n\r\n{}"[..]);
+        assert!(read_message(&mut reader).is_err());
+    }
+}
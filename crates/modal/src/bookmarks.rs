@@ -0,0 +1,303 @@
+//! Bookmark picker modal.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use termide_config::constants::{MODAL_MAX_WIDTH_PERCENTAGE_WIDE, MODAL_MIN_WIDTH_WIDE};
+use termide_i18n as i18n;
+use termide_theme::Theme;
+
+use crate::{centered_rect_with_size, Modal, ModalResult};
+
+/// Bookmark shown in the picker
+#[derive(Debug, Clone)]
+pub struct BookmarkItem {
+    /// Bookmark name
+    pub name: String,
+    /// File and line (`src/main.rs:12`)
+    pub location: String,
+    /// Text of the line
+    pub text: String,
+    /// The file no longer exists
+    pub missing: bool,
+}
+
+/// What to do with the highlighted bookmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkChoice {
+    /// Open its file at its line
+    Jump(usize),
+    /// Delete it
+    Remove(usize),
+}
+
+/// List of bookmarks with their file, line and line text. Enter jumps to
+/// the highlighted one, Delete removes it.
+#[derive(Debug)]
+pub struct BookmarksModal {
+    items: Vec<BookmarkItem>,
+    cursor: usize,
+    /// First visible row
+    scroll: usize,
+    /// Rows that fit on screen in the last render
+    page: usize,
+    last_list_area: Option<Rect>,
+}
+
+impl BookmarksModal {
+    /// Create the picker with the cursor on item `cursor`
+    pub fn new(items: Vec<BookmarkItem>, cursor: usize) -> Self {
+        let cursor = cursor.min(items.len().saturating_sub(1));
+        Self {
+            items,
+            cursor,
+            scroll: 0,
+            page: 1,
+            last_list_area: None,
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.items.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+        self.adjust_scroll();
+    }
+
+    /// Keep the cursor inside the visible rows
+    fn adjust_scroll(&mut self) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.page {
+            self.scroll = self.cursor + 1 - self.page;
+        }
+    }
+
+    fn choice(&self, choice: fn(usize) -> BookmarkChoice) -> Option<ModalResult<BookmarkChoice>> {
+        (!self.items.is_empty()).then(|| ModalResult::Confirmed(choice(self.cursor)))
+    }
+
+    fn line(
+        &self,
+        index: usize,
+        item: &BookmarkItem,
+        widths: (usize, usize),
+        theme: &Theme,
+    ) -> Line<'static> {
+        let (name_width, location_width) = widths;
+        let selected = index == self.cursor;
+        let base = if selected {
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.bg)
+        };
+        let (name_style, text_style) = if item.missing {
+            let style = base.fg(theme.disabled).add_modifier(Modifier::CROSSED_OUT);
+            (style, style)
+        } else if selected {
+            (base, base)
+        } else {
+            (base.fg(theme.accented_fg), base.fg(theme.disabled))
+        };
+        let pad = |text: &str, width: usize| {
+            format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+        };
+        let mut spans = vec![
+            Span::styled(if selected { "▶ " } else { "  " }, base),
+            Span::styled(pad(&item.name, name_width + 2), name_style),
+            Span::styled(pad(&item.location, location_width + 2), base),
+        ];
+        if item.missing {
+            spans.push(Span::styled(
+                format!("[{}] ", i18n::t().bookmarks_missing()),
+                base.fg(theme.error),
+            ));
+        }
+        spans.push(Span::styled(item.text.replace('\t', " "), text_style));
+        Line::from(spans)
+    }
+}
+
+impl Modal for BookmarksModal {
+    type Result = BookmarkChoice;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let modal_width = ((area.width as f32 * MODAL_MAX_WIDTH_PERCENTAGE_WIDE) as u16)
+            .max(MODAL_MIN_WIDTH_WIDE)
+            .min(area.width);
+        // Borders (2) + rows + empty line (1) + hint (1)
+        let modal_height = (self.items.len().max(1) as u16)
+            .saturating_add(4)
+            .min(area.height.saturating_sub(2));
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        Clear.render(modal_area, buf);
+
+        let t = i18n::t();
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", t.bookmarks_title()),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+        if inner.height == 0 {
+            return;
+        }
+
+        let list_area = Rect {
+            height: inner.height.saturating_sub(2).max(1),
+            ..inner
+        };
+        self.page = list_area.height as usize;
+        self.adjust_scroll();
+        self.last_list_area = Some(list_area);
+
+        let text: Vec<Line> = if self.items.is_empty() {
+            vec![Line::from(Span::styled(
+                t.bookmarks_empty(),
+                Style::default().fg(theme.disabled),
+            ))]
+        } else {
+            let widths = self.items.iter().fold((0, 0), |(name, location), item| {
+                (
+                    name.max(item.name.width()),
+                    location.max(item.location.width()),
+                )
+            });
+            self.items
+                .iter()
+                .enumerate()
+                .skip(self.scroll)
+                .take(self.page)
+                .map(|(index, item)| self.line(index, item, widths, theme))
+                .collect()
+        };
+        Paragraph::new(text).render(list_area, buf);
+
+        if inner.height > 2 {
+            let hint_area = Rect {
+                y: inner.y + inner.height - 1,
+                height: 1,
+                ..inner
+            };
+            Paragraph::new(Span::styled(
+                t.bookmarks_hint(),
+                Style::default().fg(theme.disabled),
+            ))
+            .alignment(Alignment::Center)
+            .render(hint_area, buf);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        let page = self.page as isize;
+        match key.code {
+            KeyCode::Esc => return Ok(Some(ModalResult::Cancelled)),
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-page),
+            KeyCode::PageDown => self.move_cursor(page),
+            KeyCode::Home => self.move_cursor(-(self.cursor as isize)),
+            KeyCode::End => self.move_cursor(self.items.len() as isize),
+            KeyCode::Enter => return Ok(self.choice(BookmarkChoice::Jump)),
+            KeyCode::Delete => return Ok(self.choice(BookmarkChoice::Remove)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_cursor(-1),
+            MouseEventKind::ScrollDown => self.move_cursor(1),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(area) = self.last_list_area else {
+                    return Ok(None);
+                };
+                let inside = mouse.row >= area.y
+                    && mouse.row < area.y + area.height
+                    && mouse.column >= area.x
+                    && mouse.column < area.x + area.width;
+                let index = self.scroll + mouse.row.saturating_sub(area.y) as usize;
+                if inside && index < self.items.len() {
+                    self.cursor = index;
+                    return Ok(self.choice(BookmarkChoice::Jump));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, missing: bool) -> BookmarkItem {
+        BookmarkItem {
+            name: name.to_string(),
+            location: format!("src/{}.rs:3", name),
+            text: format!("fn {}() {{", name),
+            missing,
+        }
+    }
+
+    fn rows(modal: &mut BookmarksModal) -> Vec<String> {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 100, 20));
+        modal.render(buf.area, &mut buf, &Theme::default());
+        (0..20)
+            .map(|y| (0..100).map(|x| buf[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_lists_location_and_text_and_marks_deleted_files() {
+        termide_i18n::init_with_language("en");
+        let mut modal = BookmarksModal::new(vec![item("1", false), item("todo", true)], 0);
+        let rows = rows(&mut modal);
+        let row = |text: &str| rows.iter().find(|row| row.contains(text)).unwrap();
+        assert!(row("src/1.rs:3").contains("fn 1() {"));
+        assert!(row("src/todo.rs:3").contains("[file deleted]"));
+    }
+
+    #[test]
+    fn test_enter_jumps_and_delete_removes() {
+        termide_i18n::init_with_language("en");
+        let mut modal = BookmarksModal::new(vec![item("1", false), item("2", false)], 5);
+        let key = |code| KeyEvent::from(code);
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Enter)).unwrap(),
+            Some(ModalResult::Confirmed(BookmarkChoice::Jump(1)))
+        ));
+        modal.handle_key(key(KeyCode::Up)).unwrap();
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Delete)).unwrap(),
+            Some(ModalResult::Confirmed(BookmarkChoice::Remove(0)))
+        ));
+
+        let mut empty = BookmarksModal::new(Vec::new(), 0);
+        assert!(empty.handle_key(key(KeyCode::Enter)).unwrap().is_none());
+        assert!(rows(&mut empty)
+            .iter()
+            .any(|row| row.contains("No bookmarks")));
+    }
+}
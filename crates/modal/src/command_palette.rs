@@ -0,0 +1,386 @@
+//! Command palette modal.
+//!
+//! Lists commands with their key bindings and filters them with fuzzy
+//! matching as the user types. Enter runs the highlighted command.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget},
+};
+
+use termide_theme::Theme;
+use termide_ui::fuzzy::fuzzy_match;
+
+use crate::{
+    calculate_modal_width, centered_rect_with_size, Modal, ModalResult, ModalWidthConfig,
+    TextInputHandler,
+};
+
+/// Maximum number of visible commands
+const MAX_VISIBLE_ITEMS: usize = 12;
+
+/// Command shown in the palette
+#[derive(Debug, Clone)]
+pub struct PaletteItem {
+    /// Human-readable command name (matched against the query)
+    pub label: String,
+    /// Key binding shown next to the name (may be empty)
+    pub keys: String,
+}
+
+/// Command matching the current query
+#[derive(Debug, Clone)]
+struct FilteredItem {
+    /// Index in `items`
+    index: usize,
+    /// Char indices of matched characters in the label
+    positions: Vec<usize>,
+}
+
+/// Command palette modal window
+///
+/// Confirms with the index of the chosen item.
+#[derive(Debug)]
+pub struct CommandPaletteModal {
+    title: String,
+    input: TextInputHandler,
+    /// Commands in their order without a query (recent ones first)
+    items: Vec<PaletteItem>,
+    filtered: Vec<FilteredItem>,
+    cursor: usize,
+    /// First visible row of `filtered`
+    scroll_offset: usize,
+    last_list_area: Option<Rect>,
+}
+
+impl CommandPaletteModal {
+    /// Create a palette over `items`
+    pub fn new(title: impl Into<String>, items: Vec<PaletteItem>) -> Self {
+        let mut modal = Self {
+            title: title.into(),
+            input: TextInputHandler::new(),
+            items,
+            filtered: Vec::new(),
+            cursor: 0,
+            scroll_offset: 0,
+            last_list_area: None,
+        };
+        modal.update_filter();
+        modal
+    }
+
+    /// Rebuild the filtered list for the current query, best match first.
+    ///
+    /// Ties keep the original order, so recent commands stay on top.
+    fn update_filter(&mut self) {
+        let query = self.input.text();
+        let mut matches: Vec<(i32, FilteredItem)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                fuzzy_match(query, &item.label).map(|found| {
+                    (
+                        found.score,
+                        FilteredItem {
+                            index,
+                            positions: found.positions,
+                        },
+                    )
+                })
+            })
+            .collect();
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        self.filtered = matches.into_iter().map(|(_, item)| item).collect();
+        self.cursor = 0;
+        self.scroll_offset = 0;
+    }
+
+    /// Index of the highlighted item
+    fn selected(&self) -> Option<usize> {
+        self.filtered.get(self.cursor).map(|item| item.index)
+    }
+
+    /// Move cursor by `delta` rows within the filtered list
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.filtered.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+        if self.cursor < self.scroll_offset {
+            self.scroll_offset = self.cursor;
+        } else if self.cursor >= self.scroll_offset + MAX_VISIBLE_ITEMS {
+            self.scroll_offset = self.cursor + 1 - MAX_VISIBLE_ITEMS;
+        }
+    }
+
+    /// Calculate dynamic modal width
+    fn calculate_modal_width(&self, screen_width: u16) -> u16 {
+        let title_width = self.title.chars().count() as u16 + 2;
+        // "▶ " + label + gap + keys
+        let items_width = self
+            .items
+            .iter()
+            .map(|item| (item.label.chars().count() + item.keys.chars().count() + 4) as u16)
+            .max()
+            .unwrap_or(0);
+
+        calculate_modal_width(
+            [title_width, items_width].into_iter(),
+            screen_width,
+            ModalWidthConfig::default(),
+        )
+    }
+}
+
+impl Modal for CommandPaletteModal {
+    type Result = usize;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let modal_width = self.calculate_modal_width(area.width);
+
+        // 1 (top border) + 1 (input) + list + 1 (bottom border), list height is
+        // kept constant while filtering so the window does not jump
+        let list_height = self.items.len().clamp(1, MAX_VISIBLE_ITEMS) as u16;
+        let modal_height = 1 + 1 + list_height + 1;
+
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+        Clear.render(modal_area, buf);
+
+        // Create block with inverted colors
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),           // Query
+                Constraint::Length(list_height), // List
+            ])
+            .split(inner);
+
+        let input_line = Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.disabled)),
+            Span::styled(
+                self.input.text_before_cursor(),
+                Style::default().fg(theme.bg),
+            ),
+            Span::styled("█", Style::default().fg(theme.success)),
+            Span::styled(
+                self.input.text_after_cursor(),
+                Style::default().fg(theme.bg),
+            ),
+        ]);
+        Paragraph::new(input_line).render(chunks[0], buf);
+
+        let row_width = chunks[1].width as usize;
+        let items: Vec<ListItem> = self
+            .filtered
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(MAX_VISIBLE_ITEMS)
+            .map(|(row, filtered)| {
+                let item = &self.items[filtered.index];
+                let is_cursor = row == self.cursor;
+                let style = if is_cursor {
+                    Style::default()
+                        .fg(theme.fg)
+                        .bg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.bg)
+                };
+                let matched_style = style.add_modifier(Modifier::UNDERLINED);
+                let keys_style = if is_cursor {
+                    style
+                } else {
+                    Style::default().fg(theme.disabled)
+                };
+
+                let mut spans = vec![Span::styled(if is_cursor { "▶ " } else { "  " }, style)];
+                spans.extend(item.label.chars().enumerate().map(|(idx, ch)| {
+                    let style = if filtered.positions.contains(&idx) {
+                        matched_style
+                    } else {
+                        style
+                    };
+                    Span::styled(ch.to_string(), style)
+                }));
+
+                // Right-align the key binding
+                let used = 2 + item.label.chars().count();
+                let padding = row_width.saturating_sub(used + item.keys.chars().count() + 1);
+                spans.push(Span::styled(" ".repeat(padding.max(1)), style));
+                spans.push(Span::styled(item.keys.clone(), keys_style));
+                spans.push(Span::styled(" ", style));
+
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        List::new(items)
+            .style(Style::default().bg(theme.fg))
+            .render(chunks[1], buf);
+
+        // Save list area for mouse handling
+        self.last_list_area = Some(chunks[1]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        match key.code {
+            KeyCode::Esc => return Ok(Some(ModalResult::Cancelled)),
+            KeyCode::Enter => {
+                return Ok(self.selected().map(ModalResult::Confirmed));
+            }
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-(MAX_VISIBLE_ITEMS as isize)),
+            KeyCode::PageDown => self.move_cursor(MAX_VISIBLE_ITEMS as isize),
+            KeyCode::Left => {
+                self.input.move_left();
+            }
+            KeyCode::Right => {
+                self.input.move_right();
+            }
+            KeyCode::Home => self.input.move_home(),
+            KeyCode::End => self.input.move_end(),
+            KeyCode::Backspace if self.input.backspace() => {
+                self.update_filter();
+            }
+            KeyCode::Backspace => {}
+            KeyCode::Delete if self.input.delete() => {
+                self.update_filter();
+            }
+            KeyCode::Delete => {}
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.input.insert_char(ch);
+                self.update_filter();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: crossterm::event::MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        use crossterm::event::MouseEventKind;
+
+        let Some(list_area) = self.last_list_area else {
+            return Ok(None);
+        };
+        let inside = mouse.row >= list_area.y
+            && mouse.row < list_area.y + list_area.height
+            && mouse.column >= list_area.x
+            && mouse.column < list_area.x + list_area.width;
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_cursor(-1),
+            MouseEventKind::ScrollDown => self.move_cursor(1),
+            // Click runs the command under the pointer
+            MouseEventKind::Down(crossterm::event::MouseButton::Left) if inside => {
+                let row = self.scroll_offset + (mouse.row - list_area.y) as usize;
+                if row < self.filtered.len() {
+                    self.cursor = row;
+                    return Ok(self.selected().map(ModalResult::Confirmed));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn palette(labels: &[&str]) -> CommandPaletteModal {
+        let items = labels
+            .iter()
+            .map(|label| PaletteItem {
+                label: label.to_string(),
+                keys: String::new(),
+            })
+            .collect();
+        CommandPaletteModal::new("Commands", items)
+    }
+
+    fn type_text(modal: &mut CommandPaletteModal, text: &str) {
+        for ch in text.chars() {
+            modal.handle_key(key(KeyCode::Char(ch))).unwrap();
+        }
+    }
+
+    fn confirm(modal: &mut CommandPaletteModal) -> Option<usize> {
+        match modal.handle_key(key(KeyCode::Enter)).unwrap() {
+            Some(ModalResult::Confirmed(index)) => Some(index),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_empty_query_keeps_order() {
+        let mut modal = palette(&["Close Panel", "New Terminal", "Quit"]);
+        assert_eq!(modal.filtered.len(), 3);
+        modal.handle_key(key(KeyCode::Down)).unwrap();
+        assert_eq!(confirm(&mut modal), Some(1));
+    }
+
+    #[test]
+    fn test_query_filters_and_ranks() {
+        let mut modal = palette(&["Toggle Menu", "New Terminal", "Next Panel in Group"]);
+        type_text(&mut modal, "nt");
+        let labels: Vec<usize> = modal.filtered.iter().map(|item| item.index).collect();
+        assert_eq!(labels, vec![1, 2]);
+        assert_eq!(confirm(&mut modal), Some(1));
+
+        // Backspace widens the filter again
+        type_text(&mut modal, "x");
+        assert!(modal.filtered.is_empty());
+        assert_eq!(confirm(&mut modal), None);
+        modal.handle_key(key(KeyCode::Backspace)).unwrap();
+        assert_eq!(modal.filtered.len(), 2);
+    }
+
+    #[test]
+    fn test_ties_keep_recent_first() {
+        let mut modal = palette(&["Go to Panel 3", "Go to Panel 1", "Go to Panel 2"]);
+        type_text(&mut modal, "go");
+        assert_eq!(confirm(&mut modal), Some(0));
+    }
+
+    #[test]
+    fn test_escape_cancels() {
+        let mut modal = palette(&["Quit"]);
+        type_text(&mut modal, "q");
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Esc)).unwrap(),
+            Some(ModalResult::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+//! Read-only diff viewer modal.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use termide_config::constants::{MODAL_MAX_WIDTH_PERCENTAGE_WIDE, MODAL_MIN_WIDTH_WIDE};
+use termide_i18n as i18n;
+use termide_theme::Theme;
+
+use crate::{centered_rect_with_size, Modal, ModalResult};
+
+/// Lines scrolled by the mouse wheel
+const WHEEL_LINES: usize = 3;
+
+/// Scrollable unified diff; any way of closing it confirms, so the caller
+/// can go back to what it was doing
+#[derive(Debug)]
+pub struct DiffModal {
+    title: String,
+    lines: Vec<String>,
+    /// First visible line
+    scroll: usize,
+    /// Lines that fit on screen in the last render
+    page: usize,
+    last_button_area: Option<Rect>,
+}
+
+impl DiffModal {
+    /// Create a viewer of a unified diff (empty when nothing differs)
+    pub fn new(title: impl Into<String>, diff: &str) -> Self {
+        let lines = if diff.is_empty() {
+            vec![i18n::t().diff_identical().to_string()]
+        } else {
+            diff.lines()
+                .map(|line| line.replace('\t', "    "))
+                .collect()
+        };
+        Self {
+            title: title.into(),
+            lines,
+            scroll: 0,
+            page: 1,
+            last_button_area: None,
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.page)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    fn line_style(line: &str, theme: &Theme) -> Style {
+        if line.starts_with("+++") || line.starts_with("---") {
+            Style::default().fg(theme.bg).add_modifier(Modifier::BOLD)
+        } else if line.starts_with("@@") {
+            Style::default().fg(theme.accented_fg)
+        } else if line.starts_with('+') {
+            Style::default().fg(theme.success)
+        } else if line.starts_with('-') {
+            Style::default().fg(theme.error)
+        } else {
+            Style::default().fg(theme.bg)
+        }
+    }
+}
+
+impl Modal for DiffModal {
+    type Result = ();
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let modal_width = ((area.width as f32 * MODAL_MAX_WIDTH_PERCENTAGE_WIDE) as u16)
+            .max(MODAL_MIN_WIDTH_WIDE)
+            .min(area.width);
+        // Borders (2) + lines + empty line (1) + button (1)
+        let modal_height = (self.lines.len() as u16)
+            .saturating_add(4)
+            .min(area.height.saturating_sub(2));
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        Clear.render(modal_area, buf);
+
+        let position = format!(
+            " {}-{}/{} ",
+            self.scroll + 1,
+            (self.scroll + self.page).min(self.lines.len()),
+            self.lines.len()
+        );
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Line::from(position).alignment(Alignment::Right))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),    // Diff
+                Constraint::Length(1), // Empty line
+                Constraint::Length(1), // Button
+            ])
+            .split(inner);
+
+        self.page = (chunks[0].height as usize).max(1);
+        self.scroll = self.scroll.min(self.max_scroll());
+
+        let text: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.page)
+            .map(|line| Line::from(Span::styled(line.clone(), Self::line_style(line, theme))))
+            .collect();
+        Paragraph::new(text).render(chunks[0], buf);
+
+        let close_button = Line::from(vec![Span::styled(
+            format!("[ {} ]", i18n::t().ui_close()),
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD),
+        )]);
+        Paragraph::new(close_button)
+            .alignment(Alignment::Center)
+            .render(chunks[2], buf);
+        self.last_button_area = Some(chunks[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        let page = self.page as isize;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = self.max_scroll(),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                return Ok(Some(ModalResult::Confirmed(())))
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(-(WHEEL_LINES as isize)),
+            MouseEventKind::ScrollDown => self.scroll_by(WHEEL_LINES as isize),
+            MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
+                let on_button = self.last_button_area.is_some_and(|area| {
+                    mouse.row >= area.y
+                        && mouse.row < area.y + area.height
+                        && mouse.column >= area.x
+                        && mouse.column < area.x + area.width
+                });
+                if on_button {
+                    return Ok(Some(ModalResult::Confirmed(())));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_scroll_stays_within_diff() {
+        termide_i18n::init_with_language("en");
+        let diff: String = (0..30).map(|i| format!("+line {}\n", i)).collect();
+        let mut modal = DiffModal::new("Diff", &diff);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 60, 20));
+        modal.render(buf.area, &mut buf, &Theme::default());
+        // 18 rows: 2 borders, an empty line and the button leave 14
+        assert_eq!(modal.page, 14);
+
+        modal.handle_key(key(KeyCode::Up)).unwrap();
+        assert_eq!(modal.scroll, 0);
+        modal.handle_key(key(KeyCode::PageDown)).unwrap();
+        assert_eq!(modal.scroll, 14);
+        modal.handle_key(key(KeyCode::PageDown)).unwrap();
+        assert_eq!(modal.scroll, 16);
+        modal.handle_key(key(KeyCode::Home)).unwrap();
+        assert_eq!(modal.scroll, 0);
+
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Esc)).unwrap(),
+            Some(ModalResult::Confirmed(()))
+        ));
+    }
+
+    #[test]
+    fn test_empty_diff_says_so() {
+        termide_i18n::init_with_language("en");
+        let modal = DiffModal::new("Diff", "");
+        assert_eq!(modal.lines, ["No differences"]);
+    }
+}
@@ -0,0 +1,385 @@
+//! File finder modal.
+//!
+//! Lists the files of the project index and ranks them with fuzzy path
+//! matching as the user types. Files can be added while the modal is open
+//! (the index is built in the background), so typing early searches the
+//! files indexed so far. Enter opens the highlighted file.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget},
+};
+use std::collections::HashMap;
+
+use termide_theme::Theme;
+use termide_ui::file_match::match_path;
+
+use crate::{centered_rect_with_size, Modal, ModalResult, TextInputHandler};
+
+/// Number of visible files
+const MAX_VISIBLE_ITEMS: usize = 15;
+
+/// Best matches kept for the current query
+const MAX_RESULTS: usize = 500;
+
+/// File matching the current query
+#[derive(Debug, Clone)]
+struct FilteredFile {
+    /// Index in `files`
+    index: usize,
+    score: i32,
+    /// Char indices of matched characters in the path
+    positions: Vec<usize>,
+}
+
+/// File finder modal window
+///
+/// Confirms with the relative path of the chosen file.
+#[derive(Debug)]
+pub struct FileFinderModal {
+    title: String,
+    input: TextInputHandler,
+    /// Relative paths of the indexed files
+    files: Vec<String>,
+    /// Position of recently opened files in the recent list
+    recent: HashMap<String, usize>,
+    filtered: Vec<FilteredFile>,
+    /// The index is still being built
+    indexing: bool,
+    /// Text shown in the title while indexing
+    indexing_label: String,
+    cursor: usize,
+    /// First visible row of `filtered`
+    scroll_offset: usize,
+    last_list_area: Option<Rect>,
+}
+
+impl FileFinderModal {
+    /// Create a finder over `files`; `recent` lists recently opened files
+    /// (relative paths, most recent first)
+    pub fn new(title: impl Into<String>, files: Vec<String>, recent: Vec<String>) -> Self {
+        let mut modal = Self {
+            title: title.into(),
+            input: TextInputHandler::new(),
+            files,
+            recent: recent
+                .into_iter()
+                .enumerate()
+                .map(|(rank, path)| (path, rank))
+                .collect(),
+            filtered: Vec::new(),
+            indexing: false,
+            indexing_label: String::new(),
+            cursor: 0,
+            scroll_offset: 0,
+            last_list_area: None,
+        };
+        modal.update_filter();
+        modal
+    }
+
+    /// Show that the index is still being built (`label` is added to the title)
+    pub fn set_indexing(&mut self, indexing: bool, label: impl Into<String>) {
+        self.indexing = indexing;
+        self.indexing_label = label.into();
+    }
+
+    /// Add files indexed after the modal was opened
+    pub fn add_files(&mut self, files: &[String]) {
+        let start = self.files.len();
+        self.files.extend_from_slice(files);
+        let cursor_file = self.selected_file_index();
+        let added = self.matches(start..self.files.len());
+        self.filtered.extend(added);
+        self.rank();
+        // Keep the highlighted file while the list grows
+        if let Some(position) =
+            cursor_file.and_then(|index| self.filtered.iter().position(|f| f.index == index))
+        {
+            self.cursor = position;
+        }
+    }
+
+    /// Replace the files (the index was rebuilt)
+    pub fn set_files(&mut self, files: Vec<String>) {
+        self.files = files;
+        self.update_filter();
+    }
+
+    /// Matches of the query among `files[range]`
+    fn matches(&self, range: std::ops::Range<usize>) -> Vec<FilteredFile> {
+        let query = self.input.text();
+        range
+            .filter_map(|index| {
+                let path = &self.files[index];
+                match_path(query, path, self.recent.get(path).copied()).map(|found| FilteredFile {
+                    index,
+                    score: found.score,
+                    positions: found.positions,
+                })
+            })
+            .collect()
+    }
+
+    /// Sort the matches, best first, and keep the best ones.
+    ///
+    /// Ties keep the index order (sorted paths from git or the walk).
+    fn rank(&mut self) {
+        self.filtered
+            .sort_by_key(|file| (std::cmp::Reverse(file.score), file.index));
+        self.filtered.truncate(MAX_RESULTS);
+    }
+
+    /// Rebuild the filtered list for the current query
+    fn update_filter(&mut self) {
+        self.filtered = self.matches(0..self.files.len());
+        self.rank();
+        self.cursor = 0;
+        self.scroll_offset = 0;
+    }
+
+    /// Index in `files` of the highlighted file
+    fn selected_file_index(&self) -> Option<usize> {
+        self.filtered.get(self.cursor).map(|file| file.index)
+    }
+
+    /// Path of the highlighted file
+    fn selected(&self) -> Option<String> {
+        self.selected_file_index()
+            .map(|index| self.files[index].clone())
+    }
+
+    /// Move cursor by `delta` rows within the filtered list
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.filtered.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+        if self.cursor < self.scroll_offset {
+            self.scroll_offset = self.cursor;
+        } else if self.cursor >= self.scroll_offset + MAX_VISIBLE_ITEMS {
+            self.scroll_offset = self.cursor + 1 - MAX_VISIBLE_ITEMS;
+        }
+    }
+}
+
+impl Modal for FileFinderModal {
+    type Result = String;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        // Wide enough for long paths, the height does not change while typing
+        let modal_width = (area.width * 3 / 4).clamp(40.min(area.width), 120);
+        let modal_height = 1 + 1 + MAX_VISIBLE_ITEMS as u16 + 1;
+
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+        Clear.render(modal_area, buf);
+
+        let title = if self.indexing {
+            format!(" {} ({}) ", self.title, self.indexing_label)
+        } else {
+            format!(" {} ", self.title)
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),                        // Query
+                Constraint::Length(MAX_VISIBLE_ITEMS as u16), // List
+            ])
+            .split(inner);
+
+        let input_line = Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.disabled)),
+            Span::styled(
+                self.input.text_before_cursor(),
+                Style::default().fg(theme.bg),
+            ),
+            Span::styled("█", Style::default().fg(theme.success)),
+            Span::styled(
+                self.input.text_after_cursor(),
+                Style::default().fg(theme.bg),
+            ),
+        ]);
+        Paragraph::new(input_line).render(chunks[0], buf);
+
+        let items: Vec<ListItem> = self
+            .filtered
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(MAX_VISIBLE_ITEMS)
+            .map(|(row, filtered)| {
+                let path = &self.files[filtered.index];
+                let is_cursor = row == self.cursor;
+                let style = if is_cursor {
+                    Style::default()
+                        .fg(theme.fg)
+                        .bg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.bg)
+                };
+                let dir_style = if is_cursor {
+                    style
+                } else {
+                    Style::default().fg(theme.disabled)
+                };
+                let matched_style = style.add_modifier(Modifier::UNDERLINED);
+
+                // Directories are dimmed, the file name stands out
+                let name_start = path
+                    .rfind('/')
+                    .map_or(0, |idx| path[..=idx].chars().count());
+                let mut spans = vec![Span::styled(if is_cursor { "▶ " } else { "  " }, style)];
+                spans.extend(path.chars().enumerate().map(|(idx, ch)| {
+                    let base = if idx < name_start { dir_style } else { style };
+                    let style = if filtered.positions.contains(&idx) {
+                        base.patch(matched_style)
+                    } else {
+                        base
+                    };
+                    Span::styled(ch.to_string(), style)
+                }));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        List::new(items)
+            .style(Style::default().bg(theme.fg))
+            .render(chunks[1], buf);
+
+        // Save list area for mouse handling
+        self.last_list_area = Some(chunks[1]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        match key.code {
+            KeyCode::Esc => return Ok(Some(ModalResult::Cancelled)),
+            KeyCode::Enter => {
+                return Ok(self.selected().map(ModalResult::Confirmed));
+            }
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-(MAX_VISIBLE_ITEMS as isize)),
+            KeyCode::PageDown => self.move_cursor(MAX_VISIBLE_ITEMS as isize),
+            KeyCode::Left => {
+                self.input.move_left();
+            }
+            KeyCode::Right => {
+                self.input.move_right();
+            }
+            KeyCode::Home => self.input.move_home(),
+            KeyCode::End => self.input.move_end(),
+            KeyCode::Backspace if self.input.backspace() => {
+                self.update_filter();
+            }
+            KeyCode::Backspace => {}
+            KeyCode::Delete if self.input.delete() => {
+                self.update_filter();
+            }
+            KeyCode::Delete => {}
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.input.insert_char(ch);
+                self.update_filter();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: crossterm::event::MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        use crossterm::event::MouseEventKind;
+
+        let Some(list_area) = self.last_list_area else {
+            return Ok(None);
+        };
+        let inside = mouse.row >= list_area.y
+            && mouse.row < list_area.y + list_area.height
+            && mouse.column >= list_area.x
+            && mouse.column < list_area.x + list_area.width;
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_cursor(-1),
+            MouseEventKind::ScrollDown => self.move_cursor(1),
+            // Click opens the file under the pointer
+            MouseEventKind::Down(crossterm::event::MouseButton::Left) if inside => {
+                let row = self.scroll_offset + (mouse.row - list_area.y) as usize;
+                if row < self.filtered.len() {
+                    self.cursor = row;
+                    return Ok(self.selected().map(ModalResult::Confirmed));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_text(modal: &mut FileFinderModal, text: &str) {
+        for ch in text.chars() {
+            modal.handle_key(key(KeyCode::Char(ch))).unwrap();
+        }
+    }
+
+    fn confirm(modal: &mut FileFinderModal) -> Option<String> {
+        match modal.handle_key(key(KeyCode::Enter)).unwrap() {
+            Some(ModalResult::Confirmed(path)) => Some(path),
+            _ => None,
+        }
+    }
+
+    fn paths(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn test_recent_files_first_without_query() {
+        let mut modal =
+            FileFinderModal::new("Files", paths(&["a.rs", "b.rs", "c.rs"]), paths(&["c.rs"]));
+        assert_eq!(confirm(&mut modal), Some("c.rs".to_string()));
+    }
+
+    #[test]
+    fn test_files_added_while_typing_are_searched() {
+        let mut modal = FileFinderModal::new("Files", paths(&["src/lib.rs"]), vec![]);
+        type_text(&mut modal, "main");
+        assert_eq!(confirm(&mut modal), None);
+
+        modal.add_files(&paths(&["src/main.rs", "docs/maintenance.md"]));
+        assert_eq!(modal.filtered.len(), 2);
+        assert_eq!(confirm(&mut modal), Some("src/main.rs".to_string()));
+
+        modal.set_files(paths(&["other.rs"]));
+        assert!(modal.filtered.is_empty());
+    }
+}
@@ -16,27 +16,47 @@ pub use termide_ui::{
 };
 
 pub mod base;
+pub mod bookmarks;
+pub mod command_palette;
 pub mod confirm;
 pub mod conflict;
+pub mod diff;
 pub mod editable_select;
+pub mod file_finder;
 pub mod info;
 pub mod input;
+pub mod multiline_input;
+pub mod notifications;
 pub mod overwrite;
+pub mod permissions;
+pub mod progress;
+pub mod quit;
 pub mod rename_pattern;
 pub mod replace;
 pub mod search;
 pub mod select;
+pub mod symlink;
 
+pub use bookmarks::{BookmarkChoice, BookmarkItem, BookmarksModal};
+pub use command_palette::{CommandPaletteModal, PaletteItem};
 pub use confirm::ConfirmModal;
 pub use conflict::{ConflictModal, ConflictResolution};
+pub use diff::DiffModal;
 pub use editable_select::{EditableSelectModal, SelectOption};
+pub use file_finder::FileFinderModal;
 pub use info::InfoModal;
 pub use input::InputModal;
+pub use multiline_input::MultilineInputModal;
+pub use notifications::NotificationsModal;
 pub use overwrite::{OverwriteChoice, OverwriteModal};
+pub use permissions::PermissionsModal;
+pub use progress::ProgressModal;
+pub use quit::{QuitChoice, QuitItem, QuitModal};
 pub use rename_pattern::RenamePatternModal;
 pub use replace::{ReplaceAction, ReplaceModal, ReplaceModalResult};
-pub use search::{SearchAction, SearchModal, SearchModalResult};
+pub use search::{SearchAction, SearchModal, SearchModalResult, SearchOptions};
 pub use select::SelectModal;
+pub use symlink::{SymlinkModal, SymlinkModalResult};
 
 /// Active modal window enum.
 ///
@@ -47,6 +67,8 @@ pub enum ActiveModal {
     Confirm(Box<ConfirmModal>),
     /// Text input modal
     Input(Box<InputModal>),
+    /// Multi-line text input modal
+    MultilineInput(Box<MultilineInputModal>),
     /// Selection modal (single selection)
     Select(Box<SelectModal>),
     /// File overwrite modal
@@ -64,6 +86,24 @@ pub enum ActiveModal {
     Search(Box<SearchModal>),
     /// Interactive replace modal
     Replace(Box<ReplaceModal>),
+    /// Create symlink modal (link path + relative toggle)
+    Symlink(Box<SymlinkModal>),
+    /// Batch operation progress modal
+    Progress(Box<ProgressModal>),
+    /// Command palette (fuzzy command search)
+    CommandPalette(Box<CommandPaletteModal>),
+    /// File finder (fuzzy search of project files)
+    FileFinder(Box<FileFinderModal>),
+    /// Quit with unsaved changes or running processes
+    Quit(Box<QuitModal>),
+    /// Read-only diff viewer
+    Diff(Box<DiffModal>),
+    /// Permissions and ownership of files
+    Permissions(Box<PermissionsModal>),
+    /// Notification history
+    Notifications(Box<NotificationsModal>),
+    /// Bookmark picker
+    Bookmarks(Box<BookmarksModal>),
 }
 
 /// Trait for all modal windows.
@@ -0,0 +1,509 @@
+//! Multi-line text input modal dialog.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use termide_config::constants::MODAL_BUTTON_SPACING;
+use termide_i18n as i18n;
+use termide_theme::Theme;
+
+use crate::{
+    calculate_modal_width, centered_rect_with_size, max_line_width, Modal, ModalResult,
+    ModalWidthConfig, TextInputHandler,
+};
+
+/// Text lines shown when the input is short
+const MIN_TEXT_LINES: u16 = 5;
+
+/// Text lines shown at most (longer text scrolls)
+const MAX_TEXT_LINES: u16 = 15;
+
+/// Focus area in the modal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FocusArea {
+    Input,
+    Buttons,
+}
+
+/// Modal window for entering text of several lines.
+///
+/// Enter starts a new line; Ctrl+S or Alt+Enter confirms.
+#[derive(Debug)]
+pub struct MultilineInputModal {
+    title: String,
+    prompt: String,
+    /// Text lines, each with its own cursor
+    lines: Vec<TextInputHandler>,
+    /// Line with the cursor
+    row: usize,
+    /// First line shown
+    scroll: usize,
+    focus: FocusArea,
+    selected_button: usize, // 0 = OK, 1 = Cancel
+    last_buttons_area: Option<Rect>,
+}
+
+impl MultilineInputModal {
+    /// Create a new multi-line input modal window
+    pub fn new(title: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            prompt: prompt.into(),
+            lines: vec![TextInputHandler::new()],
+            row: 0,
+            scroll: 0,
+            focus: FocusArea::Input,
+            selected_button: 0, // OK button selected by default
+            last_buttons_area: None,
+        }
+    }
+
+    /// Entered text with lines joined by `\n`
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.text())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Result of confirming: the text without trailing blank space,
+    /// or cancel if nothing was entered
+    fn confirm(&self) -> ModalResult<String> {
+        let text = self.text();
+        let text = text.trim_end();
+        if text.trim().is_empty() {
+            ModalResult::Cancelled
+        } else {
+            ModalResult::Confirmed(text.to_string())
+        }
+    }
+
+    /// Line with the cursor
+    fn current(&mut self) -> &mut TextInputHandler {
+        &mut self.lines[self.row]
+    }
+
+    /// Split the current line at the cursor
+    fn new_line(&mut self) {
+        let line = self.current();
+        let after = line.text_after_cursor().to_string();
+        let before = line.text_before_cursor().to_string();
+        line.set_text(before);
+
+        let mut next = TextInputHandler::with_text(after);
+        next.move_home();
+        self.row += 1;
+        self.lines.insert(self.row, next);
+    }
+
+    /// Delete before the cursor, joining with the previous line at its start
+    fn backspace(&mut self) {
+        if self.current().cursor_pos() > 0 || self.row == 0 {
+            self.current().backspace();
+            return;
+        }
+        let line = self.lines.remove(self.row);
+        self.row -= 1;
+        self.join_with_current(line.text());
+    }
+
+    /// Delete at the cursor, joining the next line at the end of the line
+    fn delete(&mut self) {
+        let at_end = self.current().text_after_cursor().is_empty();
+        if !at_end || self.row + 1 == self.lines.len() {
+            self.current().delete();
+            return;
+        }
+        let line = self.lines.remove(self.row + 1);
+        self.join_with_current(line.text());
+    }
+
+    /// Append text to the current line, keeping the cursor at the join
+    fn join_with_current(&mut self, text: &str) {
+        let line = self.current();
+        line.move_end();
+        let cursor = line.cursor_pos();
+        let joined = format!("{}{}", line.text(), text);
+        line.set_text(joined);
+        line.move_home();
+        for _ in 0..cursor {
+            line.move_right();
+        }
+    }
+
+    /// Move the cursor to another line, keeping its column where possible
+    fn move_to_row(&mut self, row: usize) {
+        let column = self.current().cursor_pos();
+        self.row = row;
+        let line = self.current();
+        line.move_home();
+        for _ in 0..column {
+            if !line.move_right() {
+                break;
+            }
+        }
+    }
+
+    /// Number of text lines shown
+    fn visible_lines(&self) -> u16 {
+        (self.lines.len() as u16).clamp(MIN_TEXT_LINES, MAX_TEXT_LINES)
+    }
+
+    /// Number of prompt lines
+    fn prompt_lines(&self) -> u16 {
+        if self.prompt.is_empty() {
+            0
+        } else {
+            self.prompt.lines().count().max(1) as u16
+        }
+    }
+
+    /// Calculate dynamic modal width and height
+    fn calculate_modal_size(&self, screen_width: u16, screen_height: u16) -> (u16, u16) {
+        let t = i18n::t();
+        let title_width = self.title.len() as u16 + 2;
+        let prompt_width = max_line_width(&self.prompt);
+        let hint_width = t.modal_multiline_hint().chars().count() as u16;
+        let text_width = self
+            .lines
+            .iter()
+            .map(|line| line.text().chars().count() as u16 + 4)
+            .max()
+            .unwrap_or(0);
+
+        let width = calculate_modal_width(
+            [title_width, prompt_width, hint_width, text_width].into_iter(),
+            screen_width,
+            ModalWidthConfig {
+                wide: true,
+                double_border: true,
+            },
+        );
+
+        // Calculate height: border + prompt + text + hint + buttons + border
+        let height =
+            (1 + self.prompt_lines() + self.visible_lines() + 2 + 1 + 1 + 1).min(screen_height);
+
+        (width, height)
+    }
+
+    /// Line of the text area, with the cursor on the current line
+    fn render_line(&self, idx: usize, width: usize, theme: &Theme) -> Line<'static> {
+        let line = &self.lines[idx];
+        let text_style = Style::default().fg(theme.bg);
+        if idx != self.row || self.focus != FocusArea::Input {
+            return Line::styled(line.text().to_string(), text_style);
+        }
+
+        // Scroll long lines so the cursor stays visible
+        let skip = line.cursor_pos().saturating_sub(width.saturating_sub(1));
+        let before: String = line.text_before_cursor().chars().skip(skip).collect();
+        Line::from(vec![
+            Span::styled(before, text_style),
+            Span::styled("█", Style::default().fg(theme.success)),
+            Span::styled(line.text_after_cursor().to_string(), text_style),
+        ])
+    }
+
+    /// Confirm with the selected button
+    fn press_selected_button(&self) -> ModalResult<String> {
+        if self.selected_button == 0 {
+            self.confirm()
+        } else {
+            ModalResult::Cancelled
+        }
+    }
+}
+
+impl Modal for MultilineInputModal {
+    type Result = String;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let t = i18n::t();
+
+        // Calculate dynamic dimensions
+        let (modal_width, modal_height) = self.calculate_modal_size(area.width, area.height);
+
+        // Create centered area
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        // Clear the area
+        Clear.render(modal_area, buf);
+
+        // Create block with inverted colors
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let prompt_lines = self.prompt_lines();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(prompt_lines),             // Prompt
+                Constraint::Length(self.visible_lines() + 2), // Text
+                Constraint::Length(1),                        // Hint
+                Constraint::Length(1),                        // Buttons
+            ])
+            .split(inner);
+
+        // Render prompt if not empty
+        if prompt_lines > 0 {
+            Paragraph::new(self.prompt.clone())
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(theme.bg))
+                .render(chunks[0], buf);
+        }
+
+        // Render text, scrolled to keep the cursor line visible
+        let text_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.success));
+        let text_area = text_block.inner(chunks[1]);
+        let height = text_area.height.max(1) as usize;
+        if self.row < self.scroll {
+            self.scroll = self.row;
+        } else if self.row >= self.scroll + height {
+            self.scroll = self.row + 1 - height;
+        }
+        let lines: Vec<Line> = (self.scroll..self.lines.len())
+            .take(height)
+            .map(|idx| self.render_line(idx, text_area.width as usize, theme))
+            .collect();
+        Paragraph::new(lines)
+            .block(text_block)
+            .style(Style::default().bg(theme.fg))
+            .render(chunks[1], buf);
+
+        Paragraph::new(Line::styled(
+            t.modal_multiline_hint(),
+            Style::default().fg(theme.disabled),
+        ))
+        .alignment(Alignment::Center)
+        .render(chunks[2], buf);
+
+        // Render buttons
+        let button_style = |index: usize| {
+            if self.focus == FocusArea::Buttons && self.selected_button == index {
+                Style::default()
+                    .fg(theme.fg)
+                    .bg(theme.accented_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.accented_fg)
+            }
+        };
+        let buttons = Line::from(vec![
+            Span::styled(format!("[ {} ]", t.ui_ok()), button_style(0)),
+            Span::raw("    "),
+            Span::styled(format!("[ {} ]", t.ui_cancel()), button_style(1)),
+        ]);
+        Paragraph::new(buttons)
+            .alignment(Alignment::Center)
+            .render(chunks[3], buf);
+
+        // Save buttons area for mouse handling
+        self.last_buttons_area = Some(chunks[3]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        // Escape always cancels
+        if key.code == KeyCode::Esc {
+            return Ok(Some(ModalResult::Cancelled));
+        }
+
+        // Confirm from anywhere
+        let confirm = match key.code {
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                key.modifiers.contains(KeyModifiers::CONTROL)
+            }
+            KeyCode::Enter => key.modifiers.contains(KeyModifiers::ALT),
+            _ => false,
+        };
+        if confirm {
+            return Ok(Some(self.confirm()));
+        }
+
+        if key.code == KeyCode::Tab || key.code == KeyCode::BackTab {
+            self.focus = match self.focus {
+                FocusArea::Input => FocusArea::Buttons,
+                FocusArea::Buttons => FocusArea::Input,
+            };
+            return Ok(None);
+        }
+
+        match self.focus {
+            FocusArea::Input => match key.code {
+                KeyCode::Enter => self.new_line(),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.current().insert_char(c);
+                }
+                KeyCode::Backspace => self.backspace(),
+                KeyCode::Delete => self.delete(),
+                KeyCode::Left if !self.current().move_left() && self.row > 0 => {
+                    self.row -= 1;
+                    self.current().move_end();
+                }
+                KeyCode::Left => {}
+                KeyCode::Right
+                    if !self.current().move_right() && self.row + 1 < self.lines.len() =>
+                {
+                    self.row += 1;
+                    self.current().move_home();
+                }
+                KeyCode::Right => {}
+                KeyCode::Up if self.row > 0 => self.move_to_row(self.row - 1),
+                KeyCode::Down if self.row + 1 < self.lines.len() => self.move_to_row(self.row + 1),
+                KeyCode::Down => {
+                    // Move focus to buttons from the last line
+                    self.focus = FocusArea::Buttons;
+                }
+                KeyCode::Home => self.current().move_home(),
+                KeyCode::End => self.current().move_end(),
+                _ => {}
+            },
+            FocusArea::Buttons => match key.code {
+                KeyCode::Left | KeyCode::Right => {
+                    // Switch between the two buttons
+                    self.selected_button = 1 - self.selected_button;
+                }
+                KeyCode::Up => {
+                    // Move focus back to input
+                    self.focus = FocusArea::Input;
+                }
+                KeyCode::Enter => return Ok(Some(self.press_selected_button())),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    // Switch back to input and insert character
+                    self.focus = FocusArea::Input;
+                    self.current().insert_char(c);
+                }
+                _ => {}
+            },
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: crossterm::event::MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        use crossterm::event::MouseEventKind;
+
+        // Only handle left button press
+        if mouse.kind != MouseEventKind::Down(crossterm::event::MouseButton::Left) {
+            return Ok(None);
+        }
+
+        // Check if click is within buttons area
+        let Some(buttons_area) = self.last_buttons_area else {
+            return Ok(None);
+        };
+        if mouse.row != buttons_area.y
+            || mouse.column < buttons_area.x
+            || mouse.column >= buttons_area.x + buttons_area.width
+        {
+            return Ok(None);
+        }
+
+        // Buttons are centered: "[ OK ]    [ Cancel ]"
+        let t = i18n::t();
+        let ok_text = format!("[ {} ]", t.ui_ok());
+        let cancel_text = format!("[ {} ]", t.ui_cancel());
+        let total_text_width = ok_text.len() + MODAL_BUTTON_SPACING as usize + cancel_text.len();
+
+        let start_col =
+            buttons_area.x + (buttons_area.width.saturating_sub(total_text_width as u16)) / 2;
+        let ok_end = start_col + ok_text.len() as u16;
+        let cancel_start = ok_end + MODAL_BUTTON_SPACING;
+        let cancel_end = cancel_start + cancel_text.len() as u16;
+
+        if mouse.column >= start_col && mouse.column < ok_end {
+            self.focus = FocusArea::Buttons;
+            self.selected_button = 0;
+            Ok(Some(self.confirm()))
+        } else if mouse.column >= cancel_start && mouse.column < cancel_end {
+            self.focus = FocusArea::Buttons;
+            self.selected_button = 1;
+            Ok(Some(ModalResult::Cancelled))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(modal: &mut MultilineInputModal, text: &str) {
+        for c in text.chars() {
+            let code = if c == '\n' {
+                KeyCode::Enter
+            } else {
+                KeyCode::Char(c)
+            };
+            modal.handle_key(KeyEvent::from(code)).unwrap();
+        }
+    }
+
+    fn press(modal: &mut MultilineInputModal, code: KeyCode) {
+        modal.handle_key(KeyEvent::from(code)).unwrap();
+    }
+
+    #[test]
+    fn test_enter_splits_and_backspace_joins() {
+        let mut modal = MultilineInputModal::new("Commit", "");
+        type_text(&mut modal, "Subject\n\nBody");
+        assert_eq!(modal.text(), "Subject\n\nBody");
+
+        // Split "Body" in the middle, then join it back
+        press(&mut modal, KeyCode::Left);
+        press(&mut modal, KeyCode::Left);
+        press(&mut modal, KeyCode::Enter);
+        assert_eq!(modal.text(), "Subject\n\nBo\ndy");
+        press(&mut modal, KeyCode::Backspace);
+        assert_eq!(modal.text(), "Subject\n\nBody");
+
+        // Delete at the end of the first line joins the empty line
+        press(&mut modal, KeyCode::Up);
+        press(&mut modal, KeyCode::Up);
+        press(&mut modal, KeyCode::End);
+        press(&mut modal, KeyCode::Delete);
+        type_text(&mut modal, "!");
+        assert_eq!(modal.text(), "Subject!\nBody");
+    }
+
+    #[test]
+    fn test_confirm() {
+        let mut modal = MultilineInputModal::new("Commit", "");
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        type_text(&mut modal, "  \n");
+        assert!(matches!(
+            modal.handle_key(ctrl_s).unwrap(),
+            Some(ModalResult::Cancelled)
+        ));
+
+        let mut modal = MultilineInputModal::new("Commit", "");
+        type_text(&mut modal, "Fix parser\n\nDetails\n\n");
+        assert!(matches!(
+            modal.handle_key(ctrl_s).unwrap(),
+            Some(ModalResult::Confirmed(text)) if text == "Fix parser\n\nDetails"
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+//! Notification history modal.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use termide_config::constants::{MODAL_MAX_WIDTH_PERCENTAGE_WIDE, MODAL_MIN_WIDTH_WIDE};
+use termide_i18n as i18n;
+use termide_state::{Notification, NotificationLevel};
+use termide_theme::Theme;
+
+use crate::{centered_rect_with_size, Modal, ModalResult};
+
+/// Lines scrolled by the mouse wheel
+const WHEEL_LINES: usize = 3;
+
+/// Scrollable list of past notifications, newest first, with the time
+/// each arrived
+#[derive(Debug)]
+pub struct NotificationsModal {
+    notifications: Vec<Notification>,
+    /// First visible line
+    scroll: usize,
+    /// Lines that fit on screen in the last render
+    page: usize,
+    last_button_area: Option<Rect>,
+}
+
+impl NotificationsModal {
+    /// Create the list from notifications given newest first
+    pub fn new(notifications: Vec<Notification>) -> Self {
+        Self {
+            notifications,
+            scroll: 0,
+            page: 1,
+            last_button_area: None,
+        }
+    }
+
+    fn line_count(&self) -> usize {
+        self.notifications.len().max(1)
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.line_count().saturating_sub(self.page)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    fn line(notification: &Notification, theme: &Theme) -> Line<'static> {
+        let text_style = match notification.level {
+            NotificationLevel::Info => Style::default().fg(theme.bg),
+            NotificationLevel::Warning => Style::default().fg(theme.warning),
+            NotificationLevel::Error => Style::default().fg(theme.error),
+        };
+        Line::from(vec![
+            Span::styled(
+                format!("{}  ", notification.time.format("%H:%M:%S")),
+                Style::default().fg(theme.accented_fg),
+            ),
+            Span::styled(notification.text.replace(['\n', '\t'], " "), text_style),
+        ])
+    }
+}
+
+impl Modal for NotificationsModal {
+    type Result = ();
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let modal_width = ((area.width as f32 * MODAL_MAX_WIDTH_PERCENTAGE_WIDE) as u16)
+            .max(MODAL_MIN_WIDTH_WIDE)
+            .min(area.width);
+        // Borders (2) + lines + empty line (1) + button (1)
+        let modal_height = (self.line_count() as u16)
+            .saturating_add(4)
+            .min(area.height.saturating_sub(2));
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        Clear.render(modal_area, buf);
+
+        let t = i18n::t();
+        let mut block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", t.notifications_title()),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+        if self.notifications.len() > self.page {
+            block = block.title_bottom(
+                Line::from(format!(
+                    " {}-{}/{} ",
+                    self.scroll + 1,
+                    (self.scroll + self.page).min(self.notifications.len()),
+                    self.notifications.len()
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),    // Notifications
+                Constraint::Length(1), // Empty line
+                Constraint::Length(1), // Button
+            ])
+            .split(inner);
+
+        self.page = (chunks[0].height as usize).max(1);
+        self.scroll = self.scroll.min(self.max_scroll());
+
+        let text: Vec<Line> = if self.notifications.is_empty() {
+            vec![Line::from(Span::styled(
+                t.notifications_empty(),
+                Style::default().fg(theme.disabled),
+            ))]
+        } else {
+            self.notifications
+                .iter()
+                .skip(self.scroll)
+                .take(self.page)
+                .map(|notification| Self::line(notification, theme))
+                .collect()
+        };
+        Paragraph::new(text).render(chunks[0], buf);
+
+        let close_button = Line::from(vec![Span::styled(
+            format!("[ {} ]", t.ui_close()),
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD),
+        )]);
+        Paragraph::new(close_button)
+            .alignment(Alignment::Center)
+            .render(chunks[2], buf);
+        self.last_button_area = Some(chunks[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        let page = self.page as isize;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = self.max_scroll(),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                return Ok(Some(ModalResult::Confirmed(())))
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(-(WHEEL_LINES as isize)),
+            MouseEventKind::ScrollDown => self.scroll_by(WHEEL_LINES as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let on_button = self.last_button_area.is_some_and(|area| {
+                    mouse.row >= area.y
+                        && mouse.row < area.y + area.height
+                        && mouse.column >= area.x
+                        && mouse.column < area.x + area.width
+                });
+                if on_button {
+                    return Ok(Some(ModalResult::Confirmed(())));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_state::Notifications;
+
+    #[test]
+    fn test_lists_newest_first_with_time() {
+        termide_i18n::init_with_language("en");
+        let mut notifications = Notifications::default();
+        let now = std::time::Instant::now();
+        notifications.push("copy finished", NotificationLevel::Info, None, now);
+        notifications.push("fetch failed", NotificationLevel::Error, None, now);
+        let mut modal = NotificationsModal::new(notifications.history().cloned().collect());
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 20));
+        modal.render(buf.area, &mut buf, &Theme::default());
+        let rows: Vec<String> = (0..20)
+            .map(|y| (0..80).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        let row = |text: &str| rows.iter().position(|row| row.contains(text)).unwrap();
+        assert!(row("fetch failed") < row("copy finished"));
+        let time = notifications.history().next().unwrap().time;
+        assert!(rows[row("fetch failed")].contains(&time.format("%H:%M:%S").to_string()));
+    }
+
+    #[test]
+    fn test_empty_history_says_so() {
+        termide_i18n::init_with_language("en");
+        let mut modal = NotificationsModal::new(Vec::new());
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 20));
+        modal.render(buf.area, &mut buf, &Theme::default());
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("No notifications"));
+    }
+}
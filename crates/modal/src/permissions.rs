@@ -0,0 +1,675 @@
+//! Permissions and ownership modal dialog.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use termide_i18n as i18n;
+use termide_state::PermissionsChange;
+use termide_theme::Theme;
+
+use crate::{centered_rect_with_size, Modal, ModalResult};
+
+/// Rows of the mode matrix: owner, group, others and special bits
+const BIT_ROWS: usize = 4;
+
+/// Focusable parts of the dialog, in Tab order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    /// Mode bit at a row of the matrix and a column (read, write, execute;
+    /// set UID, set GID, sticky for the special row)
+    Bit(usize, usize),
+    Owner,
+    Group,
+    Recursive,
+    Apply,
+    Cancel,
+}
+
+impl Focus {
+    /// The matrix counts as one stop for Tab
+    fn section(self) -> Focus {
+        match self {
+            Focus::Bit(..) => Focus::Bit(0, 0),
+            other => other,
+        }
+    }
+}
+
+/// Mode bit at a cell of the matrix
+fn bit(row: usize, col: usize) -> u32 {
+    if row < 3 {
+        0o400 >> (row * 3 + col)
+    } else {
+        0o4000 >> col
+    }
+}
+
+/// `ls`-style mode string, e.g. `rwxr-sr-t`
+pub fn mode_string(mode: u32) -> String {
+    let mut text = String::with_capacity(9);
+    for row in 0..3 {
+        for col in 0..3 {
+            let set = mode & bit(row, col) != 0;
+            let c = if col < 2 {
+                if set {
+                    ['r', 'w'][col]
+                } else {
+                    '-'
+                }
+            } else {
+                let special = mode & bit(3, row) != 0;
+                match (special, set) {
+                    (false, true) => 'x',
+                    (false, false) => '-',
+                    (true, true) => ['s', 's', 't'][row],
+                    (true, false) => ['S', 'S', 'T'][row],
+                }
+            };
+            text.push(c);
+        }
+    }
+    text
+}
+
+/// Dialog changing the mode bits of files, and their owner and group when
+/// allowed. Bits are toggled in a matrix or typed in octal; the result
+/// holds only what changed.
+#[derive(Debug)]
+pub struct PermissionsModal {
+    title: String,
+    initial_mode: u32,
+    mode: u32,
+    /// Octal digits typed since the last other key
+    octal: Option<String>,
+    /// Owner and group the dialog was opened with
+    initial_owner: String,
+    initial_group: String,
+    owners: Vec<String>,
+    owner: usize,
+    groups: Vec<String>,
+    group: usize,
+    /// Owner and group can be changed
+    ownership: bool,
+    /// Some of the items are directories
+    directories: bool,
+    recursive: bool,
+    focus: Focus,
+    /// Screen areas of the focusable parts in the last render
+    hit_areas: Vec<(Rect, Focus)>,
+}
+
+impl PermissionsModal {
+    /// Create the dialog for items with this mode, owner and group
+    pub fn new(title: impl Into<String>, mode: u32, owner: &str, group: &str) -> Self {
+        Self {
+            title: title.into(),
+            initial_mode: mode & 0o7777,
+            mode: mode & 0o7777,
+            octal: None,
+            initial_owner: owner.to_string(),
+            initial_group: group.to_string(),
+            owners: vec![owner.to_string()],
+            owner: 0,
+            groups: vec![group.to_string()],
+            group: 0,
+            ownership: false,
+            directories: false,
+            recursive: false,
+            focus: Focus::Bit(0, 0),
+            hit_areas: Vec::new(),
+        }
+    }
+
+    /// Let the user pick another owner and group from these names
+    pub fn with_ownership(mut self, owners: Vec<String>, groups: Vec<String>) -> Self {
+        fn merge(current: &str, mut names: Vec<String>) -> (Vec<String>, usize) {
+            names.push(current.to_string());
+            names.sort();
+            names.dedup();
+            let index = names.iter().position(|name| name == current).unwrap_or(0);
+            (names, index)
+        }
+        (self.owners, self.owner) = merge(&self.initial_owner, owners);
+        (self.groups, self.group) = merge(&self.initial_group, groups);
+        self.ownership = true;
+        self
+    }
+
+    /// Offer to apply the change to the contents of directories too
+    pub fn with_directories(mut self) -> Self {
+        self.directories = true;
+        self
+    }
+
+    /// What the user changed
+    pub fn change(&self) -> PermissionsChange {
+        let owner = &self.owners[self.owner];
+        let group = &self.groups[self.group];
+        PermissionsChange {
+            set: self.mode & !self.initial_mode,
+            clear: self.initial_mode & !self.mode,
+            owner: (*owner != self.initial_owner).then(|| owner.clone()),
+            group: (*group != self.initial_group).then(|| group.clone()),
+            recursive: self.directories && self.recursive,
+        }
+    }
+
+    /// Stops of the Tab order
+    fn sections(&self) -> Vec<Focus> {
+        let mut sections = vec![Focus::Bit(0, 0)];
+        if self.ownership {
+            sections.extend([Focus::Owner, Focus::Group]);
+        }
+        if self.directories {
+            sections.push(Focus::Recursive);
+        }
+        sections.extend([Focus::Apply, Focus::Cancel]);
+        sections
+    }
+
+    /// Move to the next or previous stop, wrapping around with Tab
+    fn move_section(&mut self, forward: bool, wrap: bool) {
+        let sections = self.sections();
+        let index = sections
+            .iter()
+            .position(|&section| section == self.focus.section())
+            .unwrap_or(0);
+        let next = if forward {
+            if index + 1 < sections.len() {
+                index + 1
+            } else if wrap {
+                0
+            } else {
+                index
+            }
+        } else if index > 0 {
+            index - 1
+        } else if wrap {
+            sections.len() - 1
+        } else {
+            index
+        };
+        self.focus = match sections[next] {
+            // Entering the matrix from below lands on its last row
+            Focus::Bit(..) if !forward => Focus::Bit(BIT_ROWS - 1, 0),
+            section => section,
+        };
+    }
+
+    /// Select the next or previous name of a list
+    fn cycle(index: &mut usize, len: usize, forward: bool) {
+        *index = if forward {
+            (*index + 1) % len
+        } else {
+            (*index + len - 1) % len
+        };
+    }
+
+    /// Jump to the next name starting with `c`
+    fn jump(names: &[String], index: &mut usize, c: char) {
+        let c = c.to_lowercase().to_string();
+        let len = names.len();
+        if let Some(found) = (1..=len)
+            .map(|step| (*index + step) % len)
+            .find(|&i| names[i].to_lowercase().starts_with(&c))
+        {
+            *index = found;
+        }
+    }
+
+    /// Space or a click on the focused part
+    fn activate(&mut self) -> Option<ModalResult<PermissionsChange>> {
+        match self.focus {
+            Focus::Bit(row, col) => self.mode ^= bit(row, col),
+            Focus::Owner => Self::cycle(&mut self.owner, self.owners.len(), true),
+            Focus::Group => Self::cycle(&mut self.group, self.groups.len(), true),
+            Focus::Recursive => self.recursive = !self.recursive,
+            Focus::Apply => return Some(ModalResult::Confirmed(self.change())),
+            Focus::Cancel => return Some(ModalResult::Cancelled),
+        }
+        None
+    }
+
+    /// Add a typed octal digit; up to four digits make up the mode
+    fn type_digit(&mut self, digit: char) {
+        let octal = self.octal.get_or_insert_with(String::new);
+        if octal.len() < 4 {
+            octal.push(digit);
+        }
+        if let Ok(mode) = u32::from_str_radix(octal, 8) {
+            self.mode = mode;
+        }
+    }
+
+    fn modal_width(&self, screen_width: u16) -> u16 {
+        let t = i18n::t();
+        let label = self.label_width();
+        let cell = self.cell_width();
+        let widths = [
+            label + 3 * cell,
+            self.title.width() + 4,
+            t.permissions_recursive().width() + 4,
+        ];
+        // Padding (2) and borders (2)
+        ((widths.into_iter().max().unwrap_or(0) + 4) as u16).min(screen_width)
+    }
+
+    /// Width of the row labels, with a gap after them
+    fn label_width(&self) -> usize {
+        let t = i18n::t();
+        [
+            t.file_info_owner(),
+            t.file_info_group(),
+            t.permissions_others(),
+            t.permissions_special(),
+            t.permissions_octal(),
+        ]
+        .iter()
+        .map(|label| label.width())
+        .max()
+        .unwrap_or(0)
+            + 2
+    }
+
+    /// Width of a checkbox with its name, with a gap after it
+    fn cell_width(&self) -> usize {
+        let t = i18n::t();
+        [
+            t.permissions_read(),
+            t.permissions_write(),
+            t.permissions_execute(),
+            t.permissions_setuid(),
+            t.permissions_setgid(),
+            t.permissions_sticky(),
+        ]
+        .iter()
+        .map(|name| name.width())
+        .max()
+        .unwrap_or(0)
+            + 6
+    }
+}
+
+impl Modal for PermissionsModal {
+    type Result = PermissionsChange;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let t = i18n::t();
+        // Matrix, empty line, octal, owner and group, checkbox, empty
+        // line, buttons
+        let mut rows = BIT_ROWS + 2;
+        if self.ownership {
+            rows += 2;
+        }
+        if self.directories {
+            rows += 1;
+        }
+        let rows = rows + 2;
+        let modal_width = self.modal_width(area.width);
+        let modal_height = (rows as u16 + 2).min(area.height);
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        Clear.render(modal_area, buf);
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let text_style = Style::default().fg(theme.bg);
+        let label_style = Style::default()
+            .fg(theme.accented_fg)
+            .add_modifier(Modifier::BOLD);
+        let focused_style = Style::default()
+            .fg(theme.fg)
+            .bg(theme.accented_fg)
+            .add_modifier(Modifier::BOLD);
+        let style_for = |focus: Focus, current: Focus| {
+            if focus == current {
+                focused_style
+            } else {
+                text_style
+            }
+        };
+
+        let label_width = self.label_width();
+        let cell_width = self.cell_width();
+        let x0 = inner.x + 1;
+        let mut lines: Vec<Line> = Vec::new();
+        let mut hit_areas = Vec::new();
+        let mut hit = |line: usize, x: usize, width: usize, focus: Focus| {
+            hit_areas.push((
+                Rect::new(x0 + x as u16, inner.y + line as u16, width as u16, 1),
+                focus,
+            ));
+        };
+        let pad = |text: &str, width: usize| {
+            format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+        };
+
+        let row_labels = [
+            t.file_info_owner(),
+            t.file_info_group(),
+            t.permissions_others(),
+            t.permissions_special(),
+        ];
+        let bit_names = [
+            [
+                t.permissions_read(),
+                t.permissions_write(),
+                t.permissions_execute(),
+            ],
+            [
+                t.permissions_setuid(),
+                t.permissions_setgid(),
+                t.permissions_sticky(),
+            ],
+        ];
+        for (row, label) in row_labels.iter().enumerate() {
+            let mut spans = vec![Span::styled(pad(label, label_width), label_style)];
+            let names = &bit_names[usize::from(row == 3)];
+            for (col, name) in names.iter().enumerate() {
+                let checkbox = if self.mode & bit(row, col) != 0 {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                let cell = format!("{} {}", checkbox, name);
+                let focus = Focus::Bit(row, col);
+                hit(
+                    lines.len(),
+                    label_width + col * cell_width,
+                    cell.width(),
+                    focus,
+                );
+                spans.push(Span::styled(cell.clone(), style_for(focus, self.focus)));
+                spans.push(Span::raw(" ".repeat(cell_width - cell.width())));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::default());
+
+        let octal = match &self.octal {
+            Some(typed) => format!("{}_", typed),
+            None => format!("{:04o}", self.mode),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(pad(t.permissions_octal(), label_width), label_style),
+            Span::styled(octal, text_style.add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  {}", mode_string(self.mode)), text_style),
+        ]));
+
+        if self.ownership {
+            for (focus, label, name) in [
+                (Focus::Owner, t.file_info_owner(), &self.owners[self.owner]),
+                (Focus::Group, t.file_info_group(), &self.groups[self.group]),
+            ] {
+                let value = format!("< {} >", name);
+                hit(lines.len(), label_width, value.width(), focus);
+                lines.push(Line::from(vec![
+                    Span::styled(pad(label, label_width), label_style),
+                    Span::styled(value, style_for(focus, self.focus)),
+                ]));
+            }
+        }
+
+        if self.directories {
+            let checkbox = if self.recursive { "[x]" } else { "[ ]" };
+            let text = format!("{} {}", checkbox, t.permissions_recursive());
+            hit(lines.len(), 0, text.width(), Focus::Recursive);
+            lines.push(Line::from(Span::styled(
+                text,
+                style_for(Focus::Recursive, self.focus),
+            )));
+        }
+        lines.push(Line::default());
+
+        // Buttons, centered
+        let apply = format!("[ {} ]", t.permissions_apply());
+        let cancel = format!("[ {} ]", t.ui_cancel());
+        let buttons_width = apply.width() + 4 + cancel.width();
+        let start = (inner.width as usize)
+            .saturating_sub(2)
+            .saturating_sub(buttons_width)
+            / 2;
+        hit(lines.len(), start, apply.width(), Focus::Apply);
+        hit(
+            lines.len(),
+            start + apply.width() + 4,
+            cancel.width(),
+            Focus::Cancel,
+        );
+        let button_style = |focus: Focus| {
+            if focus == self.focus {
+                focused_style
+            } else {
+                Style::default().fg(theme.accented_fg)
+            }
+        };
+        lines.push(Line::from(vec![
+            Span::raw(" ".repeat(start)),
+            Span::styled(apply, button_style(Focus::Apply)),
+            Span::raw("    "),
+            Span::styled(cancel, button_style(Focus::Cancel)),
+        ]));
+
+        let content = Rect::new(x0, inner.y, inner.width.saturating_sub(2), inner.height);
+        Paragraph::new(lines).render(content, buf);
+        self.hit_areas = hit_areas;
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        let typing = matches!(self.focus, Focus::Bit(..))
+            && matches!(key.code, KeyCode::Char('0'..='7') | KeyCode::Backspace);
+        if !typing {
+            self.octal = None;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Ok(Some(ModalResult::Cancelled)),
+            KeyCode::Enter => {
+                return Ok(Some(if self.focus == Focus::Cancel {
+                    ModalResult::Cancelled
+                } else {
+                    ModalResult::Confirmed(self.change())
+                }))
+            }
+            KeyCode::Tab => self.move_section(true, true),
+            KeyCode::BackTab => self.move_section(false, true),
+            KeyCode::Char(' ') => return Ok(self.activate()),
+            KeyCode::Char(digit @ '0'..='7') if typing => self.type_digit(digit),
+            KeyCode::Backspace if typing => {
+                if let Some(octal) = &mut self.octal {
+                    octal.pop();
+                    self.mode = u32::from_str_radix(octal, 8).unwrap_or(self.initial_mode);
+                }
+            }
+            KeyCode::Up => match self.focus {
+                Focus::Bit(row, col) if row > 0 => self.focus = Focus::Bit(row - 1, col),
+                Focus::Bit(..) => {}
+                _ => self.move_section(false, false),
+            },
+            KeyCode::Down => match self.focus {
+                Focus::Bit(row, col) if row + 1 < BIT_ROWS => self.focus = Focus::Bit(row + 1, col),
+                Focus::Apply | Focus::Cancel => {}
+                _ => self.move_section(true, false),
+            },
+            KeyCode::Left | KeyCode::Right => {
+                let forward = key.code == KeyCode::Right;
+                match self.focus {
+                    Focus::Bit(row, col) => {
+                        let col = if forward {
+                            (col + 1).min(2)
+                        } else {
+                            col.saturating_sub(1)
+                        };
+                        self.focus = Focus::Bit(row, col);
+                    }
+                    Focus::Owner => Self::cycle(&mut self.owner, self.owners.len(), forward),
+                    Focus::Group => Self::cycle(&mut self.group, self.groups.len(), forward),
+                    Focus::Apply | Focus::Cancel => {
+                        self.focus = if forward { Focus::Cancel } else { Focus::Apply };
+                    }
+                    Focus::Recursive => {}
+                }
+            }
+            KeyCode::Char(c) => match self.focus {
+                Focus::Owner => Self::jump(&self.owners, &mut self.owner, c),
+                Focus::Group => Self::jump(&self.groups, &mut self.group, c),
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        let Some(&(_, focus)) = self.hit_areas.iter().find(|(area, _)| {
+            mouse.row >= area.y
+                && mouse.row < area.y + area.height
+                && mouse.column >= area.x
+                && mouse.column < area.x + area.width
+        }) else {
+            return Ok(None);
+        };
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.octal = None;
+                self.focus = focus;
+                Ok(self.activate())
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let forward = mouse.kind == MouseEventKind::ScrollDown;
+                match focus {
+                    Focus::Owner => Self::cycle(&mut self.owner, self.owners.len(), forward),
+                    Focus::Group => Self::cycle(&mut self.group, self.groups.len(), forward),
+                    _ => {}
+                }
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn confirmed(modal: &mut PermissionsModal) -> PermissionsChange {
+        match modal.handle_key(key(KeyCode::Enter)).unwrap() {
+            Some(ModalResult::Confirmed(change)) => change,
+            other => panic!("{:?}", other.is_some()),
+        }
+    }
+
+    #[test]
+    fn test_mode_string() {
+        assert_eq!(mode_string(0o755), "rwxr-xr-x");
+        assert_eq!(mode_string(0o4644), "rwSr--r--");
+        assert_eq!(mode_string(0o2755), "rwxr-sr-x");
+        assert_eq!(mode_string(0o1777), "rwxrwxrwt");
+    }
+
+    #[test]
+    fn test_toggle_and_type_octal() {
+        termide_i18n::init_with_language("en");
+        let mut modal = PermissionsModal::new("Permissions", 0o100644, "me", "staff");
+        // Owner execute
+        modal.handle_key(key(KeyCode::Right)).unwrap();
+        modal.handle_key(key(KeyCode::Right)).unwrap();
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        // Others read, off
+        modal.handle_key(key(KeyCode::Down)).unwrap();
+        modal.handle_key(key(KeyCode::Down)).unwrap();
+        modal.handle_key(key(KeyCode::Left)).unwrap();
+        modal.handle_key(key(KeyCode::Left)).unwrap();
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        assert_eq!(modal.mode, 0o740);
+        let change = confirmed(&mut modal);
+        assert_eq!((change.set, change.clear), (0o100, 0o004));
+        assert!(change.owner.is_none() && change.group.is_none());
+
+        for digit in "1770".chars() {
+            modal.handle_key(key(KeyCode::Char(digit))).unwrap();
+        }
+        assert_eq!(modal.mode, 0o1770);
+        modal.handle_key(key(KeyCode::Backspace)).unwrap();
+        assert_eq!(modal.mode, 0o177);
+    }
+
+    #[test]
+    fn test_ownership_and_recursion() {
+        termide_i18n::init_with_language("en");
+        let mut modal = PermissionsModal::new("Permissions", 0o755, "me", "staff")
+            .with_ownership(
+                vec!["root".into(), "me".into(), "mail".into()],
+                vec!["wheel".into(), "staff".into()],
+            )
+            .with_directories();
+        assert_eq!(modal.owners, ["mail", "me", "root"]);
+        assert_eq!(modal.owner, 1);
+
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        modal.handle_key(key(KeyCode::Char('r'))).unwrap();
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        let change = confirmed(&mut modal);
+        assert_eq!(change.owner.as_deref(), Some("root"));
+        assert_eq!(change.group, None);
+        assert!(change.recursive);
+        assert!(!change.is_empty());
+
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        modal.handle_key(key(KeyCode::Right)).unwrap();
+        assert_eq!(modal.focus, Focus::Cancel);
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Enter)).unwrap(),
+            Some(ModalResult::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_click_toggles_bit() {
+        termide_i18n::init_with_language("en");
+        let mut modal = PermissionsModal::new("Permissions", 0o644, "me", "staff");
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 24));
+        modal.render(buf.area, &mut buf, &Theme::default());
+        let (area, _) = *modal
+            .hit_areas
+            .iter()
+            .find(|(_, focus)| *focus == Focus::Bit(1, 1))
+            .unwrap();
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: area.x,
+            row: area.y,
+            modifiers: KeyModifiers::NONE,
+        };
+        modal.handle_mouse(click, buf.area).unwrap();
+        assert_eq!(modal.mode, 0o664);
+    }
+}
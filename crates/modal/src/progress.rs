@@ -0,0 +1,301 @@
+//! Batch operation progress modal dialog.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
+
+use termide_i18n as i18n;
+use termide_state::CancellationToken;
+use termide_theme::Theme;
+
+use crate::{centered_rect_with_size, Modal, ModalResult};
+
+/// Preferred modal width (clamped to screen)
+const PROGRESS_MODAL_WIDTH: u16 = 60;
+
+/// Progress modal for copy/move/delete operations (Escape requests cancellation)
+#[derive(Debug)]
+pub struct ProgressModal {
+    title: String,
+    cancel_token: CancellationToken,
+    format_size: fn(u64) -> String,
+    current_file: String,
+    files_done: usize,
+    files_total: usize,
+    bytes_done: u64,
+    bytes_total: Option<u64>,
+    /// Size and speed lines are shown (not for operations counting files only)
+    show_sizes: bool,
+    started: Instant,
+}
+
+impl ProgressModal {
+    /// Create a new progress modal
+    ///
+    /// `cancel_token` is cancelled when user presses Escape;
+    /// `format_size` formats byte counts for display.
+    pub fn new(
+        title: impl Into<String>,
+        cancel_token: CancellationToken,
+        format_size: fn(u64) -> String,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            cancel_token,
+            format_size,
+            current_file: String::new(),
+            files_done: 0,
+            files_total: 0,
+            bytes_done: 0,
+            bytes_total: None,
+            show_sizes: true,
+            started: Instant::now(),
+        }
+    }
+
+    /// Hide size and speed, for operations that only count files
+    pub fn without_sizes(mut self) -> Self {
+        self.show_sizes = false;
+        self
+    }
+
+    /// Set name of the file being processed
+    pub fn set_current_file(&mut self, name: impl Into<String>) {
+        self.current_file = name.into();
+    }
+
+    /// Update file and byte counters
+    pub fn set_progress(
+        &mut self,
+        files_done: usize,
+        files_total: usize,
+        bytes_done: u64,
+        bytes_total: Option<u64>,
+    ) {
+        self.files_done = files_done;
+        self.files_total = files_total;
+        self.bytes_done = bytes_done;
+        self.bytes_total = bytes_total;
+    }
+
+    /// Check if user requested cancellation
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel_token.is_cancelled()
+    }
+
+    /// Completed fraction (by bytes if total is known, by files otherwise)
+    fn ratio(&self) -> f64 {
+        match self.bytes_total {
+            Some(total) if total > 0 => (self.bytes_done as f64 / total as f64).min(1.0),
+            _ if self.files_total > 0 => {
+                (self.files_done as f64 / self.files_total as f64).min(1.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Average throughput in bytes per second since the modal was opened
+    fn throughput(&self) -> u64 {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            (self.bytes_done as f64 / elapsed) as u64
+        } else {
+            0
+        }
+    }
+
+    /// Text progress bar of given width
+    fn progress_bar(&self, width: usize) -> String {
+        let filled = ((width as f64) * self.ratio()).round() as usize;
+        format!(
+            "{}{}",
+            "█".repeat(filled),
+            "░".repeat(width.saturating_sub(filled))
+        )
+    }
+
+    /// Truncate text from the left to fit width (keeps the file name end visible)
+    fn truncate_left(text: &str, max_width: usize) -> String {
+        if text.width() <= max_width {
+            return text.to_string();
+        }
+        let mut result = String::new();
+        let mut width = 1; // leading ellipsis
+        for ch in text.chars().rev() {
+            let ch_width = ch.to_string().width();
+            if width + ch_width > max_width {
+                break;
+            }
+            width += ch_width;
+            result.insert(0, ch);
+        }
+        format!("…{}", result)
+    }
+}
+
+impl Modal for ProgressModal {
+    type Result = ();
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let t = i18n::t();
+
+        // border + file + files + size + speed + bar + hint + border
+        let modal_width = PROGRESS_MODAL_WIDTH.min(area.width);
+        let modal_height = if self.show_sizes { 8u16 } else { 6 }.min(area.height);
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        // Clear the area
+        Clear.render(modal_area, buf);
+
+        // Create block with inverted colors
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let content_width = inner.width.saturating_sub(2) as usize;
+        let text_style = Style::default().fg(theme.bg);
+        let format_size = self.format_size;
+
+        let size_text = match self.bytes_total {
+            Some(total) => format!(
+                "{}: {} / {} ({:.0}%)",
+                t.progress_size(),
+                format_size(self.bytes_done),
+                format_size(total),
+                self.ratio() * 100.0
+            ),
+            None => format!("{}: {}", t.progress_size(), format_size(self.bytes_done)),
+        };
+
+        let hint = if self.is_cancelling() {
+            Span::styled(
+                t.progress_cancelling(),
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(
+                t.progress_cancel_hint(),
+                Style::default().fg(theme.accented_fg),
+            )
+        };
+
+        let mut lines = vec![
+            Line::from(Span::styled(
+                Self::truncate_left(&self.current_file, content_width),
+                text_style.add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "{}: {} / {}",
+                    t.progress_files(),
+                    self.files_done,
+                    self.files_total
+                ),
+                text_style,
+            )),
+        ];
+        if self.show_sizes {
+            lines.push(Line::from(Span::styled(size_text, text_style)));
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}: {}/s",
+                    t.progress_speed(),
+                    format_size(self.throughput())
+                ),
+                text_style,
+            )));
+        }
+        lines.push(Line::from(Span::styled(
+            self.progress_bar(content_width),
+            Style::default().fg(theme.success),
+        )));
+        lines.push(Line::from(hint).alignment(Alignment::Center));
+
+        let content_area = Rect {
+            x: inner.x + 1,
+            width: inner.width.saturating_sub(2),
+            ..inner
+        };
+        Paragraph::new(lines).render(content_area, buf);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        // Escape requests cancellation; the modal stays open until
+        // the worker stops after the current chunk
+        if key.code == KeyCode::Esc {
+            self.cancel_token.cancel();
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        _mouse: crossterm::event::MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn format_size(bytes: u64) -> String {
+        format!("{} B", bytes)
+    }
+
+    #[test]
+    fn test_escape_requests_cancellation() {
+        let token = CancellationToken::new();
+        let mut modal = ProgressModal::new("Copying", token.clone(), format_size);
+
+        let result = modal
+            .handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+            .unwrap();
+
+        assert!(result.is_none());
+        assert!(token.is_cancelled());
+        assert!(modal.is_cancelling());
+    }
+
+    #[test]
+    fn test_ratio_prefers_bytes() {
+        let mut modal = ProgressModal::new("Copying", CancellationToken::new(), format_size);
+
+        modal.set_progress(1, 4, 0, None);
+        assert_eq!(modal.ratio(), 0.25);
+
+        modal.set_progress(1, 4, 30, Some(40));
+        assert_eq!(modal.ratio(), 0.75);
+        assert_eq!(modal.progress_bar(4), "███░");
+    }
+
+    #[test]
+    fn test_truncate_left_keeps_file_name() {
+        assert_eq!(ProgressModal::truncate_left("short", 10), "short");
+        assert_eq!(
+            ProgressModal::truncate_left("/very/long/path/file.txt", 9),
+            "…file.txt"
+        );
+    }
+}
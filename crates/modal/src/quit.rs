@@ -0,0 +1,582 @@
+//! Quit modal: what to do with each panel that would lose work on exit.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use termide_config::constants::MODAL_BUTTON_SPACING;
+use termide_i18n as i18n;
+use termide_theme::Theme;
+
+use crate::{
+    calculate_modal_width, centered_rect_with_size, max_line_width, Modal, ModalResult,
+    ModalWidthConfig,
+};
+
+/// Maximum number of items shown at once (the list scrolls beyond)
+const MAX_VISIBLE_ITEMS: usize = 10;
+
+/// What to do with a panel before quitting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuitChoice {
+    /// Save the file
+    Save,
+    /// Drop the unsaved changes
+    Discard,
+    /// Kill the running processes
+    Kill,
+}
+
+impl QuitChoice {
+    fn label(self) -> &'static str {
+        let t = i18n::t();
+        match self {
+            Self::Save => t.modal_quit_save(),
+            Self::Discard => t.modal_quit_discard(),
+            Self::Kill => t.modal_quit_kill(),
+        }
+    }
+}
+
+/// Panel listed in the quit modal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuitItem {
+    /// Panel title (file name, terminal title)
+    pub label: String,
+    /// Why the panel is listed
+    pub detail: String,
+    /// Choices in toggle order, the first one is the default
+    choices: &'static [QuitChoice],
+}
+
+impl QuitItem {
+    /// Editor with unsaved (or externally changed) content: save or discard
+    pub fn file(label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            detail: detail.into(),
+            choices: &[QuitChoice::Save, QuitChoice::Discard],
+        }
+    }
+
+    /// Terminal with running processes: they are killed
+    pub fn terminal(label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            detail: detail.into(),
+            choices: &[QuitChoice::Kill],
+        }
+    }
+}
+
+/// Buttons below the list, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuitButton {
+    SaveAll,
+    DiscardAll,
+    Quit,
+    Cancel,
+}
+
+const BUTTONS: [QuitButton; 4] = [
+    QuitButton::SaveAll,
+    QuitButton::DiscardAll,
+    QuitButton::Quit,
+    QuitButton::Cancel,
+];
+
+impl QuitButton {
+    fn text(self) -> String {
+        let t = i18n::t();
+        let label = match self {
+            Self::SaveAll => t.modal_quit_save_all(),
+            Self::DiscardAll => t.modal_quit_discard_all(),
+            Self::Quit => t.modal_quit_button(),
+            Self::Cancel => t.ui_cancel(),
+        };
+        format!("[ {} ]", label)
+    }
+}
+
+/// Focused element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Item(usize),
+    Button(usize),
+}
+
+/// Quit modal listing the panels with unsaved changes or running processes,
+/// each with its own choice.
+///
+/// Confirms with the choice for each item, in item order.
+#[derive(Debug)]
+pub struct QuitModal {
+    title: String,
+    message: String,
+    items: Vec<QuitItem>,
+    choices: Vec<QuitChoice>,
+    focus: Focus,
+    scroll: usize,
+    last_list_area: Option<Rect>,
+    last_buttons_area: Option<Rect>,
+}
+
+impl QuitModal {
+    /// Create a quit modal; every item starts with its first choice
+    pub fn new(title: impl Into<String>, message: impl Into<String>, items: Vec<QuitItem>) -> Self {
+        let choices = items.iter().map(|item| item.choices[0]).collect();
+        let focus = if items.is_empty() {
+            Focus::Button(BUTTONS.len() - 2)
+        } else {
+            Focus::Item(0)
+        };
+        Self {
+            title: title.into(),
+            message: message.into(),
+            items,
+            choices,
+            focus,
+            scroll: 0,
+            last_list_area: None,
+            last_buttons_area: None,
+        }
+    }
+
+    /// Current choices, in item order
+    pub fn choices(&self) -> &[QuitChoice] {
+        &self.choices
+    }
+
+    /// Switch an item to its next choice
+    fn toggle(&mut self, index: usize) {
+        let choices = self.items[index].choices;
+        let current = choices
+            .iter()
+            .position(|&choice| choice == self.choices[index])
+            .unwrap_or(0);
+        self.choices[index] = choices[(current + 1) % choices.len()];
+    }
+
+    /// Set every item that offers `choice` to it
+    fn set_all(&mut self, choice: QuitChoice) {
+        for (item, current) in self.items.iter().zip(self.choices.iter_mut()) {
+            if item.choices.contains(&choice) {
+                *current = choice;
+            }
+        }
+    }
+
+    fn press(&mut self, button: QuitButton) -> ModalResult<Vec<QuitChoice>> {
+        match button {
+            QuitButton::SaveAll => self.set_all(QuitChoice::Save),
+            QuitButton::DiscardAll => self.set_all(QuitChoice::Discard),
+            QuitButton::Quit => {}
+            QuitButton::Cancel => return ModalResult::Cancelled,
+        }
+        ModalResult::Confirmed(self.choices.clone())
+    }
+
+    /// Focus positions in Tab order
+    fn focus_order(&self) -> Vec<Focus> {
+        (0..self.items.len())
+            .map(Focus::Item)
+            .chain((0..BUTTONS.len()).map(Focus::Button))
+            .collect()
+    }
+
+    fn cycle_focus(&mut self, forward: bool) {
+        let order = self.focus_order();
+        let current = order.iter().position(|&f| f == self.focus).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % order.len()
+        } else {
+            (current + order.len() - 1) % order.len()
+        };
+        self.focus = order[next];
+    }
+
+    fn visible_items(&self) -> usize {
+        self.items.len().min(MAX_VISIBLE_ITEMS)
+    }
+
+    /// Scroll the list so that the focused item is visible
+    fn scroll_to_focus(&mut self) {
+        if let Focus::Item(index) = self.focus {
+            let visible = self.visible_items();
+            if index < self.scroll {
+                self.scroll = index;
+            } else if index >= self.scroll + visible {
+                self.scroll = index + 1 - visible;
+            }
+        }
+    }
+
+    /// Width of the widest choice label
+    fn choice_width() -> usize {
+        [QuitChoice::Save, QuitChoice::Discard, QuitChoice::Kill]
+            .iter()
+            .map(|choice| choice.label().width())
+            .max()
+            .unwrap_or(0)
+            + 4 // "‹ " + " ›"
+    }
+
+    fn item_text(item: &QuitItem) -> String {
+        format!("{} — {}", item.label, item.detail)
+    }
+
+    fn buttons_width() -> usize {
+        BUTTONS
+            .iter()
+            .map(|button| button.text().width())
+            .sum::<usize>()
+            + (BUTTONS.len() - 1) * MODAL_BUTTON_SPACING as usize
+    }
+
+    /// Calculate dynamic modal width and height
+    fn calculate_modal_size(&self, screen_width: u16, screen_height: u16) -> (u16, u16) {
+        let title_width = self.title.width() as u16 + 2;
+        let message_width = max_line_width(&self.message);
+        let item_width = self
+            .items
+            .iter()
+            .map(|item| 2 + Self::item_text(item).width() + 2 + Self::choice_width())
+            .max()
+            .unwrap_or(0) as u16;
+
+        let width = calculate_modal_width(
+            [
+                title_width,
+                message_width,
+                item_width,
+                Self::buttons_width() as u16,
+            ]
+            .into_iter(),
+            screen_width,
+            ModalWidthConfig {
+                wide: true,
+                double_border: false,
+            },
+        );
+
+        // Border + message + items + blank line + buttons + border
+        let message_lines = self.message.lines().count().max(1);
+        let height = (1 + message_lines + self.visible_items() + 1 + 1 + 1) as u16;
+        (width, height.min(screen_height))
+    }
+
+    fn button_at(&self, column: u16) -> Option<usize> {
+        let area = self.last_buttons_area?;
+        let total = Self::buttons_width() as u16;
+        let mut start = area.x + area.width.saturating_sub(total) / 2;
+        for (index, button) in BUTTONS.iter().enumerate() {
+            let end = start + button.text().width() as u16;
+            if column >= start && column < end {
+                return Some(index);
+            }
+            start = end + MODAL_BUTTON_SPACING;
+        }
+        None
+    }
+}
+
+impl Modal for QuitModal {
+    type Result = Vec<QuitChoice>;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let (modal_width, modal_height) = self.calculate_modal_size(area.width, area.height);
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let message_lines = self.message.lines().count().max(1) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(message_lines),               // Message
+                Constraint::Length(self.visible_items() as u16), // Items
+                Constraint::Length(1),                           // Spacing
+                Constraint::Length(1),                           // Buttons
+            ])
+            .split(inner);
+
+        Paragraph::new(self.message.clone())
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(theme.bg))
+            .render(chunks[0], buf);
+
+        // Items: "▶ ‹ Save ›  name — reason", choices first so that long
+        // paths cannot push them out of view
+        self.scroll_to_focus();
+        let list_area = chunks[1];
+        let choice_width = Self::choice_width();
+        let selected_style = Style::default()
+            .fg(theme.fg)
+            .bg(theme.accented_fg)
+            .add_modifier(Modifier::BOLD);
+        for (row, index) in (self.scroll..self.items.len())
+            .take(self.visible_items())
+            .enumerate()
+        {
+            let focused = self.focus == Focus::Item(index);
+            let style = if focused {
+                selected_style
+            } else {
+                Style::default().fg(theme.bg)
+            };
+            let choice_style = if focused {
+                style
+            } else {
+                Style::default().fg(theme.accented_fg)
+            };
+            let prefix = if focused { "▶ " } else { "  " };
+            let choice = format!("‹ {} ›", self.choices[index].label());
+            let choice_padding = " ".repeat(choice_width.saturating_sub(choice.width()) + 2);
+            let line = Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(choice, choice_style),
+                Span::styled(choice_padding, style),
+                Span::styled(Self::item_text(&self.items[index]), style),
+            ]);
+            let row_area = Rect::new(list_area.x, list_area.y + row as u16, list_area.width, 1);
+            Paragraph::new(line).render(row_area, buf);
+        }
+        self.last_list_area = Some(list_area);
+
+        let mut buttons = Vec::new();
+        for (index, button) in BUTTONS.iter().enumerate() {
+            if index > 0 {
+                buttons.push(Span::raw(" ".repeat(MODAL_BUTTON_SPACING as usize)));
+            }
+            let style = if self.focus == Focus::Button(index) {
+                selected_style
+            } else {
+                Style::default().fg(theme.accented_fg)
+            };
+            buttons.push(Span::styled(button.text(), style));
+        }
+        Paragraph::new(Line::from(buttons))
+            .alignment(Alignment::Center)
+            .render(chunks[3], buf);
+        self.last_buttons_area = Some(chunks[3]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        let result = match (key.code, self.focus) {
+            (KeyCode::Esc, _) => Some(ModalResult::Cancelled),
+            (KeyCode::Tab, _) => {
+                self.cycle_focus(true);
+                None
+            }
+            (KeyCode::BackTab, _) => {
+                self.cycle_focus(false);
+                None
+            }
+            (KeyCode::Up, Focus::Item(index)) => {
+                self.focus = Focus::Item(index.saturating_sub(1));
+                None
+            }
+            (KeyCode::Down, Focus::Item(index)) => {
+                self.focus = if index + 1 < self.items.len() {
+                    Focus::Item(index + 1)
+                } else {
+                    Focus::Button(BUTTONS.len() - 2)
+                };
+                None
+            }
+            (KeyCode::Left | KeyCode::Right | KeyCode::Char(' '), Focus::Item(index)) => {
+                self.toggle(index);
+                None
+            }
+            // Enter on the list quits with the current choices
+            (KeyCode::Enter, Focus::Item(_)) => Some(self.press(QuitButton::Quit)),
+            (KeyCode::Up, Focus::Button(_)) => {
+                if !self.items.is_empty() {
+                    self.focus = Focus::Item(self.items.len() - 1);
+                }
+                None
+            }
+            (KeyCode::Left, Focus::Button(index)) => {
+                self.focus = Focus::Button(index.saturating_sub(1));
+                None
+            }
+            (KeyCode::Right, Focus::Button(index)) => {
+                self.focus = Focus::Button((index + 1).min(BUTTONS.len() - 1));
+                None
+            }
+            (KeyCode::Enter | KeyCode::Char(' '), Focus::Button(index)) => {
+                Some(self.press(BUTTONS[index]))
+            }
+            _ => None,
+        };
+        self.scroll_to_focus();
+        Ok(result)
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: crossterm::event::MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        use crossterm::event::MouseEventKind;
+
+        // Only handle left button press
+        if mouse.kind != MouseEventKind::Down(crossterm::event::MouseButton::Left) {
+            return Ok(None);
+        }
+
+        let contains = |area: Rect| {
+            mouse.row >= area.y
+                && mouse.row < area.y + area.height
+                && mouse.column >= area.x
+                && mouse.column < area.x + area.width
+        };
+
+        // Click on an item focuses it and switches its choice
+        if let Some(list_area) = self.last_list_area.filter(|&area| contains(area)) {
+            let index = self.scroll + (mouse.row - list_area.y) as usize;
+            if index < self.items.len() {
+                self.focus = Focus::Item(index);
+                self.toggle(index);
+            }
+            return Ok(None);
+        }
+
+        if self.last_buttons_area.is_some_and(contains) {
+            if let Some(index) = self.button_at(mouse.column) {
+                self.focus = Focus::Button(index);
+                return Ok(Some(self.press(BUTTONS[index])));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn modal() -> QuitModal {
+        termide_i18n::init_with_language("en");
+        QuitModal::new(
+            "Quit",
+            "Unsaved changes:",
+            vec![
+                QuitItem::file("a.rs", "modified"),
+                QuitItem::terminal("Terminal", "running processes"),
+                QuitItem::file("b.rs", "modified"),
+            ],
+        )
+    }
+
+    fn confirmed(result: Option<ModalResult<Vec<QuitChoice>>>) -> Vec<QuitChoice> {
+        match result {
+            Some(ModalResult::Confirmed(choices)) => choices,
+            other => panic!("expected choices, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_per_item_choices() {
+        let mut modal = modal();
+        assert_eq!(
+            modal.choices(),
+            [QuitChoice::Save, QuitChoice::Kill, QuitChoice::Save]
+        );
+
+        // The first file is discarded, the terminal has only one choice
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        modal.handle_key(key(KeyCode::Down)).unwrap();
+        modal.handle_key(key(KeyCode::Right)).unwrap();
+        let choices = confirmed(modal.handle_key(key(KeyCode::Enter)).unwrap());
+        assert_eq!(
+            choices,
+            [QuitChoice::Discard, QuitChoice::Kill, QuitChoice::Save]
+        );
+    }
+
+    #[test]
+    fn test_save_all_and_discard_all() {
+        let mut modal = modal();
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        // Past the last item focus lands on Quit; Left twice is Save all
+        for code in [KeyCode::Down, KeyCode::Down, KeyCode::Down] {
+            modal.handle_key(key(code)).unwrap();
+        }
+        assert_eq!(modal.focus, Focus::Button(2));
+        modal.handle_key(key(KeyCode::Left)).unwrap();
+        modal.handle_key(key(KeyCode::Left)).unwrap();
+        let choices = confirmed(modal.handle_key(key(KeyCode::Enter)).unwrap());
+        assert_eq!(
+            choices,
+            [QuitChoice::Save, QuitChoice::Kill, QuitChoice::Save]
+        );
+
+        let mut modal = self::modal();
+        modal.focus = Focus::Button(1);
+        let choices = confirmed(modal.handle_key(key(KeyCode::Enter)).unwrap());
+        assert_eq!(
+            choices,
+            [QuitChoice::Discard, QuitChoice::Kill, QuitChoice::Discard]
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut modal = modal();
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Esc)).unwrap(),
+            Some(ModalResult::Cancelled)
+        ));
+
+        let mut modal = self::modal();
+        modal.handle_key(key(KeyCode::BackTab)).unwrap();
+        assert_eq!(modal.focus, Focus::Button(3));
+        assert!(matches!(
+            modal.handle_key(key(KeyCode::Enter)).unwrap(),
+            Some(ModalResult::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_list_scrolls_to_focus() {
+        termide_i18n::init_with_language("en");
+        let items = (0..15)
+            .map(|i| QuitItem::file(format!("{}.rs", i), "modified"))
+            .collect();
+        let mut modal = QuitModal::new("Quit", "", items);
+        for _ in 0..12 {
+            modal.handle_key(key(KeyCode::Down)).unwrap();
+        }
+        assert_eq!(modal.focus, Focus::Item(12));
+        assert_eq!(modal.scroll, 3);
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        assert_eq!(modal.focus, Focus::Button(0));
+    }
+}
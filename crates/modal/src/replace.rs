@@ -10,7 +10,7 @@ use ratatui::{
 
 use termide_theme::Theme;
 
-use crate::{base, Modal, ModalResult, TextInputHandler};
+use crate::{base, search::SearchOptions, Modal, ModalResult, TextInputHandler};
 
 /// Replace modal result
 #[derive(Debug, Clone)]
@@ -18,6 +18,7 @@ pub struct ReplaceModalResult {
     pub find_query: String,
     pub replace_with: String,
     pub action: ReplaceAction,
+    pub options: SearchOptions,
 }
 
 /// Replace action
@@ -55,6 +56,9 @@ pub struct ReplaceModal {
     /// Last rendered areas for mouse handling
     last_button_areas: Vec<(Rect, usize)>, // (area, button_idx)
     last_close_button_area: Option<Rect>,
+    /// Whole-word and in-selection toggles
+    options: SearchOptions,
+    last_option_areas: [Rect; 2],
 }
 
 impl ReplaceModal {
@@ -68,9 +72,34 @@ impl ReplaceModal {
             match_info: None,
             last_button_areas: Vec::new(),
             last_close_button_area: None,
+            options: SearchOptions::default(),
+            last_option_areas: [Rect::default(); 2],
         }
     }
 
+    /// Set the option toggles (e.g., from the previous replace)
+    pub fn set_options(&mut self, whole_word: bool, in_selection: bool) {
+        self.options = SearchOptions {
+            whole_word,
+            in_selection,
+        };
+    }
+
+    /// Result with the current texts and options
+    fn confirm(&self, action: ReplaceAction) -> ModalResult<ReplaceModalResult> {
+        ModalResult::Confirmed(ReplaceModalResult {
+            find_query: self.find_input_handler.text().to_string(),
+            replace_with: self.replace_input_handler.text().to_string(),
+            action,
+            options: self.options,
+        })
+    }
+
+    /// Search again after an option was toggled (if there is a find text)
+    fn options_toggled(&self) -> Option<ModalResult<ReplaceModalResult>> {
+        (!self.find_input_handler.is_empty()).then(|| self.confirm(ReplaceAction::Search))
+    }
+
     /// Update match information (current index, total count)
     pub fn set_match_info(&mut self, current: usize, total: usize) {
         self.match_info = Some((current, total));
@@ -132,10 +161,12 @@ impl Modal for ReplaceModal {
             ])
             .split(inner);
 
-        // === Render find input line ===
+        // === Render find input line with the option toggles ===
+        let (find_area, option_areas) = self.options.render(chunks[0], buf, theme);
+        self.last_option_areas = option_areas;
         base::render_labeled_input(
             buf,
-            chunks[0],
+            find_area,
             "Find:    ",
             self.find_input_handler.text(),
             matches!(self.focus, FocusArea::FindInput),
@@ -220,6 +251,11 @@ impl Modal for ReplaceModal {
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        // Alt+W / Alt+S - toggle options without retyping the query
+        if self.options.toggle_key(&key) {
+            return Ok(self.options_toggled());
+        }
+
         match self.focus {
             FocusArea::FindInput => self.handle_find_input_key(key),
             FocusArea::ReplaceInput => self.handle_replace_input_key(key),
@@ -254,6 +290,14 @@ impl Modal for ReplaceModal {
             }
         }
 
+        // Check if clicked on an option toggle
+        if self
+            .options
+            .toggle_click(&self.last_option_areas, click_pos.0, click_pos.1)
+        {
+            return Ok(self.options_toggled());
+        }
+
         // Check if clicked on any button
         for (area, idx) in &self.last_button_areas {
             if click_pos.0 >= area.x && click_pos.0 < area.x + area.width && click_pos.1 == area.y {
@@ -265,11 +309,7 @@ impl Modal for ReplaceModal {
                         2 => ReplaceAction::Previous,
                         _ => ReplaceAction::Next,
                     };
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action,
-                    })));
+                    return Ok(Some(self.confirm(action)));
                 }
             }
         }
@@ -288,109 +328,58 @@ impl ReplaceModal {
             (KeyCode::Tab, KeyModifiers::NONE) => {
                 if !self.find_input_handler.is_empty() {
                     // If there's text, navigate to next match
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Next,
-                    })));
+                    return Ok(Some(self.confirm(ReplaceAction::Next)));
                 } else {
                     // Otherwise move focus to replace field
                     self.focus = FocusArea::ReplaceInput;
                 }
             }
             // Shift+Tab - trigger previous
-            (KeyCode::BackTab, _) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Previous,
-                    })));
-                }
+            (KeyCode::BackTab, _) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Previous)));
             }
             // Enter - replace current and move to next
-            (KeyCode::Enter, KeyModifiers::NONE) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Replace,
-                    })));
-                }
+            (KeyCode::Enter, KeyModifiers::NONE) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Replace)));
             }
             // Esc - cancel
             (KeyCode::Esc, KeyModifiers::NONE) => {
                 return Ok(Some(ModalResult::Cancelled));
             }
             // F3 - next match
-            (KeyCode::F(3), KeyModifiers::NONE) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Next,
-                    })));
-                }
+            (KeyCode::F(3), KeyModifiers::NONE) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Next)));
             }
             // Shift+F3 - previous match
-            (KeyCode::F(3), KeyModifiers::SHIFT) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Previous,
-                    })));
-                }
+            (KeyCode::F(3), KeyModifiers::SHIFT) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Previous)));
             }
             // Ctrl+R - replace current
-            (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Replace,
-                    })));
-                }
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Replace)));
             }
             // Ctrl+Alt+R - replace all
             (KeyCode::Char('r'), modifiers)
                 if modifiers.contains(KeyModifiers::CONTROL)
-                    && modifiers.contains(KeyModifiers::ALT) =>
+                    && modifiers.contains(KeyModifiers::ALT)
+                    && !self.find_input_handler.is_empty() =>
             {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::ReplaceAll,
-                    })));
-                }
+                return Ok(Some(self.confirm(ReplaceAction::ReplaceAll)));
             }
             // Backspace - delete character
-            (KeyCode::Backspace, KeyModifiers::NONE) => {
-                if self.find_input_handler.backspace() {
-                    // Trigger live search
-                    if !self.find_input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                            find_query: self.find_input_handler.text().to_string(),
-                            replace_with: self.replace_input_handler.text().to_string(),
-                            action: ReplaceAction::Search,
-                        })));
-                    }
-                }
+            (KeyCode::Backspace, KeyModifiers::NONE)
+                if self.find_input_handler.backspace() && !self.find_input_handler.is_empty() =>
+            {
+                return Ok(Some(self.confirm(ReplaceAction::Search)));
             }
+            (KeyCode::Backspace, KeyModifiers::NONE) => {}
             // Delete - delete character at cursor
-            (KeyCode::Delete, KeyModifiers::NONE) => {
-                if self.find_input_handler.delete() {
-                    // Trigger live search
-                    if !self.find_input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                            find_query: self.find_input_handler.text().to_string(),
-                            replace_with: self.replace_input_handler.text().to_string(),
-                            action: ReplaceAction::Search,
-                        })));
-                    }
-                }
+            (KeyCode::Delete, KeyModifiers::NONE)
+                if self.find_input_handler.delete() && !self.find_input_handler.is_empty() =>
+            {
+                return Ok(Some(self.confirm(ReplaceAction::Search)));
             }
+            (KeyCode::Delete, KeyModifiers::NONE) => {}
             // Left - move cursor left
             (KeyCode::Left, KeyModifiers::NONE) => {
                 self.find_input_handler.move_left();
@@ -416,11 +405,7 @@ impl ReplaceModal {
                 self.find_input_handler.insert_char(ch);
 
                 // Trigger live search
-                return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                    find_query: self.find_input_handler.text().to_string(),
-                    replace_with: self.replace_input_handler.text().to_string(),
-                    action: ReplaceAction::Search,
-                })));
+                return Ok(Some(self.confirm(ReplaceAction::Search)));
             }
             _ => {}
         }
@@ -442,61 +427,32 @@ impl ReplaceModal {
                 self.focus = FocusArea::FindInput;
             }
             // Enter - replace current
-            (KeyCode::Enter, KeyModifiers::NONE) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Replace,
-                    })));
-                }
+            (KeyCode::Enter, KeyModifiers::NONE) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Replace)));
             }
             // Esc - cancel
             (KeyCode::Esc, KeyModifiers::NONE) => {
                 return Ok(Some(ModalResult::Cancelled));
             }
             // F3 - next match
-            (KeyCode::F(3), KeyModifiers::NONE) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Next,
-                    })));
-                }
+            (KeyCode::F(3), KeyModifiers::NONE) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Next)));
             }
             // Shift+F3 - previous match
-            (KeyCode::F(3), KeyModifiers::SHIFT) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Previous,
-                    })));
-                }
+            (KeyCode::F(3), KeyModifiers::SHIFT) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Previous)));
             }
             // Ctrl+R - replace current
-            (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::Replace,
-                    })));
-                }
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) if !self.find_input_handler.is_empty() => {
+                return Ok(Some(self.confirm(ReplaceAction::Replace)));
             }
             // Ctrl+Alt+R - replace all
             (KeyCode::Char('r'), modifiers)
                 if modifiers.contains(KeyModifiers::CONTROL)
-                    && modifiers.contains(KeyModifiers::ALT) =>
+                    && modifiers.contains(KeyModifiers::ALT)
+                    && !self.find_input_handler.is_empty() =>
             {
-                if !self.find_input_handler.is_empty() {
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action: ReplaceAction::ReplaceAll,
-                    })));
-                }
+                return Ok(Some(self.confirm(ReplaceAction::ReplaceAll)));
             }
             // Backspace - delete character
             (KeyCode::Backspace, KeyModifiers::NONE) => {
@@ -554,20 +510,14 @@ impl ReplaceModal {
             KeyCode::Up => {
                 self.focus = FocusArea::ReplaceInput;
             }
-            KeyCode::Enter => {
-                if !self.find_input_handler.is_empty() {
-                    let action = match self.selected_button {
-                        0 => ReplaceAction::Replace,
-                        1 => ReplaceAction::ReplaceAll,
-                        2 => ReplaceAction::Previous,
-                        _ => ReplaceAction::Next,
-                    };
-                    return Ok(Some(ModalResult::Confirmed(ReplaceModalResult {
-                        find_query: self.find_input_handler.text().to_string(),
-                        replace_with: self.replace_input_handler.text().to_string(),
-                        action,
-                    })));
-                }
+            KeyCode::Enter if !self.find_input_handler.is_empty() => {
+                let action = match self.selected_button {
+                    0 => ReplaceAction::Replace,
+                    1 => ReplaceAction::ReplaceAll,
+                    2 => ReplaceAction::Previous,
+                    _ => ReplaceAction::Next,
+                };
+                return Ok(Some(self.confirm(action)));
             }
             KeyCode::Esc => {
                 return Ok(Some(ModalResult::Cancelled));
@@ -17,6 +17,89 @@ use crate::{base, Modal, ModalResult, TextInputHandler};
 pub struct SearchModalResult {
     pub query: String,
     pub action: SearchAction,
+    pub options: SearchOptions,
+}
+
+/// Label of the whole-word toggle
+const WHOLE_WORD_LABEL: &str = "[Word]";
+
+/// Label of the in-selection toggle
+const IN_SELECTION_LABEL: &str = "[Sel]";
+
+/// Search options toggled in the search and replace modals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Match whole words only (Alt+W)
+    pub whole_word: bool,
+    /// Search in the selection the modal was opened with (Alt+S)
+    pub in_selection: bool,
+}
+
+impl SearchOptions {
+    /// Toggle the option of an Alt+W / Alt+S key, returns false for other keys
+    pub(crate) fn toggle_key(&mut self, key: &KeyEvent) -> bool {
+        if key.modifiers != KeyModifiers::ALT {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('w') => self.whole_word = !self.whole_word,
+            KeyCode::Char('s') => self.in_selection = !self.in_selection,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Toggle the option whose indicator is at a click position, returns
+    /// false if the click missed them
+    pub(crate) fn toggle_click(&mut self, areas: &[Rect; 2], x: u16, y: u16) -> bool {
+        let hit = |area: &Rect| x >= area.x && x < area.x + area.width && y == area.y;
+        if hit(&areas[0]) {
+            self.whole_word = !self.whole_word;
+        } else if hit(&areas[1]) {
+            self.in_selection = !self.in_selection;
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Render the indicators at the end of `area`, returns the rest of the
+    /// area and the areas of the indicators
+    pub(crate) fn render(&self, area: Rect, buf: &mut Buffer, theme: &Theme) -> (Rect, [Rect; 2]) {
+        let toggle_style = |on: bool| {
+            if on {
+                Style::default()
+                    .fg(theme.fg)
+                    .bg(theme.accented_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.disabled)
+            }
+        };
+        let word_width = WHOLE_WORD_LABEL.len() as u16;
+        let selection_width = IN_SELECTION_LABEL.len() as u16;
+        let width = word_width + 1 + selection_width;
+        if area.width <= width + 1 {
+            return (area, [Rect::default(); 2]);
+        }
+
+        let x = area.x + area.width - width;
+        let word_area = Rect::new(x, area.y, word_width, 1);
+        let selection_area = Rect::new(x + word_width + 1, area.y, selection_width, 1);
+        buf.set_string(x, area.y, WHOLE_WORD_LABEL, toggle_style(self.whole_word));
+        buf.set_string(
+            selection_area.x,
+            area.y,
+            IN_SELECTION_LABEL,
+            toggle_style(self.in_selection),
+        );
+
+        let rest = Rect {
+            width: area.width - width - 1,
+            ..area
+        };
+        (rest, [word_area, selection_area])
+    }
 }
 
 /// Search action
@@ -49,6 +132,15 @@ pub struct SearchModal {
     /// Last rendered areas for mouse handling
     last_button_areas: Vec<(Rect, usize)>, // (area, button_idx)
     last_close_button_area: Option<Rect>,
+    /// Whole-word and in-selection toggles
+    options: SearchOptions,
+    last_option_areas: [Rect; 2],
+    /// Previous queries, most recent first (Up/Down)
+    history: Vec<String>,
+    /// Query of `history` shown, `None` while editing
+    history_index: Option<usize>,
+    /// Query typed before browsing the history
+    draft: String,
 }
 
 impl SearchModal {
@@ -61,7 +153,71 @@ impl SearchModal {
             match_info: None,
             last_button_areas: Vec::new(),
             last_close_button_area: None,
+            options: SearchOptions::default(),
+            last_option_areas: [Rect::default(); 2],
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Set the previous queries recalled with Up/Down, most recent first
+    pub fn set_history(&mut self, history: Vec<String>) {
+        self.history = history;
+        self.history_index = None;
+    }
+
+    /// Show the previous (`older`) or next query of the history; past the
+    /// most recent one the typed query comes back. Returns false if there
+    /// is nothing to move to.
+    fn browse_history(&mut self, older: bool) -> bool {
+        let index = match (self.history_index, older) {
+            (None, true) if !self.history.is_empty() => {
+                self.draft = self.input_handler.text().to_string();
+                Some(0)
+            }
+            (Some(index), true) if index + 1 < self.history.len() => Some(index + 1),
+            (Some(0), false) => None,
+            (Some(index), false) => Some(index - 1),
+            _ => return false,
+        };
+        self.history_index = index;
+        let text = match index {
+            Some(index) => self.history[index].clone(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.input_handler = TextInputHandler::with_default(text);
+        true
+    }
+
+    /// Search for a recalled query as it is shown
+    fn history_browsed(&mut self, older: bool) -> Option<ModalResult<SearchModalResult>> {
+        if !self.browse_history(older) {
+            return None;
         }
+        Some(self.confirm(SearchAction::Search))
+    }
+
+    /// Set the option toggles (e.g., from the previous search)
+    pub fn set_options(&mut self, whole_word: bool, in_selection: bool) {
+        self.options = SearchOptions {
+            whole_word,
+            in_selection,
+        };
+    }
+
+    /// Result with the current query and options
+    fn confirm(&self, action: SearchAction) -> ModalResult<SearchModalResult> {
+        ModalResult::Confirmed(SearchModalResult {
+            query: self.input_handler.text().to_string(),
+            action,
+            options: self.options,
+        })
+    }
+
+    /// Search again after an option was toggled (if there is a query)
+    fn options_toggled(&self) -> Option<ModalResult<SearchModalResult>> {
+        (!self.input_handler.is_empty()).then(|| self.confirm(SearchAction::Search))
     }
 
     /// Update match information (current index, total count)
@@ -110,8 +266,9 @@ impl Modal for SearchModal {
             ])
             .split(inner);
 
-        // === Render input line ===
-        let input_area = chunks[0];
+        // === Render input line with the option toggles ===
+        let (input_area, option_areas) = self.options.render(chunks[0], buf, theme);
+        self.last_option_areas = option_areas;
         base::render_input_field(
             buf,
             input_area.x,
@@ -190,82 +347,62 @@ impl Modal for SearchModal {
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        // Alt+W / Alt+S - toggle options without retyping the query
+        if self.options.toggle_key(&key) {
+            return Ok(self.options_toggled());
+        }
+
         match self.focus {
             FocusArea::Input => match (key.code, key.modifiers) {
                 // Tab - move to buttons / trigger next
-                (KeyCode::Tab, KeyModifiers::NONE) => {
-                    // Trigger next search
-                    if !self.input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                            query: self.input_handler.text().to_string(),
-                            action: SearchAction::Next,
-                        })));
-                    }
+                (KeyCode::Tab, KeyModifiers::NONE) if !self.input_handler.is_empty() => {
+                    return Ok(Some(self.confirm(SearchAction::Next)));
                 }
                 // Shift+Tab - trigger previous
-                (KeyCode::BackTab, _) => {
-                    if !self.input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                            query: self.input_handler.text().to_string(),
-                            action: SearchAction::Previous,
-                        })));
-                    }
+                (KeyCode::BackTab, _) if !self.input_handler.is_empty() => {
+                    return Ok(Some(self.confirm(SearchAction::Previous)));
+                }
+                // Up/Down - previous queries
+                (KeyCode::Up, KeyModifiers::NONE) => {
+                    return Ok(self.history_browsed(true));
+                }
+                (KeyCode::Down, KeyModifiers::NONE) => {
+                    return Ok(self.history_browsed(false));
                 }
                 // Enter - close modal with selection
-                (KeyCode::Enter, KeyModifiers::NONE) => {
-                    if !self.input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                            query: self.input_handler.text().to_string(),
-                            action: SearchAction::CloseWithSelection,
-                        })));
-                    }
+                (KeyCode::Enter, KeyModifiers::NONE) if !self.input_handler.is_empty() => {
+                    return Ok(Some(self.confirm(SearchAction::CloseWithSelection)));
                 }
                 // Esc - cancel
                 (KeyCode::Esc, KeyModifiers::NONE) => {
                     return Ok(Some(ModalResult::Cancelled));
                 }
                 // F3 - next match
-                (KeyCode::F(3), KeyModifiers::NONE) => {
-                    if !self.input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                            query: self.input_handler.text().to_string(),
-                            action: SearchAction::Next,
-                        })));
-                    }
+                (KeyCode::F(3), KeyModifiers::NONE) if !self.input_handler.is_empty() => {
+                    return Ok(Some(self.confirm(SearchAction::Next)));
                 }
                 // Shift+F3 - previous match
-                (KeyCode::F(3), KeyModifiers::SHIFT) => {
-                    if !self.input_handler.is_empty() {
-                        return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                            query: self.input_handler.text().to_string(),
-                            action: SearchAction::Previous,
-                        })));
-                    }
+                (KeyCode::F(3), KeyModifiers::SHIFT) if !self.input_handler.is_empty() => {
+                    return Ok(Some(self.confirm(SearchAction::Previous)));
                 }
                 // Backspace - delete character
-                (KeyCode::Backspace, KeyModifiers::NONE) => {
-                    if self.input_handler.backspace() {
-                        // Trigger live search
-                        if !self.input_handler.is_empty() {
-                            return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                                query: self.input_handler.text().to_string(),
-                                action: SearchAction::Search,
-                            })));
-                        }
+                (KeyCode::Backspace, KeyModifiers::NONE) if self.input_handler.backspace() => {
+                    self.history_index = None;
+                    // Trigger live search
+                    if !self.input_handler.is_empty() {
+                        return Ok(Some(self.confirm(SearchAction::Search)));
                     }
                 }
+                (KeyCode::Backspace, KeyModifiers::NONE) => {}
                 // Delete - delete character at cursor
-                (KeyCode::Delete, KeyModifiers::NONE) => {
-                    if self.input_handler.delete() {
-                        // Trigger live search
-                        if !self.input_handler.is_empty() {
-                            return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                                query: self.input_handler.text().to_string(),
-                                action: SearchAction::Search,
-                            })));
-                        }
+                (KeyCode::Delete, KeyModifiers::NONE) if self.input_handler.delete() => {
+                    self.history_index = None;
+                    // Trigger live search
+                    if !self.input_handler.is_empty() {
+                        return Ok(Some(self.confirm(SearchAction::Search)));
                     }
                 }
+                (KeyCode::Delete, KeyModifiers::NONE) => {}
                 // Left - move cursor left
                 (KeyCode::Left, KeyModifiers::NONE) => {
                     self.input_handler.move_left();
@@ -285,12 +422,10 @@ impl Modal for SearchModal {
                 // Character input - insert character and trigger live search
                 (KeyCode::Char(ch), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
                     self.input_handler.insert_char(ch);
+                    self.history_index = None;
 
                     // Trigger live search
-                    return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                        query: self.input_handler.text().to_string(),
-                        action: SearchAction::Search,
-                    })));
+                    return Ok(Some(self.confirm(SearchAction::Search)));
                 }
                 _ => {}
             },
@@ -326,6 +461,14 @@ impl Modal for SearchModal {
             }
         }
 
+        // Check if clicked on an option toggle
+        if self
+            .options
+            .toggle_click(&self.last_option_areas, click_pos.0, click_pos.1)
+        {
+            return Ok(self.options_toggled());
+        }
+
         // Check if clicked on any button
         for (area, idx) in &self.last_button_areas {
             if click_pos.0 >= area.x && click_pos.0 < area.x + area.width && click_pos.1 == area.y {
@@ -335,10 +478,7 @@ impl Modal for SearchModal {
                         0 => SearchAction::Previous,
                         _ => SearchAction::Next,
                     };
-                    return Ok(Some(ModalResult::Confirmed(SearchModalResult {
-                        query: self.input_handler.text().to_string(),
-                        action,
-                    })));
+                    return Ok(Some(self.confirm(action)));
                 }
             }
         }
@@ -346,3 +486,44 @@ impl Modal for SearchModal {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// Query searched for after a key, `None` if nothing is searched
+    fn searched(modal: &mut SearchModal, code: KeyCode) -> Option<String> {
+        match modal.handle_key(key(code)).unwrap() {
+            Some(ModalResult::Confirmed(result)) => Some(result.query),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_history_recall() {
+        let mut modal = SearchModal::new("");
+        modal.set_history(vec!["new".to_string(), "old".to_string()]);
+        searched(&mut modal, KeyCode::Char('x'));
+
+        assert_eq!(searched(&mut modal, KeyCode::Up).as_deref(), Some("new"));
+        assert_eq!(searched(&mut modal, KeyCode::Up).as_deref(), Some("old"));
+        assert_eq!(searched(&mut modal, KeyCode::Up), None);
+        assert_eq!(searched(&mut modal, KeyCode::Down).as_deref(), Some("new"));
+        // Past the most recent query the typed one comes back
+        assert_eq!(searched(&mut modal, KeyCode::Down).as_deref(), Some("x"));
+        assert_eq!(searched(&mut modal, KeyCode::Down), None);
+
+        // Editing a recalled query starts browsing from the most recent again
+        searched(&mut modal, KeyCode::Up);
+        assert_eq!(
+            searched(&mut modal, KeyCode::Char('s')).as_deref(),
+            Some("news")
+        );
+        assert_eq!(searched(&mut modal, KeyCode::Up).as_deref(), Some("new"));
+        assert_eq!(searched(&mut modal, KeyCode::Down).as_deref(), Some("news"));
+    }
+}
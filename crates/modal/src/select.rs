@@ -1,4 +1,6 @@
-//! Selection modal dialog (single selection).
+//! Selection modal dialog (single or multiple selection).
+
+use std::collections::BTreeSet;
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
@@ -17,13 +19,21 @@ use crate::{
     ModalResult, ModalWidthConfig,
 };
 
-/// Selection modal window (single selection only)
+/// Maximum number of visible list items
+const MAX_VISIBLE_ITEMS: usize = 10;
+
+/// Selection modal window
 #[derive(Debug)]
 pub struct SelectModal {
     title: String,
     prompt: String,
     items: Vec<String>,
     cursor: usize,
+    /// First visible item (lists longer than MAX_VISIBLE_ITEMS scroll)
+    scroll_offset: usize,
+    /// Multiple selection mode: Space marks items, Enter confirms marked ones
+    multiple: bool,
+    checked: BTreeSet<usize>,
     last_list_area: Option<Rect>,
 }
 
@@ -39,15 +49,64 @@ impl SelectModal {
             prompt: prompt.into(),
             items: labels,
             cursor: 0,
+            scroll_offset: 0,
+            multiple: false,
+            checked: BTreeSet::new(),
             last_list_area: None,
         }
     }
 
+    /// Create a multiple selection window from strings
+    ///
+    /// Space toggles the item under cursor; Enter confirms marked items
+    /// (or the item under cursor if nothing is marked).
+    pub fn multiple(
+        title: impl Into<String>,
+        prompt: impl Into<String>,
+        labels: Vec<String>,
+    ) -> Self {
+        Self {
+            multiple: true,
+            ..Self::single(title, prompt, labels)
+        }
+    }
+
+    /// Place the cursor on an item (e.g. the current value)
+    pub fn with_cursor(mut self, index: usize) -> Self {
+        self.cursor = index.min(self.items.len().saturating_sub(1));
+        self.adjust_scroll();
+        self
+    }
+
+    /// Index of the item under the cursor
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Selected indices to return on confirmation
+    fn selection(&self) -> Vec<usize> {
+        if self.checked.is_empty() {
+            vec![self.cursor]
+        } else {
+            self.checked.iter().copied().collect()
+        }
+    }
+
+    /// Keep cursor inside visible window
+    fn adjust_scroll(&mut self) {
+        if self.cursor < self.scroll_offset {
+            self.scroll_offset = self.cursor;
+        } else if self.cursor >= self.scroll_offset + MAX_VISIBLE_ITEMS {
+            self.scroll_offset = self.cursor + 1 - MAX_VISIBLE_ITEMS;
+        }
+    }
+
     /// Calculate dynamic modal width
     fn calculate_modal_width(&self, screen_width: u16) -> u16 {
         let title_width = self.title.len() as u16 + 2;
         let prompt_width = max_line_width(&self.prompt);
-        let items_width = max_item_width(&self.items, 2); // "▶ " prefix
+        let prefix_width = if self.multiple { 6 } else { 2 }; // "▶ [x] " / "▶ "
+        let items_width = max_item_width(&self.items, prefix_width);
 
         calculate_modal_width(
             [title_width, prompt_width, items_width].into_iter(),
@@ -69,7 +128,7 @@ impl Modal for SelectModal {
 
         // Calculate height:
         // 1 (top border) + N (prompt) + M (list) + 1 (bottom border)
-        let list_height = self.items.len().min(10) as u16; // Limit to 10 items
+        let list_height = self.items.len().min(MAX_VISIBLE_ITEMS) as u16;
         let modal_height = 1 + prompt_lines + list_height + 1;
 
         // Create centered area
@@ -106,8 +165,20 @@ impl Modal for SelectModal {
             .items
             .iter()
             .enumerate()
+            .skip(self.scroll_offset)
+            .take(MAX_VISIBLE_ITEMS)
             .map(|(idx, label)| {
-                let prefix = if idx == self.cursor { "▶ " } else { "  " };
+                let cursor_mark = if idx == self.cursor { "▶ " } else { "  " };
+                let prefix = if self.multiple {
+                    let check = if self.checked.contains(&idx) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    };
+                    format!("{}{}", cursor_mark, check)
+                } else {
+                    cursor_mark.to_string()
+                };
 
                 let style = if idx == self.cursor {
                     Style::default()
@@ -140,23 +211,38 @@ impl Modal for SelectModal {
                 if self.cursor > 0 {
                     self.cursor -= 1;
                 }
+                self.adjust_scroll();
                 Ok(None)
             }
             KeyCode::Down => {
                 if self.cursor < self.items.len().saturating_sub(1) {
                     self.cursor += 1;
                 }
+                self.adjust_scroll();
                 Ok(None)
             }
             KeyCode::Home => {
                 self.cursor = 0;
+                self.adjust_scroll();
                 Ok(None)
             }
             KeyCode::End => {
                 self.cursor = self.items.len().saturating_sub(1);
+                self.adjust_scroll();
                 Ok(None)
             }
-            KeyCode::Enter => Ok(Some(ModalResult::Confirmed(vec![self.cursor]))),
+            KeyCode::Char(' ') if self.multiple => {
+                // Toggle mark and move to next item
+                if !self.checked.remove(&self.cursor) {
+                    self.checked.insert(self.cursor);
+                }
+                if self.cursor < self.items.len().saturating_sub(1) {
+                    self.cursor += 1;
+                }
+                self.adjust_scroll();
+                Ok(None)
+            }
+            KeyCode::Enter => Ok(Some(ModalResult::Confirmed(self.selection()))),
             _ => Ok(None),
         }
     }
@@ -188,14 +274,68 @@ impl Modal for SelectModal {
         }
 
         // Calculate which item was clicked
-        let clicked_item = (mouse.row - list_area.y) as usize;
+        let clicked_item = self.scroll_offset + (mouse.row - list_area.y) as usize;
 
-        if clicked_item < self.items.len() {
+        if clicked_item >= self.items.len() {
+            Ok(None)
+        } else if self.multiple {
+            // Click toggles mark in multiple selection mode
+            self.cursor = clicked_item;
+            if !self.checked.remove(&clicked_item) {
+                self.checked.insert(clicked_item);
+            }
+            Ok(None)
+        } else {
             // Item clicked - select and confirm immediately
             self.cursor = clicked_item;
             Ok(Some(ModalResult::Confirmed(vec![self.cursor])))
-        } else {
-            Ok(None)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn labels(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("item {}", i)).collect()
+    }
+
+    #[test]
+    fn test_single_confirms_cursor() {
+        let mut modal = SelectModal::single("T", "P", labels(3));
+        modal.handle_key(key(KeyCode::Down)).unwrap();
+        // Space does nothing in single mode
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        let result = modal.handle_key(key(KeyCode::Enter)).unwrap();
+        assert!(matches!(result, Some(ModalResult::Confirmed(ref v)) if v == &vec![1]));
+    }
+
+    #[test]
+    fn test_multiple_confirms_marked_items() {
+        let mut modal = SelectModal::multiple("T", "P", labels(4));
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap(); // mark 0, cursor -> 1
+        modal.handle_key(key(KeyCode::Down)).unwrap(); // cursor -> 2
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap(); // mark 2
+        let result = modal.handle_key(key(KeyCode::Enter)).unwrap();
+        assert!(matches!(result, Some(ModalResult::Confirmed(ref v)) if v == &vec![0, 2]));
+    }
+
+    #[test]
+    fn test_scroll_follows_cursor() {
+        let mut modal = SelectModal::single("T", "P", labels(25));
+        modal.handle_key(key(KeyCode::End)).unwrap();
+        assert_eq!(modal.scroll_offset, 25 - MAX_VISIBLE_ITEMS);
+        modal.handle_key(key(KeyCode::Home)).unwrap();
+        assert_eq!(modal.scroll_offset, 0);
+
+        let modal = SelectModal::single("T", "P", labels(25)).with_cursor(15);
+        assert_eq!(modal.scroll_offset, 15 + 1 - MAX_VISIBLE_ITEMS);
+        assert_eq!(modal.selection(), vec![15]);
+    }
+}
@@ -0,0 +1,446 @@
+//! Create symlink modal dialog.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use termide_config::constants::MODAL_BUTTON_SPACING;
+use termide_i18n as i18n;
+use termide_theme::Theme;
+
+use crate::{
+    calculate_modal_width, centered_rect_with_size, max_line_width, Modal, ModalResult,
+    ModalWidthConfig, TextInputHandler,
+};
+
+/// Symlink modal result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymlinkModalResult {
+    /// Path of the link to create (as entered by user)
+    pub link_path: String,
+    /// Store target path relative to the link's directory
+    pub relative: bool,
+}
+
+/// Focus area in the modal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FocusArea {
+    Input,
+    Toggle,
+    Buttons,
+}
+
+/// Link path input modal with relative/absolute toggle
+#[derive(Debug)]
+pub struct SymlinkModal {
+    title: String,
+    prompt: String,
+    input_handler: TextInputHandler,
+    relative: bool,
+    focus: FocusArea,
+    selected_button: usize, // 0 = OK, 1 = Cancel
+    last_toggle_area: Option<Rect>,
+    last_buttons_area: Option<Rect>,
+}
+
+impl SymlinkModal {
+    /// Create a new symlink modal with default link path (relative mode enabled)
+    pub fn new(
+        title: impl Into<String>,
+        prompt: impl Into<String>,
+        default: impl Into<String>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            prompt: prompt.into(),
+            input_handler: TextInputHandler::with_default(default),
+            relative: true,
+            focus: FocusArea::Input,
+            selected_button: 0, // OK button selected by default
+            last_toggle_area: None,
+            last_buttons_area: None,
+        }
+    }
+
+    /// Build result from current input (or cancel if empty)
+    fn confirm(&self) -> ModalResult<SymlinkModalResult> {
+        if self.input_handler.is_empty() {
+            ModalResult::Cancelled
+        } else {
+            ModalResult::Confirmed(SymlinkModalResult {
+                link_path: self.input_handler.text().to_string(),
+                relative: self.relative,
+            })
+        }
+    }
+
+    /// Calculate dynamic modal width and height
+    fn calculate_modal_size(&self, screen_width: u16, screen_height: u16) -> (u16, u16) {
+        let title_width = self.title.len() as u16 + 2;
+        let prompt_width = max_line_width(&self.prompt);
+        let buttons_width = 21u16; // "[ OK ]    [ Cancel ]"
+        let input_width = self.input_handler.text().chars().count() as u16 + 20;
+
+        let width = calculate_modal_width(
+            [title_width, prompt_width, buttons_width, input_width].into_iter(),
+            screen_width,
+            ModalWidthConfig {
+                wide: false,
+                double_border: true,
+            },
+        );
+
+        // Calculate height: border + prompt + input(3) + toggle + buttons + border
+        let prompt_lines = if self.prompt.is_empty() {
+            0
+        } else {
+            self.prompt.lines().count().max(1) as u16
+        };
+        let height = (1 + prompt_lines + 3 + 1 + 1 + 1).min(screen_height);
+
+        (width, height)
+    }
+
+    /// Handle text editing keys shared by input and buttons focus
+    fn edit_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.focus = FocusArea::Input;
+                self.input_handler.insert_char(c);
+            }
+            KeyCode::Backspace => {
+                self.focus = FocusArea::Input;
+                self.input_handler.backspace();
+            }
+            KeyCode::Delete => {
+                self.focus = FocusArea::Input;
+                self.input_handler.delete();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Modal for SymlinkModal {
+    type Result = SymlinkModalResult;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        // Calculate dynamic dimensions
+        let (modal_width, modal_height) = self.calculate_modal_size(area.width, area.height);
+
+        // Create centered area
+        let modal_area = centered_rect_with_size(modal_width, modal_height, area);
+
+        // Clear the area
+        Clear.render(modal_area, buf);
+
+        // Create block with inverted colors
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", self.title),
+                Style::default().fg(theme.bg).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bg))
+            .style(Style::default().bg(theme.fg));
+
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let prompt_lines = if self.prompt.is_empty() {
+            0
+        } else {
+            self.prompt.lines().count().max(1) as u16
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(prompt_lines), // Prompt
+                Constraint::Length(3),            // Input
+                Constraint::Length(1),            // Relative toggle
+                Constraint::Length(1),            // Buttons
+            ])
+            .split(inner);
+
+        // Render prompt
+        if prompt_lines > 0 {
+            let prompt = Paragraph::new(self.prompt.clone())
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(theme.bg));
+            prompt.render(chunks[0], buf);
+        }
+
+        // Render input field
+        let input_line = Line::from(vec![
+            Span::styled(
+                self.input_handler.text_before_cursor(),
+                Style::default().fg(theme.bg),
+            ),
+            Span::styled("█", Style::default().fg(theme.success)),
+            Span::styled(
+                self.input_handler.text_after_cursor(),
+                Style::default().fg(theme.bg),
+            ),
+        ]);
+
+        let input_paragraph = Paragraph::new(input_line)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.success)),
+            )
+            .style(Style::default().bg(theme.fg));
+        input_paragraph.render(chunks[1], buf);
+
+        let t = i18n::t();
+
+        // Render relative/absolute toggle
+        let toggle_style = if self.focus == FocusArea::Toggle {
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.bg)
+        };
+        let checkbox = if self.relative { "[x]" } else { "[ ]" };
+        let toggle = Line::from(Span::styled(
+            format!("{} {}", checkbox, t.modal_symlink_relative()),
+            toggle_style,
+        ));
+        Paragraph::new(toggle).render(chunks[2], buf);
+        self.last_toggle_area = Some(chunks[2]);
+
+        // Render buttons
+        let ok_style = if self.focus == FocusArea::Buttons && self.selected_button == 0 {
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.accented_fg)
+        };
+
+        let cancel_style = if self.focus == FocusArea::Buttons && self.selected_button == 1 {
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.accented_fg)
+        };
+
+        let buttons = Line::from(vec![
+            Span::styled(format!("[ {} ]", t.ui_ok()), ok_style),
+            Span::raw("    "),
+            Span::styled(format!("[ {} ]", t.ui_cancel()), cancel_style),
+        ]);
+
+        let buttons_paragraph = Paragraph::new(buttons).alignment(Alignment::Center);
+        buttons_paragraph.render(chunks[3], buf);
+
+        // Save buttons area for mouse handling
+        self.last_buttons_area = Some(chunks[3]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ModalResult<Self::Result>>> {
+        // Escape always cancels
+        if key.code == KeyCode::Esc {
+            return Ok(Some(ModalResult::Cancelled));
+        }
+
+        // Tab flips relative/absolute mode from anywhere
+        if key.code == KeyCode::Tab {
+            self.relative = !self.relative;
+            return Ok(None);
+        }
+
+        match self.focus {
+            FocusArea::Input => match key.code {
+                KeyCode::Down => {
+                    self.focus = FocusArea::Toggle;
+                    Ok(None)
+                }
+                KeyCode::Enter => Ok(Some(self.confirm())),
+                KeyCode::Left => {
+                    self.input_handler.move_left();
+                    Ok(None)
+                }
+                KeyCode::Right => {
+                    self.input_handler.move_right();
+                    Ok(None)
+                }
+                KeyCode::Home => {
+                    self.input_handler.move_home();
+                    Ok(None)
+                }
+                KeyCode::End => {
+                    self.input_handler.move_end();
+                    Ok(None)
+                }
+                _ => {
+                    self.edit_input(key);
+                    Ok(None)
+                }
+            },
+            FocusArea::Toggle => match key.code {
+                KeyCode::Up => {
+                    self.focus = FocusArea::Input;
+                    Ok(None)
+                }
+                KeyCode::Down => {
+                    self.focus = FocusArea::Buttons;
+                    Ok(None)
+                }
+                KeyCode::Char(' ') => {
+                    self.relative = !self.relative;
+                    Ok(None)
+                }
+                KeyCode::Enter => Ok(Some(self.confirm())),
+                _ => {
+                    self.edit_input(key);
+                    Ok(None)
+                }
+            },
+            FocusArea::Buttons => match key.code {
+                KeyCode::Left | KeyCode::Right => {
+                    self.selected_button = 1 - self.selected_button;
+                    Ok(None)
+                }
+                KeyCode::Up => {
+                    self.focus = FocusArea::Toggle;
+                    Ok(None)
+                }
+                KeyCode::Enter => {
+                    if self.selected_button == 0 {
+                        Ok(Some(self.confirm()))
+                    } else {
+                        Ok(Some(ModalResult::Cancelled))
+                    }
+                }
+                _ => {
+                    self.edit_input(key);
+                    Ok(None)
+                }
+            },
+        }
+    }
+
+    fn handle_mouse(
+        &mut self,
+        mouse: crossterm::event::MouseEvent,
+        _modal_area: Rect,
+    ) -> Result<Option<ModalResult<Self::Result>>> {
+        use crossterm::event::MouseEventKind;
+
+        // Only handle left button press
+        if mouse.kind != MouseEventKind::Down(crossterm::event::MouseButton::Left) {
+            return Ok(None);
+        }
+
+        let contains = |area: Rect| {
+            mouse.row >= area.y
+                && mouse.row < area.y + area.height
+                && mouse.column >= area.x
+                && mouse.column < area.x + area.width
+        };
+
+        // Click on toggle line flips mode
+        if let Some(toggle_area) = self.last_toggle_area {
+            if contains(toggle_area) {
+                self.focus = FocusArea::Toggle;
+                self.relative = !self.relative;
+                return Ok(None);
+            }
+        }
+
+        let Some(buttons_area) = self.last_buttons_area else {
+            return Ok(None);
+        };
+        if !contains(buttons_area) {
+            return Ok(None);
+        }
+
+        // Buttons are centered: "[ OK ]    [ Cancel ]"
+        let t = i18n::t();
+        let ok_text = format!("[ {} ]", t.ui_ok());
+        let cancel_text = format!("[ {} ]", t.ui_cancel());
+        let total_text_width = ok_text.len() + MODAL_BUTTON_SPACING as usize + cancel_text.len();
+
+        let start_col =
+            buttons_area.x + (buttons_area.width.saturating_sub(total_text_width as u16)) / 2;
+        let ok_end = start_col + ok_text.len() as u16;
+        let cancel_start = ok_end + MODAL_BUTTON_SPACING;
+        let cancel_end = cancel_start + cancel_text.len() as u16;
+
+        if mouse.column >= start_col && mouse.column < ok_end {
+            self.focus = FocusArea::Buttons;
+            self.selected_button = 0;
+            Ok(Some(self.confirm()))
+        } else if mouse.column >= cancel_start && mouse.column < cancel_end {
+            self.focus = FocusArea::Buttons;
+            self.selected_button = 1;
+            Ok(Some(ModalResult::Cancelled))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_confirm_returns_path_and_mode() {
+        let mut modal = SymlinkModal::new("Title", "Prompt", "/tmp/");
+        modal.handle_key(key(KeyCode::Char('l'))).unwrap();
+        let result = modal.handle_key(key(KeyCode::Enter)).unwrap();
+        assert!(matches!(
+            result,
+            Some(ModalResult::Confirmed(SymlinkModalResult { ref link_path, relative: true }))
+                if link_path == "/tmp/l"
+        ));
+    }
+
+    #[test]
+    fn test_tab_toggles_relative() {
+        let mut modal = SymlinkModal::new("Title", "Prompt", "link");
+        modal.handle_key(key(KeyCode::Tab)).unwrap();
+        let result = modal.handle_key(key(KeyCode::Enter)).unwrap();
+        assert!(matches!(
+            result,
+            Some(ModalResult::Confirmed(SymlinkModalResult {
+                relative: false,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_space_on_toggle_does_not_edit_input() {
+        let mut modal = SymlinkModal::new("Title", "Prompt", "link");
+        modal.handle_key(key(KeyCode::Down)).unwrap();
+        modal.handle_key(key(KeyCode::Char(' '))).unwrap();
+        assert!(!modal.relative);
+        assert_eq!(modal.input_handler.text(), "link");
+    }
+
+    #[test]
+    fn test_empty_input_cancels() {
+        let mut modal = SymlinkModal::new("Title", "Prompt", "");
+        let result = modal.handle_key(key(KeyCode::Enter)).unwrap();
+        assert!(matches!(result, Some(ModalResult::Cancelled)));
+    }
+}
@@ -5,7 +5,7 @@
 
 use anyhow::Result;
 
-use termide_buffer::{Cursor, TextBuffer};
+use termide_buffer::{indent_column, Cursor, IndentStyle, TextBuffer};
 
 /// Result of a clipboard operation.
 pub struct ClipboardResult {
@@ -86,22 +86,157 @@ pub fn cut_to_clipboard(selected_text: Option<String>) -> (ClipboardResult, bool
     }
 }
 
+/// Copy the text of a block selection to clipboard, tagged as a block.
+pub fn copy_block_to_clipboard(block_text: Option<String>) -> ClipboardResult {
+    let status_message = match block_text {
+        Some(text) => match termide_clipboard::copy_block(&text) {
+            Ok(()) => format!(
+                "Copied block to clipboard ({} lines)",
+                text.split('\n').count()
+            ),
+            Err(e) => format!("Clipboard error: {}", e),
+        },
+        None => "Nothing selected to copy".to_string(),
+    };
+    ClipboardResult { status_message }
+}
+
 /// Paste from clipboard into buffer.
 ///
-/// Returns new cursor position and cache invalidation info on success.
+/// Text copied as a block is pasted as a rectangle at the cursor column.
+/// Returns new cursor position and the first changed line on success.
 pub fn paste_from_clipboard(
     buffer: &mut TextBuffer,
     cursor: &Cursor,
-) -> Result<Option<(Cursor, usize, bool)>> {
+) -> Result<Option<(Cursor, usize)>> {
     // Read from system clipboard via arboard
-    if let Some(text) = termide_clipboard::paste() {
+    match termide_clipboard::paste() {
+        Some(text) if termide_clipboard::is_block(&text) => paste_block(buffer, cursor, &text),
+        text => paste_text(buffer, cursor, text),
+    }
+}
+
+/// Paste from clipboard with its lines re-indented so that the first one
+/// takes the indentation of the cursor line, keeping relative indentation.
+pub fn paste_reindented(
+    buffer: &mut TextBuffer,
+    cursor: &Cursor,
+    style: IndentStyle,
+    tab_width: usize,
+) -> Result<Option<(Cursor, usize)>> {
+    let Some(text) = termide_clipboard::paste() else {
+        return Ok(None);
+    };
+    let line = buffer.line(cursor.line).unwrap_or_default();
+    let target = indent_column(leading_whitespace(&line), tab_width);
+    paste_text(
+        buffer,
+        cursor,
+        Some(reindent(&text, target, style, tab_width)),
+    )
+}
+
+/// Insert the lines of a block at the cursor column of consecutive lines.
+pub(crate) fn paste_block(
+    buffer: &mut TextBuffer,
+    cursor: &Cursor,
+    text: &str,
+) -> Result<Option<(Cursor, usize)>> {
+    let rows: Vec<&str> = text.lines().collect();
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    let new_cursor = buffer.insert_block(cursor, &rows)?;
+    Ok(Some((new_cursor, cursor.line)))
+}
+
+/// Re-indent `text` to start at indent column `target`.
+///
+/// The first line loses its leading whitespace (it is inserted at the
+/// cursor); the others are shifted by the difference between `target` and
+/// the indentation of the first non-blank line, rebuilt in `style`. Blank
+/// lines become empty.
+pub(crate) fn reindent(text: &str, target: usize, style: IndentStyle, tab_width: usize) -> String {
+    let text = text.replace("\r\n", "\n");
+    let base = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map_or(0, |line| indent_column(leading_whitespace(line), tab_width));
+
+    text.split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let leading = leading_whitespace(line);
+            let content = &line[leading.len()..];
+            if index == 0 || content.is_empty() {
+                return content.to_string();
+            }
+            let column = (indent_column(leading, tab_width) + target).saturating_sub(base);
+            format!("{}{}", style.indent_for_column(column, tab_width), content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Leading spaces and tabs of a line
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Paste from the PRIMARY selection (middle click) into buffer.
+pub fn paste_from_primary(
+    buffer: &mut TextBuffer,
+    cursor: &Cursor,
+) -> Result<Option<(Cursor, usize)>> {
+    paste_text(buffer, cursor, termide_clipboard::paste_primary())
+}
+
+/// Insert pasted text at the cursor.
+pub(crate) fn paste_text(
+    buffer: &mut TextBuffer,
+    cursor: &Cursor,
+    text: Option<String>,
+) -> Result<Option<(Cursor, usize)>> {
+    if let Some(text) = text {
         if !text.is_empty() {
             let start_line = cursor.line;
             let new_cursor = buffer.insert(cursor, &text)?;
-
-            let is_multiline = text.contains('\n');
-            return Ok(Some((new_cursor, start_line, is_multiline)));
+            return Ok(Some((new_cursor, start_line)));
         }
     }
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reindent_keeps_relative_indentation() {
+        let text = "        if x {\n            y();\n\n        }\n";
+        assert_eq!(
+            reindent(text, 4, IndentStyle::Spaces(4), 4),
+            "if x {\n        y();\n\n    }\n"
+        );
+        assert_eq!(
+            reindent(text, 4, IndentStyle::Tabs, 4),
+            "if x {\n\t\ty();\n\n\t}\n"
+        );
+    }
+
+    #[test]
+    fn test_reindent_outdented_lines_stop_at_column_zero() {
+        let text = "    a\nb\r\n      c";
+        assert_eq!(reindent(text, 0, IndentStyle::Spaces(2), 4), "a\nb\n  c");
+    }
+
+    #[test]
+    fn test_paste_block_past_end_of_buffer() {
+        let mut buffer = TextBuffer::from_text("one\ntwo");
+        let pasted = paste_block(&mut buffer, &Cursor::at(1, 1), "ab\ncd\nef")
+            .unwrap()
+            .unwrap();
+        assert_eq!(buffer.text(), "one\ntabwo\n cd\n ef");
+        assert_eq!(pasted, (Cursor::at(3, 3), 1));
+    }
+}
@@ -0,0 +1,305 @@
+//! Word completion from the words of open buffers.
+//!
+//! Each buffer keeps an index of the words on its lines. The index is
+//! updated from the line edits of the buffer, so typing only rescans the
+//! edited lines. Candidates start with the typed prefix and are ranked by
+//! the distance to their nearest occurrence in the edited buffer, then by
+//! how often they occur.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+use termide_buffer::{is_word_char, Cursor, LineEdit, TextBuffer};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Shortest word added to the index (in characters)
+const MIN_WORD_CHARS: usize = 2;
+
+/// Lines above and below the cursor searched for the nearest occurrence
+/// of a candidate
+const PROXIMITY_LINES: usize = 1000;
+
+/// Most candidates offered
+const MAX_CANDIDATES: usize = 50;
+
+/// Words of a buffer with their number of occurrences.
+pub(crate) struct WordIndex {
+    /// Words of each line, None for lines edited since the last update
+    lines: Vec<Option<Vec<String>>>,
+    /// Number of lines waiting for a rescan
+    dirty: usize,
+    /// Occurrences of each word
+    counts: BTreeMap<String, usize>,
+    /// Buffer revision the index matches
+    revision: u64,
+}
+
+impl WordIndex {
+    /// Index all lines of a buffer
+    pub fn build(buffer: &TextBuffer) -> Self {
+        let mut index = Self {
+            lines: vec![None; buffer.line_count()],
+            dirty: buffer.line_count(),
+            counts: BTreeMap::new(),
+            revision: buffer.revision(),
+        };
+        index.rescan(buffer, 0);
+        index
+    }
+
+    /// Check if the index matches the current text of the buffer
+    pub fn is_current(&self, buffer: &TextBuffer) -> bool {
+        self.revision == buffer.revision()
+    }
+
+    /// Update the index after `edits` were made to `buffer`, rescanning
+    /// only the edited lines
+    pub fn apply_edits(&mut self, edits: &[LineEdit], buffer: &TextBuffer) {
+        let mut first = usize::MAX;
+        for edit in edits {
+            let end = edit.line + edit.removed + 1;
+            if end > self.lines.len() {
+                *self = Self::build(buffer);
+                return;
+            }
+            let removed: Vec<_> = self
+                .lines
+                .splice(edit.line..end, (0..=edit.inserted).map(|_| None))
+                .collect();
+            for words in removed {
+                match words {
+                    Some(words) => self.remove_words(&words),
+                    None => self.dirty -= 1,
+                }
+            }
+            self.dirty += edit.inserted + 1;
+            first = first.min(edit.line);
+        }
+        if self.lines.len() != buffer.line_count() {
+            *self = Self::build(buffer);
+            return;
+        }
+        self.rescan(buffer, first);
+        self.revision = buffer.revision();
+    }
+
+    /// Words starting with `prefix` and their number of occurrences
+    pub fn words_with_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, usize)> + 'a {
+        self.counts
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(word, _)| word.starts_with(prefix))
+            .map(|(word, &count)| (word.as_str(), count))
+    }
+
+    /// Index the words of lines waiting for a rescan, from line `first` on
+    fn rescan(&mut self, buffer: &TextBuffer, first: usize) {
+        let mut line = first;
+        while self.dirty > 0 && line < self.lines.len() {
+            if self.lines[line].is_none() {
+                let words = line_words(&buffer.line(line).unwrap_or_default());
+                for word in &words {
+                    *self.counts.entry(word.clone()).or_default() += 1;
+                }
+                self.lines[line] = Some(words);
+                self.dirty -= 1;
+            }
+            line += 1;
+        }
+    }
+
+    /// Forget the occurrences of the words of a removed line
+    fn remove_words(&mut self, words: &[String]) {
+        for word in words {
+            if let Some(count) = self.counts.get_mut(word) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(word);
+                }
+            }
+        }
+    }
+}
+
+/// Words of a line (identifiers of at least `MIN_WORD_CHARS` characters,
+/// numbers are skipped)
+fn line_words(line: &str) -> Vec<String> {
+    line.split(|c: char| !is_word_char(c))
+        .filter(|word| {
+            word.chars().count() >= MIN_WORD_CHARS
+                && !word.starts_with(|c: char| c.is_ascii_digit())
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Start of the word ending at `column` of `line` and its typed part, or
+/// None if the cursor is not at the end of a word
+pub(crate) fn word_before(line: &str, column: usize) -> Option<(usize, String)> {
+    let graphemes: Vec<&str> = line.trim_end_matches('\n').graphemes(true).collect();
+    let column = column.min(graphemes.len());
+    let is_word = |g: &str| g.chars().next().is_some_and(is_word_char);
+    if graphemes.get(column).is_some_and(|g| is_word(g)) {
+        return None;
+    }
+    let start = graphemes[..column]
+        .iter()
+        .rposition(|g| !is_word(g))
+        .map_or(0, |pos| pos + 1);
+    Some((start, graphemes[start..column].concat()))
+}
+
+/// Completion popup state.
+pub(crate) struct Completion {
+    /// Start of the completed word
+    pub start: Cursor,
+    /// Typed part of the word
+    pub prefix: String,
+    /// Candidates, best first
+    pub items: Vec<String>,
+    /// Index of the highlighted candidate
+    pub selected: usize,
+}
+
+impl Completion {
+    /// Highlight the next candidate (wrapping around)
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    /// Highlight the previous candidate (wrapping around)
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.checked_sub(1).unwrap_or(self.items.len() - 1);
+    }
+
+    /// Text inserted after the prefix when the highlighted candidate is accepted
+    pub fn suffix(&self) -> &str {
+        &self.items[self.selected][self.prefix.len()..]
+    }
+}
+
+/// Candidates completing `prefix` typed on `line`: words of the edited
+/// buffer closest to the line first, then by number of occurrences
+/// (including `other_counts` from other buffers)
+pub(crate) fn rank_candidates(
+    index: &WordIndex,
+    line: usize,
+    prefix: &str,
+    other_counts: HashMap<String, usize>,
+) -> Vec<String> {
+    let mut counts = other_counts;
+    for (word, count) in index.words_with_prefix(prefix) {
+        *counts.entry(word.to_string()).or_default() += count;
+    }
+    counts.remove(prefix);
+    if counts.is_empty() {
+        return Vec::new();
+    }
+
+    // Distance to the nearest line using the word
+    let mut distances: HashMap<&str, usize> = HashMap::new();
+    for distance in 0..=PROXIMITY_LINES {
+        if distances.len() == counts.len() {
+            break;
+        }
+        let above = line.checked_sub(distance);
+        let below = Some(line + distance).filter(|_| distance > 0);
+        if above.is_none() && below.is_some_and(|below| below >= index.lines.len()) {
+            break;
+        }
+        for near in [above, below].into_iter().flatten() {
+            let Some(Some(words)) = index.lines.get(near) else {
+                continue;
+            };
+            for word in words {
+                if let Some((word, _)) = counts.get_key_value(word.as_str()) {
+                    distances.entry(word.as_str()).or_insert(distance);
+                }
+            }
+        }
+    }
+
+    let mut candidates: Vec<(&String, usize)> = counts.iter().map(|(w, &c)| (w, c)).collect();
+    candidates.sort_by_key(|&(word, count)| {
+        (
+            distances.get(word.as_str()).copied().unwrap_or(usize::MAX),
+            Reverse(count),
+            word,
+        )
+    });
+    candidates
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .map(|(word, _)| word.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(index: &WordIndex) -> Vec<(String, usize)> {
+        index
+            .counts
+            .iter()
+            .map(|(word, &count)| (word.clone(), count))
+            .collect()
+    }
+
+    #[test]
+    fn test_index_follows_edits() {
+        let mut buffer = TextBuffer::from_text("let alpha = 1;\nlet beta = alpha;\n");
+        buffer.set_line_edit_tracking(true);
+        let mut index = WordIndex::build(&buffer);
+        assert_eq!(
+            index.words_with_prefix("al").collect::<Vec<_>>(),
+            [("alpha", 2)]
+        );
+
+        buffer.insert(&Cursor::at(1, 4), "gamma_").unwrap();
+        buffer.insert(&Cursor::at(0, 0), "x\nalphabet\n").unwrap();
+        buffer.undo().unwrap();
+        buffer.insert(&Cursor::at(2, 0), "// alpha2\n").unwrap();
+        index.apply_edits(&buffer.take_line_edits(), &buffer);
+
+        assert!(index.is_current(&buffer));
+        assert_eq!(counts(&index), counts(&WordIndex::build(&buffer)));
+        assert_eq!(
+            index.words_with_prefix("al").collect::<Vec<_>>(),
+            [("alpha", 2), ("alpha2", 1)]
+        );
+    }
+
+    #[test]
+    fn test_word_before_cursor() {
+        assert_eq!(word_before("let foo_b", 9), Some((4, "foo_b".to_string())));
+        assert_eq!(word_before("foo bar", 2), None);
+        assert_eq!(word_before("(x", 0), Some((0, String::new())));
+    }
+
+    #[test]
+    fn test_candidates_ranked_by_proximity_then_frequency() {
+        let text = "value_far\nvalue_often value_often\nvalue_near\nval\n";
+        let index = WordIndex::build(&TextBuffer::from_text(text));
+        let others = HashMap::from([
+            ("value_often".to_string(), 5),
+            ("value_other".to_string(), 1),
+            ("valid".to_string(), 9),
+        ]);
+
+        assert_eq!(
+            rank_candidates(&index, 3, "val", others),
+            [
+                "value_near",
+                "value_often",
+                "value_far",
+                "valid",
+                "value_other"
+            ]
+        );
+        assert!(rank_candidates(&index, 3, "value_near", HashMap::new()).is_empty());
+    }
+}
@@ -1,5 +1,14 @@
 //! Editor configuration and information types.
 
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use termide_buffer::{SaveTransforms, WriteOptions};
+use termide_config::{defaults, IndentMode, RenderWhitespace};
+use termide_spell::Dictionary;
+
+use crate::constants::MEGABYTE;
+
 /// Editor mode configuration
 #[derive(Debug, Clone)]
 pub struct EditorConfig {
@@ -9,8 +18,45 @@ pub struct EditorConfig {
     pub read_only: bool,
     /// Automatic line wrapping by window width
     pub word_wrap: bool,
+    /// Vertical movement and Home/End follow wrapped rows, not whole lines
+    pub visual_line_navigation: bool,
     /// Tab size (number of spaces)
     pub tab_size: usize,
+    /// Indentation inserted (`Auto`: detected from the buffer)
+    pub indent_style: IndentMode,
+    /// Cleanup applied to the buffer on save
+    pub save_transforms: SaveTransforms,
+    /// How the file is written on save (backup copy)
+    pub write_options: WriteOptions,
+    /// File size in bytes above which large file mode is used (0 = no limit)
+    pub large_file_threshold_bytes: u64,
+    /// Line count above which large file mode is used (0 = no limit)
+    pub large_file_threshold_lines: usize,
+    /// Graphemes of a line highlighted before the rest is shown plain
+    /// (0 = no limit)
+    pub max_highlight_columns: usize,
+    /// Insert closing brackets and quotes automatically
+    pub auto_close_brackets: bool,
+    /// Maximum number of positions in the jump list
+    pub jump_list_size: usize,
+    /// Maximum number of edits kept in the undo tree
+    pub undo_tree_size: usize,
+    /// Lines kept visible above and below the cursor when scrolling
+    pub scroll_off: usize,
+    /// Offer words of open buffers while typing
+    pub autocomplete: bool,
+    /// Characters typed before completion is offered
+    pub autocomplete_min_chars: usize,
+    /// Also complete words of other open files
+    pub autocomplete_open_buffers: bool,
+    /// Formatter command line of each language (`{file}`: path of the file)
+    pub formatters: BTreeMap<String, String>,
+    /// Languages formatted before saving with Ctrl+S
+    pub format_on_save: Vec<String>,
+    /// Dictionary to check spelling with (None: spell checking is off)
+    pub spell_dictionary: Option<Arc<Dictionary>>,
+    /// Whitespace drawn with visible glyphs
+    pub render_whitespace: RenderWhitespace,
 }
 
 impl Default for EditorConfig {
@@ -19,7 +65,25 @@ impl Default for EditorConfig {
             syntax_highlighting: true,
             read_only: false,
             word_wrap: true,
+            visual_line_navigation: defaults::VISUAL_LINE_NAVIGATION,
             tab_size: 4,
+            indent_style: defaults::INDENT_STYLE,
+            save_transforms: SaveTransforms::default(),
+            write_options: WriteOptions::default(),
+            large_file_threshold_bytes: defaults::LARGE_FILE_THRESHOLD_MB * MEGABYTE,
+            large_file_threshold_lines: defaults::LARGE_FILE_THRESHOLD_LINES,
+            max_highlight_columns: defaults::MAX_HIGHLIGHT_COLUMNS,
+            auto_close_brackets: defaults::AUTO_CLOSE_BRACKETS,
+            jump_list_size: defaults::JUMP_LIST_SIZE,
+            undo_tree_size: defaults::UNDO_TREE_SIZE,
+            scroll_off: defaults::SCROLL_OFF,
+            autocomplete: defaults::AUTOCOMPLETE,
+            autocomplete_min_chars: defaults::AUTOCOMPLETE_MIN_CHARS,
+            autocomplete_open_buffers: defaults::AUTOCOMPLETE_OPEN_BUFFERS,
+            formatters: default_formatters(),
+            format_on_save: Vec::new(),
+            spell_dictionary: None,
+            render_whitespace: defaults::RENDER_WHITESPACE,
         }
     }
 }
@@ -31,19 +95,48 @@ impl EditorConfig {
             syntax_highlighting: true,
             read_only: true,
             word_wrap: true,
+            visual_line_navigation: defaults::VISUAL_LINE_NAVIGATION,
             tab_size: 4,
+            indent_style: defaults::INDENT_STYLE,
+            save_transforms: SaveTransforms::default(),
+            write_options: WriteOptions::default(),
+            large_file_threshold_bytes: defaults::LARGE_FILE_THRESHOLD_MB * MEGABYTE,
+            large_file_threshold_lines: defaults::LARGE_FILE_THRESHOLD_LINES,
+            max_highlight_columns: defaults::MAX_HIGHLIGHT_COLUMNS,
+            auto_close_brackets: defaults::AUTO_CLOSE_BRACKETS,
+            jump_list_size: defaults::JUMP_LIST_SIZE,
+            undo_tree_size: defaults::UNDO_TREE_SIZE,
+            scroll_off: defaults::SCROLL_OFF,
+            autocomplete: false,
+            autocomplete_min_chars: defaults::AUTOCOMPLETE_MIN_CHARS,
+            autocomplete_open_buffers: defaults::AUTOCOMPLETE_OPEN_BUFFERS,
+            formatters: BTreeMap::new(),
+            format_on_save: Vec::new(),
+            spell_dictionary: None,
+            render_whitespace: defaults::RENDER_WHITESPACE,
         }
     }
 }
 
+fn default_formatters() -> BTreeMap<String, String> {
+    defaults::FORMAT_COMMANDS
+        .iter()
+        .map(|(language, command)| (language.to_string(), command.to_string()))
+        .collect()
+}
+
 /// Editor information for status bar
 #[derive(Debug, Clone)]
 pub struct EditorInfo {
     pub line: usize,               // Current line (1-based)
     pub column: usize,             // Current column (1-based)
-    pub tab_size: usize,           // Tab size
+    pub tab_size: usize,           // Indent width (tab size for tabs)
+    pub indent_tabs: bool,         // Indentation uses tabs
     pub encoding: String,          // Encoding (UTF-8)
+    pub line_ending: String,       // Line ending used on save (LF / CRLF)
+    pub mixed_line_endings: bool,  // File had both LF and CRLF when loaded
     pub file_type: String,         // File type / syntax language
     pub read_only: bool,           // Read-only mode
     pub syntax_highlighting: bool, // Syntax highlighting enabled
+    pub large_file: bool,          // Large file mode (expensive features disabled)
 }
@@ -3,5 +3,30 @@
 /// One megabyte in bytes.
 pub const MEGABYTE: u64 = 1024 * 1024;
 
-/// Maximum file size that can be opened in the editor (50 MB).
-pub const MAX_EDITOR_FILE_SIZE: u64 = 50 * MEGABYTE;
+/// Maximum file size that can be opened in the editor (512 MB).
+pub const MAX_EDITOR_FILE_SIZE: u64 = 512 * MEGABYTE;
+
+/// Cursor movements over at least this many lines are recorded in the jump list.
+pub const JUMP_MIN_LINES: usize = 10;
+
+/// Syntax override that turns highlighting off.
+pub const PLAIN_TEXT_SYNTAX: &str = "text";
+
+/// Maximum number of suggestions offered for a misspelled word.
+pub const SPELLING_SUGGESTIONS: usize = 8;
+
+/// Buffers larger than this are searched as you type only after a pause.
+pub const SEARCH_DEBOUNCE_BYTES: usize = MEGABYTE as usize;
+
+/// Pause in typing after which a large buffer is searched.
+pub const SEARCH_DEBOUNCE_MS: u64 = 150;
+
+/// Pause in typing after which the markdown preview is updated.
+pub const PREVIEW_DEBOUNCE_MS: u64 = 300;
+
+/// Pause in typing after which the whole document is parsed again for
+/// highlighting (until then only edited lines are highlighted again).
+pub const HIGHLIGHT_DEBOUNCE_MS: u64 = 150;
+
+/// Pause in typing after which the buffer is compared with HEAD.
+pub const GIT_DIFF_DEBOUNCE_MS: u64 = 300;
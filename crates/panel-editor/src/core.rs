@@ -2,22 +2,50 @@ use anyhow::Result;
 use crossterm::event::KeyEvent;
 use ratatui::{buffer::Buffer, layout::Rect};
 use std::any::Any;
-use std::path::PathBuf;
-
-use termide_buffer::{Cursor, SearchState, Selection, TextBuffer, Viewport};
-use termide_config::Config;
-use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext, SessionPanel};
-use termide_git::GitDiffCache;
+use std::cell::Ref;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::DerefMut;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+
+use termide_buffer::{
+    is_word_char, Action, Cursor, IndentStyle, JumpList, LineEdit, SearchState, Selection,
+    TextBuffer, TextEncoding, Viewport, WrapMode, WriteMethod, COMMON_ENCODINGS,
+};
+use termide_config::constants::FORMAT_TIMEOUT_MS;
+use termide_config::{Config, IndentMode, RenderWhitespace};
+use termide_core::{
+    CommandResult, MenuItem, Panel, PanelCommand, PanelEvent, RenderContext, SessionPanel,
+    StatusKind, StatusSegment,
+};
+use termide_git::{GitDiffCache, Hunk};
+use termide_highlight::{CommentStyle, LOG_LANGUAGE};
 use termide_i18n::t;
-use termide_modal::{ActiveModal, InputModal, ReplaceModal, SearchModal};
+use termide_lsp::{utf16_column, Diagnostic};
+use termide_modal::{ActiveModal, InputModal, ReplaceModal, SearchModal, SelectModal};
+use termide_session::Bookmarks;
+use termide_spell::Dictionary;
 use termide_state::PendingAction;
 use termide_theme::Theme;
 
 use crate::{
     clipboard,
+    completion::{self, Completion},
     config::*,
-    constants, cursor, file_io, git, keyboard, rendering, search, selection,
-    state::{FileState, GitIntegration, InputState, RenderingCache, SearchController},
+    constants, cursor, file_io, folding,
+    format::{self, FormatJob, FormatOutcome},
+    git, keyboard,
+    lsp::{LspState, TextVersion},
+    preview::MarkdownPreview,
+    recovery::RecoveryUpdate,
+    rendering::{self, context::DiagnosticSpan, whitespace::WhitespaceMarkers},
+    search, selection,
+    state::{
+        BookmarkMarks, FileState, GitIntegration, InputState, RenderingCache, SearchController,
+        SearchOrigin, SharedBuffer, SuppressedFeatures, ViewEdit,
+    },
     text_editing, word_wrap,
 };
 
@@ -26,17 +54,23 @@ pub struct Editor {
     // === Core editing state ===
     /// Editor mode configuration
     config: EditorConfig,
-    /// Text buffer with Rope
-    buffer: TextBuffer,
+    /// Text buffer with Rope (shared with split views of the file)
+    buffer: SharedBuffer,
+    /// Buffer generation the view state belongs to (changes on reload)
+    buffer_generation: u64,
     /// Cursor
     cursor: Cursor,
     /// Text selection (if any)
     selection: Option<Selection>,
     /// Viewport for virtual scrolling
     viewport: Viewport,
+    /// Positions recorded before jumps (search, go to line, page jumps)
+    jumps: JumpList,
+    /// Marks of the bookmarks in the file
+    bookmarks: BookmarkMarks,
 
     // === Grouped state ===
-    /// File-related state (mtime, external changes, title)
+    /// File-related state (path, title, permissions)
     pub(crate) file_state: FileState,
     /// Search-related state
     pub(crate) search: SearchController,
@@ -46,6 +80,12 @@ pub struct Editor {
     pub(crate) render_cache: RenderingCache,
     /// Input state (clicks, preferred column)
     pub(crate) input: InputState,
+    /// Word completion popup (if shown)
+    pub(crate) completion: Option<Completion>,
+    /// Language server diagnostics and text synchronization
+    pub(crate) lsp: LspState,
+    /// External formatter running on the buffer (if any)
+    format_job: Option<FormatJob>,
 
     // === UI state ===
     /// Modal window request
@@ -54,6 +94,8 @@ pub struct Editor {
     config_update: Option<Config>,
     /// Status message to display to user
     pub(crate) status_message: Option<String>,
+    /// Events for the application produced by the last command
+    pending_events: Vec<PanelEvent>,
 }
 
 impl Editor {
@@ -64,42 +106,62 @@ impl Editor {
 
     /// Create new empty editor with specified configuration
     pub fn with_config(config: EditorConfig) -> Self {
+        let jumps = JumpList::new(config.jump_list_size);
+        let viewport = Viewport {
+            scroll_off: config.scroll_off,
+            ..Viewport::default()
+        };
+
+        let mut buffer = TextBuffer::new();
+        buffer.set_undo_limit(config.undo_tree_size);
+
         Self {
             config,
-            buffer: TextBuffer::new(),
+            buffer: SharedBuffer::new(buffer),
+            buffer_generation: 0,
             cursor: Cursor::new(),
             selection: None,
-            viewport: Viewport::default(),
+            viewport,
+            jumps,
+            bookmarks: BookmarkMarks::new(),
             file_state: FileState::new(),
             search: SearchController::new(),
             git: GitIntegration::new(),
             render_cache: RenderingCache::new(),
             input: InputState::new(),
+            completion: None,
+            lsp: LspState::default(),
+            format_job: None,
             modal_request: None,
             config_update: None,
             status_message: None,
+            pending_events: Vec::new(),
         }
     }
 
-    /// Check if smart word wrapping should be used
+    /// Where wrapped lines are broken
     ///
-    /// Smart wrapping is enabled when:
+    /// Lines are broken at word boundaries when:
     /// - File size is below the configured threshold
     ///
-    /// Smart wrap works for both code files (with syntax) and plain text files.
-    fn should_use_smart_wrap(&self, config: &Config) -> bool {
+    /// Otherwise (for performance) they are broken at the width.
+    fn wrap_mode(&self, config: &Config) -> WrapMode {
         // Check file size threshold (for performance)
         let threshold_bytes = config.editor.large_file_threshold_mb * constants::MEGABYTE;
         if self.file_state.size > threshold_bytes {
-            return false;
+            return WrapMode::Hard;
         }
 
-        true
+        if config.editor.wrap_at_punctuation {
+            WrapMode::WordsAndPunctuation
+        } else {
+            WrapMode::Words
+        }
     }
 
     /// Get file path
     pub fn file_path(&self) -> Option<&std::path::Path> {
-        self.buffer.file_path()
+        self.file_state.path.as_deref()
     }
 
     /// Get cached git repository root (returns None if not yet cached)
@@ -143,73 +205,365 @@ impl Editor {
         // Check file size before loading and get modification time
         let metadata = file_io::check_file_metadata(&path)?;
         let file_size = metadata.size;
-        let file_mtime = metadata.mtime;
+        let mut buffer = TextBuffer::from_file(&path)?;
+        buffer.set_undo_limit(config.undo_tree_size);
 
-        let buffer = TextBuffer::from_file(&path)?;
+        // Create file state
+        let mut file_state = FileState::from_path(&path, file_size);
 
         // Check file access rights for auto-detection of read-only
-        if file_io::is_file_readonly(&path) {
+        file_state.update_write_permission(&path);
+        if file_state.read_only {
             log::warn!("File detected as read-only: {}", path.display());
-            config.read_only = true;
         }
 
-        // Create file state
-        let file_state = FileState::from_path(&path, file_mtime, file_size);
+        // Large files open without highlighting, word wrap and git diff
+        if Self::exceeds_large_file_threshold(&config, file_size, buffer.line_count()) {
+            log::info!(
+                "Opening {} in large file mode ({} bytes, {} lines)",
+                path.display(),
+                file_size,
+                buffer.line_count()
+            );
+            file_state.large_file = Some(SuppressedFeatures {
+                syntax_highlighting: config.syntax_highlighting,
+                word_wrap: config.word_wrap,
+            });
+            config.syntax_highlighting = false;
+            config.word_wrap = false;
+        }
 
-        // Create rendering cache and set syntax by file extension
+        // Create rendering cache and set syntax by file name or shebang
         let mut render_cache = RenderingCache::new();
         if config.syntax_highlighting {
-            render_cache.highlight.set_syntax_from_path(&path);
+            if let Some(language) = Self::file_language(&buffer) {
+                render_cache.highlight.set_syntax(language);
+            }
         }
 
         // Initialize git integration
         let mut git = GitIntegration::new();
-        let mut cache = GitDiffCache::new(path.clone());
-        if cache.update().is_ok() {
-            git.diff_cache = Some(cache);
+        if file_state.large_file.is_none() {
+            let mut cache = GitDiffCache::new(path.clone());
+            if cache.update().is_ok() {
+                git.diff_cache = Some(cache);
+            }
         }
 
+        let jumps = JumpList::new(config.jump_list_size);
+        let viewport = Viewport {
+            scroll_off: config.scroll_off,
+            ..Viewport::default()
+        };
+
+        // Bytes invalid in the detected encoding were replaced on load
+        let status_message = buffer.had_decode_errors().then(|| {
+            log::warn!("Invalid bytes replaced when loading {}", path.display());
+            t().editor_invalid_bytes(&buffer.encoding().name())
+        });
+
+        let buffer = SharedBuffer::new(buffer);
+        buffer.set_mtime(metadata.mtime);
+
         Ok(Self {
             config,
             buffer,
+            buffer_generation: 0,
             cursor: Cursor::new(),
             selection: None,
-            viewport: Viewport::default(),
+            viewport,
+            jumps,
+            bookmarks: BookmarkMarks::new(),
             file_state,
             search: SearchController::new(),
             git,
             render_cache,
             input: InputState::new(),
+            completion: None,
+            lsp: LspState::default(),
+            format_job: None,
             modal_request: None,
             config_update: None,
-            status_message: None,
+            status_message,
+            pending_events: Vec::new(),
         })
     }
 
+    /// Check if a file of given size and line count should open in large file mode
+    fn exceeds_large_file_threshold(config: &EditorConfig, size: u64, line_count: usize) -> bool {
+        (config.large_file_threshold_bytes > 0 && size > config.large_file_threshold_bytes)
+            || (config.large_file_threshold_lines > 0
+                && line_count > config.large_file_threshold_lines)
+    }
+
+    /// Language of the buffer's file: by file name, or by the first line
+    /// (shebang, `<?php`) for files with an unknown name and buffers
+    /// without a file
+    fn file_language(buffer: &TextBuffer) -> Option<&'static str> {
+        let by_content = || {
+            buffer
+                .line(0)
+                .and_then(|line| termide_highlight::detect_language_from_content(line.trim_end()))
+        };
+        match buffer.file_path() {
+            Some(path) => termide_highlight::detect_language(path).or_else(by_content),
+            None => by_content(),
+        }
+    }
+
+    /// Language of the buffer: the user's override, or the detected one
+    fn language(&self) -> Option<&str> {
+        match self.file_state.syntax_override.as_deref() {
+            Some(constants::PLAIN_TEXT_SYNTAX) => None,
+            Some(language) => Some(language),
+            None => Self::file_language(&self.buffer.borrow()),
+        }
+    }
+
+    /// Language picked by the user for this buffer, if any
+    pub fn syntax_override(&self) -> Option<&str> {
+        self.file_state.syntax_override.as_deref()
+    }
+
+    /// Highlight the buffer as `language` instead of the detected language
+    /// (`PLAIN_TEXT_SYNTAX` turns highlighting off). Unknown languages are ignored.
+    pub fn set_syntax_override(&mut self, language: &str) {
+        if language != constants::PLAIN_TEXT_SYNTAX
+            && !termide_highlight::highlighted_languages().any(|lang| lang == language)
+        {
+            return;
+        }
+        self.file_state.syntax_override = Some(language.to_string());
+        self.apply_syntax();
+        self.render_cache.fold_regions_revision = None;
+    }
+
+    /// Set the highlighter syntax to the buffer's language
+    fn apply_syntax(&mut self) {
+        if !self.config.syntax_highlighting {
+            return;
+        }
+        match self.language().map(str::to_string) {
+            Some(language) => self.render_cache.highlight.set_syntax(&language),
+            None => self.render_cache.highlight.clear_syntax(),
+        }
+    }
+
+    /// Open the language list to override syntax highlighting
+    pub(crate) fn open_syntax_select(&mut self) {
+        let t = t();
+        let mut languages = vec![constants::PLAIN_TEXT_SYNTAX.to_string()];
+        languages.extend(termide_highlight::highlighted_languages().map(str::to_string));
+        let labels = languages
+            .iter()
+            .map(|language| match language.as_str() {
+                constants::PLAIN_TEXT_SYNTAX => t.editor_plain_text().to_string(),
+                language => Self::format_language_name(language).to_string(),
+            })
+            .collect();
+        let current = self.language().unwrap_or(constants::PLAIN_TEXT_SYNTAX);
+        let cursor = languages
+            .iter()
+            .position(|language| language == current)
+            .unwrap_or(0);
+
+        let modal = SelectModal::single(t.modal_syntax_title(), "", labels).with_cursor(cursor);
+        self.modal_request = Some((
+            PendingAction::SelectSyntax { languages },
+            ActiveModal::Select(Box::new(modal)),
+        ));
+    }
+
+    /// Open the list of encodings to reopen or convert the file
+    pub(crate) fn open_encoding_select(&mut self) {
+        let current = self.buffer.borrow().encoding().without_bom();
+        let encodings: Vec<String> = COMMON_ENCODINGS.iter().map(|e| e.to_string()).collect();
+        let cursor = encodings
+            .iter()
+            .position(|label| TextEncoding::for_label(label) == Some(current))
+            .unwrap_or(0);
+
+        let modal = SelectModal::single(t().modal_encoding_title(), "", encodings.clone())
+            .with_cursor(cursor);
+        self.modal_request = Some((
+            PendingAction::SelectEncoding { encodings },
+            ActiveModal::Select(Box::new(modal)),
+        ));
+    }
+
+    /// Open the list of undo branches (newest first) to switch the text to
+    pub(crate) fn open_undo_tree(&mut self) {
+        let t = t();
+        let branches = self.buffer.borrow_mut().undo_branches();
+        if branches.is_empty() {
+            self.status_message = Some(t.editor_undo_tree_empty().to_string());
+            return;
+        }
+
+        let labels = branches
+            .iter()
+            .map(|branch| {
+                let time = chrono::DateTime::<chrono::Local>::from(branch.time).format("%H:%M:%S");
+                let change = branch
+                    .first_difference
+                    .as_ref()
+                    .map_or_else(|| t.editor_undo_branch_here().to_string(), preview_action);
+                let marker = if branch.current { "● " } else { "  " };
+                format!(
+                    "{}{}",
+                    marker,
+                    t.editor_undo_branch(&time.to_string(), &change, branch.edits)
+                )
+            })
+            .collect();
+        let cursor = branches
+            .iter()
+            .position(|branch| branch.current)
+            .unwrap_or(0);
+
+        let modal = SelectModal::single(t.modal_undo_tree_title(), "", labels).with_cursor(cursor);
+        self.modal_request = Some((
+            PendingAction::SwitchUndoBranch {
+                branches: branches.iter().map(|branch| branch.id).collect(),
+            },
+            ActiveModal::Select(Box::new(modal)),
+        ));
+    }
+
+    /// Undo and redo the edits to reach the end of another undo branch
+    pub fn switch_undo_branch(&mut self, id: usize) -> Result<()> {
+        self.handle_undo_redo(|buffer| buffer.switch_undo_branch(id))
+    }
+
+    /// Switch the file to another encoding.
+    ///
+    /// Without unsaved edits the file is read again in that encoding (for a
+    /// wrong guess on load); otherwise the text is kept and will be saved
+    /// in the new encoding.
+    pub fn set_encoding(&mut self, label: &str) -> Result<()> {
+        let encoding = TextEncoding::for_label(label)
+            .ok_or_else(|| anyhow::anyhow!("Unknown encoding: {}", label))?;
+        let t = t();
+
+        if self.buffer.borrow().is_modified() || !self.has_file_path() {
+            self.buffer.borrow_mut().set_encoding(encoding);
+            self.status_message = Some(t.editor_encoding_convert(&encoding.name()));
+            return Ok(());
+        }
+
+        self.reload_with_encoding(encoding)?;
+        self.status_message = Some(if self.buffer.borrow().had_decode_errors() {
+            t.editor_invalid_bytes(&encoding.name())
+        } else {
+            t.editor_encoding_reopened(&encoding.name())
+        });
+        Ok(())
+    }
+
+    /// Check if the file was opened in large file mode
+    pub fn is_large_file(&self) -> bool {
+        self.file_state.large_file.is_some()
+    }
+
+    /// Leave large file mode, restoring highlighting, word wrap and git diff
+    pub(crate) fn enable_large_file_features(&mut self) {
+        let Some(features) = self.file_state.large_file.take() else {
+            return;
+        };
+
+        self.config.syntax_highlighting = features.syntax_highlighting;
+        self.config.word_wrap = features.word_wrap;
+        self.apply_syntax();
+        self.update_git_diff();
+        self.status_message = Some(t().editor_large_file_features_enabled().to_string());
+    }
+
     /// Create editor with text (for displaying help, etc.)
     pub fn from_text(content: &str, title: String) -> Self {
         use ropey::Rope;
 
         // Create buffer directly through Rope
         let rope = Rope::from_str(content);
+        let buffer = TextBuffer::from_rope(rope);
 
         let mut file_state = FileState::new();
         file_state.title = title;
+        let config = EditorConfig::view_only();
+        let jumps = JumpList::new(config.jump_list_size);
+        let viewport = Viewport {
+            scroll_off: config.scroll_off,
+            ..Viewport::default()
+        };
 
         Self {
-            config: EditorConfig::view_only(),
-            buffer: TextBuffer::from_rope(rope),
+            config,
+            buffer: SharedBuffer::new(buffer),
+            buffer_generation: 0,
             cursor: Cursor::new(),
             selection: None,
-            viewport: Viewport::default(),
+            viewport,
+            jumps,
+            bookmarks: BookmarkMarks::new(),
             file_state,
             search: SearchController::new(),
             git: GitIntegration::new(),
             render_cache: RenderingCache::new(),
             input: InputState::new(),
+            completion: None,
+            lsp: LspState::default(),
+            format_job: None,
+            modal_request: None,
+            config_update: None,
+            status_message: None,
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Open another view of the buffer (split view)
+    ///
+    /// The views share text, undo history and saving; cursor, selection,
+    /// scroll position, folds and caches belong to each view.
+    pub fn split_view(&self) -> Self {
+        let mut render_cache = RenderingCache::new();
+        if self.config.syntax_highlighting {
+            if let Some(language) = self.language() {
+                render_cache.highlight.set_syntax(language);
+            }
+        }
+
+        let mut git = GitIntegration::new();
+        if let Some(path) = self.file_path().filter(|_| !self.is_large_file()) {
+            let mut cache = GitDiffCache::new(path.to_path_buf());
+            let content = self.buffer.borrow().to_string();
+            if cache.update().is_ok() && cache.update_from_buffer(&content).is_ok() {
+                git.diff_cache = Some(cache);
+            }
+        }
+
+        let mut file_state = self.file_state.clone();
+        file_state.unsaved_buffer_file = None;
+
+        Self {
+            config: self.config.clone(),
+            buffer: self.buffer.split(),
+            buffer_generation: self.buffer_generation,
+            cursor: self.cursor,
+            selection: None,
+            viewport: self.viewport.clone(),
+            jumps: JumpList::new(self.config.jump_list_size),
+            bookmarks: BookmarkMarks::new(),
+            file_state,
+            search: SearchController::new(),
+            git,
+            render_cache,
+            input: InputState::new(),
+            completion: None,
+            lsp: self.lsp.clone(),
+            format_job: None,
             modal_request: None,
             config_update: None,
             status_message: None,
+            pending_events: Vec::new(),
         }
     }
 
@@ -217,27 +571,31 @@ impl Editor {
     /// Returns error if file was modified externally (use force_save() to override)
     pub fn save(&mut self) -> Result<()> {
         // Check for external modification conflict
-        if self.file_state.external_change_detected {
+        if self.buffer.external_change() {
             return Err(anyhow::anyhow!(
                 "File was modified on disk. Use force save (Ctrl+Shift+S) to overwrite or reload (Ctrl+Shift+R) to discard changes."
             ));
         }
 
+        self.apply_save_transforms()?;
+
         // Check if this is a config file
-        if let Some(path) = self.buffer.file_path().map(|p| p.to_path_buf()) {
+        let path = self.buffer.borrow().file_path().map(Path::to_path_buf);
+        if let Some(path) = path {
             if Config::is_config_file(&path) {
                 let path_str = path.display().to_string();
                 // Validate config before saving
-                let content = self.buffer.to_string();
+                let content = self.buffer.borrow().to_string();
                 match Config::validate_content(&content) {
                     Ok(new_config) => {
                         // Save and set config update flag
-                        self.buffer.save()?;
+                        let method = self.buffer.borrow_mut().save(self.config.write_options)?;
+                        self.report_write_method(method);
                         log::info!("Config file saved: {}", path_str);
                         self.config_update = Some(new_config);
                         // Update file modification time after successful save
-                        self.file_state.mtime = file_io::get_file_mtime(&path);
-                        self.file_state.external_change_detected = false;
+                        self.buffer.set_mtime(file_io::get_file_mtime(&path));
+                        self.buffer.set_external_change(false);
                     }
                     Err(e) => {
                         log::error!("Save failed - config validation error: {}", e);
@@ -248,13 +606,14 @@ impl Editor {
             }
         }
 
-        self.buffer.save()?;
+        let method = self.buffer.borrow_mut().save(self.config.write_options)?;
+        self.report_write_method(method);
 
-        if let Some(path) = self.buffer.file_path() {
+        if let Some(path) = self.file_path() {
             log::info!("File saved: {}", path.display());
             // Update file modification time after successful save
-            self.file_state.mtime = file_io::get_file_mtime(path);
-            self.file_state.external_change_detected = false;
+            self.buffer.set_mtime(file_io::get_file_mtime(path));
+            self.buffer.set_external_change(false);
         }
 
         // Update git diff after successful save
@@ -263,10 +622,69 @@ impl Editor {
         Ok(())
     }
 
+    /// Warn in the status line when the file was not saved atomically
+    fn report_write_method(&mut self, method: WriteMethod) {
+        if let WriteMethod::Direct { reason } = method {
+            log::warn!("File written in place instead of atomically: {}", reason);
+            self.status_message = Some(t().editor_saved_in_place(&reason));
+        }
+    }
+
+    /// Apply on-save cleanup and report changes in the status line.
+    ///
+    /// Cursor and viewport stay in place (cursor is only clamped if it was
+    /// inside removed whitespace).
+    fn apply_save_transforms(&mut self) -> Result<()> {
+        let cleanup = self
+            .buffer
+            .borrow_mut()
+            .apply_save_transforms(&self.config.save_transforms)?;
+        if cleanup.is_empty() {
+            return Ok(());
+        }
+
+        self.clamp_cursor();
+        if let Some(ref mut sel) = self.selection {
+            cursor::physical::clamp_cursor(&mut sel.anchor, &self.buffer.borrow());
+            cursor::physical::clamp_cursor(&mut sel.active, &self.buffer.borrow());
+        }
+        self.render_cache
+            .highlight
+            .invalidate_range(0, self.buffer.borrow().line_count());
+
+        let t = t();
+        let mut changes = Vec::new();
+        if cleanup.trailing_whitespace > 0 {
+            changes.push(t.editor_stripped_whitespace(cleanup.trailing_whitespace));
+        }
+        if cleanup.final_newline {
+            changes.push(t.editor_fixed_final_newline().to_string());
+        }
+        if cleanup.line_endings > 0 {
+            changes.push(t.editor_normalized_line_endings(cleanup.line_endings));
+        }
+        self.status_message = Some(t.editor_saved_with_cleanup(&changes.join(", ")));
+
+        Ok(())
+    }
+
+    /// Create an editor for text without a file (read from standard input),
+    /// with the cursor at the start. The text counts as unsaved
+    pub fn with_text(content: &str, title: &str, config: EditorConfig) -> Self {
+        let mut editor = Self::with_config(config);
+        editor.file_state.title = title.to_string();
+        if let Err(e) = editor.insert_text(content) {
+            log::error!("Failed to insert text: {}", e);
+        }
+        editor.cursor = Cursor::new();
+        editor.apply_syntax();
+        editor
+    }
+
     /// Insert text at the beginning of the buffer (for restoring unsaved buffers)
     pub fn insert_text(&mut self, text: &str) -> Result<()> {
         let cursor_at_start = Cursor::new();
-        self.cursor = self.buffer.insert(&cursor_at_start, text)?;
+        self.cursor = self.buffer.borrow_mut().insert(&cursor_at_start, text)?;
         Ok(())
     }
 
@@ -280,6 +698,11 @@ impl Editor {
     /// Spawns a background thread to load original content from HEAD.
     /// The result will be applied on next tick via check_git_diff_receiver().
     pub fn update_git_diff(&mut self) {
+        // Git diff is disabled in large file mode
+        if self.is_large_file() {
+            return;
+        }
+
         // Clone file path to avoid borrow conflict with git_diff_cache
         let file_path = self.file_path().map(|p| p.to_path_buf());
         if let Some(rx) = git::update_git_diff_async(&mut self.git.diff_cache, file_path.as_deref())
@@ -288,70 +711,392 @@ impl Editor {
         }
     }
 
-    /// Check and apply async git diff result if ready (called on each tick)
+    /// Check and apply async git diff results if ready (called on each tick)
     ///
     /// Returns true if result was applied and needs_redraw should be set.
     pub fn check_git_diff_receiver(&mut self) -> bool {
-        git::check_git_diff_receiver(&mut self.git.diff_receiver, &mut self.git.diff_cache)
+        let loaded =
+            git::check_git_diff_receiver(&mut self.git.diff_receiver, &mut self.git.diff_cache);
+        let compared = git::check_buffer_diff_receiver(
+            &mut self.git.buffer_diff_receiver,
+            &mut self.git.diff_cache,
+        );
+        loaded || compared
     }
 
-    /// Schedule git diff update with debounce (300ms delay)
+    /// Schedule git diff update with debounce (`GIT_DIFF_DEBOUNCE_MS` delay)
     pub fn schedule_git_diff_update(&mut self) {
         if let Some(instant) = git::schedule_git_diff_update(&self.git.diff_cache) {
             self.git.update_pending = Some(instant);
         }
     }
 
-    /// Check and apply pending git diff update if debounce time has passed
+    /// Start comparing the buffer with HEAD in the background once the
+    /// debounce time has passed (the result is applied by
+    /// `check_git_diff_receiver`)
     pub fn check_pending_git_diff_update(&mut self) {
-        let (updated, new_pending) = git::check_pending_git_diff_update(
-            self.git.update_pending,
-            &mut self.git.diff_cache,
-            &self.buffer,
+        if !self
+            .git
+            .check_debounce(constants::GIT_DIFF_DEBOUNCE_MS as u128)
+        {
+            return;
+        }
+        if let Some(rx) = git::diff_buffer_async(&self.git.diff_cache, &self.buffer.borrow()) {
+            self.git.buffer_diff_receiver = Some(rx);
+        }
+    }
+
+    /// Run the work deferred until edits pause: parse the whole document
+    /// again for highlighting (constructs spanning lines, such as block
+    /// comments) and compare the buffer with HEAD in the background.
+    ///
+    /// Returns true if highlighting changed and needs a redraw.
+    pub fn run_deferred_work(&mut self) -> bool {
+        self.check_pending_git_diff_update();
+        let due = self.render_cache.document_edited.is_some_and(|edited| {
+            edited.elapsed() >= Duration::from_millis(constants::HIGHLIGHT_DEBOUNCE_MS)
+        });
+        if !due {
+            return false;
+        }
+        self.render_cache.document_edited = None;
+        self.sync_highlight_document();
+        true
+    }
+
+    /// Bring the parse of the whole document highlighting slices lines
+    /// from up to date (documents with long lines are highlighted line by
+    /// line)
+    fn sync_highlight_document(&mut self) {
+        if !self.config.syntax_highlighting {
+            return;
+        }
+        let buffer = &self.buffer.borrow();
+        let size = if buffer.has_long_lines() {
+            usize::MAX
+        } else {
+            buffer.len_bytes()
+        };
+        self.render_cache
+            .highlight
+            .sync_document(buffer.revision(), size, || buffer.to_string());
+    }
+
+    /// Move cursor to the next or previous git change (wraps around the file)
+    pub(crate) fn goto_hunk(&mut self, forward: bool) {
+        let line = self.cursor.line;
+        let target = self
+            .git
+            .diff_cache
+            .as_ref()
+            .and_then(|cache| {
+                if forward {
+                    cache.next_hunk(line)
+                } else {
+                    cache.prev_hunk(line)
+                }
+            })
+            .map(Hunk::anchor_line);
+
+        let Some(target) = target else {
+            self.status_message = Some(t().editor_no_git_changes().to_string());
+            return;
+        };
+
+        self.goto_line(target);
+    }
+
+    /// Restore the git change under the cursor to its HEAD version (undoable)
+    pub(crate) fn revert_hunk(&mut self) -> Result<()> {
+        // The diff is refreshed with a debounce, so bring it up to date
+        // with the buffer before choosing lines to replace
+        let content = self.buffer.borrow().to_string();
+        let hunk = match self.git.diff_cache.as_mut() {
+            Some(cache) => {
+                cache.update_from_buffer(&content)?;
+                cache.hunk_at(self.cursor.line).cloned()
+            }
+            None => None,
+        };
+
+        let Some(hunk) = hunk else {
+            self.status_message = Some(t().editor_no_hunk_at_cursor().to_string());
+            return Ok(());
+        };
+
+        self.close_search();
+        self.selection = None;
+        self.buffer
+            .borrow_mut()
+            .replace_lines(hunk.new_start, hunk.new_count, &hunk.original)?;
+        self.set_cursor_line(hunk.new_start);
+        self.input.preferred_column = None;
+
+        self.invalidate_cache_after_edit(hunk.new_start);
+        self.status_message = Some(t().editor_hunk_reverted(hunk.original.lines().count()));
+        Ok(())
+    }
+
+    /// Ask the application to compare the buffer with the file's version in HEAD
+    pub(crate) fn request_head_diff(&mut self) {
+        let Some(path) = self.file_path().map(Path::to_path_buf) else {
+            self.status_message = Some(t().editor_diff_no_file().to_string());
+            return;
+        };
+        self.pending_events.push(PanelEvent::DiffAgainstHead {
+            path,
+            content: self.buffer.borrow().to_string(),
+        });
+    }
+
+    /// Ask the app to bookmark the cursor line
+    pub(crate) fn request_bookmark(&mut self) {
+        let Some(path) = self.file_path().map(Path::to_path_buf) else {
+            self.status_message = Some(t().editor_bookmark_no_file().to_string());
+            return;
+        };
+        let line = self.cursor.line;
+        self.pending_events.push(PanelEvent::SetBookmark {
+            path,
+            line,
+            text: self.buffer.borrow().line(line).unwrap_or_default(),
+        });
+    }
+
+    /// Show the bookmarks of the file. Marks already placed for the same
+    /// bookmarks revision, buffer and file are kept where edits moved them.
+    pub fn set_bookmarks(&mut self, bookmarks: &Bookmarks) {
+        let Some(path) = self.file_path().map(Path::to_path_buf) else {
+            return;
+        };
+        let placement = (bookmarks.revision(), self.buffer.generation(), path);
+        if self.bookmarks.is_placed_for(&placement) {
+            return;
+        }
+        let path = placement.2.clone();
+        self.bookmarks.set(
+            &mut self.buffer.borrow_mut(),
+            bookmarks.in_file(&path),
+            placement,
         );
-        if updated {
-            self.git.update_pending = new_pending;
+    }
+
+    /// Name, line and line text of the bookmarks shown, where edits moved
+    /// them
+    pub fn bookmark_positions(&self) -> Vec<(String, usize, String)> {
+        self.bookmarks.positions(&self.buffer.borrow())
+    }
+
+    /// Ask the app to open a markdown preview of this view
+    pub(crate) fn request_markdown_preview(&mut self) {
+        if self.language() != Some("markdown") {
+            self.status_message = Some(t().editor_preview_not_markdown().to_string());
+            return;
         }
+        self.pending_events.push(PanelEvent::OpenMarkdownPreview);
+    }
+
+    /// Preview panel that follows this view
+    pub fn markdown_preview(&self) -> MarkdownPreview {
+        MarkdownPreview::new(self.buffer.watch(), self.file_state.title.clone())
     }
 
     /// Check if the file was modified externally (outside of this editor)
+    ///
+    /// A buffer without unsaved edits is reloaded right away. Otherwise the
+    /// edits are kept and the user is asked which version to keep (once per
+    /// change on disk).
     pub fn check_external_modification(&mut self) {
-        if let Some(file_path) = self.buffer.file_path() {
-            if file_io::was_modified_externally(file_path, self.file_state.mtime) {
-                self.file_state.external_change_detected = true;
+        let Some(file_path) = self.buffer.borrow().file_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let already_detected = self.buffer.external_change();
+        if file_io::was_modified_externally(&file_path, self.buffer.mtime()) {
+            self.buffer.set_external_change(true);
+        }
+        self.file_state.update_write_permission(&file_path);
+
+        // Views sharing the buffer handle a change once
+        if !self.buffer.external_change() || already_detected {
+            return;
+        }
+        if self.buffer.borrow().is_modified() {
+            self.request_conflict_resolution(file_path);
+        } else {
+            match self.reload_from_disk() {
+                Ok(()) => {
+                    self.status_message = Some(t().editor_reloaded_external().to_string());
+                }
+                Err(e) => log::warn!("Auto-reload of {} failed: {}", file_path.display(), e),
             }
         }
     }
 
+    /// Ask whether to keep local edits, reload the file or compare them
+    fn request_conflict_resolution(&mut self, path: PathBuf) {
+        let t = t();
+        let modal = SelectModal::single(
+            t.editor_close_conflict(),
+            t.editor_external_conflict_question(),
+            vec![
+                t.editor_keep_mine().to_string(),
+                t.editor_reload_from_disk().to_string(),
+                t.editor_show_diff().to_string(),
+            ],
+        );
+        self.modal_request = Some((
+            PendingAction::ResolveExternalChange { path },
+            ActiveModal::Select(Box::new(modal)),
+        ));
+    }
+
+    /// Keep local edits over the changed file on disk
+    ///
+    /// The disk version becomes the new baseline, so saving overwrites it
+    /// without a conflict error and later changes are detected again.
+    pub fn keep_local_changes(&mut self) {
+        if let Some(path) = self.file_path() {
+            self.buffer.set_mtime(file_io::get_file_mtime(path));
+            self.buffer.set_external_change(false);
+        }
+    }
+
+    /// Unified diff between the file on disk and the buffer
+    pub fn external_diff(&self) -> Result<String> {
+        let path = self
+            .file_path()
+            .ok_or_else(|| anyhow::anyhow!("Buffer has no file"))?;
+        let buffer = self.buffer.borrow();
+        let (disk, _, _) = buffer.encoding().decode(&std::fs::read(path)?);
+        Ok(file_io::unified_diff(
+            &disk,
+            &buffer.to_string(),
+            &self.file_state.title,
+        ))
+    }
+
+    /// Unified diff of the unsaved edits: the file on disk against the
+    /// buffer. A buffer without a file, or whose file is gone, is compared
+    /// with an empty text.
+    pub fn unsaved_diff(&self) -> Result<String> {
+        let buffer = self.buffer.borrow();
+        let disk = match self.file_path().map(std::fs::read) {
+            Some(Ok(bytes)) => buffer.encoding().decode(&bytes).0,
+            Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => String::new(),
+        };
+        Ok(file_io::unified_diff(
+            &disk,
+            &buffer.to_string(),
+            &self.file_state.title,
+        ))
+    }
+
+    /// Use settings changed in the config while the editor is open
+    ///
+    /// Highlighting and large file mode stay as they are; word
+    /// wrap changes in large file mode apply when the mode is left.
+    pub fn apply_settings(&mut self, mut config: EditorConfig) {
+        // Help and log viewers keep their settings
+        if self.config.read_only {
+            return;
+        }
+        config.read_only = self.config.read_only;
+        config.syntax_highlighting = self.config.syntax_highlighting;
+        if let Some(features) = &mut self.file_state.large_file {
+            features.word_wrap = config.word_wrap;
+            config.word_wrap = false;
+        }
+        self.viewport.scroll_off = config.scroll_off;
+        self.buffer
+            .borrow_mut()
+            .set_undo_limit(config.undo_tree_size);
+        self.config = config;
+    }
+
+    /// Check if editing is blocked (view mode or file without write permission)
+    pub fn is_read_only(&self) -> bool {
+        self.config.read_only || self.file_state.read_only
+    }
+
+    /// Allow editing a file without write permission
+    ///
+    /// Saving will still fail unless permissions change, so the status
+    /// message points to Save As.
+    pub(crate) fn edit_anyway(&mut self) {
+        if !self.file_state.read_only {
+            return;
+        }
+        self.file_state.read_only = false;
+        self.file_state.edit_anyway = true;
+        self.status_message = Some(t().editor_edit_anyway().to_string());
+    }
+
     /// Check if external modification was detected
     pub fn has_external_change(&self) -> bool {
-        self.file_state.external_change_detected
+        self.buffer.external_change()
     }
 
     /// Check if buffer has unsaved modifications
     pub fn buffer_is_modified(&self) -> bool {
-        self.buffer.is_modified()
+        self.buffer.borrow().is_modified()
+    }
+
+    /// Whether `other` shows the same buffer (a split view of this editor)
+    pub fn shares_buffer_with(&self, other: &Editor) -> bool {
+        self.buffer.same_buffer(&other.buffer)
     }
 
     /// Clear external change flag (after user acknowledged or reloaded)
     #[allow(dead_code)]
     pub fn clear_external_change(&mut self) {
-        self.file_state.external_change_detected = false;
+        self.buffer.set_external_change(false);
     }
 
     /// Reload file from disk (discards local changes)
+    ///
+    /// The cursor stays on the same content: lines added or removed above
+    /// it move it along instead of leaving it on a different line.
     pub fn reload_from_disk(&mut self) -> Result<()> {
-        if let Some(path) = self.buffer.file_path().map(|p| p.to_path_buf()) {
+        let encoding = self.buffer.borrow().encoding();
+        self.reload_with_encoding(encoding)
+    }
+
+    /// Reload file from disk decoded with `encoding`
+    fn reload_with_encoding(&mut self, encoding: TextEncoding) -> Result<()> {
+        let path = self.buffer.borrow().file_path().map(Path::to_path_buf);
+        if let Some(path) = path {
             // Re-read the file
-            self.buffer = TextBuffer::from_file(&path)?;
+            let old_text = self.buffer.borrow().to_string();
+            let mut buffer = TextBuffer::from_file_with_encoding(&path, encoding)?;
+            buffer.set_undo_limit(self.config.undo_tree_size);
+            self.buffer.replace(buffer);
+            self.buffer_generation = self.buffer.generation();
+            self.viewport.folds = Default::default();
+            self.render_cache.fold_regions_revision = None;
+            self.render_cache.highlight.invalidate_all();
 
             // Update modification time
-            self.file_state.mtime = file_io::get_file_mtime(&path);
-            self.file_state.external_change_detected = false;
+            self.buffer.set_mtime(file_io::get_file_mtime(&path));
+            self.buffer.set_external_change(false);
 
-            // Reset cursor and selection
-            self.cursor = Cursor::new();
+            // Keep cursor and its screen row on the same content (diffing
+            // is skipped for large files, the line number is kept instead)
+            let cursor_line = if self.is_large_file() {
+                self.cursor.line
+            } else {
+                file_io::anchor_line(
+                    &old_text,
+                    &self.buffer.borrow().to_string(),
+                    self.cursor.line,
+                )
+            };
+            let screen_row = self.cursor.line.saturating_sub(self.viewport.top_line);
+            self.cursor.line = cursor_line;
+            self.clamp_cursor();
+            self.viewport.top_line = self.cursor.line.saturating_sub(screen_row);
             self.selection = None;
+            self.input.preferred_column = None;
 
             // Update git diff
             self.update_git_diff();
@@ -363,10 +1108,15 @@ impl Editor {
 
     /// Force save (ignore external changes)
     pub fn force_save(&mut self) -> Result<()> {
-        self.file_state.external_change_detected = false;
+        self.buffer.set_external_change(false);
         self.save()
     }
 
+    /// Take the status message set outside of key handling
+    pub fn take_status_message(&mut self) -> Option<String> {
+        self.status_message.take()
+    }
+
     /// Get updated config (if config file was saved)
     pub fn take_config_update(&mut self) -> Option<Config> {
         self.config_update.take()
@@ -374,18 +1124,28 @@ impl Editor {
 
     /// Save file as (Save As)
     pub fn save_file_as(&mut self, path: PathBuf) -> Result<()> {
-        self.buffer.save_to(&path)?;
+        self.apply_save_transforms()?;
+        let method = self
+            .buffer
+            .borrow_mut()
+            .save_to(&path, self.config.write_options)?;
+        self.report_write_method(method);
         log::info!("File saved as: {}", path.display());
 
-        // Update title
+        // Update title, modification time and permissions for the new file
         self.file_state.title = file_io::path_to_title(&path);
+        self.buffer.set_mtime(file_io::get_file_mtime(&path));
+        self.buffer.set_external_change(false);
+        self.file_state.path = Some(path.clone());
+        self.file_state.edit_anyway = false;
+        self.file_state.update_write_permission(&path);
 
         Ok(())
     }
 
     /// Check if file has path (not unnamed)
     pub fn has_file_path(&self) -> bool {
-        self.buffer.file_path().is_some()
+        self.buffer.borrow().file_path().is_some()
     }
 
     /// Get editor information for status bar
@@ -399,27 +1159,472 @@ impl Editor {
             .unwrap_or("Plain Text")
             .to_string();
 
+        let indent_style = self.indent_style();
+
         EditorInfo {
             line: self.cursor.line + 1,     // 1-based
             column: self.cursor.column + 1, // 1-based
-            tab_size: self.config.tab_size,
-            encoding: "UTF-8".to_string(),
+            tab_size: self.indent_width(),
+            indent_tabs: indent_style.is_tabs(),
+            encoding: self.buffer.borrow().encoding().name(),
+            line_ending: self.buffer.borrow().line_ending().name().to_string(),
+            mixed_line_endings: self.buffer.borrow().has_mixed_line_endings(),
             file_type,
-            read_only: self.config.read_only,
+            read_only: self.is_read_only(),
             syntax_highlighting: self.config.syntax_highlighting,
+            large_file: self.is_large_file(),
+        }
+    }
+
+    /// Status bar segments: cursor position, indentation, encoding, line
+    /// ending, language and modes
+    pub fn status_bar_segments(&self) -> Vec<StatusSegment> {
+        let t = t();
+        let info = self.get_editor_info();
+        let indent_label = if info.indent_tabs {
+            t.status_indent_tabs()
+        } else {
+            t.status_indent_spaces()
+        };
+        let line_ending = if info.mixed_line_endings {
+            format!("{} ({})", info.line_ending, t.status_mixed_line_endings())
+        } else {
+            info.line_ending
+        };
+        let language = if info.syntax_highlighting {
+            info.file_type
+        } else {
+            t.status_plain_text().to_string()
+        };
+
+        let mut segments = vec![
+            StatusSegment::labeled(
+                StatusKind::Cursor,
+                t.status_pos(),
+                format!("{}:{}", info.line, info.column),
+            ),
+            StatusSegment::labeled(StatusKind::Indent, indent_label, info.tab_size.to_string()),
+            StatusSegment::new(StatusKind::Encoding, info.encoding),
+            StatusSegment::new(StatusKind::LineEnding, line_ending),
+            StatusSegment::new(StatusKind::Language, language),
+        ];
+
+        // Read-only and large file mode (with the key to leave it)
+        let modes: Vec<&str> = [
+            (info.read_only, t.status_readonly()),
+            (info.large_file, t.status_large_file()),
+        ]
+        .into_iter()
+        .filter_map(|(on, mode)| on.then_some(mode))
+        .collect();
+        if !modes.is_empty() {
+            segments.push(StatusSegment::new(
+                StatusKind::Mode,
+                modes.join(t.ui_hint_separator()),
+            ));
+        }
+
+        let (errors, warnings) = self.lsp.counts();
+        if errors + warnings > 0 {
+            segments.push(StatusSegment::new(
+                StatusKind::Diagnostics,
+                t.task_problems(errors, warnings),
+            ));
+        }
+
+        segments
+    }
+
+    // ===== Language server support =====
+
+    /// Recovery copy to write or delete: the text once unsaved changes have
+    /// not changed for `idle` (or after `max_edits` edits), or the copy to
+    /// delete once the changes are saved or undone. Read-only buffers and
+    /// buffers over `max_bytes` get no copy
+    pub fn take_recovery_update(
+        &mut self,
+        idle: Duration,
+        max_edits: u64,
+        max_bytes: usize,
+    ) -> Option<RecoveryUpdate> {
+        let (modified, size, revision) = {
+            let buffer = self.buffer.borrow();
+            (buffer.is_modified(), buffer.len_bytes(), buffer.revision())
+        };
+        if !modified || self.is_read_only() || size > max_bytes {
+            let id = self.buffer.recovery().clear()?;
+            return Some(RecoveryUpdate::Remove { id });
+        }
+
+        let version = (self.buffer.generation(), revision);
+        let id = {
+            let mut recovery = self.buffer.recovery();
+            if !recovery.take_update(version, idle, max_edits) {
+                return None;
+            }
+            recovery
+                .id
+                .get_or_insert_with(termide_session::RecoveryFile::new_id)
+                .clone()
+        };
+        Some(RecoveryUpdate::Write {
+            id,
+            path: self.file_path().map(Path::to_path_buf),
+            title: self.file_state.title.clone(),
+            text: self.buffer.borrow().text(),
+        })
+    }
+
+    /// When unsaved changes left for `idle` are due for a recovery copy
+    pub fn recovery_deadline(&self, idle: Duration) -> Option<Instant> {
+        self.buffer.recovery().deadline(idle)
+    }
+
+    /// Name of the recovery copy of the buffer, if one was written
+    pub fn recovery_id(&self) -> Option<String> {
+        self.buffer.recovery().id.clone()
+    }
+
+    /// Replace the text with recovered text as one undo step (undo goes
+    /// back to the file as on disk); false if the text is the same
+    pub fn restore_text(&mut self, text: &str) -> Result<bool> {
+        self.apply_formatted(text)
+    }
+
+    /// Language of the file for its language server (None for unnamed
+    /// buffers, plain text and large files)
+    pub fn lsp_language(&self) -> Option<&str> {
+        if self.is_large_file() || self.file_path().is_none() {
+            return None;
+        }
+        self.language()
+    }
+
+    /// Text of the file to send to its language server, once the text has
+    /// not changed for `debounce` since it was last sent
+    pub fn take_lsp_update(&mut self, debounce: Duration) -> Option<String> {
+        let version = TextVersion {
+            path: self.file_path()?.to_path_buf(),
+            language: self.lsp_language()?.to_string(),
+            generation: self.buffer.generation(),
+            revision: self.buffer.borrow().revision(),
+        };
+        self.lsp
+            .take_update(version, debounce)
+            .then(|| self.buffer.borrow().to_string())
+    }
+
+    /// Show the diagnostics published by the language server for the file
+    pub fn set_lsp_diagnostics(&mut self, diagnostics: &[Diagnostic]) {
+        self.lsp.set_diagnostics(diagnostics, &self.buffer.borrow());
+    }
+
+    /// Ask the application to jump to the definition of the symbol under
+    /// the cursor
+    pub(crate) fn request_definition(&mut self) {
+        let Some(path) = self.file_path().map(Path::to_path_buf) else {
+            self.status_message = Some(t().editor_definition_no_file().to_string());
+            return;
+        };
+        let line = self
+            .buffer
+            .borrow()
+            .line(self.cursor.line)
+            .unwrap_or_default();
+        self.pending_events.push(PanelEvent::GotoDefinition {
+            path,
+            line: self.cursor.line,
+            character: utf16_column(&line, self.cursor.column),
+        });
+    }
+
+    /// Run the formatter of the file's language on the buffer; with `save`
+    /// the file is saved once the formatter finished
+    pub(crate) fn format_document(&mut self, save: bool) -> Result<()> {
+        if let Some(job) = &mut self.format_job {
+            if save {
+                job.save = true;
+            } else {
+                self.status_message = Some(t().editor_format_busy().to_string());
+            }
+            return Ok(());
+        }
+        let Some(command) = self.formatter().map(str::to_string) else {
+            if save {
+                return self.save();
+            }
+            self.status_message = Some(t().editor_format_unavailable().to_string());
+            return Ok(());
+        };
+
+        self.close_search();
+        let text = self.buffer.borrow().text();
+        let version = (self.buffer.generation(), self.buffer.borrow().revision());
+        self.format_job = Some(FormatJob::spawn(
+            &command,
+            self.file_path(),
+            text,
+            Duration::from_millis(FORMAT_TIMEOUT_MS),
+            version,
+            save,
+        ));
+        self.status_message = Some(t().editor_formatting().to_string());
+        Ok(())
+    }
+
+    /// Formatter command line of the buffer's language
+    fn formatter(&self) -> Option<&str> {
+        self.config
+            .formatters
+            .get(self.language()?)
+            .map(String::as_str)
+    }
+
+    /// Check if Ctrl+S formats the file before writing it
+    ///
+    /// Config files are written right away so their settings apply.
+    fn formats_on_save(&self) -> bool {
+        let is_config = self.file_path().is_some_and(Config::is_config_file);
+        !is_config
+            && self.formatter().is_some()
+            && self
+                .language()
+                .is_some_and(|language| self.config.format_on_save.iter().any(|l| l == language))
+    }
+
+    /// Apply the outcome of a finished formatter and save the file if that
+    /// was requested
+    fn finish_format(&mut self, job: FormatJob, outcome: FormatOutcome) -> Vec<PanelEvent> {
+        let t = t();
+        let changed = job.generation != self.buffer.generation()
+            || job.revision != self.buffer.borrow().revision();
+        let (message, is_error) = match outcome {
+            // The text the formatter read is gone
+            _ if changed => (t.editor_format_discarded().to_string(), true),
+            FormatOutcome::Formatted(text) => match self.apply_formatted(&text) {
+                Ok(true) => (t.editor_formatted().to_string(), false),
+                Ok(false) => (t.editor_format_unchanged().to_string(), false),
+                Err(e) => (t.editor_format_failed(&e.to_string()), true),
+            },
+            FormatOutcome::Failed(error) => {
+                log::warn!("Formatter '{}' failed: {}", job.command, error);
+                let first_line = error.lines().next().unwrap_or_default();
+                (t.editor_format_failed(first_line), true)
+            }
+            FormatOutcome::TimedOut => {
+                log::warn!("Formatter '{}' timed out", job.command);
+                (t.editor_format_timed_out(FORMAT_TIMEOUT_MS / 1000), true)
+            }
+        };
+
+        // A failed formatter does not keep the file from being saved
+        if job.save {
+            if let Err(e) = self.save() {
+                return vec![PanelEvent::SetStatusMessage {
+                    message: e.to_string(),
+                    is_error: true,
+                }];
+            }
+        }
+        // Messages of saving (cleanup, write method) replace a success
+        let message = match self.status_message.take() {
+            Some(saved) if !is_error => saved,
+            _ => message,
+        };
+        vec![PanelEvent::SetStatusMessage { message, is_error }]
+    }
+
+    /// Replace the changed lines with the formatted text as one undo step,
+    /// keeping the cursor on the same code; false if nothing changed
+    fn apply_formatted(&mut self, text: &str) -> Result<bool> {
+        let old = self.buffer.borrow().text();
+        let edits = format::line_edits(&old, text);
+        if edits.is_empty() {
+            return Ok(false);
+        }
+        let (line, column) = format::reanchor(&old, text, self.cursor.line, self.cursor.column);
+        self.buffer.borrow_mut().replace_line_ranges(&edits)?;
+
+        self.cursor.line = line;
+        self.cursor.column = column;
+        self.selection = None;
+        self.input.preferred_column = None;
+        self.clamp_cursor();
+        self.render_cache
+            .highlight
+            .invalidate_range(0, self.buffer.borrow().line_count());
+        self.schedule_git_diff_update();
+        Ok(true)
+    }
+
+    /// Dictionary to check the buffer with: all text of plain text files,
+    /// comments and strings of highlighted code (None when spell checking
+    /// is off)
+    fn spell_dictionary(&self) -> Option<Arc<Dictionary>> {
+        let dictionary = self.config.spell_dictionary.as_ref()?;
+        let checked = match self.language() {
+            None => true,
+            Some(language) => {
+                language != LOG_LANGUAGE
+                    && self.render_cache.highlight.current_syntax() == Some(language)
+            }
+        };
+        checked.then(|| dictionary.clone())
+    }
+
+    /// Underlines of the misspelled words of the visible lines
+    fn spelling_spans(&mut self, theme: &Theme) -> BTreeMap<usize, Vec<DiagnosticSpan>> {
+        let dictionary = self.spell_dictionary();
+        let checked = dictionary.is_some();
+        self.render_cache.highlight.set_dictionary(dictionary);
+        let mut spans = BTreeMap::new();
+        if !checked {
+            return spans;
+        }
+
+        let buffer = self.buffer.borrow();
+        let end = self.viewport.bottom_line().min(buffer.line_count());
+        for line in self.viewport.top_line..end {
+            if self.viewport.folds.is_hidden(line) {
+                continue;
+            }
+            // The part of the line passed to the highlighter when rendering
+            let Some(part) =
+                rendering::highlighted_part(&buffer, line, self.config.max_highlight_columns)
+            else {
+                continue;
+            };
+            let text = part.text.as_str();
+            let line_spans: Vec<DiagnosticSpan> = self
+                .render_cache
+                .highlight
+                .misspellings(line, text)
+                .iter()
+                .map(|range| DiagnosticSpan {
+                    start: text[..range.start].graphemes(true).count(),
+                    end: Some(text[..range.end].graphemes(true).count()),
+                    color: theme.disabled,
+                })
+                .collect();
+            if !line_spans.is_empty() {
+                spans.insert(line, line_spans);
+            }
+        }
+        spans
+    }
+
+    /// Offer corrections of the misspelled word under the cursor
+    pub(crate) fn open_spelling_suggestions(&mut self) {
+        let t = t();
+        let Some(dictionary) = self.spell_dictionary() else {
+            self.status_message = Some(t.editor_spell_check_off().to_string());
+            return;
+        };
+        if !dictionary.is_available() {
+            self.status_message = Some(t.editor_spell_no_dictionary().to_string());
+            return;
+        }
+
+        let line = self.cursor.line;
+        let part = rendering::highlighted_part(
+            &self.buffer.borrow(),
+            line,
+            self.config.max_highlight_columns,
+        );
+        let Some(part) = part else {
+            return;
+        };
+        let text = part.text.as_str();
+        // Check the line as typed, not as last parsed
+        self.sync_line_edits();
+        self.render_cache.document_edited = None;
+        self.sync_highlight_document();
+        self.render_cache
+            .highlight
+            .set_dictionary(Some(dictionary.clone()));
+        let word = self
+            .render_cache
+            .highlight
+            .misspellings(line, text)
+            .iter()
+            .map(|range| {
+                let start = text[..range.start].graphemes(true).count();
+                (start, &text[range.clone()])
+            })
+            .find(|(start, word)| {
+                (*start..=start + word.graphemes(true).count()).contains(&self.cursor.column)
+            });
+        let Some((column, word)) = word else {
+            self.status_message = Some(t.editor_spell_no_word().to_string());
+            return;
+        };
+
+        let suggestions = dictionary.suggestions(word, constants::SPELLING_SUGGESTIONS);
+        let mut labels = suggestions.clone();
+        labels.push(t.editor_spell_add_word().to_string());
+        let modal = SelectModal::single(t.modal_spelling_title(word), "", labels);
+        self.modal_request = Some((
+            PendingAction::CorrectSpelling {
+                line,
+                column,
+                word: word.to_string(),
+                suggestions,
+            },
+            ActiveModal::Select(Box::new(modal)),
+        ));
+    }
+
+    /// Replace a misspelled word at a position with a suggestion, or add
+    /// it to the user dictionary (`replacement` None)
+    pub fn correct_spelling(
+        &mut self,
+        line: usize,
+        column: usize,
+        word: &str,
+        replacement: Option<&str>,
+    ) -> Result<()> {
+        let Some(replacement) = replacement else {
+            if let Some(dictionary) = &self.config.spell_dictionary {
+                dictionary.add_word(word)?;
+                self.status_message = Some(t().editor_spell_word_added(word));
+            }
+            return Ok(());
+        };
+
+        // The text may have changed while the list was open
+        let start = Cursor::at(line, column);
+        let end = Cursor::at(line, column + word.graphemes(true).count());
+        let unchanged = self.buffer.borrow().line(line).is_some_and(|text| {
+            text.graphemes(true)
+                .skip(column)
+                .take(end.column - column)
+                .collect::<String>()
+                == word
+        });
+        if !unchanged {
+            return Ok(());
         }
+        self.close_search();
+        self.cursor = self
+            .buffer
+            .borrow_mut()
+            .replace_range(&start, &end, replacement)?;
+        self.selection = None;
+        self.input.preferred_column = None;
+        self.invalidate_cache_after_edit(line);
+        Ok(())
     }
 
     // ===== LogViewer support methods =====
 
     /// Get immutable reference to buffer.
-    pub fn buffer(&self) -> &TextBuffer {
-        &self.buffer
+    pub fn buffer(&self) -> Ref<'_, TextBuffer> {
+        self.buffer.borrow()
     }
 
     /// Get mutable reference to buffer.
-    pub fn buffer_mut(&mut self) -> &mut TextBuffer {
-        &mut self.buffer
+    pub fn buffer_mut(&mut self) -> impl DerefMut<Target = TextBuffer> + '_ {
+        self.buffer.borrow_mut()
     }
 
     /// Get immutable reference to viewport.
@@ -434,10 +1639,48 @@ impl Editor {
 
     /// Set cursor to specific line (for log viewer scroll-to-end).
     pub fn set_cursor_line(&mut self, line: usize) {
-        self.cursor.line = line.min(self.buffer.line_count().saturating_sub(1));
+        self.cursor.line = line.min(self.buffer.borrow().line_count().saturating_sub(1));
         self.cursor.column = 0;
     }
 
+    /// Move cursor to the start of a line, recording the jump
+    pub fn goto_line(&mut self, line: usize) {
+        self.jumps.push(self.cursor);
+        self.close_search();
+        self.selection = None;
+        self.input.preferred_column = None;
+        self.set_cursor_line(line);
+    }
+
+    /// Move cursor to a line and column (clamped to the line), recording the jump
+    pub fn goto_position(&mut self, line: usize, column: usize) {
+        self.goto_line(line);
+        self.cursor.column = column;
+        self.clamp_cursor();
+    }
+
+    /// Go back to the position before the last jump
+    pub(crate) fn jump_back(&mut self) {
+        if let Some(position) = self.jumps.back(self.cursor) {
+            self.jump_to(position);
+        }
+    }
+
+    /// Go forward to the position left by going back
+    pub(crate) fn jump_forward(&mut self) {
+        if let Some(position) = self.jumps.forward(self.cursor) {
+            self.jump_to(position);
+        }
+    }
+
+    /// Move cursor to a jump list position (clamped to the buffer)
+    fn jump_to(&mut self, position: Cursor) {
+        self.prepare_for_navigation();
+        self.input.preferred_column = None;
+        self.cursor = position;
+        self.clamp_cursor();
+    }
+
     /// Render with custom highlighter (for LogViewer).
     pub fn render_with_highlighter<H: termide_highlight::LineHighlighter>(
         &mut self,
@@ -456,16 +1699,16 @@ impl Editor {
         } else {
             0
         };
-        self.render_cache.use_smart_wrap = false;
+        self.render_cache.wrap_mode = WrapMode::Hard;
 
         self.viewport.resize(content_width, content_height);
 
-        let use_smart_wrap = if self.config.word_wrap && content_width > 0 {
-            self.should_use_smart_wrap(config)
+        let wrap_mode = if self.config.word_wrap && content_width > 0 {
+            self.wrap_mode(config)
         } else {
-            false
+            WrapMode::Hard
         };
-        self.render_cache.use_smart_wrap = use_smart_wrap;
+        self.render_cache.wrap_mode = wrap_mode;
 
         let virtual_lines_total = self.virtual_line_count(config);
         self.render_cache.virtual_line_count = virtual_lines_total;
@@ -479,10 +1722,10 @@ impl Editor {
         }
 
         // Render with custom highlighter
-        rendering::render_editor_content(
+        let cursor_cell = rendering::render_editor_content(
             buf,
             area,
-            &self.buffer,
+            &self.buffer.borrow(),
             &self.viewport,
             &self.cursor,
             &self.git.diff_cache,
@@ -490,13 +1733,47 @@ impl Editor {
             highlighter,
             &self.search.state,
             &self.selection,
+            self.lsp.spans(theme),
+            BTreeMap::new(),
+            self.bookmarks.lines(&self.buffer.borrow()),
+            WhitespaceMarkers::new(self.config.render_whitespace, &config.editor, theme),
+            self.config.max_highlight_columns,
             theme,
             config.editor.show_git_diff,
             self.config.word_wrap,
-            use_smart_wrap,
+            wrap_mode,
             content_width,
             content_height,
         );
+        self.render_scrollbar(area, buf, theme, config);
+        self.render_completion(area, buf, theme, cursor_cell);
+    }
+
+    /// Render the completion popup at the start of the completed word
+    fn render_completion(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        theme: &Theme,
+        cursor_cell: Option<(u16, u16)>,
+    ) {
+        // Edits made in another view may have moved the cursor away
+        let cursor = self.cursor;
+        self.completion = self.completion.take().filter(|completion| {
+            completion.start.line == cursor.line && completion.start.column <= cursor.column
+        });
+        let (Some(completion), Some((x, y))) = (&self.completion, cursor_cell) else {
+            return;
+        };
+        let word_x = x.saturating_sub((cursor.column - completion.start.column) as u16);
+        rendering::completion_popup::render_completion_popup(
+            buf,
+            area,
+            (word_x.max(area.x), y),
+            &completion.items,
+            completion.selected,
+            theme,
+        );
     }
 
     /// Check if visual movement should be used (word wrap enabled and width cached).
@@ -504,6 +1781,23 @@ impl Editor {
         self.config.word_wrap && self.render_cache.content_width > 0
     }
 
+    /// Check if Up/Down, Home/End and PageUp/PageDown should move by
+    /// wrapped rows (`visual_line_navigation`) rather than whole lines.
+    fn should_use_visual_navigation(&self) -> bool {
+        self.config.visual_line_navigation && self.should_use_visual_movement()
+    }
+
+    /// Move the cursor to the column it had when vertical movement began,
+    /// so passing through shorter lines does not lose it.
+    fn restore_preferred_column(&mut self, column: usize, maintain_preferred: bool) {
+        if maintain_preferred {
+            self.cursor.column = column;
+        } else {
+            self.input.preferred_column = None;
+        }
+        self.clamp_cursor();
+    }
+
     /// Ensure preferred column is set for vertical navigation.
     ///
     /// Sets preferred_column to visual offset within current visual row if not already set.
@@ -512,15 +1806,14 @@ impl Editor {
         if self.input.preferred_column.is_none() {
             // Calculate visual offset (position within current visual row)
             let visual_offset = if self.render_cache.content_width > 0 {
-                if let Some(line_text) = self.buffer.line(self.cursor.line) {
-                    let line_text = line_text.trim_end_matches('\n');
-                    let line_len = line_text.chars().count();
+                let line_wrap = word_wrap::line_wrap_points(
+                    &self.buffer.borrow(),
+                    self.cursor.line,
+                    self.render_cache.content_width,
+                    self.render_cache.wrap_mode,
+                );
+                if let Some((_visual_rows, wrap_points, line_len)) = line_wrap {
                     let cursor_col = self.cursor.column.min(line_len);
-                    let (_visual_rows, wrap_points) = word_wrap::get_line_wrap_points(
-                        line_text,
-                        self.render_cache.content_width,
-                        self.render_cache.use_smart_wrap,
-                    );
                     let current_visual_row =
                         wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
                     let visual_row_start = if current_visual_row == 0 {
@@ -561,26 +1854,39 @@ impl Editor {
             "yaml" => "YAML",
             "bash" => "Bash",
             "markdown" => "Markdown",
+            "jsx" => "JSX",
+            "php" => "PHP",
+            "haskell" => "Haskell",
+            "nix" => "Nix",
+            "lua" => "Lua",
+            "zig" => "Zig",
+            "kotlin" => "Kotlin",
+            "sql" => "SQL",
+            "dockerfile" => "Dockerfile",
+            "log" => "Log",
             _ => syntax_name,
         }
     }
 
     /// Move cursor up
     pub(crate) fn move_cursor_up(&mut self) {
+        let column = *self
+            .input
+            .preferred_column
+            .get_or_insert(self.cursor.column);
         let maintain_preferred = cursor::physical::move_up(&mut self.cursor);
-        if !maintain_preferred {
-            self.input.preferred_column = None;
-        }
-        self.clamp_cursor();
+        self.restore_preferred_column(column, maintain_preferred);
     }
 
     /// Move cursor down
     pub(crate) fn move_cursor_down(&mut self) {
-        let maintain_preferred = cursor::physical::move_down(&mut self.cursor, &self.buffer);
-        if !maintain_preferred {
-            self.input.preferred_column = None;
-        }
-        self.clamp_cursor();
+        let column = *self
+            .input
+            .preferred_column
+            .get_or_insert(self.cursor.column);
+        let maintain_preferred =
+            cursor::physical::move_down(&mut self.cursor, &self.buffer.borrow());
+        self.restore_preferred_column(column, maintain_preferred);
     }
 
     /// Move cursor up by one visual line (accounting for word wrap)
@@ -594,10 +1900,10 @@ impl Editor {
 
         if let Some(new_cursor) = cursor::visual::move_up(
             &self.cursor,
-            &self.buffer,
+            &self.buffer.borrow(),
             self.input.preferred_column,
             self.render_cache.content_width,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
         ) {
             self.cursor = new_cursor;
         }
@@ -616,10 +1922,10 @@ impl Editor {
 
         if let Some(new_cursor) = cursor::visual::move_down(
             &self.cursor,
-            &self.buffer,
+            &self.buffer.borrow(),
             self.input.preferred_column,
             self.render_cache.content_width,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
         ) {
             self.cursor = new_cursor;
         }
@@ -629,7 +1935,8 @@ impl Editor {
 
     /// Move cursor left
     pub(crate) fn move_cursor_left(&mut self) {
-        let maintain_preferred = cursor::physical::move_left(&mut self.cursor, &self.buffer);
+        let maintain_preferred =
+            cursor::physical::move_left(&mut self.cursor, &self.buffer.borrow());
         if !maintain_preferred {
             self.input.preferred_column = None;
         }
@@ -637,16 +1944,18 @@ impl Editor {
 
     /// Move cursor right
     pub(crate) fn move_cursor_right(&mut self) {
-        let maintain_preferred = cursor::physical::move_right(&mut self.cursor, &self.buffer);
+        let maintain_preferred =
+            cursor::physical::move_right(&mut self.cursor, &self.buffer.borrow());
         if !maintain_preferred {
             self.input.preferred_column = None;
         }
         self.clamp_cursor();
     }
 
-    /// Move cursor to start of line
+    /// Move cursor to the first non-whitespace character or start of line
     pub(crate) fn move_to_line_start(&mut self) {
-        let maintain_preferred = cursor::physical::move_to_line_start(&mut self.cursor);
+        let maintain_preferred =
+            cursor::physical::move_to_line_start(&mut self.cursor, &self.buffer.borrow());
         if !maintain_preferred {
             self.input.preferred_column = None;
         }
@@ -654,7 +1963,8 @@ impl Editor {
 
     /// Move cursor to end of line
     pub(crate) fn move_to_line_end(&mut self) {
-        let maintain_preferred = cursor::physical::move_to_line_end(&mut self.cursor, &self.buffer);
+        let maintain_preferred =
+            cursor::physical::move_to_line_end(&mut self.cursor, &self.buffer.borrow());
         if !maintain_preferred {
             self.input.preferred_column = None;
         }
@@ -671,12 +1981,19 @@ impl Editor {
             return;
         }
 
-        self.cursor.column = cursor::visual::move_to_visual_line_start(
+        let start = cursor::visual::move_to_visual_line_start(
             &self.cursor,
-            &self.buffer,
+            &self.buffer.borrow(),
             self.render_cache.content_width,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
         );
+        // On the first row or at the start of a wrapped row, Home toggles
+        // between indentation and line start
+        if start == 0 || start == self.cursor.column {
+            self.move_to_line_start();
+        } else {
+            self.cursor.column = start;
+        }
     }
 
     /// Move cursor to end of visual line (for wrapped lines)
@@ -692,17 +2009,21 @@ impl Editor {
 
         self.cursor.column = cursor::visual::move_to_visual_line_end(
             &self.cursor,
-            &self.buffer,
+            &self.buffer.borrow(),
             self.render_cache.content_width,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
         );
     }
 
     /// Move cursor page up
     pub(crate) fn page_up(&mut self) {
         let page_size = self.viewport.height;
+        let column = *self
+            .input
+            .preferred_column
+            .get_or_insert(self.cursor.column);
         let (should_scroll, scroll_amount) = cursor::jump::page_up(&mut self.cursor, page_size);
-        self.clamp_cursor();
+        self.restore_preferred_column(column, true);
         if should_scroll {
             self.viewport.scroll_up(scroll_amount);
         }
@@ -711,9 +2032,13 @@ impl Editor {
     /// Move cursor page down
     pub(crate) fn page_down(&mut self) {
         let page_size = self.viewport.height;
+        let column = *self
+            .input
+            .preferred_column
+            .get_or_insert(self.cursor.column);
         let (should_scroll, scroll_amount) =
-            cursor::jump::page_down(&mut self.cursor, &self.buffer, page_size);
-        self.clamp_cursor();
+            cursor::jump::page_down(&mut self.cursor, &self.buffer.borrow(), page_size);
+        self.restore_preferred_column(column, true);
         if should_scroll {
             // Use cached virtual line count for viewport scroll (accounts for deletion markers)
             self.viewport
@@ -734,10 +2059,10 @@ impl Editor {
         let page_size = self.viewport.height;
         self.cursor = cursor::visual::page_up(
             &self.cursor,
-            &self.buffer,
+            &self.buffer.borrow(),
             self.input.preferred_column,
             self.render_cache.content_width,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
             page_size,
         );
 
@@ -758,10 +2083,10 @@ impl Editor {
         let page_size = self.viewport.height;
         self.cursor = cursor::visual::page_down(
             &self.cursor,
-            &self.buffer,
+            &self.buffer.borrow(),
             self.input.preferred_column,
             self.render_cache.content_width,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
             page_size,
         );
 
@@ -780,7 +2105,8 @@ impl Editor {
 
     /// Move cursor to end of document
     pub(crate) fn move_to_document_end(&mut self) {
-        let (new_cursor, should_scroll) = cursor::physical::move_to_document_end(&self.buffer);
+        let (new_cursor, should_scroll) =
+            cursor::physical::move_to_document_end(&self.buffer.borrow());
         self.cursor = new_cursor;
         if should_scroll {
             // Use cached virtual line count for viewport scroll
@@ -791,7 +2117,7 @@ impl Editor {
 
     /// Select all
     pub(crate) fn select_all(&mut self) {
-        let (new_selection, new_cursor) = selection::select_all(&self.buffer);
+        let (new_selection, new_cursor) = selection::select_all(&self.buffer.borrow());
         self.selection = Some(new_selection);
         self.cursor = new_cursor;
     }
@@ -812,27 +2138,20 @@ impl Editor {
 
     /// Get selected text
     fn get_selected_text(&self) -> Option<String> {
-        selection::get_selected_text(&self.buffer, self.selection.as_ref())
+        selection::get_selected_text(&self.buffer.borrow(), self.selection.as_ref())
     }
 
     /// Delete selected text
     fn delete_selection(&mut self) -> Result<()> {
-        if let Some(new_cursor) =
-            selection::delete_selection(&mut self.buffer, self.selection.as_ref())?
-        {
+        let new_cursor =
+            selection::delete_selection(&mut self.buffer.borrow_mut(), self.selection.as_ref())?;
+        if let Some(new_cursor) = new_cursor {
             self.cursor = new_cursor;
             self.selection = None;
             self.input.preferred_column = None; // Reset preferred column on text edit
 
-            // Invalidate highlighting cache
-            selection::invalidate_cache_after_deletion(
-                &mut self.render_cache.highlight,
-                new_cursor.line,
-                self.buffer.line_count(),
-            );
-
-            // Schedule git diff update
-            self.schedule_git_diff_update();
+            // Invalidate highlighting cache and schedule git update
+            self.invalidate_cache_after_edit(new_cursor.line);
         }
         Ok(())
     }
@@ -845,6 +2164,15 @@ impl Editor {
         Ok(())
     }
 
+    /// Copy the rectangle between the selection's corners to clipboard,
+    /// pasted back as a block
+    pub(crate) fn copy_block_to_clipboard(&mut self) -> Result<()> {
+        let block_text = selection::get_block_text(&self.buffer.borrow(), self.selection.as_ref());
+        let result = clipboard::copy_block_to_clipboard(block_text);
+        self.status_message = Some(result.status_message);
+        Ok(())
+    }
+
     /// Cut selected text to clipboard
     pub(crate) fn cut_to_clipboard(&mut self) -> Result<()> {
         let selected_text = self.get_selected_text();
@@ -866,40 +2194,147 @@ impl Editor {
         self.delete_selection()?;
 
         // Paste from clipboard using clipboard module
-        if let Some((new_cursor, start_line, is_multiline)) =
-            clipboard::paste_from_clipboard(&mut self.buffer, &self.cursor)?
-        {
+        let pasted = clipboard::paste_from_clipboard(&mut self.buffer.borrow_mut(), &self.cursor)?;
+        self.apply_paste(pasted);
+        Ok(())
+    }
+
+    /// Paste from clipboard re-indented to the cursor line
+    pub(crate) fn paste_reindented(&mut self) -> Result<()> {
+        self.close_search();
+        self.delete_selection()?;
+        let (style, tab_width) = (self.indent_style(), self.config.tab_size);
+        let pasted = clipboard::paste_reindented(
+            &mut self.buffer.borrow_mut(),
+            &self.cursor,
+            style,
+            tab_width,
+        )?;
+        self.apply_paste(pasted);
+        Ok(())
+    }
+
+    /// Insert text pasted into the terminal at the cursor, replacing the
+    /// selection
+    pub fn paste_text(&mut self, text: &str) -> Result<()> {
+        self.close_search();
+        self.delete_selection()?;
+        let pasted = clipboard::paste_text(
+            &mut self.buffer.borrow_mut(),
+            &self.cursor,
+            Some(text.to_string()),
+        )?;
+        self.apply_paste(pasted);
+        Ok(())
+    }
+
+    /// Paste the PRIMARY selection at the cursor (middle click)
+    fn paste_from_primary(&mut self) -> Result<()> {
+        self.close_search();
+        self.selection = None;
+        let pasted = clipboard::paste_from_primary(&mut self.buffer.borrow_mut(), &self.cursor)?;
+        self.apply_paste(pasted);
+        Ok(())
+    }
+
+    /// Move the cursor after pasted text and refresh caches
+    fn apply_paste(&mut self, pasted: Option<(Cursor, usize)>) {
+        if let Some((new_cursor, start_line)) = pasted {
             self.cursor = new_cursor;
             self.input.preferred_column = None; // Reset preferred column on text edit
             self.clamp_cursor();
 
             // Invalidate highlighting cache and schedule git update
-            self.invalidate_cache_after_edit(start_line, is_multiline);
+            self.invalidate_cache_after_edit(start_line);
         }
-        Ok(())
     }
 
     /// Duplicate current line or selected lines
     pub(crate) fn duplicate_line(&mut self) -> Result<()> {
-        let result =
-            text_editing::duplicate_line(&mut self.buffer, &self.cursor, self.selection.as_ref())?;
+        let result = text_editing::duplicate_line(
+            &mut self.buffer.borrow_mut(),
+            &self.cursor,
+            self.selection.as_ref(),
+        )?;
 
+        // Cursor and selection stay on the same text in the copy
+        let offset = result.new_cursor.line - self.cursor.line;
         self.cursor = result.new_cursor;
         self.input.preferred_column = None; // Reset preferred column on text edit
         self.clamp_cursor();
+        if let Some(selection) = &mut self.selection {
+            selection.anchor.line += offset;
+            selection.active.line += offset;
+        }
+
+        // Invalidate highlighting cache and schedule git update
+        self.invalidate_cache_after_edit(result.start_line);
+
+        Ok(())
+    }
+
+    /// Lines covered by the selection, or the cursor line
+    fn selected_line_range(&self) -> (usize, usize) {
+        match &self.selection {
+            Some(selection) if !selection.is_empty() => {
+                let (start, end) = (selection.start(), selection.end());
+                // A selection ending at column 0 does not include that line
+                let end_line = if end.column == 0 && end.line > start.line {
+                    end.line - 1
+                } else {
+                    end.line
+                };
+                (start.line, end_line)
+            }
+            _ => (self.cursor.line, self.cursor.line),
+        }
+    }
+
+    /// Delete the cursor line or selected lines (Ctrl+Shift+K)
+    pub(crate) fn delete_lines(&mut self) -> Result<()> {
+        self.close_search();
+        let (start_line, end_line) = self.selected_line_range();
+        let result = text_editing::delete_lines(
+            &mut self.buffer.borrow_mut(),
+            start_line,
+            end_line,
+            self.cursor.column,
+        )?;
 
-        // Clear selection
         self.selection = None;
+        self.cursor = result.new_cursor;
+        self.clamp_cursor();
+        self.invalidate_cache_after_edit(result.start_line);
+        Ok(())
+    }
 
-        // Invalidate highlighting cache and schedule git update
-        self.invalidate_cache_after_edit(result.start_line, result.is_multiline);
+    /// Move the cursor line or selected lines up or down (Alt+Shift+Up/Down)
+    pub(crate) fn move_lines(&mut self, up: bool) -> Result<()> {
+        self.close_search();
+        let (start_line, end_line) = self.selected_line_range();
+        let result =
+            text_editing::move_lines(&mut self.buffer.borrow_mut(), start_line, end_line, up)?;
+        let Some(result) = result else {
+            return Ok(());
+        };
 
+        // Cursor and selection move with the lines
+        let shift = |cursor: &mut Cursor| {
+            cursor.line = if up { cursor.line - 1 } else { cursor.line + 1 };
+        };
+        shift(&mut self.cursor);
+        if let Some(selection) = &mut self.selection {
+            shift(&mut selection.anchor);
+            shift(&mut selection.active);
+        }
+        self.clamp_cursor();
+        self.invalidate_cache_after_edit(result.start_line);
         Ok(())
     }
 
     /// Clamp cursor position to valid values
     fn clamp_cursor(&mut self) {
-        cursor::physical::clamp_cursor(&mut self.cursor, &self.buffer);
+        cursor::physical::clamp_cursor(&mut self.cursor, &self.buffer.borrow());
     }
 
     /// Insert character at cursor position
@@ -907,156 +2342,349 @@ impl Editor {
         // Close search mode when editing begins
         self.close_search();
 
+        if self.config.auto_close_brackets && self.insert_auto_pair(ch)? {
+            return Ok(());
+        }
+
         // Delete selected text before insertion
         self.delete_selection()?;
 
-        let result = text_editing::insert_char(&mut self.buffer, &self.cursor, ch)?;
+        let result = text_editing::insert_char(&mut self.buffer.borrow_mut(), &self.cursor, ch)?;
         self.cursor = result.new_cursor;
         self.input.preferred_column = None;
         self.clamp_cursor();
 
         // Invalidate highlighting cache and schedule git update
-        self.invalidate_cache_after_edit(result.start_line, result.is_multiline);
+        self.invalidate_cache_after_edit(result.start_line);
 
         Ok(())
     }
 
-    /// Insert tab (spaces based on tab_size config)
-    pub(crate) fn insert_tab(&mut self) -> Result<()> {
-        // Close search mode when editing begins
-        self.close_search();
+    /// Handle typed bracket or quote: wrap the selection, skip over the
+    /// closing character or insert the pair.
+    /// Returns false if the character needs a plain insert.
+    fn insert_auto_pair(&mut self, ch: char) -> Result<bool> {
+        let close = text_editing::closing_pair(ch);
 
-        // Delete selected text before insertion
-        self.delete_selection()?;
+        if let Some(sel) = self.selection.as_ref().filter(|sel| !sel.is_empty()) {
+            let Some(close) = close else {
+                return Ok(false);
+            };
+            let (start, end) = (sel.start(), sel.end());
+            let (anchor, active) = (sel.anchor, sel.active);
+            self.buffer
+                .borrow_mut()
+                .surround(&start, &end, &ch.to_string(), &close.to_string())?;
+
+            // Keep the wrapped text selected
+            let shift = |pos: Cursor| {
+                if pos.line == start.line {
+                    Cursor::at(pos.line, pos.column + 1)
+                } else {
+                    pos
+                }
+            };
+            self.cursor = shift(active);
+            self.selection = Some(Selection::new(shift(anchor), self.cursor));
+            self.input.preferred_column = None;
 
-        // Insert tab_size spaces
-        let spaces = " ".repeat(self.config.tab_size);
-        for ch in spaces.chars() {
-            let result = text_editing::insert_char(&mut self.buffer, &self.cursor, ch)?;
-            self.cursor = result.new_cursor;
+            // Invalidate highlighting cache and schedule git update
+            self.invalidate_cache_after_edit(start.line);
+            return Ok(true);
         }
 
-        self.input.preferred_column = None;
-        self.clamp_cursor();
+        let (prev, next) = text_editing::chars_around(&self.buffer.borrow(), &self.cursor);
+        if text_editing::should_skip_closing(ch, prev, next) {
+            self.selection = None;
+            self.cursor.column += 1;
+            self.input.preferred_column = None;
+            return Ok(true);
+        }
+
+        let Some(close) = close.filter(|_| text_editing::should_auto_close(ch, prev, next)) else {
+            return Ok(false);
+        };
+        self.selection = None;
+        let result =
+            text_editing::insert_pair(&mut self.buffer.borrow_mut(), &self.cursor, ch, close)?;
+        self.cursor = result.new_cursor;
+        self.input.preferred_column = None;
 
         // Invalidate highlighting cache and schedule git update
-        self.invalidate_cache_after_edit(self.cursor.line, false);
+        self.invalidate_cache_after_edit(result.start_line);
+
+        Ok(true)
+    }
+
+    /// Indentation style of the buffer: the configured one, or detected
+    /// (config tab size with spaces if not detected)
+    pub fn indent_style(&self) -> IndentStyle {
+        match self.config.indent_style {
+            IndentMode::Spaces => IndentStyle::Spaces(self.config.tab_size),
+            IndentMode::Tabs => IndentStyle::Tabs,
+            IndentMode::Auto => self
+                .buffer
+                .borrow()
+                .indent_style()
+                .unwrap_or(IndentStyle::Spaces(self.config.tab_size)),
+        }
+    }
 
-        Ok(())
+    /// Visual width of one indent level
+    fn indent_width(&self) -> usize {
+        match self.indent_style() {
+            IndentStyle::Tabs => self.config.tab_size,
+            IndentStyle::Spaces(width) => width,
+        }
     }
 
-    /// Indent selected lines (or current line if no selection)
-    pub(crate) fn indent_lines(&mut self) -> Result<()> {
+    /// Insert tab (one indent level in the buffer's indentation style)
+    pub(crate) fn insert_tab(&mut self) -> Result<()> {
         // Close search mode when editing begins
         self.close_search();
 
-        let tab_size = self.config.tab_size;
-        let indent = " ".repeat(tab_size);
-
-        // Get line range from selection or current cursor
-        let (start_line, end_line) = if let Some(ref sel) = self.selection {
-            (sel.start().line, sel.end().line)
-        } else {
-            (self.cursor.line, self.cursor.line)
-        };
+        // Delete selected text before insertion
+        self.delete_selection()?;
 
-        // Insert indent at the beginning of each line (iterate in reverse to avoid index shifts)
-        for line_idx in (start_line..=end_line).rev() {
-            let cursor_at_start = Cursor::at(line_idx, 0);
-            self.buffer.insert(&cursor_at_start, &indent)?;
+        let indent = self.indent_style().unit();
+        for ch in indent.chars() {
+            let result =
+                text_editing::insert_char(&mut self.buffer.borrow_mut(), &self.cursor, ch)?;
+            self.cursor = result.new_cursor;
         }
 
-        // Update cursor position
-        self.cursor.column += tab_size;
+        self.input.preferred_column = None;
+        self.clamp_cursor();
+
+        // Invalidate highlighting cache and schedule git update
+        self.invalidate_cache_after_edit(self.cursor.line);
+
+        Ok(())
+    }
+
+    /// Lines covered by the selection (or the cursor line); a selection
+    /// ending at the start of a line doesn't include that line
+    fn selected_lines(&self) -> (usize, usize) {
+        match self.selection {
+            Some(ref sel) if sel.end().line > sel.start().line && sel.end().column == 0 => {
+                (sel.start().line, sel.end().line - 1)
+            }
+            Some(ref sel) => (sel.start().line, sel.end().line),
+            None => (self.cursor.line, self.cursor.line),
+        }
+    }
 
-        // Update selection positions if present
+    /// Move cursor and selection along with the leading whitespace
+    /// inserted (`indent`) or removed per line of `start_line..`.
+    ///
+    /// Selection ends at column 0 stay there, so the selection keeps
+    /// covering whole lines.
+    fn shift_after_indent(&mut self, start_line: usize, changed: &[usize], indent: bool) {
+        let has_selection = self.selection.is_some();
+        let shift = |pos: &mut Cursor| {
+            let Some(&count) = pos
+                .line
+                .checked_sub(start_line)
+                .and_then(|index| changed.get(index))
+            else {
+                return;
+            };
+            if !indent {
+                pos.column = pos.column.saturating_sub(count);
+            } else if pos.column > 0 || !has_selection {
+                pos.column += count;
+            }
+        };
+        shift(&mut self.cursor);
         if let Some(ref mut sel) = self.selection {
-            sel.anchor.column += tab_size;
-            sel.active.column += tab_size;
+            shift(&mut sel.anchor);
+            shift(&mut sel.active);
         }
 
         self.input.preferred_column = None;
         self.clamp_cursor();
 
         // Invalidate highlighting cache and schedule git update
-        self.invalidate_cache_after_edit(start_line, true);
+        self.invalidate_cache_after_edit(start_line);
         self.schedule_git_diff_update();
+    }
+
+    /// Indent selected lines (or current line if no selection) by one
+    /// level as a single undo step
+    pub(crate) fn indent_lines(&mut self) -> Result<()> {
+        // Close search mode when editing begins
+        self.close_search();
+
+        let indent = self.indent_style().unit();
+        let (start_line, end_line) = self.selected_lines();
+        let inserted = self
+            .buffer
+            .borrow_mut()
+            .indent_lines(start_line, end_line, &indent)?;
+        self.shift_after_indent(start_line, &inserted, true);
 
         Ok(())
     }
 
-    /// Unindent selected lines (or current line if no selection)
+    /// Unindent selected lines (or current line if no selection) by up to
+    /// one level as a single undo step
     pub(crate) fn unindent_lines(&mut self) -> Result<()> {
         // Close search mode when editing begins
         self.close_search();
 
-        let tab_size = self.config.tab_size;
+        let indent_width = self.indent_width();
+        let (start_line, end_line) = self.selected_lines();
+        let removed = self.buffer.borrow_mut().outdent_lines(
+            start_line,
+            end_line,
+            indent_width,
+            self.config.tab_size,
+        )?;
+        self.shift_after_indent(start_line, &removed, false);
 
-        // Get line range from selection or current cursor
-        let (start_line, end_line) = if let Some(ref sel) = self.selection {
-            (sel.start().line, sel.end().line)
-        } else {
-            (self.cursor.line, self.cursor.line)
-        };
-
-        // Track how many spaces were removed from each line for cursor adjustment
-        let mut cursor_line_spaces_removed = 0;
-        let mut anchor_line_spaces_removed = 0;
-        let mut active_line_spaces_removed = 0;
-
-        // Remove up to tab_size spaces from the beginning of each line
-        for line_idx in (start_line..=end_line).rev() {
-            if let Some(line) = self.buffer.line(line_idx) {
-                // Count leading spaces (up to tab_size)
-                let spaces_to_remove = line
-                    .chars()
-                    .take(tab_size)
-                    .take_while(|c| *c == ' ')
-                    .count();
-
-                if spaces_to_remove > 0 {
-                    let start = Cursor::at(line_idx, 0);
-                    let end = Cursor::at(line_idx, spaces_to_remove);
-                    self.buffer.delete_range(&start, &end)?;
-
-                    // Track spaces removed for cursor/selection adjustment
-                    if line_idx == self.cursor.line {
-                        cursor_line_spaces_removed = spaces_to_remove;
-                    }
-                    if let Some(ref sel) = self.selection {
-                        if line_idx == sel.anchor.line {
-                            anchor_line_spaces_removed = spaces_to_remove;
-                        }
-                        if line_idx == sel.active.line {
-                            active_line_spaces_removed = spaces_to_remove;
-                        }
-                    }
-                }
+        Ok(())
+    }
+
+    /// Comment or uncomment selected lines (or current line) in the
+    /// file's language (single undo step)
+    pub(crate) fn toggle_comment(&mut self) -> Result<()> {
+        let style = self.language().and_then(termide_highlight::comment_style);
+        let (prefix, suffix) = match style {
+            Some(CommentStyle::Line(prefix)) => (prefix, ""),
+            Some(CommentStyle::Block(prefix, suffix)) => (prefix, suffix),
+            None => {
+                self.status_message = Some(t().editor_comment_unsupported().to_string());
+                return Ok(());
             }
-        }
+        };
 
-        // Update cursor position (subtract removed spaces, but don't go below 0)
-        self.cursor.column = self
-            .cursor
-            .column
-            .saturating_sub(cursor_line_spaces_removed);
+        self.close_search();
+
+        let (start_line, end_line) = self.selected_lines();
+        let old_lines: Vec<String> = (start_line..=end_line)
+            .map(|line_idx| {
+                self.buffer
+                    .borrow()
+                    .line(line_idx)
+                    .unwrap_or_default()
+                    .trim_end_matches('\n')
+                    .to_string()
+            })
+            .collect();
+
+        let Some(toggle) = self
+            .buffer
+            .borrow_mut()
+            .toggle_comments(start_line, end_line, prefix, suffix)?
+        else {
+            return Ok(());
+        };
 
-        // Update selection positions if present
+        let shift = |pos: &mut Cursor| {
+            if (start_line..=end_line).contains(&pos.line) {
+                pos.column =
+                    toggle.shift_column(&old_lines[pos.line - start_line], pos.column, prefix);
+            }
+        };
+        shift(&mut self.cursor);
         if let Some(ref mut sel) = self.selection {
-            sel.anchor.column = sel.anchor.column.saturating_sub(anchor_line_spaces_removed);
-            sel.active.column = sel.active.column.saturating_sub(active_line_spaces_removed);
+            shift(&mut sel.anchor);
+            shift(&mut sel.active);
         }
 
         self.input.preferred_column = None;
         self.clamp_cursor();
 
         // Invalidate highlighting cache and schedule git update
-        self.invalidate_cache_after_edit(start_line, true);
-        self.schedule_git_diff_update();
+        self.invalidate_cache_after_edit(start_line);
+
+        Ok(())
+    }
+
+    /// Convert indentation of the whole file between tabs and spaces (single undo step)
+    pub(crate) fn convert_indentation(&mut self) -> Result<()> {
+        self.close_search();
+
+        let tab_width = self.indent_width();
+        let target = match self.indent_style() {
+            IndentStyle::Tabs => IndentStyle::Spaces(self.config.tab_size),
+            IndentStyle::Spaces(_) => IndentStyle::Tabs,
+        };
+
+        let old_line_len = self.buffer.borrow().line_len_graphemes(self.cursor.line);
+        let changed = self
+            .buffer
+            .borrow_mut()
+            .convert_indentation(target, tab_width)?;
+
+        // Keep cursor at the same place relative to the line end
+        let new_line_len = self.buffer.borrow().line_len_graphemes(self.cursor.line);
+        self.cursor.column = (self.cursor.column + new_line_len).saturating_sub(old_line_len);
+        self.selection = None;
+        self.input.preferred_column = None;
+        self.clamp_cursor();
+
+        if changed > 0 {
+            self.render_cache
+                .highlight
+                .invalidate_range(0, self.buffer.borrow().line_count());
+            self.schedule_git_diff_update();
+        }
+
+        let t = t();
+        let style_name = match target {
+            IndentStyle::Tabs => t.editor_indent_tabs().to_string(),
+            IndentStyle::Spaces(width) => t.editor_indent_spaces(width),
+        };
+        self.status_message = Some(t.editor_indent_converted(&style_name, changed));
 
         Ok(())
     }
 
+    /// Switch line ending used on save between LF and CRLF (undoable)
+    pub(crate) fn toggle_line_ending(&mut self) {
+        let line_ending = self.buffer.borrow().line_ending().toggled();
+        self.buffer
+            .borrow_mut()
+            .set_line_ending(line_ending, &self.cursor);
+        self.status_message = Some(t().editor_line_ending_set(line_ending.name()));
+    }
+
+    /// Show all whitespace, or hide it when shown
+    pub(crate) fn toggle_whitespace(&mut self) {
+        self.config.render_whitespace = match self.config.render_whitespace {
+            RenderWhitespace::Off => RenderWhitespace::All,
+            _ => RenderWhitespace::Off,
+        };
+        self.status_message = Some(
+            if self.config.render_whitespace == RenderWhitespace::Off {
+                t().editor_whitespace_hidden()
+            } else {
+                t().editor_whitespace_shown()
+            }
+            .to_string(),
+        );
+    }
+
+    /// Turn word wrap on or off (kept off in large file mode)
+    pub(crate) fn toggle_word_wrap(&mut self) {
+        if self.is_large_file() {
+            return;
+        }
+        self.config.word_wrap = !self.config.word_wrap;
+        self.viewport.left_column = 0;
+        // The preferred column counts within wrapped rows only while wrapping
+        self.input.preferred_column = None;
+        self.status_message = Some(
+            if self.config.word_wrap {
+                t().editor_word_wrap_on()
+            } else {
+                t().editor_word_wrap_off()
+            }
+            .to_string(),
+        );
+    }
+
     /// Insert newline
     pub(crate) fn insert_newline(&mut self) -> Result<()> {
         // Close search mode when editing begins
@@ -1065,40 +2693,317 @@ impl Editor {
         // Delete selected text before insertion
         self.delete_selection()?;
 
-        let result = text_editing::insert_newline(&mut self.buffer, &self.cursor)?;
+        let result = text_editing::insert_newline(&mut self.buffer.borrow_mut(), &self.cursor)?;
         self.cursor = result.new_cursor;
         self.input.preferred_column = None; // Reset preferred column on text edit
         self.clamp_cursor();
 
         // Invalidate highlighting cache and schedule git update
-        self.invalidate_cache_after_edit(result.start_line, result.is_multiline);
+        self.invalidate_cache_after_edit(result.start_line);
 
         Ok(())
     }
 
     /// Delete character (backspace)
     pub(crate) fn backspace(&mut self) -> Result<()> {
-        if let Some(result) = text_editing::backspace(&mut self.buffer, &self.cursor)? {
+        // Empty bracket or quote pair is deleted as a whole
+        let pair = if self.config.auto_close_brackets {
+            text_editing::backspace_pair(&mut self.buffer.borrow_mut(), &self.cursor)?
+        } else {
+            None
+        };
+        let result = match pair {
+            Some(result) => Some(result),
+            None => text_editing::backspace(&mut self.buffer.borrow_mut(), &self.cursor)?,
+        };
+
+        if let Some(result) = result {
             self.cursor = result.new_cursor;
             self.input.preferred_column = None; // Reset preferred column on text edit
             self.clamp_cursor();
 
             // Invalidate highlighting cache and schedule git update
-            self.invalidate_cache_after_edit(result.start_line, result.is_multiline);
+            self.invalidate_cache_after_edit(result.start_line);
         }
         Ok(())
     }
 
     /// Delete character (delete)
     pub(crate) fn delete(&mut self) -> Result<()> {
-        if let Some(result) = text_editing::delete_char(&mut self.buffer, &self.cursor)? {
+        let result = text_editing::delete_char(&mut self.buffer.borrow_mut(), &self.cursor)?;
+        if let Some(result) = result {
             self.input.preferred_column = None; // Reset preferred column on text edit
                                                 // Invalidate highlighting cache and schedule git update
-            self.invalidate_cache_after_edit(result.start_line, result.is_multiline);
+            self.invalidate_cache_after_edit(result.start_line);
+        }
+        Ok(())
+    }
+
+    /// Delete to the start of the previous word (Ctrl+Backspace)
+    pub(crate) fn delete_word_backward(&mut self) -> Result<()> {
+        let result =
+            text_editing::delete_word_backward(&mut self.buffer.borrow_mut(), &self.cursor)?;
+        self.apply_word_edit(result);
+        Ok(())
+    }
+
+    /// Delete to the end of the next word (Ctrl+Delete)
+    pub(crate) fn delete_word_forward(&mut self) -> Result<()> {
+        let result =
+            text_editing::delete_word_forward(&mut self.buffer.borrow_mut(), &self.cursor)?;
+        self.apply_word_edit(result);
+        Ok(())
+    }
+
+    /// Swap the word at the cursor with the next one (Ctrl+T)
+    pub(crate) fn transpose_words(&mut self) -> Result<()> {
+        self.close_search();
+        self.selection = None;
+        let result = text_editing::transpose_words(&mut self.buffer.borrow_mut(), &self.cursor)?;
+        self.apply_word_edit(result);
+        Ok(())
+    }
+
+    /// Move the cursor after a word-wise edit and refresh caches
+    fn apply_word_edit(&mut self, result: Option<text_editing::EditResult>) {
+        if let Some(result) = result {
+            self.cursor = result.new_cursor;
+            self.input.preferred_column = None; // Reset preferred column on text edit
+            self.clamp_cursor();
+            self.invalidate_cache_after_edit(result.start_line);
+        }
+    }
+
+    /// Show, refine or close the completion popup for the word before the cursor
+    fn update_completion(&mut self) {
+        self.completion = self.find_completion();
+    }
+
+    /// Words completing the word typed before the cursor, if there are any
+    /// and enough of the word is typed
+    fn find_completion(&self) -> Option<Completion> {
+        if !self.config.autocomplete || self.config.read_only || self.is_large_file() {
+            return None;
+        }
+        let line = self.buffer.borrow().line(self.cursor.line)?;
+        let (start, prefix) = completion::word_before(&line, self.cursor.column)?;
+        if prefix.chars().count() < self.config.autocomplete_min_chars.max(1) {
+            return None;
+        }
+
+        let mut other_counts = HashMap::new();
+        if self.config.autocomplete_open_buffers {
+            let config = &self.config;
+            self.buffer.with_other_words(
+                |buffer| {
+                    Self::exceeds_large_file_threshold(
+                        config,
+                        buffer.len_bytes() as u64,
+                        buffer.line_count(),
+                    )
+                },
+                |words| {
+                    for (word, count) in words.words_with_prefix(&prefix) {
+                        *other_counts.entry(word.to_string()).or_default() += count;
+                    }
+                },
+            );
+        }
+        let items = self.buffer.with_words(|words| {
+            completion::rank_candidates(words, self.cursor.line, &prefix, other_counts)
+        });
+
+        (!items.is_empty()).then(|| Completion {
+            start: Cursor::at(self.cursor.line, start),
+            prefix,
+            items,
+            selected: 0,
+        })
+    }
+
+    /// Highlight the next or previous word of the completion popup
+    pub(crate) fn select_completion(&mut self, forward: bool) {
+        if let Some(completion) = &mut self.completion {
+            if forward {
+                completion.select_next();
+            } else {
+                completion.select_prev();
+            }
+        }
+    }
+
+    /// Complete the word before the cursor with the highlighted word
+    pub(crate) fn accept_completion(&mut self) -> Result<()> {
+        let Some(completion) = self.completion.take() else {
+            return Ok(());
+        };
+        if completion.start.line != self.cursor.line || completion.suffix().is_empty() {
+            return Ok(());
         }
+        self.selection = None;
+        self.cursor = self
+            .buffer
+            .borrow_mut()
+            .insert(&self.cursor, completion.suffix())?;
+        self.input.preferred_column = None;
+
+        // Invalidate highlighting cache and schedule git update
+        self.invalidate_cache_after_edit(self.cursor.line);
         Ok(())
     }
 
+    /// Apply buffer edits to folds (unfolding edited ranges) and the jump
+    /// list, and unfold the range hiding the cursor after jumps (search,
+    /// go to line, undo). Edits and reloads made in another view of the
+    /// buffer also move the cursor along and refresh the caches.
+    fn sync_line_edits(&mut self) {
+        if self.buffer_generation != self.buffer.generation() {
+            self.buffer_generation = self.buffer.generation();
+            self.viewport.folds = Default::default();
+            self.render_cache.fold_regions_revision = None;
+            self.render_cache.highlight.invalidate_all();
+            self.selection = None;
+            self.clamp_cursor();
+            self.update_git_diff();
+        }
+
+        let edits = self.buffer.take_edits();
+        let followed = edits.iter().any(|edit| edit.foreign);
+        if !edits.is_empty() {
+            // Parsing the whole document waits for a pause in typing
+            self.render_cache.document_edited = Some(Instant::now());
+        }
+        for ViewEdit { edit, foreign } in edits {
+            self.render_cache
+                .highlight
+                .record_edit(edit.line, edit.removed, edit.inserted);
+            self.viewport.folds.record_edit(&edit);
+            self.jumps.record_edit(&edit);
+            if foreign {
+                self.follow_foreign_edit(&edit);
+            }
+        }
+        if followed {
+            self.clamp_cursor();
+            if let Some(sel) = &mut self.selection {
+                cursor::physical::clamp_cursor(&mut sel.anchor, &self.buffer.borrow());
+                cursor::physical::clamp_cursor(&mut sel.active, &self.buffer.borrow());
+            }
+            self.schedule_git_diff_update();
+        }
+
+        // Save As in another view renames the file of this one too
+        let path = self.buffer.borrow().file_path().map(Path::to_path_buf);
+        if path.is_some() && path != self.file_state.path {
+            if let Some(path) = &path {
+                self.file_state.title = file_io::path_to_title(path);
+            }
+            self.file_state.path = path;
+        }
+        self.viewport.folds.reveal(self.cursor.line);
+    }
+
+    /// Keep cursor, selection and scroll position on their lines after an
+    /// edit made in another view
+    fn follow_foreign_edit(&mut self, edit: &LineEdit) {
+        self.cursor.line = edit.move_line(self.cursor.line);
+        if let Some(sel) = &mut self.selection {
+            sel.anchor.line = edit.move_line(sel.anchor.line);
+            sel.active.line = edit.move_line(sel.active.line);
+        }
+        self.viewport.top_line = edit.move_line(self.viewport.top_line);
+        // Matches may have moved
+        if self.search.state.is_some() {
+            self.close_search();
+        }
+    }
+
+    /// Recompute foldable regions after edits, at most once per
+    /// `FOLD_REGIONS_INTERVAL` unless forced
+    fn update_fold_regions(&mut self, force: bool) {
+        let revision = self.buffer.borrow().revision();
+        let cache = &self.render_cache;
+        if cache.fold_regions_revision == Some(revision) {
+            return;
+        }
+        let recent = cache
+            .fold_regions_updated
+            .is_some_and(|updated| updated.elapsed() < folding::FOLD_REGIONS_INTERVAL);
+        if recent && !force {
+            return;
+        }
+
+        // Large files and files with long lines skip syntax parsing
+        let language = self
+            .language()
+            .filter(|_| !self.is_large_file() && !self.buffer.borrow().has_long_lines());
+        let regions =
+            folding::compute_fold_regions(&self.buffer.borrow(), language, self.indent_width());
+        self.viewport.folds.set_regions(regions);
+        self.render_cache.fold_regions_revision = Some(revision);
+        self.render_cache.fold_regions_updated = Some(Instant::now());
+    }
+
+    /// Fold the innermost region at cursor, or unfold it if folded
+    pub(crate) fn toggle_fold(&mut self) {
+        self.sync_line_edits();
+        self.update_fold_regions(true);
+
+        let line = self.cursor.line;
+        if self.viewport.folds.unfold(line) {
+            return;
+        }
+        match self.viewport.folds.innermost_region(line) {
+            Some(region) => {
+                self.viewport.folds.fold(region);
+                self.move_cursor_out_of_folds();
+            }
+            None => self.status_message = Some(t().editor_nothing_to_fold().to_string()),
+        }
+    }
+
+    /// Fold all outermost regions
+    pub(crate) fn fold_all(&mut self) {
+        self.sync_line_edits();
+        self.update_fold_regions(true);
+        self.viewport.folds.fold_all();
+        self.move_cursor_out_of_folds();
+    }
+
+    /// Unfold all folded regions
+    pub(crate) fn unfold_all(&mut self) {
+        self.viewport.folds.unfold_all();
+    }
+
+    /// Move cursor and selection from hidden lines to the fold headers
+    fn move_cursor_out_of_folds(&mut self) {
+        let folds = &self.viewport.folds;
+        if folds.is_hidden(self.cursor.line) {
+            self.cursor = Cursor::at(folds.visible_line(self.cursor.line), 0);
+            self.selection = None;
+            self.input.preferred_column = None;
+        }
+    }
+
+    /// Skip lines hidden by folds after moving the cursor from `from_line`:
+    /// moving down lands below the fold, moving up lands on its header
+    fn skip_folded_lines(&mut self, from_line: usize) {
+        let folds = &self.viewport.folds;
+        if !folds.is_hidden(self.cursor.line) {
+            return;
+        }
+
+        let header = folds.visible_line(self.cursor.line);
+        let below = folds.next_visible(header);
+        self.cursor.line =
+            if self.cursor.line > from_line && below < self.buffer.borrow().line_count() {
+                below
+            } else {
+                header
+            };
+        self.clamp_cursor();
+    }
+
     /// Ensure cursor is visible when word wrap is enabled.
     /// This is more complex than the standard ensure_cursor_visible because we need
     /// to work with visual rows, not physical lines.
@@ -1112,36 +3017,35 @@ impl Editor {
             self.viewport.top_line = self.cursor.line;
         }
 
-        // Calculate the visual row of the cursor relative to viewport.top_line
-        let cursor_visual_row = word_wrap::calculate_visual_row_for_cursor(
-            &self.buffer,
+        // Keep the scroll-off margin above the cursor (up to the document start)
+        let margin = self.viewport.margin();
+        while self.cursor_screen_row() < margin {
+            let Some(prev) = self.viewport.folds.prev_visible(self.viewport.top_line) else {
+                break;
+            };
+            self.viewport.top_line = prev;
+        }
+
+        // Below the cursor the margin ends at the document end
+        let margin_below = word_wrap::visual_rows_below_cursor(
+            &self.buffer.borrow(),
+            &self.viewport.folds,
             self.cursor.line,
             self.cursor.column,
-            self.viewport.top_line,
+            margin,
             self.render_cache.content_width,
-            self.config.word_wrap,
-            self.render_cache.use_smart_wrap,
+            self.render_cache.wrap_mode,
         );
 
-        // If cursor is below the visible area, scroll down
-        if cursor_visual_row >= content_height {
+        // If cursor is below the visible area or in its margin, scroll down
+        if self.cursor_screen_row() + margin_below >= content_height {
             // We need to increase top_line until cursor fits in view
-            // Iterate: increase top_line and recalculate cursor_visual_row
+            // Iterate: increase top_line and recalculate the cursor row
             while self.viewport.top_line < self.cursor.line {
-                self.viewport.top_line += 1;
+                self.viewport.top_line = self.viewport.folds.next_visible(self.viewport.top_line);
 
-                let new_visual_row = word_wrap::calculate_visual_row_for_cursor(
-                    &self.buffer,
-                    self.cursor.line,
-                    self.cursor.column,
-                    self.viewport.top_line,
-                    self.render_cache.content_width,
-                    self.config.word_wrap,
-                    self.render_cache.use_smart_wrap,
-                );
-
-                // Stop when cursor is at the bottom of viewport
-                if new_visual_row < content_height {
+                // Stop when cursor and margin fit at the bottom of viewport
+                if self.cursor_screen_row() + margin_below < content_height {
                     break;
                 }
             }
@@ -1164,51 +3068,203 @@ impl Editor {
         }
     }
 
-    /// Get the total count of virtual lines (real buffer lines + deletion marker lines + word wrap)
-    /// This is used for viewport calculations to account for deletion markers and word wrapping
-    fn virtual_line_count(&self, config: &Config) -> usize {
-        // If word wrap is enabled, count visual rows instead of buffer lines
-        if self.should_use_visual_movement() {
-            // Use calculate_total_visual_rows which accounts for word wrapping
-            let total_visual_rows = word_wrap::calculate_total_visual_rows(
-                &self.buffer,
-                self.render_cache.content_width,
-                self.config.word_wrap,
-                self.render_cache.use_smart_wrap,
+    /// Visual row of the cursor relative to the viewport top
+    fn cursor_screen_row(&self) -> usize {
+        word_wrap::calculate_visual_row_for_cursor(
+            &self.buffer.borrow(),
+            &self.viewport.folds,
+            self.cursor.line,
+            self.cursor.column,
+            self.viewport.top_line,
+            self.render_cache.content_width,
+            self.config.word_wrap,
+            self.render_cache.wrap_mode,
+        )
+    }
+
+    /// Scroll so the cursor is shown on viewport row `row`
+    fn scroll_cursor_to_row(&mut self, row: usize) {
+        if !self.should_use_visual_movement() {
+            self.viewport.scroll_cursor_to_row(
+                &self.cursor,
+                row,
+                self.render_cache.virtual_line_count,
             );
+            return;
+        }
 
-            // Add deletion markers if git diff is shown
-            if config.editor.show_git_diff {
-                if let Some(git_diff) = &self.git.diff_cache {
-                    let buffer_line_count = self.buffer.line_count();
-                    let deletion_marker_count = (0..buffer_line_count)
-                        .filter(|&idx| git_diff.has_deletion_marker(idx))
-                        .count();
-                    return total_visual_rows + deletion_marker_count;
-                }
+        // Wrapped lines scroll by whole lines: go up from the cursor line
+        // while the cursor stays at or above the row
+        self.viewport.top_line = self.cursor.line;
+        while let Some(prev) = self.viewport.folds.prev_visible(self.viewport.top_line) {
+            let top = self.viewport.top_line;
+            self.viewport.top_line = prev;
+            if self.cursor_screen_row() > row {
+                self.viewport.top_line = top;
+                break;
             }
-
-            return total_visual_rows;
         }
+    }
 
-        // No word wrap - use old logic with buffer lines + deletion markers
-        if !config.editor.show_git_diff || self.git.diff_cache.is_none() {
-            return self.buffer.line_count();
+    /// Move the cursor into the viewport, outside its scroll-off margins,
+    /// after scrolling with the mouse wheel
+    fn keep_cursor_in_view(&mut self) {
+        if !self.should_use_visual_movement() {
+            let line = self
+                .viewport
+                .line_within_margin(self.cursor.line, self.buffer.borrow().line_count());
+            if line != self.cursor.line {
+                self.cursor.line = line;
+                self.clamp_cursor();
+            }
+            return;
         }
 
-        let buffer_line_count = self.buffer.line_count();
-        let deletion_marker_count = self
-            .git
-            .diff_cache
-            .as_ref()
-            .map(|cache| {
-                (0..buffer_line_count)
-                    .filter(|&idx| cache.has_deletion_marker(idx))
-                    .count()
-            })
-            .unwrap_or(0);
-
-        buffer_line_count + deletion_marker_count
+        let margin = self.viewport.margin();
+        let first_row = if self.viewport.top_line == 0 {
+            0
+        } else {
+            margin
+        };
+        let last_row = self
+            .viewport
+            .height
+            .saturating_sub(margin + 1)
+            .max(first_row);
+        let target_row = if self.cursor.line < self.viewport.top_line {
+            first_row
+        } else {
+            let row = self.cursor_screen_row();
+            if row < first_row {
+                first_row
+            } else if row > last_row {
+                last_row
+            } else {
+                return;
+            }
+        };
+
+        let (line, column) = word_wrap::visual_row_to_buffer_position(
+            &self.buffer.borrow(),
+            &self.viewport.folds,
+            target_row,
+            self.viewport.top_line,
+            self.render_cache.content_width,
+            self.render_cache.wrap_mode,
+        );
+        self.cursor = Cursor::at(line, column);
+        self.clamp_cursor();
+    }
+
+    /// Scroll the cursor line to the center, then top, then bottom of the
+    /// viewport on repeated presses (Ctrl+L)
+    pub(crate) fn recenter(&mut self) {
+        let step = match self.input.recenter {
+            Some((cursor, step)) if cursor == self.cursor => (step + 1) % 3,
+            _ => 0,
+        };
+        self.input.recenter = Some((self.cursor, step));
+
+        let margin = self.viewport.margin();
+        let row = match step {
+            0 => self.viewport.height / 2,
+            1 => margin,
+            _ => self.viewport.height.saturating_sub(margin + 1),
+        };
+        self.scroll_cursor_to_row(row);
+    }
+
+    /// Scroll half a page, moving the cursor by the same number of rows
+    /// (Ctrl+PageUp / Ctrl+PageDown)
+    pub(crate) fn scroll_half_page(&mut self, down: bool) {
+        let screen_row = self.cursor_screen_row();
+        let rows = (self.viewport.height / 2).max(1);
+        self.prepare_for_navigation();
+        let from = self.cursor;
+
+        if self.should_use_visual_movement() {
+            self.ensure_preferred_column();
+            let page_fn = if down {
+                cursor::visual::page_down
+            } else {
+                cursor::visual::page_up
+            };
+            self.cursor = page_fn(
+                &self.cursor,
+                &self.buffer.borrow(),
+                self.input.preferred_column,
+                self.render_cache.content_width,
+                self.render_cache.wrap_mode,
+                rows,
+            );
+        } else {
+            let folds = &self.viewport.folds;
+            let cursor_row = folds.row_of(self.cursor.line);
+            let row = if down {
+                cursor_row + rows
+            } else {
+                cursor_row.saturating_sub(rows)
+            };
+            self.cursor.line = folds.line_of(row);
+            self.clamp_cursor();
+        }
+
+        self.skip_folded_lines(from.line);
+        self.record_large_movement(from);
+        self.scroll_cursor_to_row(screen_row);
+    }
+
+    /// Get the total count of virtual lines (real buffer lines + deletion marker lines + word wrap)
+    /// This is used for viewport calculations to account for deletion markers and word wrapping
+    fn virtual_line_count(&self, config: &Config) -> usize {
+        // If word wrap is enabled, count visual rows instead of buffer lines
+        if self.should_use_visual_movement() {
+            // Use calculate_total_visual_rows which accounts for word wrapping
+            let total_visual_rows = word_wrap::calculate_total_visual_rows(
+                &self.buffer.borrow(),
+                &self.viewport.folds,
+                self.render_cache.content_width,
+                self.config.word_wrap,
+                self.render_cache.wrap_mode,
+            );
+
+            // Add deletion markers if git diff is shown
+            if config.editor.show_git_diff {
+                if let Some(git_diff) = &self.git.diff_cache {
+                    let buffer_line_count = self.buffer.borrow().line_count();
+                    let deletion_marker_count = (0..buffer_line_count)
+                        .filter(|&idx| git_diff.has_deletion_marker(idx))
+                        .count();
+                    return total_visual_rows + deletion_marker_count;
+                }
+            }
+
+            return total_visual_rows;
+        }
+
+        // No word wrap - use old logic with visible buffer lines + deletion markers
+        let visible_line_count = self
+            .buffer
+            .borrow()
+            .line_count()
+            .saturating_sub(self.viewport.folds.hidden_lines());
+        if !config.editor.show_git_diff || self.git.diff_cache.is_none() {
+            return visible_line_count;
+        }
+
+        let buffer_line_count = self.buffer.borrow().line_count();
+        let deletion_marker_count = self
+            .git
+            .diff_cache
+            .as_ref()
+            .map(|cache| {
+                (0..buffer_line_count)
+                    .filter(|&idx| cache.has_deletion_marker(idx))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        visible_line_count + deletion_marker_count
     }
 
     /// Render editor content
@@ -1224,18 +3280,30 @@ impl Editor {
             0 // Set to 0 when word wrap is disabled to trigger fallback behavior
         };
 
-        // Initially set smart wrap to false (will be updated later if word wrap is enabled)
-        self.render_cache.use_smart_wrap = false;
+        // Initially wrap at the width (will be updated later if word wrap is enabled)
+        self.render_cache.wrap_mode = WrapMode::Hard;
 
         self.viewport.resize(content_width, content_height);
 
-        // Determine smart wrap setting early (needed for ensure_cursor_visible_word_wrap)
-        let use_smart_wrap = if self.config.word_wrap && content_width > 0 {
-            self.should_use_smart_wrap(config)
+        // Fold indicators are skipped for large files (regions are computed on demand)
+        self.sync_line_edits();
+        if !self.is_large_file() {
+            self.update_fold_regions(false);
+        }
+
+        // While typing, edited lines are highlighted on their own until
+        // the document is parsed again (see `run_deferred_work`)
+        if self.render_cache.document_edited.is_none() {
+            self.sync_highlight_document();
+        }
+
+        // Determine wrap mode early (needed for ensure_cursor_visible_word_wrap)
+        let wrap_mode = if self.config.word_wrap && content_width > 0 {
+            self.wrap_mode(config)
         } else {
-            false
+            WrapMode::Hard
         };
-        self.render_cache.use_smart_wrap = use_smart_wrap;
+        self.render_cache.wrap_mode = wrap_mode;
 
         // Compute and cache virtual line count for viewport calculations
         let virtual_lines_total = self.virtual_line_count(config);
@@ -1251,11 +3319,14 @@ impl Editor {
                 .ensure_cursor_visible(&self.cursor, virtual_lines_total);
         }
 
+        // Spelling is checked on the lines about to be shown
+        let misspellings = self.spelling_spans(theme);
+
         // Delegate to rendering orchestrator
-        rendering::render_editor_content(
+        let cursor_cell = rendering::render_editor_content(
             buf,
             area,
-            &self.buffer,
+            &self.buffer.borrow(),
             &self.viewport,
             &self.cursor,
             &self.git.diff_cache,
@@ -1263,28 +3334,96 @@ impl Editor {
             &mut self.render_cache.highlight,
             &self.search.state,
             &self.selection,
+            self.lsp.spans(theme),
+            misspellings,
+            self.bookmarks.lines(&self.buffer.borrow()),
+            WhitespaceMarkers::new(self.config.render_whitespace, &config.editor, theme),
+            self.config.max_highlight_columns,
             theme,
             config.editor.show_git_diff,
             self.config.word_wrap,
-            use_smart_wrap,
+            wrap_mode,
             content_width,
             content_height,
         );
+        self.render_scrollbar(area, buf, theme, config);
+        self.render_completion(area, buf, theme, cursor_cell);
+    }
+
+    /// Render the scrollbar on the right edge of the area, if it fits
+    fn render_scrollbar(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme, config: &Config) {
+        if !rendering::scrollbar::has_scrollbar(area.width) || area.height == 0 {
+            return;
+        }
+        let buffer = self.buffer.borrow();
+        let total_rows = self.viewport.total_rows(buffer.line_count());
+        let git_diff_cache = if config.editor.show_git_diff {
+            self.git.diff_cache.as_ref()
+        } else {
+            None
+        };
+        self.render_cache.scrollbar.update(
+            area.height as usize,
+            total_rows,
+            buffer.revision(),
+            &self.viewport.folds,
+            git_diff_cache,
+            self.search.state.as_ref(),
+        );
+
+        let top_row = self.viewport.top_row();
+        let visible = top_row..(top_row + self.viewport.height).min(total_rows);
+        let scrollbar_area = Rect {
+            x: area.x + area.width - 1,
+            width: 1,
+            ..area
+        };
+        rendering::scrollbar::render_scrollbar(
+            buf,
+            scrollbar_area,
+            &self.render_cache.scrollbar,
+            visible,
+            total_rows,
+            theme,
+        );
+    }
+
+    /// Scroll the part of the document at scrollbar row `bar_row` of a
+    /// scrollbar `bar_height` rows high into the middle of the view
+    fn scroll_to_scrollbar_row(&mut self, bar_row: usize, bar_height: usize) {
+        let total_rows = self.viewport.total_rows(self.buffer.borrow().line_count());
+        let row = rendering::scrollbar::document_row(bar_row, bar_height, total_rows);
+        self.viewport.scroll_to_row(
+            row.saturating_sub(self.viewport.height / 2),
+            self.render_cache.virtual_line_count,
+        );
+        self.keep_cursor_in_view();
     }
 
     /// Start search
     pub fn start_search(&mut self, query: String, case_sensitive: bool) {
-        let mut search_state = SearchState::new(query, case_sensitive);
+        // Updating the query of an open search does not add jumps
+        if self.search.state.is_none() {
+            self.jumps.push(self.cursor);
+        }
+        let mut search_state = SearchState::new(query, case_sensitive)
+            .with_options(self.search.whole_word, self.search.scope());
 
         // Perform search throughout document
         self.perform_search(&mut search_state);
 
-        // Find closest match to current cursor
-        search_state.find_closest_match(&self.cursor);
+        // Find closest match to the cursor (as it was before the search
+        // when searching as you type)
+        let from = self
+            .search
+            .origin
+            .as_ref()
+            .map_or(self.cursor, |origin| origin.cursor);
+        search_state.find_closest_match(&from);
 
         // Move cursor to end of match and create selection
         if let Some(match_cursor) = search_state.current_match_cursor() {
-            let query_len = search_state.query.chars().count();
+            let query_len = search_state.query_len();
             let (selection, end_cursor) = search::get_match_selection(match_cursor, query_len);
             self.cursor = end_cursor;
             self.selection = Some(selection);
@@ -1293,17 +3432,56 @@ impl Editor {
         self.search.state = Some(search_state);
     }
 
+    /// Search for a query typed into the search modal; large buffers are
+    /// searched once typing pauses
+    pub fn search_as_you_type(&mut self, query: String) {
+        if self.buffer.borrow().len_bytes() > constants::SEARCH_DEBOUNCE_BYTES {
+            self.search.pending_query = Some((query, Instant::now()));
+        } else {
+            self.search.pending_query = None;
+            self.start_search(query, false);
+        }
+    }
+
+    /// Search for the query typed last, if not searched yet
+    fn run_pending_search(&mut self) {
+        if let Some((query, _)) = self.search.pending_query.take() {
+            self.start_search(query, false);
+        }
+    }
+
+    /// Keep the match found by the search modal (Enter)
+    pub fn confirm_search(&mut self) {
+        self.run_pending_search();
+        self.search.origin = None;
+    }
+
+    /// Close the search modal with Esc: the cursor, selection and scroll
+    /// position go back to where they were before the search
+    pub fn cancel_search(&mut self) {
+        self.search.pending_query = None;
+        if let Some(origin) = self.search.origin.take() {
+            self.cursor = origin.cursor;
+            self.selection = origin.selection;
+            self.viewport.top_line = origin.top_line;
+            self.viewport.left_column = origin.left_column;
+        }
+        self.close_search();
+    }
+
     /// Perform search in document
     fn perform_search(&self, search_state: &mut SearchState) {
-        search::perform_search(&self.buffer, search_state);
+        search::perform_search(&self.buffer.borrow(), search_state);
     }
 
     /// Go to next match
     pub fn search_next(&mut self) {
+        self.run_pending_search();
         if let Some(ref mut search_state) = self.search.state {
+            self.jumps.push(self.cursor);
             search_state.next_match();
             if let Some(match_cursor) = search_state.current_match_cursor() {
-                let query_len = search_state.query.chars().count();
+                let query_len = search_state.query_len();
                 let (selection, end_cursor) = search::get_match_selection(match_cursor, query_len);
                 self.cursor = end_cursor;
                 self.selection = Some(selection);
@@ -1313,10 +3491,12 @@ impl Editor {
 
     /// Go to previous match
     pub fn search_prev(&mut self) {
+        self.run_pending_search();
         if let Some(ref mut search_state) = self.search.state {
+            self.jumps.push(self.cursor);
             search_state.prev_match();
             if let Some(match_cursor) = search_state.current_match_cursor() {
-                let query_len = search_state.query.chars().count();
+                let query_len = search_state.query_len();
                 let (selection, end_cursor) = search::get_match_selection(match_cursor, query_len);
                 self.cursor = end_cursor;
                 self.selection = Some(selection);
@@ -1338,6 +3518,8 @@ impl Editor {
             }
         }
         self.search.state = None;
+        self.search.origin = None;
+        self.search.pending_query = None;
     }
 
     /// Get search match information (current index, total count)
@@ -1353,7 +3535,8 @@ impl Editor {
 
     /// Start search with replace
     pub fn start_replace(&mut self, query: String, replace_with: String, case_sensitive: bool) {
-        let mut search_state = SearchState::new_with_replace(query, replace_with, case_sensitive);
+        let mut search_state = SearchState::new_with_replace(query, replace_with, case_sensitive)
+            .with_options(self.search.whole_word, self.search.scope());
 
         // Perform search throughout document
         self.perform_search(&mut search_state);
@@ -1363,7 +3546,7 @@ impl Editor {
 
         // Move cursor to first match and create selection
         if let Some(match_cursor) = search_state.current_match_cursor() {
-            let query_len = search_state.query.chars().count();
+            let query_len = search_state.query_len();
             let (selection, end_cursor) = search::get_match_selection(match_cursor, query_len);
             self.cursor = end_cursor;
             self.selection = Some(selection);
@@ -1372,6 +3555,31 @@ impl Editor {
         self.search.state = Some(search_state);
     }
 
+    /// Set the whole-word and in-selection search options (used by the
+    /// next search)
+    pub fn set_search_options(&mut self, whole_word: bool, in_selection: bool) {
+        self.search.whole_word = whole_word;
+        self.search.in_selection = in_selection;
+    }
+
+    /// Whole-word and in-selection search options; searching in selection
+    /// is off when there was no selection to search in
+    pub fn search_options(&self) -> (bool, bool) {
+        (self.search.whole_word, self.search.scope().is_some())
+    }
+
+    /// Remember the selection to search in when a search modal opens
+    /// (an active search keeps the selection it started with)
+    fn capture_search_selection(&mut self) {
+        if self.search.state.is_none() {
+            self.search.selection = self
+                .selection
+                .clone()
+                .filter(|selection| !selection.is_empty());
+            self.search.in_selection = false;
+        }
+    }
+
     /// Update replace_with value in active search state without rebuilding search
     pub fn update_replace_with(&mut self, replace_with: String) {
         if let Some(ref mut search) = self.search.state {
@@ -1388,7 +3596,7 @@ impl Editor {
                     (&search_state.replace_with, search_state.current_match)
                 {
                     if let Some(match_cursor) = search_state.matches.get(idx).cloned() {
-                        (match_cursor, replace_with.clone(), search_state.query.len())
+                        (match_cursor, replace_with.clone(), search_state.query_len())
                     } else {
                         return Ok(());
                     }
@@ -1400,8 +3608,12 @@ impl Editor {
             };
 
         // Perform replacement
-        let result =
-            search::replace_at_position(&mut self.buffer, &match_cursor, query_len, &replace_with)?;
+        let result = search::replace_at_position(
+            &mut self.buffer.borrow_mut(),
+            &match_cursor,
+            query_len,
+            &replace_with,
+        )?;
         self.cursor = result.new_cursor;
 
         // Invalidate highlighting cache for changed line
@@ -1416,12 +3628,14 @@ impl Editor {
                 search_state.matches.remove(idx);
 
                 // Update positions of remaining matches on the same line after replacement point
+                let replace_len = replace_with.graphemes(true).count();
                 search::update_match_positions_after_replace(
                     &mut search_state.matches,
                     &match_cursor,
                     query_len,
-                    replace_with.len(),
+                    replace_len,
                 );
+                search_state.shift_selection_after_replace(&match_cursor, query_len, replace_len);
 
                 // Update current match index
                 if search_state.matches.is_empty() {
@@ -1432,7 +3646,7 @@ impl Editor {
 
                 // Move cursor to next match and create selection
                 if let Some(match_cursor) = search_state.current_match_cursor() {
-                    let query_len = search_state.query.chars().count();
+                    let query_len = search_state.query_len();
                     let (selection, end_cursor) =
                         search::get_match_selection(match_cursor, query_len);
                     self.cursor = end_cursor;
@@ -1447,17 +3661,23 @@ impl Editor {
         Ok(())
     }
 
-    /// Replace all matches
+    /// Replace all matches (in the selection when searching in selection)
+    /// and report the count in the status message
     pub fn replace_all(&mut self) -> Result<usize> {
         let count = if let Some(ref search_state) = self.search.state.clone() {
             if let Some(replace_with) = &search_state.replace_with {
                 // Perform all replacements
                 let count = search::replace_all_matches(
-                    &mut self.buffer,
+                    &mut self.buffer.borrow_mut(),
                     &search_state.matches,
-                    search_state.query.len(),
+                    search_state.query_len(),
                     replace_with,
                 )?;
+                self.status_message = Some(if search_state.in_selection.is_some() {
+                    t().editor_replaced_in_selection(count)
+                } else {
+                    t().editor_replaced(count)
+                });
 
                 // Invalidate highlighting cache for all affected lines
                 for match_cursor in &search_state.matches {
@@ -1519,18 +3739,13 @@ impl Editor {
         Ok(())
     }
 
-    /// Invalidate syntax highlighting cache after text edit and schedule git diff update.
+    /// Invalidate syntax highlighting of the edited line and schedule git diff update.
     ///
-    /// If the edit is multiline, invalidates all lines from start_line to end of buffer.
-    /// Otherwise, invalidates only the single changed line.
-    fn invalidate_cache_after_edit(&mut self, start_line: usize, is_multiline: bool) {
-        if is_multiline {
-            self.render_cache
-                .highlight
-                .invalidate_range(start_line, self.buffer.line_count());
-        } else {
-            self.render_cache.highlight.invalidate_line(start_line);
-        }
+    /// Lines added or removed by the edit move with their highlighting when
+    /// the view picks up the buffer's line edits (see `sync_line_edits`);
+    /// the whole document is parsed again only once typing pauses.
+    fn invalidate_cache_after_edit(&mut self, start_line: usize) {
+        self.render_cache.highlight.invalidate_line(start_line);
         self.schedule_git_diff_update();
     }
 
@@ -1544,14 +3759,11 @@ impl Editor {
     {
         self.close_search();
 
-        if let Some(new_cursor) = operation(&mut self.buffer)? {
+        let new_cursor = operation(&mut self.buffer.borrow_mut())?;
+        if let Some(new_cursor) = new_cursor {
             self.cursor = new_cursor;
             self.clamp_cursor();
-            // Invalidate entire highlighting cache after undo/redo
-            self.render_cache
-                .highlight
-                .invalidate_range(0, self.buffer.line_count());
-            // Schedule git diff update
+            // Highlighting follows the undone edits like typed ones
             self.schedule_git_diff_update();
         }
         Ok(())
@@ -1563,6 +3775,15 @@ impl Editor {
     /// exists and execute_search is true, executes it immediately.
     pub(crate) fn open_search_modal(&mut self, execute_search: bool) {
         let mut search_modal = SearchModal::new("");
+        self.capture_search_selection();
+        self.search.origin = Some(SearchOrigin {
+            cursor: self.cursor,
+            selection: self.selection.clone(),
+            top_line: self.viewport.top_line,
+            left_column: self.viewport.left_column,
+        });
+        let (whole_word, in_selection) = self.search_options();
+        search_modal.set_options(whole_word, in_selection);
 
         // Restore active search state if it exists
         if let Some(ref search_state) = self.search.state {
@@ -1595,18 +3816,29 @@ impl Editor {
     /// Execute navigation with visual/physical mode selection.
     ///
     /// Prepares for navigation, then calls visual_fn if word wrap is enabled,
-    /// otherwise calls physical_fn.
+    /// otherwise calls physical_fn. Lines hidden by folds are skipped.
     pub(crate) fn navigate<FV, FP>(&mut self, visual_fn: FV, physical_fn: FP)
     where
         FV: FnOnce(&mut Self),
         FP: FnOnce(&mut Self),
     {
         self.prepare_for_navigation();
-        if self.should_use_visual_movement() {
+        let from = self.cursor;
+        if self.should_use_visual_navigation() {
             visual_fn(self);
         } else {
             physical_fn(self);
         }
+        self.skip_folded_lines(from.line);
+        self.record_large_movement(from);
+    }
+
+    /// Record a movement from `from` over `JUMP_MIN_LINES` or more lines
+    /// (page up/down, document start/end) in the jump list
+    fn record_large_movement(&mut self, from: Cursor) {
+        if self.cursor.line.abs_diff(from.line) >= constants::JUMP_MIN_LINES {
+            self.jumps.push(from);
+        }
     }
 
     /// Execute navigation with selection, using visual/physical mode.
@@ -1619,11 +3851,13 @@ impl Editor {
         FP: FnOnce(&mut Self),
     {
         self.prepare_for_navigation_with_selection();
-        if self.should_use_visual_movement() {
+        let from_line = self.cursor.line;
+        if self.should_use_visual_navigation() {
             visual_fn(self);
         } else {
             physical_fn(self);
         }
+        self.skip_folded_lines(from_line);
         self.update_selection_active();
     }
 
@@ -1636,7 +3870,10 @@ impl Editor {
         F: FnOnce(&mut Self),
     {
         self.prepare_for_navigation();
+        let from = self.cursor;
         movement_fn(self);
+        self.skip_folded_lines(from.line);
+        self.record_large_movement(from);
     }
 
     /// Execute simple navigation with selection (no visual/physical choice).
@@ -1648,7 +3885,9 @@ impl Editor {
         F: FnOnce(&mut Self),
     {
         self.prepare_for_navigation_with_selection();
+        let from_line = self.cursor.line;
         movement_fn(self);
+        self.skip_folded_lines(from_line);
         self.update_selection_active();
     }
 
@@ -1672,9 +3911,13 @@ impl Editor {
 
     /// Handle save command - either save to existing path or open "Save As" modal
     pub(crate) fn handle_save(&mut self) -> Result<()> {
-        if self.buffer.file_path().is_some() {
-            // File has path - save normally
-            self.save()
+        if self.buffer.borrow().file_path().is_some() {
+            // File has path - save normally, or once the formatter finished
+            if self.formats_on_save() {
+                self.format_document(true)
+            } else {
+                self.save()
+            }
         } else {
             // File has no path - open "Save As" dialog
             self.handle_save_as()
@@ -1686,7 +3929,16 @@ impl Editor {
         let directory = std::env::current_dir()
             .unwrap_or_else(|_| dirs::home_dir().unwrap_or_else(|| PathBuf::from("/")));
 
-        let modal = InputModal::new("Save File As", "untitled.txt");
+        let t = t();
+        let modal = match self.buffer.borrow().file_path() {
+            // Start from the current path so it only needs a small edit
+            Some(path) => InputModal::with_default(
+                t.modal_save_as_title(),
+                t.modal_enter_filename(),
+                path.display().to_string(),
+            ),
+            None => InputModal::new(t.modal_save_as_title(), t.modal_enter_filename()),
+        };
         let action = PendingAction::SaveFileAs {
             panel_index: 0, // will be updated in app.rs
             directory,
@@ -1698,6 +3950,9 @@ impl Editor {
     /// Open replace modal with previous find/replace text restored
     pub(crate) fn handle_start_replace(&mut self) {
         let mut replace_modal = ReplaceModal::new();
+        self.capture_search_selection();
+        let (whole_word, in_selection) = self.search_options();
+        replace_modal.set_options(whole_word, in_selection);
 
         // Restore previous find/replace text if available
         if let Some(ref find) = self.search.last_replace_find {
@@ -1723,17 +3978,153 @@ impl Editor {
             ActiveModal::Replace(Box::new(replace_modal)),
         ));
     }
+
+    /// Run an editor command, returning the events it raised (status
+    /// messages included)
+    fn run_command(&mut self, command: keyboard::EditorCommand) -> Vec<PanelEvent> {
+        // Typing or deleting a word character refines the completion
+        // popup, keys other than the popup's own close it
+        let refines_completion = match command {
+            keyboard::EditorCommand::InsertChar(ch) => is_word_char(ch),
+            keyboard::EditorCommand::Backspace => self.completion.is_some(),
+            _ => false,
+        };
+        let keeps_completion = matches!(
+            command,
+            keyboard::EditorCommand::CompletionNext | keyboard::EditorCommand::CompletionPrev
+        );
+
+        // Collect events from internal state
+        let mut events = Vec::new();
+        self.sync_line_edits();
+
+        // Reject edits to files without write permission until "edit anyway"
+        if self.file_state.read_only && command.is_modifying() {
+            events.push(PanelEvent::SetStatusMessage {
+                message: t().editor_read_only_rejected().to_string(),
+                is_error: true,
+            });
+            return events;
+        }
+
+        // Execute command and handle errors
+        if let Err(e) = command.execute(self) {
+            events.push(PanelEvent::SetStatusMessage {
+                message: e.to_string(),
+                is_error: true,
+            });
+        }
+        if refines_completion {
+            self.update_completion();
+        } else if !keeps_completion {
+            self.completion = None;
+        }
+        events.append(&mut self.pending_events);
+
+        // Convert status_message to event and take it (removes from legacy field)
+        if let Some(message) = self.status_message.take() {
+            events.push(PanelEvent::SetStatusMessage {
+                message,
+                is_error: false,
+            });
+        }
+
+        events
+    }
 }
 
+/// Keys of `EditorCommand::from_key_event` with the `key_help_*` id describing
+/// them (cursor movement and typing left out).
+const KEY_HELP: &[(&str, &str)] = &[
+    ("Ctrl+S", "ed_save"),
+    ("Ctrl+Shift+S", "ed_force_save"),
+    ("Ctrl+Alt+S", "ed_save_as"),
+    ("Ctrl+Shift+W", "ed_edit_anyway"),
+    ("Ctrl+Shift+R", "ed_reload"),
+    ("Ctrl+C, Ctrl+X, Ctrl+V", "ed_clipboard"),
+    ("Ctrl+Alt+C", "ed_copy_block"),
+    ("Ctrl+Alt+V", "ed_paste_reindented"),
+    ("Ctrl+Z", "ed_undo"),
+    ("Ctrl+Y", "ed_redo"),
+    ("Ctrl+Shift+U", "ed_undo_tree"),
+    ("Ctrl+A", "ed_select_all"),
+    ("Ctrl+F", "ed_find"),
+    ("Ctrl+H", "ed_replace"),
+    ("F3, Shift+F3", "ed_find_next"),
+    ("Ctrl+O, Ctrl+Shift+O", "ed_jump"),
+    ("Ctrl+PageUp, Ctrl+PageDown", "ed_half_page"),
+    ("Ctrl+L", "ed_center"),
+    ("Ctrl+/", "ed_comment"),
+    ("Ctrl+Backspace, Ctrl+Delete", "ed_delete_word"),
+    ("Ctrl+T", "ed_swap_words"),
+    ("Ctrl+D", "ed_duplicate_line"),
+    ("Ctrl+Shift+K", "ed_delete_line"),
+    ("Alt+Shift+Up, Alt+Shift+Down", "ed_move_lines"),
+    ("Tab, Shift+Tab", "ed_indent"),
+    ("Ctrl+Shift+T", "ed_convert_indent"),
+    ("Ctrl+Shift+L", "ed_line_endings"),
+    ("Ctrl+Shift+A", "ed_whitespace"),
+    ("Ctrl+Shift+E", "ed_large_file"),
+    ("Ctrl+Shift+H", "ed_syntax"),
+    ("Ctrl+Shift+N", "ed_encoding"),
+    ("F7, Shift+F7", "ed_git_change"),
+    ("Ctrl+Alt+Z", "ed_revert"),
+    ("Ctrl+Shift+D", "ed_diff"),
+    ("Ctrl+Shift+M", "ed_markdown_preview"),
+    ("Ctrl+F2", "ed_bookmark"),
+    ("F9", "ed_fold"),
+    ("Ctrl+F9, Shift+F9", "ed_fold_all"),
+    ("F12", "ed_definition"),
+    ("Ctrl+Alt+F", "ed_format"),
+    ("F8", "ed_spelling"),
+];
+
 impl Panel for Editor {
     fn name(&self) -> &'static str {
         "editor"
     }
 
+    fn menu_items(&self) -> Vec<MenuItem> {
+        let t = t();
+        let editable = !self.config.read_only && !self.file_state.read_only;
+        vec![
+            MenuItem::new("save", t.panel_menu_item("save"))
+                .with_enabled(editable && self.buffer.borrow().is_modified())
+                .with_key_hint("Ctrl+S"),
+            MenuItem::new("format", t.panel_menu_item("format"))
+                .with_enabled(editable && self.formatter().is_some())
+                .with_key_hint("Ctrl+Alt+F"),
+            MenuItem::toggle(
+                "word_wrap",
+                t.panel_menu_item("word_wrap"),
+                self.config.word_wrap,
+            )
+            .with_enabled(!self.is_large_file()),
+            MenuItem::new("bookmark", t.panel_menu_item("bookmark"))
+                .with_enabled(self.file_path().is_some())
+                .with_key_hint("Ctrl+F2"),
+            MenuItem::new("markdown_preview", t.panel_menu_item("markdown_preview"))
+                .with_enabled(self.language() == Some("markdown"))
+                .with_key_hint("Ctrl+Shift+M"),
+        ]
+    }
+
+    fn key_help(&self) -> Vec<(String, String)> {
+        let t = termide_i18n::t();
+        KEY_HELP
+            .iter()
+            .map(|(keys, id)| (keys.to_string(), t.key_help(id).to_string()))
+            .collect()
+    }
+
     fn title(&self) -> String {
-        let modified = if self.buffer.is_modified() { "*" } else { "" };
+        let modified = if self.buffer.borrow().is_modified() {
+            "*"
+        } else {
+            ""
+        };
 
-        let external_change = if self.file_state.external_change_detected {
+        let external_change = if self.buffer.external_change() {
             " [changed on disk]"
         } else {
             ""
@@ -1755,15 +4146,20 @@ impl Panel for Editor {
             String::new()
         };
 
+        let read_only = if self.file_state.read_only {
+            " [RO]"
+        } else {
+            ""
+        };
+
         format!(
-            "{}{}{}{}",
-            self.file_state.title, modified, external_change, search_info
+            "{}{}{}{}{}",
+            self.file_state.title, modified, read_only, external_change, search_info
         )
     }
 
     fn prepare_render(&mut self, theme: &Theme, config: &Config) {
-        self.render_cache.theme = *theme;
-        self.render_cache.config = config.clone();
+        self.render_cache.prepare(theme, config);
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer, ctx: &RenderContext) {
@@ -1775,6 +4171,32 @@ impl Panel for Editor {
         let theme = self.render_cache.theme;
         let config = self.render_cache.config.clone();
         self.render_content(area, buf, &theme, &config);
+        self.buffer.set_top_line(self.viewport.top_line);
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        let search_due = self
+            .search
+            .pending_query
+            .as_ref()
+            .is_some_and(|(_, typed)| {
+                typed.elapsed() >= Duration::from_millis(constants::SEARCH_DEBOUNCE_MS)
+            });
+        if search_due {
+            self.run_pending_search();
+            return vec![PanelEvent::NeedsRedraw];
+        }
+
+        let Some(outcome) = self.format_job.as_ref().and_then(FormatJob::poll) else {
+            return vec![];
+        };
+        let Some(job) = self.format_job.take() else {
+            return vec![];
+        };
+        self.sync_line_edits();
+        let mut events = self.finish_format(job, outcome);
+        events.push(PanelEvent::NeedsRedraw);
+        events
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
@@ -1786,27 +4208,32 @@ impl Panel for Editor {
             self.config.read_only,
             self.search.state.is_some(),
             self.selection.is_some(),
+            self.completion.is_some(),
         );
+        self.run_command(command)
+    }
 
-        // Collect events from internal state
+    fn handle_paste(&mut self, text: &str) -> Vec<PanelEvent> {
         let mut events = Vec::new();
-
-        // Execute command and handle errors
-        if let Err(e) = command.execute(self) {
+        if self.config.read_only {
+            return events;
+        }
+        self.sync_line_edits();
+        self.completion = None;
+        if self.file_state.read_only {
             events.push(PanelEvent::SetStatusMessage {
-                message: e.to_string(),
+                message: t().editor_read_only_rejected().to_string(),
                 is_error: true,
             });
+            return events;
         }
-
-        // Convert status_message to event and take it (removes from legacy field)
-        if let Some(message) = self.status_message.take() {
+        if let Err(e) = self.paste_text(text) {
             events.push(PanelEvent::SetStatusMessage {
-                message,
-                is_error: false,
+                message: e.to_string(),
+                is_error: true,
             });
         }
-
+        events.append(&mut self.pending_events);
         events
     }
 
@@ -1817,22 +4244,20 @@ impl Panel for Editor {
     ) -> Vec<PanelEvent> {
         use crossterm::event::{MouseButton, MouseEventKind};
 
+        self.sync_line_edits();
+        if !matches!(mouse.kind, MouseEventKind::Moved) {
+            self.completion = None;
+        }
         match mouse.kind {
             MouseEventKind::ScrollUp => {
                 self.viewport.scroll_up(3);
-                if self.cursor.line >= self.viewport.bottom_line() {
-                    self.cursor.line = self.viewport.bottom_line().saturating_sub(1);
-                    self.clamp_cursor();
-                }
+                self.keep_cursor_in_view();
                 return vec![];
             }
             MouseEventKind::ScrollDown => {
                 self.viewport
                     .scroll_down(3, self.render_cache.virtual_line_count);
-                if self.cursor.line < self.viewport.top_line {
-                    self.cursor.line = self.viewport.top_line;
-                    self.clamp_cursor();
-                }
+                self.keep_cursor_in_view();
                 return vec![];
             }
             _ => {}
@@ -1846,11 +4271,38 @@ impl Panel for Editor {
         };
 
         let line_number_width = rendering::LINE_NUMBER_WIDTH as u16;
+        let scrollbar_width = u16::from(rendering::scrollbar::has_scrollbar(inner.width));
         let content_x = inner.x + line_number_width;
         let content_y = inner.y;
-        let content_width = inner.width.saturating_sub(line_number_width);
+        let content_width = inner
+            .width
+            .saturating_sub(line_number_width + scrollbar_width);
         let content_height = inner.height;
 
+        // Clicking or dragging on the scrollbar scrolls the view
+        let bar_row = (mouse.row.saturating_sub(content_y) as usize)
+            .min((content_height as usize).saturating_sub(1));
+        let on_scrollbar = scrollbar_width > 0
+            && mouse.column == inner.x + inner.width - 1
+            && mouse.row >= content_y
+            && mouse.row < content_y + content_height;
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if on_scrollbar => {
+                self.input.scrollbar_drag = true;
+                self.scroll_to_scrollbar_row(bar_row, content_height as usize);
+                return vec![];
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.input.scrollbar_drag => {
+                self.scroll_to_scrollbar_row(bar_row, content_height as usize);
+                return vec![];
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.input.scrollbar_drag => {
+                self.input.scrollbar_drag = false;
+                return vec![];
+            }
+            _ => {}
+        }
+
         if mouse.column < content_x || mouse.column >= content_x + content_width {
             return vec![];
         }
@@ -1863,14 +4315,15 @@ impl Panel for Editor {
 
         let (buffer_line, wrapped_offset) = if self.config.word_wrap {
             word_wrap::visual_row_to_buffer_position(
-                &self.buffer,
+                &self.buffer.borrow(),
+                &self.viewport.folds,
                 rel_y,
                 self.viewport.top_line,
                 content_width as usize,
-                self.render_cache.use_smart_wrap,
+                self.render_cache.wrap_mode,
             )
         } else {
-            (self.viewport.top_line + rel_y, 0)
+            (self.viewport.viewport_pos_to_cursor(rel_y, 0).line, 0)
         };
 
         let buffer_col = if self.config.word_wrap {
@@ -1879,9 +4332,9 @@ impl Panel for Editor {
             self.viewport.left_column + rel_x
         };
 
-        let max_line = self.buffer.line_count().saturating_sub(1);
+        let max_line = self.buffer.borrow().line_count().saturating_sub(1);
         let target_line = buffer_line.min(max_line);
-        let line_len = self.buffer.line_len_graphemes(target_line);
+        let line_len = self.buffer.borrow().line_len_graphemes(target_line);
         let target_col = buffer_col.min(line_len);
 
         match mouse.kind {
@@ -1895,7 +4348,7 @@ impl Panel for Editor {
                 {
                     let temp_cursor = Cursor::at(target_line, target_col);
                     if let Some((new_selection, new_cursor)) =
-                        selection::select_word(&self.buffer, &temp_cursor)
+                        selection::select_word(&self.buffer.borrow(), &temp_cursor)
                     {
                         self.selection = Some(new_selection);
                         self.cursor = new_cursor;
@@ -1931,6 +4384,18 @@ impl Panel for Editor {
                     }
                 }
             }
+            MouseEventKind::Down(MouseButton::Middle) => {
+                if self.is_read_only() {
+                    return vec![];
+                }
+                self.cursor = Cursor::at(target_line, target_col);
+                if let Err(e) = self.paste_from_primary() {
+                    return vec![PanelEvent::SetStatusMessage {
+                        message: e.to_string(),
+                        is_error: true,
+                    }];
+                }
+            }
             _ => {}
         }
 
@@ -1953,10 +4418,7 @@ impl Panel for Editor {
                 }
                 CommandResult::NeedsRedraw(false)
             }
-            PanelCommand::CheckPendingGitDiff => {
-                self.check_pending_git_diff_update();
-                CommandResult::None
-            }
+            PanelCommand::RunDeferredWork => CommandResult::NeedsRedraw(self.run_deferred_work()),
             PanelCommand::CheckGitDiffReceiver => {
                 let needs_redraw = self.check_git_diff_receiver();
                 CommandResult::NeedsRedraw(needs_redraw)
@@ -2009,8 +4471,8 @@ impl Panel for Editor {
                 }
             }
             PanelCommand::GetModificationStatus => CommandResult::ModificationStatus {
-                is_modified: self.buffer.is_modified(),
-                has_external_change: self.file_state.external_change_detected,
+                is_modified: self.buffer.borrow().is_modified(),
+                has_external_change: self.buffer.external_change(),
             },
             PanelCommand::Save => match self.save() {
                 Ok(_) => CommandResult::SaveResult {
@@ -2024,21 +4486,36 @@ impl Panel for Editor {
             },
             PanelCommand::CloseWithoutSaving => {
                 // Clear external change flag - the panel is being closed without saving
-                self.file_state.external_change_detected = false;
+                self.buffer.set_external_change(false);
                 // Note: buffer.modified stays true but caller handles closing directly
                 CommandResult::None
             }
+            PanelCommand::MenuAction { id } => {
+                let command = match id {
+                    "save" => keyboard::EditorCommand::Save,
+                    "format" => keyboard::EditorCommand::FormatDocument,
+                    "word_wrap" => keyboard::EditorCommand::ToggleWordWrap,
+                    "markdown_preview" => keyboard::EditorCommand::MarkdownPreview,
+                    "bookmark" => keyboard::EditorCommand::SetBookmark,
+                    _ => return CommandResult::None,
+                };
+                CommandResult::Events(self.run_command(command))
+            }
             // Commands not applicable to Editor
             PanelCommand::SetFsWatchRoot { .. }
             | PanelCommand::Resize { .. }
-            | PanelCommand::RefreshDirectory => CommandResult::None,
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. } => CommandResult::None,
         }
     }
 
     fn needs_close_confirmation(&self) -> Option<String> {
-        if self.buffer.is_modified() {
+        // Another view keeps the buffer with its changes
+        if self.buffer.view_count() > 1 {
+            None
+        } else if self.buffer.borrow().is_modified() {
             Some("File has unsaved changes. Close anyway?".to_string())
-        } else if self.file_state.external_change_detected {
+        } else if self.buffer.external_change() {
             Some("File changed on disk. Close anyway?".to_string())
         } else {
             None
@@ -2046,7 +4523,7 @@ impl Panel for Editor {
     }
 
     fn captures_escape(&self) -> bool {
-        self.search.state.is_some()
+        self.search.state.is_some() || self.completion.is_some()
     }
 
     fn to_session(&self, session_dir: &std::path::Path) -> Option<SessionPanel> {
@@ -2055,6 +4532,7 @@ impl Panel for Editor {
             Some(SessionPanel::Editor {
                 path: Some(path.to_path_buf()),
                 unsaved_buffer_file: None,
+                syntax: self.file_state.syntax_override.clone(),
             })
         } else if self.buffer_is_modified() {
             // Unnamed buffer with unsaved content - save to session dir
@@ -2068,7 +4546,7 @@ impl Panel for Editor {
                     )
                 });
 
-            let content = self.buffer.text();
+            let content = self.buffer.borrow().text();
             if content.trim().is_empty() {
                 return None; // Don't save empty buffers
             }
@@ -2082,6 +4560,7 @@ impl Panel for Editor {
             Some(SessionPanel::Editor {
                 path: None,
                 unsaved_buffer_file: Some(filename),
+                syntax: self.file_state.syntax_override.clone(),
             })
         } else {
             // Unnamed buffer without changes - don't save
@@ -2101,6 +4580,14 @@ impl Panel for Editor {
         self.file_path()
             .and_then(|p| p.parent().map(|parent| parent.to_path_buf()))
     }
+
+    fn file_path(&self) -> Option<&std::path::Path> {
+        Editor::file_path(self)
+    }
+
+    fn status_segments(&self) -> Vec<StatusSegment> {
+        self.status_bar_segments()
+    }
 }
 
 // Additional methods used by app layer (not part of Panel trait)
@@ -2117,11 +4604,40 @@ impl Default for Editor {
     }
 }
 
+/// Short description of an edit for the undo tree
+fn preview_action(action: &Action) -> String {
+    const PREVIEW_CHARS: usize = 24;
+    let quote = |sign: char, text: &str| {
+        let mut short: String = text
+            .chars()
+            .take(PREVIEW_CHARS)
+            .map(|ch| if ch == '\n' { '⏎' } else { ch })
+            .filter(|&ch| ch != '\r')
+            .collect();
+        if text.chars().count() > PREVIEW_CHARS {
+            short.push('…');
+        }
+        format!("{} \"{}\"", sign, short)
+    };
+    match action {
+        Action::Insert { text, .. } => quote('+', text),
+        Action::Delete { text, .. } => quote('-', text),
+        Action::Group { actions } => match actions.first() {
+            Some(first) if actions.len() > 1 => format!("{} …", preview_action(first)),
+            Some(first) => preview_action(first),
+            None => String::new(),
+        },
+        Action::SetLineEnding { to, .. } => to.name().to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
     use std::io::Write;
     use tempfile::NamedTempFile;
+    use termide_buffer::SaveTransforms;
     use termide_core::{CommandResult, Panel, PanelCommand};
 
     fn create_editor_with_content(content: &str) -> (Editor, NamedTempFile) {
@@ -2218,13 +4734,13 @@ mod tests {
     #[test]
     fn test_handle_command_close_without_saving() {
         let (mut editor, _file) = create_editor_with_content("hello");
-        editor.file_state.external_change_detected = true;
+        editor.buffer.set_external_change(true);
 
         let result = editor.handle_command(PanelCommand::CloseWithoutSaving);
         assert!(matches!(result, CommandResult::None));
 
         // External change flag should be cleared
-        assert!(!editor.file_state.external_change_detected);
+        assert!(!editor.buffer.external_change());
     }
 
     #[test]
@@ -2255,6 +4771,48 @@ mod tests {
         assert!(editor.title().ends_with(".tmp") || !editor.title().is_empty());
     }
 
+    #[test]
+    fn test_editor_with_text_has_no_file() {
+        let editor = Editor::with_text(
+            "#!/usr/bin/env python3\nprint(1)\n",
+            "stdin",
+            EditorConfig::default(),
+        );
+        assert!(editor.file_path().is_none());
+        assert_eq!(editor.title(), "stdin*");
+        assert_eq!((editor.cursor.line, editor.cursor.column), (0, 0));
+        assert_eq!(editor.language(), Some("python"));
+        assert!(editor.needs_close_confirmation().is_some());
+    }
+
+    #[test]
+    fn test_recovery_update_follows_unsaved_changes() {
+        let (mut editor, file) = create_editor_with_content("hello");
+        let view = editor.split_view();
+        let take = |editor: &mut Editor| editor.take_recovery_update(Duration::ZERO, 100, 1024);
+        assert_eq!(take(&mut editor), None);
+
+        editor.insert_char('x').unwrap();
+        let Some(RecoveryUpdate::Write { id, path, text, .. }) = take(&mut editor) else {
+            panic!("expected a recovery copy");
+        };
+        assert_eq!(path.as_deref(), Some(file.path()));
+        assert_eq!(text, "xhello");
+        // Both views share one copy
+        let mut view = view;
+        assert_eq!(take(&mut view), None);
+        assert_eq!(view.recovery_id(), Some(id.clone()));
+
+        // Saving makes the copy unneeded
+        editor.save().unwrap();
+        assert_eq!(take(&mut editor), Some(RecoveryUpdate::Remove { id }));
+        assert_eq!(editor.recovery_id(), None);
+
+        // Buffers over the size cap get no copy
+        editor.insert_char('x').unwrap();
+        assert_eq!(editor.take_recovery_update(Duration::ZERO, 100, 3), None);
+    }
+
     #[test]
     fn test_editor_panel_trait_needs_close_confirmation() {
         let editor = Editor::new();
@@ -2288,7 +4846,7 @@ mod tests {
     fn test_large_file_load_10k_lines() {
         let (editor, _file) = create_large_file(10_000);
         // writeln! adds trailing newline, so we get one extra empty line
-        assert!(editor.buffer.line_count() >= 10_000);
+        assert!(editor.buffer.borrow().line_count() >= 10_000);
         assert_eq!(editor.cursor.line, 0);
         assert_eq!(editor.cursor.column, 0);
     }
@@ -2307,7 +4865,7 @@ mod tests {
         editor.set_cursor_line(4999);
         editor
             .viewport
-            .ensure_cursor_visible(&editor.cursor, editor.buffer.line_count());
+            .ensure_cursor_visible(&editor.cursor, editor.buffer.borrow().line_count());
         assert!(editor.viewport().is_cursor_visible(&editor.cursor));
         assert_eq!(editor.cursor.line, 4999);
 
@@ -2315,7 +4873,7 @@ mod tests {
         editor.set_cursor_line(9999);
         editor
             .viewport
-            .ensure_cursor_visible(&editor.cursor, editor.buffer.line_count());
+            .ensure_cursor_visible(&editor.cursor, editor.buffer.borrow().line_count());
         assert_eq!(editor.cursor.line, 9999);
         assert!(editor.viewport().is_cursor_visible(&editor.cursor));
     }
@@ -2335,7 +4893,7 @@ mod tests {
         // Move to end
         editor.move_to_document_end();
         // Should be at last line (buffer may have trailing empty line)
-        assert_eq!(editor.cursor.line, editor.buffer.line_count() - 1);
+        assert_eq!(editor.cursor.line, editor.buffer.borrow().line_count() - 1);
 
         // Move to start
         editor.move_to_document_start();
@@ -2349,20 +4907,30 @@ mod tests {
 
         // Edit at beginning
         let _ = editor.insert_char('A');
-        assert_eq!(editor.buffer.line(0).unwrap().chars().next().unwrap(), 'A');
+        assert_eq!(
+            editor
+                .buffer
+                .borrow()
+                .line(0)
+                .unwrap()
+                .chars()
+                .next()
+                .unwrap(),
+            'A'
+        );
 
         // Edit at middle
         editor.set_cursor_line(499);
         let _ = editor.insert_char('M');
-        assert!(editor.buffer.line(499).unwrap().starts_with('M'));
+        assert!(editor.buffer.borrow().line(499).unwrap().starts_with('M'));
 
         // Edit at end
         editor.set_cursor_line(999);
         let _ = editor.insert_char('Z');
-        assert!(editor.buffer.line(999).unwrap().starts_with('Z'));
+        assert!(editor.buffer.borrow().line(999).unwrap().starts_with('Z'));
 
         // Verify buffer is modified
-        assert!(editor.buffer.is_modified());
+        assert!(editor.buffer.borrow().is_modified());
     }
 
     #[test]
@@ -2377,13 +4945,13 @@ mod tests {
         let _ = editor.insert_char('Z');
 
         // Undo all
-        let _ = editor.buffer.undo();
-        let _ = editor.buffer.undo();
-        let _ = editor.buffer.undo();
+        let _ = editor.buffer.borrow_mut().undo();
+        let _ = editor.buffer.borrow_mut().undo();
+        let _ = editor.buffer.borrow_mut().undo();
 
         // Buffer should not be modified after full undo
         // (assuming we undid all changes)
-        let first_line = editor.buffer.line(0).unwrap();
+        let first_line = editor.buffer.borrow().line(0).unwrap();
         assert!(first_line.starts_with("Line 1:"));
     }
 
@@ -2407,7 +4975,9 @@ mod tests {
         // Rapid scrolling should be efficient
         let start = std::time::Instant::now();
         for _ in 0..1000 {
-            editor.viewport.scroll_down(10, editor.buffer.line_count());
+            editor
+                .viewport
+                .scroll_down(10, editor.buffer.borrow().line_count());
         }
         let scroll_time = start.elapsed();
 
@@ -2421,4 +4991,1259 @@ mod tests {
         // Verify we actually scrolled
         assert!(editor.viewport().top_line > 0);
     }
+
+    #[test]
+    fn test_large_file_mode_open_time() {
+        let start = std::time::Instant::now();
+        let (mut editor, _file) = create_large_file(150_000);
+        let open_time = start.elapsed();
+
+        // Opening skips highlighting and git diff, so it stays fast
+        assert!(
+            open_time.as_secs() < 5,
+            "Opening took too long: {:?}",
+            open_time
+        );
+        assert!(editor.is_large_file());
+        assert!(editor.get_editor_info().large_file);
+        assert!(!editor.config.syntax_highlighting);
+        assert!(!editor.config.word_wrap);
+        assert!(editor.git.diff_cache.is_none());
+
+        // Scrolling and search keep working
+        editor.viewport.resize(80, 24);
+        editor.set_cursor_line(149_999);
+        editor
+            .viewport
+            .ensure_cursor_visible(&editor.cursor, editor.buffer.borrow().line_count());
+        assert!(editor.viewport().is_cursor_visible(&editor.cursor));
+
+        editor.cursor = Cursor::new();
+        editor.start_search("Line 149999:".to_string(), true);
+        assert_eq!(editor.cursor.line, 149_998);
+    }
+
+    #[test]
+    fn test_enable_large_file_features() {
+        termide_i18n::init_with_language("en");
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "a\nb\nc\nd\n").unwrap();
+        file.flush().unwrap();
+
+        let config = EditorConfig {
+            large_file_threshold_lines: 3,
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::open_file_with_config(file.path().to_path_buf(), config).unwrap();
+        assert!(editor.is_large_file());
+        assert!(!editor.config.word_wrap);
+
+        editor.enable_large_file_features();
+        assert!(!editor.is_large_file());
+        assert!(editor.config.syntax_highlighting);
+        assert!(editor.config.word_wrap);
+        assert!(editor.status_message.is_some());
+
+        // Files below both thresholds open normally
+        let (editor, _file) = create_editor_with_content("a\nb\n");
+        assert!(!editor.is_large_file());
+    }
+
+    #[test]
+    fn test_apply_settings() {
+        termide_i18n::init_with_language("en");
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "a\nb\nc\nd\n").unwrap();
+        file.flush().unwrap();
+        let config = EditorConfig {
+            large_file_threshold_lines: 3,
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::open_file_with_config(file.path().to_path_buf(), config).unwrap();
+
+        editor.apply_settings(EditorConfig {
+            tab_size: 2,
+            scroll_off: 5,
+            word_wrap: false,
+            ..EditorConfig::default()
+        });
+        assert_eq!(editor.config.tab_size, 2);
+        assert_eq!(editor.viewport.scroll_off, 5);
+        assert!(!editor.config.syntax_highlighting);
+        // Word wrap stays off until large file mode is left
+        editor.apply_settings(EditorConfig::default());
+        assert!(!editor.config.word_wrap);
+        editor.enable_large_file_features();
+        assert!(editor.config.word_wrap);
+
+        let mut viewer = Editor::from_text("help", "Help".to_string());
+        viewer.apply_settings(EditorConfig::default());
+        assert!(viewer.config.read_only);
+    }
+
+    #[test]
+    fn test_tab_uses_detected_indentation() {
+        let (mut editor, _file) = create_editor_with_content("fn a() {\n\tb();\n}\n");
+        assert_eq!(editor.indent_style(), IndentStyle::Tabs);
+
+        editor.cursor = Cursor::at(1, 0);
+        editor.insert_tab().unwrap();
+        assert_eq!(editor.buffer.borrow().line(1).unwrap(), "\t\tb();\n");
+
+        editor.unindent_lines().unwrap();
+        assert_eq!(editor.buffer.borrow().line(1).unwrap(), "\tb();\n");
+        assert!(editor.get_editor_info().indent_tabs);
+    }
+
+    #[test]
+    fn test_configured_indentation_overrides_detection() {
+        let (mut editor, _file) = create_editor_with_content("fn a() {\n\tb();\n}\n");
+        editor.apply_settings(EditorConfig {
+            tab_size: 2,
+            indent_style: IndentMode::Spaces,
+            ..EditorConfig::default()
+        });
+        assert_eq!(editor.indent_style(), IndentStyle::Spaces(2));
+
+        editor.cursor = Cursor::at(1, 0);
+        editor.insert_tab().unwrap();
+        assert_eq!(editor.buffer.borrow().line(1).unwrap(), "  \tb();\n");
+    }
+
+    #[test]
+    fn test_tab_indents_selected_lines() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let (mut editor, _file) = create_editor_with_content("a\n  b\nc\nd\n");
+        assert_eq!(editor.indent_style(), IndentStyle::Spaces(2));
+
+        // Selection from the middle of line 0 to the start of line 3:
+        // line 3 is not indented
+        editor.selection = Some(Selection::new(Cursor::at(0, 1), Cursor::at(3, 0)));
+        editor.cursor = Cursor::at(3, 0);
+        editor.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(editor.buffer.borrow().text(), "  a\n    b\n  c\nd\n");
+        let sel = editor.selection.clone().unwrap();
+        assert_eq!(
+            (sel.anchor, sel.active),
+            (Cursor::at(0, 3), Cursor::at(3, 0))
+        );
+
+        // Shift+Tab removes at most one level and keeps the lines selected
+        editor.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+        editor.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!(editor.buffer.borrow().text(), "a\nb\nc\nd\n");
+        let sel = editor.selection.clone().unwrap();
+        assert_eq!(
+            (sel.anchor, sel.active),
+            (Cursor::at(0, 1), Cursor::at(3, 0))
+        );
+
+        // Whole-line selection keeps starting at column 0; one undo step
+        editor.selection = Some(Selection::new(Cursor::at(0, 0), Cursor::at(1, 1)));
+        editor.cursor = Cursor::at(1, 1);
+        editor.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(editor.buffer.borrow().text(), "  a\n  b\nc\nd\n");
+        let sel = editor.selection.clone().unwrap();
+        assert_eq!(
+            (sel.anchor, sel.active),
+            (Cursor::at(0, 0), Cursor::at(1, 3))
+        );
+        assert_eq!(editor.cursor, Cursor::at(1, 3));
+        editor.handle_undo_redo(|buf| buf.undo()).unwrap();
+        assert_eq!(editor.buffer.borrow().text(), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn test_convert_indentation_toggles_style() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("a:\n  b:\n    c\n");
+        assert_eq!(editor.indent_style(), IndentStyle::Spaces(2));
+
+        editor.convert_indentation().unwrap();
+        assert_eq!(editor.buffer.borrow().text(), "a:\n\tb:\n\t\tc\n");
+        assert_eq!(editor.indent_style(), IndentStyle::Tabs);
+
+        editor.handle_undo_redo(|buf| buf.undo()).unwrap();
+        assert_eq!(editor.buffer.borrow().text(), "a:\n  b:\n    c\n");
+    }
+
+    #[test]
+    fn test_save_applies_transforms_and_reports_them() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, file) = create_editor_with_content("a  \nb\t\nc");
+        editor.config.save_transforms = SaveTransforms {
+            trim_trailing_whitespace: true,
+            trim_modified_lines_only: false,
+            ensure_final_newline: true,
+        };
+        editor.cursor = Cursor::at(1, 1);
+
+        editor.save().unwrap();
+
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "a\nb\nc\n");
+        assert_eq!(editor.buffer.borrow().text(), "a\nb\nc\n");
+        assert_eq!(editor.cursor, Cursor::at(1, 1));
+        assert_eq!(
+            editor.status_message.as_deref(),
+            Some("Saved: stripped 3 trailing spaces, fixed final newline")
+        );
+    }
+
+    #[test]
+    fn test_toggle_line_ending() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, file) = create_editor_with_content("a\r\nb\r\n");
+        assert_eq!(editor.get_editor_info().line_ending, "CRLF");
+
+        editor.toggle_line_ending();
+        assert_eq!(editor.get_editor_info().line_ending, "LF");
+        assert!(editor.buffer_is_modified());
+
+        editor.save().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "a\nb\n");
+    }
+
+    #[test]
+    fn test_read_only_file_rejects_edits_until_edit_anyway() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("text\n");
+        editor.file_state.read_only = true;
+        assert!(editor.title().contains("[RO]"));
+        assert!(editor.get_editor_info().read_only);
+
+        let events = editor.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "text\n");
+        assert!(matches!(
+            events.as_slice(),
+            [PanelEvent::SetStatusMessage { is_error: true, .. }]
+        ));
+
+        // Navigation still works
+        editor.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        assert_eq!(editor.cursor.column, 4);
+
+        editor.handle_key(KeyEvent::new(
+            KeyCode::Char('W'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        ));
+        assert!(!editor.is_read_only());
+        assert!(editor.file_state.edit_anyway);
+
+        editor.handle_key(KeyEvent::new(KeyCode::Char('!'), KeyModifiers::NONE));
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "text!\n");
+
+        // Permission re-check sees a writable file and clears the override
+        editor.check_external_modification();
+        assert!(!editor.file_state.edit_anyway);
+        assert!(!editor.title().contains("[RO]"));
+    }
+
+    #[test]
+    fn test_save_as_keeps_edits_of_read_only_file() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("text\n");
+        editor.file_state.read_only = true;
+        editor.edit_anyway();
+        editor.insert_text("new ").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let copy = dir.path().join("copy.txt");
+        editor.save_file_as(copy.clone()).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "new text\n");
+        assert!(!editor.file_state.read_only);
+        assert!(!editor.file_state.edit_anyway);
+        assert!(!editor.has_external_change());
+    }
+
+    #[test]
+    fn test_goto_and_revert_hunk() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, file) = create_editor_with_content("a\nB\nc\nd\n");
+        let mut cache = GitDiffCache::new(file.path().to_path_buf());
+        cache.apply_async_result(termide_git::GitDiffAsyncResult {
+            file_path: file.path().to_path_buf(),
+            original_content: Some("a\nb\nc\nd\ne\n".to_string()),
+        });
+        editor.git.diff_cache = Some(cache);
+
+        editor.goto_hunk(true);
+        assert_eq!(editor.cursor.line, 1);
+        editor.goto_hunk(true);
+        assert_eq!(editor.cursor.line, 3);
+        editor.goto_hunk(false);
+        assert_eq!(editor.cursor.line, 1);
+
+        editor.revert_hunk().unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a\nb\nc\nd\n");
+
+        // Deleted lines are restored after the marker line
+        editor.set_cursor_line(3);
+        editor.revert_hunk().unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a\nb\nc\nd\ne\n");
+
+        editor.handle_undo_redo(|buf| buf.undo()).unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn test_toggle_comment_uses_language_prefix() {
+        termide_i18n::init_with_language("en");
+        let mut file = tempfile::Builder::new().suffix(".py").tempfile().unwrap();
+        write!(file, "if x:\n    y()\n").unwrap();
+        let mut editor = Editor::open_file(file.path().to_path_buf()).unwrap();
+
+        editor.cursor = Cursor::at(1, 4);
+        editor.selection = Some(Selection::new(Cursor::at(0, 0), Cursor::at(1, 4)));
+        editor.toggle_comment().unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "# if x:\n#     y()\n");
+        assert_eq!(editor.cursor, Cursor::at(1, 6));
+
+        editor.toggle_comment().unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "if x:\n    y()\n");
+        assert_eq!(editor.cursor, Cursor::at(1, 4));
+
+        // Files without comment syntax are left unchanged
+        let (mut editor, _file) = create_editor_with_content("a\n");
+        editor.toggle_comment().unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a\n");
+        assert!(editor.status_message.is_some());
+    }
+
+    #[test]
+    fn test_extensionless_script_language_from_shebang() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("#!/usr/bin/env bash\necho hi\n");
+        assert_eq!(editor.render_cache.highlight.current_syntax(), Some("bash"));
+
+        editor.cursor = Cursor::at(1, 0);
+        editor.toggle_comment().unwrap();
+        assert_eq!(
+            editor.buffer.borrow().to_string(),
+            "#!/usr/bin/env bash\n# echo hi\n"
+        );
+    }
+
+    #[test]
+    fn test_syntax_override() {
+        let mut file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        writeln!(file, "{{\"a\": 1}}").unwrap();
+        let mut editor = Editor::open_file(file.path().to_path_buf()).unwrap();
+        assert_eq!(editor.render_cache.highlight.current_syntax(), None);
+
+        editor.set_syntax_override("json");
+        assert_eq!(editor.render_cache.highlight.current_syntax(), Some("json"));
+        assert_eq!(editor.get_editor_info().file_type, "JSON");
+
+        // Plain text drops highlighting and comment syntax
+        editor.set_syntax_override(constants::PLAIN_TEXT_SYNTAX);
+        assert!(!editor.render_cache.highlight.has_syntax());
+        assert_eq!(editor.language(), None);
+
+        // Unknown languages and languages whose grammar did not load keep
+        // the current override
+        editor.set_syntax_override("cobol");
+        assert_eq!(editor.syntax_override(), Some(constants::PLAIN_TEXT_SYNTAX));
+        let unloaded = termide_highlight::SUPPORTED_LANGUAGES
+            .iter()
+            .find(|lang| !termide_highlight::highlighted_languages().any(|l| l == **lang));
+        if let Some(language) = unloaded {
+            editor.set_syntax_override(language);
+            assert_eq!(editor.syntax_override(), Some(constants::PLAIN_TEXT_SYNTAX));
+        }
+
+        let session_dir = tempfile::tempdir().unwrap();
+        match editor.to_session(session_dir.path()) {
+            Some(SessionPanel::Editor { syntax, .. }) => {
+                assert_eq!(syntax.as_deref(), Some(constants::PLAIN_TEXT_SYNTAX))
+            }
+            other => panic!("unexpected session panel: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_auto_close_brackets() {
+        let (mut editor, _file) = create_editor_with_content("\n");
+
+        editor.insert_char('(').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "()\n");
+        assert_eq!(editor.cursor, Cursor::at(0, 1));
+
+        // Pair insert is a single undo step
+        editor.handle_undo_redo(|buf| buf.undo()).unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "\n");
+
+        editor.cursor = Cursor::at(0, 0);
+        editor.insert_char('(').unwrap();
+        editor.insert_char('a').unwrap();
+        editor.insert_char(')').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "(a)\n");
+        assert_eq!(editor.cursor, Cursor::at(0, 3));
+
+        // Backspace between an empty pair deletes both characters
+        editor.insert_char('[').unwrap();
+        editor.backspace().unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "(a)\n");
+
+        // No pair in front of a word
+        editor.cursor = Cursor::at(0, 1);
+        editor.insert_char('{').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "({a)\n");
+    }
+
+    #[test]
+    fn test_auto_close_quotes_respects_escapes() {
+        let (mut editor, _file) = create_editor_with_content("s = \"a\\\"\n");
+
+        // Quote after a backslash is inserted instead of skipping over
+        editor.cursor = Cursor::at(0, 7);
+        editor.insert_char('"').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "s = \"a\\\"\"\n");
+
+        // Apostrophe after a word character is not paired
+        let (mut editor, _file) = create_editor_with_content("don\n");
+        editor.cursor = Cursor::at(0, 3);
+        editor.insert_char('\'').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "don'\n");
+    }
+
+    #[test]
+    fn test_auto_close_wraps_selection() {
+        let (mut editor, _file) = create_editor_with_content("a word\n");
+        editor.cursor = Cursor::at(0, 6);
+        editor.selection = Some(Selection::new(Cursor::at(0, 2), Cursor::at(0, 6)));
+
+        editor.insert_char('"').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a \"word\"\n");
+        let sel = editor.selection.as_ref().unwrap();
+        assert_eq!(
+            (sel.start(), sel.end()),
+            (Cursor::at(0, 3), Cursor::at(0, 7))
+        );
+
+        editor.handle_undo_redo(|buf| buf.undo()).unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a word\n");
+
+        // Disabled by config
+        editor.config.auto_close_brackets = false;
+        editor.selection = None;
+        editor.cursor = Cursor::at(0, 6);
+        editor.insert_char('(').unwrap();
+        assert_eq!(editor.buffer.borrow().to_string(), "a word(\n");
+    }
+
+    #[test]
+    fn test_fold_skips_hidden_lines() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        termide_i18n::init_with_language("en");
+        let mut file = tempfile::Builder::new().suffix(".rs").tempfile().unwrap();
+        write!(
+            file,
+            "fn a() {{
+    b();
+    c();
+}}
+fn d() {{}}
+"
+        )
+        .unwrap();
+        let mut editor = Editor::open_file(file.path().to_path_buf()).unwrap();
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        editor.cursor = Cursor::at(1, 2);
+        editor.handle_key(key(KeyCode::F(9)));
+        assert!(editor.viewport.folds.is_folded(0));
+        assert_eq!(editor.cursor, Cursor::at(0, 0));
+
+        // Moving down skips the folded body, moving up lands on the header
+        editor.handle_key(key(KeyCode::Down));
+        assert_eq!(editor.cursor.line, 3);
+        editor.handle_key(key(KeyCode::Up));
+        assert_eq!(editor.cursor.line, 0);
+
+        // Nothing to fold on a single-line function
+        editor.cursor = Cursor::at(4, 0);
+        let events = editor.handle_key(key(KeyCode::F(9)));
+        assert_eq!(events.len(), 1);
+
+        editor.handle_key(KeyEvent::new(KeyCode::F(9), KeyModifiers::SHIFT));
+        assert!(!editor.viewport.folds.has_folds());
+    }
+
+    #[test]
+    fn test_edits_and_jumps_unfold() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let (mut editor, _file) = create_editor_with_content("a:\n  b\n  c\nd\n");
+
+        editor.fold_all();
+        assert!(editor.viewport.folds.is_folded(0));
+
+        // Typing on the folded header unfolds it
+        editor.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        editor.sync_line_edits();
+        assert!(!editor.viewport.folds.has_folds());
+
+        // Search match inside a fold unfolds it
+        editor.fold_all();
+        editor.start_search("c".to_string(), false);
+        assert_eq!(editor.cursor.line, 2);
+        editor.sync_line_edits();
+        assert!(!editor.viewport.folds.has_folds());
+    }
+
+    #[test]
+    fn test_cancelled_search_restores_position() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("one\ntwo\nthree two\n");
+        editor.cursor = Cursor::at(1, 1);
+        editor.viewport.left_column = 2;
+
+        editor.open_search_modal(false);
+        editor.search_as_you_type("t".to_string());
+        editor.search_as_you_type("thr".to_string());
+        assert_eq!(editor.cursor, Cursor::at(2, 3));
+        assert!(editor.selection.is_some());
+
+        editor.cancel_search();
+        assert_eq!(editor.cursor, Cursor::at(1, 1));
+        assert!(editor.selection.is_none());
+        assert_eq!(editor.viewport.left_column, 2);
+
+        // Enter keeps the match
+        editor.open_search_modal(false);
+        editor.search_as_you_type("two".to_string());
+        editor.confirm_search();
+        editor.close_search();
+        assert_eq!(editor.cursor, Cursor::at(2, 9));
+    }
+
+    #[test]
+    fn test_search_in_selection_while_cycling() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("x x\nx x\nx x\n");
+        editor.selection = Some(Selection::new(Cursor::at(0, 2), Cursor::at(1, 3)));
+        editor.cursor = Cursor::at(1, 3);
+
+        editor.handle_start_replace();
+        editor.set_search_options(false, true);
+        editor.start_replace("x".to_string(), "yy".to_string(), false);
+        assert_eq!(editor.search_options(), (false, true));
+        assert_eq!(editor.get_search_match_info(), Some((0, 3)));
+
+        // Cycling stays in the selection although the match is selected now
+        for expected in [Cursor::at(1, 0), Cursor::at(1, 2), Cursor::at(0, 2)] {
+            editor.search_next();
+            assert_eq!(editor.selection.as_ref().unwrap().start(), expected);
+        }
+
+        assert_eq!(editor.replace_all().unwrap(), 3);
+        assert_eq!(editor.buffer().text(), "x yy\nyy yy\nx x\n");
+        assert_eq!(
+            editor.take_status_message().as_deref(),
+            Some("Replaced 3 occurrences in selection")
+        );
+    }
+
+    #[test]
+    fn test_goto_position_clamps_column() {
+        let (mut editor, _file) = create_editor_with_content("one\ntwo three\n");
+        editor.goto_position(1, 4);
+        assert_eq!(editor.cursor, Cursor::at(1, 4));
+        editor.goto_position(0, 80);
+        assert_eq!(editor.cursor, Cursor::at(0, 3));
+    }
+
+    #[test]
+    fn test_jump_back_and_forward() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let content: String = (0..40).map(|i| format!("line {i}\n")).collect();
+        let (mut editor, _file) = create_editor_with_content(&content);
+        let ctrl = |code, modifiers| KeyEvent::new(code, KeyModifiers::CONTROL | modifiers);
+
+        editor.cursor = Cursor::at(2, 3);
+        editor.goto_line(30);
+        editor.start_search("line 12".to_string(), false);
+        editor.close_search();
+        assert_eq!(editor.cursor.line, 12);
+
+        editor.handle_key(ctrl(KeyCode::Char('o'), KeyModifiers::NONE));
+        assert_eq!(editor.cursor, Cursor::at(30, 0));
+        editor.handle_key(ctrl(KeyCode::Char('o'), KeyModifiers::NONE));
+        assert_eq!(editor.cursor, Cursor::at(2, 3));
+        editor.handle_key(ctrl(KeyCode::Char('O'), KeyModifiers::SHIFT));
+        assert_eq!(editor.cursor, Cursor::at(30, 0));
+
+        // Positions follow edits above them
+        editor.cursor = Cursor::at(0, 0);
+        editor.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        editor.handle_key(ctrl(KeyCode::Char('O'), KeyModifiers::SHIFT));
+        assert_eq!(editor.cursor.line, 13);
+
+        // Small movements are not recorded, page jumps are
+        editor.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        editor.handle_key(ctrl(KeyCode::Home, KeyModifiers::NONE));
+        editor.handle_key(ctrl(KeyCode::Char('o'), KeyModifiers::NONE));
+        assert_eq!(editor.cursor.line, 14);
+    }
+
+    /// Replace the file under an open editor, as another program would
+    fn modify_on_disk(editor: &mut Editor, file: &NamedTempFile, content: &str) {
+        std::fs::write(file.path(), content).unwrap();
+        // The rewrite may land within the mtime resolution of the load
+        editor
+            .buffer
+            .set_mtime(Some(std::time::SystemTime::UNIX_EPOCH));
+    }
+
+    #[test]
+    fn test_unmodified_buffer_reloads_and_keeps_cursor_on_content() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, file) = create_editor_with_content("one\ntwo\nthree\nfour\n");
+        editor.cursor.line = 2;
+        editor.cursor.column = 3;
+
+        modify_on_disk(&mut editor, &file, "zero\none\ntwo\nthree\nfour\n");
+        editor.check_external_modification();
+
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "zero\n");
+        assert!(!editor.has_external_change());
+        assert!(editor.take_modal_request().is_none());
+        assert!(editor.take_status_message().is_some());
+        // Still on "three"
+        assert_eq!((editor.cursor.line, editor.cursor.column), (3, 3));
+
+        // The line under the cursor was removed: stay where it was
+        modify_on_disk(&mut editor, &file, "zero\none\nfour\n");
+        editor.check_external_modification();
+        assert_eq!(editor.cursor.line, 2);
+        assert_eq!(editor.cursor.column, 3);
+    }
+
+    #[test]
+    fn test_modified_buffer_asks_about_conflict_once() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, file) = create_editor_with_content("line\n");
+        editor.insert_text("mine ").unwrap();
+
+        modify_on_disk(&mut editor, &file, "theirs\n");
+        editor.check_external_modification();
+
+        // Local edits are kept until the user decides
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "mine line\n");
+        assert!(editor.has_external_change());
+        match editor.take_modal_request() {
+            Some((PendingAction::ResolveExternalChange { path }, ActiveModal::Select(_))) => {
+                assert_eq!(path, file.path());
+            }
+            _ => panic!("expected conflict modal"),
+        }
+
+        // Further checks for the same change do not ask again
+        editor.check_external_modification();
+        assert!(editor.take_modal_request().is_none());
+
+        let diff = editor.external_diff().unwrap();
+        assert!(diff.contains("-theirs\n+mine line\n"));
+
+        // Keeping local edits makes the disk version the new baseline
+        editor.keep_local_changes();
+        assert!(!editor.has_external_change());
+        editor.save().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "mine line\n");
+    }
+
+    #[test]
+    fn test_unsaved_diff_against_disk_or_nothing() {
+        let (mut editor, _file) = create_editor_with_content("one\ntwo\n");
+        assert!(editor.unsaved_diff().unwrap().is_empty());
+        editor.insert_text("new ").unwrap();
+        assert!(editor
+            .unsaved_diff()
+            .unwrap()
+            .contains("-one\n+new one\n two\n"));
+
+        let mut unnamed = Editor::new();
+        unnamed.insert_text("draft").unwrap();
+        let diff = unnamed.unsaved_diff().unwrap();
+        assert!(diff.contains("@@ -0,0 +1 @@\n+draft"));
+    }
+
+    #[test]
+    fn test_reload_theirs_discards_local_edits() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, file) = create_editor_with_content("line\n");
+        editor.insert_text("mine ").unwrap();
+
+        modify_on_disk(&mut editor, &file, "theirs\n");
+        editor.check_external_modification();
+        editor.reload_from_disk().unwrap();
+
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "theirs\n");
+        assert!(!editor.buffer_is_modified());
+        assert!(!editor.has_external_change());
+    }
+
+    #[test]
+    fn test_reopen_and_convert_encoding() {
+        termide_i18n::init_with_language("en");
+        // "Привет" in KOI8-R, reloaded in the UTF-8 of the empty file
+        let (mut editor, file) = create_editor_with_content("");
+        std::fs::write(file.path(), b"\xf0\xd2\xc9\xd7\xc5\xd4\n").unwrap();
+        editor.reload_from_disk().unwrap();
+        assert!(editor.buffer.borrow().line(0).unwrap().contains('\u{FFFD}'));
+
+        editor.set_encoding("KOI8-R").unwrap();
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "Привет\n");
+        assert_eq!(editor.get_editor_info().encoding, "KOI8-R");
+        assert!(!editor.buffer_is_modified());
+
+        // With unsaved edits the text is kept and saved in the new encoding
+        editor.insert_text("!").unwrap();
+        editor.set_encoding("UTF-8").unwrap();
+        assert_eq!(editor.buffer.borrow().line(0).unwrap(), "!Привет\n");
+        editor.save().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "!Привет\n");
+    }
+
+    #[test]
+    fn test_undo_tree_switches_branches() {
+        termide_i18n::init_with_language("en");
+        let (mut editor, _file) = create_editor_with_content("x\n");
+        let line = |editor: &Editor| editor.buffer.borrow().line(0).unwrap();
+        editor.open_undo_tree();
+        assert!(editor.take_modal_request().is_none());
+        assert_eq!(
+            editor.take_status_message().as_deref(),
+            Some("No edits to undo")
+        );
+
+        editor.insert_text("one ").unwrap();
+        editor.handle_undo_redo(|buffer| buffer.undo()).unwrap();
+        editor.insert_text("two ").unwrap();
+        assert_eq!(line(&editor), "two x\n");
+
+        editor.open_undo_tree();
+        let Some((PendingAction::SwitchUndoBranch { branches }, ActiveModal::Select(_))) =
+            editor.take_modal_request()
+        else {
+            panic!("expected undo tree modal");
+        };
+        // Newest branch first: the current text
+        assert_eq!(branches.len(), 2);
+        editor.switch_undo_branch(branches[1]).unwrap();
+        assert_eq!(line(&editor), "one x\n");
+        editor.switch_undo_branch(branches[0]).unwrap();
+        assert_eq!(line(&editor), "two x\n");
+
+        // Plain undo and redo walk the branch that was switched to
+        editor.handle_undo_redo(|buffer| buffer.undo()).unwrap();
+        assert_eq!(line(&editor), "x\n");
+        editor.handle_undo_redo(|buffer| buffer.redo()).unwrap();
+        assert_eq!(line(&editor), "two x\n");
+    }
+
+    #[test]
+    fn test_preview_undo_actions() {
+        let insert = Action::Insert {
+            position: Cursor::at(0, 0),
+            text: "fn main() {\n".to_string(),
+        };
+        assert_eq!(preview_action(&insert), "+ \"fn main() {⏎\"");
+        let delete = Action::Delete {
+            position: Cursor::at(0, 0),
+            text: "x".repeat(30),
+        };
+        assert_eq!(
+            preview_action(&delete),
+            format!("- \"{}…\"", "x".repeat(24))
+        );
+        let group = Action::Group {
+            actions: vec![delete, insert],
+        };
+        assert!(preview_action(&group).ends_with("…\" …"));
+    }
+
+    #[test]
+    fn test_word_deletion_and_transpose() {
+        let (mut editor, _file) = create_editor_with_content("let snake_case = ключ.len();\n");
+        let line = |editor: &Editor| editor.buffer.borrow().line(0).unwrap();
+
+        // Underscores join words, Cyrillic words are not split
+        editor.cursor = Cursor::at(0, 14);
+        editor.delete_word_backward().unwrap();
+        assert_eq!(line(&editor), "let  = ключ.len();\n");
+        assert_eq!(editor.cursor, Cursor::at(0, 4));
+        editor.cursor = Cursor::at(0, 6);
+        editor.delete_word_forward().unwrap();
+        assert_eq!(line(&editor), "let  =.len();\n");
+
+        // Each deletion is one undo step
+        editor.handle_undo_redo(|buffer| buffer.undo()).unwrap();
+        assert_eq!(line(&editor), "let  = ключ.len();\n");
+
+        // A selection is deleted instead of a word
+        editor.selection = Some(Selection::new(Cursor::at(0, 7), Cursor::at(0, 9)));
+        keyboard::EditorCommand::DeleteWordBackward
+            .execute(&mut editor)
+            .unwrap();
+        assert_eq!(line(&editor), "let  = юч.len();\n");
+
+        // Transposing swaps the word at the cursor with the next one
+        editor.cursor = Cursor::at(0, 1);
+        editor.transpose_words().unwrap();
+        assert_eq!(line(&editor), "юч  = let.len();\n");
+        assert_eq!(editor.cursor, Cursor::at(0, 9));
+    }
+
+    #[test]
+    fn test_smart_home_and_line_operations() {
+        let (mut editor, _file) = create_editor_with_content("a\n    b\nc");
+        let text = |editor: &Editor| editor.buffer.borrow().text();
+
+        // Home toggles between indentation and line start
+        editor.cursor = Cursor::at(1, 5);
+        editor.move_to_visual_line_start();
+        assert_eq!(editor.cursor.column, 4);
+        editor.move_to_visual_line_start();
+        assert_eq!(editor.cursor.column, 0);
+        editor.move_to_visual_line_start();
+        assert_eq!(editor.cursor.column, 4);
+
+        // Moving stops at the buffer edges, the cursor stays on the line
+        editor.move_lines(true).unwrap();
+        assert_eq!(text(&editor), "    b\na\nc");
+        assert_eq!(editor.cursor, Cursor::at(0, 4));
+        editor.move_lines(true).unwrap();
+        assert_eq!(text(&editor), "    b\na\nc");
+        editor.cursor = Cursor::at(1, 0);
+        editor.move_lines(false).unwrap();
+        assert_eq!(text(&editor), "    b\nc\na");
+        editor.move_lines(false).unwrap();
+        assert_eq!(editor.cursor, Cursor::at(2, 0));
+        editor.handle_undo_redo(|buffer| buffer.undo()).unwrap();
+        assert_eq!(text(&editor), "    b\na\nc");
+
+        // Duplicating keeps the cursor on the copy
+        editor.cursor = Cursor::at(0, 2);
+        editor.duplicate_line().unwrap();
+        assert_eq!(text(&editor), "    b\n    b\na\nc");
+        assert_eq!(editor.cursor, Cursor::at(1, 2));
+
+        // Deleting the last line removes the line break before it
+        editor.cursor = Cursor::at(3, 0);
+        editor.delete_lines().unwrap();
+        assert_eq!(text(&editor), "    b\n    b\na");
+        editor.selection = Some(Selection::new(Cursor::at(0, 1), Cursor::at(1, 1)));
+        editor.delete_lines().unwrap();
+        assert_eq!(text(&editor), "a");
+        editor.handle_undo_redo(|buffer| buffer.undo()).unwrap();
+        assert_eq!(text(&editor), "    b\n    b\na");
+    }
+
+    #[test]
+    fn test_scroll_off_and_recenter_with_word_wrap() {
+        let content: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        let (mut editor, _file) = create_editor_with_content(&content);
+        let (theme, config) = (Theme::default(), Config::default());
+        let area = Rect::new(0, 0, 60, 20);
+        let render = |editor: &mut Editor| {
+            let mut buf = Buffer::empty(area);
+            editor.render_content(area, &mut buf, &theme, &config);
+            editor.cursor_screen_row()
+        };
+        render(&mut editor);
+        let height = editor.viewport.height;
+
+        // The margin ends at the document start and end
+        editor.cursor = Cursor::at(1, 0);
+        assert_eq!(render(&mut editor), 1);
+        editor.cursor = Cursor::at(100, 0);
+        assert_eq!(render(&mut editor), height - 1);
+
+        // Moving keeps 3 rows above and below the cursor
+        editor.cursor = Cursor::at(50, 0);
+        assert_eq!(render(&mut editor), 3);
+        editor.cursor = Cursor::at(70, 0);
+        assert_eq!(render(&mut editor), height - 4);
+
+        // Ctrl+L cycles center, top and bottom
+        editor.recenter();
+        assert_eq!(render(&mut editor), height / 2);
+        editor.recenter();
+        assert_eq!(render(&mut editor), 3);
+        editor.recenter();
+        assert_eq!(render(&mut editor), height - 4);
+
+        // Half-page scrolls keep the cursor on its screen row
+        editor.scroll_half_page(false);
+        assert_eq!(editor.cursor.line, 70 - height / 2);
+        assert_eq!(render(&mut editor), height - 4);
+    }
+
+    #[test]
+    fn test_vertical_movement_by_wrapped_rows_or_lines() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let long = "word ".repeat(30);
+        let (mut editor, _file) = create_editor_with_content(&format!("{}\nx\nabcdefgh\n", long));
+        let (theme, config) = (Theme::default(), Config::default());
+        let area = Rect::new(0, 0, 40, 20);
+        editor.render_content(area, &mut Buffer::empty(area), &theme, &config);
+        let down = |editor: &mut Editor| {
+            editor.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+            editor.cursor
+        };
+
+        // Down moves to the next wrapped row of the long line
+        editor.cursor = Cursor::at(0, 6);
+        let cursor = down(&mut editor);
+        assert_eq!(cursor.line, 0);
+        assert!(cursor.column > 6);
+
+        // By whole lines, keeping the column across the short line
+        editor.config.visual_line_navigation = false;
+        editor.cursor = Cursor::at(0, 6);
+        editor.input.preferred_column = None;
+        assert_eq!(down(&mut editor), Cursor::at(1, 1));
+        assert_eq!(down(&mut editor), Cursor::at(2, 6));
+    }
+
+    #[test]
+    fn test_scrollbar_click_and_drag() {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+        let content: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let (mut editor, _file) = create_editor_with_content(&content);
+        let (theme, config) = (Theme::default(), Config::default());
+        let panel_area = Rect::new(0, 0, 62, 22);
+        let area = Rect::new(1, 1, 60, 20);
+        let mut buf = Buffer::empty(panel_area);
+        editor.render_content(area, &mut buf, &theme, &config);
+        assert_eq!(editor.viewport.width, 60 - rendering::LINE_NUMBER_WIDTH - 1);
+
+        let mouse = |kind, row| MouseEvent {
+            kind,
+            column: 60,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        // Clicking halfway down centers the middle of the document
+        editor.handle_mouse(
+            mouse(MouseEventKind::Down(MouseButton::Left), 11),
+            panel_area,
+        );
+        assert_eq!(editor.viewport.top_line, 105 - 10);
+        assert!(editor.viewport.is_line_visible(editor.cursor.line));
+
+        // Dragging keeps scrolling after leaving the scrollbar column
+        let mut drag = mouse(MouseEventKind::Drag(MouseButton::Left), 21);
+        drag.column = 30;
+        editor.handle_mouse(drag, panel_area);
+        assert_eq!(editor.viewport.top_line, 201 - 20);
+        editor.handle_mouse(mouse(MouseEventKind::Up(MouseButton::Left), 21), panel_area);
+        assert!(!editor.input.scrollbar_drag);
+        assert!(editor.selection.is_none());
+
+        // Narrow panels have no scrollbar
+        let narrow = Rect::new(1, 1, 30, 20);
+        editor.render_content(narrow, &mut buf, &theme, &config);
+        assert_eq!(editor.viewport.width, 30 - rendering::LINE_NUMBER_WIDTH);
+    }
+
+    #[test]
+    fn test_split_view_shares_buffer() {
+        termide_i18n::init_with_language("en");
+        let (mut first, file) = create_editor_with_content("a\nb\nc\n");
+        first.cursor = Cursor::at(1, 0);
+        let mut second = first.split_view();
+        second.cursor = Cursor::at(2, 0);
+
+        // Edits move the cursor of the other view along
+        first.insert_text("new\n").unwrap();
+        second.sync_line_edits();
+        assert_eq!(second.cursor.line, 3);
+        assert_eq!(second.buffer.borrow().line(3).unwrap(), "c\n");
+
+        // Closing one of the views keeps the changes without asking
+        assert!(first.needs_close_confirmation().is_none());
+        assert!(first.shares_buffer_with(&second));
+        assert!(!first.shares_buffer_with(&Editor::new()));
+
+        // Undo and save work from either view
+        second.insert_text("x").unwrap();
+        second.handle_undo_redo(|buffer| buffer.undo()).unwrap();
+        assert_eq!(first.buffer.borrow().line(0).unwrap(), "new\n");
+        second.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "new\na\nb\nc\n"
+        );
+        assert!(!first.buffer_is_modified());
+
+        // Reload in one view resets the other
+        std::fs::write(file.path(), "a\n").unwrap();
+        first.reload_from_disk().unwrap();
+        second.sync_line_edits();
+        assert_eq!(second.cursor.line, 0);
+
+        drop(second);
+        first.insert_text("y").unwrap();
+        assert!(first.needs_close_confirmation().is_some());
+    }
+
+    #[test]
+    fn test_word_completion_popup() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let (mut editor, _file) = create_editor_with_content(
+            "counter
+count_all
+
+",
+        );
+        let other = Editor::from_text(
+            "country
+",
+            "other".to_string(),
+        );
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        editor.cursor = Cursor::at(2, 0);
+
+        // Offered from the minimum prefix length, closest words first
+        for ch in "co".chars() {
+            editor.handle_key(key(KeyCode::Char(ch)));
+        }
+        assert!(editor.completion.is_none());
+        editor.handle_key(key(KeyCode::Char('u')));
+        let items = &editor.completion.as_ref().unwrap().items;
+        assert_eq!(items, &["count_all", "counter", "country"]);
+
+        // Typing refines the list, arrows pick and Tab inserts the rest
+        editor.handle_key(key(KeyCode::Char('n')));
+        editor.handle_key(key(KeyCode::Char('t')));
+        editor.handle_key(key(KeyCode::Char('e')));
+        assert_eq!(editor.completion.as_ref().unwrap().items, ["counter"]);
+        editor.handle_key(key(KeyCode::Backspace));
+        editor.handle_key(key(KeyCode::Down));
+        editor.handle_key(key(KeyCode::Tab));
+        assert!(editor.completion.is_none());
+        assert_eq!(editor.buffer.borrow().line(2).unwrap(), "counter\n");
+        assert_eq!(editor.cursor, Cursor::at(2, 7));
+
+        // Esc closes the popup, other keys pass through
+        editor.handle_key(key(KeyCode::Enter));
+        for ch in "cou".chars() {
+            editor.handle_key(key(KeyCode::Char(ch)));
+        }
+        assert!(editor.captures_escape());
+        editor.handle_key(key(KeyCode::Esc));
+        assert!(editor.completion.is_none());
+        editor.handle_key(key(KeyCode::Enter));
+        assert_eq!(editor.cursor, Cursor::at(4, 0));
+
+        drop(other);
+        editor.config.autocomplete = false;
+        for ch in "cou".chars() {
+            editor.handle_key(key(KeyCode::Char(ch)));
+        }
+        assert!(editor.completion.is_none());
+    }
+
+    #[test]
+    fn test_format_on_save() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        termide_i18n::init_with_language("en");
+        let mut file = tempfile::Builder::new().suffix(".py").tempfile().unwrap();
+        write!(file, "x=1\ny=2\n").unwrap();
+        let config = EditorConfig {
+            formatters: BTreeMap::from([("python".to_string(), "tr = :".to_string())]),
+            format_on_save: vec!["python".to_string()],
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::open_file_with_config(file.path().to_path_buf(), config).unwrap();
+        editor.cursor = Cursor::at(1, 2);
+        editor.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(editor.format_job.is_some());
+
+        let mut events = Vec::new();
+        for _ in 0..500 {
+            events = editor.tick();
+            if !events.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(
+            &events[0],
+            PanelEvent::SetStatusMessage {
+                is_error: false,
+                ..
+            }
+        ));
+        assert_eq!(editor.buffer().text(), "x:1\ny:2\n");
+        assert_eq!(editor.cursor, Cursor::at(1, 2));
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "x:1\ny:2\n");
+
+        // Formatting is a single undo step
+        editor.handle_key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL));
+        assert_eq!(editor.buffer().text(), "x=1\ny=2\n");
+    }
+
+    #[test]
+    fn test_correct_spelling() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        let words = dir.path().join("words");
+        std::fs::write(&words, "the\nquick\nfox\n").unwrap();
+        let dictionary = Arc::new(Dictionary::new(
+            vec![words],
+            Some(dir.path().join("dictionary.txt")),
+        ));
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "Teh quick foxx\n").unwrap();
+        let config = EditorConfig {
+            spell_dictionary: Some(dictionary.clone()),
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::open_file_with_config(path, config).unwrap();
+        editor.cursor = Cursor::at(0, 1);
+        editor.handle_key(KeyEvent::new(KeyCode::F(8), KeyModifiers::NONE));
+        let Some((
+            PendingAction::CorrectSpelling {
+                line,
+                column,
+                word,
+                suggestions,
+            },
+            _,
+        )) = editor.modal_request.take()
+        else {
+            panic!("no spelling suggestions");
+        };
+        assert_eq!((line, column, word.as_str()), (0, 0, "Teh"));
+        assert_eq!(suggestions[0], "The");
+
+        editor
+            .correct_spelling(line, column, &word, Some(&suggestions[0]))
+            .unwrap();
+        assert_eq!(editor.buffer().text(), "The quick foxx\n");
+        assert_eq!(editor.cursor, Cursor::at(0, 3));
+
+        editor.correct_spelling(0, 10, "foxx", None).unwrap();
+        assert!(dictionary.is_known("foxx"));
+        editor.cursor = Cursor::at(0, 12);
+        let events = editor.handle_key(KeyEvent::new(KeyCode::F(8), KeyModifiers::NONE));
+        assert!(editor.modal_request.is_none());
+        assert!(matches!(
+            &events[..],
+            [PanelEvent::SetStatusMessage { message, .. }]
+                if message == "No misspelled word under the cursor"
+        ));
+    }
+
+    #[test]
+    fn test_paste_replaces_selection() {
+        let mut editor = Editor::from_text("hello world", "test".to_string());
+        editor.config.read_only = false;
+        editor.selection = Some(Selection::new(Cursor::at(0, 6), Cursor::at(0, 11)));
+        editor.cursor = Cursor::at(0, 11);
+        editor.handle_paste("there\nfriend");
+        assert_eq!(editor.buffer().to_string(), "hello there\nfriend");
+        assert_eq!((editor.cursor.line, editor.cursor.column), (1, 6));
+
+        let mut viewer = Editor::from_text("log", "test".to_string());
+        viewer.handle_paste("text");
+        assert_eq!(viewer.buffer().to_string(), "log");
+    }
+
+    #[test]
+    fn test_long_line_renders_quickly() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        // Minified script: a single 1MB line
+        let line: String = (0..200_000).map(|i| format!("a{}=1;", i % 10)).collect();
+        let mut file = tempfile::Builder::new().suffix(".js").tempfile().unwrap();
+        writeln!(file, "{}", &line[..1_000_000]).unwrap();
+        let (theme, config) = (Theme::default(), Config::default());
+        let area = Rect::new(0, 0, 120, 40);
+
+        for word_wrap in [false, true] {
+            let mut editor = Editor::open_file(file.path().to_path_buf()).unwrap();
+            editor.config.word_wrap = word_wrap;
+            let render = |editor: &mut Editor| {
+                let start = Instant::now();
+                let mut buf = Buffer::empty(area);
+                editor.render_content(area, &mut buf, &theme, &config);
+                // Generous for debug builds: rendering the whole line takes seconds
+                let elapsed = start.elapsed();
+                assert!(
+                    elapsed < Duration::from_secs(2),
+                    "Rendering took too long: {:?}",
+                    elapsed
+                );
+            };
+            render(&mut editor);
+            for key in [KeyCode::End, KeyCode::Left, KeyCode::Right] {
+                editor.handle_key(KeyEvent::new(key, KeyModifiers::NONE));
+                render(&mut editor);
+            }
+            if !word_wrap {
+                assert_eq!(editor.cursor.column, 1_000_000);
+                assert!(editor.viewport.is_cursor_visible(&editor.cursor));
+            }
+        }
+    }
+
+    #[test]
+    fn test_typing_in_large_file_defers_whole_document_work() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let mut file = tempfile::Builder::new().suffix(".rs").tempfile().unwrap();
+        write!(file, "{}", "let x = 1;\n".repeat(50_000)).unwrap();
+        let mut editor = Editor::open_file(file.path().to_path_buf()).unwrap();
+        let (theme, config) = (Theme::default(), Config::default());
+        let area = Rect::new(0, 0, 80, 20);
+        let render = |editor: &mut Editor| {
+            let mut buf = Buffer::empty(area);
+            editor.render_content(area, &mut buf, &theme, &config);
+            buf[(rendering::LINE_NUMBER_WIDTH as u16, 2)].fg
+        };
+        let code_style = render(&mut editor);
+
+        // A keystroke only drops the highlighting of the typed line
+        editor.cursor = Cursor::at(25_000, 0);
+        let start = Instant::now();
+        for _ in 0..1_000 {
+            editor.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+            editor.sync_line_edits();
+        }
+        let elapsed = start.elapsed();
+        // Generous for debug builds
+        assert!(
+            elapsed < Duration::from_secs(5),
+            "Typing took too long: {:?}",
+            elapsed
+        );
+
+        // Opening a block comment reaches lines below once typing pauses
+        editor.cursor = Cursor::at(0, 0);
+        assert_eq!(render(&mut editor), code_style);
+        for ch in ['/', '*'] {
+            editor.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+        }
+        assert_eq!(render(&mut editor), code_style);
+        editor.render_cache.document_edited =
+            Some(Instant::now() - Duration::from_millis(constants::HIGHLIGHT_DEBOUNCE_MS));
+        assert!(editor.run_deferred_work());
+        assert_ne!(render(&mut editor), code_style);
+    }
 }
@@ -46,11 +46,20 @@ pub fn move_right(cursor: &mut Cursor, buffer: &TextBuffer) -> bool {
     false // Reset preferred column
 }
 
-/// Move cursor to start of current line.
+/// Move cursor to the first non-whitespace character of the line, or to
+/// column 0 if it is already there (smart Home).
 ///
 /// Returns true to reset preferred column.
-pub fn move_to_line_start(cursor: &mut Cursor) -> bool {
-    cursor.column = 0;
+pub fn move_to_line_start(cursor: &mut Cursor, buffer: &TextBuffer) -> bool {
+    let indent_end = buffer
+        .line(cursor.line)
+        .map(|line| line.chars().take_while(|c| *c == ' ' || *c == '\t').count())
+        .unwrap_or(0);
+    cursor.column = if cursor.column == indent_end {
+        0
+    } else {
+        indent_end
+    };
     false // Reset preferred column
 }
 
@@ -2,7 +2,7 @@
 //!
 //! This module provides cursor movement that accounts for word wrapping.
 
-use termide_buffer::{Cursor, TextBuffer};
+use termide_buffer::{Cursor, TextBuffer, WrapMode};
 
 use crate::word_wrap;
 
@@ -52,16 +52,14 @@ pub fn move_up(
     buffer: &TextBuffer,
     preferred_column: Option<usize>,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> Option<Cursor> {
     // Calculate visual offset from current position if not provided
     let visual_offset = preferred_column.unwrap_or_else(|| {
-        if let Some(line_text) = buffer.line(cursor.line) {
-            let line_text = line_text.trim_end_matches('\n');
-            let line_len = line_text.chars().count();
+        if let Some((_, wrap_points, line_len)) =
+            word_wrap::line_wrap_points(buffer, cursor.line, content_width, wrap_mode)
+        {
             let cursor_col = cursor.column.min(line_len);
-            let (_visual_rows, wrap_points) =
-                word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
             let current_visual_row = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
             let (visual_row_start, _) =
                 get_visual_row_bounds(current_visual_row, &wrap_points, line_len);
@@ -72,14 +70,11 @@ pub fn move_up(
     });
 
     // Try to move within current line first
-    if let Some(line_text) = buffer.line(cursor.line) {
-        let line_text = line_text.trim_end_matches('\n');
-        let line_len = line_text.chars().count();
+    if let Some((_, wrap_points, line_len)) =
+        word_wrap::line_wrap_points(buffer, cursor.line, content_width, wrap_mode)
+    {
         let cursor_col = cursor.column.min(line_len);
 
-        let (_visual_rows, wrap_points) =
-            word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
         let current_visual_row = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
 
         if current_visual_row > 0 {
@@ -96,16 +91,12 @@ pub fn move_up(
     if cursor.line > 0 {
         let new_line = cursor.line - 1;
 
-        if let Some(line_text) = buffer.line(new_line) {
-            let line_text = line_text.trim_end_matches('\n');
-            let line_len = line_text.chars().count();
-
+        if let Some((visual_rows, wrap_points, line_len)) =
+            word_wrap::line_wrap_points(buffer, new_line, content_width, wrap_mode)
+        {
             if line_len == 0 {
                 return Some(Cursor::at(new_line, 0));
             }
-
-            let (visual_rows, wrap_points) =
-                word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
             let last_visual_row = visual_rows - 1;
 
             let (visual_row_start, visual_row_end) =
@@ -127,16 +118,14 @@ pub fn move_down(
     buffer: &TextBuffer,
     preferred_column: Option<usize>,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> Option<Cursor> {
     // Calculate visual offset from current position if not provided
     let visual_offset = preferred_column.unwrap_or_else(|| {
-        if let Some(line_text) = buffer.line(cursor.line) {
-            let line_text = line_text.trim_end_matches('\n');
-            let line_len = line_text.chars().count();
+        if let Some((_, wrap_points, line_len)) =
+            word_wrap::line_wrap_points(buffer, cursor.line, content_width, wrap_mode)
+        {
             let cursor_col = cursor.column.min(line_len);
-            let (_visual_rows, wrap_points) =
-                word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
             let current_visual_row = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
             let (visual_row_start, _) =
                 get_visual_row_bounds(current_visual_row, &wrap_points, line_len);
@@ -147,14 +136,11 @@ pub fn move_down(
     });
 
     // Try to move within current line first
-    if let Some(line_text) = buffer.line(cursor.line) {
-        let line_text = line_text.trim_end_matches('\n');
-        let line_len = line_text.chars().count();
+    if let Some((total_visual_rows, wrap_points, line_len)) =
+        word_wrap::line_wrap_points(buffer, cursor.line, content_width, wrap_mode)
+    {
         let cursor_col = cursor.column.min(line_len);
 
-        let (total_visual_rows, wrap_points) =
-            word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
         let current_visual_row = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
 
         if current_visual_row + 1 < total_visual_rows {
@@ -172,17 +158,13 @@ pub fn move_down(
     if cursor.line < max_line {
         let new_line = cursor.line + 1;
 
-        if let Some(line_text) = buffer.line(new_line) {
-            let line_text = line_text.trim_end_matches('\n');
-            let line_len = line_text.chars().count();
-
+        if let Some((_, wrap_points, line_len)) =
+            word_wrap::line_wrap_points(buffer, new_line, content_width, wrap_mode)
+        {
             if line_len == 0 {
                 return Some(Cursor::at(new_line, 0));
             }
 
-            let (_visual_rows, wrap_points) =
-                word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
             // First visual row
             let visual_row_end = if !wrap_points.is_empty() {
                 wrap_points[0]
@@ -205,16 +187,13 @@ pub fn move_to_visual_line_start(
     cursor: &Cursor,
     buffer: &TextBuffer,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> usize {
-    if let Some(line_text) = buffer.line(cursor.line) {
-        let line_text = line_text.trim_end_matches('\n');
-        let line_len = line_text.chars().count();
+    if let Some((_, wrap_points, line_len)) =
+        word_wrap::line_wrap_points(buffer, cursor.line, content_width, wrap_mode)
+    {
         let cursor_col = cursor.column.min(line_len);
 
-        let (_visual_rows, wrap_points) =
-            word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
         // Find which visual row the cursor is on
         let current_visual_row = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
 
@@ -234,16 +213,13 @@ pub fn move_to_visual_line_end(
     cursor: &Cursor,
     buffer: &TextBuffer,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> usize {
-    if let Some(line_text) = buffer.line(cursor.line) {
-        let line_text = line_text.trim_end_matches('\n');
-        let line_len = line_text.chars().count();
+    if let Some((_, wrap_points, line_len)) =
+        word_wrap::line_wrap_points(buffer, cursor.line, content_width, wrap_mode)
+    {
         let cursor_col = cursor.column.min(line_len);
 
-        let (_visual_rows, wrap_points) =
-            word_wrap::get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
         // Find which visual row the cursor is on
         let current_visual_row = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
 
@@ -271,7 +247,7 @@ pub fn page_up(
     buffer: &TextBuffer,
     preferred_column: Option<usize>,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
     page_size: usize,
 ) -> Cursor {
     let mut current_cursor = *cursor;
@@ -284,7 +260,7 @@ pub fn page_up(
             buffer,
             preferred_column,
             content_width,
-            use_smart_wrap,
+            wrap_mode,
         ) {
             current_cursor = new_cursor;
         }
@@ -306,7 +282,7 @@ pub fn page_down(
     buffer: &TextBuffer,
     preferred_column: Option<usize>,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
     page_size: usize,
 ) -> Cursor {
     let mut current_cursor = *cursor;
@@ -320,7 +296,7 @@ pub fn page_down(
             buffer,
             preferred_column,
             content_width,
-            use_smart_wrap,
+            wrap_mode,
         ) {
             current_cursor = new_cursor;
         }
@@ -6,6 +6,7 @@ use std::path::Path;
 use std::time::SystemTime;
 
 use anyhow::Result;
+use similar::{DiffTag, TextDiff};
 
 use crate::constants;
 
@@ -45,11 +46,25 @@ pub(crate) fn check_file_metadata(path: &Path) -> Result<FileMetadata> {
     }
 }
 
-/// Check if file is read-only.
+/// Check if an existing file cannot be written by the current user.
 pub(crate) fn is_file_readonly(path: &Path) -> bool {
-    std::fs::metadata(path)
-        .map(|m| m.permissions().readonly())
-        .unwrap_or(false)
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        // access() accounts for ownership, group membership, root
+        // and read-only mounts, unlike the permission bits alone
+        path.exists() && unsafe { libc::access(c_path.as_ptr(), libc::W_OK) } != 0
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::metadata(path)
+            .map(|m| m.permissions().readonly())
+            .unwrap_or(false)
+    }
 }
 
 /// Get current modification time of a file.
@@ -83,6 +98,39 @@ pub(crate) fn path_to_title(path: &Path) -> String {
         .unwrap_or_else(|| "Untitled".to_string())
 }
 
+/// Find where `line` of `old` text ended up in `new` text.
+///
+/// Unchanged lines keep their place relative to the content around them;
+/// a changed or removed line maps to the start of its replacement.
+/// The result may be past the end of `new`, callers clamp it.
+pub(crate) fn anchor_line(old: &str, new: &str, line: usize) -> usize {
+    let diff = TextDiff::from_lines(old, new);
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if !old_range.contains(&line) {
+            continue;
+        }
+        return match tag {
+            DiffTag::Equal => new_range.start + (line - old_range.start),
+            _ => new_range.start,
+        };
+    }
+    // Past the last old line (empty line after a trailing newline):
+    // keep the same distance from the end
+    let past_end = line.saturating_sub(old.lines().count());
+    new.lines().count() + past_end
+}
+
+/// Unified diff from the file on disk to the edited text.
+pub(crate) fn unified_diff(disk: &str, edited: &str, name: &str) -> String {
+    termide_git::unified_diff(
+        disk,
+        edited,
+        &format!("{} (disk)", name),
+        &format!("{} (editor)", name),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +155,38 @@ mod tests {
         assert!(metadata.size > 0);
         assert!(metadata.mtime.is_some());
     }
+
+    #[test]
+    fn test_is_file_readonly() {
+        let file = NamedTempFile::new().unwrap();
+        assert!(!is_file_readonly(file.path()));
+
+        // Missing files can be created, so they are not read-only
+        assert!(!is_file_readonly(Path::new(
+            "/nonexistent/termide/file.txt"
+        )));
+    }
+
+    #[test]
+    fn test_anchor_line() {
+        let old = "a\nb\nc\nd\n";
+        // Lines inserted above shift the anchor down
+        assert_eq!(anchor_line(old, "x\ny\na\nb\nc\nd\n", 2), 4);
+        // Lines removed above shift it up
+        assert_eq!(anchor_line(old, "c\nd\n", 3), 1);
+        // A rewritten line maps to its replacement
+        assert_eq!(anchor_line(old, "a\nB\nc\nd\n", 1), 1);
+        // A removed line maps to the following content
+        assert_eq!(anchor_line(old, "a\nc\nd\n", 1), 1);
+        // The empty line after the trailing newline stays last
+        assert_eq!(anchor_line(old, "a\n", 4), 1);
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("a\nb\n", "a\nB\n", "f.txt");
+        assert!(diff.starts_with("--- f.txt (disk)\n+++ f.txt (editor)\n"));
+        assert!(diff.contains("-b\n+B\n"));
+        assert!(unified_diff("same\n", "same\n", "f.txt").is_empty());
+    }
 }
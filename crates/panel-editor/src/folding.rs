@@ -0,0 +1,49 @@
+//! Code folding for the editor.
+//!
+//! Foldable regions come from the tree-sitter syntax tree when the file's
+//! language is supported, otherwise from indentation.
+
+use std::time::Duration;
+
+use termide_buffer::{indent_regions, FoldRange, TextBuffer};
+
+/// Minimum delay between recomputing fold regions while editing
+pub const FOLD_REGIONS_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Compute foldable regions of the buffer.
+///
+/// `language` is the name returned by `termide_highlight::detect_language`.
+pub fn compute_fold_regions(
+    buffer: &TextBuffer,
+    language: Option<&str>,
+    tab_width: usize,
+) -> Vec<FoldRange> {
+    let text = buffer.to_string();
+    match language.and_then(|lang| termide_highlight::fold_ranges(lang, &text)) {
+        Some(ranges) => ranges
+            .into_iter()
+            .map(|(start, end)| FoldRange::new(start, end))
+            .collect(),
+        None => indent_regions(text.lines(), tab_width),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regions_fall_back_to_indentation() {
+        let buffer = TextBuffer::from_text("a:\n  b\n  c\nd\n");
+        assert_eq!(
+            compute_fold_regions(&buffer, None, 4),
+            vec![FoldRange::new(0, 2)]
+        );
+
+        let buffer = TextBuffer::from_text("fn a() {\n    b();\n}\n");
+        assert_eq!(
+            compute_fold_regions(&buffer, Some("rust"), 4),
+            vec![FoldRange::new(0, 1)]
+        );
+    }
+}
@@ -0,0 +1,417 @@
+//! External formatters of the editor.
+//!
+//! A formatter reads the text on stdin and writes the formatted text to
+//! stdout. It runs on a background thread and is killed after a timeout, so
+//! a hung formatter never blocks the UI; the editor polls the job on each
+//! tick. The formatted text replaces only the lines that changed, and the
+//! cursor is moved to the same code in the new text.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use similar::{DiffTag, TextDiff};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Interval of checking if the formatter exited
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Time the character diff of changed lines may take before it settles
+/// for a coarser result
+const DIFF_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Result of running a formatter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FormatOutcome {
+    /// Formatted text (line breaks as `\n`)
+    Formatted(String),
+    /// The formatter could not be started, exited with an error or wrote to
+    /// stderr
+    Failed(String),
+    /// The formatter was killed after the timeout
+    TimedOut,
+}
+
+/// Formatter running for an editor
+#[derive(Debug)]
+pub(crate) struct FormatJob {
+    /// Command line of the formatter
+    pub command: String,
+    /// Buffer generation and revision of the formatted text
+    pub generation: u64,
+    pub revision: u64,
+    /// Save the file when the formatter finished
+    pub save: bool,
+    receiver: Receiver<FormatOutcome>,
+}
+
+impl FormatJob {
+    /// Start formatting `text` with `command`; `{file}` in its arguments is
+    /// replaced with `path`, and it runs in the directory of `path`
+    pub fn spawn(
+        command: &str,
+        path: Option<&Path>,
+        text: String,
+        timeout: Duration,
+        (generation, revision): (u64, u64),
+        save: bool,
+    ) -> Self {
+        let args = command_args(command, path);
+        let dir = path.and_then(Path::parent).map(Path::to_path_buf);
+        let (sender, receiver) = termide_wake::channel();
+        thread::spawn(move || {
+            let _ = sender.send(run(&args, dir, text, timeout));
+        });
+        Self {
+            command: command.to_string(),
+            generation,
+            revision,
+            save,
+            receiver,
+        }
+    }
+
+    /// Outcome of the formatter, once it finished
+    pub fn poll(&self) -> Option<FormatOutcome> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(FormatOutcome::Failed(
+                "formatter thread stopped".to_string(),
+            )),
+        }
+    }
+}
+
+/// Arguments of a command line (split at whitespace, no shell quoting)
+fn command_args(command: &str, path: Option<&Path>) -> Vec<String> {
+    let file = path
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    command
+        .split_whitespace()
+        .map(|arg| arg.replace("{file}", &file))
+        .collect()
+}
+
+/// Run a formatter, feeding `text` on stdin
+fn run(args: &[String], dir: Option<PathBuf>, text: String, timeout: Duration) -> FormatOutcome {
+    let Some((program, args)) = args.split_first() else {
+        return FormatOutcome::Failed("empty formatter command".to_string());
+    };
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = dir.filter(|dir| dir.is_dir()) {
+        command.current_dir(dir);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return FormatOutcome::Failed(format!("{}: {}", program, e)),
+    };
+
+    // Pipes are served by their own threads so a formatter writing a lot
+    // before reading all of its input does not deadlock
+    let stdin = child.stdin.take();
+    thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(text.as_bytes());
+        }
+    });
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return FormatOutcome::TimedOut;
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return FormatOutcome::Failed(e.to_string()),
+        }
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let errors = String::from_utf8_lossy(&stderr).trim().to_string();
+    if !status.success() {
+        return FormatOutcome::Failed(if errors.is_empty() {
+            status.to_string()
+        } else {
+            errors
+        });
+    }
+    if !errors.is_empty() {
+        return FormatOutcome::Failed(errors);
+    }
+    match String::from_utf8(stdout) {
+        Ok(text) => FormatOutcome::Formatted(text.replace("\r\n", "\n")),
+        Err(_) => FormatOutcome::Failed("output is not valid UTF-8".to_string()),
+    }
+}
+
+/// Read a pipe to its end on a thread
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        output
+    })
+}
+
+/// Changed line ranges of `old`: `(start, count, new text)`, sorted
+pub(crate) fn line_edits(old: &str, new: &str) -> Vec<(usize, usize, String)> {
+    let diff = TextDiff::from_lines(old, new);
+    let new_lines = diff.new_slices();
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+    let mut last_end = None;
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        let text = new_lines[new_range].concat();
+        match edits.last_mut() {
+            // Adjacent changes are merged into one edit
+            Some((_, count, edit_text)) if last_end == Some(old_range.start) => {
+                *count += old_range.len();
+                edit_text.push_str(&text);
+            }
+            _ => edits.push((old_range.start, old_range.len(), text)),
+        }
+        last_end = Some(old_range.end);
+    }
+    edits
+}
+
+/// Position in `new` of the code at `(line, column)` of `old` (columns in
+/// graphemes)
+///
+/// An unchanged line keeps the cursor column. A changed line is looked up
+/// by its content among the lines that replaced it (or anywhere, if it was
+/// moved); otherwise the cursor offset is mapped through the characters
+/// that the change kept.
+pub(crate) fn reanchor(old: &str, new: &str, line: usize, column: usize) -> (usize, usize) {
+    let diff = TextDiff::from_lines(old, new);
+    let old_lines = diff.old_slices();
+    let new_lines = diff.new_slices();
+    let Some(old_line) = old_lines.get(line) else {
+        // The cursor is on the empty line after the last line break
+        return (new_lines.len(), 0);
+    };
+    let ops = diff.ops();
+    let Some((tag, old_range, new_range)) = ops
+        .iter()
+        .map(|op| op.as_tag_tuple())
+        .find(|(_, old_range, _)| old_range.contains(&line))
+    else {
+        return (line.min(new_lines.len()), 0);
+    };
+    if tag == DiffTag::Equal {
+        return (new_range.start + line - old_range.start, column);
+    }
+
+    // Same content: the line was only reindented or moved
+    let content = old_line.trim();
+    if !content.is_empty() {
+        let same_line = |index: &usize| new_lines[*index].trim() == content;
+        let found = if new_range.is_empty() {
+            ops.iter()
+                .map(|op| op.as_tag_tuple())
+                .filter(|(tag, _, _)| *tag != DiffTag::Equal)
+                .flat_map(|(_, _, range)| range)
+                .filter(same_line)
+                .min_by_key(|index| index.abs_diff(new_range.start))
+        } else {
+            new_range.clone().find(same_line)
+        };
+        if let Some(index) = found {
+            return (index, reanchor_column(old_line, new_lines[index], column));
+        }
+    }
+
+    // Map the offset of the cursor in the changed lines
+    let old_text = old_lines[old_range.clone()].concat();
+    let new_text = new_lines[new_range.clone()].concat();
+    let line_start: usize = old_lines[old_range.start..line]
+        .iter()
+        .map(|l| l.len())
+        .sum();
+    let column_offset: usize = old_line
+        .trim_end_matches(['\r', '\n'])
+        .graphemes(true)
+        .take(column)
+        .map(str::len)
+        .sum();
+    let offset = map_offset(&old_text, &new_text, line_start + column_offset);
+    let before = &new_text[..offset];
+    let line_in_range = before.matches('\n').count();
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .graphemes(true)
+        .count();
+    (new_range.start + line_in_range, column)
+}
+
+/// Byte offset in `new` of the character at byte `offset` of `old`
+/// (changed characters map to the start of their replacement)
+fn map_offset(old: &str, new: &str, offset: usize) -> usize {
+    let diff = TextDiff::configure()
+        .timeout(DIFF_TIMEOUT)
+        .diff_chars(old, new);
+    let new_chars = diff.new_slices();
+    let index = old[..offset].chars().count();
+    let new_index = diff
+        .ops()
+        .iter()
+        .map(|op| op.as_tag_tuple())
+        .find(|(_, old_range, _)| old_range.contains(&index))
+        .map_or(new_chars.len(), |(tag, old_range, new_range)| {
+            if tag == DiffTag::Equal {
+                new_range.start + index - old_range.start
+            } else {
+                new_range.start
+            }
+        });
+    new_chars[..new_index].iter().map(|c| c.len()).sum()
+}
+
+/// Column of `new` with the code after `column` of `old`
+fn reanchor_column(old: &str, new: &str, column: usize) -> usize {
+    let old: Vec<&str> = old.trim_end_matches(['\r', '\n']).graphemes(true).collect();
+    let new: Vec<&str> = new.trim_end_matches(['\r', '\n']).graphemes(true).collect();
+    let is_space = |grapheme: &str| grapheme.chars().all(char::is_whitespace);
+
+    let code_before = old
+        .iter()
+        .take(column)
+        .filter(|grapheme| !is_space(grapheme))
+        .count();
+    let mut new_column = 0;
+    let mut seen = 0;
+    while seen < code_before && new_column < new.len() {
+        if !is_space(new[new_column]) {
+            seen += 1;
+        }
+        new_column += 1;
+    }
+    // A cursor before code stays before it, whatever spacing precedes it
+    if old.get(column).is_none_or(|grapheme| !is_space(grapheme)) {
+        while new_column < new.len() && is_space(new[new_column]) {
+            new_column += 1;
+        }
+    }
+    new_column
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_buffer::TextBuffer;
+
+    #[test]
+    fn test_line_edits_apply_to_buffer() {
+        let old = "fn main(){\nlet x=1;\n    x\n}\nfn other() {}\n";
+        let new = "fn main() {\n    let x = 1;\n    x\n}\n\nfn other() {}\n";
+        let edits = line_edits(old, new);
+        assert_eq!(edits.len(), 2);
+
+        let mut buffer = TextBuffer::from_text(old);
+        buffer.replace_line_ranges(&edits).unwrap();
+        assert_eq!(buffer.text(), new);
+        buffer.undo().unwrap();
+        assert_eq!(buffer.text(), old);
+    }
+
+    #[test]
+    fn test_line_edits_without_final_newline() {
+        let mut buffer = TextBuffer::from_text("a\nb");
+        buffer
+            .replace_line_ranges(&line_edits("a\nb", "a\nb\n"))
+            .unwrap();
+        assert_eq!(buffer.text(), "a\nb\n");
+        assert!(line_edits("same\n", "same\n").is_empty());
+    }
+
+    #[test]
+    fn test_reanchor_keeps_code_under_cursor() {
+        let old = "fn main(){\nlet x=foo(a,b);\n}\n";
+        let new = "// header\nfn main() {\n    let x = foo(a, b);\n}\n";
+        // On "b"
+        assert_eq!(reanchor(old, new, 1, 12), (2, 19));
+        // Before "let" at the line start
+        assert_eq!(reanchor(old, new, 1, 0), (2, 4));
+        // Unchanged line after the inserted one
+        assert_eq!(reanchor(old, new, 2, 1), (3, 1));
+        // Empty line after the last line break
+        assert_eq!(reanchor(old, new, 3, 0), (4, 0));
+    }
+
+    #[test]
+    fn test_reanchor_finds_moved_line_by_content() {
+        let old = "use b;\nuse a;\n";
+        let new = "use a;\nuse b;\n";
+        assert_eq!(reanchor(old, new, 1, 4), (0, 4));
+        // A deleted line falls back to the line after it
+        assert_eq!(reanchor("a\n\n\n\nb\n", "a\n\nb\n", 3, 0), (2, 0));
+        // Lines with common content are not searched beyond the change
+        assert_eq!(reanchor("}\nx\n}\n", "}\nx\n  }\n", 2, 1), (2, 3));
+    }
+
+    #[test]
+    fn test_formatter_output_and_errors() {
+        let timeout = Duration::from_secs(5);
+        let args = |command: &str| command_args(command, None);
+        assert_eq!(
+            run(&args("tr a-z A-Z"), None, "abc\r\n".to_string(), timeout),
+            FormatOutcome::Formatted("ABC\n".to_string())
+        );
+        assert!(matches!(
+            run(&args("cat nonexistent-file"), None, String::new(), timeout),
+            FormatOutcome::Failed(message) if message.contains("nonexistent-file")
+        ));
+        assert!(matches!(
+            run(
+                &args("termide-no-such-formatter"),
+                None,
+                String::new(),
+                timeout
+            ),
+            FormatOutcome::Failed(_)
+        ));
+        assert_eq!(
+            run(
+                &args("sleep 5"),
+                None,
+                String::new(),
+                Duration::from_millis(50)
+            ),
+            FormatOutcome::TimedOut
+        );
+    }
+
+    #[test]
+    fn test_command_args_substitute_file() {
+        assert_eq!(
+            command_args(
+                "prettier --stdin-filepath {file}",
+                Some(Path::new("/a/b.ts"))
+            ),
+            ["prettier", "--stdin-filepath", "/a/b.ts"]
+        );
+    }
+}
@@ -6,8 +6,8 @@
 use ratatui::style::Color;
 use std::sync::mpsc;
 
-use termide_buffer::TextBuffer;
-use termide_git::{load_original_async, GitDiffAsyncResult, GitDiffCache, LineStatus};
+use termide_buffer::{FoldMap, TextBuffer};
+use termide_git::{load_original_async, BufferDiff, GitDiffAsyncResult, GitDiffCache, LineStatus};
 use termide_theme::Theme;
 
 /// Git line status information for rendering
@@ -98,31 +98,43 @@ pub fn schedule_git_diff_update(
     }
 }
 
-/// Check and apply pending git diff update if debounce time has passed.
+/// Compare the buffer with HEAD in a background thread.
 ///
-/// Returns true if update was performed, false otherwise.
-pub fn check_pending_git_diff_update(
-    pending_time: Option<std::time::Instant>,
-    git_diff_cache: &mut Option<GitDiffCache>,
+/// Returns the receiver of the result, None without a diff cache or before
+/// the HEAD content was loaded.
+pub fn diff_buffer_async(
+    git_diff_cache: &Option<GitDiffCache>,
     buffer: &TextBuffer,
-) -> (bool, Option<std::time::Instant>) {
-    const DEBOUNCE_MS: u64 = 300;
-
-    if let Some(pending_time) = pending_time {
-        if pending_time.elapsed().as_millis() >= DEBOUNCE_MS as u128 {
-            // Time has passed, perform update
-            let content = buffer.to_string();
+) -> Option<mpsc::Receiver<BufferDiff>> {
+    git_diff_cache
+        .as_ref()?
+        .diff_buffer_async(|| buffer.to_string())
+}
 
-            // Update diff cache with current buffer
-            if let Some(ref mut cache) = git_diff_cache {
-                let _ = cache.update_from_buffer(&content);
+/// Check the receiver of a buffer diff and apply the result if ready.
+///
+/// Returns true if result was applied, false otherwise.
+pub fn check_buffer_diff_receiver(
+    receiver: &mut Option<mpsc::Receiver<BufferDiff>>,
+    git_diff_cache: &mut Option<GitDiffCache>,
+) -> bool {
+    let Some(rx) = receiver else {
+        return false;
+    };
+    match rx.try_recv() {
+        Ok(diff) => {
+            if let Some(cache) = git_diff_cache {
+                cache.apply_buffer_diff(diff);
             }
-
-            return (true, None); // Update performed, clear pending
+            *receiver = None;
+            true
+        }
+        Err(mpsc::TryRecvError::Empty) => false,
+        Err(mpsc::TryRecvError::Disconnected) => {
+            *receiver = None;
+            false
         }
     }
-
-    (false, pending_time) // No update, keep pending time
 }
 
 /// Get git line information for rendering.
@@ -146,8 +158,8 @@ pub fn get_git_line_info(
 
             // Status marker and color
             let (status_color, status_marker) = match status {
-                LineStatus::Added => (theme.success, ' '),
-                LineStatus::Modified => (theme.warning, ' '),
+                LineStatus::Added => (theme.success, '+'),
+                LineStatus::Modified => (theme.warning, '~'),
                 LineStatus::Unchanged => (theme.disabled, ' '),
                 LineStatus::DeletedAfter => (theme.disabled, ' '),
             };
@@ -163,36 +175,38 @@ pub fn get_git_line_info(
         })
 }
 
-/// Build list of virtual lines (real buffer lines + deletion marker lines).
+/// Build list of visible virtual lines (real buffer lines + deletion marker lines).
 ///
-/// Returns a Vec mapping visual row index to VirtualLine.
+/// Starts at buffer line `top_line` and stops after `max_rows` entries, so the
+/// cost depends on the viewport height rather than the file length. Lines
+/// hidden by folds are skipped.
 pub fn build_virtual_lines(
     buffer: &TextBuffer,
+    folds: &FoldMap,
     git_diff_cache: &Option<GitDiffCache>,
     show_git_diff: bool,
+    top_line: usize,
+    max_rows: usize,
 ) -> Vec<VirtualLine> {
-    let mut virtual_lines = Vec::new();
+    let mut virtual_lines = Vec::with_capacity(max_rows);
     let buffer_line_count = buffer.line_count();
+    let git_diff = git_diff_cache.as_ref().filter(|_| show_git_diff);
 
-    // If git diff is disabled or not available, just return real lines
-    if !show_git_diff || git_diff_cache.is_none() {
-        for line_idx in 0..buffer_line_count {
-            virtual_lines.push(VirtualLine::Real(line_idx));
+    let mut line_idx = top_line;
+    while line_idx < buffer_line_count {
+        if virtual_lines.len() >= max_rows {
+            break;
         }
-        return virtual_lines;
-    }
-
-    let git_diff = git_diff_cache.as_ref().unwrap();
-
-    // Interleave real lines with deletion markers
-    for line_idx in 0..buffer_line_count {
         virtual_lines.push(VirtualLine::Real(line_idx));
 
         // Check if there's a deletion marker after this line
-        if git_diff.has_deletion_marker(line_idx) {
-            let deletion_count = git_diff.get_deletion_count(line_idx);
-            virtual_lines.push(VirtualLine::DeletionMarker(line_idx, deletion_count));
+        if let Some(git_diff) = git_diff {
+            if git_diff.has_deletion_marker(line_idx) && virtual_lines.len() < max_rows {
+                let deletion_count = git_diff.get_deletion_count(line_idx);
+                virtual_lines.push(VirtualLine::DeletionMarker(line_idx, deletion_count));
+            }
         }
+        line_idx = folds.next_visible(line_idx);
     }
 
     virtual_lines
@@ -27,6 +27,14 @@ pub enum EditorCommand {
     PageDown,
     MoveToDocumentStart,
     MoveToDocumentEnd,
+    /// Scroll half a page up, moving the cursor with the view
+    HalfPageUp,
+    /// Scroll half a page down, moving the cursor with the view
+    HalfPageDown,
+    /// Scroll the cursor line to the center, top or bottom (cycling)
+    Recenter,
+    JumpBack,
+    JumpForward,
 
     // Navigation with selection (Shift modifier, closes search)
     MoveCursorUpWithSelection,
@@ -52,30 +60,86 @@ pub enum EditorCommand {
     InsertNewline,
     Backspace,
     Delete,
+    /// Delete to the start of the previous word
+    DeleteWordBackward,
+    /// Delete to the end of the next word
+    DeleteWordForward,
+    TransposeWords,
 
     // Undo/Redo
     Undo,
     Redo,
+    UndoTree,
 
     // File operations
     Save,
-    #[allow(dead_code)] // Included for completeness, triggered by Save when no file path exists
     SaveAs,
     /// Force save (ignore external changes)
     ForceSave,
     /// Reload file from disk (discard local changes)
     ReloadFromDisk,
+    /// Allow editing a file without write permission
+    EditAnyway,
 
     // Selection
     SelectAll,
 
     // Clipboard
     Copy,
+    CopyBlock,
     Cut,
     Paste,
+    PasteReindented,
 
     // Advanced editing
     DuplicateLine,
+    DeleteLines,
+    MoveLinesUp,
+    MoveLinesDown,
+    ToggleComment,
+    ConvertIndentation,
+    ToggleLineEnding,
+    /// Re-enable features disabled in large file mode
+    EnableLargeFileFeatures,
+    /// Pick the syntax highlighting language
+    SelectSyntax,
+    /// Pick the file encoding (reopen or convert)
+    SelectEncoding,
+    /// Show or hide spaces, tabs and Unicode spaces
+    ToggleWhitespace,
+    /// Turn word wrap on or off (Panel menu)
+    ToggleWordWrap,
+
+    // Git changes
+    NextHunk,
+    PrevHunk,
+    RevertHunk,
+    /// Compare the buffer with the file's version in HEAD
+    DiffAgainstHead,
+
+    /// Open a markdown preview that follows the editor
+    MarkdownPreview,
+
+    // Bookmarks
+    /// Bookmark the cursor line
+    SetBookmark,
+
+    // Language server
+    /// Jump to the definition of the symbol under the cursor
+    GotoDefinition,
+
+    // Formatting
+    /// Run the external formatter of the file's language
+    FormatDocument,
+
+    // Spelling
+    /// Offer corrections of the misspelled word under the cursor
+    SpellingSuggestions,
+
+    // Folding
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
 
     // Search
     StartSearch,
@@ -90,6 +154,12 @@ pub enum EditorCommand {
     ReplaceNext,
     ReplaceAll,
 
+    // Completion popup
+    CompletionNext,
+    CompletionPrev,
+    AcceptCompletion,
+    CloseCompletion,
+
     // No operation (for unhandled keys)
     None,
 }
@@ -106,13 +176,23 @@ impl EditorCommand {
     /// * `read_only` - Whether the editor is in read-only mode
     /// * `has_search` - Whether there's an active search
     /// * `has_selection` - Whether there's an active text selection
+    /// * `has_completion` - Whether the completion popup is shown
     pub fn from_key_event(
         key: KeyEvent,
         read_only: bool,
         has_search: bool,
         has_selection: bool,
+        has_completion: bool,
     ) -> Self {
         match (key.code, key.modifiers) {
+            // Completion popup: arrows pick a word, Tab/Enter insert it, Esc closes
+            (KeyCode::Up, KeyModifiers::NONE) if has_completion => Self::CompletionPrev,
+            (KeyCode::Down, KeyModifiers::NONE) if has_completion => Self::CompletionNext,
+            (KeyCode::Tab | KeyCode::Enter, KeyModifiers::NONE) if has_completion => {
+                Self::AcceptCompletion
+            }
+            (KeyCode::Esc, KeyModifiers::NONE) if has_completion => Self::CloseCompletion,
+
             // Navigation (clears selection and closes search)
             (KeyCode::Up, KeyModifiers::NONE) => Self::MoveCursorUp,
             (KeyCode::Down, KeyModifiers::NONE) => Self::MoveCursorDown,
@@ -124,6 +204,21 @@ impl EditorCommand {
             (KeyCode::PageDown, KeyModifiers::NONE) => Self::PageDown,
             (KeyCode::Home, KeyModifiers::CONTROL) => Self::MoveToDocumentStart,
             (KeyCode::End, KeyModifiers::CONTROL) => Self::MoveToDocumentEnd,
+            (KeyCode::PageUp, KeyModifiers::CONTROL) => Self::HalfPageUp,
+            (KeyCode::PageDown, KeyModifiers::CONTROL) => Self::HalfPageDown,
+
+            // Ctrl+L - cursor line to center / top / bottom of the view
+            (KeyCode::Char('l'), KeyModifiers::CONTROL) => Self::Recenter,
+
+            // Ctrl+O / Ctrl+Shift+O - jump back/forward (Ctrl+I where the
+            // terminal reports it apart from Tab)
+            (KeyCode::Char('o'), KeyModifiers::CONTROL) => Self::JumpBack,
+            (KeyCode::Char('i'), KeyModifiers::CONTROL) => Self::JumpForward,
+            (KeyCode::Char('O'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::JumpForward
+            }
 
             // Navigation with selection (Shift) - closes search
             (KeyCode::Up, KeyModifiers::SHIFT) => Self::MoveCursorUpWithSelection,
@@ -170,6 +265,11 @@ impl EditorCommand {
             (KeyCode::Enter, KeyModifiers::NONE) if !read_only => Self::InsertNewline,
             (KeyCode::Backspace, KeyModifiers::NONE) if !read_only => Self::Backspace,
             (KeyCode::Delete, KeyModifiers::NONE) if !read_only => Self::Delete,
+            (KeyCode::Backspace, KeyModifiers::CONTROL) if !read_only => Self::DeleteWordBackward,
+            (KeyCode::Delete, KeyModifiers::CONTROL) if !read_only => Self::DeleteWordForward,
+
+            // Ctrl+T - transpose words (Alt+T opens a terminal)
+            (KeyCode::Char('t'), KeyModifiers::CONTROL) if !read_only => Self::TransposeWords,
 
             // Ctrl+S - save (only if not read-only)
             (KeyCode::Char('s'), KeyModifiers::CONTROL) if !read_only => Self::Save,
@@ -183,6 +283,24 @@ impl EditorCommand {
                 Self::ForceSave
             }
 
+            // Ctrl+Alt+S - save as (also for files without write permission)
+            (KeyCode::Char('s'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::ALT) =>
+            {
+                Self::SaveAs
+            }
+
+            // Ctrl+Shift+W - edit a file without write permission anyway
+            (KeyCode::Char('W'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::EditAnyway
+            }
+
             // Ctrl+Shift+R - reload from disk
             (KeyCode::Char('R'), mods)
                 if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
@@ -196,6 +314,15 @@ impl EditorCommand {
             // Ctrl+Y - redo (only if not read-only)
             (KeyCode::Char('y'), KeyModifiers::CONTROL) if !read_only => Self::Redo,
 
+            // Ctrl+Shift+U - undo tree (only if not read-only)
+            (KeyCode::Char('U'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::UndoTree
+            }
+
             // Ctrl+F - search
             (KeyCode::Char('f'), KeyModifiers::CONTROL) => Self::StartSearch,
 
@@ -241,9 +368,147 @@ impl EditorCommand {
             // Ctrl+D - duplicate line
             (KeyCode::Char('d'), KeyModifiers::CONTROL) if !read_only => Self::DuplicateLine,
 
+            // Ctrl+Shift+K - delete line
+            (KeyCode::Char('K'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::DeleteLines
+            }
+
+            // Alt+Shift+Up/Down - move lines (Alt+Up/Down switch panels)
+            (KeyCode::Up, mods)
+                if !read_only && mods == KeyModifiers::ALT | KeyModifiers::SHIFT =>
+            {
+                Self::MoveLinesUp
+            }
+            (KeyCode::Down, mods)
+                if !read_only && mods == KeyModifiers::ALT | KeyModifiers::SHIFT =>
+            {
+                Self::MoveLinesDown
+            }
+
+            // Ctrl+/ - toggle line comments (most terminals send it as Ctrl+7)
+            (KeyCode::Char('/') | KeyCode::Char('7'), KeyModifiers::CONTROL) if !read_only => {
+                Self::ToggleComment
+            }
+
+            // Ctrl+Shift+T - convert indentation between tabs and spaces
+            (KeyCode::Char('T'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::ConvertIndentation
+            }
+
+            // Ctrl+Shift+L - switch line ending between LF and CRLF
+            (KeyCode::Char('L'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::ToggleLineEnding
+            }
+
+            // Ctrl+Shift+E - enable features disabled for large files
+            (KeyCode::Char('E'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::EnableLargeFileFeatures
+            }
+
+            // Ctrl+Shift+H - pick the syntax highlighting language
+            (KeyCode::Char('H'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::SelectSyntax
+            }
+
+            // Ctrl+Shift+N - reopen with another encoding
+            (KeyCode::Char('N'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::SelectEncoding
+            }
+
+            // Ctrl+Shift+A - show/hide whitespace
+            (KeyCode::Char('A'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::ToggleWhitespace
+            }
+
+            // Ctrl+Shift+D - compare with the version in HEAD
+            (KeyCode::Char('D'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::DiffAgainstHead
+            }
+
+            // Ctrl+Shift+M - markdown preview
+            (KeyCode::Char('M'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
+            {
+                Self::MarkdownPreview
+            }
+
+            // F7 / Shift+F7 - next/previous git change
+            (KeyCode::F(7), KeyModifiers::NONE) => Self::NextHunk,
+            (KeyCode::F(7), KeyModifiers::SHIFT) => Self::PrevHunk,
+
+            // Ctrl+F2 - bookmark the cursor line
+            (KeyCode::F(2), KeyModifiers::CONTROL) => Self::SetBookmark,
+
+            // F12 - go to definition
+            (KeyCode::F(12), KeyModifiers::NONE) => Self::GotoDefinition,
+
+            // Ctrl+Alt+F - format document (only if not read-only)
+            (KeyCode::Char('f'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::ALT) =>
+            {
+                Self::FormatDocument
+            }
+
+            // F8 - corrections of the misspelled word (only if not read-only)
+            (KeyCode::F(8), KeyModifiers::NONE) if !read_only => Self::SpellingSuggestions,
+
+            // F9 - fold/unfold at cursor, Ctrl+F9 / Shift+F9 - fold/unfold all
+            (KeyCode::F(9), KeyModifiers::NONE) => Self::ToggleFold,
+            (KeyCode::F(9), KeyModifiers::CONTROL) => Self::FoldAll,
+            (KeyCode::F(9), KeyModifiers::SHIFT) => Self::UnfoldAll,
+
+            // Ctrl+Alt+Z - revert git change under cursor (only if not read-only)
+            (KeyCode::Char('z'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::ALT) =>
+            {
+                Self::RevertHunk
+            }
+
             // Ctrl+Insert - copy
             (KeyCode::Insert, KeyModifiers::CONTROL) => Self::Copy,
 
+            // Ctrl+Alt+C - copy the selection's rectangle as a block
+            (KeyCode::Char('c'), mods)
+                if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::ALT) =>
+            {
+                Self::CopyBlock
+            }
+
+            // Ctrl+Alt+V - paste re-indented to the cursor line (only if not read-only)
+            (KeyCode::Char('v'), mods)
+                if !read_only
+                    && mods.contains(KeyModifiers::CONTROL)
+                    && mods.contains(KeyModifiers::ALT) =>
+            {
+                Self::PasteReindented
+            }
+
             // Ctrl+Shift+C - copy (terminal shortcut)
             (KeyCode::Char('c'), mods)
                 if mods.contains(KeyModifiers::CONTROL) && mods.contains(KeyModifiers::SHIFT) =>
@@ -289,6 +554,44 @@ impl EditorCommand {
         }
     }
 
+    /// Check if this command modifies the buffer or writes the file.
+    pub fn is_modifying(&self) -> bool {
+        matches!(
+            self,
+            Self::InsertChar(_)
+                | Self::InsertTab
+                | Self::IndentLines
+                | Self::UnindentLines
+                | Self::InsertNewline
+                | Self::Backspace
+                | Self::Delete
+                | Self::DeleteWordBackward
+                | Self::DeleteWordForward
+                | Self::TransposeWords
+                | Self::Undo
+                | Self::Redo
+                | Self::UndoTree
+                | Self::Save
+                | Self::ForceSave
+                | Self::Cut
+                | Self::Paste
+                | Self::PasteReindented
+                | Self::DuplicateLine
+                | Self::DeleteLines
+                | Self::MoveLinesUp
+                | Self::MoveLinesDown
+                | Self::ToggleComment
+                | Self::ConvertIndentation
+                | Self::ToggleLineEnding
+                | Self::RevertHunk
+                | Self::FormatDocument
+                | Self::StartReplace
+                | Self::ReplaceNext
+                | Self::ReplaceAll
+                | Self::AcceptCompletion
+        )
+    }
+
     /// Execute this command on the given editor.
     ///
     /// This method performs the actual action associated with the command.
@@ -361,6 +664,26 @@ impl EditorCommand {
                 editor.navigate_simple(Editor::move_to_document_end);
                 Ok(())
             }
+            Self::HalfPageUp => {
+                editor.scroll_half_page(false);
+                Ok(())
+            }
+            Self::HalfPageDown => {
+                editor.scroll_half_page(true);
+                Ok(())
+            }
+            Self::Recenter => {
+                editor.recenter();
+                Ok(())
+            }
+            Self::JumpBack => {
+                editor.jump_back();
+                Ok(())
+            }
+            Self::JumpForward => {
+                editor.jump_forward();
+                Ok(())
+            }
 
             // Navigation with selection
             Self::MoveCursorUpWithSelection => {
@@ -436,17 +759,21 @@ impl EditorCommand {
             Self::InsertNewline => editor.insert_newline(),
             Self::Backspace => editor.handle_delete_key(|e| e.backspace()),
             Self::Delete => editor.handle_delete_key(|e| e.delete()),
+            Self::DeleteWordBackward => editor.handle_delete_key(|e| e.delete_word_backward()),
+            Self::DeleteWordForward => editor.handle_delete_key(|e| e.delete_word_forward()),
+            Self::TransposeWords => editor.transpose_words(),
 
             // Undo/Redo
             Self::Undo => editor.handle_undo_redo(|buf| buf.undo()),
             Self::Redo => editor.handle_undo_redo(|buf| buf.redo()),
+            Self::UndoTree => {
+                editor.open_undo_tree();
+                Ok(())
+            }
 
             // File operations - Save requires special handling for SaveAs modal
             Self::Save => editor.handle_save(),
-            Self::SaveAs => {
-                // This shouldn't be reached from key parsing, but included for completeness
-                editor.handle_save_as()
-            }
+            Self::SaveAs => editor.handle_save_as(),
             Self::ForceSave => {
                 if let Err(e) = editor.force_save() {
                     editor.status_message = Some(format!("Force save failed: {}", e));
@@ -473,10 +800,99 @@ impl EditorCommand {
             // Clipboard
             Self::Copy => editor.copy_to_clipboard(),
             Self::Cut => editor.cut_to_clipboard(),
+            Self::CopyBlock => editor.copy_block_to_clipboard(),
             Self::Paste => editor.paste_from_clipboard(),
+            Self::PasteReindented => editor.paste_reindented(),
 
             // Advanced editing
             Self::DuplicateLine => editor.duplicate_line(),
+            Self::DeleteLines => editor.delete_lines(),
+            Self::MoveLinesUp => editor.move_lines(true),
+            Self::MoveLinesDown => editor.move_lines(false),
+            Self::ToggleComment => editor.toggle_comment(),
+            Self::ConvertIndentation => editor.convert_indentation(),
+            Self::ToggleLineEnding => {
+                editor.toggle_line_ending();
+                Ok(())
+            }
+            Self::EditAnyway => {
+                editor.edit_anyway();
+                Ok(())
+            }
+            Self::EnableLargeFileFeatures => {
+                editor.enable_large_file_features();
+                Ok(())
+            }
+            Self::SelectSyntax => {
+                editor.open_syntax_select();
+                Ok(())
+            }
+            Self::SelectEncoding => {
+                editor.open_encoding_select();
+                Ok(())
+            }
+            Self::ToggleWhitespace => {
+                editor.toggle_whitespace();
+                Ok(())
+            }
+            Self::ToggleWordWrap => {
+                editor.toggle_word_wrap();
+                Ok(())
+            }
+
+            // Git changes
+            Self::NextHunk => {
+                editor.goto_hunk(true);
+                Ok(())
+            }
+            Self::PrevHunk => {
+                editor.goto_hunk(false);
+                Ok(())
+            }
+            Self::RevertHunk => editor.revert_hunk(),
+            Self::DiffAgainstHead => {
+                editor.request_head_diff();
+                Ok(())
+            }
+            Self::MarkdownPreview => {
+                editor.request_markdown_preview();
+                Ok(())
+            }
+
+            // Bookmarks
+            Self::SetBookmark => {
+                editor.request_bookmark();
+                Ok(())
+            }
+
+            // Language server
+            Self::GotoDefinition => {
+                editor.request_definition();
+                Ok(())
+            }
+
+            // Formatting
+            Self::FormatDocument => editor.format_document(false),
+
+            // Spelling
+            Self::SpellingSuggestions => {
+                editor.open_spelling_suggestions();
+                Ok(())
+            }
+
+            // Folding
+            Self::ToggleFold => {
+                editor.toggle_fold();
+                Ok(())
+            }
+            Self::FoldAll => {
+                editor.fold_all();
+                Ok(())
+            }
+            Self::UnfoldAll => {
+                editor.unfold_all();
+                Ok(())
+            }
 
             // Search
             Self::StartSearch => {
@@ -512,17 +928,22 @@ impl EditorCommand {
                 Ok(())
             }
             Self::ReplaceNext => editor.replace_current(),
-            Self::ReplaceAll => match editor.replace_all() {
-                Ok(count) => {
-                    editor.status_message = Some(format!(
-                        "Replaced {} occurrence{}",
-                        count,
-                        if count == 1 { "" } else { "s" }
-                    ));
-                    Ok(())
-                }
-                Err(e) => Err(e),
-            },
+            Self::ReplaceAll => editor.replace_all().map(|_| ()),
+
+            // Completion popup
+            Self::CompletionNext => {
+                editor.select_completion(true);
+                Ok(())
+            }
+            Self::CompletionPrev => {
+                editor.select_completion(false);
+                Ok(())
+            }
+            Self::AcceptCompletion => editor.accept_completion(),
+            Self::CloseCompletion => {
+                editor.completion = None;
+                Ok(())
+            }
 
             // No operation
             Self::None => Ok(()),
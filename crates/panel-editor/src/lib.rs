@@ -5,13 +5,19 @@
 
 mod click_tracker;
 pub mod clipboard;
+mod completion;
 pub mod config;
 pub mod constants;
 mod core;
 pub mod cursor;
 mod file_io;
+pub mod folding;
+mod format;
 pub mod git;
 pub mod keyboard;
+mod lsp;
+mod preview;
+mod recovery;
 pub mod rendering;
 pub mod search;
 pub mod selection;
@@ -22,3 +28,6 @@ pub mod word_wrap;
 // Re-export main types
 pub use config::{EditorConfig, EditorInfo};
 pub use core::Editor;
+pub use preview::MarkdownPreview;
+pub use recovery::RecoveryUpdate;
+pub use termide_buffer::{SaveTransforms, SearchHistory, WriteOptions};
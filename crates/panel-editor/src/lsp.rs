@@ -0,0 +1,231 @@
+//! Language server state of an editor.
+//!
+//! The application sends the text of the file to its language server a
+//! moment after the last edit (`take_update`) and hands back the
+//! diagnostics the server publishes (`set_diagnostics`). Diagnostics are
+//! kept per line in grapheme columns, ready for underlining.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use ratatui::style::Color;
+use termide_buffer::TextBuffer;
+use termide_lsp::{grapheme_column, Diagnostic, Severity};
+use termide_theme::Theme;
+
+use crate::rendering::context::DiagnosticSpan;
+
+/// Range of a line covered by a diagnostic (grapheme columns)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LineDiagnostic {
+    pub start: usize,
+    /// Column after the range (None: to the end of the line)
+    pub end: Option<usize>,
+    pub severity: Severity,
+}
+
+/// Text of the buffer as the server knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TextVersion {
+    pub path: PathBuf,
+    pub language: String,
+    /// Buffer generation (changes on reload) and revision
+    pub generation: u64,
+    pub revision: u64,
+}
+
+/// Diagnostics and text synchronization of an editor.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LspState {
+    /// Diagnostic ranges of each line, most severe first
+    lines: BTreeMap<usize, Vec<LineDiagnostic>>,
+    errors: usize,
+    warnings: usize,
+    /// Text version last sent to the server
+    sent: Option<TextVersion>,
+    /// Text version not sent yet and when it was first seen
+    changed: Option<(TextVersion, Instant)>,
+}
+
+impl LspState {
+    /// Check if the text version should be sent: it differs from the last
+    /// one sent and has not changed for `debounce` (a new file or language
+    /// is sent right away)
+    pub fn take_update(&mut self, version: TextVersion, debounce: Duration) -> bool {
+        if self.sent.as_ref() == Some(&version) {
+            self.changed = None;
+            return false;
+        }
+        let same_document = self
+            .sent
+            .as_ref()
+            .is_some_and(|sent| sent.path == version.path && sent.language == version.language);
+        if same_document {
+            match &self.changed {
+                Some((changed, since)) if *changed == version => {
+                    if since.elapsed() < debounce {
+                        return false;
+                    }
+                }
+                _ => {
+                    self.changed = Some((version, Instant::now()));
+                    return false;
+                }
+            }
+        }
+        self.sent = Some(version);
+        self.changed = None;
+        true
+    }
+
+    /// Replace the diagnostics, converting their UTF-16 columns with the
+    /// lines of `buffer`
+    pub fn set_diagnostics(&mut self, diagnostics: &[Diagnostic], buffer: &TextBuffer) {
+        self.lines.clear();
+        self.errors = 0;
+        self.warnings = 0;
+        let line_count = buffer.line_count();
+        let column = |line: usize, character: usize| {
+            buffer
+                .line(line)
+                .map_or(0, |text| grapheme_column(&text, character))
+        };
+
+        for diagnostic in diagnostics {
+            match diagnostic.severity {
+                Severity::Error => self.errors += 1,
+                Severity::Warning => self.warnings += 1,
+                Severity::Information | Severity::Hint => {}
+            }
+            let (start, end) = (diagnostic.start, diagnostic.end);
+            if start.line >= line_count {
+                continue;
+            }
+            let start_column = column(start.line, start.character);
+            let last_line = end.line.min(line_count - 1);
+            for line in start.line..=last_line {
+                let from = if line == start.line { start_column } else { 0 };
+                let to = if line == end.line {
+                    // An empty range still marks the grapheme it is on
+                    Some(column(line, end.character).max(from + 1))
+                } else {
+                    None
+                };
+                self.lines.entry(line).or_default().push(LineDiagnostic {
+                    start: from,
+                    end: to,
+                    severity: diagnostic.severity,
+                });
+            }
+        }
+        for ranges in self.lines.values_mut() {
+            ranges.sort_by_key(|range| range.severity);
+        }
+    }
+
+    /// Number of errors and warnings
+    pub fn counts(&self) -> (usize, usize) {
+        (self.errors, self.warnings)
+    }
+
+    /// Underlines of the diagnostics colored by severity (hints are not
+    /// underlined)
+    pub fn spans(&self, theme: &Theme) -> BTreeMap<usize, Vec<DiagnosticSpan>> {
+        self.lines
+            .iter()
+            .filter_map(|(&line, ranges)| {
+                let spans: Vec<DiagnosticSpan> = ranges
+                    .iter()
+                    .filter_map(|range| {
+                        Some(DiagnosticSpan {
+                            start: range.start,
+                            end: range.end,
+                            color: severity_color(range.severity, theme)?,
+                        })
+                    })
+                    .collect();
+                (!spans.is_empty()).then_some((line, spans))
+            })
+            .collect()
+    }
+}
+
+fn severity_color(severity: Severity, theme: &Theme) -> Option<Color> {
+    match severity {
+        Severity::Error => Some(theme.error),
+        Severity::Warning => Some(theme.warning),
+        Severity::Information => Some(theme.accented_fg),
+        Severity::Hint => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_lsp::Position;
+
+    fn diagnostic(start: (usize, usize), end: (usize, usize), severity: Severity) -> Diagnostic {
+        Diagnostic {
+            start: Position::new(start.0, start.1),
+            end: Position::new(end.0, end.1),
+            severity,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_are_split_into_lines() {
+        let buffer = TextBuffer::from_text("let 😀x = 1;\nfoo(\n  bar)\n");
+        let mut state = LspState::default();
+        state.set_diagnostics(
+            &[
+                // "x" after the two UTF-16 units of the emoji
+                diagnostic((0, 6), (0, 7), Severity::Warning),
+                diagnostic((1, 3), (2, 5), Severity::Error),
+                diagnostic((0, 4), (0, 4), Severity::Error),
+                diagnostic((9, 0), (9, 1), Severity::Hint),
+            ],
+            &buffer,
+        );
+
+        assert_eq!(state.counts(), (2, 1));
+        let line = |start, end, severity| LineDiagnostic {
+            start,
+            end,
+            severity,
+        };
+        assert_eq!(
+            state.lines[&0],
+            [
+                line(4, Some(5), Severity::Error),
+                line(5, Some(6), Severity::Warning)
+            ]
+        );
+        assert_eq!(state.lines[&1], [line(3, None, Severity::Error)]);
+        assert_eq!(state.lines[&2], [line(0, Some(5), Severity::Error)]);
+        assert!(!state.lines.contains_key(&9));
+    }
+
+    #[test]
+    fn test_updates_wait_for_quiet_text() {
+        let version = |language: &str, revision| TextVersion {
+            path: PathBuf::from("/src/main.rs"),
+            language: language.to_string(),
+            generation: 0,
+            revision,
+        };
+        let mut state = LspState::default();
+        let debounce = Duration::from_millis(50);
+        assert!(state.take_update(version("rust", 1), debounce));
+        assert!(!state.take_update(version("rust", 1), debounce));
+
+        assert!(!state.take_update(version("rust", 2), debounce));
+        assert!(!state.take_update(version("rust", 3), debounce));
+        assert!(!state.take_update(version("rust", 3), debounce));
+        std::thread::sleep(debounce);
+        assert!(state.take_update(version("rust", 3), debounce));
+
+        assert!(state.take_update(version("c", 3), debounce));
+    }
+}
@@ -0,0 +1,855 @@
+//! Markdown to styled lines.
+//!
+//! The text is parsed with pulldown-cmark into lines of styled spans with
+//! the prefix of their container (quote bars, list bullets). Lines are
+//! wrapped to the panel width later, so a resize does not parse again.
+//! Each block remembers the source line it starts at, for scrolling along
+//! with the editor.
+
+use std::path::Path;
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use termide_highlight::{detect_language, global_highlighter, HighlightCache};
+use termide_theme::Theme;
+
+/// Bullets of unordered lists by nesting depth
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// Line of the rendered document
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PreviewLine {
+    /// Text wrapped to the panel width
+    Text {
+        /// Drawn before the first row
+        prefix: Vec<Span<'static>>,
+        /// Drawn before the following rows
+        continuation: Vec<Span<'static>>,
+        spans: Vec<Span<'static>>,
+        /// Cut at the panel width instead of wrapping (code)
+        cut: bool,
+    },
+    /// Horizontal rule across the panel
+    Rule,
+}
+
+/// Rendered document
+#[derive(Debug, Default)]
+pub(crate) struct Rendered {
+    pub lines: Vec<PreviewLine>,
+    /// (source line, index in `lines`) of each block, in order
+    pub anchors: Vec<(usize, usize)>,
+}
+
+/// Rows of a document wrapped to a width
+#[derive(Debug, Default)]
+pub(crate) struct Layout {
+    pub rows: Vec<Line<'static>>,
+    /// (source line, row) of each block, in order
+    pub anchors: Vec<(usize, usize)>,
+}
+
+impl Layout {
+    /// Row of the last block starting at or before `source_line`
+    pub fn row_for(&self, source_line: usize) -> usize {
+        let index = self
+            .anchors
+            .partition_point(|(line, _)| *line <= source_line);
+        index
+            .checked_sub(1)
+            .map_or(0, |index| self.anchors[index].1)
+    }
+}
+
+/// Block that prefixes its lines
+enum Container {
+    Quote,
+    /// List item whose text is indented by `width` columns
+    Item {
+        width: usize,
+    },
+}
+
+/// Table being collected
+#[derive(Default)]
+struct Table {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<Vec<Span<'static>>>>,
+    /// Rows of the header
+    head: usize,
+}
+
+/// Builds the lines from the parser events
+struct Renderer<'t> {
+    theme: &'t Theme,
+    /// Byte offset where each source line starts
+    line_starts: Vec<usize>,
+    out: Rendered,
+    /// Spans of the line being built
+    current: Vec<Span<'static>>,
+    /// Inline style, innermost last
+    styles: Vec<Style>,
+    containers: Vec<Container>,
+    /// Bullet of a list item not drawn yet
+    bullet: Option<String>,
+    /// Next number of each open list (None for bullets)
+    lists: Vec<Option<u64>>,
+    /// Fence language and text of the code block being collected
+    code: Option<(Option<String>, String)>,
+    table: Option<Table>,
+    /// Alt text and address of the image being collected
+    image: Option<(String, String)>,
+    /// Inside a metadata block, which is not shown
+    metadata: bool,
+}
+
+impl<'t> Renderer<'t> {
+    fn new(text: &str, theme: &'t Theme) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            theme,
+            line_starts,
+            out: Rendered::default(),
+            current: Vec::new(),
+            styles: vec![Style::default().fg(theme.fg)],
+            containers: Vec::new(),
+            bullet: None,
+            lists: Vec::new(),
+            code: None,
+            table: None,
+            image: None,
+            metadata: false,
+        }
+    }
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, style: Style) {
+        let style = self.style().patch(style);
+        self.styles.push(style);
+    }
+
+    fn pop_style(&mut self) {
+        if self.styles.len() > 1 {
+            self.styles.pop();
+        }
+    }
+
+    /// Prefixes of the first and following rows of a new line
+    fn prefixes(&mut self) -> (Vec<Span<'static>>, Vec<Span<'static>>) {
+        let bar = Style::default().fg(self.theme.disabled);
+        let mut continuation = Vec::new();
+        for container in &self.containers {
+            continuation.push(match container {
+                Container::Quote => Span::styled("│ ", bar),
+                Container::Item { width } => Span::raw(" ".repeat(*width)),
+            });
+        }
+        let mut prefix = continuation.clone();
+        if let Some(bullet) = self.bullet.take() {
+            let marker = Style::default().fg(self.theme.accented_fg);
+            match prefix.last_mut() {
+                Some(last) if matches!(self.containers.last(), Some(Container::Item { .. })) => {
+                    *last = Span::styled(bullet, marker)
+                }
+                _ => prefix.push(Span::styled(bullet, marker)),
+            }
+        }
+        (prefix, continuation)
+    }
+
+    fn push_line(&mut self, spans: Vec<Span<'static>>, cut: bool) {
+        let (prefix, continuation) = self.prefixes();
+        self.out.lines.push(PreviewLine::Text {
+            prefix,
+            continuation,
+            spans,
+            cut,
+        });
+    }
+
+    /// End the line being built
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            let spans = std::mem::take(&mut self.current);
+            self.push_line(spans, false);
+        }
+    }
+
+    fn last_is_blank(&self) -> bool {
+        match self.out.lines.last() {
+            None => true,
+            Some(PreviewLine::Text { spans, .. }) => spans.is_empty(),
+            Some(PreviewLine::Rule) => false,
+        }
+    }
+
+    /// Start a block at byte `offset`: a blank line after the previous
+    /// block (none between the items of a list) and an anchor
+    fn start_block(&mut self, offset: usize) {
+        self.flush();
+        let starts_item =
+            self.bullet.is_some() && matches!(self.containers.last(), Some(Container::Item { .. }));
+        if !self.last_is_blank() && !starts_item {
+            let (_, continuation) = self.prefixes_without_bullet();
+            self.out.lines.push(PreviewLine::Text {
+                prefix: continuation.clone(),
+                continuation,
+                spans: Vec::new(),
+                cut: false,
+            });
+        }
+        self.anchor(offset);
+    }
+
+    /// Prefixes ignoring a pending bullet (for blank lines)
+    fn prefixes_without_bullet(&mut self) -> (Vec<Span<'static>>, Vec<Span<'static>>) {
+        let bullet = self.bullet.take();
+        let prefixes = self.prefixes();
+        self.bullet = bullet;
+        prefixes
+    }
+
+    fn anchor(&mut self, offset: usize) {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let index = self.out.lines.len();
+        if self.out.anchors.last() != Some(&(line, index)) {
+            self.out.anchors.push((line, index));
+        }
+    }
+
+    /// Add inline text with the current style
+    fn text(&mut self, text: &str) {
+        let style = self.style();
+        self.styled(text, style);
+    }
+
+    fn styled(&mut self, text: &str, style: Style) {
+        if let Some((alt, _)) = &mut self.image {
+            alt.push_str(text);
+        } else if let Some(table) = &mut self.table {
+            if let Some(cell) = table.rows.last_mut().and_then(|row| row.last_mut()) {
+                cell.push(Span::styled(text.to_string(), style));
+            }
+        } else {
+            self.current.push(Span::styled(text.to_string(), style));
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>, offset: usize) {
+        if self.metadata && !matches!(event, Event::End(TagEnd::MetadataBlock(_))) {
+            return;
+        }
+        if let Some((_, code)) = &mut self.code {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => self.end_code_block(),
+                _ => {}
+            }
+            return;
+        }
+        match event {
+            Event::Start(tag) => self.start(tag, offset),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => {
+                let style = self.style().fg(self.theme.warning);
+                self.styled(&code, style);
+            }
+            Event::InlineMath(math) | Event::DisplayMath(math) => self.text(&math),
+            Event::Html(html) => {
+                let style = Style::default().fg(self.theme.disabled);
+                for line in html.lines() {
+                    self.current.push(Span::styled(line.to_string(), style));
+                    self.flush();
+                }
+            }
+            Event::InlineHtml(html) => {
+                let style = Style::default().fg(self.theme.disabled);
+                self.styled(&html, style);
+            }
+            Event::FootnoteReference(name) => self.text(&format!("[^{}]", name)),
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => {
+                if self.table.is_none() {
+                    self.flush();
+                }
+            }
+            Event::Rule => {
+                self.start_block(offset);
+                self.out.lines.push(PreviewLine::Rule);
+            }
+            Event::TaskListMarker(done) => {
+                let style = Style::default().fg(self.theme.accented_fg);
+                self.styled(if done { "☑ " } else { "☐ " }, style);
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>, offset: usize) {
+        let theme = self.theme;
+        match tag {
+            Tag::Paragraph | Tag::HtmlBlock => self.start_block(offset),
+            Tag::Heading { level, .. } => {
+                self.start_block(offset);
+                let style = match level {
+                    HeadingLevel::H1 => Style::default()
+                        .fg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => Style::default()
+                        .fg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD),
+                    _ => Style::default().add_modifier(Modifier::BOLD),
+                };
+                self.push_style(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.start_block(offset);
+                self.containers.push(Container::Quote);
+                self.push_style(Style::default().add_modifier(Modifier::ITALIC));
+            }
+            Tag::CodeBlock(kind) => {
+                self.start_block(offset);
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => Some(info.to_string()),
+                    CodeBlockKind::Indented => None,
+                };
+                self.code = Some((language, String::new()));
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.start_block(offset);
+                } else {
+                    self.flush();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                self.anchor(offset);
+                let depth = self.lists.len().saturating_sub(1);
+                let bullet = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => format!("{} ", BULLETS[depth % BULLETS.len()]),
+                };
+                self.containers.push(Container::Item {
+                    width: bullet.width(),
+                });
+                self.bullet = Some(bullet);
+            }
+            Tag::Table(alignments) => {
+                self.start_block(offset);
+                self.table = Some(Table {
+                    alignments,
+                    ..Table::default()
+                });
+            }
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(table) = &mut self.table {
+                    table.rows.push(Vec::new());
+                }
+            }
+            Tag::TableCell => {
+                if let Some(row) = self.table.as_mut().and_then(|table| table.rows.last_mut()) {
+                    row.push(Vec::new());
+                }
+            }
+            Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => {
+                self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT))
+            }
+            Tag::Link { .. } => self.push_style(
+                Style::default()
+                    .fg(theme.accented_fg)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            Tag::Image { dest_url, .. } => {
+                self.image = Some((String::new(), dest_url.to_string()));
+            }
+            Tag::MetadataBlock(_) => self.metadata = true,
+            Tag::FootnoteDefinition(name) => {
+                self.start_block(offset);
+                self.text(&format!("[^{}]: ", name));
+            }
+            Tag::Superscript
+            | Tag::Subscript
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::HtmlBlock | TagEnd::FootnoteDefinition => self.flush(),
+            TagEnd::Heading(_) => {
+                self.flush();
+                self.pop_style();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.containers.pop();
+                self.pop_style();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+            }
+            TagEnd::Item => {
+                self.flush();
+                // An empty item still shows its bullet
+                if self.bullet.is_some() {
+                    self.push_line(Vec::new(), false);
+                }
+                self.containers.pop();
+            }
+            TagEnd::Table => self.end_table(),
+            TagEnd::TableHead => {
+                if let Some(table) = &mut self.table {
+                    table.head = table.rows.len();
+                }
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                self.pop_style()
+            }
+            TagEnd::Image => self.end_image(),
+            TagEnd::MetadataBlock(_) => self.metadata = false,
+            TagEnd::CodeBlock
+            | TagEnd::TableRow
+            | TagEnd::TableCell
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::DefinitionList
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition => {}
+        }
+    }
+
+    /// Highlighted lines of the collected code block
+    fn end_code_block(&mut self) {
+        let Some((language, code)) = self.code.take() else {
+            return;
+        };
+        let code = code.strip_suffix('\n').unwrap_or(&code);
+        let language = language.as_deref().and_then(code_language);
+        for line in highlight_code(code, language.as_deref(), self.theme) {
+            let mut spans = vec![Span::raw("  ")];
+            spans.extend(line);
+            self.push_line(spans, true);
+        }
+    }
+
+    /// Placeholder box with the alt text of the image
+    fn end_image(&mut self) {
+        let Some((alt, url)) = self.image.take() else {
+            return;
+        };
+        let label = if alt.trim().is_empty() {
+            Path::new(&url)
+                .file_name()
+                .map_or(url.clone(), |name| name.to_string_lossy().into_owned())
+        } else {
+            alt
+        };
+        let label = format!(" ▣ {} ", label.trim());
+        if let Some(table) = &mut self.table {
+            if let Some(cell) = table.rows.last_mut().and_then(|row| row.last_mut()) {
+                cell.push(Span::styled(
+                    format!("[{}]", label.trim()),
+                    Style::default().fg(self.theme.disabled),
+                ));
+            }
+            return;
+        }
+        self.flush();
+        let style = Style::default().fg(self.theme.disabled);
+        let border = "─".repeat(label.width());
+        for text in [
+            format!("┌{}┐", border),
+            format!("│{}│", label),
+            format!("└{}┘", border),
+        ] {
+            self.push_line(vec![Span::styled(text, style)], true);
+        }
+    }
+
+    /// Table with aligned columns
+    fn end_table(&mut self) {
+        let Some(table) = self.table.take() else {
+            return;
+        };
+        let width = |cell: &Vec<Span<'static>>| cell.iter().map(Span::width).sum::<usize>();
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                table
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .map(width)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let border = Style::default().fg(self.theme.disabled);
+
+        for (index, row) in table.rows.into_iter().enumerate() {
+            let mut spans = Vec::new();
+            for (column, &column_width) in widths.iter().enumerate() {
+                if column > 0 {
+                    spans.push(Span::styled(" │ ", border));
+                }
+                let cell = row.get(column).cloned().unwrap_or_default();
+                let padding = column_width - width(&cell);
+                let (before, after) = match table.alignments.get(column) {
+                    Some(Alignment::Right) => (padding, 0),
+                    Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                    _ => (0, padding),
+                };
+                spans.push(Span::raw(" ".repeat(before)));
+                for span in cell {
+                    if index < table.head {
+                        spans.push(span.patch_style(Style::default().add_modifier(Modifier::BOLD)));
+                    } else {
+                        spans.push(span);
+                    }
+                }
+                spans.push(Span::raw(" ".repeat(after)));
+            }
+            self.push_line(spans, true);
+
+            if index + 1 == table.head {
+                let rule: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+                self.push_line(vec![Span::styled(rule.join("─┼─"), border)], true);
+            }
+        }
+    }
+}
+
+/// Highlighter language of a code fence tag (`rust`, `rs`, `sh`)
+fn code_language(info: &str) -> Option<String> {
+    let tag = info.split([',', ' ', '{']).next()?.trim().to_lowercase();
+    if tag.is_empty() {
+        return None;
+    }
+    if global_highlighter().get_config(&tag).is_some() {
+        return Some(tag);
+    }
+    match tag.as_str() {
+        "shell" | "console" => Some("bash".to_string()),
+        _ => detect_language(Path::new(&format!("code.{}", tag))).map(str::to_string),
+    }
+}
+
+/// Lines of a code block as spans, highlighted when the language is known
+fn highlight_code(code: &str, language: Option<&str>, theme: &Theme) -> Vec<Vec<Span<'static>>> {
+    let mut cache = HighlightCache::new(global_highlighter(), theme.is_light());
+    if let Some(language) = language {
+        cache.set_syntax(language);
+    }
+    if !cache.has_syntax() {
+        let style = Style::default().fg(theme.fg);
+        return code
+            .split('\n')
+            .map(|line| vec![Span::styled(line.to_string(), style)])
+            .collect();
+    }
+    cache.sync_document(0, code.len(), || code.to_string());
+    code.split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            cache
+                .get_line_segments(index, line)
+                .iter()
+                .map(|(text, style)| Span::styled(text.clone(), *style))
+                .collect()
+        })
+        .collect()
+}
+
+/// Render markdown text
+pub(crate) fn render(text: &str, theme: &Theme) -> Rendered {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+    let mut renderer = Renderer::new(text, theme);
+    for (event, range) in Parser::new_ext(text, options).into_offset_iter() {
+        renderer.event(event, range.start);
+    }
+    renderer.flush();
+    renderer.out
+}
+
+/// Wrap the lines of a document to `width` columns
+pub(crate) fn layout(rendered: &Rendered, width: usize, theme: &Theme) -> Layout {
+    let mut layout = Layout::default();
+    let mut anchors = rendered.anchors.iter().peekable();
+    for (index, line) in rendered.lines.iter().enumerate() {
+        while let Some(&(source_line, _)) = anchors.next_if(|(_, line)| *line <= index) {
+            layout.anchors.push((source_line, layout.rows.len()));
+        }
+        match line {
+            PreviewLine::Rule => layout.rows.push(Line::styled(
+                "─".repeat(width),
+                Style::default().fg(theme.disabled),
+            )),
+            PreviewLine::Text {
+                prefix,
+                continuation,
+                spans,
+                cut,
+            } => layout
+                .rows
+                .extend(wrap(prefix, continuation, spans, width, *cut)),
+        }
+    }
+    for &(source_line, _) in anchors {
+        layout.anchors.push((source_line, layout.rows.len()));
+    }
+    layout
+}
+
+/// Add `text` to the row, joining spans of the same style
+fn append(row: &mut Vec<Span<'static>>, text: &str, style: Style) {
+    match row.last_mut() {
+        Some(last) if last.style == style => last.content.to_mut().push_str(text),
+        _ => row.push(Span::styled(text.to_string(), style)),
+    }
+}
+
+/// Wrap spans at spaces into rows of `width` columns; words longer than a
+/// row are broken, and with `cut` the rest of the line is left out
+fn wrap(
+    prefix: &[Span<'static>],
+    continuation: &[Span<'static>],
+    spans: &[Span<'static>],
+    width: usize,
+    cut: bool,
+) -> Vec<Line<'static>> {
+    let indent: usize = prefix.iter().map(Span::width).sum();
+    let available = width.saturating_sub(indent).max(1);
+
+    let mut rows: Vec<Vec<Span<'static>>> = vec![Vec::new()];
+    let mut used = 0;
+    'spans: for span in spans {
+        let style = span.style;
+        let mut rest: &str = &span.content;
+        while !rest.is_empty() {
+            // Next run of spaces or of other characters
+            let space = rest.starts_with(' ');
+            let end = rest
+                .find(|c: char| (c == ' ') != space)
+                .unwrap_or(rest.len());
+            let (piece, after) = rest.split_at(end);
+            rest = after;
+            let piece_width = piece.width();
+
+            if used + piece_width <= available {
+                append(
+                    rows.last_mut().expect("rows start with one row"),
+                    piece,
+                    style,
+                );
+                used += piece_width;
+                continue;
+            }
+            if cut {
+                let mut fitting = String::new();
+                for c in piece.chars() {
+                    let char_width = c.width().unwrap_or(0);
+                    if used + char_width > available {
+                        break;
+                    }
+                    fitting.push(c);
+                    used += char_width;
+                }
+                append(
+                    rows.last_mut().expect("rows start with one row"),
+                    &fitting,
+                    style,
+                );
+                break 'spans;
+            }
+            if space {
+                // Spaces at a break are dropped
+                rows.push(Vec::new());
+                used = 0;
+                continue;
+            }
+            if used > 0 && piece_width <= available {
+                rows.push(Vec::new());
+                append(rows.last_mut().expect("a row was just added"), piece, style);
+                used = piece_width;
+                continue;
+            }
+            // A word longer than a row is broken
+            for c in piece.chars() {
+                let char_width = c.width().unwrap_or(0);
+                if used + char_width > available && used > 0 {
+                    rows.push(Vec::new());
+                    used = 0;
+                }
+                let mut buf = [0u8; 4];
+                append(
+                    rows.last_mut().expect("rows start with one row"),
+                    c.encode_utf8(&mut buf),
+                    style,
+                );
+                used += char_width;
+            }
+        }
+    }
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let mut line = if index == 0 {
+                prefix.to_vec()
+            } else {
+                continuation.to_vec()
+            };
+            line.extend(row);
+            Line::from(line)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(text: &str, width: usize) -> Vec<String> {
+        let theme = Theme::default();
+        layout(&render(text, &theme), width, &theme)
+            .rows
+            .iter()
+            .map(|line| line.to_string().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_blocks() {
+        let text = "# Title\n\
+                    \n\
+                    Some *emphasis* and a [link](https://example.com).\n\
+                    \n\
+                    - one\n\
+                    - two\n  \
+                      1. nested\n\
+                    \n\
+                    > quoted\n\
+                    \n\
+                    ---\n\
+                    \n\
+                    ![logo](img/logo.png)\n";
+        assert_eq!(
+            rows(text, 30),
+            [
+                "Title",
+                "",
+                "Some emphasis and a link.",
+                "",
+                "• one",
+                "• two",
+                "  1. nested",
+                "",
+                "│ quoted",
+                "",
+                "──────────────────────────────",
+                "",
+                "┌────────┐",
+                "│ ▣ logo │",
+                "└────────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_styles() {
+        let theme = Theme::default();
+        let rendered = render("# Title\n\nplain **bold** `code`\n", &theme);
+        let PreviewLine::Text { spans, .. } = &rendered.lines[0] else {
+            panic!("heading is text");
+        };
+        assert!(spans[0].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(spans[0].style.fg, Some(theme.accented_fg));
+
+        let PreviewLine::Text { spans, .. } = &rendered.lines[2] else {
+            panic!("paragraph is text");
+        };
+        let style_of = |text: &str| {
+            spans
+                .iter()
+                .find(|span| span.content == text)
+                .unwrap()
+                .style
+        };
+        assert!(style_of("bold").add_modifier.contains(Modifier::BOLD));
+        assert_eq!(style_of("code").fg, Some(theme.warning));
+    }
+
+    #[test]
+    fn test_code_block_is_highlighted_and_cut() {
+        let theme = Theme::default();
+        let text = "```rust\nfn main() { let long_name = 1; }\n```\n";
+        let rendered = render(text, &theme);
+        let PreviewLine::Text { spans, cut, .. } = &rendered.lines[0] else {
+            panic!("code is text");
+        };
+        assert!(cut);
+        let styles: Vec<Style> = spans.iter().map(|span| span.style).collect();
+        assert!(styles.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_eq!(rows(text, 12), ["  fn main()"]);
+    }
+
+    #[test]
+    fn test_table() {
+        let text = "| Name | Size |\n|:-----|-----:|\n| a | 1 |\n| long | 200 |\n";
+        assert_eq!(
+            rows(text, 40),
+            ["Name │ Size", "─────┼─────", "a    │    1", "long │  200"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_list_indent() {
+        assert_eq!(
+            rows("- alpha beta gamma delta\n", 12),
+            ["• alpha beta", "  gamma", "  delta"]
+        );
+        assert_eq!(rows("> abcdefghij\n", 6), ["│ abcd", "│ efgh", "│ ij"]);
+    }
+
+    #[test]
+    fn test_anchors_map_source_lines_to_rows() {
+        let theme = Theme::default();
+        let text = "# One\n\nfirst paragraph that wraps\n\n# Two\n\nlast\n";
+        let layout = layout(&render(text, &theme), 10, &theme);
+        let row_of = |text: &str| {
+            layout
+                .rows
+                .iter()
+                .position(|row| row.to_string().contains(text))
+                .unwrap()
+        };
+        assert_eq!(layout.row_for(0), 0);
+        assert_eq!(layout.row_for(3), row_of("first"));
+        assert_eq!(layout.row_for(4), row_of("Two"));
+        assert_eq!(layout.row_for(100), row_of("last"));
+    }
+}
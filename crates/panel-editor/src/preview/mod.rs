@@ -0,0 +1,307 @@
+//! Markdown preview panel.
+//!
+//! Shows the text of an editor rendered as markdown. The preview follows
+//! the editor view it was opened from: edits show up after a pause in
+//! typing, scrolling the editor scrolls the preview to the block at the
+//! top of the editor, and the preview closes when the view closes.
+
+mod markdown;
+
+use std::any::Any;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::{buffer::Buffer, layout::Rect, prelude::Widget, widgets::Paragraph};
+
+use termide_core::{Panel, PanelEvent, RenderContext};
+use termide_theme::Theme;
+
+use crate::constants::PREVIEW_DEBOUNCE_MS;
+use crate::state::BufferWatch;
+use markdown::{Layout, Rendered};
+
+/// Rows scrolled by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Markdown preview of an editor view
+pub struct MarkdownPreview {
+    source: BufferWatch,
+    /// File name for the title
+    name: String,
+    /// Version of the source text last seen
+    version: Option<(u64, u64)>,
+    /// When the source changed without being rendered yet
+    changed: Option<Instant>,
+    /// Source text rendered
+    text: String,
+    /// Document for the theme it was rendered with
+    rendered: Option<(Theme, Rendered)>,
+    /// Rows for the width they were wrapped to
+    layout: Option<(usize, Layout)>,
+    /// Editor top line the preview scrolls to on the next render
+    sync_to: Option<usize>,
+    /// Editor top line last synced to
+    synced: Option<usize>,
+    /// First row shown
+    scroll: usize,
+    /// Rows shown in the last render
+    page: usize,
+    cached_theme: Theme,
+}
+
+impl MarkdownPreview {
+    /// Preview of the view watched by `source`
+    pub(crate) fn new(source: BufferWatch, name: String) -> Self {
+        let version = source.version();
+        let text = source.text().unwrap_or_default();
+        let top = source.top_line();
+        Self {
+            source,
+            name,
+            version,
+            changed: None,
+            text,
+            rendered: None,
+            layout: None,
+            sync_to: top,
+            synced: top,
+            scroll: 0,
+            page: 1,
+            cached_theme: Theme::default(),
+        }
+    }
+
+    /// Take a new source text once typing paused; true if it changed
+    fn update_text(&mut self, now: Instant) -> bool {
+        let version = self.source.version();
+        if version != self.version {
+            self.version = version;
+            self.changed = Some(now);
+        }
+        let due = self.changed.is_some_and(|changed| {
+            now.duration_since(changed) >= Duration::from_millis(PREVIEW_DEBOUNCE_MS)
+        });
+        if !due {
+            return false;
+        }
+        self.changed = None;
+        let Some(text) = self.source.text() else {
+            return false;
+        };
+        if text == self.text {
+            return false;
+        }
+        self.text = text;
+        self.rendered = None;
+        self.layout = None;
+        // Stay on the block shown at the top of the editor
+        self.sync_to = self.synced;
+        true
+    }
+
+    /// Follow the editor's scroll position; true if it moved
+    fn update_scroll(&mut self) -> bool {
+        let top = self.source.top_line();
+        if top == self.synced {
+            return false;
+        }
+        self.synced = top;
+        self.sync_to = top;
+        true
+    }
+
+    /// Rows wrapped to `width`, rendering the text if needed
+    fn layout(&mut self, width: usize) -> &Layout {
+        let theme = self.cached_theme;
+        if self
+            .rendered
+            .as_ref()
+            .is_none_or(|(rendered_theme, _)| *rendered_theme != theme)
+        {
+            self.rendered = Some((theme, markdown::render(&self.text, &theme)));
+            self.layout = None;
+        }
+        if self
+            .layout
+            .as_ref()
+            .is_none_or(|(layout_width, _)| *layout_width != width)
+        {
+            let (_, rendered) = self.rendered.as_ref().expect("rendered above");
+            self.layout = Some((width, markdown::layout(rendered, width, &theme)));
+        }
+        &self.layout.as_ref().expect("laid out above").1
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.layout
+            .as_ref()
+            .map_or(0, |(_, layout)| layout.rows.len().saturating_sub(self.page))
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+}
+
+impl Panel for MarkdownPreview {
+    fn name(&self) -> &'static str {
+        "markdown_preview"
+    }
+
+    fn title(&self) -> String {
+        termide_i18n::t().markdown_preview_title(&self.name)
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        self.page = area.height as usize;
+        let sync_to = self.sync_to.take();
+        let layout = self.layout(area.width as usize);
+        let scroll = sync_to.map(|line| layout.row_for(line));
+        let rows = layout.rows.len();
+        if let Some(scroll) = scroll {
+            self.scroll = scroll;
+        }
+        self.scroll = self.scroll.min(rows.saturating_sub(self.page));
+
+        let (_, layout) = self.layout.as_ref().expect("laid out above");
+        let lines: Vec<_> = layout
+            .rows
+            .iter()
+            .skip(self.scroll)
+            .take(self.page)
+            .cloned()
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        let page = self.page.saturating_sub(1).max(1) as isize;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = self.max_scroll(),
+            _ => return vec![],
+        }
+        vec![PanelEvent::NeedsRedraw]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _panel_area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.scroll_by(SCROLL_STEP as isize),
+            _ => return vec![],
+        }
+        vec![PanelEvent::NeedsRedraw]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        let changed = self.update_text(Instant::now());
+        if self.update_scroll() || changed {
+            vec![PanelEvent::NeedsRedraw]
+        } else {
+            vec![]
+        }
+    }
+
+    fn should_auto_close(&self) -> bool {
+        !self.source.is_open()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SharedBuffer;
+    use termide_buffer::{Cursor, TextBuffer};
+
+    fn preview(buffer: &SharedBuffer) -> MarkdownPreview {
+        termide_i18n::init_with_language("en");
+        MarkdownPreview::new(buffer.watch(), "README.md".to_string())
+    }
+
+    fn screen(preview: &mut MarkdownPreview) -> Vec<String> {
+        let area = Rect::new(0, 0, 30, 4);
+        let mut buf = Buffer::empty(area);
+        let colors = termide_core::ThemeColors::from(&Theme::default());
+        let config = termide_core::PanelConfig::default();
+        let ctx = RenderContext {
+            theme: &colors,
+            config: &config,
+            is_focused: true,
+            panel_index: 0,
+            terminal_width: area.width,
+            terminal_height: area.height,
+        };
+        preview.render(area, &mut buf, &ctx);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_updates_after_a_pause() {
+        let buffer = SharedBuffer::new(TextBuffer::from_text("# Old\n"));
+        let mut preview = preview(&buffer);
+        assert_eq!(screen(&mut preview)[0], "Old");
+
+        buffer
+            .borrow_mut()
+            .insert(&Cursor::at(0, 2), "Very ")
+            .unwrap();
+        let now = Instant::now();
+        assert!(!preview.update_text(now));
+        assert_eq!(screen(&mut preview)[0], "Old");
+        let later = now + Duration::from_millis(PREVIEW_DEBOUNCE_MS);
+        assert!(preview.update_text(later));
+        assert_eq!(screen(&mut preview)[0], "Very Old");
+    }
+
+    #[test]
+    fn test_follows_editor_scroll_and_closes_with_it() {
+        let text = "# One\n\none\n\n# Two\n\ntwo\n\n# Three\n\nthree\n";
+        let buffer = SharedBuffer::new(TextBuffer::from_text(text));
+        let mut preview = preview(&buffer);
+        assert_eq!(screen(&mut preview)[0], "One");
+
+        // The editor shows "# Two" at the top
+        buffer.set_top_line(4);
+        assert!(preview.update_scroll());
+        assert_eq!(screen(&mut preview)[0], "Two");
+        // Scrolling the preview by itself is kept until the editor moves
+        preview.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(screen(&mut preview)[0], "");
+        assert!(!preview.update_scroll());
+        assert_eq!(screen(&mut preview)[0], "");
+
+        assert!(!preview.should_auto_close());
+        drop(buffer);
+        assert!(preview.should_auto_close());
+    }
+}
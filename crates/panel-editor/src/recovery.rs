@@ -0,0 +1,104 @@
+//! Recovery copies of unsaved changes.
+//!
+//! The application asks each editor on every tick whether its buffer needs
+//! copying to the recovery directory. The state lives with the shared
+//! buffer, so split views of a file write a single copy.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Buffer generation (changes on reload) and revision
+type Version = (u64, u64);
+
+/// What to do with the recovery copy of a buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryUpdate {
+    /// Write the text to the copy
+    Write {
+        id: String,
+        path: Option<PathBuf>,
+        title: String,
+        text: String,
+    },
+    /// Delete the copy (the changes were saved or undone)
+    Remove { id: String },
+}
+
+/// Recovery copy of a buffer and the text version it holds
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RecoveryState {
+    /// Name of the copy, once one was written
+    pub id: Option<String>,
+    /// Text version written last
+    written: Option<Version>,
+    /// Revision of the first change seen before anything was written
+    first_change: Option<u64>,
+    /// Latest text version and when it was first seen
+    changed: Option<(Version, Instant)>,
+}
+
+impl RecoveryState {
+    /// Check if the text version should be copied: it differs from the one
+    /// written last and has not changed for `idle`, or it is `max_edits`
+    /// edits past the copy (or past the first change when there is none)
+    pub fn take_update(&mut self, version: Version, idle: Duration, max_edits: u64) -> bool {
+        if self.written == Some(version) {
+            self.first_change = None;
+            self.changed = None;
+            return false;
+        }
+        let since = match self.written {
+            Some((generation, revision)) if generation == version.0 => revision,
+            _ => *self.first_change.get_or_insert(version.1),
+        };
+        let idle_passed = match self.changed {
+            Some((changed, since)) if changed == version => since.elapsed() >= idle,
+            _ => {
+                self.changed = Some((version, Instant::now()));
+                idle.is_zero()
+            }
+        };
+        if !idle_passed && version.1.saturating_sub(since) < max_edits {
+            return false;
+        }
+        self.written = Some(version);
+        self.first_change = None;
+        self.changed = None;
+        true
+    }
+
+    /// When the latest change will have been left for `idle`, so that
+    /// `take_update` copies it
+    pub fn deadline(&self, idle: Duration) -> Option<Instant> {
+        self.changed.map(|(_, since)| since + idle)
+    }
+
+    /// Forget the copy (the buffer has nothing to recover), returning its
+    /// name if one was written
+    pub fn clear(&mut self) -> Option<String> {
+        std::mem::take(self).id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_update_after_idle_or_edits() {
+        let idle = Duration::from_millis(20);
+        let mut state = RecoveryState::default();
+        assert!(!state.take_update((0, 1), idle, 5));
+        assert!(!state.take_update((0, 2), idle, 5));
+        std::thread::sleep(idle);
+        // Still typing
+        assert!(!state.take_update((0, 3), idle, 5));
+        std::thread::sleep(idle);
+        assert!(state.take_update((0, 3), idle, 5));
+        assert!(!state.take_update((0, 3), Duration::ZERO, 5));
+
+        // Enough edits are written without a pause
+        assert!(!state.take_update((0, 6), idle, 5));
+        assert!(state.take_update((0, 8), idle, 5));
+    }
+}
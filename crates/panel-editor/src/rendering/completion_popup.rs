@@ -0,0 +1,92 @@
+//! Word completion popup.
+//!
+//! The popup lists candidates under the completed word. It goes above the
+//! word when there is more room there, and is shifted left and cut to the
+//! editor area so it never draws outside the panel.
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use unicode_width::UnicodeWidthStr;
+
+use termide_theme::Theme;
+
+/// Most candidates shown at once (the list scrolls to the highlighted one)
+pub const MAX_VISIBLE_ITEMS: usize = 8;
+
+/// Area of a popup `width` columns wide with `items` rows for a word
+/// starting at screen cell `anchor`, within `area`
+pub fn popup_area(area: Rect, anchor: (u16, u16), width: u16, items: usize) -> Rect {
+    let (x, y) = anchor;
+    let wanted = items.min(MAX_VISIBLE_ITEMS) as u16;
+    let below = area.bottom().saturating_sub(y + 1);
+    let above = y.saturating_sub(area.y);
+
+    let (top, height) = if below >= wanted || below >= above {
+        (y + 1, wanted.min(below))
+    } else {
+        let height = wanted.min(above);
+        (y - height, height)
+    };
+    let width = width.min(area.width);
+    let left = x.min(area.right().saturating_sub(width)).max(area.x);
+    Rect::new(left, top, width, height)
+}
+
+/// Render the candidates with the `selected` one highlighted
+pub fn render_completion_popup(
+    buf: &mut Buffer,
+    area: Rect,
+    anchor: (u16, u16),
+    items: &[String],
+    selected: usize,
+    theme: &Theme,
+) {
+    let width = items.iter().map(|item| item.width()).max().unwrap_or(0) + 2;
+    let popup = popup_area(area, anchor, width as u16, items.len());
+    if popup.is_empty() {
+        return;
+    }
+
+    let item_style = Style::default().fg(theme.fg).bg(theme.accented_bg);
+    let selected_style = Style::default().fg(theme.selected_fg).bg(theme.selected_bg);
+    let rows = popup.height as usize;
+    let first = selected.saturating_sub(rows - 1);
+    for (row, (index, item)) in items.iter().enumerate().skip(first).take(rows).enumerate() {
+        let style = if index == selected {
+            selected_style
+        } else {
+            item_style
+        };
+        let y = popup.y + row as u16;
+        buf.set_style(Rect::new(popup.x, y, popup.width, 1), style);
+        buf.set_stringn(
+            popup.x + 1,
+            y,
+            item,
+            (popup.width as usize).saturating_sub(2),
+            style,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_popup_stays_inside_area() {
+        let area = Rect::new(10, 5, 30, 20);
+
+        // Below the word when it fits
+        assert_eq!(popup_area(area, (12, 6), 8, 3), Rect::new(12, 7, 8, 3));
+
+        // Above the word near the bottom, shifted left at the right edge
+        assert_eq!(popup_area(area, (36, 22), 8, 20), Rect::new(32, 14, 8, 8));
+
+        // Cut to the room left and to the area width
+        assert_eq!(popup_area(area, (10, 22), 50, 1), Rect::new(10, 23, 30, 1));
+        assert_eq!(
+            popup_area(Rect::new(0, 0, 20, 3), (5, 1), 6, 5),
+            Rect::new(5, 2, 6, 1)
+        );
+    }
+}
@@ -3,10 +3,24 @@
 //! This module provides the RenderContext struct that encapsulates all state
 //! needed for rendering the editor content area.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
+use ratatui::style::Color;
 use termide_buffer::{Cursor, SearchState, Selection};
 
+use super::whitespace::WhitespaceMarkers;
+
+/// Underlined range of a line: a language server diagnostic or a
+/// misspelled word.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiagnosticSpan {
+    /// First grapheme column
+    pub start: usize,
+    /// Column after the range (None: to the end of the line)
+    pub end: Option<usize>,
+    pub color: Color,
+}
+
 /// Pre-computed rendering context.
 ///
 /// Contains all derived state needed for efficient rendering without
@@ -28,6 +42,22 @@ pub struct RenderContext {
 
     /// Cursor position in viewport coordinates (row, col), set during rendering.
     pub cursor_viewport_pos: Option<(usize, usize)>,
+
+    /// Diagnostic underlines of each line, most severe first.
+    pub diagnostics: BTreeMap<usize, Vec<DiagnosticSpan>>,
+
+    /// Underlines of misspelled words of each line.
+    pub misspellings: BTreeMap<usize, Vec<DiagnosticSpan>>,
+
+    /// Lines holding a bookmark.
+    pub bookmarks: BTreeSet<usize>,
+
+    /// Glyphs drawn for whitespace.
+    pub whitespace: WhitespaceMarkers,
+
+    /// Graphemes of a line highlighted before the rest is shown plain
+    /// (0 = no limit).
+    pub max_highlight_columns: usize,
 }
 
 impl RenderContext {
@@ -41,7 +71,7 @@ impl RenderContext {
             search
                 .matches
                 .iter()
-                .map(|c| (c.line, c.column, search.query.len()))
+                .map(|c| (c.line, c.column, search.query_len()))
                 .collect()
         } else {
             Vec::new()
@@ -61,8 +91,34 @@ impl RenderContext {
             current_match_idx,
             selection_range,
             cursor_viewport_pos: None,
+            diagnostics: BTreeMap::new(),
+            misspellings: BTreeMap::new(),
+            bookmarks: BTreeSet::new(),
+            whitespace: WhitespaceMarkers::off(),
+            max_highlight_columns: 0,
         }
     }
+
+    /// Color of the most severe diagnostic of a line (for its number)
+    pub fn diagnostic_line_color(&self, line: usize) -> Option<Color> {
+        self.diagnostics
+            .get(&line)
+            .and_then(|spans| spans.first())
+            .map(|span| span.color)
+    }
+
+    /// Underline color of the most severe diagnostic covering a cell, or
+    /// of a misspelled word
+    pub fn diagnostic_color(&self, line: usize, column: usize) -> Option<Color> {
+        let covering = |spans: &BTreeMap<usize, Vec<DiagnosticSpan>>| {
+            spans
+                .get(&line)?
+                .iter()
+                .find(|span| column >= span.start && span.end.is_none_or(|end| column < end))
+                .map(|span| span.color)
+        };
+        covering(&self.diagnostics).or_else(|| covering(&self.misspellings))
+    }
 }
 
 /// Build HashMap for O(1) search match lookups during rendering.
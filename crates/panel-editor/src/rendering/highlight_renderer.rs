@@ -3,7 +3,10 @@
 //! This module provides functions for determining the final visual style of each
 //! character based on syntax highlighting, selection, search matches, and cursor position.
 
-use ratatui::style::{Color, Style};
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
 
 use termide_buffer::Cursor;
 
@@ -17,6 +20,8 @@ use super::context::RenderContext;
 /// 3. Text selection
 /// 4. Cursor line (base style with accented background)
 /// 5. Base syntax highlighting style
+///
+/// Cells covered by a diagnostic are underlined in its color.
 #[allow(clippy::too_many_arguments)] // Logical grouping of styling parameters
 pub fn determine_cell_style(
     line: usize,
@@ -47,7 +52,7 @@ pub fn determine_cell_style(
     };
 
     // Determine final style based on priority
-    if let Some(idx) = match_idx {
+    let style = if let Some(idx) = match_idx {
         // Search match - highest priority
         if Some(idx) == render_context.current_match_idx {
             current_match_style
@@ -63,14 +68,62 @@ pub fn determine_cell_style(
     } else {
         // Regular syntax highlighting
         base_style
+    };
+
+    // Diagnostics underline the text whatever its background
+    match render_context.diagnostic_color(line, column) {
+        Some(color) => style
+            .add_modifier(Modifier::UNDERLINED)
+            .underline_color(color),
+        None => style,
+    }
+}
+
+/// Draw a grapheme `width` columns wide at `(x, y)`, or the whitespace
+/// `marker` glyph in its color instead (blanking the rest of a wide space).
+/// Nothing is drawn at or right of `right`.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_grapheme(
+    buf: &mut Buffer,
+    x: u16,
+    y: u16,
+    grapheme: &str,
+    width: usize,
+    style: Style,
+    marker: Option<(&str, Color)>,
+    right: u16,
+) {
+    let Some((glyph, color)) = marker else {
+        if let Some(cell) = buf.cell_mut((x, y)) {
+            // Use set_symbol for proper grapheme cluster handling
+            cell.set_symbol(grapheme);
+            cell.set_style(style);
+        }
+        return;
+    };
+    for (i, symbol) in std::iter::once(glyph)
+        .chain(std::iter::repeat(" "))
+        .take(width)
+        .enumerate()
+    {
+        let x = x + i as u16;
+        if x >= right {
+            break;
+        }
+        if let Some(cell) = buf.cell_mut((x, y)) {
+            cell.set_symbol(symbol);
+            cell.set_style(style.fg(color));
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rendering::context::DiagnosticSpan;
+    use crate::rendering::whitespace::WhitespaceMarkers;
     use ratatui::style::{Color, Style};
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
 
     fn create_test_context(
         search_matches: Vec<(usize, usize)>,
@@ -88,6 +141,11 @@ mod tests {
             current_match_idx,
             selection_range,
             cursor_viewport_pos: None,
+            diagnostics: BTreeMap::new(),
+            misspellings: BTreeMap::new(),
+            bookmarks: BTreeSet::new(),
+            whitespace: WhitespaceMarkers::off(),
+            max_highlight_columns: 0,
         }
     }
 
@@ -213,4 +271,81 @@ mod tests {
 
         assert_eq!(result, base_style); // Returns base style unchanged
     }
+
+    #[test]
+    fn test_diagnostic_underline() {
+        let mut context = create_test_context(vec![], None, None);
+        context.diagnostics.insert(
+            0,
+            vec![DiagnosticSpan {
+                start: 4,
+                end: Some(6),
+                color: Color::Red,
+            }],
+        );
+
+        let base_style = Style::default().fg(Color::Cyan);
+        let style_at = |column| {
+            determine_cell_style(
+                0,
+                column,
+                base_style,
+                false,
+                &context,
+                Style::default(),
+                Style::default(),
+                Style::default(),
+                Color::DarkGray,
+            )
+        };
+
+        assert_eq!(style_at(3), base_style);
+        let underlined = style_at(5);
+        assert!(underlined.add_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(underlined.underline_color, Some(Color::Red));
+        assert_eq!(style_at(6), base_style);
+    }
+
+    #[test]
+    fn test_misspelling_underline_below_diagnostics() {
+        let mut context = create_test_context(vec![], None, None);
+        context.diagnostics.insert(
+            0,
+            vec![DiagnosticSpan {
+                start: 4,
+                end: Some(6),
+                color: Color::Red,
+            }],
+        );
+        context.misspellings.insert(
+            0,
+            vec![DiagnosticSpan {
+                start: 0,
+                end: Some(5),
+                color: Color::DarkGray,
+            }],
+        );
+
+        let base_style = Style::default().fg(Color::Cyan);
+        let style_at = |column| {
+            determine_cell_style(
+                0,
+                column,
+                base_style,
+                false,
+                &context,
+                Style::default(),
+                Style::default(),
+                Style::default(),
+                Color::DarkGray,
+            )
+        };
+
+        let misspelled = style_at(3);
+        assert!(misspelled.add_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(misspelled.underline_color, Some(Color::DarkGray));
+        // A diagnostic on the same text wins
+        assert_eq!(style_at(4).underline_color, Some(Color::Red));
+        assert_eq!(style_at(6), base_style);
+    }
 }
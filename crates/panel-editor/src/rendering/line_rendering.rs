@@ -5,9 +5,8 @@
 
 use ratatui::{buffer::Buffer, layout::Rect, style::Style};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
-use termide_buffer::{Cursor, TextBuffer, Viewport};
+use termide_buffer::{Cursor, LineSlice, TextBuffer, Viewport};
 use termide_git::GitDiffCache;
 use termide_highlight::LineHighlighter;
 use termide_theme::Theme;
@@ -18,7 +17,7 @@ use crate::git;
 /// Render a single line in no-wrap mode.
 ///
 /// Handles:
-/// - Line number gutter with git status
+/// - Line number gutter with git status and fold or bookmark marker
 /// - Syntax-highlighted content with horizontal scrolling
 /// - Search matches, selection, and cursor line styling
 /// - Background fill for cursor line
@@ -27,9 +26,11 @@ pub fn render_line_no_wrap<H: LineHighlighter>(
     buf: &mut Buffer,
     area: Rect,
     row: usize,
+    buffer: &TextBuffer,
     line_idx: usize,
-    line_text: &str,
+    slice: &LineSlice,
     is_cursor_line: bool,
+    gutter_marker: char,
     text_style: Style,
     cursor_line_style: Style,
     git_diff_cache: &Option<GitDiffCache>,
@@ -57,18 +58,21 @@ pub fn render_line_no_wrap<H: LineHighlighter>(
         area,
         row,
         line_idx,
+        gutter_marker,
         git_diff_cache,
         show_git_diff,
         theme,
+        render_context,
     );
 
     // Render line content with horizontal scrolling
-    render_line_content_horizontal_scroll(
+    let line_display_width = render_line_content_horizontal_scroll(
         buf,
         area,
         row,
+        buffer,
         line_idx,
-        line_text,
+        slice,
         is_cursor_line,
         style,
         line_number_width,
@@ -89,7 +93,7 @@ pub fn render_line_no_wrap<H: LineHighlighter>(
             buf,
             area,
             row,
-            line_text,
+            line_display_width,
             line_number_width,
             content_width,
             left_column,
@@ -98,20 +102,26 @@ pub fn render_line_no_wrap<H: LineHighlighter>(
     }
 }
 
-/// Render line number gutter with git status markers.
+/// Render line number gutter with git status and fold or bookmark markers.
+#[allow(clippy::too_many_arguments)]
 fn render_line_gutter(
     buf: &mut Buffer,
     area: Rect,
     row: usize,
     line_idx: usize,
+    gutter_marker: char,
     git_diff_cache: &Option<GitDiffCache>,
     show_git_diff: bool,
     theme: &Theme,
+    render_context: &RenderContext,
 ) {
     let git_info = git::get_git_line_info(line_idx, git_diff_cache, show_git_diff, theme);
 
     // Render line number (4 chars) + status marker (1 char)
-    let line_num_style = Style::default().fg(git_info.status_color);
+    // Lines with diagnostics show their number in the color of the most severe one
+    let line_num_style = Style::default().fg(render_context
+        .diagnostic_line_color(line_idx)
+        .unwrap_or(git_info.status_color));
     let line_num_part = format!("{:>4}{}", line_idx + 1, git_info.status_marker);
 
     for (i, ch) in line_num_part.chars().enumerate() {
@@ -123,23 +133,31 @@ fn render_line_gutter(
         }
     }
 
-    // Render space after marker (deletion markers are now virtual lines)
+    // Render fold or bookmark marker after git marker (deletion markers are now virtual lines)
     let x = area.x + 5;
     let y = area.y + row as u16;
     if let Some(cell) = buf.cell_mut((x, y)) {
-        cell.set_char(' ');
-        cell.set_style(line_num_style);
+        cell.set_char(gutter_marker);
+        cell.set_style(super::gutter_marker_style(
+            gutter_marker,
+            line_num_style,
+            theme,
+        ));
     }
 }
 
 /// Render line content with horizontal scrolling.
+///
+/// `slice` is the part of the line in the visible columns. Returns the
+/// display column after the rendered part.
 #[allow(clippy::too_many_arguments)]
 fn render_line_content_horizontal_scroll<H: LineHighlighter>(
     buf: &mut Buffer,
     area: Rect,
     row: usize,
+    buffer: &TextBuffer,
     line_idx: usize,
-    line_text: &str,
+    slice: &LineSlice,
     is_cursor_line: bool,
     style: Style,
     line_number_width: u16,
@@ -152,23 +170,27 @@ fn render_line_content_horizontal_scroll<H: LineHighlighter>(
     current_match_style: Style,
     selection_style: Style,
     theme: &Theme,
-) {
-    // Get syntax highlighting segments
-    let segments = if syntax_highlighting_enabled && highlight_cache.has_syntax() {
-        highlight_cache.get_line_segments(line_idx, line_text)
-    } else {
-        // No syntax highlighting - use single segment
-        &[(line_text.to_string(), style)][..]
-    };
+) -> usize {
+    // Highlighting segments cut to the visible part of the line
+    let part = super::part_to_highlight(
+        buffer,
+        line_idx,
+        syntax_highlighting_enabled,
+        highlight_cache,
+        render_context.max_highlight_columns,
+    );
+    let spans = super::slice_spans(line_idx, slice, part.as_ref(), highlight_cache, style);
 
-    // Render segments with horizontal scrolling
+    // Render spans with horizontal scrolling
     // Using graphemes instead of chars to properly handle combining characters (Hindi, etc.)
-    let mut col_offset = 0;
-    let mut grapheme_idx = 0; // Grapheme index for selection/search matching
-    for (segment_text, segment_style) in segments {
+    let whitespace = &render_context.whitespace;
+    let trailing_start = whitespace.trailing_start(slice);
+    let mut col_offset = slice.start.column;
+    let mut grapheme_idx = slice.start.grapheme; // Grapheme index for selection/search matching
+    for (segment_text, segment_style) in spans {
         for grapheme in segment_text.graphemes(true) {
-            // Get display width of grapheme cluster
-            let grapheme_width = grapheme.width();
+            // Get display width of grapheme cluster (marked zero-width spaces take a column)
+            let grapheme_width = whitespace.width(grapheme, grapheme_idx, trailing_start);
 
             // Skip zero-width graphemes
             if grapheme_width == 0 {
@@ -181,30 +203,36 @@ fn render_line_content_horizontal_scroll<H: LineHighlighter>(
                 let y = area.y + row as u16;
 
                 if x < area.x + area.width && y < area.y + area.height {
-                    if let Some(cell) = buf.cell_mut((x, y)) {
-                        // Use set_symbol for proper grapheme cluster handling
-                        cell.set_symbol(grapheme);
-
-                        // Determine final style using highlight renderer
-                        let final_style = highlight_renderer::determine_cell_style(
-                            line_idx,
-                            grapheme_idx,
-                            *segment_style,
-                            is_cursor_line,
-                            render_context,
-                            search_match_style,
-                            current_match_style,
-                            selection_style,
-                            theme.accented_bg,
-                        );
-                        cell.set_style(final_style);
-                    }
+                    // Determine final style using highlight renderer
+                    let final_style = highlight_renderer::determine_cell_style(
+                        line_idx,
+                        grapheme_idx,
+                        segment_style,
+                        is_cursor_line,
+                        render_context,
+                        search_match_style,
+                        current_match_style,
+                        selection_style,
+                        theme.accented_bg,
+                    );
+                    let marker = whitespace.marker(grapheme, grapheme_idx, trailing_start);
+                    highlight_renderer::draw_grapheme(
+                        buf,
+                        x,
+                        y,
+                        grapheme,
+                        grapheme_width,
+                        final_style,
+                        marker,
+                        area.x + area.width,
+                    );
                 }
             }
             col_offset += grapheme_width;
             grapheme_idx += 1;
         }
     }
+    col_offset
 }
 
 /// Fill remainder of line with cursor line background.
@@ -213,15 +241,12 @@ fn fill_line_remainder(
     buf: &mut Buffer,
     area: Rect,
     row: usize,
-    line_text: &str,
+    line_display_width: usize,
     line_number_width: u16,
     content_width: usize,
     left_column: usize,
     cursor_line_style: Style,
 ) {
-    // Use display width for CJK characters
-    let line_display_width = line_text.width();
-
     for col in line_display_width..content_width {
         if col >= left_column {
             let x = area.x + line_number_width + (col - left_column) as u16;
@@ -243,6 +268,8 @@ fn fill_line_remainder(
 /// - Virtual lines (real lines + deletion markers)
 /// - Horizontal scrolling
 /// - Cursor positioning accounting for virtual lines
+///
+/// Returns the screen cell of the cursor if it is visible.
 #[allow(clippy::too_many_arguments)]
 pub fn render_content_no_wrap<H: LineHighlighter>(
     buf: &mut Buffer,
@@ -264,41 +291,35 @@ pub fn render_content_no_wrap<H: LineHighlighter>(
     search_match_style: Style,
     current_match_style: Style,
     selection_style: Style,
-) {
-    // Build list of virtual lines (real buffer lines + deletion markers)
-    let virtual_lines = git::build_virtual_lines(buffer, git_diff_cache, show_git_diff);
-
-    // Find index of first virtual line for viewport.top_line
-    let start_virtual_idx = virtual_lines
-        .iter()
-        .position(|vline| matches!(vline, git::VirtualLine::Real(idx) if *idx >= viewport.top_line))
-        .unwrap_or(virtual_lines.len());
+) -> Option<(u16, u16)> {
+    // Build list of visible virtual lines (real buffer lines + deletion markers)
+    let virtual_lines = git::build_virtual_lines(
+        buffer,
+        &viewport.folds,
+        git_diff_cache,
+        show_git_diff,
+        viewport.top_line,
+        content_height,
+    );
 
     // Render visible virtual lines
-    for row in 0..content_height {
-        let virtual_idx = start_virtual_idx + row;
-
-        if virtual_idx >= virtual_lines.len() {
-            break;
-        }
-
-        let virtual_line = virtual_lines[virtual_idx];
-
+    for (row, &virtual_line) in virtual_lines.iter().enumerate() {
         // Handle different types of virtual lines
         match virtual_line {
             git::VirtualLine::Real(line_idx) => {
-                // Render real line
-                if let Some(line_text) = buffer.line(line_idx) {
-                    let line_text = line_text.trim_end_matches('\n');
+                // Render the visible part of the line
+                if let Some(slice) = buffer.line_slice_columns(line_idx, viewport.columns()) {
                     let is_cursor_line = line_idx == cursor.line;
 
                     render_line_no_wrap(
                         buf,
                         area,
                         row,
+                        buffer,
                         line_idx,
-                        line_text,
+                        &slice,
                         is_cursor_line,
+                        super::gutter_marker(&viewport.folds, &render_context.bookmarks, line_idx),
                         text_style,
                         cursor_line_style,
                         git_diff_cache,
@@ -336,20 +357,18 @@ pub fn render_content_no_wrap<H: LineHighlighter>(
         .iter()
         .position(|vline| matches!(vline, git::VirtualLine::Real(idx) if *idx == cursor.line));
 
-    if let Some(cursor_virtual_idx) = cursor_virtual_idx {
-        if cursor_virtual_idx >= start_virtual_idx {
-            let viewport_row = cursor_virtual_idx - start_virtual_idx;
+    if let Some(viewport_row) = cursor_virtual_idx {
+        if cursor.column >= viewport.left_column {
+            let viewport_col = cursor.column - viewport.left_column;
 
-            if cursor.column >= viewport.left_column {
-                let viewport_col = cursor.column - viewport.left_column;
+            let cursor_x = area.x + line_number_width + viewport_col as u16;
+            let cursor_y = area.y + viewport_row as u16;
 
-                let cursor_x = area.x + line_number_width + viewport_col as u16;
-                let cursor_y = area.y + viewport_row as u16;
-
-                if viewport_col < content_width {
-                    super::cursor_renderer::render_cursor_at(buf, cursor_x, cursor_y, area, theme);
-                }
+            if viewport_col < content_width {
+                super::cursor_renderer::render_cursor_at(buf, cursor_x, cursor_y, area, theme);
+                return Some((cursor_x, cursor_y));
             }
         }
     }
+    None
 }
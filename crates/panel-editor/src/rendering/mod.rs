@@ -8,17 +8,23 @@ use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
 };
+use std::collections::{BTreeMap, BTreeSet};
 
-use termide_buffer::{Cursor, SearchState, Selection, TextBuffer, Viewport};
+use termide_buffer::{
+    Cursor, FoldMap, LineSlice, SearchState, Selection, TextBuffer, Viewport, WrapMode,
+};
 use termide_git::GitDiffCache;
 use termide_highlight::LineHighlighter;
 use termide_theme::Theme;
 
+pub mod completion_popup;
 pub mod context;
 pub mod cursor_renderer;
 pub mod deletion_markers;
 pub mod highlight_renderer;
 pub mod line_rendering;
+pub mod scrollbar;
+pub mod whitespace;
 pub mod wrap_rendering;
 
 /// Width of the line number column (including git markers).
@@ -26,11 +32,101 @@ pub mod wrap_rendering;
 /// Format: "  123  " (2 spaces + 3 digits + 2 git markers)
 pub const LINE_NUMBER_WIDTH: usize = 6;
 
+/// Gutter marker of a line holding a bookmark
+pub const BOOKMARK_MARKER: char = '◆';
+
+/// Gutter marker after the git status marker: folded line, line holding a
+/// bookmark, or foldable line.
+pub fn gutter_marker(folds: &FoldMap, bookmarks: &BTreeSet<usize>, line_idx: usize) -> char {
+    if folds.is_folded(line_idx) {
+        '▸'
+    } else if bookmarks.contains(&line_idx) {
+        BOOKMARK_MARKER
+    } else if folds.is_foldable(line_idx) {
+        '▾'
+    } else {
+        ' '
+    }
+}
+
+/// Style of a gutter marker: bookmarks in the accent color, other markers
+/// like the line number
+pub fn gutter_marker_style(marker: char, line_number_style: Style, theme: &Theme) -> Style {
+    if marker == BOOKMARK_MARKER {
+        line_number_style.fg(theme.accented_fg)
+    } else {
+        line_number_style
+    }
+}
+
+/// Start of a line passed to the highlighter: its first `limit` graphemes
+/// (0 = the whole line). Everything asking the highlighter about a line
+/// must pass the same text.
+pub fn highlighted_part(buffer: &TextBuffer, line_idx: usize, limit: usize) -> Option<LineSlice> {
+    let end = if limit == 0 { usize::MAX } else { limit };
+    buffer.line_slice(line_idx, 0..end)
+}
+
+/// Highlighted part of a line about to be rendered (None when the line is
+/// rendered plain).
+pub fn part_to_highlight<H: LineHighlighter>(
+    buffer: &TextBuffer,
+    line_idx: usize,
+    syntax_highlighting_enabled: bool,
+    highlight_cache: &H,
+    max_highlight_columns: usize,
+) -> Option<LineSlice> {
+    if !syntax_highlighting_enabled || !highlight_cache.has_syntax() {
+        return None;
+    }
+    highlighted_part(buffer, line_idx, max_highlight_columns)
+}
+
+/// Styled spans of a part of a line: highlighted as far as the highlighted
+/// `part` of the line goes, in `style` after that.
+///
+/// Only the part is split, so the cost doesn't grow with the line.
+pub fn slice_spans<'a, H: LineHighlighter>(
+    line_idx: usize,
+    slice: &'a LineSlice,
+    part: Option<&LineSlice>,
+    highlight_cache: &mut H,
+    style: Style,
+) -> Vec<(&'a str, Style)> {
+    let Some(part) = part.filter(|part| slice.start.byte < part.text.len()) else {
+        return vec![(slice.text.as_str(), style)];
+    };
+
+    // Segments cover the highlighted part from the start of the line
+    let start = slice.start.byte;
+    let end = start + slice.text.len();
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for (text, segment_style) in highlight_cache.get_line_segments(line_idx, &part.text) {
+        let (segment_start, segment_end) = (offset, offset + text.len());
+        offset = segment_end;
+        if segment_end <= start {
+            continue;
+        }
+        if segment_start >= end {
+            break;
+        }
+        let (from, to) = (segment_start.max(start), segment_end.min(end));
+        spans.push((&slice.text[from - start..to - start], *segment_style));
+    }
+    if offset < end {
+        spans.push((&slice.text[offset.max(start) - start..], style));
+    }
+    spans
+}
+
 /// Calculate content area dimensions.
 ///
-/// Returns (content_width, content_height) accounting for line numbers.
+/// Returns (content_width, content_height) accounting for line numbers
+/// and the scrollbar.
 pub fn calculate_content_dimensions(area_width: u16, area_height: u16) -> (usize, usize) {
-    let content_width = (area_width as usize).saturating_sub(LINE_NUMBER_WIDTH);
+    let scrollbar_width = usize::from(scrollbar::has_scrollbar(area_width));
+    let content_width = (area_width as usize).saturating_sub(LINE_NUMBER_WIDTH + scrollbar_width);
     let content_height = area_height as usize;
     (content_width, content_height)
 }
@@ -42,6 +138,8 @@ pub fn calculate_content_dimensions(area_width: u16, area_height: u16) -> (usize
 /// - Prepares rendering context (search matches, selection)
 /// - Selects appropriate rendering mode (word wrap vs no wrap)
 /// - Delegates to specialized rendering functions
+///
+/// Returns the screen cell of the cursor if it is visible.
 #[allow(clippy::too_many_arguments)]
 pub fn render_editor_content<H: LineHighlighter>(
     buf: &mut Buffer,
@@ -54,13 +152,18 @@ pub fn render_editor_content<H: LineHighlighter>(
     highlight_cache: &mut H,
     search_state: &Option<SearchState>,
     selection: &Option<Selection>,
+    diagnostics: BTreeMap<usize, Vec<context::DiagnosticSpan>>,
+    misspellings: BTreeMap<usize, Vec<context::DiagnosticSpan>>,
+    bookmarks: BTreeSet<usize>,
+    whitespace: whitespace::WhitespaceMarkers,
+    max_highlight_columns: usize,
     theme: &Theme,
     show_git_diff: bool,
     word_wrap_enabled: bool,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
     content_width: usize,
     content_height: usize,
-) {
+) -> Option<(u16, u16)> {
     let line_number_width = LINE_NUMBER_WIDTH as u16;
 
     // Create rendering styles from theme
@@ -79,6 +182,11 @@ pub fn render_editor_content<H: LineHighlighter>(
 
     // Prepare rendering context
     let mut render_context = context::RenderContext::prepare(search_state, selection);
+    render_context.diagnostics = diagnostics;
+    render_context.misspellings = misspellings;
+    render_context.bookmarks = bookmarks;
+    render_context.whitespace = whitespace;
+    render_context.max_highlight_columns = max_highlight_columns;
 
     // Select rendering mode
     if word_wrap_enabled && content_width > 0 {
@@ -98,14 +206,14 @@ pub fn render_editor_content<H: LineHighlighter>(
             content_width,
             content_height,
             line_number_width,
-            use_smart_wrap,
+            wrap_mode,
             text_style,
             cursor_line_style,
             line_number_style,
             search_match_style,
             current_match_style,
             selection_style,
-        );
+        )
     } else {
         // No-wrap mode
         line_rendering::render_content_no_wrap(
@@ -128,6 +236,6 @@ pub fn render_editor_content<H: LineHighlighter>(
             search_match_style,
             current_match_style,
             selection_style,
-        );
+        )
     }
 }
@@ -0,0 +1,215 @@
+//! Scrollbar on the right edge of the editor.
+//!
+//! One column shows the part of the document in view together with marks
+//! for lines changed since HEAD and search matches. Marks are collected
+//! into one bucket per scrollbar row only when the text, the diff, the
+//! search, the folds or the height change, so a frame just reads them.
+
+use std::ops::Range;
+use std::time::Instant;
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+use termide_buffer::{FoldMap, SearchState};
+use termide_git::{GitDiffCache, LineStatus};
+use termide_theme::Theme;
+
+/// Narrowest editor area (in columns) that shows the scrollbar.
+pub const SCROLLBAR_MIN_WIDTH: u16 = 40;
+
+/// Check if an editor area of the given width has room for the scrollbar.
+pub fn has_scrollbar(area_width: u16) -> bool {
+    area_width >= SCROLLBAR_MIN_WIDTH
+}
+
+/// Mark shown on a scrollbar row, ordered by priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScrollbarMark {
+    /// Lines deleted since HEAD
+    Deleted,
+    /// Lines added since HEAD
+    Added,
+    /// Lines modified since HEAD
+    Modified,
+    /// Search match
+    SearchMatch,
+}
+
+/// State the marks were collected from.
+#[derive(Debug, Clone, PartialEq)]
+struct MarksKey {
+    height: usize,
+    total_rows: usize,
+    revision: u64,
+    diff: Option<(Instant, usize)>,
+    search: Option<(String, bool, usize)>,
+}
+
+/// Scrollbar marks bucketed by scrollbar row.
+#[derive(Debug, Default)]
+pub struct ScrollbarMarks {
+    key: Option<MarksKey>,
+    buckets: Vec<Option<ScrollbarMark>>,
+}
+
+impl ScrollbarMarks {
+    /// Recollect the marks if anything they depend on changed.
+    ///
+    /// `revision` is the buffer revision; lines are placed by their visible
+    /// row in `folds`. Returns true if the marks were recollected.
+    pub fn update(
+        &mut self,
+        height: usize,
+        total_rows: usize,
+        revision: u64,
+        folds: &FoldMap,
+        git_diff_cache: Option<&GitDiffCache>,
+        search_state: Option<&SearchState>,
+    ) -> bool {
+        let key = MarksKey {
+            height,
+            total_rows,
+            revision,
+            diff: git_diff_cache.map(|cache| (cache.last_updated(), cache.hunks().len())),
+            search: search_state.map(|state| {
+                (
+                    state.query.clone(),
+                    state.case_sensitive,
+                    state.matches.len(),
+                )
+            }),
+        };
+        if self.key.as_ref() == Some(&key) {
+            return false;
+        }
+
+        self.buckets = vec![None; height];
+        if height > 0 {
+            let mut mark_line = |line: usize, mark: ScrollbarMark| {
+                let row = bar_row(folds.row_of(line), height, total_rows);
+                let bucket = &mut self.buckets[row];
+                *bucket = (*bucket).max(Some(mark));
+            };
+            if let Some(cache) = git_diff_cache {
+                for hunk in cache.hunks() {
+                    if hunk.new_count == 0 {
+                        mark_line(hunk.anchor_line(), ScrollbarMark::Deleted);
+                    }
+                    for line in hunk.new_start..hunk.new_start + hunk.new_count {
+                        match cache.get_line_status(line) {
+                            LineStatus::Modified => mark_line(line, ScrollbarMark::Modified),
+                            LineStatus::Added => mark_line(line, ScrollbarMark::Added),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            if let Some(state) = search_state {
+                for cursor in &state.matches {
+                    mark_line(cursor.line, ScrollbarMark::SearchMatch);
+                }
+            }
+        }
+        self.key = Some(key);
+        true
+    }
+
+    /// Mark of each scrollbar row.
+    pub fn buckets(&self) -> &[Option<ScrollbarMark>] {
+        &self.buckets
+    }
+}
+
+/// Document rows per scrollbar of `height` rows (short documents map
+/// one row to one row, so marks line up with the text).
+fn scale(height: usize, total_rows: usize) -> usize {
+    total_rows.max(height).max(1)
+}
+
+/// Scrollbar row showing document row `row`.
+pub fn bar_row(row: usize, height: usize, total_rows: usize) -> usize {
+    (row * height / scale(height, total_rows)).min(height.saturating_sub(1))
+}
+
+/// Document row in the middle of the rows shown at scrollbar row `bar_row`.
+pub fn document_row(bar_row: usize, height: usize, total_rows: usize) -> usize {
+    let row = (2 * bar_row + 1) * scale(height, total_rows) / (2 * height.max(1));
+    row.min(total_rows.saturating_sub(1))
+}
+
+/// Render the scrollbar into the one-column `area`.
+///
+/// `visible` is the range of document rows in view, drawn as the thumb.
+pub fn render_scrollbar(
+    buf: &mut Buffer,
+    area: Rect,
+    marks: &ScrollbarMarks,
+    visible: Range<usize>,
+    total_rows: usize,
+    theme: &Theme,
+) {
+    let height = area.height as usize;
+    let thumb_start = bar_row(visible.start, height, total_rows);
+    let thumb_end = (visible.end * height)
+        .div_ceil(scale(height, total_rows))
+        .clamp(thumb_start + 1, height);
+
+    for row in 0..height {
+        let in_thumb = (thumb_start..thumb_end).contains(&row);
+        let mark = marks.buckets().get(row).copied().flatten();
+        let (symbol, fg) = match mark {
+            Some(ScrollbarMark::SearchMatch) => ('━', theme.accented_fg),
+            Some(ScrollbarMark::Modified) => ('▐', theme.warning),
+            Some(ScrollbarMark::Added) => ('▐', theme.success),
+            Some(ScrollbarMark::Deleted) => ('▁', theme.error),
+            None if in_thumb => (' ', theme.fg),
+            None => ('│', theme.disabled),
+        };
+        let mut style = Style::default().fg(fg);
+        if in_thumb {
+            style = style.bg(theme.disabled);
+        }
+        if let Some(cell) = buf.cell_mut((area.x, area.y + row as u16)) {
+            cell.set_char(symbol);
+            cell.set_style(style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_buffer::Cursor;
+
+    #[test]
+    fn test_bar_rows_scale_with_document() {
+        // Long documents are scaled down to the scrollbar height
+        assert_eq!(bar_row(0, 10, 100), 0);
+        assert_eq!(bar_row(55, 10, 100), 5);
+        assert_eq!(bar_row(99, 10, 100), 9);
+        assert_eq!(document_row(5, 10, 100), 55);
+        // Short documents map one row to one row
+        assert_eq!(bar_row(3, 10, 4), 3);
+        assert_eq!(document_row(8, 10, 4), 3);
+    }
+
+    #[test]
+    fn test_marks_recollected_only_on_change() {
+        let folds = FoldMap::default();
+        let mut search = SearchState::new("x".to_string(), false);
+        search.matches = vec![Cursor::at(10, 0), Cursor::at(95, 2)];
+
+        let mut marks = ScrollbarMarks::default();
+        assert!(marks.update(10, 100, 1, &folds, None, Some(&search)));
+        assert_eq!(marks.buckets()[1], Some(ScrollbarMark::SearchMatch));
+        assert_eq!(marks.buckets()[9], Some(ScrollbarMark::SearchMatch));
+        assert_eq!(marks.buckets()[5], None);
+
+        // Same state keeps the buckets
+        assert!(!marks.update(10, 100, 1, &folds, None, Some(&search)));
+        // An edit or a closed search collects them again
+        assert!(marks.update(10, 100, 2, &folds, None, Some(&search)));
+        assert!(marks.update(10, 100, 2, &folds, None, None));
+        assert!(marks.buckets().iter().all(Option::is_none));
+    }
+}
@@ -0,0 +1,222 @@
+//! Visible whitespace.
+//!
+//! Spaces, tabs and unusual Unicode spaces are drawn with glyphs while
+//! rendering; the buffer keeps its text. Markers replace single graphemes,
+//! so they keep the style boundaries of highlighting segments and follow
+//! wrapped lines like any other text.
+
+use ratatui::style::Color;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use termide_buffer::LineSlice;
+use termide_config::{defaults, EditorSettings, RenderWhitespace};
+use termide_theme::Theme;
+
+/// Non-breaking, zero-width and other Unicode spaces that look like a
+/// space (or nothing) but are not one.
+const UNICODE_SPACES: &[char] = &[
+    '\u{00A0}', // no-break space
+    '\u{1680}', // ogham space mark
+    '\u{2000}', '\u{2001}', '\u{2002}', '\u{2003}', '\u{2004}', '\u{2005}', '\u{2006}',
+    '\u{2007}', // figure space
+    '\u{2008}', '\u{2009}', '\u{200A}', '\u{200B}', // zero-width space
+    '\u{202F}', // narrow no-break space
+    '\u{205F}', // medium mathematical space
+    '\u{2060}', // word joiner
+    '\u{3000}', // ideographic space
+    '\u{FEFF}', // zero-width no-break space (BOM)
+];
+
+/// Kind of whitespace grapheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Whitespace {
+    Space,
+    Tab,
+    Unicode,
+}
+
+impl Whitespace {
+    fn of(grapheme: &str) -> Option<Self> {
+        let mut chars = grapheme.chars();
+        let ch = chars.next()?;
+        if chars.next().is_some() {
+            return None;
+        }
+        match ch {
+            ' ' => Some(Self::Space),
+            '\t' => Some(Self::Tab),
+            _ if UNICODE_SPACES.contains(&ch) => Some(Self::Unicode),
+            _ => None,
+        }
+    }
+}
+
+/// Glyphs and colors of visible whitespace for one render.
+#[derive(Debug, Clone)]
+pub struct WhitespaceMarkers {
+    mode: RenderWhitespace,
+    space: String,
+    tab: String,
+    unicode: String,
+    space_color: Color,
+    trailing_color: Color,
+    unicode_color: Color,
+}
+
+impl WhitespaceMarkers {
+    /// Markers in `mode` with the glyphs of the settings (glyphs that are
+    /// not one column wide are replaced by the defaults).
+    pub fn new(mode: RenderWhitespace, settings: &EditorSettings, theme: &Theme) -> Self {
+        let glyph = |glyph: &str, default: &str| {
+            if glyph.graphemes(true).count() == 1 && glyph.width() == 1 {
+                glyph.to_string()
+            } else {
+                default.to_string()
+            }
+        };
+        Self {
+            mode,
+            space: glyph(
+                &settings.whitespace_space_glyph,
+                defaults::WHITESPACE_SPACE_GLYPH,
+            ),
+            tab: glyph(
+                &settings.whitespace_tab_glyph,
+                defaults::WHITESPACE_TAB_GLYPH,
+            ),
+            unicode: glyph(
+                &settings.whitespace_unicode_glyph,
+                defaults::WHITESPACE_UNICODE_GLYPH,
+            ),
+            space_color: theme.disabled,
+            trailing_color: theme.warning,
+            unicode_color: theme.error,
+        }
+    }
+
+    /// No visible whitespace.
+    pub fn off() -> Self {
+        Self {
+            mode: RenderWhitespace::Off,
+            space: String::new(),
+            tab: String::new(),
+            unicode: String::new(),
+            space_color: Color::Reset,
+            trailing_color: Color::Reset,
+            unicode_color: Color::Reset,
+        }
+    }
+
+    /// Grapheme index where the trailing spaces and tabs of a line start,
+    /// as far as `slice` of the line shows them.
+    pub fn trailing_start(&self, slice: &LineSlice) -> usize {
+        if self.mode == RenderWhitespace::Off || !slice.to_end {
+            return usize::MAX;
+        }
+        // Spaces and tabs are one grapheme each
+        let trailing = slice.text.len() - slice.text.trim_end_matches([' ', '\t']).len();
+        slice.len - trailing
+    }
+
+    /// Glyph and color drawn for the grapheme at `index` of a line whose
+    /// trailing whitespace starts at `trailing_start`.
+    pub fn marker(
+        &self,
+        grapheme: &str,
+        index: usize,
+        trailing_start: usize,
+    ) -> Option<(&str, Color)> {
+        if self.mode == RenderWhitespace::Off {
+            return None;
+        }
+        let trailing = index >= trailing_start;
+        let color = if trailing {
+            self.trailing_color
+        } else {
+            self.space_color
+        };
+        match Whitespace::of(grapheme)? {
+            Whitespace::Unicode => Some((&self.unicode, self.unicode_color)),
+            _ if !trailing && self.mode == RenderWhitespace::Trailing => None,
+            Whitespace::Space => Some((&self.space, color)),
+            Whitespace::Tab => Some((&self.tab, color)),
+        }
+    }
+
+    /// Columns taken by the grapheme at `index`: zero-width spaces take
+    /// one when marked.
+    pub fn width(&self, grapheme: &str, index: usize, trailing_start: usize) -> usize {
+        let width = grapheme.width();
+        if width == 0 && self.marker(grapheme, index, trailing_start).is_some() {
+            1
+        } else {
+            width
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_buffer::TextBuffer;
+
+    fn markers(mode: RenderWhitespace) -> WhitespaceMarkers {
+        WhitespaceMarkers::new(mode, &EditorSettings::default(), &Theme::default())
+    }
+
+    /// Glyphs drawn for a line (`.` where the text is shown)
+    fn shown(markers: &WhitespaceMarkers, line: &str) -> String {
+        let slice = TextBuffer::from_text(line).line_slice(0, 0..usize::MAX);
+        let trailing = markers.trailing_start(&slice.unwrap());
+        line.graphemes(true)
+            .enumerate()
+            .map(|(i, g)| {
+                markers
+                    .marker(g, i, trailing)
+                    .map_or(".", |(glyph, _)| glyph)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_whitespace_markers_by_mode() {
+        let line = "\tlet a\u{00A0}= 1;  ";
+        assert_eq!(
+            shown(&markers(RenderWhitespace::Off), line),
+            "............."
+        );
+        assert_eq!(
+            shown(&markers(RenderWhitespace::Trailing), line),
+            "......⍽....··"
+        );
+        assert_eq!(
+            shown(&markers(RenderWhitespace::All), line),
+            "→...·.⍽.·..··"
+        );
+
+        let all = markers(RenderWhitespace::All);
+        let theme = Theme::default();
+        assert_eq!(all.marker(" ", 4, 12), Some(("·", theme.disabled)));
+        assert_eq!(all.marker(" ", 12, 12), Some(("·", theme.warning)));
+    }
+
+    #[test]
+    fn test_zero_width_spaces_take_a_column() {
+        let trailing = markers(RenderWhitespace::Trailing);
+        assert_eq!(trailing.width("\u{200B}", 0, 5), 1);
+        assert_eq!(trailing.width("\u{3000}", 0, 5), 2);
+        assert_eq!(markers(RenderWhitespace::Off).width("\u{200B}", 0, 5), 0);
+    }
+
+    #[test]
+    fn test_wide_glyphs_fall_back_to_defaults() {
+        let settings = EditorSettings {
+            whitespace_space_glyph: "..".to_string(),
+            whitespace_tab_glyph: "»".to_string(),
+            ..EditorSettings::default()
+        };
+        let markers = WhitespaceMarkers::new(RenderWhitespace::All, &settings, &Theme::default());
+        assert_eq!(shown(&markers, " \t"), "·»");
+    }
+}
@@ -5,9 +5,10 @@
 
 use ratatui::{buffer::Buffer, layout::Rect, style::Style};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
-use termide_buffer::{calculate_wrap_point, Cursor, TextBuffer, Viewport};
+use termide_buffer::{
+    calculate_wrap_point, Cursor, LinePosition, LineSlice, TextBuffer, Viewport, WrapMode,
+};
 use termide_git::GitDiffCache;
 use termide_highlight::LineHighlighter;
 use termide_theme::Theme;
@@ -18,10 +19,12 @@ use crate::git;
 /// Render editor content in word wrap mode.
 ///
 /// This is the main rendering function that handles all aspects of word wrap mode:
-/// - Line breaking at word boundaries (smart wrap) or content width (simple wrap)
+/// - Line breaking at word boundaries or content width (as `wrap_mode` says)
 /// - Syntax highlighting with search/selection/cursor line styling
-/// - Git diff markers and line numbers
+/// - Git diff markers, fold and bookmark markers and line numbers
 /// - Cursor positioning tracking
+///
+/// Returns the screen cell of the cursor if it is visible.
 #[allow(clippy::too_many_arguments)] // Complex rendering requires many parameters
 pub fn render_content_word_wrap<H: LineHighlighter>(
     buf: &mut Buffer,
@@ -38,31 +41,52 @@ pub fn render_content_word_wrap<H: LineHighlighter>(
     content_width: usize,
     content_height: usize,
     line_number_width: u16,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
     text_style: Style,
     cursor_line_style: Style,
     line_number_style: Style,
     search_match_style: Style,
     current_match_style: Style,
     selection_style: Style,
-) {
+) -> Option<(u16, u16)> {
     let mut visual_row = 0;
     let mut line_idx = viewport.top_line;
 
     while visual_row < content_height && line_idx < buffer.line_count() {
         let is_cursor_line = line_idx == cursor.line;
+        let gutter_marker =
+            super::gutter_marker(&viewport.folds, &render_context.bookmarks, line_idx);
         let style = if is_cursor_line {
             cursor_line_style
         } else {
             text_style
         };
 
-        if let Some(line_text) = buffer.line(line_idx) {
+        // Long lines are cut into fixed chunks, read part by part
+        let long_line = buffer.is_long_line(line_idx);
+        let line_text = if long_line {
+            Some(String::new())
+        } else {
+            buffer.line(line_idx)
+        };
+        if let Some(line_text) = line_text {
             let line_text = line_text.trim_end_matches('\n');
             let graphemes: Vec<&str> = line_text.graphemes(true).collect();
-            let line_len = graphemes.len();
-
+            let line_len = if long_line {
+                buffer.line_len_graphemes(line_idx)
+            } else {
+                graphemes.len()
+            };
+
+            let part = super::part_to_highlight(
+                buffer,
+                line_idx,
+                syntax_highlighting_enabled,
+                highlight_cache,
+                render_context.max_highlight_columns,
+            );
             let mut grapheme_offset = 0;
+            let mut byte_offset = 0;
             let mut is_first_visual_row = true;
 
             // Special handling for empty lines
@@ -73,6 +97,7 @@ pub fn render_content_word_wrap<H: LineHighlighter>(
                     visual_row,
                     line_idx,
                     is_cursor_line,
+                    gutter_marker,
                     git_diff_cache,
                     show_git_diff,
                     theme,
@@ -86,11 +111,36 @@ pub fn render_content_word_wrap<H: LineHighlighter>(
             } else {
                 // Handle non-empty lines with wrapping
                 while grapheme_offset < line_len && visual_row < content_height {
-                    let chunk_end = if use_smart_wrap {
-                        calculate_wrap_point(&graphemes, grapheme_offset, content_width, line_len)
+                    let chunk_end = if long_line {
+                        (grapheme_offset + content_width).min(line_len)
+                    } else {
+                        calculate_wrap_point(
+                            &graphemes,
+                            grapheme_offset,
+                            content_width,
+                            line_len,
+                            wrap_mode,
+                        )
+                    };
+                    let slice = if long_line {
+                        buffer.line_slice(line_idx, grapheme_offset..chunk_end)
                     } else {
-                        // Simple wrap: calculate based on display width
-                        calculate_simple_wrap_point(&graphemes, grapheme_offset, content_width)
+                        let text = graphemes[grapheme_offset..chunk_end].concat();
+                        let start = LinePosition {
+                            grapheme: grapheme_offset,
+                            byte: byte_offset,
+                            column: 0,
+                        };
+                        byte_offset += text.len();
+                        Some(LineSlice {
+                            text,
+                            start,
+                            len: line_len,
+                            to_end: chunk_end == line_len,
+                        })
+                    };
+                    let Some(slice) = slice else {
+                        break;
                     };
 
                     render_visual_line(
@@ -98,15 +148,14 @@ pub fn render_content_word_wrap<H: LineHighlighter>(
                         area,
                         visual_row,
                         line_idx,
-                        line_text,
-                        grapheme_offset,
+                        &slice,
+                        part.as_ref(),
                         chunk_end,
-                        line_len,
                         is_first_visual_row,
                         is_cursor_line,
+                        gutter_marker,
                         git_diff_cache,
                         show_git_diff,
-                        syntax_highlighting_enabled,
                         highlight_cache,
                         render_context,
                         theme,
@@ -147,15 +196,15 @@ pub fn render_content_word_wrap<H: LineHighlighter>(
             }
         }
 
-        line_idx += 1;
+        line_idx = viewport.folds.next_visible(line_idx);
     }
 
     // Render cursor
-    if let Some((row, col)) = render_context.cursor_viewport_pos {
-        let cursor_x = area.x + line_number_width + col as u16;
-        let cursor_y = area.y + row as u16;
-        cursor_renderer::render_cursor_at(buf, cursor_x, cursor_y, area, theme);
-    }
+    let (row, col) = render_context.cursor_viewport_pos?;
+    let cursor_x = area.x + line_number_width + col as u16;
+    let cursor_y = area.y + row as u16;
+    cursor_renderer::render_cursor_at(buf, cursor_x, cursor_y, area, theme);
+    Some((cursor_x, cursor_y))
 }
 
 /// Render an empty line in word wrap mode.
@@ -166,6 +215,7 @@ fn render_empty_line(
     visual_row: usize,
     line_idx: usize,
     is_cursor_line: bool,
+    gutter_marker: char,
     git_diff_cache: &Option<GitDiffCache>,
     show_git_diff: bool,
     theme: &Theme,
@@ -178,7 +228,10 @@ fn render_empty_line(
     let git_info = git::get_git_line_info(line_idx, git_diff_cache, show_git_diff, theme);
 
     // Render line number
-    let line_num_style = Style::default().fg(git_info.status_color);
+    // Lines with diagnostics show their number in the color of the most severe one
+    let line_num_style = Style::default().fg(render_context
+        .diagnostic_line_color(line_idx)
+        .unwrap_or(git_info.status_color));
     let line_num_part = format!("{:>4}{}", line_idx + 1, git_info.status_marker);
 
     for (i, ch) in line_num_part.chars().enumerate() {
@@ -190,12 +243,16 @@ fn render_empty_line(
         }
     }
 
-    // Space after marker
+    // Fold or bookmark marker after git marker
     let x = area.x + 5;
     let y = area.y + visual_row as u16;
     if let Some(cell) = buf.cell_mut((x, y)) {
-        cell.set_char(' ');
-        cell.set_style(line_num_style);
+        cell.set_char(gutter_marker);
+        cell.set_style(super::gutter_marker_style(
+            gutter_marker,
+            line_num_style,
+            theme,
+        ));
     }
 
     // Fill line with background
@@ -218,21 +275,23 @@ fn render_empty_line(
 }
 
 /// Render a single visual line (wrapped segment) in word wrap mode.
+///
+/// `slice` holds the graphemes of the line up to `chunk_end`, `part` is
+/// the highlighted part of the line.
 #[allow(clippy::too_many_arguments)]
 fn render_visual_line<H: LineHighlighter>(
     buf: &mut Buffer,
     area: Rect,
     visual_row: usize,
     line_idx: usize,
-    line_text: &str,
-    char_offset: usize,
+    slice: &LineSlice,
+    part: Option<&LineSlice>,
     chunk_end: usize,
-    line_len: usize,
     is_first_visual_row: bool,
     is_cursor_line: bool,
+    gutter_marker: char,
     git_diff_cache: &Option<GitDiffCache>,
     show_git_diff: bool,
-    syntax_highlighting_enabled: bool,
     highlight_cache: &mut H,
     render_context: &mut RenderContext,
     theme: &Theme,
@@ -249,7 +308,10 @@ fn render_visual_line<H: LineHighlighter>(
     // Render line number gutter
     if is_first_visual_row {
         let git_info = git::get_git_line_info(line_idx, git_diff_cache, show_git_diff, theme);
-        let line_num_style = Style::default().fg(git_info.status_color);
+        // Lines with diagnostics show their number in the color of the most severe one
+        let line_num_style = Style::default().fg(render_context
+            .diagnostic_line_color(line_idx)
+            .unwrap_or(git_info.status_color));
         let line_num_part = format!("{:>4}{}", line_idx + 1, git_info.status_marker);
 
         for (i, ch) in line_num_part.chars().enumerate() {
@@ -264,8 +326,12 @@ fn render_visual_line<H: LineHighlighter>(
         let x = area.x + 5;
         let y = area.y + visual_row as u16;
         if let Some(cell) = buf.cell_mut((x, y)) {
-            cell.set_char(' ');
-            cell.set_style(line_num_style);
+            cell.set_char(gutter_marker);
+            cell.set_style(super::gutter_marker_style(
+                gutter_marker,
+                line_num_style,
+                theme,
+            ));
         }
     } else {
         // Empty gutter for continuation lines
@@ -279,23 +345,23 @@ fn render_visual_line<H: LineHighlighter>(
         }
     }
 
-    // Get syntax highlighting segments
-    let segments = if syntax_highlighting_enabled && highlight_cache.has_syntax() {
-        highlight_cache.get_line_segments(line_idx, line_text)
-    } else {
-        &[(line_text.to_string(), style)][..]
-    };
+    // Highlighting segments cut to the chunk
+    let spans = super::slice_spans(line_idx, slice, part, highlight_cache, style);
 
     // Render graphemes for this visual line
     // Using graphemes instead of chars to properly handle combining characters (Hindi, etc.)
-    let mut grapheme_idx = 0;
+    let char_offset = slice.start.grapheme;
+    let line_len = slice.len;
+    let whitespace = render_context.whitespace.clone();
+    let trailing_start = whitespace.trailing_start(slice);
+    let mut grapheme_idx = char_offset;
     let mut visual_col = 0;
 
-    for (segment_text, segment_style) in segments {
+    for (segment_text, segment_style) in spans {
         for grapheme in segment_text.graphemes(true) {
-            if grapheme_idx >= char_offset && grapheme_idx < chunk_end {
-                // Get display width of grapheme cluster
-                let grapheme_width = grapheme.width();
+            if grapheme_idx < chunk_end {
+                // Get display width of grapheme cluster (marked zero-width spaces take a column)
+                let grapheme_width = whitespace.width(grapheme, grapheme_idx, trailing_start);
 
                 // Skip zero-width graphemes (shouldn't happen with proper grapheme iteration)
                 if grapheme_width == 0 {
@@ -305,25 +371,33 @@ fn render_visual_line<H: LineHighlighter>(
 
                 let x = area.x + line_number_width + visual_col as u16;
                 let y = area.y + visual_row as u16;
+                // Marked zero-width spaces are not counted by the wrap points
+                let right =
+                    (area.x + line_number_width + content_width as u16).min(area.x + area.width);
 
-                if x < area.x + area.width && y < area.y + area.height {
-                    if let Some(cell) = buf.cell_mut((x, y)) {
-                        // Use set_symbol for proper grapheme cluster handling
-                        cell.set_symbol(grapheme);
-
-                        let final_style = highlight_renderer::determine_cell_style(
-                            line_idx,
-                            grapheme_idx,
-                            *segment_style,
-                            is_cursor_line,
-                            render_context,
-                            search_match_style,
-                            current_match_style,
-                            selection_style,
-                            theme.accented_bg,
-                        );
-                        cell.set_style(final_style);
-                    }
+                if x < right && y < area.y + area.height {
+                    let final_style = highlight_renderer::determine_cell_style(
+                        line_idx,
+                        grapheme_idx,
+                        segment_style,
+                        is_cursor_line,
+                        render_context,
+                        search_match_style,
+                        current_match_style,
+                        selection_style,
+                        theme.accented_bg,
+                    );
+                    let marker = whitespace.marker(grapheme, grapheme_idx, trailing_start);
+                    highlight_renderer::draw_grapheme(
+                        buf,
+                        x,
+                        y,
+                        grapheme,
+                        grapheme_width,
+                        final_style,
+                        marker,
+                        right,
+                    );
                 }
 
                 // Track cursor position
@@ -361,20 +435,3 @@ fn render_visual_line<H: LineHighlighter>(
         }
     }
 }
-
-/// Calculate simple wrap point based on display width (no word boundary detection)
-fn calculate_simple_wrap_point(graphemes: &[&str], start: usize, max_width: usize) -> usize {
-    let mut display_width = 0;
-
-    for (i, grapheme) in graphemes.iter().enumerate().skip(start) {
-        let grapheme_width = grapheme.width();
-
-        if display_width + grapheme_width > max_width {
-            return i;
-        }
-
-        display_width += grapheme_width;
-    }
-
-    graphemes.len()
-}
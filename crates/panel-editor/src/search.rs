@@ -5,11 +5,15 @@
 
 use anyhow::Result;
 
+use unicode_segmentation::UnicodeSegmentation;
+
 use termide_buffer::{Cursor, SearchState, Selection, TextBuffer};
 
 /// Perform search through the entire buffer.
 ///
-/// Populates the search state with all matching positions.
+/// Populates the search state with all matching positions, skipping
+/// matches that are not whole words (with `whole_word`) or that are not
+/// fully inside the selection searched in.
 pub fn perform_search(buffer: &TextBuffer, search: &mut SearchState) {
     search.matches.clear();
 
@@ -22,9 +26,15 @@ pub fn perform_search(buffer: &TextBuffer, search: &mut SearchState) {
     } else {
         search.query.to_lowercase()
     };
+    let query_len = search.query_len();
+
+    let lines = match &search.in_selection {
+        Some(selection) => selection.start().line..selection.end().line + 1,
+        None => 0..buffer.line_count(),
+    };
 
     // Search through all lines
-    for line_idx in 0..buffer.line_count() {
+    for line_idx in lines {
         if let Some(line_text) = buffer.line(line_idx) {
             let search_text = if search.case_sensitive {
                 line_text.to_string()
@@ -32,15 +42,32 @@ pub fn perform_search(buffer: &TextBuffer, search: &mut SearchState) {
                 line_text.to_lowercase()
             };
 
-            // Find all occurrences in line
+            // Find all occurrences in line, counting graphemes up to each
             let mut col = 0;
+            let mut counted = (0, 0);
             while let Some(pos) = search_text[col..].find(&query) {
-                let match_col = col + pos;
-                search.matches.push(Cursor {
-                    line: line_idx,
-                    column: match_col,
-                });
-                col = match_col + 1;
+                let match_start = col + pos;
+                let match_end = match_start + query.len();
+                col = match_start
+                    + search_text[match_start..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8);
+
+                if search.whole_word
+                    && !SearchState::is_whole_word(&search_text, match_start..match_end)
+                {
+                    continue;
+                }
+                counted = (
+                    match_start,
+                    counted.1 + search_text[counted.0..match_start].graphemes(true).count(),
+                );
+                let start = Cursor::at(line_idx, counted.1);
+                let end = Cursor::at(line_idx, counted.1 + query_len);
+                if search.in_scope(&start, &end) {
+                    search.matches.push(start);
+                }
             }
         }
     }
@@ -82,7 +109,7 @@ pub fn replace_at_position(
 
     let new_cursor = Cursor {
         line: match_cursor.line,
-        column: match_cursor.column + replace_with.len(),
+        column: match_cursor.column + replace_with.graphemes(true).count(),
     };
 
     Ok(ReplaceResult {
@@ -140,3 +167,83 @@ pub fn replace_all_matches(
 
     Ok(count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(
+        text: &str,
+        query: &str,
+        whole_word: bool,
+        selection: Option<Selection>,
+    ) -> Vec<Cursor> {
+        let buffer = TextBuffer::from_text(text);
+        let mut state =
+            SearchState::new(query.to_string(), false).with_options(whole_word, selection);
+        perform_search(&buffer, &mut state);
+        state.matches
+    }
+
+    #[test]
+    fn test_search_whole_word() {
+        let text = "Word words\nsword word_list (word)";
+        assert_eq!(
+            search(text, "word", false, None),
+            vec![
+                Cursor::at(0, 0),
+                Cursor::at(0, 5),
+                Cursor::at(1, 1),
+                Cursor::at(1, 6),
+                Cursor::at(1, 17),
+            ]
+        );
+        assert_eq!(
+            search(text, "word", true, None),
+            vec![Cursor::at(0, 0), Cursor::at(1, 17)]
+        );
+    }
+
+    #[test]
+    fn test_search_columns_count_graphemes() {
+        assert_eq!(search("ééab ab", "ab", true, None), vec![Cursor::at(0, 5)]);
+    }
+
+    #[test]
+    fn test_search_in_selection() {
+        let text = "ab ab ab\nab ab ab\nab ab ab";
+        // From the second match of the first line to the end of the second
+        // match of the last line
+        let selection = Selection::new(Cursor::at(2, 5), Cursor::at(0, 3));
+        assert_eq!(
+            search(text, "ab", false, Some(selection)),
+            vec![
+                Cursor::at(0, 3),
+                Cursor::at(0, 6),
+                Cursor::at(1, 0),
+                Cursor::at(1, 3),
+                Cursor::at(1, 6),
+                Cursor::at(2, 0),
+                Cursor::at(2, 3),
+            ]
+        );
+
+        // Matches crossing the edges are left out
+        let selection = Selection::new(Cursor::at(0, 4), Cursor::at(0, 7));
+        assert!(search(text, "ab", false, Some(selection)).is_empty());
+    }
+
+    #[test]
+    fn test_replace_all_in_selection() {
+        let mut buffer = TextBuffer::from_text("ab ab ab\nab ab");
+        let selection = Selection::new(Cursor::at(0, 3), Cursor::at(1, 2));
+        let mut state = SearchState::new_with_replace("ab".to_string(), "xyz".to_string(), false)
+            .with_options(true, Some(selection));
+        perform_search(&buffer, &mut state);
+
+        let count =
+            replace_all_matches(&mut buffer, &state.matches, state.query_len(), "xyz").unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(buffer.text(), "ab xyz xyz\nxyz ab");
+    }
+}
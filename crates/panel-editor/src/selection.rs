@@ -5,8 +5,8 @@
 
 use anyhow::Result;
 
-use termide_buffer::{is_word_boundary, Cursor, Selection, TextBuffer};
-use termide_highlight::HighlightCache;
+use termide_buffer::{is_word_char, Cursor, Selection, TextBuffer};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Select all text in the buffer.
 ///
@@ -94,6 +94,32 @@ pub fn get_selected_text(buffer: &TextBuffer, selection: Option<&Selection>) ->
     }
 }
 
+/// Get the text of the rectangle between the selection's corners, one
+/// line per row.
+///
+/// Returns None if the rectangle has no columns.
+pub fn get_block_text(buffer: &TextBuffer, selection: Option<&Selection>) -> Option<String> {
+    let selection = selection?;
+    let (anchor, active) = (selection.anchor, selection.active);
+    let left = anchor.column.min(active.column);
+    let right = anchor.column.max(active.column);
+    if left == right {
+        return None;
+    }
+
+    let rows: Vec<String> = (anchor.line.min(active.line)..=anchor.line.max(active.line))
+        .map(|line_idx| {
+            let line = buffer.line(line_idx).unwrap_or_default();
+            line.trim_end_matches('\n')
+                .graphemes(true)
+                .skip(left)
+                .take(right - left)
+                .collect()
+        })
+        .collect();
+    Some(rows.join("\n"))
+}
+
 /// Delete selected text from buffer.
 ///
 /// Returns (new_cursor_position, should_invalidate_cache) on success.
@@ -113,21 +139,10 @@ pub fn delete_selection(
     Ok(None)
 }
 
-/// Invalidate highlight cache after selection deletion.
-///
-/// This is a helper to ensure cache invalidation happens consistently.
-pub fn invalidate_cache_after_deletion(
-    highlight_cache: &mut HighlightCache,
-    deletion_start_line: usize,
-    buffer_line_count: usize,
-) {
-    highlight_cache.invalidate_range(deletion_start_line, buffer_line_count);
-}
-
 /// Select word at cursor position.
 ///
 /// Finds word boundaries around the cursor and returns selection covering the word.
-/// Word boundaries are non-alphanumeric characters (spaces, punctuation, etc.).
+/// Words are letters, digits and underscores (see `is_word_char`).
 /// Returns (new_selection, new_cursor_position) where cursor is at the end of the word.
 pub fn select_word(buffer: &TextBuffer, cursor: &Cursor) -> Option<(Selection, Cursor)> {
     let line_text = buffer.line(cursor.line)?;
@@ -142,19 +157,19 @@ pub fn select_word(buffer: &TextBuffer, cursor: &Cursor) -> Option<(Selection, C
     let col = cursor.column.min(char_count.saturating_sub(1));
 
     // If cursor is at a word boundary character, don't select anything
-    if col < char_count && is_word_boundary(chars[col]) {
+    if col < char_count && !is_word_char(chars[col]) {
         return None;
     }
 
     // Find word start (go backwards until word boundary)
     let mut start_col = col;
-    while start_col > 0 && !is_word_boundary(chars[start_col - 1]) {
+    while start_col > 0 && is_word_char(chars[start_col - 1]) {
         start_col -= 1;
     }
 
     // Find word end (go forwards until word boundary or end of line)
     let mut end_col = col;
-    while end_col < char_count && !is_word_boundary(chars[end_col]) {
+    while end_col < char_count && is_word_char(chars[end_col]) {
         end_col += 1;
     }
 
@@ -0,0 +1,75 @@
+//! Bookmarks shown in an editor.
+//!
+//! The application owns the bookmarks; an editor keeps an anchor for each
+//! bookmark of its file, so the marks follow edits until the application
+//! reads their lines back.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use termide_buffer::{Affinity, Anchor, Cursor, TextBuffer};
+use termide_session::Bookmark;
+
+/// Revision of the bookmarks, generation of the buffer and file the marks
+/// were placed for
+pub(crate) type Placement = (u64, u64, PathBuf);
+
+/// Marks of the bookmarks in the editor's file
+#[derive(Debug, Default)]
+pub(crate) struct BookmarkMarks {
+    /// Bookmark name and the start of its line
+    marks: Vec<(String, Anchor)>,
+    placement: Option<Placement>,
+}
+
+impl BookmarkMarks {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the marks were placed for `placement`
+    pub(crate) fn is_placed_for(&self, placement: &Placement) -> bool {
+        self.placement.as_ref() == Some(placement)
+    }
+
+    /// Place marks for `bookmarks`, each moved to its text in `buffer`
+    /// first
+    pub(crate) fn set<'a>(
+        &mut self,
+        buffer: &mut TextBuffer,
+        bookmarks: impl Iterator<Item = &'a Bookmark>,
+        placement: Placement,
+    ) {
+        let mut marks = Vec::new();
+        for bookmark in bookmarks {
+            let mut bookmark = bookmark.clone();
+            let line_count = buffer.line_count();
+            bookmark.reanchor(line_count, |line| buffer.line(line));
+            // The start of the line keeps to the line when text is typed there
+            if let Ok(anchor) = buffer.anchor_at(&Cursor::at(bookmark.line, 0), Affinity::Right) {
+                marks.push((bookmark.name, anchor));
+            }
+        }
+        self.marks = marks;
+        self.placement = Some(placement);
+    }
+
+    /// Name, line and line text of each mark
+    pub(crate) fn positions(&self, buffer: &TextBuffer) -> Vec<(String, usize, String)> {
+        self.marks
+            .iter()
+            .map(|(name, anchor)| {
+                let line = buffer.anchor_cursor(anchor).line;
+                (name.clone(), line, buffer.line(line).unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// Lines holding a bookmark
+    pub(crate) fn lines(&self, buffer: &TextBuffer) -> BTreeSet<usize> {
+        self.marks
+            .iter()
+            .map(|(_, anchor)| buffer.anchor_cursor(anchor).line)
+            .collect()
+    }
+}
@@ -1,66 +1,79 @@
 //! File-related state for the editor.
 
-use std::path::Path;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
 
 use crate::file_io;
 
+/// Features disabled when the file was opened in large file mode.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SuppressedFeatures {
+    /// Syntax highlighting setting before large file mode.
+    pub syntax_highlighting: bool,
+    /// Word wrap setting before large file mode.
+    pub word_wrap: bool,
+}
+
 /// State related to the file being edited.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub(crate) struct FileState {
-    /// File modification time at load/save (for detecting external changes).
-    pub mtime: Option<SystemTime>,
-    /// Flag: file was modified externally.
-    pub external_change_detected: bool,
+    /// Path of the file (kept in sync with the buffer's path).
+    pub path: Option<PathBuf>,
     /// File size in bytes (for determining whether to use smart features).
     pub size: u64,
     /// Cached title (filename).
     pub title: String,
     /// Temporary file name for unsaved buffer (for session restoration).
     pub unsaved_buffer_file: Option<String>,
+    /// Features suppressed because the file exceeds large file thresholds.
+    pub large_file: Option<SuppressedFeatures>,
+    /// File is not writable by the current user (modifying keys are rejected).
+    pub read_only: bool,
+    /// User chose to edit the read-only file anyway.
+    pub edit_anyway: bool,
+    /// Language picked by the user instead of the detected one
+    /// (`PLAIN_TEXT_SYNTAX` for no highlighting).
+    pub syntax_override: Option<String>,
 }
 
 impl FileState {
     /// Create new FileState with default values.
     pub fn new() -> Self {
         Self {
-            mtime: None,
-            external_change_detected: false,
+            path: None,
             size: 0,
             title: "Untitled".to_string(),
             unsaved_buffer_file: None,
+            large_file: None,
+            read_only: false,
+            edit_anyway: false,
+            syntax_override: None,
         }
     }
 
     /// Create FileState from file metadata.
-    pub fn from_path(path: &Path, mtime: Option<SystemTime>, size: u64) -> Self {
+    pub fn from_path(path: &Path, size: u64) -> Self {
         Self {
-            mtime,
-            external_change_detected: false,
+            path: Some(path.to_path_buf()),
             size,
             title: file_io::path_to_title(path),
             unsaved_buffer_file: None,
+            large_file: None,
+            read_only: false,
+            edit_anyway: false,
+            syntax_override: None,
         }
     }
 
-    /// Check if file was modified externally.
-    pub fn check_external_modification(&mut self, path: &Path) {
-        if file_io::was_modified_externally(path, self.mtime) {
-            self.external_change_detected = true;
+    /// Re-check write permission (keeps "edit anyway" while the file stays read-only).
+    pub fn update_write_permission(&mut self, path: &Path) {
+        if file_io::is_file_readonly(path) {
+            self.read_only = !self.edit_anyway;
+        } else {
+            self.read_only = false;
+            self.edit_anyway = false;
         }
     }
 
-    /// Update mtime after save.
-    pub fn update_mtime(&mut self, path: &Path) {
-        self.mtime = file_io::get_file_mtime(path);
-        self.external_change_detected = false;
-    }
-
-    /// Clear external change flag.
-    pub fn clear_external_change(&mut self) {
-        self.external_change_detected = false;
-    }
-
     /// Update title from path.
     pub fn update_title(&mut self, path: &Path) {
         self.title = file_io::path_to_title(path);
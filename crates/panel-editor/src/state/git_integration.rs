@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use std::sync::mpsc::Receiver;
 use std::time::Instant;
 
-use termide_git::{GitDiffAsyncResult, GitDiffCache};
+use termide_git::{BufferDiff, GitDiffAsyncResult, GitDiffCache};
 
 /// Git-related state for the editor.
 pub(crate) struct GitIntegration {
@@ -14,6 +14,8 @@ pub(crate) struct GitIntegration {
     pub update_pending: Option<Instant>,
     /// Receiver for async git diff result (non-blocking load from HEAD).
     pub diff_receiver: Option<Receiver<GitDiffAsyncResult>>,
+    /// Receiver for the buffer compared with HEAD in the background.
+    pub buffer_diff_receiver: Option<Receiver<BufferDiff>>,
     /// Cached git repository root for this file (to avoid repeated filesystem lookups).
     /// None = not cached, Some(None) = no repo, Some(Some(path)) = repo found.
     pub cached_repo_root: Option<Option<PathBuf>>,
@@ -32,6 +34,7 @@ impl GitIntegration {
             diff_cache: None,
             update_pending: None,
             diff_receiver: None,
+            buffer_diff_receiver: None,
             cached_repo_root: None,
         }
     }
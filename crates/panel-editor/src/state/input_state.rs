@@ -1,5 +1,7 @@
 //! Input-related state for the editor.
 
+use termide_buffer::Cursor;
+
 use crate::click_tracker::ClickTracker;
 
 /// Input-related state for the editor.
@@ -9,6 +11,10 @@ pub(crate) struct InputState {
     pub click_tracker: ClickTracker,
     /// Preferred column for vertical navigation (maintains column across lines).
     pub preferred_column: Option<usize>,
+    /// Cursor position and step of the last recenter (center, top, bottom)
+    pub recenter: Option<(Cursor, usize)>,
+    /// Left button was pressed on the scrollbar and is still held
+    pub scrollbar_drag: bool,
 }
 
 impl InputState {
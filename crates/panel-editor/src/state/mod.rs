@@ -6,14 +6,18 @@
 // Allow unused methods - they are helpers for future refactoring phases
 #![allow(dead_code)]
 
+mod bookmark_marks;
 mod file_state;
 mod git_integration;
 mod input_state;
 mod rendering_cache;
 mod search_controller;
+mod shared_buffer;
 
-pub(crate) use file_state::FileState;
+pub(crate) use bookmark_marks::BookmarkMarks;
+pub(crate) use file_state::{FileState, SuppressedFeatures};
 pub(crate) use git_integration::GitIntegration;
 pub(crate) use input_state::InputState;
 pub(crate) use rendering_cache::RenderingCache;
-pub(crate) use search_controller::SearchController;
+pub(crate) use search_controller::{SearchController, SearchOrigin};
+pub(crate) use shared_buffer::{BufferWatch, SharedBuffer, ViewEdit};
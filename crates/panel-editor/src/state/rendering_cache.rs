@@ -1,11 +1,15 @@
 //! Rendering cache state for the editor.
 
 use std::collections::HashMap;
+use std::time::Instant;
 
+use termide_buffer::WrapMode;
 use termide_config::Config;
 use termide_highlight::{global_highlighter, HighlightCache};
 use termide_theme::Theme;
 
+use crate::rendering::scrollbar::ScrollbarMarks;
+
 /// Cached rendering state for the editor.
 pub(crate) struct RenderingCache {
     /// Syntax highlighting cache.
@@ -14,8 +18,8 @@ pub(crate) struct RenderingCache {
     pub virtual_line_count: usize,
     /// Cached content width from last render.
     pub content_width: usize,
-    /// Cached smart wrap setting from last render.
-    pub use_smart_wrap: bool,
+    /// Cached wrap mode from last render.
+    pub wrap_mode: WrapMode,
     /// Cache of wrap points for each line.
     #[allow(dead_code)]
     pub wrap_points: HashMap<usize, Vec<usize>>,
@@ -23,6 +27,15 @@ pub(crate) struct RenderingCache {
     pub theme: Theme,
     /// Cached config for rendering.
     pub config: Config,
+    /// Buffer revision of the last fold regions update.
+    pub fold_regions_revision: Option<u64>,
+    /// Time of the last fold regions update.
+    pub fold_regions_updated: Option<Instant>,
+    /// Scrollbar marks bucketed by row.
+    pub scrollbar: ScrollbarMarks,
+    /// Time of the last edit the highlighting's parse of the whole
+    /// document has not caught up with.
+    pub document_edited: Option<Instant>,
 }
 
 impl Default for RenderingCache {
@@ -38,10 +51,14 @@ impl RenderingCache {
             highlight: HighlightCache::new(global_highlighter(), false),
             virtual_line_count: 0,
             content_width: 0,
-            use_smart_wrap: false,
+            wrap_mode: WrapMode::Hard,
             wrap_points: HashMap::new(),
             theme: Theme::default(),
             config: Config::default(),
+            fold_regions_revision: None,
+            fold_regions_updated: None,
+            scrollbar: ScrollbarMarks::default(),
+            document_edited: None,
         }
     }
 
@@ -51,10 +68,14 @@ impl RenderingCache {
             highlight: HighlightCache::new(global_highlighter(), true),
             virtual_line_count: 0,
             content_width: 0,
-            use_smart_wrap: false,
+            wrap_mode: WrapMode::Hard,
             wrap_points: HashMap::new(),
             theme: Theme::default(),
             config: Config::default(),
+            fold_regions_revision: None,
+            fold_regions_updated: None,
+            scrollbar: ScrollbarMarks::default(),
+            document_edited: None,
         }
     }
 
@@ -62,6 +83,7 @@ impl RenderingCache {
     pub fn prepare(&mut self, theme: &Theme, config: &Config) {
         self.theme = *theme;
         self.config = config.clone();
+        self.highlight.set_light_theme(theme.is_light());
     }
 
     /// Invalidate wrap cache (e.g., when content changes).
@@ -1,6 +1,18 @@
 //! Search state management for the editor.
 
-use termide_buffer::SearchState;
+use std::time::Instant;
+
+use termide_buffer::{Cursor, SearchState, Selection};
+
+/// Cursor, selection and scroll position before a search, restored when
+/// it is cancelled.
+#[derive(Debug, Clone)]
+pub(crate) struct SearchOrigin {
+    pub cursor: Cursor,
+    pub selection: Option<Selection>,
+    pub top_line: usize,
+    pub left_column: usize,
+}
 
 /// Search-related state for the editor.
 #[derive(Default)]
@@ -13,6 +25,17 @@ pub(crate) struct SearchController {
     pub last_replace_find: Option<String>,
     /// Last replace with text (preserved when replace is closed).
     pub last_replace_with: Option<String>,
+    /// Match whole words only.
+    pub whole_word: bool,
+    /// Restrict matches to `selection`.
+    pub in_selection: bool,
+    /// Selection when the search or replace modal was opened.
+    pub selection: Option<Selection>,
+    /// Position when the search modal was opened.
+    pub origin: Option<SearchOrigin>,
+    /// Query typed into the search modal of a large buffer and when, searched
+    /// once typing pauses.
+    pub pending_query: Option<(String, Instant)>,
 }
 
 impl SearchController {
@@ -31,6 +54,11 @@ impl SearchController {
         self.state.as_ref().map(|s| s.query.as_str())
     }
 
+    /// Selection searched in, if searching in selection.
+    pub fn scope(&self) -> Option<Selection> {
+        self.selection.clone().filter(|_| self.in_selection)
+    }
+
     /// Save current search query as last query.
     pub fn save_last_query(&mut self) {
         if let Some(ref state) = self.state {
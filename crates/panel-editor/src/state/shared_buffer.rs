@@ -0,0 +1,404 @@
+//! Text buffer shared by the editor views of a file.
+//!
+//! A split view shows the buffer of an editor in a second panel. Each view
+//! holds a `SharedBuffer` handle to the same text, undo history and disk
+//! state (modification time, external change flag), while cursor,
+//! selection, viewport and caches stay per view. Line edits made through
+//! one handle are queued for every view, marked as foreign for the others,
+//! so they can move their cursor along and refresh their caches.
+//!
+//! The shared state also holds the word index used for completion, built
+//! on first use and kept up to date with the line edits.
+//!
+//! A [`BufferWatch`] follows the text and the top line of one view without
+//! keeping the buffer alive (markdown preview).
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+use std::rc::{Rc, Weak};
+use std::time::SystemTime;
+
+use termide_buffer::{LineEdit, TextBuffer};
+
+use crate::completion::WordIndex;
+use crate::recovery::RecoveryState;
+
+thread_local! {
+    /// Buffers of all editors, for completion from other open files
+    static BUFFERS: RefCell<Vec<Weak<RefCell<Shared>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Line edit received by a view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ViewEdit {
+    pub edit: LineEdit,
+    /// Made through another view
+    pub foreign: bool,
+}
+
+/// State shared by all views of a buffer
+struct Shared {
+    buffer: TextBuffer,
+    /// Edits not yet taken, per view id
+    views: Vec<(usize, Vec<ViewEdit>)>,
+    next_view: usize,
+    /// Incremented when the text is replaced (reload from disk)
+    generation: u64,
+    /// File modification time at load/save (for detecting external changes)
+    mtime: Option<SystemTime>,
+    /// The file was modified on disk since it was loaded or saved
+    external_change: bool,
+    /// Words of the text, built when completion first needs them
+    words: Option<WordIndex>,
+    /// Recovery copy of unsaved changes
+    recovery: RecoveryState,
+    /// First line each view showed when last drawn, per view id
+    top_lines: Vec<(usize, usize)>,
+}
+
+impl Shared {
+    /// Word index of the text, built or rebuilt if needed
+    fn words(&mut self) -> &WordIndex {
+        let buffer = &self.buffer;
+        let words = self
+            .words
+            .take()
+            .filter(|words| words.is_current(buffer))
+            .unwrap_or_else(|| WordIndex::build(buffer));
+        self.words.insert(words)
+    }
+}
+
+/// Handle of one view to a shared text buffer
+pub(crate) struct SharedBuffer {
+    shared: Rc<RefCell<Shared>>,
+    view: usize,
+}
+
+impl SharedBuffer {
+    /// Share `buffer`, starting with a single view
+    pub fn new(mut buffer: TextBuffer) -> Self {
+        buffer.set_line_edit_tracking(true);
+        let shared = Rc::new(RefCell::new(Shared {
+            buffer,
+            views: vec![(0, Vec::new())],
+            next_view: 1,
+            generation: 0,
+            mtime: None,
+            external_change: false,
+            words: None,
+            recovery: RecoveryState::default(),
+            top_lines: Vec::new(),
+        }));
+        BUFFERS.with_borrow_mut(|buffers| {
+            buffers.retain(|buffer| buffer.strong_count() > 0);
+            buffers.push(Rc::downgrade(&shared));
+        });
+        Self { shared, view: 0 }
+    }
+
+    /// Handle for another view of the same buffer
+    pub fn split(&self) -> Self {
+        let mut shared = self.shared.borrow_mut();
+        let view = shared.next_view;
+        shared.next_view += 1;
+        shared.views.push((view, Vec::new()));
+        Self {
+            shared: Rc::clone(&self.shared),
+            view,
+        }
+    }
+
+    /// Number of views sharing the buffer
+    pub fn view_count(&self) -> usize {
+        Rc::strong_count(&self.shared)
+    }
+
+    /// Whether `other` is a view of the same buffer
+    pub fn same_buffer(&self, other: &SharedBuffer) -> bool {
+        Rc::ptr_eq(&self.shared, &other.shared)
+    }
+
+    /// Read the text
+    pub fn borrow(&self) -> Ref<'_, TextBuffer> {
+        Ref::map(self.shared.borrow(), |shared| &shared.buffer)
+    }
+
+    /// Edit the text; line edits are passed to the views when done
+    pub fn borrow_mut(&self) -> BufferMut<'_> {
+        BufferMut {
+            shared: self.shared.borrow_mut(),
+            view: self.view,
+        }
+    }
+
+    /// Replace the text of all views (pending line edits are dropped)
+    pub fn replace(&self, mut buffer: TextBuffer) {
+        buffer.set_line_edit_tracking(true);
+        let mut shared = self.shared.borrow_mut();
+        shared.buffer = buffer;
+        shared.generation += 1;
+        shared.words = None;
+        for (_, edits) in &mut shared.views {
+            edits.clear();
+        }
+    }
+
+    /// Number of times the text was replaced
+    pub fn generation(&self) -> u64 {
+        self.shared.borrow().generation
+    }
+
+    /// Take the line edits recorded for this view
+    pub fn take_edits(&self) -> Vec<ViewEdit> {
+        let mut shared = self.shared.borrow_mut();
+        shared
+            .views
+            .iter_mut()
+            .find(|(view, _)| *view == self.view)
+            .map(|(_, edits)| std::mem::take(edits))
+            .unwrap_or_default()
+    }
+
+    /// Run `f` with the word index of the text
+    pub fn with_words<R>(&self, f: impl FnOnce(&WordIndex) -> R) -> R {
+        f(self.shared.borrow_mut().words())
+    }
+
+    /// Run `f` with the word index of each buffer of other files, except
+    /// those `skip` rejects (large files)
+    pub fn with_other_words(
+        &self,
+        skip: impl Fn(&TextBuffer) -> bool,
+        mut f: impl FnMut(&WordIndex),
+    ) {
+        let others: Vec<_> = BUFFERS.with_borrow(|buffers| {
+            buffers
+                .iter()
+                .filter_map(Weak::upgrade)
+                .filter(|shared| !Rc::ptr_eq(shared, &self.shared))
+                .collect()
+        });
+        for shared in others {
+            let Ok(mut shared) = shared.try_borrow_mut() else {
+                continue;
+            };
+            if !skip(&shared.buffer) {
+                f(shared.words());
+            }
+        }
+    }
+
+    /// File modification time at load or save
+    pub fn mtime(&self) -> Option<SystemTime> {
+        self.shared.borrow().mtime
+    }
+
+    /// Record the file modification time after load or save
+    pub fn set_mtime(&self, mtime: Option<SystemTime>) {
+        self.shared.borrow_mut().mtime = mtime;
+    }
+
+    /// Check if the file was modified on disk since load or save
+    pub fn external_change(&self) -> bool {
+        self.shared.borrow().external_change
+    }
+
+    /// Set or clear the external change flag
+    pub fn set_external_change(&self, changed: bool) {
+        self.shared.borrow_mut().external_change = changed;
+    }
+
+    /// Recovery copy state of the buffer
+    pub fn recovery(&self) -> RefMut<'_, RecoveryState> {
+        RefMut::map(self.shared.borrow_mut(), |shared| &mut shared.recovery)
+    }
+
+    /// Record the first line this view shows
+    pub fn set_top_line(&self, line: usize) {
+        let mut shared = self.shared.borrow_mut();
+        match shared
+            .top_lines
+            .iter_mut()
+            .find(|(view, _)| *view == self.view)
+        {
+            Some((_, top)) => *top = line,
+            None => shared.top_lines.push((self.view, line)),
+        }
+    }
+
+    /// Watch the text and top line of this view
+    pub fn watch(&self) -> BufferWatch {
+        BufferWatch {
+            shared: Rc::downgrade(&self.shared),
+            view: self.view,
+        }
+    }
+}
+
+/// Follows one view of a buffer until the view is closed
+pub(crate) struct BufferWatch {
+    shared: Weak<RefCell<Shared>>,
+    view: usize,
+}
+
+impl BufferWatch {
+    /// Shared state while the view is open
+    fn shared(&self) -> Option<Rc<RefCell<Shared>>> {
+        self.shared.upgrade().filter(|shared| {
+            shared
+                .borrow()
+                .views
+                .iter()
+                .any(|(view, _)| *view == self.view)
+        })
+    }
+
+    /// Whether the view is still open
+    pub fn is_open(&self) -> bool {
+        self.shared().is_some()
+    }
+
+    /// Version of the text (replacements and edits), None once closed
+    pub fn version(&self) -> Option<(u64, u64)> {
+        let shared = self.shared()?;
+        let shared = shared.borrow();
+        Some((shared.generation, shared.buffer.revision()))
+    }
+
+    /// Text of the buffer, None once closed
+    pub fn text(&self) -> Option<String> {
+        Some(self.shared()?.borrow().buffer.to_string())
+    }
+
+    /// First line the view showed when last drawn
+    pub fn top_line(&self) -> Option<usize> {
+        let shared = self.shared()?;
+        let shared = shared.borrow();
+        shared
+            .top_lines
+            .iter()
+            .find(|(view, _)| *view == self.view)
+            .map(|(_, line)| *line)
+    }
+}
+
+impl Drop for SharedBuffer {
+    fn drop(&mut self) {
+        // Borrowing fails only while a guard of this handle is alive,
+        // which cannot outlive the handle
+        if let Ok(mut shared) = self.shared.try_borrow_mut() {
+            shared.views.retain(|(view, _)| *view != self.view);
+            shared.top_lines.retain(|(view, _)| *view != self.view);
+        }
+    }
+}
+
+/// Mutable access to a shared buffer
+pub(crate) struct BufferMut<'a> {
+    shared: RefMut<'a, Shared>,
+    view: usize,
+}
+
+impl Deref for BufferMut<'_> {
+    type Target = TextBuffer;
+
+    fn deref(&self) -> &TextBuffer {
+        &self.shared.buffer
+    }
+}
+
+impl DerefMut for BufferMut<'_> {
+    fn deref_mut(&mut self) -> &mut TextBuffer {
+        &mut self.shared.buffer
+    }
+}
+
+impl Drop for BufferMut<'_> {
+    fn drop(&mut self) {
+        let edits = self.shared.buffer.take_line_edits();
+        if edits.is_empty() {
+            return;
+        }
+        let shared = &mut *self.shared;
+        if let Some(words) = &mut shared.words {
+            words.apply_edits(&edits, &shared.buffer);
+        }
+        let author = self.view;
+        for (view, queue) in &mut shared.views {
+            queue.extend(edits.iter().map(|&edit| ViewEdit {
+                edit,
+                foreign: *view != author,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termide_buffer::Cursor;
+
+    #[test]
+    fn test_edits_reach_all_views() {
+        let first = SharedBuffer::new(TextBuffer::from_text("a\nb\n"));
+        let second = first.split();
+        assert_eq!(first.view_count(), 2);
+
+        first
+            .borrow_mut()
+            .insert(&Cursor::at(0, 0), "new\n")
+            .unwrap();
+        assert_eq!(second.borrow().line(0).unwrap(), "new\n");
+
+        let edit = LineEdit {
+            line: 0,
+            removed: 0,
+            inserted: 1,
+        };
+        assert_eq!(
+            first.take_edits(),
+            vec![ViewEdit {
+                edit,
+                foreign: false
+            }]
+        );
+        assert_eq!(
+            second.take_edits(),
+            vec![ViewEdit {
+                edit,
+                foreign: true
+            }]
+        );
+        assert!(second.take_edits().is_empty());
+
+        // The buffer stays with the remaining view
+        drop(first);
+        assert_eq!(second.view_count(), 1);
+        second.borrow_mut().undo().unwrap();
+        assert_eq!(second.borrow().text(), "a\nb\n");
+    }
+
+    #[test]
+    fn test_watch_follows_one_view() {
+        let first = SharedBuffer::new(TextBuffer::from_text("a\n"));
+        let second = first.split();
+        let watch = first.watch();
+        assert_eq!(watch.top_line(), None);
+
+        let version = watch.version();
+        second.borrow_mut().insert(&Cursor::at(0, 0), "b").unwrap();
+        assert_ne!(watch.version(), version);
+        assert_eq!(watch.text().as_deref(), Some("ba\n"));
+
+        first.set_top_line(7);
+        second.set_top_line(3);
+        assert_eq!(watch.top_line(), Some(7));
+
+        // Closing the watched view ends the watch, the buffer lives on
+        drop(first);
+        assert!(!watch.is_open());
+        assert_eq!(watch.text(), None);
+        assert_eq!(second.view_count(), 1);
+    }
+}
@@ -5,7 +5,8 @@
 
 use anyhow::Result;
 
-use termide_buffer::{Cursor, Selection, TextBuffer};
+use termide_buffer::{is_word_char, Cursor, Selection, TextBuffer};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Result of a text editing operation.
 ///
@@ -14,7 +15,6 @@ use termide_buffer::{Cursor, Selection, TextBuffer};
 pub struct EditResult {
     pub new_cursor: Cursor,
     pub start_line: usize,
-    pub is_multiline: bool,
 }
 
 /// Insert a character at the cursor position.
@@ -27,7 +27,6 @@ pub fn insert_char(buffer: &mut TextBuffer, cursor: &Cursor, ch: char) -> Result
     Ok(EditResult {
         new_cursor,
         start_line: cursor.line,
-        is_multiline: false,
     })
 }
 
@@ -41,7 +40,6 @@ pub fn insert_newline(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<EditRe
     Ok(EditResult {
         new_cursor,
         start_line: old_line,
-        is_multiline: true,
     })
 }
 
@@ -49,14 +47,10 @@ pub fn insert_newline(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<EditRe
 ///
 /// Returns Some(EditResult) if deletion occurred, None if nothing to delete.
 pub fn backspace(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<EditResult>> {
-    let old_line = cursor.line;
-    let was_at_line_start = cursor.column == 0;
-
     if let Some(new_cursor) = buffer.backspace(cursor)? {
         Ok(Some(EditResult {
             new_cursor,
             start_line: new_cursor.line,
-            is_multiline: was_at_line_start && old_line > 0,
         }))
     } else {
         Ok(None)
@@ -67,14 +61,10 @@ pub fn backspace(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<Edit
 ///
 /// Returns Some(EditResult) if deletion occurred, None if nothing to delete.
 pub fn delete_char(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<EditResult>> {
-    let line_len = buffer.line_len_graphemes(cursor.line);
-    let was_at_line_end = cursor.column >= line_len;
-
     if buffer.delete_char(cursor)? {
         Ok(Some(EditResult {
             new_cursor: *cursor,
             start_line: cursor.line,
-            is_multiline: was_at_line_end,
         }))
     } else {
         Ok(None)
@@ -83,7 +73,7 @@ pub fn delete_char(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<Ed
 
 /// Duplicate current line or selected lines.
 ///
-/// Returns EditResult with new cursor position and cache invalidation info.
+/// Returns EditResult with the cursor at the same position in the copy.
 pub fn duplicate_line(
     buffer: &mut TextBuffer,
     cursor: &Cursor,
@@ -125,15 +115,320 @@ pub fn duplicate_line(
 
     buffer.insert(&insert_cursor, &text_to_duplicate)?;
 
-    // Return new cursor at the beginning of the first duplicated line
-    let new_cursor = Cursor {
-        line: end_line + 1,
-        column: 0,
-    };
+    // The copy is inserted below, so the cursor keeps its column
+    let new_cursor = Cursor::at(cursor.line + end_line - start_line + 1, cursor.column);
 
     Ok(EditResult {
         new_cursor,
         start_line,
-        is_multiline: true,
     })
 }
+
+/// Delete lines `start_line..=end_line` with their line break.
+///
+/// The cursor stays at `column` (clamped later) on the line that takes
+/// their place, or on the new last line.
+pub fn delete_lines(
+    buffer: &mut TextBuffer,
+    start_line: usize,
+    end_line: usize,
+    column: usize,
+) -> Result<EditResult> {
+    let last_line = buffer.line_count().saturating_sub(1);
+    let end_line = end_line.min(last_line);
+
+    let (start, end) = if end_line < last_line {
+        (Cursor::at(start_line, 0), Cursor::at(end_line + 1, 0))
+    } else if start_line > 0 {
+        // The last line has no line break; remove the one before it
+        let prev_len = buffer.line_len_graphemes(start_line - 1);
+        (
+            Cursor::at(start_line - 1, prev_len),
+            Cursor::at(end_line, buffer.line_len_graphemes(end_line)),
+        )
+    } else {
+        (
+            Cursor::at(0, 0),
+            Cursor::at(end_line, buffer.line_len_graphemes(end_line)),
+        )
+    };
+    buffer.replace_range(&start, &end, "")?;
+
+    let line = start_line.min(buffer.line_count().saturating_sub(1));
+    Ok(EditResult {
+        new_cursor: Cursor::at(line, column),
+        start_line: start.line,
+    })
+}
+
+/// Move lines `start_line..=end_line` one line up or down as one undo step.
+///
+/// Returns None at the start (up) or end (down) of the buffer.
+pub fn move_lines(
+    buffer: &mut TextBuffer,
+    start_line: usize,
+    end_line: usize,
+    up: bool,
+) -> Result<Option<EditResult>> {
+    let last_line = buffer.line_count().saturating_sub(1);
+    if (up && start_line == 0) || (!up && end_line >= last_line) {
+        return Ok(None);
+    }
+
+    // The moved lines and the line they swap places with
+    let first = if up { start_line - 1 } else { start_line };
+    let count = end_line - start_line + 2;
+    let mut lines: Vec<String> = (first..first + count)
+        .map(|line| buffer.line(line).unwrap_or_default())
+        .collect();
+    let ends_with_newline = lines.last().is_some_and(|line| line.ends_with('\n'));
+    for line in &mut lines {
+        line.truncate(line.trim_end_matches('\n').len());
+    }
+    if up {
+        lines.rotate_left(1);
+    } else {
+        lines.rotate_right(1);
+    }
+
+    let mut text = lines.join("\n");
+    if ends_with_newline {
+        text.push('\n');
+    }
+    buffer.replace_lines(first, count, &text)?;
+
+    Ok(Some(EditResult {
+        new_cursor: Cursor::at(first, 0),
+        start_line: first,
+    }))
+}
+
+/// Closing character for an auto-closed bracket or quote.
+pub fn closing_pair(ch: char) -> Option<char> {
+    match ch {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '"' | '\'' | '`' => Some(ch),
+        _ => None,
+    }
+}
+
+/// Check if typed character should skip over the identical next character
+/// instead of being inserted (closing brackets and unescaped quotes).
+pub fn should_skip_closing(ch: char, prev: Option<char>, next: Option<char>) -> bool {
+    match ch {
+        ')' | ']' | '}' => next == Some(ch),
+        '"' | '\'' | '`' => next == Some(ch) && prev != Some('\\'),
+        _ => false,
+    }
+}
+
+/// Check if typed opening character should get its closing pair.
+///
+/// Pairs are only inserted before whitespace, closing brackets and
+/// punctuation, so typing in front of a word inserts just one character.
+/// Quotes are not paired after a backslash (escaped quote) or after a word
+/// character (apostrophes like `don't`).
+pub fn should_auto_close(ch: char, prev: Option<char>, next: Option<char>) -> bool {
+    if closing_pair(ch).is_none() {
+        return false;
+    }
+
+    let next_allows = next.is_none_or(|c| c.is_whitespace() || ")]},;:".contains(c));
+    let is_quote = matches!(ch, '"' | '\'' | '`');
+    let prev_allows =
+        !is_quote || !prev.is_some_and(|c| c == '\\' || c == ch || c.is_alphanumeric() || c == '_');
+
+    next_allows && prev_allows
+}
+
+/// Insert an opening character with its closing pair as one undo step.
+///
+/// Returns EditResult with the cursor between the two characters.
+pub fn insert_pair(
+    buffer: &mut TextBuffer,
+    cursor: &Cursor,
+    open: char,
+    close: char,
+) -> Result<EditResult> {
+    buffer.insert(cursor, &format!("{}{}", open, close))?;
+
+    Ok(EditResult {
+        new_cursor: Cursor::at(cursor.line, cursor.column + 1),
+        start_line: cursor.line,
+    })
+}
+
+/// Delete an empty bracket or quote pair around the cursor (backspace).
+///
+/// Returns Some(EditResult) if the characters around the cursor form a pair.
+pub fn backspace_pair(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<EditResult>> {
+    let (prev, next) = chars_around(buffer, cursor);
+    let close = prev.and_then(closing_pair);
+    if close.is_none() || close != next {
+        return Ok(None);
+    }
+
+    let start = Cursor::at(cursor.line, cursor.column - 1);
+    buffer.delete_range(&start, &Cursor::at(cursor.line, cursor.column + 1))?;
+
+    Ok(Some(EditResult {
+        new_cursor: start,
+        start_line: cursor.line,
+    }))
+}
+
+/// Kind of a grapheme for word-wise editing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WordClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn word_class(grapheme: &str) -> WordClass {
+    match grapheme.chars().next() {
+        Some(c) if c.is_whitespace() => WordClass::Space,
+        Some(c) if is_word_char(c) => WordClass::Word,
+        _ => WordClass::Punctuation,
+    }
+}
+
+/// Graphemes of a line without its line break.
+fn line_graphemes(line: &str) -> Vec<&str> {
+    line.trim_end_matches('\n').graphemes(true).collect()
+}
+
+/// Column of the start of the word before `column`, skipping whitespace.
+///
+/// A run of punctuation counts as a word.
+fn word_start_before(graphemes: &[&str], column: usize) -> usize {
+    let mut col = column.min(graphemes.len());
+    while col > 0 && word_class(graphemes[col - 1]) == WordClass::Space {
+        col -= 1;
+    }
+    if let Some(class) = col.checked_sub(1).map(|prev| word_class(graphemes[prev])) {
+        while col > 0 && word_class(graphemes[col - 1]) == class {
+            col -= 1;
+        }
+    }
+    col
+}
+
+/// Column of the end of the word after `column`, skipping whitespace.
+fn word_end_after(graphemes: &[&str], column: usize) -> usize {
+    let mut col = column.min(graphemes.len());
+    while col < graphemes.len() && word_class(graphemes[col]) == WordClass::Space {
+        col += 1;
+    }
+    if let Some(class) = graphemes.get(col).map(|g| word_class(g)) {
+        while col < graphemes.len() && word_class(graphemes[col]) == class {
+            col += 1;
+        }
+    }
+    col
+}
+
+/// Delete from the cursor to the start of the previous word (Ctrl+Backspace).
+///
+/// At the start of a line, joins it with the previous line like backspace.
+pub fn delete_word_backward(
+    buffer: &mut TextBuffer,
+    cursor: &Cursor,
+) -> Result<Option<EditResult>> {
+    if cursor.column == 0 {
+        return backspace(buffer, cursor);
+    }
+
+    let line = buffer.line(cursor.line).unwrap_or_default();
+    let start = Cursor::at(
+        cursor.line,
+        word_start_before(&line_graphemes(&line), cursor.column),
+    );
+    buffer.replace_range(&start, cursor, "")?;
+
+    Ok(Some(EditResult {
+        new_cursor: start,
+        start_line: cursor.line,
+    }))
+}
+
+/// Delete from the cursor to the end of the next word (Ctrl+Delete).
+///
+/// At the end of a line, joins the next line like delete.
+pub fn delete_word_forward(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<EditResult>> {
+    let line = buffer.line(cursor.line).unwrap_or_default();
+    let graphemes = line_graphemes(&line);
+    if cursor.column >= graphemes.len() {
+        return delete_char(buffer, cursor);
+    }
+
+    let end = Cursor::at(cursor.line, word_end_after(&graphemes, cursor.column));
+    buffer.replace_range(cursor, &end, "")?;
+
+    Ok(Some(EditResult {
+        new_cursor: *cursor,
+        start_line: cursor.line,
+    }))
+}
+
+/// Swap the word at or before the cursor with the next word on the line.
+///
+/// At or after the last word, swaps the last two words. The cursor moves
+/// to the end of the swapped pair. Returns None with fewer than two words.
+pub fn transpose_words(buffer: &mut TextBuffer, cursor: &Cursor) -> Result<Option<EditResult>> {
+    let line = buffer.line(cursor.line).unwrap_or_default();
+    let graphemes = line_graphemes(&line);
+
+    // Column ranges of the words on the line
+    let mut words = Vec::new();
+    let mut col = 0;
+    while col < graphemes.len() {
+        let start = col;
+        let class = word_class(graphemes[col]);
+        while col < graphemes.len() && word_class(graphemes[col]) == class {
+            col += 1;
+        }
+        if class == WordClass::Word {
+            words.push(start..col);
+        }
+    }
+    if words.len() < 2 {
+        return Ok(None);
+    }
+
+    let first = words
+        .iter()
+        .rposition(|word| word.start < cursor.column)
+        .unwrap_or(0)
+        .min(words.len() - 2);
+    let (left, right) = (words[first].clone(), words[first + 1].clone());
+
+    let text = |range: std::ops::Range<usize>| graphemes[range].concat();
+    let swapped = text(right.clone()) + &text(left.end..right.start) + &text(left.clone());
+    let new_cursor = buffer.replace_range(
+        &Cursor::at(cursor.line, left.start),
+        &Cursor::at(cursor.line, right.end),
+        &swapped,
+    )?;
+
+    Ok(Some(EditResult {
+        new_cursor,
+        start_line: cursor.line,
+    }))
+}
+
+/// Characters immediately before and after the cursor on its line.
+pub fn chars_around(buffer: &TextBuffer, cursor: &Cursor) -> (Option<char>, Option<char>) {
+    let line = buffer.line(cursor.line).unwrap_or_default();
+    let graphemes: Vec<&str> = line.trim_end_matches('\n').graphemes(true).collect();
+
+    let prev = cursor
+        .column
+        .checked_sub(1)
+        .and_then(|col| graphemes.get(col))
+        .and_then(|g| g.chars().last());
+    let next = graphemes.get(cursor.column).and_then(|g| g.chars().next());
+    (prev, next)
+}
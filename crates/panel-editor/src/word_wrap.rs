@@ -2,76 +2,102 @@
 //!
 //! This module provides utilities for calculating line wrapping in the editor,
 //! including smart wrapping (breaking at word boundaries) and hard wrapping
-//! (breaking at fixed column width).
+//! (breaking at fixed column width). Positions are grapheme indices and
+//! widths are display widths, as in the renderer.
 
-use termide_buffer::{calculate_wrap_points_for_line, TextBuffer};
+use unicode_segmentation::UnicodeSegmentation;
+
+use termide_buffer::{
+    calculate_wrap_points_for_line, FoldMap, TextBuffer, WrapMode, LONG_LINE_BYTES,
+};
 
 /// Calculate wrap points for a single line of text.
 ///
 /// Returns (visual_row_count, wrap_points) where wrap_points contains
-/// the character indices where the line should wrap.
+/// the grapheme indices where the line should wrap.
 pub fn get_line_wrap_points(
     line_text: &str,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> (usize, Vec<usize>) {
     if content_width == 0 {
         return (1, Vec::new());
     }
 
-    let chars: Vec<char> = line_text.chars().collect();
-    let line_len = chars.len();
-
-    if line_len == 0 {
-        return (1, Vec::new());
+    // Word boundaries are not searched in long lines
+    if line_text.len() > LONG_LINE_BYTES {
+        return fixed_wrap_points(line_text.graphemes(true).count(), content_width);
     }
 
-    if line_len <= content_width {
-        return (1, Vec::new()); // No wrapping needed
-    }
+    let wrap_points = calculate_wrap_points_for_line(line_text, content_width, wrap_mode);
+    let visual_rows = wrap_points.len() + 1; // +1 for the first line
+    (visual_rows, wrap_points)
+}
 
-    if use_smart_wrap {
-        // Use smart wrapping from termide-buffer
-        let wrap_points = calculate_wrap_points_for_line(line_text, content_width);
-        let visual_rows = wrap_points.len() + 1; // +1 for the first line
-        (visual_rows, wrap_points)
-    } else {
-        // Use simple wrapping (hard break at content_width)
-        let visual_rows = line_len.div_ceil(content_width);
-        let mut wrap_points = Vec::with_capacity(visual_rows.saturating_sub(1));
-        for i in 1..visual_rows {
-            wrap_points.push(i * content_width);
+/// Calculate wrap points for a line of the buffer.
+///
+/// Returns (visual_row_count, wrap_points, line_len), None if the line
+/// doesn't exist. Long lines are cut into chunks of `content_width`
+/// graphemes, as the renderer does, without reading their text.
+pub fn line_wrap_points(
+    buffer: &TextBuffer,
+    line_idx: usize,
+    content_width: usize,
+    wrap_mode: WrapMode,
+) -> Option<(usize, Vec<usize>, usize)> {
+    if buffer.is_long_line(line_idx) {
+        let line_len = buffer.line_len_graphemes(line_idx);
+        if content_width == 0 {
+            return Some((1, Vec::new(), line_len));
         }
-        (visual_rows, wrap_points)
+        let (visual_rows, wrap_points) = fixed_wrap_points(line_len, content_width);
+        return Some((visual_rows, wrap_points, line_len));
     }
+    let line_text = buffer.line(line_idx)?;
+    let line_text = line_text.trim_end_matches('\n');
+    let (visual_rows, wrap_points) = get_line_wrap_points(line_text, content_width, wrap_mode);
+    Some((visual_rows, wrap_points, line_text.graphemes(true).count()))
+}
+
+/// Wrap points every `content_width` positions of a line of `line_len`.
+fn fixed_wrap_points(line_len: usize, content_width: usize) -> (usize, Vec<usize>) {
+    let visual_rows = line_len.div_ceil(content_width).max(1);
+    let wrap_points = (1..visual_rows).map(|i| i * content_width).collect();
+    (visual_rows, wrap_points)
 }
 
 /// Calculate the visual row index for a cursor position.
 ///
 /// Returns the visual row index from viewport.top_line.
 /// This accounts for word wrapping - a single buffer line may span multiple visual rows.
+/// Lines hidden by folds take no rows.
 ///
 /// # Parameters
 /// - `buffer`: The text buffer
+/// - `folds`: Folded line ranges
 /// - `cursor_line`: Current cursor line (buffer coordinates)
 /// - `cursor_col`: Current cursor column (buffer coordinates)
 /// - `viewport_top`: Top line of viewport (buffer coordinates)
 /// - `content_width`: Width of content area for wrapping
 /// - `word_wrap_enabled`: Whether word wrap is enabled
-/// - `use_smart_wrap`: Whether to use smart wrapping
+/// - `wrap_mode`: Where lines are broken
 #[allow(dead_code)] // May be used in future phases
+#[allow(clippy::too_many_arguments)]
 pub fn calculate_visual_row_for_cursor(
     buffer: &TextBuffer,
+    folds: &FoldMap,
     cursor_line: usize,
     cursor_col: usize,
     viewport_top: usize,
     content_width: usize,
     word_wrap_enabled: bool,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> usize {
     if content_width == 0 || !word_wrap_enabled {
-        // No word wrap - visual row is just buffer line offset from top
-        return cursor_line.saturating_sub(viewport_top);
+        // No word wrap - visual row is just visible line offset from top
+        return folds
+            .row_of(cursor_line)
+            .saturating_sub(folds.row_of(viewport_top));
     }
 
     let mut visual_row = 0;
@@ -79,25 +105,22 @@ pub fn calculate_visual_row_for_cursor(
 
     // Count visual rows from viewport top to cursor line
     while line_idx < cursor_line && line_idx < buffer.line_count() {
-        if let Some(line_text) = buffer.line(line_idx) {
-            let line_text = line_text.trim_end_matches('\n');
-            let (line_visual_rows, _) =
-                get_line_wrap_points(line_text, content_width, use_smart_wrap);
+        if let Some((line_visual_rows, _, _)) =
+            line_wrap_points(buffer, line_idx, content_width, wrap_mode)
+        {
             visual_row += line_visual_rows;
         } else {
             visual_row += 1; // Empty line = 1 visual row
         }
-        line_idx += 1;
+        line_idx = folds.next_visible(line_idx);
     }
 
     // Now add the visual row within the cursor's line
-    if let Some(line_text) = buffer.line(cursor_line) {
-        let line_text = line_text.trim_end_matches('\n');
-        let (_line_visual_rows, wrap_points) =
-            get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
+    if let Some((_line_visual_rows, wrap_points, line_len)) =
+        line_wrap_points(buffer, cursor_line, content_width, wrap_mode)
+    {
         // Find which visual row within this line the cursor is on
-        let cursor_col_clamped = cursor_col.min(line_text.chars().count());
+        let cursor_col_clamped = cursor_col.min(line_len);
         let row_within_line = wrap_points
             .iter()
             .filter(|&&wp| wp <= cursor_col_clamped)
@@ -108,31 +131,66 @@ pub fn calculate_visual_row_for_cursor(
     visual_row
 }
 
+/// Count the visual rows after the cursor's row, up to `limit`.
+///
+/// Used to end the scroll-off margin at the end of the document.
+pub fn visual_rows_below_cursor(
+    buffer: &TextBuffer,
+    folds: &FoldMap,
+    cursor_line: usize,
+    cursor_col: usize,
+    limit: usize,
+    content_width: usize,
+    wrap_mode: WrapMode,
+) -> usize {
+    let line_rows = |line_idx: usize| {
+        line_wrap_points(buffer, line_idx, content_width, wrap_mode)
+            .map_or((1, Vec::new()), |(rows, wrap_points, _)| {
+                (rows, wrap_points)
+            })
+    };
+
+    // Rows of the cursor line below the cursor
+    let (rows, wrap_points) = line_rows(cursor_line);
+    let row_within_line = wrap_points.iter().filter(|&&wp| wp <= cursor_col).count();
+    let mut below = rows.saturating_sub(row_within_line + 1);
+
+    let mut line_idx = folds.next_visible(cursor_line);
+    while below < limit && line_idx < buffer.line_count() {
+        below += line_rows(line_idx).0;
+        line_idx = folds.next_visible(line_idx);
+    }
+    below.min(limit)
+}
+
 /// Calculate total number of visual rows in the entire buffer.
 ///
-/// This accounts for word wrapping - returns total visual rows across all lines.
+/// This accounts for word wrapping - returns total visual rows across all lines
+/// not hidden by folds.
 pub fn calculate_total_visual_rows(
     buffer: &TextBuffer,
+    folds: &FoldMap,
     content_width: usize,
     word_wrap_enabled: bool,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> usize {
     if content_width == 0 || !word_wrap_enabled {
-        // No word wrap - just return buffer line count
-        return buffer.line_count();
+        // No word wrap - just return visible line count
+        return buffer.line_count().saturating_sub(folds.hidden_lines());
     }
 
     let mut total_visual_rows = 0;
+    let mut line_idx = 0;
 
-    for line_idx in 0..buffer.line_count() {
-        if let Some(line_text) = buffer.line(line_idx) {
-            let line_text = line_text.trim_end_matches('\n');
-            let (line_visual_rows, _) =
-                get_line_wrap_points(line_text, content_width, use_smart_wrap);
+    while line_idx < buffer.line_count() {
+        if let Some((line_visual_rows, _, _)) =
+            line_wrap_points(buffer, line_idx, content_width, wrap_mode)
+        {
             total_visual_rows += line_visual_rows;
         } else {
             total_visual_rows += 1; // Empty line = 1 visual row
         }
+        line_idx = folds.next_visible(line_idx);
     }
 
     total_visual_rows
@@ -144,32 +202,31 @@ pub fn calculate_total_visual_rows(
 ///
 /// # Parameters
 /// - `buffer`: The text buffer
+/// - `folds`: Folded line ranges
 /// - `visual_row`: Visual row index relative to viewport
 /// - `viewport_top`: Top line of viewport (buffer coordinates)
 /// - `content_width`: Width of content area for wrapping
-/// - `use_smart_wrap`: Whether to use smart wrapping
+/// - `wrap_mode`: Where lines are broken
 pub fn visual_row_to_buffer_position(
     buffer: &TextBuffer,
+    folds: &FoldMap,
     visual_row: usize,
     viewport_top: usize,
     content_width: usize,
-    use_smart_wrap: bool,
+    wrap_mode: WrapMode,
 ) -> (usize, usize) {
     if content_width == 0 {
-        return (viewport_top + visual_row, 0);
+        return (folds.line_of(folds.row_of(viewport_top) + visual_row), 0);
     }
 
     let mut current_visual_row = 0;
     let mut line_idx = viewport_top;
 
     while line_idx < buffer.line_count() {
-        if let Some(line_text) = buffer.line(line_idx) {
-            let line_text = line_text.trim_end_matches('\n');
-
-            // Calculate how many visual rows this line occupies using actual wrap points
-            let (visual_rows_for_line, wrap_points) =
-                get_line_wrap_points(line_text, content_width, use_smart_wrap);
-
+        // Calculate how many visual rows this line occupies using actual wrap points
+        if let Some((visual_rows_for_line, wrap_points, _)) =
+            line_wrap_points(buffer, line_idx, content_width, wrap_mode)
+        {
             // Check if target visual row is in this buffer line
             if current_visual_row + visual_rows_for_line > visual_row {
                 // Found the buffer line containing the target visual row
@@ -196,9 +253,32 @@ pub fn visual_row_to_buffer_position(
             current_visual_row += 1;
         }
 
-        line_idx += 1;
+        line_idx = folds.next_visible(line_idx);
     }
 
     // If we've exhausted all lines, return the last line
     (buffer.line_count().saturating_sub(1), 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_points_follow_display_width() {
+        // Six ideographs are twelve columns wide
+        assert_eq!(
+            get_line_wrap_points("中文字符测试", 8, WrapMode::WordsAndPunctuation),
+            (2, vec![4])
+        );
+        assert_eq!(
+            get_line_wrap_points("中文字符测试", 8, WrapMode::Hard),
+            (2, vec![4])
+        );
+        // Combining marks do not take columns
+        assert_eq!(
+            get_line_wrap_points("e\u{301}e\u{301}e\u{301}", 3, WrapMode::Hard),
+            (1, vec![])
+        );
+    }
+}
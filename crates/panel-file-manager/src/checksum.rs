@@ -0,0 +1,131 @@
+//! File checksums.
+//!
+//! SHA-256 of files read in chunks, so a file of any size hashes in
+//! constant memory, with a progress callback that can stop the read.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use sha2::{Digest, Sha256};
+use termide_state::ChecksumUpdate;
+
+/// Bytes read from the file at a time
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes hashed between two progress reports of `spawn_checksum`
+const PROGRESS_STEP: u64 = 4 * 1024 * 1024;
+
+/// Key of the checksum line in the file info window
+pub const INFO_KEY: &str = "SHA-256";
+
+/// Lowercase hex of a digest
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// SHA-256 of a file, read in chunks. `on_progress` gets the bytes hashed
+/// so far after every chunk; returning false stops the read and gives
+/// `None`.
+pub fn hash_file(
+    path: &Path,
+    on_progress: &mut dyn FnMut(u64) -> bool,
+) -> io::Result<Option<[u8; 32]>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut done = 0u64;
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..read]);
+        done += read as u64;
+        if !on_progress(done) {
+            return Ok(None);
+        }
+    }
+    Ok(Some(hasher.finalize().into()))
+}
+
+/// Hash `path` on a background thread. Progress is reported every few
+/// megabytes; dropping the receiver stops the worker at its next report.
+pub fn spawn_checksum(path: PathBuf) -> mpsc::Receiver<ChecksumUpdate> {
+    let (tx, rx) = termide_wake::channel();
+    std::thread::spawn(move || {
+        let total = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let mut next_report = PROGRESS_STEP;
+        let result = hash_file(&path, &mut |done| {
+            if done < next_report {
+                return true;
+            }
+            next_report = done + PROGRESS_STEP;
+            tx.send(ChecksumUpdate::Progress { done, total }).is_ok()
+        });
+        let result = match result {
+            Ok(Some(digest)) => Ok(to_hex(&digest)),
+            // Cancelled: nobody is listening any more
+            Ok(None) => return,
+            Err(e) => Err(e.to_string()),
+        };
+        let _ = tx.send(ChecksumUpdate::Finished(result));
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha256_hex(data: &[u8]) -> String {
+        to_hex(&Sha256::digest(data))
+    }
+
+    #[test]
+    fn test_hash_file_streams_and_stops() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, vec![7u8; 3 * CHUNK_SIZE + 5]).unwrap();
+
+        let mut reports = Vec::new();
+        let digest = hash_file(&path, &mut |done| {
+            reports.push(done);
+            true
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(to_hex(&digest), sha256_hex(&vec![7u8; 3 * CHUNK_SIZE + 5]));
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports.last(), Some(&(3 * CHUNK_SIZE as u64 + 5)));
+
+        let mut chunks = 0;
+        let stopped = hash_file(&path, &mut |_| {
+            chunks += 1;
+            false
+        })
+        .unwrap();
+        assert!(stopped.is_none());
+        assert_eq!(chunks, 1);
+    }
+
+    #[test]
+    fn test_spawn_checksum_reports_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc");
+        std::fs::write(&path, b"abc").unwrap();
+
+        let last = spawn_checksum(path).iter().last();
+        assert_eq!(
+            last,
+            Some(ChecksumUpdate::Finished(Ok(
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string()
+            )))
+        );
+        let missing = spawn_checksum(dir.path().join("missing")).iter().last();
+        assert!(matches!(missing, Some(ChecksumUpdate::Finished(Err(_)))));
+    }
+}
@@ -0,0 +1,283 @@
+//! Deletion of files and directory trees with progress and cancellation.
+//!
+//! `spawn_delete` removes paths on a worker thread, walking directory trees
+//! depth-first and streaming `DeleteProgressUpdate`s back to the app. An
+//! entry that cannot be removed is recorded and the walk goes on (its
+//! parent directories stay). Cancellation is checked between entries and
+//! leaves a partially deleted tree.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use termide_state::{CancellationToken, DeleteProgressUpdate, DeleteSummary};
+
+/// Removed entries between progress reports
+const PROGRESS_EVERY: usize = 100;
+
+/// Number of entries in `paths`, counting everything below directories
+/// (symlinks are not followed); stops early on cancellation
+pub fn count_entries(paths: &[PathBuf], cancel: &CancellationToken) -> usize {
+    fn count(path: &Path, cancel: &CancellationToken) -> usize {
+        if cancel.is_cancelled() {
+            return 0;
+        }
+        let is_dir = fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir());
+        let below = if is_dir {
+            fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| count(&entry.path(), cancel))
+                        .sum()
+                })
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        below + 1
+    }
+    paths.iter().map(|path| count(path, cancel)).sum()
+}
+
+/// Remove `paths` with everything below them
+///
+/// `on_progress` gets the number of removed entries and the path being
+/// removed every `PROGRESS_EVERY` entries.
+pub fn delete_with_progress(
+    paths: &[PathBuf],
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(usize, &Path),
+) -> DeleteSummary {
+    let mut remover = Remover {
+        cancel,
+        on_progress,
+        summary: DeleteSummary::default(),
+    };
+    for path in paths {
+        if remover.remove(path) {
+            remover.summary.deleted += 1;
+        }
+        if remover.summary.cancelled {
+            break;
+        }
+    }
+    remover.summary
+}
+
+/// Delete paths on a worker thread
+///
+/// The worker first reports the estimated number of entries (`Total`), then
+/// streams `Removed` updates and ends with `Finished`.
+pub fn spawn_delete(
+    paths: Vec<PathBuf>,
+    cancel: CancellationToken,
+) -> mpsc::Receiver<DeleteProgressUpdate> {
+    let (tx, rx) = termide_wake::channel();
+
+    thread::spawn(move || {
+        let _ = tx.send(DeleteProgressUpdate::Total(count_entries(&paths, &cancel)));
+
+        let mut on_progress = |count, current: &Path| {
+            let _ = tx.send(DeleteProgressUpdate::Removed {
+                count,
+                current: current.to_path_buf(),
+            });
+        };
+        let summary = delete_with_progress(&paths, &cancel, &mut on_progress);
+        let _ = tx.send(DeleteProgressUpdate::Finished(summary));
+    });
+
+    rx
+}
+
+/// Depth-first removal state
+struct Remover<'a> {
+    cancel: &'a CancellationToken,
+    on_progress: &'a mut dyn FnMut(usize, &Path),
+    summary: DeleteSummary,
+}
+
+impl Remover<'_> {
+    /// Remove `path` and everything below it; false if anything was left
+    fn remove(&mut self, path: &Path) -> bool {
+        if self.cancel.is_cancelled() {
+            self.summary.cancelled = true;
+            return false;
+        }
+
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => return self.failed(path, e),
+        };
+        if !metadata.is_dir() {
+            let result = fs::remove_file(path);
+            return self.removed(path, result);
+        }
+
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(e) => return self.failed(path, e),
+        };
+        let mut emptied = true;
+        for entry in entries {
+            // Removing every entry even after a failure
+            emptied &= match entry {
+                Ok(entry) => self.remove(&entry.path()),
+                Err(e) => self.failed(path, e),
+            };
+            if self.summary.cancelled {
+                return false;
+            }
+        }
+        // A directory with entries left cannot go, which is not another error
+        emptied && {
+            let result = fs::remove_dir(path);
+            self.removed(path, result)
+        }
+    }
+
+    /// Count a removal attempt, reporting progress
+    fn removed(&mut self, path: &Path, result: io::Result<()>) -> bool {
+        if let Err(e) = result {
+            return self.failed(path, e);
+        }
+        self.summary.removed += 1;
+        if self.summary.removed.is_multiple_of(PROGRESS_EVERY) {
+            (self.on_progress)(self.summary.removed, path);
+        }
+        true
+    }
+
+    /// Record an entry that could not be removed
+    fn failed(&mut self, path: &Path, error: io::Error) -> bool {
+        self.summary
+            .errors
+            .push((path.to_path_buf(), error.to_string()));
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directory tree with `dirs` directories of `files` files each
+    fn make_tree(root: &Path, dirs: usize, files: usize) {
+        for dir in 0..dirs {
+            let dir = root.join(format!("dir{}", dir)).join("nested");
+            fs::create_dir_all(&dir).unwrap();
+            for file in 0..files {
+                fs::write(dir.join(format!("file{}", file)), "x").unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_delete_tree_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let tree = dir.path().join("node_modules");
+        make_tree(&tree, 10, 30);
+        let single = dir.path().join("single.txt");
+        fs::write(&single, "x").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path(), tree.join("link")).unwrap();
+        let paths = vec![tree.clone(), single.clone()];
+
+        let cancel = CancellationToken::new();
+        // Tree root, 10 directories with a nested one each, 300 files, link
+        let total = count_entries(&paths, &cancel);
+        assert_eq!(total, 1 + 20 + 300 + 1 + 1);
+
+        let mut reports = Vec::new();
+        let summary = delete_with_progress(&paths, &cancel, &mut |count, _| reports.push(count));
+
+        assert_eq!(summary.deleted, 2);
+        assert_eq!(summary.removed, total);
+        assert!(summary.errors.is_empty());
+        assert!(!summary.cancelled);
+        assert_eq!(reports, vec![100, 200, 300]);
+        assert!(!tree.exists() && !single.exists());
+        // The link was removed, not followed
+        assert!(dir.path().exists());
+    }
+
+    #[test]
+    fn test_cancelled_delete_leaves_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let tree = dir.path().join("target");
+        make_tree(&tree, 5, 100);
+
+        let cancel = CancellationToken::new();
+        let mut on_progress = |count, _: &Path| {
+            if count == 200 {
+                cancel.cancel();
+            }
+        };
+        let summary = delete_with_progress(std::slice::from_ref(&tree), &cancel, &mut on_progress);
+
+        assert!(summary.cancelled);
+        assert_eq!(summary.deleted, 0);
+        assert_eq!(summary.removed, 200);
+        assert!(summary.errors.is_empty());
+        assert!(tree.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permission_errors_are_collected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Permissions do not stop root
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let tree = dir.path().join("tree");
+        make_tree(&tree, 3, 2);
+        let locked = tree.join("dir1");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o500)).unwrap();
+
+        let summary = delete_with_progress(
+            std::slice::from_ref(&tree),
+            &CancellationToken::new(),
+            &mut |_, _| {},
+        );
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o700)).unwrap();
+
+        // dir1/nested is emptied but cannot leave dir1, everything else is gone
+        assert_eq!(summary.deleted, 0);
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(summary.errors[0].0, locked.join("nested"));
+        assert!(!summary.cancelled);
+        assert!(locked.join("nested").exists());
+        assert!(!tree.join("dir0").exists() && !tree.join("dir2").exists());
+    }
+
+    #[test]
+    fn test_spawn_delete_streams_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let tree = dir.path().join("tree");
+        make_tree(&tree, 2, 60);
+
+        let updates: Vec<_> = spawn_delete(vec![tree.clone()], CancellationToken::new())
+            .iter()
+            .collect();
+
+        assert_eq!(updates[0], DeleteProgressUpdate::Total(125));
+        assert!(matches!(
+            updates[1],
+            DeleteProgressUpdate::Removed { count: 100, .. }
+        ));
+        match updates.last() {
+            Some(DeleteProgressUpdate::Finished(summary)) => {
+                assert_eq!(summary.deleted, 1);
+                assert_eq!(summary.removed, 125);
+            }
+            other => panic!("unexpected last update: {:?}", other),
+        }
+        assert!(!tree.exists());
+    }
+}
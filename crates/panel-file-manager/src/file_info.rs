@@ -1,10 +1,10 @@
 use std::fs;
-use std::sync::mpsc;
 
-use super::{utils, FileManager};
+use super::{checksum, utils, FileManager};
+use termide_core::{StatusKind, StatusSegment};
 use termide_modal::ActiveModal;
 use termide_state::{DirSizeResult, PendingAction};
-use termide_ui::system_monitor::DiskSpaceInfo;
+use termide_ui::system_monitor::{DiskSpaceInfo, DiskSpaceInfoExt};
 
 /// File information for display
 #[derive(Clone, Debug)]
@@ -36,11 +36,13 @@ impl FileManager {
             self.current_path.join(&entry.name)
         };
 
-        let metadata = fs::metadata(&file_path).ok()?;
+        let metadata = fs::metadata(&file_path)
+            .or_else(|_| fs::symlink_metadata(&file_path))
+            .ok()?;
 
         let file_type = if metadata.is_dir() {
             "Directory"
-        } else if metadata.is_symlink() {
+        } else if entry.is_symlink {
             "Symlink"
         } else {
             "File"
@@ -84,6 +86,80 @@ impl FileManager {
         })
     }
 
+    /// Status bar segments: entry under the cursor, selection, listing
+    /// counts and disk space
+    pub(crate) fn status_bar_segments(&self) -> Vec<StatusSegment> {
+        let t = termide_i18n::t();
+        let mut segments = Vec::new();
+
+        if let Some(info) = self.get_current_file_info() {
+            let is_dir = info.file_type == "Directory";
+            let label = if is_dir {
+                t.status_dir()
+            } else {
+                t.status_file()
+            };
+            segments.push(StatusSegment::labeled(StatusKind::File, label, info.name));
+            if !is_dir {
+                segments.push(StatusSegment::new(StatusKind::Size, info.size));
+            }
+            segments.push(StatusSegment::labeled(
+                StatusKind::Permissions,
+                t.status_mod(),
+                info.mode,
+            ));
+            segments.push(StatusSegment::labeled(
+                StatusKind::Owner,
+                t.status_owner(),
+                format!("{}:{}", info.owner, info.group),
+            ));
+        }
+
+        if !self.selected_items.is_empty() {
+            let size: u64 = self
+                .selected_items
+                .iter()
+                .filter_map(|&idx| self.entries.get(idx))
+                .filter_map(|entry| entry.size)
+                .sum();
+            let count = self.selected_items.len();
+            let text = if size > 0 {
+                format!("{} ({})", count, utils::format_size(size))
+            } else {
+                count.to_string()
+            };
+            segments.push(StatusSegment::labeled(
+                StatusKind::Selection,
+                t.status_selected(),
+                text,
+            ));
+        }
+
+        let (dirs, files) = self.entries.iter().filter(|entry| entry.name != "..").fold(
+            (0, 0),
+            |(dirs, files), entry| {
+                if entry.is_dir {
+                    (dirs + 1, files)
+                } else {
+                    (dirs, files + 1)
+                }
+            },
+        );
+        segments.push(StatusSegment::new(
+            StatusKind::Entries,
+            t.status_entries(dirs, files),
+        ));
+
+        if let Some(disk) = self.get_disk_space_info() {
+            segments.push(
+                StatusSegment::new(StatusKind::Disk, disk.format_space())
+                    .with_usage(disk.usage_percent()),
+            );
+        }
+
+        segments
+    }
+
     /// Show file/directory information (Space)
     pub(crate) fn show_file_info(&mut self) {
         use std::os::unix::fs::MetadataExt;
@@ -99,14 +175,18 @@ impl FileManager {
                 self.current_path.join(&entry.name)
             };
 
-            if let Ok(metadata) = fs::metadata(&file_path) {
+            // Follow symlinks, but still show info for broken ones
+            let metadata = fs::metadata(&file_path).or_else(|_| fs::symlink_metadata(&file_path));
+            let is_symlink = entry.is_symlink && entry.name != "..";
+
+            if let Ok(metadata) = metadata {
                 let t = termide_i18n::t();
 
                 // Determine type and title
-                let (modal_title, is_dir) = if metadata.is_dir() {
+                let (modal_title, is_dir) = if is_symlink {
+                    (t.file_info_title_symlink(&entry.name), metadata.is_dir())
+                } else if metadata.is_dir() {
                     (t.file_info_title_directory(&entry.name), true)
-                } else if metadata.is_symlink() {
-                    (t.file_info_title_symlink(&entry.name), false)
                 } else {
                     (t.file_info_title_file(&entry.name), false)
                 };
@@ -157,11 +237,37 @@ impl FileManager {
                         file_path.display().to_string(),
                     ),
                     (t.file_info_size().to_string(), size),
+                ];
+                // Regular files get a checksum, computed in the background
+                let has_checksum = metadata.is_file();
+                if has_checksum {
+                    data.push((
+                        checksum::INFO_KEY.to_string(),
+                        format!("{}...", t.file_info_calculating()),
+                    ));
+                }
+
+                // Show where the symlink points and whether the target exists
+                if is_symlink {
+                    let target = entry
+                        .symlink_target
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default();
+                    let target = if entry.is_broken_symlink {
+                        t.file_info_symlink_broken(&target)
+                    } else {
+                        target
+                    };
+                    data.push((t.file_info_symlink_target().to_string(), target));
+                }
+
+                data.extend([
                     (t.file_info_owner().to_string(), owner),
                     (t.file_info_group().to_string(), group),
                     (t.file_info_created().to_string(), created),
                     (t.file_info_modified().to_string(), modified),
-                ];
+                ]);
 
                 // Add git status if in repository (filtered by specific file/directory)
                 if let Some(git_status) =
@@ -196,8 +302,11 @@ impl FileManager {
                     ActiveModal::Info(Box::new(modal)),
                 ));
 
+                self.checksum_receiver =
+                    has_checksum.then(|| checksum::spawn_checksum(file_path.clone()));
+
                 if is_dir {
-                    let (tx, rx) = mpsc::channel();
+                    let (tx, rx) = termide_wake::channel();
 
                     std::thread::spawn(move || {
                         let size = utils::calculate_dir_size(&file_path);
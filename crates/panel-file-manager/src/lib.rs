@@ -2,32 +2,47 @@
 //!
 //! Provides a smart file manager with git integration, drag selection, and file operations.
 
+pub mod checksum;
+mod deletion;
 mod file_info;
 mod navigation;
 mod operations;
+mod permissions;
 mod rendering;
 mod selection;
+mod transfer;
+mod trash;
 mod utils;
+mod view_memory;
 
+pub use deletion::spawn_delete;
 pub use file_info::FileInfo;
+pub use permissions::spawn_change_permissions;
+pub use transfer::{spawn_transfer, CopyOptions};
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{buffer::Buffer, layout::Rect, prelude::Widget, widgets::Paragraph};
 use std::any::Any;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::mpsc;
 
-use termide_config::{constants, Config, FileManagerSettings};
-use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext, SessionPanel};
+use termide_config::{constants, Config, FileManagerSettings, FileSort};
+use termide_core::{
+    CommandResult, MenuItem, Panel, PanelCommand, PanelEvent, RenderContext, SessionPanel,
+    StatusSegment,
+};
 use termide_git::{get_git_status, GitStatus, GitStatusCache};
-use termide_modal::{ActiveModal, ConfirmModal, InputModal};
-use termide_state::{DirSizeResult, PendingAction};
+use termide_modal::{ActiveModal, ConfirmModal, InputModal, SelectModal, SymlinkModal};
+use termide_session::SessionDirView;
+use termide_state::{ChecksumUpdate, DirSizeResult, PendingAction};
 use termide_theme::Theme;
 use termide_ui::{clipboard, path_utils};
 
+use view_memory::{DirView, ViewMemory};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DragMode {
     Select, // Shift+drag - selection
@@ -56,6 +71,8 @@ pub struct FileManager {
     git_status_cache: Option<GitStatusCache>,
     /// Channel receiver for directory size calculation results (needs to be passed to AppState)
     pub dir_size_receiver: Option<mpsc::Receiver<DirSizeResult>>,
+    /// Channel receiver for the checksum shown in file info (needs to be passed to AppState)
+    pub checksum_receiver: Option<mpsc::Receiver<ChecksumUpdate>>,
     /// Starting index for drag selection
     drag_start_index: Option<usize>,
     /// Drag mode (Shift/Ctrl)
@@ -77,6 +94,16 @@ pub struct FileManager {
     cached_theme: Theme,
     /// Cached config for rendering
     cached_config: FileManagerSettings,
+    /// Directory the entries were loaded from
+    loaded_path: Option<PathBuf>,
+    /// Sort order and hidden files toggle of the current directory
+    view: DirView,
+    /// Views of recently visited directories
+    views: ViewMemory,
+    /// Canonical path of the current directory (for matching open files)
+    canonical_path: PathBuf,
+    /// Canonical paths of files open in editors, with unsaved changes flag
+    open_files: HashMap<PathBuf, bool>,
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +113,10 @@ pub(crate) struct FileEntry {
     #[allow(dead_code)]
     pub is_hidden: bool,
     pub is_symlink: bool,
+    /// Raw symlink target as stored in the link (None for non-symlinks)
+    pub symlink_target: Option<PathBuf>,
+    /// Symlink whose target does not exist
+    pub is_broken_symlink: bool,
     pub is_executable: bool,
     pub is_readonly: bool,
     pub git_status: GitStatus,
@@ -102,7 +133,13 @@ impl FileManager {
 
     /// Create a new smart file manager with the specified path
     pub fn new_with_path(current_path: PathBuf) -> Self {
+        Self::new_with_views(current_path, Vec::new())
+    }
+
+    /// Create a file manager with directory views restored from the session
+    pub fn new_with_views(current_path: PathBuf, views: Vec<SessionDirView>) -> Self {
         let display_title = current_path.display().to_string();
+        let canonical_path = current_path.clone();
         let mut fm = Self {
             current_path,
             entries: Vec::new(),
@@ -116,6 +153,7 @@ impl FileManager {
             selected_items: HashSet::new(),
             git_status_cache: None,
             dir_size_receiver: None,
+            checksum_receiver: None,
             drag_start_index: None,
             drag_mode: None,
             dragged_items: HashSet::new(),
@@ -126,6 +164,11 @@ impl FileManager {
             last_reload_time: None,
             cached_theme: Theme::default(),
             cached_config: FileManagerSettings::default(),
+            loaded_path: None,
+            view: DirView::default(),
+            views: ViewMemory::from_session(views),
+            canonical_path,
+            open_files: HashMap::new(),
         };
         let _ = fm.load_directory();
         fm
@@ -221,14 +264,41 @@ impl FileManager {
 
     /// Internal method to load directory with optional selection preservation
     fn load_directory_inner(&mut self, preserve_selection: bool) -> Result<()> {
+        // On entering another directory, remember the view of the one left
+        // and apply the view saved for the new one
+        let saved_view = if self.loaded_path.as_ref() != Some(&self.current_path) {
+            self.remember_view();
+            let saved_view = self.views.get(&self.current_path).cloned();
+            self.view = DirView {
+                sort: saved_view.as_ref().and_then(|view| view.sort),
+                show_hidden: saved_view.as_ref().and_then(|view| view.show_hidden),
+                ..DirView::default()
+            };
+            self.loaded_path = Some(self.current_path.clone());
+            self.canonical_path =
+                fs::canonicalize(&self.current_path).unwrap_or_else(|_| self.current_path.clone());
+            saved_view
+        } else {
+            None
+        };
+
         // Save current file name and index to restore position
-        // Use previous_dir_name if navigating up, otherwise use current selection
+        // Use previous_dir_name if navigating up, then the saved cursor of
+        // the directory, otherwise use current selection
+        let saved_cursor = saved_view.as_ref().and_then(|view| view.cursor.clone());
+        if saved_cursor.is_some() {
+            // Returning to a directory restores its cursor instead of the top
+            self.navigating_down = false;
+        }
         let current_name = self
             .previous_dir_name
             .take()
+            .or(saved_cursor)
             .or_else(|| self.entries.get(self.selected).map(|e| e.name.clone()));
-        let previous_index = self.selected;
-        let previous_scroll_offset = self.scroll_offset;
+        let (previous_index, previous_scroll_offset) = match &saved_view {
+            Some(view) => (0, view.scroll_offset),
+            None => (self.selected, self.scroll_offset),
+        };
 
         // Save names of selected files if we need to restore selection
         let selected_names: HashSet<String> = if preserve_selection {
@@ -256,6 +326,8 @@ impl FileManager {
         // Load git statuses for the current directory
         self.git_status_cache = get_git_status(&self.current_path);
 
+        let show_hidden = self.show_hidden();
+
         // Add parent directory if not at root
         if self.current_path.parent().is_some() {
             self.entries.push(FileEntry {
@@ -263,6 +335,8 @@ impl FileManager {
                 is_dir: true,
                 is_hidden: false,
                 is_symlink: false,
+                symlink_target: None,
+                is_broken_symlink: false,
                 is_executable: false,
                 is_readonly: false,
                 git_status: GitStatus::Unmodified,
@@ -274,9 +348,27 @@ impl FileManager {
         // Read directory contents
         if let Ok(read_dir) = fs::read_dir(&self.current_path) {
             for entry in read_dir.flatten() {
-                if let Ok(metadata) = entry.metadata() {
+                // DirEntry::metadata() does not follow symlinks
+                if let Ok(link_metadata) = entry.metadata() {
+                    let is_symlink = link_metadata.is_symlink();
+
+                    // For symlinks use target metadata (so linked directories behave
+                    // as directories); fall back to the link itself if target is missing
+                    let (metadata, symlink_target, is_broken_symlink) = if is_symlink {
+                        let target = fs::read_link(entry.path()).ok();
+                        match fs::metadata(entry.path()) {
+                            Ok(target_metadata) => (target_metadata, target, false),
+                            Err(_) => (link_metadata, target, true),
+                        }
+                    } else {
+                        (link_metadata, None, false)
+                    };
+
                     let name = entry.file_name().to_string_lossy().to_string();
                     let is_hidden = name.starts_with('.');
+                    if is_hidden && !show_hidden {
+                        continue;
+                    }
 
                     // Determine git status for this entry
                     let git_status = if metadata.is_dir() {
@@ -293,13 +385,6 @@ impl FileManager {
                             .unwrap_or(GitStatus::Unmodified)
                     };
 
-                    // Check if this is a symlink (use symlink_metadata to not follow links)
-                    let is_symlink = if let Ok(link_metadata) = fs::symlink_metadata(entry.path()) {
-                        link_metadata.is_symlink()
-                    } else {
-                        false
-                    };
-
                     // Check if file is executable (Unix permissions)
                     #[cfg(unix)]
                     let is_executable = {
@@ -332,6 +417,8 @@ impl FileManager {
                         is_dir: metadata.is_dir(),
                         is_hidden,
                         is_symlink,
+                        symlink_target,
+                        is_broken_symlink,
                         is_executable,
                         is_readonly,
                         git_status,
@@ -348,7 +435,9 @@ impl FileManager {
         if let Some(cache) = &self.git_status_cache {
             for deleted_name in cache.get_deleted_files() {
                 // Skip if already in entries (shouldn't happen, but safety check)
-                if self.entries.iter().any(|e| e.name == deleted_name) {
+                if self.entries.iter().any(|e| e.name == deleted_name)
+                    || (deleted_name.starts_with('.') && !show_hidden)
+                {
                     continue;
                 }
                 self.entries.push(FileEntry {
@@ -356,6 +445,8 @@ impl FileManager {
                     is_dir: false, // Assume file (git doesn't track empty dirs)
                     is_hidden: false,
                     is_symlink: false,
+                    symlink_target: None,
+                    is_broken_symlink: false,
                     is_executable: false,
                     is_readonly: false, // Don't show "R" attribute for deleted
                     git_status: GitStatus::Deleted,
@@ -365,11 +456,18 @@ impl FileManager {
             }
         }
 
-        // Sort: directories first, then files
-        self.entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        // Sort: "..", directories, then files, each by the sort order
+        let sort = self.sort();
+        self.entries.sort_by(|a, b| {
+            (b.name == "..")
+                .cmp(&(a.name == ".."))
+                .then_with(|| b.is_dir.cmp(&a.is_dir))
+                .then_with(|| match sort {
+                    FileSort::Name => std::cmp::Ordering::Equal,
+                    FileSort::Size => b.size.cmp(&a.size),
+                    FileSort::Modified => b.modified.cmp(&a.modified),
+                })
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
         });
 
         // Restore selection by file names
@@ -415,6 +513,105 @@ impl FileManager {
         Ok(())
     }
 
+    /// Sort order of the current directory
+    fn sort(&self) -> FileSort {
+        self.view.sort.unwrap_or(self.cached_config.sort)
+    }
+
+    /// Whether the current directory shows hidden files
+    fn show_hidden(&self) -> bool {
+        self.view
+            .show_hidden
+            .unwrap_or(self.cached_config.show_hidden)
+    }
+
+    /// Whether an entry is open in an editor: Some(true) with unsaved changes
+    pub(crate) fn open_file_state(&self, entry: &FileEntry) -> Option<bool> {
+        if self.open_files.is_empty() || entry.is_dir {
+            return None;
+        }
+        // Symlinks match the file they point to
+        let path = if entry.is_symlink {
+            fs::canonicalize(self.current_path.join(&entry.name)).ok()?
+        } else {
+            self.canonical_path.join(&entry.name)
+        };
+        self.open_files.get(&path).copied()
+    }
+
+    /// View of the loaded directory with the cursor and scroll position
+    fn current_view(&self) -> DirView {
+        DirView {
+            cursor: self.entries.get(self.selected).map(|e| e.name.clone()),
+            scroll_offset: self.scroll_offset,
+            ..self.view.clone()
+        }
+    }
+
+    /// Save the view of the loaded directory in the view memory
+    fn remember_view(&mut self) {
+        if let Some(path) = self.loaded_path.clone() {
+            let view = self.current_view();
+            self.views.remember(path, view);
+        }
+    }
+
+    /// Switch to the next sort order in the current directory
+    fn cycle_sort(&mut self) -> PanelEvent {
+        let sort = match self.sort() {
+            FileSort::Name => FileSort::Size,
+            FileSort::Size => FileSort::Modified,
+            FileSort::Modified => FileSort::Name,
+        };
+        self.set_sort(sort)
+    }
+
+    /// Ask for the name of a new file in the current directory
+    fn request_new_file(&mut self) {
+        let t = termide_i18n::t();
+        let modal = InputModal::new(t.modal_create_file_title(), "");
+        let action = PendingAction::CreateFile {
+            panel_index: 0, // will be updated in app.rs
+            directory: self.current_path.clone(),
+        };
+        self.modal_request = Some((action, ActiveModal::Input(Box::new(modal))));
+    }
+
+    /// Sort the current directory by `sort`
+    fn set_sort(&mut self, sort: FileSort) -> PanelEvent {
+        self.view.sort = Some(sort);
+        let _ = self.load_directory_inner(true);
+
+        let t = termide_i18n::t();
+        let message = match sort {
+            FileSort::Name => t.fm_sort_by_name(),
+            FileSort::Size => t.fm_sort_by_size(),
+            FileSort::Modified => t.fm_sort_by_modified(),
+        };
+        PanelEvent::SetStatusMessage {
+            message: message.to_string(),
+            is_error: false,
+        }
+    }
+
+    /// Show or hide hidden files in the current directory
+    fn toggle_hidden(&mut self) -> PanelEvent {
+        let show_hidden = !self.show_hidden();
+        self.view.show_hidden = Some(show_hidden);
+        let _ = self.load_directory_inner(true);
+
+        let t = termide_i18n::t();
+        let message = if show_hidden {
+            t.fm_hidden_shown()
+        } else {
+            t.fm_hidden_hidden()
+        };
+        PanelEvent::SetStatusMessage {
+            message: message.to_string(),
+            is_error: false,
+        }
+    }
+
     /// Get current directory path
     pub fn current_path(&self) -> &std::path::Path {
         &self.current_path
@@ -429,6 +626,11 @@ impl FileManager {
                 return None;
             }
 
+            // Following a dangling link would silently fail
+            if entry.is_broken_symlink {
+                return Some(Self::broken_symlink_error(entry));
+            }
+
             if entry.name == ".." {
                 // Save current directory name before going up
                 if let Some(dir_name) = self.current_path.file_name() {
@@ -446,7 +648,7 @@ impl FileManager {
             } else {
                 // This is a file - emit event to open in editor
                 let file_path = self.current_path.join(&entry.name);
-                return Some(PanelEvent::OpenFile(file_path));
+                return Some(PanelEvent::open_file(file_path));
             }
         }
         None
@@ -461,21 +663,110 @@ impl FileManager {
                 return None;
             }
 
+            if entry.is_broken_symlink {
+                return Some(Self::broken_symlink_error(entry));
+            }
+
             // Check that this is a file, not a directory and not ".."
             if !entry.is_dir && entry.name != ".." {
                 let file_path = self.current_path.join(&entry.name);
-                return Some(PanelEvent::OpenFile(file_path));
+                return Some(PanelEvent::open_file(file_path));
             }
         }
         None
     }
 
+    /// Event comparing the two selected files or directories. A single
+    /// directory is compared with one asked for; any other selection is
+    /// an error.
+    fn compare_selected(&mut self) -> Option<PanelEvent> {
+        // Marked items come in no particular order
+        let mut paths = self.get_selected_paths();
+        paths.sort();
+        match paths.as_slice() {
+            [left, right] if left.is_file() && right.is_file() => Some(PanelEvent::CompareFiles {
+                left: left.clone(),
+                right: right.clone(),
+            }),
+            [left, right] if left.is_dir() && right.is_dir() => {
+                Some(PanelEvent::CompareDirectories {
+                    left: left.clone(),
+                    right: right.clone(),
+                })
+            }
+            [directory] if directory.is_dir() => {
+                // The other side is asked for (app.rs suggests the other file manager)
+                let t = termide_i18n::t();
+                let name = path_utils::get_file_name_str(directory);
+                let modal = InputModal::new(t.modal_compare_title(), t.modal_compare_prompt(name));
+                let action = PendingAction::CompareDirectory {
+                    left: directory.clone(),
+                };
+                self.modal_request = Some((action, ActiveModal::Input(Box::new(modal))));
+                None
+            }
+            _ => Some(PanelEvent::SetStatusMessage {
+                message: termide_i18n::t().diff_select_two().to_string(),
+                is_error: true,
+            }),
+        }
+    }
+
+    /// Build error event for an attempt to follow a broken symlink
+    fn broken_symlink_error(entry: &FileEntry) -> PanelEvent {
+        let t = termide_i18n::t();
+        let target = entry
+            .symlink_target
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        PanelEvent::ShowError(t.fm_broken_symlink(&entry.name, &target))
+    }
+
     /// Format file size in human-readable format (public method for external use)
     pub fn format_size_static(bytes: u64) -> String {
         utils::format_size(bytes)
     }
 }
 
+/// Keys of `handle_key` with the `key_help_*` id describing them.
+const KEY_HELP: &[(&str, &str)] = &[
+    ("Up, Down", "fm_navigate"),
+    ("Enter", "fm_open"),
+    ("Backspace", "fm_parent"),
+    ("~", "fm_home"),
+    ("Home, End", "fm_first_last"),
+    ("PageUp, PageDown", "fm_page"),
+    ("Tab, Shift+Tab", "fm_switch_panel"),
+    ("Insert", "fm_select"),
+    (
+        "Shift+Up, Shift+Down, Shift+Home, Shift+End",
+        "fm_select_range",
+    ),
+    (
+        "Ctrl+Up, Ctrl+Down, Ctrl+PageUp, Ctrl+PageDown",
+        "fm_toggle_range",
+    ),
+    ("Ctrl+A", "fm_select_all"),
+    ("Space", "fm_info"),
+    ("F4", "fm_edit"),
+    ("F", "fm_new_file"),
+    ("D, F7", "fm_new_dir"),
+    ("C, F5", "fm_copy"),
+    ("M, F6", "fm_move"),
+    ("L", "fm_symlink"),
+    ("P", "fm_permissions"),
+    ("Delete, F8", "fm_delete"),
+    ("Shift+Delete", "fm_delete_permanently"),
+    ("T", "fm_restore"),
+    ("=", "fm_compare"),
+    ("U", "fm_duplicates"),
+    ("S", "fm_sort"),
+    (".", "fm_hidden"),
+    ("Ctrl+R", "fm_refresh"),
+    ("Ctrl+C, Ctrl+X, Ctrl+V", "fm_clipboard"),
+];
+
 impl Panel for FileManager {
     fn name(&self) -> &'static str {
         "file_manager"
@@ -485,9 +776,39 @@ impl Panel for FileManager {
         self.display_title.clone()
     }
 
+    fn menu_items(&self) -> Vec<MenuItem> {
+        let t = termide_i18n::t();
+        let sort = self.sort();
+        let sort_item =
+            |id: &str, by: FileSort| MenuItem::toggle(id, t.panel_menu_item(id), sort == by);
+        vec![
+            MenuItem::new("new_file", t.panel_menu_item("new_file")).with_key_hint("F"),
+            MenuItem::new("permissions", t.panel_menu_item("permissions")).with_key_hint("P"),
+            MenuItem::new("duplicates", t.panel_menu_item("duplicates")).with_key_hint("U"),
+            sort_item("sort_name", FileSort::Name),
+            sort_item("sort_size", FileSort::Size),
+            sort_item("sort_modified", FileSort::Modified),
+            MenuItem::toggle("hidden", t.panel_menu_item("hidden"), self.show_hidden())
+                .with_key_hint("."),
+        ]
+    }
+
+    fn key_help(&self) -> Vec<(String, String)> {
+        let t = termide_i18n::t();
+        KEY_HELP
+            .iter()
+            .map(|(keys, id)| (keys.to_string(), t.key_help(id).to_string()))
+            .collect()
+    }
+
     fn prepare_render(&mut self, theme: &termide_theme::Theme, config: &Config) {
         self.cached_theme = *theme;
+        let view = (self.sort(), self.show_hidden());
         self.cached_config = config.file_manager.clone();
+        // Directories without their own view follow the global settings
+        if (self.sort(), self.show_hidden()) != view {
+            let _ = self.load_directory_inner(true);
+        }
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer, ctx: &RenderContext) {
@@ -640,14 +961,7 @@ impl Panel for FileManager {
                 }
             }
             (KeyCode::Char('f'), _) | (KeyCode::Char('F'), _) => {
-                // Create new file - open InputModal
-                let t = termide_i18n::t();
-                let modal = InputModal::new(t.modal_create_file_title(), "");
-                let action = PendingAction::CreateFile {
-                    panel_index: 0, // will be updated in app.rs
-                    directory: self.current_path.clone(),
-                };
-                self.modal_request = Some((action, ActiveModal::Input(Box::new(modal))));
+                self.request_new_file();
             }
             (KeyCode::Char('d'), _) | (KeyCode::Char('D'), _) | (KeyCode::F(7), _) => {
                 // Create new directory - open InputModal
@@ -661,26 +975,91 @@ impl Panel for FileManager {
             }
             (KeyCode::Delete, _) | (KeyCode::F(8), _) => {
                 // Delete selected files/directories - open ConfirmModal
+                // (to trash if enabled; Shift+Delete always deletes permanently)
                 let paths = self.get_selected_paths();
                 if paths.is_empty() {
                     return vec![];
                 }
 
+                let to_trash = self.cached_config.use_trash
+                    && !(key.code == KeyCode::Delete
+                        && key.modifiers.contains(KeyModifiers::SHIFT));
+
                 let t = termide_i18n::t();
-                let title = if paths.len() == 1 {
-                    let file_name = path_utils::get_file_name_str(&paths[0]);
-                    t.modal_delete_single_title(file_name)
-                } else {
-                    t.modal_delete_multiple_title(paths.len())
+                let title = match (paths.len() == 1, to_trash) {
+                    (true, true) => {
+                        t.modal_trash_single_title(path_utils::get_file_name_str(&paths[0]))
+                    }
+                    (true, false) => {
+                        t.modal_delete_single_title(path_utils::get_file_name_str(&paths[0]))
+                    }
+                    (false, true) => t.modal_trash_multiple_title(paths.len()),
+                    (false, false) => t.modal_delete_multiple_title(paths.len()),
                 };
 
                 let modal = ConfirmModal::new(&title, "");
-                let action = PendingAction::DeletePath {
-                    panel_index: 0, // will be updated in app.rs
-                    paths,
+                let action = if to_trash {
+                    PendingAction::TrashPath {
+                        panel_index: 0, // will be updated in app.rs
+                        paths,
+                    }
+                } else {
+                    PendingAction::DeletePath {
+                        panel_index: 0, // will be updated in app.rs
+                        paths,
+                    }
                 };
                 self.modal_request = Some((action, ActiveModal::Confirm(Box::new(modal))));
             }
+            (KeyCode::Char('t'), _) | (KeyCode::Char('T'), _) => {
+                // Restore from trash - pick items in multi-select list
+                let entries = trash::list_all();
+                let t = termide_i18n::t();
+                if entries.is_empty() {
+                    events.push(PanelEvent::SetStatusMessage {
+                        message: t.fm_trash_empty().to_string(),
+                        is_error: false,
+                    });
+                    return events;
+                }
+
+                let labels = entries
+                    .iter()
+                    .map(|e| {
+                        format!(
+                            "{}  {}",
+                            e.deletion_date.replace('T', " "),
+                            e.original_path.display()
+                        )
+                    })
+                    .collect();
+                let modal = SelectModal::multiple(
+                    t.modal_restore_title(),
+                    t.modal_restore_prompt(),
+                    labels,
+                );
+                let action = PendingAction::RestoreFromTrash {
+                    panel_index: 0, // will be updated in app.rs
+                    entries: entries.into_iter().map(|e| e.info_path).collect(),
+                };
+                self.modal_request = Some((action, ActiveModal::Select(Box::new(modal))));
+            }
+            (KeyCode::Char('='), _) => {
+                // Compare the two selected files or directories
+                events.extend(self.compare_selected());
+            }
+            (KeyCode::Char('u'), _) | (KeyCode::Char('U'), _) => {
+                // Look for duplicate files in the current directory
+                events.push(PanelEvent::FindDuplicates(self.current_path.clone()));
+            }
+            (KeyCode::Char('s'), _) | (KeyCode::Char('S'), _) => {
+                // Sort by name, size or modification time
+                events.push(self.cycle_sort());
+            }
+            (KeyCode::Char('.'), _) => {
+                // Show or hide hidden files
+                events.push(self.toggle_hidden());
+            }
             (KeyCode::F(4), _) => {
                 // Open selected file for editing
                 if let Some(event) = self.edit_file() {
@@ -794,7 +1173,34 @@ impl Panel for FileManager {
                 };
                 self.modal_request = Some((action, ActiveModal::Input(Box::new(modal))));
             }
-            // Tab - go to next panel
+            (KeyCode::Char('p'), _) | (KeyCode::Char('P'), _) => {
+                self.request_permissions();
+            }
+            (KeyCode::Char('l'), _) | (KeyCode::Char('L'), _) => {
+                // Create symlink pointing to the item under cursor
+                let Some(entry) = self.entries.get(self.selected) else {
+                    return vec![];
+                };
+                if entry.name == ".." || entry.git_status == GitStatus::Deleted {
+                    return vec![];
+                }
+
+                let target = self.current_path.join(&entry.name);
+                let default_link = format!("{}/", self.current_path.display());
+
+                let t = termide_i18n::t();
+                let modal = SymlinkModal::new(
+                    t.modal_create_symlink_title(),
+                    t.fm_symlink_prompt(&entry.name),
+                    &default_link,
+                );
+                let action = PendingAction::CreateSymlink {
+                    panel_index: 0, // will be updated in app.rs
+                    target,
+                };
+                self.modal_request = Some((action, ActiveModal::Symlink(Box::new(modal))));
+            }
+            // Tab - go to next panel (the other file manager with two open)
             (KeyCode::Tab, KeyModifiers::NONE) => {
                 // Use dummy ConfirmModal that won't be shown
                 let modal = ConfirmModal::new("", "");
@@ -1021,10 +1427,39 @@ impl Panel for FileManager {
                     CommandResult::NeedsRedraw(false)
                 }
             }
+            PanelCommand::SetOpenFiles { files } => {
+                let unchanged = files.len() == self.open_files.len()
+                    && files
+                        .iter()
+                        .all(|(path, dirty)| self.open_files.get(path) == Some(dirty));
+                if !unchanged {
+                    self.open_files = files.iter().cloned().collect();
+                }
+                CommandResult::NeedsRedraw(!unchanged)
+            }
+            PanelCommand::MenuAction { id } => {
+                let event = match id {
+                    "new_file" => {
+                        self.request_new_file();
+                        return CommandResult::Events(Vec::new());
+                    }
+                    "permissions" => {
+                        self.request_permissions();
+                        return CommandResult::Events(Vec::new());
+                    }
+                    "sort_name" => self.set_sort(FileSort::Name),
+                    "sort_size" => self.set_sort(FileSort::Size),
+                    "sort_modified" => self.set_sort(FileSort::Modified),
+                    "hidden" => self.toggle_hidden(),
+                    "duplicates" => PanelEvent::FindDuplicates(self.current_path.clone()),
+                    _ => return CommandResult::None,
+                };
+                CommandResult::Events(vec![event])
+            }
             // Commands not applicable to FileManager
             PanelCommand::GetRepoRoot
             | PanelCommand::OnGitUpdate { .. }
-            | PanelCommand::CheckPendingGitDiff
+            | PanelCommand::RunDeferredWork
             | PanelCommand::CheckGitDiffReceiver
             | PanelCommand::CheckExternalModification
             | PanelCommand::Resize { .. }
@@ -1041,9 +1476,14 @@ impl Panel for FileManager {
     }
 
     fn to_session(&self, _session_dir: &std::path::Path) -> Option<SessionPanel> {
-        // Save file manager with current directory path
+        // Save file manager with current directory path and directory views
+        let mut views = self.views.clone();
+        if let Some(path) = &self.loaded_path {
+            views.remember(path.clone(), self.current_view());
+        }
         Some(SessionPanel::FileManager {
             path: self.current_path.clone(),
+            views: views.to_session(),
         })
     }
 
@@ -1058,6 +1498,10 @@ impl Panel for FileManager {
     fn get_working_directory(&self) -> Option<PathBuf> {
         Some(self.current_path.clone())
     }
+
+    fn status_segments(&self) -> Vec<StatusSegment> {
+        self.status_bar_segments()
+    }
 }
 
 // Additional methods used by app layer (not part of Panel trait)
@@ -1175,6 +1619,213 @@ mod tests {
         assert!(fm.title().contains(&temp_dir.path().display().to_string()));
     }
 
+    #[test]
+    fn test_broken_symlink_entry() {
+        termide_i18n::init_with_language("en");
+        let temp_dir = TempDir::new().unwrap();
+        std::os::unix::fs::symlink("missing", temp_dir.path().join("dangling")).unwrap();
+        let mut fm = FileManager::new_with_path(temp_dir.path().to_path_buf());
+
+        let index = fm
+            .entries
+            .iter()
+            .position(|e| e.name == "dangling")
+            .unwrap();
+        let entry = &fm.entries[index];
+        assert!(entry.is_symlink);
+        assert!(entry.is_broken_symlink);
+        assert_eq!(entry.symlink_target, Some(PathBuf::from("missing")));
+
+        // Enter on a broken symlink reports an error instead of doing nothing
+        fm.selected = index;
+        assert!(matches!(fm.enter(), Some(PanelEvent::ShowError(_))));
+    }
+
+    #[test]
+    fn test_compare_needs_two_files() {
+        termide_i18n::init_with_language("en");
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "a\n").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "b\n").unwrap();
+        let mut fm = FileManager::new_with_path(temp_dir.path().to_path_buf());
+        let index =
+            |fm: &FileManager, name: &str| fm.entries.iter().position(|e| e.name == name).unwrap();
+
+        fm.selected = index(&fm, "a.txt");
+        assert!(matches!(
+            fm.handle_key(KeyEvent::from(KeyCode::Char('='))).as_slice(),
+            [PanelEvent::SetStatusMessage { is_error: true, .. }]
+        ));
+
+        fm.selected_items.insert(index(&fm, "a.txt"));
+        fm.selected_items.insert(index(&fm, "b.txt"));
+        match fm.handle_key(KeyEvent::from(KeyCode::Char('='))).as_slice() {
+            [PanelEvent::CompareFiles { left, right }] => {
+                assert_eq!(left, &temp_dir.path().join("a.txt"));
+                assert_eq!(right, &temp_dir.path().join("b.txt"));
+            }
+            events => panic!("Expected CompareFiles, got {:?}", events),
+        }
+    }
+
+    #[test]
+    fn test_compare_directories() {
+        termide_i18n::init_with_language("en");
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("a")).unwrap();
+        fs::create_dir(temp_dir.path().join("b")).unwrap();
+        let mut fm = FileManager::new_with_path(temp_dir.path().to_path_buf());
+        let index =
+            |fm: &FileManager, name: &str| fm.entries.iter().position(|e| e.name == name).unwrap();
+
+        // A single directory asks for the other side
+        fm.selected = index(&fm, "b");
+        assert!(fm.handle_key(KeyEvent::from(KeyCode::Char('='))).is_empty());
+        match fm.take_modal_request() {
+            Some((PendingAction::CompareDirectory { left }, ActiveModal::Input(_))) => {
+                assert_eq!(left, temp_dir.path().join("b"));
+            }
+            other => panic!("Expected CompareDirectory, got {:?}", other.map(|(a, _)| a)),
+        }
+
+        fm.selected_items.insert(index(&fm, "a"));
+        fm.selected_items.insert(index(&fm, "b"));
+        assert!(matches!(
+            fm.handle_key(KeyEvent::from(KeyCode::Char('='))).as_slice(),
+            [PanelEvent::CompareDirectories { left, right }]
+                if left == &temp_dir.path().join("a") && right == &temp_dir.path().join("b")
+        ));
+    }
+
+    #[test]
+    fn test_view_remembered_per_directory() {
+        termide_i18n::init_with_language("en");
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "a\n").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "bbbb\n").unwrap();
+        fs::write(temp_dir.path().join(".hidden"), "").unwrap();
+        fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        fs::write(temp_dir.path().join("sub").join(".config"), "").unwrap();
+        let mut fm = FileManager::new_with_path(temp_dir.path().to_path_buf());
+        let names = |fm: &FileManager| -> Vec<String> {
+            fm.entries.iter().map(|e| e.name.clone()).collect()
+        };
+
+        fm.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        fm.handle_key(KeyEvent::from(KeyCode::Char('.')));
+        assert_eq!(names(&fm), ["..", "sub", "b.txt", "a.txt"]);
+        fm.selected = 3;
+
+        // Other directories keep the global view
+        fm.navigate_to(temp_dir.path().join("sub")).unwrap();
+        assert_eq!(names(&fm), ["..", ".config"]);
+
+        // Coming back restores the sort, hidden files and cursor
+        fm.navigate_to(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(names(&fm), ["..", "sub", "b.txt", "a.txt"]);
+        assert_eq!(fm.entries[fm.selected].name, "a.txt");
+
+        // The views survive a session restore
+        let Some(SessionPanel::FileManager { path, views }) = fm.to_session(temp_dir.path()) else {
+            panic!("Expected a file manager session panel");
+        };
+        let restored = FileManager::new_with_views(path, views);
+        assert_eq!(names(&restored), ["..", "sub", "b.txt", "a.txt"]);
+        assert_eq!(restored.entries[restored.selected].name, "a.txt");
+    }
+
+    #[test]
+    fn test_open_files_matched_through_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let real = temp_dir.path().join("real");
+        fs::create_dir(&real).unwrap();
+        fs::write(real.join("a.txt"), "a\n").unwrap();
+        fs::write(real.join("b.txt"), "b\n").unwrap();
+        std::os::unix::fs::symlink("a.txt", real.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink("real", temp_dir.path().join("alias")).unwrap();
+        // Browse the directory through a symlink
+        let mut fm = FileManager::new_with_path(temp_dir.path().join("alias"));
+        let state = |fm: &FileManager, name: &str| {
+            let entry = fm.entries.iter().find(|e| e.name == name).unwrap();
+            fm.open_file_state(entry)
+        };
+
+        let files = [(fs::canonicalize(real.join("a.txt")).unwrap(), true)];
+        let result = fm.handle_command(PanelCommand::SetOpenFiles { files: &files });
+        assert!(result.needs_redraw());
+        assert_eq!(state(&fm, "a.txt"), Some(true));
+        assert_eq!(state(&fm, "link.txt"), Some(true));
+        assert_eq!(state(&fm, "b.txt"), None);
+
+        // The same files again need no redraw
+        let result = fm.handle_command(PanelCommand::SetOpenFiles { files: &files });
+        assert!(!result.needs_redraw());
+        let result = fm.handle_command(PanelCommand::SetOpenFiles { files: &[] });
+        assert!(result.needs_redraw());
+        assert_eq!(state(&fm, "a.txt"), None);
+    }
+
+    #[test]
+    fn test_symlinked_directory_is_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("real")).unwrap();
+        std::os::unix::fs::symlink("real", temp_dir.path().join("alias")).unwrap();
+        let fm = FileManager::new_with_path(temp_dir.path().to_path_buf());
+
+        let entry = fm.entries.iter().find(|e| e.name == "alias").unwrap();
+        assert!(entry.is_symlink);
+        assert!(entry.is_dir);
+        assert!(!entry.is_broken_symlink);
+    }
+
+    #[test]
+    fn test_create_symlink_relative() {
+        let (mut fm, temp_dir) = create_file_manager_in_temp();
+        fs::write(temp_dir.path().join("target.txt"), "data").unwrap();
+        fs::create_dir(temp_dir.path().join("links")).unwrap();
+
+        let link = fm
+            .create_symlink(
+                temp_dir.path().join("target.txt"),
+                PathBuf::from("links"),
+                true,
+            )
+            .unwrap();
+
+        assert_eq!(link, temp_dir.path().join("links/target.txt"));
+        assert_eq!(
+            fs::read_link(&link).unwrap(),
+            PathBuf::from("../target.txt")
+        );
+        assert_eq!(fs::read_to_string(&link).unwrap(), "data");
+    }
+
+    #[test]
+    fn test_create_symlink_absolute() {
+        let (mut fm, temp_dir) = create_file_manager_in_temp();
+        let target = temp_dir.path().join("target.txt");
+        fs::write(&target, "data").unwrap();
+
+        let link = fm
+            .create_symlink(target.clone(), temp_dir.path().join("abs_link"), false)
+            .unwrap();
+
+        assert_eq!(fs::read_link(&link).unwrap(), target);
+        assert!(fm.entries.iter().any(|e| e.name == "abs_link"));
+    }
+
+    #[test]
+    fn test_create_symlink_existing_path_fails() {
+        let (mut fm, temp_dir) = create_file_manager_in_temp();
+        let target = temp_dir.path().join("target.txt");
+        fs::write(&target, "data").unwrap();
+        fs::write(temp_dir.path().join("taken"), "").unwrap();
+
+        assert!(fm
+            .create_symlink(target, PathBuf::from("taken"), true)
+            .is_err());
+    }
+
     #[test]
     fn test_file_manager_panel_trait_needs_close_confirmation() {
         let (fm, _temp_dir) = create_file_manager_in_temp();
@@ -1,11 +1,9 @@
-// Allow clippy lints for file operations
-#![allow(clippy::only_used_in_recursion)]
-
 use anyhow::Result;
 use std::fs;
 use std::path::PathBuf;
 
-use super::FileManager;
+use super::{transfer, trash, FileManager};
+use termide_state::CancellationToken;
 use termide_ui::path_utils;
 
 impl FileManager {
@@ -25,6 +23,53 @@ impl FileManager {
         Ok(())
     }
 
+    /// Create a symlink pointing to `target`
+    ///
+    /// `link_path` may be relative to the current directory; if it names an
+    /// existing directory, the link is created inside it with the target's name.
+    /// With `relative` the stored link target is relative to the link's directory.
+    /// Returns the path of the created link.
+    pub fn create_symlink(
+        &mut self,
+        target: PathBuf,
+        link_path: PathBuf,
+        relative: bool,
+    ) -> Result<PathBuf> {
+        let link_path = if link_path.is_absolute() {
+            link_path
+        } else {
+            self.current_path.join(link_path)
+        };
+        let link_path = path_utils::resolve_destination_path(&target, &link_path);
+
+        if fs::symlink_metadata(&link_path).is_ok() {
+            return Err(anyhow::anyhow!(
+                "File or directory already exists: {}",
+                link_path.display()
+            ));
+        }
+
+        let link_target = if relative {
+            let link_dir = link_path.parent().unwrap_or(&self.current_path);
+            path_utils::relative_path(link_dir, &target)
+        } else {
+            target
+        };
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&link_target, &link_path)?;
+        #[cfg(not(unix))]
+        {
+            let _ = link_target;
+            return Err(anyhow::anyhow!(
+                "Symlinks are not supported on this platform"
+            ));
+        }
+
+        self.load_directory()?;
+        Ok(link_path)
+    }
+
     /// Delete file or directory
     pub fn delete_path(&mut self, path: PathBuf) -> Result<()> {
         if path.is_dir() {
@@ -36,93 +81,44 @@ impl FileManager {
         Ok(())
     }
 
-    /// Copy file or directory
-    pub fn copy_path(&mut self, source: PathBuf, destination: PathBuf) -> Result<()> {
-        if source.is_dir() {
-            self.copy_directory_recursive(&source, &destination)?;
-        } else {
-            let dest_path = path_utils::resolve_destination_path(&source, &destination);
-            fs::copy(&source, &dest_path)?;
-        }
+    /// Move file or directory to trash
+    pub fn trash_path(&mut self, path: PathBuf) -> Result<()> {
+        trash::move_to_trash(&path)?;
         self.load_directory()?;
         Ok(())
     }
 
-    /// Recursively copy directory
-    fn copy_directory_recursive(&self, source: &PathBuf, destination: &PathBuf) -> Result<()> {
-        self.copy_directory_recursive_with_depth(source, destination, 0)
+    /// Restore trashed item (by its `.trashinfo` path) to original location
+    pub fn restore_from_trash(&mut self, info_path: PathBuf) -> Result<PathBuf> {
+        let restored = trash::Trash::restore(&info_path)?;
+        self.load_directory()?;
+        Ok(restored)
     }
 
-    /// Recursively copy directory with depth limit
-    fn copy_directory_recursive_with_depth(
-        &self,
-        source: &PathBuf,
-        destination: &PathBuf,
-        depth: usize,
-    ) -> Result<()> {
-        const MAX_DEPTH: usize = termide_ui::constants::MAX_DIRECTORY_COPY_DEPTH;
-
-        if depth > MAX_DEPTH {
-            return Err(anyhow::anyhow!(
-                "Directory nesting too deep (> {})",
-                MAX_DEPTH
-            ));
-        }
-
-        // Create target directory if it doesn't exist
-        if !destination.exists() {
-            fs::create_dir_all(destination)?;
-        }
-
-        for entry in fs::read_dir(source)? {
-            let entry = entry?;
-            let source_path = entry.path();
-            let dest_path = destination.join(entry.file_name());
-
-            // Check metadata without following symlinks
-            let metadata = fs::symlink_metadata(&source_path)?;
-
-            if metadata.is_symlink() {
-                // Copy symlink as symlink (don't follow it)
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs as unix_fs;
-                    let link_target = fs::read_link(&source_path)?;
-                    unix_fs::symlink(link_target, &dest_path)?;
-                }
-                #[cfg(not(unix))]
-                {
-                    // On Windows, just copy as file
-                    fs::copy(&source_path, &dest_path)?;
-                }
-            } else if metadata.is_dir() {
-                // Recursively copy directory with incremented depth counter
-                self.copy_directory_recursive_with_depth(&source_path, &dest_path, depth + 1)?;
-            } else {
-                // Regular file
-                fs::copy(&source_path, &dest_path)?;
-            }
-        }
-
+    /// Copy file or directory
+    pub fn copy_path(&mut self, source: PathBuf, destination: PathBuf) -> Result<()> {
+        let options = transfer::CopyOptions {
+            dereference_symlinks: self.cached_config.copy_dereference_symlinks,
+        };
+        transfer::copy_with_progress(
+            &source,
+            &destination,
+            options,
+            &CancellationToken::new(),
+            &mut |_| {},
+        )?;
+        self.load_directory()?;
         Ok(())
     }
 
     /// Move file or directory
     pub fn move_path(&mut self, source: PathBuf, destination: PathBuf) -> Result<()> {
-        let dest_path = path_utils::resolve_destination_path(&source, &destination);
-
-        // Try simple rename (works only within same filesystem)
-        if fs::rename(&source, &dest_path).is_err() {
-            // If that failed - copy and delete
-            if source.is_dir() {
-                self.copy_directory_recursive(&source, &dest_path)?;
-                fs::remove_dir_all(&source)?;
-            } else {
-                fs::copy(&source, &dest_path)?;
-                fs::remove_file(&source)?;
-            }
-        }
-
+        transfer::move_with_progress(
+            &source,
+            &destination,
+            &CancellationToken::new(),
+            &mut |_| {},
+        )?;
         self.load_directory()?;
         Ok(())
     }
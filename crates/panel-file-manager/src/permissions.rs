@@ -0,0 +1,474 @@
+//! Changing permissions and ownership of files.
+//!
+//! A change holds the mode bits to set and to clear, so every item keeps
+//! the bits the user did not touch. Below a directory, links are not
+//! followed, and added execute bits only go to directories and to files
+//! some already execute (like `chmod -R +X`). Recursive changes run on a
+//! worker thread (`spawn_change_permissions`) that streams progress and
+//! checks for cancellation between items.
+
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use anyhow::{anyhow, Result};
+
+use super::deletion::count_entries;
+use super::FileManager;
+use termide_modal::{ActiveModal, PermissionsModal};
+use termide_state::{
+    CancellationToken, PendingAction, PermissionsChange, PermissionsProgressUpdate,
+    PermissionsReport,
+};
+
+/// Execute bits of owner, group and others
+const EXECUTE_BITS: u32 = 0o111;
+
+/// Changed items between progress reports
+const PROGRESS_EVERY: usize = 100;
+
+/// Names from the first field of `/etc/passwd`-style lines
+fn parse_names(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split(':').next())
+        .filter(|name| !name.is_empty() && !name.starts_with(['+', '-']))
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Names of the local users
+fn user_names() -> Vec<String> {
+    parse_names(&fs::read_to_string("/etc/passwd").unwrap_or_default())
+}
+
+/// Names of the local groups
+fn group_names() -> Vec<String> {
+    parse_names(&fs::read_to_string("/etc/group").unwrap_or_default())
+}
+
+/// Whether owners can be changed: only the superuser can give files away
+fn can_change_owner() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// UID of a user name
+fn user_id(name: &str) -> Result<u32> {
+    let c_name = CString::new(name)?;
+    // SAFETY: getpwnam receives a valid null-terminated string and returns
+    // a pointer to a static passwd struct or NULL, which is checked before
+    // reading pw_uid; nothing is kept past this call.
+    unsafe {
+        let pwd = libc::getpwnam(c_name.as_ptr());
+        if pwd.is_null() {
+            Err(anyhow!("Unknown user: {}", name))
+        } else {
+            Ok((*pwd).pw_uid)
+        }
+    }
+}
+
+/// GID of a group name
+fn group_id(name: &str) -> Result<u32> {
+    let c_name = CString::new(name)?;
+    // SAFETY: getgrnam receives a valid null-terminated string and returns
+    // a pointer to a static group struct or NULL, which is checked before
+    // reading gr_gid; nothing is kept past this call.
+    unsafe {
+        let grp = libc::getgrnam(c_name.as_ptr());
+        if grp.is_null() {
+            Err(anyhow!("Unknown group: {}", name))
+        } else {
+            Ok((*grp).gr_gid)
+        }
+    }
+}
+
+/// Applies one change to items and, when recursive, below directories
+struct Apply<'a> {
+    change: &'a PermissionsChange,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    cancel: &'a CancellationToken,
+    on_progress: &'a mut dyn FnMut(usize, &Path),
+    report: PermissionsReport,
+}
+
+impl Apply<'_> {
+    /// Whether to stop, marking the report cancelled
+    fn stopped(&mut self) -> bool {
+        if self.cancel.is_cancelled() {
+            self.report.cancelled = true;
+        }
+        self.report.cancelled
+    }
+
+    /// Change a selected item; links are followed like `chmod` does
+    fn item(&mut self, path: &Path) {
+        if self.stopped() {
+            return;
+        }
+        let result = fs::metadata(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|metadata| self.change_one(path, &metadata, true));
+        self.record(path, result);
+        if self.change.recursive && fs::symlink_metadata(path).is_ok_and(|m| m.is_dir()) {
+            self.below(path);
+        }
+    }
+
+    /// Change everything inside a directory, without following links
+    fn below(&mut self, directory: &Path) {
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.report
+                    .failures
+                    .push((directory.to_path_buf(), e.to_string()));
+                return;
+            }
+        };
+        for entry in entries.filter_map(Result::ok) {
+            if self.stopped() {
+                return;
+            }
+            let path = entry.path();
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if metadata.file_type().is_symlink() {
+                continue;
+            }
+            let result = self.change_one(&path, &metadata, false);
+            self.record(&path, result);
+            if metadata.is_dir() {
+                self.below(&path);
+            }
+        }
+    }
+
+    fn record(&mut self, path: &Path, result: Result<()>) {
+        match result {
+            Ok(()) => {
+                self.report.changed += 1;
+                if self.report.changed.is_multiple_of(PROGRESS_EVERY) {
+                    (self.on_progress)(self.report.changed, path);
+                }
+            }
+            Err(e) => self
+                .report
+                .failures
+                .push((path.to_path_buf(), e.to_string())),
+        }
+    }
+
+    fn change_one(&self, path: &Path, metadata: &fs::Metadata, selected: bool) -> Result<()> {
+        if self.uid.is_some() || self.gid.is_some() {
+            std::os::unix::fs::chown(path, self.uid, self.gid)?;
+        }
+        let mut set = self.change.set;
+        if !selected && !metadata.is_dir() && metadata.mode() & EXECUTE_BITS == 0 {
+            set &= !EXECUTE_BITS;
+        }
+        if set != 0 || self.change.clear != 0 {
+            let old = metadata.mode() & 0o7777;
+            let mode = (old & !self.change.clear) | set;
+            // Changing the owner clears set UID and set GID; set the mode
+            // even when unchanged so they come back as asked
+            if mode != old || self.uid.is_some() || self.gid.is_some() {
+                fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Apply a change to items
+pub fn apply_change(paths: &[PathBuf], change: &PermissionsChange) -> PermissionsReport {
+    apply_change_with_progress(paths, change, &CancellationToken::new(), &mut |_, _| {})
+}
+
+/// Apply a change to items
+///
+/// `on_progress` gets the number of changed items and the path being
+/// changed every `PROGRESS_EVERY` items.
+pub fn apply_change_with_progress(
+    paths: &[PathBuf],
+    change: &PermissionsChange,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(usize, &Path),
+) -> PermissionsReport {
+    let mut apply = Apply {
+        change,
+        uid: None,
+        gid: None,
+        cancel,
+        on_progress,
+        report: PermissionsReport::default(),
+    };
+    let ids = change
+        .owner
+        .as_deref()
+        .map(user_id)
+        .transpose()
+        .and_then(|uid| Ok((uid, change.group.as_deref().map(group_id).transpose()?)));
+    match ids {
+        Ok((uid, gid)) => {
+            apply.uid = uid;
+            apply.gid = gid;
+            for path in paths {
+                apply.item(path);
+            }
+        }
+        Err(e) => {
+            for path in paths {
+                apply.report.failures.push((path.clone(), e.to_string()));
+            }
+        }
+    }
+    apply.report
+}
+
+/// Apply a change on a worker thread
+///
+/// The worker first reports the estimated number of items (`Total`), then
+/// streams `Changed` updates and ends with `Finished`.
+pub fn spawn_change_permissions(
+    paths: Vec<PathBuf>,
+    change: PermissionsChange,
+    cancel: CancellationToken,
+) -> mpsc::Receiver<PermissionsProgressUpdate> {
+    let (tx, rx) = termide_wake::channel();
+
+    thread::spawn(move || {
+        let total = if change.recursive {
+            count_entries(&paths, &cancel)
+        } else {
+            paths.len()
+        };
+        let _ = tx.send(PermissionsProgressUpdate::Total(total));
+
+        let mut on_progress = |count, current: &Path| {
+            let _ = tx.send(PermissionsProgressUpdate::Changed {
+                count,
+                current: current.to_path_buf(),
+            });
+        };
+        let report = apply_change_with_progress(&paths, &change, &cancel, &mut on_progress);
+        let _ = tx.send(PermissionsProgressUpdate::Finished(report));
+    });
+
+    rx
+}
+
+impl FileManager {
+    /// Open the permissions dialog for the selected items
+    pub(crate) fn request_permissions(&mut self) {
+        let paths = self.get_selected_paths();
+        let Some(first) = paths.first() else {
+            return;
+        };
+        let Ok(metadata) = fs::metadata(first).or_else(|_| fs::symlink_metadata(first)) else {
+            return;
+        };
+
+        let t = termide_i18n::t();
+        let title = if paths.len() == 1 {
+            t.permissions_title(&first.file_name().unwrap_or_default().to_string_lossy())
+        } else {
+            t.permissions_title_multiple(paths.len())
+        };
+        let mut modal = PermissionsModal::new(
+            title,
+            metadata.mode(),
+            &super::utils::get_user_name(metadata.uid()),
+            &super::utils::get_group_name(metadata.gid()),
+        );
+        if can_change_owner() {
+            modal = modal.with_ownership(user_names(), group_names());
+        }
+        if paths.iter().any(|path| path.is_dir()) {
+            modal = modal.with_directories();
+        }
+
+        let action = PendingAction::ChangePermissions {
+            panel_index: 0, // will be updated in app.rs
+            paths,
+        };
+        self.modal_request = Some((action, ActiveModal::Permissions(Box::new(modal))));
+    }
+
+    /// Apply a change to items and reload the listing, so the read-only
+    /// marks show the new permissions
+    pub fn change_permissions(
+        &mut self,
+        paths: &[PathBuf],
+        change: &PermissionsChange,
+    ) -> PermissionsReport {
+        let report = apply_change(paths, change);
+        let _ = self.load_directory_inner(true);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(path: &Path) -> u32 {
+        fs::symlink_metadata(path).unwrap().mode() & 0o7777
+    }
+
+    fn set_mode(path: &Path, mode: u32) {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_parse_names() {
+        let passwd = "root:x:0:0:root:/root:/bin/bash\n\
+                      # comment\n\
+                      \n\
+                      daemon:x:1:1::/:/usr/sbin/nologin\n\
+                      +nis\n\
+                      root:x:0:0::/:/bin/sh\n";
+        assert_eq!(parse_names(passwd), ["daemon", "root"]);
+    }
+
+    #[test]
+    fn test_apply_keeps_other_bits() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        fs::write(&a, "").unwrap();
+        fs::write(&b, "").unwrap();
+        set_mode(&a, 0o644);
+        set_mode(&b, 0o600);
+
+        // Owner execute on, owner write off
+        let change = PermissionsChange {
+            set: 0o100,
+            clear: 0o200,
+            ..Default::default()
+        };
+        let report = apply_change(&[a.clone(), b.clone()], &change);
+        assert_eq!(report.changed, 2);
+        assert!(report.failures.is_empty());
+        assert_eq!(mode(&a), 0o544);
+        assert_eq!(mode(&b), 0o500);
+    }
+
+    #[test]
+    fn test_recursive_adds_execute_like_capital_x() {
+        let dir = tempfile::tempdir().unwrap();
+        let top = dir.path().join("top");
+        let sub = top.join("sub");
+        fs::create_dir_all(&sub).unwrap();
+        let plain = sub.join("plain");
+        let script = sub.join("script");
+        fs::write(&plain, "").unwrap();
+        fs::write(&script, "").unwrap();
+        set_mode(&plain, 0o600);
+        set_mode(&script, 0o700);
+        set_mode(&sub, 0o700);
+        std::os::unix::fs::symlink("/", top.join("link")).unwrap();
+
+        let change = PermissionsChange {
+            set: 0o055,
+            recursive: true,
+            ..Default::default()
+        };
+        let report = apply_change(std::slice::from_ref(&top), &change);
+        // The link is left alone
+        assert_eq!(report.total(), 4);
+        assert_eq!(mode(&sub), 0o755);
+        assert_eq!(mode(&plain), 0o644);
+        assert_eq!(mode(&script), 0o755);
+
+        let flat = PermissionsChange {
+            clear: 0o055,
+            ..Default::default()
+        };
+        let report = apply_change(std::slice::from_ref(&top), &flat);
+        assert_eq!(report.total(), 1);
+        assert_eq!(mode(&sub), 0o755);
+    }
+
+    #[test]
+    fn test_failures_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let change = PermissionsChange {
+            set: 0o100,
+            ..Default::default()
+        };
+        let report = apply_change(std::slice::from_ref(&missing), &change);
+        assert_eq!(report.changed, 0);
+        assert_eq!(report.failures[0].0, missing);
+
+        let unknown = PermissionsChange {
+            owner: Some("no such user here".to_string()),
+            ..Default::default()
+        };
+        let report = apply_change(&[dir.path().to_path_buf()], &unknown);
+        assert_eq!(report.failures.len(), 1);
+    }
+
+    #[test]
+    fn test_worker_reports_progress_and_stops_on_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let top = dir.path().join("top");
+        fs::create_dir(&top).unwrap();
+        for i in 0..250 {
+            fs::write(top.join(i.to_string()), "").unwrap();
+        }
+        let change = PermissionsChange {
+            clear: 0o022,
+            recursive: true,
+            ..Default::default()
+        };
+
+        let receiver =
+            spawn_change_permissions(vec![top.clone()], change.clone(), CancellationToken::new());
+        let updates: Vec<_> = receiver.iter().collect();
+        assert_eq!(updates[0], PermissionsProgressUpdate::Total(251));
+        let counts: Vec<usize> = updates
+            .iter()
+            .filter_map(|update| match update {
+                PermissionsProgressUpdate::Changed { count, .. } => Some(*count),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(counts, vec![100, 200]);
+        match updates.last() {
+            Some(PermissionsProgressUpdate::Finished(report)) => {
+                assert_eq!(report.changed, 251);
+                assert!(!report.cancelled);
+            }
+            other => panic!("unexpected last update: {:?}", other),
+        }
+
+        let cancel = CancellationToken::new();
+        let mut on_progress = |count, _: &Path| {
+            if count == 100 {
+                cancel.cancel();
+            }
+        };
+        let report = apply_change_with_progress(
+            std::slice::from_ref(&top),
+            &change,
+            &cancel,
+            &mut on_progress,
+        );
+        assert!(report.cancelled);
+        assert_eq!(report.changed, 100);
+    }
+}
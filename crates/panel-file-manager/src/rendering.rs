@@ -4,11 +4,14 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-use super::{utils, FileManager};
+use super::{utils, FileEntry, FileManager};
 use termide_config::FileManagerSettings;
 use termide_git::GitStatus;
 use termide_theme::Theme;
 
+/// Marker after the name of files open in an editor
+const OPEN_MARKER: &str = " ●";
+
 impl FileManager {
     /// Get display title with path
     /// Truncates path from left if it doesn't fit in available width
@@ -78,6 +81,15 @@ impl FileManager {
             };
             let prefix_width = dir_prefix.width();
 
+            // Files open in an editor get a marker after the name
+            let open_state = self.open_file_state(entry);
+            let marker = if open_state.is_some() {
+                OPEN_MARKER
+            } else {
+                ""
+            };
+            let marker_width = marker.width();
+
             // Calculate maximum visual width of name WITHOUT prefix, considering display mode
             let max_name_len = if show_extended {
                 // For wide mode: attr + icon + space + prefix + two columns and two separators
@@ -86,6 +98,7 @@ impl FileManager {
                         + icon_width
                         + 1
                         + prefix_width
+                        + marker_width
                         + SEPARATOR_WIDTH
                         + SIZE_COLUMN_WIDTH
                         + SEPARATOR_WIDTH
@@ -93,24 +106,24 @@ impl FileManager {
                 )
             } else {
                 // For normal mode: attr + icon + space + prefix
-                available_width.saturating_sub(attr_width + icon_width + 1 + prefix_width)
+                available_width
+                    .saturating_sub(attr_width + icon_width + 1 + prefix_width + marker_width)
             };
 
-            let name = utils::truncate_name(&entry.name, max_name_len);
+            // Optionally show symlink target inline: "name → target"
+            let display_name = match &entry.symlink_target {
+                Some(target) if config.show_symlink_targets => {
+                    format!("{} → {}", entry.name, target.display())
+                }
+                _ => entry.name.clone(),
+            };
+            let name = utils::truncate_name(&display_name, max_name_len);
             let name_width = name.width();
             let full_name = format!("{}{}", dir_prefix, name);
 
             let (bg_style, fg_style) = if is_cursor && is_focused {
                 // Вычислить нормальный fg_style для этой строки
-                let normal_fg_style = match entry.git_status {
-                    GitStatus::Ignored => Style::default()
-                        .fg(theme.disabled)
-                        .add_modifier(Modifier::DIM),
-                    GitStatus::Modified => Style::default().fg(theme.warning),
-                    GitStatus::Added => Style::default().fg(theme.success),
-                    GitStatus::Deleted => Style::default().fg(theme.error),
-                    GitStatus::Unmodified => Style::default().fg(theme.fg),
-                };
+                let normal_fg_style = entry_style(entry, theme);
 
                 // Извлечь fg цвет и создать инверсный стиль курсора
                 let fg_color = normal_fg_style.fg.unwrap_or(theme.fg);
@@ -121,16 +134,7 @@ impl FileManager {
 
                 (cursor_style, cursor_style)
             } else {
-                let fg_style = match entry.git_status {
-                    GitStatus::Ignored => Style::default()
-                        .fg(theme.disabled)
-                        .add_modifier(Modifier::DIM),
-                    GitStatus::Modified => Style::default().fg(theme.warning),
-                    GitStatus::Added => Style::default().fg(theme.success),
-                    GitStatus::Deleted => Style::default().fg(theme.error),
-                    GitStatus::Unmodified => Style::default().fg(theme.fg),
-                };
-                (Style::default(), fg_style)
+                (Style::default(), entry_style(entry, theme))
             };
 
             let attr_style = if is_selected {
@@ -165,6 +169,20 @@ impl FileManager {
                 fg_style
             };
 
+            // Marker in the accent color (cursor colors on the cursor line),
+            // bold together with the name for unsaved changes
+            let mut marker_style = if is_cursor && is_focused {
+                fg_style
+            } else {
+                Style::default().fg(theme.accented_fg)
+            };
+            let name_style = if open_state == Some(true) {
+                marker_style = marker_style.add_modifier(Modifier::BOLD);
+                name_style.add_modifier(Modifier::BOLD)
+            } else {
+                name_style
+            };
+
             if show_extended {
                 // Extended mode with columns
                 // Use name_width without prefix, since max_name_len already accounted for prefix_width when subtracting
@@ -186,6 +204,7 @@ impl FileManager {
                     Span::styled(icon, icon_style),
                     Span::styled(" ", bg_style),
                     Span::styled(full_name, name_style),
+                    Span::styled(marker, marker_style),
                     Span::styled(padding, bg_style),
                     Span::styled(SEPARATOR, bg_style.fg(theme.disabled)),
                     Span::styled(size_str, fg_style),
@@ -194,7 +213,8 @@ impl FileManager {
                 ]));
             } else {
                 // Normal mode without columns
-                let content_width = attr_width + icon_width + 1 + prefix_width + name_width;
+                let content_width =
+                    attr_width + icon_width + 1 + prefix_width + name_width + marker_width;
                 let padding_len = available_width.saturating_sub(content_width);
                 let padding = " ".repeat(padding_len);
 
@@ -203,6 +223,7 @@ impl FileManager {
                     Span::styled(icon, icon_style),
                     Span::styled(" ", bg_style),
                     Span::styled(full_name, name_style),
+                    Span::styled(marker, marker_style),
                     Span::styled(padding, bg_style),
                 ]));
             }
@@ -232,3 +253,23 @@ impl FileManager {
         lines
     }
 }
+
+/// Foreground style of an entry based on its git status
+/// (broken symlinks get their own style regardless of status)
+fn entry_style(entry: &FileEntry, theme: &Theme) -> Style {
+    if entry.is_broken_symlink {
+        return Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::ITALIC);
+    }
+
+    match entry.git_status {
+        GitStatus::Ignored => Style::default()
+            .fg(theme.disabled)
+            .add_modifier(Modifier::DIM),
+        GitStatus::Modified => Style::default().fg(theme.warning),
+        GitStatus::Added => Style::default().fg(theme.success),
+        GitStatus::Deleted => Style::default().fg(theme.error),
+        GitStatus::Unmodified => Style::default().fg(theme.fg),
+    }
+}
@@ -0,0 +1,661 @@
+//! File copy/move engine with byte-level progress and cancellation.
+//!
+//! Batch operations run each item on a worker thread (`spawn_transfer`),
+//! which copies in fixed-size chunks and streams `BatchProgressUpdate`s back
+//! to the app. Cancellation is checked between chunks; partially written
+//! output is removed when a transfer is cancelled.
+//!
+//! Copies preserve permissions and access/modification times, recreate
+//! symlinks as symlinks (unless dereferencing is requested) and keep files
+//! hardlinked within the copied tree hardlinked in the copy.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs::{self, File, Metadata};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use termide_state::{BatchOperationType, BatchProgressUpdate, CancellationToken};
+use termide_ui::path_utils;
+
+use super::utils;
+
+/// Size of a single copy chunk (cancellation is checked between chunks)
+const COPY_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Outcome of a transfer that did not fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOutcome {
+    /// Everything was copied/moved
+    Completed,
+    /// Stopped on cancellation request (partial output removed)
+    Cancelled,
+}
+
+/// Copy behaviour options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    /// Copy what symlinks point to instead of the symlinks themselves
+    pub dereference_symlinks: bool,
+}
+
+/// Copy file, directory or symlink, reporting copied bytes through `on_progress`
+///
+/// A directory is copied to `destination`; anything else is copied to
+/// `destination` or into it, if it is an existing directory.
+pub fn copy_with_progress(
+    source: &Path,
+    destination: &Path,
+    options: CopyOptions,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(u64),
+) -> Result<TransferOutcome> {
+    let mut copier = Copier::new(options, cancel, on_progress);
+    let metadata = copier.metadata(source)?;
+    if metadata.is_dir() {
+        copier.copy_tree(source, destination, &metadata)
+    } else {
+        let dest_path = path_utils::resolve_destination_path(source, destination);
+        copier.copy_entry(source, &dest_path, &metadata, 0)
+    }
+}
+
+/// Move file, directory or symlink, falling back to copy+delete across filesystems
+///
+/// The source is removed only after the copy completed; a cancelled move
+/// leaves the source untouched.
+pub fn move_with_progress(
+    source: &Path,
+    destination: &Path,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(u64),
+) -> Result<TransferOutcome> {
+    let dest_path = path_utils::resolve_destination_path(source, destination);
+
+    // Try simple rename (works only within same filesystem)
+    if fs::rename(source, &dest_path).is_ok() {
+        return Ok(TransferOutcome::Completed);
+    }
+
+    // Moved symlinks must stay symlinks
+    let mut copier = Copier::new(CopyOptions::default(), cancel, on_progress);
+    let metadata = copier.metadata(source)?;
+    if metadata.is_dir() {
+        let outcome = copier.copy_tree(source, &dest_path, &metadata)?;
+        if outcome == TransferOutcome::Completed {
+            fs::remove_dir_all(source)?;
+        }
+        Ok(outcome)
+    } else {
+        let outcome = copier.copy_entry(source, &dest_path, &metadata, 0)?;
+        if outcome == TransferOutcome::Completed {
+            fs::remove_file(source)?;
+        }
+        Ok(outcome)
+    }
+}
+
+/// Total size in bytes of files and directories (symlinks inside directories are not followed)
+pub fn total_size(paths: &[PathBuf]) -> u64 {
+    paths
+        .iter()
+        .map(|path| match fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => utils::calculate_dir_size(path),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Run copy/move of a single batch item on a worker thread
+///
+/// With `scan`, the worker first reports total size of those paths
+/// (`TotalBytes`). It then streams `Bytes` updates and ends with either
+/// `Finished` or `Cancelled`.
+pub fn spawn_transfer(
+    operation_type: BatchOperationType,
+    source: PathBuf,
+    destination: PathBuf,
+    options: CopyOptions,
+    cancel: CancellationToken,
+    scan: Option<Vec<PathBuf>>,
+) -> mpsc::Receiver<BatchProgressUpdate> {
+    let (tx, rx) = termide_wake::channel();
+
+    thread::spawn(move || {
+        if let Some(paths) = scan {
+            let _ = tx.send(BatchProgressUpdate::TotalBytes(total_size(&paths)));
+        }
+
+        let mut on_progress = |bytes| {
+            let _ = tx.send(BatchProgressUpdate::Bytes(bytes));
+        };
+        let result = match operation_type {
+            BatchOperationType::Copy => {
+                copy_with_progress(&source, &destination, options, &cancel, &mut on_progress)
+            }
+            BatchOperationType::Move => {
+                move_with_progress(&source, &destination, &cancel, &mut on_progress)
+            }
+        };
+
+        let update = match result {
+            Ok(TransferOutcome::Completed) => BatchProgressUpdate::Finished(Ok(())),
+            Ok(TransferOutcome::Cancelled) => BatchProgressUpdate::Cancelled,
+            Err(e) => BatchProgressUpdate::Finished(Err(e.to_string())),
+        };
+        let _ = tx.send(update);
+    });
+
+    rx
+}
+
+/// Recursively copy directory (no progress, not cancellable)
+pub(crate) fn copy_directory_recursive(source: &Path, destination: &Path) -> Result<()> {
+    let cancel = CancellationToken::new();
+    let mut on_progress = |_| {};
+    let mut copier = Copier::new(CopyOptions::default(), &cancel, &mut on_progress);
+    let metadata = fs::metadata(source)?;
+    copier.copy_dir(source, destination, &metadata, 0)?;
+    Ok(())
+}
+
+/// Chunked copier shared by copy and move
+struct Copier<'a> {
+    options: CopyOptions,
+    cancel: &'a CancellationToken,
+    on_progress: &'a mut dyn FnMut(u64),
+    /// Copies of multiply-linked files by source (device, inode)
+    hardlinks: HashMap<(u64, u64), PathBuf>,
+}
+
+impl<'a> Copier<'a> {
+    fn new(
+        options: CopyOptions,
+        cancel: &'a CancellationToken,
+        on_progress: &'a mut dyn FnMut(u64),
+    ) -> Self {
+        Self {
+            options,
+            cancel,
+            on_progress,
+            hardlinks: HashMap::new(),
+        }
+    }
+
+    /// Source metadata, following symlinks only when dereferencing
+    /// (broken symlinks are always copied as symlinks)
+    fn metadata(&self, path: &Path) -> Result<Metadata> {
+        if self.options.dereference_symlinks {
+            if let Ok(metadata) = fs::metadata(path) {
+                return Ok(metadata);
+            }
+        }
+        Ok(fs::symlink_metadata(path)?)
+    }
+
+    /// Copy directory tree, removing it on cancellation if it was created by this copy
+    fn copy_tree(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        metadata: &Metadata,
+    ) -> Result<TransferOutcome> {
+        let existed = destination.exists();
+        let outcome = self.copy_dir(source, destination, metadata, 0)?;
+        if outcome == TransferOutcome::Cancelled && !existed {
+            let _ = fs::remove_dir_all(destination);
+        }
+        Ok(outcome)
+    }
+
+    /// Copy single entry of any type
+    fn copy_entry(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        metadata: &Metadata,
+        depth: usize,
+    ) -> Result<TransferOutcome> {
+        if metadata.is_symlink() {
+            self.copy_symlink(source, destination, metadata)?;
+            Ok(TransferOutcome::Completed)
+        } else if metadata.is_dir() {
+            self.copy_dir(source, destination, metadata, depth)
+        } else {
+            self.copy_file(source, destination, metadata)
+        }
+    }
+
+    /// Recursively copy directory with depth limit
+    fn copy_dir(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        metadata: &Metadata,
+        depth: usize,
+    ) -> Result<TransferOutcome> {
+        const MAX_DEPTH: usize = termide_ui::constants::MAX_DIRECTORY_COPY_DEPTH;
+
+        if depth > MAX_DEPTH {
+            return Err(anyhow::anyhow!(
+                "Directory nesting too deep (> {})",
+                MAX_DEPTH
+            ));
+        }
+
+        // Create target directory if it doesn't exist
+        if !destination.exists() {
+            fs::create_dir_all(destination)?;
+        }
+
+        for entry in fs::read_dir(source)? {
+            if self.cancel.is_cancelled() {
+                return Ok(TransferOutcome::Cancelled);
+            }
+
+            let entry = entry?;
+            let source_path = entry.path();
+            let dest_path = destination.join(entry.file_name());
+            let entry_metadata = self.metadata(&source_path)?;
+
+            let outcome = self.copy_entry(&source_path, &dest_path, &entry_metadata, depth + 1)?;
+            if outcome == TransferOutcome::Cancelled {
+                return Ok(outcome);
+            }
+        }
+
+        // Apply metadata after contents: a read-only directory can't be filled,
+        // and adding entries would reset its modification time
+        set_times(destination, metadata);
+        fs::set_permissions(destination, metadata.permissions())?;
+        Ok(TransferOutcome::Completed)
+    }
+
+    /// Recreate symlink with the same target
+    fn copy_symlink(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        metadata: &Metadata,
+    ) -> Result<()> {
+        // Replace existing file or symlink (overwrite confirmed by user)
+        if fs::symlink_metadata(destination).is_ok_and(|m| !m.is_dir()) {
+            fs::remove_file(destination)?;
+        }
+
+        #[cfg(unix)]
+        {
+            let link_target = fs::read_link(source)?;
+            std::os::unix::fs::symlink(link_target, destination)?;
+            set_times(destination, metadata);
+        }
+        #[cfg(not(unix))]
+        {
+            // On Windows, just copy as file
+            let _ = metadata;
+            fs::copy(source, destination)?;
+        }
+        Ok(())
+    }
+
+    /// Copy single file, removing partial destination on cancel or error
+    ///
+    /// Files with several hard links are linked to their earlier copy.
+    fn copy_file(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        metadata: &Metadata,
+    ) -> Result<TransferOutcome> {
+        let link_key = hardlink_key(metadata);
+        if let Some(copy) = link_key.and_then(|key| self.hardlinks.get(&key)) {
+            if fs::hard_link(copy, destination).is_ok() {
+                return Ok(TransferOutcome::Completed);
+            }
+        }
+
+        let result = self.copy_file_contents(source, destination, metadata);
+        match result {
+            Ok(TransferOutcome::Completed) => {
+                if let Some(key) = link_key {
+                    self.hardlinks.insert(key, destination.to_path_buf());
+                }
+            }
+            _ => {
+                let _ = fs::remove_file(destination);
+            }
+        }
+        result
+    }
+
+    fn copy_file_contents(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        metadata: &Metadata,
+    ) -> Result<TransferOutcome> {
+        let mut reader = File::open(source)?;
+        let mut writer = File::create(destination)?;
+        let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+
+        loop {
+            if self.cancel.is_cancelled() {
+                return Ok(TransferOutcome::Cancelled);
+            }
+
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            writer.write_all(&buffer[..read])?;
+            (self.on_progress)(read as u64);
+        }
+
+        writer.flush()?;
+        drop(writer);
+        set_times(destination, metadata);
+        fs::set_permissions(destination, metadata.permissions())?;
+        Ok(TransferOutcome::Completed)
+    }
+}
+
+/// Identity of a file with several hard links
+#[cfg(unix)]
+fn hardlink_key(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn hardlink_key(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Copy access/modification times to path without following symlinks (best effort)
+#[cfg(unix)]
+fn set_times(path: &Path, metadata: &Metadata) {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return;
+    };
+    let times = [
+        libc::timespec {
+            tv_sec: metadata.atime() as libc::time_t,
+            tv_nsec: metadata.atime_nsec() as _,
+        },
+        libc::timespec {
+            tv_sec: metadata.mtime() as libc::time_t,
+            tv_nsec: metadata.mtime_nsec() as _,
+        },
+    ];
+    unsafe {
+        libc::utimensat(
+            libc::AT_FDCWD,
+            c_path.as_ptr(),
+            times.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW,
+        );
+    }
+}
+
+#[cfg(not(unix))]
+fn set_times(path: &Path, metadata: &Metadata) {
+    if let (Ok(file), Ok(modified)) = (File::options().write(true).open(path), metadata.modified())
+    {
+        let _ = file.set_modified(modified);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_file_reports_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("big.bin");
+        let data = vec![7u8; COPY_CHUNK_SIZE * 2 + 10];
+        fs::write(&source, &data).unwrap();
+
+        let mut reported = Vec::new();
+        let outcome = copy_with_progress(
+            &source,
+            &dir.path().join("copy.bin"),
+            CopyOptions::default(),
+            &CancellationToken::new(),
+            &mut |bytes| reported.push(bytes),
+        )
+        .unwrap();
+
+        assert_eq!(outcome, TransferOutcome::Completed);
+        assert_eq!(reported.len(), 3);
+        assert_eq!(reported.iter().sum::<u64>(), data.len() as u64);
+        assert_eq!(fs::read(dir.path().join("copy.bin")).unwrap(), data);
+    }
+
+    #[test]
+    fn test_cancelled_file_copy_removes_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("big.bin");
+        fs::write(&source, vec![1u8; COPY_CHUNK_SIZE * 3]).unwrap();
+        let destination = dir.path().join("copy.bin");
+
+        // Cancel after the first chunk was written
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let outcome = copy_with_progress(
+            &source,
+            &destination,
+            CopyOptions::default(),
+            &cancel,
+            &mut |_| token.cancel(),
+        )
+        .unwrap();
+
+        assert_eq!(outcome, TransferOutcome::Cancelled);
+        assert!(!destination.exists());
+        assert!(source.exists());
+    }
+
+    #[test]
+    fn test_cancelled_directory_copy_removes_created_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::write(source.join("a.txt"), "a").unwrap();
+        fs::write(source.join("nested/b.txt"), "b").unwrap();
+        let destination = dir.path().join("dst");
+
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let outcome = copy_with_progress(
+            &source,
+            &destination,
+            CopyOptions::default(),
+            &cancel,
+            &mut |_| token.cancel(),
+        )
+        .unwrap();
+
+        assert_eq!(outcome, TransferOutcome::Cancelled);
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn test_copy_directory_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::write(source.join("a.txt"), "aaa").unwrap();
+        fs::write(source.join("nested/b.txt"), "bb").unwrap();
+        let destination = dir.path().join("dst");
+
+        let mut copied = 0;
+        let outcome = copy_with_progress(
+            &source,
+            &destination,
+            CopyOptions::default(),
+            &CancellationToken::new(),
+            &mut |bytes| copied += bytes,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, TransferOutcome::Completed);
+        assert_eq!(copied, 5);
+        assert_eq!(total_size(&[source]), 5);
+        assert_eq!(
+            fs::read_to_string(destination.join("nested/b.txt")).unwrap(),
+            "bb"
+        );
+    }
+
+    #[test]
+    fn test_spawn_transfer_streams_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("file.txt");
+        fs::write(&source, "hello").unwrap();
+        let destination = dir.path().join("moved.txt");
+
+        let rx = spawn_transfer(
+            BatchOperationType::Move,
+            source.clone(),
+            destination.clone(),
+            CopyOptions::default(),
+            CancellationToken::new(),
+            Some(vec![source.clone()]),
+        );
+        let updates: Vec<_> = rx.iter().collect();
+
+        assert_eq!(updates[0], BatchProgressUpdate::TotalBytes(5));
+        assert_eq!(updates.last(), Some(&BatchProgressUpdate::Finished(Ok(()))));
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(destination).unwrap(), "hello");
+    }
+
+    /// Fixture: executable script with old mtime, relative symlink to it,
+    /// hardlinked pair and a private subdirectory
+    #[cfg(unix)]
+    fn create_fixture(root: &Path) -> std::time::SystemTime {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mtime = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        fs::create_dir_all(root.join("private")).unwrap();
+        fs::write(root.join("run.sh"), "#!/bin/sh\necho hi\n").unwrap();
+        fs::set_permissions(root.join("run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        File::options()
+            .write(true)
+            .open(root.join("run.sh"))
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        std::os::unix::fs::symlink("run.sh", root.join("link")).unwrap();
+        fs::write(root.join("private/a.txt"), "shared").unwrap();
+        fs::hard_link(root.join("private/a.txt"), root.join("private/b.txt")).unwrap();
+        fs::set_permissions(root.join("private"), fs::Permissions::from_mode(0o700)).unwrap();
+        mtime
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_preserves_metadata_symlinks_and_hardlinks() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        let mtime = create_fixture(&source);
+        let destination = dir.path().join("dst");
+
+        let mut copied = 0;
+        let outcome = copy_with_progress(
+            &source,
+            &destination,
+            CopyOptions::default(),
+            &CancellationToken::new(),
+            &mut |bytes| copied += bytes,
+        )
+        .unwrap();
+        assert_eq!(outcome, TransferOutcome::Completed);
+
+        let script = fs::metadata(destination.join("run.sh")).unwrap();
+        assert_eq!(script.permissions().mode() & 0o777, 0o755);
+        assert_eq!(script.modified().unwrap(), mtime);
+
+        let link = fs::symlink_metadata(destination.join("link")).unwrap();
+        assert!(link.is_symlink());
+        assert_eq!(
+            fs::read_link(destination.join("link")).unwrap(),
+            PathBuf::from("run.sh")
+        );
+
+        let private = fs::metadata(destination.join("private")).unwrap();
+        assert_eq!(private.permissions().mode() & 0o777, 0o700);
+
+        // Hardlinked pair is copied once and linked again
+        let a = fs::metadata(destination.join("private/a.txt")).unwrap();
+        let b = fs::metadata(destination.join("private/b.txt")).unwrap();
+        assert_eq!(a.ino(), b.ino());
+        assert_eq!(copied, 18 + 6);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_dereference_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        create_fixture(&source);
+        std::os::unix::fs::symlink("missing", source.join("broken")).unwrap();
+        let destination = dir.path().join("dst");
+
+        let options = CopyOptions {
+            dereference_symlinks: true,
+        };
+        copy_with_progress(
+            &source,
+            &destination,
+            options,
+            &CancellationToken::new(),
+            &mut |_| {},
+        )
+        .unwrap();
+
+        let link = fs::symlink_metadata(destination.join("link")).unwrap();
+        assert!(link.is_file());
+        assert_eq!(
+            fs::read_to_string(destination.join("link")).unwrap(),
+            "#!/bin/sh\necho hi\n"
+        );
+        // Broken symlinks have nothing to dereference
+        assert!(fs::symlink_metadata(destination.join("broken"))
+            .unwrap()
+            .is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_top_level_symlink_as_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        create_fixture(dir.path());
+        let destination = dir.path().join("private");
+
+        copy_with_progress(
+            &dir.path().join("link"),
+            &destination,
+            CopyOptions::default(),
+            &CancellationToken::new(),
+            &mut |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_link(destination.join("link")).unwrap(),
+            PathBuf::from("run.sh")
+        );
+    }
+}
@@ -0,0 +1,534 @@
+//! Trash can following the freedesktop.org Trash specification.
+//!
+//! Home trash lives in `$XDG_DATA_HOME/Trash` (usually `~/.local/share/Trash`):
+//! `files/` holds trashed items and `info/` the matching `.trashinfo` entries
+//! with original path and deletion date. Items on other filesystems go to the
+//! mount's `$topdir/.Trash-$uid` when it can be used, otherwise they are copied
+//! into home trash and the original is removed.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::path::{Path, PathBuf};
+
+use super::transfer::copy_directory_recursive;
+
+const INFO_EXTENSION: &str = "trashinfo";
+
+/// Item stored in a trash directory
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    /// Name inside trash `files/` directory
+    pub name: String,
+    /// Path the item was deleted from
+    pub original_path: PathBuf,
+    /// Deletion date as stored in `.trashinfo` (YYYY-MM-DDThh:mm:ss)
+    pub deletion_date: String,
+    /// Path to the `.trashinfo` file describing this item
+    pub info_path: PathBuf,
+}
+
+/// A single trash directory (home trash or per-mount `.Trash-$uid`)
+#[derive(Debug, Clone)]
+pub struct Trash {
+    root: PathBuf,
+    /// Mount point for per-mount trash (stored paths are relative to it)
+    topdir: Option<PathBuf>,
+}
+
+impl Trash {
+    /// Trash rooted at the given directory (paths stored as absolute)
+    pub fn new(root: PathBuf) -> Self {
+        Self { root, topdir: None }
+    }
+
+    /// Home trash: `$XDG_DATA_HOME/Trash`, falling back to `~/.local/share/Trash`
+    pub fn home() -> Option<Self> {
+        Self::home_in(std::env::var_os("XDG_DATA_HOME").map(PathBuf::from))
+    }
+
+    /// Home trash for the given `$XDG_DATA_HOME` (ignored unless absolute)
+    fn home_in(xdg_data_home: Option<PathBuf>) -> Option<Self> {
+        let data_home = xdg_data_home
+            .filter(|p| p.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")))?;
+        Some(Self::new(data_home.join("Trash")))
+    }
+
+    /// Per-mount trash `$topdir/.Trash-$uid`
+    fn for_mount(topdir: &Path) -> Self {
+        // SAFETY: getuid() has no preconditions and cannot fail
+        let uid = unsafe { libc::getuid() };
+        Self {
+            root: topdir.join(format!(".Trash-{}", uid)),
+            topdir: Some(topdir.to_path_buf()),
+        }
+    }
+
+    /// Trash root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    fn info_dir(&self) -> PathBuf {
+        self.root.join("info")
+    }
+
+    /// Create `files/` and `info/` directories (mode 0700) if missing
+    fn ensure_dirs(&self) -> Result<()> {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true).mode(0o700);
+        builder.create(self.files_dir())?;
+        builder.create(self.info_dir())?;
+        Ok(())
+    }
+
+    /// Move path into this trash. Returns the item's new location inside `files/`.
+    ///
+    /// Falls back to copy + delete when the trash is on another filesystem.
+    pub fn trash(&self, path: &Path) -> Result<PathBuf> {
+        let original = absolute_path(path)?;
+        // Fail early (before creating .trashinfo) if path doesn't exist
+        fs::symlink_metadata(&original)
+            .with_context(|| format!("Cannot access {}", original.display()))?;
+
+        self.ensure_dirs()?;
+
+        let stored_path = match &self.topdir {
+            Some(topdir) => original
+                .strip_prefix(topdir)
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|_| original.clone()),
+            None => original.clone(),
+        };
+
+        let (name, info_path) = self.reserve_info(&original, &stored_path)?;
+        let trashed_path = self.files_dir().join(&name);
+
+        if let Err(e) = move_path(&original, &trashed_path) {
+            // Roll back reserved entry so trash stays consistent
+            let _ = fs::remove_file(&info_path);
+            return Err(e);
+        }
+
+        Ok(trashed_path)
+    }
+
+    /// Atomically create a uniquely named `.trashinfo` file for path
+    fn reserve_info(&self, original: &Path, stored_path: &Path) -> Result<(String, PathBuf)> {
+        let file_name = original
+            .file_name()
+            .ok_or_else(|| anyhow!("Cannot trash {}", original.display()))?
+            .to_string_lossy()
+            .to_string();
+
+        let deletion_date = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        let content = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            encode_path(stored_path),
+            deletion_date
+        );
+
+        for attempt in 1.. {
+            let name = unique_name(&file_name, attempt);
+            let info_path = self.info_dir().join(format!("{}.{}", name, INFO_EXTENSION));
+
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_path)
+            {
+                Ok(mut file) => {
+                    // Name must also be free in files/ (leftovers from other tools)
+                    if fs::symlink_metadata(self.files_dir().join(&name)).is_ok() {
+                        let _ = fs::remove_file(&info_path);
+                        continue;
+                    }
+                    file.write_all(content.as_bytes())?;
+                    return Ok((name, info_path));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        unreachable!("unbounded name search always returns")
+    }
+
+    /// List items in this trash (entries without matching file are skipped)
+    pub fn list(&self) -> Vec<TrashEntry> {
+        let Ok(read_dir) = fs::read_dir(self.info_dir()) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<TrashEntry> = read_dir
+            .flatten()
+            .filter_map(|entry| self.read_entry(&entry.path()))
+            .filter(|entry| fs::symlink_metadata(self.files_dir().join(&entry.name)).is_ok())
+            .collect();
+
+        // Most recently deleted first
+        entries.sort_by(|a, b| b.deletion_date.cmp(&a.deletion_date));
+        entries
+    }
+
+    /// Parse a `.trashinfo` file
+    fn read_entry(&self, info_path: &Path) -> Option<TrashEntry> {
+        if info_path.extension().and_then(|e| e.to_str()) != Some(INFO_EXTENSION) {
+            return None;
+        }
+        let name = info_path.file_stem()?.to_string_lossy().to_string();
+        let content = fs::read_to_string(info_path).ok()?;
+
+        let mut in_section = false;
+        let mut stored_path = None;
+        let mut deletion_date = String::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_section = line == "[Trash Info]";
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("Path=") {
+                stored_path = Some(decode_path(value));
+            } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                deletion_date = value.to_string();
+            }
+        }
+
+        let stored_path = stored_path?;
+        // Relative paths (per-mount trash) are relative to the mount point
+        let original_path = if stored_path.is_absolute() {
+            stored_path
+        } else {
+            self.root.parent()?.join(stored_path)
+        };
+
+        Some(TrashEntry {
+            name,
+            original_path,
+            deletion_date,
+            info_path: info_path.to_path_buf(),
+        })
+    }
+
+    /// Restore item described by `info_path` to its original location
+    pub fn restore(info_path: &Path) -> Result<PathBuf> {
+        let root = info_path
+            .parent()
+            .and_then(Path::parent)
+            .ok_or_else(|| anyhow!("Invalid trash entry: {}", info_path.display()))?;
+        let trash = Self::new(root.to_path_buf());
+        let entry = trash
+            .read_entry(info_path)
+            .ok_or_else(|| anyhow!("Invalid trash entry: {}", info_path.display()))?;
+
+        if fs::symlink_metadata(&entry.original_path).is_ok() {
+            return Err(anyhow!(
+                "File or directory already exists: {}",
+                entry.original_path.display()
+            ));
+        }
+        if let Some(parent) = entry.original_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        move_path(&trash.files_dir().join(&entry.name), &entry.original_path)?;
+        fs::remove_file(info_path)?;
+        Ok(entry.original_path)
+    }
+}
+
+/// Move path to trash, choosing home or per-mount trash directory
+pub fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    let home = Trash::home().ok_or_else(|| anyhow!("Cannot determine trash directory"))?;
+    move_to_trash_of(path, &home)
+}
+
+/// Move to trash, using `home` as the home trash
+fn move_to_trash_of(path: &Path, home: &Trash) -> Result<PathBuf> {
+    let original = absolute_path(path)?;
+
+    // Items on another filesystem prefer that mount's own trash (no copying)
+    if let (Some(path_dev), Some(home_dev)) = (device_of(&original), trash_device(home)) {
+        if path_dev != home_dev {
+            if let Some(topdir) = mount_point(&original) {
+                let mount_trash = Trash::for_mount(&topdir);
+                if let Ok(trashed) = mount_trash.trash(&original) {
+                    return Ok(trashed);
+                }
+            }
+        }
+    }
+
+    home.trash(&original)
+}
+
+/// List items in home trash and per-mount trash directories
+pub fn list_all() -> Vec<TrashEntry> {
+    let mut entries = Trash::home().map(|t| t.list()).unwrap_or_default();
+    for topdir in mount_points() {
+        let trash = Trash::for_mount(&topdir);
+        if trash.root().is_dir() {
+            entries.extend(trash.list());
+        }
+    }
+    entries.sort_by(|a, b| b.deletion_date.cmp(&a.deletion_date));
+    entries
+}
+
+/// Rename, falling back to copy + delete across filesystems
+fn move_path(source: &Path, destination: &Path) -> Result<()> {
+    match fs::rename(source, destination) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            let metadata = fs::symlink_metadata(source)?;
+            if metadata.is_symlink() {
+                std::os::unix::fs::symlink(fs::read_link(source)?, destination)?;
+                fs::remove_file(source)?;
+            } else if metadata.is_dir() {
+                copy_directory_recursive(source, destination)?;
+                fs::remove_dir_all(source)?;
+            } else {
+                fs::copy(source, destination)?;
+                fs::remove_file(source)?;
+            }
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Make path absolute without resolving the final symlink component
+fn absolute_path(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
+/// Device id of path (symlinks are not followed)
+fn device_of(path: &Path) -> Option<u64> {
+    fs::symlink_metadata(path).ok().map(|m| m.dev())
+}
+
+/// Device id of the trash directory (or its nearest existing ancestor)
+fn trash_device(trash: &Trash) -> Option<u64> {
+    trash
+        .root()
+        .ancestors()
+        .find_map(|p| fs::metadata(p).ok())
+        .map(|m| m.dev())
+}
+
+/// Find mount point containing path: topmost ancestor on the same device
+fn mount_point(path: &Path) -> Option<PathBuf> {
+    let dev = device_of(path)?;
+    let mut topdir = path.parent()?.to_path_buf();
+    while let Some(parent) = topdir.parent() {
+        if fs::metadata(parent).map(|m| m.dev()).ok() != Some(dev) {
+            break;
+        }
+        topdir = parent.to_path_buf();
+    }
+    Some(topdir)
+}
+
+/// Mount points from /proc/mounts (empty if unavailable)
+fn mount_points() -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .filter(|mount| *mount != "/")
+        .map(|mount| PathBuf::from(mount.replace("\\040", " ")))
+        .collect()
+}
+
+/// Name for the n-th attempt: "file.txt", "file.2.txt", "file.3.txt", ...
+fn unique_name(file_name: &str, attempt: usize) -> String {
+    if attempt == 1 {
+        return file_name.to_string();
+    }
+    match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}.{}.{}", stem, attempt, ext),
+        _ => format!("{}.{}", file_name, attempt),
+    }
+}
+
+/// Percent-encode path for `.trashinfo` (RFC 2396, '/' kept)
+fn encode_path(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut result = String::new();
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.!~*'()/".contains(&byte) {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    result
+}
+
+/// Decode percent-encoded `.trashinfo` path
+fn decode_path(value: &str) -> PathBuf {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = (
+                (bytes[i + 1] as char).to_digit(16),
+                (bytes[i + 2] as char).to_digit(16),
+            );
+            if let (Some(high), Some(low)) = hex {
+                result.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    PathBuf::from(OsString::from_vec(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Trash) {
+        let temp_dir = TempDir::new().unwrap();
+        let trash = Trash::new(temp_dir.path().join("data/Trash"));
+        (temp_dir, trash)
+    }
+
+    #[test]
+    fn test_trash_creates_info_and_moves_file() {
+        let (temp_dir, trash) = setup();
+        let file = temp_dir.path().join("notes.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let trashed = trash.trash(&file).unwrap();
+
+        assert!(!file.exists());
+        assert_eq!(trashed, trash.root().join("files/notes.txt"));
+        assert_eq!(fs::read_to_string(&trashed).unwrap(), "hello");
+
+        let info = fs::read_to_string(trash.root().join("info/notes.txt.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains(&format!("Path={}\n", file.display())));
+        assert!(info.contains("DeletionDate="));
+    }
+
+    #[test]
+    fn test_trash_name_collision() {
+        let (temp_dir, trash) = setup();
+        let file = temp_dir.path().join("a.txt");
+
+        fs::write(&file, "first").unwrap();
+        trash.trash(&file).unwrap();
+        fs::write(&file, "second").unwrap();
+        let trashed = trash.trash(&file).unwrap();
+
+        assert_eq!(trashed, trash.root().join("files/a.2.txt"));
+        assert_eq!(trash.list().len(), 2);
+    }
+
+    #[test]
+    fn test_list_and_restore() {
+        let (temp_dir, trash) = setup();
+        let dir = temp_dir.path().join("project dir");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("file"), "content").unwrap();
+
+        trash.trash(&dir).unwrap();
+        assert!(!dir.exists());
+
+        let entries = trash.list();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].original_path, dir);
+
+        let restored = Trash::restore(&entries[0].info_path).unwrap();
+        assert_eq!(restored, dir);
+        assert_eq!(fs::read_to_string(dir.join("file")).unwrap(), "content");
+        assert!(trash.list().is_empty());
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite() {
+        let (temp_dir, trash) = setup();
+        let file = temp_dir.path().join("file");
+        fs::write(&file, "old").unwrap();
+        trash.trash(&file).unwrap();
+        fs::write(&file, "new").unwrap();
+
+        let entry = trash.list().remove(0);
+        assert!(Trash::restore(&entry.info_path).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_trash_missing_path_leaves_no_info() {
+        let (temp_dir, trash) = setup();
+        assert!(trash.trash(&temp_dir.path().join("missing")).is_err());
+        assert!(trash.list().is_empty());
+        assert!(fs::read_dir(trash.root().join("info"))
+            .map(|mut d| d.next().is_none())
+            .unwrap_or(true));
+    }
+
+    #[test]
+    fn test_home_trash_uses_xdg_data_home() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = Trash::home_in(Some(temp_dir.path().join("xdg"))).unwrap();
+        assert_eq!(home.root(), temp_dir.path().join("xdg/Trash"));
+        // A relative value is ignored
+        let fallback = Trash::home_in(Some(PathBuf::from("xdg")));
+        assert_ne!(
+            fallback.map(|trash| trash.root),
+            Some(PathBuf::from("xdg/Trash"))
+        );
+
+        let file = temp_dir.path().join("doc.md");
+        fs::write(&file, "x").unwrap();
+        move_to_trash_of(&file, &home).unwrap();
+        assert!(temp_dir.path().join("xdg/Trash/files/doc.md").exists());
+        assert!(temp_dir
+            .path()
+            .join("xdg/Trash/info/doc.md.trashinfo")
+            .exists());
+    }
+
+    #[test]
+    fn test_path_encoding_roundtrip() {
+        let path = Path::new("/home/user/My Files/100%_проект.txt");
+        let encoded = encode_path(path);
+        assert!(encoded.starts_with("/home/user/My%20Files/100%25_%D0%BF"));
+        assert_eq!(decode_path(&encoded), path);
+    }
+
+    #[test]
+    fn test_unique_name() {
+        assert_eq!(unique_name("file.txt", 1), "file.txt");
+        assert_eq!(unique_name("file.txt", 3), "file.3.txt");
+        assert_eq!(unique_name("dir", 2), "dir.2");
+        assert_eq!(unique_name(".hidden", 2), ".hidden.2");
+    }
+}
@@ -0,0 +1,107 @@
+//! View settings remembered per directory.
+//!
+//! A file manager keeps the sort order and hidden files toggle chosen in a
+//! directory, with the cursor and scroll position, for the most recently
+//! visited directories. They are applied when the directory is shown again
+//! and saved in the session.
+
+use std::path::{Path, PathBuf};
+
+use termide_config::{constants::MAX_DIR_VIEWS, FileSort};
+use termide_session::SessionDirView;
+
+/// View settings of a directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DirView {
+    /// Sort order chosen in the directory (None = global setting)
+    pub sort: Option<FileSort>,
+    /// Hidden files toggle chosen in the directory (None = global setting)
+    pub show_hidden: Option<bool>,
+    /// Name of the entry under the cursor
+    pub cursor: Option<String>,
+    /// Index of the first visible entry
+    pub scroll_offset: usize,
+}
+
+/// Views of the most recently visited directories, oldest first
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ViewMemory {
+    views: Vec<(PathBuf, DirView)>,
+}
+
+impl ViewMemory {
+    /// Restore views saved in the session
+    pub fn from_session(views: Vec<SessionDirView>) -> Self {
+        let mut memory = Self::default();
+        for view in views {
+            memory.remember(
+                view.path,
+                DirView {
+                    sort: view.sort,
+                    show_hidden: view.show_hidden,
+                    cursor: view.cursor,
+                    scroll_offset: view.scroll_offset,
+                },
+            );
+        }
+        memory
+    }
+
+    /// Views for the session
+    pub fn to_session(&self) -> Vec<SessionDirView> {
+        self.views
+            .iter()
+            .map(|(path, view)| SessionDirView {
+                path: path.clone(),
+                sort: view.sort,
+                show_hidden: view.show_hidden,
+                cursor: view.cursor.clone(),
+                scroll_offset: view.scroll_offset,
+            })
+            .collect()
+    }
+
+    /// View saved for a directory
+    pub fn get(&self, path: &Path) -> Option<&DirView> {
+        self.views
+            .iter()
+            .find(|(view_path, _)| view_path == path)
+            .map(|(_, view)| view)
+    }
+
+    /// Save the view of a directory as the most recent one, dropping the
+    /// oldest view when full
+    pub fn remember(&mut self, path: PathBuf, view: DirView) {
+        self.views.retain(|(view_path, _)| *view_path != path);
+        self.views.push((path, view));
+        if self.views.len() > MAX_DIR_VIEWS {
+            self.views.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_keeps_most_recent_views() {
+        let mut memory = ViewMemory::default();
+        let view = |scroll_offset| DirView {
+            scroll_offset,
+            ..DirView::default()
+        };
+        for i in 0..MAX_DIR_VIEWS {
+            memory.remember(PathBuf::from(format!("/dir{}", i)), view(i));
+        }
+
+        // Visiting a directory again makes it the most recent one
+        memory.remember(PathBuf::from("/dir0"), view(100));
+        memory.remember(PathBuf::from("/new"), view(0));
+        assert_eq!(memory.get(Path::new("/dir0")), Some(&view(100)));
+        assert!(memory.get(Path::new("/dir1")).is_none());
+
+        let restored = ViewMemory::from_session(memory.to_session());
+        assert_eq!(restored.to_session(), memory.to_session());
+    }
+}
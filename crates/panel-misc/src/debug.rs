@@ -156,7 +156,7 @@ impl DebugPanel {
             }
 
             let level_style = match entry.level {
-                LogLevel::Debug => Style::default().fg(Color::DarkGray),
+                LogLevel::Trace | LogLevel::Debug => Style::default().fg(Color::DarkGray),
                 LogLevel::Info => Style::default().fg(ctx.theme.fg),
                 LogLevel::Warn => Style::default()
                     .fg(Color::Yellow)
@@ -165,6 +165,7 @@ impl DebugPanel {
             };
 
             let level_text = match entry.level {
+                LogLevel::Trace => "TRACE",
                 LogLevel::Debug => "DEBUG",
                 LogLevel::Info => "INFO ",
                 LogLevel::Warn => "WARN ",
@@ -0,0 +1,885 @@
+//! Diff panel.
+//!
+//! Shows the differences between two texts, either side by side or as one
+//! unified column: two files picked in the file manager, or an editor
+//! buffer against the version of its file in HEAD. Changed line pairs
+//! highlight the words that differ. Both sides of the side-by-side view are
+//! rows of one list, so they always scroll together; `n`/`N` jump between
+//! hunks.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use similar::{Algorithm, ChangeTag, DiffOp, TextDiff};
+use std::any::Any;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
+
+use termide_config::DiffLayout;
+use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext};
+use termide_theme::Theme;
+
+/// Rows moved by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Columns moved by Left/Right
+const HSCROLL_STEP: usize = 8;
+
+/// Unchanged rows shown above a hunk jumped to
+const HUNK_CONTEXT: usize = 3;
+
+/// Time after which the line diff settles for a less minimal result
+const DIFF_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Spaces a tab is shown as
+const TAB_WIDTH: usize = 4;
+
+/// What the panel compares
+#[derive(Debug, Clone)]
+enum Source {
+    /// Two files on disk
+    Files { left: PathBuf, right: PathBuf },
+    /// Editor content against the file's version in HEAD
+    Head { path: PathBuf },
+    /// File on disk against text recovered after a crash
+    Recovery { path: PathBuf },
+}
+
+/// How a row differs between the two sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+    Equal,
+    /// Only in the old text
+    Removed,
+    /// Only in the new text
+    Added,
+    /// Old line replaced by the new one (side by side only)
+    Changed,
+}
+
+/// Text of a line split into parts, true for parts changed within the line
+type Parts = Vec<(String, bool)>;
+
+/// Line of one side of a row
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiffLine {
+    /// Line number (1-based)
+    number: usize,
+    parts: Parts,
+}
+
+/// Row of the diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    kind: RowKind,
+    old: Option<DiffLine>,
+    new: Option<DiffLine>,
+}
+
+/// Diff viewer panel
+pub struct DiffPanel {
+    source: Source,
+    /// Names of the two sides shown above them
+    labels: (String, String),
+    old_lines: Vec<String>,
+    new_lines: Vec<String>,
+    ops: Vec<DiffOp>,
+    layout: DiffLayout,
+    rows: Vec<Row>,
+    /// First row of each hunk
+    hunks: Vec<usize>,
+    /// Hunk last jumped to (None after scrolling by hand)
+    current_hunk: Option<usize>,
+    /// First row shown
+    scroll: usize,
+    /// First column of the text shown
+    hscroll: usize,
+    /// Height of the row area of the last render
+    last_height: usize,
+    /// Error of the last reload
+    status_message: Option<String>,
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl DiffPanel {
+    /// Compare two files
+    pub fn compare_files(
+        left: PathBuf,
+        right: PathBuf,
+        layout: DiffLayout,
+    ) -> anyhow::Result<Self> {
+        let old = read_text(&left)?;
+        let new = read_text(&right)?;
+        let labels = (left.display().to_string(), right.display().to_string());
+        Ok(Self::new(
+            Source::Files { left, right },
+            labels,
+            &old,
+            &new,
+            layout,
+        ))
+    }
+
+    /// Compare editor `content` with `head`, the file's version in HEAD
+    pub fn against_head(path: PathBuf, head: &str, content: &str, layout: DiffLayout) -> Self {
+        let labels = (
+            format!("HEAD:{}", file_name(&path)),
+            path.display().to_string(),
+        );
+        Self::new(Source::Head { path }, labels, head, content, layout)
+    }
+
+    /// Compare the file on disk (`disk`) with its text `recovered` after a
+    /// crash
+    pub fn against_recovery(
+        path: PathBuf,
+        disk: &str,
+        recovered: &str,
+        layout: DiffLayout,
+    ) -> Self {
+        let labels = (
+            path.display().to_string(),
+            format!(
+                "{} ({})",
+                file_name(&path),
+                termide_i18n::t().diff_recovered()
+            ),
+        );
+        Self::new(Source::Recovery { path }, labels, disk, recovered, layout)
+    }
+
+    fn new(
+        source: Source,
+        labels: (String, String),
+        old: &str,
+        new: &str,
+        layout: DiffLayout,
+    ) -> Self {
+        let mut panel = Self {
+            source,
+            labels,
+            old_lines: Vec::new(),
+            new_lines: Vec::new(),
+            ops: Vec::new(),
+            layout,
+            rows: Vec::new(),
+            hunks: Vec::new(),
+            current_hunk: None,
+            scroll: 0,
+            hscroll: 0,
+            last_height: 0,
+            status_message: None,
+            cached_theme: Theme::default(),
+        };
+        panel.set_texts(old, new);
+        panel.goto_hunk(true);
+        panel
+    }
+
+    /// Current layout
+    pub fn layout(&self) -> DiffLayout {
+        self.layout
+    }
+
+    /// Number of hunks
+    pub fn hunk_count(&self) -> usize {
+        self.hunks.len()
+    }
+
+    /// Diff two texts and rebuild the rows
+    fn set_texts(&mut self, old: &str, new: &str) {
+        self.old_lines = old.lines().map(expand_tabs).collect();
+        self.new_lines = new.lines().map(expand_tabs).collect();
+        self.ops = similar::capture_diff_slices_deadline(
+            Algorithm::Myers,
+            &self.old_lines,
+            &self.new_lines,
+            Some(Instant::now() + DIFF_TIMEOUT),
+        );
+        self.rebuild_rows();
+    }
+
+    /// Build the rows of the current layout and find the hunks
+    fn rebuild_rows(&mut self) {
+        self.rows = build_rows(&self.old_lines, &self.new_lines, &self.ops, self.layout);
+        self.hunks = (0..self.rows.len())
+            .filter(|&idx| {
+                self.rows[idx].kind != RowKind::Equal
+                    && (idx == 0 || self.rows[idx - 1].kind == RowKind::Equal)
+            })
+            .collect();
+        if self
+            .current_hunk
+            .is_some_and(|hunk| hunk >= self.hunks.len())
+        {
+            self.current_hunk = None;
+        }
+    }
+
+    /// Switch between side-by-side and unified layout, keeping the position
+    fn toggle_layout(&mut self) {
+        // Line number of the top row, old side if it has one
+        let top = self
+            .rows
+            .get(self.scroll)
+            .and_then(|row| match (&row.old, &row.new) {
+                (Some(line), _) => Some((true, line.number)),
+                (None, Some(line)) => Some((false, line.number)),
+                (None, None) => None,
+            });
+
+        self.layout = match self.layout {
+            DiffLayout::SideBySide => DiffLayout::Unified,
+            DiffLayout::Unified => DiffLayout::SideBySide,
+        };
+        self.rebuild_rows();
+
+        if let Some(hunk) = self.current_hunk {
+            self.scroll_to_hunk(hunk);
+        } else if let Some((old, number)) = top {
+            self.scroll = self
+                .rows
+                .iter()
+                .position(|row| {
+                    let line = if old { &row.old } else { &row.new };
+                    line.as_ref().is_some_and(|line| line.number >= number)
+                })
+                .unwrap_or(0);
+        }
+    }
+
+    /// Jump to the next or previous hunk
+    fn goto_hunk(&mut self, forward: bool) {
+        if self.hunks.is_empty() {
+            return;
+        }
+        let target = match (self.current_hunk, forward) {
+            (Some(hunk), true) => (hunk + 1).min(self.hunks.len() - 1),
+            (Some(hunk), false) => hunk.saturating_sub(1),
+            // After scrolling by hand: the first hunk below or above the top
+            (None, true) => self
+                .hunks
+                .iter()
+                .position(|&start| start >= self.scroll)
+                .unwrap_or(self.hunks.len() - 1),
+            (None, false) => self
+                .hunks
+                .iter()
+                .rposition(|&start| start < self.scroll)
+                .unwrap_or(0),
+        };
+        self.scroll_to_hunk(target);
+    }
+
+    fn scroll_to_hunk(&mut self, hunk: usize) {
+        self.current_hunk = Some(hunk);
+        self.scroll = self.hunks[hunk].saturating_sub(HUNK_CONTEXT);
+    }
+
+    /// Scroll by `delta` rows
+    fn scroll_by(&mut self, delta: isize) {
+        self.current_hunk = None;
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.rows.len().saturating_sub(self.last_height.max(1))
+    }
+
+    /// Rows moved by PageUp/PageDown (the visible height)
+    fn page_size(&self) -> isize {
+        self.last_height.max(1) as isize
+    }
+
+    /// Read compared files again
+    fn reload_files(&mut self) {
+        let Source::Files { left, right } = &self.source else {
+            return;
+        };
+        match read_text(left).and_then(|old| Ok((old, read_text(right)?))) {
+            Ok((old, new)) => {
+                self.set_texts(&old, &new);
+                self.scroll = self.scroll.min(self.max_scroll());
+                self.status_message = None;
+            }
+            Err(e) => self.status_message = Some(e.to_string()),
+        }
+    }
+
+    /// Number of added and removed lines
+    fn line_counts(&self) -> (usize, usize) {
+        self.ops
+            .iter()
+            .fold((0, 0), |(added, removed), op| match *op {
+                DiffOp::Equal { .. } => (added, removed),
+                DiffOp::Delete { old_len, .. } => (added, removed + old_len),
+                DiffOp::Insert { new_len, .. } => (added + new_len, removed),
+                DiffOp::Replace {
+                    old_len, new_len, ..
+                } => (added + new_len, removed + old_len),
+            })
+    }
+
+    /// Render the names of the compared sides
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.cached_theme;
+        let style = Style::default()
+            .fg(theme.accented_fg)
+            .add_modifier(Modifier::BOLD);
+        match self.layout {
+            DiffLayout::SideBySide => {
+                let (left, right) = split_columns(area);
+                Paragraph::new(Line::styled(self.labels.0.clone(), style)).render(left, buf);
+                Paragraph::new(Line::styled(self.labels.1.clone(), style)).render(right, buf);
+            }
+            DiffLayout::Unified => {
+                let line = Line::from(vec![
+                    Span::styled(format!("--- {}", self.labels.0), style.fg(theme.error)),
+                    Span::raw("  "),
+                    Span::styled(format!("+++ {}", self.labels.1), style.fg(theme.success)),
+                ]);
+                Paragraph::new(line).render(area, buf);
+            }
+        }
+    }
+
+    /// Render the visible rows
+    fn render_rows(&mut self, area: Rect, buf: &mut Buffer) {
+        self.last_height = area.height as usize;
+        self.scroll = self.scroll.min(self.max_scroll());
+        let rows = self
+            .rows
+            .iter()
+            .skip(self.scroll)
+            .take(area.height as usize);
+        let number_width = self
+            .old_lines
+            .len()
+            .max(self.new_lines.len())
+            .max(1)
+            .to_string()
+            .len();
+
+        match self.layout {
+            DiffLayout::SideBySide => {
+                let (left, right) = split_columns(area);
+                let (old, new): (Vec<Line>, Vec<Line>) = rows
+                    .map(|row| {
+                        (
+                            self.side_line(row, true, number_width),
+                            self.side_line(row, false, number_width),
+                        )
+                    })
+                    .unzip();
+                Paragraph::new(old).render(left, buf);
+                Paragraph::new(new).render(right, buf);
+                let separator = Style::default().fg(self.cached_theme.disabled);
+                for y in area.y..area.y + area.height {
+                    buf.set_string(left.x + left.width, y, "│", separator);
+                }
+            }
+            DiffLayout::Unified => {
+                let lines: Vec<Line> = rows
+                    .map(|row| self.unified_line(row, number_width))
+                    .collect();
+                Paragraph::new(lines).render(area, buf);
+            }
+        }
+    }
+
+    /// Line of one side in the side-by-side layout
+    fn side_line(&self, row: &Row, old: bool, number_width: usize) -> Line<'static> {
+        let theme = &self.cached_theme;
+        let line = if old { &row.old } else { &row.new };
+        let Some(line) = line else {
+            // Filler opposite an added or removed line
+            return Line::styled(
+                format!("{:>w$}", "", w = number_width + 3),
+                Style::default().fg(theme.disabled),
+            );
+        };
+        let (marker, color) = match (row.kind, old) {
+            (RowKind::Equal, _) => (' ', theme.fg),
+            (RowKind::Changed, _) => ('~', theme.warning),
+            (_, true) => ('-', theme.error),
+            (_, false) => ('+', theme.success),
+        };
+        let emphasis = if old { theme.error } else { theme.success };
+        let mut spans = vec![Span::styled(
+            format!("{:>w$} {} ", line.number, marker, w = number_width),
+            Style::default().fg(theme.disabled),
+        )];
+        spans.extend(self.text_spans(line, color, emphasis));
+        Line::from(spans)
+    }
+
+    /// Line of the unified layout: old and new line number, marker, text
+    fn unified_line(&self, row: &Row, number_width: usize) -> Line<'static> {
+        let theme = &self.cached_theme;
+        let number = |line: &Option<DiffLine>| match line {
+            Some(line) => format!("{:>w$}", line.number, w = number_width),
+            None => format!("{:>w$}", "", w = number_width),
+        };
+        let (marker, color, line) = match (row.kind, &row.old, &row.new) {
+            (RowKind::Equal, Some(line), _) => (' ', theme.fg, line),
+            (_, Some(line), None) => ('-', theme.error, line),
+            (_, _, Some(line)) => ('+', theme.success, line),
+            (_, None, None) => return Line::default(),
+        };
+        let mut spans = vec![Span::styled(
+            format!("{} {} {} ", number(&row.old), number(&row.new), marker),
+            Style::default().fg(theme.disabled),
+        )];
+        spans.extend(self.text_spans(line, color, color));
+        Line::from(spans)
+    }
+
+    /// Text of a line after the horizontal scroll, changed parts emphasized
+    fn text_spans(&self, line: &DiffLine, color: Color, emphasis: Color) -> Vec<Span<'static>> {
+        let mut skip = self.hscroll;
+        let mut spans = Vec::new();
+        for (text, changed) in &line.parts {
+            let text = skip_columns(text, &mut skip);
+            if text.is_empty() {
+                continue;
+            }
+            let style = if *changed {
+                Style::default().fg(self.cached_theme.bg).bg(emphasis)
+            } else {
+                Style::default().fg(color)
+            };
+            spans.push(Span::styled(text.to_string(), style));
+        }
+        spans
+    }
+
+    /// Render the bottom line: hunk position, line counts and key hint
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        if let Some(error) = &self.status_message {
+            Paragraph::new(Line::styled(
+                error.clone(),
+                Style::default().fg(theme.error),
+            ))
+            .render(area, buf);
+            return;
+        }
+
+        let mut spans = Vec::new();
+        if self.hunks.is_empty() {
+            spans.push(Span::styled(
+                t.diff_identical(),
+                Style::default().fg(theme.success),
+            ));
+        } else {
+            let position = match self.current_hunk {
+                Some(hunk) => t.diff_hunk_position(hunk + 1, self.hunks.len()),
+                None => t.diff_hunk_count(self.hunks.len()),
+            };
+            let (added, removed) = self.line_counts();
+            spans.push(Span::styled(position, Style::default().fg(theme.fg)));
+            spans.push(Span::styled(
+                format!("  +{}", added),
+                Style::default().fg(theme.success),
+            ));
+            spans.push(Span::styled(
+                format!(" -{}", removed),
+                Style::default().fg(theme.error),
+            ));
+        }
+        spans.push(Span::styled(
+            format!("  {}", t.diff_hint()),
+            Style::default().fg(theme.disabled),
+        ));
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
+
+/// Rows of a layout from the line diff
+///
+/// Side by side, replaced lines are paired up in `Changed` rows; unified,
+/// they become removed rows followed by added rows.
+fn build_rows(old: &[String], new: &[String], ops: &[DiffOp], layout: DiffLayout) -> Vec<Row> {
+    let plain = |lines: &[String], idx: usize| DiffLine {
+        number: idx + 1,
+        parts: vec![(lines[idx].clone(), false)],
+    };
+    let mut rows = Vec::new();
+    for op in ops {
+        match *op {
+            DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } => rows.extend((0..len).map(|i| Row {
+                kind: RowKind::Equal,
+                old: Some(plain(old, old_index + i)),
+                new: Some(plain(new, new_index + i)),
+            })),
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => rows.extend((old_index..old_index + old_len).map(|idx| Row {
+                kind: RowKind::Removed,
+                old: Some(plain(old, idx)),
+                new: None,
+            })),
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => rows.extend((new_index..new_index + new_len).map(|idx| Row {
+                kind: RowKind::Added,
+                old: None,
+                new: Some(plain(new, idx)),
+            })),
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                // Lines paired with a line of the other side get word changes
+                let mut old_side: Vec<DiffLine> =
+                    (0..old_len).map(|i| plain(old, old_index + i)).collect();
+                let mut new_side: Vec<DiffLine> =
+                    (0..new_len).map(|i| plain(new, new_index + i)).collect();
+                for i in 0..old_len.min(new_len) {
+                    let (old_parts, new_parts) =
+                        inline_changes(&old[old_index + i], &new[new_index + i]);
+                    old_side[i].parts = old_parts;
+                    new_side[i].parts = new_parts;
+                }
+
+                match layout {
+                    DiffLayout::SideBySide => {
+                        let mut old_side = old_side.into_iter();
+                        let mut new_side = new_side.into_iter();
+                        for _ in 0..old_len.max(new_len) {
+                            let (old, new) = (old_side.next(), new_side.next());
+                            let kind = match (&old, &new) {
+                                (Some(_), Some(_)) => RowKind::Changed,
+                                (Some(_), None) => RowKind::Removed,
+                                _ => RowKind::Added,
+                            };
+                            rows.push(Row { kind, old, new });
+                        }
+                    }
+                    DiffLayout::Unified => {
+                        rows.extend(old_side.into_iter().map(|line| Row {
+                            kind: RowKind::Removed,
+                            old: Some(line),
+                            new: None,
+                        }));
+                        rows.extend(new_side.into_iter().map(|line| Row {
+                            kind: RowKind::Added,
+                            old: None,
+                            new: Some(line),
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    rows
+}
+
+/// Parts of a replaced line and its replacement, words that differ marked
+fn inline_changes(old: &str, new: &str) -> (Parts, Parts) {
+    let diff = TextDiff::from_words(old, new);
+    let mut old_parts = Parts::new();
+    let mut new_parts = Parts::new();
+    let push = |parts: &mut Parts, text: &str, changed: bool| match parts.last_mut() {
+        Some((last, last_changed)) if *last_changed == changed => last.push_str(text),
+        _ => parts.push((text.to_string(), changed)),
+    };
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Equal => {
+                push(&mut old_parts, change.value(), false);
+                push(&mut new_parts, change.value(), false);
+            }
+            ChangeTag::Delete => push(&mut old_parts, change.value(), true),
+            ChangeTag::Insert => push(&mut new_parts, change.value(), true),
+        }
+    }
+    (old_parts, new_parts)
+}
+
+/// Left and right column of the side-by-side layout (a separator between)
+fn split_columns(area: Rect) -> (Rect, Rect) {
+    let left_width = area.width.saturating_sub(1) / 2;
+    let left = Rect {
+        width: left_width,
+        ..area
+    };
+    let right = Rect {
+        x: area.x + left_width + 1,
+        width: area.width.saturating_sub(left_width + 1),
+        ..area
+    };
+    (left, right)
+}
+
+/// Rest of `text` after skipping `skip` columns (decreased by the columns skipped)
+fn skip_columns<'a>(text: &'a str, skip: &mut usize) -> &'a str {
+    for (idx, ch) in text.char_indices() {
+        if *skip == 0 {
+            return &text[idx..];
+        }
+        *skip = skip.saturating_sub(ch.width().unwrap_or(0).max(1));
+    }
+    ""
+}
+
+fn expand_tabs(line: &str) -> String {
+    line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Text of a file with LF line breaks (binary files are rejected)
+fn read_text(path: &Path) -> anyhow::Result<String> {
+    let bytes = std::fs::read(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    if bytes.contains(&0) {
+        anyhow::bail!(termide_i18n::t().diff_binary_file(&path.display().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&bytes).replace("\r\n", "\n"))
+}
+
+impl Panel for DiffPanel {
+    fn name(&self) -> &'static str {
+        "diff"
+    }
+
+    fn title(&self) -> String {
+        let title = termide_i18n::t().diff_title();
+        match &self.source {
+            Source::Files { left, right } => {
+                format!("{}: {} ↔ {}", title, file_name(left), file_name(right))
+            }
+            Source::Head { path } => format!("{}: {} (HEAD)", title, file_name(path)),
+            Source::Recovery { path } => format!(
+                "{}: {} ({})",
+                title,
+                file_name(path),
+                termide_i18n::t().diff_recovered()
+            ),
+        }
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 3 {
+            return;
+        }
+        self.render_header(Rect { height: 1, ..area }, buf);
+        self.render_rows(
+            Rect {
+                y: area.y + 1,
+                height: area.height - 2,
+                ..area
+            },
+            buf,
+        );
+        self.render_footer(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-self.page_size()),
+            KeyCode::PageDown => self.scroll_by(self.page_size()),
+            KeyCode::Home => self.scroll_by(isize::MIN),
+            KeyCode::End => self.scroll_by(isize::MAX),
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.hscroll = self.hscroll.saturating_sub(HSCROLL_STEP)
+            }
+            KeyCode::Right | KeyCode::Char('l') => self.hscroll += HSCROLL_STEP,
+            KeyCode::Char('n') => self.goto_hunk(true),
+            KeyCode::Char('N') => self.goto_hunk(false),
+            KeyCode::F(7) => self.goto_hunk(!key.modifiers.contains(KeyModifiers::SHIFT)),
+            KeyCode::Char('v') => self.toggle_layout(),
+            KeyCode::Char('r') => self.reload_files(),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.scroll_by(SCROLL_STEP as isize),
+            MouseEventKind::ScrollLeft => self.hscroll = self.hscroll.saturating_sub(HSCROLL_STEP),
+            MouseEventKind::ScrollRight => self.hscroll += HSCROLL_STEP,
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        match cmd {
+            PanelCommand::Reload => {
+                self.reload_files();
+                CommandResult::NeedsRedraw(true)
+            }
+            // Commands not applicable to DiffPanel
+            PanelCommand::GetRepoRoot
+            | PanelCommand::OnGitUpdate { .. }
+            | PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::GetFsWatchInfo
+            | PanelCommand::SetFsWatchRoot { .. }
+            | PanelCommand::OnFsUpdate { .. }
+            | PanelCommand::Resize { .. }
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
+    fn get_working_directory(&self) -> Option<PathBuf> {
+        let path = match &self.source {
+            Source::Files { right, .. } => right,
+            Source::Head { path } | Source::Recovery { path } => path,
+        };
+        path.parent().map(Path::to_path_buf)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n";
+    const NEW: &str = "one\n2\nthree\nfour\nfive\nsix\nseven\neight\nnine\n";
+
+    fn panel(layout: DiffLayout) -> DiffPanel {
+        DiffPanel::against_head(PathBuf::from("/tmp/file.txt"), OLD, NEW, layout)
+    }
+
+    #[test]
+    fn test_side_by_side_pairs_changed_lines() {
+        let panel = panel(DiffLayout::SideBySide);
+        assert_eq!(panel.rows.len(), 9);
+        assert_eq!(panel.hunks, vec![1, 8]);
+
+        let changed = &panel.rows[1];
+        assert_eq!(changed.kind, RowKind::Changed);
+        assert_eq!(changed.old.as_ref().unwrap().number, 2);
+        assert_eq!(changed.new.as_ref().unwrap().number, 2);
+
+        let added = &panel.rows[8];
+        assert_eq!(added.kind, RowKind::Added);
+        assert!(added.old.is_none());
+    }
+
+    #[test]
+    fn test_unified_lists_removed_before_added() {
+        let panel = panel(DiffLayout::Unified);
+        let kinds: Vec<RowKind> = panel.rows.iter().take(4).map(|row| row.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RowKind::Equal,
+                RowKind::Removed,
+                RowKind::Added,
+                RowKind::Equal
+            ]
+        );
+        assert_eq!(panel.hunk_count(), 2);
+    }
+
+    #[test]
+    fn test_changed_words_are_emphasized() {
+        let (old, new) = inline_changes("let x = 1;", "let y = 1;");
+        assert_eq!(
+            old,
+            vec![
+                ("let ".to_string(), false),
+                ("x".to_string(), true),
+                (" = 1;".to_string(), false)
+            ]
+        );
+        assert_eq!(new[1], ("y".to_string(), true));
+    }
+
+    #[test]
+    fn test_hunk_navigation_and_layout_toggle() {
+        let mut panel = panel(DiffLayout::SideBySide);
+        // Opens at the first hunk
+        assert_eq!(panel.current_hunk, Some(0));
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('n')));
+        assert_eq!(panel.current_hunk, Some(1));
+        assert_eq!(panel.scroll, 8 - HUNK_CONTEXT);
+        // Stays at the last hunk
+        panel.handle_key(KeyEvent::from(KeyCode::Char('n')));
+        assert_eq!(panel.current_hunk, Some(1));
+
+        // The unified layout has one more row, the hunk is kept
+        panel.handle_key(KeyEvent::from(KeyCode::Char('v')));
+        assert_eq!(panel.layout(), DiffLayout::Unified);
+        assert_eq!(panel.hunks[1], 9);
+        assert_eq!(panel.scroll, 9 - HUNK_CONTEXT);
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('N')));
+        assert_eq!(panel.current_hunk, Some(0));
+        assert_eq!(panel.scroll, 0);
+    }
+
+    #[test]
+    fn test_compare_files() {
+        termide_i18n::init_with_language("en");
+        let dir = std::env::temp_dir().join(format!("termide-diff-panel-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (left, right, binary) = (dir.join("a.txt"), dir.join("b.txt"), dir.join("c.bin"));
+        std::fs::write(&left, "same\r\nold\r\n").unwrap();
+        std::fs::write(&right, "same\nnew\n").unwrap();
+        std::fs::write(&binary, [0u8, 1, 2]).unwrap();
+
+        let panel = DiffPanel::compare_files(left.clone(), right, DiffLayout::SideBySide).unwrap();
+        assert_eq!(panel.hunk_count(), 1);
+        assert_eq!(panel.line_counts(), (1, 1));
+        assert_eq!(panel.title(), "Diff: a.txt ↔ b.txt");
+        assert!(DiffPanel::compare_files(left, binary, DiffLayout::SideBySide).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,594 @@
+//! Directory comparison panel.
+//!
+//! Compares two directory trees (`DirComparison`, on a background thread)
+//! and lists the entries that differ as they are found. Enter shows the two
+//! versions of a file in the diff panel; `>` and `<` copy the selected entry
+//! to the other side. Contents are compared only when asked to (F2), as
+//! reading both trees is much slower than comparing sizes and times.
+
+mod worker;
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use std::path::{Path, PathBuf};
+
+use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext};
+use termide_system_monitor::format_bytes;
+use termide_theme::Theme;
+
+pub use worker::{copy_entry, Budget};
+use worker::{CompareUpdate, Difference, DirComparison, Status};
+
+/// Rows moved by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Progress of the comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    Finished {
+        /// Entries present and equal on both sides
+        same: usize,
+        /// The depth or entry budget stopped the walk
+        truncated: bool,
+    },
+    /// Stopped with Esc
+    Cancelled,
+}
+
+/// Side an entry is copied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Directory comparison panel
+pub struct DirComparePanel {
+    left: PathBuf,
+    right: PathBuf,
+    budget: Budget,
+    /// Compare the contents of files of the same size
+    contents: bool,
+    /// Running comparison (dropping it cancels the comparison)
+    comparison: Option<DirComparison>,
+    state: State,
+    /// Entries that differ, in path order
+    differences: Vec<Difference>,
+    /// Selected row of the list
+    selected: usize,
+    /// First list row shown
+    scroll: usize,
+    /// List area and first row of the last render (for mouse clicks)
+    last_list: (Rect, usize),
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl DirComparePanel {
+    /// Create a panel comparing `left` with `right` and start the comparison
+    pub fn new(left: PathBuf, right: PathBuf, budget: Budget) -> Self {
+        let mut panel = Self {
+            left,
+            right,
+            budget,
+            contents: false,
+            comparison: None,
+            state: State::Running,
+            differences: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            last_list: (Rect::default(), 0),
+            cached_theme: Theme::default(),
+        };
+        panel.start();
+        panel
+    }
+
+    /// Left directory
+    pub fn left(&self) -> &Path {
+        &self.left
+    }
+
+    /// Right directory
+    pub fn right(&self) -> &Path {
+        &self.right
+    }
+
+    /// Compare the directories again, cancelling the running comparison
+    fn start(&mut self) {
+        self.differences.clear();
+        self.selected = 0;
+        self.scroll = 0;
+        self.comparison = Some(DirComparison::spawn(
+            self.left.clone(),
+            self.right.clone(),
+            self.budget,
+            self.contents,
+        ));
+        self.state = State::Running;
+    }
+
+    /// Stop the running comparison, keeping the differences found so far
+    fn cancel(&mut self) {
+        if self.comparison.take().is_some() {
+            self.state = State::Cancelled;
+        }
+    }
+
+    /// Apply the updates sent by the running comparison, true if any arrived
+    fn poll_comparison(&mut self) -> bool {
+        let Some(comparison) = &self.comparison else {
+            return false;
+        };
+        let updates = comparison.try_updates();
+        if updates.is_empty() {
+            return false;
+        }
+        for update in updates {
+            match update {
+                CompareUpdate::Differences(found) => self.differences.extend(found),
+                CompareUpdate::Finished { same, truncated } => {
+                    self.state = State::Finished { same, truncated };
+                    self.comparison = None;
+                }
+            }
+        }
+        true
+    }
+
+    /// Move the selection by `delta` rows (clamped to the list)
+    fn move_selection(&mut self, delta: isize) {
+        let count = self.differences.len();
+        if count == 0 {
+            return;
+        }
+        self.selected = self.selected.saturating_add_signed(delta).min(count - 1);
+    }
+
+    /// Rows moved by PageUp/PageDown
+    fn page_size(&self) -> isize {
+        self.last_list.0.height.max(1) as isize
+    }
+
+    /// Show the selected entry: both versions of a file in the diff panel,
+    /// a file present on one side in an editor
+    fn open_selected(&self) -> Vec<PanelEvent> {
+        let Some(difference) = self.differences.get(self.selected) else {
+            return vec![];
+        };
+        if difference.directory {
+            return vec![];
+        }
+        let left = self.left.join(&difference.path);
+        let right = self.right.join(&difference.path);
+        match difference.status {
+            Status::OnlyLeft => vec![PanelEvent::open_file(left)],
+            Status::OnlyRight => vec![PanelEvent::open_file(right)],
+            Status::Size | Status::Contents | Status::Time => {
+                vec![PanelEvent::CompareFiles { left, right }]
+            }
+            Status::Kind => vec![],
+        }
+    }
+
+    /// Copy the selected entry to `side`, replacing the entry there
+    fn copy_selected(&mut self, side: Side) -> Vec<PanelEvent> {
+        let t = termide_i18n::t();
+        let Some(difference) = self.differences.get(self.selected) else {
+            return vec![];
+        };
+        let missing = match side {
+            Side::Right => Status::OnlyRight,
+            Side::Left => Status::OnlyLeft,
+        };
+        // Replacing a directory by a file (or back) is left to the user
+        if difference.status == missing || difference.status == Status::Kind {
+            return vec![];
+        }
+        let (from, to) = match side {
+            Side::Right => (&self.left, &self.right),
+            Side::Left => (&self.right, &self.left),
+        };
+        let path = difference.path.display().to_string();
+        match copy_entry(&from.join(&difference.path), &to.join(&difference.path)) {
+            Ok(()) => {
+                self.differences.remove(self.selected);
+                self.move_selection(0);
+                let message = match side {
+                    Side::Right => t.compare_copied_right(&path),
+                    Side::Left => t.compare_copied_left(&path),
+                };
+                vec![
+                    PanelEvent::SetStatusMessage {
+                        message,
+                        is_error: false,
+                    },
+                    PanelEvent::NeedsRedraw,
+                ]
+            }
+            Err(e) => vec![PanelEvent::SetStatusMessage {
+                message: t.compare_copy_failed(&path, &e.to_string()),
+                is_error: true,
+            }],
+        }
+    }
+
+    /// Render the line with the compared directories and the contents toggle
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.cached_theme;
+        let toggle = if self.contents {
+            Style::default()
+                .fg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.disabled)
+        };
+        let line = Line::from(vec![
+            Span::styled(
+                format!("[{}] ", termide_i18n::t().compare_contents()),
+                toggle,
+            ),
+            Span::styled(
+                format!("{} ⇄ {}", self.left.display(), self.right.display()),
+                Style::default().fg(theme.fg),
+            ),
+        ]);
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the line with the comparison progress
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let count = self.differences.len();
+        let line = match self.state {
+            State::Running => Line::from(vec![
+                Span::styled(t.compare_found(count), Style::default().fg(theme.disabled)),
+                Span::styled(
+                    format!("  {}", t.compare_running()),
+                    Style::default().fg(theme.warning),
+                ),
+            ]),
+            State::Cancelled => Line::styled(
+                format!("{} ({})", t.compare_found(count), t.compare_cancelled()),
+                Style::default().fg(theme.warning),
+            ),
+            State::Finished { truncated, .. } if count == 0 && !truncated => {
+                Line::styled(t.diff_identical(), Style::default().fg(theme.success))
+            }
+            State::Finished { same, truncated } => {
+                let mut spans = vec![Span::styled(
+                    t.compare_summary(count, same),
+                    Style::default().fg(theme.success),
+                )];
+                if truncated {
+                    spans.push(Span::styled(
+                        format!(" ({})", t.compare_truncated()),
+                        Style::default().fg(theme.warning),
+                    ));
+                }
+                Line::from(spans)
+            }
+        };
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the list of differences
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = self.cached_theme;
+        self.last_list = (area, self.scroll);
+        if area.height == 0 {
+            return;
+        }
+
+        // Keep the selection visible
+        let height = area.height as usize;
+        self.selected = self.selected.min(self.differences.len().saturating_sub(1));
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let width = area.width as usize;
+        let lines: Vec<Line> = self
+            .differences
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(height)
+            .map(|(idx, difference)| row_line(difference, idx == self.selected, width, &theme))
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+        self.last_list = (area, self.scroll);
+    }
+}
+
+/// What differs about an entry
+fn describe(difference: &Difference) -> String {
+    let t = termide_i18n::t();
+    match difference.status {
+        Status::OnlyLeft => t.compare_only_left().to_string(),
+        Status::OnlyRight => t.compare_only_right().to_string(),
+        Status::Size => {
+            let size = |info: Option<worker::EntryInfo>| format_bytes(info.map_or(0, |i| i.size));
+            t.compare_size(&size(difference.left), &size(difference.right))
+        }
+        Status::Contents => t.compare_contents_differ().to_string(),
+        Status::Time => {
+            let modified = |info: Option<worker::EntryInfo>| info.and_then(|i| i.modified);
+            if modified(difference.left) > modified(difference.right) {
+                t.compare_newer_left().to_string()
+            } else {
+                t.compare_newer_right().to_string()
+            }
+        }
+        Status::Kind => t.compare_kind_differs().to_string(),
+    }
+}
+
+/// Line of a difference
+fn row_line(difference: &Difference, selected: bool, width: usize, theme: &Theme) -> Line<'static> {
+    let base = if selected {
+        Style::default().fg(theme.selected_fg).bg(theme.selected_bg)
+    } else {
+        Style::default()
+    };
+    let (marker, color) = match difference.status {
+        Status::OnlyLeft => ("<", theme.success),
+        Status::OnlyRight => (">", theme.success),
+        Status::Kind => ("!", theme.error),
+        Status::Size | Status::Contents | Status::Time => ("≠", theme.warning),
+    };
+    let mut path = difference.path.display().to_string();
+    if difference.directory {
+        path.push('/');
+    }
+    let mut spans = vec![
+        Span::styled(format!(" {} ", marker), base.fg(color)),
+        Span::styled(path, base),
+        Span::styled(
+            format!("  {}", describe(difference)),
+            base.fg(theme.disabled),
+        ),
+    ];
+
+    // Fill the row to highlight the whole selection
+    if selected {
+        let used: usize = spans.iter().map(Span::width).sum();
+        spans.push(Span::styled(" ".repeat(width.saturating_sub(used)), base));
+    }
+    Line::from(spans)
+}
+
+impl Panel for DirComparePanel {
+    fn name(&self) -> &'static str {
+        "dir_compare"
+    }
+
+    fn title(&self) -> String {
+        let name = |path: &Path| {
+            path.file_name().map_or_else(
+                || path.display().to_string(),
+                |n| n.to_string_lossy().into(),
+            )
+        };
+        format!(
+            "{}: {} ⇄ {}",
+            termide_i18n::t().compare_title(),
+            name(&self.left),
+            name(&self.right)
+        )
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 4 {
+            return;
+        }
+        self.render_header(Rect { height: 1, ..area }, buf);
+        self.render_status(
+            Rect {
+                y: area.y + 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+        self.render_list(
+            Rect {
+                y: area.y + 2,
+                height: area.height - 3,
+                ..area
+            },
+            buf,
+        );
+        Paragraph::new(Line::styled(
+            termide_i18n::t().compare_hint(),
+            Style::default().fg(self.cached_theme.disabled),
+        ))
+        .render(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        match key.code {
+            KeyCode::Enter => return self.open_selected(),
+            KeyCode::Char('>') => return self.copy_selected(Side::Right),
+            KeyCode::Char('<') => return self.copy_selected(Side::Left),
+            KeyCode::F(2) => {
+                self.contents = !self.contents;
+                self.start();
+            }
+            KeyCode::Char('r') => self.start(),
+            KeyCode::Esc => self.cancel(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-self.page_size()),
+            KeyCode::PageDown => self.move_selection(self.page_size()),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.move_selection(SCROLL_STEP as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (list, scroll) = self.last_list;
+                let inside = mouse.row >= list.y
+                    && mouse.row < list.y + list.height
+                    && mouse.column >= list.x
+                    && mouse.column < list.x + list.width;
+                let idx = scroll + mouse.row.saturating_sub(list.y) as usize;
+                if inside && idx < self.differences.len() {
+                    self.selected = idx;
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        if self.poll_comparison() {
+            vec![PanelEvent::NeedsRedraw]
+        } else {
+            vec![]
+        }
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        match cmd {
+            PanelCommand::Reload => {
+                self.start();
+                CommandResult::NeedsRedraw(true)
+            }
+            // Commands not applicable to DirComparePanel
+            PanelCommand::GetRepoRoot
+            | PanelCommand::OnGitUpdate { .. }
+            | PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::GetFsWatchInfo
+            | PanelCommand::SetFsWatchRoot { .. }
+            | PanelCommand::OnFsUpdate { .. }
+            | PanelCommand::Resize { .. }
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
+    fn get_working_directory(&self) -> Option<PathBuf> {
+        Some(self.left.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const BUDGET: Budget = Budget {
+        max_depth: 8,
+        max_entries: 1000,
+    };
+
+    /// Tick the panel until its comparison finished
+    fn wait(panel: &mut DirComparePanel) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while panel.state == State::Running && Instant::now() < deadline {
+            panel.tick();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_ne!(panel.state, State::Running, "comparison did not finish");
+    }
+
+    #[test]
+    fn test_open_and_copy_differences() {
+        termide_i18n::init_with_language("en");
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        std::fs::write(left.path().join("a.txt"), "left").unwrap();
+        std::fs::write(right.path().join("a.txt"), "right side").unwrap();
+        std::fs::write(left.path().join("b.txt"), "only left").unwrap();
+
+        let mut panel = DirComparePanel::new(
+            left.path().to_path_buf(),
+            right.path().to_path_buf(),
+            BUDGET,
+        );
+        wait(&mut panel);
+        assert_eq!(panel.differences.len(), 2);
+        assert_eq!(panel.differences[0].status, Status::Size);
+
+        let events = panel.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(
+            events.as_slice(),
+            [PanelEvent::CompareFiles { left: l, right: r }]
+                if l == &left.path().join("a.txt") && r == &right.path().join("a.txt")
+        ));
+
+        // A file missing on the left cannot be copied from there
+        panel.handle_key(KeyEvent::from(KeyCode::Down));
+        assert!(panel
+            .handle_key(KeyEvent::from(KeyCode::Char('<')))
+            .is_empty());
+        panel.handle_key(KeyEvent::from(KeyCode::Char('>')));
+        assert_eq!(
+            std::fs::read_to_string(right.path().join("b.txt")).unwrap(),
+            "only left"
+        );
+        assert_eq!(panel.differences.len(), 1);
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('<')));
+        assert_eq!(
+            std::fs::read_to_string(left.path().join("a.txt")).unwrap(),
+            "right side"
+        );
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('r')));
+        wait(&mut panel);
+        assert!(panel.differences.is_empty());
+        assert_eq!(
+            panel.state,
+            State::Finished {
+                same: 2,
+                truncated: false
+            }
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Comparing two directory trees on a background thread.
+//!
+//! Both trees are walked together in name order, without following
+//! symbolic links. Entries present on one side only are reported without
+//! walking into them; files present on both sides differ by size, then by
+//! contents (when asked to read them) or by modification time. The walk
+//! stops at the depth and entry budget. Dropping the comparison cancels it.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+use std::time::SystemTime;
+
+/// Differences sent together
+const BATCH_SIZE: usize = 64;
+
+/// Bytes read at a time when comparing contents
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Limits of a comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    /// Directory levels walked below the compared directories
+    pub max_depth: usize,
+    /// Entries examined (on both sides together)
+    pub max_entries: usize,
+}
+
+/// How an entry differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    OnlyLeft,
+    OnlyRight,
+    /// Files of different sizes
+    Size,
+    /// Files of the same size with different contents
+    Contents,
+    /// Files of the same size modified at different times (contents not
+    /// compared)
+    Time,
+    /// File or link on one side, directory on the other
+    Kind,
+}
+
+/// Entry that differs between the trees
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// Path relative to the compared directories
+    pub path: PathBuf,
+    pub status: Status,
+    /// The entry is a directory (on the sides it exists on)
+    pub directory: bool,
+    /// Metadata of the entry on the left side
+    pub left: Option<EntryInfo>,
+    /// Metadata of the entry on the right side
+    pub right: Option<EntryInfo>,
+}
+
+/// Metadata of an entry on one side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryInfo {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Message sent by a running comparison
+#[derive(Debug)]
+pub enum CompareUpdate {
+    /// More differences, in path order
+    Differences(Vec<Difference>),
+    /// Both trees were walked (or the budget ran out)
+    Finished {
+        /// Entries present and equal on both sides
+        same: usize,
+        /// The depth or entry budget stopped the walk
+        truncated: bool,
+    },
+}
+
+/// Comparison running on a background thread
+#[derive(Debug)]
+pub struct DirComparison {
+    receiver: Receiver<CompareUpdate>,
+    /// Set to stop the thread
+    cancel: Arc<AtomicBool>,
+}
+
+impl DirComparison {
+    /// Start comparing `left` with `right`, reading the contents of files
+    /// of the same size if `contents`
+    pub fn spawn(left: PathBuf, right: PathBuf, budget: Budget, contents: bool) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = termide_wake::channel();
+
+        let mut walk = Walk {
+            left,
+            right,
+            budget,
+            contents,
+            cancel: Arc::clone(&cancel),
+            sender,
+            batch: Vec::new(),
+            entries: 0,
+            same: 0,
+            truncated: false,
+        };
+        thread::spawn(move || walk.run());
+
+        Self { receiver, cancel }
+    }
+
+    /// Updates received since the previous call
+    pub fn try_updates(&self) -> Vec<CompareUpdate> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for DirComparison {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Kind of a directory entry (symbolic links are not followed)
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    File,
+    Directory,
+    /// Symbolic link with its target
+    Link(PathBuf),
+    Other,
+}
+
+/// Directory entry on one side
+#[derive(Debug, Clone)]
+struct Entry {
+    kind: Kind,
+    info: EntryInfo,
+}
+
+/// State of the walk on the background thread
+struct Walk {
+    left: PathBuf,
+    right: PathBuf,
+    budget: Budget,
+    contents: bool,
+    cancel: Arc<AtomicBool>,
+    sender: termide_wake::Sender<CompareUpdate>,
+    /// Differences not sent yet
+    batch: Vec<Difference>,
+    /// Entries examined
+    entries: usize,
+    same: usize,
+    truncated: bool,
+}
+
+impl Walk {
+    fn run(&mut self) {
+        self.compare_directory(Path::new(""), 0);
+        if self.cancelled() {
+            return;
+        }
+        self.flush();
+        let _ = self.sender.send(CompareUpdate::Finished {
+            same: self.same,
+            truncated: self.truncated,
+        });
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// Send the collected differences
+    fn flush(&mut self) {
+        if self.batch.is_empty() {
+            return;
+        }
+        let batch = std::mem::take(&mut self.batch);
+        if self.sender.send(CompareUpdate::Differences(batch)).is_err() {
+            self.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn push(&mut self, difference: Difference) {
+        self.batch.push(difference);
+        if self.batch.len() >= BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    /// Compare the entries of a directory present on both sides, walking
+    /// into the subdirectories present on both
+    fn compare_directory(&mut self, relative: &Path, depth: usize) {
+        let left = read_entries(&self.left.join(relative));
+        let mut right = read_entries(&self.right.join(relative));
+        let mut names: BTreeMap<OsString, (Option<Entry>, Option<Entry>)> = left
+            .into_iter()
+            .map(|(name, entry)| {
+                let other = right.remove(&name);
+                (name, (Some(entry), other))
+            })
+            .collect();
+        names.extend(
+            right
+                .into_iter()
+                .map(|(name, entry)| (name, (None, Some(entry)))),
+        );
+
+        for (name, sides) in names {
+            if self.cancelled() {
+                return;
+            }
+            self.entries += usize::from(sides.0.is_some()) + usize::from(sides.1.is_some());
+            if self.entries > self.budget.max_entries {
+                // Unwind without cancelling: what was found is kept
+                self.truncated = true;
+                self.budget.max_entries = 0;
+                return;
+            }
+            let path = relative.join(&name);
+            match sides {
+                (Some(left), Some(right))
+                    if left.kind == Kind::Directory && right.kind == Kind::Directory =>
+                {
+                    if depth < self.budget.max_depth {
+                        self.compare_directory(&path, depth + 1);
+                    } else {
+                        self.truncated = true;
+                    }
+                }
+                (Some(left), Some(right)) => match self.compare_entries(&path, &left, &right) {
+                    Some(status) => self.push(Difference {
+                        path,
+                        status,
+                        directory: false,
+                        left: Some(left.info),
+                        right: Some(right.info),
+                    }),
+                    None => self.same += 1,
+                },
+                (Some(left), None) => self.push(Difference {
+                    path,
+                    status: Status::OnlyLeft,
+                    directory: left.kind == Kind::Directory,
+                    left: Some(left.info),
+                    right: None,
+                }),
+                (None, Some(right)) => self.push(Difference {
+                    path,
+                    status: Status::OnlyRight,
+                    directory: right.kind == Kind::Directory,
+                    left: None,
+                    right: Some(right.info),
+                }),
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// How two entries that are not both directories differ (None if they
+    /// are the same)
+    fn compare_entries(&self, path: &Path, left: &Entry, right: &Entry) -> Option<Status> {
+        match (&left.kind, &right.kind) {
+            (Kind::File, Kind::File) => {
+                if left.info.size != right.info.size {
+                    Some(Status::Size)
+                } else if self.contents {
+                    let same =
+                        same_contents(&self.left.join(path), &self.right.join(path), &self.cancel);
+                    (!same.unwrap_or(false)).then_some(Status::Contents)
+                } else {
+                    (!same_time(left.info.modified, right.info.modified)).then_some(Status::Time)
+                }
+            }
+            (Kind::Link(left), Kind::Link(right)) => (left != right).then_some(Status::Contents),
+            (Kind::Other, Kind::Other) => None,
+            _ => Some(Status::Kind),
+        }
+    }
+}
+
+/// Entries of a directory by name (empty if it cannot be read)
+fn read_entries(directory: &Path) -> BTreeMap<OsString, Entry> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let kind = if metadata.is_dir() {
+                Kind::Directory
+            } else if metadata.is_file() {
+                Kind::File
+            } else if metadata.file_type().is_symlink() {
+                Kind::Link(std::fs::read_link(entry.path()).unwrap_or_default())
+            } else {
+                Kind::Other
+            };
+            let info = EntryInfo {
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            };
+            Some((entry.file_name(), Entry { kind, info }))
+        })
+        .collect()
+}
+
+/// Modification times equal to the second (file systems keep them with
+/// different precision)
+fn same_time(left: Option<SystemTime>, right: Option<SystemTime>) -> bool {
+    let seconds = |time: Option<SystemTime>| {
+        time.and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs())
+    };
+    seconds(left) == seconds(right)
+}
+
+/// Whether two files have the same contents, reading both until they differ
+fn same_contents(left: &Path, right: &Path, cancel: &AtomicBool) -> io::Result<bool> {
+    let mut left = File::open(left)?;
+    let mut right = File::open(right)?;
+    let mut left_chunk = vec![0; CHUNK_SIZE];
+    let mut right_chunk = vec![0; CHUNK_SIZE];
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let read = read_chunk(&mut left, &mut left_chunk)?;
+        if read != read_chunk(&mut right, &mut right_chunk)? {
+            return Ok(false);
+        }
+        if read == 0 {
+            return Ok(true);
+        }
+        if left_chunk[..read] != right_chunk[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+/// Fill `chunk` as far as the file allows, returning the bytes read
+fn read_chunk(file: &mut File, chunk: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < chunk.len() {
+        match file.read(&mut chunk[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+/// Copy an entry from one tree to the same place in the other, replacing
+/// a file there. Directories are copied with their contents; modification
+/// times of files are kept, so a copied file compares equal afterwards.
+pub fn copy_entry(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = std::fs::symlink_metadata(from)?;
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Elsewhere the file a link points to is copied
+    #[cfg(unix)]
+    if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(from)?;
+        if std::fs::symlink_metadata(to).is_ok() {
+            std::fs::remove_file(to)?;
+        }
+        return std::os::unix::fs::symlink(target, to);
+    }
+    if metadata.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_entry(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    if let Ok(modified) = metadata.modified() {
+        File::options()
+            .write(true)
+            .open(to)?
+            .set_modified(modified)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const BUDGET: Budget = Budget {
+        max_depth: 8,
+        max_entries: 1000,
+    };
+
+    /// Differences and the finish message of a comparison
+    fn compare(
+        left: &Path,
+        right: &Path,
+        budget: Budget,
+        contents: bool,
+    ) -> (Vec<Difference>, usize, bool) {
+        let comparison = DirComparison::spawn(left.into(), right.into(), budget, contents);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut differences = Vec::new();
+        while Instant::now() < deadline {
+            for update in comparison.try_updates() {
+                match update {
+                    CompareUpdate::Differences(found) => differences.extend(found),
+                    CompareUpdate::Finished { same, truncated } => {
+                        return (differences, same, truncated)
+                    }
+                }
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        panic!("comparison did not finish");
+    }
+
+    fn write(root: &Path, path: &str, text: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    /// Give a file the modification time of another
+    fn copy_time(from: &Path, to: &Path) {
+        let modified = std::fs::metadata(from).unwrap().modified().unwrap();
+        File::options()
+            .write(true)
+            .open(to)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_compare_trees() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        let (l, r) = (left.path(), right.path());
+        for root in [l, r] {
+            write(root, "same.txt", "same");
+            write(root, "src/lib.rs", "fn a() {}");
+        }
+        write(l, "src/main.rs", "fn main() {}");
+        write(r, "src/main.rs", "fn main() { }");
+        write(l, "only_left/a.txt", "a");
+        write(r, "only_right.txt", "b");
+        write(l, "kind", "file");
+        std::fs::create_dir(r.join("kind")).unwrap();
+        // Same size, same time, other contents
+        write(l, "sneaky.txt", "abc");
+        write(r, "sneaky.txt", "abd");
+        for path in ["same.txt", "src/lib.rs", "sneaky.txt"] {
+            copy_time(&l.join(path), &r.join(path));
+        }
+
+        let (differences, same, truncated) = compare(l, r, BUDGET, false);
+        let found: Vec<(&str, Status, bool)> = differences
+            .iter()
+            .map(|d| (d.path.to_str().unwrap(), d.status, d.directory))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("kind", Status::Kind, false),
+                ("only_left", Status::OnlyLeft, true),
+                ("only_right.txt", Status::OnlyRight, false),
+                ("src/main.rs", Status::Size, false),
+            ]
+        );
+        assert_eq!(same, 3);
+        assert!(!truncated);
+
+        // Reading contents finds what the times hide
+        let (differences, same, _) = compare(l, r, BUDGET, true);
+        assert_eq!(differences.len(), 5);
+        assert_eq!(differences[3].path, Path::new("sneaky.txt"));
+        assert_eq!(differences[3].status, Status::Contents);
+        assert_eq!(same, 2);
+    }
+
+    #[test]
+    fn test_budget_stops_walk() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "a/b/c/deep.txt", "x");
+        write(right.path(), "a/b/c/other.txt", "x");
+
+        let shallow = Budget {
+            max_depth: 1,
+            ..BUDGET
+        };
+        let (differences, _, truncated) = compare(left.path(), right.path(), shallow, false);
+        assert!(differences.is_empty());
+        assert!(truncated);
+
+        let few = Budget {
+            max_entries: 2,
+            ..BUDGET
+        };
+        let (_, _, truncated) = compare(left.path(), right.path(), few, false);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_copy_entry_keeps_time() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "dir/a.txt", "a");
+        write(left.path(), "b.txt", "new");
+        write(right.path(), "b.txt", "old!");
+
+        copy_entry(&left.path().join("dir"), &right.path().join("dir")).unwrap();
+        copy_entry(&left.path().join("b.txt"), &right.path().join("b.txt")).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(right.path().join("dir/a.txt")).unwrap(),
+            "a"
+        );
+
+        let (differences, same, _) = compare(left.path(), right.path(), BUDGET, false);
+        assert!(differences.is_empty());
+        assert_eq!(same, 2);
+    }
+}
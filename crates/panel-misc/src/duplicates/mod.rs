@@ -0,0 +1,673 @@
+//! Duplicate files panel.
+//!
+//! Looks for files with the same contents below a directory
+//! (`DuplicateSearch`, on a background thread) and lists them in sets.
+//! Files marked with Space (or every copy but the first with `a`) are
+//! deleted through the usual confirmation; a set cannot lose all its
+//! copies at once.
+
+mod worker;
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext};
+use termide_modal::{ActiveModal, ConfirmModal};
+use termide_state::PendingAction;
+use termide_system_monitor::format_bytes;
+use termide_theme::Theme;
+use termide_ui::path_utils;
+
+use worker::{DuplicateSearch, DuplicatesUpdate};
+pub use worker::{DuplicateSet, Options};
+
+/// Rows moved by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Progress of the search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Listing files (count so far)
+    Listing(usize),
+    /// Hashing files of the same size
+    Hashing { done: u64, total: u64 },
+    Finished {
+        /// Files listed
+        files: usize,
+        /// The file budget stopped the walk
+        truncated: bool,
+    },
+    /// Stopped with Esc
+    Cancelled,
+}
+
+/// Row of the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    /// Heading of a set
+    Set(usize),
+    /// File of a set
+    File(usize, usize),
+}
+
+/// Duplicate files panel
+pub struct DuplicatesPanel {
+    directory: PathBuf,
+    options: Options,
+    /// Running search (dropping it cancels the search)
+    search: Option<DuplicateSearch>,
+    state: State,
+    /// Duplicate sets, most wasted space first once finished
+    sets: Vec<DuplicateSet>,
+    /// Rows of the list (set headings and their files)
+    rows: Vec<Row>,
+    /// Files marked for deletion
+    marked: HashSet<PathBuf>,
+    /// Selected row of the list
+    selected: usize,
+    /// First list row shown
+    scroll: usize,
+    /// List area and first row of the last render (for mouse clicks)
+    last_list: (Rect, usize),
+    /// Modal window request (action, modal)
+    modal_request: Option<(PendingAction, ActiveModal)>,
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl DuplicatesPanel {
+    /// Create a panel looking for duplicates below `directory` and start
+    /// the search
+    pub fn new(directory: PathBuf, options: Options) -> Self {
+        let mut panel = Self {
+            directory,
+            options,
+            search: None,
+            state: State::Listing(0),
+            sets: Vec::new(),
+            rows: Vec::new(),
+            marked: HashSet::new(),
+            selected: 0,
+            scroll: 0,
+            last_list: (Rect::default(), 0),
+            modal_request: None,
+            cached_theme: Theme::default(),
+        };
+        panel.start();
+        panel
+    }
+
+    /// Searched directory
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Take modal window request
+    pub fn take_modal_request(&mut self) -> Option<(PendingAction, ActiveModal)> {
+        self.modal_request.take()
+    }
+
+    /// Drop the files that no longer exist (after a deletion), and the
+    /// sets left with a single file
+    pub fn prune_missing(&mut self) {
+        for set in &mut self.sets {
+            set.files.retain(|path| path.exists());
+        }
+        self.sets.retain(|set| set.files.len() > 1);
+        self.marked.retain(|path| path.exists());
+        self.rebuild_rows();
+    }
+
+    /// Search again, cancelling the running search
+    fn start(&mut self) {
+        self.sets.clear();
+        self.marked.clear();
+        self.rebuild_rows();
+        self.selected = 0;
+        self.scroll = 0;
+        self.search = Some(DuplicateSearch::spawn(self.directory.clone(), self.options));
+        self.state = State::Listing(0);
+    }
+
+    /// Stop the running search, keeping the sets found so far
+    fn cancel(&mut self) {
+        if self.search.take().is_some() {
+            self.state = State::Cancelled;
+        }
+    }
+
+    /// Apply the updates sent by the running search, true if any arrived
+    fn poll_search(&mut self) -> bool {
+        let Some(search) = &self.search else {
+            return false;
+        };
+        let updates = search.try_updates();
+        if updates.is_empty() {
+            return false;
+        }
+        for update in updates {
+            match update {
+                DuplicatesUpdate::Listed(count) => self.state = State::Listing(count),
+                DuplicatesUpdate::Hashing { done, total } => {
+                    self.state = State::Hashing { done, total }
+                }
+                DuplicatesUpdate::Found(sets) => self.sets.extend(sets),
+                DuplicatesUpdate::Finished { files, truncated } => {
+                    self.sets.sort_by(|a, b| {
+                        b.wasted()
+                            .cmp(&a.wasted())
+                            .then_with(|| a.files.cmp(&b.files))
+                    });
+                    self.state = State::Finished { files, truncated };
+                    self.search = None;
+                }
+            }
+        }
+        self.rebuild_rows();
+        true
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows = self
+            .sets
+            .iter()
+            .enumerate()
+            .flat_map(|(set, duplicates)| {
+                std::iter::once(Row::Set(set))
+                    .chain((0..duplicates.files.len()).map(move |file| Row::File(set, file)))
+            })
+            .collect();
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    /// Move the selection by `delta` rows (clamped to the list)
+    fn move_selection(&mut self, delta: isize) {
+        let count = self.rows.len();
+        if count == 0 {
+            return;
+        }
+        self.selected = self.selected.saturating_add_signed(delta).min(count - 1);
+    }
+
+    /// Rows moved by PageUp/PageDown
+    fn page_size(&self) -> isize {
+        self.last_list.0.height.max(1) as isize
+    }
+
+    /// File of the selected row
+    fn selected_file(&self) -> Option<&Path> {
+        match self.rows.get(self.selected)? {
+            Row::File(set, file) => Some(&self.sets[*set].files[*file]),
+            Row::Set(_) => None,
+        }
+    }
+
+    /// Mark or unmark the selected file; on a set heading, every copy but
+    /// the first
+    fn toggle_selected(&mut self) {
+        match self.rows.get(self.selected) {
+            Some(&Row::File(set, file)) => {
+                let path = &self.sets[set].files[file];
+                if !self.marked.remove(path) {
+                    self.marked.insert(path.clone());
+                }
+            }
+            Some(&Row::Set(set)) => {
+                let extras = &self.sets[set].files[1..];
+                if extras.iter().all(|path| self.marked.contains(path)) {
+                    for path in extras {
+                        self.marked.remove(path);
+                    }
+                } else {
+                    self.marked.extend(extras.iter().cloned());
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Mark every copy but the first in all sets
+    fn mark_extras(&mut self) {
+        self.marked = self
+            .sets
+            .iter()
+            .flat_map(|set| set.files[1..].iter().cloned())
+            .collect();
+    }
+
+    /// Ask to delete the marked files (or the selected one when none is
+    /// marked)
+    fn delete_marked(&mut self) -> Vec<PanelEvent> {
+        let t = termide_i18n::t();
+        let mut paths: Vec<PathBuf> = if self.marked.is_empty() {
+            self.selected_file()
+                .map(Path::to_path_buf)
+                .into_iter()
+                .collect()
+        } else {
+            self.marked.iter().cloned().collect()
+        };
+        if paths.is_empty() {
+            return vec![];
+        }
+        // At least one copy of each set stays
+        let targets: HashSet<&PathBuf> = paths.iter().collect();
+        if self
+            .sets
+            .iter()
+            .any(|set| set.files.iter().all(|path| targets.contains(path)))
+        {
+            return vec![PanelEvent::SetStatusMessage {
+                message: t.duplicates_keep_one().to_string(),
+                is_error: true,
+            }];
+        }
+        paths.sort();
+
+        let title = if paths.len() == 1 {
+            t.modal_delete_single_title(path_utils::get_file_name_str(&paths[0]))
+        } else {
+            t.modal_delete_multiple_title(paths.len())
+        };
+        let modal = ConfirmModal::new(&title, "");
+        let action = PendingAction::DeletePath {
+            panel_index: 0, // will be updated in app.rs
+            paths,
+        };
+        self.modal_request = Some((action, ActiveModal::Confirm(Box::new(modal))));
+        vec![]
+    }
+
+    /// Render the line with the searched directory and the toggles
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let toggle = |on: bool| {
+            if on {
+                Style::default()
+                    .fg(theme.accented_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.disabled)
+            }
+        };
+        let line = Line::from(vec![
+            Span::styled(
+                format!("[{}] ", t.duplicates_recursive()),
+                toggle(self.options.recursive),
+            ),
+            Span::styled(
+                format!("[{}] ", t.duplicates_skip_ignored()),
+                toggle(self.options.skip_ignored),
+            ),
+            Span::styled(
+                self.directory.display().to_string(),
+                Style::default().fg(theme.fg),
+            ),
+        ]);
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the line with the search progress
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let count = self.sets.len();
+        let mut spans = match self.state {
+            State::Listing(files) => vec![Span::styled(
+                t.duplicates_listing(files),
+                Style::default().fg(theme.warning),
+            )],
+            State::Hashing { done, total } => {
+                let percent = (done * 100).checked_div(total).unwrap_or(0).min(100);
+                vec![
+                    Span::styled(
+                        t.duplicates_found(count),
+                        Style::default().fg(theme.disabled),
+                    ),
+                    Span::styled(
+                        format!("  {}", t.duplicates_hashing(percent as usize)),
+                        Style::default().fg(theme.warning),
+                    ),
+                ]
+            }
+            State::Cancelled => vec![Span::styled(
+                format!("{} ({})", t.duplicates_found(count), t.compare_cancelled()),
+                Style::default().fg(theme.warning),
+            )],
+            State::Finished { .. } if count == 0 => vec![Span::styled(
+                t.duplicates_none(),
+                Style::default().fg(theme.success),
+            )],
+            State::Finished { .. } => {
+                let wasted = self.sets.iter().map(DuplicateSet::wasted).sum();
+                vec![Span::styled(
+                    t.duplicates_summary(count, &format_bytes(wasted)),
+                    Style::default().fg(theme.success),
+                )]
+            }
+        };
+        if let State::Finished {
+            files,
+            truncated: true,
+        } = self.state
+        {
+            spans.push(Span::styled(
+                format!(" ({})", t.duplicates_truncated(files)),
+                Style::default().fg(theme.warning),
+            ));
+        }
+        if !self.marked.is_empty() {
+            spans.push(Span::styled(
+                format!("  {}", t.duplicates_marked(self.marked.len())),
+                Style::default().fg(theme.accented_fg),
+            ));
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+
+    /// Render the list of sets
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = self.cached_theme;
+        self.last_list = (area, self.scroll);
+        if area.height == 0 {
+            return;
+        }
+
+        // Keep the selection visible
+        let height = area.height as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let width = area.width as usize;
+        let lines: Vec<Line> = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(height)
+            .map(|(idx, row)| self.row_line(*row, idx == self.selected, width, &theme))
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+        self.last_list = (area, self.scroll);
+    }
+
+    /// Line of a set heading or a file
+    fn row_line(&self, row: Row, selected: bool, width: usize, theme: &Theme) -> Line<'static> {
+        let base = if selected {
+            Style::default().fg(theme.selected_fg).bg(theme.selected_bg)
+        } else {
+            Style::default()
+        };
+        let mut spans = match row {
+            Row::Set(set) => {
+                let set = &self.sets[set];
+                vec![Span::styled(
+                    termide_i18n::t().duplicates_set(set.files.len(), &format_bytes(set.size)),
+                    base.fg(theme.accented_fg).add_modifier(Modifier::BOLD),
+                )]
+            }
+            Row::File(set, file) => {
+                let path = &self.sets[set].files[file];
+                let marked = self.marked.contains(path);
+                let shown = path.strip_prefix(&self.directory).unwrap_or(path);
+                vec![
+                    Span::styled(
+                        if marked { "  [x] " } else { "  [ ] " },
+                        base.fg(if marked { theme.error } else { theme.disabled }),
+                    ),
+                    Span::styled(shown.display().to_string(), base),
+                ]
+            }
+        };
+
+        // Fill the row to highlight the whole selection
+        if selected {
+            let used: usize = spans.iter().map(Span::width).sum();
+            spans.push(Span::styled(" ".repeat(width.saturating_sub(used)), base));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Panel for DuplicatesPanel {
+    fn name(&self) -> &'static str {
+        "duplicates"
+    }
+
+    fn title(&self) -> String {
+        let name = self.directory.file_name().map_or_else(
+            || self.directory.display().to_string(),
+            |n| n.to_string_lossy().into(),
+        );
+        format!("{}: {}", termide_i18n::t().duplicates_title(), name)
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 4 {
+            return;
+        }
+        self.render_header(Rect { height: 1, ..area }, buf);
+        self.render_status(
+            Rect {
+                y: area.y + 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+        self.render_list(
+            Rect {
+                y: area.y + 2,
+                height: area.height - 3,
+                ..area
+            },
+            buf,
+        );
+        Paragraph::new(Line::styled(
+            termide_i18n::t().duplicates_hint(),
+            Style::default().fg(self.cached_theme.disabled),
+        ))
+        .render(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        match key.code {
+            KeyCode::Enter => {
+                if let Some(path) = self.selected_file() {
+                    return vec![PanelEvent::open_file(path.to_path_buf())];
+                }
+            }
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Insert => {
+                self.toggle_selected();
+                self.move_selection(1);
+            }
+            KeyCode::Char('a') => self.mark_extras(),
+            KeyCode::Delete | KeyCode::F(8) => return self.delete_marked(),
+            KeyCode::F(2) => {
+                self.options.recursive = !self.options.recursive;
+                self.start();
+            }
+            KeyCode::F(3) => {
+                self.options.skip_ignored = !self.options.skip_ignored;
+                self.start();
+            }
+            KeyCode::Char('r') => self.start(),
+            KeyCode::Esc => self.cancel(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-self.page_size()),
+            KeyCode::PageDown => self.move_selection(self.page_size()),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.move_selection(SCROLL_STEP as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (list, scroll) = self.last_list;
+                let inside = mouse.row >= list.y
+                    && mouse.row < list.y + list.height
+                    && mouse.column >= list.x
+                    && mouse.column < list.x + list.width;
+                let idx = scroll + mouse.row.saturating_sub(list.y) as usize;
+                if inside && idx < self.rows.len() {
+                    self.selected = idx;
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        if self.poll_search() {
+            vec![PanelEvent::NeedsRedraw]
+        } else {
+            vec![]
+        }
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        match cmd {
+            PanelCommand::Reload => {
+                self.start();
+                CommandResult::NeedsRedraw(true)
+            }
+            // Commands not applicable to DuplicatesPanel
+            PanelCommand::GetRepoRoot
+            | PanelCommand::OnGitUpdate { .. }
+            | PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::GetFsWatchInfo
+            | PanelCommand::SetFsWatchRoot { .. }
+            | PanelCommand::OnFsUpdate { .. }
+            | PanelCommand::Resize { .. }
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
+    fn get_working_directory(&self) -> Option<PathBuf> {
+        Some(self.directory.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const OPTIONS: Options = Options {
+        recursive: true,
+        skip_ignored: false,
+        max_files: 1000,
+    };
+
+    /// Tick the panel until its search finished
+    fn wait(panel: &mut DuplicatesPanel) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while panel.search.is_some() && Instant::now() < deadline {
+            panel.tick();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(panel.search.is_none(), "search did not finish");
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::from(code)
+    }
+
+    #[test]
+    fn test_mark_and_delete_extras() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for name in ["a", "b", "c"] {
+            std::fs::write(root.join(name), "copy").unwrap();
+        }
+        std::fs::write(root.join("x"), "pair").unwrap();
+        std::fs::write(root.join("y"), "pair").unwrap();
+
+        let mut panel = DuplicatesPanel::new(root.to_path_buf(), OPTIONS);
+        wait(&mut panel);
+        assert_eq!(panel.sets.len(), 2);
+        assert_eq!(panel.rows.len(), 7);
+        // Same size: the set of three wastes more
+        assert_eq!(panel.sets[0].files.len(), 3);
+
+        // Deleting every copy of a set is refused
+        panel.handle_key(key(KeyCode::Down));
+        panel.handle_key(key(KeyCode::Char(' ')));
+        panel.handle_key(key(KeyCode::Down));
+        panel.handle_key(key(KeyCode::Insert));
+        panel.handle_key(key(KeyCode::Char(' ')));
+        let events = panel.handle_key(key(KeyCode::Delete));
+        assert!(matches!(
+            events.as_slice(),
+            [PanelEvent::SetStatusMessage { is_error: true, .. }]
+        ));
+        assert!(panel.take_modal_request().is_none());
+
+        panel.handle_key(key(KeyCode::Char('a')));
+        assert_eq!(panel.marked.len(), 3);
+        panel.handle_key(key(KeyCode::Delete));
+        let Some((PendingAction::DeletePath { paths, .. }, ActiveModal::Confirm(_))) =
+            panel.take_modal_request()
+        else {
+            panic!("no delete confirmation");
+        };
+        assert_eq!(paths, [root.join("b"), root.join("c"), root.join("y")]);
+
+        for path in &paths {
+            std::fs::remove_file(path).unwrap();
+        }
+        panel.prune_missing();
+        assert!(panel.sets.is_empty());
+        assert!(panel.marked.is_empty());
+        assert!(panel.rows.is_empty());
+    }
+}
@@ -0,0 +1,403 @@
+//! Looking for duplicate files on a background thread.
+//!
+//! The files of a directory (and of its subdirectories when asked to,
+//! without following symbolic links) are grouped by size; files sharing a
+//! size are then hashed in chunks, and files sharing a hash make up a
+//! duplicate set. Empty files are left out. The walk stops at the file
+//! budget. Dropping the search cancels it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+
+use termide_git::GitStatusCache;
+use termide_panel_file_manager::checksum::hash_file;
+
+/// Files listed between two progress reports
+const LISTED_STEP: usize = 512;
+
+/// Bytes hashed between two progress reports
+const HASHED_STEP: u64 = 8 * 1024 * 1024;
+
+/// What a search looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Walk into subdirectories
+    pub recursive: bool,
+    /// Leave out files ignored by git (and git's own files)
+    pub skip_ignored: bool,
+    /// Files listed before the walk stops
+    pub max_files: usize,
+}
+
+/// Files with the same contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateSet {
+    /// Size of each file
+    pub size: u64,
+    /// The files, in path order
+    pub files: Vec<PathBuf>,
+}
+
+impl DuplicateSet {
+    /// Bytes freed by keeping a single copy
+    pub fn wasted(&self) -> u64 {
+        self.size * (self.files.len() as u64).saturating_sub(1)
+    }
+}
+
+/// Message sent by a running search
+#[derive(Debug)]
+pub enum DuplicatesUpdate {
+    /// Files listed so far
+    Listed(usize),
+    /// Bytes hashed so far of the bytes to hash
+    Hashing { done: u64, total: u64 },
+    /// More duplicate sets, largest files first
+    Found(Vec<DuplicateSet>),
+    /// Every listed file was compared
+    Finished {
+        /// Files listed
+        files: usize,
+        /// The file budget stopped the walk
+        truncated: bool,
+    },
+}
+
+/// Search running on a background thread
+#[derive(Debug)]
+pub struct DuplicateSearch {
+    receiver: Receiver<DuplicatesUpdate>,
+    /// Set to stop the thread
+    cancel: Arc<AtomicBool>,
+}
+
+impl DuplicateSearch {
+    /// Start looking for duplicates below `root`
+    pub fn spawn(root: PathBuf, options: Options) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = termide_wake::channel();
+
+        let mut search = Search {
+            root,
+            options,
+            cancel: Arc::clone(&cancel),
+            sender,
+            ignored: None,
+            files: Vec::new(),
+            truncated: false,
+        };
+        thread::spawn(move || search.run());
+
+        Self { receiver, cancel }
+    }
+
+    /// Updates received since the previous call
+    pub fn try_updates(&self) -> Vec<DuplicatesUpdate> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for DuplicateSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Files git ignores below the searched directory
+struct Ignored {
+    status: GitStatusCache,
+    /// Searched directory relative to the repository root
+    prefix: PathBuf,
+}
+
+/// State of the search on the background thread
+struct Search {
+    root: PathBuf,
+    options: Options,
+    cancel: Arc<AtomicBool>,
+    sender: termide_wake::Sender<DuplicatesUpdate>,
+    ignored: Option<Ignored>,
+    /// Files listed with their sizes
+    files: Vec<(PathBuf, u64)>,
+    truncated: bool,
+}
+
+impl Search {
+    fn run(&mut self) {
+        if self.options.skip_ignored {
+            self.ignored = ignored_files(&self.root);
+        }
+        let root = self.root.clone();
+        self.list_directory(&root);
+        if self.cancelled() {
+            return;
+        }
+        let listed = self.files.len();
+        self.send(DuplicatesUpdate::Listed(listed));
+
+        // Only files sharing a size can be the same
+        let mut by_size: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+        for (path, size) in std::mem::take(&mut self.files) {
+            if size > 0 {
+                by_size.entry(size).or_default().push(path);
+            }
+        }
+        by_size.retain(|_, files| files.len() > 1);
+        let total: u64 = by_size
+            .iter()
+            .map(|(size, files)| size * files.len() as u64)
+            .sum();
+
+        let mut done = 0;
+        let mut next_report = 0;
+        for (size, files) in by_size.into_iter().rev() {
+            let mut by_hash: HashMap<[u8; 32], Vec<PathBuf>> = HashMap::new();
+            for path in files {
+                let start = done;
+                let digest = hash_file(&path, &mut |read| {
+                    let hashed = start + read;
+                    if hashed >= next_report {
+                        next_report = hashed + HASHED_STEP;
+                        self.send(DuplicatesUpdate::Hashing {
+                            done: hashed,
+                            total,
+                        });
+                    }
+                    !self.cancelled()
+                });
+                done = start + size;
+                match digest {
+                    Ok(Some(digest)) => by_hash.entry(digest).or_default().push(path),
+                    Ok(None) => return,
+                    // Unreadable files are left out
+                    Err(_) => {}
+                }
+            }
+            let mut sets: Vec<DuplicateSet> = by_hash
+                .into_values()
+                .filter(|files| files.len() > 1)
+                .map(|mut files| {
+                    files.sort();
+                    DuplicateSet { size, files }
+                })
+                .collect();
+            if !sets.is_empty() {
+                sets.sort_by(|a, b| a.files.cmp(&b.files));
+                self.send(DuplicatesUpdate::Found(sets));
+            }
+        }
+        if self.cancelled() {
+            return;
+        }
+        self.send(DuplicatesUpdate::Finished {
+            files: listed,
+            truncated: self.truncated,
+        });
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// Send an update, stopping the search if nobody is listening
+    fn send(&self, update: DuplicatesUpdate) {
+        if self.sender.send(update).is_err() {
+            self.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether `path` is left out as ignored by git
+    fn is_ignored(&self, path: &Path) -> bool {
+        if !self.options.skip_ignored {
+            return false;
+        }
+        if path.file_name().is_some_and(|name| name == ".git") {
+            return true;
+        }
+        let Some(ignored) = &self.ignored else {
+            return false;
+        };
+        path.strip_prefix(&self.root).is_ok_and(|relative| {
+            ignored
+                .status
+                .is_path_in_ignored(&ignored.prefix.join(relative))
+        })
+    }
+
+    /// List the files of a directory, then walk into its subdirectories
+    fn list_directory(&mut self, directory: &Path) {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut subdirectories = Vec::new();
+        for entry in entries {
+            if self.cancelled() || self.truncated {
+                return;
+            }
+            // The type of the entry itself: links are not followed
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if self.is_ignored(&path) {
+                continue;
+            }
+            if file_type.is_dir() {
+                subdirectories.push(path);
+            } else if file_type.is_file() {
+                if self.files.len() >= self.options.max_files {
+                    // Stop without cancelling: what was listed is compared
+                    self.truncated = true;
+                    return;
+                }
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                self.files.push((path, metadata.len()));
+                if self.files.len().is_multiple_of(LISTED_STEP) {
+                    self.send(DuplicatesUpdate::Listed(self.files.len()));
+                }
+            }
+        }
+        if self.options.recursive {
+            for subdirectory in subdirectories {
+                self.list_directory(&subdirectory);
+            }
+        }
+    }
+}
+
+/// Ignored files of the repository holding `root`, if any
+fn ignored_files(root: &Path) -> Option<Ignored> {
+    let status = termide_git::get_git_status(root)?;
+    let canonical = root.canonicalize().ok()?;
+    let prefix = canonical
+        .strip_prefix(status.repo_root())
+        .ok()?
+        .to_path_buf();
+    Some(Ignored { status, prefix })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const OPTIONS: Options = Options {
+        recursive: true,
+        skip_ignored: false,
+        max_files: 1000,
+    };
+
+    /// Duplicate sets and the finish message of a search
+    fn search(root: &Path, options: Options) -> (Vec<DuplicateSet>, usize, bool) {
+        let search = DuplicateSearch::spawn(root.into(), options);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut sets = Vec::new();
+        while Instant::now() < deadline {
+            for update in search.try_updates() {
+                match update {
+                    DuplicatesUpdate::Found(found) => sets.extend(found),
+                    DuplicatesUpdate::Finished { files, truncated } => {
+                        return (sets, files, truncated)
+                    }
+                    DuplicatesUpdate::Listed(_) | DuplicatesUpdate::Hashing { .. } => {}
+                }
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        panic!("search did not finish");
+    }
+
+    fn write(root: &Path, path: &str, text: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "a.txt", "same text");
+        write(root, "sub/b.txt", "same text");
+        write(root, "sub/deeper/c.txt", "same text");
+        // Same size, other contents
+        write(root, "d.txt", "other txt");
+        write(root, "big1", "longer contents");
+        write(root, "big2", "longer contents");
+        write(root, "empty1", "");
+        write(root, "empty2", "");
+
+        let (sets, files, truncated) = search(root, OPTIONS);
+        assert_eq!(files, 8);
+        assert!(!truncated);
+        assert_eq!(
+            sets,
+            [
+                DuplicateSet {
+                    size: 15,
+                    files: vec![root.join("big1"), root.join("big2")],
+                },
+                DuplicateSet {
+                    size: 9,
+                    files: vec![
+                        root.join("a.txt"),
+                        root.join("sub/b.txt"),
+                        root.join("sub/deeper/c.txt")
+                    ],
+                },
+            ]
+        );
+        assert_eq!(sets[1].wasted(), 18);
+
+        let flat = Options {
+            recursive: false,
+            ..OPTIONS
+        };
+        let (sets, files, _) = search(root, flat);
+        assert_eq!(files, 6);
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].size, 15);
+    }
+
+    #[test]
+    fn test_file_budget_truncates() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c", "d"] {
+            write(dir.path(), name, "x");
+        }
+        let few = Options {
+            max_files: 2,
+            ..OPTIONS
+        };
+        let (sets, files, truncated) = search(dir.path(), few);
+        assert_eq!(files, 2);
+        assert!(truncated);
+        assert_eq!(sets[0].files, [dir.path().join("a"), dir.path().join("b")]);
+    }
+
+    #[test]
+    fn test_skip_git_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), ".git/objects/x", "blob");
+        write(dir.path(), "copy", "blob");
+
+        let skip = Options {
+            skip_ignored: true,
+            ..OPTIONS
+        };
+        let (sets, files, _) = search(dir.path(), skip);
+        assert_eq!(files, 1);
+        assert!(sets.is_empty());
+        let (sets, _, _) = search(dir.path(), OPTIONS);
+        assert_eq!(sets.len(), 1);
+    }
+}
@@ -0,0 +1,802 @@
+//! Git panel.
+//!
+//! Lists the staged and unstaged files of a repository with the current
+//! branch and its ahead/behind counts. Files (or single hunks, once a file
+//! is expanded) are staged and unstaged from the list, and staged changes
+//! are committed with a message entered in a multi-line modal.
+//!
+//! The panel does not poll: it reports its repository through
+//! `GetRepoRoot`, so the git and filesystem watchers send it updates, and
+//! reloads on the next tick after one arrived.
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext};
+use termide_git::{FileChange, FilePatch, GitRepoStatus, GitStatus, GitStatusCache};
+use termide_modal::{ActiveModal, MultilineInputModal};
+use termide_state::PendingAction;
+use termide_theme::Theme;
+
+/// Rows moved by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Part of the list a file is shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Section {
+    /// Changes in the index
+    Staged,
+    /// Changes in the working tree and untracked files
+    Unstaged,
+}
+
+/// Row of the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    /// Section title
+    Header(Section),
+    /// File (index into `changes`)
+    File(Section, usize),
+    /// Hunk of an expanded file
+    Hunk(Section, usize, usize),
+}
+
+/// Selected row, kept across reloads
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Selection {
+    section: Section,
+    path: PathBuf,
+    hunk: Option<usize>,
+}
+
+/// Git status panel
+pub struct GitPanel {
+    /// Directory the panel was opened in
+    directory: PathBuf,
+    /// Status of the repository (None outside a repository)
+    status: Option<GitStatusCache>,
+    /// Checked out branch
+    branch: Option<String>,
+    /// Commits ahead of and behind the upstream branch
+    repo_status: Option<GitRepoStatus>,
+    /// Diffs of expanded files
+    expanded: HashMap<(Section, PathBuf), FilePatch>,
+    selected: Option<Selection>,
+    /// First list row shown
+    scroll: usize,
+    /// List area and first row of the last render (for mouse clicks)
+    last_list: (Rect, usize),
+    /// A watcher reported changes: reload on the next tick
+    stale: bool,
+    /// Result of the last operation (message, is error)
+    status_message: Option<(String, bool)>,
+    /// Commit message modal to open
+    modal_request: Option<(PendingAction, ActiveModal)>,
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl GitPanel {
+    /// Create a panel for the repository containing `directory`
+    pub fn new(directory: PathBuf) -> Self {
+        let mut panel = Self {
+            directory,
+            status: None,
+            branch: None,
+            repo_status: None,
+            expanded: HashMap::new(),
+            selected: None,
+            scroll: 0,
+            last_list: (Rect::default(), 0),
+            stale: false,
+            status_message: None,
+            modal_request: None,
+            cached_theme: Theme::default(),
+        };
+        panel.reload();
+        panel
+    }
+
+    /// Root of the repository (None outside a repository)
+    pub fn repo_root(&self) -> Option<&Path> {
+        self.status.as_ref().map(GitStatusCache::repo_root)
+    }
+
+    /// Take modal window request (if any).
+    pub fn take_modal_request(&mut self) -> Option<(PendingAction, ActiveModal)> {
+        self.modal_request.take()
+    }
+
+    /// Read the status of the repository again
+    fn reload(&mut self) {
+        self.stale = false;
+        self.status = termide_git::get_git_status(&self.directory);
+        let Some(repo_root) = self.repo_root().map(Path::to_path_buf) else {
+            self.branch = None;
+            self.repo_status = None;
+            self.expanded.clear();
+            return;
+        };
+        self.branch = termide_git::current_branch(&repo_root);
+        self.repo_status = termide_git::get_repo_status(&repo_root, &repo_root);
+
+        // Expanded files keep showing their current hunks
+        let keys: Vec<(Section, PathBuf)> = self.expanded.keys().cloned().collect();
+        for (section, path) in keys {
+            let changed = self
+                .changes()
+                .iter()
+                .any(|change| change.path == path && in_section(change, section));
+            let patch = changed
+                .then(|| termide_git::file_patch(&repo_root, &path, section == Section::Staged))
+                .and_then(Result::ok);
+            match patch {
+                Some(patch) => self.expanded.insert((section, path), patch),
+                None => self.expanded.remove(&(section, path)),
+            };
+        }
+    }
+
+    /// Changed files of the repository
+    fn changes(&self) -> &[FileChange] {
+        self.status
+            .as_ref()
+            .map(GitStatusCache::changes)
+            .unwrap_or_default()
+    }
+
+    /// List rows: each section title followed by its files and their hunks
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for section in [Section::Staged, Section::Unstaged] {
+            rows.push(Row::Header(section));
+            for (idx, change) in self.changes().iter().enumerate() {
+                if !in_section(change, section) {
+                    continue;
+                }
+                rows.push(Row::File(section, idx));
+                if let Some(patch) = self.expanded.get(&(section, change.path.clone())) {
+                    rows.extend((0..patch.hunks.len()).map(|hunk| Row::Hunk(section, idx, hunk)));
+                }
+            }
+        }
+        rows
+    }
+
+    /// Selection pointing at a row
+    fn selection_of(&self, row: Row) -> Option<Selection> {
+        let (section, idx, hunk) = match row {
+            Row::Header(_) => return None,
+            Row::File(section, idx) => (section, idx, None),
+            Row::Hunk(section, idx, hunk) => (section, idx, Some(hunk)),
+        };
+        Some(Selection {
+            section,
+            path: self.changes()[idx].path.clone(),
+            hunk,
+        })
+    }
+
+    /// Row index of the selection (the first file if it is gone)
+    fn selected_row(&self, rows: &[Row]) -> Option<usize> {
+        let selected = rows
+            .iter()
+            .position(|&row| self.selected.is_some() && self.selection_of(row) == self.selected);
+        // A staged or unstaged file may have moved to the other section
+        let same_file = || {
+            let path = &self.selected.as_ref()?.path;
+            rows.iter().position(
+                |&row| matches!(row, Row::File(_, idx) if self.changes()[idx].path == *path),
+            )
+        };
+        selected
+            .or_else(same_file)
+            .or_else(|| rows.iter().position(|row| !matches!(row, Row::Header(_))))
+    }
+
+    /// Selected row
+    fn current_row(&self) -> Option<Row> {
+        let rows = self.rows();
+        self.selected_row(&rows).map(|idx| rows[idx])
+    }
+
+    /// Move the selection by `delta` selectable rows
+    fn move_selection(&mut self, delta: isize) {
+        let rows: Vec<Row> = self
+            .rows()
+            .into_iter()
+            .filter(|row| !matches!(row, Row::Header(_)))
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+        let current = self
+            .current_row()
+            .and_then(|row| rows.iter().position(|&r| r == row))
+            .unwrap_or(0);
+        let target = current.saturating_add_signed(delta).min(rows.len() - 1);
+        self.selected = self.selection_of(rows[target]);
+    }
+
+    /// Rows moved by PageUp/PageDown (the list height)
+    fn page_size(&self) -> isize {
+        self.last_list.0.height.max(1) as isize
+    }
+
+    /// Show the hunks of the selected file
+    fn expand_selected(&mut self) {
+        let (Some(Row::File(section, idx)), Some(repo_root)) =
+            (self.current_row(), self.repo_root())
+        else {
+            return;
+        };
+        let path = self.changes()[idx].path.clone();
+        match termide_git::file_patch(repo_root, &path, section == Section::Staged) {
+            Ok(patch) => {
+                self.expanded.insert((section, path), patch);
+            }
+            Err(e) => self.set_error(&e),
+        }
+    }
+
+    /// Hide the hunks of the selected file, selecting the file
+    fn collapse_selected(&mut self) {
+        let Some(mut selection) = self.selected.clone() else {
+            return;
+        };
+        self.expanded
+            .remove(&(selection.section, selection.path.clone()));
+        selection.hunk = None;
+        self.selected = Some(selection);
+    }
+
+    /// Stage or unstage the selected file or hunk
+    fn toggle_selected(&mut self) {
+        match self.current_row() {
+            Some(Row::File(Section::Unstaged, _) | Row::Hunk(Section::Unstaged, _, _)) => {
+                self.stage_selected()
+            }
+            Some(Row::File(Section::Staged, _) | Row::Hunk(Section::Staged, _, _)) => {
+                self.unstage_selected()
+            }
+            _ => {}
+        }
+    }
+
+    /// Stage the selected unstaged file or hunk
+    fn stage_selected(&mut self) {
+        let Some(repo_root) = self.repo_root().map(Path::to_path_buf) else {
+            return;
+        };
+        let result = match self.current_row() {
+            Some(Row::File(Section::Unstaged, idx)) => {
+                termide_git::stage_file(&repo_root, &self.changes()[idx].path)
+            }
+            Some(Row::Hunk(Section::Unstaged, idx, hunk)) => {
+                let path = self.changes()[idx].path.clone();
+                let patch = &self.expanded[&(Section::Unstaged, path)];
+                termide_git::stage_hunk(&repo_root, patch, &patch.hunks[hunk])
+            }
+            _ => return,
+        };
+        self.finish_operation(result);
+    }
+
+    /// Unstage the selected staged file or hunk
+    fn unstage_selected(&mut self) {
+        let Some(repo_root) = self.repo_root().map(Path::to_path_buf) else {
+            return;
+        };
+        let result = match self.current_row() {
+            Some(Row::File(Section::Staged, idx)) => {
+                termide_git::unstage_file(&repo_root, &self.changes()[idx].path)
+            }
+            Some(Row::Hunk(Section::Staged, idx, hunk)) => {
+                let path = self.changes()[idx].path.clone();
+                let patch = &self.expanded[&(Section::Staged, path)];
+                termide_git::unstage_hunk(&repo_root, patch, &patch.hunks[hunk])
+            }
+            _ => return,
+        };
+        self.finish_operation(result);
+    }
+
+    /// Show an operation's error, or the new status after it succeeded
+    fn finish_operation(&mut self, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => self.reload(),
+            Err(e) => self.set_error(&e),
+        }
+    }
+
+    /// Ask for the commit message of the staged changes
+    fn request_commit(&mut self) {
+        let t = termide_i18n::t();
+        let Some(repo_root) = self.repo_root().map(Path::to_path_buf) else {
+            return;
+        };
+        let staged = self
+            .changes()
+            .iter()
+            .filter(|change| change.staged.is_some())
+            .count();
+        if staged == 0 {
+            self.status_message = Some((t.git_nothing_staged().to_string(), true));
+            return;
+        }
+
+        let branch = self.branch.as_deref().unwrap_or("HEAD");
+        let modal =
+            MultilineInputModal::new(t.git_commit_title(), t.git_commit_prompt(staged, branch));
+        self.modal_request = Some((
+            PendingAction::GitCommit { repo_root },
+            ActiveModal::MultilineInput(Box::new(modal)),
+        ));
+    }
+
+    /// Open the selected file in an editor
+    fn open_selected(&mut self) -> Vec<PanelEvent> {
+        let idx = match self.current_row() {
+            Some(Row::File(_, idx) | Row::Hunk(_, idx, _)) => idx,
+            _ => return vec![],
+        };
+        let Some(repo_root) = self.repo_root() else {
+            return vec![];
+        };
+        let path = repo_root.join(&self.changes()[idx].path);
+        if path.is_file() {
+            vec![PanelEvent::open_file(path)]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Compare the selected file on disk with its version in HEAD
+    fn diff_selected(&self) -> Vec<PanelEvent> {
+        let idx = match self.current_row() {
+            Some(Row::File(_, idx) | Row::Hunk(_, idx, _)) => idx,
+            _ => return vec![],
+        };
+        let Some(repo_root) = self.repo_root() else {
+            return vec![];
+        };
+        let path = repo_root.join(&self.changes()[idx].path);
+        // A deleted file compares as empty
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        vec![PanelEvent::DiffAgainstHead { path, content }]
+    }
+
+    fn set_error(&mut self, error: &anyhow::Error) {
+        let message = termide_i18n::t().git_error(&error.to_string());
+        self.status_message = Some((message, true));
+    }
+
+    /// Render the branch line
+    fn render_branch(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.cached_theme;
+        let mut spans = vec![Span::styled(
+            format!("⎇ {}", self.branch.as_deref().unwrap_or("HEAD")),
+            Style::default()
+                .fg(theme.accented_fg)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if let Some(status) = self.repo_status {
+            if status.ahead > 0 {
+                spans.push(Span::styled(
+                    format!(" ↑{}", status.ahead),
+                    Style::default().fg(theme.success),
+                ));
+            }
+            if status.behind > 0 {
+                spans.push(Span::styled(
+                    format!(" ↓{}", status.behind),
+                    Style::default().fg(theme.warning),
+                ));
+            }
+        }
+        if let Some(root) = self.repo_root() {
+            spans.push(Span::styled(
+                format!("  {}", root.display()),
+                Style::default().fg(theme.disabled),
+            ));
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+
+    /// Render the bottom line: the last operation's result or the key hint
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.cached_theme;
+        let line = match &self.status_message {
+            Some((message, is_error)) => {
+                let color = if *is_error {
+                    theme.error
+                } else {
+                    theme.success
+                };
+                Line::styled(message.clone(), Style::default().fg(color))
+            }
+            None => Line::styled(
+                termide_i18n::t().git_hint(),
+                Style::default().fg(theme.disabled),
+            ),
+        };
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the list of changes
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = self.cached_theme;
+        if area.height == 0 {
+            return;
+        }
+        if self.changes().is_empty() {
+            Paragraph::new(Line::styled(
+                t.git_no_changes(),
+                Style::default().fg(theme.disabled),
+            ))
+            .render(area, buf);
+            self.last_list = (area, 0);
+            return;
+        }
+
+        // Keep the selection visible
+        let rows = self.rows();
+        let height = area.height as usize;
+        let selected = self.selected_row(&rows);
+        let mut scroll = self.scroll.min(rows.len().saturating_sub(height));
+        if let Some(selected) = selected {
+            if selected < scroll {
+                scroll = selected.saturating_sub(1);
+            } else if selected >= scroll + height {
+                scroll = selected + 1 - height;
+            }
+        }
+
+        let width = area.width as usize;
+        let lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .map(|(idx, &row)| {
+                let (text, style) = self.row_line(row, &theme);
+                if Some(idx) == selected {
+                    Line::styled(
+                        format!("{:<width$}", text, width = width),
+                        Style::default().fg(theme.selected_fg).bg(theme.selected_bg),
+                    )
+                } else {
+                    Line::styled(text, style)
+                }
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+
+        self.selected = selected.and_then(|idx| self.selection_of(rows[idx]));
+        self.scroll = scroll;
+        self.last_list = (area, scroll);
+    }
+
+    /// Text and style of a list row
+    fn row_line(&self, row: Row, theme: &Theme) -> (String, Style) {
+        let t = termide_i18n::t();
+        match row {
+            Row::Header(section) => {
+                let (title, count) = match section {
+                    Section::Staged => (t.git_staged(), self.count(Section::Staged)),
+                    Section::Unstaged => (t.git_unstaged(), self.count(Section::Unstaged)),
+                };
+                (
+                    format!("{} ({})", title, count),
+                    Style::default()
+                        .fg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }
+            Row::File(section, idx) => {
+                let change = &self.changes()[idx];
+                let status = match section {
+                    Section::Staged => change.staged,
+                    Section::Unstaged => change.unstaged,
+                };
+                let (letter, color) = match status {
+                    Some(GitStatus::Added) if change.staged.is_none() => ('?', theme.success),
+                    Some(GitStatus::Added) => ('A', theme.success),
+                    Some(GitStatus::Deleted) => ('D', theme.error),
+                    _ => ('M', theme.warning),
+                };
+                let marker = if self.expanded.contains_key(&(section, change.path.clone())) {
+                    '▾'
+                } else {
+                    ' '
+                };
+                (
+                    format!(" {} {} {}", marker, letter, change.path.display()),
+                    Style::default().fg(color),
+                )
+            }
+            Row::Hunk(section, idx, hunk) => {
+                let patch = &self.expanded[&(section, self.changes()[idx].path.clone())];
+                let hunk = &patch.hunks[hunk];
+                let (added, removed) = hunk.line_counts();
+                (
+                    format!("      {}  +{} -{}", hunk.header(), added, removed),
+                    Style::default().fg(theme.disabled),
+                )
+            }
+        }
+    }
+
+    /// Number of files in a section
+    fn count(&self, section: Section) -> usize {
+        self.changes()
+            .iter()
+            .filter(|change| in_section(change, section))
+            .count()
+    }
+}
+
+/// Check if a file is listed in a section
+fn in_section(change: &FileChange, section: Section) -> bool {
+    match section {
+        Section::Staged => change.staged.is_some(),
+        Section::Unstaged => change.unstaged.is_some(),
+    }
+}
+
+impl Panel for GitPanel {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn title(&self) -> String {
+        let title = termide_i18n::t().git_title();
+        match self.repo_root().and_then(Path::file_name) {
+            Some(name) => format!("{}: {}", title, name.to_string_lossy()),
+            None => title.to_string(),
+        }
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 3 {
+            return;
+        }
+        if self.status.is_none() {
+            Paragraph::new(Line::styled(
+                termide_i18n::t().git_not_repository(),
+                Style::default().fg(self.cached_theme.disabled),
+            ))
+            .render(area, buf);
+            return;
+        }
+        self.render_branch(Rect { height: 1, ..area }, buf);
+        self.render_list(
+            Rect {
+                y: area.y + 2,
+                height: area.height - 3,
+                ..area
+            },
+            buf,
+        );
+        self.render_footer(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        self.status_message = None;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-self.page_size()),
+            KeyCode::PageDown => self.move_selection(self.page_size()),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            KeyCode::Right | KeyCode::Char('l') => self.expand_selected(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse_selected(),
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Char('s') => self.stage_selected(),
+            KeyCode::Char('u') => self.unstage_selected(),
+            KeyCode::Char('c') => self.request_commit(),
+            KeyCode::Char('d') => return self.diff_selected(),
+            KeyCode::Enter => return self.open_selected(),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.move_selection(SCROLL_STEP as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (list, scroll) = self.last_list;
+                let inside = mouse.row >= list.y
+                    && mouse.row < list.y + list.height
+                    && mouse.column >= list.x
+                    && mouse.column < list.x + list.width;
+                if inside {
+                    let idx = scroll + (mouse.row - list.y) as usize;
+                    if let Some(selection) =
+                        self.rows().get(idx).and_then(|&row| self.selection_of(row))
+                    {
+                        self.selected = Some(selection);
+                    }
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        if !self.stale {
+            return vec![];
+        }
+        self.reload();
+        vec![PanelEvent::NeedsRedraw]
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        match cmd {
+            PanelCommand::GetRepoRoot => {
+                CommandResult::RepoRoot(self.repo_root().map(Path::to_path_buf))
+            }
+            PanelCommand::OnGitUpdate { repo_paths } => {
+                if let Some(root) = self.repo_root() {
+                    self.stale |= repo_paths.contains(&root);
+                }
+                CommandResult::NeedsRedraw(false)
+            }
+            PanelCommand::OnFsUpdate { changed_path } => {
+                // Build output and other ignored files do not change the status
+                if let Some(status) = &self.status {
+                    let relevant = changed_path
+                        .strip_prefix(status.repo_root())
+                        .is_ok_and(|relative| !status.is_path_in_ignored(relative));
+                    self.stale |= relevant;
+                }
+                CommandResult::NeedsRedraw(false)
+            }
+            PanelCommand::Reload => {
+                self.reload();
+                CommandResult::NeedsRedraw(true)
+            }
+            // Commands not applicable to GitPanel
+            PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::GetFsWatchInfo
+            | PanelCommand::SetFsWatchRoot { .. }
+            | PanelCommand::Resize { .. }
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
+    fn to_session(&self, _session_dir: &Path) -> Option<termide_core::SessionPanel> {
+        Some(termide_core::SessionPanel::Git {
+            path: self.directory.clone(),
+        })
+    }
+
+    fn get_working_directory(&self) -> Option<PathBuf> {
+        Some(
+            self.repo_root()
+                .map_or_else(|| self.directory.clone(), Path::to_path_buf),
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_and_commit_request() {
+        termide_i18n::init_with_language("en");
+        if !termide_git::is_available() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("termide-git-panel-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::write(dir.join("new.txt"), "text\n").unwrap();
+
+        let mut panel = GitPanel::new(dir.clone());
+        assert_eq!(
+            panel.rows(),
+            vec![
+                Row::Header(Section::Staged),
+                Row::Header(Section::Unstaged),
+                Row::File(Section::Unstaged, 0)
+            ]
+        );
+
+        // Nothing staged yet
+        panel.handle_key(KeyEvent::from(KeyCode::Char('c')));
+        assert!(panel.take_modal_request().is_none());
+
+        // Space stages the selected file, which stays selected
+        panel.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+        assert_eq!(
+            panel.rows(),
+            vec![
+                Row::Header(Section::Staged),
+                Row::File(Section::Staged, 0),
+                Row::Header(Section::Unstaged)
+            ]
+        );
+        assert_eq!(panel.current_row(), Some(Row::File(Section::Staged, 0)));
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('c')));
+        assert!(matches!(
+            panel.take_modal_request(),
+            Some((
+                PendingAction::GitCommit { .. },
+                ActiveModal::MultilineInput(_)
+            ))
+        ));
+        assert_eq!(
+            panel.handle_key(KeyEvent::from(KeyCode::Enter)).len(),
+            1,
+            "Enter opens the file"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_outside_repository() {
+        termide_i18n::init_with_language("en");
+        let dir = std::env::temp_dir();
+        if termide_git::find_repo_root(&dir).is_some() {
+            return;
+        }
+        let mut panel = GitPanel::new(dir);
+        assert!(panel.repo_root().is_none());
+        assert!(panel.rows().iter().all(|row| matches!(row, Row::Header(_))));
+
+        // Nothing to act on
+        panel.handle_key(KeyEvent::from(KeyCode::Char('c')));
+        assert!(panel.take_modal_request().is_none());
+        assert!(panel.handle_key(KeyEvent::from(KeyCode::Enter)).is_empty());
+    }
+}
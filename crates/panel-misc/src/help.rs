@@ -0,0 +1,465 @@
+//! Keyboard shortcuts panel.
+//!
+//! Lists the keys of the application and of the open panel types in
+//! sections that flow through as many columns as the panel width allows.
+//! The app builds the sections from the live key bindings, so the panel
+//! shows the keys configured by the user. Typing filters the entries by
+//! description, action name or keys.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use termide_config::Config;
+use termide_core::{Panel, PanelEvent, RenderContext};
+use termide_theme::Theme;
+
+/// Narrowest column; the panel shows as many as fit
+const MIN_COLUMN_WIDTH: usize = 44;
+/// Space between columns
+const COLUMN_GAP: usize = 3;
+/// Widest keys cell, longer keys are cut
+const MAX_KEYS_WIDTH: usize = 24;
+
+/// Keys and what they do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpEntry {
+    /// Keys as shown (`Ctrl+S, F2`)
+    pub keys: String,
+    pub description: String,
+    /// Config name of the action (`close_panel`), empty for panel keys
+    pub name: String,
+}
+
+/// Titled group of entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub title: String,
+    pub entries: Vec<HelpEntry>,
+}
+
+/// Row of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row<'a> {
+    Header(&'a str),
+    Entry(&'a HelpEntry),
+    Blank,
+}
+
+/// Keyboard shortcuts panel (Alt+H)
+pub struct HelpPanel {
+    sections: Vec<HelpSection>,
+    query: String,
+    /// First visible row of the columns
+    scroll_offset: usize,
+    /// Rows shown at the last render (for paging)
+    visible_rows: usize,
+    /// Rows of the longest column at the last render
+    content_rows: usize,
+    theme: Theme,
+}
+
+impl HelpPanel {
+    pub fn new(sections: Vec<HelpSection>) -> Self {
+        Self {
+            sections,
+            query: String::new(),
+            scroll_offset: 0,
+            visible_rows: 0,
+            content_rows: 0,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Show other sections (after the key bindings changed), keeping the
+    /// filter
+    pub fn set_sections(&mut self, sections: Vec<HelpSection>) {
+        self.sections = sections;
+        self.scroll_offset = 0;
+    }
+
+    /// Sections with the entries matching the query; a section whose title
+    /// matches is shown whole
+    fn filtered(&self) -> Vec<(&str, Vec<&HelpEntry>)> {
+        let query = self.query.to_lowercase();
+        let matches = |text: &str| text.to_lowercase().contains(&query);
+        self.sections
+            .iter()
+            .filter_map(|section| {
+                let entries: Vec<&HelpEntry> = if matches(&section.title) {
+                    section.entries.iter().collect()
+                } else {
+                    section
+                        .entries
+                        .iter()
+                        .filter(|entry| {
+                            matches(&entry.description)
+                                || matches(&entry.keys)
+                                || matches(&entry.name)
+                                || matches(&entry.name.replace('_', " "))
+                        })
+                        .collect()
+                };
+                (!entries.is_empty()).then_some((section.title.as_str(), entries))
+            })
+            .collect()
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max = self.content_rows.saturating_sub(self.visible_rows);
+        self.scroll_offset = self.scroll_offset.saturating_add_signed(delta).min(max);
+    }
+
+    /// Styled row of a column `width` cells wide
+    fn row_line(&self, row: Row, keys_width: usize, width: usize) -> Line<'static> {
+        let theme = self.theme;
+        match row {
+            Row::Header(title) => Line::from(Span::styled(
+                truncate(title, width),
+                Style::default()
+                    .fg(theme.accented_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Row::Entry(entry) => {
+                let keys = truncate(&entry.keys, keys_width);
+                let padding = keys_width + 2 - keys.width();
+                let description =
+                    truncate(&entry.description, width.saturating_sub(keys_width + 2));
+                Line::from(vec![
+                    Span::styled(keys, Style::default().fg(theme.success)),
+                    Span::raw(" ".repeat(padding)),
+                    Span::styled(description, Style::default().fg(theme.fg)),
+                ])
+            }
+            Row::Blank => Line::default(),
+        }
+    }
+}
+
+/// Number of columns fitting in `width` cells
+fn column_count(width: usize) -> usize {
+    ((width + COLUMN_GAP) / (MIN_COLUMN_WIDTH + COLUMN_GAP)).max(1)
+}
+
+/// Rows of the sections split into at most `columns` columns of about
+/// equal height, without leaving a header at the bottom of a column or a
+/// blank row at the top
+fn layout<'a>(sections: &[(&'a str, Vec<&'a HelpEntry>)], columns: usize) -> Vec<Vec<Row<'a>>> {
+    let mut rows = Vec::new();
+    for (title, entries) in sections {
+        if !rows.is_empty() {
+            rows.push(Row::Blank);
+        }
+        rows.push(Row::Header(title));
+        rows.extend(entries.iter().map(|entry| Row::Entry(entry)));
+    }
+    let height = rows.len().div_ceil(columns).max(1);
+
+    let mut result: Vec<Vec<Row>> = vec![Vec::new()];
+    for row in rows {
+        let column = result.last_mut().expect("at least one column");
+        let full = match row {
+            Row::Header(_) => column.len() + 2 > height,
+            _ => column.len() >= height,
+        };
+        if full && result.len() < columns {
+            result.push(Vec::new());
+        }
+        let column = result.last_mut().expect("at least one column");
+        if column.is_empty() && row == Row::Blank {
+            continue;
+        }
+        column.push(row);
+    }
+    result
+}
+
+/// `text` cut to `width` cells
+fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width + 1 > width {
+            break;
+        }
+        used += ch_width;
+        result.push(ch);
+    }
+    if width > 0 {
+        result.push('…');
+    }
+    result
+}
+
+impl Panel for HelpPanel {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn title(&self) -> String {
+        termide_i18n::t().panel_help().to_string()
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &Config) {
+        self.theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        let theme = self.theme;
+        let t = termide_i18n::t();
+        let query_line = if self.query.is_empty() {
+            Line::from(Span::styled(
+                t.help_filter_hint(),
+                Style::default().fg(theme.disabled),
+            ))
+        } else {
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(theme.disabled)),
+                Span::styled(self.query.clone(), Style::default().fg(theme.fg)),
+                Span::styled("█", Style::default().fg(theme.success)),
+            ])
+        };
+        Paragraph::new(query_line).render(Rect { height: 1, ..area }, buf);
+        if area.height < 3 {
+            return;
+        }
+
+        let sections = self.filtered();
+        let list_area = Rect {
+            y: area.y + 2,
+            height: area.height - 2,
+            ..area
+        };
+        if sections.is_empty() {
+            let line = Line::from(Span::styled(
+                t.help_no_matches(),
+                Style::default().fg(theme.disabled),
+            ));
+            Paragraph::new(line).render(list_area, buf);
+            return;
+        }
+
+        let width = area.width as usize;
+        let columns = layout(&sections, column_count(width));
+        let column_width = (width - COLUMN_GAP * (columns.len() - 1)) / columns.len();
+        let visible_rows = list_area.height as usize;
+        let content_rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+        let scroll_offset = self
+            .scroll_offset
+            .min(content_rows.saturating_sub(visible_rows));
+
+        for (idx, column) in columns.iter().enumerate() {
+            let keys_width = column
+                .iter()
+                .filter_map(|row| match row {
+                    Row::Entry(entry) => Some(entry.keys.width()),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0)
+                .min(MAX_KEYS_WIDTH)
+                .min(column_width / 2);
+            let x = list_area.x + (idx * (column_width + COLUMN_GAP)) as u16;
+            for (row_idx, row) in column
+                .iter()
+                .skip(scroll_offset)
+                .take(visible_rows)
+                .enumerate()
+            {
+                let row_area = Rect {
+                    x,
+                    y: list_area.y + row_idx as u16,
+                    width: column_width as u16,
+                    height: 1,
+                };
+                Paragraph::new(self.row_line(*row, keys_width, column_width)).render(row_area, buf);
+            }
+        }
+        self.visible_rows = visible_rows;
+        self.content_rows = content_rows;
+        self.scroll_offset = scroll_offset;
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        let page = self.visible_rows.max(1) as isize;
+        match key.code {
+            KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Down => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::PageDown => self.scroll_by(page),
+            KeyCode::Home => self.scroll_offset = 0,
+            KeyCode::End => self.scroll_by(isize::MAX),
+            KeyCode::Esc if !self.query.is_empty() => {
+                self.query.clear();
+                self.scroll_offset = 0;
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.scroll_offset = 0;
+            }
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.query.push(ch);
+                self.scroll_offset = 0;
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _panel_area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(-3),
+            MouseEventKind::ScrollDown => self.scroll_by(3),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn captures_escape(&self) -> bool {
+        // Escape clears the filter
+        !self.query.is_empty()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(keys: &str, description: &str, name: &str) -> HelpEntry {
+        HelpEntry {
+            keys: keys.to_string(),
+            description: description.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn sections() -> Vec<HelpSection> {
+        vec![
+            HelpSection {
+                title: "Application".to_string(),
+                entries: vec![
+                    entry("Alt+Q", "Quit", "quit"),
+                    entry("Alt+X, Esc", "Close Panel", "close_panel"),
+                ],
+            },
+            HelpSection {
+                title: "Editor".to_string(),
+                entries: (0..20)
+                    .map(|n| entry(&format!("Ctrl+{}", n), "Editor key", ""))
+                    .collect(),
+            },
+        ]
+    }
+
+    fn typed(panel: &mut HelpPanel, text: &str) {
+        for ch in text.chars() {
+            panel.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn test_filter_by_description_name_and_keys() {
+        let mut panel = HelpPanel::new(sections());
+        let found = |panel: &HelpPanel| -> Vec<String> {
+            panel
+                .filtered()
+                .iter()
+                .flat_map(|(_, entries)| entries.iter().map(|entry| entry.keys.clone()))
+                .collect()
+        };
+
+        typed(&mut panel, "quit");
+        assert_eq!(found(&panel), ["Alt+Q"]);
+
+        panel.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        typed(&mut panel, "close panel");
+        assert_eq!(found(&panel), ["Alt+X, Esc"]);
+
+        panel.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        typed(&mut panel, "esc");
+        assert_eq!(found(&panel), ["Alt+X, Esc"]);
+
+        // A matching section title shows the whole section
+        panel.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        typed(&mut panel, "editor");
+        assert_eq!(found(&panel).len(), 20);
+        assert!(panel.captures_escape());
+    }
+
+    #[test]
+    fn test_columns_follow_width() {
+        let panel = HelpPanel::new(sections());
+        let filtered = panel.filtered();
+        assert_eq!(column_count(40), 1);
+        assert_eq!(column_count(100), 2);
+        assert_eq!(column_count(150), 3);
+
+        let single = layout(&filtered, 1);
+        assert_eq!(single.len(), 1);
+        // Two headers, a blank row between the sections and 22 entries
+        assert_eq!(single[0].len(), 25);
+
+        let columns = layout(&filtered, 3);
+        assert_eq!(columns.len(), 3);
+        assert!(columns.iter().all(|column| column.len() <= 9));
+        for column in &columns {
+            assert_ne!(column.first(), Some(&Row::Blank));
+            assert!(!matches!(column.last(), Some(Row::Header(_))));
+        }
+    }
+
+    #[test]
+    fn test_render_fits_narrow_panel() {
+        termide_i18n::init_with_language("en");
+        let mut panel = HelpPanel::new(sections());
+        let area = Rect::new(0, 0, 30, 10);
+        let mut buf = Buffer::empty(area);
+        let colors = termide_core::ThemeColors::from(&Theme::default());
+        let config = termide_core::PanelConfig::default();
+        let ctx = RenderContext {
+            theme: &colors,
+            config: &config,
+            is_focused: true,
+            panel_index: 0,
+            terminal_width: area.width,
+            terminal_height: area.height,
+        };
+        panel.render(area, &mut buf, &ctx);
+
+        let row = |y: u16| -> String {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect()
+        };
+        assert_eq!(row(2).trim_end(), "Application");
+        assert!(row(3).starts_with("Alt+Q"), "{}", row(3));
+
+        // Scrolling stops at the last row
+        panel.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        assert_eq!(panel.scroll_offset, 25 - 8);
+    }
+}
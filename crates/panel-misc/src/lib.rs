@@ -1,11 +1,35 @@
 //! Miscellaneous panels for termide.
 //!
-//! This crate contains simple utility panels: welcome screen, log viewer, and debug panel.
+//! This crate contains simple utility panels: welcome screen, keyboard
+//! shortcuts, log viewer, debug panel, system monitor, git status, diff
+//! viewer, directory comparison, duplicate files, workspace search and
+//! tasks.
+//! The `scratchpad` feature adds an example panel for the panel registry.
 
 pub mod debug;
+pub mod diff;
+pub mod dir_compare;
+pub mod duplicates;
+pub mod git;
+pub mod help;
 pub mod log_viewer;
+#[cfg(feature = "scratchpad")]
+pub mod scratchpad;
+pub mod search;
+pub mod system_monitor;
+pub mod task;
 pub mod welcome;
 
 pub use debug::DebugPanel;
+pub use diff::DiffPanel;
+pub use dir_compare::DirComparePanel;
+pub use duplicates::DuplicatesPanel;
+pub use git::GitPanel;
+pub use help::{HelpEntry, HelpPanel, HelpSection};
 pub use log_viewer::LogViewerPanel;
+#[cfg(feature = "scratchpad")]
+pub use scratchpad::{ScratchpadPanel, SCRATCHPAD_NAME};
+pub use search::SearchPanel;
+pub use system_monitor::SystemMonitorPanel;
+pub use task::TaskPanel;
 pub use welcome::WelcomePanel;
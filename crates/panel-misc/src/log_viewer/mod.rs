@@ -1,35 +1,81 @@
 //! Log viewer panel based on Editor with read-only mode.
 //!
+//! Shows termide's own log, or follows a log file on disk (`tail -f`).
+//!
 //! Provides a full-featured log viewer with:
 //! - Cursor navigation and text selection
 //! - Copy to clipboard
-//! - Auto-scroll to new entries
-//! - Log level highlighting (DEBUG, INFO, WARN, ERROR)
+//! - Follow mode: sticks to new entries until scrolled up, resumed by End
+//! - Filtering by minimum level (`l`) and text (`/`)
+//! - Log level highlighting (TRACE, DEBUG, INFO, WARN, ERROR)
+//!
+//! Entries are fetched from the logger (or read from the file)
+//! incrementally; each new entry is checked against the filter once, when
+//! it arrives. The whole history is only scanned again when the filter
+//! changes.
 
-pub mod highlighting;
+mod tail;
 
-use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
-use ratatui::{buffer::Buffer, layout::Rect};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
 use std::any::Any;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 
-use termide_core::{Panel, PanelEvent, RenderContext};
-use termide_highlight::LineHighlighter;
-use termide_logger::LogLevel;
+use termide_config::constants::{LOG_FILE_TAIL_BYTES, MAX_LOG_ENTRIES, MAX_LOG_FILE_LINES};
+use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext};
+use termide_highlight::{line_severity, LineHighlighter, LogHighlighter};
+use termide_logger::{LogEntry, LogLevel};
 use termide_panel_editor::{config::EditorConfig, Editor};
 use termide_theme::Theme;
 
-use highlighting::LogHighlightCache;
+use tail::FileTail;
+
+/// Minimum levels in the order `l` cycles through them
+const LEVELS: [LogLevel; 5] = [
+    LogLevel::Trace,
+    LogLevel::Debug,
+    LogLevel::Info,
+    LogLevel::Warn,
+    LogLevel::Error,
+];
+
+/// Log file followed by the viewer
+struct LogFile {
+    tail: FileTail,
+    /// Directory registered with the filesystem watcher
+    watched_root: Option<PathBuf>,
+}
 
 /// Log viewer panel with Editor-based text display.
 pub struct LogViewerPanel {
-    /// Internal editor in read-only mode
+    /// Internal editor in read-only mode, holding the lines that pass the filter
     editor: Editor,
     /// Custom highlighter for log levels
-    highlight_cache: LogHighlightCache,
-    /// Auto-scroll enabled (scroll to new entries)
+    highlight_cache: LogHighlighter,
+    /// Follow mode (scroll to new entries)
     auto_scroll: bool,
-    /// Number of log entries already synced to buffer
-    last_synced_count: usize,
+    /// All synced entries, oldest first
+    entries: VecDeque<LogEntry>,
+    /// Logger sequence number of the next entry to sync
+    next_seq: u64,
+    /// Number of entries shown in the editor
+    shown_count: usize,
+    /// Minimum level of shown entries
+    min_level: LogLevel,
+    /// Text shown entries contain (case-insensitive, empty shows all)
+    filter: String,
+    /// Filter being typed (Some while the prompt is open)
+    filter_input: Option<String>,
+    /// Followed file (None shows termide's own log)
+    file: Option<LogFile>,
     /// Cached theme for rendering
     cached_theme: Theme,
     /// Cached config for rendering
@@ -39,50 +85,239 @@ pub struct LogViewerPanel {
 impl LogViewerPanel {
     /// Create a new log viewer panel.
     pub fn new(theme: &termide_theme::Theme) -> Self {
+        Self {
+            editor: Self::new_editor(),
+            highlight_cache: LogHighlighter::new(),
+            auto_scroll: true,
+            entries: VecDeque::new(),
+            next_seq: 0,
+            shown_count: 0,
+            min_level: LogLevel::Trace,
+            filter: String::new(),
+            filter_input: None,
+            file: None,
+            cached_theme: *theme,
+            cached_config: termide_config::Config::default(),
+        }
+    }
+
+    /// Open a log file, showing its last lines and following new ones.
+    pub fn open_file(path: PathBuf) -> std::io::Result<Self> {
+        let (tail, lines) = FileTail::open(&path, LOG_FILE_TAIL_BYTES)?;
+        let mut viewer = Self {
+            file: Some(LogFile {
+                tail,
+                watched_root: None,
+            }),
+            ..Self::default()
+        };
+        viewer.push_entries(file_entries(lines, LogLevel::Info));
+        Ok(viewer)
+    }
+
+    /// Path of the followed file
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file.as_ref().map(|file| file.tail.path())
+    }
+
+    /// Take the watched directory (for cleanup when closing)
+    pub fn take_watched_root(&mut self) -> Option<PathBuf> {
+        self.file.as_mut()?.watched_root.take()
+    }
+
+    /// Create the empty read-only editor for log lines
+    fn new_editor() -> Editor {
         // Create editor with view_only config
         let mut config = EditorConfig::view_only();
         config.syntax_highlighting = true; // Enable to use our custom highlighter
+        Editor::with_config(config)
+    }
 
-        let editor = Editor::with_config(config);
-        let highlight_cache = LogHighlightCache::new(*theme);
+    /// Text filter in effect (the one being typed while the prompt is open)
+    fn active_filter(&self) -> &str {
+        self.filter_input.as_deref().unwrap_or(&self.filter)
+    }
 
-        Self {
-            editor,
-            highlight_cache,
-            auto_scroll: true,
-            last_synced_count: 0,
-            cached_theme: *theme,
-            cached_config: termide_config::Config::default(),
+    /// Check if an entry passes the level and text filters
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if entry.level < self.min_level {
+            return false;
+        }
+        let filter = self.active_filter();
+        filter.is_empty()
+            || entry
+                .message
+                .to_lowercase()
+                .contains(&filter.to_lowercase())
+    }
+
+    /// Editor line of an entry (file lines are shown as they are)
+    fn format_line(&self, entry: &LogEntry) -> String {
+        if self.file.is_some() {
+            format!("{}\n", entry.message)
+        } else {
+            format_entry(entry)
         }
     }
 
-    /// Sync log entries from logger to buffer.
+    /// Fill the editor again after the filter changed.
+    fn refilter(&mut self) {
+        let shown: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| self.matches(entry))
+            .map(|entry| self.format_line(entry))
+            .collect();
+        self.editor = Self::new_editor();
+        self.editor.buffer_mut().append(&shown.concat());
+        self.shown_count = shown.len();
+        self.highlight_cache.invalidate_from(0);
+    }
+
+    /// Sync new log entries from the logger, filtering only the new ones.
     fn sync_logs(&mut self) {
-        let entries = termide_logger::get_entries();
-        let new_count = entries.len();
+        let (new_entries, next_seq) = termide_logger::get_entries_since(self.next_seq);
+        self.next_seq = next_seq;
+        self.push_entries(new_entries);
+    }
 
-        if new_count > self.last_synced_count {
-            // Get buffer access through editor
-            let buffer = self.editor.buffer_mut();
+    /// Read lines appended to the followed file.
+    ///
+    /// Returns true if the view changed.
+    fn read_file(&mut self) -> bool {
+        let Some(file) = self.file.as_mut() else {
+            return false;
+        };
+        // The file may be missing for a moment while it is rotated
+        let Ok(read) = file.tail.read() else {
+            return false;
+        };
+        if read.restarted {
+            self.entries.clear();
+            self.refilter();
+        }
+        let changed = read.restarted || !read.lines.is_empty();
+        let previous = self.entries.back().map_or(LogLevel::Info, |e| e.level);
+        self.push_entries(file_entries(read.lines, previous));
+        changed
+    }
 
-            // Append new entries
-            for entry in entries.iter().skip(self.last_synced_count) {
-                let level_text = match entry.level {
-                    LogLevel::Debug => "DEBUG",
-                    LogLevel::Info => "INFO ",
-                    LogLevel::Warn => "WARN ",
-                    LogLevel::Error => "ERROR",
-                };
+    /// Append new entries, showing the ones that pass the filter.
+    fn push_entries(&mut self, new_entries: Vec<LogEntry>) {
+        if new_entries.is_empty() {
+            return;
+        }
 
-                let line = format!("[{}] {} {}\n", entry.timestamp, level_text, entry.message);
-                buffer.append(&line);
+        // Invalidate highlight cache for new lines
+        self.highlight_cache.invalidate_from(self.shown_count);
+        for entry in new_entries {
+            if self.matches(&entry) {
+                let line = self.format_line(&entry);
+                self.editor.buffer_mut().append(&line);
+                self.shown_count += 1;
             }
+            self.entries.push_back(entry);
+        }
 
-            // Invalidate highlight cache for new lines
-            self.highlight_cache.invalidate_from(self.last_synced_count);
+        // Drop old entries in batches so the editor is rebuilt rarely
+        let max_entries = if self.file.is_some() {
+            MAX_LOG_FILE_LINES
+        } else {
+            MAX_LOG_ENTRIES
+        };
+        if self.entries.len() > max_entries + max_entries / 4 {
+            let excess = self.entries.len() - max_entries;
+            self.entries.drain(..excess);
+            self.refilter();
+        }
+    }
+
+    /// Switch to the next minimum level (wrapping to TRACE after ERROR)
+    fn cycle_level(&mut self) {
+        let idx = LEVELS
+            .iter()
+            .position(|&l| l == self.min_level)
+            .unwrap_or(0);
+        self.min_level = LEVELS[(idx + 1) % LEVELS.len()];
+        self.refilter();
+    }
 
-            self.last_synced_count = new_count;
+    /// Handle a key while the filter prompt is open
+    fn handle_prompt_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.filter_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                self.filter = self.filter_input.take().unwrap_or_default();
+                return;
+            }
+            KeyCode::Esc => {
+                self.filter_input = None;
+            }
+            KeyCode::Backspace => {
+                if input.pop().is_none() {
+                    return;
+                }
+            }
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                input.push(ch);
+            }
+            _ => return,
         }
+        // Filter as the user types
+        self.refilter();
+    }
+
+    /// Render the filter status line
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let label = Style::default().fg(theme.disabled);
+        let value = Style::default()
+            .fg(theme.accented_fg)
+            .add_modifier(Modifier::BOLD);
+
+        let mut spans = vec![
+            Span::styled(format!("{} ", t.log_level()), label),
+            Span::styled(format!("{}+", self.min_level.to_str()), value),
+        ];
+        if let Some(input) = &self.filter_input {
+            spans.push(Span::styled(format!("  {} ", t.log_filter()), label));
+            spans.push(Span::styled(input.clone(), Style::default().fg(theme.fg)));
+            spans.push(Span::styled("█", Style::default().fg(theme.success)));
+        } else if !self.filter.is_empty() {
+            spans.push(Span::styled(format!("  {} ", t.log_filter()), label));
+            spans.push(Span::styled(self.filter.clone(), value));
+        }
+        spans.push(Span::styled(
+            format!(
+                "  {}",
+                t.log_showing(
+                    &group_digits(self.shown_count),
+                    &group_digits(self.entries.len())
+                )
+            ),
+            label,
+        ));
+        spans.push(if self.auto_scroll {
+            Span::styled(
+                format!("  {}", t.log_following()),
+                Style::default().fg(theme.success),
+            )
+        } else {
+            Span::styled(
+                format!("  {}", t.log_paused()),
+                Style::default().fg(theme.warning),
+            )
+        });
+        spans.push(Span::styled(format!("  {}", t.log_filter_hint()), label));
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
     }
 
     /// Scroll to the end of the log.
@@ -105,26 +340,84 @@ impl LogViewerPanel {
     }
 }
 
+/// Editor line of a log entry
+fn format_entry(entry: &LogEntry) -> String {
+    format!(
+        "[{}] {:<5} {}\n",
+        entry.timestamp,
+        entry.level.to_str(),
+        entry.message
+    )
+}
+
+/// Entries for lines of a log file.
+///
+/// The level is taken from the first severity word of a line; lines
+/// without one (stack traces, continued messages) keep the level of the
+/// line before them.
+fn file_entries(lines: Vec<String>, mut level: LogLevel) -> Vec<LogEntry> {
+    lines
+        .into_iter()
+        .map(|line| {
+            if let Some(found) = line_severity(&line).and_then(|name| name.parse().ok()) {
+                level = found;
+            }
+            LogEntry {
+                timestamp: String::new(),
+                level,
+                message: line,
+            }
+        })
+        .collect()
+}
+
+/// Format a count with thousands separators (`8,031`)
+fn group_digits(count: usize) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, ch) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    grouped
+}
+
 impl Panel for LogViewerPanel {
     fn name(&self) -> &'static str {
         "log_viewer"
     }
 
     fn title(&self) -> String {
-        "Log".to_string()
+        match self.file_path() {
+            Some(path) => path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ),
+            None => "Log".to_string(),
+        }
     }
 
     fn prepare_render(&mut self, theme: &Theme, config: &termide_config::Config) {
         self.cached_theme = *theme;
         self.cached_config = config.clone();
-        self.highlight_cache.set_theme(*theme);
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
-        // Sync new log entries
-        self.sync_logs();
+        // Sync new log entries (files are read on filesystem updates)
+        if self.file.is_none() {
+            self.sync_logs();
+        }
 
-        let content_height = area.height as usize;
+        // Filter status on the first line, log lines below
+        self.render_status(Rect { height: 1, ..area }, buf);
+        let content_area = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        let content_height = content_area.height as usize;
 
         // Auto-scroll if enabled
         if self.auto_scroll && content_height > 0 {
@@ -133,7 +426,7 @@ impl Panel for LogViewerPanel {
 
         // Render using editor's rendering with our custom highlighter
         self.editor.render_with_highlighter(
-            area,
+            content_area,
             buf,
             &self.cached_theme,
             &self.cached_config,
@@ -142,8 +435,26 @@ impl Panel for LogViewerPanel {
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
-        // Check for auto-scroll toggle keys
+        if self.filter_input.is_some() {
+            self.handle_prompt_key(key);
+            return vec![];
+        }
+
+        // Check for filter and auto-scroll toggle keys
         match key.code {
+            KeyCode::Char('l') => {
+                self.cycle_level();
+                return vec![];
+            }
+            KeyCode::Char('/') => {
+                self.filter_input = Some(self.filter.clone());
+                return vec![];
+            }
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.refilter();
+                return vec![];
+            }
             // Disable auto-scroll on scroll up
             KeyCode::Up
             | KeyCode::Char('k')
@@ -165,6 +476,13 @@ impl Panel for LogViewerPanel {
     }
 
     fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) -> Vec<PanelEvent> {
+        // Log lines start below the filter status line
+        let area = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+
         // Check for scroll events that affect auto-scroll
         match mouse.kind {
             MouseEventKind::ScrollUp => {
@@ -185,15 +503,70 @@ impl Panel for LogViewerPanel {
         vec![]
     }
 
+    fn captures_escape(&self) -> bool {
+        // Escape closes the filter prompt or clears the filter
+        self.filter_input.is_some() || !self.filter.is_empty()
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        let Some(file) = self.file.as_mut() else {
+            return CommandResult::None;
+        };
+        match cmd {
+            PanelCommand::GetFsWatchInfo => CommandResult::FsWatchInfo {
+                watched_root: file.watched_root.clone(),
+                current_path: file
+                    .tail
+                    .path()
+                    .parent()
+                    .map_or_else(|| PathBuf::from("/"), Path::to_path_buf),
+                is_git_repo: false,
+            },
+            PanelCommand::SetFsWatchRoot { root, .. } => {
+                file.watched_root = root;
+                CommandResult::None
+            }
+            PanelCommand::OnFsUpdate { changed_path } => {
+                // Rotation may show up as changes of sibling files only
+                let path = file.tail.path();
+                let affected = changed_path == path || changed_path.parent() == path.parent();
+                CommandResult::NeedsRedraw(affected && self.read_file())
+            }
+            PanelCommand::Reload => CommandResult::NeedsRedraw(self.read_file()),
+            // Commands not applicable to LogViewer
+            PanelCommand::GetRepoRoot
+            | PanelCommand::OnGitUpdate { .. }
+            | PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::Resize { .. }
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
     fn to_session(&self, _session_dir: &std::path::Path) -> Option<termide_core::SessionPanel> {
-        // Save as Debug panel type (same session type)
-        Some(termide_core::SessionPanel::Debug)
+        match self.file_path() {
+            Some(path) => Some(termide_core::SessionPanel::LogFile {
+                path: path.to_path_buf(),
+            }),
+            // Save as Debug panel type (same session type)
+            None => Some(termide_core::SessionPanel::Debug),
+        }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
 
+    fn file_path(&self) -> Option<&Path> {
+        LogViewerPanel::file_path(self)
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
@@ -204,3 +577,131 @@ impl Default for LogViewerPanel {
         Self::new(&termide_theme::Theme::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn entry(level: LogLevel, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: "12:00:00".to_string(),
+            level,
+            message: message.to_string(),
+        }
+    }
+
+    fn viewer(entries: Vec<LogEntry>) -> LogViewerPanel {
+        let mut viewer = LogViewerPanel {
+            entries: entries.into(),
+            ..Default::default()
+        };
+        viewer.refilter();
+        viewer
+    }
+
+    fn shown_lines(viewer: &LogViewerPanel) -> Vec<String> {
+        viewer
+            .editor
+            .buffer()
+            .to_string()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_level_filter_cycles() {
+        let mut viewer = viewer(vec![
+            entry(LogLevel::Debug, "loading"),
+            entry(LogLevel::Warn, "slow"),
+            entry(LogLevel::Error, "failed"),
+        ]);
+        assert_eq!(viewer.shown_count, 3);
+
+        viewer.handle_key(key(KeyCode::Char('l')));
+        viewer.handle_key(key(KeyCode::Char('l')));
+        viewer.handle_key(key(KeyCode::Char('l')));
+        assert_eq!(viewer.min_level, LogLevel::Warn);
+        assert_eq!(
+            shown_lines(&viewer),
+            vec!["[12:00:00] WARN  slow", "[12:00:00] ERROR failed"]
+        );
+
+        viewer.handle_key(key(KeyCode::Char('l')));
+        viewer.handle_key(key(KeyCode::Char('l')));
+        assert_eq!(viewer.min_level, LogLevel::Trace);
+        assert_eq!(viewer.shown_count, 3);
+    }
+
+    #[test]
+    fn test_text_filter_prompt() {
+        let mut viewer = viewer(vec![
+            entry(LogLevel::Info, "Opening file"),
+            entry(LogLevel::Info, "Saved file"),
+            entry(LogLevel::Info, "Git watcher ready"),
+        ]);
+
+        viewer.handle_key(key(KeyCode::Char('/')));
+        assert!(viewer.captures_escape());
+        for ch in "FILE".chars() {
+            viewer.handle_key(key(KeyCode::Char(ch)));
+        }
+        // Applied while typing
+        assert_eq!(viewer.shown_count, 2);
+
+        // Esc in the prompt restores the previous filter
+        viewer.handle_key(key(KeyCode::Esc));
+        assert_eq!(viewer.shown_count, 3);
+        assert!(!viewer.captures_escape());
+
+        viewer.handle_key(key(KeyCode::Char('/')));
+        for ch in "watch".chars() {
+            viewer.handle_key(key(KeyCode::Char(ch)));
+        }
+        viewer.handle_key(key(KeyCode::Enter));
+        assert_eq!(viewer.filter, "watch");
+        assert_eq!(
+            shown_lines(&viewer),
+            vec!["[12:00:00] INFO  Git watcher ready"]
+        );
+
+        // Esc outside the prompt clears the filter
+        viewer.handle_key(key(KeyCode::Esc));
+        assert_eq!(viewer.shown_count, 3);
+    }
+
+    #[test]
+    fn test_follows_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "INFO started\nERROR failed\n  at main.rs:3\n").unwrap();
+
+        let mut viewer = LogViewerPanel::open_file(path.clone()).unwrap();
+        assert_eq!(viewer.title(), "app.log");
+        // The stack trace line keeps the level of the error above it
+        viewer.min_level = LogLevel::Error;
+        viewer.refilter();
+        assert_eq!(shown_lines(&viewer), vec!["ERROR failed", "  at main.rs:3"]);
+
+        std::fs::write(&path, "WARN restarted\nERROR again\n").unwrap();
+        let result = viewer.handle_command(PanelCommand::OnFsUpdate {
+            changed_path: &path,
+        });
+        assert!(result.needs_redraw());
+        // Truncated: earlier lines are dropped
+        assert_eq!(shown_lines(&viewer), vec!["ERROR again"]);
+        assert_eq!(viewer.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(214), "214");
+        assert_eq!(group_digits(8031), "8,031");
+        assert_eq!(group_digits(1234567), "1,234,567");
+    }
+}
@@ -0,0 +1,188 @@
+//! Following a log file as it grows.
+//!
+//! Only bytes appended since the previous read are read. A file that got
+//! shorter (truncated) or was replaced by another one (rotated) is read
+//! again from the start. Reads are capped: when more was appended than the
+//! cap, the beginning is skipped like on open.
+
+use std::fs::{File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Lines read from a followed file
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TailRead {
+    /// Complete lines, without line endings
+    pub lines: Vec<String>,
+    /// File was truncated or replaced: previously read lines are gone
+    pub restarted: bool,
+}
+
+/// Reader of lines appended to a file
+#[derive(Debug)]
+pub(crate) struct FileTail {
+    path: PathBuf,
+    /// Maximum number of bytes read at once
+    max_bytes: u64,
+    /// Byte offset of the next read
+    offset: u64,
+    /// Identity of the file being read (inode on Unix)
+    file_id: Option<u64>,
+    /// Bytes of an unfinished last line
+    partial: Vec<u8>,
+}
+
+impl FileTail {
+    /// Open a file, reading its last `max_bytes` (from the start of a line).
+    pub fn open(path: &Path, max_bytes: u64) -> io::Result<(Self, Vec<String>)> {
+        let file_id = file_id(&File::open(path)?.metadata()?);
+        let mut tail = Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            offset: 0,
+            file_id,
+            partial: Vec::new(),
+        };
+        let read = tail.read()?;
+        Ok((tail, read.lines))
+    }
+
+    /// Path of the followed file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read lines completed since the previous read.
+    pub fn read(&mut self) -> io::Result<TailRead> {
+        let mut file = File::open(&self.path)?;
+        let metadata = file.metadata()?;
+        let len = metadata.len();
+
+        let file_id = file_id(&metadata);
+        let restarted = file_id != self.file_id || len < self.offset;
+        if restarted {
+            self.file_id = file_id;
+            self.offset = 0;
+            self.partial.clear();
+        }
+
+        // Too much to read: skip to the last max_bytes, dropping the line
+        // cut in the middle
+        let skipped = len - self.offset > self.max_bytes;
+        if skipped {
+            self.offset = len - self.max_bytes;
+            self.partial.clear();
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.take(len - self.offset).read_to_end(&mut bytes)?;
+        self.offset += bytes.len() as u64;
+
+        let mut bytes = bytes.as_slice();
+        if skipped {
+            let line_start = bytes.iter().position(|&b| b == b'\n').map_or(0, |i| i + 1);
+            bytes = &bytes[line_start..];
+        }
+
+        Ok(TailRead {
+            lines: self.split_lines(bytes),
+            restarted,
+        })
+    }
+
+    /// Split complete lines off the read bytes, keeping an unfinished last line
+    fn split_lines(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.partial.extend_from_slice(bytes);
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        complete[..end]
+            .split(|&b| b == b'\n')
+            .map(|line| {
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                String::from_utf8_lossy(line).into_owned()
+            })
+            .collect()
+    }
+}
+
+/// Identity of a file that changes when it is replaced
+fn file_id(metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    fn append(path: &Path, text: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_reads_appended_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "one\r\ntwo\nthr").unwrap();
+
+        let (mut tail, lines) = FileTail::open(&path, 1024).unwrap();
+        assert_eq!(lines, vec!["one", "two"]);
+
+        // The unfinished line is returned once it ends
+        append(&path, "ee\nfour\n");
+        assert_eq!(tail.read().unwrap().lines, vec!["three", "four"]);
+        assert_eq!(tail.read().unwrap(), TailRead::default());
+    }
+
+    #[test]
+    fn test_starts_at_line_within_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "first line\nsecond\nthird\n").unwrap();
+
+        let (mut tail, lines) = FileTail::open(&path, 10).unwrap();
+        assert_eq!(lines, vec!["third"]);
+
+        append(&path, &"x".repeat(30));
+        append(&path, "\nlast\n");
+        assert_eq!(tail.read().unwrap().lines, vec!["last"]);
+    }
+
+    #[test]
+    fn test_truncation_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old 1\nold 2\n").unwrap();
+        let (mut tail, _) = FileTail::open(&path, 1024).unwrap();
+
+        // Truncated in place
+        fs::write(&path, "new\n").unwrap();
+        let read = tail.read().unwrap();
+        assert!(read.restarted);
+        assert_eq!(read.lines, vec!["new"]);
+
+        // Renamed away and recreated with more bytes than were read
+        fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        fs::write(&path, "rotated 1\nrotated 2\n").unwrap();
+        let read = tail.read().unwrap();
+        if cfg!(unix) {
+            assert!(read.restarted);
+            assert_eq!(read.lines, vec!["rotated 1", "rotated 2"]);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Scratchpad panel: notes kept with the session instead of in a file.
+//!
+//! An example of a panel registered through the panel registry (enabled by
+//! the `scratchpad` feature): the text is edited in an editor without a
+//! file and saved as the `text` parameter of its `SessionPanel::Custom`
+//! entry, so restoring the session creates it again with its text.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use termide_config::Config;
+use termide_core::{Panel, PanelEvent, RenderContext, SessionPanel};
+use termide_panel_editor::{Editor, EditorConfig};
+use termide_theme::Theme;
+
+/// Registered panel type name
+pub const SCRATCHPAD_NAME: &str = "scratchpad";
+
+/// Session parameter holding the text
+const TEXT_PARAM: &str = "text";
+
+/// Notes panel saved with the session
+pub struct ScratchpadPanel {
+    editor: Editor,
+    title: String,
+}
+
+impl ScratchpadPanel {
+    /// Create a scratchpad with the text saved in `params`
+    pub fn new(params: &BTreeMap<String, String>, title: &str, config: EditorConfig) -> Self {
+        let text = params.get(TEXT_PARAM).map(String::as_str).unwrap_or("");
+        Self {
+            editor: Editor::with_text(text, title, config),
+            title: title.to_string(),
+        }
+    }
+
+    /// Current text
+    pub fn text(&self) -> String {
+        self.editor.buffer().text()
+    }
+}
+
+impl Panel for ScratchpadPanel {
+    fn name(&self) -> &'static str {
+        SCRATCHPAD_NAME
+    }
+
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, config: &Config) {
+        self.editor.prepare_render(theme, config);
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, ctx: &RenderContext) {
+        self.editor.render(area, buf, ctx);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        self.editor.handle_key(key)
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent, panel_area: Rect) -> Vec<PanelEvent> {
+        self.editor.handle_mouse(event, panel_area)
+    }
+
+    fn handle_paste(&mut self, text: &str) -> Vec<PanelEvent> {
+        self.editor.handle_paste(text)
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        self.editor.tick()
+    }
+
+    fn captures_escape(&self) -> bool {
+        self.editor.captures_escape()
+    }
+
+    fn to_session(&self, _session_dir: &Path) -> Option<SessionPanel> {
+        let mut params = BTreeMap::new();
+        let text = self.text();
+        if !text.is_empty() {
+            params.insert(TEXT_PARAM.to_string(), text);
+        }
+        Some(SessionPanel::Custom {
+            name: SCRATCHPAD_NAME.to_string(),
+            params,
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratchpad_text_saved_in_session() {
+        let params = BTreeMap::from([(TEXT_PARAM.to_string(), "todo\n".to_string())]);
+        let mut panel = ScratchpadPanel::new(&params, "Scratchpad", EditorConfig::default());
+        panel.handle_paste("- ");
+
+        let dir = tempfile::tempdir().unwrap();
+        let Some(SessionPanel::Custom { name, params }) = panel.to_session(dir.path()) else {
+            panic!("scratchpad not saved");
+        };
+        assert_eq!(name, SCRATCHPAD_NAME);
+        assert_eq!(params.get(TEXT_PARAM).map(String::as_str), Some("- todo\n"));
+        // Nothing is written next to the session
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
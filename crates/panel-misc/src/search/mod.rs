@@ -0,0 +1,646 @@
+//! Search panel.
+//!
+//! Searches the files of a directory (the repository root when it is in a
+//! git repository) for a literal string or a regex. The search runs on
+//! background threads (`WorkspaceSearch`); the matches of each file are
+//! picked up on `tick` and listed grouped by file as they arrive. Starting
+//! another search cancels the running one.
+
+mod worker;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use std::path::{Path, PathBuf};
+
+use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext};
+use termide_modal::TextInputHandler;
+use termide_text_search::{build_regex, SearchOptions};
+use termide_theme::Theme;
+
+use worker::{FileMatches, SearchUpdate, WorkspaceSearch};
+
+/// Rows moved by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Part of the panel receiving keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Query,
+    Results,
+}
+
+/// Row of the result list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    /// File (index into `files`)
+    File(usize),
+    /// Matching line of a file
+    Match(usize, usize),
+    /// Notice that a file has more matches than listed
+    More(usize),
+}
+
+/// Progress of the last search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// No search started yet
+    Idle,
+    Running,
+    Finished {
+        /// The overall cap was reached
+        truncated: bool,
+    },
+}
+
+/// Workspace search panel
+pub struct SearchPanel {
+    /// Directory searched
+    root: PathBuf,
+    input: TextInputHandler,
+    case_sensitive: bool,
+    regex: bool,
+    focus: Focus,
+    /// Running search (dropping it cancels the search)
+    search: Option<WorkspaceSearch>,
+    state: State,
+    /// Files with matches, in the order they were found
+    files: Vec<FileMatches>,
+    /// Invalid regex message
+    error: Option<String>,
+    /// Selected row of the result list
+    selected: usize,
+    /// First list row shown
+    scroll: usize,
+    /// List area and first row of the last render (for mouse clicks)
+    last_list: (Rect, usize),
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl SearchPanel {
+    /// Create a panel searching the repository containing `directory`, or
+    /// `directory` itself outside a repository
+    pub fn new(directory: PathBuf) -> Self {
+        let root = termide_git::find_repo_root(&directory).unwrap_or(directory);
+        Self {
+            root,
+            input: TextInputHandler::new(),
+            case_sensitive: false,
+            regex: false,
+            focus: Focus::Query,
+            search: None,
+            state: State::Idle,
+            files: Vec::new(),
+            error: None,
+            selected: 0,
+            scroll: 0,
+            last_list: (Rect::default(), 0),
+            cached_theme: Theme::default(),
+        }
+    }
+
+    /// Directory searched
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Start searching for the query, cancelling the running search
+    fn start_search(&mut self) {
+        self.search = None;
+        self.files.clear();
+        self.error = None;
+        self.selected = 0;
+        self.scroll = 0;
+        self.state = State::Idle;
+
+        let query = self.input.text();
+        if query.is_empty() {
+            return;
+        }
+        let options = SearchOptions {
+            case_sensitive: self.case_sensitive,
+            regex: self.regex,
+            whole_word: false,
+        };
+        match build_regex(query, &options) {
+            Ok(regex) => {
+                self.search = Some(WorkspaceSearch::spawn(self.root.clone(), regex));
+                self.state = State::Running;
+            }
+            Err(e) => {
+                self.error = Some(termide_i18n::t().search_invalid_regex(&e.to_string()));
+            }
+        }
+    }
+
+    /// Apply the updates sent by the running search, true if any arrived
+    fn poll_search(&mut self) -> bool {
+        let Some(search) = &self.search else {
+            return false;
+        };
+        let updates = search.try_updates();
+        if updates.is_empty() {
+            return false;
+        }
+        for update in updates {
+            match update {
+                SearchUpdate::File(found) => self.files.push(found),
+                SearchUpdate::Finished { truncated } => {
+                    self.state = State::Finished { truncated };
+                    self.search = None;
+                }
+            }
+        }
+        true
+    }
+
+    /// Toggle a search option and search again with it
+    fn toggle_option(&mut self, case_sensitive: bool) {
+        if case_sensitive {
+            self.case_sensitive = !self.case_sensitive;
+        } else {
+            self.regex = !self.regex;
+        }
+        if self.state != State::Idle || self.error.is_some() {
+            self.start_search();
+        }
+    }
+
+    /// Rows of the result list
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (file_idx, file) in self.files.iter().enumerate() {
+            rows.push(Row::File(file_idx));
+            rows.extend((0..file.lines.len()).map(|line_idx| Row::Match(file_idx, line_idx)));
+            if file.truncated {
+                rows.push(Row::More(file_idx));
+            }
+        }
+        rows
+    }
+
+    /// Number of matching lines listed
+    fn match_count(&self) -> usize {
+        self.files.iter().map(|file| file.lines.len()).sum()
+    }
+
+    /// Move the selection by `delta` rows (clamped to the list)
+    fn move_selection(&mut self, delta: isize) {
+        let count = self.rows().len();
+        if count == 0 {
+            return;
+        }
+        self.selected = self.selected.saturating_add_signed(delta).min(count - 1);
+    }
+
+    /// Rows moved by PageUp/PageDown
+    fn page_size(&self) -> isize {
+        self.last_list.0.height.max(1) as isize
+    }
+
+    /// Open the file of the selected row at its matching line
+    fn open_selected(&self) -> Vec<PanelEvent> {
+        let Some(&row) = self.rows().get(self.selected) else {
+            return vec![];
+        };
+        let (file_idx, line_idx) = match row {
+            Row::File(file_idx) | Row::More(file_idx) => (file_idx, 0),
+            Row::Match(file_idx, line_idx) => (file_idx, line_idx),
+        };
+        let file = &self.files[file_idx];
+        let path = self.root.join(&file.path);
+        vec![match file.lines.get(line_idx) {
+            Some(found) => PanelEvent::open_file_at(path, found.line + 1, Some(found.column + 1)),
+            None => PanelEvent::open_file(path),
+        }]
+    }
+
+    /// Handle a key while the query has focus
+    fn handle_query_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.start_search(),
+            KeyCode::Down if !self.files.is_empty() => self.focus = Focus::Results,
+            KeyCode::Backspace => {
+                self.input.backspace();
+            }
+            KeyCode::Delete => {
+                self.input.delete();
+            }
+            KeyCode::Left => {
+                self.input.move_left();
+            }
+            KeyCode::Right => {
+                self.input.move_right();
+            }
+            KeyCode::Home => self.input.move_home(),
+            KeyCode::End => self.input.move_end(),
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.input.insert(ch);
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key while the result list has focus
+    fn handle_results_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        match key.code {
+            KeyCode::Enter => return self.open_selected(),
+            KeyCode::Up if self.selected == 0 => self.focus = Focus::Query,
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-self.page_size()),
+            KeyCode::PageDown => self.move_selection(self.page_size()),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            // Typing goes back to the query
+            KeyCode::Char(_) | KeyCode::Backspace => {
+                self.focus = Focus::Query;
+                self.handle_query_key(key);
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    /// Render the query line with the option toggles
+    fn render_query(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.cached_theme;
+        let toggle = |label: &'static str, on: bool| {
+            let style = if on {
+                Style::default()
+                    .fg(theme.accented_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.disabled)
+            };
+            Span::styled(label, style)
+        };
+
+        let mut spans = vec![
+            toggle("[Aa]", self.case_sensitive),
+            toggle("[.*] ", self.regex),
+            Span::styled(
+                termide_i18n::t().search_prompt(),
+                Style::default().fg(theme.accented_fg),
+            ),
+        ];
+        let text_style = Style::default().fg(theme.fg);
+        if self.focus == Focus::Query {
+            let after = self.input.text_after_cursor();
+            let mut chars = after.chars();
+            let cursor = chars.next().map_or(" ".to_string(), String::from);
+            spans.push(Span::styled(
+                self.input.text_before_cursor().to_string(),
+                text_style,
+            ));
+            spans.push(Span::styled(
+                cursor,
+                Style::default().fg(theme.bg).bg(theme.fg),
+            ));
+            spans.push(Span::styled(chars.as_str().to_string(), text_style));
+        } else {
+            spans.push(Span::styled(self.input.text().to_string(), text_style));
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+
+    /// Render the line with the search progress or error
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let summary = t.search_summary(self.match_count(), self.files.len());
+        let line = if let Some(error) = &self.error {
+            Line::styled(error.clone(), Style::default().fg(theme.error))
+        } else {
+            match self.state {
+                State::Idle => Line::styled(
+                    self.root.display().to_string(),
+                    Style::default().fg(theme.disabled),
+                ),
+                State::Running => Line::from(vec![
+                    Span::styled(summary, Style::default().fg(theme.disabled)),
+                    Span::styled(
+                        format!("  {}", t.search_running()),
+                        Style::default().fg(theme.warning),
+                    ),
+                ]),
+                State::Finished { .. } if self.files.is_empty() => {
+                    Line::styled(t.search_no_matches(), Style::default().fg(theme.disabled))
+                }
+                State::Finished { truncated } => {
+                    let mut spans = vec![Span::styled(summary, Style::default().fg(theme.success))];
+                    if truncated {
+                        spans.push(Span::styled(
+                            format!(" ({})", t.search_truncated()),
+                            Style::default().fg(theme.warning),
+                        ));
+                    }
+                    Line::from(spans)
+                }
+            }
+        };
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the result list
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = self.cached_theme;
+        self.last_list = (area, self.scroll);
+        if area.height == 0 {
+            return;
+        }
+
+        // Keep the selection visible
+        let rows = self.rows();
+        let height = area.height as usize;
+        self.selected = self.selected.min(rows.len().saturating_sub(1));
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let width = area.width as usize;
+        let lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(height)
+            .map(|(idx, &row)| {
+                let selected = self.focus == Focus::Results && idx == self.selected;
+                self.row_line(row, selected, width, &theme)
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+        self.last_list = (area, self.scroll);
+    }
+
+    /// Line of a result row
+    fn row_line(&self, row: Row, selected: bool, width: usize, theme: &Theme) -> Line<'static> {
+        let base = if selected {
+            Style::default().fg(theme.selected_fg).bg(theme.selected_bg)
+        } else {
+            Style::default()
+        };
+        let mut spans = match row {
+            Row::File(file_idx) => {
+                let file = &self.files[file_idx];
+                vec![
+                    Span::styled(
+                        file.path.display().to_string(),
+                        base.fg(theme.accented_fg).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format!(" ({})", file.lines.len()), base.fg(theme.disabled)),
+                ]
+            }
+            Row::Match(file_idx, line_idx) => {
+                let found = &self.files[file_idx].lines[line_idx];
+                // Tabs are shown as single spaces to keep the match ranges
+                let text = found.text.replace('\t', " ");
+                let mut spans = vec![Span::styled(
+                    format!("{:>6}: ", found.line + 1),
+                    base.fg(theme.disabled),
+                )];
+                let mut pos = 0;
+                for range in &found.ranges {
+                    spans.push(Span::styled(text[pos..range.start].to_string(), base));
+                    spans.push(Span::styled(
+                        text[range.clone()].to_string(),
+                        Style::default().fg(theme.bg).bg(theme.warning),
+                    ));
+                    pos = range.end;
+                }
+                spans.push(Span::styled(text[pos..].to_string(), base));
+                spans
+            }
+            Row::More(_) => vec![Span::styled(
+                format!("        … {}", termide_i18n::t().search_more_in_file()),
+                base.fg(theme.disabled),
+            )],
+        };
+
+        // Fill the row to highlight the whole selection
+        if selected {
+            let used: usize = spans.iter().map(Span::width).sum();
+            spans.push(Span::styled(" ".repeat(width.saturating_sub(used)), base));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Panel for SearchPanel {
+    fn name(&self) -> &'static str {
+        "search"
+    }
+
+    fn title(&self) -> String {
+        let title = termide_i18n::t().search_title();
+        match self.root.file_name() {
+            Some(name) => format!("{}: {}", title, name.to_string_lossy()),
+            None => title.to_string(),
+        }
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 4 {
+            return;
+        }
+        self.render_query(Rect { height: 1, ..area }, buf);
+        self.render_status(
+            Rect {
+                y: area.y + 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+        self.render_list(
+            Rect {
+                y: area.y + 2,
+                height: area.height - 3,
+                ..area
+            },
+            buf,
+        );
+        Paragraph::new(Line::styled(
+            termide_i18n::t().search_hint(),
+            Style::default().fg(self.cached_theme.disabled),
+        ))
+        .render(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        match key.code {
+            KeyCode::F(2) => self.toggle_option(true),
+            KeyCode::F(3) => self.toggle_option(false),
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Query if !self.files.is_empty() => Focus::Results,
+                    _ => Focus::Query,
+                };
+            }
+            _ => match self.focus {
+                Focus::Query => self.handle_query_key(key),
+                Focus::Results => return self.handle_results_key(key),
+            },
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.move_selection(SCROLL_STEP as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (list, scroll) = self.last_list;
+                let inside = mouse.row >= list.y
+                    && mouse.row < list.y + list.height
+                    && mouse.column >= list.x
+                    && mouse.column < list.x + list.width;
+                let idx = scroll + mouse.row.saturating_sub(list.y) as usize;
+                if inside && idx < self.rows().len() {
+                    self.selected = idx;
+                    self.focus = Focus::Results;
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        if self.poll_search() {
+            vec![PanelEvent::NeedsRedraw]
+        } else {
+            vec![]
+        }
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        match cmd {
+            PanelCommand::Reload => {
+                if self.state != State::Idle {
+                    self.start_search();
+                }
+                CommandResult::NeedsRedraw(true)
+            }
+            // Commands not applicable to SearchPanel
+            PanelCommand::GetRepoRoot
+            | PanelCommand::OnGitUpdate { .. }
+            | PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::GetFsWatchInfo
+            | PanelCommand::SetFsWatchRoot { .. }
+            | PanelCommand::OnFsUpdate { .. }
+            | PanelCommand::Resize { .. }
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
+    fn get_working_directory(&self) -> Option<PathBuf> {
+        Some(self.root.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Tick the panel until its search finished
+    fn wait(panel: &mut SearchPanel) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while panel.state == State::Running && Instant::now() < deadline {
+            panel.tick();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_ne!(panel.state, State::Running, "search did not finish");
+    }
+
+    fn type_query(panel: &mut SearchPanel, query: &str) {
+        for ch in query.chars() {
+            panel.handle_key(KeyEvent::from(KeyCode::Char(ch)));
+        }
+        panel.handle_key(KeyEvent::from(KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_search_and_open_match() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn main() {}\nlet Value = 1;\n").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/b.rs"), "  value\n").unwrap();
+
+        let mut panel = SearchPanel::new(dir.path().to_path_buf());
+        type_query(&mut panel, "value");
+        wait(&mut panel);
+        assert_eq!(panel.match_count(), 2);
+        assert_eq!(panel.state, State::Finished { truncated: false });
+
+        // Case sensitivity searches again
+        panel.handle_key(KeyEvent::from(KeyCode::F(2)));
+        wait(&mut panel);
+        assert_eq!(panel.match_count(), 1);
+        assert_eq!(panel.files[0].path, PathBuf::from("sub/b.rs"));
+
+        panel.handle_key(KeyEvent::from(KeyCode::Down));
+        panel.handle_key(KeyEvent::from(KeyCode::Down));
+        let events = panel.handle_key(KeyEvent::from(KeyCode::Enter));
+        match events.as_slice() {
+            [PanelEvent::OpenFile { path, line, column }] => {
+                assert_eq!(path, &dir.path().join("sub/b.rs"));
+                assert_eq!((*line, *column), (Some(1), Some(3)));
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_regex_is_reported() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        let mut panel = SearchPanel::new(dir.path().to_path_buf());
+        panel.handle_key(KeyEvent::from(KeyCode::F(3)));
+        type_query(&mut panel, "(");
+        assert!(panel.error.is_some());
+        assert!(panel.search.is_none());
+    }
+}
@@ -0,0 +1,322 @@
+//! Searching the files of a directory on background threads.
+//!
+//! Files are listed with git when the root is inside a repository (so
+//! ignored files are skipped), or by walking the directory otherwise.
+//! Several threads then search the files and send the matches of each file
+//! as soon as it is done. Dropping the search cancels it.
+
+use regex::Regex;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+
+use termide_config::constants::{
+    MAX_SEARCH_FILE_SIZE, MAX_SEARCH_MATCHES, MAX_SEARCH_MATCHES_PER_FILE,
+};
+
+/// Maximum number of threads searching files
+const MAX_THREADS: usize = 8;
+
+/// Bytes checked for NUL to detect binary files
+const BINARY_CHECK_BYTES: usize = 8192;
+
+/// Longer lines are cut around their first match
+const MAX_LINE_BYTES: usize = 400;
+
+/// Bytes kept before the first match of a cut line
+const LINE_CONTEXT_BYTES: usize = 60;
+
+/// Matching line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// Line number (0-indexed)
+    pub line: usize,
+    /// Text of the line (cut around the first match when long)
+    pub text: String,
+    /// Byte ranges of the matches in `text`
+    pub ranges: Vec<Range<usize>>,
+    /// Column of the first match in the whole line (0-indexed characters)
+    pub column: usize,
+}
+
+/// Matches found in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMatches {
+    /// Path relative to the search root
+    pub path: PathBuf,
+    pub lines: Vec<LineMatch>,
+    /// More lines matched than listed
+    pub truncated: bool,
+}
+
+/// Message sent by a running search
+#[derive(Debug)]
+pub enum SearchUpdate {
+    /// Matches of one more file
+    File(FileMatches),
+    /// All files were searched (or the overall cap was reached)
+    Finished {
+        /// The overall cap was reached
+        truncated: bool,
+    },
+}
+
+/// Search running on background threads
+#[derive(Debug)]
+pub struct WorkspaceSearch {
+    receiver: Receiver<SearchUpdate>,
+    /// Set to stop the threads
+    cancel: Arc<AtomicBool>,
+}
+
+impl WorkspaceSearch {
+    /// Start searching the files under `root` for `regex`
+    pub fn spawn(root: PathBuf, regex: Regex) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = termide_wake::channel();
+
+        let thread_cancel = Arc::clone(&cancel);
+        thread::spawn(move || run(&root, &regex, &thread_cancel, &sender));
+
+        Self { receiver, cancel }
+    }
+
+    /// Updates received since the previous call
+    pub fn try_updates(&self) -> Vec<SearchUpdate> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for WorkspaceSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// List and search the files, sending the matches of each file
+fn run(
+    root: &Path,
+    regex: &Regex,
+    cancel: &AtomicBool,
+    sender: &termide_wake::Sender<SearchUpdate>,
+) {
+    let files = list_files(root, cancel);
+    let next = AtomicUsize::new(0);
+    let total = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+
+    let threads = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .clamp(1, MAX_THREADS);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                if cancel.load(Ordering::Relaxed) || truncated.load(Ordering::Relaxed) {
+                    return;
+                }
+                let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                    return;
+                };
+                let Some(mut found) = search_file(root, path, regex, cancel) else {
+                    continue;
+                };
+
+                // Keep the overall number of lines under the cap
+                let before = total.fetch_add(found.lines.len(), Ordering::Relaxed);
+                if before + found.lines.len() > MAX_SEARCH_MATCHES {
+                    truncated.store(true, Ordering::Relaxed);
+                    found
+                        .lines
+                        .truncate(MAX_SEARCH_MATCHES.saturating_sub(before));
+                    if found.lines.is_empty() {
+                        return;
+                    }
+                }
+                if sender.send(SearchUpdate::File(found)).is_err() {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    if !cancel.load(Ordering::Relaxed) {
+        let _ = sender.send(SearchUpdate::Finished {
+            truncated: truncated.load(Ordering::Relaxed),
+        });
+    }
+}
+
+/// Files to search, relative to `root`
+fn list_files(root: &Path, cancel: &AtomicBool) -> Vec<PathBuf> {
+    if let Some(repo_root) = termide_git::find_repo_root(root) {
+        if let Some(files) = termide_git::list_files(&repo_root) {
+            return files
+                .into_iter()
+                .filter_map(|path| {
+                    repo_root
+                        .join(path)
+                        .strip_prefix(root)
+                        .ok()
+                        .map(Path::to_path_buf)
+                })
+                .collect();
+        }
+    }
+    walk(root, cancel)
+}
+
+/// List the files under `root`, skipping hidden entries and not following
+/// symbolic links
+fn walk(root: &Path, cancel: &AtomicBool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut directories = vec![PathBuf::new()];
+    while let Some(relative) = directories.pop() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let Ok(entries) = std::fs::read_dir(root.join(&relative)) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                directories.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Search a file, None if nothing matched or it cannot be searched
+/// (unreadable, too large or binary)
+fn search_file(
+    root: &Path,
+    path: &Path,
+    regex: &Regex,
+    cancel: &AtomicBool,
+) -> Option<FileMatches> {
+    let full_path = root.join(path);
+    if std::fs::metadata(&full_path).ok()?.len() > MAX_SEARCH_FILE_SIZE {
+        return None;
+    }
+    let bytes = std::fs::read(&full_path).ok()?;
+    if bytes[..bytes.len().min(BINARY_CHECK_BYTES)].contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+
+    let mut lines = Vec::new();
+    let mut truncated = false;
+    for (number, line) in text.lines().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        // Empty matches (e.g. `^`) would mark every line
+        let ranges: Vec<Range<usize>> = regex
+            .find_iter(line)
+            .map(|m| m.range())
+            .filter(|range| !range.is_empty())
+            .collect();
+        if ranges.is_empty() {
+            continue;
+        }
+        if lines.len() == MAX_SEARCH_MATCHES_PER_FILE {
+            truncated = true;
+            break;
+        }
+        lines.push(excerpt(number, line, ranges));
+    }
+
+    (!lines.is_empty()).then(|| FileMatches {
+        path: path.to_path_buf(),
+        lines,
+        truncated,
+    })
+}
+
+/// Matching line, cut around its first match when it is long
+fn excerpt(line: usize, text: &str, ranges: Vec<Range<usize>>) -> LineMatch {
+    let column = text[..ranges[0].start].chars().count();
+    if text.len() <= MAX_LINE_BYTES {
+        return LineMatch {
+            line,
+            text: text.to_string(),
+            ranges,
+            column,
+        };
+    }
+
+    let mut start = ranges[0].start.saturating_sub(LINE_CONTEXT_BYTES);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + MAX_LINE_BYTES).min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let ranges = ranges
+        .into_iter()
+        .filter(|range| range.start < end)
+        .map(|range| range.start.max(start) - start..range.end.min(end) - start)
+        .filter(|range| !range.is_empty())
+        .collect();
+    LineMatch {
+        line,
+        text: text[start..end].to_string(),
+        ranges,
+        column,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_skips_binary_and_hidden_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\nfound it\nthree found\n").unwrap();
+        std::fs::write(dir.path().join("b.bin"), b"found\0binary").unwrap();
+        std::fs::create_dir(dir.path().join(".hidden")).unwrap();
+        std::fs::write(dir.path().join(".hidden/c.txt"), "found").unwrap();
+
+        let regex = Regex::new("found").unwrap();
+        let cancel = AtomicBool::new(false);
+        let files = walk(dir.path(), &cancel);
+        assert_eq!(files, vec![PathBuf::from("a.txt"), PathBuf::from("b.bin")]);
+
+        let found = search_file(dir.path(), Path::new("a.txt"), &regex, &cancel).unwrap();
+        assert_eq!(found.lines.len(), 2);
+        assert_eq!(found.lines[0].line, 1);
+        assert_eq!(found.lines[0].ranges, vec![0..5]);
+        assert_eq!(found.lines[1].ranges, vec![6..11]);
+        assert_eq!(found.lines[1].column, 6);
+        assert!(search_file(dir.path(), Path::new("b.bin"), &regex, &cancel).is_none());
+    }
+
+    #[test]
+    fn test_long_line_is_cut_around_match() {
+        let text = format!("{}needle{}", "é".repeat(300), "x".repeat(300));
+        let ranges = Regex::new("needle")
+            .unwrap()
+            .find_iter(&text)
+            .map(|m| m.range())
+            .collect();
+        let found = excerpt(0, &text, ranges);
+        assert!(found.text.len() <= MAX_LINE_BYTES);
+        assert_eq!(found.column, 300);
+        assert_eq!(&found.text[found.ranges[0].clone()], "needle");
+    }
+}
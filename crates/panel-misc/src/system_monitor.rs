@@ -0,0 +1,521 @@
+//! System monitor panel.
+//!
+//! Shows CPU, memory and load average of the system, and the resource usage
+//! of each terminal panel's shell with all its child processes. Samples are
+//! taken on a background thread (`ProcessMonitor`) and picked up on `tick`;
+//! the panel only asks for a redraw when a sample changed noticeably.
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use std::time::Duration;
+
+use termide_core::{Panel, PanelEvent, RenderContext};
+use termide_system_monitor::{
+    format_bytes, send_signal, MonitorSnapshot, ProcessMonitor, ProcessTree, ProcessUsage, Signal,
+    TreeUsage,
+};
+use termide_theme::Theme;
+use termide_ui_render::resource_color;
+
+/// Rows moved by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Order of processes in the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortOrder {
+    /// Process trees as they are (parents before children)
+    Tree,
+    /// Highest CPU usage first
+    Cpu,
+    /// Highest memory usage first
+    Memory,
+}
+
+impl SortOrder {
+    /// Next order in the cycle (`s`)
+    fn next(self) -> Self {
+        match self {
+            Self::Tree => Self::Cpu,
+            Self::Cpu => Self::Memory,
+            Self::Memory => Self::Tree,
+        }
+    }
+}
+
+/// Row of the process table
+enum Row<'a> {
+    /// Terminal panel with the totals of its process tree
+    Tree(&'a TreeUsage),
+    /// Process of a tree
+    Process(&'a ProcessUsage),
+}
+
+/// System monitor panel
+pub struct SystemMonitorPanel {
+    /// Background sampling thread
+    monitor: ProcessMonitor,
+    /// Latest sample
+    snapshot: MonitorSnapshot,
+    /// Trees last given to the monitor
+    trees: Vec<ProcessTree>,
+    sort: SortOrder,
+    /// Selected process (kept while the process exists)
+    selected_pid: Option<u32>,
+    /// First table row shown
+    scroll: usize,
+    /// Table area and first row of the last render (for mouse clicks)
+    last_table: (Rect, usize),
+    /// Result of the last signal sent
+    status_message: Option<String>,
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl SystemMonitorPanel {
+    /// Create a panel sampling every `interval` and redrawing on changes
+    /// larger than `delta` percentage points.
+    pub fn new(interval: Duration, delta: f32) -> Self {
+        Self {
+            monitor: ProcessMonitor::spawn(interval, delta),
+            snapshot: MonitorSnapshot::default(),
+            trees: Vec::new(),
+            sort: SortOrder::Tree,
+            selected_pid: None,
+            scroll: 0,
+            last_table: (Rect::default(), 0),
+            status_message: None,
+            cached_theme: Theme::default(),
+        }
+    }
+
+    /// Set the process trees to show (the shells of terminal panels)
+    pub fn set_trees(&mut self, trees: Vec<ProcessTree>) {
+        if trees != self.trees {
+            self.monitor.set_trees(trees.clone());
+            self.trees = trees;
+        }
+    }
+
+    /// Table rows in the current sort order
+    fn rows(&self) -> Vec<Row<'_>> {
+        let mut trees: Vec<&TreeUsage> = self.snapshot.trees.iter().collect();
+        match self.sort {
+            SortOrder::Tree => {}
+            SortOrder::Cpu => trees.sort_by(|a, b| b.cpu_usage().total_cmp(&a.cpu_usage())),
+            SortOrder::Memory => trees.sort_by_key(|tree| std::cmp::Reverse(tree.memory())),
+        }
+
+        let mut rows = Vec::new();
+        for tree in trees {
+            rows.push(Row::Tree(tree));
+            let mut processes: Vec<&ProcessUsage> = tree.processes.iter().collect();
+            match self.sort {
+                SortOrder::Tree => {}
+                SortOrder::Cpu => processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage)),
+                SortOrder::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.memory)),
+            }
+            rows.extend(processes.into_iter().map(Row::Process));
+        }
+        rows
+    }
+
+    /// Row index of the selected process (the first process if it is gone)
+    fn selected_row(&self, rows: &[Row]) -> Option<usize> {
+        let is_process = |row: &Row| matches!(row, Row::Process(_));
+        rows.iter()
+            .position(|row| matches!(row, Row::Process(p) if Some(p.pid) == self.selected_pid))
+            .or_else(|| rows.iter().position(is_process))
+    }
+
+    /// Move the selection by `delta` processes
+    fn move_selection(&mut self, delta: isize) {
+        let pids: Vec<u32> = self
+            .rows()
+            .iter()
+            .filter_map(|row| match row {
+                Row::Process(p) => Some(p.pid),
+                Row::Tree(_) => None,
+            })
+            .collect();
+        if pids.is_empty() {
+            return;
+        }
+        let current = self
+            .selected_pid
+            .and_then(|pid| pids.iter().position(|&p| p == pid))
+            .unwrap_or(0);
+        let target = current.saturating_add_signed(delta).min(pids.len() - 1);
+        self.selected_pid = Some(pids[target]);
+    }
+
+    /// Rows moved by PageUp/PageDown (the table height)
+    fn page_size(&self) -> isize {
+        self.last_table.0.height.max(1) as isize
+    }
+
+    /// Send a signal to the selected process
+    fn signal_selected(&mut self, signal: Signal, signal_name: &str) {
+        let rows = self.rows();
+        let Some(Row::Process(process)) = self.selected_row(&rows).map(|idx| &rows[idx]) else {
+            return;
+        };
+        let t = termide_i18n::t();
+        let pid = process.pid.to_string();
+        let message = if send_signal(process.pid, signal) {
+            t.sysmon_signal_sent(signal_name, &pid, &process.name)
+        } else {
+            t.sysmon_signal_failed(signal_name, &pid)
+        };
+        self.status_message = Some(message);
+    }
+
+    /// Render the system-wide usage line
+    fn render_summary(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let label = Style::default().fg(theme.disabled);
+        let stats = &self.snapshot.stats;
+        let cpu = stats.cpu_usage.round().clamp(0.0, 100.0) as u8;
+        let memory = stats.memory_percent().round().clamp(0.0, 100.0) as u8;
+        let [one, five, fifteen] = self.snapshot.load_average;
+
+        let line = Line::from(vec![
+            Span::styled(format!("{} ", t.sysmon_cpu()), label),
+            Span::styled(
+                format!("{}%", cpu),
+                Style::default()
+                    .fg(resource_color(cpu, theme))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("  {} ", t.sysmon_memory()), label),
+            Span::styled(
+                format!(
+                    "{} / {} ({}%)",
+                    format_bytes(stats.memory_used),
+                    format_bytes(stats.memory_total),
+                    memory
+                ),
+                Style::default()
+                    .fg(resource_color(memory, theme))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("  {} ", t.sysmon_load()), label),
+            Span::styled(
+                format!("{:.2} {:.2} {:.2}", one, five, fifteen),
+                Style::default().fg(theme.fg),
+            ),
+        ]);
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the bottom line: the last signal result or the key hint
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let line = match &self.status_message {
+            Some(message) => Line::styled(message.clone(), Style::default().fg(theme.warning)),
+            None => {
+                let sort = match self.sort {
+                    SortOrder::Tree => t.sysmon_sort_tree(),
+                    SortOrder::Cpu => t.sysmon_cpu(),
+                    SortOrder::Memory => t.sysmon_memory(),
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", t.sysmon_sort()),
+                        Style::default().fg(theme.disabled),
+                    ),
+                    Span::styled(sort, Style::default().fg(theme.accented_fg)),
+                    Span::styled(
+                        format!("  {}", t.sysmon_hint()),
+                        Style::default().fg(theme.disabled),
+                    ),
+                ])
+            }
+        };
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the process table
+    fn render_table(&mut self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = self.cached_theme;
+        if area.height == 0 {
+            return;
+        }
+
+        let header = format!(
+            "{:>7} {:>6} {:>9}  {}",
+            "PID",
+            "CPU%",
+            t.sysmon_memory(),
+            t.sysmon_name()
+        );
+        Paragraph::new(Line::styled(
+            header,
+            Style::default()
+                .fg(theme.disabled)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .render(Rect { height: 1, ..area }, buf);
+
+        let body = Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        };
+        let rows = self.rows();
+        if rows.is_empty() {
+            Paragraph::new(Line::styled(
+                t.sysmon_no_terminals(),
+                Style::default().fg(theme.disabled),
+            ))
+            .render(body, buf);
+            return;
+        }
+
+        // Keep the selection visible
+        let height = body.height as usize;
+        let selected = self.selected_row(&rows);
+        let mut scroll = self.scroll.min(rows.len().saturating_sub(height));
+        if let Some(selected) = selected {
+            if selected < scroll {
+                scroll = selected.saturating_sub(1);
+            } else if selected >= scroll + height {
+                scroll = selected + 1 - height;
+            }
+        }
+
+        let width = body.width as usize;
+        let lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .map(|(idx, row)| match row {
+                Row::Tree(tree) => Line::styled(
+                    format!(
+                        "{:>7} {:>5.1}% {:>9}  {}",
+                        "",
+                        tree.cpu_usage(),
+                        format_bytes(tree.memory()),
+                        tree.label
+                    ),
+                    Style::default()
+                        .fg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Row::Process(process) => {
+                    let indent = match self.sort {
+                        SortOrder::Tree => "  ".repeat(process.depth),
+                        _ => String::new(),
+                    };
+                    let text = format!(
+                        "{:>7} {:>5.1}% {:>9}  {}{}",
+                        process.pid,
+                        process.cpu_usage,
+                        format_bytes(process.memory),
+                        indent,
+                        process.name
+                    );
+                    if Some(idx) == selected {
+                        Line::styled(
+                            format!("{:<width$}", text, width = width),
+                            Style::default().fg(theme.selected_fg).bg(theme.selected_bg),
+                        )
+                    } else {
+                        Line::styled(text, Style::default().fg(theme.fg))
+                    }
+                }
+            })
+            .collect();
+        Paragraph::new(lines).render(body, buf);
+
+        let selected_pid = selected.and_then(|idx| match rows[idx] {
+            Row::Process(process) => Some(process.pid),
+            Row::Tree(_) => None,
+        });
+        self.selected_pid = selected_pid;
+        self.scroll = scroll;
+        self.last_table = (body, scroll);
+    }
+}
+
+impl Panel for SystemMonitorPanel {
+    fn name(&self) -> &'static str {
+        "system_monitor"
+    }
+
+    fn title(&self) -> String {
+        termide_i18n::t().sysmon_title().to_string()
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 3 {
+            return;
+        }
+        self.render_summary(Rect { height: 1, ..area }, buf);
+        self.render_table(
+            Rect {
+                y: area.y + 2,
+                height: area.height - 3,
+                ..area
+            },
+            buf,
+        );
+        self.render_footer(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        self.status_message = None;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-self.page_size()),
+            KeyCode::PageDown => self.move_selection(self.page_size()),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            KeyCode::Char('s') => self.sort = self.sort.next(),
+            KeyCode::Char('t') => self.signal_selected(Signal::Term, "SIGTERM"),
+            KeyCode::Char('K') => self.signal_selected(Signal::Kill, "SIGKILL"),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.move_selection(SCROLL_STEP as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (table, scroll) = self.last_table;
+                let inside = mouse.row >= table.y
+                    && mouse.row < table.y + table.height
+                    && mouse.column >= table.x
+                    && mouse.column < table.x + table.width;
+                if inside {
+                    let idx = scroll + (mouse.row - table.y) as usize;
+                    if let Some(Row::Process(process)) = self.rows().get(idx) {
+                        self.selected_pid = Some(process.pid);
+                    }
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        match self.monitor.try_latest() {
+            Some(snapshot) => {
+                self.snapshot = snapshot;
+                vec![PanelEvent::NeedsRedraw]
+            }
+            None => vec![],
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, cpu_usage: f32, memory: u64, depth: usize) -> ProcessUsage {
+        ProcessUsage {
+            pid,
+            name: format!("p{}", pid),
+            cpu_usage,
+            memory,
+            depth,
+        }
+    }
+
+    fn panel() -> SystemMonitorPanel {
+        let mut panel = SystemMonitorPanel::new(Duration::from_secs(3600), 1.0);
+        panel.snapshot.trees = vec![
+            TreeUsage {
+                label: "Terminal 1".to_string(),
+                root_pid: 10,
+                processes: vec![process(10, 0.0, 100, 0), process(11, 5.0, 300, 1)],
+            },
+            TreeUsage {
+                label: "Terminal 2".to_string(),
+                root_pid: 20,
+                processes: vec![process(20, 1.0, 200, 0), process(21, 40.0, 50, 1)],
+            },
+        ];
+        panel
+    }
+
+    fn order(panel: &SystemMonitorPanel) -> Vec<String> {
+        panel
+            .rows()
+            .iter()
+            .map(|row| match row {
+                Row::Tree(tree) => tree.label.clone(),
+                Row::Process(p) => p.pid.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let mut panel = panel();
+        assert_eq!(
+            order(&panel),
+            vec!["Terminal 1", "10", "11", "Terminal 2", "20", "21"]
+        );
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert_eq!(
+            order(&panel),
+            vec!["Terminal 2", "21", "20", "Terminal 1", "11", "10"]
+        );
+
+        panel.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert_eq!(
+            order(&panel),
+            vec!["Terminal 1", "11", "10", "Terminal 2", "20", "21"]
+        );
+    }
+
+    #[test]
+    fn test_selection_skips_tree_rows() {
+        let mut panel = panel();
+        panel.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(panel.selected_pid, Some(11));
+        panel.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(panel.selected_pid, Some(20));
+        panel.handle_key(KeyEvent::from(KeyCode::End));
+        assert_eq!(panel.selected_pid, Some(21));
+        panel.handle_key(KeyEvent::from(KeyCode::Home));
+        assert_eq!(panel.selected_pid, Some(10));
+    }
+}
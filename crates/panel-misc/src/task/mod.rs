@@ -0,0 +1,548 @@
+//! Task panel.
+//!
+//! Runs a named command from the `[tasks]` section of the config (such as
+//! `build = "cargo build"`) in the project directory and shows its output
+//! as it arrives. Errors and warnings printed by compilers and test runners
+//! are listed above the output; Enter opens the file of the selected
+//! problem at its line. Running the task again reuses its panel.
+
+mod problems;
+mod runner;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use termide_config::constants::MAX_TASK_OUTPUT_LINES;
+use termide_core::{
+    CommandResult, NotificationLevel, Panel, PanelCommand, PanelEvent, RenderContext,
+};
+use termide_theme::Theme;
+
+use problems::{Problem, ProblemParser, Severity};
+use runner::{RunUpdate, TaskRun};
+
+/// Lines scrolled by the mouse wheel
+const SCROLL_STEP: usize = 3;
+
+/// Progress of the task
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum State {
+    Running,
+    /// Exit code (None if stopped by a signal)
+    Exited(Option<i32>),
+    /// The command could not be started
+    Failed(String),
+}
+
+/// Task panel
+pub struct TaskPanel {
+    /// Task name from the config
+    name: String,
+    command: String,
+    /// Directory the command runs in
+    cwd: PathBuf,
+    /// Running command (dropping it stops the command)
+    run: Option<TaskRun>,
+    state: State,
+    /// Latest output lines
+    output: VecDeque<String>,
+    /// Number of output lines dropped from the front
+    dropped: usize,
+    parser: ProblemParser,
+    problems: Vec<Problem>,
+    /// Selected problem
+    selected: usize,
+    /// First problem row shown
+    problem_scroll: usize,
+    /// Output line shown in the middle (None follows new output)
+    output_target: Option<usize>,
+    /// Problem list area and first row of the last render (for mouse clicks)
+    last_problems: (Rect, usize),
+    /// Cached theme for rendering
+    cached_theme: Theme,
+}
+
+impl TaskPanel {
+    /// Create a panel running `command` in `cwd`
+    pub fn new(name: String, command: String, cwd: PathBuf) -> Self {
+        let mut panel = Self {
+            name,
+            command,
+            cwd,
+            run: None,
+            state: State::Running,
+            output: VecDeque::new(),
+            dropped: 0,
+            parser: ProblemParser::new(),
+            problems: Vec::new(),
+            selected: 0,
+            problem_scroll: 0,
+            output_target: None,
+            last_problems: (Rect::default(), 0),
+            cached_theme: Theme::default(),
+        };
+        panel.start();
+        panel
+    }
+
+    /// Name of the task
+    pub fn task_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Run the task again with `command`, stopping the running one
+    pub fn restart(&mut self, command: String) {
+        self.command = command;
+        self.start();
+    }
+
+    /// Start the command with empty output
+    fn start(&mut self) {
+        self.run = None;
+        self.output.clear();
+        self.dropped = 0;
+        self.parser = ProblemParser::new();
+        self.problems.clear();
+        self.selected = 0;
+        self.problem_scroll = 0;
+        self.output_target = None;
+        match TaskRun::spawn(&self.command, &self.cwd) {
+            Ok(run) => {
+                self.run = Some(run);
+                self.state = State::Running;
+            }
+            Err(e) => self.state = State::Failed(e.to_string()),
+        }
+    }
+
+    /// Stop the running command
+    fn stop(&mut self) {
+        if let Some(run) = &self.run {
+            run.stop();
+        }
+    }
+
+    /// Apply the updates of the running command, true if any arrived
+    fn poll(&mut self) -> bool {
+        let Some(run) = &mut self.run else {
+            return false;
+        };
+        let updates = run.try_updates();
+        if updates.is_empty() {
+            return false;
+        }
+        for update in updates {
+            match update {
+                RunUpdate::Line(line) => self.push_line(line),
+                RunUpdate::Exited(code) => {
+                    self.state = State::Exited(code);
+                    self.run = None;
+                }
+            }
+        }
+        true
+    }
+
+    /// Notification telling how the command ended
+    fn finished_notification(&self) -> PanelEvent {
+        let t = termide_i18n::t();
+        let (status, level) = match &self.state {
+            State::Exited(Some(0)) => (t.task_exited(0), NotificationLevel::Info),
+            State::Exited(Some(code)) => (t.task_exited(*code), NotificationLevel::Error),
+            State::Exited(None) => (t.task_stopped().to_string(), NotificationLevel::Warning),
+            State::Running => (t.task_running().to_string(), NotificationLevel::Info),
+            State::Failed(error) => (t.task_failed_to_start(error), NotificationLevel::Error),
+        };
+        PanelEvent::Notify {
+            text: format!("{}: {} — {}", t.task_title(), self.name, status),
+            level,
+        }
+    }
+
+    /// Add an output line, checking it for a problem
+    fn push_line(&mut self, line: String) {
+        let index = self.dropped + self.output.len();
+        if let Some(problem) = self.parser.parse_line(&line, index) {
+            self.problems.push(problem);
+        }
+        self.output.push_back(line);
+        if self.output.len() > MAX_TASK_OUTPUT_LINES {
+            self.output.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    /// Move the selection by `delta` problems and show its output line
+    fn move_selection(&mut self, delta: isize) {
+        if self.problems.is_empty() {
+            return;
+        }
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.problems.len() - 1);
+        self.output_target = Some(self.problems[self.selected].output_line);
+    }
+
+    /// Scroll the output by `delta` lines
+    fn scroll_output(&mut self, delta: isize) {
+        let end = self.dropped + self.output.len().saturating_sub(1);
+        let target = self.output_target.unwrap_or(end);
+        let target = target.saturating_add_signed(delta).clamp(self.dropped, end);
+        // Scrolling to the end follows new output again
+        self.output_target = (target < end).then_some(target);
+    }
+
+    /// Open the file of the selected problem at its line
+    fn open_selected(&self) -> Vec<PanelEvent> {
+        let Some(problem) = self.problems.get(self.selected) else {
+            return vec![];
+        };
+        vec![PanelEvent::open_file_at(
+            self.cwd.join(&problem.path),
+            problem.line,
+            problem.column,
+        )]
+    }
+
+    /// Style of a problem's severity
+    fn severity_style(&self, severity: Severity) -> Style {
+        let theme = &self.cached_theme;
+        Style::default().fg(match severity {
+            Severity::Error => theme.error,
+            Severity::Warning => theme.warning,
+            Severity::Note => theme.accented_fg,
+        })
+    }
+
+    /// Render the command line with the task state and problem counts
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let t = termide_i18n::t();
+        let theme = &self.cached_theme;
+        let (state, color) = match &self.state {
+            State::Running => (t.task_running().to_string(), theme.warning),
+            State::Exited(Some(0)) => (t.task_exited(0), theme.success),
+            State::Exited(Some(code)) => (t.task_exited(*code), theme.error),
+            State::Exited(None) => (t.task_stopped().to_string(), theme.warning),
+            State::Failed(error) => (t.task_failed_to_start(error), theme.error),
+        };
+        let count = |severity| {
+            self.problems
+                .iter()
+                .filter(|problem| problem.severity == severity)
+                .count()
+        };
+        let problems = if self.problems.is_empty() && self.state != State::Running {
+            t.task_no_problems().to_string()
+        } else {
+            t.task_problems(count(Severity::Error), count(Severity::Warning))
+        };
+
+        let line = Line::from(vec![
+            Span::styled(
+                format!("$ {}", self.command),
+                Style::default()
+                    .fg(theme.accented_fg)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("  {}", state), Style::default().fg(color)),
+            Span::styled(
+                format!("  ({})", problems),
+                Style::default().fg(theme.disabled),
+            ),
+        ]);
+        Paragraph::new(line).render(area, buf);
+    }
+
+    /// Render the problem list
+    fn render_problems(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = self.cached_theme;
+        let height = area.height as usize;
+        if self.selected < self.problem_scroll {
+            self.problem_scroll = self.selected;
+        } else if self.selected >= self.problem_scroll + height {
+            self.problem_scroll = self.selected + 1 - height;
+        }
+        self.last_problems = (area, self.problem_scroll);
+
+        let width = area.width as usize;
+        let lines: Vec<Line> = self
+            .problems
+            .iter()
+            .enumerate()
+            .skip(self.problem_scroll)
+            .take(height)
+            .map(|(idx, problem)| {
+                let selected = idx == self.selected;
+                let base = if selected {
+                    Style::default().fg(theme.selected_fg).bg(theme.selected_bg)
+                } else {
+                    Style::default()
+                };
+                let location = match problem.column {
+                    Some(column) => {
+                        format!("{}:{}:{}", problem.path.display(), problem.line, column)
+                    }
+                    None => format!("{}:{}", problem.path.display(), problem.line),
+                };
+                let mut spans = vec![
+                    Span::styled(location, base.patch(self.severity_style(problem.severity))),
+                    Span::styled(format!("  {}", problem.message), base),
+                ];
+                // Fill the row to highlight the whole selection
+                if selected {
+                    let used: usize = spans.iter().map(Span::width).sum();
+                    spans.push(Span::styled(" ".repeat(width.saturating_sub(used)), base));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    /// Render the output, following new lines or around the selected problem
+    fn render_output(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.cached_theme;
+        let height = area.height as usize;
+        let start = match self.output_target {
+            Some(target) => target
+                .saturating_sub(self.dropped)
+                .saturating_sub(height / 2)
+                .min(self.output.len().saturating_sub(height)),
+            None => self.output.len().saturating_sub(height),
+        };
+
+        let selected_line = self
+            .problems
+            .get(self.selected)
+            .map(|problem| problem.output_line);
+        let lines: Vec<Line> = self
+            .output
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(idx, text)| {
+                let index = self.dropped + idx;
+                let problem = self
+                    .problems
+                    .iter()
+                    .find(|problem| problem.output_line == index);
+                let style = match problem {
+                    Some(_) if selected_line == Some(index) => {
+                        Style::default().fg(theme.selected_fg).bg(theme.selected_bg)
+                    }
+                    Some(problem) => self.severity_style(problem.severity),
+                    None => Style::default(),
+                };
+                Line::styled(text.clone(), style)
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+impl Panel for TaskPanel {
+    fn name(&self) -> &'static str {
+        "task"
+    }
+
+    fn title(&self) -> String {
+        format!("{}: {}", termide_i18n::t().task_title(), self.name)
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &termide_config::Config) {
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _ctx: &RenderContext) {
+        if area.height < 3 {
+            return;
+        }
+        self.render_status(Rect { height: 1, ..area }, buf);
+
+        // Problems take up to a third of the space left
+        let body = area.height - 2;
+        let problems_height = (self.problems.len() as u16).min(body / 3);
+        if problems_height > 0 {
+            self.render_problems(
+                Rect {
+                    y: area.y + 1,
+                    height: problems_height,
+                    ..area
+                },
+                buf,
+            );
+        }
+        self.render_output(
+            Rect {
+                y: area.y + 1 + problems_height,
+                height: body - problems_height,
+                ..area
+            },
+            buf,
+        );
+        Paragraph::new(Line::styled(
+            termide_i18n::t().task_hint(),
+            Style::default().fg(self.cached_theme.disabled),
+        ))
+        .render(
+            Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            },
+            buf,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        let page = (self.last_problems.0.height.max(1)) as isize;
+        match key.code {
+            KeyCode::Enter => return self.open_selected(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-page),
+            KeyCode::PageDown => self.move_selection(page),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.stop(),
+            KeyCode::Char('r') if key.modifiers.is_empty() => self.start(),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, _area: Rect) -> Vec<PanelEvent> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_output(-(SCROLL_STEP as isize)),
+            MouseEventKind::ScrollDown => self.scroll_output(SCROLL_STEP as isize),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (list, scroll) = self.last_problems;
+                let inside = mouse.row >= list.y
+                    && mouse.row < list.y + list.height
+                    && mouse.column >= list.x
+                    && mouse.column < list.x + list.width;
+                let idx = scroll + mouse.row.saturating_sub(list.y) as usize;
+                if inside && idx < self.problems.len() {
+                    self.selected = idx;
+                    self.output_target = Some(self.problems[idx].output_line);
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        let was_running = self.run.is_some();
+        if !self.poll() {
+            return vec![];
+        }
+        let mut events = vec![PanelEvent::NeedsRedraw];
+        if was_running && self.run.is_none() {
+            events.push(self.finished_notification());
+        }
+        events
+    }
+
+    fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
+        match cmd {
+            // Commands not applicable to TaskPanel
+            PanelCommand::GetRepoRoot
+            | PanelCommand::OnGitUpdate { .. }
+            | PanelCommand::RunDeferredWork
+            | PanelCommand::CheckGitDiffReceiver
+            | PanelCommand::CheckExternalModification
+            | PanelCommand::GetFsWatchInfo
+            | PanelCommand::SetFsWatchRoot { .. }
+            | PanelCommand::OnFsUpdate { .. }
+            | PanelCommand::Resize { .. }
+            | PanelCommand::Reload
+            | PanelCommand::GetModificationStatus
+            | PanelCommand::Save
+            | PanelCommand::CloseWithoutSaving
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. }
+            | PanelCommand::MenuAction { .. } => CommandResult::None,
+        }
+    }
+
+    fn get_working_directory(&self) -> Option<PathBuf> {
+        Some(self.cwd.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Tick the panel until its command exited
+    fn wait(panel: &mut TaskPanel) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while panel.state == State::Running && Instant::now() < deadline {
+            panel.tick();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_ne!(panel.state, State::Running, "task did not finish");
+    }
+
+    #[test]
+    fn test_run_and_open_problem() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        let command = "echo 'src/main.c:3:7: error: expected expression'; \
+                       echo 'lib.c:9:1: warning: unused'; exit 1";
+        let mut panel = TaskPanel::new(
+            "build".to_string(),
+            command.to_string(),
+            dir.path().to_path_buf(),
+        );
+        wait(&mut panel);
+        assert_eq!(panel.state, State::Exited(Some(1)));
+        assert_eq!(panel.output.len(), 2);
+        assert_eq!(panel.problems.len(), 2);
+        assert!(matches!(
+            panel.finished_notification(),
+            PanelEvent::Notify { text, level: NotificationLevel::Error }
+                if text == "Task: build — Exited with code 1"
+        ));
+
+        panel.handle_key(KeyEvent::from(KeyCode::Down));
+        panel.handle_key(KeyEvent::from(KeyCode::Up));
+        let events = panel.handle_key(KeyEvent::from(KeyCode::Enter));
+        match events.as_slice() {
+            [PanelEvent::OpenFile { path, line, column }] => {
+                assert_eq!(path, &dir.path().join("src/main.c"));
+                assert_eq!((*line, *column), (Some(3), Some(7)));
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+
+        // Running again starts over
+        panel.restart("echo done".to_string());
+        wait(&mut panel);
+        assert_eq!(panel.state, State::Exited(Some(0)));
+        assert!(panel.problems.is_empty());
+        assert_eq!(panel.output, ["done"]);
+    }
+}
@@ -0,0 +1,242 @@
+//! Problems (errors and warnings) found in the output of a task.
+//!
+//! Output lines are matched against a table of compiler and test runner
+//! formats. Some tools print the message and the location on separate
+//! lines (rustc prints `error[E0308]: ...` and then `--> src/main.rs:4:5`),
+//! so a header line is remembered until the location line that follows it.
+
+use regex::Regex;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Severity of a problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    fn parse(text: &str) -> Self {
+        match text {
+            "warning" => Self::Warning,
+            "note" => Self::Note,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// Problem reported in the output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// File as printed (relative to the task directory or absolute)
+    pub path: PathBuf,
+    /// Line number (1-based)
+    pub line: usize,
+    /// Column (1-based) when printed
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+    /// Output line the location was printed on
+    pub output_line: usize,
+}
+
+/// What a line matching a pattern provides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Severity and message, the location follows on a later line
+    Header,
+    /// Location for the last header
+    Location,
+    /// Location, severity and message on one line
+    Full,
+}
+
+/// Output format of a tool.
+///
+/// Named groups: `file`, `line`, `col`, `severity` and `message`.
+struct Pattern {
+    kind: Kind,
+    regex: &'static str,
+    /// Severity when the regex has no `severity` group
+    severity: Severity,
+}
+
+/// Known formats, tried in order
+const PATTERNS: &[Pattern] = &[
+    // rustc: `error[E0308]: mismatched types`
+    Pattern {
+        kind: Kind::Header,
+        regex: r"^(?P<severity>error|warning)(?:\[\w+\])?: (?P<message>.+)$",
+        severity: Severity::Error,
+    },
+    // rustc: `  --> src/main.rs:4:5`
+    Pattern {
+        kind: Kind::Location,
+        regex: r"^\s*--> (?P<file>.+?):(?P<line>\d+):(?P<col>\d+)$",
+        severity: Severity::Error,
+    },
+    // gcc, clang: `main.c:5:10: error: expected ';'`
+    Pattern {
+        kind: Kind::Full,
+        regex: r"^(?P<file>[^\s:][^:]*):(?P<line>\d+):(?:(?P<col>\d+):)? (?:fatal )?(?P<severity>error|warning|note): (?P<message>.+)$",
+        severity: Severity::Error,
+    },
+    // pytest: `tests/test_math.py:12: AssertionError`
+    Pattern {
+        kind: Kind::Full,
+        regex: r"^(?P<file>[^\s:][^:]*\.py):(?P<line>\d+): (?P<message>.+)$",
+        severity: Severity::Error,
+    },
+];
+
+/// Compiled patterns
+fn patterns() -> &'static [(&'static Pattern, Regex)] {
+    static COMPILED: OnceLock<Vec<(&'static Pattern, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        PATTERNS
+            .iter()
+            .map(|pattern| (pattern, Regex::new(pattern.regex).expect("valid pattern")))
+            .collect()
+    })
+}
+
+/// Finds problems in output lines fed in order
+#[derive(Debug, Default)]
+pub struct ProblemParser {
+    /// Severity and message of the last header line
+    header: Option<(Severity, String)>,
+}
+
+impl ProblemParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the next output line (`output_line` is its index)
+    pub fn parse_line(&mut self, text: &str, output_line: usize) -> Option<Problem> {
+        let (pattern, captures) = patterns()
+            .iter()
+            .find_map(|(pattern, regex)| regex.captures(text).map(|found| (pattern, found)))?;
+        let group = |name: &str| captures.name(name).map(|found| found.as_str());
+
+        let severity = group("severity").map_or(pattern.severity, Severity::parse);
+        let message = group("message").unwrap_or_default().trim().to_string();
+        let (severity, message) = match pattern.kind {
+            Kind::Header => {
+                self.header = Some((severity, message));
+                return None;
+            }
+            Kind::Location => self.header.take().unwrap_or((severity, message)),
+            Kind::Full => (severity, message),
+        };
+
+        Some(Problem {
+            path: PathBuf::from(group("file")?),
+            line: group("line")?.parse().ok()?,
+            column: group("col").and_then(|col| col.parse().ok()),
+            severity,
+            message,
+            output_line,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(output: &str) -> Vec<Problem> {
+        let mut parser = ProblemParser::new();
+        output
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| parser.parse_line(line, idx))
+            .collect()
+    }
+
+    #[test]
+    fn test_rustc_output() {
+        let output = r#"   Compiling demo v0.1.0 (/home/user/demo)
+warning: unused variable: `x`
+ --> src/lib.rs:2:9
+  |
+2 |     let x = 1;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+  |
+  = note: `#[warn(unused_variables)]` on by default
+
+error[E0308]: mismatched types
+  --> src/main.rs:14:20
+   |
+14 |     let n: usize = "three";
+   |            -----   ^^^^^^^ expected `usize`, found `&str`
+   |            |
+   |            expected due to this
+
+error: could not compile `demo` (bin "demo") due to 1 previous error
+"#;
+        let problems = parse(output);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].path, PathBuf::from("src/lib.rs"));
+        assert_eq!((problems[0].line, problems[0].column), (2, Some(9)));
+        assert_eq!(problems[0].severity, Severity::Warning);
+        assert_eq!(problems[0].message, "unused variable: `x`");
+        assert_eq!(problems[0].output_line, 2);
+        assert_eq!(problems[1].path, PathBuf::from("src/main.rs"));
+        assert_eq!((problems[1].line, problems[1].column), (14, Some(20)));
+        assert_eq!(problems[1].severity, Severity::Error);
+        assert_eq!(problems[1].message, "mismatched types");
+    }
+
+    #[test]
+    fn test_gcc_output() {
+        let output = r#"main.c: In function 'main':
+main.c:5:12: error: expected ';' before '}' token
+    5 |     return 0
+      |            ^
+      |            ;
+src/util.c:17:5: warning: implicit declaration of function 'foo' [-Wimplicit-function-declaration]
+src/util.h:3: note: previous declaration here
+"#;
+        let problems = parse(output);
+        assert_eq!(problems.len(), 3);
+        assert_eq!(problems[0].path, PathBuf::from("main.c"));
+        assert_eq!((problems[0].line, problems[0].column), (5, Some(12)));
+        assert_eq!(problems[0].message, "expected ';' before '}' token");
+        assert_eq!(problems[1].severity, Severity::Warning);
+        assert_eq!(problems[1].path, PathBuf::from("src/util.c"));
+        assert_eq!(
+            (problems[2].line, problems[2].column, problems[2].severity),
+            (3, None, Severity::Note)
+        );
+    }
+
+    #[test]
+    fn test_pytest_output() {
+        let output = r#"============================= test session starts ==============================
+collected 2 items
+
+tests/test_math.py .F                                                    [100%]
+
+=================================== FAILURES ===================================
+_________________________________ test_divide __________________________________
+
+    def test_divide():
+>       assert divide(6, 3) == 3
+E       assert 2.0 == 3
+
+tests/test_math.py:12: AssertionError
+=========================== short test summary info ============================
+FAILED tests/test_math.py::test_divide - assert 2.0 == 3
+========================= 1 failed, 1 passed in 0.02s ==========================
+"#;
+        let problems = parse(output);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("tests/test_math.py"));
+        assert_eq!((problems[0].line, problems[0].column), (12, None));
+        assert_eq!(problems[0].severity, Severity::Error);
+        assert_eq!(problems[0].message, "AssertionError");
+    }
+}
@@ -0,0 +1,170 @@
+//! Running a task command on background threads.
+//!
+//! The command runs through `sh -c` in its own process group, with stdout
+//! and stderr read line by line on separate threads. Dropping the run
+//! terminates the whole group, so build tools spawned by the command stop
+//! too.
+
+use nix::sys::signal::{killpg, Signal};
+use nix::unistd::Pid;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Receiver;
+use std::thread;
+
+/// Message sent by a running task
+#[derive(Debug, PartialEq, Eq)]
+pub enum RunUpdate {
+    /// Line of output (stdout or stderr)
+    Line(String),
+    /// The process exited, with its exit code (None if killed by a signal)
+    Exited(Option<i32>),
+}
+
+/// Task command running in the background
+#[derive(Debug)]
+pub struct TaskRun {
+    receiver: Receiver<RunUpdate>,
+    /// Process group of the command
+    pid: i32,
+    finished: bool,
+}
+
+impl TaskRun {
+    /// Start `command` in `cwd`
+    pub fn spawn(command: &str, cwd: &Path) -> std::io::Result<Self> {
+        use std::os::unix::process::CommandExt;
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()?;
+        let pid = child.id() as i32;
+        let (sender, receiver) = termide_wake::channel();
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        thread::spawn(move || {
+            let stderr_sender = sender.clone();
+            let stderr_thread = thread::spawn(move || {
+                if let Some(stderr) = stderr {
+                    send_lines(stderr, &stderr_sender);
+                }
+            });
+            if let Some(stdout) = stdout {
+                send_lines(stdout, &sender);
+            }
+            let _ = stderr_thread.join();
+            let code = child.wait().ok().and_then(|status| status.code());
+            let _ = sender.send(RunUpdate::Exited(code));
+        });
+
+        Ok(Self {
+            receiver,
+            pid,
+            finished: false,
+        })
+    }
+
+    /// Updates received since the previous call
+    pub fn try_updates(&mut self) -> Vec<RunUpdate> {
+        let updates: Vec<RunUpdate> = self.receiver.try_iter().collect();
+        if updates
+            .iter()
+            .any(|update| matches!(update, RunUpdate::Exited(_)))
+        {
+            self.finished = true;
+        }
+        updates
+    }
+
+    /// Terminate the command and the processes it started
+    pub fn stop(&self) {
+        if !self.finished {
+            let _ = killpg(Pid::from_raw(self.pid), Signal::SIGTERM);
+        }
+    }
+}
+
+impl Drop for TaskRun {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Send the lines read from `reader` until it is closed
+fn send_lines(reader: impl Read, sender: &termide_wake::Sender<RunUpdate>) {
+    for line in BufReader::new(reader).split(b'\n') {
+        let Ok(line) = line else {
+            return;
+        };
+        if sender.send(RunUpdate::Line(clean_line(&line))).is_err() {
+            return;
+        }
+    }
+}
+
+/// Output line as text, without the carriage return and color escapes
+fn clean_line(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let mut line = String::with_capacity(text.len());
+    let mut chars = text.trim_end_matches('\r').chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // Skip a CSI sequence (`ESC [ params final`)
+            if chars.next() == Some('[') {
+                for ch in chars.by_ref() {
+                    if ('@'..='~').contains(&ch) {
+                        break;
+                    }
+                }
+            }
+        } else if ch == '\t' {
+            line.push_str("    ");
+        } else if !ch.is_control() {
+            line.push(ch);
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Collect updates until the task exited
+    fn wait(run: &mut TaskRun) -> Vec<RunUpdate> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut updates = Vec::new();
+        while !run.finished && Instant::now() < deadline {
+            updates.extend(run.try_updates());
+            thread::sleep(Duration::from_millis(5));
+        }
+        updates
+    }
+
+    #[test]
+    fn test_output_and_exit_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = TaskRun::spawn("echo out; echo err >&2; exit 3", dir.path()).unwrap();
+        let updates = wait(&mut run);
+        assert!(updates.contains(&RunUpdate::Line("out".to_string())));
+        assert!(updates.contains(&RunUpdate::Line("err".to_string())));
+        assert_eq!(updates.last(), Some(&RunUpdate::Exited(Some(3))));
+    }
+
+    #[test]
+    fn test_color_escapes_are_removed() {
+        assert_eq!(
+            clean_line(b"\x1b[1m\x1b[31merror\x1b[0m: bad\tthing\r"),
+            "error: bad    thing"
+        );
+    }
+}
@@ -1,17 +1,29 @@
-use crossterm::event::KeyEvent;
-use ratatui::{buffer::Buffer, layout::Rect};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
 use std::any::Any;
+use std::path::{Path, PathBuf};
 
 use termide_config::Config;
 use termide_core::{Panel, PanelEvent, RenderContext};
 use termide_i18n;
 use termide_panel_editor::Editor;
 use termide_theme::Theme;
+use termide_ui::fuzzy::fuzzy_match;
 
 // Embed help files at compile time
 const HELP_EN: &str = include_str!("../../../help/en.txt");
 const HELP_RU: &str = include_str!("../../../help/ru.txt");
 
+/// Maximum number of rows of the recent list (headers included)
+const MAX_RECENT_ROWS: usize = 12;
+
 /// Get help text based on current locale from i18n system
 fn get_help_text() -> &'static str {
     // Use the language from the already initialized i18n system
@@ -21,19 +33,299 @@ fn get_help_text() -> &'static str {
     }
 }
 
+/// Recently used file or project shown on the welcome screen
+#[derive(Debug, Clone)]
+struct RecentEntry {
+    path: PathBuf,
+    /// Path shown and matched (home directory as `~`)
+    label: String,
+    is_dir: bool,
+    /// Deleted entries are greyed out and cannot be opened
+    exists: bool,
+}
+
+impl RecentEntry {
+    fn new(path: PathBuf, is_dir: bool) -> Self {
+        let exists = if is_dir {
+            path.is_dir()
+        } else {
+            path.is_file()
+        };
+        Self {
+            label: display_path(&path),
+            path,
+            is_dir,
+            exists,
+        }
+    }
+}
+
+/// Entry matching the current query
+#[derive(Debug, Clone)]
+struct FilteredEntry {
+    /// Index in `recent`
+    index: usize,
+    /// Char indices of matched characters in the label
+    positions: Vec<usize>,
+}
+
+/// Row of the rendered recent list
+enum RecentRow {
+    /// Section title (projects or files)
+    Header(String),
+    /// Index in `filtered`
+    Entry(usize),
+}
+
 /// Welcome panel (shown when all panels are closed)
-/// Uses Editor in read-only mode to display scrollable help
+///
+/// Lists recent projects and files above the help text. Typing filters the
+/// list, Up/Down select an entry and Enter opens it; other keys scroll the
+/// help, which uses Editor in read-only mode.
 pub struct WelcomePanel {
     editor: Editor,
+    /// Recent projects followed by recent files
+    recent: Vec<RecentEntry>,
+    query: String,
+    filtered: Vec<FilteredEntry>,
+    /// Selected position in `filtered`
+    cursor: usize,
+    /// First visible row of the recent list
+    scroll_offset: usize,
+    theme: Theme,
+    /// Visible entry rows with their `filtered` index (for mouse clicks)
+    last_rows: Vec<(Rect, usize)>,
+    /// Area of the help text (for mouse handling)
+    last_editor_area: Option<Rect>,
 }
 
 impl WelcomePanel {
     pub fn new() -> Self {
+        Self::with_recent(Vec::new(), Vec::new())
+    }
+
+    /// Create the panel with recent files and projects, most recent first
+    pub fn with_recent(files: Vec<PathBuf>, projects: Vec<PathBuf>) -> Self {
         let help_text = get_help_text();
         let title = termide_i18n::t().panel_welcome().to_string();
         let editor = Editor::from_text(help_text, title);
 
-        Self { editor }
+        let recent = projects
+            .into_iter()
+            .map(|path| RecentEntry::new(path, true))
+            .chain(files.into_iter().map(|path| RecentEntry::new(path, false)))
+            .collect();
+
+        let mut panel = Self {
+            editor,
+            recent,
+            query: String::new(),
+            filtered: Vec::new(),
+            cursor: 0,
+            scroll_offset: 0,
+            theme: Theme::default(),
+            last_rows: Vec::new(),
+            last_editor_area: None,
+        };
+        panel.update_filter();
+        panel
+    }
+
+    /// Rebuild the filtered list for the current query.
+    ///
+    /// Projects stay above files; within each section the best match comes
+    /// first and ties keep the most recent first.
+    fn update_filter(&mut self) {
+        let mut matches: Vec<(bool, i32, FilteredEntry)> = self
+            .recent
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                fuzzy_match(&self.query, &entry.label).map(|found| {
+                    (
+                        entry.is_dir,
+                        found.score,
+                        FilteredEntry {
+                            index,
+                            positions: found.positions,
+                        },
+                    )
+                })
+            })
+            .collect();
+        matches.sort_by_key(|(is_dir, score, _)| (!is_dir, std::cmp::Reverse(*score)));
+
+        self.filtered = matches.into_iter().map(|(_, _, entry)| entry).collect();
+        self.cursor = 0;
+        self.scroll_offset = 0;
+        if !self.is_openable(0) {
+            self.move_cursor(1);
+        }
+    }
+
+    /// Check if the filtered entry at `pos` can be opened
+    fn is_openable(&self, pos: usize) -> bool {
+        self.filtered
+            .get(pos)
+            .is_some_and(|entry| self.recent[entry.index].exists)
+    }
+
+    /// Move the cursor by `delta` entries, skipping deleted ones
+    fn move_cursor(&mut self, delta: isize) {
+        let mut pos = self.cursor;
+        for _ in 0..delta.unsigned_abs() {
+            let next = (1..=self.filtered.len()).find_map(|step| {
+                let candidate = if delta < 0 {
+                    pos.checked_sub(step)?
+                } else {
+                    pos + step
+                };
+                self.is_openable(candidate).then_some(candidate)
+            });
+            match next {
+                Some(next) => pos = next,
+                None => break,
+            }
+        }
+        self.cursor = pos;
+    }
+
+    /// Selected entry that can be opened
+    fn selected(&self) -> Option<&RecentEntry> {
+        if !self.is_openable(self.cursor) {
+            return None;
+        }
+        Some(&self.recent[self.filtered[self.cursor].index])
+    }
+
+    /// Event opening the selected entry
+    fn open_selected(&self) -> Vec<PanelEvent> {
+        match self.selected() {
+            Some(entry) if entry.is_dir => vec![PanelEvent::OpenDirectory(entry.path.clone())],
+            Some(entry) => vec![PanelEvent::open_file(entry.path.clone())],
+            None => vec![],
+        }
+    }
+
+    /// Rows of the recent list: section headers and filtered entries
+    fn rows(&self) -> Vec<RecentRow> {
+        let t = termide_i18n::t();
+        let mut rows = Vec::new();
+        let mut section = None;
+        for (pos, filtered) in self.filtered.iter().enumerate() {
+            let is_dir = self.recent[filtered.index].is_dir;
+            if section != Some(is_dir) {
+                section = Some(is_dir);
+                let title = if is_dir {
+                    t.welcome_recent_projects()
+                } else {
+                    t.welcome_recent_files()
+                };
+                rows.push(RecentRow::Header(title.to_string()));
+            }
+            rows.push(RecentRow::Entry(pos));
+        }
+        rows
+    }
+
+    /// Render the query line and the recent list into `area`
+    fn render_recent(&mut self, area: Rect, rows: &[RecentRow], buf: &mut Buffer) {
+        let theme = self.theme;
+        let query_line = if self.query.is_empty() {
+            Line::from(Span::styled(
+                termide_i18n::t().welcome_recent_hint(),
+                Style::default().fg(theme.disabled),
+            ))
+        } else {
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(theme.disabled)),
+                Span::styled(self.query.clone(), Style::default().fg(theme.fg)),
+                Span::styled("█", Style::default().fg(theme.success)),
+            ])
+        };
+        Paragraph::new(query_line).render(Rect { height: 1, ..area }, buf);
+
+        // Keep the selected entry visible
+        let list_height = area.height.saturating_sub(1) as usize;
+        let cursor_row = rows
+            .iter()
+            .position(|row| matches!(row, RecentRow::Entry(pos) if *pos == self.cursor))
+            .unwrap_or(0);
+        if cursor_row < self.scroll_offset {
+            // Show the section header above the first entry
+            self.scroll_offset = cursor_row.saturating_sub(1);
+        } else if cursor_row >= self.scroll_offset + list_height {
+            self.scroll_offset = cursor_row + 1 - list_height;
+        }
+
+        self.last_rows.clear();
+        for (row_idx, row) in rows
+            .iter()
+            .skip(self.scroll_offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let row_area = Rect {
+                y: area.y + 1 + row_idx as u16,
+                height: 1,
+                ..area
+            };
+            let line = match row {
+                RecentRow::Header(title) => Line::from(Span::styled(
+                    title.clone(),
+                    Style::default()
+                        .fg(theme.accented_fg)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                RecentRow::Entry(pos) => {
+                    self.last_rows.push((row_area, *pos));
+                    self.entry_line(*pos, row_area.width as usize)
+                }
+            };
+            Paragraph::new(line).render(row_area, buf);
+        }
+    }
+
+    /// Styled line of the filtered entry at `pos`
+    fn entry_line(&self, pos: usize, width: usize) -> Line<'static> {
+        let theme = self.theme;
+        let filtered = &self.filtered[pos];
+        let entry = &self.recent[filtered.index];
+        let style = if pos == self.cursor && entry.exists {
+            Style::default()
+                .fg(theme.selected_fg)
+                .bg(theme.selected_bg)
+                .add_modifier(Modifier::BOLD)
+        } else if entry.exists {
+            Style::default().fg(theme.fg)
+        } else {
+            Style::default()
+                .fg(theme.disabled)
+                .add_modifier(Modifier::CROSSED_OUT)
+        };
+        let matched_style = style.add_modifier(Modifier::UNDERLINED);
+
+        let mut spans = vec![Span::styled("  ", style)];
+        spans.extend(entry.label.chars().enumerate().map(|(idx, ch)| {
+            let style = if filtered.positions.contains(&idx) {
+                matched_style
+            } else {
+                style
+            };
+            Span::styled(ch.to_string(), style)
+        }));
+        let used = 2 + entry.label.chars().count();
+        spans.push(Span::styled(" ".repeat(width.saturating_sub(used)), style));
+        Line::from(spans)
+    }
+}
+
+/// Path with the home directory shown as `~`
+fn display_path(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(relative) if relative.as_os_str().is_empty() => "~".to_string(),
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
     }
 }
 
@@ -47,31 +339,104 @@ impl Panel for WelcomePanel {
     }
 
     fn prepare_render(&mut self, theme: &Theme, config: &Config) {
+        self.theme = *theme;
         self.editor.prepare_render(theme, config);
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer, ctx: &RenderContext) {
-        // Delegate rendering to the embedded editor
-        self.editor.render(area, buf, ctx);
+        let rows = self.rows();
+        self.last_rows.clear();
+        if self.recent.is_empty() || area.height < 4 {
+            self.last_editor_area = Some(area);
+            self.editor.render(area, buf, ctx);
+            return;
+        }
+
+        // Query line, list rows and a blank line, at most half of the panel
+        let list_height = rows.len().clamp(1, MAX_RECENT_ROWS) as u16;
+        let recent_height = (1 + list_height).min(area.height / 2);
+        let recent_area = Rect {
+            height: recent_height,
+            ..area
+        };
+        let editor_area = Rect {
+            y: area.y + recent_height + 1,
+            height: area.height.saturating_sub(recent_height + 1),
+            ..area
+        };
+
+        self.render_recent(recent_area, &rows, buf);
+        // Delegate rendering of the help to the embedded editor
+        self.last_editor_area = Some(editor_area);
+        self.editor.render(editor_area, buf, ctx);
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
-        // Delegate key handling to the embedded editor
-        self.editor.handle_key(key)
+        if self.recent.is_empty() {
+            // Delegate key handling to the embedded editor
+            return self.editor.handle_key(key);
+        }
+
+        match key.code {
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Enter => return self.open_selected(),
+            KeyCode::Esc if !self.query.is_empty() => {
+                self.query.clear();
+                self.update_filter();
+            }
+            KeyCode::Backspace => {
+                if self.query.pop().is_some() {
+                    self.update_filter();
+                }
+            }
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.query.push(ch);
+                self.update_filter();
+            }
+            // Scrolling and other keys go to the help text
+            _ => return self.editor.handle_key(key),
+        }
+        vec![]
     }
 
-    fn handle_mouse(
-        &mut self,
-        mouse: crossterm::event::MouseEvent,
-        panel_area: Rect,
-    ) -> Vec<PanelEvent> {
+    fn handle_mouse(&mut self, mouse: MouseEvent, panel_area: Rect) -> Vec<PanelEvent> {
+        use crossterm::event::{MouseButton, MouseEventKind};
+
+        let clicked = self.last_rows.iter().find_map(|(area, pos)| {
+            let inside =
+                mouse.row == area.y && mouse.column >= area.x && mouse.column < area.x + area.width;
+            inside.then_some(*pos)
+        });
+        if let Some(pos) = clicked {
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left) && self.is_openable(pos) {
+                self.cursor = pos;
+                return self.open_selected();
+            }
+            return vec![];
+        }
+
+        // The editor expects the panel area with its border around the text
+        let editor_area = match self.last_editor_area {
+            Some(area) => Rect {
+                x: area.x.saturating_sub(1),
+                y: area.y.saturating_sub(1),
+                width: area.width + 2,
+                height: area.height + 2,
+            },
+            None => panel_area,
+        };
         // Delegate mouse handling to the embedded editor
-        self.editor.handle_mouse(mouse, panel_area)
+        self.editor.handle_mouse(mouse, editor_area)
     }
 
     fn captures_escape(&self) -> bool {
-        // Welcome panel doesn't need to capture Escape
-        false
+        // Escape clears the filter of the recent list
+        !self.query.is_empty()
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -92,3 +457,78 @@ impl Default for WelcomePanel {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn opened(events: Vec<PanelEvent>) -> Option<PathBuf> {
+        match events.as_slice() {
+            [PanelEvent::OpenFile { path, .. }] | [PanelEvent::OpenDirectory(path)] => {
+                Some(path.clone())
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_filter_and_open() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        let main_rs = dir.path().join("main.rs");
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&main_rs, "").unwrap();
+        std::fs::write(&notes, "").unwrap();
+
+        let mut panel = WelcomePanel::with_recent(
+            vec![notes.clone(), main_rs.clone()],
+            vec![dir.path().to_path_buf()],
+        );
+
+        // Projects are listed first
+        assert!(matches!(
+            panel.handle_key(key(KeyCode::Enter)).as_slice(),
+            [PanelEvent::OpenDirectory(_)]
+        ));
+        panel.handle_key(key(KeyCode::Down));
+        assert_eq!(opened(panel.handle_key(key(KeyCode::Enter))), Some(notes));
+
+        for ch in "mainrs".chars() {
+            panel.handle_key(key(KeyCode::Char(ch)));
+        }
+        assert_eq!(opened(panel.handle_key(key(KeyCode::Enter))), Some(main_rs));
+
+        // Escape clears the query before it closes anything
+        assert!(panel.captures_escape());
+        panel.handle_key(key(KeyCode::Esc));
+        assert!(!panel.captures_escape());
+        assert_eq!(panel.filtered.len(), 3);
+    }
+
+    #[test]
+    fn test_deleted_entries_are_skipped() {
+        termide_i18n::init_with_language("en");
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.txt");
+        std::fs::write(&kept, "").unwrap();
+
+        let mut panel = WelcomePanel::with_recent(
+            vec![dir.path().join("deleted.txt"), kept.clone()],
+            Vec::new(),
+        );
+
+        // The cursor starts on the first entry that still exists
+        assert_eq!(opened(panel.handle_key(key(KeyCode::Enter))), Some(kept));
+        panel.handle_key(key(KeyCode::Up));
+        assert_eq!(panel.cursor, 1);
+
+        for ch in "deleted".chars() {
+            panel.handle_key(key(KeyCode::Char(ch)));
+        }
+        assert!(panel.handle_key(key(KeyCode::Enter)).is_empty());
+    }
+}
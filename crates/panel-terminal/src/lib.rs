@@ -5,7 +5,7 @@ mod terminal;
 mod terminal_info;
 
 pub use terminal::vt100_parser::VtPerformer;
-pub use terminal::{Cell, CellStyle, MouseTrackingMode, TerminalScreen};
+pub use terminal::{Cell, CellStyle, MouseTrackingMode, TerminalScreen, UnderlineStyle};
 pub use terminal_info::TerminalInfo;
 
 use anyhow::Result;
@@ -24,23 +24,34 @@ use ratatui::{
 use std::any::Any;
 use std::io::{Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
 use vte::Parser;
 
-use termide_config::Config;
-use termide_core::{CommandResult, Panel, PanelCommand, PanelEvent, RenderContext, SessionPanel};
+use termide_config::{Config, TerminalExit, TerminalSettings};
+use termide_core::{
+    CommandResult, MenuItem, Panel, PanelCommand, PanelEvent, RenderContext, SessionPanel,
+    StatusKind, StatusSegment,
+};
 use termide_theme::Theme;
-use termide_ui::system_monitor::DiskSpaceInfo;
+use termide_ui::system_monitor::{DiskSpaceInfo, DiskSpaceInfoExt};
 
 /// Full-featured terminal with PTY
 pub struct Terminal {
     /// PTY master (wrapped in Arc<Mutex<>> for shared access)
     pty: Arc<Mutex<Box<dyn MasterPty + Send>>>,
-    /// Writer for writing to PTY
-    writer: Box<dyn Write + Send>,
+    /// Input for the PTY, written by a separate thread so a process that
+    /// stops reading cannot block the UI
+    input: Sender<Vec<u8>>,
     /// Shell process
     child: Box<dyn Child + Send>,
+    /// Program and arguments run instead of the shell
+    command: Option<Vec<String>>,
+    /// What happens to the panel when the process exits
+    on_exit: TerminalExit,
+    /// Exit code, once the process exited and was waited for
+    exit_code: Option<u32>,
     /// Shell process PID
     shell_pid: Option<u32>,
     /// Virtual terminal screen (RwLock allows concurrent reads during render)
@@ -51,6 +62,8 @@ pub struct Terminal {
     is_alive: Arc<Mutex<bool>>,
     /// Terminal title (user@host:dir)
     terminal_title: String,
+    /// Prefer the title set by the running program
+    program_title: bool,
     /// Initial working directory (set when terminal was created)
     initial_cwd: std::path::PathBuf,
     /// Cached theme for rendering
@@ -66,17 +79,56 @@ pub struct Terminal {
     cached_cursor_shown: bool,
     /// Last focus state (for cache invalidation)
     cached_focus: bool,
+    /// Selection drawn in the cached lines
+    cached_selection: Option<((usize, usize), (usize, usize))>,
+    /// Line shown in the first row of the cached lines
+    cached_top_line: usize,
+    /// History view offset of the cached lines
+    cached_scroll_offset: usize,
+    /// Rows rebuilt by `get_display_lines`
+    #[cfg(test)]
+    rebuilt_rows: usize,
+    /// Keys typed into the focused terminal are sent to all terminals
+    broadcast: bool,
 }
 
 impl Terminal {
     /// Create new terminal with PTY
     #[allow(dead_code)]
     pub fn new(rows: u16, cols: u16) -> Result<Self> {
-        Self::new_with_cwd(rows, cols, None)
+        Self::new_with_cwd(rows, cols, None, &TerminalSettings::default())
+    }
+
+    /// Create new terminal running the shell in the specified working directory
+    pub fn new_with_cwd(
+        rows: u16,
+        cols: u16,
+        cwd: Option<std::path::PathBuf>,
+        settings: &TerminalSettings,
+    ) -> Result<Self> {
+        Self::spawn(rows, cols, cwd, settings, None)
     }
 
-    /// Create new terminal with specified working directory
-    pub fn new_with_cwd(rows: u16, cols: u16, cwd: Option<std::path::PathBuf>) -> Result<Self> {
+    /// Create new terminal running `command` (program and arguments) instead
+    /// of the shell
+    pub fn new_with_command(
+        rows: u16,
+        cols: u16,
+        cwd: Option<std::path::PathBuf>,
+        settings: &TerminalSettings,
+        command: Vec<String>,
+    ) -> Result<Self> {
+        Self::spawn(rows, cols, cwd, settings, Some(command))
+    }
+
+    /// Start the shell or command on a new PTY
+    fn spawn(
+        rows: u16,
+        cols: u16,
+        cwd: Option<std::path::PathBuf>,
+        settings: &TerminalSettings,
+        command: Option<Vec<String>>,
+    ) -> Result<Self> {
         let pty_system = native_pty_system();
 
         let size = PtySize {
@@ -88,16 +140,28 @@ impl Terminal {
 
         let pair = pty_system.openpty(size)?;
 
-        // Detect shell
-        let shell = Self::detect_shell();
-        let shell_args = Self::get_shell_args(&shell);
-
-        let mut cmd = CommandBuilder::new(&shell);
-
-        // Add arguments for interactive mode
-        for arg in shell_args {
-            cmd.arg(arg);
-        }
+        // Shell from the config, or detected
+        let shell = settings.shell.clone().unwrap_or_else(Self::detect_shell);
+
+        let mut cmd = match &command {
+            Some(command) => {
+                let (program, args) = command
+                    .split_first()
+                    .ok_or_else(|| anyhow::anyhow!("Empty terminal command"))?;
+                let mut cmd = CommandBuilder::new(program);
+                cmd.args(args);
+                cmd
+            }
+            None => {
+                let mut cmd = CommandBuilder::new(&shell);
+                // Add arguments for interactive mode
+                match &settings.shell_args {
+                    Some(args) => cmd.args(args),
+                    None => cmd.args(Self::get_shell_args(&shell)),
+                }
+                cmd
+            }
+        };
 
         // Set working directory: passed or current
         let working_dir =
@@ -129,6 +193,9 @@ impl Terminal {
             std::env::var("PATH")
                 .unwrap_or_else(|_| "/run/current-system/sw/bin:/usr/bin:/bin".to_string()),
         );
+        for (name, value) in &settings.env {
+            cmd.env(name, value);
+        }
 
         let child = pair.slave.spawn_command(cmd)?;
         let shell_pid = child.process_id();
@@ -140,7 +207,7 @@ impl Terminal {
 
         // Create reader and writer BEFORE placing PTY in Arc<Mutex>
         let mut reader = pair.master.try_clone_reader()?;
-        let writer = pair.master.take_writer()?;
+        let mut writer = pair.master.take_writer()?;
 
         let pty = Arc::new(Mutex::new(pair.master));
         let is_alive = Arc::new(Mutex::new(true));
@@ -172,6 +239,7 @@ impl Terminal {
                         performer.flush();
                         // Signal main thread that new data is available for rendering
                         has_new_data_clone.store(true, Ordering::Release);
+                        termide_wake::wake();
                     }
                     Ok(_) => {
                         // EOF - shell terminated
@@ -188,6 +256,21 @@ impl Terminal {
             if let Ok(mut alive) = is_alive_clone.lock() {
                 *alive = false;
             }
+            termide_wake::wake();
+        });
+
+        // Start thread for writing to PTY
+        let (input, input_receiver) = mpsc::channel::<Vec<u8>>();
+        thread::spawn(move || {
+            for data in input_receiver {
+                if writer
+                    .write_all(&data)
+                    .and_then(|_| writer.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
         });
 
         // Get information for terminal title
@@ -200,17 +283,31 @@ impl Terminal {
             .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
             .unwrap_or_else(|| "~".to_string());
 
-        let terminal_title = format!("{}@{}:{}", username, hostname, current_dir);
+        let terminal_title = match &command {
+            Some(command) => command.join(" "),
+            None => format!("{}@{}:{}", username, hostname, current_dir),
+        };
+
+        // Commands keep their output unless told to close like a shell
+        let on_exit = match command {
+            Some(_) if settings.close_on_exit => TerminalExit::Close,
+            Some(_) => TerminalExit::Keep,
+            None => settings.on_exit,
+        };
 
         Ok(Self {
             pty,
-            writer,
+            input,
             child,
+            command,
+            on_exit,
+            exit_code: None,
             shell_pid,
             screen,
             size,
             is_alive,
             terminal_title,
+            program_title: settings.program_title,
             initial_cwd: working_dir,
             cached_theme: Theme::default(),
             has_new_data,
@@ -218,6 +315,12 @@ impl Terminal {
             cached_cursor: (0, 0),
             cached_cursor_shown: false,
             cached_focus: false,
+            cached_selection: None,
+            cached_top_line: 0,
+            cached_scroll_offset: 0,
+            #[cfg(test)]
+            rebuilt_rows: 0,
+            broadcast: false,
         })
     }
 
@@ -289,10 +392,7 @@ impl Terminal {
 
             // If size changed, resize in-place
             if screen.rows != new_rows || screen.cols != new_cols {
-                let empty_cell = Cell {
-                    ch: ' ',
-                    style: CellStyle::default(),
-                };
+                let empty_cell = Cell::blank(CellStyle::default());
 
                 // Adjust row count
                 while screen.lines.len() > new_rows {
@@ -314,8 +414,8 @@ impl Terminal {
                 screen.cursor.0 = screen.cursor.0.min(new_rows.saturating_sub(1));
                 screen.cursor.1 = screen.cursor.1.min(new_cols.saturating_sub(1));
 
-                // Mark dirty to force re-render
-                screen.dirty = true;
+                // Mark every row dirty to force re-render
+                screen.mark_all_dirty();
             }
         }
 
@@ -330,6 +430,11 @@ impl Terminal {
         self.is_alive.lock().map(|alive| *alive).unwrap_or(false)
     }
 
+    /// Process ID of the shell
+    pub fn shell_pid(&self) -> Option<u32> {
+        self.shell_pid
+    }
+
     /// Get terminal info for status bar
     pub fn get_terminal_info(&self) -> TerminalInfo {
         // Get user@host
@@ -371,6 +476,22 @@ impl Terminal {
         }
     }
 
+    /// Status bar segments: user@host, working directory and disk space
+    pub fn status_bar_segments(&self) -> Vec<StatusSegment> {
+        let info = self.get_terminal_info();
+        let mut segments = vec![
+            StatusSegment::new(StatusKind::UserHost, info.user_host),
+            StatusSegment::new(StatusKind::Cwd, info.cwd),
+        ];
+        if let Some(disk) = info.disk_space {
+            segments.push(
+                StatusSegment::new(StatusKind::Disk, disk.format_space())
+                    .with_usage(disk.usage_percent()),
+            );
+        }
+        segments
+    }
+
     /// Resolve dm-X device to physical partition
     /// e.g., /dev/dm-0 -> /dev/nvme0n1p2
     fn resolve_dm_device(device: &str) -> Option<String> {
@@ -481,63 +602,21 @@ impl Terminal {
 
     /// Send input to PTY
     fn send_input(&mut self, data: &[u8]) -> Result<()> {
-        self.writer.write_all(data)?;
-        self.writer.flush()?;
+        self.input.send(data.to_vec())?;
         Ok(())
     }
 
+    /// Show whether keys are broadcast to all terminals (in the title)
+    pub fn set_broadcast(&mut self, broadcast: bool) {
+        self.broadcast = broadcast;
+    }
+
     /// Get selected text
     fn get_selected_text(&self) -> String {
-        let screen = self.screen.read().expect("Terminal screen lock poisoned");
-        let (start, end) = match (screen.selection_start, screen.selection_end) {
-            (Some(s), Some(e)) => (s, e),
-            _ => return String::new(),
-        };
-
-        // Normalize
-        let (start, end) = if start <= end {
-            (start, end)
-        } else {
-            (end, start)
-        };
-
-        let buffer = screen.active_buffer();
-        let mut result = String::new();
-
-        for row_idx in start.0..=end.0 {
-            if row_idx >= buffer.len() {
-                break;
-            }
-
-            let row = &buffer[row_idx];
-            let col_start = if row_idx == start.0 { start.1 } else { 0 };
-            let col_end = if row_idx == end.0 {
-                end.1.min(row.len().saturating_sub(1))
-            } else {
-                row.len().saturating_sub(1)
-            };
-
-            for col_idx in col_start..=col_end {
-                if col_idx < row.len() {
-                    let ch = row[col_idx].ch;
-                    if ch != '\0' {
-                        result.push(ch);
-                    }
-                }
-            }
-
-            // Add line break between lines (but not at the end)
-            if row_idx < end.0 {
-                result.push('\n');
-            }
-        }
-
-        // Trim trailing whitespace from each line
-        result
-            .lines()
-            .map(|line| line.trim_end())
-            .collect::<Vec<_>>()
-            .join("\n")
+        self.screen
+            .read()
+            .expect("Terminal screen lock poisoned")
+            .selected_text()
     }
 
     /// Copy selected text to clipboard
@@ -559,7 +638,22 @@ impl Terminal {
         let Some(text) = termide_ui::clipboard::paste() else {
             return Ok(());
         };
+        self.paste_text(&text)
+    }
 
+    /// Check if a middle click pastes instead of being sent to the program
+    fn pastes_on_middle_click(&self, mouse: &crossterm::event::MouseEvent) -> bool {
+        mouse.modifiers.contains(KeyModifiers::SHIFT)
+            || self
+                .screen
+                .read()
+                .expect("Terminal screen lock poisoned")
+                .mouse_tracking
+                == MouseTrackingMode::None
+    }
+
+    /// Paste text to PTY, bracketed if the program enabled bracketed paste
+    fn paste_text(&mut self, text: &str) -> Result<()> {
         // Check if bracketed paste mode is enabled
         let bracketed_paste = self
             .screen
@@ -590,6 +684,12 @@ impl Terminal {
     ) -> Result<()> {
         use crossterm::event::{MouseButton, MouseEventKind};
 
+        // Nobody reads the input once the process exited (selection and
+        // scrolling still work)
+        if !self.is_alive() {
+            return Ok(());
+        }
+
         let (mouse_tracking, sgr_mode) = {
             let screen = self.screen.read().expect("Terminal screen lock poisoned");
             (screen.mouse_tracking, screen.sgr_mouse_mode)
@@ -681,8 +781,11 @@ impl Terminal {
 
     /// Get lines for display using copy-out pattern with caching.
     ///
-    /// Optimization: Uses dirty flag to skip re-rendering when terminal
-    /// content hasn't changed. Returns Arc-wrapped lines for O(1) cache hits.
+    /// Only rows changed since the previous frame are rebuilt: the screen
+    /// tracks the rows its output touched, and rows where the selection or
+    /// the cursor changed are added here; the other rows reuse the previous
+    /// frame's lines. Viewing history and a size or theme change rebuild
+    /// every row. Returns Arc-wrapped lines for O(1) cache hits.
     ///
     /// Returns: (lines_arc, cursor_position, cursor_shown)
     fn get_display_lines(
@@ -691,17 +794,19 @@ impl Terminal {
         theme: &Theme,
     ) -> (Arc<Vec<Line<'static>>>, (usize, usize), bool) {
         // === PHASE 0: Check if we can return cached result ===
-        let (is_dirty, has_selection) = {
+        let unchanged = {
             let screen = self.screen.read().expect("Terminal screen lock poisoned");
-            (screen.dirty, screen.selection_start.is_some())
+            !screen.dirty
+                && screen.scroll_offset == self.cached_scroll_offset
+                && screen.selection_bounds() == self.cached_selection
         };
 
         // Return cached if:
         // - Screen is not dirty (no new PTY output)
         // - Focus state hasn't changed (cursor visibility depends on focus)
-        // - No active selection (selection changes without dirty flag)
+        // - Neither the selection nor the history view moved
         // - We have cached lines
-        if !is_dirty && self.cached_focus == show_cursor && !has_selection {
+        if unchanged && self.cached_focus == show_cursor {
             if let Some(ref cached) = self.cached_lines {
                 // O(1) Arc clone - no data copying!
                 return (
@@ -712,223 +817,137 @@ impl Terminal {
             }
         }
 
-        // === PHASE 1: Quick data copy under lock ===
+        // === PHASE 1: Quick copy of the rows to rebuild under lock ===
         let (
-            visible_buffer,
-            scrollback_slice,
+            changed_rows,
+            scrolled,
+            full_rebuild,
             cursor_pos,
             visible_rows,
-            cols,
-            cursor_visible,
-            has_selection,
-            selection_start,
-            selection_end,
+            scroll_offset,
+            show_cursor_now,
+            selection,
+            top_line,
         ) = {
             let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
-            // Clear dirty flag since we're about to render
-            screen.dirty = false;
+            let (mut dirty_rows, scrolled) = screen.take_dirty_rows();
 
             let visible_rows = screen.rows;
             let scroll_offset = screen.scroll_offset;
-            let use_alt_screen = screen.use_alt_screen;
+            let viewing_history = scroll_offset > 0 && !screen.use_alt_screen;
+            let cursor_pos = screen.cursor;
+            let selection = screen.selection_bounds();
+            let top_line = screen.line_at(0);
+            // Don't show cursor when viewing history
+            let show_cursor_now = !viewing_history && show_cursor && screen.cursor_visible;
+
+            let full_rebuild = viewing_history
+                || scroll_offset != self.cached_scroll_offset
+                || self
+                    .cached_lines
+                    .as_ref()
+                    .is_none_or(|lines| lines.len() != visible_rows);
+
+            if full_rebuild {
+                dirty_rows = vec![true; visible_rows];
+            } else {
+                // The cursor is drawn into its row: redraw the row it was
+                // drawn in (moved up by scrolling) and the row it is on now
+                let drawn_before = if self.cached_cursor_shown {
+                    let (row, col) = self.cached_cursor;
+                    row.checked_sub(scrolled).map(|row| (row, col))
+                } else {
+                    None
+                };
+                let drawn_now = show_cursor_now.then_some(cursor_pos);
+                if drawn_before != drawn_now {
+                    for (row, _) in [drawn_before, drawn_now].into_iter().flatten() {
+                        if let Some(dirty) = dirty_rows.get_mut(row) {
+                            *dirty = true;
+                        }
+                    }
+                }
+
+                // Rows whose selected columns differ from the ones drawn
+                for (row, dirty) in dirty_rows.iter_mut().enumerate() {
+                    if selected_columns(
+                        self.cached_selection,
+                        self.cached_top_line + row + scrolled,
+                    ) != selected_columns(selection, top_line + row)
+                    {
+                        *dirty = true;
+                    }
+                }
+            }
 
-            // Determine what to copy based on scroll state
-            let (visible_buffer, scrollback_slice) = if scroll_offset > 0 && !use_alt_screen {
-                // Viewing history - need both scrollback and buffer data
+            // Copy only the rows to rebuild
+            let changed_rows: Vec<(usize, Vec<Cell>)> = if viewing_history {
+                // Viewing history - rows come from both scrollback and buffer
                 let total_scrollback = screen.scrollback.len();
                 let total_lines = total_scrollback + visible_rows;
                 let view_end = total_lines.saturating_sub(scroll_offset);
                 let view_start = view_end.saturating_sub(visible_rows);
 
-                // Copy only the visible portion
-                let mut combined: Vec<Vec<Cell>> = Vec::with_capacity(visible_rows);
-                for i in 0..visible_rows {
-                    let source_idx = view_start + i;
-                    if source_idx < total_scrollback {
-                        combined.push(screen.scrollback[source_idx].clone());
-                    } else {
-                        let buf_idx = source_idx - total_scrollback;
-                        if buf_idx < screen.active_buffer().len() {
-                            combined.push(screen.active_buffer()[buf_idx].clone());
-                        }
-                    }
-                }
-                (combined, true)
+                (0..visible_rows)
+                    .filter_map(|i| {
+                        let source_idx = view_start + i;
+                        let row = if source_idx < total_scrollback {
+                            screen.scrollback.get(source_idx)
+                        } else {
+                            screen.active_buffer().get(source_idx - total_scrollback)
+                        };
+                        row.map(|row| (i, row.clone()))
+                    })
+                    .collect()
             } else {
-                // Normal view - copy active buffer
-                (screen.active_buffer().iter().cloned().collect(), false)
+                let buffer = screen.active_buffer();
+                dirty_rows
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, dirty)| **dirty)
+                    .filter_map(|(i, _)| buffer.get(i).map(|row| (i, row.clone())))
+                    .collect()
             };
 
             (
-                visible_buffer,
-                scrollback_slice,
-                screen.cursor,
+                changed_rows,
+                scrolled,
+                full_rebuild,
+                cursor_pos,
                 visible_rows,
-                screen.cols,
-                screen.cursor_visible,
-                screen.selection_start.is_some() && screen.selection_end.is_some(),
-                screen.selection_start,
-                screen.selection_end,
+                scroll_offset,
+                show_cursor_now,
+                selection,
+                top_line,
             )
         };
         // Lock released here - PTY writer can proceed
 
-        // === PHASE 2: Expensive rendering without lock ===
-        let mut lines = Vec::with_capacity(visible_rows);
-        let mut current_text = String::with_capacity(cols);
-
-        // Don't show cursor when viewing history
-        let show_cursor_now = if scrollback_slice {
-            false
-        } else {
-            show_cursor && cursor_visible
-        };
-
-        // Pre-compute selection bounds if selection exists
-        let selection_bounds = if has_selection {
-            let (start, end) = (selection_start.unwrap(), selection_end.unwrap());
-            let (start, end) = if start <= end {
-                (start, end)
-            } else {
-                (end, start)
-            };
-            Some((start, end))
-        } else {
-            None
-        };
-
-        // Helper to check selection (inlined for performance)
-        let is_in_selection = |row: usize, col: usize| -> bool {
-            if let Some((start, end)) = selection_bounds {
-                if row < start.0 || row > end.0 {
-                    return false;
-                }
-                if row == start.0 && row == end.0 {
-                    col >= start.1 && col <= end.1
-                } else if row == start.0 {
-                    col >= start.1
-                } else if row == end.0 {
-                    col <= end.1
-                } else {
-                    true
-                }
-            } else {
-                false
+        // === PHASE 2: Expensive rendering of changed rows without lock ===
+        let mut lines = match self.cached_lines.take() {
+            Some(cached) if !full_rebuild => {
+                // Usually the only reference, so the lines are not copied
+                let mut lines = Arc::try_unwrap(cached).unwrap_or_else(|arc| (*arc).clone());
+                // Rows scrolled off the top are gone, the rest moved up
+                lines.drain(..scrolled.min(lines.len()));
+                lines.resize(visible_rows, Line::default());
+                lines
             }
+            _ => vec![Line::default(); visible_rows],
         };
 
-        for (row_idx, row) in visible_buffer.iter().enumerate() {
-            let mut spans = Vec::with_capacity(8); // Pre-allocate for typical line
-            current_text.clear();
-            // Use direct style value instead of Option for faster comparison
-            let mut current_style = Style::default();
-
-            for (col_idx, cell) in row.iter().enumerate() {
-                // Apply reverse if set
-                let (mut fg, mut bg) = if cell.style.reverse {
-                    (cell.style.bg, cell.style.fg)
-                } else {
-                    (cell.style.fg, cell.style.bg)
-                };
-
-                // Apply theme colors during rendering (not post-processing)
-                if fg == Color::White || fg == Color::Reset {
-                    fg = theme.fg;
-                }
-                if bg == Color::Reset {
-                    bg = theme.bg;
-                }
-
-                let mut style = Style::default().fg(fg).bg(bg);
-
-                if cell.style.bold {
-                    style = style.add_modifier(Modifier::BOLD);
-                }
-                if cell.style.italic {
-                    style = style.add_modifier(Modifier::ITALIC);
-                }
-                if cell.style.underline {
-                    style = style.add_modifier(Modifier::UNDERLINED);
-                }
-                if cell.style.reverse {
-                    style = style.add_modifier(Modifier::REVERSED);
-                }
-
-                // Check if cell is in selection (optimized - skips if no selection)
-                if is_in_selection(row_idx, col_idx) {
-                    style = Style::default().fg(Color::Black).bg(Color::LightYellow);
-                }
-
-                // If this is cursor position and needs showing, use inverse colors
-                if show_cursor_now && row_idx == cursor_pos.0 && col_idx == cursor_pos.1 {
-                    // Flush accumulated text
-                    if !current_text.is_empty() {
-                        spans.push(Span::styled(
-                            std::mem::take(&mut current_text),
-                            current_style,
-                        ));
-                    }
-
-                    // Cursor with inverted colors (use original fg/bg for inversion)
-                    let cursor_style = Style::default()
-                        .bg(
-                            if cell.style.fg == Color::White || cell.style.fg == Color::Reset {
-                                theme.fg
-                            } else {
-                                cell.style.fg
-                            },
-                        )
-                        .fg(if cell.style.bg == Color::Reset {
-                            theme.bg
-                        } else {
-                            cell.style.bg
-                        })
-                        .add_modifier(Modifier::BOLD);
-
-                    let cursor_char = if cell.ch == ' ' || cell.ch == '\0' {
-                        ' '
-                    } else {
-                        cell.ch
-                    };
-                    let mut cursor_buf = [0u8; 4];
-                    let cursor_str = cursor_char.encode_utf8(&mut cursor_buf);
-                    spans.push(Span::styled(cursor_str.to_owned(), cursor_style));
-                    continue;
-                }
-
-                // Group characters with same style (no Option overhead)
-                if current_text.is_empty() || current_style == style {
-                    current_text.push(cell.ch);
-                    current_style = style;
-                } else {
-                    // Flush accumulated text with previous style
-                    spans.push(Span::styled(
-                        std::mem::take(&mut current_text),
-                        current_style,
-                    ));
-                    current_text.push(cell.ch);
-                    current_style = style;
-                }
-            }
-
-            // Add last span
-            if !current_text.is_empty() {
-                spans.push(Span::styled(
-                    std::mem::take(&mut current_text),
-                    current_style,
-                ));
+        for (row_idx, row) in changed_rows {
+            let cursor_col = (show_cursor_now && row_idx == cursor_pos.0).then_some(cursor_pos.1);
+            lines[row_idx] = render_row(
+                &row,
+                selected_columns(selection, top_line + row_idx),
+                cursor_col,
+                theme,
+            );
+            #[cfg(test)]
+            {
+                self.rebuilt_rows += 1;
             }
-
-            // If line is empty and cursor is on it, add cursor
-            if show_cursor_now && spans.is_empty() && row_idx == cursor_pos.0 {
-                let cursor_style = Style::default()
-                    .bg(theme.fg)
-                    .fg(theme.bg)
-                    .add_modifier(Modifier::BOLD);
-                spans.push(Span::styled(" ", cursor_style));
-            }
-
-            lines.push(Line::from(spans));
         }
 
         // === PHASE 3: Cache the result (no clone - just wrap in Arc) ===
@@ -937,110 +956,29 @@ impl Terminal {
         self.cached_cursor = cursor_pos;
         self.cached_cursor_shown = show_cursor_now;
         self.cached_focus = show_cursor;
+        self.cached_selection = selection;
+        self.cached_top_line = top_line;
+        self.cached_scroll_offset = scroll_offset;
 
         (arc_lines, cursor_pos, show_cursor_now)
     }
 
-    /// Check if PTY has new data that needs rendering
-    pub fn has_pending_output(&self) -> bool {
-        self.has_new_data.swap(false, Ordering::AcqRel)
-    }
-}
-
-impl Panel for Terminal {
-    fn name(&self) -> &'static str {
-        "terminal"
-    }
-
-    fn title(&self) -> String {
-        self.terminal_title.clone()
-    }
-
-    fn prepare_render(&mut self, theme: &Theme, _config: &Config) {
-        // Invalidate cache if theme changed
-        if self.cached_theme != *theme {
-            self.cached_lines = None;
-        }
-        self.cached_theme = *theme;
-    }
-
-    fn render(&mut self, area: Rect, buf: &mut Buffer, ctx: &RenderContext) {
-        // Update size if changed
-        // area is already the inner content area (accordion drew outer border)
-        let new_rows = area.height;
-        let new_cols = area.width;
-
-        if new_rows != self.size.rows || new_cols != self.size.cols {
-            let _ = self.resize(new_rows, new_cols);
-        }
-
-        // Data is read in a separate thread, just render current state
-        // Show cursor only when panel is focused
-        // Theme colors are now applied during get_display_lines() - no post-processing needed
-        let theme = self.cached_theme;
-        let (arc_lines, _cursor_pos, _cursor_shown) =
-            self.get_display_lines(ctx.is_focused, &theme);
-
-        // Render terminal content directly (accordion already drew border with title/buttons)
-        // Extract Vec from Arc - this is the only clone point now
-        // On cache hit: Arc clone was O(1), this clone is the only cost
-        // On cache miss: Arc wrap was O(1), this clone is the only cost
-        let lines = Arc::try_unwrap(arc_lines).unwrap_or_else(|arc| (*arc).clone());
-        let paragraph = Paragraph::new(lines);
-        paragraph.render(area, buf);
-    }
-
-    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
-        // If process exited, don't handle input
+    /// Handle a key typed into another terminal while broadcasting. A paste
+    /// is pasted here too, history scrolling stays with the other terminal.
+    pub fn handle_broadcast_key(&mut self, key: KeyEvent) {
         if !self.is_alive() {
-            return vec![];
+            return;
         }
-
-        // Translate Cyrillic to Latin for hotkeys
         let key = termide_keyboard::translate_hotkey(key);
-
-        // Handle paste from clipboard (Ctrl+Shift+V)
-        // When Shift is pressed with a letter, crossterm returns the uppercase character
-        // with only CONTROL in modifiers (Shift is "applied" to the character)
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('V'), modifiers) if modifiers.contains(KeyModifiers::CONTROL) => {
-                let _ = self.paste_from_clipboard();
-                return vec![];
-            }
-            _ => {}
-        }
-
-        // Handle history scrolling (Shift+PageUp/PageDown) - single lock per operation
-        if key.modifiers.contains(KeyModifiers::SHIFT) {
-            match key.code {
-                KeyCode::PageUp => {
-                    let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
-                    let scroll_amount = screen.rows.saturating_sub(1);
-                    screen.scroll_view_up(scroll_amount);
-                    return vec![];
-                }
-                KeyCode::PageDown => {
-                    let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
-                    let scroll_amount = screen.rows.saturating_sub(1);
-                    screen.scroll_view_down(scroll_amount);
-                    return vec![];
-                }
-                KeyCode::Home => {
-                    let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
-                    screen.scroll_offset = screen.scrollback.len();
-                    return vec![];
-                }
-                KeyCode::End => {
-                    self.screen
-                        .write()
-                        .expect("Terminal screen lock poisoned")
-                        .reset_scroll();
-                    return vec![];
-                }
-                _ => {}
-            }
+        if is_paste_key(&key) {
+            let _ = self.paste_from_clipboard();
+        } else if !is_scroll_key(&key) {
+            self.send_key(key);
         }
+    }
 
+    /// Send a key to the PTY as the escape sequence the program expects
+    pub fn send_key(&mut self, key: KeyEvent) {
         // Reset scroll on input and cache application_cursor_keys - single lock
         let application_cursor_keys = {
             let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
@@ -1189,7 +1127,381 @@ impl Panel for Terminal {
             }
             _ => {}
         }
+    }
+
+    /// Check if the panel stays open after the process exited (for
+    /// "keep-on-error" only once the exit code is known)
+    fn keeps_output(&self) -> bool {
+        match self.on_exit {
+            TerminalExit::Close => false,
+            TerminalExit::Keep => true,
+            TerminalExit::KeepOnError => self.exit_code.is_some_and(|code| code != 0),
+        }
+    }
+
+    /// Wait for the exited process and show its exit code below the output
+    /// of a panel that stays open
+    fn check_exit(&mut self) {
+        let Ok(Some(status)) = self.child.try_wait() else {
+            return;
+        };
+        let code = status.exit_code();
+        self.exit_code = Some(code);
+        // The title set by the program no longer applies
+        if let Ok(mut screen) = self.screen.write() {
+            screen.title = None;
+        }
+        if !self.keeps_output() {
+            return;
+        }
 
+        let message = format!(
+            "\r\n[{}]\r\n",
+            termide_i18n::t().terminal_exited(code as i32)
+        );
+        let mut performer = terminal::VtPerformer {
+            screen: Arc::clone(&self.screen),
+            pending_backslash: false,
+            pending_ops: Vec::new(),
+        };
+        let mut parser = Parser::new();
+        for byte in message.as_bytes() {
+            parser.advance(&mut performer, *byte);
+        }
+        performer.flush();
+        self.cached_lines = None;
+        self.has_new_data.store(true, Ordering::Release);
+    }
+
+    /// Check if PTY has new data that needs rendering
+    pub fn has_pending_output(&self) -> bool {
+        self.has_new_data.swap(false, Ordering::AcqRel)
+    }
+}
+
+/// Ctrl+Shift+V pastes from the clipboard.
+///
+/// When Shift is pressed with a letter, crossterm returns the uppercase
+/// character with only CONTROL in modifiers (Shift is "applied" to the
+/// character).
+fn is_paste_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('V') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Shift+PageUp/PageDown/Home/End scroll the history
+fn is_scroll_key(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::SHIFT)
+        && matches!(
+            key.code,
+            KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End
+        )
+}
+
+/// Columns of line `row` covered by `selection` (inclusive)
+fn selected_columns(
+    selection: Option<((usize, usize), (usize, usize))>,
+    row: usize,
+) -> Option<(usize, usize)> {
+    let (start, end) = selection?;
+    if row < start.0 || row > end.0 {
+        return None;
+    }
+    let first = if row == start.0 { start.1 } else { 0 };
+    let last = if row == end.0 { end.1 } else { usize::MAX };
+    Some((first, last))
+}
+
+/// Render one screen row, highlighting the `selected` columns and drawing
+/// the cursor at `cursor_col`
+fn render_row(
+    row: &[Cell],
+    selected: Option<(usize, usize)>,
+    cursor_col: Option<usize>,
+    theme: &Theme,
+) -> Line<'static> {
+    let mut spans = Vec::with_capacity(8); // Pre-allocate for typical line
+    let mut current_text = String::with_capacity(row.len());
+    // Use direct style value instead of Option for faster comparison
+    let mut current_style = Style::default();
+
+    for (col_idx, cell) in row.iter().enumerate() {
+        // Wide characters are drawn once, over both of their cells
+        if cell.is_continuation() && col_idx > 0 && row[col_idx - 1].width == 2 {
+            continue;
+        }
+        let drawn = match cell.width {
+            1 => true,
+            2 => row.get(col_idx + 1).is_some_and(Cell::is_continuation),
+            // Halves of wide characters cut by resizing show as blanks
+            _ => false,
+        };
+        let push_text = |text: &mut String| {
+            if drawn {
+                cell.push_to(text);
+            } else {
+                text.push(' ');
+            }
+        };
+
+        // Apply reverse if set
+        let (mut fg, mut bg) = if cell.style.reverse {
+            (cell.style.bg, cell.style.fg)
+        } else {
+            (cell.style.fg, cell.style.bg)
+        };
+
+        // Apply theme colors during rendering (not post-processing)
+        if fg == Color::White || fg == Color::Reset {
+            fg = theme.fg;
+        }
+        if bg == Color::Reset {
+            bg = theme.bg;
+        }
+
+        let mut style = Style::default().fg(fg).bg(bg);
+
+        if cell.style.bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if cell.style.italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        if cell.style.underline != UnderlineStyle::None {
+            // Double, curly, dotted and dashed underlines are drawn plain
+            style = style.add_modifier(Modifier::UNDERLINED);
+            if let Some(color) = cell.style.underline_color {
+                style = style.underline_color(color);
+            }
+        }
+        if cell.style.strikethrough {
+            style = style.add_modifier(Modifier::CROSSED_OUT);
+        }
+        if cell.style.reverse {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+
+        // Check if cell is in selection
+        if selected.is_some_and(|(first, last)| col_idx >= first && col_idx <= last) {
+            style = Style::default().fg(Color::Black).bg(Color::LightYellow);
+        }
+
+        // If this is cursor position and needs showing, use inverse colors
+        if cursor_col == Some(col_idx) {
+            // Flush accumulated text
+            if !current_text.is_empty() {
+                spans.push(Span::styled(
+                    std::mem::take(&mut current_text),
+                    current_style,
+                ));
+            }
+
+            // Cursor with inverted colors (use original fg/bg for inversion)
+            let cursor_style = Style::default()
+                .bg(
+                    if cell.style.fg == Color::White || cell.style.fg == Color::Reset {
+                        theme.fg
+                    } else {
+                        cell.style.fg
+                    },
+                )
+                .fg(if cell.style.bg == Color::Reset {
+                    theme.bg
+                } else {
+                    cell.style.bg
+                })
+                .add_modifier(Modifier::BOLD);
+
+            let mut cursor_text = String::new();
+            if cell.ch == '\0' {
+                cursor_text.push(' ');
+            } else {
+                push_text(&mut cursor_text);
+            }
+            spans.push(Span::styled(cursor_text, cursor_style));
+            continue;
+        }
+
+        // Group characters with same style (no Option overhead)
+        if current_text.is_empty() || current_style == style {
+            push_text(&mut current_text);
+            current_style = style;
+        } else {
+            // Flush accumulated text with previous style
+            spans.push(Span::styled(
+                std::mem::take(&mut current_text),
+                current_style,
+            ));
+            push_text(&mut current_text);
+            current_style = style;
+        }
+    }
+
+    // Add last span
+    if !current_text.is_empty() {
+        spans.push(Span::styled(
+            std::mem::take(&mut current_text),
+            current_style,
+        ));
+    }
+
+    // If line is empty and cursor is on it, add cursor
+    if cursor_col.is_some() && spans.is_empty() {
+        let cursor_style = Style::default()
+            .bg(theme.fg)
+            .fg(theme.bg)
+            .add_modifier(Modifier::BOLD);
+        spans.push(Span::styled(" ", cursor_style));
+    }
+    Line::from(spans)
+}
+
+/// Keys the terminal keeps from the program, with the `key_help_*` id
+/// describing them.
+const KEY_HELP: &[(&str, &str)] = &[
+    ("Shift+PageUp, Shift+PageDown", "term_scroll"),
+    ("Shift+Home, Shift+End", "term_scroll_ends"),
+    ("Ctrl+Shift+V", "term_paste"),
+];
+
+impl Panel for Terminal {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn menu_items(&self) -> Vec<MenuItem> {
+        let t = termide_i18n::t();
+        let has_scrollback = !self
+            .screen
+            .read()
+            .expect("Terminal screen lock poisoned")
+            .scrollback
+            .is_empty();
+        vec![
+            MenuItem::new("clear_scrollback", t.panel_menu_item("clear_scrollback"))
+                .with_enabled(has_scrollback),
+            MenuItem::new("paste", t.panel_menu_item("paste"))
+                .with_enabled(self.is_alive())
+                .with_key_hint("Ctrl+Shift+V"),
+        ]
+    }
+
+    fn key_help(&self) -> Vec<(String, String)> {
+        let t = termide_i18n::t();
+        KEY_HELP
+            .iter()
+            .map(|(keys, id)| (keys.to_string(), t.key_help(id).to_string()))
+            .collect()
+    }
+
+    fn title(&self) -> String {
+        let t = termide_i18n::t();
+        let program_title = self
+            .program_title
+            .then(|| self.screen.read().ok()?.title.clone())
+            .flatten();
+        let base = program_title.as_deref().unwrap_or(&self.terminal_title);
+        let mut title = if self.broadcast {
+            format!("[{}] {}", t.terminal_broadcast(), base)
+        } else {
+            base.to_string()
+        };
+        // Output kept after the process exited
+        if self.exit_code.is_some() && self.keeps_output() {
+            title = format!("{} [{}]", title, t.terminal_exited_title());
+        }
+        title
+    }
+
+    fn prepare_render(&mut self, theme: &Theme, _config: &Config) {
+        // Invalidate cache if theme changed
+        if self.cached_theme != *theme {
+            self.cached_lines = None;
+        }
+        self.cached_theme = *theme;
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, ctx: &RenderContext) {
+        // Update size if changed
+        // area is already the inner content area (accordion drew outer border)
+        let new_rows = area.height;
+        let new_cols = area.width;
+
+        if new_rows != self.size.rows || new_cols != self.size.cols {
+            let _ = self.resize(new_rows, new_cols);
+        }
+
+        // Data is read in a separate thread, just render current state
+        // Show cursor only when panel is focused
+        // Theme colors are now applied during get_display_lines() - no post-processing needed
+        let theme = self.cached_theme;
+        let (arc_lines, _cursor_pos, _cursor_shown) =
+            self.get_display_lines(ctx.is_focused, &theme);
+
+        // Render terminal content directly (accordion already drew border with title/buttons)
+        // Extract Vec from Arc - this is the only clone point now
+        // On cache hit: Arc clone was O(1), this clone is the only cost
+        // On cache miss: Arc wrap was O(1), this clone is the only cost
+        let lines = Arc::try_unwrap(arc_lines).unwrap_or_else(|arc| (*arc).clone());
+        let paragraph = Paragraph::new(lines);
+        paragraph.render(area, buf);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Vec<PanelEvent> {
+        // Translate Cyrillic to Latin for hotkeys
+        let key = termide_keyboard::translate_hotkey(key);
+
+        // Handle history scrolling (Shift+PageUp/PageDown) - single lock per operation,
+        // also after the process exited
+        if is_scroll_key(&key) {
+            match key.code {
+                KeyCode::PageUp => {
+                    let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
+                    let scroll_amount = screen.rows.saturating_sub(1);
+                    screen.scroll_view_up(scroll_amount);
+                    return vec![];
+                }
+                KeyCode::PageDown => {
+                    let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
+                    let scroll_amount = screen.rows.saturating_sub(1);
+                    screen.scroll_view_down(scroll_amount);
+                    return vec![];
+                }
+                KeyCode::Home => {
+                    let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
+                    screen.scroll_offset = screen.scrollback.len();
+                    return vec![];
+                }
+                KeyCode::End => {
+                    self.screen
+                        .write()
+                        .expect("Terminal screen lock poisoned")
+                        .reset_scroll();
+                    return vec![];
+                }
+                _ => {}
+            }
+        }
+
+        // If process exited, don't handle input
+        if !self.is_alive() {
+            return vec![];
+        }
+
+        // Handle paste from clipboard (Ctrl+Shift+V)
+        if is_paste_key(&key) {
+            let _ = self.paste_from_clipboard();
+            return vec![];
+        }
+
+        self.send_key(key);
+        vec![]
+    }
+
+    fn handle_paste(&mut self, text: &str) -> Vec<PanelEvent> {
+        if self.is_alive() {
+            // Terminals send line breaks of pasted text as carriage returns
+            let _ = self.paste_text(&text.replace('\n', "\r"));
+        }
         vec![]
     }
 
@@ -1200,11 +1512,6 @@ impl Panel for Terminal {
     ) -> Vec<PanelEvent> {
         use crossterm::event::{MouseButton, MouseEventKind};
 
-        // If process exited, don't handle mouse
-        if !self.is_alive() {
-            return vec![];
-        }
-
         // Calculate inner area (without border)
         let inner_x_min = panel_area.x + 1;
         let inner_x_max = panel_area.x + panel_area.width.saturating_sub(2);
@@ -1242,10 +1549,11 @@ impl Panel for Terminal {
                 if !is_inside {
                     return vec![];
                 }
-                // Start text selection
+                // Start text selection, also in the history
                 let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
-                screen.selection_start = Some((inner_row, inner_col));
-                screen.selection_end = Some((inner_row, inner_col)); // Set immediately for visibility
+                let line = screen.line_at(inner_row);
+                screen.selection_start = Some((line, inner_col));
+                screen.selection_end = Some((line, inner_col)); // Set immediately for visibility
                 drop(screen);
 
                 // Also send click to PTY if mouse tracking is enabled
@@ -1255,7 +1563,7 @@ impl Panel for Terminal {
                 // Update selection end (using clamped coordinates)
                 let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
                 if screen.selection_start.is_some() {
-                    screen.selection_end = Some((inner_row, inner_col));
+                    screen.selection_end = Some((screen.line_at(inner_row), inner_col));
                 }
             }
             MouseEventKind::Up(MouseButton::Left) => {
@@ -1263,7 +1571,7 @@ impl Panel for Terminal {
                 {
                     let mut screen = self.screen.write().expect("Terminal screen lock poisoned");
                     if screen.selection_start.is_some() {
-                        screen.selection_end = Some((inner_row, inner_col));
+                        screen.selection_end = Some((screen.line_at(inner_row), inner_col));
                     }
                 }
 
@@ -1297,6 +1605,17 @@ impl Panel for Terminal {
                     .expect("Terminal screen lock poisoned")
                     .scroll_view_down(3);
             }
+            // Middle click pastes the PRIMARY selection unless the program
+            // tracks the mouse (Shift forces the paste)
+            MouseEventKind::Down(MouseButton::Middle) | MouseEventKind::Up(MouseButton::Middle)
+                if self.pastes_on_middle_click(&mouse) =>
+            {
+                if is_inside && self.is_alive() && matches!(mouse.kind, MouseEventKind::Down(_)) {
+                    if let Some(text) = termide_ui::clipboard::paste_primary() {
+                        let _ = self.paste_text(&text);
+                    }
+                }
+            }
             // Other mouse events send to PTY
             _ => {
                 let _ = self.send_mouse_to_pty(&mouse, panel_area);
@@ -1307,8 +1626,25 @@ impl Panel for Terminal {
     }
 
     fn should_auto_close(&self) -> bool {
-        // Automatically close panel if process exited
+        // Close the panel once the process exited unless its output is
+        // kept; "keep-on-error" waits for the exit code
         !self.is_alive()
+            && match self.on_exit {
+                TerminalExit::Close => true,
+                TerminalExit::Keep => false,
+                TerminalExit::KeepOnError => self.exit_code == Some(0),
+            }
+    }
+
+    fn needs_render(&self) -> bool {
+        self.has_pending_output()
+    }
+
+    fn tick(&mut self) -> Vec<PanelEvent> {
+        if self.exit_code.is_none() && !self.is_alive() {
+            self.check_exit();
+        }
+        vec![]
     }
 
     fn handle_command(&mut self, cmd: PanelCommand<'_>) -> CommandResult {
@@ -1320,10 +1656,24 @@ impl Panel for Terminal {
                     CommandResult::NeedsRedraw(false)
                 }
             }
+            PanelCommand::MenuAction { id } => {
+                match id {
+                    "clear_scrollback" => self
+                        .screen
+                        .write()
+                        .expect("Terminal screen lock poisoned")
+                        .clear_scrollback(),
+                    "paste" => {
+                        let _ = self.paste_from_clipboard();
+                    }
+                    _ => return CommandResult::None,
+                }
+                CommandResult::Events(Vec::new())
+            }
             // Commands not applicable to Terminal
             PanelCommand::GetRepoRoot
             | PanelCommand::OnGitUpdate { .. }
-            | PanelCommand::CheckPendingGitDiff
+            | PanelCommand::RunDeferredWork
             | PanelCommand::CheckGitDiffReceiver
             | PanelCommand::CheckExternalModification
             | PanelCommand::GetFsWatchInfo
@@ -1333,7 +1683,8 @@ impl Panel for Terminal {
             | PanelCommand::GetModificationStatus
             | PanelCommand::Save
             | PanelCommand::CloseWithoutSaving
-            | PanelCommand::RefreshDirectory => CommandResult::None,
+            | PanelCommand::RefreshDirectory
+            | PanelCommand::SetOpenFiles { .. } => CommandResult::None,
         }
     }
 
@@ -1355,6 +1706,7 @@ impl Panel for Terminal {
         // Save terminal with initial working directory
         Some(SessionPanel::Terminal {
             working_dir: self.initial_cwd.clone(),
+            command: self.command.clone(),
         })
     }
 
@@ -1370,6 +1722,10 @@ impl Panel for Terminal {
         Some(self.initial_cwd.clone())
     }
 
+    fn status_segments(&self) -> Vec<StatusSegment> {
+        self.status_bar_segments()
+    }
+
     fn has_running_processes(&self) -> bool {
         // Check if shell has child processes
         if let Some(pid) = self.shell_pid {
@@ -1411,3 +1767,196 @@ impl Drop for Terminal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait_for_exit(terminal: &mut Terminal) {
+        let started = Instant::now();
+        while terminal.exit_code.is_none() && started.elapsed() < Duration::from_secs(5) {
+            terminal.tick();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn shell_exiting_with(code: u32, on_exit: TerminalExit) -> Terminal {
+        let settings = TerminalSettings {
+            shell: Some("sh".to_string()),
+            shell_args: Some(vec!["-c".to_string(), format!("exit {}", code)]),
+            on_exit,
+            ..TerminalSettings::default()
+        };
+        let mut terminal = Terminal::new_with_cwd(24, 80, None, &settings).unwrap();
+        wait_for_exit(&mut terminal);
+        terminal
+    }
+
+    #[test]
+    fn test_command_stays_open_with_exit_code() {
+        termide_i18n::init_with_language("en");
+        let command = vec!["sh".to_string(), "-c".to_string(), "exit 3".to_string()];
+        let mut terminal =
+            Terminal::new_with_command(24, 80, None, &TerminalSettings::default(), command)
+                .unwrap();
+
+        wait_for_exit(&mut terminal);
+        assert_eq!(terminal.exit_code, Some(3));
+        assert!(!terminal.should_auto_close());
+        assert_eq!(terminal.title(), "sh -c exit 3 [exited]");
+
+        let screen = terminal.screen.read().unwrap();
+        let text: Vec<String> = screen
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|cell| cell.ch).collect())
+            .collect();
+        assert!(text
+            .iter()
+            .any(|line| line.contains("[Process exited with code 3]")));
+    }
+
+    #[test]
+    fn test_title_set_by_program() {
+        termide_i18n::init_with_language("en");
+        let command = vec!["sleep".to_string(), "5".to_string()];
+        let mut terminal =
+            Terminal::new_with_command(24, 80, None, &TerminalSettings::default(), command)
+                .unwrap();
+        let feed = |terminal: &Terminal, output: &str| {
+            let mut performer = terminal::VtPerformer::new(Arc::clone(&terminal.screen));
+            let mut parser = vte::Parser::new();
+            for byte in output.as_bytes() {
+                parser.advance(&mut performer, *byte);
+            }
+        };
+
+        feed(&terminal, "\x1b]0;vim: main.rs\x07");
+        assert_eq!(terminal.title(), "vim: main.rs");
+        terminal.program_title = false;
+        assert_eq!(terminal.title(), "sleep 5");
+        terminal.program_title = true;
+
+        // Reset by the program
+        feed(&terminal, "\x1b]2;\x07");
+        assert_eq!(terminal.title(), "sleep 5");
+
+        // Dropped when the program exits
+        feed(&terminal, "\x1b]2;ssh host\x07");
+        let _ = terminal.child.kill();
+        wait_for_exit(&mut terminal);
+        assert_eq!(terminal.title(), "sleep 5 [exited]");
+    }
+
+    #[test]
+    fn test_wide_characters_rendered_once() {
+        termide_i18n::init_with_language("en");
+        let command = vec![
+            "printf".to_string(),
+            "a\u{5b57}\u{1f600}b e\u{301}".to_string(),
+        ];
+        let mut terminal =
+            Terminal::new_with_command(24, 80, None, &TerminalSettings::default(), command)
+                .unwrap();
+        wait_for_exit(&mut terminal);
+
+        let (lines, _, _) = terminal.get_display_lines(false, &Theme::default());
+        let text: String = lines[0]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert!(text.starts_with("a\u{5b57}\u{1f600}b e\u{301} "));
+        assert_eq!(lines[0].width(), 80);
+    }
+
+    /// Feed `output` to the screen the way the PTY reader does
+    fn feed(terminal: &Terminal, output: &str) {
+        let mut performer = VtPerformer::new(Arc::clone(&terminal.screen));
+        let mut parser = Parser::new();
+        for byte in output.as_bytes() {
+            parser.advance(&mut performer, *byte);
+        }
+        performer.flush();
+    }
+
+    /// Lines rendered incrementally, checked against a full rebuild
+    fn assert_matches_full_rebuild(terminal: &mut Terminal, theme: &Theme) {
+        let (incremental, _, _) = terminal.get_display_lines(true, theme);
+        terminal.cached_lines = None;
+        let (full, _, _) = terminal.get_display_lines(true, theme);
+        assert_eq!(incremental, full);
+    }
+
+    #[test]
+    fn test_output_burst_rebuilds_written_rows() {
+        termide_i18n::init_with_language("en");
+        let command = vec!["true".to_string()];
+        let mut terminal =
+            Terminal::new_with_command(24, 80, None, &TerminalSettings::default(), command)
+                .unwrap();
+        wait_for_exit(&mut terminal);
+        let theme = Theme::default();
+        terminal.get_display_lines(true, &theme);
+
+        // A progress counter redrawn in place: only its row is rebuilt
+        terminal.rebuilt_rows = 0;
+        let frames = 200;
+        for frame in 0..frames {
+            let output: String = (0..50)
+                .map(|step| format!("\r{:>6}/10000", frame * 50 + step))
+                .collect();
+            feed(&terminal, &output);
+            terminal.get_display_lines(true, &theme);
+        }
+        assert_eq!(terminal.rebuilt_rows, frames);
+        assert_matches_full_rebuild(&mut terminal, &theme);
+
+        // Scrolling output: the written rows plus the row the cursor left
+        terminal.rebuilt_rows = 0;
+        let lines_per_frame = 3;
+        for frame in 0..frames {
+            let output: String = (0..lines_per_frame)
+                .map(|line| format!("\x1b[1mline\x1b[0m {}\r\n", frame * lines_per_frame + line))
+                .collect();
+            feed(&terminal, &output);
+            terminal.get_display_lines(true, &theme);
+        }
+        assert!(
+            terminal.rebuilt_rows <= frames * (lines_per_frame + 1),
+            "{} rows rebuilt for {} written",
+            terminal.rebuilt_rows,
+            frames * lines_per_frame
+        );
+        assert_matches_full_rebuild(&mut terminal, &theme);
+
+        // Selecting rebuilds the selected rows only
+        terminal.rebuilt_rows = 0;
+        {
+            let mut screen = terminal.screen.write().unwrap();
+            let top = screen.line_at(0);
+            screen.selection_start = Some((top + 5, 3));
+            screen.selection_end = Some((top + 7, 2));
+        }
+        terminal.get_display_lines(true, &theme);
+        assert_eq!(terminal.rebuilt_rows, 3);
+        assert_matches_full_rebuild(&mut terminal, &theme);
+    }
+
+    #[test]
+    fn test_shell_exit_behavior() {
+        termide_i18n::init_with_language("en");
+        assert!(shell_exiting_with(0, TerminalExit::Close).should_auto_close());
+        assert!(shell_exiting_with(0, TerminalExit::KeepOnError).should_auto_close());
+
+        let kept = shell_exiting_with(0, TerminalExit::Keep);
+        assert!(!kept.should_auto_close());
+        assert!(kept.title().ends_with(" [exited]"));
+
+        let failed = shell_exiting_with(2, TerminalExit::KeepOnError);
+        assert_eq!(failed.exit_code, Some(2));
+        assert!(!failed.should_auto_close());
+        assert!(failed.title().ends_with(" [exited]"));
+    }
+}
@@ -6,6 +6,7 @@ pub mod vt100_parser;
 
 use ratatui::style::Color;
 use std::collections::VecDeque;
+use unicode_width::UnicodeWidthChar;
 
 pub use vt100_parser::VtPerformer;
 
@@ -18,21 +19,110 @@ pub enum MouseTrackingMode {
     AnyEvent,    // ?1003 - all movements
 }
 
+/// Combining characters kept per cell (more are dropped)
+pub const MAX_COMBINING: usize = 2;
+
+/// Longest title a program may set, in characters (longer ones are cut)
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Titles saved with `CSI 22 t` (older ones are dropped)
+const MAX_SAVED_TITLES: usize = 10;
+
 /// Terminal cell containing a character and its style
 #[derive(Clone, Debug, Copy)]
 pub struct Cell {
     pub ch: char,
+    /// Combining characters drawn with `ch` (accents, variation selectors)
+    pub combining: [Option<char>; MAX_COMBINING],
+    /// Columns taken by `ch`: 2 for wide characters, 0 for the
+    /// continuation cell after a wide character
+    pub width: u8,
     pub style: CellStyle,
 }
 
+impl Cell {
+    /// Character taking one column
+    pub fn new(ch: char, style: CellStyle) -> Self {
+        Self {
+            ch,
+            combining: [None; MAX_COMBINING],
+            width: 1,
+            style,
+        }
+    }
+
+    /// Empty cell
+    pub fn blank(style: CellStyle) -> Self {
+        Self::new(' ', style)
+    }
+
+    /// Second cell of a wide character
+    pub fn continuation(style: CellStyle) -> Self {
+        Self {
+            width: 0,
+            ..Self::blank(style)
+        }
+    }
+
+    /// Check if the cell is covered by the wide character before it
+    pub fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+
+    /// Attach a combining character (dropped when the cell is full)
+    pub fn push_combining(&mut self, ch: char) {
+        if let Some(slot) = self.combining.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(ch);
+        }
+    }
+
+    /// Append the text of the cell (nothing for a continuation cell)
+    pub fn push_to(&self, text: &mut String) {
+        if self.is_continuation() {
+            return;
+        }
+        text.push(self.ch);
+        text.extend(self.combining.iter().flatten());
+    }
+}
+
+/// Underline style (SGR 4, with the `4:n` subparameter for the kind)
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum UnderlineStyle {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Style of a `4:n` subparameter (unknown kinds are plain underlines)
+    pub fn from_subparam(kind: u16) -> Self {
+        match kind {
+            0 => Self::None,
+            2 => Self::Double,
+            3 => Self::Curly,
+            4 => Self::Dotted,
+            5 => Self::Dashed,
+            _ => Self::Single,
+        }
+    }
+}
+
 /// Cell style with colors and text attributes
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub struct CellStyle {
     pub fg: Color,
     pub bg: Color,
     pub bold: bool,
     pub italic: bool,
-    pub underline: bool,
+    pub underline: UnderlineStyle,
+    /// Underline color (SGR 58), the text color if None
+    pub underline_color: Option<Color>,
+    pub strikethrough: bool,
     pub reverse: bool,
 }
 
@@ -43,7 +133,9 @@ impl Default for CellStyle {
             bg: Color::Reset, // Use theme background by default
             bold: false,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
+            strikethrough: false,
             reverse: false,
         }
     }
@@ -152,12 +244,15 @@ pub struct TerminalScreen {
     pub sgr_mouse_mode: bool,
     /// Bracketed paste mode (?2004)
     pub bracketed_paste_mode: bool,
-    /// Text selection start (row, col)
+    /// Text selection start (line, col), see [`Self::line_at`]
     pub selection_start: Option<(usize, usize)>,
-    /// Text selection end (row, col)
+    /// Text selection end (line, col)
     pub selection_end: Option<(usize, usize)>,
     /// History buffer (scrollback) - VecDeque for O(1) push/pop at both ends
     pub scrollback: VecDeque<Vec<Cell>>,
+    /// Lines dropped from the front of the scrollback, so line numbers of
+    /// the remaining lines stay the same
+    pub dropped_lines: usize,
     /// View offset (0 = current screen, >0 = viewing history)
     pub scroll_offset: usize,
     /// Maximum scrollback lines
@@ -166,14 +261,20 @@ pub struct TerminalScreen {
     pub wrap_pending: bool,
     /// Dirty flag - screen content has changed and needs re-render
     pub dirty: bool,
+    /// Rows of the active buffer changed since they were last rendered
+    pub dirty_rows: Vec<bool>,
+    /// Lines the active buffer scrolled up by since the last render
+    /// (at most `rows`); rows keep their dirty flags as they move up
+    pub scrolled_rows: usize,
+    /// Title set by the program (OSC 0/2), `None` when not set or reset
+    pub title: Option<String>,
+    /// Titles saved with `CSI 22 t`, restored by `CSI 23 t`
+    pub saved_titles: Vec<Option<String>>,
 }
 
 impl TerminalScreen {
     pub fn new(rows: usize, cols: usize) -> Self {
-        let empty_cell = Cell {
-            ch: ' ',
-            style: CellStyle::default(),
-        };
+        let empty_cell = Cell::blank(CellStyle::default());
 
         Self {
             lines: std::collections::VecDeque::from(vec![vec![empty_cell; cols]; rows]),
@@ -193,11 +294,74 @@ impl TerminalScreen {
             selection_start: None,
             selection_end: None,
             scrollback: std::collections::VecDeque::new(),
+            dropped_lines: 0,
             scroll_offset: 0,
             max_scrollback: 10000,
             wrap_pending: false,
             dirty: true,
+            dirty_rows: vec![true; rows],
+            scrolled_rows: 0,
+            title: None,
+            saved_titles: Vec::new(),
+        }
+    }
+
+    /// Set the title requested by the program; control characters are
+    /// dropped and long titles cut, an empty title resets it
+    pub fn set_title(&mut self, title: &str) {
+        let title: String = title
+            .chars()
+            .filter(|ch| !ch.is_control())
+            .take(MAX_TITLE_CHARS)
+            .collect();
+        let title = title.trim();
+        self.title = (!title.is_empty()).then(|| title.to_string());
+    }
+
+    /// Save the current title (`CSI 22 t`)
+    pub fn push_title(&mut self) {
+        if self.saved_titles.len() == MAX_SAVED_TITLES {
+            self.saved_titles.remove(0);
+        }
+        self.saved_titles.push(self.title.clone());
+    }
+
+    /// Restore the last saved title (`CSI 23 t`)
+    pub fn pop_title(&mut self) {
+        if let Some(title) = self.saved_titles.pop() {
+            self.title = title;
+        }
+    }
+
+    /// Mark a row of the active buffer as changed
+    pub fn mark_row_dirty(&mut self, row: usize) {
+        if let Some(dirty) = self.dirty_rows.get_mut(row) {
+            *dirty = true;
+        }
+        self.dirty = true;
+    }
+
+    /// Mark the rows in `rows` as changed
+    pub fn mark_rows_dirty(&mut self, rows: std::ops::Range<usize>) {
+        let end = rows.end.min(self.dirty_rows.len());
+        if rows.start < end {
+            self.dirty_rows[rows.start..end].fill(true);
         }
+        self.dirty = true;
+    }
+
+    /// Mark the whole screen as changed (screen switch, resize, clear)
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty_rows = vec![true; self.rows];
+        self.dirty = true;
+    }
+
+    /// Take the changed rows and the lines scrolled since the last render,
+    /// clearing them
+    pub fn take_dirty_rows(&mut self) -> (Vec<bool>, usize) {
+        self.dirty = false;
+        let rows = std::mem::replace(&mut self.dirty_rows, vec![false; self.rows]);
+        (rows, std::mem::take(&mut self.scrolled_rows))
     }
 
     /// Get mutable reference to active buffer
@@ -218,19 +382,25 @@ impl TerminalScreen {
         }
     }
 
+    /// Drop the lines scrolled out of the main screen
+    pub fn clear_scrollback(&mut self) {
+        self.dropped_lines += self.scrollback.len();
+        self.scrollback.clear();
+        self.scroll_offset = 0;
+        self.mark_all_dirty();
+    }
+
     /// Switch to alternate screen
     pub fn switch_to_alt_screen(&mut self) {
         if !self.use_alt_screen {
             self.use_alt_screen = true;
             self.wrap_pending = false;
             // Clear alt buffer
-            let empty_cell = Cell {
-                ch: ' ',
-                style: CellStyle::default(),
-            };
+            let empty_cell = Cell::blank(CellStyle::default());
             self.alt_lines =
                 std::collections::VecDeque::from(vec![vec![empty_cell; self.cols]; self.rows]);
             self.cursor = (0, 0);
+            self.mark_all_dirty();
         }
     }
 
@@ -239,20 +409,28 @@ impl TerminalScreen {
         if self.use_alt_screen {
             self.use_alt_screen = false;
             self.wrap_pending = false;
+            self.mark_all_dirty();
         }
     }
 
-    /// Write character at current cursor position
+    /// Write character at current cursor position.
+    ///
+    /// Wide characters take two cells, combining characters attach to the
+    /// character before the cursor.
     pub fn put_char(&mut self, ch: char) {
+        let width = ch.width().unwrap_or(1);
+        if width == 0 {
+            self.put_combining(ch);
+            return;
+        }
+
         // If there was a deferred wrap - execute it now
         if self.wrap_pending {
-            self.wrap_pending = false;
-            self.cursor.1 = 0;
-            if self.cursor.0 + 1 >= self.rows {
-                self.scroll_up();
-            } else {
-                self.cursor.0 += 1;
-            }
+            self.wrap();
+        }
+        // A wide character doesn't fit in the last column - wrap it
+        if width == 2 && self.cols >= 2 && self.cursor.1 + 1 >= self.cols {
+            self.wrap();
         }
 
         let (row, col) = self.cursor;
@@ -261,18 +439,78 @@ impl TerminalScreen {
         let style = self.current_style;
 
         if row < rows && col < cols {
+            self.mark_row_dirty(row);
+            let width = width.min(cols - col);
+            // Overwriting half of a wide character blanks its other half
+            for i in col..col + width {
+                self.split_wide_char(row, i);
+            }
             let buffer = self.active_buffer_mut();
-            buffer[row][col] = Cell { ch, style };
+            buffer[row][col] = Cell {
+                width: width as u8,
+                ..Cell::new(ch, style)
+            };
+            if width == 2 {
+                buffer[row][col + 1] = Cell::continuation(style);
+            }
             // Move cursor right
-            if col + 1 >= cols {
+            if col + width >= cols {
                 // Reached last column - defer wrap
+                self.cursor.1 = cols - 1;
                 self.wrap_pending = true;
             } else {
-                self.cursor.1 = col + 1;
+                self.cursor.1 = col + width;
             }
         }
     }
 
+    /// Move cursor to the start of the next line (scrolling at the bottom)
+    fn wrap(&mut self) {
+        self.wrap_pending = false;
+        self.cursor.1 = 0;
+        if self.cursor.0 + 1 >= self.rows {
+            self.scroll_up();
+        } else {
+            self.cursor.0 += 1;
+        }
+    }
+
+    /// Attach a combining character to the character before the cursor
+    fn put_combining(&mut self, ch: char) {
+        let (row, col) = self.cursor;
+        // With a deferred wrap the cursor is still on the last character
+        let col = if self.wrap_pending {
+            Some(col)
+        } else {
+            col.checked_sub(1)
+        };
+        self.mark_row_dirty(row);
+        let buffer = self.active_buffer_mut();
+        let (Some(mut col), Some(line)) = (col, buffer.get_mut(row)) else {
+            return;
+        };
+        if col > 0 && line.get(col).is_some_and(Cell::is_continuation) {
+            col -= 1;
+        }
+        if let Some(cell) = line.get_mut(col) {
+            cell.push_combining(ch);
+        }
+    }
+
+    /// Blank the other half of a wide character covering cell (row, col)
+    fn split_wide_char(&mut self, row: usize, col: usize) {
+        let buffer = self.active_buffer_mut();
+        let Some(line) = buffer.get_mut(row) else {
+            return;
+        };
+        let other = match line.get(col).map(|cell| cell.width) {
+            Some(0) if col > 0 => col - 1,
+            Some(2) if col + 1 < line.len() => col + 1,
+            _ => return,
+        };
+        line[other] = Cell::blank(line[other].style);
+    }
+
     /// Newline
     pub fn newline(&mut self) {
         self.wrap_pending = false;
@@ -303,16 +541,22 @@ impl TerminalScreen {
             // Limit scrollback size - O(1) with VecDeque instead of O(n) with Vec::remove(0)
             if self.scrollback.len() > self.max_scrollback {
                 self.scrollback.pop_front();
+                self.dropped_lines += 1;
             }
         }
 
         let buffer = self.active_buffer_mut();
         buffer.pop_front(); // O(1) with VecDeque instead of O(n) with Vec::remove(0)
-        let empty_cell = Cell {
-            ch: ' ',
-            style: CellStyle::default(),
-        };
+        let empty_cell = Cell::blank(CellStyle::default());
         buffer.push_back(vec![empty_cell; cols]);
+
+        // Rendered rows move up with the content, the new bottom row is blank
+        if !self.dirty_rows.is_empty() {
+            self.dirty_rows.remove(0);
+            self.dirty_rows.push(true);
+        }
+        self.scrolled_rows = (self.scrolled_rows + 1).min(self.rows);
+        self.dirty = true;
     }
 
     /// Scroll view up (into history)
@@ -331,7 +575,41 @@ impl TerminalScreen {
         self.scroll_offset = 0;
     }
 
-    /// Check if cell (row, col) is in current selection
+    /// Line shown in screen row `row`.
+    ///
+    /// Lines are numbered across the scrollback and the screen from the
+    /// first line ever scrolled into history, so a line keeps its number
+    /// while the view scrolls and new output pushes it up.
+    pub fn line_at(&self, row: usize) -> usize {
+        let offset = if self.use_alt_screen {
+            0
+        } else {
+            self.scroll_offset
+        };
+        self.dropped_lines + self.scrollback.len() - offset + row
+    }
+
+    /// Cells of a line numbered as by [`Self::line_at`], from the
+    /// scrollback or the active buffer
+    fn line(&self, line: usize) -> Option<&Vec<Cell>> {
+        let index = line.checked_sub(self.dropped_lines)?;
+        match index.checked_sub(self.scrollback.len()) {
+            None => self.scrollback.get(index),
+            Some(row) => self.active_buffer().get(row),
+        }
+    }
+
+    /// Selection as (start, end) with start first, `None` without one
+    pub fn selection_bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (start, end) = (self.selection_start?, self.selection_end?);
+        Some(if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        })
+    }
+
+    /// Check if cell (line, col) is in current selection
     pub fn is_in_selection(&self, row: usize, col: usize) -> bool {
         let (start, end) = match (self.selection_start, self.selection_end) {
             (Some(s), Some(e)) => (s, e),
@@ -366,17 +644,70 @@ impl TerminalScreen {
         }
     }
 
+    /// Text of the selection, with trailing whitespace trimmed from each line
+    pub fn selected_text(&self) -> String {
+        let (start, end) = match (self.selection_start, self.selection_end) {
+            (Some(s), Some(e)) => (s, e),
+            _ => return String::new(),
+        };
+
+        // Normalize
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+
+        let mut result = String::new();
+
+        for row_idx in start.0..=end.0 {
+            // Lines dropped from the history are skipped
+            let Some(row) = self.line(row_idx) else {
+                if row_idx < self.dropped_lines {
+                    continue;
+                }
+                break;
+            };
+            let mut col_start = if row_idx == start.0 { start.1 } else { 0 };
+            // Starting on the second half of a wide character takes all of it
+            if col_start > 0 && row.get(col_start).is_some_and(Cell::is_continuation) {
+                col_start -= 1;
+            }
+            let col_end = if row_idx == end.0 {
+                end.1.min(row.len().saturating_sub(1))
+            } else {
+                row.len().saturating_sub(1)
+            };
+
+            for col_idx in col_start..=col_end {
+                if col_idx < row.len() && row[col_idx].ch != '\0' {
+                    row[col_idx].push_to(&mut result);
+                }
+            }
+
+            // Add line break between lines (but not at the end)
+            if row_idx < end.0 {
+                result.push('\n');
+            }
+        }
+
+        // Trim trailing whitespace from each line
+        result
+            .lines()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Clear screen (doesn't move cursor)
     #[allow(dead_code)]
     pub fn clear_screen(&mut self) {
         let rows = self.rows;
         let cols = self.cols;
-        let empty_cell = Cell {
-            ch: ' ',
-            style: CellStyle::default(),
-        };
+        let empty_cell = Cell::blank(CellStyle::default());
         let buffer = self.active_buffer_mut();
         *buffer = std::collections::VecDeque::from(vec![vec![empty_cell; cols]; rows]);
+        self.mark_all_dirty();
         // Cursor stays in place (standard ED 2 behavior)
     }
 
@@ -415,3 +746,216 @@ impl TerminalScreen {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, RwLock};
+
+    /// Screen of `cols` columns after the program printed `output`
+    fn screen_after(cols: usize, output: &str) -> TerminalScreen {
+        let screen = Arc::new(RwLock::new(TerminalScreen::new(3, cols)));
+        let mut performer = VtPerformer::new(Arc::clone(&screen));
+        let mut parser = vte::Parser::new();
+        for byte in output.as_bytes() {
+            parser.advance(&mut performer, *byte);
+        }
+        performer.flush();
+        let screen = screen.read().unwrap().clone();
+        screen
+    }
+
+    /// Text and width of the cells of a row, up to the first blank
+    fn cells(screen: &TerminalScreen, row: usize) -> Vec<(String, u8)> {
+        screen.lines[row]
+            .iter()
+            .take_while(|cell| cell.ch != ' ' || cell.is_continuation())
+            .map(|cell| {
+                let mut text = String::new();
+                cell.push_to(&mut text);
+                (text, cell.width)
+            })
+            .collect()
+    }
+
+    fn cell(text: &str, width: u8) -> (String, u8) {
+        (text.to_string(), width)
+    }
+
+    #[test]
+    fn test_wide_characters_take_two_cells() {
+        let screen = screen_after(10, "a字😀b");
+        assert_eq!(
+            cells(&screen, 0),
+            [
+                cell("a", 1),
+                cell("字", 2),
+                cell("", 0),
+                cell("😀", 2),
+                cell("", 0),
+                cell("b", 1)
+            ]
+        );
+        assert_eq!(screen.cursor, (0, 6));
+
+        // Cursor positions count cells: writing over the second half of a
+        // wide character blanks the first
+        let screen = screen_after(10, "a字b\x1b[1;3Hx");
+        assert_eq!(cells(&screen, 0), [cell("a", 1)]);
+        assert_eq!(screen.lines[0][1].ch, ' ');
+        assert_eq!(screen.lines[0][2].ch, 'x');
+        assert_eq!(screen.lines[0][3].ch, 'b');
+    }
+
+    #[test]
+    fn test_wide_character_wraps_at_last_column() {
+        let screen = screen_after(4, "abc字");
+        assert_eq!(
+            cells(&screen, 0),
+            [cell("a", 1), cell("b", 1), cell("c", 1)]
+        );
+        assert_eq!(cells(&screen, 1), [cell("字", 2), cell("", 0)]);
+        assert_eq!(screen.cursor, (1, 2));
+
+        // Filling the line defers the wrap like a narrow character
+        let screen = screen_after(4, "ab字");
+        assert_eq!(screen.cursor, (0, 3));
+        assert!(screen.wrap_pending);
+    }
+
+    #[test]
+    fn test_combining_characters_attach_to_previous_cell() {
+        let screen = screen_after(10, "e\u{301}x\u{2764}\u{fe0f}字\u{301}");
+        assert_eq!(
+            cells(&screen, 0),
+            [
+                cell("e\u{301}", 1),
+                cell("x", 1),
+                cell("\u{2764}\u{fe0f}", 1),
+                cell("字\u{301}", 2),
+                cell("", 0)
+            ]
+        );
+        assert_eq!(screen.cursor, (0, 5));
+    }
+
+    #[test]
+    fn test_selected_text_skips_continuation_cells() {
+        let mut screen = screen_after(10, "a字😀b\r\ne\u{301}");
+        screen.selection_start = Some((0, 1));
+        screen.selection_end = Some((1, 0));
+        assert_eq!(screen.selected_text(), "字😀b\ne\u{301}");
+
+        // Starting on the second half of a wide character takes all of it
+        screen.selection_start = Some((0, 4));
+        screen.selection_end = Some((0, 4));
+        assert_eq!(screen.selected_text(), "😀");
+    }
+
+    #[test]
+    fn test_selection_stays_on_its_lines_while_scrolling() {
+        let output: String = (0..10).map(|i| format!("l{}\r\n", i)).collect();
+        let mut screen = screen_after(10, &output);
+        screen.selection_start = Some((screen.line_at(0), 0));
+        screen.selection_end = Some((screen.line_at(1), 9));
+        assert_eq!(screen.selected_text(), "l8\nl9");
+
+        // Scrolling a page up moves the selected lines down the screen
+        screen.scroll_view_up(screen.rows - 1);
+        assert_eq!(screen.selected_text(), "l8\nl9");
+        assert!(!screen.is_in_selection(screen.line_at(1), 0));
+        assert!(screen.is_in_selection(screen.line_at(2), 0));
+
+        // Selecting in the history
+        screen.selection_start = Some((screen.line_at(0), 0));
+        screen.selection_end = Some((screen.line_at(0), 9));
+        assert_eq!(screen.selected_text(), "l6");
+
+        // New output and trimmed history leave the selection alone
+        screen.max_scrollback = screen.scrollback.len();
+        screen.newline();
+        assert_eq!(screen.dropped_lines, 1);
+        assert_eq!(screen.selected_text(), "l6");
+    }
+
+    /// Style of the first cell after the program printed `output`
+    fn style_after(output: &str) -> CellStyle {
+        screen_after(10, output).lines[0][0].style
+    }
+
+    #[test]
+    fn test_underline_styles_and_strikethrough() {
+        assert_eq!(style_after("\x1b[4mx").underline, UnderlineStyle::Single);
+        assert_eq!(style_after("\x1b[4:3mx").underline, UnderlineStyle::Curly);
+        assert_eq!(style_after("\x1b[4:2mx").underline, UnderlineStyle::Double);
+        assert_eq!(style_after("\x1b[21mx").underline, UnderlineStyle::Double);
+        assert_eq!(style_after("\x1b[4:4mx").underline, UnderlineStyle::Dotted);
+        assert_eq!(style_after("\x1b[4:5mx").underline, UnderlineStyle::Dashed);
+        assert_eq!(
+            style_after("\x1b[4:3m\x1b[4:0mx").underline,
+            UnderlineStyle::None
+        );
+        assert_eq!(
+            style_after("\x1b[4m\x1b[24mx").underline,
+            UnderlineStyle::None
+        );
+
+        // The subparameter is not read as another attribute (3 = italic)
+        assert!(!style_after("\x1b[4:3mx").italic);
+
+        let style = style_after("\x1b[9mx");
+        assert!(style.strikethrough);
+        assert!(!style_after("\x1b[9;29mx").strikethrough);
+
+        // ESC[m resets everything
+        let screen = screen_after(10, "\x1b[1;4:3;9;58;5;1mx\x1b[my");
+        assert_eq!(screen.lines[0][1].style, CellStyle::default());
+    }
+
+    #[test]
+    fn test_underline_color() {
+        let style = style_after("\x1b[4:3;58:2::255:0:0mx");
+        assert_eq!(style.underline, UnderlineStyle::Curly);
+        assert_eq!(style.underline_color, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(style.fg, CellStyle::default().fg);
+
+        assert_eq!(
+            style_after("\x1b[58:2:1:2:3mx").underline_color,
+            Some(Color::Rgb(1, 2, 3))
+        );
+        assert_eq!(
+            style_after("\x1b[58;5;196mx").underline_color,
+            Some(ansi_256_to_color(196))
+        );
+        assert_eq!(
+            style_after("\x1b[58;2;10;20;30;1mx").underline_color,
+            Some(Color::Rgb(10, 20, 30))
+        );
+        assert!(style_after("\x1b[58;2;10;20;30;1mx").bold);
+        assert_eq!(style_after("\x1b[58;5;196;59mx").underline_color, None);
+
+        // Colon forms of the text and background colors
+        let style = style_after("\x1b[38:5:21;48:2::1:2:3mx");
+        assert_eq!(style.fg, ansi_256_to_color(21));
+        assert_eq!(style.bg, Color::Rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn test_program_title() {
+        let screen = screen_after(10, "\x1b]2;vim; a.rs\x07");
+        assert_eq!(screen.title.as_deref(), Some("vim; a.rs"));
+
+        // Control characters dropped, long titles cut, ST terminator
+        let long = "x".repeat(MAX_TITLE_CHARS + 5);
+        let screen = screen_after(10, &format!("\x1b]0;\u{9b}{}\x1b\\", long));
+        assert_eq!(screen.title, Some("x".repeat(MAX_TITLE_CHARS)));
+
+        // Icon name does not set the title, an empty title resets it
+        assert_eq!(screen_after(10, "\x1b]1;icon\x07").title, None);
+        assert_eq!(screen_after(10, "\x1b]0;a\x07\x1b]0;\x07").title, None);
+
+        // Saved and restored around a program
+        let screen = screen_after(10, "\x1b]2;shell\x07\x1b[22;0t\x1b]2;vim\x07\x1b[23;0t");
+        assert_eq!(screen.title.as_deref(), Some("shell"));
+    }
+}
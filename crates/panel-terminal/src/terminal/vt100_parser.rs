@@ -13,7 +13,7 @@ use ratatui::style::Color;
 
 use super::{
     ansi_256_to_color, ansi_to_bright_color, ansi_to_color, Cell, CellStyle, MouseTrackingMode,
-    TerminalScreen,
+    TerminalScreen, UnderlineStyle,
 };
 
 /// Batched screen operation to reduce mutex contention.
@@ -129,7 +129,20 @@ impl Perform for VtPerformer {
 
     fn unhook(&mut self) {}
 
-    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        // Window title (0 sets the icon name too, 1 only the icon name, which
+        // is ignored); the title may contain ';'
+        if let [b"0" | b"2", title @ ..] = params {
+            let title = title
+                .iter()
+                .map(|part| String::from_utf8_lossy(part))
+                .collect::<Vec<_>>()
+                .join(";");
+            if let Ok(mut screen) = self.screen.write() {
+                screen.set_title(&title);
+            }
+        }
+    }
 
     fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, c: char) {
         // Flush pending operations before CSI dispatch to maintain order
@@ -266,10 +279,7 @@ impl Perform for VtPerformer {
                         .copied()
                         .unwrap_or(0);
                     let (row, col) = screen.cursor;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     match param {
                         0 => {
@@ -291,6 +301,7 @@ impl Perform for VtPerformer {
                                     buffer[r][c] = empty_cell;
                                 }
                             }
+                            screen.mark_rows_dirty(row..buf_rows);
                         }
                         1 => {
                             // Clear from start of screen to cursor
@@ -311,6 +322,7 @@ impl Perform for VtPerformer {
                                     buffer[row][i] = empty_cell;
                                 }
                             }
+                            screen.mark_rows_dirty(0..row + 1);
                         }
                         2 => {
                             // Clear entire screen and move cursor to (0,0)
@@ -320,6 +332,7 @@ impl Perform for VtPerformer {
                             }
                             // Move cursor to home position (compatibility with old behavior)
                             screen.cursor = (0, 0);
+                            screen.mark_all_dirty();
                         }
                         3 => {
                             // Clear entire screen and scrollback
@@ -330,9 +343,10 @@ impl Perform for VtPerformer {
                             }
                             // Clear scrollback only for main screen
                             if !is_alt {
-                                screen.scrollback.clear();
+                                screen.clear_scrollback();
                             }
                             screen.cursor = (0, 0);
+                            screen.mark_all_dirty();
                         }
                         _ => {}
                     }
@@ -346,10 +360,7 @@ impl Perform for VtPerformer {
                         .copied()
                         .unwrap_or(0);
                     let (row, col) = screen.cursor;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     if row < buffer.len() {
@@ -376,6 +387,7 @@ impl Perform for VtPerformer {
                             _ => {}
                         }
                     }
+                    screen.mark_row_dirty(row);
                 }
                 'P' => {
                     // DCH - Delete Character
@@ -387,10 +399,7 @@ impl Perform for VtPerformer {
                         .unwrap_or(1) as usize;
                     let (row, col) = screen.cursor;
                     let cols = screen.cols;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     // Shift characters left from deleted position using copy_within (3-5x faster)
@@ -402,6 +411,7 @@ impl Perform for VtPerformer {
                     for i in (cols - n)..cols {
                         buffer[row][i] = empty_cell;
                     }
+                    screen.mark_row_dirty(row);
                 }
                 'X' => {
                     // ECH - Erase Character
@@ -413,15 +423,13 @@ impl Perform for VtPerformer {
                         .unwrap_or(1) as usize;
                     let (row, col) = screen.cursor;
                     let cols = screen.cols;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     for i in col..(col + n).min(cols) {
                         buffer[row][i] = empty_cell;
                     }
+                    screen.mark_row_dirty(row);
                 }
                 '@' => {
                     // ICH - Insert Character (shift characters right)
@@ -433,10 +441,7 @@ impl Perform for VtPerformer {
                         .unwrap_or(1) as usize;
                     let (row, col) = screen.cursor;
                     let cols = screen.cols;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     // Shift characters right using copy_within (3-5x faster)
@@ -448,6 +453,7 @@ impl Perform for VtPerformer {
                     for i in col..(col + n).min(cols) {
                         buffer[row][i] = empty_cell;
                     }
+                    screen.mark_row_dirty(row);
                 }
                 'L' => {
                     // IL - Insert Lines (insert blank lines)
@@ -460,10 +466,7 @@ impl Perform for VtPerformer {
                     let row = screen.cursor.0;
                     let cols = screen.cols;
                     let rows = screen.rows;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     if row < buffer.len() {
@@ -483,6 +486,7 @@ impl Perform for VtPerformer {
                             }
                         }
                     }
+                    screen.mark_rows_dirty(row..rows);
                 }
                 'M' => {
                     // DL - Delete Lines (delete lines)
@@ -495,10 +499,7 @@ impl Perform for VtPerformer {
                     let row = screen.cursor.0;
                     let cols = screen.cols;
                     let rows = screen.rows;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     if row < buffer.len() {
@@ -518,6 +519,7 @@ impl Perform for VtPerformer {
                             buffer.push_back(vec![empty_cell; cols]);
                         }
                     }
+                    screen.mark_rows_dirty(row..rows);
                 }
                 'S' => {
                     // SU - Scroll Up (scroll screen up)
@@ -529,10 +531,7 @@ impl Perform for VtPerformer {
                         .unwrap_or(1) as usize;
                     let cols = screen.cols;
                     let rows = screen.rows;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     for _ in 0..n.min(rows) {
@@ -541,6 +540,7 @@ impl Perform for VtPerformer {
                         }
                         buffer.push_back(vec![empty_cell; cols]);
                     }
+                    screen.mark_all_dirty();
                 }
                 'T' => {
                     // SD - Scroll Down (scroll screen down)
@@ -552,10 +552,7 @@ impl Perform for VtPerformer {
                         .unwrap_or(1) as usize;
                     let cols = screen.cols;
                     let rows = screen.rows;
-                    let empty_cell = Cell {
-                        ch: ' ',
-                        style: screen.current_style,
-                    };
+                    let empty_cell = Cell::blank(screen.current_style);
 
                     let buffer = screen.active_buffer_mut();
                     for _ in 0..n.min(rows) {
@@ -564,6 +561,7 @@ impl Perform for VtPerformer {
                         }
                         buffer.push_front(vec![empty_cell; cols]); // O(1) with VecDeque
                     }
+                    screen.mark_all_dirty();
                 }
                 'A' => {
                     // Cursor up
@@ -657,78 +655,7 @@ impl Perform for VtPerformer {
                 }
                 'm' => {
                     // SGR - set style
-                    // Collect all parameters into one vector to handle 38;5;N and 48;5;N
-                    let all_params: Vec<u16> =
-                        params.iter().flat_map(|p| p.iter().copied()).collect();
-                    let mut i = 0;
-                    while i < all_params.len() {
-                        let p = all_params[i];
-                        match p {
-                            0 => screen.current_style = CellStyle::default(),
-                            1 => screen.current_style.bold = true,
-                            3 => screen.current_style.italic = true,
-                            4 => screen.current_style.underline = true,
-                            7 => screen.current_style.reverse = true,
-                            22 => screen.current_style.bold = false,
-                            23 => screen.current_style.italic = false,
-                            24 => screen.current_style.underline = false,
-                            27 => screen.current_style.reverse = false,
-                            30..=37 => {
-                                screen.current_style.fg = ansi_to_color(p - 30);
-                            }
-                            38 => {
-                                // 256-color or RGB foreground
-                                if i + 2 < all_params.len() && all_params[i + 1] == 5 {
-                                    // 38;5;N - 256-color
-                                    let color_idx = all_params[i + 2];
-                                    screen.current_style.fg = ansi_256_to_color(color_idx);
-                                    i += 2;
-                                } else if i + 4 < all_params.len() && all_params[i + 1] == 2 {
-                                    // 38;2;R;G;B - True Color (24-bit)
-                                    let r = all_params[i + 2] as u8;
-                                    let g = all_params[i + 3] as u8;
-                                    let b = all_params[i + 4] as u8;
-                                    screen.current_style.fg = Color::Rgb(r, g, b);
-                                    i += 4;
-                                }
-                            }
-                            39 => {
-                                // Reset foreground to default
-                                screen.current_style.fg = Color::Reset;
-                            }
-                            40..=47 => {
-                                screen.current_style.bg = ansi_to_color(p - 40);
-                            }
-                            48 => {
-                                // 256-color or RGB background
-                                if i + 2 < all_params.len() && all_params[i + 1] == 5 {
-                                    // 48;5;N - 256-color
-                                    let color_idx = all_params[i + 2];
-                                    screen.current_style.bg = ansi_256_to_color(color_idx);
-                                    i += 2;
-                                } else if i + 4 < all_params.len() && all_params[i + 1] == 2 {
-                                    // 48;2;R;G;B - True Color (24-bit)
-                                    let r = all_params[i + 2] as u8;
-                                    let g = all_params[i + 3] as u8;
-                                    let b = all_params[i + 4] as u8;
-                                    screen.current_style.bg = Color::Rgb(r, g, b);
-                                    i += 4;
-                                }
-                            }
-                            49 => {
-                                // Reset background to default
-                                screen.current_style.bg = Color::Reset;
-                            }
-                            90..=97 => {
-                                screen.current_style.fg = ansi_to_bright_color(p - 90);
-                            }
-                            100..=107 => {
-                                screen.current_style.bg = ansi_to_bright_color(p - 100);
-                            }
-                            _ => {}
-                        }
-                        i += 1;
-                    }
+                    apply_sgr(&mut screen.current_style, params);
                 }
                 's' => {
                     // Save cursor position
@@ -744,6 +671,14 @@ impl Perform for VtPerformer {
                 'l' | 'h' => {
                     // Set/Reset Mode (ignore but don't break)
                 }
+                't' => {
+                    // Window operations: save (22) and restore (23) the title
+                    match params.iter().next().and_then(|p| p.first()) {
+                        Some(22) => screen.push_title(),
+                        Some(23) => screen.pop_title(),
+                        _ => {}
+                    }
+                }
                 _ => {}
             }
             screen.dirty = true;
@@ -752,3 +687,91 @@ impl Perform for VtPerformer {
 
     fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
 }
+
+/// Apply SGR (Select Graphic Rendition) parameters to a style.
+///
+/// Parameters with colon-separated subparameters (`4:3`, `38:2::R:G:B`)
+/// arrive from vte as one group; the older semicolon-separated colors
+/// (`38;5;N`, `38;2;R;G;B`) are spread over several groups.
+fn apply_sgr(style: &mut CellStyle, params: &Params) {
+    let groups: Vec<&[u16]> = params.iter().collect();
+    // ESC[m resets like ESC[0m
+    if groups.is_empty() {
+        *style = CellStyle::default();
+        return;
+    }
+
+    let mut i = 0;
+    while i < groups.len() {
+        let group = groups[i];
+        let p = group[0];
+        match p {
+            0 => *style = CellStyle::default(),
+            1 => style.bold = true,
+            3 => style.italic = true,
+            4 => {
+                style.underline = group.get(1).map_or(UnderlineStyle::Single, |&kind| {
+                    UnderlineStyle::from_subparam(kind)
+                });
+            }
+            7 => style.reverse = true,
+            9 => style.strikethrough = true,
+            21 => style.underline = UnderlineStyle::Double,
+            22 => style.bold = false,
+            23 => style.italic = false,
+            24 => style.underline = UnderlineStyle::None,
+            27 => style.reverse = false,
+            29 => style.strikethrough = false,
+            30..=37 => style.fg = ansi_to_color(p - 30),
+            // 256-color or RGB foreground, background and underline
+            38 | 48 | 58 => {
+                let (color, taken) = extended_color(&groups[i..]);
+                if let Some(color) = color {
+                    match p {
+                        38 => style.fg = color,
+                        48 => style.bg = color,
+                        _ => style.underline_color = Some(color),
+                    }
+                }
+                i += taken;
+            }
+            // Reset to default colors
+            39 => style.fg = Color::Reset,
+            49 => style.bg = Color::Reset,
+            59 => style.underline_color = None,
+            40..=47 => style.bg = ansi_to_color(p - 40),
+            90..=97 => style.fg = ansi_to_bright_color(p - 90),
+            100..=107 => style.bg = ansi_to_bright_color(p - 100),
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Color of an extended color parameter (38, 48 or 58, the first group)
+/// and the number of following groups it took
+fn extended_color(groups: &[&[u16]]) -> (Option<Color>, usize) {
+    let group = groups[0];
+    if group.len() > 1 {
+        // 38:5:N, 38:2:R:G:B or 38:2:ID:R:G:B (with a color space id)
+        let color = match group[1] {
+            5 => group.get(2).map(|&idx| ansi_256_to_color(idx)),
+            2 => {
+                let rgb = &group[2..];
+                let rgb = if rgb.len() > 3 { &rgb[1..] } else { rgb };
+                (rgb.len() >= 3).then(|| Color::Rgb(rgb[0] as u8, rgb[1] as u8, rgb[2] as u8))
+            }
+            _ => None,
+        };
+        return (color, 0);
+    }
+
+    let value = |k: usize| groups.get(k).map(|group| group[0]);
+    match (value(1), value(2), value(3), value(4)) {
+        // 38;5;N - 256-color
+        (Some(5), Some(idx), _, _) => (Some(ansi_256_to_color(idx)), 2),
+        // 38;2;R;G;B - True Color (24-bit)
+        (Some(2), Some(r), Some(g), Some(b)) => (Some(Color::Rgb(r as u8, g as u8, b as u8)), 4),
+        _ => (None, 0),
+    }
+}
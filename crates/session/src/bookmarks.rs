@@ -0,0 +1,241 @@
+//! Bookmarks: named lines of project files.
+//!
+//! Saved with the session of the project. A bookmark keeps the text of its
+//! line, so it can find the line again after the file was edited.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Characters of a line kept to find it again
+const MAX_TEXT_CHARS: usize = 200;
+
+/// Line of a file marked with a name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Name given by the user (a number unless named)
+    pub name: String,
+    /// File the bookmark is in
+    pub path: PathBuf,
+    /// Line (0-based)
+    pub line: usize,
+    /// Text of the line without surrounding whitespace, when last seen
+    #[serde(default)]
+    pub text: String,
+}
+
+impl Bookmark {
+    /// Bookmark `line` of `path`, whose text is `text`
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>, line: usize, text: &str) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            line,
+            text: line_key(text),
+        }
+    }
+
+    /// Check the bookmark still points at its text, in a file of
+    /// `line_count` lines read with `line_text`.
+    ///
+    /// When the line no longer holds the text, the bookmark moves to the
+    /// nearest line that does. Text found nowhere leaves the bookmark on
+    /// its line (within the file), taking the line's current text. Returns
+    /// true if the line or text changed.
+    pub fn reanchor(
+        &mut self,
+        line_count: usize,
+        line_text: impl Fn(usize) -> Option<String>,
+    ) -> bool {
+        let matches =
+            |line: usize| line_text(line).is_some_and(|text| line_key(&text) == self.text);
+        let line = self.line.min(line_count.saturating_sub(1));
+        let found = if self.text.is_empty() || matches(line) {
+            Some(line)
+        } else {
+            (1..line_count).find_map(|distance| {
+                [line.checked_sub(distance), line.checked_add(distance)]
+                    .into_iter()
+                    .flatten()
+                    .find(|&candidate| candidate < line_count && matches(candidate))
+            })
+        };
+        let line = found.unwrap_or(line);
+        let text = if found.is_some() {
+            self.text.clone()
+        } else {
+            line_text(line)
+                .map(|text| line_key(&text))
+                .unwrap_or_default()
+        };
+        let changed = line != self.line || text != self.text;
+        self.line = line;
+        self.text = text;
+        changed
+    }
+}
+
+/// Text a bookmark keeps of a line
+fn line_key(text: &str) -> String {
+    text.trim().chars().take(MAX_TEXT_CHARS).collect()
+}
+
+/// Bookmarks of a project, in the order they were set.
+///
+/// The revision changes when bookmarks are set or removed, so editors can
+/// tell when to update their marks.
+#[derive(Debug, Clone, Default)]
+pub struct Bookmarks {
+    entries: Vec<Bookmark>,
+    revision: u64,
+}
+
+impl Bookmarks {
+    /// Bookmarks restored from a session
+    pub fn from_entries(entries: Vec<Bookmark>) -> Self {
+        Self {
+            entries,
+            revision: 0,
+        }
+    }
+
+    /// All bookmarks, oldest first
+    pub fn entries(&self) -> &[Bookmark] {
+        &self.entries
+    }
+
+    /// Number that changes on every set or removal
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Bookmarks in a file
+    pub fn in_file<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = &'a Bookmark> {
+        self.entries
+            .iter()
+            .filter(move |bookmark| bookmark.path == path)
+    }
+
+    /// Name of the bookmark on a line, if any
+    pub fn name_at(&self, path: &Path, line: usize) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|bookmark| bookmark.path == path && bookmark.line == line)
+            .map(|bookmark| bookmark.name.as_str())
+    }
+
+    /// Smallest number not used as a bookmark name, starting from 1
+    pub fn next_number(&self) -> String {
+        (1..)
+            .map(|number: usize| number.to_string())
+            .find(|name| self.entries.iter().all(|bookmark| bookmark.name != *name))
+            .expect("unused number")
+    }
+
+    /// Add a bookmark. A bookmark with the same name moves to the new
+    /// place, as does one on the same line.
+    pub fn set(&mut self, bookmark: Bookmark) {
+        self.entries.retain(|entry| {
+            entry.name != bookmark.name
+                && !(entry.path == bookmark.path && entry.line == bookmark.line)
+        });
+        self.entries.push(bookmark);
+        self.revision += 1;
+    }
+
+    /// Remove a bookmark by name; true if it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|bookmark| bookmark.name != name);
+        let removed = self.entries.len() != before;
+        if removed {
+            self.revision += 1;
+        }
+        removed
+    }
+
+    /// Record where an editor shows a bookmark now. Editors move their
+    /// marks with the text, so this does not change the revision.
+    pub fn update(&mut self, path: &Path, name: &str, line: usize, text: &str) {
+        let found = self
+            .entries
+            .iter_mut()
+            .find(|bookmark| bookmark.name == name && bookmark.path == path);
+        if let Some(bookmark) = found {
+            bookmark.line = line;
+            bookmark.text = line_key(text);
+        }
+    }
+
+    /// Check the bookmarks of `path` against its text (see
+    /// [`Bookmark::reanchor`])
+    pub fn reanchor_file(&mut self, path: &Path, text: &str) {
+        let lines: Vec<&str> = text.lines().collect();
+        for bookmark in self
+            .entries
+            .iter_mut()
+            .filter(|bookmark| bookmark.path == path)
+        {
+            bookmark.reanchor(lines.len(), |line| {
+                lines.get(line).map(|text| text.to_string())
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reanchored(text: &str, mut bookmark: Bookmark) -> Bookmark {
+        let lines: Vec<&str> = text.lines().collect();
+        bookmark.reanchor(lines.len(), |line| {
+            lines.get(line).map(|text| text.to_string())
+        });
+        bookmark
+    }
+
+    #[test]
+    fn test_reanchor_finds_nearest_moved_line() {
+        let bookmark = Bookmark::new("1", "a.rs", 2, "  fn target() {");
+        // Unchanged
+        let kept = reanchored("a\nb\nfn target() {\n", bookmark.clone());
+        assert_eq!(kept.line, 2);
+        // Two lines inserted above, and another copy further away
+        let text = "a\nx\ny\nb\n    fn target() {\nc\nd\ne\nfn target() {\n";
+        assert_eq!(reanchored(text, bookmark.clone()).line, 4);
+        // Earlier lines deleted
+        assert_eq!(reanchored("fn target() {\n", bookmark).line, 0);
+    }
+
+    #[test]
+    fn test_reanchor_keeps_line_when_text_is_gone() {
+        let bookmark = Bookmark::new("1", "a.rs", 5, "gone");
+        let moved = reanchored("a\nb\nc\n", bookmark);
+        assert_eq!(moved.line, 2);
+        assert_eq!(moved.text, "c");
+    }
+
+    #[test]
+    fn test_set_replaces_same_name_and_line() {
+        let mut bookmarks = Bookmarks::default();
+        assert_eq!(bookmarks.next_number(), "1");
+        bookmarks.set(Bookmark::new("1", "a.rs", 3, "x"));
+        bookmarks.set(Bookmark::new("todo", "b.rs", 0, "y"));
+        assert_eq!(bookmarks.next_number(), "2");
+
+        bookmarks.set(Bookmark::new("todo", "b.rs", 7, "z"));
+        assert_eq!(bookmarks.entries().len(), 2);
+        assert_eq!(bookmarks.name_at(Path::new("b.rs"), 7), Some("todo"));
+        bookmarks.set(Bookmark::new("2", "a.rs", 3, "x"));
+        assert_eq!(bookmarks.name_at(Path::new("a.rs"), 3), Some("2"));
+        assert_eq!(bookmarks.next_number(), "1");
+
+        let revision = bookmarks.revision();
+        bookmarks.update(Path::new("a.rs"), "2", 4, "moved");
+        assert_eq!(bookmarks.name_at(Path::new("a.rs"), 4), Some("2"));
+        assert_eq!(bookmarks.revision(), revision);
+        assert!(bookmarks.remove("2"));
+        assert!(!bookmarks.remove("2"));
+        assert_eq!(bookmarks.revision(), revision + 1);
+    }
+}
@@ -5,32 +5,56 @@
 use anyhow::{Context, Result};
 use chrono::Local;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use termide_config::FileSort;
+
+mod bookmarks;
+mod recent;
+mod recovery;
+
+pub use bookmarks::{Bookmark, Bookmarks};
+pub use recent::RecentPaths;
+pub use recovery::{orphaned_recovery_files, RecoveryFile};
 
 /// Session state for saving and restoring panel layout
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Layout fields default when missing so hand-edited or older session
+/// files still load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Session {
-    /// Panel groups (vertical columns with accordion)
+    /// Panel groups (vertical columns with accordion), left to right
     pub panel_groups: Vec<SessionPanelGroup>,
     /// Which group is currently focused (0-based index)
+    #[serde(default)]
     pub focused_group: usize,
+    /// Queries of the editors' search modal, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_history: Vec<String>,
+    /// Bookmarks set in the project's files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<Bookmark>,
 }
 
 /// A group of panels (one vertical column)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionPanelGroup {
-    /// Panels in this group
+    /// Panels in this group, top to bottom (more than one = stacked accordion)
     pub panels: Vec<SessionPanel>,
     /// Which panel is expanded (0-based index)
+    #[serde(default)]
     pub expanded_index: usize,
     /// Column width in characters (None = auto-distributed)
+    #[serde(default)]
     pub width: Option<u16>,
+    /// Panels shown as tabs in one header row instead of an accordion
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tabs: bool,
 }
 
 /// Panel data for serialization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum SessionPanel {
     /// File manager panel
@@ -38,6 +62,9 @@ pub enum SessionPanel {
     FileManager {
         /// Current directory path
         path: PathBuf,
+        /// View settings of recently visited directories, oldest first
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        views: Vec<SessionDirView>,
     },
     /// Text editor panel
     #[serde(rename = "editor")]
@@ -47,19 +74,66 @@ pub enum SessionPanel {
         /// Temporary file name for unsaved buffers (format: unsaved-YYYYMMDD-HHIISS-MSEC.txt)
         #[serde(skip_serializing_if = "Option::is_none")]
         unsaved_buffer_file: Option<String>,
+        /// Syntax highlighting language picked by the user
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        syntax: Option<String>,
     },
     /// Terminal panel
     #[serde(rename = "terminal")]
     Terminal {
         /// Working directory
         working_dir: PathBuf,
+        /// Program and arguments run instead of the shell
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        command: Option<Vec<String>>,
     },
     /// Debug log panel
     #[serde(rename = "debug")]
     Debug,
+    /// Log viewer following a file
+    #[serde(rename = "log_file")]
+    LogFile {
+        /// Followed log file
+        path: PathBuf,
+    },
+    /// Git panel
+    #[serde(rename = "git")]
+    Git {
+        /// Directory the panel was opened in
+        path: PathBuf,
+    },
+    /// Panel of a type registered at startup, created again by its
+    /// registered constructor
+    #[serde(rename = "custom")]
+    Custom {
+        /// Registered panel type name
+        name: String,
+        /// Parameters passed to the constructor
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        params: BTreeMap<String, String>,
+    },
     // Note: Welcome panels are NOT saved (they auto-close)
 }
 
+/// View settings a file manager remembers for a directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionDirView {
+    /// Directory path
+    pub path: PathBuf,
+    /// Sort order chosen in the directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<FileSort>,
+    /// Hidden files toggle chosen in the directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_hidden: Option<bool>,
+    /// Name of the entry under the cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Index of the first visible entry
+    #[serde(default)]
+    pub scroll_offset: usize,
+}
+
 /// Get the data directory for termide.
 fn get_data_dir() -> Result<PathBuf> {
     dirs::data_dir()
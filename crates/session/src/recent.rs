@@ -0,0 +1,160 @@
+//! Recently opened files and projects.
+//!
+//! Shared by all projects and stored in the data directory
+//! (`~/.local/share/termide/recent.toml`), most recent first.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::get_data_dir;
+
+/// Maximum number of entries kept in each list
+const MAX_RECENT: usize = 20;
+
+/// Most recently used files and project directories
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecentPaths {
+    /// Files opened in the editor
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    /// Working directories termide was started in or opened from the Welcome panel
+    #[serde(default)]
+    pub projects: Vec<PathBuf>,
+}
+
+impl RecentPaths {
+    /// Get the path to the recent.toml file
+    pub fn get_path() -> Result<PathBuf> {
+        Ok(get_data_dir()?.join("recent.toml"))
+    }
+
+    /// Load the lists, empty if the file is missing or corrupted
+    pub fn load() -> Self {
+        Self::get_path()
+            .and_then(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    /// Load the lists from a file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read recent file: {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse recent file: {}", path.display()))
+    }
+
+    /// Save the lists to the data directory
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::get_path()?)
+    }
+
+    /// Save the lists to a file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create data directory: {}", parent.display())
+            })?;
+        }
+        let contents = toml::to_string_pretty(self).context("Failed to serialize recent paths")?;
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write recent file: {}", path.display()))
+    }
+
+    /// Move a file to the top of the recent files
+    pub fn add_file(&mut self, path: &Path) {
+        push_recent(&mut self.files, path);
+    }
+
+    /// Move a directory to the top of the recent projects
+    pub fn add_project(&mut self, path: &Path) {
+        push_recent(&mut self.projects, path);
+    }
+
+    /// Remove entries whose paths no longer exist.
+    ///
+    /// Returns true if anything was removed.
+    pub fn prune_missing(&mut self) -> bool {
+        let before = self.files.len() + self.projects.len();
+        self.files.retain(|path| path.is_file());
+        self.projects.retain(|path| path.is_dir());
+        self.files.len() + self.projects.len() != before
+    }
+}
+
+/// Put `path` first (as an absolute path), dropping an older entry for it
+/// and entries past the cap
+fn push_recent(list: &mut Vec<PathBuf>, path: &Path) {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    list.retain(|entry| *entry != path);
+    list.insert(0, path);
+    list.truncate(MAX_RECENT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_recent_first_without_duplicates() {
+        let mut recent = RecentPaths::default();
+        recent.add_file(Path::new("/nonexistent/a.rs"));
+        recent.add_file(Path::new("/nonexistent/b.rs"));
+        recent.add_file(Path::new("/nonexistent/a.rs"));
+        recent.add_project(Path::new("/nonexistent/project"));
+
+        assert_eq!(
+            recent.files,
+            vec![
+                PathBuf::from("/nonexistent/a.rs"),
+                PathBuf::from("/nonexistent/b.rs")
+            ]
+        );
+        assert_eq!(recent.projects, vec![PathBuf::from("/nonexistent/project")]);
+    }
+
+    #[test]
+    fn test_capped() {
+        let mut recent = RecentPaths::default();
+        for i in 0..MAX_RECENT + 5 {
+            recent.add_file(&PathBuf::from(format!("/nonexistent/{}.txt", i)));
+        }
+
+        assert_eq!(recent.files.len(), MAX_RECENT);
+        assert_eq!(
+            recent.files[0],
+            PathBuf::from(format!("/nonexistent/{}.txt", MAX_RECENT + 4))
+        );
+        assert_eq!(
+            recent.files[MAX_RECENT - 1],
+            PathBuf::from("/nonexistent/5.txt")
+        );
+    }
+
+    #[test]
+    fn test_prune_and_round_trip() {
+        let dir = std::env::temp_dir().join(format!("termide-recent-{}", std::process::id()));
+        let file = dir.join("kept.txt");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&file, "").unwrap();
+
+        let mut recent = RecentPaths::default();
+        recent.add_file(&file);
+        recent.add_file(Path::new("/nonexistent/gone.txt"));
+        recent.add_project(&dir);
+        // A file is not a project
+        recent.add_project(&file);
+
+        assert!(recent.prune_missing());
+        assert_eq!(recent.files, vec![file.canonicalize().unwrap()]);
+        assert_eq!(recent.projects, vec![dir.canonicalize().unwrap()]);
+        assert!(!recent.prune_missing());
+
+        let path = dir.join("recent.toml");
+        recent.save_to(&path).unwrap();
+        assert_eq!(RecentPaths::load_from(&path).unwrap(), recent);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+//! Recovery copies of editor buffers with unsaved changes.
+//!
+//! While termide runs, the text of modified buffers is copied to the
+//! recovery directory (`~/.local/share/termide/recovery/`) after a pause in
+//! typing. Each copy is a text file next to a TOML file describing it
+//! (original path, title, time and the process that wrote it). Copies are
+//! removed when their buffer is saved or closed and when termide exits, so
+//! the ones left by a process that is no longer running are changes lost
+//! in a crash, offered for restore on the next start.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::get_data_dir;
+
+/// Copies named by this process so far (keeps names unique)
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Description of a recovery copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryFile {
+    /// Name of the copy in the recovery directory (without extension)
+    #[serde(skip)]
+    pub id: String,
+    /// File the buffer was opened from (None for unnamed buffers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Editor title
+    pub title: String,
+    /// When the copy was written
+    pub saved_at: DateTime<Local>,
+    /// Process that wrote the copy
+    pub pid: u32,
+}
+
+impl RecoveryFile {
+    /// Describe a copy written now by this process
+    pub fn new(id: &str, path: Option<PathBuf>, title: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            path,
+            title: title.to_string(),
+            saved_at: Local::now(),
+            pid: std::process::id(),
+        }
+    }
+
+    /// Get the recovery directory
+    pub fn dir() -> Result<PathBuf> {
+        Ok(get_data_dir()?.join("recovery"))
+    }
+
+    /// Generate a unique name for a new copy
+    ///
+    /// Format: YYYYMMDD-HHMMSS-PID-N
+    pub fn new_id() -> String {
+        format!(
+            "{}-{}-{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// Write the copy with its description to `dir`
+    ///
+    /// Both files are written under temporary names and renamed into place,
+    /// text first, so a crash while writing leaves the previous copy intact.
+    pub fn write(&self, dir: &Path, text: &str) -> Result<()> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create recovery directory: {}", dir.display()))?;
+        let description = toml::to_string_pretty(self).context("Failed to serialize recovery")?;
+        write_atomic(&dir.join(format!("{}.txt", self.id)), text.as_bytes())?;
+        write_atomic(
+            &dir.join(format!("{}.toml", self.id)),
+            description.as_bytes(),
+        )
+    }
+
+    /// Read the text of the copy
+    pub fn read_text(&self, dir: &Path) -> Result<String> {
+        let path = dir.join(format!("{}.txt", self.id));
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read recovery file: {}", path.display()))
+    }
+
+    /// Load the description of a copy
+    pub fn load(dir: &Path, id: &str) -> Result<Self> {
+        let path = dir.join(format!("{}.toml", id));
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read recovery file: {}", path.display()))?;
+        let mut file: Self = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse recovery file: {}", path.display()))?;
+        file.id = id.to_string();
+        Ok(file)
+    }
+
+    /// Delete a copy and its description
+    pub fn remove(dir: &Path, id: &str) -> Result<()> {
+        for extension in ["toml", "txt"] {
+            let path = dir.join(format!("{}.{}", id, extension));
+            if path.exists() {
+                fs::remove_file(&path).with_context(|| {
+                    format!("Failed to delete recovery file: {}", path.display())
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Descriptions of the copies in `dir`, oldest first (unreadable ones
+    /// are skipped)
+    pub fn list(dir: &Path) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<Self> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != "toml" {
+                    return None;
+                }
+                let id = path.file_stem()?.to_str()?;
+                Self::load(dir, id).ok()
+            })
+            .collect();
+        files.sort_by_key(|file| file.saved_at);
+        files
+    }
+
+    /// Check if the process that wrote the copy is gone. Copies carrying the
+    /// id of this process are from an earlier one (it writes none before
+    /// the startup check)
+    pub fn is_orphaned(&self) -> bool {
+        self.pid == std::process::id() || !process_running(self.pid)
+    }
+
+    /// Check if the copy was written after the file was last modified
+    /// (always for unnamed buffers and files that no longer exist)
+    pub fn is_newer_than_file(&self) -> bool {
+        let Some(path) = &self.path else {
+            return true;
+        };
+        match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => DateTime::<Local>::from(modified) < self.saved_at,
+            Err(_) => true,
+        }
+    }
+}
+
+/// Copies left by termide processes that are no longer running and that
+/// still hold changes: copies older than their file are deleted
+pub fn orphaned_recovery_files(dir: &Path) -> Vec<RecoveryFile> {
+    RecoveryFile::list(dir)
+        .into_iter()
+        .filter(RecoveryFile::is_orphaned)
+        .filter(|file| {
+            let newer = file.is_newer_than_file();
+            if !newer {
+                let _ = RecoveryFile::remove(dir, &file.id);
+            }
+            newer
+        })
+        .collect()
+}
+
+/// Write a file under a temporary name and rename it into place
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents)
+        .with_context(|| format!("Failed to write recovery file: {}", temp.display()))?;
+    fs::rename(&temp, path)
+        .with_context(|| format!("Failed to write recovery file: {}", path.display()))
+}
+
+/// Check if a process is running
+#[cfg(unix)]
+fn process_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the process exists
+    let result = unsafe { libc::kill(pid, 0) };
+    // EPERM: the process exists but belongs to another user
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Check if a process is running (assumed gone where it cannot be checked)
+#[cfg(not(unix))]
+fn process_running(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_list_recovery_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = RecoveryFile::new(&RecoveryFile::new_id(), None, "notes");
+        file.write(dir.path(), "first").unwrap();
+        file.write(dir.path(), "second").unwrap();
+
+        let listed = RecoveryFile::list(dir.path());
+        assert_eq!(listed, vec![file.clone()]);
+        assert_eq!(listed[0].read_text(dir.path()).unwrap(), "second");
+        // No temporary files are left
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        RecoveryFile::remove(dir.path(), &file.id).unwrap();
+        assert!(RecoveryFile::list(dir.path()).is_empty());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_orphaned_recovery_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let edited = dir.path().join("edited.rs");
+        fs::write(&edited, "fn main() {}\n").unwrap();
+
+        // Written by a crashed termide after the file was saved
+        let mut newer = RecoveryFile::new("newer", Some(edited.clone()), "edited.rs");
+        newer.saved_at = Local::now() + chrono::Duration::hours(1);
+        newer.write(dir.path(), "fn main() { todo!() }\n").unwrap();
+        // The file was saved again after the copy was written
+        let mut older = RecoveryFile::new("older", Some(edited), "edited.rs");
+        older.saved_at = Local::now() - chrono::Duration::hours(1);
+        older.write(dir.path(), "fn old() {}\n").unwrap();
+        // Unnamed buffer of a running termide
+        let mut running = RecoveryFile::new("running", None, "untitled");
+        running.pid = std::os::unix::process::parent_id();
+        running.write(dir.path(), "still edited").unwrap();
+
+        let orphaned = orphaned_recovery_files(dir.path());
+        assert_eq!(orphaned, vec![newer]);
+        assert!(RecoveryFile::load(dir.path(), "older").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Spell checking for termide.
+//!
+//! Words are checked against plain word lists (one word per line, as in
+//! `/usr/share/dict/words`) or hunspell `.dic` files, whose affix flags are
+//! ignored; common suffixes are stripped instead. A bundled list of
+//! programming terms and the user dictionary are always included.
+//!
+//! The word lists are read the first time a word is checked, so editors
+//! without spell checking never pay for loading them.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+mod words;
+
+pub use words::checked_words;
+
+/// Programming terms common in comments and documentation
+const PROGRAMMING_WORDS: &str = include_str!("../words/programming.txt");
+
+/// Maximum edit distance of a suggestion
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Suffixes stripped from unknown words, with the endings of the stems to
+/// try instead (`tries` -> `try`, `named` -> `name`)
+const SUFFIXES: &[(&str, &[&str])] = &[
+    ("'s", &[""]),
+    ("ies", &["y"]),
+    ("ied", &["y"]),
+    ("es", &[""]),
+    ("s", &[""]),
+    ("ed", &["", "e"]),
+    ("d", &[""]),
+    ("ing", &["", "e"]),
+    ("ly", &[""]),
+    ("er", &["", "e"]),
+    ("est", &["", "e"]),
+];
+
+/// Words loaded from the dictionary files
+struct WordList {
+    words: HashSet<String>,
+    /// A system dictionary was found (without one every word is unknown)
+    available: bool,
+}
+
+/// Spelling dictionary shared by the editors.
+pub struct Dictionary {
+    /// Word lists and hunspell dictionaries (missing files are skipped)
+    paths: Vec<PathBuf>,
+    /// Words added by the user, one per line
+    user_path: Option<PathBuf>,
+    words: OnceLock<RwLock<WordList>>,
+    /// Incremented when a word is added, so checked text is checked again
+    generation: AtomicU64,
+}
+
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dictionary")
+            .field("paths", &self.paths)
+            .field("user_path", &self.user_path)
+            .field("loaded", &self.words.get().is_some())
+            .finish()
+    }
+}
+
+impl Dictionary {
+    /// Dictionary of the word lists at `paths` and the user dictionary at
+    /// `user_path`. Nothing is read until a word is checked
+    pub fn new(paths: Vec<PathBuf>, user_path: Option<PathBuf>) -> Self {
+        Self {
+            paths,
+            user_path,
+            words: OnceLock::new(),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Word lists the dictionary reads
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Check if a system dictionary was found (loads the dictionary)
+    pub fn is_available(&self) -> bool {
+        self.read().available
+    }
+
+    /// Changes when words are added to the dictionary
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Check if a word (or its stem) is in the dictionary, ignoring case
+    pub fn is_known(&self, word: &str) -> bool {
+        is_known(&self.read().words, &normalize(word))
+    }
+
+    /// Byte ranges of the unknown words of `text` (none if no system
+    /// dictionary is available)
+    pub fn misspelled(&self, text: &str) -> Vec<Range<usize>> {
+        let list = self.read();
+        if !list.available {
+            return Vec::new();
+        }
+        checked_words(text)
+            .into_iter()
+            .filter(|range| !is_known(&list.words, &normalize(&text[range.clone()])))
+            .collect()
+    }
+
+    /// Known words closest to `word` (at most `limit`), capitalized like it
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let word = normalize(word);
+        let target: Vec<char> = word.chars().collect();
+        let list = self.read();
+        let mut candidates: Vec<(usize, usize, &str)> = list
+            .words
+            .iter()
+            .filter_map(|candidate| {
+                let len_diff = candidate.chars().count().abs_diff(target.len());
+                if len_diff > MAX_SUGGESTION_DISTANCE || *candidate == word {
+                    return None;
+                }
+                let distance = edit_distance(&target, candidate);
+                (distance <= MAX_SUGGESTION_DISTANCE).then_some((
+                    distance,
+                    len_diff,
+                    candidate.as_str(),
+                ))
+            })
+            .collect();
+        candidates.sort_unstable();
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, candidate)| {
+                if capitalized {
+                    capitalize(candidate)
+                } else {
+                    candidate.to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Add a word to the user dictionary
+    pub fn add_word(&self, word: &str) -> io::Result<()> {
+        let word = word.trim();
+        if let Some(path) = &self.user_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", word)?;
+        }
+        if let Ok(mut list) = self.words().write() {
+            list.words.insert(normalize(word));
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn words(&self) -> &RwLock<WordList> {
+        self.words.get_or_init(|| RwLock::new(self.load()))
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, WordList> {
+        self.words()
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Read the word lists
+    fn load(&self) -> WordList {
+        let mut words = HashSet::new();
+        let mut available = false;
+        for path in &self.paths {
+            available |= read_words(path, &mut words);
+        }
+        add_words(PROGRAMMING_WORDS, &mut words);
+        if let Some(path) = &self.user_path {
+            read_words(path, &mut words);
+        }
+        WordList { words, available }
+    }
+}
+
+/// Add the words of a file; false if it cannot be read or has no words
+fn read_words(path: &Path, words: &mut HashSet<String>) -> bool {
+    let Ok(bytes) = std::fs::read(path) else {
+        return false;
+    };
+    let count = words.len();
+    add_words(&String::from_utf8_lossy(&bytes), words);
+    words.len() > count
+}
+
+/// Add the words of a word list or hunspell dictionary (the word count
+/// line and affix flags after `/` are skipped)
+fn add_words(text: &str, words: &mut HashSet<String>) {
+    for line in text.lines() {
+        let word = line.split('/').next().unwrap_or_default().trim();
+        if word.is_empty() || word.starts_with('#') || word.chars().all(|ch| ch.is_ascii_digit()) {
+            continue;
+        }
+        words.insert(normalize(word));
+    }
+}
+
+/// Lower case with typographic apostrophes replaced
+fn normalize(word: &str) -> String {
+    word.to_lowercase().replace('’', "'")
+}
+
+/// Check a normalized word, trying stems of known suffixes
+fn is_known(words: &HashSet<String>, word: &str) -> bool {
+    if words.contains(word) {
+        return true;
+    }
+    SUFFIXES.iter().any(|(suffix, endings)| {
+        let Some(stem) = word.strip_suffix(suffix).filter(|stem| stem.len() > 1) else {
+            return false;
+        };
+        endings
+            .iter()
+            .any(|ending| words.contains(&format!("{}{}", stem, ending)))
+            || undoubled(stem).is_some_and(|stem| words.contains(stem))
+    })
+}
+
+/// Stem without a doubled last letter (`stopp` -> `stop`)
+fn undoubled(stem: &str) -> Option<&str> {
+    let mut chars = stem.chars().rev();
+    let last = chars.next()?;
+    (chars.next() == Some(last)).then(|| &stem[..stem.len() - last.len_utf8()])
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Optimal string alignment distance (edits and swaps of adjacent letters)
+fn edit_distance(a: &[char], b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(dir: &Path, words: &str) -> Dictionary {
+        let path = dir.join("words");
+        std::fs::write(&path, words).unwrap();
+        Dictionary::new(vec![path], Some(dir.join("user").join("dictionary.txt")))
+    }
+
+    #[test]
+    fn test_known_words_and_stems() {
+        let dir = tempfile::tempdir().unwrap();
+        let dict = dictionary(dir.path(), "apple\nname\ntry\nstop\nquick\n");
+        for word in [
+            "Apple", "apples", "named", "tries", "stopped", "quickly", "regex",
+        ] {
+            assert!(dict.is_known(word), "{}", word);
+        }
+        assert!(!dict.is_known("aple"));
+        assert_eq!(
+            dict.misspelled("Quick aple, named \"bananna\"."),
+            [6..10, 19..26]
+        );
+    }
+
+    #[test]
+    fn test_hunspell_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let dict = dictionary(dir.path(), "3\nhello/MS\nworld/M\nfriend\n");
+        assert!(dict.is_known("hello"));
+        assert!(dict.is_known("worlds"));
+        assert!(!dict.is_known("3"));
+    }
+
+    #[test]
+    fn test_missing_dictionary_reports_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let dict = Dictionary::new(vec![dir.path().join("missing")], None);
+        assert!(dict.words.get().is_none());
+        assert!(dict.misspelled("qwzxv plonk").is_empty());
+        assert!(!dict.is_available());
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let dir = tempfile::tempdir().unwrap();
+        let dict = dictionary(dir.path(), "receive\nrecipe\nrelieve\nbanana\n");
+        assert_eq!(
+            dict.suggestions("recieve", 5),
+            ["receive", "relieve", "recipe"]
+        );
+        assert_eq!(dict.suggestions("recieve", 1), ["receive"]);
+        assert_eq!(dict.suggestions("Bananna", 5), ["Banana"]);
+        assert!(dict.suggestions("xyzzy", 5).is_empty());
+    }
+
+    #[test]
+    fn test_added_words_are_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let dict = dictionary(dir.path(), "hello\n");
+        let generation = dict.generation();
+        assert_eq!(dict.misspelled("hello termide"), Vec::<Range<usize>>::new());
+        let unknown = dict.misspelled("hello frobnicate");
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0], 6..16);
+
+        dict.add_word("frobnicate").unwrap();
+        assert_ne!(dict.generation(), generation);
+        assert!(dict.misspelled("hello frobnicate").is_empty());
+
+        let reloaded = dictionary(dir.path(), "hello\n");
+        assert!(reloaded.is_known("Frobnicate"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Words of a text worth checking.
+//!
+//! Comments and strings mix prose with code: identifiers, paths, URLs,
+//! format placeholders. Only plain words are checked; anything that looks
+//! like code is skipped rather than reported as misspelled.
+
+use std::ops::Range;
+
+/// Words shorter than this are not checked (abbreviations, short names)
+const MIN_WORD_CHARS: usize = 3;
+
+/// Characters that make a whitespace-separated chunk look like code
+/// (paths, URLs, identifiers, e-mail addresses, placeholders)
+const CODE_CHARS: &[char] = &[
+    '/', '\\', '_', '@', '=', '<', '>', '{', '}', '$', '~', '^', '&',
+];
+
+/// Byte ranges of the words of `text` to check.
+///
+/// Text in backticks is skipped, as are chunks containing code characters
+/// and words next to digits, dotted or `::` paths, calls (`name(`) and
+/// words with capitals after the first letter (camelCase, ALLCAPS).
+pub fn checked_words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut offset = 0;
+    // Pieces between backticks alternate between prose and code
+    for (idx, piece) in text.split('`').enumerate() {
+        if idx % 2 == 0 {
+            let mut chunk_start = None;
+            for (pos, ch) in piece.char_indices().chain([(piece.len(), ' ')]) {
+                if ch.is_whitespace() {
+                    if let Some(start) = chunk_start.take() {
+                        let base = offset + start;
+                        words.extend(
+                            chunk_words(&piece[start..pos])
+                                .into_iter()
+                                .map(|word| base + word.start..base + word.end),
+                        );
+                    }
+                } else if chunk_start.is_none() {
+                    chunk_start = Some(pos);
+                }
+            }
+        }
+        offset += piece.len() + 1;
+    }
+    words
+}
+
+/// Words of a chunk of text without whitespace
+fn chunk_words(chunk: &str) -> Vec<Range<usize>> {
+    if chunk.contains(CODE_CHARS) {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = chunk.char_indices().collect();
+    let is_apostrophe = |ch: char| ch == '\'' || ch == '’';
+
+    let mut words = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        if !chars[idx].1.is_alphabetic() {
+            idx += 1;
+            continue;
+        }
+        // Letters, and apostrophes between letters (don't, it's)
+        let start = idx;
+        while idx < chars.len()
+            && (chars[idx].1.is_alphabetic()
+                || (is_apostrophe(chars[idx].1)
+                    && chars.get(idx + 1).is_some_and(|(_, ch)| ch.is_alphabetic())))
+        {
+            idx += 1;
+        }
+        let prev = start.checked_sub(1).map(|prev| chars[prev].1);
+        let next = chars.get(idx).map(|&(_, ch)| ch);
+        let after_next = chars.get(idx + 1).map(|&(_, ch)| ch);
+
+        let touches_digit =
+            prev.is_some_and(|ch| ch.is_numeric()) || next.is_some_and(|ch| ch.is_numeric());
+        let in_path = matches!(prev, Some('.' | ':'))
+            || (matches!(next, Some('.' | ':'))
+                && after_next.is_some_and(|ch| ch.is_alphanumeric() || ch == ':'));
+        let is_call = next == Some('(');
+        let has_inner_capital = chars[start + 1..idx]
+            .iter()
+            .any(|(_, ch)| ch.is_uppercase());
+
+        if idx - start >= MIN_WORD_CHARS
+            && !touches_digit
+            && !in_path
+            && !is_call
+            && !has_inner_capital
+        {
+            let end = chars.get(idx).map_or(chunk.len(), |&(pos, _)| pos);
+            words.push(chars[start].0..end);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        checked_words(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_prose_words() {
+        assert_eq!(
+            words("// Don't parse the header's \"fields\" twice."),
+            ["Don't", "parse", "the", "header's", "fields", "twice"]
+        );
+        assert_eq!(words("Привет, мир"), ["Привет", "мир"]);
+    }
+
+    #[test]
+    fn test_code_is_skipped() {
+        assert_eq!(
+            words("call `fooo bar` or parse_args() in src/main.rs"),
+            ["call"]
+        );
+        assert_eq!(
+            words("see https://example.com or user@example.com"),
+            ["see"]
+        );
+        assert_eq!(
+            words("std::io and self.buffer use utf8 via getLine(), HTTP and x86"),
+            ["and", "use", "via", "and"]
+        );
+        assert_eq!(words("Saved {file} as $name"), ["Saved"]);
+    }
+}
@@ -5,14 +5,139 @@
 
 use chrono::{DateTime, Local};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::SystemTime;
 
+pub mod notification;
+
+pub use notification::{Notification, NotificationLevel, Notifications};
+
 /// Message about background directory size calculation result
 #[derive(Debug)]
 pub struct DirSizeResult {
     pub size: u64,
 }
 
+/// Progress of a background file checksum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumUpdate {
+    /// Bytes hashed so far of the file size
+    Progress { done: u64, total: u64 },
+    /// Hex digest, or the error that stopped the read
+    Finished(Result<String, String>),
+}
+
+/// Change picked in the permissions dialog. Only the bits the user
+/// changed are applied, so each item keeps the rest of its mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionsChange {
+    /// Mode bits to turn on (including setuid, setgid and sticky)
+    pub set: u32,
+    /// Mode bits to turn off
+    pub clear: u32,
+    /// New owner name
+    pub owner: Option<String>,
+    /// New group name
+    pub group: Option<String>,
+    /// Also change everything inside directories
+    pub recursive: bool,
+}
+
+impl PermissionsChange {
+    /// Whether applying the change would do nothing
+    pub fn is_empty(&self) -> bool {
+        self.set == 0 && self.clear == 0 && self.owner.is_none() && self.group.is_none()
+    }
+}
+
+/// Outcome of applying a permissions change
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionsReport {
+    /// Items changed
+    pub changed: usize,
+    /// Items left unchanged, with the reason
+    pub failures: Vec<(PathBuf, String)>,
+    /// Stopped on cancellation request, leaving the rest as it was
+    pub cancelled: bool,
+}
+
+impl PermissionsReport {
+    /// Items the change was tried on
+    pub fn total(&self) -> usize {
+        self.changed + self.failures.len()
+    }
+}
+
+/// Progress update streamed from a background permissions worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionsProgressUpdate {
+    /// Estimated number of items to change (sent once, before changing starts)
+    Total(usize),
+    /// Items changed so far and the path being changed
+    Changed { count: usize, current: PathBuf },
+    /// Change ended
+    Finished(PermissionsReport),
+}
+
+/// Progress update streamed from a background file transfer worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchProgressUpdate {
+    /// Total size of all batch sources (sent once, before copying starts)
+    TotalBytes(u64),
+    /// Bytes copied since the previous update
+    Bytes(u64),
+    /// Current item finished (error message on failure)
+    Finished(Result<(), String>),
+    /// Current item stopped on cancellation request (partial output removed)
+    Cancelled,
+}
+
+/// Progress update streamed from a background deletion worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeleteProgressUpdate {
+    /// Estimated number of entries to remove (sent once, before removing starts)
+    Total(usize),
+    /// Entries removed so far and the path being removed
+    Removed { count: usize, current: PathBuf },
+    /// Deletion ended
+    Finished(DeleteSummary),
+}
+
+/// Outcome of a background deletion
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeleteSummary {
+    /// Paths (of the ones asked for) removed completely
+    pub deleted: usize,
+    /// Entries removed at any depth (files, links and directories)
+    pub removed: usize,
+    /// Entries that could not be removed, with the error
+    pub errors: Vec<(PathBuf, String)>,
+    /// Stopped on cancellation request, leaving the rest in place
+    pub cancelled: bool,
+}
+
+/// Shared flag for requesting cancellation of a background operation
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create new (not cancelled) token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 /// Batch operation type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BatchOperationType {
@@ -236,6 +361,14 @@ pub struct BatchOperation {
     pub error_count: usize,
     /// Statistics: skipped
     pub skipped_count: usize,
+    /// Bytes copied so far
+    pub bytes_done: u64,
+    /// Total bytes of all sources (None until scanned)
+    pub bytes_total: Option<u64>,
+    /// Operation was stopped by user (statistics are partial)
+    pub cancelled: bool,
+    /// Token shared with transfer workers to request cancellation
+    pub cancel_token: CancellationToken,
 }
 
 impl BatchOperation {
@@ -256,6 +389,10 @@ impl BatchOperation {
             success_count: 0,
             error_count: 0,
             skipped_count: 0,
+            bytes_done: 0,
+            bytes_total: None,
+            cancelled: false,
+            cancel_token: CancellationToken::new(),
         }
     }
 
@@ -276,9 +413,9 @@ impl BatchOperation {
         self.sources.get(self.current_index)
     }
 
-    /// Check if operation is complete
+    /// Check if operation is complete (all files processed or cancelled)
     pub fn is_complete(&self) -> bool {
-        self.current_index >= self.sources.len()
+        self.cancelled || self.current_index >= self.sources.len()
     }
 
     /// Advance to next file
@@ -310,6 +447,42 @@ impl BatchOperation {
     pub fn increment_skipped(&mut self) {
         self.skipped_count += 1;
     }
+
+    /// Add copied bytes to progress
+    pub fn add_bytes(&mut self, bytes: u64) {
+        self.bytes_done += bytes;
+    }
+
+    /// Set total bytes of all sources
+    pub fn set_bytes_total(&mut self, bytes: u64) {
+        self.bytes_total = Some(bytes);
+    }
+
+    /// Ask running transfer to stop after the current chunk
+    pub fn request_cancel(&self) {
+        self.cancel_token.cancel();
+    }
+
+    /// Check if cancellation was requested
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_token.is_cancelled()
+    }
+
+    /// Mark operation as cancelled (remaining files are not processed)
+    pub fn mark_cancelled(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+/// Question of the config wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigWizardStep {
+    /// Replace the existing config file (asked before the questions)
+    Overwrite,
+    Theme,
+    Shell,
+    TabSize,
+    MouseCapture,
 }
 
 /// Action pending modal result
@@ -325,11 +498,23 @@ pub enum PendingAction {
         panel_index: usize,
         directory: PathBuf,
     },
+    /// Create symlink pointing to target (link path entered in modal)
+    CreateSymlink { panel_index: usize, target: PathBuf },
     /// Delete files/directories (one or multiple)
     DeletePath {
         panel_index: usize,
         paths: Vec<PathBuf>,
     },
+    /// Move files/directories to trash (one or multiple)
+    TrashPath {
+        panel_index: usize,
+        paths: Vec<PathBuf>,
+    },
+    /// Restore items picked from trash (paths to their `.trashinfo` files)
+    RestoreFromTrash {
+        panel_index: usize,
+        entries: Vec<PathBuf>,
+    },
     /// Copy files/directories (one or multiple)
     CopyPath {
         panel_index: usize,
@@ -342,6 +527,19 @@ pub enum PendingAction {
         sources: Vec<PathBuf>,
         target_directory: Option<PathBuf>,
     },
+    /// Change permissions and ownership (picked in the permissions modal)
+    ChangePermissions {
+        panel_index: usize,
+        paths: Vec<PathBuf>,
+    },
+    /// Recursive permissions change waiting for confirmation
+    ChangePermissionsRecursively {
+        panel_index: usize,
+        paths: Vec<PathBuf>,
+        change: PermissionsChange,
+    },
+    /// Compare a directory with another (entered in modal)
+    CompareDirectory { left: PathBuf },
     /// Save unnamed file (Save As)
     SaveFileAs {
         panel_index: usize,
@@ -351,10 +549,15 @@ pub enum PendingAction {
     ClosePanel { panel_index: usize },
     /// Close editor with choice: save, don't save, cancel
     CloseEditorWithSave { panel_index: usize },
+    /// Unsaved changes shown before deciding; closing the diff asks again
+    ReviewUnsavedChanges { panel_index: usize },
     /// Close editor with external changes (file changed on disk)
     CloseEditorExternal { panel_index: usize },
     /// Close editor with conflict (local changes + external changes)
     CloseEditorConflict { panel_index: usize },
+    /// File open in an editor with unsaved edits changed on disk:
+    /// keep local edits, reload the file or show the diff
+    ResolveExternalChange { path: PathBuf },
     /// File overwrite decision when copying/moving
     #[allow(dead_code)]
     OverwriteDecision {
@@ -373,16 +576,82 @@ pub enum PendingAction {
         operation: BatchOperation,
         original_name: String,
     },
+    /// Open files whose paths were pasted into an editor, or insert the
+    /// pasted text
+    OpenDroppedFiles { paths: Vec<PathBuf>, text: String },
+    /// Create paths given on the command line that do not exist, asking
+    /// about the first (path, whether it is a directory)
+    CreateMissingPaths { paths: Vec<(PathBuf, bool)> },
+    /// Pick one of the buffers recovered after a crash (names of their
+    /// recovery copies)
+    RecoverBuffers { ids: Vec<String> },
+    /// Restore, compare or discard the recovered buffer `ids[index]`
+    RecoverBuffer { ids: Vec<String>, index: usize },
     /// Text search in editor
     Search,
     /// Text replace in editor
     Replace,
+    /// Pick the syntax highlighting language of the active editor
+    /// (language name of each option)
+    SelectSyntax { languages: Vec<String> },
+    /// Reopen or convert the active editor's file in another encoding
+    /// (encoding label of each option)
+    SelectEncoding { encodings: Vec<String> },
+    /// Switch the active editor's text to another undo branch
+    /// (undo tree node id of each option)
+    SwitchUndoBranch { branches: Vec<usize> },
+    /// Replace the misspelled `word` at a position of the active editor
+    /// with the picked suggestion (the option after the suggestions adds
+    /// the word to the user dictionary)
+    CorrectSpelling {
+        line: usize,
+        column: usize,
+        word: String,
+        suggestions: Vec<String>,
+    },
+    /// Commit the staged changes of a repository with the entered message
+    GitCommit { repo_root: PathBuf },
+    /// Run a command picked in the command palette
+    /// (action name of each item)
+    RunCommand { commands: Vec<String> },
+    /// Open a file picked in the file finder (paths are relative to `root`)
+    OpenFoundFile { root: PathBuf },
+    /// Name a bookmark at `line` of `path`, whose text is `text`
+    SetBookmark {
+        path: PathBuf,
+        line: usize,
+        text: String,
+    },
+    /// Jump to or remove a bookmark picked in the bookmark list (index
+    /// into the bookmarks)
+    PickBookmark,
+    /// Run a task picked from the configured ones (task name of each option)
+    RunTask { names: Vec<String> },
+    /// Switch to the theme picked in the theme list (theme name of each
+    /// option). The theme under the cursor is previewed; `original` is
+    /// restored on cancel
+    SelectTheme {
+        names: Vec<String>,
+        original: String,
+    },
+    /// Answer a question of the config wizard, filling in `config` (value
+    /// of each option). The theme under the cursor is previewed;
+    /// `original_theme` is restored when the wizard is cancelled
+    ConfigWizard {
+        step: ConfigWizardStep,
+        config: Box<termide_config::Config>,
+        values: Vec<String>,
+        original_theme: String,
+    },
     /// Switch to next panel
     NextPanel,
     /// Switch to previous panel
     PrevPanel,
     /// Quit application (with confirmation if there are unsaved changes)
     QuitApplication,
+    /// Quit after the choices for panels with unsaved changes or running
+    /// processes (positions as (group, panel), in the order of the modal)
+    QuitWithChanges { panels: Vec<(usize, usize)> },
 }
 
 #[cfg(test)]
@@ -444,4 +713,26 @@ mod tests {
         assert_eq!(op.total_count(), 2);
         assert!(!op.is_complete());
     }
+
+    #[test]
+    fn test_batch_operation_cancel() {
+        let mut op = BatchOperation::new(
+            BatchOperationType::Copy,
+            vec![PathBuf::from("/a"), PathBuf::from("/b")],
+            PathBuf::from("/dest"),
+        );
+        let token = op.cancel_token.clone();
+        op.add_bytes(10);
+        op.increment_success();
+        op.advance();
+
+        token.cancel();
+        assert!(op.is_cancel_requested());
+        assert!(!op.is_complete());
+
+        op.mark_cancelled();
+        assert!(op.is_complete());
+        assert_eq!(op.success_count, 1);
+        assert_eq!(op.bytes_done, 10);
+    }
 }
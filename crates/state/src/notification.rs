@@ -0,0 +1,193 @@
+//! Notifications shown as toasts and kept in a history.
+//!
+//! Unlike the status bar message, which the next message replaces, each
+//! notification gets a toast of its own. Toasts of informational messages
+//! and warnings go away after a timeout; errors stay until dismissed. The
+//! last [`HISTORY_SIZE`] notifications are kept with the time they arrived.
+//!
+//! Notifications are pushed on the main loop: background workers report
+//! their results through their own channels, and the handler that picks a
+//! result up decides what to show.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+
+/// Notifications kept in the history
+pub const HISTORY_SIZE: usize = 100;
+
+/// How important a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotificationLevel {
+    #[default]
+    Info,
+    Warning,
+    /// Stays on screen until dismissed
+    Error,
+}
+
+/// One notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub text: String,
+    pub level: NotificationLevel,
+    /// When it arrived
+    pub time: DateTime<Local>,
+}
+
+/// Toast on screen
+#[derive(Debug, Clone)]
+struct Toast {
+    notification: Notification,
+    /// When the toast goes away (never for None)
+    expires: Option<Instant>,
+}
+
+/// Toasts on screen and the notification history
+#[derive(Debug)]
+pub struct Notifications {
+    /// Oldest first
+    toasts: Vec<Toast>,
+    /// Oldest first
+    history: VecDeque<Notification>,
+    /// Timeout of informational messages and warnings
+    timeout: Duration,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5))
+    }
+}
+
+impl Notifications {
+    /// Create with the timeout of informational messages and warnings
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            toasts: Vec::new(),
+            history: VecDeque::with_capacity(HISTORY_SIZE),
+            timeout,
+        }
+    }
+
+    /// Change the timeout of notifications to come
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Show a notification; `timeout` overrides the default of the level
+    /// (errors have none)
+    pub fn push(
+        &mut self,
+        text: impl Into<String>,
+        level: NotificationLevel,
+        timeout: Option<Duration>,
+        now: Instant,
+    ) {
+        let notification = Notification {
+            text: text.into(),
+            level,
+            time: Local::now(),
+        };
+        let timeout = match (timeout, level) {
+            (Some(timeout), _) => Some(timeout),
+            (None, NotificationLevel::Error) => None,
+            (None, _) => Some(self.timeout),
+        };
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(notification.clone());
+        if self.toasts.len() == HISTORY_SIZE {
+            self.toasts.remove(0);
+        }
+        self.toasts.push(Toast {
+            notification,
+            expires: timeout.map(|timeout| now + timeout),
+        });
+    }
+
+    /// Remove toasts whose time is up; true if any went away
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.toasts.len();
+        self.toasts
+            .retain(|toast| toast.expires.is_none_or(|expires| expires > now));
+        self.toasts.len() != before
+    }
+
+    /// When the next toast goes away
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.toasts.iter().filter_map(|toast| toast.expires).min()
+    }
+
+    /// Remove all toasts (the history stays); true if any was shown
+    pub fn dismiss(&mut self) -> bool {
+        let shown = !self.toasts.is_empty();
+        self.toasts.clear();
+        shown
+    }
+
+    /// Notifications with a toast on screen, newest first
+    pub fn toasts(&self) -> impl Iterator<Item = &Notification> {
+        self.toasts.iter().rev().map(|toast| &toast.notification)
+    }
+
+    /// Past notifications, newest first
+    pub fn history(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(notifications: impl Iterator<Item = &'a Notification>) -> Vec<&'a str> {
+        notifications.map(|n| n.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_info_fades_and_errors_stay() {
+        let start = Instant::now();
+        let mut notifications = Notifications::new(Duration::from_secs(5));
+        notifications.push("copied", NotificationLevel::Info, None, start);
+        notifications.push("fetch failed", NotificationLevel::Error, None, start);
+        notifications.push(
+            "quick",
+            NotificationLevel::Warning,
+            Some(Duration::from_secs(1)),
+            start,
+        );
+        assert_eq!(
+            texts(notifications.toasts()),
+            ["quick", "fetch failed", "copied"]
+        );
+
+        assert!(notifications.expire(start + Duration::from_secs(2)));
+        assert_eq!(texts(notifications.toasts()), ["fetch failed", "copied"]);
+        assert!(notifications.expire(start + Duration::from_secs(5)));
+        assert_eq!(texts(notifications.toasts()), ["fetch failed"]);
+        assert!(!notifications.expire(start + Duration::from_secs(3600)));
+
+        assert!(notifications.dismiss());
+        assert_eq!(notifications.toasts().count(), 0);
+        assert_eq!(
+            texts(notifications.history()),
+            ["quick", "fetch failed", "copied"]
+        );
+    }
+
+    #[test]
+    fn test_history_keeps_the_last() {
+        let now = Instant::now();
+        let mut notifications = Notifications::default();
+        for i in 0..HISTORY_SIZE + 5 {
+            notifications.push(i.to_string(), NotificationLevel::Info, None, now);
+        }
+        let history = texts(notifications.history());
+        assert_eq!(history.len(), HISTORY_SIZE);
+        assert_eq!(history[0], (HISTORY_SIZE + 4).to_string());
+        assert_eq!(history[HISTORY_SIZE - 1], "5");
+    }
+}
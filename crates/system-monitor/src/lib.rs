@@ -1,12 +1,19 @@
 //! System resource monitoring for termide.
 //!
-//! Provides CPU and memory usage information.
+//! Provides CPU and memory usage information, and the usage of process
+//! trees sampled on a background thread.
+
+mod processes;
+
+pub use processes::{
+    send_signal, MonitorSnapshot, ProcessMonitor, ProcessTree, ProcessUsage, Signal, TreeUsage,
+};
 
 use std::sync::{Arc, Mutex};
 use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
 
 /// System resource statistics.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct SystemStats {
     /// CPU usage percentage (0-100).
     pub cpu_usage: f32,
@@ -232,11 +239,7 @@ impl DiskSpaceInfoExt for DiskSpaceInfo {
 
         // Calculate used space and percentage
         let used = self.total.saturating_sub(self.available);
-        let percent = if self.total > 0 {
-            ((used * 100) / self.total).min(100)
-        } else {
-            0
-        };
+        let percent = (used * 100).checked_div(self.total).unwrap_or(0).min(100);
 
         // Convert to GB (rounded to nearest integer)
         let used_gb = (used as f64 / 1_073_741_824.0).round() as u64;
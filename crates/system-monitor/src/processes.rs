@@ -0,0 +1,356 @@
+//! Resource usage of process trees, sampled on a background thread.
+//!
+//! A tree is a root process (the shell of a terminal panel) with all of
+//! its descendants; its usage is the sum over them. Samples that differ
+//! from the last sent one by less than a delta are not sent, so an idle
+//! system does not wake up the UI.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+pub use sysinfo::Signal;
+
+use crate::SystemStats;
+
+/// How often the sampling thread checks whether it should stop
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Process tree to sample
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessTree {
+    /// Name shown for the tree (the panel title)
+    pub label: String,
+    /// Root process of the tree
+    pub root_pid: u32,
+}
+
+/// Resource usage of one process
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessUsage {
+    /// Process ID
+    pub pid: u32,
+    /// Process name
+    pub name: String,
+    /// CPU usage percentage (100 is one fully used core)
+    pub cpu_usage: f32,
+    /// Resident memory in bytes
+    pub memory: u64,
+    /// Depth below the root of the tree (0 for the root)
+    pub depth: usize,
+}
+
+/// Resource usage of a process tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeUsage {
+    /// Name shown for the tree
+    pub label: String,
+    /// Root process of the tree
+    pub root_pid: u32,
+    /// Processes of the tree, each parent followed by its children
+    /// (empty if the root process is gone)
+    pub processes: Vec<ProcessUsage>,
+}
+
+impl TreeUsage {
+    /// Total CPU usage percentage of the tree
+    pub fn cpu_usage(&self) -> f32 {
+        self.processes.iter().map(|p| p.cpu_usage).sum()
+    }
+
+    /// Total resident memory of the tree in bytes
+    pub fn memory(&self) -> u64 {
+        self.processes.iter().map(|p| p.memory).sum()
+    }
+}
+
+/// System and process tree usage at one point in time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MonitorSnapshot {
+    /// System-wide CPU and memory usage
+    pub stats: SystemStats,
+    /// Load average over 1, 5 and 15 minutes
+    pub load_average: [f64; 3],
+    /// Usage of the sampled trees, in the order they were given
+    pub trees: Vec<TreeUsage>,
+}
+
+impl MonitorSnapshot {
+    /// Check if the snapshot differs from another one worth a redraw.
+    ///
+    /// CPU and memory usage (as a share of total memory) must change by more
+    /// than `delta` percentage points; any process started or exited counts.
+    /// The load average changes slowly and is not compared.
+    pub fn differs_from(&self, other: &Self, delta: f32) -> bool {
+        let changed = |a: f32, b: f32| (a - b).abs() > delta;
+        let total = self.stats.memory_total.max(1) as f32;
+        let memory_percent = |memory: u64| memory as f32 / total * 100.0;
+
+        if changed(self.stats.cpu_usage, other.stats.cpu_usage)
+            || changed(self.stats.memory_percent(), other.stats.memory_percent())
+            || self.trees.len() != other.trees.len()
+        {
+            return true;
+        }
+        self.trees.iter().zip(&other.trees).any(|(tree, old)| {
+            tree.label != old.label
+                || tree.processes.len() != old.processes.len()
+                || tree.processes.iter().zip(&old.processes).any(|(p, q)| {
+                    p.pid != q.pid
+                        || changed(p.cpu_usage, q.cpu_usage)
+                        || changed(memory_percent(p.memory), memory_percent(q.memory))
+                })
+        })
+    }
+}
+
+/// Background thread sampling process trees.
+///
+/// The thread stops when the monitor is dropped.
+#[derive(Debug)]
+pub struct ProcessMonitor {
+    /// Trees sampled by the thread
+    trees: Arc<Mutex<Vec<ProcessTree>>>,
+    /// Snapshots that differ from the previous one
+    receiver: Receiver<MonitorSnapshot>,
+    /// Set to stop the thread
+    stop: Arc<AtomicBool>,
+}
+
+impl ProcessMonitor {
+    /// Start sampling every `interval`, sending snapshots that changed by
+    /// more than `delta` percentage points.
+    pub fn spawn(interval: Duration, delta: f32) -> Self {
+        let trees = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = termide_wake::channel();
+
+        let thread_trees = Arc::clone(&trees);
+        let thread_stop = Arc::clone(&stop);
+        thread::spawn(move || sample_loop(interval, delta, thread_trees, thread_stop, sender));
+
+        Self {
+            trees,
+            receiver,
+            stop,
+        }
+    }
+
+    /// Set the trees to sample (used from the next sample on)
+    pub fn set_trees(&self, trees: Vec<ProcessTree>) {
+        if let Ok(mut current) = self.trees.lock() {
+            *current = trees;
+        }
+    }
+
+    /// Latest snapshot received since the previous call
+    pub fn try_latest(&self) -> Option<MonitorSnapshot> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl Drop for ProcessMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Sample until stopped, sending changed snapshots
+fn sample_loop(
+    interval: Duration,
+    delta: f32,
+    trees: Arc<Mutex<Vec<ProcessTree>>>,
+    stop: Arc<AtomicBool>,
+    sender: termide_wake::Sender<MonitorSnapshot>,
+) {
+    let mut system = System::new();
+    let mut last_sent: Option<MonitorSnapshot> = None;
+    let mut last_trees = Vec::new();
+
+    while !stop.load(Ordering::Relaxed) {
+        let current_trees = trees.lock().map(|t| t.clone()).unwrap_or_default();
+        let snapshot = sample(&mut system, &current_trees);
+
+        // New trees are sent right away, even before CPU usage settles
+        let send = current_trees != last_trees
+            || last_sent
+                .as_ref()
+                .is_none_or(|last| snapshot.differs_from(last, delta));
+        if send {
+            if sender.send(snapshot.clone()).is_err() {
+                return;
+            }
+            last_sent = Some(snapshot);
+            last_trees = current_trees;
+        }
+
+        let wake_at = Instant::now() + interval;
+        while !stop.load(Ordering::Relaxed) && Instant::now() < wake_at {
+            thread::sleep(STOP_CHECK_INTERVAL.min(wake_at - Instant::now()));
+        }
+    }
+}
+
+/// Refresh system information and take a snapshot of the trees
+fn sample(system: &mut System, trees: &[ProcessTree]) -> MonitorSnapshot {
+    system.refresh_cpu_usage();
+    system.refresh_memory();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        ProcessRefreshKind::new().with_cpu().with_memory(),
+    );
+
+    let processes: Vec<(Option<u32>, ProcessUsage)> = system
+        .processes()
+        .values()
+        // Threads are listed as processes on Linux
+        .filter(|process| process.thread_kind().is_none())
+        .map(|process| {
+            (
+                process.parent().map(Pid::as_u32),
+                ProcessUsage {
+                    pid: process.pid().as_u32(),
+                    name: process.name().to_string_lossy().into_owned(),
+                    cpu_usage: process.cpu_usage(),
+                    memory: process.memory(),
+                    depth: 0,
+                },
+            )
+        })
+        .collect();
+
+    let load = System::load_average();
+    MonitorSnapshot {
+        stats: SystemStats {
+            cpu_usage: system.global_cpu_usage(),
+            memory_used: system.used_memory(),
+            memory_total: system.total_memory(),
+        },
+        load_average: [load.one, load.five, load.fifteen],
+        trees: trees
+            .iter()
+            .map(|tree| tree_usage(tree, &processes))
+            .collect(),
+    }
+}
+
+/// Usage of a tree from all processes with their parent IDs
+fn tree_usage(tree: &ProcessTree, processes: &[(Option<u32>, ProcessUsage)]) -> TreeUsage {
+    let mut children: HashMap<u32, Vec<&ProcessUsage>> = HashMap::new();
+    for (parent, process) in processes {
+        if let Some(parent) = parent {
+            children.entry(*parent).or_default().push(process);
+        }
+    }
+    for list in children.values_mut() {
+        list.sort_by_key(|process| process.pid);
+    }
+
+    let mut result = Vec::new();
+    let root = processes
+        .iter()
+        .map(|(_, process)| process)
+        .find(|process| process.pid == tree.root_pid);
+    // Depth-first, so each parent is followed by its children
+    let mut stack: Vec<(&ProcessUsage, usize)> = root.into_iter().map(|p| (p, 0)).collect();
+    while let Some((process, depth)) = stack.pop() {
+        result.push(ProcessUsage {
+            depth,
+            ..process.clone()
+        });
+        if let Some(list) = children.get(&process.pid) {
+            stack.extend(list.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+
+    TreeUsage {
+        label: tree.label.clone(),
+        root_pid: tree.root_pid,
+        processes: result,
+    }
+}
+
+/// Send a signal to a process.
+///
+/// Returns false if the process does not exist or the signal could not be
+/// sent.
+pub fn send_signal(pid: u32, signal: Signal) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), ProcessRefreshKind::new());
+    system
+        .process(pid)
+        .and_then(|process| process.kill_with(signal))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, cpu_usage: f32, memory: u64) -> ProcessUsage {
+        ProcessUsage {
+            pid,
+            name: format!("p{}", pid),
+            cpu_usage,
+            memory,
+            depth: 0,
+        }
+    }
+
+    #[test]
+    fn test_tree_sums_descendants() {
+        let processes = vec![
+            (None, process(1, 0.0, 100)),
+            (Some(1), process(10, 1.0, 1000)),
+            (Some(10), process(12, 30.0, 5000)),
+            (Some(10), process(11, 2.0, 2000)),
+            (Some(12), process(13, 10.0, 500)),
+            (Some(1), process(20, 50.0, 9000)),
+        ];
+        let tree = ProcessTree {
+            label: "Terminal".to_string(),
+            root_pid: 10,
+        };
+
+        let usage = tree_usage(&tree, &processes);
+        let order: Vec<(u32, usize)> = usage.processes.iter().map(|p| (p.pid, p.depth)).collect();
+        assert_eq!(order, vec![(10, 0), (11, 1), (12, 1), (13, 2)]);
+        assert_eq!(usage.cpu_usage(), 43.0);
+        assert_eq!(usage.memory(), 8500);
+
+        let gone = ProcessTree {
+            label: "Exited".to_string(),
+            root_pid: 99,
+        };
+        assert!(tree_usage(&gone, &processes).processes.is_empty());
+    }
+
+    #[test]
+    fn test_small_changes_ignored() {
+        let snapshot = |cpu_usage: f32, pids: &[u32]| MonitorSnapshot {
+            stats: SystemStats {
+                cpu_usage,
+                memory_used: 4 << 30,
+                memory_total: 16 << 30,
+            },
+            load_average: [0.5, 0.5, 0.5],
+            trees: vec![TreeUsage {
+                label: "Terminal".to_string(),
+                root_pid: pids[0],
+                processes: pids.iter().map(|&pid| process(pid, 1.0, 1 << 20)).collect(),
+            }],
+        };
+
+        let base = snapshot(10.0, &[10, 11]);
+        assert!(!snapshot(10.5, &[10, 11]).differs_from(&base, 1.0));
+        assert!(snapshot(12.0, &[10, 11]).differs_from(&base, 1.0));
+        // A process exited
+        assert!(snapshot(10.0, &[10]).differs_from(&base, 1.0));
+    }
+}
@@ -34,15 +34,8 @@ pub struct SearchOptions {
     pub whole_word: bool,
 }
 
-/// Search in text and return all matches.
-pub fn find_all(text: &str, pattern: &str, options: &SearchOptions) -> Vec<Match> {
-    if pattern.is_empty() {
-        return vec![];
-    }
-
-    let mut matches = Vec::new();
-
-    // Build search pattern
+/// Build the regex matching `pattern` with the given options.
+pub fn build_regex(pattern: &str, options: &SearchOptions) -> Result<Regex, regex::Error> {
     let search_pattern = if options.regex {
         pattern.to_string()
     } else {
@@ -55,17 +48,25 @@ pub fn find_all(text: &str, pattern: &str, options: &SearchOptions) -> Vec<Match
         search_pattern
     };
 
-    let regex = if options.case_sensitive {
+    if options.case_sensitive {
         Regex::new(&search_pattern)
     } else {
         Regex::new(&format!("(?i){}", search_pattern))
-    };
+    }
+}
 
-    let regex = match regex {
+/// Search in text and return all matches.
+pub fn find_all(text: &str, pattern: &str, options: &SearchOptions) -> Vec<Match> {
+    if pattern.is_empty() {
+        return vec![];
+    }
+
+    let regex = match build_regex(pattern, options) {
         Ok(r) => r,
         Err(_) => return vec![],
     };
 
+    let mut matches = Vec::new();
     for (line_idx, line) in text.lines().enumerate() {
         for mat in regex.find_iter(line) {
             // Convert byte offset to char offset
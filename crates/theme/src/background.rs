@@ -0,0 +1,152 @@
+//! Terminal background color detection.
+//!
+//! Terminals report their background color in reply to an OSC 11 query.
+//! The query is followed by a primary device attributes request (DA1),
+//! which every terminal answers, so terminals without OSC 11 support are
+//! detected without waiting for the timeout.
+
+use ratatui::style::Color;
+use std::time::Duration;
+
+/// Start of an OSC 11 reply
+const PREFIX: &[u8] = b"\x1b]11;";
+
+/// Start of a primary device attributes reply
+const DA1_PREFIX: &[u8] = b"\x1b[?";
+
+/// Ask the terminal for its background color
+///
+/// Returns None if the terminal does not report it within `timeout`.
+/// Keys typed while waiting are lost.
+#[cfg(unix)]
+pub fn query_background(timeout: Duration) -> Option<Color> {
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = std::fs::File::open("/dev/tty").ok()?;
+    {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(b"\x1b]11;?\x07\x1b[c").ok()?;
+        stdout.flush().ok()?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    let mut chunk = [0u8; 256];
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return background(&reply);
+        }
+        let mut pollfd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pollfd is a valid pollfd for the duration of the call
+        let ready = unsafe { libc::poll(&mut pollfd, 1, left.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return background(&reply);
+        }
+        let read = tty.read(&mut chunk).ok()?;
+        if read == 0 {
+            return None;
+        }
+        reply.extend_from_slice(&chunk[..read]);
+        if let Some(color) = parse_reply(&reply) {
+            return color;
+        }
+    }
+}
+
+/// Background queries need a Unix terminal device
+#[cfg(not(unix))]
+pub fn query_background(_timeout: Duration) -> Option<Color> {
+    None
+}
+
+/// Background color once the device attributes reply arrived (the
+/// terminal answers in order, so an OSC 11 reply comes before it), or None
+/// while waiting for it
+fn parse_reply(reply: &[u8]) -> Option<Option<Color>> {
+    let da1 = find(reply, DA1_PREFIX)?;
+    if !reply[da1..].contains(&b'c') {
+        return None;
+    }
+    Some(background(&reply[..da1]))
+}
+
+/// Color of a complete OSC 11 reply (`ESC ] 11 ; rgb:RRRR/GGGG/BBBB BEL`,
+/// or terminated by `ESC \`)
+fn background(reply: &[u8]) -> Option<Color> {
+    let start = find(reply, PREFIX)?;
+    let body = &reply[start + PREFIX.len()..];
+    let end = (0..body.len())
+        .find(|&i| body[i] == 0x07 || (body[i] == 0x1b && body.get(i + 1) == Some(&b'\\')))?;
+    parse_color(&body[..end])
+}
+
+/// Position of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Parse an X11 color specification `rgb:R/G/B` with 1 to 4 hex digits
+/// per channel (`rgba:R/G/B/A` is accepted too)
+fn parse_color(spec: &[u8]) -> Option<Color> {
+    let spec = std::str::from_utf8(spec).ok()?;
+    let channels = spec
+        .strip_prefix("rgb:")
+        .or_else(|| spec.strip_prefix("rgba:"))?;
+    let mut values = channels.split('/').map(|channel| {
+        if channel.is_empty() || channel.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = (1u32 << (4 * channel.len())) - 1;
+        Some((value * 255 / max) as u8)
+    });
+    Some(Color::Rgb(
+        values.next()??,
+        values.next()??,
+        values.next()??,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        // Incomplete replies wait for more input
+        assert_eq!(parse_reply(b""), None);
+        assert_eq!(parse_reply(b"\x1b]11;rgb:ffff/8080/0000\x07"), None);
+        assert_eq!(parse_reply(b"\x1b]11;rgb:1e/1e/1e\x07\x1b[?62;"), None);
+
+        assert_eq!(
+            parse_reply(b"\x1b]11;rgb:ffff/8080/0000\x07\x1b[?62;22c"),
+            Some(Some(Color::Rgb(255, 128, 0)))
+        );
+        assert_eq!(
+            parse_reply(b"\x1b]11;rgb:1e/1e/1e\x1b\\\x1b[?62;22c"),
+            Some(Some(Color::Rgb(30, 30, 30)))
+        );
+        assert_eq!(
+            parse_reply(b"\x1b]11;rgba:f/f/f/f\x07\x1b[?1;2c"),
+            Some(Some(Color::Rgb(255, 255, 255)))
+        );
+        // Device attributes without a background: not supported
+        assert_eq!(parse_reply(b"\x1b[?62;22c"), Some(None));
+        assert_eq!(parse_reply(b"\x1b]11;cyan\x07\x1b[?62;22c"), Some(None));
+
+        // A reply cut off by the timeout
+        assert_eq!(
+            background(b"\x1b]11;rgb:0000/0000/0000\x07"),
+            Some(Color::Rgb(0, 0, 0))
+        );
+    }
+}
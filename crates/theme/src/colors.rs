@@ -46,6 +46,30 @@ pub struct Theme {
     pub error: Color,
 }
 
+impl Theme {
+    /// Check if the theme has a light background
+    pub fn is_light(&self) -> bool {
+        is_light_color(self.bg)
+    }
+}
+
+/// Check if a background color is light
+pub fn is_light_color(color: Color) -> bool {
+    match color {
+        // Perceived brightness (ITU-R BT.601)
+        Color::Rgb(r, g, b) => 299 * r as u32 + 587 * g as u32 + 114 * b as u32 > 127_500,
+        Color::White
+        | Color::Gray
+        | Color::LightRed
+        | Color::LightGreen
+        | Color::LightYellow
+        | Color::LightBlue
+        | Color::LightMagenta
+        | Color::LightCyan => true,
+        _ => false,
+    }
+}
+
 impl Default for Theme {
     fn default() -> Self {
         *Self::get_by_name("default")
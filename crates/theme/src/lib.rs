@@ -2,11 +2,13 @@
 //!
 //! Provides color theme management with support for custom TOML themes.
 
+mod background;
 mod colors;
 mod loader;
 
-pub use colors::Theme;
-pub use loader::load_theme;
+pub use background::query_background;
+pub use colors::{is_light_color, Theme};
+pub use loader::{load_theme, ThemeFile};
 
 use ratatui::style::Color;
 use std::collections::HashMap;
@@ -41,8 +43,18 @@ static THEME_ONEDARK: OnceLock<Theme> = OnceLock::new();
 static THEME_SOLARIZED_DARK: OnceLock<Theme> = OnceLock::new();
 static THEME_SOLARIZED_LIGHT: OnceLock<Theme> = OnceLock::new();
 
+/// Theme loaded from the themes directory
+#[derive(Debug, Clone)]
+struct UserTheme {
+    theme: &'static Theme,
+    highlight: Vec<(String, String)>,
+}
+
 // Cache for user-loaded themes
-static USER_THEMES: OnceLock<Mutex<HashMap<String, &'static Theme>>> = OnceLock::new();
+static USER_THEMES: OnceLock<Mutex<HashMap<String, UserTheme>>> = OnceLock::new();
+
+// Problems found while loading user themes, until taken by the app
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
 // Themes directory path (set by app on startup)
 static THEMES_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -57,6 +69,27 @@ fn get_themes_dir() -> Option<&'static PathBuf> {
     THEMES_DIR.get()
 }
 
+/// Path of a user theme file (it may not exist).
+pub fn user_theme_path(name: &str) -> Option<PathBuf> {
+    get_themes_dir().map(|dir| dir.join(format!("{}.toml", name)))
+}
+
+/// Take the problems found in user theme files since the last call
+/// (invalid colors, parse errors).
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS
+        .lock()
+        .map(|mut warnings| std::mem::take(&mut *warnings))
+        .unwrap_or_default()
+}
+
+/// Record a problem found in a user theme file.
+fn warn(name: &str, message: impl std::fmt::Display) {
+    if let Ok(mut warnings) = WARNINGS.lock() {
+        warnings.push(format!("Theme '{}': {}", name, message));
+    }
+}
+
 /// Hardcoded fallback theme in case of parse errors.
 fn get_hardcoded_fallback_theme(name: &'static str) -> Theme {
     Theme {
@@ -76,8 +109,8 @@ fn get_hardcoded_fallback_theme(name: &'static str) -> Theme {
 
 /// Load theme from embedded TOML content.
 fn load_theme_from_toml(content: &str, name: &'static str) -> Theme {
-    match loader::load_theme_from_str(content, name) {
-        Ok(theme) => theme,
+    match loader::load_theme_from_str(content, name, &get_hardcoded_fallback_theme(name)) {
+        Ok(file) => file.theme,
         Err(e) => {
             eprintln!(
                 "Failed to parse built-in theme '{}': {}. Using fallback theme.",
@@ -140,8 +173,33 @@ fn get_solarized_light_theme() -> &'static Theme {
         .get_or_init(|| load_theme_from_toml(THEME_SOLARIZED_LIGHT_TOML, "solarized-light"))
 }
 
+/// Read user theme from config directory, reporting its problems.
+fn read_user_theme(name: &str) -> Option<UserTheme> {
+    let theme_path = user_theme_path(name)?;
+    if !theme_path.exists() {
+        return None;
+    }
+
+    match load_theme(&theme_path, get_default_theme()) {
+        Ok(file) => {
+            for warning in &file.warnings {
+                warn(name, format!("{}, using the default theme color", warning));
+            }
+            Some(UserTheme {
+                // Leak the theme to get 'static reference
+                theme: Box::leak(Box::new(file.theme)),
+                highlight: file.highlight,
+            })
+        }
+        Err(e) => {
+            warn(name, e);
+            None
+        }
+    }
+}
+
 /// Try to load user theme from config directory.
-fn try_load_user_theme(name: &str) -> Option<&'static Theme> {
+fn try_load_user_theme(name: &str) -> Option<UserTheme> {
     let cache = USER_THEMES.get_or_init(|| Mutex::new(HashMap::new()));
 
     // Check if theme is already cached
@@ -149,29 +207,62 @@ fn try_load_user_theme(name: &str) -> Option<&'static Theme> {
     {
         let cache_lock = cache.lock().ok()?;
         if let Some(theme) = cache_lock.get(name) {
-            return Some(*theme);
+            return Some(theme.clone());
         }
     }
 
-    // Try to load from file
-    let themes_dir = get_themes_dir()?;
-    let theme_path = themes_dir.join(format!("{}.toml", name));
+    let theme = read_user_theme(name)?;
 
-    if !theme_path.exists() {
-        return None;
+    // Cache it (ignore if mutex is poisoned - theme already loaded, just won't be cached)
+    if let Ok(mut cache_lock) = cache.lock() {
+        cache_lock.insert(name.to_string(), theme.clone());
     }
 
-    let theme = load_theme(&theme_path).ok()?;
-
-    // Leak the theme to get 'static reference
-    let static_theme: &'static Theme = Box::leak(Box::new(theme));
+    Some(theme)
+}
 
-    // Cache it (ignore if mutex is poisoned - theme already loaded, just won't be cached)
+/// Read a user theme file again after it changed.
+///
+/// Returns false if the file can't be read; the previously loaded version
+/// of the theme stays in use then.
+pub fn reload_user_theme(name: &str) -> bool {
+    let Some(theme) = read_user_theme(name) else {
+        return false;
+    };
+    let cache = USER_THEMES.get_or_init(|| Mutex::new(HashMap::new()));
     if let Ok(mut cache_lock) = cache.lock() {
-        cache_lock.insert(name.to_string(), static_theme);
+        cache_lock.insert(name.to_string(), theme);
     }
+    true
+}
 
-    Some(static_theme)
+/// Syntax highlight styles from the `[highlight]` table of a user theme
+/// (empty for built-in themes).
+pub fn highlight_styles(name: &str) -> Vec<(String, String)> {
+    try_load_user_theme(name)
+        .map(|theme| theme.highlight)
+        .unwrap_or_default()
+}
+
+/// Names of the built-in themes and the user themes in the themes
+/// directory, sorted.
+pub fn available_theme_names() -> Vec<String> {
+    let mut names: Vec<String> = Theme::all_theme_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    if let Some(entries) = get_themes_dir().and_then(|dir| std::fs::read_dir(dir).ok()) {
+        names.extend(entries.flatten().filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        }));
+    }
+    names.sort();
+    names.dedup();
+    names
 }
 
 impl Theme {
@@ -181,8 +272,8 @@ impl Theme {
     /// If not found, falls back to built-in themes.
     pub fn get_by_name(name: &str) -> &'static Theme {
         // Try to load user theme first
-        if let Some(theme) = try_load_user_theme(name) {
-            return theme;
+        if let Some(user_theme) = try_load_user_theme(name) {
+            return user_theme.theme;
         }
 
         // Fall back to built-in themes
@@ -257,6 +348,41 @@ mod tests {
         assert_eq!(unknown.name, "default");
     }
 
+    #[test]
+    fn test_light_themes() {
+        for theme in Theme::all_themes() {
+            assert_eq!(
+                theme.is_light(),
+                theme.name.contains("light"),
+                "{}",
+                theme.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_built_in_themes_are_complete() {
+        let themes = [
+            THEME_ATOM_ONE_LIGHT_TOML,
+            THEME_AYU_LIGHT_TOML,
+            THEME_DEFAULT_TOML,
+            THEME_DRACULA_TOML,
+            THEME_GITHUB_LIGHT_TOML,
+            THEME_MATERIAL_LIGHTER_TOML,
+            THEME_MIDNIGHT_TOML,
+            THEME_MONOKAI_TOML,
+            THEME_NORD_TOML,
+            THEME_ONEDARK_TOML,
+            THEME_SOLARIZED_DARK_TOML,
+            THEME_SOLARIZED_LIGHT_TOML,
+        ];
+        for content in themes {
+            let fallback = get_hardcoded_fallback_theme("fallback");
+            let file = loader::load_theme_from_str(content, "test", &fallback).unwrap();
+            assert!(file.warnings.is_empty(), "{:?}", file.warnings);
+        }
+    }
+
     #[test]
     fn test_user_theme_loading() {
         if let Some(themes_dir) = get_themes_dir() {
@@ -3,107 +3,217 @@
 use anyhow::Result;
 use ratatui::style::Color;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::Theme;
 
-/// Color representation in TOML.
-#[derive(Debug, Clone, Deserialize)]
-#[serde(untagged)]
-enum TomlColor {
-    Named(String),
-    Rgb { rgb: [u8; 3] },
-}
-
-impl TomlColor {
-    fn to_color(&self) -> Color {
-        match self {
-            TomlColor::Named(name) => match name.as_str() {
-                "Black" => Color::Black,
-                "Red" => Color::Red,
-                "Green" => Color::Green,
-                "Yellow" => Color::Yellow,
-                "Blue" => Color::Blue,
-                "Magenta" => Color::Magenta,
-                "Cyan" => Color::Cyan,
-                "Gray" => Color::Gray,
-                "DarkGray" => Color::DarkGray,
-                "LightRed" => Color::LightRed,
-                "LightGreen" => Color::LightGreen,
-                "LightYellow" => Color::LightYellow,
-                "LightBlue" => Color::LightBlue,
-                "LightMagenta" => Color::LightMagenta,
-                "LightCyan" => Color::LightCyan,
-                "White" => Color::White,
-                _ => Color::White,
-            },
-            TomlColor::Rgb { rgb } => Color::Rgb(rgb[0], rgb[1], rgb[2]),
-        }
-    }
-}
-
-/// TOML theme colors structure.
-#[derive(Debug, Clone, Deserialize)]
-struct TomlColors {
-    bg: TomlColor,
-    fg: TomlColor,
-    accented_bg: TomlColor,
-    accented_fg: TomlColor,
-    selected_bg: TomlColor,
-    selected_fg: TomlColor,
-    disabled: TomlColor,
-    success: TomlColor,
-    warning: TomlColor,
-    error: TomlColor,
-}
+/// Names of the theme colors in the `[colors]` table.
+const COLOR_NAMES: [&str; 10] = [
+    "bg",
+    "fg",
+    "accented_bg",
+    "accented_fg",
+    "selected_bg",
+    "selected_fg",
+    "disabled",
+    "success",
+    "warning",
+    "error",
+];
 
 /// TOML theme structure.
+///
+/// Colors are kept as raw values so that an invalid one only affects
+/// its own field.
 #[derive(Debug, Clone, Deserialize)]
 struct TomlTheme {
-    name: String,
-    colors: TomlColors,
+    name: Option<String>,
+    #[serde(default)]
+    colors: toml::Table,
+    #[serde(default)]
+    highlight: BTreeMap<String, String>,
+}
+
+/// Theme read from a TOML file.
+#[derive(Debug, Clone)]
+pub struct ThemeFile {
+    /// Theme colors
+    pub theme: Theme,
+    /// Syntax highlight styles from the `[highlight]` table
+    /// (`("keyword", "#c678dd bold")`)
+    pub highlight: Vec<(String, String)>,
+    /// Invalid, missing and unknown colors (replaced by the fallback colors)
+    pub warnings: Vec<String>,
 }
 
 /// Load theme from TOML file.
 ///
-/// Returns the parsed theme with a leaked static name string.
-pub fn load_theme(path: &Path) -> Result<Theme> {
+/// The theme is named by its `name` field or the file name. Invalid and
+/// missing colors are taken from `fallback` and reported as warnings.
+pub fn load_theme(path: &Path, fallback: &Theme) -> Result<ThemeFile> {
     let content = std::fs::read_to_string(path)?;
     let toml_theme: TomlTheme = toml::from_str(&content)?;
 
+    let name = toml_theme.name.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
     // Leak the name string to get 'static lifetime
-    let name: &'static str = Box::leak(toml_theme.name.into_boxed_str());
-
-    Ok(Theme {
-        name,
-        bg: toml_theme.colors.bg.to_color(),
-        fg: toml_theme.colors.fg.to_color(),
-        accented_bg: toml_theme.colors.accented_bg.to_color(),
-        accented_fg: toml_theme.colors.accented_fg.to_color(),
-        selected_bg: toml_theme.colors.selected_bg.to_color(),
-        selected_fg: toml_theme.colors.selected_fg.to_color(),
-        disabled: toml_theme.colors.disabled.to_color(),
-        success: toml_theme.colors.success.to_color(),
-        warning: toml_theme.colors.warning.to_color(),
-        error: toml_theme.colors.error.to_color(),
-    })
+    let name: &'static str = Box::leak(name.into_boxed_str());
+
+    Ok(theme_file(toml_theme, name, fallback))
 }
 
 /// Load theme from TOML string with a static name.
-pub fn load_theme_from_str(content: &str, name: &'static str) -> Result<Theme> {
+pub fn load_theme_from_str(
+    content: &str,
+    name: &'static str,
+    fallback: &Theme,
+) -> Result<ThemeFile> {
     let toml_theme: TomlTheme = toml::from_str(content)?;
+    Ok(theme_file(toml_theme, name, fallback))
+}
 
-    Ok(Theme {
-        name,
-        bg: toml_theme.colors.bg.to_color(),
-        fg: toml_theme.colors.fg.to_color(),
-        accented_bg: toml_theme.colors.accented_bg.to_color(),
-        accented_fg: toml_theme.colors.accented_fg.to_color(),
-        selected_bg: toml_theme.colors.selected_bg.to_color(),
-        selected_fg: toml_theme.colors.selected_fg.to_color(),
-        disabled: toml_theme.colors.disabled.to_color(),
-        success: toml_theme.colors.success.to_color(),
-        warning: toml_theme.colors.warning.to_color(),
-        error: toml_theme.colors.error.to_color(),
+/// Build theme from parsed TOML, color by color.
+fn theme_file(toml_theme: TomlTheme, name: &'static str, fallback: &Theme) -> ThemeFile {
+    let mut theme = Theme { name, ..*fallback };
+    let mut warnings = Vec::new();
+
+    let fields: [&mut Color; 10] = [
+        &mut theme.bg,
+        &mut theme.fg,
+        &mut theme.accented_bg,
+        &mut theme.accented_fg,
+        &mut theme.selected_bg,
+        &mut theme.selected_fg,
+        &mut theme.disabled,
+        &mut theme.success,
+        &mut theme.warning,
+        &mut theme.error,
+    ];
+    for (field, color) in COLOR_NAMES.into_iter().zip(fields) {
+        match toml_theme.colors.get(field).map(parse_color) {
+            Some(Ok(parsed)) => *color = parsed,
+            Some(Err(error)) => warnings.push(format!("{}: {}", field, error)),
+            None => warnings.push(format!("{}: missing color", field)),
+        }
+    }
+    for field in toml_theme.colors.keys() {
+        if !COLOR_NAMES.contains(&field.as_str()) {
+            warnings.push(format!("unknown color '{}'", field));
+        }
+    }
+
+    ThemeFile {
+        theme,
+        highlight: toml_theme.highlight.into_iter().collect(),
+        warnings,
+    }
+}
+
+/// Parse a color: a name (`"Cyan"`), `"#rrggbb"` or `{ rgb = [r, g, b] }`.
+fn parse_color(value: &toml::Value) -> std::result::Result<Color, String> {
+    match value {
+        toml::Value::String(name) => named_color(name)
+            .or_else(|| hex_color(name))
+            .ok_or_else(|| format!("unknown color '{}'", name)),
+        toml::Value::Table(table) => {
+            let channels = table
+                .get("rgb")
+                .and_then(|rgb| rgb.as_array())
+                .filter(|rgb| rgb.len() == 3)
+                .and_then(|rgb| {
+                    rgb.iter()
+                        .map(|channel| channel.as_integer().and_then(|c| u8::try_from(c).ok()))
+                        .collect::<Option<Vec<u8>>>()
+                });
+            match channels.as_deref() {
+                Some(&[r, g, b]) => Ok(Color::Rgb(r, g, b)),
+                _ => Err("rgb must be three values from 0 to 255".to_string()),
+            }
+        }
+        _ => Err("expected a color name, \"#rrggbb\" or { rgb = [r, g, b] }".to_string()),
+    }
+}
+
+/// Color by its name
+fn named_color(name: &str) -> Option<Color> {
+    Some(match name {
+        "Black" => Color::Black,
+        "Red" => Color::Red,
+        "Green" => Color::Green,
+        "Yellow" => Color::Yellow,
+        "Blue" => Color::Blue,
+        "Magenta" => Color::Magenta,
+        "Cyan" => Color::Cyan,
+        "Gray" => Color::Gray,
+        "DarkGray" => Color::DarkGray,
+        "LightRed" => Color::LightRed,
+        "LightGreen" => Color::LightGreen,
+        "LightYellow" => Color::LightYellow,
+        "LightBlue" => Color::LightBlue,
+        "LightMagenta" => Color::LightMagenta,
+        "LightCyan" => Color::LightCyan,
+        "White" => Color::White,
+        _ => return None,
     })
 }
+
+/// Color written as `#rrggbb`
+fn hex_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_colors_fall_back_per_field() {
+        let fallback = Theme::get_by_name("default");
+        let content = r##"
+            [colors]
+            bg = "#102030"
+            fg = "Chartreuse"
+            accented_bg = { rgb = [300, 0, 0] }
+            accented_fg = { rgb = [1, 2, 3] }
+            selected_bg = "Blue"
+            selected_fg = "White"
+            disabled = "Gray"
+            success = "Green"
+            warning = "Yellow"
+            errors = "Red"
+
+            [highlight]
+            keyword = "#c678dd bold"
+        "##;
+
+        let file = load_theme_from_str(content, "test", fallback).unwrap();
+        assert_eq!(file.theme.name, "test");
+        assert_eq!(file.theme.bg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(file.theme.fg, fallback.fg);
+        assert_eq!(file.theme.accented_bg, fallback.accented_bg);
+        assert_eq!(file.theme.accented_fg, Color::Rgb(1, 2, 3));
+        assert_eq!(file.theme.error, fallback.error);
+        assert_eq!(
+            file.warnings,
+            [
+                "fg: unknown color 'Chartreuse'",
+                "accented_bg: rgb must be three values from 0 to 255",
+                "error: missing color",
+                "unknown color 'errors'",
+            ]
+        );
+        assert_eq!(
+            file.highlight,
+            [("keyword".to_string(), "#c678dd bold".to_string())]
+        );
+    }
+}
@@ -1,43 +1,34 @@
 //! Dropdown menu widget.
-
-// Dropdown component is prepared for future use
-#![allow(dead_code)]
+//!
+//! Shows the actions of the focused panel below the "Panel" menu entry.
 
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     prelude::Widget,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem},
+    widgets::{Block, Borders, Clear, Paragraph},
 };
+use unicode_width::UnicodeWidthStr;
 
-/// Dropdown menu item
-#[derive(Debug, Clone)]
-pub struct DropdownItem {
-    pub label: String,
-    pub key: String,
-}
+use termide_core::MenuItem;
+use termide_i18n as i18n;
+use termide_theme::Theme;
 
-impl DropdownItem {
-    pub fn new(label: impl Into<String>, key: impl Into<String>) -> Self {
-        Self {
-            label: label.into(),
-            key: key.into(),
-        }
-    }
-}
+/// Widest dropdown including its border
+const MAX_WIDTH: usize = 60;
 
 /// Dropdown menu
 pub struct Dropdown<'a> {
-    items: &'a [DropdownItem],
+    items: &'a [MenuItem],
     selected: usize,
     x: u16,
     y: u16,
 }
 
 impl<'a> Dropdown<'a> {
-    pub fn new(items: &'a [DropdownItem], selected: usize, x: u16, y: u16) -> Self {
+    pub fn new(items: &'a [MenuItem], selected: usize, x: u16, y: u16) -> Self {
         Self {
             items,
             selected,
@@ -46,86 +37,132 @@ impl<'a> Dropdown<'a> {
         }
     }
 
-    pub fn render(&self, buf: &mut Buffer) {
-        if self.items.is_empty() {
-            return;
-        }
-
-        // Calculate dropdown dimensions
-        let max_label_len = self
+    /// Area covered by the dropdown in a screen of `screen` size
+    pub fn area(&self, screen: Rect) -> Rect {
+        let label_width = self
             .items
             .iter()
-            .map(|item| item.label.len())
+            .map(|item| {
+                item.label.width() + item.key_hint.as_ref().map_or(0, |keys| keys.width() + 3)
+            })
             .max()
-            .unwrap_or(0);
-        let width = (max_label_len + 6).min(40) as u16; // 6 = padding + number
-        let height = (self.items.len() + 2) as u16; // +2 for borders
-
-        // Check screen boundaries
-        let max_x = buf.area.width.saturating_sub(width);
-        let max_y = buf.area.height.saturating_sub(height);
-        let x = self.x.min(max_x);
-        let y = self.y.min(max_y);
+            .unwrap_or_else(|| i18n::t().menu_panel_empty().width());
+        // Border, padding and the checkmark column
+        let width = (label_width + 6).min(MAX_WIDTH) as u16;
+        let height = self.items.len().max(1) as u16 + 2;
+        Rect {
+            x: self.x.min(screen.width.saturating_sub(width)),
+            y: self.y.min(screen.height.saturating_sub(height)),
+            width: width.min(screen.width),
+            height: height.min(screen.height),
+        }
+    }
 
-        let area = Rect {
-            x,
-            y,
-            width,
-            height,
-        };
+    /// Item at screen row `y`, if any
+    pub fn item_at(&self, screen: Rect, y: u16) -> Option<usize> {
+        let area = self.area(screen);
+        let row = y.checked_sub(area.y + 1)? as usize;
+        (row < self.items.len()).then_some(row)
+    }
 
-        // Clear area under dropdown
+    pub fn render(&self, buf: &mut Buffer, theme: &Theme) {
+        let area = self.area(buf.area);
         Clear.render(area, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accented_fg))
+            .style(Style::default().bg(theme.bg));
+        let inner = block.inner(area);
+        block.render(area, buf);
 
-        // Create list of items
-        let items: Vec<ListItem> = self
-            .items
-            .iter()
-            .enumerate()
-            .map(|(i, item)| {
-                let style = if i == self.selected {
-                    Style::default()
-                        .bg(Color::Cyan)
-                        .fg(Color::Black)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-
-                let line = Line::from(vec![
-                    Span::raw(" "),
-                    Span::styled(format!("{}. ", i + 1), Style::default().fg(Color::Yellow)),
-                    Span::styled(&item.label, style),
-                ]);
+        if self.items.is_empty() {
+            let line = Line::from(Span::styled(
+                format!(" {}", i18n::t().menu_panel_empty()),
+                Style::default().fg(theme.disabled),
+            ));
+            Paragraph::new(line).render(inner, buf);
+            return;
+        }
 
-                ListItem::new(line)
-            })
-            .collect();
+        let width = inner.width as usize;
+        for (idx, item) in self.items.iter().enumerate().take(inner.height as usize) {
+            let style = if idx == self.selected && item.enabled {
+                Style::default()
+                    .fg(theme.selected_fg)
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD)
+            } else if idx == self.selected {
+                Style::default().fg(theme.disabled).bg(theme.selected_bg)
+            } else if item.enabled {
+                Style::default().fg(theme.fg)
+            } else {
+                Style::default().fg(theme.disabled)
+            };
+            let check = match item.checked {
+                Some(true) => "✓ ",
+                _ => "  ",
+            };
+            let keys = item.key_hint.as_deref().unwrap_or("");
+            let label = format!(" {}{}", check, item.label);
+            let padding = width.saturating_sub(label.width() + keys.width() + 1);
+            let line = Line::from(vec![
+                Span::styled(label, style),
+                Span::styled(" ".repeat(padding), style),
+                Span::styled(
+                    format!("{} ", keys),
+                    if item.enabled {
+                        style.fg(theme.disabled)
+                    } else {
+                        style
+                    },
+                ),
+            ]);
+            let row = Rect {
+                y: inner.y + idx as u16,
+                height: 1,
+                ..inner
+            };
+            Paragraph::new(line).render(row, buf);
+        }
+    }
+}
 
-        let list = List::new(items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
-                .style(Style::default().bg(Color::DarkGray)),
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        list.render(area, buf);
+    fn items() -> Vec<MenuItem> {
+        vec![
+            MenuItem::new("save", "Save").with_key_hint("Ctrl+S"),
+            MenuItem::toggle("wrap", "Word Wrap", true).with_enabled(false),
+        ]
     }
-}
 
-/// Menu item definitions for tools dropdown
-pub fn get_tools_items() -> Vec<DropdownItem> {
-    vec![
-        DropdownItem::new("Files", "files"),
-        DropdownItem::new("Editor", "editor"),
-        DropdownItem::new("Terminal", "terminal"),
-    ]
-}
+    #[test]
+    fn test_dropdown_shows_state() {
+        let items = items();
+        let screen = Rect::new(0, 0, 40, 10);
+        let mut buf = Buffer::empty(screen);
+        Dropdown::new(&items, 0, 5, 1).render(&mut buf, &Theme::default());
+
+        let row = |y: u16| {
+            (0..screen.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(2).contains("Save") && row(2).contains("Ctrl+S"));
+        assert!(row(3).contains("✓ Word Wrap"));
+        assert_eq!(buf[(9, 3)].fg, Theme::default().disabled);
+    }
 
-/// Menu item definitions for help dropdown
-pub fn get_help_items() -> Vec<DropdownItem> {
-    vec![
-        DropdownItem::new("Welcome", "welcome"),
-        DropdownItem::new("Debug console", "debug"),
-    ]
+    #[test]
+    fn test_item_at_row() {
+        let items = items();
+        let screen = Rect::new(0, 0, 40, 10);
+        let dropdown = Dropdown::new(&items, 0, 5, 1);
+        assert_eq!(dropdown.item_at(screen, 1), None);
+        assert_eq!(dropdown.item_at(screen, 2), Some(0));
+        assert_eq!(dropdown.item_at(screen, 3), Some(1));
+        assert_eq!(dropdown.item_at(screen, 4), None);
+    }
 }
@@ -6,8 +6,16 @@ pub mod dropdown;
 pub mod menu;
 pub mod panel_rendering;
 pub mod status_bar;
+pub mod toasts;
 
-pub use dropdown::{Dropdown, DropdownItem};
-pub use menu::{get_menu_items, render_menu, resource_color, MenuRenderParams, MENU_ITEM_COUNT};
-pub use panel_rendering::{render_collapsed_panel, render_expanded_panel, ExpandedPanelParams};
+pub use dropdown::Dropdown;
+pub use menu::{
+    get_menu_items, menu_item_x, render_menu, resource_color, MenuRenderParams, MENU_ITEM_COUNT,
+    PANEL_MENU_INDEX,
+};
+pub use panel_rendering::{
+    highlight_border, render_collapsed_panel, render_drop_indicator, render_expanded_panel,
+    render_tab_strip, ExpandedPanelParams,
+};
 pub use status_bar::{StatusBar, StatusBarParams};
+pub use toasts::Toasts;
@@ -35,6 +35,7 @@ pub fn get_menu_items() -> Vec<String> {
         t.menu_terminal().to_string(),
         t.menu_editor().to_string(),
         t.menu_debug().to_string(),
+        t.menu_panel().to_string(),
         t.menu_preferences().to_string(),
         t.menu_help().to_string(),
         t.menu_quit().to_string(),
@@ -42,7 +43,20 @@ pub fn get_menu_items() -> Vec<String> {
 }
 
 /// Number of menu items
-pub const MENU_ITEM_COUNT: usize = 7;
+pub const MENU_ITEM_COUNT: usize = 8;
+
+/// Menu item opening the actions of the focused panel
+pub const PANEL_MENU_INDEX: usize = 4;
+
+/// Column where menu item `index` starts
+pub fn menu_item_x(index: usize) -> u16 {
+    let before: usize = get_menu_items()
+        .iter()
+        .take(index)
+        .map(|item| item.width() + 2)
+        .sum();
+    1 + before as u16
+}
 
 /// Choose color indicator by load level
 /// < 50% - green (success)
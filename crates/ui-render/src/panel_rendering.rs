@@ -12,6 +12,7 @@ use ratatui::{
 
 use termide_config::Config;
 use termide_core::{Panel, PanelConfig, RenderContext, ThemeColors};
+use termide_layout::{TabStrip, TAB_SEPARATOR};
 use termide_theme::Theme;
 
 /// Parameters for rendering expanded panels.
@@ -149,3 +150,87 @@ pub fn render_expanded_panel(
     // Render panel content
     panel.render(inner, buf, &ctx);
 }
+
+/// Render the tabs of a group over the top border of its expanded panel
+/// (`area` is the border row without its corners).
+pub fn render_tab_strip(
+    strip: &TabStrip,
+    active: usize,
+    area: Rect,
+    buf: &mut Buffer,
+    is_focused: bool,
+    theme: &Theme,
+) {
+    if area.height == 0 || area.width == 0 {
+        return;
+    }
+
+    let border_style = if is_focused {
+        Style::default().fg(theme.accented_fg)
+    } else {
+        Style::default().fg(theme.disabled)
+    };
+    let mut active_style = Style::default().fg(theme.selected_fg).bg(theme.selected_bg);
+    if is_focused {
+        active_style = active_style.add_modifier(Modifier::BOLD);
+    }
+
+    // Clear the panel title drawn in the border
+    let y = area.y;
+    for x in area.left()..area.right() {
+        buf[(x, y)].set_symbol("─").set_style(border_style);
+    }
+
+    for tab in &strip.tabs {
+        let style = if tab.panel == active {
+            active_style
+        } else {
+            border_style
+        };
+        let x = area.x + tab.x;
+        buf.set_stringn(x, y, &tab.label, usize::from(area.right() - x), style);
+        let separator = x + tab.width();
+        if separator < area.right() {
+            buf.set_string(separator, y, TAB_SEPARATOR, border_style);
+        }
+    }
+    if let Some(more) = &strip.overflow {
+        let x = area.x + more.x;
+        if x < area.right() {
+            buf.set_stringn(
+                x,
+                y,
+                &more.label,
+                usize::from(area.right() - x),
+                border_style,
+            );
+        }
+    }
+}
+
+/// Render the drop indicator of a dragged panel: a thick line over a
+/// one-column (between groups) or one-row (between panels) area.
+pub fn render_drop_indicator(area: Rect, buf: &mut Buffer, theme: &Theme) {
+    let symbol = if area.width == 1 { "┃" } else { "━" };
+    let style = Style::default()
+        .fg(theme.success)
+        .add_modifier(Modifier::BOLD);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            buf[(x, y)].set_symbol(symbol).set_style(style);
+        }
+    }
+}
+
+/// Highlight the panel borders in `area` (a border being resized with the
+/// mouse), keeping their symbols.
+pub fn highlight_border(area: Rect, buf: &mut Buffer, theme: &Theme) {
+    let style = Style::default()
+        .fg(theme.success)
+        .add_modifier(Modifier::BOLD);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            buf[(x, y)].set_style(style);
+        }
+    }
+}
@@ -1,7 +1,3 @@
-// Allow clippy lints for status bar
-#![allow(clippy::too_many_arguments)]
-#![allow(clippy::vec_init_then_push)]
-
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -10,11 +6,8 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
+use termide_core::{StatusKind, StatusSegment};
 use termide_i18n as i18n;
-use termide_panel_editor::EditorInfo;
-use termide_panel_file_manager::FileInfo;
-use termide_panel_terminal::TerminalInfo;
-use termide_system_monitor::{DiskSpaceInfo, DiskSpaceInfoExt};
 use termide_theme::Theme;
 
 use super::menu::resource_color;
@@ -30,6 +23,10 @@ pub struct StatusBarParams<'a> {
     pub terminal_height: u16,
     /// Recommended layout string (for Debug panel)
     pub recommended_layout: &'a str,
+    /// Segments shown on the left, in order
+    pub left: &'a [StatusKind],
+    /// Segments shown on the right, in order
+    pub right: &'a [StatusKind],
 }
 
 /// Status bar at the bottom of screen
@@ -37,16 +34,17 @@ pub struct StatusBar;
 
 impl StatusBar {
     /// Render status bar
+    ///
+    /// `panel_segments` come from the active panel, `app_segments` from the
+    /// application (git branch, clock). Panels without segments show their
+    /// title instead.
     pub fn render(
         buf: &mut Buffer,
         area: Rect,
         params: &StatusBarParams<'_>,
         panel_title: &str,
-        selected_count: Option<usize>,
-        file_info: Option<&FileInfo>,
-        disk_space: Option<&DiskSpaceInfo>,
-        editor_info: Option<&EditorInfo>,
-        terminal_info: Option<&TerminalInfo>,
+        panel_segments: &[StatusSegment],
+        app_segments: &[StatusSegment],
     ) {
         if area.height == 0 {
             return;
@@ -55,11 +53,8 @@ impl StatusBar {
         let status_text = Self::get_status_text(
             params,
             panel_title,
-            selected_count,
-            file_info,
-            disk_space,
-            editor_info,
-            terminal_info,
+            panel_segments,
+            app_segments,
             area.width,
         );
 
@@ -88,22 +83,19 @@ impl StatusBar {
         }
     }
 
-    /// Get text for status bar depending on active panel
+    /// Get text for status bar: configured segments on the left and on the
+    /// right, separated by padding
     fn get_status_text<'a>(
         params: &'a StatusBarParams<'a>,
         panel_title: &'a str,
-        selected_count: Option<usize>,
-        file_info: Option<&'a FileInfo>,
-        disk_space: Option<&'a DiskSpaceInfo>,
-        editor_info: Option<&'a EditorInfo>,
-        terminal_info: Option<&'a TerminalInfo>,
+        panel_segments: &'a [StatusSegment],
+        app_segments: &'a [StatusSegment],
         total_width: u16,
     ) -> Vec<Span<'a>> {
-        let t = i18n::t();
         let theme = params.theme;
 
         // If there's an ERROR message, show it with priority
-        // Info messages don't block file_info display
+        // Info messages don't block segment display
         if let Some((message, is_error)) = params.status_message {
             if *is_error {
                 let msg_style = Style::default()
@@ -114,211 +106,155 @@ impl StatusBar {
             }
         }
 
-        let base_style = Style::default().fg(theme.disabled).bg(theme.accented_bg);
-
-        let highlight_style = Style::default()
-            .fg(theme.accented_fg)
-            .bg(theme.accented_bg)
-            .add_modifier(Modifier::BOLD);
-
-        // Show different information depending on panel type
-        // If terminal_info is passed, this is Terminal
-        if let Some(info) = terminal_info {
-            // Terminal: user@host | /path on the left, disk space on the right
-            let mut spans = vec![];
+        let find = |kind: StatusKind| {
+            panel_segments
+                .iter()
+                .chain(app_segments)
+                .find(|segment| segment.kind == kind)
+        };
 
-            spans.push(Span::styled(" ", base_style));
-            spans.push(Span::styled(info.user_host.as_str(), highlight_style));
-            spans.push(Span::styled(" | ", base_style));
-            spans.push(Span::styled(info.cwd.as_str(), highlight_style));
-
-            // If there's disk information, add it on the right
-            if let Some(disk) = &info.disk_space {
-                let disk_text = format!(" {} ", disk.format_space());
-                let disk_color = resource_color(disk.usage_percent(), theme);
-
-                // Calculate current spans width considering unicode characters
-                let used_width: usize = spans
-                    .iter()
-                    .map(|s| match &s.content {
-                        std::borrow::Cow::Borrowed(s) => s.width(),
-                        std::borrow::Cow::Owned(s) => s.width(),
-                    })
-                    .sum();
-
-                // Add padding between left part and disk info
-                let remaining =
-                    (total_width as usize).saturating_sub(used_width + disk_text.width());
-                if remaining > 0 {
-                    spans.push(Span::raw(" ".repeat(remaining)));
-                }
+        let mut spans = if panel_segments.is_empty() {
+            Self::panel_info(params, panel_title)
+        } else {
+            let left: Vec<_> = params.left.iter().filter_map(|&kind| find(kind)).collect();
+            Self::side_spans(&left, theme)
+        };
+        if !spans.is_empty() {
+            spans.insert(0, Span::styled(" ", Self::base_style(theme)));
+        }
 
-                spans.push(Span::styled(
-                    disk_text,
-                    Style::default().fg(disk_color).bg(theme.accented_bg),
-                ));
-            }
+        // Right segments that don't fit are dropped from the start
+        let left_width = spans_width(&spans);
+        let mut right: Vec<_> = params.right.iter().filter_map(|&kind| find(kind)).collect();
+        let mut right_spans = Self::side_spans(&right, theme);
+        while !right.is_empty() && left_width + spans_width(&right_spans) + 1 > total_width as usize
+        {
+            right.remove(0);
+            right_spans = Self::side_spans(&right, theme);
+        }
 
-            spans
-        } else if let Some(info) = file_info {
-            // File manager: show information about current file
-            let mut spans = vec![];
+        if !right_spans.is_empty() {
+            let right_width = spans_width(&right_spans) + 1;
+            let padding = (total_width as usize).saturating_sub(left_width + right_width);
+            spans.push(Span::raw(" ".repeat(padding)));
+            spans.extend(right_spans);
+            spans.push(Span::styled(" ", Self::base_style(theme)));
+        }
 
-            // Format for directories: "Dir: dirname | Mod: 0755 | Owner: nvn:users"
-            // Format for files: "File: filename | 12.3MB | Mod: 0755 | Owner: nvn:users"
+        spans
+    }
 
-            if info.file_type == "Directory" {
-                spans.push(Span::styled(format!(" {} ", t.status_dir()), base_style));
-            } else {
-                spans.push(Span::styled(format!(" {} ", t.status_file()), base_style));
-            }
-            spans.push(Span::styled(info.name.as_str(), highlight_style));
+    /// Spans of segments joined with separators
+    fn side_spans<'a>(segments: &[&'a StatusSegment], theme: &Theme) -> Vec<Span<'a>> {
+        let t = i18n::t();
+        let base_style = Self::base_style(theme);
+        let highlight_style = Style::default()
+            .fg(theme.accented_fg)
+            .bg(theme.accented_bg)
+            .add_modifier(Modifier::BOLD);
 
-            // For files show size
-            if info.file_type != "Directory" {
+        let mut spans = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
                 spans.push(Span::styled(t.ui_hint_separator(), base_style));
-                spans.push(Span::styled(info.size.as_str(), highlight_style));
-            }
-
-            spans.push(Span::styled(
-                format!("{}{} ", t.ui_hint_separator(), t.status_mod()),
-                base_style,
-            ));
-            spans.push(Span::styled(info.mode.as_str(), highlight_style));
-
-            spans.push(Span::styled(
-                format!("{}{} ", t.ui_hint_separator(), t.status_owner()),
-                base_style,
-            ));
-            spans.push(Span::styled(
-                format!("{}:{}", info.owner, info.group),
-                highlight_style,
-            ));
-
-            // If there are selected files, add their count
-            if let Some(count) = selected_count {
-                if count > 0 {
-                    spans.push(Span::styled(
-                        format!("{}{} ", t.ui_hint_separator(), t.status_selected()),
-                        base_style,
-                    ));
-                    spans.push(Span::styled(
-                        format!("{}", count),
-                        Style::default()
-                            .fg(theme.success)
-                            .bg(theme.accented_bg)
-                            .add_modifier(Modifier::BOLD),
-                    ));
-                }
             }
-
-            // If there's disk information, add it on the right
-            if let Some(disk) = disk_space {
-                let disk_text = format!(" {} ", disk.format_space());
-                let disk_color = resource_color(disk.usage_percent(), theme);
-
-                // Calculate current spans width considering unicode characters
-                let used_width: usize = spans
-                    .iter()
-                    .map(|s| match &s.content {
-                        std::borrow::Cow::Borrowed(s) => s.width(),
-                        std::borrow::Cow::Owned(s) => s.width(),
-                    })
-                    .sum();
-
-                // Add padding between left part and disk info
-                let remaining =
-                    (total_width as usize).saturating_sub(used_width + disk_text.width());
-                if remaining > 0 {
-                    spans.push(Span::raw(" ".repeat(remaining)));
-                }
-
-                spans.push(Span::styled(
-                    disk_text,
-                    Style::default().fg(disk_color).bg(theme.accented_bg),
-                ));
+            if let Some(label) = &segment.label {
+                spans.push(Span::styled(format!("{} ", label), base_style));
             }
-
-            spans
-        } else if let Some(info) = editor_info {
-            // Editor: cursor position, tab size, encoding, file type, modes
-            let mut parts = vec![
-                format!("{} {}:{}", t.status_pos(), info.line, info.column),
-                format!("{} {}", t.status_tab(), info.tab_size),
-                info.encoding.clone(),
-            ];
-
-            // Add file type only if highlighting is enabled
-            if info.syntax_highlighting {
-                parts.push(info.file_type.clone());
+            let style = if let Some(usage) = segment.usage {
+                Style::default()
+                    .fg(resource_color(usage, theme))
+                    .bg(theme.accented_bg)
+            } else if segment.kind == StatusKind::Selection {
+                highlight_style.fg(theme.success)
             } else {
-                parts.push(t.status_plain_text().to_string());
-            }
-
-            // Add read-only indicator
-            if info.read_only {
-                parts.push(t.status_readonly().to_string());
-            }
-
-            let editor_status = parts.join(t.ui_hint_separator());
-            let status_width = editor_status.width();
+                highlight_style
+            };
+            spans.push(Span::styled(segment.text.as_str(), style));
+        }
+        spans
+    }
 
-            // Add left padding to align to right edge
-            let padding = (total_width as usize).saturating_sub(status_width + 1);
-            let mut spans = vec![];
+    /// General information for panels without segments
+    fn panel_info<'a>(params: &'a StatusBarParams<'a>, panel_title: &'a str) -> Vec<Span<'a>> {
+        let t = i18n::t();
+        let base_style = Self::base_style(params.theme);
+        let highlight_style = Style::default()
+            .fg(params.theme.accented_fg)
+            .bg(params.theme.accented_bg)
+            .add_modifier(Modifier::BOLD);
+        let terminal_size = format!("{}x{}", params.terminal_width, params.terminal_height);
+
+        if panel_title == "Debug" {
+            // Debug: layout mode and dimensions
+            vec![
+                Span::styled(format!("{} ", t.status_terminal()), base_style),
+                Span::styled(terminal_size, highlight_style),
+                Span::styled(
+                    format!("{}{} ", t.ui_hint_separator(), t.status_layout()),
+                    base_style,
+                ),
+                Span::styled(params.recommended_layout, highlight_style),
+            ]
+        } else {
+            vec![
+                Span::styled(format!("{}{}", t.status_panel(), panel_title), base_style),
+                Span::styled(
+                    format!("{}{}", t.ui_hint_separator(), terminal_size),
+                    base_style,
+                ),
+            ]
+        }
+    }
 
-            if padding > 0 {
-                spans.push(Span::raw(" ".repeat(padding)));
-            }
+    /// Style of labels and separators
+    fn base_style(theme: &Theme) -> Style {
+        Style::default().fg(theme.disabled).bg(theme.accented_bg)
+    }
+}
 
-            spans.push(Span::styled(format!("{} ", editor_status), highlight_style));
+/// Display width of spans
+fn spans_width(spans: &[Span<'_>]) -> usize {
+    spans.iter().map(|span| span.content.width()).sum()
+}
 
-            spans
-        } else {
-            match panel_title {
-                "Terminal" => {
-                    // Terminal: current directory
-                    vec![
-                        Span::styled(format!(" {} ", t.status_cwd()), base_style),
-                        Span::styled("~/Documents/Repos", highlight_style),
-                        Span::styled(
-                            format!("{}{} ", t.ui_hint_separator(), t.status_shell()),
-                            base_style,
-                        ),
-                        Span::styled("bash", highlight_style),
-                    ]
-                }
-                "Debug" => {
-                    // Debug: layout mode and dimensions
-                    let terminal_info =
-                        format!("{}x{}", params.terminal_width, params.terminal_height);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                    vec![
-                        Span::styled(format!(" {} ", t.status_terminal()), base_style),
-                        Span::styled(terminal_info, highlight_style),
-                        Span::styled(
-                            format!("{}{} ", t.ui_hint_separator(), t.status_layout()),
-                            base_style,
-                        ),
-                        Span::styled(params.recommended_layout.to_string(), highlight_style),
-                    ]
-                }
-                _ => {
-                    // Default: general information
-                    let panel_info = format!(" {}{}", t.status_panel(), panel_title);
-                    let term_info = format!(
-                        "{}{}x{}",
-                        t.ui_hint_separator(),
-                        params.terminal_width,
-                        params.terminal_height
-                    );
+    fn text(spans: &[Span<'_>]) -> String {
+        spans.iter().map(|span| span.content.as_ref()).collect()
+    }
 
-                    vec![
-                        Span::styled(panel_info, base_style),
-                        Span::styled(term_info, base_style),
-                    ]
-                }
-            }
-        }
+    #[test]
+    fn test_segments_placed_in_configured_order() {
+        i18n::init_with_language("en");
+        let theme = Theme::default();
+        let params = StatusBarParams {
+            theme: &theme,
+            status_message: None,
+            terminal_width: 80,
+            terminal_height: 24,
+            recommended_layout: "",
+            left: &[StatusKind::Clock, StatusKind::File, StatusKind::UserHost],
+            right: &[StatusKind::Encoding, StatusKind::Cursor],
+        };
+        let panel = [
+            StatusSegment::labeled(StatusKind::Cursor, "Pos:", "3:7"),
+            StatusSegment::new(StatusKind::Encoding, "UTF-8"),
+            StatusSegment::new(StatusKind::Language, "Rust"),
+        ];
+        let app = [StatusSegment::new(StatusKind::Clock, "12:30")];
+
+        let spans = StatusBar::get_status_text(&params, "Editor", &panel, &app, 40);
+        let line = text(&spans);
+        assert_eq!(line.width(), 40);
+        assert!(line.starts_with(" 12:30 "));
+        assert!(line.ends_with(" UTF-8 | Pos: 3:7 "));
+
+        // The first right segments are dropped when the bar is too narrow
+        let spans = StatusBar::get_status_text(&params, "Editor", &panel, &app, 20);
+        assert!(text(&spans).ends_with(" Pos: 3:7 "));
+        assert!(!text(&spans).contains("UTF-8"));
     }
 }
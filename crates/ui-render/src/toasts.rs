@@ -0,0 +1,180 @@
+//! Notification toasts.
+//!
+//! Stacked in the top right corner of the panel area, newest on top. They
+//! are drawn over the panels but take no input, so focus stays where it is.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    prelude::Widget,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use termide_core::{Notification, NotificationLevel};
+use termide_theme::Theme;
+
+/// Widest toast including its border
+const MAX_WIDTH: u16 = 48;
+
+/// Text lines of a toast before the rest is cut
+const MAX_LINES: usize = 3;
+
+/// Stack of notification toasts
+pub struct Toasts<'a> {
+    /// Newest first
+    notifications: Vec<&'a Notification>,
+}
+
+impl<'a> Toasts<'a> {
+    /// Toasts for notifications given newest first, at most `max`
+    pub fn new(notifications: impl Iterator<Item = &'a Notification>, max: usize) -> Self {
+        Self {
+            notifications: notifications.take(max).collect(),
+        }
+    }
+
+    /// Draw the toasts in the top right corner of `area`
+    pub fn render(&self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let width = MAX_WIDTH.min(area.width / 2);
+        if width < 10 {
+            return;
+        }
+        let mut y = area.y;
+        for notification in &self.notifications {
+            let lines = wrap(&notification.text, width as usize - 4);
+            let height = lines.len() as u16 + 2;
+            if y + height > area.bottom() {
+                break;
+            }
+            let toast = Rect::new(area.right() - width, y, width, height);
+            y += height;
+
+            let color = match notification.level {
+                NotificationLevel::Info => theme.accented_fg,
+                NotificationLevel::Warning => theme.warning,
+                NotificationLevel::Error => theme.error,
+            };
+            Clear.render(toast, buf);
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(color))
+                .title(Line::styled(
+                    format!(" {} ", notification.time.format("%H:%M:%S")),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ))
+                .style(Style::default().bg(theme.bg).fg(theme.fg));
+            let inner = block.inner(toast);
+            block.render(toast, buf);
+            let text: Vec<Line> = lines
+                .into_iter()
+                .map(|line| Line::from(format!(" {}", line)))
+                .collect();
+            Paragraph::new(text).render(inner, buf);
+        }
+    }
+}
+
+/// Split text into lines of at most `width` columns, at spaces where
+/// possible, cutting what does not fit in [`MAX_LINES`]
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        loop {
+            let gap = usize::from(!line.is_empty());
+            if line.width() + gap + word.width() <= width {
+                if gap == 1 {
+                    line.push(' ');
+                }
+                line.push_str(&word);
+                break;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                continue;
+            }
+            // A word longer than a line is broken
+            let mut used = 0;
+            let split = word
+                .char_indices()
+                .find(|(_, c)| {
+                    used += c.width().unwrap_or(0);
+                    used > width
+                })
+                .map_or(word.len(), |(index, _)| index);
+            lines.push(word[..split].to_string());
+            word = word[split..].to_string();
+            if word.is_empty() {
+                break;
+            }
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    if lines.len() > MAX_LINES {
+        lines.truncate(MAX_LINES);
+        let last = &mut lines[MAX_LINES - 1];
+        while last.width() + 1 > width {
+            last.pop();
+        }
+        last.push('…');
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(text: &str, level: NotificationLevel) -> Notification {
+        Notification {
+            text: text.to_string(),
+            level,
+            time: chrono::Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("build done", 20), ["build done"]);
+        assert_eq!(
+            wrap("copy of 3 files finished", 10),
+            ["copy of 3", "files", "finished"]
+        );
+        assert_eq!(wrap("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("a b c d e f g h", 3), ["a b", "c d", "e …"]);
+        assert_eq!(wrap("", 10), [""]);
+    }
+
+    #[test]
+    fn test_toasts_stack_in_the_corner() {
+        let notifications = [
+            notification("newest", NotificationLevel::Error),
+            notification("older", NotificationLevel::Info),
+            notification("hidden", NotificationLevel::Info),
+        ];
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 20));
+        let area = Rect::new(0, 1, 80, 18);
+        Toasts::new(notifications.iter(), 2).render(area, &mut buf, &Theme::default());
+
+        let row = |y: u16| {
+            (0..80)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(0).trim().is_empty());
+        // Half the width, at the right edge
+        assert_eq!(row(2).find("│ newest"), Some(40));
+        assert!(row(2).ends_with('│'));
+        assert_eq!(buf[(79, 2)].fg, Theme::default().error);
+        assert!(row(5).contains("older"));
+        assert!((0..20).all(|y| !row(y).contains("hidden")));
+        // The panels on the left are not covered
+        assert!(row(2).starts_with("        "));
+    }
+}
@@ -0,0 +1,101 @@
+//! Fuzzy matching of file paths for the file finder.
+//!
+//! Builds on `fuzzy_match`. A pattern matching within the file name ranks
+//! above one spread over the directories, characters right after a `/`
+//! score extra, and recently opened files are moved up. A pattern with a
+//! `/` is always matched against the whole path.
+
+use crate::fuzzy::{fuzzy_match, FuzzyMatch};
+
+/// Bonus for a pattern matched within the file name
+const BONUS_FILE_NAME: i32 = 64;
+/// Bonus for a character matched at the start of a path segment
+const BONUS_SEGMENT_START: i32 = 16;
+/// Bonus for the most recently opened file
+const BONUS_RECENT: i32 = 48;
+/// Recent bonus lost per step down the recent list
+const RECENT_STEP: i32 = 2;
+
+/// Match `pattern` against a relative `path` (with `/` separators).
+///
+/// `recent_rank` is the position of the file in the recently opened files
+/// (0 is the most recent). Returns None if the pattern does not match.
+/// Positions are char indices in `path`.
+pub fn match_path(pattern: &str, path: &str, recent_rank: Option<usize>) -> Option<FuzzyMatch> {
+    let recent = recent_rank.map_or(0, |rank| {
+        (BONUS_RECENT - RECENT_STEP * rank.min(i32::MAX as usize) as i32).max(RECENT_STEP)
+    });
+
+    let name_start = path.rfind('/').map_or(0, |idx| idx + 1);
+    let in_name = (!pattern.contains('/'))
+        .then(|| fuzzy_match(pattern, &path[name_start..]))
+        .flatten();
+    let mut found = match in_name {
+        Some(mut found) => {
+            let offset = path[..name_start].chars().count();
+            found.positions.iter_mut().for_each(|idx| *idx += offset);
+            if !found.positions.is_empty() {
+                found.score += BONUS_FILE_NAME;
+            }
+            found
+        }
+        None => fuzzy_match(pattern, path)?,
+    };
+
+    let chars: Vec<char> = path.chars().collect();
+    let segment_starts = found
+        .positions
+        .iter()
+        .filter(|&&idx| idx == 0 || chars[idx - 1] == '/')
+        .count();
+    found.score += BONUS_SEGMENT_START * segment_starts as i32 + recent;
+    Some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, path: &str) -> i32 {
+        match_path(pattern, path, None).unwrap().score
+    }
+
+    #[test]
+    fn test_file_name_beats_directories() {
+        assert!(score("lib", "src/lib.rs") > score("lib", "lib/tests/util.rs"));
+        assert!(score("main", "crates/app/src/main.rs") > score("main", "main/src/other.rs"));
+        assert!(match_path("xyz", "src/lib.rs", None).is_none());
+    }
+
+    #[test]
+    fn test_segment_starts_score_higher() {
+        // `ed` and `pa` at the starts of `editor/` and `panel.rs` beat a match
+        // inside a word
+        assert!(score("edpa", "editor/panel.rs") > score("edpa", "shredpaper.rs"));
+        assert!(score("a/d", "app/data.rs") > score("a/d", "bad/data.rs"));
+    }
+
+    #[test]
+    fn test_positions_are_in_full_path() {
+        let found = match_path("lib", "src/lib.rs", None).unwrap();
+        assert_eq!(found.positions, vec![4, 5, 6]);
+        // A pattern with a slash is matched against the whole path
+        let found = match_path("s/l", "src/lib.rs", None).unwrap();
+        assert_eq!(found.positions, vec![0, 3, 4]);
+        let found = match_path("ü", "dir/über.txt", None).unwrap();
+        assert_eq!(found.positions, vec![4]);
+    }
+
+    #[test]
+    fn test_recent_files_first() {
+        let recent = match_path("mod", "b/mod.rs", Some(0)).unwrap().score;
+        let older = match_path("mod", "a/mod.rs", Some(5)).unwrap().score;
+        let never = match_path("mod", "c/mod.rs", None).unwrap().score;
+        assert!(recent > older && older > never);
+        // With an empty pattern only recency counts
+        assert!(
+            match_path("", "z.rs", Some(3)).unwrap().score
+                > match_path("", "a.rs", None).unwrap().score
+        );
+    }
+}
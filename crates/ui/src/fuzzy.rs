@@ -0,0 +1,111 @@
+//! Fuzzy matching of a typed pattern against item names.
+//!
+//! A pattern matches when all its characters occur in the candidate in
+//! order (case-insensitive). Matches at word starts and runs of
+//! consecutive characters score higher, gaps score lower.
+
+/// Bonus for a matched character
+const SCORE_MATCH: i32 = 16;
+/// Bonus for a character matched right after the previous one
+const BONUS_CONSECUTIVE: i32 = 24;
+/// Bonus for a character at the start of a word
+const BONUS_WORD_START: i32 = 32;
+/// Penalty per skipped character between matches
+const PENALTY_GAP: i32 = 1;
+
+/// Successful fuzzy match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i32,
+    /// Char indices of matched characters in the candidate
+    pub positions: Vec<usize>,
+}
+
+/// Match `pattern` against `candidate`.
+///
+/// Returns None if the pattern is not a subsequence of the candidate.
+/// An empty pattern matches everything with score 0.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = candidate.chars().collect();
+
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut next = 0;
+    for &wanted in &pattern {
+        let offset = chars[next..]
+            .iter()
+            .position(|ch| ch.to_lowercase().eq(std::iter::once(wanted)))?;
+        let idx = next + offset;
+
+        score += SCORE_MATCH;
+        if is_word_start(&chars, idx) {
+            score += BONUS_WORD_START;
+        }
+        if idx > 0 && positions.last() == Some(&(idx - 1)) {
+            score += BONUS_CONSECUTIVE;
+        } else if !positions.is_empty() {
+            score -= PENALTY_GAP * offset as i32;
+        }
+        positions.push(idx);
+        next = idx + 1;
+    }
+
+    // Prefer shorter candidates among equal matches
+    if !pattern.is_empty() {
+        score -= (chars.len() - positions.len()) as i32 / 4;
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Check if the char at `idx` starts a word (`Go To`, `go_to`, `goTo`)
+fn is_word_start(chars: &[char], idx: usize) -> bool {
+    let Some(prev) = idx.checked_sub(1).map(|i| chars[i]) else {
+        return true;
+    };
+    let ch = chars[idx];
+    (!prev.is_alphanumeric() && ch.is_alphanumeric()) || (prev.is_lowercase() && ch.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, candidate: &str) -> i32 {
+        fuzzy_match(pattern, candidate).unwrap().score
+    }
+
+    #[test]
+    fn test_subsequence_required() {
+        assert!(fuzzy_match("ntm", "New Terminal").is_some());
+        assert!(fuzzy_match("NEW", "new terminal").is_some());
+        assert!(fuzzy_match("tw", "New Terminal").is_none());
+        assert!(fuzzy_match("xyz", "New Terminal").is_none());
+        assert_eq!(fuzzy_match("", "anything").unwrap().score, 0);
+    }
+
+    #[test]
+    fn test_positions() {
+        let found = fuzzy_match("nt", "New Terminal").unwrap();
+        assert_eq!(found.positions, vec![0, 4]);
+        let found = fuzzy_match("пан", "Закрыть панель").unwrap();
+        assert_eq!(found.positions, vec![8, 9, 10]);
+    }
+
+    #[test]
+    fn test_ranking() {
+        // Word starts beat matches inside words
+        assert!(score("cp", "Close Panel") > score("cp", "Escape"));
+        // Consecutive characters beat scattered ones
+        assert!(score("term", "New Terminal") > score("term", "Toggle Recent Mode"));
+        // Prefix beats the same letters later in the name
+        assert!(score("go", "Go to Panel 1") > score("go", "Toggle Group"));
+        // camelCase humps count as word starts
+        assert!(score("fm", "newFileManager") > score("fm", "newfilemanager"));
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides reusable UI widgets and layout utilities.
 
 pub mod config;
+pub mod file_match;
+pub mod fuzzy;
 pub mod path_utils;
 pub mod system_monitor;
 
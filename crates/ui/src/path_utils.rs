@@ -60,3 +60,32 @@ pub fn get_file_name_str(path: &Path) -> &str {
 pub fn get_file_name_string(path: &Path) -> String {
     get_file_name_str(path).to_string()
 }
+
+/// Compute path to `target` relative to directory `base`
+///
+/// Both paths are compared lexically (no symlink resolution), so they
+/// should be absolute. E.g. base `/a/b`, target `/a/c/file` -> `../c/file`.
+pub fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base_components: Vec<_> = base.components().collect();
+    let target_components: Vec<_> = target.components().collect();
+
+    let common = base_components
+        .iter()
+        .zip(target_components.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut result = PathBuf::new();
+    for _ in common..base_components.len() {
+        result.push("..");
+    }
+    for component in &target_components[common..] {
+        result.push(component.as_os_str());
+    }
+
+    if result.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        result
+    }
+}
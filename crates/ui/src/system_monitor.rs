@@ -1,3 +1,3 @@
 //! System monitor utilities.
 
-pub use termide_system_monitor::DiskSpaceInfo;
+pub use termide_system_monitor::{DiskSpaceInfo, DiskSpaceInfoExt};
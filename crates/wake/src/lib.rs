@@ -0,0 +1,163 @@
+//! Wakes the main loop from background threads.
+//!
+//! The main loop sleeps until terminal input arrives or a frame or timer is
+//! due. Threads producing something for it (terminal output, file watcher
+//! events, results of background work) call [`wake`], or send through a
+//! [`channel`] whose sender does, so that their output is picked up at once
+//! instead of on the next timer.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SendError};
+
+/// A wakeup was sent since the main loop last cleared them
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+static WAKER: std::sync::Mutex<Option<std::os::unix::net::UnixStream>> =
+    std::sync::Mutex::new(None);
+
+/// Wake the main loop; wakeups sent before it handles the first one are
+/// merged. Does nothing until a [`Wakeup`] is installed.
+pub fn wake() {
+    if PENDING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+
+        let waker = WAKER.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(mut stream) = waker.as_ref() {
+            // A full socket has woken the loop already
+            let _ = stream.write(&[1]);
+        }
+    }
+}
+
+/// Sending half of a [`channel`]: every message sent wakes the main loop
+#[derive(Debug)]
+pub struct Sender<T>(mpsc::Sender<T>);
+
+impl<T> Sender<T> {
+    /// Send a message and wake the main loop to pick it up
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.send(value)?;
+        wake();
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// Channel to the main loop, as [`std::sync::mpsc::channel`], whose
+/// messages wake it
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let (sender, receiver) = mpsc::channel();
+    (Sender(sender), receiver)
+}
+
+/// Receiving end of the wakeups, waited on by the main loop
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct Wakeup {
+    reader: std::os::unix::net::UnixStream,
+}
+
+#[cfg(unix)]
+impl Wakeup {
+    /// Make [`wake`] and `signals` wake the waits of the returned receiver
+    /// (it replaces a receiver installed before)
+    pub fn install(signals: &[libc::c_int]) -> std::io::Result<Self> {
+        let (reader, writer) = std::os::unix::net::UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        writer.set_nonblocking(true)?;
+        for &signal in signals {
+            signal_hook::low_level::pipe::register(signal, writer.try_clone()?)?;
+        }
+        *WAKER.lock().unwrap_or_else(|e| e.into_inner()) = Some(writer);
+        PENDING.store(false, Ordering::Release);
+        Ok(Self { reader })
+    }
+
+    /// Wait until `input` is readable, a wakeup arrives or the timeout
+    /// passes (None waits without one); false on timeout. Wakeups are
+    /// cleared before returning.
+    pub fn wait(
+        &self,
+        input: Option<std::os::fd::RawFd>,
+        timeout: Option<std::time::Duration>,
+    ) -> std::io::Result<bool> {
+        use std::os::fd::AsRawFd;
+
+        let mut fds = vec![libc::pollfd {
+            fd: self.reader.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        fds.extend(input.map(|fd| libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        }));
+        let timeout = timeout.map_or(-1, |timeout| {
+            // Round up, so that a wait does not end just before its deadline
+            libc::c_int::try_from(timeout.as_micros().div_ceil(1000)).unwrap_or(libc::c_int::MAX)
+        });
+        // SAFETY: fds is a valid array of pollfd for the duration of the call
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) };
+        if ready < 0 {
+            let error = std::io::Error::last_os_error();
+            // A signal interrupted the wait, the caller looks what changed
+            if error.kind() == std::io::ErrorKind::Interrupted {
+                return Ok(true);
+            }
+            return Err(error);
+        }
+        if fds[0].revents != 0 {
+            self.clear();
+        }
+        Ok(ready > 0)
+    }
+
+    /// Drop the wakeups received so far
+    fn clear(&self) {
+        use std::io::Read;
+
+        PENDING.store(false, Ordering::Release);
+        let mut buf = [0u8; 64];
+        while matches!((&self.reader).read(&mut buf), Ok(read) if read > 0) {}
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_send_wakes_blocking_wait() {
+        let wakeup = Wakeup::install(&[]).unwrap();
+        assert!(!wakeup.wait(None, Some(Duration::from_millis(10))).unwrap());
+
+        let (sender, receiver) = channel();
+        let started = Instant::now();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            sender.send(42).unwrap();
+        });
+        assert!(wakeup.wait(None, None).unwrap());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(receiver.try_recv(), Ok(42));
+
+        // The wakeup was cleared, a later one wakes again
+        assert!(!wakeup.wait(None, Some(Duration::from_millis(10))).unwrap());
+        wake();
+        wake();
+        assert!(wakeup.wait(None, Some(Duration::from_secs(5))).unwrap());
+        assert!(!wakeup.wait(None, Some(Duration::ZERO)).unwrap());
+    }
+}
@@ -8,10 +8,11 @@
 use anyhow::{Context, Result};
 use notify::{RecommendedWatcher, RecursiveMode};
 use notify_debouncer_mini::{new_debouncer, DebouncedEvent, Debouncer};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{self, channel, Receiver, Sender};
+use std::sync::mpsc::{self, Receiver};
 use std::time::Duration;
+use termide_git::GitStatusCache;
 
 /// Filesystem change event.
 #[derive(Debug, Clone)]
@@ -141,21 +142,43 @@ pub struct DirectoryUpdate {
 
 /// Watches filesystem directories for changes and sends update events
 /// Supports two modes:
-/// - Git repositories: watched recursively from repo root
-/// - Non-git directories: watched non-recursively (direct children only)
+/// - Trees (git repositories): every directory from the root down is
+///   watched, except gitignored subtrees such as `target/`
+/// - Single directories: watched non-recursively (direct children only)
 #[derive(Debug)]
 pub struct FileSystemWatcher {
     debouncer: Debouncer<RecommendedWatcher>,
-    /// Git repos: repo_root -> reference count (Recursive mode)
-    watched_repos: HashMap<PathBuf, usize>,
-    /// Non-git dirs: dir_path -> reference count (NonRecursive mode)
+    /// Recursive roots -> reference count and their watched directories
+    trees: HashMap<PathBuf, WatchedTree>,
+    /// Non-recursive dirs: dir_path -> reference count
     watched_dirs: HashMap<PathBuf, usize>,
+    /// Directories watched by notify -> number of trees and dirs using them
+    watches: HashMap<PathBuf, usize>,
+}
+
+/// Directories watched for a recursive root.
+#[derive(Debug)]
+struct WatchedTree {
+    count: usize,
+    dirs: HashSet<PathBuf>,
+    /// Ignored paths of the repository containing the root
+    git_status: Option<GitStatusCache>,
+}
+
+impl WatchedTree {
+    /// Whether `path` is gitignored or inside a gitignored directory
+    fn is_ignored(&self, path: &Path) -> bool {
+        self.git_status.as_ref().is_some_and(|status| {
+            path.strip_prefix(status.repo_root())
+                .is_ok_and(|relative| status.is_path_in_ignored(relative))
+        })
+    }
 }
 
 impl FileSystemWatcher {
     /// Create a new FileSystemWatcher that sends events through the provided channel
     /// Debounces events to 300ms intervals
-    pub fn new(tx: Sender<DirectoryUpdate>) -> Result<Self> {
+    pub fn new(tx: termide_wake::Sender<DirectoryUpdate>) -> Result<Self> {
         let debouncer = new_debouncer(
             Duration::from_millis(300),
             move |result: notify_debouncer_mini::DebounceEventResult| {
@@ -197,45 +220,128 @@ impl FileSystemWatcher {
 
         Ok(Self {
             debouncer,
-            watched_repos: HashMap::new(),
+            trees: HashMap::new(),
             watched_dirs: HashMap::new(),
+            watches: HashMap::new(),
         })
     }
 
-    /// Start watching a git repository root recursively
-    /// Increments reference count if already watching
-    pub fn watch_repository(&mut self, repo_root: PathBuf) -> Result<()> {
-        // Increment reference count if already watching
-        if let Some(count) = self.watched_repos.get_mut(&repo_root) {
+    /// Watch a single directory, shared by the trees and dirs containing it
+    fn add_watch(&mut self, dir: &Path) -> Result<()> {
+        if let Some(count) = self.watches.get_mut(dir) {
             *count += 1;
             return Ok(());
         }
+        watch_path(&mut self.debouncer, dir, false)?;
+        self.watches.insert(dir.to_path_buf(), 1);
+        Ok(())
+    }
+
+    /// Release a watch taken with `add_watch`
+    fn remove_watch(&mut self, dir: &Path) {
+        if let Some(count) = self.watches.get_mut(dir) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.watches.remove(dir);
+                // Fails for directories already deleted, which notify dropped
+                let _ = unwatch_path(&mut self.debouncer, dir);
+            }
+        }
+    }
 
-        let watcher = self.debouncer.watcher();
+    /// Watch `dir` and the directories below it that are not ignored,
+    /// returning the ones watched; an error only when `dir` itself fails
+    fn watch_subtree(&mut self, dir: &Path, tree: &WatchedTree) -> Result<Vec<PathBuf>> {
+        self.add_watch(dir)?;
+        let mut watched = vec![dir.to_path_buf()];
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                // Symlinked directories are not followed, as with notify
+                let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+                if !is_dir
+                    || entry.file_name() == ".git"
+                    || tree.dirs.contains(&path)
+                    || tree.is_ignored(&path)
+                {
+                    continue;
+                }
+                if self.add_watch(&path).is_ok() {
+                    watched.push(path.clone());
+                    pending.push(path);
+                }
+            }
+        }
+        Ok(watched)
+    }
 
-        // Watch repository root recursively
-        watcher.watch(&repo_root, RecursiveMode::Recursive)?;
+    /// Start watching a directory tree recursively, skipping gitignored
+    /// subtrees; directories created later are picked up by `filter_updates`
+    /// Increments reference count if already watching
+    pub fn watch_recursive(&mut self, root: PathBuf) -> Result<()> {
+        // Increment reference count if already watching
+        if let Some(tree) = self.trees.get_mut(&root) {
+            tree.count += 1;
+            return Ok(());
+        }
+
+        let mut tree = WatchedTree {
+            count: 1,
+            dirs: HashSet::new(),
+            git_status: termide_git::get_git_status(&root),
+        };
+        let dirs = self.watch_subtree(&root, &tree)?;
+        tree.dirs.extend(dirs);
 
-        self.watched_repos.insert(repo_root, 1);
+        self.trees.insert(root, tree);
         Ok(())
     }
 
+    /// Stop watching a directory tree (decrement reference count)
+    /// Only unwatches, with every directory below the root, when count reaches 0
+    pub fn unwatch_recursive(&mut self, root: &Path) {
+        let Some(tree) = self.trees.get_mut(root) else {
+            return;
+        };
+        tree.count = tree.count.saturating_sub(1);
+        if tree.count == 0 {
+            if let Some(tree) = self.trees.remove(root) {
+                for dir in &tree.dirs {
+                    self.remove_watch(dir);
+                }
+            }
+        }
+    }
+
+    /// Check if a directory tree is being watched
+    pub fn is_watching_recursive(&self, root: &Path) -> bool {
+        self.trees.contains_key(root)
+    }
+
+    /// Number of directories watched below recursive roots, roots included
+    pub fn watched_tree_dirs(&self) -> usize {
+        self.trees.values().map(|tree| tree.dirs.len()).sum()
+    }
+
+    /// Start watching a git repository root recursively
+    /// Increments reference count if already watching
+    pub fn watch_repository(&mut self, repo_root: PathBuf) -> Result<()> {
+        self.watch_recursive(repo_root)
+    }
+
     /// Stop watching a git repository (decrement reference count)
     /// Only unwatches when count reaches 0
     pub fn unwatch_repository(&mut self, repo_root: &Path) {
-        if let Some(count) = self.watched_repos.get_mut(repo_root) {
-            *count = count.saturating_sub(1);
-            if *count == 0 {
-                self.watched_repos.remove(repo_root);
-                let watcher = self.debouncer.watcher();
-                let _ = watcher.unwatch(repo_root);
-            }
-        }
+        self.unwatch_recursive(repo_root);
     }
 
     /// Check if repository root is being watched
     pub fn is_watching_repo(&self, repo_root: &Path) -> bool {
-        self.watched_repos.contains_key(repo_root)
+        self.is_watching_recursive(repo_root)
     }
 
     /// Start watching a non-git directory (non-recursive, direct children only)
@@ -247,10 +353,8 @@ impl FileSystemWatcher {
             return Ok(());
         }
 
-        let watcher = self.debouncer.watcher();
-
         // Watch the directory non-recursively (only direct children, not subdirectories)
-        watcher.watch(&dir_path, RecursiveMode::NonRecursive)?;
+        self.add_watch(&dir_path)?;
 
         self.watched_dirs.insert(dir_path, 1);
         Ok(())
@@ -263,8 +367,7 @@ impl FileSystemWatcher {
             *count = count.saturating_sub(1);
             if *count == 0 {
                 self.watched_dirs.remove(dir_path);
-                let watcher = self.debouncer.watcher();
-                let _ = watcher.unwatch(dir_path);
+                self.remove_watch(dir_path);
             }
         }
     }
@@ -273,12 +376,76 @@ impl FileSystemWatcher {
     pub fn is_watching_dir(&self, dir_path: &Path) -> bool {
         self.watched_dirs.contains_key(dir_path)
     }
+
+    /// Drop updates of gitignored paths and keep the watched trees in step
+    /// with the directories created and removed in them.
+    ///
+    /// The ignored paths of a tree are read again when its `.gitignore`
+    /// files change or a directory appears in it (a new `target/` is
+    /// ignored before any of its churn gets through).
+    pub fn filter_updates(&mut self, updates: Vec<DirectoryUpdate>) -> Vec<DirectoryUpdate> {
+        let roots: Vec<PathBuf> = self.trees.keys().cloned().collect();
+
+        // Refresh ignored paths first, so that a new directory is judged
+        // with the patterns matching it
+        for root in &roots {
+            let Some(tree) = self.trees.get_mut(root) else {
+                continue;
+            };
+            let stale = updates.iter().any(|update| {
+                let path = &update.changed_path;
+                path.starts_with(root)
+                    && (path.file_name().is_some_and(|name| name == ".gitignore")
+                        || (path.is_dir() && !tree.dirs.contains(path)))
+            });
+            if stale {
+                tree.git_status = termide_git::get_git_status(root);
+            }
+        }
+
+        let mut kept = Vec::with_capacity(updates.len());
+        for update in updates {
+            let path = &update.changed_path;
+            let mut ignored = false;
+            for root in roots.iter().filter(|root| path.starts_with(root)) {
+                let Some(mut tree) = self.trees.remove(root) else {
+                    continue;
+                };
+                if tree.is_ignored(path) {
+                    ignored = true;
+                } else if path.is_dir() {
+                    if !tree.dirs.contains(path) {
+                        if let Ok(dirs) = self.watch_subtree(path, &tree) {
+                            tree.dirs.extend(dirs);
+                        }
+                    }
+                } else if tree.dirs.contains(path) {
+                    // Removed: the directories below it went along
+                    let removed: Vec<PathBuf> = tree
+                        .dirs
+                        .iter()
+                        .filter(|dir| dir.starts_with(path))
+                        .cloned()
+                        .collect();
+                    for dir in removed {
+                        tree.dirs.remove(&dir);
+                        self.remove_watch(&dir);
+                    }
+                }
+                self.trees.insert(root.clone(), tree);
+            }
+            if !ignored {
+                kept.push(update);
+            }
+        }
+        kept
+    }
 }
 
 /// Global filesystem watcher instance
 /// This is created once at application startup
 pub fn create_fs_watcher() -> Result<(FileSystemWatcher, Receiver<DirectoryUpdate>)> {
-    let (tx, rx) = channel();
+    let (tx, rx) = termide_wake::channel();
     let watcher = FileSystemWatcher::new(tx)?;
     Ok((watcher, rx))
 }
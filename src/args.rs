@@ -0,0 +1,130 @@
+//! Command line arguments.
+
+use termide_app::StartupTarget;
+
+const USAGE: &str = "\
+Usage: termide [OPTIONS] [PATH[:LINE[:COLUMN]]]... [-]
+
+Opens each file in an editor (at LINE and COLUMN if given), each directory
+in a file manager and `-` (standard input) in an editor without a file.
+Paths that do not exist are offered to be created.
+
+Options:
+      --init-config   Ask the config setup questions and write a new config
+      --render-once   Print one rendered frame to standard output and exit,
+                      without taking over the terminal
+      --width N       Width of the rendered frame (default 120)
+      --height N      Height of the rendered frame (default 40)
+      --ansi          Keep colors and text styles in the rendered frame
+      --open PATH     Open PATH (same as giving it without the option)
+      --send JSON     Send a command to the running termide (needs
+                      `ipc_server = true`) and exit, e.g.
+                      '{\"cmd\":\"notify\",\"text\":\"build done\"}'
+  -h, --help          Show this help
+  -V, --version       Show the version
+";
+
+/// Frame size of `--render-once` without `--width` and `--height`
+const DEFAULT_RENDER_SIZE: (u16, u16) = (120, 40);
+
+/// Parsed command line
+pub struct Args {
+    /// Run the config wizard (`--init-config`)
+    pub init_config: bool,
+    /// Panels to open, in order
+    pub targets: Vec<StartupTarget>,
+    /// Print one frame and exit (`--render-once`)
+    pub render_once: Option<RenderOnce>,
+    /// Command sent to the running termide (`--send`)
+    pub send: Option<String>,
+}
+
+/// Frame printed by `--render-once`
+pub struct RenderOnce {
+    pub width: u16,
+    pub height: u16,
+    /// Colors and text styles as ANSI escape sequences (`--ansi`)
+    pub ansi: bool,
+}
+
+impl Args {
+    /// Parse the arguments of the process. Prints help, the version or an
+    /// error and exits when asked to or on an unknown option
+    pub fn parse() -> Self {
+        let mut args = Self {
+            init_config: false,
+            targets: Vec::new(),
+            render_once: None,
+            send: None,
+        };
+        let (mut width, mut height) = DEFAULT_RENDER_SIZE;
+        let mut render_once = false;
+        let mut ansi = false;
+        let mut options_done = false;
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            if options_done || arg == "-" || !arg.starts_with('-') {
+                args.targets.push(StartupTarget::parse(&arg));
+                continue;
+            }
+            // `--name=value` or `--name value`
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| iter.next())
+                    .unwrap_or_else(|| fail(&format!("option '{}' needs a value", name)))
+            };
+            match name {
+                "--width" => width = size_value(name, &value()),
+                "--height" => height = size_value(name, &value()),
+                "--open" => args.targets.push(StartupTarget::parse(&value())),
+                "--send" => args.send = Some(value()),
+                _ if inline_value.is_some() => fail(&format!("unknown option '{}'", arg)),
+                "--" => options_done = true,
+                "--init-config" => args.init_config = true,
+                "--render-once" => render_once = true,
+                "--ansi" => ansi = true,
+                "-h" | "--help" => {
+                    print!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                "-V" | "--version" => {
+                    println!("termide {}", env!("CARGO_PKG_VERSION"));
+                    std::process::exit(0);
+                }
+                _ => fail(&format!("unknown option '{}'", arg)),
+            }
+        }
+        if render_once {
+            args.render_once = Some(RenderOnce {
+                width,
+                height,
+                ansi,
+            });
+        }
+        args
+    }
+
+    /// Check if standard input is to be read (`-`)
+    pub fn reads_stdin(&self) -> bool {
+        self.targets.contains(&StartupTarget::Stdin)
+    }
+}
+
+/// Frame width or height given to an option
+fn size_value(name: &str, value: &str) -> u16 {
+    match value.parse() {
+        Ok(size) if size > 0 => size,
+        _ => fail(&format!("invalid value '{}' for '{}'", value, name)),
+    }
+}
+
+/// Print an error with the usage and exit
+fn fail(message: &str) -> ! {
+    eprintln!("termide: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
@@ -1,10 +1,12 @@
+mod args;
 mod ui;
 
 use anyhow::Result;
 use crossterm::{
     event::{
-        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
-        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     execute,
     terminal::{
@@ -12,10 +14,14 @@ use crossterm::{
         LeaveAlternateScreen,
     },
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use ratatui::{
+    backend::{CrosstermBackend, TestBackend},
+    Terminal,
+};
+use std::io::{self, Read, Write};
 
-use termide_app::App;
+use args::{Args, RenderOnce};
+use termide_app::{App, StartupTarget};
 use termide_config::Config;
 use termide_git::check_git_available;
 use termide_i18n::{init_with_language, t};
@@ -23,6 +29,18 @@ use termide_panel_file_manager::FileManager;
 use termide_theme::set_themes_dir;
 
 fn main() -> Result<()> {
+    let args = Args::parse();
+    if let Some(command) = args.send {
+        if let Err(e) = termide_ipc::send(&command) {
+            eprintln!("termide: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Without a config file this is the first run (loading creates it)
+    let first_run = Config::config_file_path().is_ok_and(|path| !path.exists());
+
     // Load config first to get language setting
     let config = Config::load().unwrap_or_default();
 
@@ -34,6 +52,21 @@ fn main() -> Result<()> {
     // Initialize translation system with language from config
     init_with_language(&config.general.language);
 
+    // `-` opens standard input, read before the terminal takes over
+    let stdin_text = if args.reads_stdin() {
+        let mut text = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut text) {
+            eprintln!("Failed to read standard input: {}", e);
+        }
+        Some(text)
+    } else {
+        None
+    };
+
+    if let Some(render) = args.render_once {
+        return render_once(render, args.targets, stdin_text, &config);
+    }
+
     // Check for git on the system
     let git_available = check_git_available();
     let tr = t();
@@ -54,9 +87,12 @@ fn main() -> Result<()> {
     execute!(
         stdout,
         EnterAlternateScreen,
-        EnableMouseCapture,
-        EnableFocusChange
+        EnableFocusChange,
+        EnableBracketedPaste
     )?;
+    if config.general.mouse_capture {
+        execute!(stdout, EnableMouseCapture)?;
+    }
 
     if keyboard_enhanced {
         // Note: REPORT_ALL_KEYS_AS_ESCAPE_CODES causes modifier keys (Shift, Ctrl, Alt)
@@ -79,13 +115,13 @@ fn main() -> Result<()> {
 
     // Create application with terminal size to ensure proper panel layout
     let mut app = App::new_with_size(size.width, size.height);
+    app.detect_terminal_background();
+    open_startup_panels(&mut app, args.targets, stdin_text, &config);
+    app.offer_recovered_buffers();
+    app.apply_ipc_server();
 
-    // Try to load session, fallback to default layout on error
-    if let Err(_e) = app.load_session() {
-        // Session file doesn't exist or is corrupted - use default layout
-        // Add two FileManager panels in a 50/50 split
-        app.add_panel(Box::new(FileManager::new()));
-        app.add_panel(Box::new(FileManager::new()));
+    if first_run || args.init_config {
+        app.start_config_wizard(!first_run);
     }
 
     // Run application
@@ -102,7 +138,8 @@ fn main() -> Result<()> {
         terminal.backend_mut(),
         LeaveAlternateScreen,
         DisableMouseCapture,
-        DisableFocusChange
+        DisableFocusChange,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
@@ -113,3 +150,55 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Restore the session or create the default layout, and open the panels
+/// given on the command line
+fn open_startup_panels(
+    app: &mut App,
+    targets: Vec<StartupTarget>,
+    stdin_text: Option<String>,
+    config: &Config,
+) {
+    // Paths on the command line replace the session unless configured
+    let has_targets = !targets.is_empty();
+    let restore_session = !has_targets || config.general.restore_session_with_args;
+    // Try to load session, fallback to default layout on error
+    let restored = restore_session && app.load_session().is_ok();
+    if !restored && !has_targets {
+        // Session file doesn't exist or is corrupted - use default layout
+        // Add two FileManager panels in a 50/50 split
+        app.add_panel(Box::new(FileManager::new()));
+        app.add_panel(Box::new(FileManager::new()));
+    }
+    if has_targets {
+        app.open_startup_targets(targets, stdin_text);
+    }
+}
+
+/// Render the startup layout into a buffer instead of the terminal and
+/// print it (`--render-once`)
+fn render_once(
+    render: RenderOnce,
+    targets: Vec<StartupTarget>,
+    stdin_text: Option<String>,
+    config: &Config,
+) -> Result<()> {
+    let mut app = App::new_with_size(render.width, render.height);
+    // Leave the session as the interactive runs saved it
+    app.disable_session_saving();
+    open_startup_panels(&mut app, targets, stdin_text, config);
+
+    let mut terminal = Terminal::new(TestBackend::new(render.width, render.height))?;
+    app.render_once(&mut terminal, |frame, state, layout_manager| {
+        ui::render_layout_with_accordion(frame, state, layout_manager);
+    })?;
+
+    let buffer = terminal.backend().buffer();
+    let dump = if render.ansi {
+        termide_core::buffer_ansi(buffer)
+    } else {
+        termide_core::buffer_text(buffer)
+    };
+    io::stdout().write_all(dump.as_bytes())?;
+    Ok(())
+}
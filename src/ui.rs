@@ -4,17 +4,16 @@ use ratatui::{
     widgets::Block,
     Frame,
 };
-use std::any::Any;
+use std::path::Path;
 
 use termide_app::state::ActiveModal;
 use termide_app::AppState;
+use termide_core::{StatusKind, StatusSegment};
 use termide_layout::LayoutManager;
-use termide_panel_editor::Editor;
-use termide_panel_file_manager::FileManager;
-use termide_panel_terminal::Terminal;
 use termide_ui_render::{
-    render_collapsed_panel, render_expanded_panel, render_menu, ExpandedPanelParams,
-    MenuRenderParams,
+    highlight_border, menu_item_x, render_collapsed_panel, render_drop_indicator,
+    render_expanded_panel, render_menu, render_tab_strip, Dropdown, ExpandedPanelParams,
+    MenuRenderParams, Toasts, PANEL_MENU_INDEX,
 };
 
 use termide_modal::Modal;
@@ -31,6 +30,7 @@ fn render_dropdowns_and_modals(frame: &mut Frame, state: &mut AppState) {
         match modal {
             ActiveModal::Confirm(m) => m.render(area, frame.buffer_mut(), theme),
             ActiveModal::Input(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::MultilineInput(m) => m.render(area, frame.buffer_mut(), theme),
             ActiveModal::Select(m) => m.render(area, frame.buffer_mut(), theme),
             ActiveModal::Overwrite(m) => m.render(area, frame.buffer_mut(), theme),
             ActiveModal::Conflict(m) => m.render(area, frame.buffer_mut(), theme),
@@ -39,6 +39,15 @@ fn render_dropdowns_and_modals(frame: &mut Frame, state: &mut AppState) {
             ActiveModal::EditableSelect(m) => m.render(area, frame.buffer_mut(), theme),
             ActiveModal::Search(m) => m.render(area, frame.buffer_mut(), theme),
             ActiveModal::Replace(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Symlink(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Progress(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::CommandPalette(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::FileFinder(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Quit(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Diff(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Permissions(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Notifications(m) => m.render(area, frame.buffer_mut(), theme),
+            ActiveModal::Bookmarks(m) => m.render(area, frame.buffer_mut(), theme),
         }
     }
 }
@@ -84,6 +93,28 @@ pub fn render_layout_with_accordion(
     // Render status bar for active panel
     render_status_bar_for_active(frame, main_chunks[2], state, layout_manager);
 
+    // Notification toasts over the panels, below menus and modals
+    Toasts::new(
+        state.notifications.toasts(),
+        state.config.general.max_notifications,
+    )
+    .render(main_chunks[1], frame.buffer_mut(), state.theme);
+
+    // Render the Panel menu with the focused panel's actions
+    if state.ui.menu_open && state.ui.selected_menu_item == Some(PANEL_MENU_INDEX) {
+        let items = layout_manager
+            .active_panel_mut()
+            .map(|panel| panel.menu_items())
+            .unwrap_or_default();
+        Dropdown::new(
+            &items,
+            state.ui.selected_dropdown_item,
+            menu_item_x(PANEL_MENU_INDEX),
+            1,
+        )
+        .render(frame.buffer_mut(), state.theme);
+    }
+
     // Render dropdowns and modals
     render_dropdowns_and_modals(frame, state);
 }
@@ -131,6 +162,20 @@ fn render_main_area_with_accordion(
 
             render_panel_group(frame, group_area, state, group, group_idx, is_active_group);
         }
+
+        // Border resized with the mouse or under it
+        if let Some(border) = layout_manager
+            .resizing_border
+            .or(layout_manager.hovered_border)
+            .and_then(|border| layout_manager.border_rect(groups_area, border))
+        {
+            highlight_border(border, frame.buffer_mut(), state.theme);
+        }
+
+        // Where a panel dragged with the mouse would be dropped
+        if let Some(indicator) = layout_manager.drop_indicator(groups_area) {
+            render_drop_indicator(indicator, frame.buffer_mut(), state.theme);
+        }
     }
 }
 
@@ -149,6 +194,11 @@ fn render_panel_group(
 
     let expanded_idx = group.expanded_index();
 
+    if group.tabs {
+        render_tabbed_group(frame, area, state, group, group_idx, is_active_group);
+        return;
+    }
+
     // Build vertical constraints: collapsed panels = 1 line, expanded = Min(0)
     let vertical_constraints: Vec<Constraint> = (0..group.len())
         .map(|i| {
@@ -211,53 +261,98 @@ fn render_panel_group(
     }
 }
 
-/// Render status bar for the active panel
-fn render_status_bar_for_active(
+/// Render a group shown as tabs: the expanded panel over the whole group
+/// with the tabs in its top border
+fn render_tabbed_group(
     frame: &mut Frame,
     area: Rect,
     state: &AppState,
-    layout_manager: &mut LayoutManager,
+    group: &mut termide_layout::PanelGroup,
+    group_idx: usize,
+    is_active_group: bool,
 ) {
-    // Get active panel
-    let active_panel = layout_manager.active_panel_mut();
-
-    if let Some(panel) = active_panel {
-        // Get information depending on panel type
-        let (selected_count, file_info, disk_space, editor_info, terminal_info) = if let Some(fm) =
-            (&mut **panel as &mut dyn Any).downcast_mut::<FileManager>()
-        {
-            (
-                Some(fm.get_selected_count()),
-                fm.get_current_file_info(),
-                fm.get_disk_space_info(),
-                None,
-                None,
-            )
-        } else if let Some(editor) = (&mut **panel as &mut dyn Any).downcast_mut::<Editor>() {
-            (None, None, None, Some(editor.get_editor_info()), None)
-        } else if let Some(terminal) = (&mut **panel as &mut dyn Any).downcast_mut::<Terminal>() {
-            (None, None, None, None, Some(terminal.get_terminal_info()))
-        } else {
-            (None, None, None, None, None)
-        };
+    let expanded_idx = group.expanded_index();
+    let strip_area = LayoutManager::tab_strip_area(area);
+    let strip = group.tab_strip(strip_area.width);
 
-        let params = StatusBarParams {
-            theme: state.theme,
-            status_message: state.ui.status_message.as_ref(),
+    if let Some(panel) = group.expanded_panel_mut() {
+        let params = ExpandedPanelParams {
+            tab_size: state.config.editor.tab_size,
+            word_wrap: state.config.editor.word_wrap,
             terminal_width: state.terminal.width,
             terminal_height: state.terminal.height,
-            recommended_layout: state.get_recommended_layout(),
         };
-        StatusBar::render(
-            frame.buffer_mut(),
+        render_expanded_panel(
+            panel,
             area,
-            &params,
-            &panel.title(),
-            selected_count,
-            file_info.as_ref(),
-            disk_space.as_ref(),
-            editor_info.as_ref(),
-            terminal_info.as_ref(),
+            frame.buffer_mut(),
+            is_active_group,
+            group_idx * 100 + expanded_idx,
+            state.theme,
+            &state.config,
+            params,
+            1,
         );
     }
+    render_tab_strip(
+        &strip,
+        expanded_idx,
+        strip_area,
+        frame.buffer_mut(),
+        is_active_group,
+        state.theme,
+    );
+}
+
+/// Render status bar for the active panel
+fn render_status_bar_for_active(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut AppState,
+    layout_manager: &mut LayoutManager,
+) {
+    let Some(panel) = layout_manager.active_panel_mut() else {
+        return;
+    };
+    let panel_segments = panel.status_segments();
+
+    // Segments provided by the application, looked up only when shown
+    let shown = |state: &AppState, kind| {
+        state.status_bar_left.contains(&kind) || state.status_bar_right.contains(&kind)
+    };
+    let mut app_segments = Vec::new();
+    if shown(state, StatusKind::GitBranch) {
+        let dir = panel
+            .file_path()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .or_else(|| panel.get_working_directory());
+        if let Some(repo_root) = dir.as_deref().and_then(termide_git::find_repo_root) {
+            if let Some(branch) = state.git_branch(&repo_root) {
+                app_segments.push(StatusSegment::new(StatusKind::GitBranch, branch));
+            }
+        }
+    }
+    if shown(state, StatusKind::Clock) {
+        let time = chrono::Local::now().format("%H:%M").to_string();
+        app_segments.push(StatusSegment::new(StatusKind::Clock, time));
+    }
+
+    let params = StatusBarParams {
+        theme: state.theme,
+        status_message: state.ui.status_message.as_ref(),
+        terminal_width: state.terminal.width,
+        terminal_height: state.terminal.height,
+        recommended_layout: state.get_recommended_layout(),
+        left: &state.status_bar_left,
+        right: &state.status_bar_right,
+    };
+    StatusBar::render(
+        frame.buffer_mut(),
+        area,
+        &params,
+        &panel.title(),
+        &panel_segments,
+        &app_segments,
+    );
 }